@@ -86,9 +86,19 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CreateCollection.optimizers_config", ""),
             ("CreateCollection.vectors_config", ""),
             ("CreateCollection.quantization_config", ""),
+            ("CreateCollection.strict_mode_config", ""),
+            ("CreateCollection.ttl_config", ""),
+            ("CreateCollection.quota_config", ""),
+            ("CreateCollection.admission_config", ""),
+            ("CreateCollection.read_cache_config", ""),
             ("UpdateCollection.collection_name", "length(min = 1, max = 255)"),
             ("UpdateCollection.optimizers_config", ""),
             ("UpdateCollection.params", ""),
+            ("UpdateCollection.strict_mode_config", ""),
+            ("UpdateCollection.ttl_config", ""),
+            ("UpdateCollection.quota_config", ""),
+            ("UpdateCollection.admission_config", ""),
+            ("UpdateCollection.read_cache_config", ""),
             ("UpdateCollection.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("DeleteCollection.collection_name", "length(min = 1, max = 255)"),
             ("DeleteCollection.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
@@ -96,6 +106,11 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CollectionConfig.hnsw_config", ""),
             ("CollectionConfig.optimizers_config", ""),
             ("CollectionConfig.quantization_config", ""),
+            ("CollectionConfig.strict_mode_config", ""),
+            ("CollectionConfig.ttl_config", ""),
+            ("CollectionConfig.quota_config", ""),
+            ("CollectionConfig.admission_config", ""),
+            ("CollectionConfig.read_cache_config", ""),
             ("CollectionParams.vectors_config", ""),
             ("ChangeAliases.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("ListCollectionAliasesRequest.collection_name", "length(min = 1, max = 255)"),
@@ -108,6 +123,21 @@ fn configure_validation(builder: Builder) -> Builder {
             ("VectorParams.hnsw_config", ""),
             ("VectorParams.quantization_config", ""),
             ("QuantizationConfig.quantization", ""),
+            ("StrictModeConfig.max_query_limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_filter_conditions", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_group_by_points", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("TtlConfig.field", "length(min = 1)"),
+            ("TtlConfig.vacuum_interval_sec", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("QuotaConfig.max_points", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("QuotaConfig.max_vectors_size_bytes", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("QuotaConfig.max_payload_size_bytes", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("AdmissionConfig.max_concurrent_reads", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("AdmissionConfig.max_write_operations_per_second", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("AdmissionConfig.max_write_points_per_second", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("AdmissionConfig.write_burst_sec", "custom = \"crate::grpc::validate::validate_u64_range_min_1_max_3600\""),
+            ("AdmissionConfig.max_write_delay_ms", "custom = \"crate::grpc::validate::validate_u64_range_max_600000\""),
+            ("ReadCacheConfig.max_entries", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("ReadCacheConfig.ttl_sec", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("ScalarQuantization.quantile", "custom = \"crate::grpc::validate::validate_f32_range_min_0_5_max_1\""),
             ("VectorParamsMap.map", ""),
         ], &[
@@ -139,6 +169,9 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CreateFieldIndexCollection.field_name", "length(min = 1)"),
             ("DeleteFieldIndexCollection.collection_name", "length(min = 1, max = 255)"),
             ("DeleteFieldIndexCollection.field_name", "length(min = 1)"),
+            ("RebuildFieldIndexCollection.collection_name", "length(min = 1, max = 255)"),
+            ("RebuildFieldIndexCollection.field_name", "length(min = 1)"),
+            ("CancelFieldIndexBuildCollection.collection_name", "length(min = 1, max = 255)"),
             ("SearchPoints.collection_name", "length(min = 1, max = 255)"),
             ("SearchPoints.limit", "range(min = 1)"),
             ("SearchPoints.params", ""),
@@ -151,7 +184,13 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchPointGroups.vector_name", "custom = \"crate::grpc::validate::validate_not_empty\""),
             ("SearchPointGroups.group_size", "range(min = 1)"),
             ("SearchPointGroups.limit", "range(min = 1)"),
+            ("SearchPointGroups.group_keys", "length(max = 1000)"),
+            ("SearchPointGroups.group_oversampling", "range(min = 0.1, max = 100.0)"),
             ("SearchParams.quantization", ""),
+            ("SearchParams.adaptive_ef", ""),
+            ("AdaptiveEfParams.selectivity_threshold", "custom = \"crate::grpc::validate::validate_f64_range_1\""),
+            ("AdaptiveEfParams.max_ef", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("AdaptiveEfParams.exact_selectivity_threshold", "custom = \"crate::grpc::validate::validate_f64_range_1\""),
             ("QuantizationSearchParams.oversampling", "custom = \"crate::grpc::validate::validate_f64_range_min_1\""),
             ("ScrollPoints.collection_name", "length(min = 1, max = 255)"),
             ("ScrollPoints.limit", "custom = \"crate::grpc::validate::validate_u32_range_min_1\""),
@@ -163,11 +202,14 @@ fn configure_validation(builder: Builder) -> Builder {
             ("RecommendPointGroups.group_by", "length(min = 1)"),
             ("RecommendPointGroups.group_size", "range(min = 1)"),
             ("RecommendPointGroups.limit", "range(min = 1)"),
+            ("RecommendPointGroups.group_keys", "length(max = 1000)"),
+            ("RecommendPointGroups.group_oversampling", "range(min = 0.1, max = 100.0)"),
             ("RecommendPointGroups.params", ""),
             ("CountPoints.collection_name", "length(min = 1, max = 255)"),
         ], &[])
         .type_attribute("NamedVectors", "#[derive(serde::Serialize)]")
         .type_attribute("Vector", "#[derive(serde::Serialize)]")
+        .type_attribute("SparseIndices", "#[derive(serde::Serialize)]")
         // Service: points_internal_service.proto
         .validates(&[
             ("UpsertPointsInternal.upsert_points", ""),
@@ -179,6 +221,9 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ClearPayloadPointsInternal.clear_payload_points", ""),
             ("CreateFieldIndexCollectionInternal.create_field_index_collection", ""),
             ("DeleteFieldIndexCollectionInternal.delete_field_index_collection", ""),
+            ("RebuildFieldIndexCollectionInternal.rebuild_field_index_collection", ""),
+            ("CancelFieldIndexBuildInternal.collection_name", "length(min = 1, max = 255)"),
+            ("CancelFieldIndexBuildInternal.field_name", "length(min = 1)"),
             ("SearchPointsInternal.search_points", ""),
             ("SearchBatchPointsInternal.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPointsInternal.search_points", ""),
@@ -186,6 +231,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ScrollPointsInternal.scroll_points", ""),
             ("GetPointsInternal.get_points", ""),
             ("CountPointsInternal.count_points", ""),
+            ("ChecksumPointsInternal.collection_name", "length(min = 1, max = 255)"),
             ("SyncPointsInternal.sync_points", ""),
             ("SyncPoints.collection_name", "length(min = 1, max = 255)"),
         ], &[])
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tonic::transport::Uri;
+
+pub const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: usize = 5;
+pub const DEFAULT_CIRCUIT_BREAKER_BACKOFF: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive connection failures, which opens the circuit. 0 disables the breaker
+    pub failure_threshold: usize,
+    /// How long requests fail immediately, before a probe request is let through
+    pub backoff: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_CIRCUIT_BREAKER_THRESHOLD,
+            backoff: DEFAULT_CIRCUIT_BREAKER_BACKOFF,
+        }
+    }
+}
+
+/// `closed` - requests are sent as usual.
+/// `open` - requests fail immediately, until the backoff is over.
+/// `half_open` - backoff is over, a single probe request decides if the circuit is closed again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CircuitBreakerTelemetry {
+    pub state: CircuitState,
+    /// Connection failures since the last successful request
+    pub consecutive_failures: usize,
+    /// How many times the circuit was opened
+    pub times_opened: usize,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: usize,
+    times_opened: usize,
+    /// Set while the circuit is open or half-open
+    opened_at: Option<Instant>,
+    /// Start of the probe request, let through in the half-open state
+    probe_started_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn state(&self, backoff: Duration) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < backoff => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns the time left until a request may be sent, if the request is rejected
+    fn acquire(&mut self, backoff: Duration) -> Result<(), Duration> {
+        match self.state(backoff) {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let elapsed = self.opened_at.map_or(Duration::ZERO, |at| at.elapsed());
+                Err(backoff.saturating_sub(elapsed))
+            }
+            CircuitState::HalfOpen => match self.probe_started_at {
+                // Probe could be cancelled without reporting, so it expires after the backoff
+                Some(started_at) if started_at.elapsed() < backoff => {
+                    Err(backoff.saturating_sub(started_at.elapsed()))
+                }
+                _ => {
+                    self.probe_started_at = Some(Instant::now());
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn report_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probe_started_at = None;
+    }
+
+    fn report_failure(&mut self, threshold: usize) {
+        self.consecutive_failures += 1;
+        if self.opened_at.is_some() {
+            // Probe failed, wait for another backoff
+            self.opened_at = Some(Instant::now());
+            self.probe_started_at = None;
+        } else if self.consecutive_failures >= threshold {
+            self.opened_at = Some(Instant::now());
+            self.times_opened += 1;
+        }
+    }
+}
+
+/// Circuit breakers of the connections to each URI.
+///
+/// After `failure_threshold` consecutive connection failures, requests to the URI fail without
+/// waiting for the connection timeout, so callers can fall back to other peers.
+#[derive(Debug, Default)]
+pub struct CircuitBreakers {
+    config: CircuitBreakerConfig,
+    breakers: Mutex<HashMap<Uri, CircuitBreaker>>,
+}
+
+impl CircuitBreakers {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: Default::default(),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.failure_threshold > 0
+    }
+
+    /// Check if a request to the URI may be sent.
+    /// Returns the time left until the next attempt, if the circuit is open
+    pub fn acquire(&self, uri: &Uri) -> Result<(), Duration> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        match self.breakers.lock().get_mut(uri) {
+            None => Ok(()),
+            Some(breaker) => breaker.acquire(self.config.backoff),
+        }
+    }
+
+    pub fn report_success(&self, uri: &Uri) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Some(breaker) = self.breakers.lock().get_mut(uri) {
+            breaker.report_success();
+        }
+    }
+
+    pub fn report_failure(&self, uri: &Uri) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut breakers = self.breakers.lock();
+        let breaker = breakers.entry(uri.clone()).or_default();
+        let was_closed = breaker.opened_at.is_none();
+        breaker.report_failure(self.config.failure_threshold);
+        if was_closed && breaker.opened_at.is_some() {
+            log::warn!(
+                "Circuit breaker for {uri} is open after {} connection failures, retry in {}ms",
+                breaker.consecutive_failures,
+                self.config.backoff.as_millis(),
+            );
+        }
+    }
+
+    pub fn remove(&self, uri: &Uri) {
+        self.breakers.lock().remove(uri);
+    }
+
+    pub fn get_telemetry(&self, uri: &Uri) -> Option<CircuitBreakerTelemetry> {
+        let breakers = self.breakers.lock();
+        breakers.get(uri).map(|breaker| CircuitBreakerTelemetry {
+            state: breaker.state(self.config.backoff),
+            consecutive_failures: breaker.consecutive_failures,
+            times_opened: breaker.times_opened,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakers(backoff: Duration) -> CircuitBreakers {
+        CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            backoff,
+        })
+    }
+
+    fn state(breakers: &CircuitBreakers, uri: &Uri) -> CircuitState {
+        breakers.get_telemetry(uri).unwrap().state
+    }
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let breakers = breakers(Duration::from_secs(60));
+        let uri = Uri::from_static("http://peer:6335");
+
+        breakers.report_failure(&uri);
+        breakers.report_failure(&uri);
+        assert_eq!(state(&breakers, &uri), CircuitState::Closed);
+        assert!(breakers.acquire(&uri).is_ok());
+
+        // Success resets the counter
+        breakers.report_success(&uri);
+        breakers.report_failure(&uri);
+        breakers.report_failure(&uri);
+        assert_eq!(state(&breakers, &uri), CircuitState::Closed);
+
+        breakers.report_failure(&uri);
+        assert_eq!(state(&breakers, &uri), CircuitState::Open);
+        assert!(breakers.acquire(&uri).is_err());
+
+        let other_uri = Uri::from_static("http://other-peer:6335");
+        assert!(breakers.acquire(&other_uri).is_ok());
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let breakers = breakers(Duration::from_millis(10));
+        let uri = Uri::from_static("http://peer:6335");
+
+        for _ in 0..3 {
+            breakers.report_failure(&uri);
+        }
+        assert!(breakers.acquire(&uri).is_err());
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(state(&breakers, &uri), CircuitState::HalfOpen);
+
+        // Only a single probe is let through
+        assert!(breakers.acquire(&uri).is_ok());
+        assert!(breakers.acquire(&uri).is_err());
+
+        // Failed probe opens the circuit again
+        breakers.report_failure(&uri);
+        assert_eq!(state(&breakers, &uri), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(breakers.acquire(&uri).is_ok());
+        breakers.report_success(&uri);
+
+        let telemetry = breakers.get_telemetry(&uri).unwrap();
+        assert_eq!(telemetry.state, CircuitState::Closed);
+        assert_eq!(telemetry.consecutive_failures, 0);
+        assert_eq!(telemetry.times_opened, 1);
+        assert!(breakers.acquire(&uri).is_ok());
+    }
+
+    #[test]
+    fn test_disabled_circuit_breaker() {
+        let breakers = CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold: 0,
+            backoff: Duration::from_secs(60),
+        });
+        let uri = Uri::from_static("http://peer:6335");
+
+        for _ in 0..10 {
+            breakers.report_failure(&uri);
+        }
+        assert!(breakers.acquire(&uri).is_ok());
+        assert!(breakers.get_telemetry(&uri).is_none());
+    }
+}
@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use chrono::{NaiveDateTime, Timelike};
+use chrono::{DateTime, NaiveDateTime, Timelike, Utc};
+use segment::data_types::keyword_index::KeywordIndexType;
+use segment::data_types::order_by::Direction;
+use segment::data_types::sparse_vector::SparseVector;
 use segment::data_types::text_index::TextIndexType;
-use segment::data_types::vectors::VectorElementType;
 use segment::types::{default_quantization_ignore_value, default_quantization_rescore_value};
 use tonic::Status;
 use uuid::Uuid;
@@ -18,14 +20,17 @@ use crate::grpc::qdrant::value::Kind;
 use crate::grpc::qdrant::vectors::VectorsOptions;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
-    with_vectors_selector, CollectionDescription, CollectionOperationResponse, Condition, Distance,
-    FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoRadius, HasIdCondition, HealthCheckReply,
-    HnswConfigDiff, IsEmptyCondition, IsNullCondition, ListCollectionsResponse, ListValue, Match,
-    NamedVectors, NestedCondition, PayloadExcludeSelector, PayloadIncludeSelector,
-    PayloadIndexParams, PayloadSchemaInfo, PayloadSchemaType, PointId, QuantizationConfig,
-    QuantizationSearchParams, Range, RepeatedIntegers, RepeatedStrings, ScalarQuantization,
-    ScoredPoint, SearchParams, Struct, TextIndexParams, TokenizerType, Value, ValuesCount, Vector,
-    Vectors, VectorsSelector, WithPayloadSelector, WithVectorsSelector,
+    with_vectors_selector, AdaptiveEfParams, CollectionDescription, CollectionOperationResponse,
+    Condition, Datatype, Distance, FailedShard, FieldCondition, Filter, GeoBoundingBox, GeoPoint,
+    GeoRadius, HardwareUsage, HasIdCondition, HealthCheckReply, HnswConfigDiff, IsEmptyCondition,
+    IsNullCondition, KeywordIndexParams, KeywordRange, ListCollectionsResponse, ListValue, Match,
+    MinShould, ModifiedSinceCondition, NamedVectors, NestedCondition, OrderBy, PartialResults,
+    PayloadExcludeSelector, PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo,
+    PayloadSchemaType, PayloadTruncation, PointId, QuantizationConfig, QuantizationSearchParams,
+    Range, RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint, SearchDebugInfo,
+    SearchParams, ShardTrace, SparseIndices, Struct, TextIndexParams, TokenizerType, Value,
+    ValuesCount, Vector, VectorSlice, VectorSliceSelector, Vectors, VectorsSelector, Warning,
+    WithPayloadSelector, WithVectorsSelector,
 };
 
 pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Value> {
@@ -157,6 +162,17 @@ impl From<segment::data_types::text_index::TextIndexParams> for PayloadIndexPara
     }
 }
 
+impl From<segment::data_types::keyword_index::KeywordIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::keyword_index::KeywordIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::KeywordIndexParams(KeywordIndexParams {
+                is_tenant: params.is_tenant,
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
 impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
     fn from(schema: segment::types::PayloadIndexInfo) -> Self {
         PayloadSchemaInfo {
@@ -169,11 +185,16 @@ impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
             }
             .into(),
             params: schema.params.map(|params| match params {
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params) => {
+                    keyword_index_params.into()
+                }
                 segment::types::PayloadSchemaParams::Text(text_index_params) => {
                     text_index_params.into()
                 }
             }),
             points: Some(schema.points as u64),
+            values_count: schema.values_count.map(|count| count as u64),
+            estimated_ram_bytes: schema.estimated_ram_bytes.map(|bytes| bytes as u64),
         }
     }
 }
@@ -215,6 +236,41 @@ impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexP
             Some(IndexParams::TextIndexParams(text_index_params)) => {
                 Ok(text_index_params.try_into()?)
             }
+            Some(IndexParams::KeywordIndexParams(_)) => Err(Status::invalid_argument(
+                "keyword index params are not applicable to text index",
+            )),
+        }
+    }
+}
+
+impl From<KeywordIndexParams> for segment::data_types::keyword_index::KeywordIndexParams {
+    fn from(params: KeywordIndexParams) -> Self {
+        segment::data_types::keyword_index::KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            is_tenant: params.is_tenant,
+            on_disk: params.on_disk,
+        }
+    }
+}
+
+impl From<OrderBy> for segment::data_types::order_by::OrderBy {
+    fn from(order_by: OrderBy) -> Self {
+        segment::data_types::order_by::OrderBy {
+            key: order_by.key,
+            direction: if order_by.descending {
+                Direction::Desc
+            } else {
+                Direction::Asc
+            },
+        }
+    }
+}
+
+impl From<segment::data_types::order_by::OrderBy> for OrderBy {
+    fn from(order_by: segment::data_types::order_by::OrderBy) -> Self {
+        OrderBy {
+            key: order_by.key,
+            descending: order_by.direction == Direction::Desc,
         }
     }
 }
@@ -227,6 +283,9 @@ impl TryFrom<IndexParams> for segment::types::PayloadSchemaParams {
             IndexParams::TextIndexParams(text_index_params) => Ok(
                 segment::types::PayloadSchemaParams::Text(text_index_params.try_into()?),
             ),
+            IndexParams::KeywordIndexParams(keyword_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            ),
         }
     }
 }
@@ -266,6 +325,8 @@ impl TryFrom<PayloadSchemaInfo> for segment::types::PayloadIndexInfo {
             data_type,
             params,
             points: schema.points.unwrap_or(0) as usize,
+            values_count: schema.values_count.map(|count| count as usize),
+            estimated_ram_bytes: schema.estimated_ram_bytes.map(|bytes| bytes as usize),
         })
     }
 }
@@ -299,9 +360,11 @@ impl TryFrom<WithPayloadSelector> for segment::types::WithPayloadInterface {
                 SelectorOptions::Exclude(s) => {
                     segment::types::PayloadSelectorExclude::new(s.fields).into()
                 }
-                SelectorOptions::Include(s) => {
-                    segment::types::PayloadSelectorInclude::new(s.fields).into()
+                SelectorOptions::Include(s) => segment::types::PayloadSelectorInclude {
+                    include: s.fields,
+                    exclude: s.exclude_fields,
                 }
+                .into(),
             }),
             _ => Err(Status::invalid_argument("No PayloadSelector".to_string())),
         }
@@ -313,11 +376,17 @@ impl From<segment::types::WithPayloadInterface> for WithPayloadSelector {
         let selector_options = match value {
             segment::types::WithPayloadInterface::Bool(flag) => SelectorOptions::Enable(flag),
             segment::types::WithPayloadInterface::Fields(fields) => {
-                SelectorOptions::Include(PayloadIncludeSelector { fields })
+                SelectorOptions::Include(PayloadIncludeSelector {
+                    fields,
+                    exclude_fields: vec![],
+                })
             }
             segment::types::WithPayloadInterface::Selector(selector) => match selector {
                 segment::types::PayloadSelector::Include(s) => {
-                    SelectorOptions::Include(PayloadIncludeSelector { fields: s.include })
+                    SelectorOptions::Include(PayloadIncludeSelector {
+                        fields: s.include,
+                        exclude_fields: s.exclude,
+                    })
                 }
                 segment::types::PayloadSelector::Exclude(s) => {
                     SelectorOptions::Exclude(PayloadExcludeSelector { fields: s.exclude })
@@ -352,12 +421,34 @@ impl From<segment::types::QuantizationSearchParams> for QuantizationSearchParams
     }
 }
 
+impl From<AdaptiveEfParams> for segment::types::AdaptiveEfParams {
+    fn from(params: AdaptiveEfParams) -> Self {
+        Self {
+            selectivity_threshold: params.selectivity_threshold,
+            max_ef: params.max_ef.map(|x| x as usize),
+            exact_selectivity_threshold: params.exact_selectivity_threshold,
+        }
+    }
+}
+
+impl From<segment::types::AdaptiveEfParams> for AdaptiveEfParams {
+    fn from(params: segment::types::AdaptiveEfParams) -> Self {
+        Self {
+            selectivity_threshold: params.selectivity_threshold,
+            max_ef: params.max_ef.map(|x| x as u64),
+            exact_selectivity_threshold: params.exact_selectivity_threshold,
+        }
+    }
+}
+
 impl From<SearchParams> for segment::types::SearchParams {
     fn from(params: SearchParams) -> Self {
         Self {
             hnsw_ef: params.hnsw_ef.map(|x| x as usize),
             exact: params.exact.unwrap_or(false),
             quantization: params.quantization.map(|q| q.into()),
+            indexed_only: params.indexed_only.unwrap_or(false),
+            adaptive_ef: params.adaptive_ef.map(|p| p.into()),
         }
     }
 }
@@ -368,6 +459,88 @@ impl From<segment::types::SearchParams> for SearchParams {
             hnsw_ef: params.hnsw_ef.map(|x| x as u64),
             exact: Some(params.exact),
             quantization: params.quantization.map(|q| q.into()),
+            indexed_only: Some(params.indexed_only),
+            adaptive_ef: params.adaptive_ef.map(|p| p.into()),
+        }
+    }
+}
+
+impl From<HardwareUsage> for segment::common::hardware_counter::HardwareUsage {
+    fn from(usage: HardwareUsage) -> Self {
+        Self {
+            scored_vectors: usage.scored_vectors as usize,
+            quantized_comparisons: usage.quantized_comparisons as usize,
+            original_comparisons: usage.original_comparisons as usize,
+            payload_values_read: usage.payload_values_read as usize,
+            filter_conditions_checked: usage.filter_conditions_checked as usize,
+            cache_hit: usage.cache_hit,
+        }
+    }
+}
+
+impl From<segment::common::hardware_counter::HardwareUsage> for HardwareUsage {
+    fn from(usage: segment::common::hardware_counter::HardwareUsage) -> Self {
+        Self {
+            scored_vectors: usage.scored_vectors as u64,
+            quantized_comparisons: usage.quantized_comparisons as u64,
+            original_comparisons: usage.original_comparisons as u64,
+            payload_values_read: usage.payload_values_read as u64,
+            filter_conditions_checked: usage.filter_conditions_checked as u64,
+            cache_hit: usage.cache_hit,
+        }
+    }
+}
+
+impl From<crate::grpc::models::FailedShard> for FailedShard {
+    fn from(failed_shard: crate::grpc::models::FailedShard) -> Self {
+        Self {
+            shard_id: failed_shard.shard_id,
+            peer_id: failed_shard.peer_id,
+            error: failed_shard.error,
+        }
+    }
+}
+
+impl From<crate::grpc::models::ShardTrace> for ShardTrace {
+    fn from(trace: crate::grpc::models::ShardTrace) -> Self {
+        Self {
+            shard_id: trace.shard_id,
+            peer_id: trace.peer_id,
+            local: trace.local,
+            replica_state: trace.replica_state,
+            latency: trace.latency,
+        }
+    }
+}
+
+impl From<crate::grpc::models::Warning> for Warning {
+    fn from(warning: crate::grpc::models::Warning) -> Self {
+        Self {
+            reason: warning.reason.as_str().to_string(),
+            message: warning.message,
+        }
+    }
+}
+
+impl From<crate::grpc::models::PartialResults> for PartialResults {
+    fn from(partial: crate::grpc::models::PartialResults) -> Self {
+        Self {
+            failed_shards: partial
+                .failed_shards
+                .into_iter()
+                .map(FailedShard::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<segment::types::SearchDebugInfo> for SearchDebugInfo {
+    fn from(info: segment::types::SearchDebugInfo) -> Self {
+        Self {
+            plain_searches: info.plain_searches as u64,
+            hnsw_searches: info.hnsw_searches as u64,
+            hnsw_adaptive_ef_searches: info.hnsw_adaptive_ef_searches as u64,
+            max_ef: info.max_ef.map(|ef| ef as u64),
         }
     }
 }
@@ -385,12 +558,48 @@ impl From<segment::types::PointIdType> for PointId {
 
 impl From<segment::data_types::vectors::VectorType> for Vector {
     fn from(vector: segment::data_types::vectors::VectorType) -> Self {
-        Self { data: vector }
+        Self {
+            data: vector,
+            indices: None,
+        }
+    }
+}
+
+impl From<SparseVector> for Vector {
+    fn from(vector: SparseVector) -> Self {
+        Self {
+            data: vector.values,
+            indices: Some(SparseIndices {
+                data: vector.indices,
+            }),
+        }
     }
 }
 
-impl From<HashMap<String, Vec<VectorElementType>>> for NamedVectors {
-    fn from(vectors: HashMap<String, Vec<VectorElementType>>) -> Self {
+impl From<segment::data_types::vectors::Vector> for Vector {
+    fn from(vector: segment::data_types::vectors::Vector) -> Self {
+        match vector {
+            segment::data_types::vectors::Vector::Dense(vector) => vector.into(),
+            segment::data_types::vectors::Vector::Sparse(vector) => vector.into(),
+        }
+    }
+}
+
+impl TryFrom<Vector> for segment::data_types::vectors::Vector {
+    type Error = Status;
+
+    fn try_from(vector: Vector) -> Result<Self, Self::Error> {
+        match vector.indices {
+            None => Ok(Self::Dense(vector.data)),
+            Some(indices) => SparseVector::new(indices.data, vector.data)
+                .map(Self::Sparse)
+                .map_err(|err| Status::invalid_argument(format!("Wrong sparse vector: {err}"))),
+        }
+    }
+}
+
+impl From<HashMap<String, segment::data_types::vectors::Vector>> for NamedVectors {
+    fn from(vectors: HashMap<String, segment::data_types::vectors::Vector>) -> Self {
         Self {
             vectors: vectors
                 .into_iter()
@@ -421,6 +630,48 @@ impl From<segment::types::ScoredPoint> for ScoredPoint {
             score: point.score,
             version: point.version,
             vectors: point.vector.map(|v| v.into()),
+            payload_truncation: point.payload_truncation.map(|truncation| truncation.into()),
+            vector_slice: point.vector_slice.map(|slice| slice.into()),
+        }
+    }
+}
+
+impl From<segment::types::PayloadTruncation> for PayloadTruncation {
+    fn from(truncation: segment::types::PayloadTruncation) -> Self {
+        Self {
+            payload_truncated: truncation.payload_truncated,
+            dropped_payload_keys: truncation.dropped_payload_keys,
+        }
+    }
+}
+
+impl From<segment::types::VectorSlice> for VectorSlice {
+    fn from(slice: segment::types::VectorSlice) -> Self {
+        Self {
+            name: slice.name,
+            start: slice.start as u64,
+            end: slice.end as u64,
+            values: slice.values,
+        }
+    }
+}
+
+impl From<VectorSlice> for segment::types::VectorSlice {
+    fn from(slice: VectorSlice) -> Self {
+        Self {
+            name: slice.name,
+            start: slice.start as usize,
+            end: slice.end as usize,
+            values: slice.values,
+        }
+    }
+}
+
+impl From<PayloadTruncation> for segment::types::PayloadTruncation {
+    fn from(truncation: PayloadTruncation) -> Self {
+        Self {
+            payload_truncated: truncation.payload_truncated,
+            dropped_payload_keys: truncation.dropped_payload_keys,
         }
     }
 }
@@ -441,12 +692,27 @@ impl From<segment::data_types::groups::GroupId> for GroupId {
     }
 }
 
-impl From<NamedVectors> for HashMap<String, Vec<VectorElementType>> {
-    fn from(vectors: NamedVectors) -> Self {
+impl TryFrom<GroupId> for serde_json::Value {
+    type Error = Status;
+
+    fn try_from(key: GroupId) -> Result<Self, Self::Error> {
+        match key.kind {
+            Some(crate::grpc::qdrant::group_id::Kind::StringValue(str)) => Ok(str.into()),
+            Some(crate::grpc::qdrant::group_id::Kind::UnsignedValue(n)) => Ok(n.into()),
+            Some(crate::grpc::qdrant::group_id::Kind::IntegerValue(n)) => Ok(n.into()),
+            None => Err(Status::invalid_argument("Malformed GroupId type")),
+        }
+    }
+}
+
+impl TryFrom<NamedVectors> for HashMap<String, segment::data_types::vectors::Vector> {
+    type Error = Status;
+
+    fn try_from(vectors: NamedVectors) -> Result<Self, Self::Error> {
         vectors
             .vectors
             .into_iter()
-            .map(|(name, vector)| (name, vector.data))
+            .map(|(name, vector)| Ok((name, vector.try_into()?)))
             .collect()
     }
 }
@@ -458,10 +724,15 @@ impl TryFrom<Vectors> for segment::data_types::vectors::VectorStruct {
         match vectors.vectors_options {
             Some(vectors_options) => Ok(match vectors_options {
                 VectorsOptions::Vector(vector) => {
+                    if vector.indices.is_some() {
+                        return Err(Status::invalid_argument(
+                            "Sparse vector must be provided with a name",
+                        ));
+                    }
                     segment::data_types::vectors::VectorStruct::Single(vector.data)
                 }
                 VectorsOptions::Vectors(vectors) => {
-                    segment::data_types::vectors::VectorStruct::Multi(vectors.into())
+                    segment::data_types::vectors::VectorStruct::Multi(vectors.try_into()?)
                 }
             }),
             None => Err(Status::invalid_argument("No Provided")),
@@ -478,6 +749,16 @@ impl From<segment::types::WithVector> for WithVectorsSelector {
             segment::types::WithVector::Selector(include) => {
                 with_vectors_selector::SelectorOptions::Include(VectorsSelector { names: include })
             }
+            segment::types::WithVector::Exclude { exclude } => {
+                with_vectors_selector::SelectorOptions::Exclude(VectorsSelector { names: exclude })
+            }
+            segment::types::WithVector::Slice { name, slice } => {
+                with_vectors_selector::SelectorOptions::Slice(VectorSliceSelector {
+                    name,
+                    start: slice.start as u64,
+                    end: slice.end as u64,
+                })
+            }
         };
         Self {
             selector_options: Some(selector_options),
@@ -493,6 +774,16 @@ impl From<WithVectorsSelector> for segment::types::WithVector {
             Some(with_vectors_selector::SelectorOptions::Include(include)) => {
                 Self::Selector(include.names)
             }
+            Some(with_vectors_selector::SelectorOptions::Exclude(exclude)) => Self::Exclude {
+                exclude: exclude.names,
+            },
+            Some(with_vectors_selector::SelectorOptions::Slice(slice)) => Self::Slice {
+                name: slice.name,
+                slice: segment::types::DimensionRange {
+                    start: slice.start as usize,
+                    end: slice.end as usize,
+                },
+            },
         }
     }
 }
@@ -639,6 +930,7 @@ impl TryFrom<Filter> for segment::types::Filter {
     fn try_from(value: Filter) -> Result<Self, Self::Error> {
         Ok(Self {
             should: conditions_helper_from_grpc(value.should)?,
+            min_should: value.min_should.map(TryInto::try_into).transpose()?,
             must: conditions_helper_from_grpc(value.must)?,
             must_not: conditions_helper_from_grpc(value.must_not)?,
         })
@@ -651,6 +943,44 @@ impl From<segment::types::Filter> for Filter {
             should: conditions_helper_to_grpc(value.should),
             must: conditions_helper_to_grpc(value.must),
             must_not: conditions_helper_to_grpc(value.must_not),
+            min_should: value.min_should.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<MinShould> for segment::types::MinShould {
+    type Error = Status;
+
+    fn try_from(value: MinShould) -> Result<Self, Self::Error> {
+        let MinShould {
+            conditions,
+            min_count,
+        } = value;
+        if min_count == 0 || min_count > conditions.len() as u64 {
+            return Err(Status::invalid_argument(format!(
+                "min_count must be between 1 and the number of conditions ({}), got {min_count}",
+                conditions.len(),
+            )));
+        }
+        Ok(Self {
+            conditions: conditions
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            min_count: min_count as usize,
+        })
+    }
+}
+
+impl From<segment::types::MinShould> for MinShould {
+    fn from(value: segment::types::MinShould) -> Self {
+        let segment::types::MinShould {
+            conditions,
+            min_count,
+        } = value;
+        Self {
+            conditions: conditions.into_iter().map(Into::into).collect(),
+            min_count: min_count as u64,
         }
     }
 }
@@ -679,6 +1009,9 @@ impl TryFrom<Condition> for segment::types::Condition {
                 ConditionOneOf::Nested(nested) => Ok(segment::types::Condition::Nested(
                     segment::types::NestedCondition::new(nested.try_into()?),
                 )),
+                ConditionOneOf::ModifiedSince(modified_since) => Ok(
+                    segment::types::Condition::ModifiedSince(modified_since.try_into()?),
+                ),
             };
         }
         Err(Status::invalid_argument("Malformed Condition type"))
@@ -698,6 +1031,9 @@ impl From<segment::types::Condition> for Condition {
             segment::types::Condition::Nested(nested) => {
                 ConditionOneOf::Nested(nested.nested.into())
             }
+            segment::types::Condition::ModifiedSince(modified_since) => {
+                ConditionOneOf::ModifiedSince(modified_since.into())
+            }
         };
 
         Self {
@@ -706,6 +1042,27 @@ impl From<segment::types::Condition> for Condition {
     }
 }
 
+impl TryFrom<ModifiedSinceCondition> for segment::types::ModifiedSinceCondition {
+    type Error = Status;
+
+    fn try_from(value: ModifiedSinceCondition) -> Result<Self, Self::Error> {
+        match value.modified_since {
+            None => Err(Status::invalid_argument(
+                "Modified since condition must have a timestamp",
+            )),
+            Some(timestamp) => Ok(date_time_from_proto(timestamp)?.into()),
+        }
+    }
+}
+
+impl From<segment::types::ModifiedSinceCondition> for ModifiedSinceCondition {
+    fn from(value: segment::types::ModifiedSinceCondition) -> Self {
+        Self {
+            modified_since: Some(date_time_to_proto(value.modified_since.naive_utc())),
+        }
+    }
+}
+
 impl TryFrom<NestedCondition> for segment::types::Nested {
     type Error = Status;
 
@@ -917,6 +1274,24 @@ impl From<segment::types::Range> for Range {
     }
 }
 
+impl From<KeywordRange> for segment::types::KeywordRange {
+    fn from(value: KeywordRange) -> Self {
+        Self {
+            gte: value.gte,
+            lte: value.lte,
+        }
+    }
+}
+
+impl From<segment::types::KeywordRange> for KeywordRange {
+    fn from(value: segment::types::KeywordRange) -> Self {
+        Self {
+            gte: value.gte,
+            lte: value.lte,
+        }
+    }
+}
+
 impl From<ValuesCount> for segment::types::ValuesCount {
     fn from(value: ValuesCount) -> Self {
         Self {
@@ -957,6 +1332,9 @@ impl TryFrom<Match> for segment::types::Match {
                 MatchValue::ExceptKeywords(ints) => {
                     segment::types::Match::Except(ints.strings.into())
                 }
+                MatchValue::KeywordRange(range) => {
+                    segment::types::Match::new_keyword_range(range.into())
+                }
             }),
             _ => Err(Status::invalid_argument("Malformed Match condition")),
         }
@@ -990,6 +1368,9 @@ impl From<segment::types::Match> for Match {
                     MatchValue::ExceptIntegers(RepeatedIntegers { integers })
                 }
             },
+            segment::types::Match::KeywordRange(segment::types::MatchKeywordRange {
+                keyword_range,
+            }) => MatchValue::KeywordRange(keyword_range.into()),
         };
         Self {
             match_value: Some(match_value),
@@ -1017,6 +1398,12 @@ pub fn date_time_to_proto(date_time: NaiveDateTime) -> prost_types::Timestamp {
     }
 }
 
+pub fn date_time_from_proto(timestamp: prost_types::Timestamp) -> Result<DateTime<Utc>, Status> {
+    NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos.max(0) as u32)
+        .map(|date_time| DateTime::from_utc(date_time, Utc))
+        .ok_or_else(|| Status::invalid_argument(format!("Invalid timestamp: {timestamp:?}")))
+}
+
 impl TryFrom<Distance> for segment::types::Distance {
     type Error = Status;
 
@@ -1042,3 +1429,28 @@ pub fn from_grpc_dist(dist: i32) -> Result<segment::types::Distance, Status> {
         Some(grpc_distance) => Ok(grpc_distance.try_into()?),
     }
 }
+
+impl From<segment::types::VectorStorageDatatype> for Datatype {
+    fn from(value: segment::types::VectorStorageDatatype) -> Self {
+        match value {
+            segment::types::VectorStorageDatatype::Float32 => Datatype::Float32,
+            segment::types::VectorStorageDatatype::Float16 => Datatype::Float16,
+            segment::types::VectorStorageDatatype::Uint8 => Datatype::Uint8,
+        }
+    }
+}
+
+/// Convert gRPC datatype, `Default` means that the datatype is not specified
+pub fn from_grpc_datatype(
+    datatype: i32,
+) -> Result<Option<segment::types::VectorStorageDatatype>, Status> {
+    match Datatype::from_i32(datatype) {
+        None => Err(Status::invalid_argument(format!(
+            "Malformed datatype parameter, unexpected value: {datatype}"
+        ))),
+        Some(Datatype::Default) => Ok(None),
+        Some(Datatype::Float32) => Ok(Some(segment::types::VectorStorageDatatype::Float32)),
+        Some(Datatype::Float16) => Ok(Some(segment::types::VectorStorageDatatype::Float16)),
+        Some(Datatype::Uint8) => Ok(Some(segment::types::VectorStorageDatatype::Uint8)),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod circuit_breaker;
 pub mod conversions;
 pub mod models;
 #[allow(clippy::all)]
@@ -1,8 +1,11 @@
 use std::fmt::Debug;
 
 use schemars::JsonSchema;
+use segment::common::hardware_counter::HardwareUsage;
+use segment::types::SearchDebugInfo;
 use serde;
-use serde::{Deserialize, Serialize};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Serialize, Deserialize)]
 pub struct VersionInfo {
@@ -31,14 +34,39 @@ impl VersionInfo {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ApiStatus {
     Ok,
     Error(String),
+    /// Error with a stable code of its reason, serialized as `{"error": ..., "reason": ...}`
+    #[serde(skip_deserializing)]
+    #[schemars(skip)]
+    ErrorWithReason {
+        error: String,
+        reason: String,
+    },
     Accepted,
 }
 
+impl Serialize for ApiStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ApiStatus::Ok => serializer.serialize_unit_variant("ApiStatus", 0, "ok"),
+            ApiStatus::Error(error) => {
+                serializer.serialize_newtype_variant("ApiStatus", 1, "error", error)
+            }
+            ApiStatus::ErrorWithReason { error, reason } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("error", error)?;
+                map.serialize_entry("reason", reason)?;
+                map.end()
+            }
+            ApiStatus::Accepted => serializer.serialize_unit_variant("ApiStatus", 3, "accepted"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ApiResponse<D: Serialize + Debug> {
@@ -46,6 +74,110 @@ pub struct ApiResponse<D: Serialize + Debug> {
     pub result: Option<D>,
     pub status: ApiStatus,
     pub time: f64,
+    /// Approximate number of not yet indexed points, skipped by `indexed_only` search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_unindexed_points: Option<usize>,
+    /// Details of the search execution, useful for tuning of the search params
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<SearchDebugInfo>,
+    /// Work done to serve the request, returned if requested with `with_usage`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<HardwareUsage>,
+    /// Shards, which failed to serve the request, returned if requested with `allow_partial_results`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<PartialResults>,
+    /// Replicas, which served the shards, returned if requested with `with_shard_trace`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<ShardTrace>>,
+    /// Issues of the request, which don't prevent it from being served
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<Warning>>,
+}
+
+/// Shard, which failed to serve a part of the request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FailedShard {
+    pub shard_id: u32,
+    /// Peer of the failed replica, if the shard is served by a single replica
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_id: Option<u64>,
+    pub error: String,
+}
+
+/// Result is merged from the shards, which responded. Points of the failed shards are missing
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct PartialResults {
+    pub failed_shards: Vec<FailedShard>,
+}
+
+/// Replica, which served a read of a shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardTrace {
+    pub shard_id: u32,
+    /// Peer of the replica, which answered
+    pub peer_id: u64,
+    /// If true - the replica is on the peer, which received the request
+    pub local: bool,
+    /// State of the replica at the moment of the read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replica_state: Option<String>,
+    /// Time spent by the replica to answer, in seconds
+    pub latency: f64,
+}
+
+/// Stable code of the reason of a warning, which clients can branch on
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningReason {
+    /// Field, the points are grouped by, has no payload index, so the payload of the points is
+    /// scanned to find the groups
+    GroupByFieldNotIndexed,
+    /// Filter has conditions, which can't be served by payload indexes,
+    /// so the payload of the points is scanned to check them
+    UnindexedFilter,
+}
+
+impl WarningReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningReason::GroupByFieldNotIndexed => "group_by_field_not_indexed",
+            WarningReason::UnindexedFilter => "unindexed_filter",
+        }
+    }
+}
+
+/// Issue of a request, which is served anyway
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Warning {
+    pub reason: WarningReason,
+    /// Human-readable description of the issue
+    pub message: String,
+}
+
+impl Warning {
+    pub fn group_by_field_not_indexed(field: &str) -> Self {
+        Self {
+            reason: WarningReason::GroupByFieldNotIndexed,
+            message: format!(
+                "Field `{field}` has no payload index, grouping by it requires a payload scan. \
+                 Create a keyword or integer index for the field to speed up the grouping"
+            ),
+        }
+    }
+
+    pub fn unindexed_filter(keys: &[String]) -> Self {
+        Self {
+            reason: WarningReason::UnindexedFilter,
+            message: format!(
+                "Filtering by fields without payload index requires a payload scan: {}",
+                keys.join(", "),
+            ),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -20,6 +20,9 @@ pub struct VectorParams {
     /// If true - serve vectors from disk. If set to false, the vectors will be loaded in RAM.
     #[prost(bool, optional, tag = "5")]
     pub on_disk: ::core::option::Option<bool>,
+    /// Data type of the stored vectors. Can not be changed after the collection is created. Default is Float32
+    #[prost(enumeration = "Datatype", optional, tag = "6")]
+    pub datatype: ::core::option::Option<i32>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -48,6 +51,29 @@ pub mod vectors_config {
         ParamsMap(super::VectorParamsMap),
     }
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseIndexConfig {
+    /// If the number of points matched by the filter is below this value, the inverted index is not used for search
+    #[prost(uint64, optional, tag = "1")]
+    pub full_scan_threshold: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseVectorParams {
+    /// Configuration of the sparse index
+    #[prost(message, optional, tag = "1")]
+    pub index: ::core::option::Option<SparseIndexConfig>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseVectorConfig {
+    #[prost(map = "string, message", tag = "1")]
+    pub map: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        SparseVectorParams,
+    >,
+}
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -204,6 +230,22 @@ pub struct OptimizersConfigDiff {
     /// Max number of threads, which can be used for optimization. If 0 - `NUM_CPU - 1` will be used
     #[prost(uint64, optional, tag = "8")]
     pub max_optimization_threads: ::core::option::Option<u64>,
+    ///
+    /// Max number of threads, which all concurrent optimizations of a shard can use for building indexes. If 0 - `NUM_CPU - 1` will be used
+    #[prost(uint64, optional, tag = "9")]
+    pub max_indexing_threads: ::core::option::Option<u64>,
+    ///
+    /// Maximum number of segments the merge optimizer will try to keep.
+    /// Merges are not proposed while the number of segments is at or below this value.
+    /// If 0 - `default_segment_number` is used.
+    #[prost(uint64, optional, tag = "10")]
+    pub max_segment_number: ::core::option::Option<u64>,
+    ///
+    /// Size (in kilobytes), at which segments are considered large enough and are not merged anymore.
+    /// Merges never produce segments above `max_segment_size`.
+    /// Note: 1Kb = 1 vector of size 256
+    #[prost(uint64, optional, tag = "11")]
+    pub target_segment_size: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -253,6 +295,176 @@ pub mod quantization_config {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StrictModeConfig {
+    /// Whether the limits are enforced
+    #[prost(bool, optional, tag = "1")]
+    pub enabled: ::core::option::Option<bool>,
+    /// Max allowed `limit` parameter of the read requests
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_query_limit: ::core::option::Option<u64>,
+    /// Max allowed `offset` parameter of the read requests
+    #[prost(uint64, optional, tag = "3")]
+    pub max_query_offset: ::core::option::Option<u64>,
+    /// Max number of conditions in a filter, including the conditions of the nested filters
+    #[prost(uint64, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_filter_conditions: ::core::option::Option<u64>,
+    /// If true - filtering by fields without payload index is forbidden
+    #[prost(bool, optional, tag = "5")]
+    pub require_payload_index: ::core::option::Option<bool>,
+    /// Max product of `limit` and `group_size` of the grouped requests
+    #[prost(uint64, optional, tag = "6")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_group_by_points: ::core::option::Option<u64>,
+    /// How to serve filters with conditions, which can't be served by payload indexes
+    #[prost(enumeration = "UnindexedFiltering", optional, tag = "7")]
+    pub unindexed_filtering: ::core::option::Option<i32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TtlConfig {
+    /// Payload field with the expiration time of the point, as UNIX timestamp in seconds
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1))]
+    pub field: ::prost::alloc::string::String,
+    /// Interval between the deletions of the expired points in seconds
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub vacuum_interval_sec: ::core::option::Option<u64>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotaConfig {
+    /// Max number of points in the collection
+    #[prost(uint64, optional, tag = "1")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_points: ::core::option::Option<u64>,
+    /// Max total size of the vectors in bytes
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_vectors_size_bytes: ::core::option::Option<u64>,
+    /// Max total size of the payloads in bytes, measured as the size of their JSON
+    #[prost(uint64, optional, tag = "3")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_payload_size_bytes: ::core::option::Option<u64>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdmissionConfig {
+    /// Max number of read operations, executed concurrently
+    #[prost(uint64, optional, tag = "1")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_concurrent_reads: ::core::option::Option<u64>,
+    /// Max number of read operations, waiting for execution. Further operations are rejected
+    #[prost(uint64, optional, tag = "2")]
+    pub max_queued_reads: ::core::option::Option<u64>,
+    /// Max number of write operations per second
+    #[prost(uint64, optional, tag = "3")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_write_operations_per_second: ::core::option::Option<u64>,
+    /// Max number of points per second, listed in the write operations
+    #[prost(uint64, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_write_points_per_second: ::core::option::Option<u64>,
+    /// Number of seconds of the unused write rate, which can be spent by a burst of operations. Default: 1
+    #[prost(uint64, optional, tag = "5")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1_max_3600")]
+    pub write_burst_sec: ::core::option::Option<u64>,
+    /// What to do with the write operations over the rate limit. Default: reject
+    #[prost(enumeration = "WriteRateLimitMode", optional, tag = "6")]
+    pub write_rate_limit_mode: ::core::option::Option<i32>,
+    /// Max delay of a write operation in `delay` mode, in milliseconds. Default: 1000
+    #[prost(uint64, optional, tag = "7")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_max_600000")]
+    pub max_write_delay_ms: ::core::option::Option<u64>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReadCacheConfig {
+    /// Max number of cached responses. If not set - responses are not cached
+    #[prost(uint64, optional, tag = "1")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_entries: ::core::option::Option<u64>,
+    /// Max age of a cached response in seconds. If not set - responses are kept until the collection is updated
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub ttl_sec: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotaUsage {
+    /// Number of points
+    #[prost(uint64, tag = "1")]
+    pub points: u64,
+    /// Total size of the vectors in bytes
+    #[prost(uint64, tag = "2")]
+    pub vectors_size_bytes: u64,
+    /// Total size of the payloads in bytes
+    #[prost(uint64, tag = "3")]
+    pub payload_size_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FieldIndexBuildProgress {
+    #[prost(string, tag = "1")]
+    pub field_name: ::prost::alloc::string::String,
+    /// Id of the operation, which started the creation of the index in the shard
+    #[prost(uint64, tag = "2")]
+    pub operation_id: u64,
+    #[prost(uint32, tag = "3")]
+    pub shard_id: u32,
+    /// Number of segments, which are building the index
+    #[prost(uint64, tag = "4")]
+    pub building_segments: u64,
+    /// Points of the building segments, which are already indexed
+    #[prost(uint64, tag = "5")]
+    pub indexed_points: u64,
+    /// All points of the building segments
+    #[prost(uint64, tag = "6")]
+    pub total_points: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MergeTargets {
+    /// Merges are not proposed at or below this number of segments
+    #[prost(uint64, tag = "1")]
+    pub max_segment_number: u64,
+    /// Segments of this size or larger are not merged
+    #[prost(uint64, tag = "2")]
+    pub target_segment_size_kb: u64,
+    /// Merges never produce segments larger than this
+    #[prost(uint64, tag = "3")]
+    pub max_segment_size_kb: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WarmupProgress {
+    #[prost(enumeration = "WarmupState", tag = "1")]
+    pub state: i32,
+    /// Number of segments, which data is read completely
+    #[prost(uint64, tag = "2")]
+    pub segments_warmed: u64,
+    /// Number of segments of the local shards at the start of the warmup
+    #[prost(uint64, tag = "3")]
+    pub segments_total: u64,
+    /// Amount of data, read so far
+    #[prost(uint64, tag = "4")]
+    pub bytes_touched: u64,
+    /// Number of searches with random vectors, made so far
+    #[prost(uint64, tag = "5")]
+    pub searches_done: u64,
+    /// Reason of the failure
+    #[prost(string, optional, tag = "6")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateCollection {
     /// Name of the collection
     #[prost(string, tag = "1")]
@@ -299,6 +511,48 @@ pub struct CreateCollection {
     #[prost(message, optional, tag = "14")]
     #[validate]
     pub quantization_config: ::core::option::Option<QuantizationConfig>,
+    /// Limits of the read requests
+    #[prost(message, optional, tag = "15")]
+    #[validate]
+    pub strict_mode_config: ::core::option::Option<StrictModeConfig>,
+    /// If true - payload values are checked against the types of the indexed fields
+    #[prost(bool, optional, tag = "16")]
+    pub validate_payload: ::core::option::Option<bool>,
+    /// Whether the whole operation or only the invalid points are rejected
+    #[prost(enumeration = "PayloadValidationMode", optional, tag = "17")]
+    pub payload_validation_mode: ::core::option::Option<i32>,
+    /// If set - a read is also sent to another replica, if the first one doesn't respond within this delay
+    #[prost(uint64, optional, tag = "18")]
+    pub read_hedge_delay_ms: ::core::option::Option<u64>,
+    /// Expiration of the points by a payload field
+    #[prost(message, optional, tag = "19")]
+    #[validate]
+    pub ttl_config: ::core::option::Option<TtlConfig>,
+    /// Configuration for sparse vectors
+    #[prost(message, optional, tag = "20")]
+    pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
+    /// Named vector, used by the requests, which don't specify the vector name
+    #[prost(string, optional, tag = "21")]
+    pub default_vector: ::core::option::Option<::prost::alloc::string::String>,
+    /// Payload indexes, created in every segment of the collection from the start
+    #[prost(map = "string, message", tag = "22")]
+    pub payload_indexes:
+        ::std::collections::HashMap<::prost::alloc::string::String, PayloadSchemaInfo>,
+    /// Payload fields, kept in memory if payload is stored on disk
+    #[prost(string, repeated, tag = "23")]
+    pub payload_cache_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Limits of the data, stored in the collection
+    #[prost(message, optional, tag = "24")]
+    #[validate]
+    pub quota_config: ::core::option::Option<QuotaConfig>,
+    /// Limits of the read and write operations
+    #[prost(message, optional, tag = "25")]
+    #[validate]
+    pub admission_config: ::core::option::Option<AdmissionConfig>,
+    /// Cache of the responses to the read operations
+    #[prost(message, optional, tag = "26")]
+    #[validate]
+    pub read_cache_config: ::core::option::Option<ReadCacheConfig>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -320,6 +574,29 @@ pub struct UpdateCollection {
     #[prost(message, optional, tag = "4")]
     #[validate]
     pub params: ::core::option::Option<CollectionParamsDiff>,
+    /// New limits of the read requests
+    #[prost(message, optional, tag = "5")]
+    #[validate]
+    pub strict_mode_config: ::core::option::Option<StrictModeConfig>,
+    /// New expiration of the points by a payload field
+    #[prost(message, optional, tag = "6")]
+    #[validate]
+    pub ttl_config: ::core::option::Option<TtlConfig>,
+    /// New named vector, used by the requests, which don't specify the vector name. Empty string resets it
+    #[prost(string, optional, tag = "7")]
+    pub default_vector: ::core::option::Option<::prost::alloc::string::String>,
+    /// New limits of the data, stored in the collection
+    #[prost(message, optional, tag = "8")]
+    #[validate]
+    pub quota_config: ::core::option::Option<QuotaConfig>,
+    /// New limits of the read and write operations
+    #[prost(message, optional, tag = "9")]
+    #[validate]
+    pub admission_config: ::core::option::Option<AdmissionConfig>,
+    /// New parameters of the cache of the responses to the read operations
+    #[prost(message, optional, tag = "10")]
+    #[validate]
+    pub read_cache_config: ::core::option::Option<ReadCacheConfig>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -364,6 +641,24 @@ pub struct CollectionParams {
     /// How many replicas should apply the operation for us to consider it successful
     #[prost(uint32, optional, tag = "7")]
     pub write_consistency_factor: ::core::option::Option<u32>,
+    /// If true - payload values are checked against the types of the indexed fields
+    #[prost(bool, optional, tag = "8")]
+    pub validate_payload: ::core::option::Option<bool>,
+    /// Whether the whole operation or only the invalid points are rejected
+    #[prost(enumeration = "PayloadValidationMode", optional, tag = "9")]
+    pub payload_validation_mode: ::core::option::Option<i32>,
+    /// If set - a read is also sent to another replica, if the first one doesn't respond within this delay
+    #[prost(uint64, optional, tag = "10")]
+    pub read_hedge_delay_ms: ::core::option::Option<u64>,
+    /// Configuration for sparse vectors
+    #[prost(message, optional, tag = "11")]
+    pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
+    /// If true - updates of the collection are rejected
+    #[prost(bool, optional, tag = "12")]
+    pub read_only: ::core::option::Option<bool>,
+    /// If true - fields, which the points are grouped by, are indexed automatically
+    #[prost(bool, optional, tag = "13")]
+    pub auto_index_group_by_fields: ::core::option::Option<bool>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -375,6 +670,21 @@ pub struct CollectionParamsDiff {
     /// How many replicas should apply the operation for us to consider it successful
     #[prost(uint32, optional, tag = "2")]
     pub write_consistency_factor: ::core::option::Option<u32>,
+    /// If true - payload values are checked against the types of the indexed fields
+    #[prost(bool, optional, tag = "3")]
+    pub validate_payload: ::core::option::Option<bool>,
+    /// Whether the whole operation or only the invalid points are rejected
+    #[prost(enumeration = "PayloadValidationMode", optional, tag = "4")]
+    pub payload_validation_mode: ::core::option::Option<i32>,
+    /// If set - a read is also sent to another replica, if the first one doesn't respond within this delay
+    #[prost(uint64, optional, tag = "5")]
+    pub read_hedge_delay_ms: ::core::option::Option<u64>,
+    /// If true - updates of the collection are rejected until the flag is cleared
+    #[prost(bool, optional, tag = "6")]
+    pub read_only: ::core::option::Option<bool>,
+    /// If true - fields, which the points are grouped by, are indexed automatically
+    #[prost(bool, optional, tag = "7")]
+    pub auto_index_group_by_fields: ::core::option::Option<bool>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -398,6 +708,29 @@ pub struct CollectionConfig {
     #[prost(message, optional, tag = "5")]
     #[validate]
     pub quantization_config: ::core::option::Option<QuantizationConfig>,
+    /// Limits of the read requests
+    #[prost(message, optional, tag = "6")]
+    #[validate]
+    pub strict_mode_config: ::core::option::Option<StrictModeConfig>,
+    /// Expiration of the points by a payload field
+    #[prost(message, optional, tag = "7")]
+    #[validate]
+    pub ttl_config: ::core::option::Option<TtlConfig>,
+    /// Named vector, used by the requests, which don't specify the vector name
+    #[prost(string, optional, tag = "8")]
+    pub default_vector: ::core::option::Option<::prost::alloc::string::String>,
+    /// Limits of the data, stored in the collection
+    #[prost(message, optional, tag = "9")]
+    #[validate]
+    pub quota_config: ::core::option::Option<QuotaConfig>,
+    /// Limits of the read and write operations
+    #[prost(message, optional, tag = "10")]
+    #[validate]
+    pub admission_config: ::core::option::Option<AdmissionConfig>,
+    /// Cache of the responses to the read operations
+    #[prost(message, optional, tag = "11")]
+    #[validate]
+    pub read_cache_config: ::core::option::Option<ReadCacheConfig>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -417,8 +750,18 @@ pub struct TextIndexParams {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeywordIndexParams {
+    /// If true - the field identifies a tenant of a multitenant collection
+    #[prost(bool, optional, tag = "1")]
+    pub is_tenant: ::core::option::Option<bool>,
+    /// If true - the index is served from disk, only the statistics are kept in RAM
+    #[prost(bool, optional, tag = "2")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PayloadIndexParams {
-    #[prost(oneof = "payload_index_params::IndexParams", tags = "1")]
+    #[prost(oneof = "payload_index_params::IndexParams", tags = "1, 2")]
     pub index_params: ::core::option::Option<payload_index_params::IndexParams>,
 }
 /// Nested message and enum types in `PayloadIndexParams`.
@@ -429,6 +772,9 @@ pub mod payload_index_params {
         /// Parameters for text index
         #[prost(message, tag = "1")]
         TextIndexParams(super::TextIndexParams),
+        /// Parameters for keyword index
+        #[prost(message, tag = "2")]
+        KeywordIndexParams(super::KeywordIndexParams),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -443,6 +789,12 @@ pub struct PayloadSchemaInfo {
     /// Number of points indexed within this field indexed
     #[prost(uint64, optional, tag = "3")]
     pub points: ::core::option::Option<u64>,
+    /// Number of indexed values, a point with several values is counted once per value
+    #[prost(uint64, optional, tag = "4")]
+    pub values_count: ::core::option::Option<u64>,
+    /// Approximate RAM, occupied by the indexes of the field, in bytes
+    #[prost(uint64, optional, tag = "5")]
+    pub estimated_ram_bytes: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -474,6 +826,18 @@ pub struct CollectionInfo {
     /// number of indexed vectors in the collection.
     #[prost(uint64, optional, tag = "10")]
     pub indexed_vectors_count: ::core::option::Option<u64>,
+    /// Data, counted against the quotas of the collection
+    #[prost(message, optional, tag = "11")]
+    pub quota_usage: ::core::option::Option<QuotaUsage>,
+    /// Progress of the latest warmup of the local shards
+    #[prost(message, optional, tag = "12")]
+    pub warmup: ::core::option::Option<WarmupProgress>,
+    /// Creations of payload field indexes in the local shards
+    #[prost(message, repeated, tag = "13")]
+    pub index_builds: ::prost::alloc::vec::Vec<FieldIndexBuildProgress>,
+    /// Effective targets of the merge optimizer
+    #[prost(message, optional, tag = "14")]
+    pub merge_targets: ::core::option::Option<MergeTargets>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -631,6 +995,9 @@ pub struct CollectionClusterInfoResponse {
     /// Shard transfers
     #[prost(message, repeated, tag = "5")]
     pub shard_transfers: ::prost::alloc::vec::Vec<ShardTransferInfo>,
+    /// Shard moves, which would even out the placement of the shards on the peers
+    #[prost(message, repeated, tag = "6")]
+    pub suggested_moves: ::prost::alloc::vec::Vec<MoveShard>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -724,6 +1091,38 @@ impl Distance {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum Datatype {
+    Default = 0,
+    Float32 = 1,
+    Float16 = 2,
+    Uint8 = 3,
+}
+impl Datatype {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Datatype::Default => "Default",
+            Datatype::Float32 => "Float32",
+            Datatype::Float16 => "Float16",
+            Datatype::Uint8 => "Uint8",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Default" => Some(Self::Default),
+            "Float32" => Some(Self::Float32),
+            "Float16" => Some(Self::Float16),
+            "Uint8" => Some(Self::Uint8),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum CollectionStatus {
     UnknownCollectionStatus = 0,
     /// All segments are ready
@@ -858,6 +1257,128 @@ impl CompressionRatio {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum PayloadValidationMode {
+    /// Reject the whole operation, if payload of any point is invalid
+    PayloadValidationOperation = 0,
+    /// Reject only the points with invalid payload
+    PayloadValidationPoint = 1,
+}
+impl PayloadValidationMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PayloadValidationMode::PayloadValidationOperation => {
+                "PayloadValidationOperation"
+            }
+            PayloadValidationMode::PayloadValidationPoint => "PayloadValidationPoint",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PayloadValidationOperation" => Some(Self::PayloadValidationOperation),
+            "PayloadValidationPoint" => Some(Self::PayloadValidationPoint),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WarmupState {
+    WarmupInProgress = 0,
+    WarmupFinished = 1,
+    WarmupCancelled = 2,
+    WarmupFailed = 3,
+}
+impl WarmupState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WarmupState::WarmupInProgress => "WarmupInProgress",
+            WarmupState::WarmupFinished => "WarmupFinished",
+            WarmupState::WarmupCancelled => "WarmupCancelled",
+            WarmupState::WarmupFailed => "WarmupFailed",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WarmupInProgress" => Some(Self::WarmupInProgress),
+            "WarmupFinished" => Some(Self::WarmupFinished),
+            "WarmupCancelled" => Some(Self::WarmupCancelled),
+            "WarmupFailed" => Some(Self::WarmupFailed),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UnindexedFiltering {
+    /// Scan the payload without notice
+    Allow = 0,
+    /// Serve the request, but warn about the unindexed conditions in the response
+    Warn = 1,
+    /// Reject the request, listing the keys of the unindexed conditions
+    Deny = 2,
+}
+impl UnindexedFiltering {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            UnindexedFiltering::Allow => "UnindexedFilteringAllow",
+            UnindexedFiltering::Warn => "UnindexedFilteringWarn",
+            UnindexedFiltering::Deny => "UnindexedFilteringDeny",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnindexedFilteringAllow" => Some(Self::Allow),
+            "UnindexedFilteringWarn" => Some(Self::Warn),
+            "UnindexedFilteringDeny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WriteRateLimitMode {
+    /// Reject the operation, suggesting when to retry it
+    Reject = 0,
+    /// Delay the operation until it fits the limit, up to `max_write_delay_ms`
+    Delay = 1,
+}
+impl WriteRateLimitMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WriteRateLimitMode::Reject => "WriteRateLimitModeReject",
+            WriteRateLimitMode::Delay => "WriteRateLimitModeDelay",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WriteRateLimitModeReject" => Some(Self::Reject),
+            "WriteRateLimitModeDelay" => Some(Self::Delay),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum TokenizerType {
     Unknown = 0,
     Prefix = 1,
@@ -2472,6 +2993,25 @@ pub mod read_consistency {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WriteConsistency {
+    #[prost(oneof = "write_consistency::Value", tags = "1, 2")]
+    pub value: ::core::option::Option<write_consistency::Value>,
+}
+/// Nested message and enum types in `WriteConsistency`.
+pub mod write_consistency {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        /// Common write consistency configurations
+        #[prost(enumeration = "super::WriteConsistencyType", tag = "1")]
+        Type(i32),
+        /// Wait for a specified number of replicas of the shard
+        #[prost(uint64, tag = "2")]
+        Factor(u64),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PointId {
     #[prost(oneof = "point_id::PointIdOptions", tags = "1, 2")]
     pub point_id_options: ::core::option::Option<point_id::PointIdOptions>,
@@ -2492,9 +3032,19 @@ pub mod point_id {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseIndices {
+    #[prost(uint32, repeated, tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u32>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Vector {
     #[prost(float, repeated, tag = "1")]
     pub data: ::prost::alloc::vec::Vec<f32>,
+    /// Indices of the non-zero dimensions, if the vector is sparse
+    #[prost(message, optional, tag = "2")]
+    pub indices: ::core::option::Option<SparseIndices>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2512,6 +3062,28 @@ pub struct UpsertPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpsertArrowPoints {
+    /// name of the collection, only read from the first message of the stream
+    #[prost(string, tag = "1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied? Only read from the first message of the stream
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Arrow IPC stream with the schema and record batches of the points
+    #[prost(bytes = "vec", tag = "3")]
+    pub arrow_ipc: ::prost::alloc::vec::Vec<u8>,
+    /// Write ordering guarantees, only read from the first message of the stream
+    #[prost(message, optional, tag = "4")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2530,6 +3102,12 @@ pub struct DeletePoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2551,6 +3129,12 @@ pub struct GetPoints {
     /// Options for specifying read consistency guarantees
     #[prost(message, optional, tag = "6")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Return the time of the last update of the points
+    #[prost(bool, optional, tag = "7")]
+    pub with_timestamps: ::core::option::Option<bool>,
+    /// Limit of the serialized JSON payload of each returned point, in bytes. Larger payloads are truncated by dropping their largest fields
+    #[prost(uint64, optional, tag = "8")]
+    pub max_payload_return_bytes: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2569,6 +3153,12 @@ pub struct UpdatePointVectors {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2600,6 +3190,12 @@ pub struct DeletePointVectors {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "5")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "6")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "7")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2621,6 +3217,12 @@ pub struct SetPayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "6")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "7")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "8")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2642,6 +3244,12 @@ pub struct DeletePayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "6")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "7")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "8")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2660,6 +3268,12 @@ pub struct ClearPayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2685,6 +3299,12 @@ pub struct CreateFieldIndexCollection {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "6")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "7")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "8")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2704,6 +3324,61 @@ pub struct DeleteFieldIndexCollection {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RebuildFieldIndexCollection {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the rebuild is scheduled?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Field name to rebuild the index of
+    #[prost(string, tag = "3")]
+    #[validate(length(min = 1))]
+    pub field_name: ::prost::alloc::string::String,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "4")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelFieldIndexBuildCollection {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the build is stopped?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Id of the operation, which started the creation of the index
+    #[prost(uint64, tag = "3")]
+    pub build_operation_id: u64,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "4")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Client-supplied UUID of the operation. Retries of the operation with the same id are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Wait until the specified number of shard replicas applies the operation
+    #[prost(message, optional, tag = "6")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2711,6 +3386,9 @@ pub struct PayloadIncludeSelector {
     /// List of payload keys to include into result
     #[prost(string, repeated, tag = "1")]
     pub fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// List of payload keys to exclude from the included ones
+    #[prost(string, repeated, tag = "2")]
+    pub exclude_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2772,8 +3450,21 @@ pub struct VectorsSelector {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VectorSliceSelector {
+    /// Name of the dense vector, empty for the unnamed vector
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// First dimension to return
+    #[prost(uint64, tag = "2")]
+    pub start: u64,
+    /// Dimension after the last one to return
+    #[prost(uint64, tag = "3")]
+    pub end: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WithVectorsSelector {
-    #[prost(oneof = "with_vectors_selector::SelectorOptions", tags = "1, 2")]
+    #[prost(oneof = "with_vectors_selector::SelectorOptions", tags = "1, 2, 3, 4")]
     pub selector_options: ::core::option::Option<with_vectors_selector::SelectorOptions>,
 }
 /// Nested message and enum types in `WithVectorsSelector`.
@@ -2787,6 +3478,12 @@ pub mod with_vectors_selector {
         /// List of payload keys to include into result
         #[prost(message, tag = "2")]
         Include(super::VectorsSelector),
+        /// List of vectors to exclude from the result, all other vectors are returned
+        #[prost(message, tag = "3")]
+        Exclude(super::VectorsSelector),
+        /// Range of the dimensions of a single dense vector to return in `vector_slice`, instead of the vectors
+        #[prost(message, tag = "4")]
+        Slice(super::VectorSliceSelector),
     }
 }
 #[derive(validator::Validate)]
@@ -2816,6 +3513,26 @@ pub struct QuantizationSearchParams {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdaptiveEfParams {
+    ///
+    /// Fraction of segment points, matching the filter, below which `ef` is scaled. Default is 0.1
+    #[prost(double, optional, tag = "1")]
+    #[validate(custom = "crate::grpc::validate::validate_f64_range_1")]
+    pub selectivity_threshold: ::core::option::Option<f64>,
+    ///
+    /// Upper limit of the scaled `ef`. Default is 16 times the original `ef`
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub max_ef: ::core::option::Option<u64>,
+    ///
+    /// Fraction of segment points, matching the filter, below which exact filtered scoring is used instead of HNSW graph search
+    #[prost(double, optional, tag = "3")]
+    #[validate(custom = "crate::grpc::validate::validate_f64_range_1")]
+    pub exact_selectivity_threshold: ::core::option::Option<f64>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SearchParams {
     ///
     /// Params relevant to HNSW index. Size of the beam in a beam-search.
@@ -2831,6 +3548,17 @@ pub struct SearchParams {
     #[prost(message, optional, tag = "3")]
     #[validate]
     pub quantization: ::core::option::Option<QuantizationSearchParams>,
+    ///
+    /// If enabled, the engine will only perform search among indexed or small segments.
+    /// Using this option prevents slow searches in case of delayed index, but does not
+    /// guarantee that all uploaded vectors will be included in search results
+    #[prost(bool, optional, tag = "4")]
+    pub indexed_only: ::core::option::Option<bool>,
+    ///
+    /// Scale `ef` of HNSW search for selective filters
+    #[prost(message, optional, tag = "5")]
+    #[validate]
+    pub adaptive_ef: ::core::option::Option<AdaptiveEfParams>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2873,6 +3601,21 @@ pub struct SearchPoints {
     /// Options for specifying read consistency guarantees
     #[prost(message, optional, tag = "12")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Return hardware usage of the request in the response
+    #[prost(bool, optional, tag = "13")]
+    pub with_usage: ::core::option::Option<bool>,
+    /// If true - shards, which failed to respond, are skipped and listed in the response
+    #[prost(bool, optional, tag = "14")]
+    pub allow_partial_results: ::core::option::Option<bool>,
+    /// If set - search by a sparse vector, `vector` holds the values of these dimensions
+    #[prost(message, optional, tag = "15")]
+    pub sparse_indices: ::core::option::Option<SparseIndices>,
+    /// Return the replicas, which served the shards of the request, in the response
+    #[prost(bool, optional, tag = "16")]
+    pub with_shard_trace: ::core::option::Option<bool>,
+    /// Limit of the serialized JSON payload of each returned point, in bytes. Larger payloads are truncated by dropping their largest fields
+    #[prost(uint64, optional, tag = "17")]
+    pub max_payload_return_bytes: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2951,6 +3694,29 @@ pub struct SearchPointGroups {
     /// Options for specifying how to use the group id to lookup points in another collection
     #[prost(message, optional, tag = "13")]
     pub with_lookup: ::core::option::Option<WithLookup>,
+    /// Return hardware usage of all searches of the grouping in the response
+    #[prost(bool, optional, tag = "14")]
+    pub with_usage: ::core::option::Option<bool>,
+    /// If true - shards, which failed to respond in any of the searches of the grouping, are skipped and listed in the response
+    #[prost(bool, optional, tag = "15")]
+    pub allow_partial_results: ::core::option::Option<bool>,
+    /// If set - search by a sparse vector, `vector` holds the values of these dimensions
+    #[prost(message, optional, tag = "16")]
+    pub sparse_indices: ::core::option::Option<SparseIndices>,
+    /// Return the statistics of the internal requests of the grouping
+    #[prost(bool, optional, tag = "17")]
+    pub with_group_by_stats: ::core::option::Option<bool>,
+    /// If set - return the best points of exactly these groups, in the same order. `limit` is not applied
+    #[prost(message, repeated, tag = "18")]
+    #[validate(length(max = 1000))]
+    pub group_keys: ::prost::alloc::vec::Vec<GroupId>,
+    /// Limit of the serialized JSON payload of each returned point, in bytes. Larger payloads are truncated by dropping their largest fields
+    #[prost(uint64, optional, tag = "19")]
+    pub max_payload_return_bytes: ::core::option::Option<u64>,
+    /// Oversampling of the first request to find the groups, relative to `limit * group_size` points. Doubled for the next request, if too few groups are found. Default: 1
+    #[prost(float, optional, tag = "20")]
+    #[validate(range(min = 0.1, max = 100.0))]
+    pub group_oversampling: ::core::option::Option<f32>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2978,6 +3744,12 @@ pub struct ScrollPoints {
     /// Options for specifying read consistency guarantees
     #[prost(message, optional, tag = "8")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Return the time of the last update of the points
+    #[prost(bool, optional, tag = "9")]
+    pub with_timestamps: ::core::option::Option<bool>,
+    /// Limit of the serialized JSON payload of each returned point, in bytes. Larger payloads are truncated by dropping their largest fields
+    #[prost(uint64, optional, tag = "10")]
+    pub max_payload_return_bytes: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2987,6 +3759,9 @@ pub struct LookupLocation {
     /// Which vector to use for search, if not specified - use default vector
     #[prost(string, optional, tag = "2")]
     pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Grouped recommendations only: copy the value of this payload key of the examples into each group
+    #[prost(string, optional, tag = "3")]
+    pub copy_payload_key: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3103,6 +3878,20 @@ pub struct RecommendPointGroups {
     /// Options for specifying how to use the group id to lookup points in another collection
     #[prost(message, optional, tag = "15")]
     pub with_lookup: ::core::option::Option<WithLookup>,
+    /// Return the statistics of the internal requests of the grouping
+    #[prost(bool, optional, tag = "16")]
+    pub with_group_by_stats: ::core::option::Option<bool>,
+    /// If set - return the best points of exactly these groups, in the same order. `limit` is not applied
+    #[prost(message, repeated, tag = "17")]
+    #[validate(length(max = 1000))]
+    pub group_keys: ::prost::alloc::vec::Vec<GroupId>,
+    /// Limit of the serialized JSON payload of each returned point, in bytes. Larger payloads are truncated by dropping their largest fields
+    #[prost(uint64, optional, tag = "18")]
+    pub max_payload_return_bytes: ::core::option::Option<u64>,
+    /// Oversampling of the first request to find the groups, relative to `limit * group_size` points. Doubled for the next request, if too few groups are found. Default: 1
+    #[prost(float, optional, tag = "19")]
+    #[validate(range(min = 0.1, max = 100.0))]
+    pub group_oversampling: ::core::option::Option<f32>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3137,6 +3926,15 @@ pub struct UpdateResult {
     /// Operation status
     #[prost(enumeration = "UpdateStatus", tag = "2")]
     pub status: i32,
+    /// Number of points, deleted by a filter
+    #[prost(uint64, optional, tag = "3")]
+    pub deleted_count: ::core::option::Option<u64>,
+    /// Number of points, which payload is updated by a filter
+    #[prost(uint64, optional, tag = "4")]
+    pub updated_count: ::core::option::Option<u64>,
+    /// If true - the count is computed, when the operation is accepted, before it is applied
+    #[prost(bool, optional, tag = "5")]
+    pub counted_before_apply: ::core::option::Option<bool>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3156,6 +3954,38 @@ pub struct ScoredPoint {
     /// Vectors to search
     #[prost(message, optional, tag = "6")]
     pub vectors: ::core::option::Option<Vectors>,
+    /// Set if the payload is truncated to fit into `max_payload_return_bytes`
+    #[prost(message, optional, tag = "7")]
+    pub payload_truncation: ::core::option::Option<PayloadTruncation>,
+    /// Dimensions of the vector, if requested with the slice vectors selector
+    #[prost(message, optional, tag = "8")]
+    pub vector_slice: ::core::option::Option<VectorSlice>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VectorSlice {
+    /// Name of the vector
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// First returned dimension
+    #[prost(uint64, tag = "2")]
+    pub start: u64,
+    /// Dimension after the last returned one
+    #[prost(uint64, tag = "3")]
+    pub end: u64,
+    /// Values of the dimensions `start..end`
+    #[prost(float, repeated, tag = "4")]
+    pub values: ::prost::alloc::vec::Vec<f32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayloadTruncation {
+    /// Always true, if the payload is truncated
+    #[prost(bool, tag = "1")]
+    pub payload_truncated: bool,
+    /// Keys of the payload fields, dropped from the response. Largest fields are dropped first
+    #[prost(string, repeated, tag = "2")]
+    pub dropped_payload_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3191,6 +4021,9 @@ pub struct PointGroup {
     /// Point(s) from the lookup collection that matches the group id
     #[prost(message, optional, tag = "3")]
     pub lookup: ::core::option::Option<RetrievedPoint>,
+    /// Examples of the recommendation, if `copy_payload_key` of the lookup location is set
+    #[prost(message, repeated, tag = "4")]
+    pub examples: ::prost::alloc::vec::Vec<RetrievedPoint>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3198,6 +4031,127 @@ pub struct GroupsResult {
     /// Groups
     #[prost(message, repeated, tag = "1")]
     pub groups: ::prost::alloc::vec::Vec<PointGroup>,
+    /// Statistics of the internal requests, if requested
+    #[prost(message, optional, tag = "2")]
+    pub stats: ::core::option::Option<GroupByStats>,
+    /// Issues of the request, which don't prevent it from being served
+    #[prost(message, repeated, tag = "3")]
+    pub warnings: ::prost::alloc::vec::Vec<Warning>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Warning {
+    /// Stable code of the reason of the warning, e.g. `group_by_field_not_indexed`
+    #[prost(string, tag = "1")]
+    pub reason: ::prost::alloc::string::String,
+    /// Human-readable description of the issue
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupByStats {
+    /// Amount of requests, made to find the best groups
+    #[prost(uint64, tag = "1")]
+    pub groups_requests: u64,
+    /// Amount of requests, made to fill up the best groups
+    #[prost(uint64, tag = "2")]
+    pub filling_requests: u64,
+    /// Amount of points, fetched by each of the requests
+    #[prost(uint64, repeated, tag = "3")]
+    pub fetched_points: ::prost::alloc::vec::Vec<u64>,
+    /// Amount of groups, the fetched points belong to
+    #[prost(uint64, tag = "4")]
+    pub groups_created: u64,
+    /// Amount of the returned groups, which have `group_size` hits
+    #[prost(uint64, tag = "5")]
+    pub groups_filled: u64,
+    /// Whether the requests to find the groups have stopped at their maximum amount
+    #[prost(bool, tag = "6")]
+    pub groups_requests_limit_reached: bool,
+    /// Whether the requests to fill up the groups have stopped at their maximum amount
+    #[prost(bool, tag = "7")]
+    pub filling_requests_limit_reached: bool,
+    /// Amount of group keys, left out of the filters of the requests
+    #[prost(uint64, tag = "8")]
+    pub dropped_filter_keys: u64,
+    /// Oversampling factors of the requests, made to find the best groups
+    #[prost(float, repeated, tag = "9")]
+    pub oversampling_factors: ::prost::alloc::vec::Vec<f32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchDebugInfo {
+    /// Number of segment searches, which scored all matching points
+    #[prost(uint64, tag = "1")]
+    pub plain_searches: u64,
+    /// Number of segment searches, which used HNSW graph with configured `ef`
+    #[prost(uint64, tag = "2")]
+    pub hnsw_searches: u64,
+    /// Number of segment searches, which used HNSW graph with scaled `ef`
+    #[prost(uint64, tag = "3")]
+    pub hnsw_adaptive_ef_searches: u64,
+    /// Largest `ef`, used by the HNSW graph searches. Not set, if the graph is not used
+    #[prost(uint64, optional, tag = "4")]
+    pub max_ef: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HardwareUsage {
+    /// Number of vectors, scored against the query. Sum of quantized and original comparisons
+    #[prost(uint64, tag = "1")]
+    pub scored_vectors: u64,
+    /// Number of query comparisons with quantized vectors
+    #[prost(uint64, tag = "2")]
+    pub quantized_comparisons: u64,
+    /// Number of query comparisons with original vectors
+    #[prost(uint64, tag = "3")]
+    pub original_comparisons: u64,
+    /// Number of payload values, read to check conditions or to return payload
+    #[prost(uint64, tag = "4")]
+    pub payload_values_read: u64,
+    /// Number of evaluated filter conditions
+    #[prost(uint64, tag = "5")]
+    pub filter_conditions_checked: u64,
+    /// Whether the response is served from the read cache of the collection, without any work
+    #[prost(bool, tag = "6")]
+    pub cache_hit: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FailedShard {
+    #[prost(uint32, tag = "1")]
+    pub shard_id: u32,
+    /// Peer of the failed replica, if the shard is served by a single replica
+    #[prost(uint64, optional, tag = "2")]
+    pub peer_id: ::core::option::Option<u64>,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PartialResults {
+    /// Shards, which failed to respond. Their points are missing in the result
+    #[prost(message, repeated, tag = "1")]
+    pub failed_shards: ::prost::alloc::vec::Vec<FailedShard>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShardTrace {
+    #[prost(uint32, tag = "1")]
+    pub shard_id: u32,
+    /// Peer of the replica, which answered
+    #[prost(uint64, tag = "2")]
+    pub peer_id: u64,
+    /// If true - the replica is on the peer, which received the request
+    #[prost(bool, tag = "3")]
+    pub local: bool,
+    /// State of the replica at the moment of the read
+    #[prost(string, optional, tag = "4")]
+    pub replica_state: ::core::option::Option<::prost::alloc::string::String>,
+    /// Time spent by the replica to answer, in seconds
+    #[prost(double, tag = "5")]
+    pub latency: f64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3207,6 +4161,21 @@ pub struct SearchResponse {
     /// Time spent to process
     #[prost(double, tag = "2")]
     pub time: f64,
+    /// Approximate number of not yet indexed points, skipped by `indexed_only` search
+    #[prost(uint64, optional, tag = "3")]
+    pub skipped_unindexed_points: ::core::option::Option<u64>,
+    /// Details of the search execution, useful for tuning of the search params
+    #[prost(message, optional, tag = "4")]
+    pub debug: ::core::option::Option<SearchDebugInfo>,
+    /// Work done to serve the request, returned if requested with `with_usage`
+    #[prost(message, optional, tag = "5")]
+    pub usage: ::core::option::Option<HardwareUsage>,
+    /// Shards, which failed to serve the request, returned if requested with `allow_partial_results`
+    #[prost(message, optional, tag = "6")]
+    pub partial: ::core::option::Option<PartialResults>,
+    /// Replicas, which served the shards of the request, returned if requested with `with_shard_trace`
+    #[prost(message, repeated, tag = "7")]
+    pub trace: ::prost::alloc::vec::Vec<ShardTrace>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3222,6 +4191,21 @@ pub struct SearchBatchResponse {
     /// Time spent to process
     #[prost(double, tag = "2")]
     pub time: f64,
+    /// Approximate number of not yet indexed points, skipped by `indexed_only` search
+    #[prost(uint64, optional, tag = "3")]
+    pub skipped_unindexed_points: ::core::option::Option<u64>,
+    /// Details of the search execution, useful for tuning of the search params
+    #[prost(message, optional, tag = "4")]
+    pub debug: ::core::option::Option<SearchDebugInfo>,
+    /// Work done to serve the request, returned if requested with `with_usage`
+    #[prost(message, optional, tag = "5")]
+    pub usage: ::core::option::Option<HardwareUsage>,
+    /// Shards, which failed to serve the request, returned if requested with `allow_partial_results`
+    #[prost(message, optional, tag = "6")]
+    pub partial: ::core::option::Option<PartialResults>,
+    /// Replicas, which served the shards of the request, returned if requested with `with_shard_trace`
+    #[prost(message, repeated, tag = "7")]
+    pub trace: ::prost::alloc::vec::Vec<ShardTrace>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3231,6 +4215,18 @@ pub struct SearchGroupsResponse {
     /// Time spent to process
     #[prost(double, tag = "2")]
     pub time: f64,
+    /// Approximate number of not yet indexed points, skipped by `indexed_only` search
+    #[prost(uint64, optional, tag = "3")]
+    pub skipped_unindexed_points: ::core::option::Option<u64>,
+    /// Details of the search execution, useful for tuning of the search params
+    #[prost(message, optional, tag = "4")]
+    pub debug: ::core::option::Option<SearchDebugInfo>,
+    /// Work done to serve the request, returned if requested with `with_usage`
+    #[prost(message, optional, tag = "5")]
+    pub usage: ::core::option::Option<HardwareUsage>,
+    /// Shards, which failed to serve the request, returned if requested with `allow_partial_results`
+    #[prost(message, optional, tag = "6")]
+    pub partial: ::core::option::Option<PartialResults>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3258,6 +4254,15 @@ pub struct ScrollResponse {
 pub struct CountResult {
     #[prost(uint64, tag = "1")]
     pub count: u64,
+    /// If `true` - the count is exact, otherwise it is estimated with the payload indexes
+    #[prost(bool, optional, tag = "2")]
+    pub exact: ::core::option::Option<bool>,
+    /// Smallest possible count. Equals `count` if it is exact
+    #[prost(uint64, optional, tag = "3")]
+    pub min: ::core::option::Option<u64>,
+    /// Largest possible count. Equals `count` if it is exact
+    #[prost(uint64, optional, tag = "4")]
+    pub max: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3268,6 +4273,18 @@ pub struct RetrievedPoint {
     pub payload: ::std::collections::HashMap<::prost::alloc::string::String, Value>,
     #[prost(message, optional, tag = "4")]
     pub vectors: ::core::option::Option<Vectors>,
+    /// Time of the last update of the point, if requested and known
+    #[prost(message, optional, tag = "5")]
+    pub last_modified: ::core::option::Option<::prost_types::Timestamp>,
+    /// Value of the `order_by` key of the point, if the points are ordered by it
+    #[prost(double, optional, tag = "6")]
+    pub order_value: ::core::option::Option<f64>,
+    /// Set if the payload is truncated to fit into `max_payload_return_bytes`
+    #[prost(message, optional, tag = "7")]
+    pub payload_truncation: ::core::option::Option<PayloadTruncation>,
+    /// Dimensions of the vector, if requested with the slice vectors selector
+    #[prost(message, optional, tag = "8")]
+    pub vector_slice: ::core::option::Option<VectorSlice>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3317,11 +4334,22 @@ pub struct Filter {
     /// All conditions must NOT match
     #[prost(message, repeated, tag = "3")]
     pub must_not: ::prost::alloc::vec::Vec<Condition>,
+    /// At least minimum amount of given conditions should match
+    #[prost(message, optional, tag = "4")]
+    pub min_should: ::core::option::Option<MinShould>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MinShould {
+    #[prost(message, repeated, tag = "1")]
+    pub conditions: ::prost::alloc::vec::Vec<Condition>,
+    #[prost(uint64, tag = "2")]
+    pub min_count: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Condition {
-    #[prost(oneof = "condition::ConditionOneOf", tags = "1, 2, 3, 4, 5, 6")]
+    #[prost(oneof = "condition::ConditionOneOf", tags = "1, 2, 3, 4, 5, 6, 7")]
     pub condition_one_of: ::core::option::Option<condition::ConditionOneOf>,
 }
 /// Nested message and enum types in `Condition`.
@@ -3341,10 +4369,19 @@ pub mod condition {
         IsNull(super::IsNullCondition),
         #[prost(message, tag = "6")]
         Nested(super::NestedCondition),
+        #[prost(message, tag = "7")]
+        ModifiedSince(super::ModifiedSinceCondition),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ModifiedSinceCondition {
+    /// Select points, updated at or after this time
+    #[prost(message, optional, tag = "1")]
+    pub modified_since: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct IsEmptyCondition {
     #[prost(string, tag = "1")]
     pub key: ::prost::alloc::string::String,
@@ -3395,7 +4432,7 @@ pub struct FieldCondition {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Match {
-    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
     pub match_value: ::core::option::Option<r#match::MatchValue>,
 }
 /// Nested message and enum types in `Match`.
@@ -3427,6 +4464,9 @@ pub mod r#match {
         /// Match any other value except those keywords
         #[prost(message, tag = "8")]
         ExceptKeywords(super::RepeatedStrings),
+        /// Match keywords in the given range
+        #[prost(message, tag = "9")]
+        KeywordRange(super::KeywordRange),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3455,6 +4495,14 @@ pub struct Range {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeywordRange {
+    #[prost(string, optional, tag = "1")]
+    pub gte: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "2")]
+    pub lte: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GeoBoundingBox {
     /// north-west corner
     #[prost(message, optional, tag = "1")]
@@ -3594,6 +4642,34 @@ impl ReadConsistencyType {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum WriteConsistencyType {
+    /// Wait for half + 1 replicas of the shard
+    Majority = 0,
+    /// Wait for all replicas of the shard
+    All = 1,
+}
+impl WriteConsistencyType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WriteConsistencyType::Majority => "Majority",
+            WriteConsistencyType::All => "All",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Majority" => Some(Self::Majority),
+            "All" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum FieldType {
     Keyword = 0,
     Integer = 1,
@@ -3768,6 +4844,34 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Upsert points from a stream of Arrow IPC encoded record batches. Columns of the batches are `id`, vectors and payload fields.
+        pub async fn upsert_arrow(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::UpsertArrowPoints,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/UpsertArrow",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut().insert(GrpcMethod::new("qdrant.Points", "UpsertArrow"));
+            self.inner.client_streaming(req, path, codec).await
+        }
+        ///
         /// Delete points
         pub async fn delete(
             &mut self,
@@ -4026,6 +5130,60 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Rebuild field index in background, while the current index keeps serving reads
+        pub async fn rebuild_field_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RebuildFieldIndexCollection>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/RebuildFieldIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "RebuildFieldIndex"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Stop the creation of field index and remove its partially built data
+        pub async fn cancel_field_index_build(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelFieldIndexBuildCollection>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/CancelFieldIndexBuild",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "CancelFieldIndexBuild"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
         /// Retrieve closest points based on vector similarity and given filtering conditions
         pub async fn search(
             &mut self,
@@ -4121,6 +5279,33 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Iterate over all or filtered points, streaming batches of `limit` points until all of them are returned
+        pub async fn scroll_stream(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ScrollPoints>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ScrollResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/ScrollStream",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "ScrollStream"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        ///
         /// Look for the points which are closer to stored positive examples and at the same time further to negative examples.
         pub async fn recommend(
             &mut self,
@@ -4238,6 +5423,15 @@ pub mod points_server {
             tonic::Status,
         >;
         ///
+        /// Upsert points from a stream of Arrow IPC encoded record batches. Columns of the batches are `id`, vectors and payload fields.
+        async fn upsert_arrow(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::UpsertArrowPoints>>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
         /// Delete points
         async fn delete(
             &self,
@@ -4325,6 +5519,24 @@ pub mod points_server {
             tonic::Status,
         >;
         ///
+        /// Rebuild field index in background, while the current index keeps serving reads
+        async fn rebuild_field_index(
+            &self,
+            request: tonic::Request<super::RebuildFieldIndexCollection>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
+        /// Stop the creation of field index and remove its partially built data
+        async fn cancel_field_index_build(
+            &self,
+            request: tonic::Request<super::CancelFieldIndexBuildCollection>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
         /// Retrieve closest points based on vector similarity and given filtering conditions
         async fn search(
             &self,
@@ -4354,6 +5566,21 @@ pub mod points_server {
             &self,
             request: tonic::Request<super::ScrollPoints>,
         ) -> std::result::Result<tonic::Response<super::ScrollResponse>, tonic::Status>;
+        /// Server streaming response type for the ScrollStream method.
+        type ScrollStreamStream: futures_core::Stream<
+                Item = std::result::Result<super::ScrollResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        ///
+        /// Iterate over all or filtered points, streaming batches of `limit` points until all of them are returned
+        async fn scroll_stream(
+            &self,
+            request: tonic::Request<super::ScrollPoints>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ScrollStreamStream>,
+            tonic::Status,
+        >;
         ///
         /// Look for the points which are closer to stored positive examples and at the same time further to negative examples.
         async fn recommend(
@@ -4509,6 +5736,54 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.Points/UpsertArrow" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpsertArrowSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::ClientStreamingService<super::UpsertArrowPoints>
+                    for UpsertArrowSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::UpsertArrowPoints>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).upsert_arrow(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpsertArrowSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.client_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.Points/Delete" => {
                     #[allow(non_camel_case_types)]
                     struct DeleteSvc<T: Points>(pub Arc<T>);
@@ -4955,6 +6230,100 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.Points/RebuildFieldIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct RebuildFieldIndexSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::RebuildFieldIndexCollection>
+                    for RebuildFieldIndexSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RebuildFieldIndexCollection>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).rebuild_field_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RebuildFieldIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/CancelFieldIndexBuild" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelFieldIndexBuildSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::CancelFieldIndexBuildCollection>
+                    for CancelFieldIndexBuildSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::CancelFieldIndexBuildCollection,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).cancel_field_index_build(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelFieldIndexBuildSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.Points/Search" => {
                     #[allow(non_camel_case_types)]
                     struct SearchSvc<T: Points>(pub Arc<T>);
@@ -5100,7 +6469,54 @@ pub mod points_server {
                             request: tonic::Request<super::ScrollPoints>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).scroll(request).await };
+                            let fut = async move { (*inner).scroll(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ScrollSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/ScrollStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct ScrollStreamSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::ServerStreamingService<super::ScrollPoints>
+                    for ScrollStreamSvc<T> {
+                        type Response = super::ScrollResponse;
+                        type ResponseStream = T::ScrollStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ScrollPoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).scroll_stream(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -5111,7 +6527,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ScrollSvc(inner);
+                        let method = ScrollStreamSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -5122,7 +6538,7 @@ pub mod points_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.unary(method, req).await;
+                        let res = grpc.server_streaming(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
@@ -5473,6 +6889,40 @@ pub struct DeleteFieldIndexCollectionInternal {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RebuildFieldIndexCollectionInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub rebuild_field_index_collection: ::core::option::Option<
+        RebuildFieldIndexCollection,
+    >,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelFieldIndexBuildInternal {
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Field, which index is being created
+    #[prost(string, tag = "2")]
+    #[validate(length(min = 1))]
+    pub field_name: ::prost::alloc::string::String,
+    #[prost(bool, optional, tag = "3")]
+    pub wait: ::core::option::Option<bool>,
+    #[prost(message, optional, tag = "4")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    #[prost(uint32, optional, tag = "5")]
+    pub shard_id: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "6")]
+    pub operation_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag = "7")]
+    pub wait_for: ::core::option::Option<WriteConsistency>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SearchPointsInternal {
     #[prost(message, optional, tag = "1")]
     #[validate]
@@ -5493,6 +6943,16 @@ pub struct SearchBatchPointsInternal {
     #[prost(uint32, optional, tag = "3")]
     pub shard_id: ::core::option::Option<u32>,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderBy {
+    /// Payload key with a numeric index to order the points by
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Largest values first
+    #[prost(bool, tag = "2")]
+    pub descending: bool,
+}
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -5502,6 +6962,9 @@ pub struct ScrollPointsInternal {
     pub scroll_points: ::core::option::Option<ScrollPoints>,
     #[prost(uint32, optional, tag = "2")]
     pub shard_id: ::core::option::Option<u32>,
+    /// Return the first points in this order instead of scrolling by id
+    #[prost(message, optional, tag = "3")]
+    pub order_by: ::core::option::Option<OrderBy>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -5533,6 +6996,35 @@ pub struct CountPointsInternal {
     #[prost(uint32, optional, tag = "2")]
     pub shard_id: ::core::option::Option<u32>,
 }
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChecksumPointsInternal {
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShardChecksum {
+    #[prost(uint64, tag = "1")]
+    pub points_count: u64,
+    #[prost(uint64, tag = "2")]
+    pub ids_checksum: u64,
+    #[prost(uint64, tag = "3")]
+    pub versions_checksum: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChecksumResponse {
+    #[prost(message, optional, tag = "1")]
+    pub result: ::core::option::Option<ShardChecksum>,
+    /// Time spent to process
+    #[prost(double, tag = "2")]
+    pub time: f64,
+}
 /// Generated client implementations.
 pub mod points_internal_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -5893,6 +7385,58 @@ pub mod points_internal_client {
                 .insert(GrpcMethod::new("qdrant.PointsInternal", "DeleteFieldIndex"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn rebuild_field_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RebuildFieldIndexCollectionInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/RebuildFieldIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "RebuildFieldIndex"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn cancel_field_index_build(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelFieldIndexBuildInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/CancelFieldIndexBuild",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("qdrant.PointsInternal", "CancelFieldIndexBuild"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn search(
             &mut self,
             request: impl tonic::IntoRequest<super::SearchPointsInternal>,
@@ -6030,6 +7574,31 @@ pub mod points_internal_client {
             req.extensions_mut().insert(GrpcMethod::new("qdrant.PointsInternal", "Get"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn checksum(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ChecksumPointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::ChecksumResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Checksum",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "Checksum"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -6116,6 +7685,20 @@ pub mod points_internal_server {
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
         >;
+        async fn rebuild_field_index(
+            &self,
+            request: tonic::Request<super::RebuildFieldIndexCollectionInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        async fn cancel_field_index_build(
+            &self,
+            request: tonic::Request<super::CancelFieldIndexBuildInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
         async fn search(
             &self,
             request: tonic::Request<super::SearchPointsInternal>,
@@ -6146,6 +7729,13 @@ pub mod points_internal_server {
             &self,
             request: tonic::Request<super::GetPointsInternal>,
         ) -> std::result::Result<tonic::Response<super::GetResponse>, tonic::Status>;
+        async fn checksum(
+            &self,
+            request: tonic::Request<super::ChecksumPointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::ChecksumResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct PointsInternalServer<T: PointsInternal> {
@@ -6730,6 +8320,103 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/RebuildFieldIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct RebuildFieldIndexSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<
+                        super::RebuildFieldIndexCollectionInternal,
+                    > for RebuildFieldIndexSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::RebuildFieldIndexCollectionInternal,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).rebuild_field_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RebuildFieldIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/CancelFieldIndexBuild" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelFieldIndexBuildSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::CancelFieldIndexBuildInternal>
+                    for CancelFieldIndexBuildSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::CancelFieldIndexBuildInternal,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).cancel_field_index_build(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelFieldIndexBuildSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.PointsInternal/Search" => {
                     #[allow(non_camel_case_types)]
                     struct SearchSvc<T: PointsInternal>(pub Arc<T>);
@@ -6996,6 +8683,50 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/Checksum" => {
+                    #[allow(non_camel_case_types)]
+                    struct ChecksumSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::ChecksumPointsInternal>
+                    for ChecksumSvc<T> {
+                        type Response = super::ChecksumResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ChecksumPointsInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).checksum(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ChecksumSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
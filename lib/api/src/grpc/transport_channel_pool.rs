@@ -8,6 +8,9 @@ use tokio::select;
 use tonic::transport::{Channel, ClientTlsConfig, Error as TonicError, Uri};
 use tonic::{Code, Status};
 
+use crate::grpc::circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerTelemetry, CircuitBreakers,
+};
 use crate::grpc::dynamic_channel_pool::DynamicChannelPool;
 use crate::grpc::dynamic_pool::CountedItem;
 use crate::grpc::qdrant::qdrant_client::QdrantClient;
@@ -64,6 +67,21 @@ enum RequestFailure {
     RequestConnection(TonicError),
 }
 
+impl RequestFailure {
+    /// Server could not be reached or did not respond in time.
+    /// Other failures mean, that the server is reachable.
+    fn is_connection_failure(&self) -> bool {
+        match self {
+            RequestFailure::HealthCheck(HealthCheckError::NoChannel) => false,
+            RequestFailure::HealthCheck(_) => true,
+            RequestFailure::RequestError(status) => {
+                matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+            }
+            RequestFailure::RequestConnection(_) => true,
+        }
+    }
+}
+
 /// Holds a pool of channels established for a set of URIs.
 /// Channel are shared by cloning them.
 /// Make the `pool_size` larger to increase throughput.
@@ -73,6 +91,7 @@ pub struct TransportChannelPool {
     grpc_timeout: Duration,
     connection_timeout: Duration,
     tls_config: Option<ClientTlsConfig>,
+    circuit_breakers: CircuitBreakers,
 }
 
 impl Default for TransportChannelPool {
@@ -83,6 +102,7 @@ impl Default for TransportChannelPool {
             grpc_timeout: DEFAULT_GRPC_TIMEOUT,
             connection_timeout: DEFAULT_CONNECT_TIMEOUT,
             tls_config: None,
+            circuit_breakers: CircuitBreakers::default(),
         }
     }
 }
@@ -93,6 +113,7 @@ impl TransportChannelPool {
         connection_timeout: Duration,
         pool_size: usize,
         tls_config: Option<ClientTlsConfig>,
+        circuit_breaker_config: CircuitBreakerConfig,
     ) -> Self {
         Self {
             uri_to_pool: Default::default(),
@@ -100,6 +121,7 @@ impl TransportChannelPool {
             connection_timeout,
             pool_size: NonZeroUsize::new(pool_size).unwrap(),
             tls_config,
+            circuit_breakers: CircuitBreakers::new(circuit_breaker_config),
         }
     }
 
@@ -133,6 +155,12 @@ impl TransportChannelPool {
     pub async fn drop_pool(&self, uri: &Uri) {
         let mut guard = self.uri_to_pool.write().await;
         guard.remove(uri);
+        self.circuit_breakers.remove(uri);
+    }
+
+    /// State of the circuit breaker of the URI, if there were connection failures to it
+    pub fn circuit_breaker_telemetry(&self, uri: &Uri) -> Option<CircuitBreakerTelemetry> {
+        self.circuit_breakers.get_telemetry(uri)
     }
 
     pub async fn drop_channel(&self, uri: &Uri, channel: CountedItem<Channel>) {
@@ -264,13 +292,30 @@ impl TransportChannelPool {
         let max_timeout = timeout.unwrap_or_else(|| self.grpc_timeout + self.connection_timeout);
 
         loop {
+            if let Err(retry_in) = self.circuit_breakers.acquire(uri) {
+                // Peer is considered unreachable, fail fast instead of waiting for the timeout
+                return Err(RequestError::FromClosure(Status::unavailable(format!(
+                    "Circuit breaker is open for {uri}, retry in {}ms",
+                    retry_in.as_millis()
+                ))));
+            }
+
             let request_result: Result<T, _> = self._make_request(uri, &f, max_timeout).await;
 
             let error_result = match request_result {
-                Ok(body) => return Ok(body),
+                Ok(body) => {
+                    self.circuit_breakers.report_success(uri);
+                    return Ok(body);
+                }
                 Err(err) => err,
             };
 
+            if error_result.is_connection_failure() {
+                self.circuit_breakers.report_failure(uri);
+            } else {
+                self.circuit_breakers.report_success(uri);
+            }
+
             let action = match error_result {
                 RequestFailure::HealthCheck(healthcheck_error) => {
                     match healthcheck_error {
@@ -124,6 +124,16 @@ pub fn validate_u64_range_min_4(value: &Option<u64>) -> Result<(), ValidationErr
     validate_range_generic(value, Some(4), None)
 }
 
+/// Validate the value is in `[1, 3600]` or `None`.
+pub fn validate_u64_range_min_1_max_3600(value: &Option<u64>) -> Result<(), ValidationError> {
+    validate_range_generic(value, Some(1), Some(3600))
+}
+
+/// Validate the value is in `[, 600000]` or `None`.
+pub fn validate_u64_range_max_600000(value: &Option<u64>) -> Result<(), ValidationError> {
+    validate_range_generic(value, None, Some(600_000))
+}
+
 /// Validate the value is in `[4, 10000]` or `None`.
 pub fn validate_u64_range_min_4_max_10000(value: &Option<u64>) -> Result<(), ValidationError> {
     validate_range_generic(value, Some(4), Some(10_000))
@@ -0,0 +1,115 @@
+use std::num::NonZeroU64;
+use std::sync::Arc;
+
+use arrow_array::types::Float32Type;
+use arrow_array::{ArrayRef, FixedSizeListArray, Int64Array, RecordBatch, UInt64Array};
+use arrow_ipc::writer::StreamWriter;
+use collection::operations::arrow_ops::ArrowPointsReader;
+use collection::operations::point_ops::PointStruct;
+use collection::operations::types::{VectorParams, VectorsConfig};
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::{thread_rng, Rng};
+use segment::types::Distance;
+
+const NUM_POINTS: usize = 1_000_000;
+const DIM: usize = 32;
+const ARROW_BATCH_SIZE: usize = 10_000;
+
+fn arrow_ipc(vectors: &[Vec<f32>]) -> Vec<u8> {
+    let batches: Vec<_> = vectors
+        .chunks(ARROW_BATCH_SIZE)
+        .enumerate()
+        .map(|(batch_index, chunk)| {
+            let offset = (batch_index * ARROW_BATCH_SIZE) as u64;
+            let ids = UInt64Array::from_iter_values(offset..offset + chunk.len() as u64);
+            let vector = FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+                chunk
+                    .iter()
+                    .map(|vector| Some(vector.iter().copied().map(Some))),
+                DIM as i32,
+            );
+            let group = Int64Array::from_iter_values((0..chunk.len() as i64).map(|i| i % 100));
+            RecordBatch::try_from_iter(vec![
+                ("id", Arc::new(ids) as ArrayRef),
+                ("vector", Arc::new(vector) as ArrayRef),
+                ("group", Arc::new(group) as ArrayRef),
+            ])
+            .unwrap()
+        })
+        .collect();
+
+    let mut data = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut data, batches[0].schema().as_ref()).unwrap();
+    for batch in &batches {
+        writer.write(batch).unwrap();
+    }
+    writer.finish().unwrap();
+    drop(writer);
+    data
+}
+
+fn json_points(vectors: &[Vec<f32>]) -> Vec<u8> {
+    let points: Vec<_> = vectors
+        .iter()
+        .enumerate()
+        .map(|(i, vector)| {
+            serde_json::json!({
+                "id": i,
+                "vector": vector,
+                "payload": { "group": i % 100 },
+            })
+        })
+        .collect();
+    serde_json::to_vec(&points).unwrap()
+}
+
+/// Conversion of the same points from Arrow IPC and from JSON, as received by the server
+fn arrow_upsert_bench(c: &mut Criterion) {
+    let mut rng = thread_rng();
+    let vectors: Vec<Vec<f32>> = (0..NUM_POINTS)
+        .map(|_| (0..DIM).map(|_| rng.gen()).collect())
+        .collect();
+    let arrow_data = arrow_ipc(&vectors);
+    let json_data = json_points(&vectors);
+    drop(vectors);
+
+    let config = VectorsConfig::Single(VectorParams {
+        size: NonZeroU64::new(DIM as u64).unwrap(),
+        distance: Distance::Dot,
+        hnsw_config: None,
+        quantization_config: None,
+        on_disk: None,
+        datatype: None,
+    });
+
+    let mut group = c.benchmark_group("upsert-conversion");
+    group.sample_size(10);
+
+    group.bench_function("arrow-ipc", |b| {
+        b.iter(|| {
+            let reader = ArrowPointsReader::try_new(arrow_data.clone(), &config).unwrap();
+            let mut num_points = 0;
+            for points in reader {
+                num_points += points.unwrap().len();
+            }
+            assert_eq!(num_points, NUM_POINTS);
+        })
+    });
+
+    group.bench_function("json", |b| {
+        b.iter(|| {
+            let points: Vec<PointStruct> = serde_json::from_slice(&json_data).unwrap();
+            assert_eq!(points.len(), NUM_POINTS);
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = arrow_upsert_bench,
+}
+
+criterion_main!(benches);
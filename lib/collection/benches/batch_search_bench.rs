@@ -63,12 +63,21 @@ fn batch_search_bench(c: &mut Criterion) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
         }
         .into(),
         shard_number: NonZeroU32::new(1).expect("Shard number can not be zero"),
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        validate_payload: false,
+        payload_validation_mode: Default::default(),
+        sparse_vectors: None,
+        payload_indexes: Default::default(),
+        payload_cache_fields: Default::default(),
+        read_only: false,
+        auto_index_group_by_fields: false,
+        read_hedge_delay_ms: None,
     };
 
     let collection_config = CollectionConfig {
@@ -78,14 +87,27 @@ fn batch_search_bench(c: &mut Criterion) {
             vacuum_min_vector_number: 1000,
             default_segment_number: 2,
             max_segment_size: Some(100_000),
+            max_segment_number: None,
+            target_segment_size: None,
             memmap_threshold: Some(100_000),
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 2,
+            max_indexing_threads: None,
         },
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+        default_search_params: Default::default(),
+        default_with_payload: Default::default(),
+        default_with_vector: Default::default(),
+        default_max_payload_return_bytes: Default::default(),
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: Default::default(),
     };
 
     let shared_config = Arc::new(RwLock::new(collection_config));
@@ -143,6 +165,10 @@ fn batch_search_bench(c: &mut Criterion) {
                             with_payload: None,
                             with_vector: None,
                             score_threshold: None,
+                            with_usage: false,
+                            allow_partial_results: false,
+                            with_shard_trace: false,
+                            max_payload_return_bytes: None,
                         };
                         let result = shard
                             .search(
@@ -175,6 +201,10 @@ fn batch_search_bench(c: &mut Criterion) {
                             with_payload: None,
                             with_vector: None,
                             score_threshold: None,
+                            with_usage: false,
+                            allow_partial_results: false,
+                            with_shard_trace: false,
+                            max_payload_return_bytes: None,
                         };
                         searches.push(search_query);
                     }
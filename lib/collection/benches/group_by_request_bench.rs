@@ -0,0 +1,83 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use std::collections::HashSet;
+
+use collection::grouping::group_by::{GroupRequest, SourceRequest};
+use collection::operations::types::SearchRequest;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::types::{Condition, ExtendedPointId, FieldCondition, Filter, Range};
+
+const DIM: usize = 1536;
+const ITERATIONS: usize = 10;
+
+fn group_by_request_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("group-by-request-bench");
+
+    let search = SearchRequest {
+        vector: random_vector(&mut rand::thread_rng(), DIM).into(),
+        filter: Some(Filter::new_must(Condition::Field(
+            FieldCondition::new_range(
+                "price",
+                Range {
+                    lt: Some(100.0),
+                    ..Default::default()
+                },
+            ),
+        ))),
+        params: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
+    };
+    let request = GroupRequest::with_limit_from_request(
+        SourceRequest::Search(search),
+        "docId".to_string(),
+        3,
+    )
+    .unwrap();
+    let ids: HashSet<_> = (0..100).map(ExtendedPointId::from).collect();
+    let exclude_ids = Filter::new_must_not(Condition::HasId(ids.into()));
+
+    // The whole group request is cloned to extend the filter of its source
+    group.bench_function("clone-group-request", |b| {
+        b.iter(|| {
+            for _ in 0..ITERATIONS {
+                let mut iteration = request.clone();
+                if let SourceRequest::Search(search) = &mut iteration.source {
+                    search.filter =
+                        Some(search.filter.take().unwrap_or_default().merge(&exclude_ids));
+                }
+                let mut internal_request = iteration.internal_request();
+                internal_request.update(iteration.limit, iteration.base_filter());
+                black_box(internal_request);
+            }
+        })
+    });
+
+    // The internal request is built once, only its limit and filter are updated
+    group.bench_function("internal-request", |b| {
+        b.iter(|| {
+            let mut internal_request = request.internal_request();
+            for _ in 0..ITERATIONS {
+                internal_request.update(request.limit, request.base_filter().merge(&exclude_ids));
+                black_box(&internal_request);
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = group_by_request_bench,
+}
+
+criterion_main!(benches);
@@ -0,0 +1,640 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Mutex, RwLock};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use validator::Validate;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Shortest retry delay, suggested to the rejected clients
+const MIN_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+const DEFAULT_WRITE_BURST_SEC: usize = 1;
+
+const DEFAULT_MAX_WRITE_DELAY_MS: u64 = 1000;
+
+/// Limits of the operations of the collection: concurrency of the reads and rate of the writes.
+///
+/// Limits are applied by each peer to the operations, received from the clients.
+/// Read operations over the concurrency limit wait in a queue, operations over the queue limit are
+/// rejected, so a burst of expensive queries on one collection doesn't starve the others.
+/// Write operations over the rate limit are delayed or rejected, according to the limit mode.
+/// Operations of the other peers, like replication and shard transfers, are not limited.
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct AdmissionConfig {
+    /// Max number of read operations, executed concurrently. If not set - reads are not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_concurrent_reads: Option<usize>,
+    /// Max number of read operations, waiting for execution.
+    /// Further operations are rejected. If not set - the queue is not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queued_reads: Option<usize>,
+    /// Max number of write operations per second. If not set - the operations are not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_write_operations_per_second: Option<usize>,
+    /// Max number of points per second, listed in the write operations.
+    /// Operations, which select the points by filter, are limited only by the rate of operations.
+    /// If not set - the rate of points is not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_write_points_per_second: Option<usize>,
+    /// Number of seconds of the unused write rate, which can be spent by a burst of operations.
+    /// Default: 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 3600))]
+    pub write_burst_sec: Option<usize>,
+    /// What to do with the write operations over the rate limit. Default: reject
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_rate_limit_mode: Option<WriteRateLimitMode>,
+    /// Max delay of a write operation in `delay` mode, in milliseconds.
+    /// Operations, which would wait longer, are rejected. Default: 1000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(max = 600000))]
+    pub max_write_delay_ms: Option<u64>,
+}
+
+/// Handling of the write operations over the rate limit
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteRateLimitMode {
+    /// Reject the operation, suggesting when to retry it
+    #[default]
+    Reject,
+    /// Delay the operation until it fits the limit, up to `max_write_delay_ms`
+    Delay,
+}
+
+impl AdmissionConfig {
+    /// Update the limits, which are specified in `diff`, and keep the rest
+    pub fn update(&self, diff: &AdmissionConfig) -> Self {
+        Self {
+            max_concurrent_reads: diff.max_concurrent_reads.or(self.max_concurrent_reads),
+            max_queued_reads: diff.max_queued_reads.or(self.max_queued_reads),
+            max_write_operations_per_second: diff
+                .max_write_operations_per_second
+                .or(self.max_write_operations_per_second),
+            max_write_points_per_second: diff
+                .max_write_points_per_second
+                .or(self.max_write_points_per_second),
+            write_burst_sec: diff.write_burst_sec.or(self.write_burst_sec),
+            write_rate_limit_mode: diff.write_rate_limit_mode.or(self.write_rate_limit_mode),
+            max_write_delay_ms: diff.max_write_delay_ms.or(self.max_write_delay_ms),
+        }
+    }
+}
+
+/// State of the admission of the read operations, reported in telemetry
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct ReadAdmissionTelemetry {
+    /// Number of read operations, waiting for execution
+    pub queue_depth: usize,
+    /// Number of read operations, executed at the moment
+    pub in_progress: usize,
+    /// Total number of read operations, rejected because the queue was full
+    pub rejected: u64,
+}
+
+/// Admission of the read operations of a collection, according to its [`AdmissionConfig`]
+#[derive(Default)]
+pub struct ReadAdmission {
+    limiter: RwLock<Option<Arc<ReadLimiter>>>,
+    rejected: AtomicU64,
+}
+
+impl ReadAdmission {
+    pub fn new(config: Option<&AdmissionConfig>) -> Self {
+        let admission = Self::default();
+        admission.configure(config);
+        admission
+    }
+
+    /// Apply new limits. Operations, admitted under the old limits, are not interrupted
+    pub fn configure(&self, config: Option<&AdmissionConfig>) {
+        let limiter = config
+            .and_then(|config| Some((config.max_concurrent_reads?, config.max_queued_reads)))
+            .map(|(max_concurrent, max_queued)| {
+                Arc::new(ReadLimiter::new(max_concurrent, max_queued))
+            });
+        *self.limiter.write() = limiter;
+    }
+
+    /// Wait for a permit to execute a read operation.
+    ///
+    /// Returns `None` if reads are not limited, and [`CollectionError::RateLimited`]
+    /// if the queue is full. The operation is executed until the permit is dropped.
+    pub async fn admit(&self) -> CollectionResult<Option<ReadPermit>> {
+        let Some(limiter) = self.limiter.read().clone() else {
+            return Ok(None);
+        };
+
+        let permit = match limiter.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(TryAcquireError::NoPermits) => {
+                let queued = QueuedRead::new(&limiter.queued);
+                if limiter
+                    .max_queued
+                    .map_or(false, |max_queued| queued.ahead >= max_queued)
+                {
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                    return Err(CollectionError::rate_limited(
+                        format!(
+                            "{} read operations are waiting for execution, the limit is {}",
+                            queued.ahead,
+                            limiter.max_queued.unwrap_or_default(),
+                        ),
+                        limiter.retry_after(queued.ahead),
+                    ));
+                }
+                limiter
+                    .semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| {
+                        CollectionError::service_error("Read limiter is closed".to_string())
+                    })?
+            }
+            Err(TryAcquireError::Closed) => {
+                return Err(CollectionError::service_error(
+                    "Read limiter is closed".to_string(),
+                ));
+            }
+        };
+
+        Ok(Some(ReadPermit {
+            _permit: permit,
+            limiter,
+            started: Instant::now(),
+        }))
+    }
+
+    pub fn telemetry(&self) -> ReadAdmissionTelemetry {
+        let (queue_depth, in_progress) = match self.limiter.read().as_ref() {
+            Some(limiter) => (
+                limiter.queued.load(Ordering::Relaxed),
+                limiter.max_concurrent - limiter.semaphore.available_permits(),
+            ),
+            None => (0, 0),
+        };
+        ReadAdmissionTelemetry {
+            queue_depth,
+            in_progress,
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct ReadLimiter {
+    semaphore: Arc<Semaphore>,
+    max_concurrent: usize,
+    max_queued: Option<usize>,
+    queued: AtomicUsize,
+    /// Moving average of the duration of the admitted operations, in microseconds
+    avg_duration_us: AtomicU64,
+}
+
+impl ReadLimiter {
+    fn new(max_concurrent: usize, max_queued: Option<usize>) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+            max_queued,
+            queued: AtomicUsize::new(0),
+            avg_duration_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Expected time until the operations, which are queued or executed now, are finished
+    fn retry_after(&self, queued: usize) -> Duration {
+        let avg_duration = Duration::from_micros(self.avg_duration_us.load(Ordering::Relaxed));
+        let rounds = queued / self.max_concurrent + 1;
+        (avg_duration * rounds as u32).max(MIN_RETRY_AFTER)
+    }
+
+    fn record_duration(&self, duration: Duration) {
+        let duration_us = duration.as_micros() as u64;
+        // Races between the concurrent updates only lose some samples of the average
+        let avg = self.avg_duration_us.load(Ordering::Relaxed);
+        let avg = if avg == 0 {
+            duration_us
+        } else {
+            (avg * 7 + duration_us) / 8
+        };
+        self.avg_duration_us.store(avg, Ordering::Relaxed);
+    }
+}
+
+/// Place in the queue of a read operation, released when the operation is admitted or cancelled
+struct QueuedRead<'a> {
+    queued: &'a AtomicUsize,
+    /// Number of operations in the queue ahead of this one
+    ahead: usize,
+}
+
+impl<'a> QueuedRead<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        let ahead = queued.fetch_add(1, Ordering::Relaxed);
+        Self { queued, ahead }
+    }
+}
+
+impl Drop for QueuedRead<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// State of the rate limit of the write operations, reported in telemetry
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct WriteAdmissionTelemetry {
+    /// Fraction of the burst budget of the write operations, available at the moment.
+    /// Only if the rate of operations is limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operations_fill: Option<f64>,
+    /// Fraction of the burst budget of the written points, available at the moment.
+    /// Only if the rate of points is limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points_fill: Option<f64>,
+    /// Total number of write operations, delayed to fit the rate limit
+    pub delayed: u64,
+    /// Total number of write operations, rejected because of the rate limit
+    pub rejected: u64,
+}
+
+/// Admission of the write operations of a collection, according to the rate limits of its
+/// [`AdmissionConfig`]
+#[derive(Default)]
+pub struct WriteAdmission {
+    limiter: Mutex<Option<WriteLimiter>>,
+    delayed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl WriteAdmission {
+    pub fn new(config: Option<&AdmissionConfig>) -> Self {
+        let admission = Self::default();
+        admission.configure(config);
+        admission
+    }
+
+    /// Apply new limits. The budget of the operations is restored to the full burst
+    pub fn configure(&self, config: Option<&AdmissionConfig>) {
+        *self.limiter.lock() = config.and_then(|config| WriteLimiter::new(config, Instant::now()));
+    }
+
+    /// Spend the budget of a write operation, which lists the given number of points.
+    ///
+    /// Waits until the operation fits the rate limits in `delay` mode,
+    /// returns [`CollectionError::RateLimited`] if it doesn't fit them otherwise.
+    pub async fn admit(&self, points: usize) -> CollectionResult<()> {
+        let delay = {
+            let mut limiter = self.limiter.lock();
+            let Some(limiter) = limiter.as_mut() else {
+                return Ok(());
+            };
+            match limiter.reserve(points, Instant::now()) {
+                Ok(delay) => delay,
+                Err(retry_after) => {
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                    return Err(CollectionError::rate_limited(
+                        "write rate limit of the collection is exceeded".to_string(),
+                        retry_after,
+                    ));
+                }
+            }
+        };
+
+        if !delay.is_zero() {
+            self.delayed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(delay).await;
+        }
+        Ok(())
+    }
+
+    pub fn telemetry(&self) -> WriteAdmissionTelemetry {
+        let (operations_fill, points_fill) = match self.limiter.lock().as_mut() {
+            Some(limiter) => {
+                limiter.refill(Instant::now());
+                (
+                    limiter.operations.as_ref().map(TokenBucket::fill),
+                    limiter.points.as_ref().map(TokenBucket::fill),
+                )
+            }
+            None => (None, None),
+        };
+        WriteAdmissionTelemetry {
+            operations_fill,
+            points_fill,
+            delayed: self.delayed.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct WriteLimiter {
+    operations: Option<TokenBucket>,
+    points: Option<TokenBucket>,
+    mode: WriteRateLimitMode,
+    max_delay: Duration,
+}
+
+impl WriteLimiter {
+    fn new(config: &AdmissionConfig, now: Instant) -> Option<Self> {
+        let burst_sec = config.write_burst_sec.unwrap_or(DEFAULT_WRITE_BURST_SEC);
+        let operations = config
+            .max_write_operations_per_second
+            .map(|rate| TokenBucket::new(rate, burst_sec, now));
+        let points = config
+            .max_write_points_per_second
+            .map(|rate| TokenBucket::new(rate, burst_sec, now));
+        if operations.is_none() && points.is_none() {
+            return None;
+        }
+        Some(Self {
+            operations,
+            points,
+            mode: config.write_rate_limit_mode.unwrap_or_default(),
+            max_delay: Duration::from_millis(
+                config
+                    .max_write_delay_ms
+                    .unwrap_or(DEFAULT_MAX_WRITE_DELAY_MS),
+            ),
+        })
+    }
+
+    fn refill(&mut self, now: Instant) {
+        self.operations
+            .iter_mut()
+            .chain(self.points.iter_mut())
+            .for_each(|bucket| bucket.refill(now));
+    }
+
+    /// Take the budget of an operation with the given number of points.
+    ///
+    /// Returns the delay, after which the operation fits the limits,
+    /// or the suggested delay before the retry, if the operation is rejected.
+    fn reserve(&mut self, points: usize, now: Instant) -> Result<Duration, Duration> {
+        self.refill(now);
+        let delay = self
+            .operations
+            .iter()
+            .map(|bucket| bucket.delay(1.0))
+            .chain(self.points.iter().map(|bucket| bucket.delay(points as f64)))
+            .max()
+            .unwrap_or_default();
+
+        let admitted = match self.mode {
+            WriteRateLimitMode::Reject => delay.is_zero(),
+            WriteRateLimitMode::Delay => delay <= self.max_delay,
+        };
+        if !admitted {
+            return Err(delay);
+        }
+
+        if let Some(bucket) = &mut self.operations {
+            bucket.take(1.0);
+        }
+        if let Some(bucket) = &mut self.points {
+            bucket.take(points as f64);
+        }
+        Ok(delay)
+    }
+}
+
+/// Budget of a rate limit, restored continuously up to the size of the burst
+struct TokenBucket {
+    /// Budget, restored per second
+    rate: f64,
+    capacity: f64,
+    /// Available budget, negative if it is already reserved by the delayed operations
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: usize, burst_sec: usize, now: Instant) -> Self {
+        let capacity = rate.saturating_mul(burst_sec) as f64;
+        Self {
+            rate: rate as f64,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Time until the given amount of budget is available.
+    /// Amounts over the burst are available with the full budget, the excess is paid afterwards
+    fn delay(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.tokens;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.rate)
+        }
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.tokens -= amount;
+    }
+
+    fn fill(&self) -> f64 {
+        (self.tokens / self.capacity).max(0.0)
+    }
+}
+
+/// Permit to execute a read operation
+pub struct ReadPermit {
+    _permit: OwnedSemaphorePermit,
+    limiter: Arc<ReadLimiter>,
+    started: Instant,
+}
+
+impl Drop for ReadPermit {
+    fn drop(&mut self) {
+        self.limiter.record_duration(self.started.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admission(max_concurrent_reads: usize, max_queued_reads: usize) -> Arc<ReadAdmission> {
+        Arc::new(ReadAdmission::new(Some(&AdmissionConfig {
+            max_concurrent_reads: Some(max_concurrent_reads),
+            max_queued_reads: Some(max_queued_reads),
+            ..Default::default()
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_reads_over_queue_limit_are_rejected() {
+        let admission = admission(1, 1);
+
+        let permit = admission.admit().await.unwrap();
+        assert!(permit.is_some());
+
+        let queued = tokio::spawn({
+            let admission = admission.clone();
+            async move { admission.admit().await.map(|permit| permit.is_some()) }
+        });
+        while admission.telemetry().queue_depth == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = admission.admit().await.err().unwrap();
+        let CollectionError::RateLimited { retry_after, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(retry_after >= MIN_RETRY_AFTER);
+
+        drop(permit);
+        assert!(queued.await.unwrap().unwrap());
+
+        let telemetry = admission.telemetry();
+        assert_eq!(telemetry.queue_depth, 0);
+        assert_eq!(telemetry.in_progress, 0);
+        assert_eq!(telemetry.rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_read_leaves_queue() {
+        let admission = admission(1, 1);
+        let _permit = admission.admit().await.unwrap();
+
+        let waiting = admission.admit();
+        let timeout = tokio::time::timeout(Duration::from_millis(10), waiting).await;
+        assert!(timeout.is_err());
+
+        assert_eq!(admission.telemetry().queue_depth, 0);
+        assert_eq!(admission.telemetry().in_progress, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_reads() {
+        let admission = ReadAdmission::new(Some(&AdmissionConfig {
+            max_concurrent_reads: None,
+            max_queued_reads: Some(0),
+            ..Default::default()
+        }));
+        assert!(admission.admit().await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let limiter = ReadLimiter::new(2, Some(10));
+        assert_eq!(limiter.retry_after(5), MIN_RETRY_AFTER);
+
+        limiter.record_duration(Duration::from_secs(2));
+        assert_eq!(limiter.retry_after(1), Duration::from_secs(2));
+        assert_eq!(limiter.retry_after(5), Duration::from_secs(6));
+    }
+
+    fn write_limiter(mode: WriteRateLimitMode, now: Instant) -> WriteLimiter {
+        let config = AdmissionConfig {
+            max_write_operations_per_second: Some(2),
+            max_write_points_per_second: Some(100),
+            write_rate_limit_mode: Some(mode),
+            max_write_delay_ms: Some(1000),
+            ..Default::default()
+        };
+        WriteLimiter::new(&config, now).unwrap()
+    }
+
+    #[test]
+    fn test_writes_over_rate_are_rejected() {
+        let now = Instant::now();
+        let mut limiter = write_limiter(WriteRateLimitMode::Reject, now);
+
+        assert_eq!(limiter.reserve(40, now), Ok(Duration::ZERO));
+        assert_eq!(limiter.reserve(40, now), Ok(Duration::ZERO));
+        assert_eq!(limiter.reserve(10, now), Err(Duration::from_millis(500)));
+
+        // Operations are restored, but the points are not
+        let now = now + Duration::from_millis(500);
+        assert_eq!(limiter.reserve(90, now), Err(Duration::from_millis(200)));
+        assert_eq!(limiter.reserve(10, now), Ok(Duration::ZERO));
+        assert_eq!(limiter.operations.as_ref().unwrap().fill(), 0.0);
+    }
+
+    #[test]
+    fn test_writes_over_rate_are_delayed() {
+        let now = Instant::now();
+        let mut limiter = write_limiter(WriteRateLimitMode::Delay, now);
+
+        // Operation over the burst is admitted with the full budget
+        assert_eq!(limiter.reserve(300, now), Ok(Duration::ZERO));
+        // Following operations wait for the excess
+        assert_eq!(limiter.reserve(0, now), Err(Duration::from_secs(2)));
+
+        let now = now + Duration::from_millis(1500);
+        assert_eq!(limiter.reserve(50, now), Ok(Duration::from_millis(1000)));
+        assert_eq!(limiter.points.as_ref().unwrap().fill(), 0.0);
+    }
+
+    #[test]
+    fn test_write_limits_are_validated() {
+        let config = AdmissionConfig {
+            write_burst_sec: Some(3601),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = AdmissionConfig {
+            max_write_delay_ms: Some(600_001),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        // Burst of the largest rate doesn't overflow
+        let config = AdmissionConfig {
+            max_write_points_per_second: Some(usize::MAX),
+            write_burst_sec: Some(3600),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let now = Instant::now();
+        let mut limiter = WriteLimiter::new(&config, now).unwrap();
+        assert_eq!(limiter.reserve(1000, now), Ok(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_write_admission_telemetry() {
+        let admission = WriteAdmission::new(Some(&AdmissionConfig {
+            max_write_operations_per_second: Some(1),
+            ..Default::default()
+        }));
+        admission.admit(1).await.unwrap();
+
+        let err = admission.admit(1).await.err().unwrap();
+        assert!(matches!(err, CollectionError::RateLimited { .. }));
+
+        let telemetry = admission.telemetry();
+        assert!(telemetry.operations_fill.unwrap() < 1.0);
+        assert!(telemetry.points_fill.is_none());
+        assert_eq!(telemetry.delayed, 0);
+        assert_eq!(telemetry.rejected, 1);
+
+        // Reads are not limited by the write limits
+        let admission = ReadAdmission::new(Some(&AdmissionConfig {
+            max_write_operations_per_second: Some(1),
+            ..Default::default()
+        }));
+        assert!(admission.admit().await.unwrap().is_none());
+    }
+}
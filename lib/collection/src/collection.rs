@@ -4,46 +4,83 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
 use futures::future::{join_all, try_join_all};
 use itertools::Itertools;
 use segment::common::version::StorageVersion;
+use segment::data_types::filter_trace::FilterTrace;
+use segment::data_types::groups::GroupId;
 use segment::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use segment::types::{
-    ExtendedPointId, Order, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
+    Condition, ExtendedPointId, Filter, Order, PayloadFieldSchema, PayloadKeyType,
+    PayloadSchemaType, PointIdType, ScoredPoint, SearchParams, SeqNumberType, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use semver::Version;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tar::Builder as TarBuilder;
 use tokio::fs::{copy, create_dir_all, remove_dir_all, remove_file, rename};
 use tokio::runtime::Handle;
-use tokio::sync::{Mutex, RwLock, RwLockWriteGuard};
+use tokio::sync::{watch, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tracing::Instrument;
 use validator::Validate;
 
+use crate::admission::{AdmissionConfig, ReadAdmission, ReadPermit, WriteAdmission};
 use crate::collection_state::{ShardInfo, State};
 use crate::common::is_ready::IsReady;
+use crate::common::measured_operation::{
+    is_measured_operation, measure_phase, measured_operation, record_requests, record_shards,
+    OperationContext,
+};
+use crate::common::partial_results::{join_shard_reads, partial_results_allowed};
+use crate::common::payload_size_limit::{truncate_record_payloads, truncate_scored_payloads};
+use crate::common::point_scope::{self, point_scope, restrict_to_scope};
+use crate::common::request_filter_cache::{is_filter_reused, with_request_filter_cache};
+use crate::common::request_hw_counter::request_hw_counter;
+use crate::common::request_warnings::report_warning;
+use crate::common::shard_trace::shard_trace_enabled;
 use crate::config::CollectionConfig;
+use crate::grouping::auto_index;
 use crate::hash_ring::HashRing;
+use crate::operations::arrow_ops::ArrowPointsReader;
 use crate::operations::config_diff::{CollectionParamsDiff, DiffConfig, OptimizersConfigDiff};
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::operation_effect::{EstimateOperationEffectArea, OperationEffectArea};
+use crate::operations::payload_validation::{self, RejectedPoint};
 use crate::operations::point_ops::WriteOrdering;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::snapshot_ops::{
     get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
 };
+use crate::operations::strict_mode::{StrictModeConfig, StrictModeRequest, UnindexedFiltering};
 use crate::operations::types::{
-    CollectionClusterInfo, CollectionError, CollectionInfo, CollectionResult, CountRequest,
-    CountResult, LocalShardInfo, NodeType, PointRequest, Record, RemoteShardInfo, ScrollRequest,
-    ScrollResult, SearchRequest, SearchRequestBatch, UpdateResult,
+    CollectionClusterInfo, CollectionError, CollectionFlushResult, CollectionInfo,
+    CollectionResult, CollectionStatus, CountRequest, CountResult, FieldIndexBuildProgress,
+    LocalShardInfo, MergeTargets, NodeType, OptimizersPlan, PointRequest, Record, RemoteShardInfo,
+    ScrollRequest, ScrollResult, SearchRequest, SearchRequestBatch, ShardFlushResult,
+    ShardIndexRebuildProgress, ShardSegmentsInfo, ShardStatus, UpdateResult, UpdateStatus,
+    WaitForStatusResult, Warning,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
 use crate::optimizers_builder::OptimizersConfig;
+use crate::quota::QuotaConfig;
+use crate::read_cache::{
+    is_internal_read, with_internal_reads, CacheKey, CachedOperation, DataVersion, ReadCache,
+    ReadCacheConfig,
+};
+use crate::shards::balancer::{plan_rebalancing, RebalancingPlan, ShardPlacement};
 use crate::shards::channel_service::ChannelService;
+use crate::shards::checksum::{CollectionChecksum, ShardChecksum};
 use crate::shards::collection_shard_distribution::CollectionShardDistribution;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
-use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing, Listener};
+use crate::shards::replica_recovery::RecoveryState;
+use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing, Listener, Partial};
 use crate::shards::replica_set::{
     Change, ChangePeerState, ReplicaState, ShardReplicaSet as ReplicaSetShard,
 }; // TODO rename ReplicaShard to ReplicaSetShard
@@ -54,17 +91,23 @@ use crate::shards::shard_versioning::versioned_shard_path;
 use crate::shards::transfer::shard_transfer::{
     change_remote_shard_route, check_transfer_conflicts, finalize_partial_shard,
     handle_transferred_shard_proxy, revert_proxy_shard_to_local, spawn_transfer_task,
-    ShardTransfer, ShardTransferKey,
+    ShardTransfer, ShardTransferKey, ShardTransferMethod,
 };
 use crate::shards::transfer::transfer_tasks_pool::{TaskResult, TransferTasksPool};
 use crate::shards::{replica_set, CollectionId, HASH_RING_SHARD_SCALE};
-use crate::telemetry::CollectionTelemetry;
+use crate::slow_log::{RequestDetails, SlowLog, SlowLogEntry};
+use crate::telemetry::{CollectionOperationType, CollectionTelemetry, OperationsTelemetry};
+use crate::ttl::{self, TtlConfig};
+use crate::warmup::{Warmup, WarmupProgress, WarmupRequest};
 
 pub type VectorLookupFuture<'a> = Box<dyn Future<Output = CollectionResult<Vec<Record>>> + 'a>;
 pub type OnTransferFailure = Arc<dyn Fn(ShardTransfer, CollectionId, &str) + Send + Sync>;
 pub type OnTransferSuccess = Arc<dyn Fn(ShardTransfer, CollectionId) + Send + Sync>;
 pub type RequestShardTransfer = Arc<dyn Fn(ShardTransfer) + Send + Sync>;
 
+/// How often statuses of remote shards are checked, while waiting for the collection status
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 struct CollectionVersion;
 
 impl StorageVersion for CollectionVersion {
@@ -99,6 +142,26 @@ pub struct Collection {
     search_runtime: Handle,
     // Update runtime handle.
     update_runtime: Handle,
+    // Statistics of group by and recommend operations, reported in telemetry.
+    pub(crate) operations_telemetry: parking_lot::Mutex<OperationsTelemetry>,
+    // Recent read operations, which took longer than the configured threshold.
+    slow_log: SlowLog,
+    // One-way flag, set when the collection is about to be deleted.
+    // Read operations check it before accessing the shards.
+    is_deleted: AtomicBool,
+    // If set, update operations of the clients are rejected with this reason,
+    // e.g. while the collection is being cloned. Internal operations are not affected.
+    updates_block_reason: parking_lot::Mutex<Option<String>>,
+    // The latest warmup of the local shards, kept after it is finished to report its result.
+    warmup: parking_lot::Mutex<Option<Warmup>>,
+    // Limits of the concurrent read operations, received from the clients.
+    read_admission: ReadAdmission,
+    // Limits of the rate of the write operations, received from the clients.
+    write_admission: WriteAdmission,
+    // Responses to the read operations of the clients, kept until the collection is updated.
+    read_cache: ReadCache,
+    // Fields, which payload indexes are being created for grouping by them.
+    auto_indexed_fields: Arc<parking_lot::Mutex<HashSet<PayloadKeyType>>>,
 }
 
 impl Collection {
@@ -106,6 +169,49 @@ impl Collection {
         self.id.clone()
     }
 
+    /// Mark the collection as being deleted.
+    ///
+    /// In-flight read operations fail with `NotFound` on their next access to the shards,
+    /// so long multi-step requests, like group by, don't hold the collection until they finish.
+    pub fn mark_deleted(&self) {
+        self.is_deleted.store(true, Ordering::Relaxed);
+    }
+
+    /// Fails with `NotFound`, if the collection is being deleted
+    pub fn check_not_deleted(&self) -> CollectionResult<()> {
+        if self.is_deleted.load(Ordering::Relaxed) {
+            return Err(CollectionError::NotFound {
+                what: format!("Collection {}", self.id),
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject update operations of the clients with the given reason, or accept them again
+    pub fn block_updates(&self, reason: Option<String>) {
+        *self.updates_block_reason.lock() = reason;
+    }
+
+    /// Fails with `Forbidden`, if the update operations of the clients are blocked
+    pub fn check_updates_not_blocked(&self) -> CollectionResult<()> {
+        match self.updates_block_reason.lock().as_ref() {
+            Some(reason) => Err(CollectionError::forbidden(reason.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails with `Forbidden`, if the collection is read-only or its updates are blocked
+    pub async fn check_writable(&self) -> CollectionResult<()> {
+        if self.collection_config.read().await.params.read_only {
+            return Err(CollectionError::forbidden(format!(
+                "Collection {name} is read-only. To accept updates again, clear the flag with \
+                 PATCH /collections/{name} {{\"params\": {{\"read_only\": false}}}}",
+                name = self.id,
+            )));
+        }
+        self.check_updates_not_blocked()
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
         name: CollectionId,
@@ -148,11 +254,24 @@ impl Collection {
         }
 
         let locked_shard_holder = Arc::new(LockedShardHolder::new(shard_holder));
+        let update_runtime = update_runtime.unwrap_or_else(Handle::current);
+        ttl::spawn_vacuum_task(
+            Arc::downgrade(&locked_shard_holder),
+            Arc::downgrade(&shared_collection_config),
+            shared_storage_config.clock.clone(),
+            this_peer_id,
+            &update_runtime,
+        );
 
         // Once the config is persisted - the collection is considered to be successfully created.
         CollectionVersion::save(path)?;
         collection_config.save(path)?;
 
+        let slow_log = SlowLog::new(shared_storage_config.slow_log.capacity);
+        let read_admission = ReadAdmission::new(collection_config.admission_config.as_ref());
+        let write_admission = WriteAdmission::new(collection_config.admission_config.as_ref());
+        let read_cache = ReadCache::new(collection_config.read_cache_config.as_ref());
+
         Ok(Self {
             id: name.clone(),
             shards_holder: locked_shard_holder,
@@ -169,7 +288,16 @@ impl Collection {
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
             search_runtime: search_runtime.unwrap_or_else(Handle::current),
-            update_runtime: update_runtime.unwrap_or_else(Handle::current),
+            update_runtime,
+            operations_telemetry: Default::default(),
+            slow_log,
+            is_deleted: AtomicBool::new(false),
+            updates_block_reason: parking_lot::Mutex::new(None),
+            warmup: parking_lot::Mutex::new(None),
+            read_admission,
+            write_admission,
+            read_cache,
+            auto_indexed_fields: Default::default(),
         })
     }
 
@@ -262,6 +390,30 @@ impl Collection {
             .await;
 
         let locked_shard_holder = Arc::new(LockedShardHolder::new(shard_holder));
+        let update_runtime = update_runtime.unwrap_or_else(Handle::current);
+        ttl::spawn_vacuum_task(
+            Arc::downgrade(&locked_shard_holder),
+            Arc::downgrade(&shared_collection_config),
+            shared_storage_config.clock.clone(),
+            this_peer_id,
+            &update_runtime,
+        );
+
+        let slow_log = SlowLog::new(shared_storage_config.slow_log.capacity);
+        let read_admission = ReadAdmission::new(collection_config.admission_config.as_ref());
+        let write_admission = WriteAdmission::new(collection_config.admission_config.as_ref());
+        let read_cache = ReadCache::new(collection_config.read_cache_config.as_ref());
+
+        let search_runtime = search_runtime.unwrap_or_else(Handle::current);
+        let warmup = shared_storage_config.warmup.on_load.then(|| {
+            Warmup::start(
+                locked_shard_holder.clone(),
+                shared_collection_config.clone(),
+                search_runtime.clone(),
+                WarmupRequest::default(),
+                &shared_storage_config.warmup,
+            )
+        });
 
         Self {
             id: collection_id.clone(),
@@ -278,8 +430,17 @@ impl Collection {
             init_time: start_time.elapsed(),
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
-            search_runtime: search_runtime.unwrap_or_else(Handle::current),
-            update_runtime: update_runtime.unwrap_or_else(Handle::current),
+            search_runtime,
+            update_runtime,
+            operations_telemetry: Default::default(),
+            slow_log,
+            is_deleted: AtomicBool::new(false),
+            updates_block_reason: parking_lot::Mutex::new(None),
+            warmup: parking_lot::Mutex::new(warmup),
+            read_admission,
+            write_admission,
+            read_cache,
+            auto_indexed_fields: Default::default(),
         }
     }
 
@@ -402,6 +563,7 @@ impl Collection {
                     from: transfer_from,
                     to: self.this_peer_id,
                     sync: true,
+                    method: Some(ShardTransferMethod::WalDelta),
                 })
             } else {
                 log::warn!("No alive replicas to recover shard {shard_id}");
@@ -722,378 +884,1225 @@ impl Collection {
         wait: bool,
         ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
-        operation.validate()?;
-        let _update_lock = self.updates_lock.read().await;
+        self.update_from_client_impl(operation, wait, ordering, false)
+            .await
+    }
 
-        let mut results = {
-            let shards_holder = self.shards_holder.read().await;
-            let shard_to_op = shards_holder.split_by_shard(operation);
+    /// Update, issued by the service itself, e.g. to initialize the collection from another one.
+    ///
+    /// Same as `update_from_client`, but applied to a read-only collection as well,
+    /// and not limited by the write rate of the collection.
+    pub async fn update_internal(
+        &self,
+        operation: CollectionUpdateOperations,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<UpdateResult> {
+        self.update_from_client_impl(operation, wait, ordering, true)
+            .await
+    }
 
-            if shard_to_op.is_empty() {
-                return Err(CollectionError::bad_request(
-                    "Empty update request".to_string(),
-                ));
+    async fn update_from_client_impl(
+        &self,
+        mut operation: CollectionUpdateOperations,
+        wait: bool,
+        ordering: WriteOrdering,
+        internal: bool,
+    ) -> CollectionResult<UpdateResult> {
+        let operation_type = CollectionOperationType::from(&operation);
+        self.measure_operation(operation_type, None, async {
+            if !internal {
+                self.check_writable().await?;
+            }
+            operation.validate()?;
+            self.resolve_existing_field_index(&mut operation).await;
+            let rejected_points = self.validate_update_payload(&mut operation).await?;
+            if let Some(scope) = point_scope().filter(|_| !internal) {
+                self.restrict_update_to_scope(&mut operation, &scope)
+                    .await?;
+            }
+            if !internal {
+                // Invalid operations are rejected without spending the budget
+                self.write_admission
+                    .admit(operation.listed_points_count())
+                    .await?;
             }
+            let _update_lock = self.updates_lock.read().await;
 
-            let shard_requests = shard_to_op
-                .into_iter()
-                .map(move |(replica_set, operation)| {
-                    replica_set.update_with_consistency(operation, wait, ordering)
-                });
-            join_all(shard_requests).await
-        };
+            let results = {
+                let shards_holder = self.shards_holder.read().await;
+                let shard_to_op = shards_holder.split_by_shard(operation);
 
-        let with_error = results
-            .iter()
-            .filter(|result| matches!(result, Err(_)))
-            .count();
+                if shard_to_op.is_empty() {
+                    return Err(CollectionError::bad_request(
+                        "Empty update request".to_string(),
+                    ));
+                }
 
-        // one request per shard
-        let result_len = results.len();
+                let shard_requests =
+                    shard_to_op
+                        .into_iter()
+                        .map(move |(replica_set, operation)| {
+                            replica_set.update_with_consistency(operation, wait, ordering)
+                        });
+                join_all(shard_requests).await
+            };
 
-        if with_error > 0 {
-            let first_err = results
-                .into_iter()
-                .find(|result| matches!(result, Err(_)))
-                .unwrap();
-            // inconsistent if only a subset of the requests fail - one request per shard.
-            if with_error < result_len {
-                first_err.map_err(|err| {
-                    // compute final status code based on the first error
-                    // e.g. a partially successful batch update failing because of bad input is a client error
-                    CollectionError::InconsistentShardFailure {
-                        shards_total: result_len as u32, // report only the number of shards that took part in the update
-                        shards_failed: with_error as u32,
-                        first_err: Box::new(err),
-                    }
-                })
+            let with_error = results
+                .iter()
+                .filter(|result| matches!(result, Err(_)))
+                .count();
+
+            // one request per shard
+            let result_len = results.len();
+
+            let result = if with_error > 0 {
+                let first_err = results
+                    .into_iter()
+                    .find(|result| matches!(result, Err(_)))
+                    .unwrap();
+                // inconsistent if only a subset of the requests fail - one request per shard.
+                if with_error < result_len {
+                    first_err.map_err(|err| {
+                        // compute final status code based on the first error
+                        // e.g. a partially successful batch update failing because of bad input is a client error
+                        CollectionError::InconsistentShardFailure {
+                            shards_total: result_len as u32, // report only the number of shards that took part in the update
+                            shards_failed: with_error as u32,
+                            first_err: Box::new(err),
+                        }
+                    })
+                } else {
+                    // all requests per shard failed - propagate first error (assume there are all the same)
+                    first_err
+                }
             } else {
-                // all requests per shard failed - propagate first error (assume there are all the same)
-                first_err
+                // At least one result is always present.
+                // Shards report the points, affected by a filter, in their own part of the collection
+                results
+                    .into_iter()
+                    .reduce(|merged, result| Ok(merged?.merge_shard_result(result?)))
+                    .unwrap()
+            };
+
+            if result.is_ok() && !rejected_points.is_empty() {
+                return Err(CollectionError::bad_input(format!(
+                    "{} points are not applied, because their payload doesn't match the schema of the indexed fields: {}",
+                    rejected_points.len(),
+                    payload_validation::describe_rejected(&rejected_points),
+                )));
             }
-        } else {
-            // At least one result is always present.
-            results.pop().unwrap()
-        }
+            result
+        })
+        .await
     }
 
-    pub async fn search_batch(
+    /// Serve a client request and record its duration and outcome in the telemetry.
+    ///
+    /// Requests of other peers and internal requests of other operations are not recorded,
+    /// so each client request is counted exactly once.
+    pub(crate) async fn measure_operation<T>(
         &self,
-        request: SearchRequestBatch,
-        read_consistency: Option<ReadConsistency>,
+        operation_type: CollectionOperationType,
         shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        // shortcuts batch if all requests with limit=0
-        if request.searches.iter().all(|s| s.limit == 0) {
-            return Ok(vec![]);
+        operation: impl Future<Output = CollectionResult<T>>,
+    ) -> CollectionResult<T> {
+        if shard_selection.is_some() || is_measured_operation() {
+            return operation.await;
         }
-        // A factor which determines if we need to use the 2-step search or not
-        // Should be adjusted based on usage statistics.
-        const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
+        let slow_log_config = &self.shared_storage_config.slow_log;
+        let collect_details = slow_log_config.threshold_ms.is_some() && operation_type.is_read();
+        let context = Arc::new(OperationContext::new(collect_details));
+
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let result = measured_operation(context.clone(), operation).await;
+        let duration = start.elapsed();
+        self.operations_telemetry
+            .lock()
+            .requests
+            .record(operation_type, duration, result.is_err());
 
-        let is_payload_required = request.searches.iter().all(|s| {
-            s.with_payload
-                .clone()
-                .map(|p| p.is_required())
-                .unwrap_or_default()
+        let is_slow = slow_log_config.threshold_ms.map_or(false, |threshold_ms| {
+            duration >= Duration::from_millis(threshold_ms)
         });
-        let with_vectors = request.searches.iter().all(|s| {
-            s.with_vector
-                .as_ref()
-                .map(|wv| wv.is_some())
-                .unwrap_or(false)
-        });
-
-        let metadata_required = is_payload_required || with_vectors;
-
-        let sum_limits: usize = request.searches.iter().map(|s| s.limit).sum();
-        let sum_offsets: usize = request.searches.iter().map(|s| s.offset).sum();
-
-        // Number of records we need to retrieve to fill the search result.
-        let require_transfers = self.shards_holder.read().await.len() * (sum_limits + sum_offsets);
-        // Actually used number of records.
-        let used_transfers = sum_limits;
-
-        let is_required_transfer_large_enough =
-            require_transfers > used_transfers * PAYLOAD_TRANSFERS_FACTOR_THRESHOLD;
-
-        if metadata_required && is_required_transfer_large_enough {
-            // If there is a significant offset, we need to retrieve the whole result
-            // set without payload first and then retrieve the payload.
-            // It is required to do this because the payload might be too large to send over the
-            // network.
-            let mut without_payload_requests = Vec::with_capacity(request.searches.len());
-            for search in &request.searches {
-                let mut without_payload_request = search.clone();
-                without_payload_request.with_payload = None;
-                without_payload_request.with_vector = None;
-                without_payload_requests.push(without_payload_request);
+        if collect_details && is_slow {
+            let details = context.take_details();
+            let entry = SlowLogEntry::new(
+                self.name(),
+                operation_type,
+                started_at,
+                duration,
+                details.requests.unwrap_or_default(),
+                details.phases,
+                details.shards,
+                slow_log_config.redact_values,
+            );
+            if slow_log_config.log_to_file {
+                match serde_json::to_string(&entry) {
+                    Ok(entry) => log::warn!("Slow operation: {entry}"),
+                    Err(err) => log::warn!("Failed to serialize slow log entry: {err}"),
+                }
             }
-            let without_payload_batch = SearchRequestBatch {
-                searches: without_payload_requests,
-            };
-            let without_payload_results = self
-                ._search_batch(without_payload_batch, read_consistency, shard_selection)
-                .await?;
-            let filled_results = without_payload_results
-                .into_iter()
-                .zip(request.clone().searches.into_iter())
-                .map(|(without_payload_result, req)| {
-                    self.fill_search_result_with_payload(
-                        without_payload_result,
-                        req.with_payload.clone(),
-                        req.with_vector.unwrap_or_default(),
-                        read_consistency,
-                        shard_selection,
-                    )
-                });
-            try_join_all(filled_results).await
-        } else {
-            let result = self
-                ._search_batch(request, read_consistency, shard_selection)
-                .await?;
-            Ok(result)
+            self.slow_log.record(entry);
         }
+        result
     }
 
-    pub async fn _search_batch(
+    /// Check client requests against the strict mode limits of the collection.
+    ///
+    /// Like [`Self::measure_operation`], requests of other peers and internal requests of other
+    /// operations are not checked: the limits are enforced once, where the request enters the cluster.
+    pub(crate) async fn check_strict_mode<'a>(
         &self,
-        request: SearchRequestBatch,
-        read_consistency: Option<ReadConsistency>,
+        requests: impl IntoIterator<Item = StrictModeRequest<'a>>,
         shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        let batch_size = request.searches.len();
-        let request = Arc::new(request);
+    ) -> CollectionResult<()> {
+        if shard_selection.is_some() || is_measured_operation() {
+            return Ok(());
+        }
+        let strict_mode_config = match &self.collection_config.read().await.strict_mode_config {
+            Some(config) if config.is_enabled() => config.clone(),
+            _ => return Ok(()),
+        };
+        let indexed_fields = if strict_mode_config.requires_payload_index() {
+            self.local_indexed_fields()
+                .await
+                .map(|fields| fields.into_keys().collect::<HashSet<_>>())
+        } else {
+            None
+        };
+        let requests: Vec<_> = requests.into_iter().collect();
+        for request in &requests {
+            strict_mode_config.check(request, indexed_fields.as_ref())?;
+        }
+        let filters: Vec<_> = requests
+            .iter()
+            .filter_map(|request| request.filter)
+            .collect();
+        self.check_unindexed_filtering(strict_mode_config.unindexed_filtering(), &filters)
+            .await
+    }
 
-        // query all shards concurrently
-        let mut all_searches_res = {
+    /// Warn about or deny the filters of client requests, which can't be served by the payload
+    /// index of local replicas and require a scan of payloads.
+    ///
+    /// Only the filters of the client are checked. The implicit condition of grouped requests,
+    /// which excludes points without the group key, is added later and is never reported.
+    async fn check_unindexed_filtering(
+        &self,
+        mode: UnindexedFiltering,
+        filters: &[&Filter],
+    ) -> CollectionResult<()> {
+        if mode == UnindexedFiltering::Allow || filters.is_empty() {
+            return Ok(());
+        }
+        let keys = {
             let shard_holder = self.shards_holder.read().await;
-            let target_shards = shard_holder.target_shard(shard_selection)?;
-            let all_searches = target_shards
-                .iter()
-                .map(|shard| shard.search(request.clone(), read_consistency, &self.search_runtime));
-            try_join_all(all_searches).await?
+            let mut keys = Vec::new();
+            for replica_set in shard_holder.all_shards() {
+                if let Some(shard_keys) = replica_set.unindexed_filter_keys(filters).await {
+                    keys.extend(shard_keys);
+                }
+            }
+            keys.sort_unstable();
+            keys.dedup();
+            keys
         };
-
-        // merge results from shards in order
-        let mut merged_results: Vec<Vec<ScoredPoint>> = vec![vec![]; batch_size];
-        for shard_searches_results in all_searches_res.iter_mut() {
-            for (index, shard_searches_result) in shard_searches_results.iter_mut().enumerate() {
-                merged_results[index].append(shard_searches_result)
+        if keys.is_empty() {
+            return Ok(());
+        }
+        match mode {
+            UnindexedFiltering::Allow => Ok(()),
+            UnindexedFiltering::Warn => {
+                self.operations_telemetry
+                    .lock()
+                    .unindexed_filtering
+                    .warned_requests += 1;
+                log::debug!(
+                    "Filtering by fields without payload index in collection {}: {}",
+                    self.name(),
+                    keys.join(", "),
+                );
+                report_warning(Warning::unindexed_filter(&keys));
+                Ok(())
+            }
+            UnindexedFiltering::Deny => {
+                self.operations_telemetry
+                    .lock()
+                    .unindexed_filtering
+                    .denied_requests += 1;
+                Err(CollectionError::strict_mode(
+                    "unindexed_filtering",
+                    format!(
+                        "filtering by fields without payload index: {}",
+                        keys.join(", ")
+                    ),
+                ))
             }
         }
-        let collection_params = self.collection_config.read().await.params.clone();
-        let top_results: Vec<_> = merged_results
-            .into_iter()
-            .zip(request.searches.iter())
-            .map(|(res, request)| {
-                let distance = collection_params
-                    .get_vector_params(request.vector.get_name())?
-                    .distance;
-                let mut top_res = match distance.distance_order() {
-                    Order::LargeBetter => {
-                        peek_top_largest_iterable(res, request.limit + request.offset)
-                    }
-                    Order::SmallBetter => {
-                        peek_top_smallest_iterable(res, request.limit + request.offset)
-                    }
-                };
-                // Remove `offset` from top result only for client requests
-                // to avoid applying `offset` twice in distributed mode.
-                if shard_selection.is_none() && request.offset > 0 {
-                    if top_res.len() >= request.offset {
-                        // Panics if the end point > length of the vector.
-                        top_res.drain(..request.offset);
-                    } else {
-                        top_res.clear()
-                    }
-                }
-                Ok(top_res)
-            })
-            .collect::<CollectionResult<Vec<_>>>()?;
-
-        Ok(top_results)
     }
 
-    pub(crate) async fn fill_search_result_with_payload(
+    /// Reject grouping by a field, which is indexed with a type, that can't be a group key.
+    /// Values of such a field never make up groups, so the result would be silently empty.
+    pub(crate) async fn check_group_by_field(
         &self,
-        search_result: Vec<ScoredPoint>,
-        with_payload: Option<WithPayloadInterface>,
-        with_vector: WithVector,
-        read_consistency: Option<ReadConsistency>,
+        group_by: &str,
         shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<ScoredPoint>> {
-        // short-circuit if not needed
-        if let (&Some(WithPayloadInterface::Bool(false)), &WithVector::Bool(false)) =
-            (&with_payload, &with_vector)
-        {
-            return Ok(search_result
-                .into_iter()
-                .map(|point| ScoredPoint {
-                    payload: None,
-                    vector: None,
-                    ..point
-                })
-                .collect());
+    ) -> CollectionResult<()> {
+        if shard_selection.is_some() {
+            return Ok(());
+        }
+        let Some(indexed_fields) = self.local_indexed_fields().await else {
+            return Ok(());
         };
+        match indexed_fields.get(group_by) {
+            Some(schema) if !schema.is_group_key() => {
+                Err(CollectionError::UnsupportedGroupKeyType {
+                    field: group_by.to_string(),
+                    field_type: schema.type_name().to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 
-        let retrieve_request = PointRequest {
-            ids: search_result.iter().map(|x| x.id).collect(),
-            with_payload,
-            with_vector,
+    /// Group key in the form of the values of the field.
+    /// Older clients send the numeric keys stringified, such keys of the integer-indexed fields
+    /// are converted back, as the order of the numeric and the string keys differs.
+    pub(crate) async fn group_key_of_field(&self, group_by: &str, key: GroupId) -> GroupId {
+        let Some(number) = key.unstringified() else {
+            return key;
         };
-        let retrieved_records = self
-            .retrieve(retrieve_request, read_consistency, shard_selection)
-            .await?;
-        let mut records_map: HashMap<ExtendedPointId, Record> = retrieved_records
-            .into_iter()
-            .map(|rec| (rec.id, rec))
-            .collect();
-        let enriched_result = search_result
-            .into_iter()
-            .filter_map(|mut scored_point| {
-                // Points might get deleted between search and retrieve.
-                // But it's not a problem, because we don't want to return deleted points.
-                // So we just filter out them.
-                records_map.remove(&scored_point.id).map(|record| {
-                    scored_point.payload = record.payload;
-                    scored_point.vector = record.vector;
-                    scored_point
-                })
-            })
-            .collect();
-        Ok(enriched_result)
+        let is_integer_field = self
+            .local_indexed_fields()
+            .await
+            .and_then(|indexed_fields| indexed_fields.get(group_by).cloned())
+            .map_or(false, |schema| {
+                schema == PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)
+            });
+        if is_integer_field {
+            number
+        } else {
+            key
+        }
     }
 
-    pub async fn search(
+    /// Whether the field, the points of a client request are grouped by, has no payload index
+    pub(crate) async fn is_group_by_field_unindexed(
         &self,
-        request: SearchRequest,
-        read_consistency: Option<ReadConsistency>,
+        group_by: &str,
         shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<ScoredPoint>> {
-        if request.limit == 0 {
-            return Ok(vec![]);
+    ) -> CollectionResult<bool> {
+        if shard_selection.is_some() {
+            return Ok(false);
         }
-        // search is a special case of search_batch with a single batch
-        let request_batch = SearchRequestBatch {
-            searches: vec![request],
-        };
-        let results = self
-            ._search_batch(request_batch, read_consistency, shard_selection)
-            .await?;
-        Ok(results.into_iter().next().unwrap())
+        let indexed_fields = self.payload_index_schema().await?;
+        Ok(!indexed_fields.contains_key(group_by))
     }
 
-    pub async fn scroll_by(
+    /// Check that the points of a client request can be ordered by the field,
+    /// i.e. it has a numeric payload index
+    pub(crate) async fn check_order_by_field(
         &self,
-        request: ScrollRequest,
-        read_consistency: Option<ReadConsistency>,
+        key: &str,
         shard_selection: Option<ShardId>,
-    ) -> CollectionResult<ScrollResult> {
-        let default_request = ScrollRequest::default();
-
-        let offset = request.offset;
-        let limit = request
-            .limit
-            .unwrap_or_else(|| default_request.limit.unwrap());
-        let with_payload_interface = request
-            .with_payload
-            .clone()
-            .unwrap_or_else(|| default_request.with_payload.clone().unwrap());
-        let with_vector = request.with_vector;
-
-        if limit == 0 {
-            return Err(CollectionError::BadRequest {
-                description: "Limit cannot be 0".to_string(),
-            });
+    ) -> CollectionResult<()> {
+        if shard_selection.is_some() || is_measured_operation() {
+            return Ok(());
         }
+        let indexed_fields = self.payload_index_schema().await?;
+        match indexed_fields.get(key) {
+            Some(schema) if schema.is_numeric() => Ok(()),
+            Some(schema) => Err(CollectionError::bad_input(format!(
+                "Points can't be ordered by `{key}`: it is indexed as {}, \
+                 but an integer or float index is required",
+                schema.type_name(),
+            ))),
+            None => Err(CollectionError::bad_input(format!(
+                "Points can't be ordered by `{key}`: it has no payload index. \
+                 Create an integer or float index for it"
+            ))),
+        }
+    }
 
-        // Needed to return next page offset.
-        let limit = limit + 1;
-        let retrieved_points: Vec<_> = {
-            let shards_holder = self.shards_holder.read().await;
-            let target_shards = shards_holder.target_shard(shard_selection)?;
-            let scroll_futures = target_shards.into_iter().map(|shard| {
-                shard.scroll_by(
-                    offset,
-                    limit,
-                    &with_payload_interface,
-                    &with_vector,
-                    request.filter.as_ref(),
-                    read_consistency,
-                )
-            });
-
-            try_join_all(scroll_futures).await?
+    /// Create a payload index for the field, the points are grouped by, in background,
+    /// if `auto_index_group_by_fields` is enabled.
+    /// The type of the index is inferred from the keys of the found groups.
+    pub(crate) async fn auto_index_group_by_field<'a>(
+        &self,
+        group_by: &str,
+        keys: impl IntoIterator<Item = &'a GroupId>,
+    ) {
+        {
+            let config = self.collection_config.read().await;
+            if !config.params.auto_index_group_by_fields || config.params.read_only {
+                return;
+            }
+        }
+        let Some(index_type) = auto_index::infer_index_type(keys) else {
+            return;
         };
-        let mut points: Vec<_> = retrieved_points
+        // Requests, grouping by the field before the index is built, don't create it again
+        if !self.auto_indexed_fields.lock().insert(group_by.to_string()) {
+            return;
+        }
+
+        let shards_holder = self.shards_holder.clone();
+        let auto_indexed_fields = self.auto_indexed_fields.clone();
+        let field = group_by.to_string();
+        self.update_runtime.spawn(async move {
+            log::info!("Creating {index_type:?} payload index for the group_by field `{field}`");
+            let result =
+                auto_index::create_field_index(&shards_holder, field.clone(), index_type).await;
+            if let Err(err) = result {
+                log::warn!(
+                    "Failed to create payload index for the group_by field `{field}`: {err}"
+                );
+            }
+            auto_indexed_fields.lock().remove(&field);
+        });
+    }
+
+    /// Collection config to fill the unset parameters of a client request with.
+    ///
+    /// Defaults are applied once, where the request enters the cluster, so `None` is returned
+    /// for requests of other peers and for internal requests of other operations.
+    pub(crate) async fn config_for_defaults(
+        &self,
+        shard_selection: Option<ShardId>,
+    ) -> Option<RwLockReadGuard<'_, CollectionConfig>> {
+        if shard_selection.is_some() || is_measured_operation() {
+            return None;
+        }
+        Some(self.collection_config.read().await)
+    }
+
+    /// Restrict the filters of a client read request to the points, which are not expired
+    /// and which belong to the access scope of the request.
+    ///
+    /// Like defaults, the expiration condition is added once, where the request enters the cluster.
+    /// The scope is added to the internal requests of other operations as well, e.g. to the lookup
+    /// of recommendation examples, while requests of other peers carry no scope.
+    /// Conditions are added after the strict mode check, so they don't count towards the limits.
+    pub(crate) async fn restrict_read_filters<'a>(
+        &self,
+        filters: impl IntoIterator<Item = &'a mut Option<Filter>>,
+        shard_selection: Option<ShardId>,
+    ) {
+        let scope = point_scope();
+        let config = self.collection_config.read().await;
+        let ttl_config = config
+            .ttl_config
+            .as_ref()
+            .filter(|_| shard_selection.is_none());
+        if ttl_config.is_none() && scope.is_none() {
+            return;
+        }
+        let now = self.shared_storage_config.clock.now();
+        for filter in filters {
+            if let Some(ttl_config) = ttl_config {
+                *filter = Some(ttl_config.exclude_expired(filter.take(), now));
+            }
+            if let Some(scope) = &scope {
+                *filter = Some(restrict_to_scope(filter.take(), scope));
+            }
+        }
+    }
+
+    /// Points of the list, which belong to the access scope of the request
+    async fn points_in_scope(
+        &self,
+        ids: &[PointIdType],
+        scope: &Filter,
+        read_consistency: Option<ReadConsistency>,
+    ) -> CollectionResult<HashSet<PointIdType>> {
+        if ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let filter = Filter {
+            must: Some(vec![
+                Condition::HasId(ids.iter().copied().collect::<HashSet<_>>().into()),
+                Condition::Filter(scope.clone()),
+            ]),
+            ..Default::default()
+        };
+        let points = self
+            .scroll_shards(
+                None,
+                ids.len(),
+                &WithPayloadInterface::Bool(false),
+                &WithVector::Bool(false),
+                Some(&filter),
+                read_consistency,
+                None,
+            )
+            .await?;
+        Ok(points.into_iter().map(|point| point.id).collect())
+    }
+
+    /// Limit the client update to the points of the access scope of the request.
+    ///
+    /// Points, selected by a filter, are restricted to the scope. The operation is rejected,
+    /// if it lists points, which exist out of the scope, or moves points out of the scope.
+    async fn restrict_update_to_scope(
+        &self,
+        operation: &mut CollectionUpdateOperations,
+        scope: &Filter,
+    ) -> CollectionResult<()> {
+        point_scope::check_operation_payload(operation, scope)?;
+        point_scope::restrict_operation_filter(operation, scope);
+        let OperationEffectArea::Points(ids) = operation.estimate_effect_area() else {
+            return Ok(());
+        };
+        let out_of_scope = Filter {
+            must: Some(vec![Condition::HasId(
+                ids.into_iter().collect::<HashSet<_>>().into(),
+            )]),
+            must_not: Some(vec![Condition::Filter(scope.clone())]),
+            ..Default::default()
+        };
+        let points = self
+            .scroll_shards(
+                None,
+                1,
+                &WithPayloadInterface::Bool(false),
+                &WithVector::Bool(false),
+                Some(&out_of_scope),
+                None,
+                None,
+            )
+            .await?;
+        if points.is_empty() {
+            Ok(())
+        } else {
+            Err(point_scope::out_of_scope_error(
+                "Some of the listed points are",
+            ))
+        }
+    }
+
+    /// Payload fields, indexed in local replicas. `None` if there are no local replicas
+    async fn local_indexed_fields(&self) -> Option<HashMap<PayloadKeyType, PayloadFieldSchema>> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut indexed_fields: Option<HashMap<PayloadKeyType, PayloadFieldSchema>> = None;
+        for replica_set in shard_holder.all_shards() {
+            if let Some(shard_fields) = replica_set.indexed_fields().await {
+                indexed_fields
+                    .get_or_insert_with(HashMap::new)
+                    .extend(shard_fields);
+            }
+        }
+        indexed_fields
+    }
+
+    /// Schema of the indexed payload fields.
+    /// Taken from local replicas, if there are any, to avoid requests to other peers.
+    async fn payload_index_schema(
+        &self,
+    ) -> CollectionResult<HashMap<PayloadKeyType, PayloadFieldSchema>> {
+        if let Some(indexed_fields) = self.local_indexed_fields().await {
+            return Ok(indexed_fields);
+        }
+        let info = self.info(None).await?;
+        Ok(info
+            .payload_schema
             .into_iter()
-            .flatten()
-            .sorted_by_key(|point| point.id)
-            .take(limit)
+            .filter_map(|(key, index_info)| {
+                Some((key, PayloadFieldSchema::try_from(index_info).ok()?))
+            })
+            .collect())
+    }
+
+    /// Turn creation of an already existing index into a no-op.
+    ///
+    /// The index might have been declared on the collection creation already.
+    /// Without an explicit schema the type would be inferred from the stored values again,
+    /// so the existing schema is used instead.
+    async fn resolve_existing_field_index(&self, operation: &mut CollectionUpdateOperations) {
+        let CollectionUpdateOperations::FieldIndexOperation(FieldIndexOperations::CreateIndex(
+            create_index,
+        )) = operation
+        else {
+            return;
+        };
+        let Some(indexed_fields) = self.local_indexed_fields().await else {
+            return;
+        };
+        let Some(existing_schema) = indexed_fields.get(&create_index.field_name) else {
+            return;
+        };
+        let field_schema = create_index
+            .field_schema
+            .get_or_insert(existing_schema.clone());
+        if *field_schema == *existing_schema {
+            log::warn!(
+                "Payload index for field `{}` already exists in collection {}, nothing to create",
+                create_index.field_name,
+                self.id,
+            );
+        }
+    }
+
+    /// Check payload of the client operation against the schema of the indexed fields, if enabled.
+    ///
+    /// Validation happens before the operation is split by shards and written into WAL,
+    /// so all replicas apply the same points. Returns points, rejected by the per-point validation.
+    async fn validate_update_payload(
+        &self,
+        operation: &mut CollectionUpdateOperations,
+    ) -> CollectionResult<Vec<RejectedPoint>> {
+        let mode = {
+            let config = self.collection_config.read().await;
+            if !config.params.validate_payload {
+                return Ok(vec![]);
+            }
+            config.params.payload_validation_mode
+        };
+        if !payload_validation::sets_payload(operation) {
+            return Ok(vec![]);
+        }
+        let schema = self.payload_index_schema().await?;
+        payload_validation::validate_operation_payload(operation, &schema, mode)
+    }
+
+    /// Read operations, which took longer than the configured threshold, from the most recent
+    pub fn slow_log_entries(&self) -> Vec<SlowLogEntry> {
+        self.slow_log.entries()
+    }
+
+    /// Start reading the data of the local shards into memory, in background
+    pub fn start_warmup(&self, request: WarmupRequest) -> CollectionResult<()> {
+        let mut warmup = self.warmup.lock();
+        if warmup.as_ref().map_or(false, Warmup::is_running) {
+            return Err(CollectionError::bad_request(format!(
+                "Warmup of collection {} is already in progress",
+                self.id
+            )));
+        }
+        *warmup = Some(Warmup::start(
+            self.shards_holder.clone(),
+            self.collection_config.clone(),
+            self.search_runtime.clone(),
+            request,
+            &self.shared_storage_config.warmup,
+        ));
+        Ok(())
+    }
+
+    /// Stop the running warmup. Returns `false` if there is no warmup in progress
+    pub fn cancel_warmup(&self) -> bool {
+        match self.warmup.lock().as_ref() {
+            Some(warmup) if warmup.is_running() => {
+                warmup.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn warmup_progress(&self) -> Option<WarmupProgress> {
+        self.warmup.lock().as_ref().map(Warmup::progress)
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(collection = %self.id, batch_size = request.searches.len(), shard = ?shard_selection)
+    )]
+    pub async fn search_batch(
+        &self,
+        mut request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        if let Some(config) = self.config_for_defaults(shard_selection).await {
+            for search in &mut request.searches {
+                config.apply_search_defaults(
+                    &mut search.params,
+                    &mut search.with_payload,
+                    &mut search.with_vector,
+                );
+                config.apply_default_vector(&mut search.vector);
+                config.apply_payload_size_default(&mut search.max_payload_return_bytes);
+                config.params.check_search_vector(&search.vector)?;
+                if let Some(with_vector) = &search.with_vector {
+                    config.params.check_with_vector(with_vector)?;
+                }
+            }
+        }
+        self.check_strict_mode(
+            request.searches.iter().map(StrictModeRequest::from),
+            shard_selection,
+        )
+        .await?;
+        self.restrict_read_filters(
+            request.searches.iter_mut().map(|search| &mut search.filter),
+            shard_selection,
+        )
+        .await;
+        let is_filter_reused = is_filter_reused(&request.searches);
+        let max_payload_return_bytes: Vec<_> = request
+            .searches
+            .iter()
+            .map(|search| search.max_payload_return_bytes)
             .collect();
+        let search_batch = self.measure_operation(
+            CollectionOperationType::SearchBatch,
+            shard_selection,
+            async {
+                // shortcuts batch if all requests with limit=0
+                if request.searches.iter().all(|s| s.limit == 0) {
+                    return Ok(vec![]);
+                }
+                record_requests(|| request.searches.iter().map(RequestDetails::from).collect());
+                // A factor which determines if we need to use the 2-step search or not
+                // Should be adjusted based on usage statistics.
+                const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
+
+                let is_payload_required = request.searches.iter().all(|s| {
+                    s.with_payload
+                        .clone()
+                        .map(|p| p.is_required())
+                        .unwrap_or_default()
+                });
+                let with_vectors = request.searches.iter().all(|s| {
+                    s.with_vector
+                        .as_ref()
+                        .map(|wv| wv.is_some())
+                        .unwrap_or(false)
+                });
 
-        let next_page_offset = if points.len() < limit {
-            // This was the last page
-            None
+                let metadata_required = is_payload_required || with_vectors;
+
+                let sum_limits: usize = request.searches.iter().map(|s| s.limit).sum();
+                let sum_offsets: usize = request.searches.iter().map(|s| s.offset).sum();
+
+                // Number of records we need to retrieve to fill the search result.
+                let require_transfers =
+                    self.shards_holder.read().await.len() * (sum_limits + sum_offsets);
+                // Actually used number of records.
+                let used_transfers = sum_limits;
+
+                let is_required_transfer_large_enough =
+                    require_transfers > used_transfers * PAYLOAD_TRANSFERS_FACTOR_THRESHOLD;
+
+                if metadata_required && is_required_transfer_large_enough {
+                    // If there is a significant offset, we need to retrieve the whole result
+                    // set without payload first and then retrieve the payload.
+                    // It is required to do this because the payload might be too large to send over the
+                    // network.
+                    let mut without_payload_requests = Vec::with_capacity(request.searches.len());
+                    for search in &request.searches {
+                        let mut without_payload_request = search.clone();
+                        without_payload_request.with_payload = None;
+                        without_payload_request.with_vector = None;
+                        without_payload_requests.push(without_payload_request);
+                    }
+                    let without_payload_batch = SearchRequestBatch {
+                        searches: without_payload_requests,
+                    };
+                    let without_payload_results = self
+                        ._search_batch(without_payload_batch, read_consistency, shard_selection)
+                        .await?;
+                    let filled_results = without_payload_results
+                        .into_iter()
+                        .zip(request.clone().searches.into_iter())
+                        .map(|(without_payload_result, req)| {
+                            self.fill_search_result_with_payload(
+                                without_payload_result,
+                                req.with_payload.clone(),
+                                req.with_vector.unwrap_or_default(),
+                                read_consistency,
+                                shard_selection,
+                            )
+                        });
+                    measure_phase("fetch_payload", try_join_all(filled_results)).await
+                } else {
+                    let result = self
+                        ._search_batch(request, read_consistency, shard_selection)
+                        .await?;
+                    Ok(result)
+                }
+            },
+        );
+        let mut results = if is_filter_reused {
+            with_request_filter_cache(None, search_batch).await
         } else {
-            // remove extra point, it would be a first point of the next page
-            Some(points.pop().unwrap().id)
+            search_batch.await
+        }?;
+        for (points, max_bytes) in results.iter_mut().zip(max_payload_return_bytes) {
+            truncate_scored_payloads(points, max_bytes);
+        }
+        Ok(results)
+    }
+
+    pub async fn _search_batch(
+        &self,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        self._search_shared_batch(Arc::new(request), read_consistency, shard_selection)
+            .await
+    }
+
+    async fn _search_shared_batch(
+        &self,
+        request: Arc<SearchRequestBatch>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let batch_size = request.searches.len();
+
+        // query all shards concurrently
+        let mut all_searches_res = self
+            .search_shards(request.clone(), read_consistency, shard_selection)
+            .await?;
+
+        // merge results from shards in order
+        let mut merged_results: Vec<Vec<ScoredPoint>> = vec![vec![]; batch_size];
+        for shard_searches_results in all_searches_res.iter_mut() {
+            for (index, shard_searches_result) in shard_searches_results.iter_mut().enumerate() {
+                merged_results[index].append(shard_searches_result)
+            }
+        }
+        let collection_params = self.collection_config.read().await.params.clone();
+        let top_results: Vec<_> = merged_results
+            .into_iter()
+            .zip(request.searches.iter())
+            .map(|(res, request)| {
+                let distance = collection_params.get_distance(request.vector.get_name())?;
+                let mut top_res = match distance.distance_order() {
+                    Order::LargeBetter => {
+                        peek_top_largest_iterable(res, request.limit + request.offset)
+                    }
+                    Order::SmallBetter => {
+                        peek_top_smallest_iterable(res, request.limit + request.offset)
+                    }
+                };
+                // Remove `offset` from top result only for client requests
+                // to avoid applying `offset` twice in distributed mode.
+                if shard_selection.is_none() && request.offset > 0 {
+                    if top_res.len() >= request.offset {
+                        // Panics if the end point > length of the vector.
+                        top_res.drain(..request.offset);
+                    } else {
+                        top_res.clear()
+                    }
+                }
+                Ok(top_res)
+            })
+            .collect::<CollectionResult<Vec<_>>>()?;
+
+        Ok(top_results)
+    }
+
+    /// Execute the batch on all target shards concurrently.
+    /// Results are returned per shard, without merging.
+    pub(crate) async fn search_shards(
+        &self,
+        request: Arc<SearchRequestBatch>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<Vec<ScoredPoint>>>> {
+        self.check_not_deleted()?;
+        let shard_holder = self.shards_holder.read().await;
+        let target_shards = shard_holder.target_shard(shard_selection)?;
+        record_shards(target_shards.iter().map(|shard| shard.shard_id));
+        let all_searches = target_shards.iter().map(|shard| {
+            shard
+                .search(request.clone(), read_consistency, &self.search_runtime)
+                .instrument(tracing::info_span!(
+                    "shard_search",
+                    shard_id = shard.shard_id
+                ))
+        });
+        measure_phase("search", join_shard_reads(&target_shards, all_searches)).await
+    }
+
+    pub(crate) async fn fill_search_result_with_payload(
+        &self,
+        search_result: Vec<ScoredPoint>,
+        with_payload: Option<WithPayloadInterface>,
+        with_vector: WithVector,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        // short-circuit if not needed
+        if let (&Some(WithPayloadInterface::Bool(false)), &WithVector::Bool(false)) =
+            (&with_payload, &with_vector)
+        {
+            return Ok(search_result
+                .into_iter()
+                .map(|point| ScoredPoint {
+                    payload: None,
+                    vector: None,
+                    ..point
+                })
+                .collect());
+        };
+
+        let retrieve_request = PointRequest {
+            ids: search_result.iter().map(|x| x.id).collect(),
+            with_payload,
+            with_vector,
+            with_timestamps: false,
+            max_payload_return_bytes: None,
         };
-        Ok(ScrollResult {
-            points,
-            next_page_offset,
+        let retrieved_records = self
+            .retrieve(retrieve_request, read_consistency, shard_selection)
+            .await?;
+        let mut records_map: HashMap<ExtendedPointId, Record> = retrieved_records
+            .into_iter()
+            .map(|rec| (rec.id, rec))
+            .collect();
+        let enriched_result = search_result
+            .into_iter()
+            .filter_map(|mut scored_point| {
+                // Points might get deleted between search and retrieve.
+                // But it's not a problem, because we don't want to return deleted points.
+                // So we just filter out them.
+                records_map.remove(&scored_point.id).map(|record| {
+                    scored_point.payload = record.payload;
+                    scored_point.vector = record.vector;
+                    scored_point.vector_slice = record.vector_slice;
+                    scored_point
+                })
+            })
+            .collect();
+        Ok(enriched_result)
+    }
+
+    #[tracing::instrument(skip_all, fields(collection = %self.id, shard = ?shard_selection))]
+    pub async fn search(
+        &self,
+        request: SearchRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        // search is a special case of search_batch with a single batch
+        let mut request_batch = Arc::new(SearchRequestBatch {
+            searches: vec![request],
+        });
+        self.search_shared(&mut request_batch, read_consistency, shard_selection)
+            .await
+    }
+
+    /// Search by the single request of the batch, which is shared with the shards.
+    ///
+    /// Internal requests of an operation, e.g. grouping, reuse the batch between the searches,
+    /// so the query vector is not copied for each of them. The request is copied only
+    /// if it is still referenced by the shards of a previous search.
+    pub(crate) async fn search_shared(
+        &self,
+        request_batch: &mut Arc<SearchRequestBatch>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let request = &mut Arc::make_mut(request_batch).searches[0];
+        if let Some(config) = self.config_for_defaults(shard_selection).await {
+            config.apply_search_defaults(
+                &mut request.params,
+                &mut request.with_payload,
+                &mut request.with_vector,
+            );
+            config.apply_default_vector(&mut request.vector);
+            config.apply_payload_size_default(&mut request.max_payload_return_bytes);
+            config.params.check_search_vector(&request.vector)?;
+            if let Some(with_vector) = &request.with_vector {
+                config.params.check_with_vector(with_vector)?;
+            }
+        }
+        self.check_strict_mode([StrictModeRequest::from(&*request)], shard_selection)
+            .await?;
+        self.restrict_read_filters([&mut request.filter], shard_selection)
+            .await;
+        let request_batch = request_batch.clone();
+        let request = &request_batch.searches[0];
+        let cache_key = self
+            .read_cache_key(
+                CachedOperation::Search,
+                request,
+                read_consistency,
+                shard_selection,
+            )
+            .await;
+        let search =
+            self.measure_operation(CollectionOperationType::Search, shard_selection, async {
+                if request.limit == 0 {
+                    return Ok(vec![]);
+                }
+                record_requests(|| vec![RequestDetails::from(request)]);
+                let max_payload_return_bytes = request.max_payload_return_bytes;
+                let results = self
+                    ._search_shared_batch(request_batch.clone(), read_consistency, shard_selection)
+                    .await?;
+                let mut points = results.into_iter().next().unwrap();
+                truncate_scored_payloads(&mut points, max_payload_return_bytes);
+                Ok(points)
+            });
+        self.cached_read(cache_key, search).await
+    }
+
+    /// Approximate number of points, which are not reachable by `indexed_only` searches
+    /// for the given vectors, because they are not indexed yet.
+    ///
+    /// Only local replicas are inspected, the result is extrapolated to the rest of the shards.
+    pub async fn indexed_only_excluded_points(
+        &self,
+        vector_names: &[String],
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<usize> {
+        let shard_holder = self.shards_holder.read().await;
+        let target_shards = shard_holder.target_shard(shard_selection)?;
+
+        let mut local_shards = 0;
+        let mut excluded_points = 0;
+        for replica_set in &target_shards {
+            if let Some(points) = replica_set.indexed_only_excluded_points(vector_names).await {
+                local_shards += 1;
+                excluded_points += points;
+            }
+        }
+        if local_shards == 0 {
+            return Ok(0);
+        }
+        Ok(excluded_points * target_shards.len() / local_shards)
+    }
+
+    #[tracing::instrument(skip_all, fields(collection = %self.id, shard = ?shard_selection))]
+    pub async fn scroll_by(
+        &self,
+        mut request: ScrollRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<ScrollResult> {
+        if let Some(config) = self.config_for_defaults(shard_selection).await {
+            config.apply_payload_size_default(&mut request.max_payload_return_bytes);
+            config.params.check_with_vector(&request.with_vector)?;
+        }
+        self.check_strict_mode([StrictModeRequest::from(&request)], shard_selection)
+            .await?;
+        self.restrict_read_filters([&mut request.filter], shard_selection)
+            .await;
+        self.measure_operation(CollectionOperationType::Scroll, shard_selection, async {
+            let default_request = ScrollRequest::default();
+
+            let offset = request.offset;
+            let limit = request
+                .limit
+                .unwrap_or_else(|| default_request.limit.unwrap());
+            let with_payload_interface = request
+                .with_payload
+                .clone()
+                .unwrap_or_else(|| default_request.with_payload.clone().unwrap());
+            let with_vector = request.with_vector;
+
+            if limit == 0 {
+                return Err(CollectionError::BadRequest {
+                    description: "Limit cannot be 0".to_string(),
+                });
+            }
+            record_requests(|| {
+                vec![RequestDetails {
+                    filter: request.filter.clone(),
+                    limit: Some(limit),
+                    params: None,
+                }]
+            });
+
+            // Needed to return next page offset.
+            let limit = limit + 1;
+            let mut points = self
+                .scroll_shards(
+                    offset,
+                    limit,
+                    &with_payload_interface,
+                    &with_vector,
+                    request.filter.as_ref(),
+                    read_consistency,
+                    shard_selection,
+                )
+                .await?;
+            if !request.with_timestamps {
+                points
+                    .iter_mut()
+                    .for_each(|point| point.last_modified = None);
+            }
+
+            let next_page_offset = if points.len() < limit {
+                // This was the last page
+                None
+            } else {
+                // remove extra point, it would be a first point of the next page
+                Some(points.pop().unwrap().id)
+            };
+            truncate_record_payloads(&mut points, request.max_payload_return_bytes);
+            Ok(ScrollResult {
+                points,
+                next_page_offset,
+            })
         })
+        .await
+    }
+
+    /// Reads up to `limit` points, starting from the `offset` id, from all of the target shards.
+    /// Points are ordered by id.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn scroll_shards(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Record>> {
+        self.check_not_deleted()?;
+        let retrieved_points: Vec<_> = {
+            let shards_holder = self.shards_holder.read().await;
+            let target_shards = shards_holder.target_shard(shard_selection)?;
+            record_shards(target_shards.iter().map(|shard| shard.shard_id));
+            let scroll_futures = target_shards.into_iter().map(|shard| {
+                shard
+                    .scroll_by(
+                        offset,
+                        limit,
+                        with_payload_interface,
+                        with_vector,
+                        filter,
+                        read_consistency,
+                    )
+                    .instrument(tracing::info_span!(
+                        "shard_scroll",
+                        shard_id = shard.shard_id
+                    ))
+            });
+
+            measure_phase("scroll", try_join_all(scroll_futures)).await?
+        };
+        // Shards return the points in order, so the pages are merged without sorting
+        Ok(retrieved_points
+            .into_iter()
+            .kmerge_by(|a, b| a.id < b.id)
+            .take(limit)
+            .collect())
     }
 
     pub async fn count(
         &self,
-        request: CountRequest,
+        mut request: CountRequest,
         shard_selection: Option<ShardId>,
     ) -> CollectionResult<CountResult> {
-        let request = Arc::new(request);
-
-        let counts: Vec<_> = {
-            let shards_holder = self.shards_holder.read().await;
-            let target_shards = shards_holder.target_shard(shard_selection)?;
-            let count_futures = target_shards
-                .into_iter()
-                .map(|shard| shard.count(request.clone()));
-            try_join_all(count_futures).await?.into_iter().collect()
-        };
+        self.check_strict_mode([StrictModeRequest::from(&request)], shard_selection)
+            .await?;
+        self.restrict_read_filters([&mut request.filter], shard_selection)
+            .await;
+        let cache_key = self
+            .read_cache_key(CachedOperation::Count, &request, None, shard_selection)
+            .await;
+        let count =
+            self.measure_operation(CollectionOperationType::Count, shard_selection, async {
+                record_requests(|| {
+                    vec![RequestDetails {
+                        filter: request.filter.clone(),
+                        ..Default::default()
+                    }]
+                });
+                let request = Arc::new(request);
+
+                self.check_not_deleted()?;
+                let counts: Vec<_> = {
+                    let shards_holder = self.shards_holder.read().await;
+                    let target_shards = shards_holder.target_shard(shard_selection)?;
+                    record_shards(target_shards.iter().map(|shard| shard.shard_id));
+                    let count_futures = target_shards
+                        .into_iter()
+                        .map(|shard| shard.count(request.clone()));
+                    measure_phase("count", try_join_all(count_futures))
+                        .await?
+                        .into_iter()
+                        .collect()
+                };
 
-        let total_count = counts.iter().map(|x| x.count).sum::<usize>();
-        let aggregated_count = CountResult { count: total_count };
-        Ok(aggregated_count)
+                Ok(CountResult::sum(&counts))
+            });
+        self.cached_read(cache_key, count).await
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(collection = %self.id, points = request.ids.len(), shard = ?shard_selection)
+    )]
     pub async fn retrieve(
         &self,
-        request: PointRequest,
+        mut request: PointRequest,
         read_consistency: Option<ReadConsistency>,
         shard_selection: Option<ShardId>,
     ) -> CollectionResult<Vec<Record>> {
-        let with_payload_interface = request
-            .with_payload
-            .as_ref()
-            .unwrap_or(&WithPayloadInterface::Bool(false));
-        let with_payload = WithPayload::from(with_payload_interface);
-        let request = Arc::new(request);
-        let all_shard_collection_results = {
-            let shard_holder = self.shards_holder.read().await;
-            let target_shards = shard_holder.target_shard(shard_selection)?;
-            let retrieve_futures = target_shards.into_iter().map(|shard| {
-                shard.retrieve(
-                    request.clone(),
-                    &with_payload,
-                    &request.with_vector,
-                    read_consistency,
+        if let Some(config) = self.config_for_defaults(shard_selection).await {
+            config.apply_payload_size_default(&mut request.max_payload_return_bytes);
+            config.params.check_with_vector(&request.with_vector)?;
+        }
+        if let Some(scope) = point_scope() {
+            // Points out of the scope are reported as missing
+            let in_scope = self
+                .points_in_scope(&request.ids, &scope, read_consistency)
+                .await?;
+            request.ids.retain(|id| in_scope.contains(id));
+        }
+        self.measure_operation(CollectionOperationType::Retrieve, shard_selection, async {
+            let with_payload_interface = request
+                .with_payload
+                .as_ref()
+                .unwrap_or(&WithPayloadInterface::Bool(false));
+            let with_payload = WithPayload::from(with_payload_interface);
+            let request = Arc::new(request);
+            self.check_not_deleted()?;
+            let all_shard_collection_results = {
+                let shard_holder = self.shards_holder.read().await;
+                let target_shards = shard_holder.target_shard(shard_selection)?;
+                record_shards(target_shards.iter().map(|shard| shard.shard_id));
+                let retrieve_futures = target_shards.iter().map(|shard| {
+                    shard
+                        .retrieve(
+                            request.clone(),
+                            &with_payload,
+                            &request.with_vector,
+                            read_consistency,
+                        )
+                        .instrument(tracing::info_span!(
+                            "shard_retrieve",
+                            shard_id = shard.shard_id
+                        ))
+                });
+                measure_phase(
+                    "retrieve",
+                    join_shard_reads(&target_shards, retrieve_futures),
                 )
-            });
-            try_join_all(retrieve_futures).await?
-        };
-        let points = all_shard_collection_results.into_iter().flatten().collect();
-        Ok(points)
+                .await?
+            };
+            let mut points: Vec<_> = all_shard_collection_results.into_iter().flatten().collect();
+            if !request.with_timestamps {
+                points
+                    .iter_mut()
+                    .for_each(|point| point.last_modified = None);
+            }
+            truncate_record_payloads(&mut points, request.max_payload_return_bytes);
+            Ok(points)
+        })
+        .await
+    }
+
+    /// Reader of the points from an Arrow IPC stream.
+    /// Fails if the columns don't match the vectors of the collection.
+    pub async fn arrow_points_reader(
+        &self,
+        arrow_ipc: Vec<u8>,
+    ) -> CollectionResult<ArrowPointsReader> {
+        let config = self.collection_config.read().await;
+        ArrowPointsReader::try_new(arrow_ipc, &config.params.vectors)
     }
 
     pub async fn update_params_from_diff(
@@ -1108,6 +2117,233 @@ impl Collection {
         Ok(())
     }
 
+    /// Updates strict mode limits, which are specified in the `strict_mode_diff`
+    pub async fn update_strict_mode_config(
+        &self,
+        strict_mode_diff: StrictModeConfig,
+    ) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            let strict_mode_config = config
+                .strict_mode_config
+                .clone()
+                .unwrap_or_default()
+                .update(&strict_mode_diff);
+            config.strict_mode_config = Some(strict_mode_config);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Updates expiration of the points. New interval of the deletions is applied after the
+    /// current one passes
+    pub async fn update_ttl_config(&self, ttl_config: TtlConfig) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            config.ttl_config = Some(ttl_config);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        // Responses of the collections with expiring points are not cached anymore
+        self.read_cache.clear();
+        Ok(())
+    }
+
+    /// Updates the limits of the data, which are specified in the `quota_diff`.
+    /// The usage of the shards is counted on the next update
+    pub async fn update_quota_config(&self, quota_diff: QuotaConfig) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            let quota_config = config
+                .quota_config
+                .clone()
+                .unwrap_or_default()
+                .update(&quota_diff);
+            config.quota_config = Some(quota_config);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Updates the limits of the operations, which are specified in the `admission_diff`.
+    /// Operations, which are already admitted, are not affected
+    pub async fn update_admission_config(
+        &self,
+        admission_diff: AdmissionConfig,
+    ) -> CollectionResult<()> {
+        let admission_config = {
+            let mut config = self.collection_config.write().await;
+            let admission_config = config
+                .admission_config
+                .clone()
+                .unwrap_or_default()
+                .update(&admission_diff);
+            config.admission_config = Some(admission_config.clone());
+            admission_config
+        };
+        self.collection_config.read().await.save(&self.path)?;
+        self.read_admission.configure(Some(&admission_config));
+        self.write_admission.configure(Some(&admission_config));
+        Ok(())
+    }
+
+    /// Wait for the permit to execute a read operation of a client.
+    ///
+    /// Requests of the other peers to the specific shards are not limited,
+    /// they are admitted by the peer, which received the request of the client.
+    pub async fn admit_read(
+        &self,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Option<ReadPermit>> {
+        if shard_selection.is_some() {
+            return Ok(None);
+        }
+        self.read_admission.admit().await
+    }
+
+    /// Updates the parameters of the read cache, which are specified in the `read_cache_diff`.
+    /// Cached responses are dropped
+    pub async fn update_read_cache_config(
+        &self,
+        read_cache_diff: ReadCacheConfig,
+    ) -> CollectionResult<()> {
+        let read_cache_config = {
+            let mut config = self.collection_config.write().await;
+            let read_cache_config = config
+                .read_cache_config
+                .clone()
+                .unwrap_or_default()
+                .update(&read_cache_diff);
+            config.read_cache_config = Some(read_cache_config.clone());
+            read_cache_config
+        };
+        self.collection_config.read().await.save(&self.path)?;
+        self.read_cache.configure(Some(&read_cache_config));
+        Ok(())
+    }
+
+    /// Version of the data of the collection, `None` if some of the shards have no active
+    /// local replica, so their updates can't be tracked by this peer
+    async fn data_version(&self) -> Option<DataVersion> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut version = DataVersion::default();
+        for replica_set in shards_holder.all_shards() {
+            version.add(replica_set.local_data_version().await?);
+        }
+        Some(version)
+    }
+
+    /// Key of the read operation in the read cache, `None` if its response is not cached.
+    ///
+    /// Only the requests of the clients are cached. The requests of the other peers to the
+    /// specific shards are parts of the requests, which are cached by those peers.
+    /// Responses are cached per access scope, as they are restricted to it.
+    ///
+    /// Responses of the collections with expiring points are not cached: the points expire
+    /// without any update, so the cached responses would keep returning them.
+    pub(crate) async fn read_cache_key(
+        &self,
+        operation: CachedOperation,
+        request: &impl Serialize,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> Option<CacheKey> {
+        if shard_selection.is_some() || !self.read_cache.is_enabled() {
+            return None;
+        }
+        if self.collection_config.read().await.ttl_config.is_some() {
+            return None;
+        }
+        // Partial results depend on the shards, which failed at the moment.
+        // Traced requests have to reach the shards to tell, which replicas served them.
+        if partial_results_allowed() || shard_trace_enabled() || is_internal_read() {
+            return None;
+        }
+        CacheKey::new(
+            operation,
+            request,
+            read_consistency,
+            point_scope().as_deref(),
+        )
+    }
+
+    /// Serve the read operation from the read cache, if its response is cached at the current
+    /// version of the data. Otherwise execute the `read` and cache its response.
+    ///
+    /// Internal reads of the operation are not cached on their own.
+    pub(crate) async fn cached_read<T, Fut>(
+        &self,
+        key: Option<CacheKey>,
+        read: Fut,
+    ) -> CollectionResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        Fut: Future<Output = CollectionResult<T>>,
+    {
+        let Some(key) = key else {
+            return with_internal_reads(read).await;
+        };
+        // Version is taken before the read, so the response is never older than its version
+        let Some(version) = self.data_version().await else {
+            return with_internal_reads(read).await;
+        };
+        if let Some(response) = self.read_cache.get(&key, version) {
+            request_hw_counter().set_cache_hit();
+            return Ok(response);
+        }
+        let response = with_internal_reads(read).await?;
+        self.read_cache.insert(key, version, &response);
+        Ok(response)
+    }
+
+    /// Updates the vector, used by the requests without a vector name. Empty name resets it.
+    /// Only the config is changed, stored vectors are not touched
+    pub async fn update_default_vector(&self, default_vector: String) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            if default_vector.is_empty() {
+                config.default_vector = None;
+            } else {
+                config
+                    .params
+                    .vectors
+                    .check_default_vector(Some(&default_vector))?;
+                config.default_vector = Some(default_vector);
+            }
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        self.read_cache.clear();
+        Ok(())
+    }
+
+    /// Updates defaults of the search requests. Defaults, which are not specified, are kept
+    pub async fn update_search_defaults(
+        &self,
+        search_params: Option<SearchParams>,
+        with_payload: Option<WithPayloadInterface>,
+        with_vector: Option<WithVector>,
+        max_payload_return_bytes: Option<usize>,
+    ) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            if search_params.is_some() {
+                config.default_search_params = search_params;
+            }
+            if with_payload.is_some() {
+                config.default_with_payload = with_payload;
+            }
+            if with_vector.is_some() {
+                config.default_with_vector = with_vector;
+            }
+            if max_payload_return_bytes.is_some() {
+                config.default_max_payload_return_bytes = max_payload_return_bytes;
+            }
+        }
+        // Grouped searches are cached before the defaults are applied
+        self.read_cache.clear();
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
     pub fn request_shard_transfer(&self, shard_transfer: ShardTransfer) {
         self.request_shard_transfer_cb.deref()(shard_transfer)
     }
@@ -1204,6 +2440,144 @@ impl Collection {
         Ok(())
     }
 
+    /// Wakes up optimizers of all local shards, without waiting for the next update
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        let shard_holder = self.shards_holder.read().await;
+        for replica_set in shard_holder.all_shards() {
+            replica_set.trigger_optimizers().await?;
+        }
+        Ok(())
+    }
+
+    /// Flush WAL and segments of each shard, located on this peer.
+    ///
+    /// If `wait` is false, returns right after the flushes are started.
+    /// Otherwise, reports the outcome of the flush of each shard.
+    pub async fn flush_local_shards(&self, wait: bool) -> CollectionResult<CollectionFlushResult> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut flushes = vec![];
+        for (shard_id, replica_set) in shard_holder.get_shards() {
+            if let Some(flush) = replica_set.local_flush().await {
+                flushes.push((*shard_id, flush));
+            }
+        }
+        drop(shard_holder);
+
+        if !wait {
+            return Ok(CollectionFlushResult {
+                status: UpdateStatus::Acknowledged,
+                shards: vec![],
+            });
+        }
+
+        let mut shards = Vec::with_capacity(flushes.len());
+        for (shard_id, flush) in flushes {
+            let result = flush
+                .await
+                .map_err(CollectionError::from)
+                .and_then(|res| res);
+            shards.push(match result {
+                Ok(flushed_operation) => ShardFlushResult {
+                    shard_id,
+                    success: true,
+                    flushed_operation: Some(flushed_operation),
+                    error: None,
+                },
+                Err(err) => ShardFlushResult {
+                    shard_id,
+                    success: false,
+                    flushed_operation: None,
+                    error: Some(err.to_string()),
+                },
+            });
+        }
+        shards.sort_by_key(|shard| shard.shard_id);
+        Ok(CollectionFlushResult {
+            status: UpdateStatus::Completed,
+            shards,
+        })
+    }
+
+    /// Describes optimizations, which would be launched next in local shards.
+    /// Nothing is executed.
+    pub async fn optimizers_plan(&self) -> CollectionResult<OptimizersPlan> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut shards = vec![];
+        for replica_set in shard_holder.all_shards() {
+            if let Some(plan) = replica_set.optimization_plan().await? {
+                shards.push(plan);
+            }
+        }
+        shards.sort_by_key(|plan| plan.shard_id);
+        Ok(OptimizersPlan { shards })
+    }
+
+    /// Wait until every shard reports the `status` or a better one, or until the `timeout`.
+    /// Current statuses of the shards are returned in either case.
+    ///
+    /// Local shards notify about possible changes of their status, remote shards are polled.
+    pub async fn wait_for_status(
+        &self,
+        status: CollectionStatus,
+        timeout: Duration,
+    ) -> CollectionResult<WaitForStatusResult> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Subscribe before reading the statuses, so that no change in between is missed
+            let mut receivers = self.subscribe_status_changes().await;
+            let shards = self.shard_statuses().await;
+            let reached = shards.iter().all(|shard| shard.status <= status);
+
+            let now = Instant::now();
+            if reached || now >= deadline {
+                return Ok(WaitForStatusResult { reached, shards });
+            }
+
+            let wait_time = (deadline - now).min(STATUS_POLL_INTERVAL);
+            if receivers.is_empty() {
+                tokio::time::sleep(wait_time).await;
+            } else {
+                let changes = receivers
+                    .iter_mut()
+                    .map(|receiver| Box::pin(receiver.changed()));
+                let _ = tokio::time::timeout(wait_time, futures::future::select_all(changes)).await;
+            }
+        }
+    }
+
+    async fn subscribe_status_changes(&self) -> Vec<watch::Receiver<()>> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut receivers = vec![];
+        for replica_set in shard_holder.all_shards() {
+            if let Some(receiver) = replica_set.subscribe_status_changes().await {
+                receivers.push(receiver);
+            }
+        }
+        receivers
+    }
+
+    async fn shard_statuses(&self) -> Vec<ShardStatus> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut shards = vec![];
+        for (shard_id, replica_set) in shard_holder.get_shards() {
+            // Shard, which can't report its status, is not able to serve requests either
+            let status = replica_set
+                .info()
+                .await
+                .map_or(CollectionStatus::Red, |info| info.status);
+            shards.push(ShardStatus {
+                shard_id: *shard_id,
+                status,
+            });
+        }
+        shards.sort_by_key(|shard| shard.shard_id);
+        shards
+    }
+
+    /// Information about the collection, summed over the shards.
+    ///
+    /// Each shard is reported by a single replica, so the numbers don't grow with replication:
+    /// point counts and the memory of the payload indexes are the ones of one copy of the data.
     pub async fn info(&self, shard_selection: Option<ShardId>) -> CollectionResult<CollectionInfo> {
         let (all_shard_collection_results, mut info) = {
             let shards_holder = self.shards_holder.read().await;
@@ -1235,14 +2609,26 @@ impl Collection {
                 for (key, schema) in shard_info.payload_schema {
                     match info.payload_schema.entry(key) {
                         Entry::Occupied(o) => {
-                            o.into_mut().points += schema.points;
+                            o.into_mut().merge(schema);
                         }
                         Entry::Vacant(v) => {
                             v.insert(schema);
                         }
                     };
                 }
+                info.quota_usage = match (info.quota_usage, shard_info.quota_usage) {
+                    (Some(mut usage), Some(shard_usage)) => {
+                        usage.add(&shard_usage);
+                        Some(usage)
+                    }
+                    (usage, shard_usage) => usage.or(shard_usage),
+                };
             });
+        info.warmup = self.warmup_progress();
+        info.index_builds = self.local_field_index_builds().await?;
+        info.merge_targets = Some(MergeTargets::from(
+            &self.collection_config.read().await.optimizer_config,
+        ));
         Ok(info)
     }
 
@@ -1271,6 +2657,7 @@ impl Collection {
                     shard_id,
                     points_count,
                     state,
+                    recovery: replica_set.recovery_state(),
                 })
             }
             for (peer_id, state) in replica_set.peers().into_iter() {
@@ -1285,21 +2672,181 @@ impl Collection {
             }
         }
         let shard_transfers = shards_holder.get_shard_transfer_info();
+        drop(shards_holder);
 
         // sort by shard_id
         local_shards.sort_by_key(|k| k.shard_id);
         remote_shards.sort_by_key(|k| k.shard_id);
 
+        let suggested_moves = self.rebalancing_plan().await?.moves;
+
         let info = CollectionClusterInfo {
             peer_id,
             shard_count,
             local_shards,
             remote_shards,
             shard_transfers,
+            suggested_moves,
         };
         Ok(info)
     }
 
+    /// Plan of the shard moves, which would even out the placement of the shards on the peers
+    pub async fn rebalancing_plan(&self) -> CollectionResult<RebalancingPlan> {
+        let transfers = self.get_transfers(|_| true).await;
+        let shards_holder = self.shards_holder.read().await;
+        let count_request = Arc::new(CountRequest {
+            filter: None,
+            exact: false, // Size estimation is enough for balancing
+        });
+        let mut placements = Vec::new();
+        for (shard_id, replica_set) in shards_holder.get_shards() {
+            // Unavailable shards are considered empty, their number still counts
+            let points = match replica_set.count(count_request.clone()).await {
+                Ok(count_result) => count_result.count,
+                Err(err) => {
+                    log::debug!("Can't count points of shard {shard_id} for balancing: {err}");
+                    0
+                }
+            };
+            placements.push(ShardPlacement {
+                shard_id: *shard_id,
+                replicas: replica_set.peers(),
+                points,
+            });
+        }
+        let peers: Vec<_> = self
+            .channel_service
+            .id_to_address
+            .read()
+            .keys()
+            .copied()
+            .collect();
+        Ok(plan_rebalancing(&placements, &peers, &transfers))
+    }
+
+    /// Compare the checksums of the replicas of each shard, to verify that they converged
+    pub async fn checksum(&self) -> CollectionChecksum {
+        let shards_holder = self.shards_holder.read().await;
+        let reports = shards_holder
+            .get_shards()
+            .map(|(_shard_id, replica_set)| replica_set.checksum_report());
+        CollectionChecksum::new(join_all(reports).await)
+    }
+
+    /// Checksum of the local replica of the shard
+    pub async fn local_shard_checksum(&self, shard_id: ShardId) -> CollectionResult<ShardChecksum> {
+        let shards_holder = self.shards_holder.read().await;
+        match shards_holder.get_shard(&shard_id) {
+            Some(replica_set) => replica_set.local_checksum().await,
+            None => Err(CollectionError::bad_shard_selection(format!(
+                "Shard {shard_id} does not exist"
+            ))),
+        }
+    }
+
+    /// Segment layout of the shard replica, located on this peer
+    pub async fn local_shard_segments(
+        &self,
+        shard_id: ShardId,
+    ) -> CollectionResult<ShardSegmentsInfo> {
+        let shards_holder = self.shards_holder.read().await;
+        match shards_holder.get_shard(&shard_id) {
+            Some(replica_set) => replica_set.local_segments_info().await,
+            None => Err(CollectionError::bad_shard_selection(format!(
+                "Shard {shard_id} does not exist"
+            ))),
+        }
+    }
+
+    /// Progress of the rebuild of the payload field index in each shard, located on this peer
+    pub async fn local_index_rebuild_progress(
+        &self,
+        field: &str,
+    ) -> CollectionResult<Vec<ShardIndexRebuildProgress>> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut progress = vec![];
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            if let Some(shard_progress) = replica_set.local_index_rebuild_progress(field).await? {
+                progress.push(shard_progress);
+            }
+        }
+        progress.sort_by_key(|shard_progress| shard_progress.shard_id);
+        Ok(progress)
+    }
+
+    /// Creations of payload field indexes in each shard, located on this peer
+    pub async fn local_field_index_builds(&self) -> CollectionResult<Vec<FieldIndexBuildProgress>> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut builds = vec![];
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            builds.extend(replica_set.local_field_index_builds().await?);
+        }
+        builds.sort_by_key(|build| build.shard_id);
+        Ok(builds)
+    }
+
+    /// Field, which index is created by the operation, if the index is still being built
+    pub async fn field_index_build_field(
+        &self,
+        operation_id: SeqNumberType,
+    ) -> CollectionResult<String> {
+        self.local_field_index_builds()
+            .await?
+            .into_iter()
+            .find(|build| build.operation_id == operation_id)
+            .map(|build| build.field_name)
+            .ok_or_else(|| CollectionError::NotFound {
+                what: format!("Index build {operation_id}"),
+            })
+    }
+
+    /// Check the filter against the point and record the outcome of every condition.
+    ///
+    /// Only the shard replicas, located on this peer, are looked up for the point.
+    pub async fn check_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Filter,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<FilterTrace> {
+        if let Some(scope) = point_scope() {
+            let in_scope = self.points_in_scope(&[point_id], &scope, None).await?;
+            if in_scope.is_empty() {
+                return Err(CollectionError::PointNotFound {
+                    missed_point_id: point_id,
+                });
+            }
+        }
+        let filter = Arc::new(filter);
+        let shards_holder = self.shards_holder.read().await;
+        let mut not_local_shards = vec![];
+        for replica_set in shards_holder.target_shard(shard_selection)? {
+            if !replica_set.has_local_shard().await {
+                not_local_shards.push(replica_set.shard_id);
+                continue;
+            }
+            if let Some(trace) = replica_set
+                .local_trace_filter(point_id, filter.clone())
+                .await?
+            {
+                return Ok(trace);
+            }
+        }
+
+        if not_local_shards.is_empty() {
+            Err(CollectionError::PointNotFound {
+                missed_point_id: point_id,
+            })
+        } else {
+            Err(CollectionError::bad_request(format!(
+                "Point {point_id} is not found in the shards of this peer. \
+                 Shards {} have no replica on this peer, check the point on the peers hosting them",
+                not_local_shards.iter().join(", "),
+            )))
+        }
+    }
+
     pub async fn state(&self) -> State {
         let shards_holder = self.shards_holder.read().await;
         let transfers = shards_holder.shard_transfers.read().clone();
@@ -1327,12 +2874,13 @@ impl Collection {
         state.apply(this_peer_id, self, abort_transfer).await
     }
 
-    pub async fn get_telemetry_data(&self) -> CollectionTelemetry {
+    /// With `with_graph_health`, the health of the HNSW graphs of the local segments is collected
+    pub async fn get_telemetry_data(&self, with_graph_health: bool) -> CollectionTelemetry {
         let (shards_telemetry, transfers) = {
             let mut shards_telemetry = Vec::new();
             let shards_holder = self.shards_holder.read().await;
             for shard in shards_holder.all_shards() {
-                shards_telemetry.push(shard.get_telemetry_data().await)
+                shards_telemetry.push(shard.get_telemetry_data(with_graph_health).await)
             }
             (shards_telemetry, shards_holder.get_shard_transfer_info())
         };
@@ -1341,8 +2889,14 @@ impl Collection {
             id: self.name(),
             init_time_ms: self.init_time.as_millis() as u64,
             config: self.collection_config.read().await.clone(),
+            indexing_threads_used: CollectionTelemetry::count_indexing_threads(&shards_telemetry),
             shards: shards_telemetry,
             transfers,
+            operations: OperationsTelemetry {
+                read_admission: self.read_admission.telemetry(),
+                write_admission: self.write_admission.telemetry(),
+                ..self.operations_telemetry.lock().clone()
+            },
         }
     }
 
@@ -1540,6 +3094,7 @@ impl Collection {
         on_finish_init: ChangePeerState,
         on_convert_to_listener: ChangePeerState,
         on_convert_from_listener: ChangePeerState,
+        recovery_slots: &mut usize,
     ) -> CollectionResult<()> {
         // Check for disabled replicas
         let shard_holder = self.shards_holder.read().await;
@@ -1607,19 +3162,55 @@ impl Collection {
             }
 
             if this_peer_state != Some(Dead) || replica_set.is_dummy().await {
+                if this_peer_state != Some(Partial) {
+                    // Replica is recovered, or its state is changed by the consensus
+                    replica_set.set_recovery_state(None)?;
+                }
                 continue; // All good
             }
 
+            let recovery_config = &self.shared_storage_config.replica_recovery;
+            if !recovery_config.auto_recovery {
+                continue; // Dead replicas are recovered by the operator
+            }
+
             // Try to find dead replicas with no active transfers
             let transfers = self.get_transfers(|_| true).await;
 
+            let now = Utc::now();
+            let recovery = match replica_set.recovery_state() {
+                None => RecoveryState::pending(now),
+                Some(recovery) if recovery.is_lost(now) => {
+                    let is_transferred = transfers.iter().any(|transfer| {
+                        transfer.shard_id == shard_id && transfer.to == *this_peer_id
+                    });
+                    if is_transferred {
+                        recovery
+                    } else {
+                        recovery.fail(
+                            "transfer of the recovery was not started".to_string(),
+                            now,
+                            recovery_config,
+                        )
+                    }
+                }
+                Some(recovery) => recovery,
+            };
+
+            if !recovery.is_due(now) || *recovery_slots == 0 {
+                replica_set.set_recovery_state(Some(recovery))?;
+                continue;
+            }
+
             // Try to find a replica to transfer from
+            let mut recovery = recovery;
             for replica_id in replica_set.active_remote_shards().await {
                 let transfer = ShardTransfer {
                     from: replica_id,
                     to: *this_peer_id,
                     shard_id,
                     sync: true,
+                    method: Some(ShardTransferMethod::WalDelta),
                 };
                 if check_transfer_conflicts(&transfer, transfers.iter()).is_some() {
                     continue; // this transfer won't work
@@ -1632,13 +3223,29 @@ impl Collection {
                     replica_id
                 );
                 self.request_shard_transfer(transfer);
+                recovery = recovery.start(replica_id, now);
+                *recovery_slots -= 1;
                 break;
             }
+            replica_set.set_recovery_state(Some(recovery))?;
         }
 
         Ok(())
     }
 
+    /// Number of the dead local replicas, which are being recovered
+    pub async fn running_recoveries(&self) -> usize {
+        let shard_holder = self.shards_holder.read().await;
+        shard_holder
+            .all_shards()
+            .filter(|replica_set| {
+                replica_set
+                    .recovery_state()
+                    .map_or(false, |recovery| recovery.is_recovering())
+            })
+            .count()
+    }
+
     pub fn wait_collection_initiated(&self, timeout: Duration) -> bool {
         self.is_initialized.await_ready_for_timeout(timeout)
     }
@@ -20,6 +20,7 @@ impl CollectionUpdater {
         op_num: SeqNumberType,
         operation_result: &CollectionResult<usize>,
     ) {
+        segments.read().on_operation_applied();
         match operation_result {
             Ok(_) => {
                 if !segments.read().failed_operation.is_empty() {
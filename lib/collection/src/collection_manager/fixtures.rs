@@ -199,6 +199,7 @@ pub(crate) fn get_merge_optimizer(
 ) -> MergeOptimizer {
     MergeOptimizer::new(
         5,
+        100_000,
         OptimizerThresholds {
             max_segment_size: 100_000,
             memmap_threshold: 1000000,
@@ -213,9 +214,18 @@ pub(crate) fn get_merge_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
             }),
             shard_number: NonZeroU32::new(1).unwrap(),
             on_disk_payload: false,
+            validate_payload: false,
+            payload_validation_mode: Default::default(),
+            sparse_vectors: None,
+            payload_indexes: Default::default(),
+            payload_cache_fields: Default::default(),
+            read_only: false,
+            auto_index_group_by_fields: false,
+            read_hedge_delay_ms: None,
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
         },
@@ -244,9 +254,18 @@ pub(crate) fn get_indexing_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
             }),
             shard_number: NonZeroU32::new(1).unwrap(),
             on_disk_payload: false,
+            validate_payload: false,
+            payload_validation_mode: Default::default(),
+            sparse_vectors: None,
+            payload_indexes: Default::default(),
+            payload_cache_fields: Default::default(),
+            read_only: false,
+            auto_index_group_by_fields: false,
+            read_hedge_delay_ms: None,
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
         },
@@ -1,26 +1,78 @@
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use itertools::Itertools;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
+use segment::data_types::filter_trace::FilterTrace;
 use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::order_by::OrderBy;
+use segment::data_types::sparse_vector::SparseVector;
 use segment::data_types::vectors::VectorElementType;
-use segment::entry::entry_point::{OperationResult, SegmentEntry, SegmentFailedState};
+use segment::entry::entry_point::{
+    OperationError, OperationResult, SegmentEntry, SegmentFailedState,
+};
 use segment::index::field_index::CardinalityEstimation;
-use segment::telemetry::SegmentTelemetry;
+use segment::index::filter_bitmask::FilterBitmask;
+use segment::telemetry::{HnswGraphHealth, SegmentTelemetry};
 use segment::types::{
-    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef,
+    PayloadSelector, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
+    SegmentType, SeqNumberType, WithPayload, WithVector,
 };
 
 use crate::collection_manager::holders::segment_holder::LockedSegment;
 
-type LockedRmSet = Arc<RwLock<HashSet<PointIdType>>>;
+type LockedRmSet = Arc<RwLock<ProxyDeletedPoints>>;
 type LockedFieldsSet = Arc<RwLock<HashSet<PayloadKeyType>>>;
 type LockedFieldsMap = Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>;
 
+/// Points removed from the wrapped segments while they are under optimization.
+///
+/// Besides the set used for lookups, it remembers the order in which points were removed.
+/// It allows the optimizer to propagate deletes into the optimized segment incrementally,
+/// without re-checking points which were already propagated.
+#[derive(Debug, Default)]
+pub struct ProxyDeletedPoints {
+    points: HashSet<PointIdType>,
+    /// Append-only, contains each point of `points` exactly once
+    log: Vec<PointIdType>,
+}
+
+impl ProxyDeletedPoints {
+    /// Returns `true` if the point was not marked as deleted before
+    pub fn insert(&mut self, point_id: PointIdType) -> bool {
+        let inserted = self.points.insert(point_id);
+        if inserted {
+            self.log.push(point_id);
+        }
+        inserted
+    }
+
+    /// Points deleted after the first `offset` ones, in order of deletion
+    pub fn since(&self, offset: usize) -> &[PointIdType] {
+        &self.log[offset.min(self.log.len())..]
+    }
+}
+
+impl Extend<PointIdType> for ProxyDeletedPoints {
+    fn extend<I: IntoIterator<Item = PointIdType>>(&mut self, iter: I) {
+        for point_id in iter {
+            self.insert(point_id);
+        }
+    }
+}
+
+impl Deref for ProxyDeletedPoints {
+    type Target = HashSet<PointIdType>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.points
+    }
+}
+
 /// This object is a wrapper around read-only segment.
 /// It could be used to provide all read and write operations while wrapped segment is being optimized (i.e. not available for writing)
 /// It writes all changed records into a temporary `write_segment` and keeps track on changed points
@@ -167,6 +219,15 @@ impl SegmentEntry for ProxySegment {
             .or_else(|| self.wrapped_segment.get().read().point_version(point_id))
     }
 
+    fn point_timestamp(&self, point_id: PointIdType) -> Option<u64> {
+        // Point in the write segment is always the latest one
+        self.write_segment
+            .get()
+            .read()
+            .point_timestamp(point_id)
+            .or_else(|| self.wrapped_segment.get().read().point_timestamp(point_id))
+    }
+
     fn search(
         &self,
         vector_name: &str,
@@ -283,6 +344,76 @@ impl SegmentEntry for ProxySegment {
         Ok(wrapped_results)
     }
 
+    /// Internal ids of the proxy are split between two segments, and points of the wrapped
+    /// segment may be deleted at any time, so it is searched with the filter as is
+    fn filter_bitmask(&self, _filter: &Filter) -> Option<FilterBitmask> {
+        None
+    }
+
+    fn search_batch_prefiltered(
+        &self,
+        _vector_name: &str,
+        _vectors: &[&[VectorElementType]],
+        _with_payload: &WithPayload,
+        _with_vector: &WithVector,
+        _bitmask: &FilterBitmask,
+        _filter: Option<&Filter>,
+        _top: usize,
+        _params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        Err(OperationError::service_error(
+            "Proxy segment doesn't provide filter bitmasks to search with",
+        ))
+    }
+
+    fn search_sparse_batch(
+        &self,
+        vector_name: &str,
+        vectors: &[&SparseVector],
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        let deleted_points = self.deleted_points.read();
+
+        // Same as for dense vectors, points deleted after temporary segment creation
+        // must not be found in the wrapped segment
+        let mut wrapped_results = if !deleted_points.is_empty() {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().search_sparse_batch(
+                vector_name,
+                vectors,
+                with_payload,
+                with_vector,
+                Some(&wrapped_filter),
+                top,
+            )?
+        } else {
+            self.wrapped_segment.get().read().search_sparse_batch(
+                vector_name,
+                vectors,
+                with_payload,
+                with_vector,
+                filter,
+                top,
+            )?
+        };
+        let mut write_results = self.write_segment.get().read().search_sparse_batch(
+            vector_name,
+            vectors,
+            with_payload,
+            with_vector,
+            filter,
+            top,
+        )?;
+        for (index, write_result) in write_results.iter_mut().enumerate() {
+            wrapped_results[index].append(write_result)
+        }
+        Ok(wrapped_results)
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -416,20 +547,44 @@ impl SegmentEntry for ProxySegment {
         };
     }
 
+    fn sparse_vector(
+        &self,
+        vector_name: &str,
+        point_id: PointIdType,
+    ) -> OperationResult<Option<SparseVector>> {
+        return if self.deleted_points.read().contains(&point_id) {
+            self.write_segment
+                .get()
+                .read()
+                .sparse_vector(vector_name, point_id)
+        } else {
+            {
+                let write_segment = self.write_segment.get();
+                let segment_guard = write_segment.read();
+                if segment_guard.has_point(point_id) {
+                    return segment_guard.sparse_vector(vector_name, point_id);
+                }
+            }
+            self.wrapped_segment
+                .get()
+                .read()
+                .sparse_vector(vector_name, point_id)
+        };
+    }
+
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors> {
         let mut result = NamedVectors::default();
-        for vector_name in self
-            .wrapped_segment
-            .get()
-            .read()
-            .config()
-            .vector_data
-            .keys()
-        {
+        let config = self.wrapped_segment.get().read().config();
+        for vector_name in config.vector_data.keys() {
             if let Some(vector) = self.vector(vector_name, point_id)? {
                 result.insert(vector_name.clone(), vector);
             }
         }
+        for vector_name in config.sparse_vector_data.keys() {
+            if let Some(vector) = self.sparse_vector(vector_name, point_id)? {
+                result.insert_sparse(vector_name.clone(), vector);
+            }
+        }
         Ok(result)
     }
 
@@ -448,6 +603,69 @@ impl SegmentEntry for ProxySegment {
         };
     }
 
+    fn selected_payload(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        return if self.deleted_points.read().contains(&point_id) {
+            self.write_segment
+                .get()
+                .read()
+                .selected_payload(point_id, selector)
+        } else {
+            {
+                let write_segment = self.write_segment.get();
+                let segment_guard = write_segment.read();
+                if segment_guard.has_point(point_id) {
+                    return segment_guard.selected_payload(point_id, selector);
+                }
+            }
+            self.wrapped_segment
+                .get()
+                .read()
+                .selected_payload(point_id, selector)
+        };
+    }
+
+    fn trace_filter(&self, point_id: PointIdType, filter: &Filter) -> OperationResult<FilterTrace> {
+        return if self.deleted_points.read().contains(&point_id) {
+            self.write_segment
+                .get()
+                .read()
+                .trace_filter(point_id, filter)
+        } else {
+            {
+                let write_segment = self.write_segment.get();
+                let segment_guard = write_segment.read();
+                if segment_guard.has_point(point_id) {
+                    return segment_guard.trace_filter(point_id, filter);
+                }
+            }
+            self.wrapped_segment
+                .get()
+                .read()
+                .trace_filter(point_id, filter)
+        };
+    }
+
+    fn unindexed_filter_keys(&self, filter: &Filter) -> Vec<PayloadKeyType> {
+        let mut keys = self
+            .wrapped_segment
+            .get()
+            .read()
+            .unindexed_filter_keys(filter);
+        keys.extend(
+            self.write_segment
+                .get()
+                .read()
+                .unindexed_filter_keys(filter),
+        );
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
     /// Not implemented for proxy
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
         // iter_points is not available for Proxy implementation
@@ -462,7 +680,7 @@ impl SegmentEntry for ProxySegment {
         filter: Option<&'a Filter>,
     ) -> Vec<PointIdType> {
         let deleted_points = self.deleted_points.read();
-        let mut read_points = if deleted_points.is_empty() {
+        let read_points = if deleted_points.is_empty() {
             self.wrapped_segment
                 .get()
                 .read()
@@ -475,14 +693,53 @@ impl SegmentEntry for ProxySegment {
                 .read()
                 .read_filtered(offset, limit, Some(&wrapped_filter))
         };
-        let mut write_segment_points = self
+        let write_segment_points = self
             .write_segment
             .get()
             .read()
             .read_filtered(offset, limit, filter);
-        read_points.append(&mut write_segment_points);
-        read_points.sort_unstable();
+        // Both of the segments return the points in order
         read_points
+            .into_iter()
+            .merge(write_segment_points)
+            .collect()
+    }
+
+    fn read_ordered_filtered(
+        &self,
+        limit: Option<usize>,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+    ) -> OperationResult<Vec<(f64, PointIdType)>> {
+        let deleted_points = self.deleted_points.read();
+        let read_points = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_ordered_filtered(limit, filter, order_by)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().read_ordered_filtered(
+                limit,
+                Some(&wrapped_filter),
+                order_by,
+            )?
+        };
+        let write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .read_ordered_filtered(limit, filter, order_by)?;
+        // Both of the segments return the points in order
+        let direction = order_by.direction;
+        Ok(read_points
+            .into_iter()
+            .merge_by(write_segment_points, |(a, _), (b, _)| {
+                direction.compare(*a, *b).is_le()
+            })
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
     }
 
     /// Read points in [from; to) range
@@ -668,6 +925,91 @@ impl SegmentEntry for ProxySegment {
         Ok(true)
     }
 
+    fn stage_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        if self.version() > op_num {
+            return Ok(false);
+        }
+        // Indexes of the wrapped segment are built from scratch by the ongoing optimization
+        self.write_segment
+            .get()
+            .write()
+            .stage_field_index_rebuild(op_num, key)
+    }
+
+    fn stage_field_index_creation(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_schema: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool> {
+        if self.version() > op_num {
+            return Ok(false);
+        }
+
+        self.write_segment
+            .get()
+            .write()
+            .stage_field_index_creation(op_num, key, field_schema)?;
+        let indexed_fields = self.write_segment.get().read().get_indexed_fields();
+
+        let payload_schema = match indexed_fields.get(key) {
+            Some(schema_type) => schema_type,
+            None => return Ok(false),
+        };
+
+        // The optimized segment builds the index right away.
+        // Explicit schema might convert the existing index, which is still indexed by the old one
+        self.created_indexes.write().insert(
+            key.into(),
+            field_schema.unwrap_or(payload_schema).to_owned(),
+        );
+        self.deleted_indexes.write().remove(key);
+
+        Ok(true)
+    }
+
+    fn cancel_field_index_build(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        if self.version() > op_num {
+            return Ok(false);
+        }
+
+        let is_cancelled = self
+            .write_segment
+            .get()
+            .write()
+            .cancel_field_index_build(op_num, key)?;
+
+        // Index, which creation is cancelled, must not appear in the optimized segment
+        let is_wrapped_building = match &self.wrapped_segment {
+            LockedSegment::Original(segment) => {
+                segment.read().building_field_indexes().contains_key(key)
+            }
+            LockedSegment::Proxy(_) => false,
+        };
+        let is_created_here = self.created_indexes.read().contains_key(key)
+            && !self
+                .write_segment
+                .get()
+                .read()
+                .get_indexed_fields()
+                .contains_key(key);
+        if is_wrapped_building || is_created_here {
+            self.created_indexes.write().remove(key);
+            self.deleted_indexes.write().insert(key.into());
+            return Ok(true);
+        }
+
+        Ok(is_cancelled)
+    }
+
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
         let indexed_fields = self.wrapped_segment.get().read().get_indexed_fields();
         indexed_fields
@@ -752,6 +1094,10 @@ impl SegmentEntry for ProxySegment {
     fn get_telemetry_data(&self) -> SegmentTelemetry {
         self.wrapped_segment.get().read().get_telemetry_data()
     }
+
+    fn graph_health(&self) -> HashMap<String, HnswGraphHealth> {
+        self.wrapped_segment.get().read().graph_health()
+    }
 }
 
 #[cfg(test)]
@@ -773,7 +1119,7 @@ mod tests {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -840,7 +1186,7 @@ mod tests {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -903,7 +1249,7 @@ mod tests {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
         let original_segment = LockedSegment::new(random_segment(dir.path(), 100, 200, 4));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -956,7 +1302,7 @@ mod tests {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
         let original_segment = LockedSegment::new(random_segment(dir.path(), 100, 200, 4));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -1016,7 +1362,7 @@ mod tests {
 
     fn wrap_proxy(dir: &TempDir, original_segment: LockedSegment) -> ProxySegment {
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -1096,7 +1442,7 @@ mod tests {
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
 
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
             HashMap::<PayloadKeyType, PayloadFieldSchema>::new(),
@@ -1156,7 +1502,7 @@ mod tests {
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
         let original_segment_2 = LockedSegment::new(build_segment_2(dir.path()));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -1224,7 +1570,7 @@ mod tests {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
         let original_segment = LockedSegment::new(build_segment_1(dir.path()));
         let write_segment = LockedSegment::new(empty_segment(dir.path()));
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
@@ -1283,6 +1629,7 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
                 (
@@ -1293,10 +1640,13 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut original_segment = build_segment(dir.path(), &config, true).unwrap();
         let write_segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1318,7 +1668,7 @@ mod tests {
 
         let original_segment = LockedSegment::new(original_segment);
         let write_segment = LockedSegment::new(write_segment);
-        let deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
 
         let deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let created_indexes = Arc::new(RwLock::new(
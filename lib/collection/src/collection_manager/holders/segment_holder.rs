@@ -2,6 +2,7 @@ use std::cmp::{max, min};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ops::{Deref, Mul};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread::sleep;
 use std::time::Duration;
@@ -107,6 +108,32 @@ impl From<ProxySegment> for LockedSegment {
     }
 }
 
+/// Segments, which were replaced in the holder, but whose data is not dropped yet.
+///
+/// Data of the replaced segments may only be removed from disk once the segment which
+/// replaced them is persisted, otherwise a crash in between could lose the points.
+/// This type makes the ordering explicit: data is only dropped by [`Self::execute_after`].
+#[must_use = "data of the replaced segments is only dropped by `execute_after`"]
+pub struct ScheduledDelete {
+    segments: Vec<LockedSegment>,
+}
+
+impl ScheduledDelete {
+    pub fn new(segments: Vec<LockedSegment>) -> Self {
+        Self { segments }
+    }
+
+    /// Flush `replacement` to disk and drop data of the scheduled segments afterwards.
+    /// If the flush fails, data of the scheduled segments is kept.
+    pub fn execute_after(self, replacement: &LockedSegment) -> OperationResult<()> {
+        replacement.get().read().flush(true)?;
+        for segment in self.segments {
+            segment.drop_data()?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default)]
 pub struct SegmentHolder {
     segments: HashMap<SegmentId, LockedSegment>,
@@ -116,6 +143,10 @@ pub struct SegmentHolder {
 
     /// Holds the first uncorrected error happened with optimizer
     pub optimizer_errors: Option<CollectionError>,
+
+    /// Number of update operations, applied to the segments, successfully or not.
+    /// Changes whenever the points of the segments might have changed
+    applied_operations: AtomicU64,
 }
 
 pub type LockedSegmentHolder = Arc<RwLock<SegmentHolder>>;
@@ -133,6 +164,15 @@ impl<'s> SegmentHolder {
         self.segments.is_empty()
     }
 
+    pub fn applied_operations(&self) -> u64 {
+        self.applied_operations.load(Ordering::Acquire)
+    }
+
+    /// Should be called after an update operation is applied to the segments
+    pub fn on_operation_applied(&self) {
+        self.applied_operations.fetch_add(1, Ordering::AcqRel);
+    }
+
     fn generate_new_key(&self) -> SegmentId {
         let key = thread_rng().gen::<SegmentId>();
         if self.segments.contains_key(&key) {
@@ -396,37 +436,50 @@ impl<'s> SegmentHolder {
     /// Flush appendable segments first, then non-appendable.
     /// This is done to ensure that all data, transferred from non-appendable segments to appendable segments
     /// is persisted, before marking records in non-appendable segments as removed.
-    fn segment_flush_ordering(&self) -> impl Iterator<Item = SegmentId> {
-        let appendable_segments = self.appendable_segments();
-        let non_appendable_segments = self.non_appendable_segments();
-
-        appendable_segments
-            .into_iter()
-            .chain(non_appendable_segments.into_iter())
+    fn segment_flush_ordering(&self) -> [Vec<SegmentId>; 2] {
+        [self.appendable_segments(), self.non_appendable_segments()]
     }
 
     /// Flushes all segments and returns maximum version to persist
     ///
     /// If there are unsaved changes after flush - detects lowest unsaved change version.
     /// If all changes are saved - returns max version.
+    ///
+    /// Failures of all segments in a group are reported together.
+    /// Non-appendable segments are not flushed if any appendable segment failed.
     pub fn flush_all(&self, sync: bool) -> OperationResult<SeqNumberType> {
         let mut max_persisted_version: SeqNumberType = SeqNumberType::MIN;
         let mut min_unsaved_version: SeqNumberType = SeqNumberType::MAX;
         let mut has_unsaved = false;
 
-        for segment_id in self.segment_flush_ordering() {
-            let segment = self.segments.get(&segment_id).unwrap();
-            let segment_lock = segment.get();
-            let read_segment = segment_lock.read();
-            let segment_version = read_segment.version();
-            let segment_persisted_version = read_segment.flush(sync)?;
+        for segment_ids in self.segment_flush_ordering() {
+            let mut errors = Vec::new();
+            for segment_id in segment_ids {
+                let segment = self.segments.get(&segment_id).unwrap();
+                let segment_lock = segment.get();
+                let read_segment = segment_lock.read();
+                let segment_version = read_segment.version();
+                let segment_persisted_version = match read_segment.flush(sync) {
+                    Ok(version) => version,
+                    Err(err) => {
+                        errors.push(format!("segment {segment_id}: {err}"));
+                        continue;
+                    }
+                };
 
-            if segment_version > segment_persisted_version {
-                has_unsaved = true;
-                min_unsaved_version = min(min_unsaved_version, segment_persisted_version);
-            }
+                if segment_version > segment_persisted_version {
+                    has_unsaved = true;
+                    min_unsaved_version = min(min_unsaved_version, segment_persisted_version);
+                }
 
-            max_persisted_version = max(max_persisted_version, segment_persisted_version)
+                max_persisted_version = max(max_persisted_version, segment_persisted_version)
+            }
+            if !errors.is_empty() {
+                return Err(OperationError::service_error(format!(
+                    "Failed to flush {}",
+                    errors.join("; ")
+                )));
+            }
         }
         if has_unsaved {
             Ok(min_unsaved_version)
@@ -221,6 +221,10 @@ impl SegmentOptimizer for IndexingOptimizer {
         &self.thresholds_config
     }
 
+    fn name(&self) -> &str {
+        "indexing"
+    }
+
     fn check_condition(
         &self,
         segments: LockedSegmentHolder,
@@ -249,6 +253,7 @@ mod tests {
     use itertools::Itertools;
     use parking_lot::lock_api::RwLock;
     use rand::thread_rng;
+    use segment::common::cpu::CpuPermit;
     use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
     use segment::fixtures::index_fixtures::random_vector;
     use segment::types::{Payload, PayloadSchemaType};
@@ -304,6 +309,7 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
                     },
                 )
             })
@@ -323,6 +329,14 @@ mod tests {
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
                 on_disk_payload: false,
+                validate_payload: false,
+                payload_validation_mode: Default::default(),
+                sparse_vectors: None,
+                payload_indexes: Default::default(),
+                payload_cache_fields: Default::default(),
+                read_only: false,
+                auto_index_group_by_fields: false,
+                read_hedge_delay_ms: None,
             },
             Default::default(),
             Default::default(),
@@ -343,7 +357,12 @@ mod tests {
         assert!(suggested_to_optimize.contains(&large_segment_id));
 
         index_optimizer
-            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &stopped,
+            )
             .unwrap();
 
         let infos = locked_holder
@@ -422,11 +441,20 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    datatype: None,
                 }),
                 shard_number: NonZeroU32::new(1).unwrap(),
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
                 on_disk_payload: false,
+                validate_payload: false,
+                payload_validation_mode: Default::default(),
+                sparse_vectors: None,
+                payload_indexes: Default::default(),
+                payload_cache_fields: Default::default(),
+                read_only: false,
+                auto_index_group_by_fields: false,
+                read_hedge_delay_ms: None,
             },
             Default::default(),
             Default::default(),
@@ -483,7 +511,12 @@ mod tests {
         assert!(suggested_to_optimize.contains(&large_segment_id));
         eprintln!("suggested_to_optimize = {suggested_to_optimize:#?}");
         index_optimizer
-            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &stopped,
+            )
             .unwrap();
         eprintln!("Done");
 
@@ -492,7 +525,12 @@ mod tests {
             index_optimizer.check_condition(locked_holder.clone(), &excluded_ids);
         assert!(suggested_to_optimize.contains(&middle_segment_id));
         index_optimizer
-            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &stopped,
+            )
             .unwrap();
 
         // ------- Keep smallest segment without changes
@@ -606,7 +644,12 @@ mod tests {
             index_optimizer.check_condition(locked_holder.clone(), &Default::default());
         assert!(suggested_to_optimize.contains(&small_segment_id));
         index_optimizer
-            .optimize(locked_holder.clone(), suggested_to_optimize, &stopped)
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &stopped,
+            )
             .unwrap();
 
         let new_infos2 = locked_holder
@@ -25,6 +25,8 @@ const BYTES_IN_KB: usize = 1024;
 /// will be less than before.
 pub struct MergeOptimizer {
     max_segments: usize,
+    /// Segments of this size (in kilobytes) or larger are not merged
+    target_segment_size: usize,
     thresholds_config: OptimizerThresholds,
     segments_path: PathBuf,
     collection_temp_dir: PathBuf,
@@ -38,6 +40,7 @@ impl MergeOptimizer {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         max_segments: usize,
+        target_segment_size: usize,
         thresholds_config: OptimizerThresholds,
         segments_path: PathBuf,
         collection_temp_dir: PathBuf,
@@ -47,6 +50,7 @@ impl MergeOptimizer {
     ) -> Self {
         MergeOptimizer {
             max_segments,
+            target_segment_size,
             thresholds_config,
             segments_path,
             collection_temp_dir,
@@ -83,6 +87,10 @@ impl SegmentOptimizer for MergeOptimizer {
         &self.thresholds_config
     }
 
+    fn name(&self) -> &str {
+        "merge"
+    }
+
     fn check_condition(
         &self,
         segments: LockedSegmentHolder,
@@ -104,7 +112,9 @@ impl SegmentOptimizer for MergeOptimizer {
 
         // Find at least top-3 smallest segments to join.
         // We need 3 segments because in this case we can guarantee that total segments number will be less
+        // Segments, which already reached the target size, are left as is
 
+        let target_segment_size_bytes = self.target_segment_size.saturating_mul(BYTES_IN_KB);
         let candidates: Vec<_> = raw_segments
             .iter()
             .cloned()
@@ -123,6 +133,7 @@ impl SegmentOptimizer for MergeOptimizer {
                         * VECTOR_ELEMENT_SIZE,
                 ))
             })
+            .filter(|(_, size)| *size < target_segment_size_bytes)
             .sorted_by_key(|(_, size)| *size)
             .scan(0, |size_sum, (sid, size)| {
                 *size_sum += size; // produce a cumulative sum of segment sizes starting from smallest
@@ -161,6 +172,7 @@ mod tests {
     use std::sync::Arc;
 
     use parking_lot::RwLock;
+    use segment::common::cpu::CpuPermit;
     use tempfile::Builder;
 
     use super::*;
@@ -201,6 +213,83 @@ mod tests {
         assert_eq!(check_result.len(), 3);
     }
 
+    #[test]
+    fn test_merge_target_segment_size() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+        let mut holder = SegmentHolder::default();
+        let dim = 256;
+
+        let small_segments = vec![
+            holder.add(random_segment(dir.path(), 100, 3, dim)),
+            holder.add(random_segment(dir.path(), 100, 4, dim)),
+            holder.add(random_segment(dir.path(), 100, 5, dim)),
+        ];
+        let _target_sized_segment = holder.add(random_segment(dir.path(), 100, 10, dim));
+
+        let mut merge_optimizer = get_merge_optimizer(dir.path(), temp_dir.path(), dim);
+        merge_optimizer.max_segments = 1;
+        merge_optimizer.target_segment_size = 10;
+
+        let locked_holder = Arc::new(RwLock::new(holder));
+
+        // Segment of the target size is excluded, even though it would fit into the max size
+        let candidates =
+            merge_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(candidates, small_segments);
+
+        merge_optimizer.target_segment_size = 5;
+        let candidates =
+            merge_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert!(candidates.is_empty());
+
+        // No merges at or below the max number of segments
+        merge_optimizer.target_segment_size = 100_000;
+        merge_optimizer.max_segments = 4;
+        let candidates = merge_optimizer.check_condition(locked_holder, &Default::default());
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn test_merge_optimization_plan() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+        let mut holder = SegmentHolder::default();
+        let dim = 256;
+
+        let segments_to_merge = vec![
+            holder.add(random_segment(dir.path(), 100, 3, dim)),
+            holder.add(random_segment(dir.path(), 100, 3, dim)),
+            holder.add(random_segment(dir.path(), 100, 3, dim)),
+        ];
+
+        let mut merge_optimizer = get_merge_optimizer(dir.path(), temp_dir.path(), dim);
+        merge_optimizer.max_segments = 1;
+
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let plan = merge_optimizer
+            .plan_optimization(locked_holder.clone(), &Default::default())
+            .unwrap();
+
+        assert_eq!(plan.optimizer, "merge");
+        assert_eq!(plan.segment_ids.len(), 3);
+        for segment_id in &plan.segment_ids {
+            assert!(segments_to_merge.contains(segment_id));
+        }
+        assert_eq!(plan.estimated_size_bytes, 3 * 3 * dim * VECTOR_ELEMENT_SIZE);
+
+        // Planning must not change segments
+        assert_eq!(locked_holder.read().iter().count(), 3);
+
+        merge_optimizer.max_segments = 3;
+        assert!(merge_optimizer
+            .plan_optimization(locked_holder, &Default::default())
+            .is_none());
+    }
+
     #[test]
     fn test_merge_optimizer() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -247,6 +336,7 @@ mod tests {
             .optimize(
                 locked_holder.clone(),
                 suggested_for_merge,
+                CpuPermit::dummy(2),
                 &AtomicBool::new(false),
             )
             .unwrap();
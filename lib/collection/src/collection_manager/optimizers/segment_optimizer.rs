@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
+use segment::common::cpu::CpuPermit;
 use segment::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator, ScopeDurationMeasurer,
 };
@@ -14,20 +15,23 @@ use segment::segment::{Segment, SegmentVersion};
 use segment::segment_constructor::build_segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
 use segment::types::{
-    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PayloadStorageType, PointIdType,
+    HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PayloadStorageType,
     QuantizationConfig, SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
 };
 
-use crate::collection_manager::holders::proxy_segment::ProxySegment;
+use crate::collection_manager::holders::proxy_segment::{ProxyDeletedPoints, ProxySegment};
 use crate::collection_manager::holders::segment_holder::{
-    LockedSegment, LockedSegmentHolder, SegmentId,
+    LockedSegment, LockedSegmentHolder, ScheduledDelete, SegmentId,
 };
 use crate::config::CollectionParams;
 use crate::operations::config_diff::DiffConfig;
-use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::types::{CollectionError, CollectionResult, OptimizationPlan};
 
 const BYTES_IN_KB: usize = 1024;
 
+/// Max number of deletes propagated from proxy segments under a single lock
+const PROXY_DELETES_BATCH_SIZE: usize = 1024;
+
 #[derive(Debug, Clone)]
 pub struct OptimizerThresholds {
     pub max_segment_size: usize,
@@ -35,6 +39,33 @@ pub struct OptimizerThresholds {
     pub indexing_threshold: usize,
 }
 
+/// Delete points, which were deleted through the proxy segments, from the optimized segment.
+///
+/// Skips the first `propagated` deletes, which were already applied.
+/// Deletes are copied in batches, so concurrent deletes are only blocked for a short time.
+///
+/// Returns the total number of propagated deletes.
+fn propagate_proxy_deletes(
+    optimized_segment: &mut Segment,
+    proxy_deleted_points: &RwLock<ProxyDeletedPoints>,
+    mut propagated: usize,
+) -> CollectionResult<usize> {
+    loop {
+        let batch = {
+            let deleted_points = proxy_deleted_points.read();
+            let pending = deleted_points.since(propagated);
+            pending[..pending.len().min(PROXY_DELETES_BATCH_SIZE)].to_vec()
+        };
+        if batch.is_empty() {
+            return Ok(propagated);
+        }
+        for &point_id in &batch {
+            optimized_segment.delete_point(optimized_segment.version(), point_id)?;
+        }
+        propagated += batch.len();
+    }
+}
+
 /// SegmentOptimizer - trait implementing common functionality of the optimizers
 ///
 /// It provides functions which allow to re-build specified segments into a new, better one.
@@ -62,6 +93,9 @@ pub trait SegmentOptimizer {
     /// Get thresholds configuration for the current optimizer
     fn threshold_config(&self) -> &OptimizerThresholds;
 
+    /// Name of the optimizer, used to describe planned optimizations
+    fn name(&self) -> &str;
+
     /// Checks if segment optimization is required
     fn check_condition(
         &self,
@@ -69,6 +103,37 @@ pub trait SegmentOptimizer {
         excluded_ids: &HashSet<SegmentId>,
     ) -> Vec<SegmentId>;
 
+    /// Describes the optimization, which would be launched by this optimizer, without running it.
+    /// Returns `None` if optimization is not required.
+    fn plan_optimization(
+        &self,
+        segments: LockedSegmentHolder,
+        excluded_ids: &HashSet<SegmentId>,
+    ) -> Option<OptimizationPlan> {
+        let segment_ids = self.check_condition(segments.clone(), excluded_ids);
+        if segment_ids.is_empty() {
+            return None;
+        }
+
+        let read_segments = segments.read();
+        let estimated_size_bytes = segment_ids
+            .iter()
+            .filter_map(|segment_id| read_segments.get(*segment_id))
+            .map(|segment| {
+                let segment_entry = segment.get();
+                let read_segment = segment_entry.read();
+                let vectors_dim: usize = read_segment.vector_dims().values().sum();
+                read_segment.available_point_count() * vectors_dim * VECTOR_ELEMENT_SIZE
+            })
+            .sum();
+
+        Some(OptimizationPlan {
+            optimizer: self.name().to_string(),
+            segment_ids,
+            estimated_size_bytes,
+        })
+    }
+
     fn get_telemetry_data(&self) -> OperationDurationStatistics;
 
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>>;
@@ -78,11 +143,13 @@ pub trait SegmentOptimizer {
         let collection_params = self.collection_params();
         let config = SegmentConfig {
             vector_data: collection_params.into_base_vector_data()?,
+            sparse_vector_data: collection_params.into_sparse_vector_data(),
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
                 PayloadStorageType::InMemory
             },
+            payload_cache_fields: collection_params.payload_cache_fields.clone(),
         };
         Ok(LockedSegment::new(build_segment(
             self.collection_path(),
@@ -166,7 +233,11 @@ pub trait SegmentOptimizer {
                     .unwrap_or_else(|| collection_hnsw.clone());
                 config.index = Indexes::Hnsw(vector_hnsw);
 
-                // Assign quantization config
+                // Assign quantization config, unless the vectors are stored in a type,
+                // which can't be quantized
+                if !config.datatype.supports_quantization() {
+                    return;
+                }
                 let param_quantization = collection_params
                     .vectors
                     .get_params(vector_name)
@@ -187,11 +258,13 @@ pub trait SegmentOptimizer {
 
         let optimized_config = SegmentConfig {
             vector_data,
+            sparse_vector_data: collection_params.into_sparse_vector_data(),
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
                 PayloadStorageType::InMemory
             },
+            payload_cache_fields: collection_params.payload_cache_fields.clone(),
         };
 
         Ok(SegmentBuilder::new(
@@ -283,20 +356,22 @@ pub trait SegmentOptimizer {
     /// # Arguments
     ///
     /// * `optimizing_segments` - Segments to optimize
-    /// * `proxy_deleted_points` - Holds a set of points, deleted while optimization was running
     /// * `proxy_deleted_indexes` - Holds a set of Indexes, deleted while optimization was running
     /// * `proxy_created_indexes` - Holds a set of Indexes, created while optimization was running
+    /// * `permit` - CPUs, which could be used for building indexes of the new segment
     /// * `stopped` - flag to check if optimization was cancelled by external thread
     ///
     /// # Result
     ///
-    /// Constructs optimized segment
+    /// Constructs optimized segment.
+    /// Points deleted while optimization was running are not removed from it,
+    /// see `propagate_proxy_deletes`.
     fn build_new_segment(
         &self,
         optimizing_segments: &[LockedSegment],
-        proxy_deleted_points: Arc<RwLock<HashSet<PointIdType>>>,
         proxy_deleted_indexes: Arc<RwLock<HashSet<PayloadKeyType>>>,
         proxy_created_indexes: Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>,
+        permit: CpuPermit,
         stopped: &AtomicBool,
     ) -> CollectionResult<Segment> {
         let mut segment_builder = self.optimized_segment_builder(optimizing_segments)?;
@@ -322,21 +397,7 @@ pub trait SegmentOptimizer {
                 .insert(field.to_owned(), schema_type.to_owned());
         }
 
-        let mut optimized_segment: Segment = segment_builder.build(stopped)?;
-
-        // Delete points in 2 steps
-        // First step - delete all points with read lock
-        // Second step - delete all the rest points with full write lock
-        //
-        // Use collection copy to prevent long time lock of `proxy_deleted_points`
-        let deleted_points_snapshot: Vec<PointIdType> =
-            proxy_deleted_points.read().iter().cloned().collect();
-
-        for &point_id in &deleted_points_snapshot {
-            optimized_segment
-                .delete_point(optimized_segment.version(), point_id)
-                .unwrap();
-        }
+        let mut optimized_segment: Segment = segment_builder.build(permit, stopped)?;
 
         let deleted_indexes = proxy_deleted_indexes.read().iter().cloned().collect_vec();
         let create_indexes = proxy_created_indexes.read().clone();
@@ -358,6 +419,19 @@ pub trait SegmentOptimizer {
         Ok(optimized_segment)
     }
 
+    /// Try to resolve optimization of the given segments without rebuilding them,
+    /// e.g. by repairing only some of their components in place.
+    ///
+    /// Returns `None` if segments should be rebuilt by the regular optimization routine.
+    fn optimize_in_place(
+        &self,
+        _segments: &LockedSegmentHolder,
+        _ids: &[SegmentId],
+        _stopped: &AtomicBool,
+    ) -> CollectionResult<Option<bool>> {
+        Ok(None)
+    }
+
     /// Performs optimization of collections's segments, including:
     ///     - Segment rebuilding
     ///     - Segment joining
@@ -366,6 +440,8 @@ pub trait SegmentOptimizer {
     ///
     /// * `segments` - segments holder
     /// * `ids` - list of segment ids to perform optimization on. All segments will be merged into single one
+    /// * `permit` - CPUs, which this optimization is allowed to use for index building.
+    ///              Released as soon as the optimization is finished
     /// * `stopped` - flag for early stopping of the optimization.
     ///               If appears to be `true` - optimization process should be cancelled, all segments unwrapped
     ///
@@ -378,6 +454,7 @@ pub trait SegmentOptimizer {
         &self,
         segments: LockedSegmentHolder,
         ids: Vec<SegmentId>,
+        permit: CpuPermit,
         stopped: &AtomicBool,
     ) -> CollectionResult<bool> {
         check_process_stopped(stopped)?;
@@ -385,6 +462,11 @@ pub trait SegmentOptimizer {
         let mut timer = ScopeDurationMeasurer::new(&self.get_telemetry_counter());
         timer.set_success(false);
 
+        if let Some(result) = self.optimize_in_place(&segments, &ids, stopped)? {
+            timer.set_success(true);
+            return Ok(result);
+        }
+
         // On the one hand - we want to check consistently if all provided segments are
         // available for optimization (not already under one) and we want to do it before creating a temp segment
         // which is an expensive operation. So we can't not unlock `segments` after the check and before the insert.
@@ -415,7 +497,7 @@ pub trait SegmentOptimizer {
 
         let tmp_segment = self.temp_segment(false)?;
 
-        let proxy_deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+        let proxy_deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
         let proxy_deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
         let proxy_created_indexes = Arc::new(RwLock::new(HashMap::<
             PayloadKeyType,
@@ -472,9 +554,9 @@ pub trait SegmentOptimizer {
 
         let mut optimized_segment = match self.build_new_segment(
             &optimizing_segments,
-            proxy_deleted_points.clone(),
             proxy_deleted_indexes.clone(),
             proxy_created_indexes.clone(),
+            permit,
             stopped,
         ) {
             Ok(segment) => segment,
@@ -486,17 +568,11 @@ pub trait SegmentOptimizer {
             }
         };
 
-        // Avoid unnecessary point removing in the critical section:
-        // - save already removed points while avoiding long read locks
-        // - exclude already removed points from post-optimization removing
-        let already_remove_points = {
-            let mut all_removed_points: HashSet<_> =
-                proxy_deleted_points.read().iter().cloned().collect();
-            for existing_point in optimized_segment.iter_points() {
-                all_removed_points.remove(&existing_point);
-            }
-            all_removed_points
-        };
+        // Delete points in 2 steps
+        // First step - propagate deletes made so far, without blocking the collection
+        // Second step - propagate the rest of the deletes with full write lock
+        let propagated_deletes =
+            propagate_proxy_deletes(&mut optimized_segment, &proxy_deleted_points, 0)?;
 
         // ---- SLOW PART ENDS HERE -----
 
@@ -508,13 +584,12 @@ pub trait SegmentOptimizer {
         {
             // This block locks all operations with collection. It should be fast
             let mut write_segments_guard = segments.write();
-            let deleted_points = proxy_deleted_points.read();
-            let points_diff = deleted_points.difference(&already_remove_points);
-            for &point_id in points_diff {
-                optimized_segment
-                    .delete_point(optimized_segment.version(), point_id)
-                    .unwrap();
-            }
+            // Updates are blocked by the write lock, so only a few deletes can be pending here
+            propagate_proxy_deletes(
+                &mut optimized_segment,
+                &proxy_deleted_points,
+                propagated_deletes,
+            )?;
 
             for deleted_field_name in proxy_deleted_indexes.read().iter() {
                 optimized_segment
@@ -531,7 +606,13 @@ pub trait SegmentOptimizer {
 
             optimized_segment.prefault_mmap_pages();
 
-            let (_, proxies) = write_segments_guard.swap(optimized_segment, &proxy_ids);
+            let (optimized_id, proxies) = write_segments_guard.swap(optimized_segment, &proxy_ids);
+            let optimized_segment = write_segments_guard
+                .get(optimized_id)
+                .cloned()
+                .expect("optimized segment was just inserted");
+            // Data of the proxied segments is dropped only after the optimized segment is flushed
+            let scheduled_delete = ScheduledDelete::new(proxies);
 
             let has_appendable_segments =
                 write_segments_guard.random_appendable_segment().is_some();
@@ -549,18 +630,14 @@ pub trait SegmentOptimizer {
 
                 // Only remove data after we ensure the consistency of the collection.
                 // If remove fails - we will still have operational collection with reported error.
-                for proxy in proxies {
-                    proxy.drop_data()?;
-                }
+                scheduled_delete.execute_after(&optimized_segment)?;
             } else {
                 // unlock collection for search and updates
                 drop(write_segments_guard);
                 // After the collection is unlocked - we can remove data as slow as we want.
 
                 // Proxy contains pointer to the `tmp_segment`, so they should be removed first
-                for proxy in proxies {
-                    proxy.drop_data()?;
-                }
+                scheduled_delete.execute_after(&optimized_segment)?;
                 tmp_segment.drop_data()?;
             }
         }
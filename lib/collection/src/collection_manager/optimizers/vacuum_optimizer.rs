@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use ordered_float::OrderedFloat;
@@ -7,10 +8,11 @@ use parking_lot::Mutex;
 use segment::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator,
 };
-use segment::entry::entry_point::SegmentEntry;
+use segment::entry::entry_point::{check_process_stopped, SegmentEntry};
 use segment::index::VectorIndex;
-use segment::types::{HnswConfig, QuantizationConfig, SegmentType};
+use segment::types::{HnswConfig, PayloadKeyType, QuantizationConfig, SegmentType};
 use segment::vector_storage::VectorStorage;
+use serde::{Deserialize, Serialize};
 
 use crate::collection_manager::holders::segment_holder::{
     LockedSegment, LockedSegmentHolder, SegmentId,
@@ -18,7 +20,22 @@ use crate::collection_manager::holders::segment_holder::{
 use crate::collection_manager::optimizers::segment_optimizer::{
     OptimizerThresholds, SegmentOptimizer,
 };
+use crate::common::io_throttle::IoThrottle;
 use crate::config::CollectionParams;
+use crate::operations::types::CollectionResult;
+
+/// Number of points, indexed into a staging payload index under a single lock of the segment
+pub(crate) const STAGED_INDEX_BATCH_SIZE: usize = 10_000;
+
+/// Background builds of the payload field indexes, including their rebuilds and conversions
+/// between the in-memory and the on-disk representation
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct FieldIndexBuildConfig {
+    /// Max speed of reading the payload into the built indexes, in megabytes per second.
+    /// If not set - builds are not throttled
+    #[serde(default)]
+    pub max_io_mb_per_sec: Option<usize>,
+}
 
 /// Optimizer which looks for segments with high amount of soft-deleted points or vectors
 ///
@@ -35,6 +52,7 @@ pub struct VacuumOptimizer {
     collection_params: CollectionParams,
     hnsw_config: HnswConfig,
     quantization_config: Option<QuantizationConfig>,
+    field_index_build: FieldIndexBuildConfig,
     telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
 }
 
@@ -49,6 +67,7 @@ impl VacuumOptimizer {
         collection_params: CollectionParams,
         hnsw_config: HnswConfig,
         quantization_config: Option<QuantizationConfig>,
+        field_index_build: FieldIndexBuildConfig,
     ) -> Self {
         VacuumOptimizer {
             deleted_threshold,
@@ -59,6 +78,7 @@ impl VacuumOptimizer {
             collection_params,
             hnsw_config,
             quantization_config,
+            field_index_build,
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
@@ -73,13 +93,22 @@ impl VacuumOptimizer {
             .iter()
             .filter(|(idx, _segment)| !excluded_ids.contains(idx))
             .flat_map(|(idx, segment)| {
-                // Calcualte littered ratio for segment and named vectors
+                // Calcualte littered ratio for segment, named vectors and payload indexes
                 let littered_ratio_segment = self.littered_ratio_segment(segment);
                 let littered_ratio_vectors = self.littered_vectors_index_ratio(segment);
-                [littered_ratio_segment, littered_ratio_vectors]
+                let littered_ratio_payload = self
+                    .littered_payload_index_fields(segment)
                     .into_iter()
-                    .flatten()
-                    .map(|ratio| (*idx, ratio))
+                    .map(|(_, ratio)| ratio)
+                    .max_by_key(|ratio| OrderedFloat(*ratio));
+                [
+                    littered_ratio_segment,
+                    littered_ratio_vectors,
+                    littered_ratio_payload,
+                ]
+                .into_iter()
+                .flatten()
+                .map(|ratio| (*idx, ratio))
             })
             .max_by_key(|(_, ratio)| OrderedFloat(*ratio))
             .map(|(idx, _)| (idx, segments_read_guard.get(idx).unwrap().clone()))
@@ -111,6 +140,10 @@ impl VacuumOptimizer {
     /// This finds the maximum deletion ratio for a named vector. The ratio is based on the number
     /// of deleted vectors versus the number of indexed vector.s
     ///
+    /// Deleted points, which are well connected in the graph, degrade the search more than their
+    /// number suggests. So the fraction of graph links, pointing to deleted points, is used
+    /// as the ratio, if it is higher.
+    ///
     /// Returns `None` if littered ratio did not reach vacuum thresholds for no named vectors.
     fn littered_vectors_index_ratio(&self, segment: &LockedSegment) -> Option<f64> {
         {
@@ -154,6 +187,9 @@ impl VacuumOptimizer {
                 } else {
                     0.0
                 };
+                let deleted_ratio = vector_index
+                    .sample_deleted_links_ratio()
+                    .map_or(deleted_ratio, |links_ratio| deleted_ratio.max(links_ratio));
 
                 let reached_minimum = deleted_from_index >= self.min_vectors_number;
                 let reached_ratio = deleted_ratio > self.deleted_threshold;
@@ -161,6 +197,57 @@ impl VacuumOptimizer {
             })
             .max_by_key(|ratio| OrderedFloat(*ratio))
     }
+
+    /// Find payload indexes, which contain too many obsolete values
+    ///
+    /// Values removed or overwritten in a payload index are not reclaimed from the storage.
+    /// The ratio is based on the number of obsolete values versus all values ever written
+    /// to the index since it was built.
+    ///
+    /// Indexes, which failed to load because of corrupted storage, are always rebuilt.
+    /// So are the indexes, which rebuild was requested or interrupted.
+    ///
+    /// Returns fields with their littered ratio, for which vacuum thresholds are reached.
+    fn littered_payload_index_fields(&self, segment: &LockedSegment) -> Vec<(PayloadKeyType, f64)> {
+        // We can only work with original segments
+        let real_segment = match segment {
+            LockedSegment::Original(segment) => segment.read(),
+            LockedSegment::Proxy(_) => return vec![],
+        };
+
+        // Never optimize special segments
+        if real_segment.segment_type() == SegmentType::Special {
+            return vec![];
+        }
+
+        let payload_index = real_segment.payload_index.borrow();
+        let corrupted_fields = payload_index
+            .fields_to_rebuild()
+            .iter()
+            .chain(payload_index.staged_fields())
+            .map(|field| (field.clone(), 1.0));
+        payload_index
+            .field_indexes
+            .iter()
+            .filter_map(|(field, indexes)| {
+                let obsolete_values = payload_index.obsolete_values_count(field);
+                let actual_values = indexes
+                    .first()
+                    .map_or(0, |index| index.get_telemetry_data().points_values_count);
+                let written_values = obsolete_values + actual_values;
+                let littered_ratio = if written_values != 0 {
+                    obsolete_values as f64 / written_values as f64
+                } else {
+                    0.0
+                };
+
+                let reached_minimum = obsolete_values >= self.min_vectors_number;
+                let reached_ratio = littered_ratio > self.deleted_threshold;
+                (reached_minimum && reached_ratio).then(|| (field.clone(), littered_ratio))
+            })
+            .chain(corrupted_fields)
+            .collect()
+    }
 }
 
 impl SegmentOptimizer for VacuumOptimizer {
@@ -188,6 +275,10 @@ impl SegmentOptimizer for VacuumOptimizer {
         &self.thresholds_config
     }
 
+    fn name(&self) -> &str {
+        "vacuum"
+    }
+
     fn check_condition(
         &self,
         segments: LockedSegmentHolder,
@@ -199,6 +290,69 @@ impl SegmentOptimizer for VacuumOptimizer {
         }
     }
 
+    /// If only payload indexes of the segment are littered, rebuild just those indexes
+    /// instead of the whole segment.
+    ///
+    /// Indexes are rebuilt into a staging storage, locking the segment for a batch of points
+    /// at a time. Meanwhile, the current indexes keep serving reads and updates are written
+    /// into both. A cancelled rebuild continues from the indexed points the next time.
+    /// Reading of the payload is throttled according to the field index build config.
+    fn optimize_in_place(
+        &self,
+        segments: &LockedSegmentHolder,
+        ids: &[SegmentId],
+        stopped: &AtomicBool,
+    ) -> CollectionResult<Option<bool>> {
+        let segment = match ids {
+            [segment_id] => match segments.read().get(*segment_id) {
+                Some(segment) => segment.clone(),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        // Points or vectors are littered as well, so the whole segment has to be rebuilt anyway
+        if self.littered_ratio_segment(&segment).is_some()
+            || self.littered_vectors_index_ratio(&segment).is_some()
+        {
+            return Ok(None);
+        }
+
+        // A field might be both littered and staged for a rebuild
+        let littered_fields: HashSet<_> = self
+            .littered_payload_index_fields(&segment)
+            .into_iter()
+            .map(|(field, _ratio)| field)
+            .collect();
+        if littered_fields.is_empty() {
+            return Ok(None);
+        }
+
+        let real_segment = match &segment {
+            LockedSegment::Original(segment) => segment,
+            LockedSegment::Proxy(_) => return Ok(None),
+        };
+
+        let mut throttle = IoThrottle::new(self.field_index_build.max_io_mb_per_sec);
+        for field in littered_fields {
+            log::debug!("Rebuilding payload index of field `{field}`");
+            real_segment.write().start_field_index_rebuild(&field)?;
+            loop {
+                check_process_stopped(stopped)?;
+                let batch = real_segment
+                    .write()
+                    .build_staged_field_index(&field, STAGED_INDEX_BATCH_SIZE)?;
+                if batch.is_complete {
+                    break;
+                }
+                // Segment is not locked while waiting
+                throttle.consume(batch.bytes_read);
+            }
+        }
+
+        Ok(Some(true))
+    }
+
     fn get_telemetry_data(&self) -> OperationDurationStatistics {
         self.get_telemetry_counter().lock().get_statistics()
     }
@@ -217,6 +371,7 @@ mod tests {
 
     use itertools::Itertools;
     use parking_lot::RwLock;
+    use segment::common::cpu::CpuPermit;
     use segment::entry::entry_point::SegmentEntry;
     use segment::types::{Distance, PayloadContainer, PayloadSchemaType};
     use serde_json::{json, Value};
@@ -305,14 +460,24 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    datatype: None,
                 }),
                 shard_number: NonZeroU32::new(1).unwrap(),
                 on_disk_payload: false,
+                validate_payload: false,
+                payload_validation_mode: Default::default(),
+                sparse_vectors: None,
+                payload_indexes: Default::default(),
+                payload_cache_fields: Default::default(),
+                read_only: false,
+                auto_index_group_by_fields: false,
+                read_hedge_delay_ms: None,
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
             },
             Default::default(),
             Default::default(),
+            Default::default(),
         );
 
         let suggested_to_optimize =
@@ -325,6 +490,7 @@ mod tests {
             .optimize(
                 locked_holder.clone(),
                 suggested_to_optimize,
+                CpuPermit::dummy(2),
                 &AtomicBool::new(false),
             )
             .unwrap();
@@ -364,6 +530,117 @@ mod tests {
         assert!(!original_segment_path.exists());
     }
 
+    #[test]
+    fn test_vacuum_payload_index() {
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(random_segment(dir.path(), 100, 200, 4));
+
+        let segment = holder.get(segment_id).unwrap().clone();
+
+        segment
+            .get()
+            .write()
+            .create_field_index(101, "number", Some(&PayloadSchemaType::Integer.into()))
+            .unwrap();
+
+        let segment_points = segment.get().read().iter_points().collect_vec();
+
+        // Overwrite indexed values several times, which leaves obsolete values in the index
+        for (i, op_num) in (102..105).enumerate() {
+            for &point_id in &segment_points {
+                segment
+                    .get()
+                    .write()
+                    .set_payload(op_num, point_id, &json!({ "number": i }).into())
+                    .unwrap();
+            }
+        }
+
+        let obsolete_values_count = |segment: &LockedSegment| match segment {
+            LockedSegment::Original(s) => s
+                .read()
+                .payload_index
+                .borrow()
+                .obsolete_values_count("number"),
+            LockedSegment::Proxy(_) => panic!("Not expected"),
+        };
+
+        assert_eq!(obsolete_values_count(&segment), 3 * segment_points.len());
+
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let vacuum_optimizer = VacuumOptimizer::new(
+            0.2,
+            50,
+            OptimizerThresholds {
+                max_segment_size: 1000000,
+                memmap_threshold: 1000000,
+                indexing_threshold: 1000000,
+            },
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            CollectionParams {
+                vectors: VectorsConfig::Single(VectorParams {
+                    size: NonZeroU64::new(4).unwrap(),
+                    distance: Distance::Dot,
+                    hnsw_config: None,
+                    quantization_config: None,
+                    on_disk: None,
+                    datatype: None,
+                }),
+                shard_number: NonZeroU32::new(1).unwrap(),
+                on_disk_payload: false,
+                validate_payload: false,
+                payload_validation_mode: Default::default(),
+                sparse_vectors: None,
+                payload_indexes: Default::default(),
+                payload_cache_fields: Default::default(),
+                read_only: false,
+                auto_index_group_by_fields: false,
+                read_hedge_delay_ms: None,
+                replication_factor: NonZeroU32::new(1).unwrap(),
+                write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            },
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+
+        let suggested_to_optimize =
+            vacuum_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize, vec![segment_id]);
+
+        vacuum_optimizer
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        // Index is rebuilt in place, segment itself is not replaced
+        let after_optimization_segments =
+            locked_holder.read().iter().map(|(x, _)| *x).collect_vec();
+        assert_eq!(after_optimization_segments, vec![segment_id]);
+        assert_eq!(obsolete_values_count(&segment), 0);
+
+        let telemetry = segment.get().read().get_telemetry_data();
+        let field_telemetry = telemetry
+            .payload_field_indices
+            .iter()
+            .find(|index| index.field_name.as_deref() == Some("number"))
+            .unwrap();
+        assert_eq!(field_telemetry.obsolete_values_count, 0);
+        assert_eq!(field_telemetry.points_count, segment_points.len());
+
+        let suggested_to_optimize =
+            vacuum_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert!(suggested_to_optimize.is_empty());
+    }
+
     /// This tests the vacuum optimizer when many vectors get deleted.
     ///
     /// It tests whether:
@@ -398,6 +675,7 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -408,11 +686,20 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
                     },
                 ),
             ])),
             shard_number: 1.try_into().unwrap(),
             on_disk_payload: false,
+            validate_payload: false,
+            payload_validation_mode: Default::default(),
+            sparse_vectors: None,
+            payload_indexes: Default::default(),
+            payload_cache_fields: Default::default(),
+            read_only: false,
+            auto_index_group_by_fields: false,
+            read_hedge_delay_ms: None,
             replication_factor: 1.try_into().unwrap(),
             write_consistency_factor: 1.try_into().unwrap(),
         };
@@ -464,11 +751,17 @@ mod tests {
             collection_params,
             hnsw_config,
             Default::default(),
+            Default::default(),
         );
 
         // Use indexing optimizer to build index for vacuum index test
         let changed = index_optimizer
-            .optimize(locked_holder.clone(), vec![segment_id], &false.into())
+            .optimize(
+                locked_holder.clone(),
+                vec![segment_id],
+                CpuPermit::dummy(2),
+                &false.into(),
+            )
             .unwrap();
         assert!(changed, "optimizer should have rebuilt this segment");
         assert!(
@@ -577,7 +870,12 @@ mod tests {
             vacuum_optimizer.check_condition(locked_holder.clone(), &Default::default());
         assert_eq!(suggested_to_optimize.len(), 1);
         let changed = vacuum_optimizer
-            .optimize(locked_holder.clone(), suggested_to_optimize, &false.into())
+            .optimize(
+                locked_holder.clone(),
+                suggested_to_optimize,
+                CpuPermit::dummy(2),
+                &false.into(),
+            )
             .unwrap();
         assert!(changed, "optimizer should have rebuilt this segment");
 
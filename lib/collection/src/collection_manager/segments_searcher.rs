@@ -1,24 +1,31 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::{TimeZone, Utc};
 use futures::future::try_join_all;
 use ordered_float::Float;
 use parking_lot::RwLock;
+use segment::common::hardware_counter::HardwareCounterCell;
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::VectorElementType;
-use segment::entry::entry_point::OperationError;
+use segment::data_types::vectors::{NamedVectorStruct, VectorElementType};
+use segment::entry::entry_point::{OperationError, OperationResult, SegmentEntry};
 use segment::types::{
     Filter, Indexes, PointIdType, ScoreType, ScoredPoint, SearchParams, SegmentConfig,
-    SeqNumberType, WithPayload, WithPayloadInterface, WithVector,
+    SeqNumberType, VectorSlice, WithPayload, WithPayloadInterface, WithVector, VECTOR_ELEMENT_SIZE,
 };
 use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
 use crate::collection_manager::probabilistic_segment_search_sampling::find_search_sampling_over_point_distribution;
 use crate::collection_manager::search_result_aggregator::BatchResultAggregator;
+use crate::common::request_filter_cache::{request_filter_cache, FilterCache};
+use crate::common::request_hw_counter::request_hw_counter;
 use crate::operations::types::{CollectionResult, Record, SearchRequestBatch};
 
+const BYTES_IN_KB: usize = 1024;
+
 type BatchOffset = usize;
 type SegmentOffset = usize;
 
@@ -143,12 +150,26 @@ impl SegmentsSearcher {
         (result_aggregator, searches_to_rerun)
     }
 
+    #[tracing::instrument(
+        name = "segments_search",
+        skip_all,
+        fields(
+            segments = tracing::field::Empty,
+            candidates = tracing::field::Empty,
+            reruns = tracing::field::Empty,
+        )
+    )]
     pub async fn search(
         segments: &RwLock<SegmentHolder>,
         batch_request: Arc<SearchRequestBatch>,
         runtime_handle: &Handle,
         sampling_enabled: bool,
+        indexing_threshold_kb: usize,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        // Segment searches are spawned as separate tasks, pass the counter of the request to them
+        let hw_counter = request_hw_counter();
+        let filter_cache = request_filter_cache();
+
         // Using { } block to ensure segments variable is dropped in the end of it
         // and is not transferred across the all_searches.await? boundary as it
         // does not impl Send trait
@@ -174,17 +195,23 @@ impl SegmentsSearcher {
             let use_sampling =
                 sampling_enabled && segments.len() > 1 && available_points_segments > 0;
 
+            tracing::Span::current().record("segments", segments.len());
+
             segments
                 .iter()
-                .map(|(_id, segment)| {
+                .map(|(id, segment)| {
                     (
-                        segment.clone(),
+                        (*id, segment.clone()),
                         search_in_segment(
                             segment.clone(),
                             batch_request.clone(),
                             available_points_segments,
                             use_sampling,
-                        ),
+                            indexing_threshold_kb,
+                            hw_counter.clone(),
+                            filter_cache.clone().map(|cache| (*id, cache)),
+                        )
+                        .instrument(tracing::info_span!("segment_search", segment_id = *id)),
                     )
                 })
                 .map(|(segment, f)| (segment, runtime_handle.spawn(f)))
@@ -195,6 +222,12 @@ impl SegmentsSearcher {
         let (all_search_results_per_segment, further_results) =
             Self::execute_searches(searches).await?;
         debug_assert!(all_search_results_per_segment.len() == locked_segments.len());
+        let candidates: usize = all_search_results_per_segment
+            .iter()
+            .flatten()
+            .map(|batch_result| batch_result.len())
+            .sum();
+        tracing::Span::current().record("candidates", candidates);
 
         let (mut result_aggregator, searches_to_rerun) = Self::process_search_result_step1(
             all_search_results_per_segment,
@@ -212,11 +245,12 @@ impl SegmentsSearcher {
             // Ensure consistent order of segment ids
             let searches_to_rerun: Vec<(SegmentOffset, Vec<BatchOffset>)> =
                 searches_to_rerun.into_iter().collect();
+            tracing::Span::current().record("reruns", searches_to_rerun.len());
 
             let secondary_searches: Vec<_> = {
                 let mut res = vec![];
-                for (segment_id, batch_ids) in searches_to_rerun.iter() {
-                    let segment = locked_segments[*segment_id].clone();
+                for (segment_offset, batch_ids) in searches_to_rerun.iter() {
+                    let (segment_id, segment) = locked_segments[*segment_offset].clone();
                     let partial_batch_request = Arc::new(SearchRequestBatch {
                         searches: batch_ids
                             .iter()
@@ -224,7 +258,15 @@ impl SegmentsSearcher {
                             .collect(),
                     });
 
-                    let search = search_in_segment(segment, partial_batch_request, 0, false);
+                    let search = search_in_segment(
+                        segment,
+                        partial_batch_request,
+                        0,
+                        false,
+                        indexing_threshold_kb,
+                        hw_counter.clone(),
+                        filter_cache.clone().map(|cache| (segment_id, cache)),
+                    );
                     res.push(runtime_handle.spawn(search))
                 }
                 res
@@ -252,6 +294,7 @@ impl SegmentsSearcher {
         Ok(top_scores)
     }
 
+    #[tracing::instrument(name = "segments_retrieve", skip_all, fields(points = points.len()))]
     pub async fn retrieve(
         segments: &RwLock<SegmentHolder>,
         points: &[PointIdType],
@@ -261,48 +304,107 @@ impl SegmentsSearcher {
         let mut point_version: HashMap<PointIdType, SeqNumberType> = Default::default();
         let mut point_records: HashMap<PointIdType, Record> = Default::default();
 
-        segments.read().read_points(points, |id, segment| {
-            let version = segment.point_version(id).ok_or_else(|| {
-                OperationError::service_error(format!("No version for point {id}"))
-            })?;
-            // If this point was not found yet or this segment have later version
-            if !point_version.contains_key(&id) || point_version[&id] < version {
-                point_records.insert(
-                    id,
-                    Record {
+        let hw_counter = request_hw_counter();
+        hw_counter.measure(|| {
+            segments.read().read_points(points, |id, segment| {
+                let version = segment.point_version(id).ok_or_else(|| {
+                    OperationError::service_error(format!("No version for point {id}"))
+                })?;
+                // If this point was not found yet or this segment have later version
+                if !point_version.contains_key(&id) || point_version[&id] < version {
+                    point_records.insert(
                         id,
-                        payload: if with_payload.enable {
-                            if let Some(selector) = &with_payload.payload_selector {
-                                Some(selector.process(segment.payload(id)?))
+                        Record {
+                            id,
+                            payload: if with_payload.enable {
+                                if let Some(selector) = &with_payload.payload_selector {
+                                    Some(segment.selected_payload(id, selector)?)
+                                } else {
+                                    Some(segment.payload(id)?)
+                                }
                             } else {
-                                Some(segment.payload(id)?)
-                            }
-                        } else {
-                            None
-                        },
-                        vector: match with_vector {
-                            WithVector::Bool(true) => Some(segment.all_vectors(id)?.into()),
-                            WithVector::Bool(false) => None,
-                            WithVector::Selector(vector_names) => {
-                                let mut selected_vectors = NamedVectors::default();
-                                for vector_name in vector_names {
-                                    if let Some(vector) = segment.vector(vector_name, id)? {
-                                        selected_vectors.insert(vector_name.into(), vector);
+                                None
+                            },
+                            vector: match with_vector {
+                                WithVector::Bool(true) => Some(segment.all_vectors(id)?.into()),
+                                WithVector::Bool(false) => None,
+                                WithVector::Selector(vector_names) => {
+                                    let mut selected_vectors = NamedVectors::default();
+                                    for vector_name in vector_names {
+                                        insert_vector_by_name(
+                                            &**segment,
+                                            vector_name,
+                                            id,
+                                            &mut selected_vectors,
+                                        )?;
                                     }
+                                    Some(selected_vectors.into())
                                 }
-                                Some(selected_vectors.into())
-                            }
+                                WithVector::Exclude { exclude } => {
+                                    let mut selected_vectors = NamedVectors::default();
+                                    let config = segment.config();
+                                    let vector_names = config
+                                        .vector_data
+                                        .keys()
+                                        .chain(config.sparse_vector_data.keys());
+                                    for vector_name in vector_names {
+                                        if exclude.contains(vector_name) {
+                                            continue;
+                                        }
+                                        insert_vector_by_name(
+                                            &**segment,
+                                            vector_name,
+                                            id,
+                                            &mut selected_vectors,
+                                        )?;
+                                    }
+                                    Some(selected_vectors.into())
+                                }
+                                WithVector::Slice { .. } => None,
+                            },
+                            last_modified: segment
+                                .point_timestamp(id)
+                                .and_then(|ms| Utc.timestamp_millis_opt(ms as i64).single()),
+                            order_value: None,
+                            payload_truncation: None,
+                            vector_slice: match with_vector {
+                                WithVector::Slice { name, slice } => segment
+                                    .vector(name, id)?
+                                    .and_then(|vector| VectorSlice::new(name, *slice, &vector)),
+                                _ => None,
+                            },
                         },
-                    },
-                );
-                point_version.insert(id, version);
-            }
-            Ok(true)
+                    );
+                    point_version.insert(id, version);
+                }
+                Ok(true)
+            })
         })?;
         Ok(point_records.into_values().collect())
     }
 }
 
+/// Add the dense or sparse vector of the given name to `vectors`, if the point has it
+fn insert_vector_by_name(
+    segment: &dyn SegmentEntry,
+    vector_name: &str,
+    point_id: PointIdType,
+    vectors: &mut NamedVectors,
+) -> OperationResult<()> {
+    if segment
+        .config()
+        .sparse_vector_data
+        .contains_key(vector_name)
+    {
+        if let Some(vector) = segment.sparse_vector(vector_name, point_id)? {
+            vectors.insert_sparse(vector_name.to_owned(), vector);
+        }
+    } else if let Some(vector) = segment.vector(vector_name, point_id)? {
+        vectors.insert(vector_name.to_owned(), vector);
+    }
+    Ok(())
+}
+
 #[derive(PartialEq, Default, Debug)]
 struct BatchSearchParams<'a> {
     pub vector_name: &'a str,
@@ -311,6 +413,8 @@ struct BatchSearchParams<'a> {
     pub with_vector: WithVector,
     pub top: usize,
     pub params: Option<&'a SearchParams>,
+    /// Sparse and dense queries are never batched together
+    pub is_sparse: bool,
 }
 
 /// Returns suggested search sampling size for a given number of points and required limit.
@@ -347,6 +451,8 @@ fn effective_limit(limit: usize, ef_limit: usize, poisson_sampling: usize) -> us
 /// * `request` - Batch of search requests
 /// * `total_points` - Number of points in all segments combined
 /// * `use_sampling` - If true, try to use probabilistic sampling
+/// * `indexing_threshold_kb` - Size of vectors in a segment, above which it is expected to be indexed
+/// * `filter_cache` - Id of the segment and the cache of the filter bitmasks of the request, if any
 ///
 /// # Returns
 ///
@@ -358,12 +464,15 @@ async fn search_in_segment(
     request: Arc<SearchRequestBatch>,
     total_points: usize,
     use_sampling: bool,
+    indexing_threshold_kb: usize,
+    hw_counter: Arc<HardwareCounterCell>,
+    filter_cache: Option<(SegmentId, Arc<FilterCache>)>,
 ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Vec<bool>)> {
     let batch_size = request.searches.len();
 
     let mut result: Vec<Vec<ScoredPoint>> = Vec::with_capacity(batch_size);
     let mut further_results: Vec<bool> = Vec::with_capacity(batch_size); // if segment have more points to return
-    let mut vectors_batch: Vec<&[VectorElementType]> = vec![];
+    let mut vectors_batch: Vec<&NamedVectorStruct> = vec![];
     let mut prev_params = BatchSearchParams::default();
 
     for search_query in &request.searches {
@@ -379,81 +488,179 @@ async fn search_in_segment(
             with_vector: search_query.with_vector.clone().unwrap_or_default(),
             top: search_query.limit + search_query.offset,
             params: search_query.params.as_ref(),
+            is_sparse: search_query.vector.is_sparse(),
         };
 
         // same params enables batching
         if params == prev_params {
-            vectors_batch.push(search_query.vector.get_vector().as_slice());
+            vectors_batch.push(&search_query.vector);
         } else {
             // different params means different batches
             // execute what has been batched so far
             if !vectors_batch.is_empty() {
-                let locked_segment = segment.get();
-                let read_segment = locked_segment.read();
-                let segment_points = read_segment.available_point_count();
-                let top = if use_sampling {
-                    let ef_limit = prev_params.params.and_then(|p| p.hnsw_ef).or_else(|| {
-                        get_hnsw_ef_construct(read_segment.config(), prev_params.vector_name)
-                    });
-                    sampling_limit(prev_params.top, ef_limit, segment_points, total_points)
-                } else {
-                    prev_params.top
-                };
-
-                let mut res = read_segment.search_batch(
-                    prev_params.vector_name,
-                    &vectors_batch,
-                    &prev_params.with_payload,
-                    &prev_params.with_vector,
-                    prev_params.filter,
-                    top,
-                    prev_params.params,
-                )?;
-                for batch_result in &res {
-                    further_results.push(batch_result.len() == top);
-                }
+                let (mut res, mut further) = hw_counter.measure(|| {
+                    execute_batch_search(
+                        &segment,
+                        &vectors_batch,
+                        &prev_params,
+                        total_points,
+                        use_sampling,
+                        indexing_threshold_kb,
+                        filter_cache
+                            .as_ref()
+                            .map(|(segment_id, cache)| (*segment_id, cache.as_ref())),
+                    )
+                })?;
+                further_results.append(&mut further);
                 result.append(&mut res);
                 // clear current batch
                 vectors_batch.clear();
             }
             // start new batch for current search query
-            vectors_batch.push(search_query.vector.get_vector().as_slice());
+            vectors_batch.push(&search_query.vector);
             prev_params = params;
         }
     }
 
     // run last batch if any
     if !vectors_batch.is_empty() {
-        let locked_segment = segment.get();
-        let read_segment = locked_segment.read();
-        let segment_points = read_segment.available_point_count();
-        let top = if use_sampling {
-            let ef_limit = prev_params
-                .params
-                .and_then(|p| p.hnsw_ef)
-                .or_else(|| get_hnsw_ef_construct(read_segment.config(), prev_params.vector_name));
-            sampling_limit(prev_params.top, ef_limit, segment_points, total_points)
-        } else {
-            prev_params.top
-        };
-        let mut res = read_segment.search_batch(
-            prev_params.vector_name,
-            &vectors_batch,
-            &prev_params.with_payload,
-            &prev_params.with_vector,
-            prev_params.filter,
-            top,
-            prev_params.params,
-        )?;
-        for batch_result in &res {
-            further_results.push(batch_result.len() == top);
-        }
+        let (mut res, mut further) = hw_counter.measure(|| {
+            execute_batch_search(
+                &segment,
+                &vectors_batch,
+                &prev_params,
+                total_points,
+                use_sampling,
+                indexing_threshold_kb,
+                filter_cache
+                    .as_ref()
+                    .map(|(segment_id, cache)| (*segment_id, cache.as_ref())),
+            )
+        })?;
+        further_results.append(&mut further);
         result.append(&mut res);
     }
 
     Ok((result, further_results))
 }
 
+/// Run a single batch of search requests with identical params on the segment
+fn execute_batch_search(
+    segment: &LockedSegment,
+    vectors_batch: &[&NamedVectorStruct],
+    search_params: &BatchSearchParams,
+    total_points: usize,
+    use_sampling: bool,
+    indexing_threshold_kb: usize,
+    filter_cache: Option<(SegmentId, &FilterCache)>,
+) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Vec<bool>)> {
+    let locked_segment = segment.get();
+    let read_segment = locked_segment.read();
+
+    let indexed_only = search_params.params.map_or(false, |p| p.indexed_only);
+    if indexed_only
+        && is_large_unindexed_segment(
+            &*read_segment,
+            search_params.vector_name,
+            indexing_threshold_kb,
+        )
+    {
+        // Scanning a big plain segment is too slow, return nothing from it.
+        // There is nothing to re-run later as well.
+        return Ok((
+            vec![vec![]; vectors_batch.len()],
+            vec![false; vectors_batch.len()],
+        ));
+    }
+
+    let segment_points = read_segment.available_point_count();
+    let top = if use_sampling {
+        let ef_limit = search_params
+            .params
+            .and_then(|p| p.hnsw_ef)
+            .or_else(|| get_hnsw_ef_construct(read_segment.config(), search_params.vector_name));
+        sampling_limit(search_params.top, ef_limit, segment_points, total_points)
+    } else {
+        search_params.top
+    };
+    let res = if search_params.is_sparse {
+        let sparse_vectors: Vec<_> = vectors_batch
+            .iter()
+            .filter_map(|vector| vector.get_sparse_vector())
+            .collect();
+        read_segment.search_sparse_batch(
+            search_params.vector_name,
+            &sparse_vectors,
+            &search_params.with_payload,
+            &search_params.with_vector,
+            search_params.filter,
+            top,
+        )?
+    } else {
+        let dense_vectors: Vec<&[VectorElementType]> = vectors_batch
+            .iter()
+            .filter_map(|vector| vector.get_vector())
+            .map(|vector| vector.as_slice())
+            .collect();
+        // Filter, shared with other searches of the request, is evaluated once per segment
+        let prefiltered =
+            filter_cache
+                .zip(search_params.filter)
+                .and_then(|((segment_id, cache), filter)| {
+                    let (cached_filter, residual) = cache.split(filter);
+                    let bitmask = cache.get_or_build(segment_id, &*read_segment, cached_filter)?;
+                    Some((bitmask, residual))
+                });
+        match prefiltered {
+            Some((bitmask, residual)) => read_segment.search_batch_prefiltered(
+                search_params.vector_name,
+                &dense_vectors,
+                &search_params.with_payload,
+                &search_params.with_vector,
+                &bitmask,
+                residual.as_ref(),
+                top,
+                search_params.params,
+            )?,
+            None => read_segment.search_batch(
+                search_params.vector_name,
+                &dense_vectors,
+                &search_params.with_payload,
+                &search_params.with_vector,
+                search_params.filter,
+                top,
+                search_params.params,
+            )?,
+        }
+    };
+    let further_results = res
+        .iter()
+        .map(|batch_result| batch_result.len() == top)
+        .collect();
+    Ok((res, further_results))
+}
+
+/// Check if the segment has no vector index for the given vector, while being too big
+/// to be scanned cheaply. Such segments are excluded from `indexed_only` searches.
+pub fn is_large_unindexed_segment(
+    segment: &dyn SegmentEntry,
+    vector_name: &str,
+    indexing_threshold_kb: usize,
+) -> bool {
+    let is_plain = segment
+        .config()
+        .vector_data
+        .get(vector_name)
+        .map_or(false, |config| matches!(config.index, Indexes::Plain {}));
+    if !is_plain {
+        return false;
+    }
+    let vector_size = segment.available_point_count()
+        * segment.vector_dim(vector_name).unwrap_or(0)
+        * VECTOR_ELEMENT_SIZE;
+    vector_size >= indexing_threshold_kb.saturating_mul(BYTES_IN_KB)
+}
+
 /// Find the HNSW ef_construct for a named vector
 ///
 /// If the given named vector has no HNSW index, `None` is returned.
@@ -470,11 +677,15 @@ fn get_hnsw_ef_construct(config: SegmentConfig, vector_name: &str) -> Option<usi
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use segment::fixtures::index_fixtures::random_vector;
+    use segment::types::{Condition, FieldCondition, Range};
     use tempfile::Builder;
 
     use super::*;
     use crate::collection_manager::fixtures::{build_test_holder, random_segment};
+    use crate::common::request_filter_cache::with_request_filter_cache;
     use crate::operations::types::SearchRequest;
 
     #[tokio::test]
@@ -494,6 +705,10 @@ mod tests {
             limit: 5,
             score_threshold: None,
             offset: 0,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
         };
 
         let batch_request = SearchRequestBatch {
@@ -505,6 +720,7 @@ mod tests {
             Arc::new(batch_request),
             &Handle::current(),
             true,
+            usize::MAX,
         )
         .await
         .unwrap()
@@ -520,6 +736,80 @@ mod tests {
         assert!(result[1].id == 3.into() || result[1].id == 11.into());
     }
 
+    #[tokio::test]
+    async fn test_segments_search_with_filter_cache() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut holder = SegmentHolder::default();
+        holder.add(random_segment(dir.path(), 10, 500, 4));
+        holder.add(random_segment(dir.path(), 10, 500, 4));
+        let segment_holder = RwLock::new(holder);
+
+        let base_filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            "number",
+            Range {
+                lt: Some(500.0),
+                ..Default::default()
+            },
+        )));
+        let search = |filter: Filter| SearchRequest {
+            vector: random_vector(&mut rand::thread_rng(), 4).into(),
+            with_payload: None,
+            with_vector: None,
+            filter: Some(filter),
+            params: Some(SearchParams {
+                exact: true,
+                ..Default::default()
+            }),
+            limit: 5,
+            score_threshold: None,
+            offset: 0,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        let first_search = search(base_filter.clone());
+        let excluded = Filter::new_must_not(Condition::HasId(
+            HashSet::from([1.into(), 2.into(), 3.into()]).into(),
+        ));
+        let extended_search = search(base_filter.merge(&excluded));
+        let batch_request = Arc::new(SearchRequestBatch {
+            searches: vec![first_search, extended_search],
+        });
+
+        let expected = SegmentsSearcher::search(
+            &segment_holder,
+            batch_request.clone(),
+            &Handle::current(),
+            false,
+            usize::MAX,
+        )
+        .await
+        .unwrap();
+        let cached = with_request_filter_cache(
+            Some(base_filter),
+            SegmentsSearcher::search(
+                &segment_holder,
+                batch_request,
+                &Handle::current(),
+                false,
+                usize::MAX,
+            ),
+        )
+        .await
+        .unwrap();
+
+        let ids = |result: &[Vec<ScoredPoint>]| -> Vec<Vec<PointIdType>> {
+            result
+                .iter()
+                .map(|points| points.iter().map(|point| point.id).collect())
+                .collect()
+        };
+        assert_eq!(ids(&cached), ids(&expected));
+        assert_eq!(cached[0].len(), 5);
+    }
+
     #[tokio::test]
     async fn test_segments_search_sampling() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -546,6 +836,10 @@ mod tests {
                 filter: None,
                 params: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             };
             let req2 = SearchRequest {
                 vector: random_vector(&mut rnd, 4).into(),
@@ -556,6 +850,10 @@ mod tests {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             };
 
             let batch_request = SearchRequestBatch {
@@ -567,6 +865,7 @@ mod tests {
                 Arc::new(batch_request.clone()),
                 &Handle::current(),
                 false,
+                usize::MAX,
             )
             .await
             .unwrap();
@@ -578,6 +877,7 @@ mod tests {
                 Arc::new(batch_request),
                 &Handle::current(),
                 true,
+                usize::MAX,
             )
             .await
             .unwrap();
@@ -595,6 +895,57 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_segments_search_indexed_only() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        // 10 * 4 * 4 bytes - small enough to be scanned without index
+        let small_segment = random_segment(dir.path(), 10, 10, 4);
+        // 4000 * 4 * 4 bytes - expected to be indexed with 10 KB threshold
+        let large_segment = random_segment(dir.path(), 10, 4000, 4);
+
+        let mut holder = SegmentHolder::default();
+        let _sid1 = holder.add(small_segment);
+        let _sid2 = holder.add(large_segment);
+        let segment_holder = RwLock::new(holder);
+
+        let mut rnd = rand::thread_rng();
+        let mut search = |indexed_only: bool| SearchRequest {
+            vector: random_vector(&mut rnd, 4).into(),
+            filter: None,
+            params: Some(SearchParams {
+                indexed_only,
+                ..Default::default()
+            }),
+            limit: 100,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        let batch_request = SearchRequestBatch {
+            searches: vec![search(true), search(false)],
+        };
+
+        let result = SegmentsSearcher::search(
+            &segment_holder,
+            Arc::new(batch_request),
+            &Handle::current(),
+            false,
+            10,
+        )
+        .await
+        .unwrap();
+
+        // Only the small segment is searched with `indexed_only`
+        assert_eq!(result[0].len(), 10);
+        assert_eq!(result[1].len(), 100);
+    }
+
     #[tokio::test]
     async fn test_retrieve() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -132,7 +132,8 @@ pub(crate) fn set_payload(
     Ok(updated_points.len())
 }
 
-fn points_by_filter(
+/// Ids of the points, which match the filter. A point, stored in several segments, is listed once
+pub(crate) fn points_by_filter(
     segments: &SegmentHolder,
     filter: &Filter,
 ) -> CollectionResult<Vec<PointIdType>> {
@@ -142,6 +143,8 @@ fn points_by_filter(
         affected_points.extend_from_slice(points.as_slice());
         Ok(true)
     })?;
+    affected_points.sort_unstable();
+    affected_points.dedup();
     Ok(affected_points)
 }
 
@@ -215,6 +218,8 @@ pub(crate) fn clear_payload_by_filter(
     Ok(updated_points.len())
 }
 
+/// Stage creation of the field index in every segment.
+/// Indexes are built in background afterwards, so that large segments don't block updates.
 pub(crate) fn create_field_index(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
@@ -223,7 +228,7 @@ pub(crate) fn create_field_index(
 ) -> CollectionResult<usize> {
     segments
         .apply_segments(|write_segment| {
-            write_segment.create_field_index(op_num, field_name, field_schema)
+            write_segment.stage_field_index_creation(op_num, field_name, field_schema)
         })
         .map_err(Into::into)
 }
@@ -238,6 +243,29 @@ pub(crate) fn delete_field_index(
         .map_err(Into::into)
 }
 
+/// Stage rebuild of the field index in every segment.
+/// Indexes are built by the optimizers afterwards.
+pub(crate) fn rebuild_field_index(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    field_name: PayloadKeyTypeRef,
+) -> CollectionResult<usize> {
+    segments
+        .apply_segments(|write_segment| write_segment.stage_field_index_rebuild(op_num, field_name))
+        .map_err(Into::into)
+}
+
+/// Stop building the field index in every segment
+pub(crate) fn cancel_field_index_build(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    field_name: PayloadKeyTypeRef,
+) -> CollectionResult<usize> {
+    segments
+        .apply_segments(|write_segment| write_segment.cancel_field_index_build(op_num, field_name))
+        .map_err(Into::into)
+}
+
 ///
 /// Returns
 /// - Ok(true) if the operation was successful and point replaced existing value
@@ -529,6 +557,12 @@ pub(crate) fn process_field_index_operation(
         FieldIndexOperations::DeleteIndex(field_name) => {
             delete_field_index(&segments.read(), op_num, field_name)
         }
+        FieldIndexOperations::RebuildIndex(field_name) => {
+            rebuild_field_index(&segments.read(), op_num, field_name)
+        }
+        FieldIndexOperations::CancelIndexBuild(field_name) => {
+            cancel_field_index_build(&segments.read(), op_num, field_name)
+        }
     }
 }
 
@@ -538,10 +572,7 @@ pub(crate) fn delete_points_by_filter(
     op_num: SeqNumberType,
     filter: &Filter,
 ) -> CollectionResult<usize> {
-    let mut deleted = 0;
-    segments.apply_segments(|s| {
-        deleted += s.delete_filtered(op_num, filter)?;
-        Ok(true)
-    })?;
-    Ok(deleted)
+    let points_to_delete = points_by_filter(segments, filter)?;
+    delete_points(segments, op_num, &points_to_delete)?;
+    Ok(points_to_delete.len())
 }
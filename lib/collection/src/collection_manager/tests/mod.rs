@@ -2,22 +2,38 @@ mod test_search_aggregation;
 
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use itertools::Itertools;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use segment::common::cpu::CpuPermit;
+use segment::common::operation_time_statistics::{
+    OperationDurationStatistics, OperationDurationsAggregator,
+};
 use segment::data_types::vectors::only_default_vector;
 use segment::entry::entry_point::SegmentEntry;
-use segment::types::{PayloadFieldSchema, PayloadKeyType, PointIdType};
+use segment::segment::Segment;
+use segment::types::{HnswConfig, PayloadFieldSchema, PayloadKeyType, QuantizationConfig};
 use tempfile::Builder;
 
-use crate::collection_manager::fixtures::{build_segment_1, build_segment_2, empty_segment};
-use crate::collection_manager::holders::proxy_segment::ProxySegment;
+use crate::collection_manager::fixtures::{
+    build_segment_1, build_segment_2, empty_segment, get_merge_optimizer, random_segment,
+};
+use crate::collection_manager::holders::proxy_segment::{ProxyDeletedPoints, ProxySegment};
 use crate::collection_manager::holders::segment_holder::{
     LockedSegment, LockedSegmentHolder, SegmentHolder, SegmentId,
 };
-use crate::collection_manager::segments_updater::upsert_points;
+use crate::collection_manager::optimizers::merge_optimizer::MergeOptimizer;
+use crate::collection_manager::optimizers::segment_optimizer::{
+    OptimizerThresholds, SegmentOptimizer,
+};
+use crate::collection_manager::segments_updater::{delete_points, upsert_points};
+use crate::config::CollectionParams;
 use crate::operations::point_ops::PointStruct;
+use crate::operations::types::CollectionResult;
 
 fn wrap_proxy(segments: LockedSegmentHolder, sid: SegmentId, path: &Path) -> SegmentId {
     let mut write_segments = segments.write();
@@ -26,7 +42,7 @@ fn wrap_proxy(segments: LockedSegmentHolder, sid: SegmentId, path: &Path) -> Seg
 
     let optimizing_segment = write_segments.get(sid).unwrap().clone();
 
-    let proxy_deleted_points = Arc::new(RwLock::new(HashSet::<PointIdType>::new()));
+    let proxy_deleted_points = Arc::new(RwLock::new(ProxyDeletedPoints::default()));
     let proxy_deleted_indexes = Arc::new(RwLock::new(HashSet::<PayloadKeyType>::new()));
     let proxy_created_indexes = Arc::new(RwLock::new(
         HashMap::<PayloadKeyType, PayloadFieldSchema>::new(),
@@ -177,3 +193,134 @@ fn test_move_points_to_copy_on_write() {
         eprintln!("{idx} -> {external}");
     }
 }
+
+/// Merge optimizer, which takes a while to build the optimized segment
+struct SlowOptimizer {
+    inner: MergeOptimizer,
+    delay: Duration,
+}
+
+impl SegmentOptimizer for SlowOptimizer {
+    fn collection_path(&self) -> &Path {
+        self.inner.collection_path()
+    }
+
+    fn temp_path(&self) -> &Path {
+        self.inner.temp_path()
+    }
+
+    fn collection_params(&self) -> CollectionParams {
+        self.inner.collection_params()
+    }
+
+    fn hnsw_config(&self) -> &HnswConfig {
+        self.inner.hnsw_config()
+    }
+
+    fn quantization_config(&self) -> Option<QuantizationConfig> {
+        self.inner.quantization_config()
+    }
+
+    fn threshold_config(&self) -> &OptimizerThresholds {
+        self.inner.threshold_config()
+    }
+
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn check_condition(
+        &self,
+        segments: LockedSegmentHolder,
+        excluded_ids: &HashSet<SegmentId>,
+    ) -> Vec<SegmentId> {
+        self.inner.check_condition(segments, excluded_ids)
+    }
+
+    fn get_telemetry_data(&self) -> OperationDurationStatistics {
+        self.inner.get_telemetry_data()
+    }
+
+    fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.inner.get_telemetry_counter()
+    }
+
+    fn build_new_segment(
+        &self,
+        optimizing_segments: &[LockedSegment],
+        proxy_deleted_indexes: Arc<RwLock<HashSet<PayloadKeyType>>>,
+        proxy_created_indexes: Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>,
+        permit: CpuPermit,
+        stopped: &AtomicBool,
+    ) -> CollectionResult<Segment> {
+        thread::sleep(self.delay);
+        let segment = self.inner.build_new_segment(
+            optimizing_segments,
+            proxy_deleted_indexes,
+            proxy_created_indexes,
+            permit,
+            stopped,
+        )?;
+        thread::sleep(self.delay);
+        Ok(segment)
+    }
+}
+
+#[test]
+fn test_deletes_during_slow_optimization() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+    let dim = 4;
+
+    let mut holder = SegmentHolder::default();
+    let segment_ids = (0..3)
+        .map(|_| holder.add(random_segment(dir.path(), 100, 200, dim)))
+        .collect_vec();
+    let point_ids = holder
+        .iter()
+        .flat_map(|(_, segment)| segment.get().read().iter_points().collect_vec())
+        .collect_vec();
+    let segments: LockedSegmentHolder = Arc::new(RwLock::new(holder));
+
+    let optimizer = SlowOptimizer {
+        inner: get_merge_optimizer(dir.path(), temp_dir.path(), dim),
+        delay: Duration::from_millis(50),
+    };
+
+    // Delete every other point in small batches, while the optimization is running
+    let to_delete = point_ids.iter().copied().step_by(2).collect_vec();
+    let deleter = {
+        let segments = segments.clone();
+        let to_delete = to_delete.clone();
+        thread::spawn(move || {
+            for (op_num, batch) in (101..).zip(to_delete.chunks(5)) {
+                delete_points(&segments.read(), op_num, batch).unwrap();
+                thread::sleep(Duration::from_millis(2));
+            }
+        })
+    };
+
+    let optimized = optimizer
+        .optimize(
+            segments.clone(),
+            segment_ids,
+            CpuPermit::dummy(1),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+    deleter.join().unwrap();
+    assert!(optimized);
+
+    let holder = segments.read();
+    assert!(holder
+        .iter()
+        .all(|(_, segment)| matches!(segment, LockedSegment::Original(_))));
+
+    let deleted: HashSet<_> = to_delete.into_iter().collect();
+    for point_id in point_ids {
+        let exists = holder
+            .iter()
+            .any(|(_, segment)| segment.get().read().has_point(point_id));
+        assert_eq!(exists, !deleted.contains(&point_id), "point {point_id}");
+    }
+}
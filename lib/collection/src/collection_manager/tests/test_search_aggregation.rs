@@ -9,6 +9,8 @@ fn score_point(id: usize, score: ScoreType, version: SeqNumberType) -> ScoredPoi
         score,
         payload: None,
         vector: None,
+        payload_truncation: None,
+        vector_slice: None,
     }
 }
 
@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+/// Keeps the average speed of IO under the limit, by waiting when it is exceeded
+pub(crate) struct IoThrottle {
+    bytes_per_sec: Option<usize>,
+    started: Instant,
+    bytes_processed: usize,
+}
+
+impl IoThrottle {
+    /// If `max_mb_per_sec` is not set - IO is not throttled
+    pub(crate) fn new(max_mb_per_sec: Option<usize>) -> Self {
+        Self {
+            bytes_per_sec: max_mb_per_sec.map(|mb| mb * 1024 * 1024),
+            started: Instant::now(),
+            bytes_processed: 0,
+        }
+    }
+
+    /// Time to wait, so the data processed in `elapsed` time doesn't exceed the limit
+    fn delay(&self, elapsed: Duration) -> Duration {
+        let Some(bytes_per_sec) = self.bytes_per_sec else {
+            return Duration::ZERO;
+        };
+        let allowed_time =
+            Duration::from_secs_f64(self.bytes_processed as f64 / bytes_per_sec as f64);
+        allowed_time.saturating_sub(elapsed)
+    }
+
+    /// Account the processed bytes. Returns the time to wait before the next IO
+    pub(crate) fn register(&mut self, bytes: usize) -> Duration {
+        self.bytes_processed += bytes;
+        self.delay(self.started.elapsed())
+    }
+
+    /// Account the processed bytes and block the thread, while the limit is exceeded
+    pub(crate) fn consume(&mut self, bytes: usize) {
+        let delay = self.register(bytes);
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_throttle_delay() {
+        let mut throttle = IoThrottle::new(Some(10));
+        throttle.bytes_processed = 20 * 1024 * 1024;
+        assert_eq!(
+            throttle.delay(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(throttle.delay(Duration::from_secs(3)), Duration::ZERO);
+
+        let mut unlimited = IoThrottle::new(None);
+        unlimited.bytes_processed = usize::MAX;
+        assert_eq!(unlimited.delay(Duration::ZERO), Duration::ZERO);
+    }
+}
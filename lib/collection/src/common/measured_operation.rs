@@ -0,0 +1,89 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+
+use crate::shards::shard::ShardId;
+use crate::slow_log::{RequestDetails, SlowLogPhase};
+
+/// Details of the operation, collected while it is served
+#[derive(Debug, Default)]
+pub struct OperationDetails {
+    /// Requests of the outermost operation
+    pub requests: Option<Vec<RequestDetails>>,
+    pub phases: Vec<SlowLogPhase>,
+    pub shards: Vec<ShardId>,
+}
+
+/// State of the measured operation, shared with the code serving it
+#[derive(Debug, Default)]
+pub struct OperationContext {
+    /// Collecting details has a cost, so they are only collected if required by the slow log
+    collect_details: bool,
+    details: Mutex<OperationDetails>,
+}
+
+impl OperationContext {
+    pub fn new(collect_details: bool) -> Self {
+        Self {
+            collect_details,
+            details: Default::default(),
+        }
+    }
+
+    pub fn take_details(&self) -> OperationDetails {
+        std::mem::take(&mut *self.details.lock())
+    }
+}
+
+tokio::task_local! {
+    /// Context of the measured operation, which is served by the current task
+    static OPERATION_CONTEXT: Arc<OperationContext>;
+}
+
+/// Run `future` as a measured operation.
+///
+/// Operations, started by the future, are considered a part of it and are not measured separately.
+/// For example, the internal searches of a grouped search.
+pub async fn measured_operation<F: Future>(context: Arc<OperationContext>, future: F) -> F::Output {
+    OPERATION_CONTEXT.scope(context, future).await
+}
+
+/// Whether the current task is already serving a measured operation
+pub fn is_measured_operation() -> bool {
+    OPERATION_CONTEXT.try_with(|_| ()).is_ok()
+}
+
+fn update_details(update: impl FnOnce(&mut OperationDetails)) {
+    let _ = OPERATION_CONTEXT.try_with(|context| {
+        if context.collect_details {
+            update(&mut context.details.lock());
+        }
+    });
+}
+
+/// Capture parameters of the requests of the operation.
+///
+/// Only the first call is taken into account, so inner operations don't override the requests
+/// of the outer one.
+pub fn record_requests(requests: impl FnOnce() -> Vec<RequestDetails>) {
+    update_details(|details| {
+        if details.requests.is_none() {
+            details.requests = Some(requests());
+        }
+    });
+}
+
+pub fn record_shards(shards: impl IntoIterator<Item = ShardId>) {
+    update_details(|details| details.shards.extend(shards));
+}
+
+/// Run `future` as a named phase of the current operation and record its duration
+pub async fn measure_phase<F: Future>(name: &str, future: F) -> F::Output {
+    let start = Instant::now();
+    let output = future.await;
+    let duration = start.elapsed();
+    update_details(|details| details.phases.push(SlowLogPhase::new(name, duration)));
+    output
+}
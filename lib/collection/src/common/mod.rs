@@ -1,3 +1,14 @@
+pub mod io_throttle;
 pub mod is_ready;
+pub mod measured_operation;
+pub mod partial_results;
+pub mod payload_size_limit;
+pub mod point_scope;
+pub mod request_filter_cache;
+pub mod request_hw_counter;
+pub mod request_warnings;
+pub mod shard_trace;
 pub mod stoppable_task;
 pub mod stoppable_task_async;
+pub mod trace_context;
+pub mod update_context;
@@ -0,0 +1,177 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use api::grpc::models::{FailedShard, PartialResults};
+use futures::future::{join_all, try_join_all};
+use parking_lot::Mutex;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::replica_set::{ReplicaState, ShardReplicaSet};
+
+/// Shards, which failed to serve the request with allowed partial results
+#[derive(Debug, Default)]
+pub struct PartialResultsCell {
+    failed_shards: Mutex<Vec<FailedShard>>,
+}
+
+impl PartialResultsCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shard is only reported once, even if it failed in several internal requests
+    fn record(&self, failed_shard: FailedShard) {
+        let mut failed_shards = self.failed_shards.lock();
+        if failed_shards
+            .iter()
+            .all(|shard| shard.shard_id != failed_shard.shard_id)
+        {
+            failed_shards.push(failed_shard);
+        }
+    }
+
+    /// Failed shards of the request, `None` if the result is complete
+    pub fn report(&self) -> Option<PartialResults> {
+        let mut failed_shards = self.failed_shards.lock().clone();
+        if failed_shards.is_empty() {
+            return None;
+        }
+        failed_shards.sort_by_key(|shard| shard.shard_id);
+        Some(PartialResults { failed_shards })
+    }
+}
+
+tokio::task_local! {
+    /// Failed shards of the request, which is served by the current task
+    static REQUEST_PARTIAL_RESULTS: Arc<PartialResultsCell>;
+}
+
+/// Serve a request by running `future`, tolerating failures of some of the shards.
+///
+/// Failures of all reads, made by the future, are recorded into `cell`. For example, the failures
+/// of the internal searches of a grouped search.
+pub async fn with_partial_results<F: Future>(
+    cell: Arc<PartialResultsCell>,
+    future: F,
+) -> F::Output {
+    REQUEST_PARTIAL_RESULTS.scope(cell, future).await
+}
+
+/// Whether the request, served by the current task, allows partial results
+pub fn partial_results_allowed() -> bool {
+    REQUEST_PARTIAL_RESULTS.try_with(|_| ()).is_ok()
+}
+
+/// Failures, which are tolerated by partial results. Same as the failures of a replica,
+/// after which the read is retried on another one. Invalid requests fail as a whole.
+fn is_shard_failure(err: &CollectionError) -> bool {
+    matches!(
+        err,
+        CollectionError::ServiceError { .. } | CollectionError::Cancelled { .. }
+    )
+}
+
+/// Peer of the failed shard. Only known if the shard has a single active replica,
+/// otherwise all of the replicas were tried.
+fn failed_peer(shard: &ShardReplicaSet) -> Option<u64> {
+    let mut active_peers = shard
+        .peers()
+        .into_iter()
+        .filter(|(_, state)| *state == ReplicaState::Active)
+        .map(|(peer_id, _)| peer_id);
+    match (active_peers.next(), active_peers.next()) {
+        (Some(peer_id), None) => Some(peer_id),
+        _ => None,
+    }
+}
+
+/// Await the reads of all `shards`, one future per shard in the same order.
+///
+/// If the request allows partial results, failed shards are recorded and skipped, as long as at
+/// least one shard responded. Otherwise the first error is returned.
+pub(crate) async fn join_shard_reads<T, Fut>(
+    shards: &[&ShardReplicaSet],
+    reads: impl IntoIterator<Item = Fut>,
+) -> CollectionResult<Vec<T>>
+where
+    Fut: Future<Output = CollectionResult<T>>,
+{
+    if !partial_results_allowed() {
+        return try_join_all(reads).await;
+    }
+
+    let results = join_all(reads).await;
+    if let Some(err) = results
+        .iter()
+        .filter_map(|result| result.as_ref().err())
+        .find(|err| !is_shard_failure(err))
+    {
+        return Err(err.clone());
+    }
+
+    let mut responses = Vec::with_capacity(results.len());
+    let mut failures = Vec::new();
+    for (shard, result) in shards.iter().zip(results) {
+        match result {
+            Ok(response) => responses.push(response),
+            Err(err) => failures.push((shard, err)),
+        }
+    }
+
+    if responses.is_empty() {
+        if let Some((_, err)) = failures.into_iter().next() {
+            return Err(err);
+        }
+        return Ok(responses);
+    }
+
+    for (shard, err) in failures {
+        log::debug!(
+            "Shard {} failed, returning partial results: {err}",
+            shard.shard_id
+        );
+        let failed_shard = FailedShard {
+            shard_id: shard.shard_id,
+            peer_id: failed_peer(shard),
+            error: err.to_string(),
+        };
+        let _ = REQUEST_PARTIAL_RESULTS.try_with(|cell| cell.record(failed_shard));
+    }
+    Ok(responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed_shard(shard_id: u32, error: &str) -> FailedShard {
+        FailedShard {
+            shard_id,
+            peer_id: None,
+            error: error.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_partial_results_cell() {
+        assert!(!partial_results_allowed());
+
+        let cell = Arc::new(PartialResultsCell::new());
+        assert!(cell.report().is_none());
+
+        with_partial_results(cell.clone(), async {
+            assert!(partial_results_allowed());
+        })
+        .await;
+
+        cell.record(failed_shard(3, "first"));
+        cell.record(failed_shard(1, "timeout"));
+        cell.record(failed_shard(3, "second"));
+
+        let report = cell.report().unwrap();
+        assert_eq!(
+            report.failed_shards,
+            vec![failed_shard(1, "timeout"), failed_shard(3, "first")]
+        );
+    }
+}
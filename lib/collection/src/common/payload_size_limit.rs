@@ -0,0 +1,43 @@
+use segment::types::{Payload, PayloadTruncation, ScoredPoint};
+
+use crate::operations::types::Record;
+
+/// Truncate the payloads of the found points to `max_bytes` of serialized JSON each.
+///
+/// Truncation is applied where the request enters the cluster, after the results of the shards
+/// are merged. Points, which are already truncated, e.g. by the defaults of a lookup collection,
+/// keep the keys, dropped before.
+pub(crate) fn truncate_scored_payloads(points: &mut [ScoredPoint], max_bytes: Option<usize>) {
+    let Some(max_bytes) = max_bytes else {
+        return;
+    };
+    for point in points {
+        truncate_payload(&mut point.payload, &mut point.payload_truncation, max_bytes);
+    }
+}
+
+/// Truncate the payloads of the retrieved points to `max_bytes` of serialized JSON each
+pub(crate) fn truncate_record_payloads(points: &mut [Record], max_bytes: Option<usize>) {
+    let Some(max_bytes) = max_bytes else {
+        return;
+    };
+    for point in points {
+        truncate_payload(&mut point.payload, &mut point.payload_truncation, max_bytes);
+    }
+}
+
+fn truncate_payload(
+    payload: &mut Option<Payload>,
+    truncation: &mut Option<PayloadTruncation>,
+    max_bytes: usize,
+) {
+    let Some(new_truncation) = PayloadTruncation::truncate(payload, max_bytes) else {
+        return;
+    };
+    match truncation {
+        Some(truncation) => truncation
+            .dropped_payload_keys
+            .extend(new_truncation.dropped_payload_keys),
+        None => *truncation = Some(new_truncation),
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use segment::index::field_index::FieldIndex;
+use segment::payload_storage::query_checker::check_payload;
+use segment::types::{Condition, Filter, Payload, PayloadKeyType};
+
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::CollectionUpdateOperations;
+
+tokio::task_local! {
+    /// Points, which the request served by the current task has access to
+    static POINT_SCOPE: Arc<Filter>;
+}
+
+/// Serve a request by running `future`, limited to the points, matching the `scope` filter.
+///
+/// The scope is resolved by the API layer from the credentials of the client.
+/// `None` gives access to all points.
+/// Tasks, spawned to serve the request, should be run within [`point_scope`] of the request.
+pub async fn with_point_scope<F: Future>(scope: Option<Arc<Filter>>, future: F) -> F::Output {
+    match scope {
+        None => future.await,
+        Some(scope) => POINT_SCOPE.scope(scope, future).await,
+    }
+}
+
+/// Points, which the request served by the current task has access to. `None` - all points
+pub fn point_scope() -> Option<Arc<Filter>> {
+    POINT_SCOPE.try_with(Arc::clone).ok()
+}
+
+/// Restrict the filter of the request to the points of the scope.
+///
+/// The scope is nested into the `must` clause, so its own `should` and `must_not` clauses keep
+/// their meaning, as well as the clauses of the request.
+pub fn restrict_to_scope(filter: Option<Filter>, scope: &Filter) -> Filter {
+    let scope = Filter::new_must(Condition::Filter(scope.clone()));
+    match filter {
+        None => scope,
+        Some(filter) => filter.merge(&scope),
+    }
+}
+
+/// Whether the point with the payload belongs to the scope
+pub fn is_payload_in_scope(payload: &Payload, scope: &Filter) -> bool {
+    let no_indexes: HashMap<PayloadKeyType, Vec<FieldIndex>> = HashMap::new();
+    check_payload(Box::new(|| payload.into()), None, scope, 0, &no_indexes)
+}
+
+pub(crate) fn out_of_scope_error(description: &str) -> CollectionError {
+    CollectionError::Forbidden {
+        description: format!("{description} out of the access scope of the request"),
+    }
+}
+
+/// First segment of the payload key path, e.g. `country` for `country.cities[].name`
+fn root_key(key: &str) -> &str {
+    let end = key.find(['.', '[']).unwrap_or(key.len());
+    &key[..end]
+}
+
+/// Root payload keys, the conditions of the filter depend on
+fn scope_root_keys(filter: &Filter) -> Vec<&str> {
+    let mut keys = Vec::new();
+    for condition in filter.iter_conditions() {
+        match condition {
+            Condition::Field(field) => keys.push(root_key(&field.key)),
+            Condition::IsEmpty(is_empty) => keys.push(root_key(&is_empty.is_empty.key)),
+            Condition::IsNull(is_null) => keys.push(root_key(&is_null.is_null.key)),
+            Condition::Nested(nested) => keys.push(root_key(&nested.nested.key)),
+            Condition::Filter(filter) => keys.extend(scope_root_keys(filter)),
+            Condition::HasId(_) | Condition::ModifiedSince(_) => {}
+        }
+    }
+    keys
+}
+
+/// Whether changing the payload keys might move points in or out of the scope
+fn touches_scope<'a>(keys: impl IntoIterator<Item = &'a String>, scope: &Filter) -> bool {
+    let scope_keys = scope_root_keys(scope);
+    keys.into_iter()
+        .any(|key| scope_keys.contains(&root_key(key)))
+}
+
+/// Reject the payload changes of the operation, which would move points out of the scope.
+///
+/// New payload of the points must belong to the scope. Payload, which is set partially, is only
+/// checked if it changes the fields of the scope, and then it must belong to the scope by itself.
+pub(crate) fn check_operation_payload(
+    operation: &CollectionUpdateOperations,
+    scope: &Filter,
+) -> CollectionResult<()> {
+    let check = |payload: Option<&Payload>, description: &str| {
+        let empty_payload = Payload::default();
+        if is_payload_in_scope(payload.unwrap_or(&empty_payload), scope) {
+            Ok(())
+        } else {
+            Err(out_of_scope_error(description))
+        }
+    };
+
+    match operation {
+        CollectionUpdateOperations::PointOperation(operation) => match operation {
+            PointOperations::UpsertPoints(PointInsertOperations::PointsBatch(batch)) => {
+                let payloads = batch.payloads.as_deref().unwrap_or_default();
+                for (position, id) in batch.ids.iter().enumerate() {
+                    let payload = payloads.get(position).and_then(Option::as_ref);
+                    check(payload, &format!("Payload of point {id} is"))?;
+                }
+                Ok(())
+            }
+            PointOperations::UpsertPoints(PointInsertOperations::PointsList(points)) => {
+                for point in points {
+                    check(
+                        point.payload.as_ref(),
+                        &format!("Payload of point {} is", point.id),
+                    )?;
+                }
+                Ok(())
+            }
+            PointOperations::SyncPoints(_) => Err(out_of_scope_error("Points sync is")),
+            PointOperations::DeletePoints { .. } | PointOperations::DeletePointsByFilter(_) => {
+                Ok(())
+            }
+        },
+        CollectionUpdateOperations::PayloadOperation(operation) => match operation {
+            PayloadOps::OverwritePayload(set_payload) => {
+                check(Some(&set_payload.payload), "Payload is")
+            }
+            PayloadOps::SetPayload(set_payload) => {
+                if touches_scope(set_payload.payload.0.keys(), scope) {
+                    check(Some(&set_payload.payload), "Payload is")
+                } else {
+                    Ok(())
+                }
+            }
+            PayloadOps::DeletePayload(delete_payload) => {
+                if touches_scope(&delete_payload.keys, scope) {
+                    Err(out_of_scope_error("Payload without the deleted keys is"))
+                } else {
+                    Ok(())
+                }
+            }
+            PayloadOps::ClearPayload { .. } | PayloadOps::ClearPayloadByFilter(_) => {
+                check(None, "Empty payload is")
+            }
+        },
+        CollectionUpdateOperations::VectorOperation(_)
+        | CollectionUpdateOperations::FieldIndexOperation(_) => Ok(()),
+    }
+}
+
+/// Restrict the points, which the operation selects by a filter, to the points of the scope
+pub(crate) fn restrict_operation_filter(
+    operation: &mut CollectionUpdateOperations,
+    scope: &Filter,
+) {
+    let restrict = |filter: &mut Filter| *filter = restrict_to_scope(Some(filter.clone()), scope);
+    match operation {
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+            filter,
+        ))
+        | CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectorsByFilter(
+            filter,
+            _,
+        ))
+        | CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(filter)) => {
+            restrict(filter)
+        }
+        CollectionUpdateOperations::PayloadOperation(
+            PayloadOps::SetPayload(set_payload) | PayloadOps::OverwritePayload(set_payload),
+        ) => set_payload.filter.iter_mut().for_each(restrict),
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(delete_payload)) => {
+            delete_payload.filter.iter_mut().for_each(restrict)
+        }
+        CollectionUpdateOperations::PointOperation(_)
+        | CollectionUpdateOperations::VectorOperation(_)
+        | CollectionUpdateOperations::PayloadOperation(_)
+        | CollectionUpdateOperations::FieldIndexOperation(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::payload_ops::{DeletePayload, SetPayload};
+    use crate::operations::point_ops::PointStruct;
+
+    fn tenant_scope(tenant: &str) -> Filter {
+        serde_json::from_value(json!({
+            "must": [{"key": "tenant_id", "match": {"value": tenant}}]
+        }))
+        .unwrap()
+    }
+
+    fn payload(value: serde_json::Value) -> Payload {
+        Payload::from(value)
+    }
+
+    #[tokio::test]
+    async fn test_point_scope_context() {
+        assert!(point_scope().is_none());
+        let scope =
+            with_point_scope(Some(Arc::new(tenant_scope("a"))), async { point_scope() }).await;
+        assert_eq!(scope.as_deref(), Some(&tenant_scope("a")));
+        let scope = with_point_scope(None, async { point_scope() }).await;
+        assert!(scope.is_none());
+    }
+
+    #[test]
+    fn test_restrict_to_scope() {
+        let scope = tenant_scope("a");
+        let request: Filter = serde_json::from_value(json!({
+            "should": [{"key": "city", "match": {"value": "Berlin"}}],
+            "must_not": [{"key": "city", "match": {"value": "London"}}]
+        }))
+        .unwrap();
+
+        let restricted = restrict_to_scope(Some(request.clone()), &scope);
+        assert_eq!(restricted.should, request.should);
+        assert_eq!(restricted.must_not, request.must_not);
+        assert_eq!(
+            restricted.must,
+            Some(vec![Condition::Filter(scope.clone())])
+        );
+
+        let restricted = restrict_to_scope(None, &scope);
+        assert_eq!(restricted, Filter::new_must(Condition::Filter(scope)));
+    }
+
+    #[test]
+    fn test_upsert_out_of_scope() {
+        let scope = tenant_scope("a");
+        let upsert = |payloads: Vec<Option<Payload>>| {
+            let points: Vec<_> = payloads
+                .into_iter()
+                .enumerate()
+                .map(|(id, payload)| PointStruct {
+                    id: (id as u64).into(),
+                    vector: vec![1.0, 0.0].into(),
+                    payload,
+                })
+                .collect();
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(points.into()))
+        };
+
+        let in_scope = upsert(vec![Some(payload(
+            json!({"tenant_id": "a", "city": "Berlin"}),
+        ))]);
+        assert!(check_operation_payload(&in_scope, &scope).is_ok());
+
+        let other_tenant = upsert(vec![
+            Some(payload(json!({"tenant_id": "a"}))),
+            Some(payload(json!({"tenant_id": "b"}))),
+        ]);
+        let error = check_operation_payload(&other_tenant, &scope).unwrap_err();
+        assert!(matches!(error, CollectionError::Forbidden { .. }));
+        // The scope itself is not revealed
+        assert!(!error.to_string().contains("tenant_id"));
+
+        let without_payload = upsert(vec![None]);
+        assert!(check_operation_payload(&without_payload, &scope).is_err());
+    }
+
+    #[test]
+    fn test_payload_update_out_of_scope() {
+        let scope = tenant_scope("a");
+        let set_payload = |value: serde_json::Value| SetPayload {
+            payload: payload(value),
+            points: Some(vec![1.into()]),
+            filter: None,
+        };
+
+        // Fields, which the scope doesn't depend on, may be changed freely
+        let operation = CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(
+            set_payload(json!({"city": "Berlin"})),
+        ));
+        assert!(check_operation_payload(&operation, &scope).is_ok());
+
+        let operation = CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(
+            set_payload(json!({"tenant_id": "b"})),
+        ));
+        assert!(check_operation_payload(&operation, &scope).is_err());
+
+        let operation = CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(
+            set_payload(json!({"city": "Berlin"})),
+        ));
+        assert!(check_operation_payload(&operation, &scope).is_err());
+
+        let delete_payload = |keys: Vec<&str>| {
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(DeletePayload {
+                keys: keys.into_iter().map(str::to_string).collect(),
+                points: Some(vec![1.into()]),
+                filter: None,
+            }))
+        };
+        assert!(check_operation_payload(&delete_payload(vec!["city"]), &scope).is_ok());
+        assert!(check_operation_payload(&delete_payload(vec!["tenant_id"]), &scope).is_err());
+
+        let operation = CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayload {
+            points: vec![1.into()],
+        });
+        assert!(check_operation_payload(&operation, &scope).is_err());
+    }
+
+    #[test]
+    fn test_restrict_operation_filter() {
+        let scope = tenant_scope("a");
+        let filter: Filter = serde_json::from_value(json!({
+            "must": [{"key": "city", "match": {"value": "Berlin"}}]
+        }))
+        .unwrap();
+
+        let mut operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::DeletePointsByFilter(filter.clone()),
+        );
+        restrict_operation_filter(&mut operation, &scope);
+        let CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+            restricted,
+        )) = operation
+        else {
+            panic!("operation must not change its type");
+        };
+        assert_eq!(restricted, restrict_to_scope(Some(filter), &scope));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use segment::entry::entry_point::SegmentEntry;
+use segment::index::filter_bitmask::FilterBitmask;
+use segment::types::{Condition, Filter, SeqNumberType};
+
+use crate::collection_manager::holders::segment_holder::SegmentId;
+use crate::operations::types::SearchRequest;
+
+/// Max total size of the bitmasks, cached for a single request
+pub const DEFAULT_FILTER_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Bitmasks of the points, matching the filters of the searches of a single request.
+///
+/// Searches of a batch or of a grouped search often share the same filter. It is evaluated once
+/// per segment, and the next searches in this segment only check the points of its bitmask.
+pub struct FilterCache {
+    /// Filter, shared by all of the searches of the request, which extend it with own conditions.
+    /// For example, the user filter of a grouped search, extended with the exclusions of the
+    /// groups, found so far. Only this part of the filters is cached then.
+    base_filter: Option<Filter>,
+    max_bytes: usize,
+    bitmasks: Mutex<HashMap<(SegmentId, u64), CachedBitmask>>,
+}
+
+struct CachedBitmask {
+    filter: Filter,
+    /// Version of the segment, the bitmask is built at
+    version: SeqNumberType,
+    bitmask: Arc<FilterBitmask>,
+}
+
+impl FilterCache {
+    pub fn new(base_filter: Option<Filter>, max_bytes: usize) -> Self {
+        Self {
+            base_filter,
+            max_bytes,
+            bitmasks: Mutex::default(),
+        }
+    }
+
+    /// Split the filter of a search into the part to cache, and the remaining conditions
+    pub fn split<'a>(&'a self, filter: &'a Filter) -> (&'a Filter, Option<Filter>) {
+        match &self.base_filter {
+            Some(base_filter) => match residual_filter(base_filter, filter) {
+                Some(residual)
+                    if residual.min_should.is_none()
+                        && residual.must.is_none()
+                        && residual.must_not.is_none() =>
+                {
+                    (base_filter, None)
+                }
+                Some(residual) => (base_filter, Some(residual)),
+                None => (filter, None),
+            },
+            None => (filter, None),
+        }
+    }
+
+    /// Bitmask of the points of the segment, matching the filter.
+    /// It is built on the first request, and again if the segment is changed since then.
+    ///
+    /// `None` if the segment doesn't support bitmasks.
+    pub fn get_or_build(
+        &self,
+        segment_id: SegmentId,
+        segment: &dyn SegmentEntry,
+        filter: &Filter,
+    ) -> Option<Arc<FilterBitmask>> {
+        let key = (segment_id, filter_hash(filter));
+        let version = segment.version();
+        if let Some(cached) = self.bitmasks.lock().get(&key) {
+            if cached.version == version && &cached.filter == filter {
+                return Some(cached.bitmask.clone());
+            }
+        }
+
+        // Build without the lock, other segments are searched concurrently
+        let bitmask = Arc::new(segment.filter_bitmask(filter)?);
+
+        let mut bitmasks = self.bitmasks.lock();
+        bitmasks.insert(
+            key,
+            CachedBitmask {
+                filter: filter.clone(),
+                version,
+                bitmask: bitmask.clone(),
+            },
+        );
+        evict_largest(&mut bitmasks, self.max_bytes);
+        Some(bitmask)
+    }
+}
+
+fn filter_hash(filter: &Filter) -> u64 {
+    // Filters contain floats, so they are hashed in serialized form
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(filter)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Conditions of the `filter`, which are added to the `base` one with [`Filter::merge`],
+/// possibly along with other filters. `None` if the `filter` is not an extension of the `base`.
+fn residual_filter(base: &Filter, filter: &Filter) -> Option<Filter> {
+    fn residual(
+        base: &Option<Vec<Condition>>,
+        conditions: &Option<Vec<Condition>>,
+    ) -> Option<Option<Vec<Condition>>> {
+        let mut rest = conditions.clone().unwrap_or_default();
+        for condition in base.iter().flatten() {
+            let position = rest.iter().position(|other| other == condition)?;
+            rest.remove(position);
+        }
+        Some((!rest.is_empty()).then_some(rest))
+    }
+
+    // Additional `should` conditions would relax the base filter
+    if base.should != filter.should {
+        return None;
+    }
+    let min_should = match (&base.min_should, &filter.min_should) {
+        (None, min_should) => min_should.clone(),
+        (Some(base), Some(min_should)) if base == min_should => None,
+        (Some(_), _) => return None,
+    };
+    Some(Filter {
+        should: None,
+        min_should,
+        must: residual(&base.must, &filter.must)?,
+        must_not: residual(&base.must_not, &filter.must_not)?,
+    })
+}
+
+/// Drop the largest bitmasks, until the rest fit into `max_bytes`
+fn evict_largest(bitmasks: &mut HashMap<(SegmentId, u64), CachedBitmask>, max_bytes: usize) {
+    let mut total_bytes: usize = bitmasks
+        .values()
+        .map(|cached| cached.bitmask.size_bytes())
+        .sum();
+    while total_bytes > max_bytes {
+        let Some((&key, cached)) = bitmasks
+            .iter()
+            .max_by_key(|(_, cached)| cached.bitmask.size_bytes())
+        else {
+            break;
+        };
+        total_bytes -= cached.bitmask.size_bytes();
+        bitmasks.remove(&key);
+    }
+}
+
+tokio::task_local! {
+    /// Filter cache of the request, which is served by the current task
+    static REQUEST_FILTER_CACHE: Arc<FilterCache>;
+}
+
+/// Serve a request by running `future`, sharing the filter bitmasks between all of its searches.
+/// Searches, which extend the `base_filter` with own conditions, share its bitmask.
+///
+/// If the current task already has a cache, e.g. for the internal requests of a grouped search,
+/// it is kept.
+pub async fn with_request_filter_cache<F: Future>(
+    base_filter: Option<Filter>,
+    future: F,
+) -> F::Output {
+    if request_filter_cache().is_some() {
+        return future.await;
+    }
+    let cache = Arc::new(FilterCache::new(
+        base_filter,
+        DEFAULT_FILTER_CACHE_MAX_BYTES,
+    ));
+    REQUEST_FILTER_CACHE.scope(cache, future).await
+}
+
+/// Filter cache of the request, which is served by the current task.
+/// `None` outside of [`with_request_filter_cache`], filters are not cached then.
+pub fn request_filter_cache() -> Option<Arc<FilterCache>> {
+    REQUEST_FILTER_CACHE.try_with(|cache| cache.clone()).ok()
+}
+
+/// Whether the same filter is evaluated by several of the segment batches of the searches.
+///
+/// Consecutive searches with the same parameters are executed in a segment as a single batch,
+/// which evaluates the filter once anyway.
+pub fn is_filter_reused(searches: &[SearchRequest]) -> bool {
+    let is_same_batch = |prev: &SearchRequest, search: &SearchRequest| {
+        prev.vector.get_name() == search.vector.get_name()
+            && prev.filter == search.filter
+            && prev.params == search.params
+            && prev.limit + prev.offset == search.limit + search.offset
+            && prev.with_payload == search.with_payload
+            && prev.with_vector == search.with_vector
+    };
+
+    let mut batch_filters: Vec<&Filter> = Vec::new();
+    let mut prev: Option<&SearchRequest> = None;
+    for search in searches {
+        if let Some(filter) = &search.filter {
+            if !prev.map_or(false, |prev| is_same_batch(prev, search)) {
+                if batch_filters.contains(&filter) {
+                    return true;
+                }
+                batch_filters.push(filter);
+            }
+        }
+        prev = Some(search);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use segment::data_types::vectors::NamedVector;
+    use segment::types::{FieldCondition, HasIdCondition};
+
+    use super::*;
+
+    fn condition(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(key, key.to_string().into()))
+    }
+
+    #[test]
+    fn test_split_filter() {
+        let base = Filter::new_must(condition("city"));
+        let cache = FilterCache::new(Some(base.clone()), DEFAULT_FILTER_CACHE_MAX_BYTES);
+
+        let excluded_ids = HashSet::from([1u64.into()]);
+        let exclusions = Filter::new_must_not(Condition::HasId(HasIdCondition::from(excluded_ids)));
+        let extended = base.merge(&exclusions);
+        let (cached, residual) = cache.split(&extended);
+        assert_eq!(cached, &base);
+        assert_eq!(residual, Some(exclusions));
+
+        let (cached, residual) = cache.split(&base);
+        assert_eq!(cached, &base);
+        assert_eq!(residual, None);
+
+        // Order of the merged filters doesn't matter
+        let other = Filter::new_must(condition("country"));
+        let (cached, residual) = cache.split(&other.merge(&base));
+        assert_eq!(cached, &base);
+        assert_eq!(residual, Some(other.clone()));
+
+        // Another filter is cached as a whole
+        let (cached, residual) = cache.split(&other);
+        assert_eq!(cached, &other);
+        assert_eq!(residual, None);
+
+        let relaxed = base.merge(&Filter::new_should(condition("country")));
+        assert_eq!(cache.split(&relaxed).1, None);
+    }
+
+    #[test]
+    fn test_is_filter_reused() {
+        let search = |vector_name: &str, filter: Option<Filter>| SearchRequest {
+            vector: NamedVector {
+                name: vector_name.to_string(),
+                vector: vec![1.0, 0.0],
+            }
+            .into(),
+            filter,
+            params: None,
+            limit: 10,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        let filter = Some(Filter::new_must(condition("city")));
+
+        // Executed in a single batch
+        let searches = [search("a", filter.clone()), search("a", filter.clone())];
+        assert!(!is_filter_reused(&searches));
+
+        let searches = [search("a", filter.clone()), search("b", filter.clone())];
+        assert!(is_filter_reused(&searches));
+
+        let searches = [
+            search("a", filter.clone()),
+            search("a", None),
+            search("a", filter),
+        ];
+        assert!(is_filter_reused(&searches));
+    }
+}
@@ -0,0 +1,28 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use segment::common::hardware_counter::HardwareCounterCell;
+
+tokio::task_local! {
+    /// Counter of the request, which is served by the current task
+    static REQUEST_HW_COUNTER: Arc<HardwareCounterCell>;
+}
+
+/// Serve a request by running `future`, accounting the work of its searches into `counter`.
+///
+/// All searches, made by the future, are accounted. For example, the internal requests of a grouped search.
+pub async fn with_request_hw_counter<F: Future>(
+    counter: Arc<HardwareCounterCell>,
+    future: F,
+) -> F::Output {
+    REQUEST_HW_COUNTER.scope(counter, future).await
+}
+
+/// Counter of the request, which is served by the current task.
+///
+/// Outside of [`with_request_hw_counter`] a detached counter is returned, the work is counted anyway.
+pub fn request_hw_counter() -> Arc<HardwareCounterCell> {
+    REQUEST_HW_COUNTER
+        .try_with(|counter| counter.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,85 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::operations::types::Warning;
+
+/// Warnings about a client request, reported while it is served
+#[derive(Debug, Default)]
+pub struct RequestWarningsCell {
+    warnings: Mutex<Vec<Warning>>,
+}
+
+impl RequestWarningsCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, warning: Warning) {
+        let mut warnings = self.warnings.lock();
+        // Requests of a batch may repeat the same issue
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+
+    /// Take the reported warnings, `None` if there are none
+    pub fn take(&self) -> Option<Vec<Warning>> {
+        let warnings = std::mem::take(&mut *self.warnings.lock());
+        (!warnings.is_empty()).then_some(warnings)
+    }
+}
+
+tokio::task_local! {
+    /// Warnings about the request, which is served by the current task
+    static REQUEST_WARNINGS: Arc<RequestWarningsCell>;
+}
+
+/// Serve a request by running `future`, collecting the warnings about it into `cell`
+pub async fn with_request_warnings<F: Future>(
+    cell: Arc<RequestWarningsCell>,
+    future: F,
+) -> F::Output {
+    REQUEST_WARNINGS.scope(cell, future).await
+}
+
+/// Take the warnings about the request, served by the current task.
+/// `None` outside of [`with_request_warnings`] or if there are no warnings.
+pub fn take_request_warnings() -> Option<Vec<Warning>> {
+    REQUEST_WARNINGS.try_with(|cell| cell.take()).ok().flatten()
+}
+
+/// Report a warning about the request. Ignored outside of [`with_request_warnings`]
+pub(crate) fn report_warning(warning: Warning) {
+    let _ = REQUEST_WARNINGS.try_with(|cell| cell.record(warning));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_warnings_cell() {
+        report_warning(Warning::unindexed_filter(&["city".to_string()]));
+        assert_eq!(take_request_warnings(), None);
+
+        let cell = Arc::new(RequestWarningsCell::new());
+        let warnings = with_request_warnings(cell.clone(), async {
+            report_warning(Warning::unindexed_filter(&["city".to_string()]));
+            report_warning(Warning::group_by_field_not_indexed("country"));
+            report_warning(Warning::unindexed_filter(&["city".to_string()]));
+            take_request_warnings()
+        })
+        .await;
+
+        assert_eq!(
+            warnings,
+            Some(vec![
+                Warning::unindexed_filter(&["city".to_string()]),
+                Warning::group_by_field_not_indexed("country"),
+            ]),
+        );
+        assert_eq!(cell.take(), None);
+    }
+}
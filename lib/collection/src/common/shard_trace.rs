@@ -0,0 +1,83 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use api::grpc::models::ShardTrace;
+use parking_lot::Mutex;
+
+/// Replicas, which served the reads of a request with the shard trace
+#[derive(Debug, Default)]
+pub struct ShardTraceCell {
+    traces: Mutex<Vec<ShardTrace>>,
+}
+
+impl ShardTraceCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, trace: ShardTrace) {
+        self.traces.lock().push(trace);
+    }
+
+    /// Reads of the request, ordered by shard. Reads of the same shard keep the order of responses
+    pub fn report(&self) -> Vec<ShardTrace> {
+        let mut traces = self.traces.lock().clone();
+        traces.sort_by_key(|trace| trace.shard_id);
+        traces
+    }
+}
+
+tokio::task_local! {
+    /// Shard trace of the request, which is served by the current task
+    static REQUEST_SHARD_TRACE: Arc<ShardTraceCell>;
+}
+
+/// Serve a request by running `future`, recording the replicas, which answered its shard reads,
+/// into `cell`. Internal reads, e.g. of a grouped search, are recorded as well.
+pub async fn with_shard_trace<F: Future>(cell: Arc<ShardTraceCell>, future: F) -> F::Output {
+    REQUEST_SHARD_TRACE.scope(cell, future).await
+}
+
+/// Whether the request, served by the current task, collects the shard trace.
+///
+/// Reads are only timed if it does, so requests without the trace don't pay for it.
+pub fn shard_trace_enabled() -> bool {
+    REQUEST_SHARD_TRACE.try_with(|_| ()).is_ok()
+}
+
+/// Record the replica, which answered a shard read. Ignored outside of [`with_shard_trace`]
+pub(crate) fn record_shard_trace(trace: ShardTrace) {
+    let _ = REQUEST_SHARD_TRACE.try_with(|cell| cell.record(trace));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(shard_id: u32, peer_id: u64) -> ShardTrace {
+        ShardTrace {
+            shard_id,
+            peer_id,
+            local: false,
+            replica_state: None,
+            latency: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shard_trace_cell() {
+        assert!(!shard_trace_enabled());
+        record_shard_trace(trace(0, 1));
+
+        let cell = Arc::new(ShardTraceCell::new());
+        with_shard_trace(cell.clone(), async {
+            assert!(shard_trace_enabled());
+            record_shard_trace(trace(2, 1));
+            record_shard_trace(trace(0, 1));
+            record_shard_trace(trace(2, 3));
+        })
+        .await;
+
+        assert_eq!(cell.report(), vec![trace(0, 1), trace(2, 1), trace(2, 3)]);
+    }
+}
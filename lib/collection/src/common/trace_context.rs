@@ -0,0 +1,58 @@
+use opentelemetry::propagation::{Extractor, Injector};
+use tonic::codegen::http::HeaderMap;
+use tonic::metadata::{MetadataKey, MetadataMap};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+pub type TraceContextInterceptor = fn(Request<()>) -> Result<Request<()>, Status>;
+
+/// Channel to a remote peer, which passes the trace context along with the requests
+pub type TracedChannel = InterceptedService<Channel, TraceContextInterceptor>;
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl Injector for MetadataInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        let key = MetadataKey::from_bytes(key.as_bytes());
+        if let (Ok(key), Ok(value)) = (key, value.parse()) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Add the context of the current span to the metadata of an internal request,
+/// so the spans of the remote peer are recorded into the same trace.
+///
+/// Without a configured propagator nothing is added.
+pub fn inject_trace_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+    let context = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(request.metadata_mut()))
+    });
+    Ok(request)
+}
+
+pub fn traced_channel(channel: Channel) -> TracedChannel {
+    InterceptedService::new(channel, inject_trace_context as TraceContextInterceptor)
+}
+
+/// Context of the trace, passed by the peer in the headers of an internal request
+pub fn extract_trace_context(headers: &HeaderMap) -> opentelemetry::Context {
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    })
+}
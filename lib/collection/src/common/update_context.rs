@@ -0,0 +1,43 @@
+use std::future::Future;
+
+use uuid::Uuid;
+
+use crate::operations::consistency_params::WriteConsistency;
+
+/// Client-supplied parameters of the update operation, which are not part of the operation itself
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpdateContext {
+    /// Id of the operation.
+    ///
+    /// It is attached to the updates of all shard replicas. Replicas skip the operations with the
+    /// ids they have already applied, so retries of the operation are not applied twice.
+    pub operation_id: Option<Uuid>,
+    /// Number of replicas of each shard, which should apply the operation before it is completed
+    pub wait_for: Option<WriteConsistency>,
+}
+
+tokio::task_local! {
+    /// Context of the update operation, which is served by the current task
+    static UPDATE_CONTEXT: UpdateContext;
+}
+
+/// Serve an update operation by running `future` within the given `context`
+pub async fn with_update_context<F: Future>(context: UpdateContext, future: F) -> F::Output {
+    if context == UpdateContext::default() {
+        future.await
+    } else {
+        UPDATE_CONTEXT.scope(context, future).await
+    }
+}
+
+/// Context of the update operation, served by the current task
+pub fn update_context() -> UpdateContext {
+    UPDATE_CONTEXT
+        .try_with(|context| *context)
+        .unwrap_or_default()
+}
+
+/// Client-supplied id of the update operation, served by the current task
+pub fn client_operation_id() -> Option<Uuid> {
+    update_context().operation_id
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::num::NonZeroU32;
@@ -8,17 +8,27 @@ use atomicwrites::AtomicFile;
 use atomicwrites::OverwriteBehavior::AllowOverwrite;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
-use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+use segment::data_types::vectors::{NamedVector, NamedVectorStruct};
 use segment::types::{
-    HnswConfig, Indexes, QuantizationConfig, VectorDataConfig, VectorStorageType,
+    Distance, HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, QuantizationConfig,
+    SearchParams, SparseVectorDataConfig, VectorDataConfig, VectorStorageType,
+    WithPayloadInterface, WithVector,
 };
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use wal::WalOptions;
 
-use crate::operations::types::{CollectionError, CollectionResult, VectorParams, VectorsConfig};
+use crate::admission::AdmissionConfig;
+use crate::operations::payload_validation::PayloadValidationMode;
+use crate::operations::strict_mode::StrictModeConfig;
+use crate::operations::types::{
+    CollectionError, CollectionResult, SparseVectorParams, UsingVector, VectorParams, VectorsConfig,
+};
 use crate::operations::validation;
 use crate::optimizers_builder::OptimizersConfig;
+use crate::quota::QuotaConfig;
+use crate::read_cache::ReadCacheConfig;
+use crate::ttl::TtlConfig;
 
 pub const COLLECTION_CONFIG_FILE: &str = "config.json";
 
@@ -73,6 +83,41 @@ pub struct CollectionParams {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default = "default_on_disk_payload")]
     pub on_disk_payload: bool,
+    /// If true - payload values of the upserted points are checked against the types of the
+    /// indexed fields, and mismatching values are rejected. Values of not indexed fields are not checked.
+    #[serde(default)]
+    pub validate_payload: bool,
+    /// Whether the whole operation or only the points with invalid payload are rejected,
+    /// if `validate_payload` is enabled
+    #[serde(default)]
+    pub payload_validation_mode: PayloadValidationMode,
+    /// If set - a read, which requires a single response, is also sent to another replica if the
+    /// first one doesn't respond within this delay in milliseconds. The first response is used,
+    /// the other read is cancelled. Reduces the latency caused by slow replicas.
+    #[serde(default)]
+    pub read_hedge_delay_ms: Option<u64>,
+    /// Configuration of the sparse vectors, stored and indexed alongside the dense ones.
+    /// Sparse vectors are always compared by dot product.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    /// Payload indexes, declared on the collection creation.
+    /// Every new segment of the collection creates them upfront, so points are indexed as they arrive
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub payload_indexes: BTreeMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Payload fields, which values are kept in memory, if `on_disk_payload` is enabled.
+    /// Conditions on these fields and grouping by them don't read the payload from disk.
+    /// Values of the indexed fields, except for the full-text ones, are kept in memory as well
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payload_cache_fields: Vec<PayloadKeyType>,
+    /// If true - updates of the points, payload and payload indexes are rejected, reads are
+    /// served as usual. Internal operations, like shard transfers and optimizations, still work.
+    #[serde(default)]
+    pub read_only: bool,
+    /// If true - a payload index is created in background for a field, which the points are
+    /// grouped by, if the field is not indexed yet. The type of the index is inferred from the
+    /// values of the field. Grouping by a not indexed field requires a payload scan
+    #[serde(default)]
+    pub auto_index_group_by_fields: bool,
 }
 
 impl Anonymize for CollectionParams {
@@ -83,6 +128,18 @@ impl Anonymize for CollectionParams {
             replication_factor: self.replication_factor,
             write_consistency_factor: self.write_consistency_factor,
             on_disk_payload: self.on_disk_payload,
+            validate_payload: self.validate_payload,
+            payload_validation_mode: self.payload_validation_mode,
+            read_hedge_delay_ms: self.read_hedge_delay_ms,
+            sparse_vectors: self.sparse_vectors.anonymize(),
+            payload_indexes: self
+                .payload_indexes
+                .iter()
+                .map(|(field, schema)| (field.anonymize(), schema.clone()))
+                .collect(),
+            payload_cache_fields: self.payload_cache_fields.anonymize(),
+            read_only: self.read_only,
+            auto_index_group_by_fields: self.auto_index_group_by_fields,
         }
     }
 }
@@ -115,6 +172,42 @@ pub struct CollectionConfig {
     pub wal_config: WalConfig,
     #[serde(default)]
     pub quantization_config: Option<QuantizationConfig>,
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// Search params, used by the requests, which don't specify them
+    #[serde(default)]
+    #[validate]
+    pub default_search_params: Option<SearchParams>,
+    /// Payload selector, used by the requests, which don't specify `with_payload`
+    #[serde(default)]
+    pub default_with_payload: Option<WithPayloadInterface>,
+    /// Vector selector, used by the requests, which don't specify `with_vector`
+    #[serde(default)]
+    pub default_with_vector: Option<WithVector>,
+    /// Limit of the returned payload size of each point, used by the requests, which don't
+    /// specify `max_payload_return_bytes`. If none - payloads are returned in full
+    #[serde(default)]
+    pub default_max_payload_return_bytes: Option<usize>,
+    /// Expiration of the points by a payload field. If none - points don't expire
+    #[serde(default)]
+    #[validate]
+    pub ttl_config: Option<TtlConfig>,
+    /// Named vector, used by the requests, which don't specify the vector name
+    #[serde(default)]
+    pub default_vector: Option<String>,
+    /// Limits of the data, stored in the collection. If none - the data is not limited
+    #[serde(default)]
+    #[validate]
+    pub quota_config: Option<QuotaConfig>,
+    /// Limits of the read and write operations. If none - operations are not limited
+    #[serde(default)]
+    #[validate]
+    pub admission_config: Option<AdmissionConfig>,
+    /// Cache of the responses to the read operations. If none - responses are not cached
+    #[serde(default)]
+    #[validate]
+    pub read_cache_config: Option<ReadCacheConfig>,
 }
 
 impl CollectionConfig {
@@ -142,6 +235,61 @@ impl CollectionConfig {
         config_path.exists()
     }
 
+    /// Fill the search parameters, which are not set by the request, with the collection defaults.
+    ///
+    /// Values of the request always take precedence, including single fields of the search params.
+    pub fn apply_search_defaults(
+        &self,
+        params: &mut Option<SearchParams>,
+        with_payload: &mut Option<WithPayloadInterface>,
+        with_vector: &mut Option<WithVector>,
+    ) {
+        if let Some(default_params) = self.default_search_params {
+            *params = Some(match *params {
+                None => default_params,
+                Some(request_params) => SearchParams {
+                    hnsw_ef: request_params.hnsw_ef.or(default_params.hnsw_ef),
+                    quantization: request_params.quantization.or(default_params.quantization),
+                    adaptive_ef: request_params.adaptive_ef.or(default_params.adaptive_ef),
+                    ..request_params
+                },
+            });
+        }
+        if with_payload.is_none() {
+            *with_payload = self.default_with_payload.clone();
+        }
+        if with_vector.is_none() {
+            *with_vector = self.default_with_vector.clone();
+        }
+    }
+
+    /// Fill the payload size limit of the returned points, if the request doesn't set it
+    pub fn apply_payload_size_default(&self, max_payload_return_bytes: &mut Option<usize>) {
+        if max_payload_return_bytes.is_none() {
+            *max_payload_return_bytes = self.default_max_payload_return_bytes;
+        }
+    }
+
+    /// Name the query vector of the request with the default vector, if the request doesn't name it
+    pub fn apply_default_vector(&self, vector: &mut NamedVectorStruct) {
+        let Some(default_vector) = &self.default_vector else {
+            return;
+        };
+        if let NamedVectorStruct::Default(dense) = vector {
+            *vector = NamedVectorStruct::Named(NamedVector {
+                name: default_vector.clone(),
+                vector: std::mem::take(dense),
+            });
+        }
+    }
+
+    /// Use the default vector for the request, which doesn't specify `using`
+    pub fn apply_default_using(&self, using: &mut Option<UsingVector>) {
+        if using.is_none() {
+            *using = self.default_vector.clone().map(UsingVector::Name);
+        }
+    }
+
     pub fn validate_and_warn(&self) {
         if let Err(ref errs) = self.validate() {
             validation::warn_validation_errors("Collection configuration file", errs);
@@ -154,13 +302,91 @@ impl CollectionParams {
         self.vectors
             .get_params(vector_name)
             .cloned()
-            .ok_or_else(|| CollectionError::BadInput {
-                description: if vector_name == DEFAULT_VECTOR_NAME {
-                    "Default vector params are not specified in config".into()
-                } else {
-                    format!("Vector params for {vector_name} are not specified in config")
-                },
+            .ok_or_else(|| CollectionError::VectorNameMismatch {
+                requested: vector_name.to_string(),
+                available: self.vector_names(),
+                collection: None,
+            })
+    }
+
+    /// Names of the dense vectors, configured in the collection
+    pub fn vector_names(&self) -> Vec<String> {
+        self.vectors
+            .params_iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Distance of the vector with the given name, sparse vectors are scored by dot product
+    pub fn get_distance(&self, vector_name: &str) -> CollectionResult<Distance> {
+        if self.is_sparse_vector(vector_name) {
+            return Ok(Distance::Dot);
+        }
+        Ok(self.get_vector_params(vector_name)?.distance)
+    }
+
+    pub fn is_sparse_vector(&self, vector_name: &str) -> bool {
+        self.sparse_vectors
+            .as_ref()
+            .map_or(false, |sparse| sparse.contains_key(vector_name))
+    }
+
+    /// Check that the dimensions, selected by a slice `with_vector` selector, exist in the vector
+    pub fn check_with_vector(&self, with_vector: &WithVector) -> CollectionResult<()> {
+        let WithVector::Slice { name, slice } = with_vector else {
+            return Ok(());
+        };
+        if self.is_sparse_vector(name) {
+            return Err(CollectionError::bad_input(format!(
+                "Vector `{name}` is sparse, only dense vectors can be sliced"
+            )));
+        }
+        let size = self.get_vector_params(name)?.size.get() as usize;
+        if !slice.fits(size) {
+            return Err(CollectionError::bad_input(format!(
+                "Slice {}..{} is out of range of vector `{name}` with {size} dimensions",
+                slice.start, slice.end,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject dense query vectors, which can't produce meaningful scores
+    ///
+    /// Empty vectors are never valid, zero vectors are rejected for cosine distance,
+    /// since they can't be normalized.
+    pub fn check_search_vector(&self, vector: &NamedVectorStruct) -> CollectionResult<()> {
+        let Some(dense) = vector.get_vector() else {
+            return Ok(());
+        };
+        let name = vector.get_name();
+        if dense.is_empty() {
+            return Err(CollectionError::bad_input(format!(
+                "Search vector `{name}` is empty"
+            )));
+        }
+        if self.get_distance(name)? == Distance::Cosine && dense.iter().all(|x| *x == 0.0) {
+            return Err(CollectionError::bad_input(format!(
+                "Search vector `{name}` is all zeros, cosine similarity is not defined for it"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sparse vector storage config of new segments, built from the collection params
+    pub fn into_sparse_vector_data(&self) -> HashMap<String, SparseVectorDataConfig> {
+        self.sparse_vectors
+            .iter()
+            .flatten()
+            .map(|(name, params)| {
+                (
+                    name.clone(),
+                    SparseVectorDataConfig {
+                        index: params.index.unwrap_or_default(),
+                    },
+                )
             })
+            .collect()
     }
 
     /// Convert into unoptimized named vector data configs
@@ -187,6 +413,7 @@ impl CollectionParams {
                         } else {
                             VectorStorageType::Memory
                         },
+                        datatype: params.datatype.unwrap_or_default(),
                     },
                 )
             })
@@ -0,0 +1,327 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use segment::types::{Order, PointIdType, ScoreType, ScoredPoint};
+
+use crate::collection::Collection;
+use crate::common::measured_operation::record_requests;
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::strict_mode::StrictModeRequest;
+use crate::operations::types::{
+    CollectionError, CollectionResult, FusedPoint, Fusion, FusionRequest, MinMaxParams, RrfParams,
+    SearchRequest, SearchRequestBatch,
+};
+use crate::slow_log::RequestDetails;
+use crate::telemetry::CollectionOperationType;
+
+/// Fuse the results of several searches into a single ranking.
+///
+/// Function works in following stages:
+///
+/// - Executes all searches as a single batch on every shard concurrently
+/// - Fuses the results of each shard separately, so ranks and score ranges are shard-local
+/// - Merges fused results of the shards by the fused score
+/// - Retrieves payload and vectors for the final top only
+pub async fn fuse_searches(
+    mut request: FusionRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<FusedPoint>> {
+    if let Fusion::MinMax(MinMaxParams {
+        weights: Some(weights),
+    }) = &request.fusion
+    {
+        if weights.len() != request.searches.len() {
+            return Err(CollectionError::bad_input(format!(
+                "Number of weights ({}) must match the number of searches ({})",
+                weights.len(),
+                request.searches.len(),
+            )));
+        }
+    }
+    if let Some(config) = collection.config_for_defaults(None).await {
+        for search in &mut request.searches {
+            config.apply_search_defaults(
+                &mut search.params,
+                &mut search.with_payload,
+                &mut search.with_vector,
+            );
+            config.apply_default_vector(&mut search.vector);
+            config.params.check_search_vector(&search.vector)?;
+        }
+        config.apply_search_defaults(
+            &mut None,
+            &mut request.with_payload,
+            &mut request.with_vector,
+        );
+    }
+    collection
+        .check_strict_mode(request.searches.iter().map(StrictModeRequest::from), None)
+        .await?;
+    collection
+        .restrict_read_filters(
+            request.searches.iter_mut().map(|search| &mut search.filter),
+            None,
+        )
+        .await;
+    collection
+        .measure_operation(CollectionOperationType::Fusion, None, async {
+            record_requests(|| request.searches.iter().map(RequestDetails::from).collect());
+            fuse_searches_impl(request, collection, read_consistency).await
+        })
+        .await
+}
+
+async fn fuse_searches_impl(
+    request: FusionRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<FusedPoint>> {
+    let FusionRequest {
+        searches,
+        fusion,
+        limit,
+        offset,
+        with_payload,
+        with_vector,
+        with_ranks,
+    } = request;
+
+    let orders = {
+        let config = collection.collection_config.read().await;
+        searches
+            .iter()
+            .map(|search| {
+                let distance = config.params.get_distance(search.vector.get_name())?;
+                Ok(distance.distance_order())
+            })
+            .collect::<CollectionResult<Vec<_>>>()?
+    };
+
+    // Payload and vectors are only retrieved for the fused top
+    let searches = searches
+        .into_iter()
+        .map(|search| SearchRequest {
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            ..search
+        })
+        .collect();
+    let shard_results = collection
+        .search_shards(
+            Arc::new(SearchRequestBatch { searches }),
+            read_consistency,
+            None,
+        )
+        .await?;
+
+    let mut fused: Vec<FusedPoint> = shard_results
+        .into_iter()
+        .flat_map(|sources| fuse_shard_results(sources, &orders, &fusion, with_ranks))
+        .collect();
+    fused.sort_unstable_by(|a, b| b.point.cmp(&a.point));
+
+    // The same point might come from several shards, e.g. while it is being transferred
+    let mut seen = HashSet::new();
+    let mut ranks: HashMap<PointIdType, Vec<Option<usize>>> = HashMap::new();
+    let top: Vec<ScoredPoint> = fused
+        .into_iter()
+        .filter(|fused_point| seen.insert(fused_point.point.id))
+        .skip(offset)
+        .take(limit)
+        .map(|fused_point| {
+            if let Some(point_ranks) = fused_point.ranks {
+                ranks.insert(fused_point.point.id, point_ranks);
+            }
+            fused_point.point
+        })
+        .collect();
+
+    let top = collection
+        .fill_search_result_with_payload(
+            top,
+            with_payload,
+            with_vector.unwrap_or_default(),
+            read_consistency,
+            None,
+        )
+        .await?;
+
+    Ok(top
+        .into_iter()
+        .map(|point| FusedPoint {
+            ranks: ranks.remove(&point.id),
+            point,
+        })
+        .collect())
+}
+
+/// Fuse the results of all searches, returned by a single shard.
+/// `sources` and `orders` are in the order of the searches of the request.
+fn fuse_shard_results(
+    mut sources: Vec<Vec<ScoredPoint>>,
+    orders: &[Order],
+    fusion: &Fusion,
+    with_ranks: bool,
+) -> Vec<FusedPoint> {
+    let sources_count = sources.len();
+    let mut fused: HashMap<PointIdType, FusedPoint> = HashMap::new();
+
+    for (source_index, (source, order)) in sources.iter_mut().zip(orders).enumerate() {
+        match order {
+            Order::LargeBetter => source.sort_unstable_by(|a, b| b.cmp(a)),
+            Order::SmallBetter => source.sort_unstable(),
+        }
+        let contributions = match fusion {
+            Fusion::Rrf(RrfParams { k }) => rrf_scores(source.len(), *k),
+            Fusion::MinMax(MinMaxParams { weights }) => {
+                let weight = weights
+                    .as_ref()
+                    .map_or(1.0, |weights| weights[source_index]);
+                min_max_scores(source, weight)
+            }
+        };
+
+        for (rank, (point, contribution)) in source.drain(..).zip(contributions).enumerate() {
+            let fused_point = fused.entry(point.id).or_insert_with(|| FusedPoint {
+                point: ScoredPoint {
+                    score: 0.0,
+                    payload: None,
+                    vector: None,
+                    vector_slice: None,
+                    ..point
+                },
+                ranks: with_ranks.then(|| vec![None; sources_count]),
+            });
+            fused_point.point.score += contribution;
+            if let Some(ranks) = &mut fused_point.ranks {
+                ranks[source_index] = Some(rank + 1);
+            }
+        }
+    }
+
+    fused.into_values().collect()
+}
+
+/// Reciprocal rank scores of a source with `len` points, from the best to the worst
+fn rrf_scores(len: usize, k: usize) -> Vec<ScoreType> {
+    (1..=len)
+        .map(|rank| 1.0 / (k + rank) as ScoreType)
+        .collect()
+}
+
+/// Scores of the source, ordered from the best to the worst, scaled into `[0, weight]`.
+/// The best point gets `weight` and the worst one gets 0, whichever way the distance is ordered.
+fn min_max_scores(source: &[ScoredPoint], weight: f32) -> Vec<ScoreType> {
+    let (Some(best), Some(worst)) = (source.first(), source.last()) else {
+        return vec![];
+    };
+    let range = best.score - worst.score;
+    source
+        .iter()
+        .map(|point| {
+            if range == 0.0 {
+                weight
+            } else {
+                weight * (point.score - worst.score) / range
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(num: u64) -> PointIdType {
+        num.into()
+    }
+
+    fn scored(num: u64, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            id: id(num),
+            version: 0,
+            score,
+            payload: None,
+            vector: None,
+            payload_truncation: None,
+            vector_slice: None,
+        }
+    }
+
+    fn sorted_ids(mut fused: Vec<FusedPoint>) -> Vec<(PointIdType, Option<Vec<Option<usize>>>)> {
+        fused.sort_unstable_by(|a, b| b.point.cmp(&a.point));
+        fused
+            .into_iter()
+            .map(|fused_point| (fused_point.point.id, fused_point.ranks))
+            .collect()
+    }
+
+    #[test]
+    fn test_rrf_fusion() {
+        let dense = vec![scored(1, 0.9), scored(2, 0.8), scored(3, 0.7)];
+        let sparse = vec![scored(3, 12.0), scored(4, 3.0), scored(1, 7.0)];
+
+        let fused = fuse_shard_results(
+            vec![dense, sparse],
+            &[Order::LargeBetter, Order::LargeBetter],
+            &Fusion::Rrf(RrfParams { k: 60 }),
+            true,
+        );
+
+        let score_of = |num: u64| {
+            fused
+                .iter()
+                .find(|fused_point| fused_point.point.id == id(num))
+                .unwrap()
+                .point
+                .score
+        };
+        assert_eq!(score_of(1), 1.0 / 61.0 + 1.0 / 62.0);
+        assert_eq!(score_of(3), 1.0 / 63.0 + 1.0 / 61.0);
+        assert_eq!(score_of(4), 1.0 / 63.0);
+
+        assert_eq!(
+            sorted_ids(fused),
+            vec![
+                (id(1), Some(vec![Some(1), Some(2)])),
+                (id(3), Some(vec![Some(3), Some(1)])),
+                (id(2), Some(vec![Some(2), None])),
+                (id(4), Some(vec![None, Some(3)])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_max_fusion() {
+        // Euclid: smaller is better
+        let euclid = vec![scored(1, 1.0), scored(2, 3.0), scored(3, 5.0)];
+        let dot = vec![scored(3, 10.0), scored(2, 0.0)];
+
+        let fused = fuse_shard_results(
+            vec![euclid, dot],
+            &[Order::SmallBetter, Order::LargeBetter],
+            &Fusion::MinMax(MinMaxParams {
+                weights: Some(vec![1.0, 0.5]),
+            }),
+            false,
+        );
+
+        let scores: HashMap<_, _> = fused
+            .iter()
+            .map(|fused_point| (fused_point.point.id, fused_point.point.score))
+            .collect();
+        assert_eq!(scores[&id(1)], 1.0);
+        assert_eq!(scores[&id(2)], 0.5);
+        assert_eq!(scores[&id(3)], 0.5);
+        assert!(fused.iter().all(|fused_point| fused_point.ranks.is_none()));
+    }
+
+    #[test]
+    fn test_min_max_equal_scores() {
+        let scores = min_max_scores(&[scored(1, 0.5), scored(2, 0.5)], 2.0);
+        assert_eq!(scores, vec![2.0, 2.0]);
+        assert!(min_max_scores(&[], 1.0).is_empty());
+    }
+}
@@ -1,19 +1,101 @@
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use segment::data_types::groups::GroupId;
-use segment::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use segment::types::{
-    ExtendedPointId, Order, PayloadContainer, PointIdType, ScoreType, ScoredPoint,
+    ExtendedPointId, Order, Payload, PayloadContainer, PointIdType, ScoreType, ScoredPoint,
+    SeqNumberType,
 };
 use serde_json::Value;
 
 use super::types::AggregatorError::{self, *};
 use super::types::Group;
 
-type Hits = HashMap<PointIdType, ScoredPoint>;
+/// The greater, the worse the score is
+fn badness(score: ScoreType, order: &Order) -> OrderedFloat<ScoreType> {
+    match order {
+        Order::LargeBetter => -OrderedFloat(score),
+        Order::SmallBetter => OrderedFloat(score),
+    }
+}
+
+/// Total order of the hits, ties of the scores are broken by the point ids
+fn hit_rank(hit: &ScoredPoint, order: &Order) -> (OrderedFloat<ScoreType>, PointIdType) {
+    (badness(hit.score, order), hit.id)
+}
+
+/// Hit of a group, ordered so that the worst hit is on top of the heap.
+/// Of the hits with the same score, the one with the largest id is the worst
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct WorstHit {
+    badness: OrderedFloat<ScoreType>,
+    id: PointIdType,
+}
+
+/// Hits of a group, the worst of them can be replaced with a better one
+#[derive(Default)]
+struct Hits {
+    points: HashMap<PointIdType, ScoredPoint>,
+    worst_first: BinaryHeap<WorstHit>,
+}
+
+impl Hits {
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn worst(&self) -> Option<&ScoredPoint> {
+        self.worst_first.peek().map(|hit| &self.points[&hit.id])
+    }
+
+    /// Inserts the point or replaces the previous version of it
+    fn insert(&mut self, point: ScoredPoint, order: &Order) {
+        if self.points.contains_key(&point.id) {
+            self.worst_first.retain(|hit| hit.id != point.id);
+        }
+        self.worst_first.push(WorstHit {
+            badness: badness(point.score, order),
+            id: point.id,
+        });
+        self.points.insert(point.id, point);
+    }
+
+    fn pop_worst(&mut self) -> Option<ScoredPoint> {
+        let worst = self.worst_first.pop()?;
+        self.points.remove(&worst.id)
+    }
+}
+
+/// Extracts the unique keys of the groups, the payload belongs to
+pub(super) fn group_keys(
+    payload: Option<&Payload>,
+    grouped_by: &str,
+) -> Result<Vec<GroupId>, AggregatorError> {
+    // extract all values from the group_by field
+    let payload_values: Vec<_> = payload
+        .map(|p| {
+            p.get_value(grouped_by)
+                .values()
+                .into_iter()
+                .flat_map(|v| match v {
+                    Value::Array(arr) => arr.iter().collect(),
+                    _ => vec![v],
+                })
+                .collect()
+        })
+        .ok_or(KeyNotFound)?;
+
+    let group_keys = payload_values
+        .into_iter()
+        .map(GroupId::try_from)
+        .collect::<Result<Vec<GroupId>, ()>>()
+        .map_err(|_| BadKeyType)?;
+
+    Ok(group_keys.into_iter().unique().collect())
+}
+
 pub(super) struct GroupsAggregator {
     groups: HashMap<GroupId, Hits>,
     max_group_size: usize,
@@ -21,8 +103,11 @@ pub(super) struct GroupsAggregator {
     max_groups: usize,
     full_groups: HashSet<GroupId>,
     group_best_scores: HashMap<GroupId, ScoreType>,
-    all_ids: HashSet<ExtendedPointId>,
+    /// Number of groups, each of the aggregated points is in
+    id_groups: HashMap<ExtendedPointId, usize>,
     order: Order,
+    /// Keys, smaller than this one, are ignored
+    min_key: Option<GroupId>,
 }
 
 impl GroupsAggregator {
@@ -34,55 +119,68 @@ impl GroupsAggregator {
             max_groups: groups,
             full_groups: HashSet::with_capacity(groups),
             group_best_scores: HashMap::with_capacity(groups),
-            all_ids: HashSet::with_capacity(groups * group_size),
+            id_groups: HashMap::with_capacity(groups * group_size),
             order,
+            min_key: None,
         }
     }
 
-    /// Adds a point to the group that corresponds based on the group_by field, assumes that the point has the group_by field
-    fn add_point(&mut self, point: ScoredPoint) -> Result<(), AggregatorError> {
-        // extract all values from the group_by field
-        let payload_values: Vec<_> = point
-            .payload
-            .as_ref()
-            .map(|p| {
-                p.get_value(&self.grouped_by)
-                    .values()
-                    .into_iter()
-                    .flat_map(|v| match v {
-                        Value::Array(arr) => arr.iter().collect(),
-                        _ => vec![v],
-                    })
-                    .collect()
-            })
-            .ok_or(KeyNotFound)?;
-
-        let group_keys = payload_values
-            .into_iter()
-            .map(GroupId::try_from)
-            .collect::<Result<Vec<GroupId>, ()>>()
-            .map_err(|_| BadKeyType)?;
+    /// Ignores the keys, smaller than the given one, e.g. the keys of the previous pages
+    pub(super) fn with_min_key(mut self, min_key: Option<GroupId>) -> Self {
+        self.min_key = min_key;
+        self
+    }
 
-        let unique_group_keys: Vec<_> = group_keys.into_iter().unique().collect();
+    /// Adds a point to the group that corresponds based on the group_by field, assumes that the point has the group_by field.
+    /// A point replaces the worst hit of a full group, if it has a better score.
+    fn add_point(&mut self, point: ScoredPoint) -> Result<(), AggregatorError> {
+        let unique_group_keys = group_keys(point.payload.as_ref(), &self.grouped_by)?;
 
         for group_key in unique_group_keys {
-            let group = self
-                .groups
-                .entry(group_key.clone())
-                .or_insert_with(|| HashMap::with_capacity(self.max_group_size));
+            if self
+                .min_key
+                .as_ref()
+                .map_or(false, |min_key| &group_key < min_key)
+            {
+                continue;
+            }
 
-            let entry = group.entry(point.id);
+            let group = self.groups.entry(group_key.clone()).or_default();
 
-            // if the point is already in the group, check if it has newer version
-            match entry {
-                Entry::Occupied(mut o) => {
-                    if o.get().version < point.version {
-                        o.insert(point.clone());
+            let present_version = group.points.get(&point.id).map(|hit| hit.version);
+            match present_version {
+                // if the point is already in the group, check if it has newer version
+                Some(version) => {
+                    if version < point.version {
+                        group.insert(point.clone(), &self.order);
                     }
                 }
-                Entry::Vacant(v) => {
-                    v.insert(point.clone());
-                    self.all_ids.insert(point.id);
+                None if group.len() < self.max_group_size => {
+                    group.insert(point.clone(), &self.order);
+                    *self.id_groups.entry(point.id).or_default() += 1;
+                }
+                None => {
+                    // Ties are broken the same way as in the results, so the kept hits don't
+                    // depend on the order the points come in
+                    let is_better = group.worst().map_or(false, |worst| {
+                        hit_rank(&point, &self.order) < hit_rank(worst, &self.order)
+                    });
+                    if !is_better {
+                        continue;
+                    }
+
+                    // The replaced point might be found again for the other groups it belongs to
+                    if let Some(replaced) = group.pop_worst() {
+                        if let Entry::Occupied(mut groups_count) = self.id_groups.entry(replaced.id)
+                        {
+                            *groups_count.get_mut() -= 1;
+                            if *groups_count.get() == 0 {
+                                groups_count.remove();
+                            }
+                        }
+                    }
+                    group.insert(point.clone(), &self.order);
+                    *self.id_groups.entry(point.id).or_default() += 1;
                 }
             }
 
@@ -113,23 +211,45 @@ impl GroupsAggregator {
         }
     }
 
-    #[cfg(test)]
+    /// Amount of groups, the added points belong to
     pub(super) fn len(&self) -> usize {
         self.groups.len()
     }
 
+    /// Total order of the groups: by the best score, then by the key, then by the best hit.
+    /// It doesn't depend on the order the points are added in, so all replicas agree on it.
+    fn group_rank<'a>(
+        &self,
+        key: &'a GroupId,
+        score: ScoreType,
+    ) -> (OrderedFloat<ScoreType>, &'a GroupId, Option<PointIdType>) {
+        let best_hit_id = self.groups.get(key).and_then(|hits| {
+            hits.points
+                .values()
+                .min_by_key(|hit| hit_rank(hit, &self.order))
+                .map(|hit| hit.id)
+        });
+        (badness(score, &self.order), key, best_hit_id)
+    }
+
     /// Return `max_groups` number of keys of the groups with the best score
     fn best_group_keys(&self) -> impl Iterator<Item = &GroupId> {
         self.group_best_scores
             .iter()
-            .sorted_by_key(|(_, score)| match self.order {
-                Order::LargeBetter => -OrderedFloat(**score),
-                Order::SmallBetter => OrderedFloat(**score),
-            })
+            .sorted_by_cached_key(|(key, score)| self.group_rank(*key, **score))
             .take(self.max_groups)
             .map(|(k, _)| k)
     }
 
+    /// Key of the worst of the best groups, once there are `max_groups` of them.
+    /// Groups with the worse keys can't become the best ones, if the scores are all the same
+    pub(super) fn key_of_last_best_group(&self) -> Option<&GroupId> {
+        if self.groups.len() < self.max_groups {
+            return None;
+        }
+        self.best_group_keys().last()
+    }
+
     /// Gets the keys of the groups that have less than the max group size
     pub(super) fn keys_of_unfilled_best_groups(&self) -> Vec<Value> {
         let best_group_keys: HashSet<_> = self.best_group_keys().cloned().collect();
@@ -152,32 +272,44 @@ impl GroupsAggregator {
     }
 
     /// Gets the ids of the already present points across all the groups
-    pub(super) fn ids(&self) -> &HashSet<ExtendedPointId> {
-        &self.all_ids
+    pub(super) fn ids(&self) -> HashSet<ExtendedPointId> {
+        self.id_groups.keys().copied().collect()
     }
 
     /// Returns the best groups sorted by their best hit. The hits are sorted too.
+    /// Ties are broken by the keys of the groups and by the ids of the hits, so the same points
+    /// are distilled into the same groups, whatever order they were added in.
+    ///
+    /// A point, updated between the iterations, might be found in a group of its new key, while
+    /// the stale copy of it is still in the group of the old one. Only the copies of the version,
+    /// found in the best of the groups, are kept then.
     pub(super) fn distill(mut self) -> Vec<Group> {
         let best_groups: Vec<_> = self.best_group_keys().cloned().collect();
         let mut groups = Vec::with_capacity(best_groups.len());
 
         for group_key in best_groups {
-            let mut group = self.groups.remove(&group_key).unwrap();
-            let scored_points_iter = group.drain().map(|(_, hit)| hit);
-            let hits = match self.order {
-                Order::LargeBetter => {
-                    peek_top_largest_iterable(scored_points_iter, self.max_group_size)
-                }
-                Order::SmallBetter => {
-                    peek_top_smallest_iterable(scored_points_iter, self.max_group_size)
-                }
-            };
+            let group = self.groups.remove(&group_key).unwrap();
+            let hits = group
+                .points
+                .into_values()
+                .sorted_by_key(|hit| hit_rank(hit, &self.order))
+                .take(self.max_group_size)
+                .collect();
             groups.push(Group {
                 hits,
                 key: group_key,
             });
         }
 
+        // Copies of the same version are expected, if the key has multiple values
+        let mut kept_versions: HashMap<PointIdType, SeqNumberType> = HashMap::new();
+        for group in &mut groups {
+            group
+                .hits
+                .retain(|hit| *kept_versions.entry(hit.id).or_insert(hit.version) == hit.version);
+        }
+        groups.retain(|group| !group.hits.is_empty());
+
         groups
     }
 }
@@ -185,6 +317,8 @@ impl GroupsAggregator {
 #[cfg(test)]
 mod unit_tests {
 
+    use rand::seq::SliceRandom;
+    use rand::Rng;
     use segment::types::{Payload, ScoreType};
     use serde_json::json;
 
@@ -197,6 +331,8 @@ mod unit_tests {
             score,
             payload: Some(Payload::from(serde_json::json!({ "docId": payloads }))),
             vector: None,
+            payload_truncation: None,
+            vector_slice: None,
         }
     }
 
@@ -207,6 +343,8 @@ mod unit_tests {
             score,
             payload: None,
             vector: None,
+            payload_truncation: None,
+            vector_slice: None,
         }
     }
 
@@ -273,15 +411,15 @@ mod unit_tests {
             Case::new(json!("a"), 1, 1, Ok(()), point(1, 0.97, json!("a"))), // should not add it because it already has a point with the same id
             Case::new(json!("a"), 2, 2, Ok(()), point(2, 0.81, json!(["a", "b"]))), // to both groups
             Case::new(json!("b"), 2, 2, Ok(()), point(3, 0.84, json!("b"))), // check that `b` of size 2
-            Case::new(json!("a"), 3, 2, Ok(()), point(4, 0.9, json!("a"))), // grow beyond the max groups, as we sort later
+            Case::new(json!("a"), 3, 2, Ok(()), point(4, 0.9, json!("a"))), // `a` is full now
             Case::new(json!(3), 1, 3, Ok(()), point(5, 0.4, json!(3))),     // check that `3` of size 2
             Case::new(json!("d"), 1, 4, Ok(()), point(6, 0.3, json!("d"))),
-            Case::new(json!("a"), 4, 4, Ok(()), point(100, 0.31, json!("a"))), // small score 'a'
-            Case::new(json!("a"), 5, 4, Ok(()), point(101, 0.32, json!("a"))), // small score 'a'
-            Case::new(json!("a"), 6, 4, Ok(()), point(102, 0.33, json!("a"))), // small score 'a'
-            Case::new(json!("a"), 7, 4, Ok(()), point(103, 0.34, json!("a"))), // small score 'a'
-            Case::new(json!("a"), 8, 4, Ok(()), point(104, 0.35, json!("a"))), // small score 'a'
-            Case::new(json!("a"), 9, 4, Ok(()), point(105, 0.36, json!("a"))), // small score 'a'
+            Case::new(json!("a"), 3, 4, Ok(()), point(100, 0.31, json!("a"))), // small score 'a', group is full
+            Case::new(json!("a"), 3, 4, Ok(()), point(101, 0.32, json!("a"))), // small score 'a'
+            Case::new(json!("a"), 3, 4, Ok(()), point(102, 0.33, json!("a"))), // small score 'a'
+            Case::new(json!("a"), 3, 4, Ok(()), point(103, 0.34, json!("a"))), // small score 'a'
+            Case::new(json!("a"), 3, 4, Ok(()), point(104, 0.35, json!("a"))), // small score 'a'
+            Case::new(json!("a"), 3, 4, Ok(()), point(105, 0.36, json!("a"))), // small score 'a'
             Case::new(json!("b"), 3, 4, Ok(()), point(7, 1.0, json!("b"))),
             Case::new(json!("false"), 0, 4, Err(BadKeyType), point(8, 1.0, json!(false))),
             Case::new(json!("none"), 0, 4, Err(KeyNotFound), empty_point(9, 1.0)),
@@ -431,4 +569,186 @@ mod unit_tests {
             assert_eq!(expected_id_score, group_id_score);
         }
     }
+
+    fn ids_and_scores(group: &Group) -> Vec<(PointIdType, ScoreType)> {
+        group.hits.iter().map(|hit| (hit.id, hit.score)).collect()
+    }
+
+    #[test]
+    fn test_later_iteration_improves_full_group() {
+        let mut aggregator = GroupsAggregator::new(2, 2, "docId".to_string(), Order::LargeBetter);
+
+        // first iteration fills the group with mediocre hits
+        aggregator.add_points(&[point(1, 0.5, json!("a")), point(2, 0.4, json!("a"))]);
+        assert_eq!(aggregator.len_of_filled_best_groups(), 1);
+        assert_eq!(aggregator.ids(), HashSet::from([1.into(), 2.into()]));
+
+        // second iteration surfaces better hits
+        aggregator.add_points(&[point(3, 0.9, json!("a")), point(4, 0.45, json!("a"))]);
+
+        // replaced points are not excluded from the next iterations anymore
+        assert_eq!(aggregator.ids(), HashSet::from([1.into(), 3.into()]));
+
+        // worse hit doesn't replace anything
+        aggregator.add_points(&[point(5, 0.1, json!("a"))]);
+        assert_eq!(aggregator.ids(), HashSet::from([1.into(), 3.into()]));
+
+        let groups = aggregator.distill();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            ids_and_scores(&groups[0]),
+            vec![(3.into(), 0.9), (1.into(), 0.5)]
+        );
+    }
+
+    #[test]
+    fn test_replaced_point_stays_in_other_groups() {
+        let mut aggregator = GroupsAggregator::new(2, 1, "docId".to_string(), Order::LargeBetter);
+
+        aggregator.add_points(&[point(1, 0.5, json!(["a", "b"]))]);
+        aggregator.add_points(&[point(2, 0.7, json!("a"))]);
+
+        // point 1 is replaced in `a`, but still is the hit of `b`
+        assert_eq!(aggregator.ids(), HashSet::from([1.into(), 2.into()]));
+
+        aggregator.add_points(&[point(3, 0.6, json!("b"))]);
+        assert_eq!(aggregator.ids(), HashSet::from([2.into(), 3.into()]));
+
+        let groups = aggregator.distill();
+        assert_eq!(groups[0].key, GroupId::from("a"));
+        assert_eq!(ids_and_scores(&groups[0]), vec![(2.into(), 0.7)]);
+        assert_eq!(groups[1].key, GroupId::from("b"));
+        assert_eq!(ids_and_scores(&groups[1]), vec![(3.into(), 0.6)]);
+    }
+
+    #[test]
+    fn test_small_better_replaces_larger_scores() {
+        let mut aggregator = GroupsAggregator::new(1, 2, "docId".to_string(), Order::SmallBetter);
+
+        aggregator.add_points(&[point(1, 3.0, json!("a")), point(2, 5.0, json!("a"))]);
+        aggregator.add_points(&[point(3, 1.0, json!("a")), point(4, 4.0, json!("a"))]);
+
+        assert_eq!(aggregator.ids(), HashSet::from([1.into(), 3.into()]));
+
+        let groups = aggregator.distill();
+        assert_eq!(
+            ids_and_scores(&groups[0]),
+            vec![(3.into(), 1.0), (1.into(), 3.0)]
+        );
+    }
+
+    #[test]
+    fn test_point_moved_between_iterations_is_not_duplicated() {
+        let mut aggregator = GroupsAggregator::new(3, 3, "docId".to_string(), Order::LargeBetter);
+
+        aggregator.add_points(&[
+            point(1, 0.9, json!("a")),
+            point(2, 0.5, json!("a")),
+            point(3, 0.7, json!("b")),
+        ]);
+
+        // the key of point 1 is updated before the next iteration
+        let moved = ScoredPoint {
+            version: 1,
+            ..point(1, 0.8, json!("b"))
+        };
+        aggregator.add_points(&[moved, point(4, 0.6, json!("c"))]);
+
+        // multiple values of the key are still in several groups
+        aggregator.add_points(&[point(5, 0.55, json!(["b", "c"]))]);
+
+        let groups = aggregator.distill();
+
+        let keys_and_ids: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                let ids: Vec<PointIdType> = group.hits.iter().map(|hit| hit.id).collect();
+                (group.key.clone(), ids)
+            })
+            .collect();
+        assert_eq!(
+            keys_and_ids,
+            vec![
+                (GroupId::from("a"), vec![1.into(), 2.into()]),
+                (GroupId::from("b"), vec![3.into(), 5.into()]),
+                (GroupId::from("c"), vec![4.into(), 5.into()]),
+            ]
+        );
+    }
+
+    fn keys_and_ids(groups: &[Group]) -> Vec<(GroupId, Vec<PointIdType>)> {
+        groups
+            .iter()
+            .map(|group| {
+                (
+                    group.key.clone(),
+                    group.hits.iter().map(|hit| hit.id).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ties_are_ordered_by_keys_and_ids() {
+        let mut aggregator = GroupsAggregator::new(3, 2, "docId".to_string(), Order::LargeBetter);
+
+        aggregator.add_points(&[
+            point(4, 0.5, json!("a")),
+            point(6, 0.5, json!("a")),
+            point(3, 0.5, json!(2)),
+            point(2, 0.5, json!(-1)),
+            point(5, 0.5, json!("b")),
+            point(1, 0.5, json!("a")),
+        ]);
+
+        // numbers go before strings, of the tied hits the ones with smaller ids are kept
+        assert_eq!(
+            keys_and_ids(&aggregator.distill()),
+            vec![
+                (GroupId::from(-1i64), vec![2.into()]),
+                (GroupId::from(2u64), vec![3.into()]),
+                (GroupId::from("a"), vec![1.into(), 4.into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distill_does_not_depend_on_insertion_order() {
+        let mut rng = rand::thread_rng();
+        let keys = [
+            json!(1),
+            json!(-1),
+            json!(2),
+            json!("a"),
+            json!("b"),
+            json!(["a", 3]),
+        ];
+
+        for _ in 0..20 {
+            // few distinct scores to produce many ties between the hits and the groups
+            let mut points: Vec<_> = (0..40)
+                .map(|idx| {
+                    let score = rng.gen_range(0..3) as ScoreType;
+                    let key = keys.choose(&mut rng).unwrap().clone();
+                    point(idx, score, key)
+                })
+                .collect();
+
+            // points are added in one or several iterations
+            let distill = |points: &[ScoredPoint], chunk_size: usize| {
+                let mut aggregator =
+                    GroupsAggregator::new(4, 3, "docId".to_string(), Order::LargeBetter);
+                for chunk in points.chunks(chunk_size) {
+                    aggregator.add_points(chunk);
+                }
+                keys_and_ids(&aggregator.distill())
+            };
+
+            let expected = distill(&points, points.len());
+            for chunk_size in [40, 20, 10, 7] {
+                points.shuffle(&mut rng);
+                assert_eq!(distill(&points, chunk_size), expected, "{points:?}");
+            }
+        }
+    }
 }
@@ -0,0 +1,69 @@
+use futures::future::try_join_all;
+use segment::data_types::groups::GroupId;
+use segment::types::{PayloadFieldSchema, PayloadKeyType, PayloadSchemaType};
+
+use crate::operations::point_ops::WriteOrdering;
+use crate::operations::types::CollectionResult;
+use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use crate::shards::shard_holder::LockedShardHolder;
+
+/// Type of the payload index for the field with the given group keys.
+/// Strings and numbers can't share an index, so nothing is inferred for a mix of them.
+pub(crate) fn infer_index_type<'a>(
+    keys: impl IntoIterator<Item = &'a GroupId>,
+) -> Option<PayloadSchemaType> {
+    let mut index_type = None;
+    for key in keys {
+        let key_type = match key {
+            GroupId::String(_) => PayloadSchemaType::Keyword,
+            GroupId::NumberU64(_) | GroupId::NumberI64(_) => PayloadSchemaType::Integer,
+        };
+        if *index_type.get_or_insert(key_type) != key_type {
+            return None;
+        }
+    }
+    index_type
+}
+
+/// Create the payload index in all shards and wait until it is built
+pub(crate) async fn create_field_index(
+    shards_holder: &LockedShardHolder,
+    field_name: PayloadKeyType,
+    index_type: PayloadSchemaType,
+) -> CollectionResult<()> {
+    let operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name,
+            field_schema: Some(PayloadFieldSchema::FieldType(index_type)),
+        }),
+    );
+    let shards_holder = shards_holder.read().await;
+    let updates =
+        shards_holder
+            .split_by_shard(operation)
+            .into_iter()
+            .map(|(replica_set, operation)| {
+                replica_set.update_with_consistency(operation, true, WriteOrdering::default())
+            });
+    try_join_all(updates).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_index_type() {
+        let strings = [GroupId::from("a"), GroupId::from("b")];
+        assert_eq!(infer_index_type(&strings), Some(PayloadSchemaType::Keyword));
+
+        let numbers = [GroupId::from(1u64), GroupId::from(-1i64)];
+        assert_eq!(infer_index_type(&numbers), Some(PayloadSchemaType::Integer));
+
+        let mixed = [GroupId::from(1u64), GroupId::from("a")];
+        assert_eq!(infer_index_type(&mixed), None);
+
+        assert_eq!(infer_index_type(std::iter::empty()), None);
+    }
+}
@@ -1,13 +1,20 @@
 use futures::Future;
 use itertools::Itertools;
+use segment::types::{WithPayloadInterface, WithVector};
 use tokio::sync::RwLockReadGuard;
 
-use super::group_by::{group_by, GroupRequest};
+use super::group_by::{group_by_with_stats, GroupRequest, SourceRequest};
 use crate::collection::Collection;
+use crate::common::payload_size_limit::{truncate_record_payloads, truncate_scored_payloads};
+use crate::common::request_filter_cache::with_request_filter_cache;
 use crate::lookup::lookup_ids;
 use crate::lookup::types::PseudoId;
 use crate::operations::consistency_params::ReadConsistency;
-use crate::operations::types::{CollectionResult, PointGroup};
+use crate::operations::types::{
+    CollectionError, CollectionResult, GroupsResult, LookupLocation, PointGroup, PointRequest,
+    Record, Warning,
+};
+use crate::read_cache::CachedOperation;
 use crate::shards::shard::ShardId;
 
 /// Builds on top of the group_by function to add lookup and possibly other features
@@ -51,15 +58,72 @@ where
     }
 
     pub async fn execute(self) -> CollectionResult<Vec<PointGroup>> {
-        let mut groups = group_by(
-            self.group_by.clone(),
-            self.collection,
-            self.collection_by_name.clone(),
-            self.read_consistency,
-            self.shard_selection,
+        self.execute_with_details()
+            .await
+            .map(|result| result.groups)
+    }
+
+    /// Executes the request, along with the warnings about it.
+    /// Statistics of the grouping are returned if the request asks for them
+    pub async fn execute_with_details(self) -> CollectionResult<GroupsResult> {
+        let collection = self.collection;
+        // Lookups read other collections, which updates don't invalidate the cache
+        let is_cacheable = matches!(
+            self.group_by.source,
+            SourceRequest::Search(_) | SourceRequest::Query(_)
+        ) && self.group_by.with_lookup.is_none();
+        let cache_key = if is_cacheable {
+            collection
+                .read_cache_key(
+                    CachedOperation::Group,
+                    &self.group_by,
+                    self.read_consistency,
+                    self.shard_selection,
+                )
+                .await
+        } else {
+            None
+        };
+        collection
+            .cached_read(cache_key, self.execute_uncached())
+            .await
+    }
+
+    async fn execute_uncached(self) -> CollectionResult<GroupsResult> {
+        let group_by_field = self.group_by.group_by.clone();
+        let is_unindexed = self
+            .collection
+            .is_group_by_field_unindexed(&group_by_field, self.shard_selection)
+            .await?;
+
+        let (mut groups, stats) = with_request_filter_cache(
+            Some(self.group_by.base_filter()),
+            group_by_with_stats(
+                self.group_by.clone(),
+                self.collection,
+                self.collection_by_name.clone(),
+                self.read_consistency,
+                self.shard_selection,
+            ),
         )
         .await?;
 
+        if let Some(examples) = self.lookup_examples().await? {
+            groups
+                .iter_mut()
+                .for_each(|group| group.examples = Some(examples.clone()));
+        }
+
+        let stats = self.group_by.with_stats.then_some(stats);
+
+        let mut warnings = Vec::new();
+        if is_unindexed {
+            warnings.push(Warning::group_by_field_not_indexed(&group_by_field));
+            self.collection
+                .auto_index_group_by_field(&group_by_field, groups.iter().map(|group| &group.id))
+                .await;
+        }
+
         if let Some(lookup) = self.group_by.with_lookup {
             let mut lookups = {
                 let pseudo_ids = groups
@@ -71,7 +135,7 @@ where
                 lookup_ids(
                     lookup,
                     pseudo_ids,
-                    self.collection_by_name,
+                    self.collection_by_name.clone(),
                     self.read_consistency,
                     self.shard_selection,
                 )
@@ -84,6 +148,65 @@ where
             });
         }
 
-        Ok(groups)
+        let mut max_payload_return_bytes = self.group_by.max_payload_return_bytes;
+        if let Some(config) = self
+            .collection
+            .config_for_defaults(self.shard_selection)
+            .await
+        {
+            config.apply_payload_size_default(&mut max_payload_return_bytes);
+        }
+        for group in &mut groups {
+            truncate_scored_payloads(&mut group.hits, max_payload_return_bytes);
+            if let Some(lookup) = &mut group.lookup {
+                truncate_record_payloads(std::slice::from_mut(lookup), max_payload_return_bytes);
+            }
+        }
+
+        Ok(GroupsResult {
+            groups,
+            stats,
+            warnings,
+        })
+    }
+
+    /// Retrieves the examples of the recommendation from the `lookup_from` collection,
+    /// if its `copy_payload_key` is set. Only the value of this key is kept in the payload.
+    async fn lookup_examples(&self) -> CollectionResult<Option<Vec<Record>>> {
+        let SourceRequest::Recommend(request) = &self.group_by.source else {
+            return Ok(None);
+        };
+        let Some(LookupLocation {
+            collection: collection_name,
+            copy_payload_key: Some(payload_key),
+            ..
+        }) = &request.lookup_from
+        else {
+            return Ok(None);
+        };
+
+        let collection = (self.collection_by_name)(collection_name.clone())
+            .await
+            .ok_or_else(|| CollectionError::LookupCollectionMissing {
+                name: collection_name.clone(),
+            })?;
+
+        let point_request = PointRequest {
+            ids: request
+                .positive
+                .iter()
+                .chain(&request.negative)
+                .cloned()
+                .collect(),
+            with_payload: Some(WithPayloadInterface::Fields(vec![payload_key.clone()])),
+            with_vector: WithVector::Bool(false),
+            with_timestamps: false,
+            max_payload_return_bytes: None,
+        };
+        let examples = collection
+            .retrieve(point_request, self.read_consistency, self.shard_selection)
+            .await?;
+
+        Ok(Some(examples))
     }
 }
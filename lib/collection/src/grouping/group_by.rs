@@ -1,38 +1,62 @@
 use std::collections::HashMap;
 use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
 
+use futures::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
+use segment::data_types::groups::GroupId;
 use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::types::{
-    AnyVariants, Condition, FieldCondition, Filter, Match, ScoredPoint, WithPayloadInterface,
-    WithVector,
+    AnyVariants, Condition, FieldCondition, Filter, Match, Order, ScoreType, ScoredPoint,
+    WithPayloadInterface, WithVector,
 };
+use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::RwLockReadGuard;
+use tracing::Instrument;
 
-use super::aggregator::GroupsAggregator;
+use super::aggregator::{group_keys, GroupsAggregator};
+use super::types::Group;
 use crate::collection::Collection;
+use crate::common::measured_operation::record_requests;
+use crate::config::CollectionConfig;
 use crate::lookup::WithLookup;
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::strict_mode::StrictModeRequest;
 use crate::operations::types::{
-    BaseGroupRequest, CollectionError, CollectionResult, PointGroup, RecommendGroupsRequest,
-    RecommendRequest, SearchGroupsRequest, SearchRequest, UsingVector,
+    BaseGroupRequest, CollectionError, CollectionResult, GroupByStats, OrderedQueryRequest,
+    PointGroup, QueryGroupsRequest, RecommendGroupsRequest, RecommendRequest,
+    RecommendRequestBatch, Record, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
+    UsingVector,
 };
-use crate::recommendations::recommend_by;
+use crate::ordered_query::query_ordered;
+use crate::recommendations::recommend_single_by;
 use crate::shards::shard::ShardId;
+use crate::slow_log::RequestDetails;
+use crate::telemetry::CollectionOperationType;
 
 const MAX_GET_GROUPS_REQUESTS: usize = 5;
 const MAX_GROUP_FILLING_REQUESTS: usize = 5;
-
-#[derive(Clone, Debug)]
+/// Oversampling of the requests to find the groups, which fetch `limit * group_size` points
+const DEFAULT_GROUP_OVERSAMPLING: f32 = 1.0;
+/// Oversampling, up to which it is doubled if the requests find too few groups
+const MAX_GROUP_OVERSAMPLING: f32 = 16.0;
+/// Max number of the requests for the explicitly given groups, executed concurrently
+const MAX_CONCURRENT_GROUP_KEY_REQUESTS: usize = 8;
+
+#[derive(Clone, Debug, Serialize)]
 pub enum SourceRequest {
     Search(SearchRequest),
     Recommend(RecommendRequest),
+    /// Points in the order of a payload field, the scores of the hits are the values of the field
+    Query(OrderedQueryRequest),
 }
 
 impl SourceRequest {
-    fn vector_field_name(&self) -> &str {
-        match self {
+    /// Order of the scores of the hits, if the higher ones are better or the lower ones
+    async fn score_ordering(&self, collection: &Collection) -> CollectionResult<Order> {
+        let vector_name = match self {
             SourceRequest::Search(request) => request.vector.get_name(),
             SourceRequest::Recommend(request) => {
                 if let Some(UsingVector::Name(name)) = &request.using {
@@ -41,16 +65,45 @@ impl SourceRequest {
                     DEFAULT_VECTOR_NAME
                 }
             }
+            SourceRequest::Query(request) => return Ok(request.order_by.direction.as_order()),
+        };
+        let collection_params = collection.collection_config.read().await;
+        let distance = collection_params.params.get_distance(vector_name)?;
+        Ok(distance.distance_order())
+    }
+
+    fn filter(&self) -> Option<&Filter> {
+        match self {
+            SourceRequest::Search(request) => request.filter.as_ref(),
+            SourceRequest::Recommend(request) => request.filter.as_ref(),
+            SourceRequest::Query(request) => request.filter.as_ref(),
         }
     }
 
-    fn merge_filter(&mut self, filter: &Filter) {
+    fn apply_search_defaults(&mut self, config: &CollectionConfig) {
         match self {
             SourceRequest::Search(request) => {
-                request.filter = Some(request.filter.clone().unwrap_or_default().merge(filter))
+                config.apply_search_defaults(
+                    &mut request.params,
+                    &mut request.with_payload,
+                    &mut request.with_vector,
+                );
+                config.apply_default_vector(&mut request.vector);
             }
             SourceRequest::Recommend(request) => {
-                request.filter = Some(request.filter.clone().unwrap_or_default().merge(filter))
+                config.apply_search_defaults(
+                    &mut request.params,
+                    &mut request.with_payload,
+                    &mut request.with_vector,
+                );
+                config.apply_default_using(&mut request.using);
+            }
+            SourceRequest::Query(request) => {
+                config.apply_search_defaults(
+                    &mut None,
+                    &mut request.with_payload,
+                    &mut request.with_vector,
+                );
             }
         }
     }
@@ -59,6 +112,7 @@ impl SourceRequest {
         match self {
             SourceRequest::Search(request) => request.with_payload.clone(),
             SourceRequest::Recommend(request) => request.with_payload.clone(),
+            SourceRequest::Query(request) => request.with_payload.clone(),
         }
     }
 
@@ -66,13 +120,40 @@ impl SourceRequest {
         match self {
             SourceRequest::Search(request) => request.with_vector.clone(),
             SourceRequest::Recommend(request) => request.with_vector.clone(),
+            SourceRequest::Query(request) => request.with_vector.clone(),
+        }
+    }
+
+    fn offset(&self) -> usize {
+        match self {
+            SourceRequest::Search(request) => request.offset,
+            SourceRequest::Recommend(request) => request.offset,
+            SourceRequest::Query(request) => request.offset,
+        }
+    }
+
+    /// Reject the fields, which can't be honored by grouping.
+    /// Groups are not paginated, so an offset of the source request would be silently lost
+    fn check_groupable(&self) -> CollectionResult<()> {
+        let offset = self.offset();
+        if offset != 0 {
+            return Err(CollectionError::bad_input(format!(
+                "Offset is not supported by group requests, got {offset}"
+            )));
         }
+        Ok(())
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct GroupRequest {
     /// Request to use (search or recommend)
+    ///
+    /// Its `filter`, `params`, `score_threshold` and the query itself (`vector`, examples,
+    /// `using`, `order_by`) are honored by all internal requests.
+    /// `limit`, `with_payload` and `with_vector` are managed by grouping: internal requests
+    /// fetch only the group key, and the payload and vectors are attached to the final hits.
+    /// `offset` must be 0.
     pub source: SourceRequest,
 
     /// Path to the field to group by
@@ -86,47 +167,109 @@ pub struct GroupRequest {
 
     /// Options for specifying how to use the group id to lookup points in another collection
     pub with_lookup: Option<WithLookup>,
+
+    /// Whether to return the statistics of the internal requests
+    pub with_stats: bool,
+
+    /// Keys of the groups to return, instead of the best groups found by the search
+    pub group_keys: Option<Vec<Value>>,
+
+    /// Limit of the serialized payload of each returned hit and lookup, in bytes
+    pub max_payload_return_bytes: Option<usize>,
+
+    /// Factor of `limit`, applied to the first request to find the groups
+    pub oversampling: f32,
 }
 
 impl GroupRequest {
+    /// Group request, which returns `limit` of the source request as the number of groups.
+    ///
+    /// Source requests with an offset are rejected.
     pub fn with_limit_from_request(
         source: SourceRequest,
         group_by: String,
         group_size: usize,
-    ) -> Self {
+    ) -> CollectionResult<Self> {
+        source.check_groupable()?;
         let limit = match &source {
             SourceRequest::Search(request) => request.limit,
             SourceRequest::Recommend(request) => request.limit,
+            SourceRequest::Query(request) => request.limit,
         };
-        Self {
+        Ok(Self {
             source,
             group_by,
             group_size,
             limit,
             with_lookup: None,
-        }
+            with_stats: false,
+            group_keys: None,
+            max_payload_return_bytes: None,
+            oversampling: DEFAULT_GROUP_OVERSAMPLING,
+        })
     }
 
-    /// Apply a bunch of hacks to make `group_by` field selector work with as `with_payload`.
-    fn _group_by_to_payload_selector(&self, group_by: &str) -> CollectionResult<String> {
-        // Hack 1: `with_payload` only works with top-level fields. (ToDo: maybe fix this?)
-        group_by.split('.').next().map_or_else(
-            || {
-                Err(CollectionError::bad_request(format!(
-                    "Malformed group_by parameter which uses unsupported nested path: {}",
-                    group_by
-                )))
-            },
-            |field| {
-                // Hack 2: `with_payload` doesn't work with `[]` at the end of the field name.
-                // Remove the ending `[]`.
-                Ok(field.strip_suffix("[]").unwrap_or(field).to_owned())
-            },
-        )
+    fn key_not_empty(&self) -> Filter {
+        Filter::new_must_not(Condition::IsEmpty(self.group_by.clone().into()))
+    }
+
+    /// Filter, shared by all of the internal requests.
+    /// They extend it with the conditions on the groups, found so far.
+    pub fn base_filter(&self) -> Filter {
+        self.source
+            .filter()
+            .cloned()
+            .unwrap_or_default()
+            .merge(&self.key_not_empty())
+    }
+
+    /// Internal request, which fetches only the group keys of the points.
+    ///
+    /// The source request is copied into it once, along with its vectors and examples.
+    /// Only the limit and the filter are set for each internal search,
+    /// by [`InternalRequest::update`].
+    pub fn internal_request(&self) -> InternalRequest {
+        // `with_payload` selects nested paths, so only the group key itself is fetched
+        let only_group_by_key = Some(WithPayloadInterface::Fields(vec![self.group_by.clone()]));
+
+        // We're enriching the final results at the end, so we'll keep this minimal
+        match &self.source {
+            SourceRequest::Search(request) => {
+                InternalRequest::Search(Arc::new(SearchRequestBatch {
+                    searches: vec![SearchRequest {
+                        offset: 0,
+                        with_payload: only_group_by_key,
+                        with_vector: None,
+                        ..request.clone()
+                    }],
+                }))
+            }
+            SourceRequest::Recommend(request) => {
+                InternalRequest::Recommend(RecommendRequestBatch {
+                    searches: vec![RecommendRequest {
+                        offset: 0,
+                        with_payload: only_group_by_key,
+                        with_vector: None,
+                        ..request.clone()
+                    }],
+                })
+            }
+            SourceRequest::Query(request) => InternalRequest::Query(OrderedQueryRequest {
+                offset: 0,
+                with_payload: only_group_by_key,
+                with_vector: None,
+                ..request.clone()
+            }),
+        }
     }
 
+    /// Internal search for the points of `limit` groups.
+    /// `extra_filter` has the conditions on the groups, found so far.
     async fn r#do<'a, F, Fut>(
         &self,
+        internal_request: &mut InternalRequest,
+        limit: usize,
+        extra_filter: Option<&Filter>,
         collection: &Collection,
         // only used for recommend
         collection_by_name: F,
@@ -137,41 +280,91 @@ impl GroupRequest {
         F: Fn(String) -> Fut,
         Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
     {
-        let include_group_by = self._group_by_to_payload_selector(&self.group_by)?;
-
-        let only_group_by_key = Some(WithPayloadInterface::Fields(vec![include_group_by]));
+        // Fail fast between the internal requests, if the collection is deleted meanwhile
+        collection.check_not_deleted()?;
 
-        let key_not_empty = Filter::new_must_not(Condition::IsEmpty(self.group_by.clone().into()));
-
-        match self.source.clone() {
-            SourceRequest::Search(mut request) => {
-                request.limit = self.limit * self.group_size;
-
-                request.filter = Some(request.filter.unwrap_or_default().merge(&key_not_empty));
-
-                // We're enriching the final results at the end, so we'll keep this minimal
-                request.with_payload = only_group_by_key;
-                request.with_vector = None;
+        let filter = match extra_filter {
+            Some(extra_filter) => self.base_filter().merge(extra_filter),
+            None => self.base_filter(),
+        };
+        internal_request.update(limit * self.group_size, filter);
 
+        let result = match internal_request {
+            InternalRequest::Search(request_batch) => {
                 collection
-                    .search(request, read_consistency, shard_selection)
+                    .search_shared(request_batch, read_consistency, shard_selection)
                     .await
             }
-            SourceRequest::Recommend(mut request) => {
-                request.limit = self.limit * self.group_size;
+            InternalRequest::Recommend(request_batch) => {
+                recommend_single_by(
+                    request_batch,
+                    collection,
+                    collection_by_name,
+                    read_consistency,
+                )
+                .await
+            }
+            InternalRequest::Query(request) => query_ordered(
+                request.clone(),
+                collection,
+                read_consistency,
+                shard_selection,
+            )
+            .await
+            .map(|records| records.into_iter().map(scored_point_from_record).collect()),
+        };
 
-                request.filter = Some(request.filter.unwrap_or_default().merge(&key_not_empty));
+        // Errors of the shards, dropped along with the collection, are reported as `NotFound`
+        result.map_err(|err| collection.check_not_deleted().err().unwrap_or(err))
+    }
+}
 
-                // We're enriching the final results at the end, so we'll keep this minimal
-                request.with_payload = only_group_by_key;
-                request.with_vector = None;
+/// Request of the internal searches of grouping, reused between them.
+/// It is built by [`GroupRequest::internal_request`].
+pub enum InternalRequest {
+    /// Shared with the shards, which search by it
+    Search(Arc<SearchRequestBatch>),
+    Recommend(RecommendRequestBatch),
+    /// Ordered queries have no vectors, so the request is copied for each of them
+    Query(OrderedQueryRequest),
+}
 
-                recommend_by(request, collection, collection_by_name, read_consistency).await
+impl InternalRequest {
+    /// Sets the limit of points and the filter of the next internal search
+    pub fn update(&mut self, limit: usize, filter: Filter) {
+        match self {
+            InternalRequest::Search(request_batch) => {
+                // Copied only if the shards of the previous search still hold the request
+                let request = &mut Arc::make_mut(request_batch).searches[0];
+                request.limit = limit;
+                request.filter = Some(filter);
+            }
+            InternalRequest::Recommend(request_batch) => {
+                let request = &mut request_batch.searches[0];
+                request.limit = limit;
+                request.filter = Some(filter);
+            }
+            InternalRequest::Query(request) => {
+                request.limit = limit;
+                request.filter = Some(filter);
             }
         }
     }
 }
 
+/// Hit of an ordered query, scored by the value of the `order_by` field
+fn scored_point_from_record(record: Record) -> ScoredPoint {
+    ScoredPoint {
+        id: record.id,
+        version: 0,
+        score: record.order_value.unwrap_or_default() as ScoreType,
+        payload: record.payload,
+        vector: record.vector,
+        payload_truncation: record.payload_truncation,
+        vector_slice: record.vector_slice,
+    }
+}
+
 impl From<SearchGroupsRequest> for GroupRequest {
     fn from(request: SearchGroupsRequest) -> Self {
         let SearchGroupsRequest {
@@ -181,12 +374,18 @@ impl From<SearchGroupsRequest> for GroupRequest {
             with_payload,
             with_vector,
             score_threshold,
+            with_usage: _,
+            allow_partial_results,
             group_request:
                 BaseGroupRequest {
                     group_by,
                     group_size,
                     limit,
                     with_lookup: with_lookup_interface,
+                    with_group_by_stats,
+                    group_keys,
+                    max_payload_return_bytes,
+                    group_oversampling,
                 },
         } = request;
 
@@ -199,6 +398,10 @@ impl From<SearchGroupsRequest> for GroupRequest {
             with_payload,
             with_vector,
             score_threshold,
+            with_usage: false,
+            allow_partial_results,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
         };
 
         GroupRequest {
@@ -207,6 +410,10 @@ impl From<SearchGroupsRequest> for GroupRequest {
             group_size: group_size as usize,
             limit: limit as usize,
             with_lookup: with_lookup_interface.map(Into::into),
+            with_stats: with_group_by_stats,
+            group_keys,
+            max_payload_return_bytes,
+            oversampling: group_oversampling.unwrap_or(DEFAULT_GROUP_OVERSAMPLING),
         }
     }
 }
@@ -229,6 +436,10 @@ impl From<RecommendGroupsRequest> for GroupRequest {
                     group_size,
                     limit,
                     with_lookup: with_lookup_interface,
+                    with_group_by_stats,
+                    group_keys,
+                    max_payload_return_bytes,
+                    group_oversampling,
                 },
         } = request;
 
@@ -252,6 +463,53 @@ impl From<RecommendGroupsRequest> for GroupRequest {
             group_size: group_size as usize,
             limit: limit as usize,
             with_lookup: with_lookup_interface.map(Into::into),
+            with_stats: with_group_by_stats,
+            group_keys,
+            max_payload_return_bytes,
+            oversampling: group_oversampling.unwrap_or(DEFAULT_GROUP_OVERSAMPLING),
+        }
+    }
+}
+
+impl From<QueryGroupsRequest> for GroupRequest {
+    fn from(request: QueryGroupsRequest) -> Self {
+        let QueryGroupsRequest {
+            filter,
+            order_by,
+            with_payload,
+            with_vector,
+            group_request:
+                BaseGroupRequest {
+                    group_by,
+                    group_size,
+                    limit,
+                    with_lookup: with_lookup_interface,
+                    with_group_by_stats,
+                    group_keys,
+                    max_payload_return_bytes,
+                    group_oversampling,
+                },
+        } = request;
+
+        let query = OrderedQueryRequest {
+            filter,
+            order_by,
+            limit: 0,
+            offset: 0,
+            with_payload,
+            with_vector,
+        };
+
+        GroupRequest {
+            source: SourceRequest::Query(query),
+            group_by,
+            group_size: group_size as usize,
+            limit: limit as usize,
+            with_lookup: with_lookup_interface.map(Into::into),
+            with_stats: with_group_by_stats,
+            group_keys,
+            max_payload_return_bytes,
+            oversampling: group_oversampling.unwrap_or(DEFAULT_GROUP_OVERSAMPLING),
         }
     }
 }
@@ -269,118 +527,360 @@ where
     F: Fn(String) -> Fut + Clone,
     Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
 {
-    let score_ordering = {
-        let vector_name = request.source.vector_field_name();
-        let collection_params = collection.collection_config.read().await;
-        let vector_params = collection_params.params.get_vector_params(vector_name)?;
-        vector_params.distance.distance_order()
-    };
+    group_by_with_stats(
+        request,
+        collection,
+        collection_by_name,
+        read_consistency,
+        shard_selection,
+    )
+    .await
+    .map(|(groups, _stats)| groups)
+}
 
-    let mut aggregator = GroupsAggregator::new(
-        request.limit,
-        request.group_size,
-        request.group_by.clone(),
-        score_ordering,
-    );
-
-    // Try to complete amount of groups
-    let mut needs_filling = true;
-    for _ in 0..MAX_GET_GROUPS_REQUESTS {
-        let mut request = request.clone();
-
-        let source = &mut request.source;
-
-        // construct filter to exclude already found groups
-        let full_groups = aggregator.keys_of_filled_groups();
-        if !full_groups.is_empty() {
-            let except_any = except_on(&request.group_by, full_groups);
-            if !except_any.is_empty() {
-                let exclude_groups = Filter {
-                    must: Some(except_any),
-                    ..Default::default()
+/// Same as [`group_by`], but also returns the statistics of the internal requests.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        collection = %collection.id,
+        group_by = %request.group_by,
+        limit = request.limit,
+        group_size = request.group_size,
+    )
+)]
+pub async fn group_by_with_stats<'a, F, Fut>(
+    mut request: GroupRequest,
+    collection: &Collection,
+    // Obligatory for recommend
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> CollectionResult<(Vec<PointGroup>, GroupByStats)>
+where
+    F: Fn(String) -> Fut + Clone,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    request.source.check_groupable()?;
+    if let Some(config) = collection.config_for_defaults(shard_selection).await {
+        request.source.apply_search_defaults(&config);
+        if let SourceRequest::Search(search) = &request.source {
+            config.params.check_search_vector(&search.vector)?;
+        }
+        if let Some(with_vector) = request.source.with_vector() {
+            config.params.check_with_vector(&with_vector)?;
+        }
+    }
+    collection
+        .check_strict_mode([StrictModeRequest::from(&request)], shard_selection)
+        .await?;
+    collection
+        .check_group_by_field(&request.group_by, shard_selection)
+        .await?;
+    if let SourceRequest::Query(query) = &request.source {
+        collection
+            .check_order_by_field(&query.order_by.key, shard_selection)
+            .await?;
+    }
+    let group_key_conditions = request
+        .group_keys
+        .as_deref()
+        .map(|keys| group_key_conditions(&request.group_by, keys))
+        .transpose()?;
+    collection
+        .measure_operation(CollectionOperationType::GroupBy, shard_selection, async {
+            let start = Instant::now();
+            let mut internal_searches = 0;
+            let mut stats = GroupByStats::default();
+            record_requests(|| {
+                let source_details = match &request.source {
+                    SourceRequest::Search(request) => RequestDetails::from(request),
+                    SourceRequest::Recommend(request) => RequestDetails::from(request),
+                    SourceRequest::Query(request) => RequestDetails::from(request),
                 };
-                source.merge_filter(&exclude_groups);
+                vec![RequestDetails {
+                    limit: Some(request.limit),
+                    ..source_details
+                }]
+            });
+
+            if let Some(conditions) = group_key_conditions {
+                let groups = search_groups_of_keys(
+                    &request,
+                    conditions,
+                    collection,
+                    collection_by_name.clone(),
+                    read_consistency,
+                    shard_selection,
+                    &mut stats,
+                )
+                .await?;
+
+                {
+                    let mut telemetry = collection.operations_telemetry.lock();
+                    let group_by_telemetry = &mut telemetry.group_by;
+                    group_by_telemetry.durations.observe(start.elapsed());
+                    group_by_telemetry.internal_searches += stats.filling_requests as u64;
+                }
+
+                let groups = enrich_groups(groups, &request, collection, read_consistency).await?;
+                return Ok((groups, stats));
             }
-        }
 
-        // exclude already aggregated points
-        let ids = aggregator.ids().clone();
-        if !ids.is_empty() {
-            let exclude_ids = Filter::new_must_not(Condition::HasId(ids.into()));
-            source.merge_filter(&exclude_ids);
-        }
+            let score_ordering = request.source.score_ordering(collection).await?;
+
+            let mut aggregator = GroupsAggregator::new(
+                request.limit,
+                request.group_size,
+                request.group_by.clone(),
+                score_ordering,
+            );
+
+            // Try to complete amount of groups
+            let mut needs_filling = true;
+            let mut oversampling = request.oversampling;
+            let mut internal_request = request.internal_request();
+            for groups_request in 0..MAX_GET_GROUPS_REQUESTS {
+                let mut extra_filter = Filter::default();
+
+                // construct filter to exclude already found groups
+                let full_groups = aggregator.keys_of_filled_groups();
+                if !full_groups.is_empty() {
+                    let except_any = except_on(&request.group_by, full_groups, &mut stats);
+                    if !except_any.is_empty() {
+                        let exclude_groups = Filter {
+                            must: Some(except_any),
+                            ..Default::default()
+                        };
+                        extra_filter = extra_filter.merge(&exclude_groups);
+                    }
+                }
 
-        let points = request
-            .r#do(
-                collection,
-                collection_by_name.clone(),
-                read_consistency,
-                shard_selection,
-            )
-            .await?;
+                // exclude already aggregated points
+                let ids = aggregator.ids();
+                if !ids.is_empty() {
+                    let exclude_ids = Filter::new_must_not(Condition::HasId(ids.into()));
+                    extra_filter = extra_filter.merge(&exclude_ids);
+                }
 
-        if points.is_empty() {
-            break;
-        }
+                internal_searches += 1;
+                stats.groups_requests += 1;
+                stats.oversampling_factors.push(oversampling);
+                let span = tracing::info_span!(
+                    "groups_request",
+                    iteration = groups_request,
+                    points = tracing::field::Empty,
+                );
+                let points = request
+                    .r#do(
+                        &mut internal_request,
+                        oversampled_limit(request.limit, oversampling),
+                        Some(&extra_filter),
+                        collection,
+                        collection_by_name.clone(),
+                        read_consistency,
+                        shard_selection,
+                    )
+                    .instrument(span.clone())
+                    .await?;
+                span.record("points", points.len());
+                stats.fetched_points.push(points.len());
+
+                if points.is_empty() {
+                    break;
+                }
 
-        aggregator.add_points(&points);
+                aggregator.add_points(&points);
 
-        // TODO: should we break early if we have some amount of "enough" groups?
-        if aggregator.len_of_filled_best_groups() >= request.limit {
-            needs_filling = false;
-            break;
-        }
-    }
+                // TODO: should we break early if we have some amount of "enough" groups?
+                if aggregator.len_of_filled_best_groups() >= request.limit {
+                    needs_filling = false;
+                    break;
+                }
 
-    // Try to fill up groups
-    if needs_filling {
-        for _ in 0..MAX_GROUP_FILLING_REQUESTS {
-            let mut request = request.clone();
+                if groups_request + 1 == MAX_GET_GROUPS_REQUESTS {
+                    stats.groups_requests_limit_reached = true;
+                }
 
-            let source = &mut request.source;
+                oversampling = next_oversampling(oversampling, aggregator.len(), request.limit);
+            }
 
-            // construct filter to only include unsatisfied groups
-            let unsatisfied_groups = aggregator.keys_of_unfilled_best_groups();
-            let match_any = match_on(&request.group_by, unsatisfied_groups);
-            if !match_any.is_empty() {
-                let include_groups = Filter {
-                    must: Some(match_any),
-                    ..Default::default()
-                };
-                source.merge_filter(&include_groups);
+            // Try to fill up groups
+            let mut truncated_groups = 0;
+            if needs_filling {
+                for filling_request in 0..MAX_GROUP_FILLING_REQUESTS {
+                    let mut extra_filter = Filter::default();
+
+                    // construct filter to only include unsatisfied groups
+                    let unsatisfied_groups = aggregator.keys_of_unfilled_best_groups();
+                    let match_any = match_on(&request.group_by, unsatisfied_groups, &mut stats);
+                    if !match_any.is_empty() {
+                        let include_groups = Filter {
+                            must: Some(match_any),
+                            ..Default::default()
+                        };
+                        extra_filter = extra_filter.merge(&include_groups);
+                    }
+
+                    // exclude already aggregated points
+                    let ids = aggregator.ids();
+                    if !ids.is_empty() {
+                        let exclude_ids = Filter::new_must_not(Condition::HasId(ids.into()));
+                        extra_filter = extra_filter.merge(&exclude_ids);
+                    }
+
+                    internal_searches += 1;
+                    stats.filling_requests += 1;
+                    let span = tracing::info_span!(
+                        "group_filling_request",
+                        iteration = filling_request,
+                        points = tracing::field::Empty,
+                    );
+                    let points = request
+                        .r#do(
+                            &mut internal_request,
+                            request.limit,
+                            Some(&extra_filter),
+                            collection,
+                            collection_by_name.clone(),
+                            read_consistency,
+                            shard_selection,
+                        )
+                        .instrument(span.clone())
+                        .await?;
+                    span.record("points", points.len());
+                    stats.fetched_points.push(points.len());
+
+                    if points.is_empty() {
+                        break;
+                    }
+
+                    aggregator.add_points(&points);
+
+                    if aggregator.len_of_filled_best_groups() >= request.limit {
+                        break;
+                    }
+
+                    if filling_request + 1 == MAX_GROUP_FILLING_REQUESTS {
+                        truncated_groups = aggregator.keys_of_unfilled_best_groups().len() as u64;
+                        stats.filling_requests_limit_reached = true;
+                    }
+                }
             }
 
-            // exclude already aggregated points
-            let ids = aggregator.ids().clone();
-            if !ids.is_empty() {
-                let exclude_ids = Filter::new_must_not(Condition::HasId(ids.into()));
-                source.merge_filter(&exclude_ids);
+            {
+                let mut telemetry = collection.operations_telemetry.lock();
+                let group_by_telemetry = &mut telemetry.group_by;
+                group_by_telemetry.durations.observe(start.elapsed());
+                group_by_telemetry.internal_searches += internal_searches;
+                group_by_telemetry.truncated_groups += truncated_groups;
             }
 
-            let points = request
+            stats.groups_created = aggregator.len();
+
+            // extract best results
+            let groups = aggregator.distill();
+            stats.groups_filled = groups
+                .iter()
+                .filter(|group| group.hits.len() == request.group_size)
+                .count();
+
+            let groups = enrich_groups(groups, &request, collection, read_consistency).await?;
+            Ok((groups, stats))
+        })
+        .await
+}
+
+/// Amount of groups to fetch the points of, at least one
+fn oversampled_limit(limit: usize, oversampling: f32) -> usize {
+    ((limit as f32 * oversampling).ceil() as usize).max(1)
+}
+
+/// Oversampling of the next request to find the groups.
+/// It is doubled, if the points of a few groups crowd out the others,
+/// but a larger factor, given by the user, is kept as is.
+fn next_oversampling(oversampling: f32, groups_found: usize, limit: usize) -> f32 {
+    if groups_found * 2 < limit {
+        (oversampling * 2.0)
+            .min(MAX_GROUP_OVERSAMPLING)
+            .max(oversampling)
+    } else {
+        oversampling
+    }
+}
+
+/// Searches for the best points of each of the explicitly given groups, with a request per group.
+/// Groups are returned in the order of the keys, including the groups without points.
+async fn search_groups_of_keys<'a, F, Fut>(
+    request: &GroupRequest,
+    conditions: Vec<(GroupId, Condition)>,
+    collection: &Collection,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+    stats: &mut GroupByStats,
+) -> CollectionResult<Vec<Group>>
+where
+    F: Fn(String) -> Fut + Clone,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    let requests = conditions.into_iter().map(|(key, condition)| {
+        let collection_by_name = collection_by_name.clone();
+        async move {
+            // Each request returns `limit * group_size` points, so one group is filled up
+            let key_filter = Filter::new_must(condition);
+            // Requests of the groups are concurrent, so each of them has an own copy
+            let mut internal_request = request.internal_request();
+            let hits = request
                 .r#do(
+                    &mut internal_request,
+                    1,
+                    Some(&key_filter),
                     collection,
-                    collection_by_name.clone(),
+                    collection_by_name,
                     read_consistency,
                     shard_selection,
                 )
                 .await?;
+            let key = stored_group_key(&request.group_by, key, &hits);
+            CollectionResult::Ok(Group { hits, key })
+        }
+    });
 
-            if points.is_empty() {
-                break;
-            }
+    let groups: Vec<Group> = stream::iter(requests)
+        .buffered(MAX_CONCURRENT_GROUP_KEY_REQUESTS)
+        .try_collect()
+        .await?;
+
+    stats.filling_requests = groups.len();
+    stats.fetched_points = groups.iter().map(|group| group.hits.len()).collect();
+    stats.groups_created = groups.len();
+    stats.groups_filled = groups
+        .iter()
+        .filter(|group| group.hits.len() == request.group_size)
+        .count();
 
-            aggregator.add_points(&points);
+    Ok(groups)
+}
 
-            if aggregator.len_of_filled_best_groups() >= request.limit {
-                break;
-            }
-        }
+/// Fills the hits of the groups with the payload and vectors, requested by the client
+async fn enrich_groups(
+    mut groups: Vec<Group>,
+    request: &GroupRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<PointGroup>> {
+    let is_payload_required = request
+        .source
+        .with_payload()
+        .map_or(false, |with_payload| with_payload.is_required());
+    let is_vector_required = request
+        .source
+        .with_vector()
+        .map_or(false, |with_vector| with_vector.is_some());
+    if !is_payload_required && !is_vector_required {
+        return Ok(bare_point_groups(groups));
     }
 
-    // extract best results
-    let mut groups = aggregator.distill();
-
     // flatten results
     let bare_points = groups
         .iter()
@@ -408,22 +908,95 @@ where
         .for_each(|group| group.hydrate_from(&enriched_points));
 
     // turn into output form
-    let groups = groups.into_iter().map(PointGroup::from).collect();
+    Ok(groups.into_iter().map(PointGroup::from).collect())
+}
 
-    Ok(groups)
+/// Groups with the hits as they are found, without the payload and vectors.
+/// The payload of the hits has the group key only, it is not returned, as the group has it anyway
+fn bare_point_groups(groups: Vec<Group>) -> Vec<PointGroup> {
+    groups
+        .into_iter()
+        .map(|mut group| {
+            for hit in &mut group.hits {
+                hit.payload = None;
+                hit.vector = None;
+            }
+            PointGroup::from(group)
+        })
+        .collect()
+}
+
+/// Key of the group, as it is stored in the payload of its points.
+/// Differs from the requested key, if a numeric key was requested in the stringified form
+fn stored_group_key(group_by: &str, key: GroupId, hits: &[ScoredPoint]) -> GroupId {
+    let Some(number) = key.unstringified() else {
+        return key;
+    };
+    let is_stored = hits.iter().any(|hit| {
+        group_keys(hit.payload.as_ref(), group_by).map_or(false, |keys| keys.contains(&number))
+    });
+    if is_stored {
+        number
+    } else {
+        key
+    }
+}
+
+/// Conditions to select the points of each of the requested groups.
+/// Keys, which can't be matched, are rejected rather than dropped, so no group is left out silently
+///
+/// Stringified numeric keys match both the string and the number
+fn group_key_conditions(
+    group_by: &str,
+    keys: &[Value],
+) -> CollectionResult<Vec<(GroupId, Condition)>> {
+    let key_condition =
+        |key_match: Match| Condition::Field(FieldCondition::new_match(group_by, key_match));
+    keys.iter()
+        .map(|value| {
+            let key = GroupId::try_from(value).ok();
+            let condition = match &key {
+                Some(string_key @ GroupId::String(keyword)) => {
+                    let keyword_condition = key_condition(Match::from(keyword.clone()));
+                    let number = string_key.unstringified().and_then(|n| n.as_i64());
+                    Some(match number {
+                        Some(number) => Condition::Filter(Filter {
+                            should: Some(vec![
+                                keyword_condition,
+                                key_condition(Match::from(number)),
+                            ]),
+                            ..Default::default()
+                        }),
+                        None => keyword_condition,
+                    })
+                }
+                Some(number) => number
+                    .as_i64()
+                    .map(|number| key_condition(Match::from(number))),
+                None => None,
+            };
+            match (key, condition) {
+                (Some(key), Some(condition)) => Ok((key, condition)),
+                _ => Err(CollectionError::UnsupportedGroupKey {
+                    field: group_by.to_string(),
+                    key: value.to_string(),
+                }),
+            }
+        })
+        .collect()
 }
 
 /// Uses the set of values to create Match::Except's, if possible
-fn except_on(path: &str, values: Vec<Value>) -> Vec<Condition> {
-    values_to_any_variants(values)
+fn except_on(path: &str, values: Vec<Value>, stats: &mut GroupByStats) -> Vec<Condition> {
+    values_to_any_variants(values, stats)
         .into_iter()
         .map(|v| Condition::Field(FieldCondition::new_match(path, Match::new_except(v))))
         .collect()
 }
 
 /// Uses the set of values to create Match::Any's, if possible
-fn match_on(path: &str, values: Vec<Value>) -> Vec<Condition> {
-    values_to_any_variants(values)
+fn match_on(path: &str, values: Vec<Value>, stats: &mut GroupByStats) -> Vec<Condition> {
+    values_to_any_variants(values, stats)
         .into_iter()
         .map(|any_variants| {
             Condition::Field(FieldCondition::new_match(
@@ -434,22 +1007,25 @@ fn match_on(path: &str, values: Vec<Value>) -> Vec<Condition> {
         .collect()
 }
 
-fn values_to_any_variants(values: Vec<Value>) -> Vec<AnyVariants> {
+/// Values, which are neither strings nor integers fitting into `i64`, are dropped
+fn values_to_any_variants(values: Vec<Value>, stats: &mut GroupByStats) -> Vec<AnyVariants> {
     let mut any_variants = Vec::new();
 
     // gather int values
     let ints = values.iter().filter_map(|v| v.as_i64()).collect_vec();
 
-    if !ints.is_empty() {
-        any_variants.push(AnyVariants::Integers(ints));
-    }
-
     // gather string values
     let strs = values
         .iter()
         .filter_map(|v| v.as_str().map(|s| s.to_owned()))
         .collect_vec();
 
+    stats.dropped_filter_keys += values.len() - ints.len() - strs.len();
+
+    if !ints.is_empty() {
+        any_variants.push(AnyVariants::Integers(ints));
+    }
+
     if !strs.is_empty() {
         any_variants.push(AnyVariants::Keywords(strs));
     }
@@ -459,12 +1035,310 @@ fn values_to_any_variants(values: Vec<Value>) -> Vec<AnyVariants> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     use segment::data_types::groups::GroupId;
-    use segment::types::{Payload, ScoredPoint};
+    use segment::data_types::order_by::OrderBy;
+    use segment::types::{Payload, ScoredPoint, SearchParams};
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::types::ErrorReason;
+
+    #[test]
+    fn test_unsupported_keys_are_dropped_from_filters() {
+        let mut stats = GroupByStats::default();
+        let values = vec![
+            json!(1),
+            json!("a"),
+            json!(u64::MAX),
+            json!(true),
+            json!(-2),
+        ];
+
+        let conditions = match_on("docId", values, &mut stats);
+
+        assert_eq!(conditions.len(), 2);
+        assert_eq!(stats.dropped_filter_keys, 2);
+    }
+
+    #[test]
+    fn test_unsupported_group_keys_are_rejected() {
+        let conditions = group_key_conditions("docId", &[json!("a"), json!(-2), json!(3)]).unwrap();
+        let keys = conditions.into_iter().map(|(key, _)| key).collect_vec();
+        assert_eq!(
+            keys,
+            vec![
+                GroupId::from("a"),
+                GroupId::from(-2i64),
+                GroupId::from(3u64)
+            ],
+        );
+
+        for key in [
+            json!(1.5),
+            json!(true),
+            json!(u64::MAX),
+            json!(["a"]),
+            json!(null),
+        ] {
+            let err = group_key_conditions("docId", &[json!("a"), key]).unwrap_err();
+            assert!(matches!(err, CollectionError::UnsupportedGroupKey { .. }));
+            assert_eq!(err.reason(), Some(ErrorReason::UnsupportedGroupKeyType));
+        }
+    }
+
+    #[test]
+    fn test_stringified_group_keys() {
+        let conditions = group_key_conditions("docId", &[json!("7"), json!("07")]).unwrap();
+        let (key, condition) = &conditions[0];
+        assert_eq!(key, &GroupId::from("7"));
+        let Condition::Filter(filter) = condition else {
+            panic!("filter condition is expected");
+        };
+        assert_eq!(
+            filter.should,
+            Some(vec![
+                Condition::Field(FieldCondition::new_match(
+                    "docId",
+                    Match::from("7".to_string())
+                )),
+                Condition::Field(FieldCondition::new_match("docId", Match::from(7i64))),
+            ]),
+        );
+        // Not a stringified number
+        assert!(matches!(conditions[1].1, Condition::Field(_)));
+
+        let hit = |payload: Value| ScoredPoint {
+            id: 1.into(),
+            version: 0,
+            score: 0.5,
+            payload: Some(Payload::from(payload)),
+            vector: None,
+            payload_truncation: None,
+            vector_slice: None,
+        };
+        // The key is returned in the type of the stored values
+        let hits = [hit(json!({"docId": 7}))];
+        assert_eq!(
+            stored_group_key("docId", GroupId::from("7"), &hits),
+            GroupId::from(7u64)
+        );
+        let hits = [hit(json!({"docId": "7"}))];
+        assert_eq!(
+            stored_group_key("docId", GroupId::from("7"), &hits),
+            GroupId::from("7")
+        );
+        assert_eq!(
+            stored_group_key("docId", GroupId::from("7"), &[]),
+            GroupId::from("7")
+        );
+    }
+
+    #[test]
+    fn test_oversampling_is_doubled_on_few_groups() {
+        assert_eq!(oversampled_limit(10, 1.0), 10);
+        assert_eq!(oversampled_limit(10, 0.25), 3);
+        assert_eq!(oversampled_limit(1, 0.1), 1);
+
+        // Enough groups are found
+        assert_eq!(next_oversampling(1.0, 5, 10), 1.0);
+        // Points of a few groups crowd out the others
+        assert_eq!(next_oversampling(1.0, 4, 10), 2.0);
+        assert_eq!(next_oversampling(0.5, 0, 10), 1.0);
+        assert_eq!(next_oversampling(12.0, 1, 10), MAX_GROUP_OVERSAMPLING);
+        // Larger factors of the user are not capped
+        assert_eq!(next_oversampling(50.0, 1, 10), 50.0);
+    }
+
+    #[test]
+    fn test_internal_request() {
+        let user_filter =
+            Filter::new_must(Condition::HasId(HashSet::from([1.into(), 2.into()]).into()));
+        let search = SearchRequest {
+            vector: vec![0.5; 1536].into(),
+            filter: Some(user_filter.clone()),
+            params: None,
+            limit: 10,
+            offset: 0,
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: Some(WithVector::Bool(true)),
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        let request = GroupRequest::with_limit_from_request(
+            SourceRequest::Search(search.clone()),
+            "docId".to_string(),
+            3,
+        )
+        .unwrap();
 
-    use crate::grouping::types::Group;
+        let extra_filter = Filter::new_must_not(Condition::HasId(HashSet::from([1.into()]).into()));
+        let mut internal_request = request.internal_request();
+        internal_request.update(6, request.base_filter().merge(&extra_filter));
+        let InternalRequest::Search(request_batch) = &internal_request else {
+            panic!("search request is expected");
+        };
+        let internal = &request_batch.searches[0];
+        assert_eq!(internal.limit, 6);
+        assert_eq!(
+            internal.filter,
+            Some(
+                user_filter
+                    .merge(&request.key_not_empty())
+                    .merge(&extra_filter)
+            ),
+        );
+        assert_eq!(
+            internal.with_payload,
+            Some(WithPayloadInterface::Fields(vec!["docId".to_string()])),
+        );
+        assert_eq!(internal.with_vector, None);
+        assert_eq!(internal.vector.get_vector(), search.vector.get_vector());
+
+        // The next search updates the same request in place, as it is not shared anymore
+        let searched_request = Arc::as_ptr(request_batch);
+        internal_request.update(3, request.base_filter());
+        let InternalRequest::Search(request_batch) = &internal_request else {
+            panic!("search request is expected");
+        };
+        assert_eq!(Arc::as_ptr(request_batch), searched_request);
+        assert_eq!(request_batch.searches[0].limit, 3);
+        assert_eq!(
+            request_batch.searches[0].filter,
+            Some(request.base_filter())
+        );
+
+        // The group request itself is left intact
+        let SourceRequest::Search(source) = &request.source else {
+            panic!("search request is expected");
+        };
+        assert_eq!(source.filter, search.filter);
+        assert_eq!(source.limit, 10);
+    }
+
+    #[test]
+    fn test_offset_is_rejected() {
+        let search = SearchRequest {
+            vector: vec![0.5; 4].into(),
+            filter: None,
+            params: None,
+            limit: 10,
+            offset: 5,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        let recommend = RecommendRequest {
+            positive: vec![1.into()],
+            limit: 10,
+            offset: 5,
+            ..Default::default()
+        };
+        let query = OrderedQueryRequest {
+            filter: None,
+            order_by: OrderBy {
+                key: "price".to_string(),
+                direction: Default::default(),
+            },
+            limit: 10,
+            offset: 5,
+            with_payload: None,
+            with_vector: None,
+        };
+
+        for source in [
+            SourceRequest::Search(search),
+            SourceRequest::Recommend(recommend),
+            SourceRequest::Query(query),
+        ] {
+            let result = GroupRequest::with_limit_from_request(source, "docId".to_string(), 3);
+            assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+        }
+    }
+
+    #[test]
+    fn test_internal_request_fields() {
+        let recommend = RecommendRequest {
+            positive: vec![1.into()],
+            negative: vec![2.into()],
+            params: Some(SearchParams {
+                hnsw_ef: Some(128),
+                ..Default::default()
+            }),
+            limit: 10,
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: Some(WithVector::Bool(true)),
+            score_threshold: Some(0.7),
+            using: Some(UsingVector::Name("image".to_string())),
+            ..Default::default()
+        };
+        let mut request = GroupRequest::with_limit_from_request(
+            SourceRequest::Recommend(recommend.clone()),
+            "docId".to_string(),
+            2,
+        )
+        .unwrap();
+        assert_eq!(request.limit, 10);
+
+        // Offset of a request, constructed directly, doesn't reach the internal requests
+        if let SourceRequest::Recommend(source) = &mut request.source {
+            source.offset = 5;
+        }
+        let mut internal_request = request.internal_request();
+        internal_request.update(6, request.base_filter());
+        let InternalRequest::Recommend(request_batch) = &internal_request else {
+            panic!("recommend request is expected");
+        };
+        let internal = &request_batch.searches[0];
+
+        // Managed by grouping
+        assert_eq!(internal.limit, 6);
+        assert_eq!(internal.offset, 0);
+        assert_eq!(
+            internal.with_payload,
+            Some(WithPayloadInterface::Fields(vec!["docId".to_string()])),
+        );
+        assert_eq!(internal.with_vector, None);
+
+        // Honored as is
+        assert_eq!(internal.positive, recommend.positive);
+        assert_eq!(internal.negative, recommend.negative);
+        assert_eq!(internal.params, recommend.params);
+        assert_eq!(internal.score_threshold, Some(0.7));
+        assert!(matches!(&internal.using, Some(UsingVector::Name(name)) if name == "image"));
+        assert_eq!(internal.filter, Some(request.key_not_empty()));
+    }
+
+    #[test]
+    fn test_bare_point_groups() {
+        let groups = vec![Group {
+            hits: vec![ScoredPoint {
+                id: 1.into(),
+                version: 3,
+                score: 0.5,
+                payload: Some(Payload::from(json!({"docId": "a"}))),
+                vector: None,
+                payload_truncation: None,
+                vector_slice: None,
+            }],
+            key: GroupId::from("a"),
+        }];
+
+        let groups = bare_point_groups(groups);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].id, GroupId::from("a"));
+        let hit = &groups[0].hits[0];
+        assert_eq!((hit.id, hit.version, hit.score), (1.into(), 3, 0.5));
+        assert_eq!(hit.payload, None);
+    }
 
     #[test]
     fn test_hydrated_from() {
@@ -480,6 +1354,8 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        payload_truncation: None,
+                        vector_slice: None,
                     },
                     ScoredPoint {
                         id: 2.into(),
@@ -487,6 +1363,8 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        payload_truncation: None,
+                        vector_slice: None,
                     },
                 ],
             ),
@@ -499,6 +1377,8 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        payload_truncation: None,
+                        vector_slice: None,
                     },
                     ScoredPoint {
                         id: 4.into(),
@@ -506,6 +1386,8 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        payload_truncation: None,
+                        vector_slice: None,
                     },
                 ],
             ),
@@ -529,6 +1411,8 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_a.clone()),
                 vector: None,
+                payload_truncation: None,
+                vector_slice: None,
             },
             ScoredPoint {
                 id: 2.into(),
@@ -536,6 +1420,8 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_a.clone()),
                 vector: None,
+                payload_truncation: None,
+                vector_slice: None,
             },
             ScoredPoint {
                 id: 3.into(),
@@ -543,6 +1429,8 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_b.clone()),
                 vector: None,
+                payload_truncation: None,
+                vector_slice: None,
             },
             ScoredPoint {
                 id: 4.into(),
@@ -550,6 +1438,8 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_b.clone()),
                 vector: None,
+                payload_truncation: None,
+                vector_slice: None,
             },
         ];
 
@@ -1,6 +1,8 @@
 mod aggregator;
+pub(crate) mod auto_index;
 mod builder;
 pub mod group_by;
+pub mod scroll_groups;
 mod types;
 
 pub use builder::GroupBy;
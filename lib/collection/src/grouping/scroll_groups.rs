@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+
+use segment::data_types::groups::GroupId;
+use segment::types::{
+    Condition, FieldCondition, Filter, KeywordRange, Match, Order, Range, ScoredPoint,
+    WithPayloadInterface, WithVector,
+};
+
+use super::aggregator::GroupsAggregator;
+use super::types::Group;
+use crate::collection::Collection;
+use crate::common::measured_operation::record_requests;
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::strict_mode::StrictModeRequest;
+use crate::operations::types::{
+    CollectionResult, PointGroup, Record, ScrollGroupsRequest, ScrollGroupsResult,
+};
+use crate::shards::shard::ShardId;
+use crate::slow_log::RequestDetails;
+use crate::telemetry::CollectionOperationType;
+
+/// Amount of points, read from the shards at once
+const SCROLL_PAGE_SIZE: usize = 1000;
+
+/// Largest integer, all integers up to which are exact in `f64`
+const MAX_EXACT_KEY: u64 = 1 << f64::MANTISSA_DIGITS;
+
+/// Aggregator of the groups of a page. All the hits score the same, so the groups are ranked
+/// by their keys, and the hits - by their ids.
+///
+/// The `limit + 1` groups are aggregated: the extra group is not returned, its key is the
+/// offset of the next page.
+fn page_aggregator(
+    grouped_by: String,
+    offset: Option<GroupId>,
+    limit: usize,
+    group_size: usize,
+) -> GroupsAggregator {
+    GroupsAggregator::new(limit + 1, group_size, grouped_by, Order::SmallBetter)
+        .with_min_key(offset)
+}
+
+/// Point, read by the scroll, as a hit of the groups
+fn unscored_hit(record: Record) -> ScoredPoint {
+    ScoredPoint {
+        id: record.id,
+        version: 0,
+        score: 0.0,
+        payload: record.payload,
+        vector: None,
+        payload_truncation: None,
+        vector_slice: None,
+    }
+}
+
+/// Returns the groups of the page and the key of the first group of the next page
+fn split_next_page(mut groups: Vec<Group>, limit: usize) -> (Vec<Group>, Option<GroupId>) {
+    let next_page_offset = if groups.len() > limit {
+        groups.pop().map(|group| group.key)
+    } else {
+        None
+    };
+    (groups, next_page_offset)
+}
+
+/// Condition on the group key, which skips the points without keys between `lower` and `upper`.
+///
+/// Numeric keys go before the string ones, so the range covers the numeric keys, unless it
+/// starts at a string key, and the string keys, unless it ends at a numeric key.
+fn key_range_condition(
+    key: &str,
+    lower: Option<&GroupId>,
+    upper: Option<&GroupId>,
+) -> Option<Condition> {
+    if lower.is_none() && upper.is_none() {
+        return None;
+    }
+    let numeric_range = Condition::Field(FieldCondition::new_range(
+        key,
+        Range {
+            gte: lower.and_then(numeric_bound),
+            lte: upper.and_then(numeric_bound),
+            ..Default::default()
+        },
+    ));
+    let keyword_range = Condition::Field(FieldCondition::new_match(
+        key,
+        Match::new_keyword_range(KeywordRange {
+            gte: lower.and_then(keyword_bound),
+            lte: upper.and_then(keyword_bound),
+        }),
+    ));
+    let has_numeric_keys = !matches!(lower, Some(GroupId::String(_)));
+    let has_string_keys = !matches!(upper, Some(GroupId::NumberU64(_) | GroupId::NumberI64(_)));
+    match (has_numeric_keys, has_string_keys) {
+        (true, true) => Some(Condition::Filter(Filter {
+            should: Some(vec![numeric_range, keyword_range]),
+            min_should: None,
+            must: None,
+            must_not: None,
+        })),
+        (true, false) => Some(numeric_range),
+        (false, _) => Some(keyword_range),
+    }
+}
+
+/// Bound of the range condition for the numeric key.
+/// Keys, which are not exact in `f64`, are not bounded, as the rounded bound might exclude them.
+fn numeric_bound(key: &GroupId) -> Option<f64> {
+    match *key {
+        GroupId::NumberU64(n) if n <= MAX_EXACT_KEY => Some(n as f64),
+        GroupId::NumberI64(n) if n.unsigned_abs() <= MAX_EXACT_KEY => Some(n as f64),
+        _ => None,
+    }
+}
+
+/// Bound of the keyword range condition for the string key
+fn keyword_bound(key: &GroupId) -> Option<String> {
+    match key {
+        GroupId::String(s) => Some(s.clone()),
+        GroupId::NumberU64(_) | GroupId::NumberI64(_) => None,
+    }
+}
+
+/// Groups the points, which match the filter, without any query vector.
+///
+/// The matching points are read in pages, ordered by id, as the smallest keys might be
+/// found anywhere. Points with the keys before the offset, or after the last group found so
+/// far, are skipped by the filter of the reads. The first `group_size` points by id make up
+/// a group.
+pub async fn scroll_groups(
+    mut request: ScrollGroupsRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> CollectionResult<ScrollGroupsResult> {
+    collection
+        .check_strict_mode([StrictModeRequest::from(&request)], shard_selection)
+        .await?;
+    collection
+        .check_group_by_field(&request.group_by, shard_selection)
+        .await?;
+    if let Some(offset) = request.offset.take() {
+        request.offset = Some(
+            collection
+                .group_key_of_field(&request.group_by, offset)
+                .await,
+        );
+    }
+    collection
+        .restrict_read_filters([&mut request.filter], shard_selection)
+        .await;
+    collection
+        .measure_operation(CollectionOperationType::GroupBy, shard_selection, async {
+            record_requests(|| {
+                vec![RequestDetails {
+                    filter: request.filter.clone(),
+                    limit: Some(request.limit as usize),
+                    params: None,
+                }]
+            });
+
+            // Only the group key is read, the final results are enriched at the end
+            let only_group_by_key = WithPayloadInterface::Fields(vec![request.group_by.clone()]);
+            let key_not_empty =
+                Filter::new_must_not(Condition::IsEmpty(request.group_by.clone().into()));
+            let filter = request
+                .filter
+                .clone()
+                .unwrap_or_default()
+                .merge(&key_not_empty);
+
+            let mut aggregator = page_aggregator(
+                request.group_by.clone(),
+                request.offset.clone(),
+                request.limit as usize,
+                request.group_size as usize,
+            );
+
+            let mut offset = None;
+            loop {
+                // Bounds of the keys only narrow down, as the groups are found
+                let page_filter = key_range_condition(
+                    &request.group_by,
+                    request.offset.as_ref(),
+                    aggregator.key_of_last_best_group(),
+                )
+                .map(|condition| filter.merge(&Filter::new_must(condition)));
+                let mut points = collection
+                    .scroll_shards(
+                        offset,
+                        SCROLL_PAGE_SIZE + 1,
+                        &only_group_by_key,
+                        &WithVector::Bool(false),
+                        Some(page_filter.as_ref().unwrap_or(&filter)),
+                        read_consistency,
+                        shard_selection,
+                    )
+                    .await?;
+
+                // The extra point is the first one of the next page
+                let next_offset = if points.len() > SCROLL_PAGE_SIZE {
+                    points.pop().map(|point| point.id)
+                } else {
+                    None
+                };
+
+                let hits: Vec<_> = points.into_iter().map(unscored_hit).collect();
+                aggregator.add_points(&hits);
+
+                match next_offset {
+                    Some(next_offset) => offset = Some(next_offset),
+                    None => break,
+                }
+            }
+
+            let (mut groups, next_page_offset) =
+                split_next_page(aggregator.distill(), request.limit as usize);
+
+            // flatten results
+            let bare_points = groups
+                .iter()
+                .cloned()
+                .flat_map(|group| group.hits)
+                .collect();
+
+            // enrich with payload and vector
+            let with_payload = request
+                .with_payload
+                .clone()
+                .unwrap_or(WithPayloadInterface::Bool(true));
+            let enriched_points: HashMap<_, _> = collection
+                .fill_search_result_with_payload(
+                    bare_points,
+                    Some(with_payload),
+                    request.with_vector.clone(),
+                    read_consistency,
+                    shard_selection,
+                )
+                .await?
+                .into_iter()
+                .map(|point| (point.id, point))
+                .collect();
+
+            groups
+                .iter_mut()
+                .for_each(|group| group.hydrate_from(&enriched_points));
+
+            Ok(ScrollGroupsResult {
+                groups: groups.into_iter().map(PointGroup::from).collect(),
+                next_page_offset,
+            })
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::Payload;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn hit(idx: u64, key: Value) -> ScoredPoint {
+        unscored_hit(Record {
+            id: idx.into(),
+            payload: Some(Payload::from(json!({ "user": key }))),
+            vector: None,
+            last_modified: None,
+            order_value: None,
+            payload_truncation: None,
+            vector_slice: None,
+        })
+    }
+
+    fn keys_and_ids(groups: &[Group]) -> Vec<(GroupId, Vec<u64>)> {
+        groups
+            .iter()
+            .map(|group| {
+                let ids = group
+                    .hits
+                    .iter()
+                    .map(|hit| match hit.id {
+                        segment::types::ExtendedPointId::NumId(id) => id,
+                        segment::types::ExtendedPointId::Uuid(_) => unreachable!(),
+                    })
+                    .collect();
+                (group.key.clone(), ids)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_groups_are_ordered_by_key() {
+        let mut aggregator = page_aggregator("user".to_string(), None, 2, 2);
+        aggregator.add_points(&[
+            hit(1, json!("c")),
+            hit(2, json!("b")),
+            hit(3, json!(["a", "c"])),
+            hit(4, json!("b")),
+            hit(5, json!("b")),
+            hit(6, json!(true)),
+        ]);
+        aggregator.add_points(&[hit(7, json!("a")), hit(8, json!("d"))]);
+
+        let (groups, next_page_offset) = split_next_page(aggregator.distill(), 2);
+
+        assert_eq!(
+            keys_and_ids(&groups),
+            vec![("a".into(), vec![3, 7]), ("b".into(), vec![2, 4])]
+        );
+        assert_eq!(next_page_offset, Some("c".into()));
+    }
+
+    #[test]
+    fn test_groups_start_from_offset() {
+        let mut aggregator = page_aggregator("user".to_string(), Some("c".into()), 2, 1);
+        aggregator.add_points(&[
+            hit(1, json!("c")),
+            hit(2, json!("b")),
+            hit(3, json!(10)),
+            hit(4, json!("d")),
+        ]);
+
+        let (groups, next_page_offset) = split_next_page(aggregator.distill(), 2);
+
+        assert_eq!(
+            keys_and_ids(&groups),
+            vec![("c".into(), vec![1]), ("d".into(), vec![4])]
+        );
+        assert_eq!(next_page_offset, None);
+    }
+
+    #[test]
+    fn test_numeric_keys_go_first() {
+        let mut aggregator = page_aggregator("user".to_string(), None, 3, 1);
+        aggregator.add_points(&[hit(1, json!("a")), hit(2, json!(5)), hit(3, json!(-5))]);
+
+        let (groups, _) = split_next_page(aggregator.distill(), 3);
+
+        assert_eq!(
+            keys_and_ids(&groups),
+            vec![
+                (GroupId::from(-5i64), vec![3]),
+                (GroupId::from(5u64), vec![2]),
+                ("a".into(), vec![1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_last_best_group_bounds_the_page() {
+        let mut aggregator = page_aggregator("user".to_string(), None, 1, 1);
+        aggregator.add_points(&[hit(1, json!("c"))]);
+        assert_eq!(aggregator.key_of_last_best_group(), None);
+
+        aggregator.add_points(&[hit(2, json!("d")), hit(3, json!("b"))]);
+        assert_eq!(aggregator.key_of_last_best_group(), Some(&"c".into()));
+    }
+
+    fn numeric_range(gte: Option<f64>, lte: Option<f64>) -> Condition {
+        Condition::Field(FieldCondition::new_range(
+            "user",
+            Range {
+                gte,
+                lte,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn keyword_range(gte: Option<&str>, lte: Option<&str>) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            "user",
+            Match::new_keyword_range(KeywordRange {
+                gte: gte.map(str::to_string),
+                lte: lte.map(str::to_string),
+            }),
+        ))
+    }
+
+    fn either(conditions: Vec<Condition>) -> Condition {
+        Condition::Filter(Filter {
+            should: Some(conditions),
+            min_should: None,
+            must: None,
+            must_not: None,
+        })
+    }
+
+    #[test]
+    fn test_key_range_condition() {
+        let five = GroupId::from(5u64);
+        let ten = GroupId::from(10u64);
+        let a = GroupId::from("a");
+        let c = GroupId::from("c");
+
+        assert_eq!(key_range_condition("user", None, None), None);
+        assert_eq!(
+            key_range_condition("user", Some(&five), None),
+            Some(either(vec![
+                numeric_range(Some(5.0), None),
+                keyword_range(None, None),
+            ]))
+        );
+        assert_eq!(
+            key_range_condition("user", Some(&five), Some(&ten)),
+            Some(numeric_range(Some(5.0), Some(10.0)))
+        );
+        assert_eq!(
+            key_range_condition("user", Some(&five), Some(&c)),
+            Some(either(vec![
+                numeric_range(Some(5.0), None),
+                keyword_range(None, Some("c")),
+            ]))
+        );
+        assert_eq!(
+            key_range_condition("user", Some(&a), None),
+            Some(keyword_range(Some("a"), None))
+        );
+        assert_eq!(
+            key_range_condition("user", Some(&a), Some(&c)),
+            Some(keyword_range(Some("a"), Some("c")))
+        );
+    }
+
+    #[test]
+    fn test_key_range_condition_of_inexact_keys() {
+        let huge = GroupId::from(u64::MAX);
+        assert_eq!(
+            key_range_condition("user", Some(&huge), None),
+            Some(either(vec![
+                numeric_range(None, None),
+                keyword_range(None, None),
+            ]))
+        );
+        assert_eq!(
+            key_range_condition("user", None, Some(&huge)),
+            Some(numeric_range(None, None))
+        );
+    }
+}
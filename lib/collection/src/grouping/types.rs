@@ -22,6 +22,7 @@ impl Group {
             if let Some(point) = map.get(&hit.id) {
                 hit.payload = point.payload.clone();
                 hit.vector = point.vector.clone();
+                hit.vector_slice = point.vector_slice.clone();
             }
         });
     }
@@ -33,6 +34,7 @@ impl From<Group> for PointGroup {
             hits: group.hits,
             id: group.key,
             lookup: None,
+            examples: None,
         }
     }
 }
@@ -76,4 +78,24 @@ mod test {
         let nested_object = GroupId::try_from(&json!({"a": 1, "b": 2}));
         assert!(nested_object.is_err());
     }
+
+    #[test]
+    fn stringified_group_keys() {
+        assert_eq!(
+            GroupId::from("5").unstringified(),
+            Some(GroupId::from(5u64))
+        );
+        assert_eq!(
+            GroupId::from("-5").unstringified(),
+            Some(GroupId::from(-5i64))
+        );
+        assert_eq!(
+            GroupId::from(u64::MAX.to_string()).unstringified(),
+            Some(GroupId::from(u64::MAX))
+        );
+        for key in ["05", "+5", "-0", "5.0", "a", ""] {
+            assert_eq!(GroupId::from(key).unstringified(), None, "{key}");
+        }
+        assert_eq!(GroupId::from(5u64).unstringified(), None);
+    }
 }
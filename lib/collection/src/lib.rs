@@ -1,19 +1,27 @@
+pub mod admission;
 pub mod collection;
 pub mod collection_manager;
 pub mod collection_state;
 pub mod common;
 pub mod config;
+pub mod fusion;
 pub mod grouping;
 pub mod hash_ring;
 pub mod lookup;
 pub mod operations;
 pub mod optimizers_builder;
+pub mod ordered_query;
+pub mod quota;
+pub mod read_cache;
 pub mod recommendations;
 pub mod save_on_disk;
 pub mod shards;
+pub mod slow_log;
 pub mod telemetry;
+pub mod ttl;
 mod update_handler;
 pub mod wal;
+pub mod warmup;
 
 #[cfg(test)]
 mod tests;
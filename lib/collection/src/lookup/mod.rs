@@ -48,8 +48,8 @@ where
 {
     let collection = collection_by_name(request.collection_name.clone())
         .await
-        .ok_or(CollectionError::NotFound {
-            what: format!("Collection {}", request.collection_name),
+        .ok_or_else(|| CollectionError::LookupCollectionMissing {
+            name: request.collection_name.clone(),
         })?;
 
     let ids = values
@@ -65,6 +65,8 @@ where
         ids,
         with_payload: request.with_payload,
         with_vector: request.with_vectors.unwrap_or_default(),
+        with_timestamps: false,
+        max_payload_return_bytes: None,
     };
 
     let result = collection
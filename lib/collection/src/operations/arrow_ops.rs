@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, RecordBatch};
+use arrow_ipc::reader::StreamReader;
+use arrow_schema::{ArrowError, DataType, Schema};
+use segment::data_types::vectors::{VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::types::{ExtendedPointId, Payload, PointIdType};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::operations::point_ops::PointStruct;
+use crate::operations::types::{CollectionError, CollectionResult, VectorsConfig};
+
+/// Max number of points, converted from record batches into a single upsert operation
+pub const ARROW_UPSERT_CHUNK_SIZE: usize = 1000;
+
+/// Column with the point ids, either `UInt64` or `Utf8` with UUIDs
+pub const ARROW_ID_COLUMN: &str = "id";
+
+/// Column with the unnamed vector, if the collection has a single one
+pub const ARROW_DEFAULT_VECTOR_COLUMN: &str = "vector";
+
+impl From<ArrowError> for CollectionError {
+    fn from(err: ArrowError) -> Self {
+        CollectionError::bad_input(format!("Invalid Arrow data: {err}"))
+    }
+}
+
+fn is_payload_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+    )
+}
+
+/// Columns of the record batches with points, checked against the vectors of the collection
+#[derive(Debug)]
+pub struct ArrowPointsSchema {
+    id_column: usize,
+    /// Column index and the name of the vector
+    vector_columns: Vec<(usize, String)>,
+    /// Column index and the payload key
+    payload_columns: Vec<(usize, String)>,
+    single_vector: bool,
+}
+
+impl ArrowPointsSchema {
+    /// Match the columns to the vectors of the collection.
+    ///
+    /// Every `FixedSizeList<Float32>` column is a vector, named as the column. All other columns,
+    /// except for the `id` one, are payload fields.
+    pub fn try_new(schema: &Schema, vectors: &VectorsConfig) -> CollectionResult<Self> {
+        let single_vector = matches!(vectors, VectorsConfig::Single(_));
+        let mut id_column = None;
+        let mut vector_columns = Vec::new();
+        let mut payload_columns = Vec::new();
+
+        for (index, field) in schema.fields().iter().enumerate() {
+            let name = field.name();
+            match field.data_type() {
+                DataType::UInt64 | DataType::Utf8 if name == ARROW_ID_COLUMN => {
+                    id_column = Some(index);
+                }
+                data_type if name == ARROW_ID_COLUMN => {
+                    return Err(CollectionError::bad_input(format!(
+                        "Column `{ARROW_ID_COLUMN}` must be of UInt64 or Utf8 type, got {data_type}"
+                    )));
+                }
+                DataType::FixedSizeList(item, size) => {
+                    if item.data_type() != &DataType::Float32 {
+                        return Err(CollectionError::bad_input(format!(
+                            "Vector column `{name}` must be a list of Float32, got {}",
+                            item.data_type()
+                        )));
+                    }
+                    let vector_name = if single_vector && name == ARROW_DEFAULT_VECTOR_COLUMN {
+                        DEFAULT_VECTOR_NAME
+                    } else {
+                        name.as_str()
+                    };
+                    let params = vectors.get_params(vector_name).ok_or_else(|| {
+                        CollectionError::bad_input(format!("Unknown vector name: {name}"))
+                    })?;
+                    if params.size.get() != *size as u64 {
+                        return Err(CollectionError::bad_input(format!(
+                            "Wrong dimension of vector column `{name}`: expected {}, got {size}",
+                            params.size
+                        )));
+                    }
+                    vector_columns.push((index, vector_name.to_string()));
+                }
+                data_type if is_payload_type(data_type) => {
+                    payload_columns.push((index, name.clone()));
+                }
+                data_type => {
+                    return Err(CollectionError::bad_input(format!(
+                        "Unsupported type {data_type} of payload column `{name}`"
+                    )));
+                }
+            }
+        }
+
+        let Some(id_column) = id_column else {
+            return Err(CollectionError::bad_input(format!(
+                "Column `{ARROW_ID_COLUMN}` is missing"
+            )));
+        };
+        if vector_columns.is_empty() {
+            return Err(CollectionError::bad_input(
+                "Record batches have no vector columns".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id_column,
+            vector_columns,
+            payload_columns,
+            single_vector,
+        })
+    }
+
+    /// Convert the rows of the record batch into points
+    pub fn points(&self, batch: &RecordBatch) -> CollectionResult<Vec<PointStruct>> {
+        let ids = batch.column(self.id_column);
+        let vectors: Vec<_> = self
+            .vector_columns
+            .iter()
+            .map(|(index, name)| (batch.column(*index).as_fixed_size_list(), name))
+            .collect();
+        let vector_values: Vec<_> = vectors.iter().map(|(list, _)| list.values()).collect();
+
+        (0..batch.num_rows())
+            .map(|row| {
+                let id = point_id(ids.as_ref(), row)?;
+
+                let mut named_vectors = HashMap::with_capacity(vectors.len());
+                for ((list, name), values) in vectors.iter().zip(&vector_values) {
+                    if list.is_null(row) {
+                        continue;
+                    }
+                    let values = values.as_primitive::<Float32Type>().values();
+                    let start = list.value_offset(row) as usize;
+                    let end = start + list.value_length() as usize;
+                    named_vectors.insert(name.to_string(), values[start..end].to_vec());
+                }
+                let vector = if self.single_vector {
+                    let vector = named_vectors.remove(DEFAULT_VECTOR_NAME).ok_or_else(|| {
+                        CollectionError::bad_input(format!("Vector of point {id} is missing"))
+                    })?;
+                    VectorStruct::Single(vector)
+                } else {
+                    VectorStruct::from(named_vectors)
+                };
+
+                let mut payload = Map::new();
+                for (index, key) in &self.payload_columns {
+                    if let Some(value) = payload_value(batch.column(*index).as_ref(), row) {
+                        payload.insert(key.clone(), value);
+                    }
+                }
+
+                Ok(PointStruct {
+                    id,
+                    vector,
+                    payload: (!payload.is_empty()).then_some(Payload(payload)),
+                })
+            })
+            .collect()
+    }
+}
+
+fn point_id(ids: &dyn Array, row: usize) -> CollectionResult<PointIdType> {
+    if ids.is_null(row) {
+        return Err(CollectionError::bad_input(format!(
+            "Point id is missing in row {row}"
+        )));
+    }
+    match ids.data_type() {
+        DataType::UInt64 => Ok(ids.as_primitive::<UInt64Type>().value(row).into()),
+        _ => {
+            let id = ids.as_string::<i32>().value(row);
+            Uuid::parse_str(id)
+                .map(ExtendedPointId::Uuid)
+                .map_err(|err| CollectionError::bad_input(format!("Invalid point id {id}: {err}")))
+        }
+    }
+}
+
+/// JSON value of the payload column in the row, `None` if it is null
+fn payload_value(column: &dyn Array, row: usize) -> Option<Value> {
+    if column.is_null(row) {
+        return None;
+    }
+    let value: Value = match column.data_type() {
+        DataType::Boolean => column.as_boolean().value(row).into(),
+        DataType::Int8 => column.as_primitive::<Int8Type>().value(row).into(),
+        DataType::Int16 => column.as_primitive::<Int16Type>().value(row).into(),
+        DataType::Int32 => column.as_primitive::<Int32Type>().value(row).into(),
+        DataType::Int64 => column.as_primitive::<Int64Type>().value(row).into(),
+        DataType::UInt8 => column.as_primitive::<UInt8Type>().value(row).into(),
+        DataType::UInt16 => column.as_primitive::<UInt16Type>().value(row).into(),
+        DataType::UInt32 => column.as_primitive::<UInt32Type>().value(row).into(),
+        DataType::UInt64 => column.as_primitive::<UInt64Type>().value(row).into(),
+        DataType::Float32 => f64::from(column.as_primitive::<Float32Type>().value(row)).into(),
+        DataType::Float64 => column.as_primitive::<Float64Type>().value(row).into(),
+        DataType::Utf8 => column.as_string::<i32>().value(row).into(),
+        DataType::LargeUtf8 => column.as_string::<i64>().value(row).into(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Reader of the points from an Arrow IPC stream, in chunks of at most
+/// [`ARROW_UPSERT_CHUNK_SIZE`] points.
+///
+/// The schema is checked when the reader is created, before any of the record batches is read.
+pub struct ArrowPointsReader {
+    reader: StreamReader<Cursor<Vec<u8>>>,
+    schema: ArrowPointsSchema,
+    /// Record batch being converted and the offset of its next row
+    current: Option<(RecordBatch, usize)>,
+}
+
+impl ArrowPointsReader {
+    pub fn try_new(arrow_ipc: Vec<u8>, vectors: &VectorsConfig) -> CollectionResult<Self> {
+        let reader = StreamReader::try_new(Cursor::new(arrow_ipc), None)?;
+        let schema = ArrowPointsSchema::try_new(reader.schema().as_ref(), vectors)?;
+        Ok(Self {
+            reader,
+            schema,
+            current: None,
+        })
+    }
+}
+
+impl Iterator for ArrowPointsReader {
+    type Item = CollectionResult<Vec<PointStruct>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((batch, offset)) = &mut self.current {
+                if *offset < batch.num_rows() {
+                    let len = ARROW_UPSERT_CHUNK_SIZE.min(batch.num_rows() - *offset);
+                    let chunk = batch.slice(*offset, len);
+                    *offset += len;
+                    return Some(self.schema.points(&chunk));
+                }
+            }
+            match self.reader.next()? {
+                Ok(batch) => self.current = Some((batch, 0)),
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::num::NonZeroU64;
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, FixedSizeListArray, Float64Array, StringArray, UInt64Array};
+    use arrow_ipc::writer::StreamWriter;
+    use segment::types::Distance;
+
+    use super::*;
+    use crate::operations::types::VectorParams;
+
+    fn vector_params(size: u64) -> VectorParams {
+        VectorParams {
+            size: NonZeroU64::new(size).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+        }
+    }
+
+    fn vectors(num_points: u64, dim: usize) -> ArrayRef {
+        let vectors = (0..num_points).map(|i| Some(vec![Some(i as f32); dim]));
+        Arc::new(FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(vectors, dim as i32))
+    }
+
+    fn arrow_ipc(columns: Vec<(&str, ArrayRef)>) -> Vec<u8> {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut data = Vec::new();
+        let mut writer = StreamWriter::try_new(&mut data, batch.schema().as_ref()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+        data
+    }
+
+    #[test]
+    fn test_arrow_points_reader() {
+        let num_points = 2500;
+        let config = VectorsConfig::Single(vector_params(4));
+        let price = Float64Array::from_iter((0..num_points).map(|i| (i % 2 == 0).then_some(1.5)));
+        let data = arrow_ipc(vec![
+            (
+                "id",
+                Arc::new(UInt64Array::from_iter_values(0..num_points)) as ArrayRef,
+            ),
+            ("vector", vectors(num_points, 4)),
+            ("price", Arc::new(price) as ArrayRef),
+        ]);
+
+        let chunks = ArrowPointsReader::try_new(data, &config)
+            .unwrap()
+            .collect::<CollectionResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![1000, 1000, 500]
+        );
+
+        let point = &chunks[2][1];
+        assert_eq!(point.id, 2001u64.into());
+        assert_eq!(point.vector, VectorStruct::Single(vec![2001.0; 4]));
+        assert!(point.payload.is_none());
+        let point = &chunks[2][2];
+        assert_eq!(point.payload.as_ref().unwrap().0["price"], 1.5);
+    }
+
+    #[test]
+    fn test_arrow_schema_mismatch() {
+        let ids: ArrayRef = Arc::new(StringArray::from(vec![
+            "936DA01F-9ABD-4D9D-80C7-02AF85C822A8",
+        ]));
+        let config =
+            VectorsConfig::Multi(BTreeMap::from([("image".to_string(), vector_params(4))]));
+
+        let data = arrow_ipc(vec![("id", ids.clone()), ("image", vectors(1, 4))]);
+        let points = ArrowPointsReader::try_new(data, &config)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(points[0].id, ExtendedPointId::Uuid(_)));
+
+        // Wrong dimension
+        let data = arrow_ipc(vec![("id", ids.clone()), ("image", vectors(1, 3))]);
+        assert!(ArrowPointsReader::try_new(data, &config).is_err());
+
+        // Unknown vector name
+        let data = arrow_ipc(vec![("id", ids.clone()), ("text", vectors(1, 4))]);
+        assert!(ArrowPointsReader::try_new(data, &config).is_err());
+
+        // Missing ids
+        let data = arrow_ipc(vec![("image", vectors(1, 4))]);
+        assert!(ArrowPointsReader::try_new(data, &config).is_err());
+    }
+}
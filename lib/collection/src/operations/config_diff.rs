@@ -9,6 +9,7 @@ use serde_json::Value;
 use validator::Validate;
 
 use crate::config::{CollectionParams, WalConfig};
+use crate::operations::payload_validation::PayloadValidationMode;
 use crate::operations::types::CollectionResult;
 use crate::optimizers_builder::OptimizersConfig;
 
@@ -82,6 +83,16 @@ pub struct CollectionParamsDiff {
     pub replication_factor: Option<NonZeroU32>,
     /// Minimal number successful responses from replicas to consider operation successful
     pub write_consistency_factor: Option<NonZeroU32>,
+    /// If true - payload values of the upserted points are checked against the types of the indexed fields
+    pub validate_payload: Option<bool>,
+    /// Whether the whole operation or only the points with invalid payload are rejected
+    pub payload_validation_mode: Option<PayloadValidationMode>,
+    /// Delay in milliseconds, after which a read is also sent to another replica
+    pub read_hedge_delay_ms: Option<u64>,
+    /// If true - updates of the collection are rejected until the flag is cleared
+    pub read_only: Option<bool>,
+    /// If true - fields, which the points are grouped by, are indexed automatically
+    pub auto_index_group_by_fields: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Merge)]
@@ -108,6 +119,16 @@ pub struct OptimizersConfigDiff {
     /// Note: 1Kb = 1 vector of size 256
     #[serde(alias = "max_segment_size_kb")]
     pub max_segment_size: Option<usize>,
+    /// Maximum number of segments the merge optimizer will try to keep.
+    /// Merges are not proposed while the number of segments is at or below this value.
+    /// If `0`, `default_segment_number` is used.
+    pub max_segment_number: Option<usize>,
+    /// Size (in kilobytes), at which segments are considered large enough and are not merged anymore.
+    /// Merges never produce segments above `max_segment_size`.
+    ///
+    /// Note: 1Kb = 1 vector of size 256
+    #[serde(alias = "target_segment_size_kb")]
+    pub target_segment_size: Option<usize>,
     /// Maximum size (in kilobytes) of vectors to store in-memory per segment.
     /// Segments larger than this threshold will be stored as read-only memmaped file.
     ///
@@ -131,6 +152,9 @@ pub struct OptimizersConfigDiff {
     pub flush_interval_sec: Option<u64>,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: Option<usize>,
+    /// Maximum number of threads, which all concurrent optimizations of a shard can use for building indexes.
+    /// If `0` - `NUM_CPU - 1` will be used.
+    pub max_indexing_threads: Option<usize>,
 }
 
 impl std::hash::Hash for OptimizersConfigDiff {
@@ -139,10 +163,13 @@ impl std::hash::Hash for OptimizersConfigDiff {
         self.vacuum_min_vector_number.hash(state);
         self.default_segment_number.hash(state);
         self.max_segment_size.hash(state);
+        self.max_segment_number.hash(state);
+        self.target_segment_size.hash(state);
         self.memmap_threshold.hash(state);
         self.indexing_threshold.hash(state);
         self.flush_interval_sec.hash(state);
         self.max_optimization_threads.hash(state);
+        self.max_indexing_threads.hash(state);
     }
 }
 
@@ -153,10 +180,13 @@ impl PartialEq for OptimizersConfigDiff {
             && self.vacuum_min_vector_number == other.vacuum_min_vector_number
             && self.default_segment_number == other.default_segment_number
             && self.max_segment_size == other.max_segment_size
+            && self.max_segment_number == other.max_segment_number
+            && self.target_segment_size == other.target_segment_size
             && self.memmap_threshold == other.memmap_threshold
             && self.indexing_threshold == other.indexing_threshold
             && self.flush_interval_sec == other.flush_interval_sec
             && self.max_optimization_threads == other.max_optimization_threads
+            && self.max_indexing_threads == other.max_indexing_threads
     }
 }
 
@@ -260,23 +290,44 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
             }
             .into(),
             shard_number: NonZeroU32::new(1).unwrap(),
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
             on_disk_payload: false,
+            validate_payload: false,
+            payload_validation_mode: PayloadValidationMode::Point,
+            sparse_vectors: None,
+            payload_indexes: Default::default(),
+            payload_cache_fields: Default::default(),
+            read_hedge_delay_ms: None,
+            read_only: false,
+            auto_index_group_by_fields: false,
         };
 
         let diff = CollectionParamsDiff {
             replication_factor: None,
             write_consistency_factor: Some(NonZeroU32::new(2).unwrap()),
+            validate_payload: Some(true),
+            payload_validation_mode: None,
+            read_hedge_delay_ms: Some(50),
+            read_only: Some(true),
+            auto_index_group_by_fields: None,
         };
 
         let new_params = diff.update(&params).unwrap();
 
         assert_eq!(new_params.replication_factor.get(), 1);
         assert_eq!(new_params.write_consistency_factor.get(), 2);
+        assert!(new_params.validate_payload);
+        assert_eq!(
+            new_params.payload_validation_mode,
+            PayloadValidationMode::Point
+        );
+        assert_eq!(new_params.read_hedge_delay_ms, Some(50));
+        assert!(new_params.read_only);
     }
 
     #[test]
@@ -294,10 +345,13 @@ mod tests {
             vacuum_min_vector_number: 1000,
             default_segment_number: 10,
             max_segment_size: None,
+            max_segment_number: None,
+            target_segment_size: None,
             memmap_threshold: None,
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 1,
+            max_indexing_threads: None,
         };
         let update: OptimizersConfigDiff =
             serde_json::from_str(r#"{ "indexing_threshold": 10000 }"#).unwrap();
@@ -305,6 +359,25 @@ mod tests {
         assert_eq!(new_config.indexing_threshold, Some(10000))
     }
 
+    #[test]
+    fn test_optimizer_merge_targets_update() {
+        let base_config = OptimizersConfig::fixture();
+        let update: OptimizersConfigDiff = serde_json::from_str(
+            r#"{
+                "max_segment_number": 4,
+                "target_segment_size_kb": 1000,
+                "max_segment_size_kb": 500
+            }"#,
+        )
+        .unwrap();
+        let new_config = update.update(&base_config).unwrap();
+        assert_eq!(new_config.get_max_segment_number(), 4);
+        assert_eq!(new_config.max_segment_size, Some(500));
+        // Target size is capped by the max segment size
+        assert_eq!(new_config.target_segment_size, Some(1000));
+        assert_eq!(new_config.get_target_segment_size(), 500);
+    }
+
     #[test]
     fn test_wal_config() {
         let base_config = WalConfig::default();
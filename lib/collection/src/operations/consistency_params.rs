@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
 use api::grpc::qdrant::{
-    read_consistency, ReadConsistency as ReadConsistencyGrpc,
-    ReadConsistencyType as ReadConsistencyTypeGrpc,
+    read_consistency, write_consistency, ReadConsistency as ReadConsistencyGrpc,
+    ReadConsistencyType as ReadConsistencyTypeGrpc, WriteConsistency as WriteConsistencyGrpc,
+    WriteConsistencyType as WriteConsistencyTypeGrpc,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -127,16 +128,14 @@ where
 
     let factor = factor.map_err(|err| {
         serde::de::Error::custom(format!(
-            "failed to deserialize read consistency factor value: {err}"
+            "failed to deserialize consistency factor value: {err}"
         ))
     })?;
 
     if factor > 0 {
         Ok(factor)
     } else {
-        Err(serde::de::Error::custom(
-            "read consistency factor can't be zero",
-        ))
+        Err(serde::de::Error::custom("consistency factor can't be zero"))
     }
 }
 
@@ -196,6 +195,140 @@ impl From<ReadConsistencyType> for ReadConsistencyTypeGrpc {
     }
 }
 
+/// Write consistency parameter
+///
+/// Defines how many replicas of the shard should apply an update operation before it is
+/// completed
+///
+/// * `N` - wait for N replicas
+///
+/// * `majority` - wait for N/2+1 replicas, where N is the number of replicas in the replica set
+///
+/// * `all` - wait for all replicas of the replica set
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum WriteConsistency {
+    // wait for N replicas
+    Factor(#[serde(deserialize_with = "deserialize_factor")] usize),
+    Type(WriteConsistencyType),
+}
+
+impl Validate for WriteConsistency {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            WriteConsistency::Factor(factor) if *factor == 0 => {
+                let mut errors = ValidationErrors::new();
+                errors.add("factor", {
+                    let mut error = ValidatorError::new("range");
+                    error.add_param(Cow::from("value"), factor);
+                    error.add_param(Cow::from("min"), &1);
+                    error
+                });
+                Err(errors)
+            }
+            WriteConsistency::Factor(_) | WriteConsistency::Type(_) => Ok(()),
+        }
+    }
+}
+
+impl WriteConsistency {
+    /// Number of replicas, which should apply the operation, out of `replicas` in the replica set
+    pub fn required_replicas(&self, replicas: usize) -> usize {
+        match self {
+            WriteConsistency::Factor(factor) => *factor,
+            WriteConsistency::Type(WriteConsistencyType::Majority) => replicas / 2 + 1,
+            WriteConsistency::Type(WriteConsistencyType::All) => replicas,
+        }
+    }
+
+    pub fn try_from_optional(
+        consistency: Option<WriteConsistencyGrpc>,
+    ) -> Result<Option<Self>, tonic::Status> {
+        consistency.map(TryFrom::try_from).transpose()
+    }
+}
+
+impl TryFrom<WriteConsistencyGrpc> for WriteConsistency {
+    type Error = tonic::Status;
+
+    fn try_from(consistency: WriteConsistencyGrpc) -> Result<Self, Self::Error> {
+        let value = consistency.value.ok_or_else(|| {
+            tonic::Status::invalid_argument(
+                "invalid write consistency message: `WriteConsistency::value` field is `None`",
+            )
+        })?;
+
+        let consistency = match value {
+            write_consistency::Value::Factor(0) => {
+                return Err(tonic::Status::invalid_argument(
+                    "write consistency factor can't be zero",
+                ))
+            }
+            write_consistency::Value::Factor(factor) => Self::Factor(
+                usize::try_from(factor)
+                    .map_err(|err| tonic::Status::invalid_argument(err.to_string()))?,
+            ),
+            write_consistency::Value::Type(consistency) => Self::Type(consistency.try_into()?),
+        };
+
+        Ok(consistency)
+    }
+}
+
+impl From<WriteConsistency> for WriteConsistencyGrpc {
+    fn from(consistency: WriteConsistency) -> Self {
+        let value = match consistency {
+            WriteConsistency::Factor(factor) => {
+                write_consistency::Value::Factor(factor.try_into().unwrap())
+            }
+            WriteConsistency::Type(consistency) => {
+                write_consistency::Value::Type(consistency.into())
+            }
+        };
+
+        WriteConsistencyGrpc { value: Some(value) }
+    }
+}
+
+/// * `majority` - wait for N/2+1 replicas
+///
+/// * `all` - wait for all replicas
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteConsistencyType {
+    // wait for N/2+1 replicas
+    Majority,
+    // wait for all replicas
+    All,
+}
+
+impl TryFrom<i32> for WriteConsistencyType {
+    type Error = tonic::Status;
+
+    fn try_from(consistency: i32) -> Result<Self, Self::Error> {
+        let consistency = WriteConsistencyTypeGrpc::from_i32(consistency).ok_or_else(|| {
+            tonic::Status::invalid_argument(format!(
+                "invalid write consistency type value {consistency}",
+            ))
+        })?;
+
+        Ok(match consistency {
+            WriteConsistencyTypeGrpc::Majority => Self::Majority,
+            WriteConsistencyTypeGrpc::All => Self::All,
+        })
+    }
+}
+
+impl From<WriteConsistencyType> for i32 {
+    fn from(consistency: WriteConsistencyType) -> Self {
+        let consistency = match consistency {
+            WriteConsistencyType::Majority => WriteConsistencyTypeGrpc::Majority,
+            WriteConsistencyType::All => WriteConsistencyTypeGrpc::All,
+        };
+        consistency as _
+    }
+}
+
 #[derive(Copy, Clone, Debug, thiserror::Error)]
 #[error("Read consistency factor cannot be less than 1")]
 pub struct ValidationError;
@@ -243,4 +376,20 @@ mod tests {
         let schema_str = serde_json::to_string_pretty(&schema).unwrap();
         println!("{schema_str}")
     }
+
+    #[test]
+    fn test_write_consistency_required_replicas() {
+        let consistency: WriteConsistency = serde_json::from_str("\"majority\"").unwrap();
+        assert_eq!(consistency.required_replicas(3), 2);
+        assert_eq!(consistency.required_replicas(4), 3);
+
+        let consistency: WriteConsistency = serde_json::from_str("\"all\"").unwrap();
+        assert_eq!(consistency.required_replicas(3), 3);
+
+        let consistency: WriteConsistency = serde_json::from_str("\"2\"").unwrap();
+        assert_eq!(consistency, WriteConsistency::Factor(2));
+        assert_eq!(consistency.required_replicas(5), 2);
+
+        assert!(serde_json::from_str::<WriteConsistency>("0").is_err());
+    }
 }
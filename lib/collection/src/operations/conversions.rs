@@ -1,20 +1,30 @@
 use std::collections::{BTreeMap, HashMap};
 use std::num::{NonZeroU32, NonZeroU64};
 
-use api::grpc::conversions::{from_grpc_dist, payload_to_proto, proto_to_payloads};
+use api::grpc::conversions::{
+    date_time_from_proto, date_time_to_proto, from_grpc_datatype, from_grpc_dist, payload_to_proto,
+    proto_to_payloads,
+};
 use api::grpc::qdrant::update_collection_cluster_setup_request::Operation as ClusterOperationsPb;
 use api::grpc::qdrant::QuantizationType;
 use itertools::Itertools;
-use segment::data_types::vectors::{NamedVector, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::data_types::groups::GroupId;
+use segment::data_types::sparse_vector::SparseVector;
+use segment::data_types::vectors::{
+    NamedSparseVector, NamedVector, NamedVectorStruct, VectorStruct, DEFAULT_VECTOR_NAME,
+};
 use segment::types::{
-    CompressionRatio, Distance, ProductQuantization, ProductQuantizationConfig, QuantizationConfig,
-    ScalarQuantization, ScalarQuantizationConfig, ScalarType,
+    CompressionRatio, Distance, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType,
+    ProductQuantization, ProductQuantizationConfig, QuantizationConfig, ScalarQuantization,
+    ScalarQuantizationConfig, ScalarType, SparseIndexConfig,
 };
 use tonic::Status;
 
 use super::types::{
-    BaseGroupRequest, GroupsResult, PointGroup, RecommendGroupsRequest, SearchGroupsRequest,
+    BaseGroupRequest, CollectionGroupsRequest, GroupByStats, GroupsResult, PointGroup,
+    RecommendGroupsRequest, SearchGroupsRequest, UsingVector,
 };
+use crate::admission::{AdmissionConfig, WriteRateLimitMode};
 use crate::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
     CollectionParams, WalConfig,
@@ -28,17 +38,25 @@ use crate::operations::cluster_ops::{
 use crate::operations::config_diff::{
     CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, WalConfigDiff,
 };
+use crate::operations::payload_validation::PayloadValidationMode;
 use crate::operations::point_ops::PointsSelector::PointIdsSelector;
 use crate::operations::point_ops::{
     Batch, FilterSelector, PointIdsList, PointStruct, PointsSelector, WriteOrdering,
 };
+use crate::operations::strict_mode::{StrictModeConfig, UnindexedFiltering};
 use crate::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionStatus, CountResult,
-    LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequest, Record, RemoteShardInfo,
-    SearchRequest, ShardTransferInfo, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
+    FieldIndexBuildProgress, LocalShardInfo, LookupLocation, MergeTargets, OptimizersStatus,
+    RecommendRequest, Record, RemoteShardInfo, SearchRequest, ShardTransferInfo,
+    SparseVectorParams, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
 };
 use crate::optimizers_builder::OptimizersConfig;
+use crate::quota::{QuotaConfig, QuotaUsage};
+use crate::read_cache::ReadCacheConfig;
+use crate::shards::checksum::ShardChecksum;
 use crate::shards::remote_shard::CollectionSearchRequest;
+use crate::ttl::TtlConfig;
+use crate::warmup::{WarmupProgress, WarmupState};
 
 pub fn write_ordering_to_proto(ordering: WriteOrdering) -> api::grpc::qdrant::WriteOrdering {
     api::grpc::qdrant::WriteOrdering {
@@ -96,10 +114,16 @@ pub fn try_record_from_grpc(
         .map(|vectors| vectors.try_into())
         .transpose()?;
 
+    let last_modified = point.last_modified.map(date_time_from_proto).transpose()?;
+
     Ok(Record {
         id,
         payload,
         vector,
+        last_modified,
+        order_value: point.order_value,
+        payload_truncation: point.payload_truncation.map(|truncation| truncation.into()),
+        vector_slice: point.vector_slice.map(|slice| slice.into()),
     })
 }
 
@@ -138,6 +162,33 @@ impl From<api::grpc::qdrant::WalConfigDiff> for WalConfigDiff {
     }
 }
 
+impl From<PayloadValidationMode> for api::grpc::qdrant::PayloadValidationMode {
+    fn from(mode: PayloadValidationMode) -> Self {
+        match mode {
+            PayloadValidationMode::Operation => {
+                api::grpc::qdrant::PayloadValidationMode::PayloadValidationOperation
+            }
+            PayloadValidationMode::Point => {
+                api::grpc::qdrant::PayloadValidationMode::PayloadValidationPoint
+            }
+        }
+    }
+}
+
+pub fn payload_validation_mode_from_proto(mode: i32) -> Result<PayloadValidationMode, Status> {
+    match api::grpc::qdrant::PayloadValidationMode::from_i32(mode) {
+        Some(api::grpc::qdrant::PayloadValidationMode::PayloadValidationOperation) => {
+            Ok(PayloadValidationMode::Operation)
+        }
+        Some(api::grpc::qdrant::PayloadValidationMode::PayloadValidationPoint) => {
+            Ok(PayloadValidationMode::Point)
+        }
+        None => Err(Status::invalid_argument(format!(
+            "Unknown payload validation mode: {mode}"
+        ))),
+    }
+}
+
 impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
     type Error = Status;
 
@@ -158,6 +209,15 @@ impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
                     })
                 })
                 .transpose()?,
+            validate_payload: value.validate_payload,
+            payload_validation_mode: value
+                .payload_validation_mode
+                .map(payload_validation_mode_from_proto)
+                .transpose()?,
+
+            read_hedge_delay_ms: value.read_hedge_delay_ms,
+            read_only: value.read_only,
+            auto_index_group_by_fields: value.auto_index_group_by_fields,
         })
     }
 }
@@ -169,10 +229,285 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfigDiff {
             vacuum_min_vector_number: value.vacuum_min_vector_number.map(|v| v as usize),
             default_segment_number: value.default_segment_number.map(|v| v as usize),
             max_segment_size: value.max_segment_size.map(|v| v as usize),
+            max_segment_number: value.max_segment_number.map(|v| v as usize),
+            target_segment_size: value.target_segment_size.map(|v| v as usize),
             memmap_threshold: value.memmap_threshold.map(|v| v as usize),
             indexing_threshold: value.indexing_threshold.map(|v| v as usize),
             flush_interval_sec: value.flush_interval_sec,
             max_optimization_threads: value.max_optimization_threads.map(|v| v as usize),
+            max_indexing_threads: value.max_indexing_threads.map(|v| v as usize),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::StrictModeConfig> for StrictModeConfig {
+    fn from(value: api::grpc::qdrant::StrictModeConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            max_query_limit: value.max_query_limit.map(|v| v as usize),
+            max_query_offset: value.max_query_offset.map(|v| v as usize),
+            max_filter_conditions: value.max_filter_conditions.map(|v| v as usize),
+            require_payload_index: value.require_payload_index,
+            max_group_by_points: value.max_group_by_points.map(|v| v as usize),
+            // Modes, unknown to this version, are ignored
+            unindexed_filtering: value
+                .unindexed_filtering
+                .and_then(api::grpc::qdrant::UnindexedFiltering::from_i32)
+                .map(Into::into),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::UnindexedFiltering> for UnindexedFiltering {
+    fn from(value: api::grpc::qdrant::UnindexedFiltering) -> Self {
+        match value {
+            api::grpc::qdrant::UnindexedFiltering::Allow => UnindexedFiltering::Allow,
+            api::grpc::qdrant::UnindexedFiltering::Warn => UnindexedFiltering::Warn,
+            api::grpc::qdrant::UnindexedFiltering::Deny => UnindexedFiltering::Deny,
+        }
+    }
+}
+
+impl From<UnindexedFiltering> for api::grpc::qdrant::UnindexedFiltering {
+    fn from(value: UnindexedFiltering) -> Self {
+        match value {
+            UnindexedFiltering::Allow => api::grpc::qdrant::UnindexedFiltering::Allow,
+            UnindexedFiltering::Warn => api::grpc::qdrant::UnindexedFiltering::Warn,
+            UnindexedFiltering::Deny => api::grpc::qdrant::UnindexedFiltering::Deny,
+        }
+    }
+}
+
+impl From<StrictModeConfig> for api::grpc::qdrant::StrictModeConfig {
+    fn from(value: StrictModeConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            max_query_limit: value.max_query_limit.map(|v| v as u64),
+            max_query_offset: value.max_query_offset.map(|v| v as u64),
+            max_filter_conditions: value.max_filter_conditions.map(|v| v as u64),
+            require_payload_index: value.require_payload_index,
+            max_group_by_points: value.max_group_by_points.map(|v| v as u64),
+            unindexed_filtering: value
+                .unindexed_filtering
+                .map(|mode| api::grpc::qdrant::UnindexedFiltering::from(mode) as i32),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::QuotaConfig> for QuotaConfig {
+    fn from(value: api::grpc::qdrant::QuotaConfig) -> Self {
+        Self {
+            max_points: value.max_points.map(|v| v as usize),
+            max_vectors_size_bytes: value.max_vectors_size_bytes.map(|v| v as usize),
+            max_payload_size_bytes: value.max_payload_size_bytes.map(|v| v as usize),
+        }
+    }
+}
+
+impl From<QuotaConfig> for api::grpc::qdrant::QuotaConfig {
+    fn from(value: QuotaConfig) -> Self {
+        Self {
+            max_points: value.max_points.map(|v| v as u64),
+            max_vectors_size_bytes: value.max_vectors_size_bytes.map(|v| v as u64),
+            max_payload_size_bytes: value.max_payload_size_bytes.map(|v| v as u64),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::ReadCacheConfig> for ReadCacheConfig {
+    fn from(value: api::grpc::qdrant::ReadCacheConfig) -> Self {
+        Self {
+            max_entries: value.max_entries.map(|v| v as usize),
+            ttl_sec: value.ttl_sec,
+        }
+    }
+}
+
+impl From<ReadCacheConfig> for api::grpc::qdrant::ReadCacheConfig {
+    fn from(value: ReadCacheConfig) -> Self {
+        Self {
+            max_entries: value.max_entries.map(|v| v as u64),
+            ttl_sec: value.ttl_sec,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::AdmissionConfig> for AdmissionConfig {
+    fn from(value: api::grpc::qdrant::AdmissionConfig) -> Self {
+        Self {
+            max_concurrent_reads: value.max_concurrent_reads.map(|v| v as usize),
+            max_queued_reads: value.max_queued_reads.map(|v| v as usize),
+            max_write_operations_per_second: value
+                .max_write_operations_per_second
+                .map(|v| v as usize),
+            max_write_points_per_second: value.max_write_points_per_second.map(|v| v as usize),
+            write_burst_sec: value.write_burst_sec.map(|v| v as usize),
+            write_rate_limit_mode: value
+                .write_rate_limit_mode
+                .and_then(api::grpc::qdrant::WriteRateLimitMode::from_i32)
+                .map(Into::into),
+            max_write_delay_ms: value.max_write_delay_ms,
+        }
+    }
+}
+
+impl From<AdmissionConfig> for api::grpc::qdrant::AdmissionConfig {
+    fn from(value: AdmissionConfig) -> Self {
+        Self {
+            max_concurrent_reads: value.max_concurrent_reads.map(|v| v as u64),
+            max_queued_reads: value.max_queued_reads.map(|v| v as u64),
+            max_write_operations_per_second: value
+                .max_write_operations_per_second
+                .map(|v| v as u64),
+            max_write_points_per_second: value.max_write_points_per_second.map(|v| v as u64),
+            write_burst_sec: value.write_burst_sec.map(|v| v as u64),
+            write_rate_limit_mode: value
+                .write_rate_limit_mode
+                .map(|mode| api::grpc::qdrant::WriteRateLimitMode::from(mode) as i32),
+            max_write_delay_ms: value.max_write_delay_ms,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::WriteRateLimitMode> for WriteRateLimitMode {
+    fn from(value: api::grpc::qdrant::WriteRateLimitMode) -> Self {
+        match value {
+            api::grpc::qdrant::WriteRateLimitMode::Reject => WriteRateLimitMode::Reject,
+            api::grpc::qdrant::WriteRateLimitMode::Delay => WriteRateLimitMode::Delay,
+        }
+    }
+}
+
+impl From<WriteRateLimitMode> for api::grpc::qdrant::WriteRateLimitMode {
+    fn from(value: WriteRateLimitMode) -> Self {
+        match value {
+            WriteRateLimitMode::Reject => api::grpc::qdrant::WriteRateLimitMode::Reject,
+            WriteRateLimitMode::Delay => api::grpc::qdrant::WriteRateLimitMode::Delay,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::QuotaUsage> for QuotaUsage {
+    fn from(value: api::grpc::qdrant::QuotaUsage) -> Self {
+        Self {
+            points: value.points as usize,
+            vectors_size_bytes: value.vectors_size_bytes as usize,
+            payload_size_bytes: value.payload_size_bytes as usize,
+        }
+    }
+}
+
+impl From<QuotaUsage> for api::grpc::qdrant::QuotaUsage {
+    fn from(value: QuotaUsage) -> Self {
+        Self {
+            points: value.points as u64,
+            vectors_size_bytes: value.vectors_size_bytes as u64,
+            payload_size_bytes: value.payload_size_bytes as u64,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::WarmupProgress> for WarmupProgress {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::WarmupProgress) -> Result<Self, Self::Error> {
+        let state = match api::grpc::qdrant::WarmupState::from_i32(value.state) {
+            Some(api::grpc::qdrant::WarmupState::WarmupInProgress) => WarmupState::InProgress,
+            Some(api::grpc::qdrant::WarmupState::WarmupFinished) => WarmupState::Finished,
+            Some(api::grpc::qdrant::WarmupState::WarmupCancelled) => WarmupState::Cancelled,
+            Some(api::grpc::qdrant::WarmupState::WarmupFailed) => WarmupState::Failed,
+            None => return Err(Status::invalid_argument("Malformed WarmupState type")),
+        };
+        Ok(Self {
+            state,
+            segments_warmed: value.segments_warmed as usize,
+            segments_total: value.segments_total as usize,
+            bytes_touched: value.bytes_touched as usize,
+            searches_done: value.searches_done as usize,
+            error: value.error,
+        })
+    }
+}
+
+impl From<WarmupProgress> for api::grpc::qdrant::WarmupProgress {
+    fn from(value: WarmupProgress) -> Self {
+        let state = match value.state {
+            WarmupState::InProgress => api::grpc::qdrant::WarmupState::WarmupInProgress,
+            WarmupState::Finished => api::grpc::qdrant::WarmupState::WarmupFinished,
+            WarmupState::Cancelled => api::grpc::qdrant::WarmupState::WarmupCancelled,
+            WarmupState::Failed => api::grpc::qdrant::WarmupState::WarmupFailed,
+        };
+        Self {
+            state: state as i32,
+            segments_warmed: value.segments_warmed as u64,
+            segments_total: value.segments_total as u64,
+            bytes_touched: value.bytes_touched as u64,
+            searches_done: value.searches_done as u64,
+            error: value.error,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::FieldIndexBuildProgress> for FieldIndexBuildProgress {
+    fn from(value: api::grpc::qdrant::FieldIndexBuildProgress) -> Self {
+        Self {
+            field_name: value.field_name,
+            operation_id: value.operation_id,
+            shard_id: value.shard_id,
+            building_segments: value.building_segments as usize,
+            indexed_points: value.indexed_points as usize,
+            total_points: value.total_points as usize,
+        }
+    }
+}
+
+impl From<FieldIndexBuildProgress> for api::grpc::qdrant::FieldIndexBuildProgress {
+    fn from(value: FieldIndexBuildProgress) -> Self {
+        Self {
+            field_name: value.field_name,
+            operation_id: value.operation_id,
+            shard_id: value.shard_id,
+            building_segments: value.building_segments as u64,
+            indexed_points: value.indexed_points as u64,
+            total_points: value.total_points as u64,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::MergeTargets> for MergeTargets {
+    fn from(value: api::grpc::qdrant::MergeTargets) -> Self {
+        Self {
+            max_segment_number: value.max_segment_number as usize,
+            target_segment_size_kb: value.target_segment_size_kb as usize,
+            max_segment_size_kb: value.max_segment_size_kb as usize,
+        }
+    }
+}
+
+impl From<MergeTargets> for api::grpc::qdrant::MergeTargets {
+    fn from(value: MergeTargets) -> Self {
+        Self {
+            max_segment_number: value.max_segment_number as u64,
+            target_segment_size_kb: value.target_segment_size_kb as u64,
+            max_segment_size_kb: value.max_segment_size_kb as u64,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::TtlConfig> for TtlConfig {
+    fn from(value: api::grpc::qdrant::TtlConfig) -> Self {
+        Self {
+            field: value.field,
+            vacuum_interval_sec: value.vacuum_interval_sec,
+        }
+    }
+}
+
+impl From<TtlConfig> for api::grpc::qdrant::TtlConfig {
+    fn from(value: TtlConfig) -> Self {
+        Self {
+            field: value.field,
+            vacuum_interval_sec: value.vacuum_interval_sec,
         }
     }
 }
@@ -188,6 +523,10 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
             segments_count,
             config,
             payload_schema,
+            quota_usage,
+            warmup,
+            index_builds,
+            merge_targets,
         } = value;
 
         api::grpc::qdrant::CollectionInfo {
@@ -238,6 +577,17 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     replication_factor: Some(config.params.replication_factor.get()),
                     on_disk_payload: config.params.on_disk_payload,
                     write_consistency_factor: Some(config.params.write_consistency_factor.get()),
+                    validate_payload: Some(config.params.validate_payload),
+                    payload_validation_mode: Some(api::grpc::qdrant::PayloadValidationMode::from(
+                        config.params.payload_validation_mode,
+                    ) as i32),
+                    sparse_vectors_config: config
+                        .params
+                        .sparse_vectors
+                        .map(sparse_vectors_to_proto),
+                    read_hedge_delay_ms: config.params.read_hedge_delay_ms,
+                    read_only: Some(config.params.read_only),
+                    auto_index_group_by_fields: Some(config.params.auto_index_group_by_fields),
                 }),
                 hnsw_config: Some(api::grpc::qdrant::HnswConfigDiff {
                     m: Some(config.hnsw_config.m as u64),
@@ -265,17 +615,39 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     max_optimization_threads: Some(
                         config.optimizer_config.max_optimization_threads as u64,
                     ),
+                    max_indexing_threads: config
+                        .optimizer_config
+                        .max_indexing_threads
+                        .map(|x| x as u64),
+                    max_segment_number: config
+                        .optimizer_config
+                        .max_segment_number
+                        .map(|x| x as u64),
+                    target_segment_size: config
+                        .optimizer_config
+                        .target_segment_size
+                        .map(|x| x as u64),
                 }),
                 wal_config: Some(api::grpc::qdrant::WalConfigDiff {
                     wal_capacity_mb: Some(config.wal_config.wal_capacity_mb as u64),
                     wal_segments_ahead: Some(config.wal_config.wal_segments_ahead as u64),
                 }),
                 quantization_config: config.quantization_config.map(|x| x.into()),
+                strict_mode_config: config.strict_mode_config.map(|x| x.into()),
+                ttl_config: config.ttl_config.map(|x| x.into()),
+                default_vector: config.default_vector,
+                quota_config: config.quota_config.map(|x| x.into()),
+                admission_config: config.admission_config.map(|x| x.into()),
+                read_cache_config: config.read_cache_config.map(|x| x.into()),
             }),
             payload_schema: payload_schema
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            quota_usage: quota_usage.map(Into::into),
+            warmup: warmup.map(Into::into),
+            index_builds: index_builds.into_iter().map(Into::into).collect(),
+            merge_targets: merge_targets.map(Into::into),
         }
     }
 }
@@ -288,6 +660,14 @@ impl From<Record> for api::grpc::qdrant::RetrievedPoint {
             id: Some(record.id.into()),
             payload: record.payload.map(payload_to_proto).unwrap_or_default(),
             vectors,
+            last_modified: record
+                .last_modified
+                .map(|last_modified| date_time_to_proto(last_modified.naive_utc())),
+            order_value: record.order_value,
+            payload_truncation: record
+                .payload_truncation
+                .map(|truncation| truncation.into()),
+            vector_slice: record.vector_slice.map(|slice| slice.into()),
         }
     }
 }
@@ -315,12 +695,15 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfig {
             default_segment_number: optimizer_config.default_segment_number.unwrap_or_default()
                 as usize,
             max_segment_size: optimizer_config.max_segment_size.map(|x| x as usize),
+            max_segment_number: optimizer_config.max_segment_number.map(|x| x as usize),
+            target_segment_size: optimizer_config.target_segment_size.map(|x| x as usize),
             memmap_threshold: optimizer_config.memmap_threshold.map(|x| x as usize),
             indexing_threshold: optimizer_config.indexing_threshold.map(|x| x as usize),
             flush_interval_sec: optimizer_config.flush_interval_sec.unwrap_or_default(),
             max_optimization_threads: optimizer_config
                 .max_optimization_threads
                 .unwrap_or_default() as usize,
+            max_indexing_threads: optimizer_config.max_indexing_threads.map(|x| x as usize),
         }
     }
 }
@@ -352,6 +735,10 @@ impl TryFrom<api::grpc::qdrant::VectorParams> for VectorParams {
                 None => None,
             },
             on_disk: vector_params.on_disk,
+            datatype: match vector_params.datatype {
+                Some(datatype) => from_grpc_datatype(datatype)?,
+                None => None,
+            },
         })
     }
 }
@@ -455,6 +842,20 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     .ok_or_else(|| {
                         Status::invalid_argument("`write_consistency_factor` cannot be zero")
                     })?,
+                    validate_payload: params.validate_payload.unwrap_or_default(),
+                    payload_validation_mode: params
+                        .payload_validation_mode
+                        .map(payload_validation_mode_from_proto)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    sparse_vectors: params.sparse_vectors_config.map(sparse_vectors_from_proto),
+                    payload_indexes: Default::default(),
+                    payload_cache_fields: Default::default(),
+                    read_hedge_delay_ms: params.read_hedge_delay_ms,
+                    read_only: params.read_only.unwrap_or_default(),
+                    auto_index_group_by_fields: params
+                        .auto_index_group_by_fields
+                        .unwrap_or_default(),
                 },
             },
             hnsw_config: match config.hnsw_config {
@@ -476,6 +877,17 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     None
                 }
             },
+            strict_mode_config: config.strict_mode_config.map(Into::into),
+            // Search defaults are not exposed in gRPC, their types are defined in points.proto
+            default_search_params: None,
+            default_with_payload: None,
+            default_with_vector: None,
+            default_max_payload_return_bytes: None,
+            ttl_config: config.ttl_config.map(Into::into),
+            default_vector: config.default_vector,
+            quota_config: config.quota_config.map(Into::into),
+            admission_config: config.admission_config.map(Into::into),
+            read_cache_config: config.read_cache_config.map(Into::into),
         })
     }
 }
@@ -517,6 +929,17 @@ impl TryFrom<api::grpc::qdrant::GetCollectionInfoResponse> for CollectionInfo {
                     .into_iter()
                     .map(|(k, v)| v.try_into().map(|v| (k, v)))
                     .try_collect()?,
+                quota_usage: collection_info_response.quota_usage.map(Into::into),
+                warmup: collection_info_response
+                    .warmup
+                    .map(TryInto::try_into)
+                    .transpose()?,
+                index_builds: collection_info_response
+                    .index_builds
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                merge_targets: collection_info_response.merge_targets.map(Into::into),
             }),
         }
     }
@@ -632,6 +1055,9 @@ impl From<UpdateResult> for api::grpc::qdrant::UpdateResult {
                 UpdateStatus::Acknowledged => api::grpc::qdrant::UpdateStatus::Acknowledged as i32,
                 UpdateStatus::Completed => api::grpc::qdrant::UpdateStatus::Completed as i32,
             },
+            deleted_count: value.deleted_count.map(|count| count as u64),
+            updated_count: value.updated_count.map(|count| count as u64),
+            counted_before_apply: value.counted_before_apply,
         }
     }
 }
@@ -651,14 +1077,22 @@ impl TryFrom<api::grpc::qdrant::UpdateResult> for UpdateResult {
                 }
                 _ => return Err(Status::invalid_argument("Malformed UpdateStatus type")),
             },
+            deleted_count: value.deleted_count.map(|count| count as usize),
+            updated_count: value.updated_count.map(|count| count as usize),
+            counted_before_apply: value.counted_before_apply,
         })
     }
 }
 
 impl From<api::grpc::qdrant::CountResult> for CountResult {
     fn from(value: api::grpc::qdrant::CountResult) -> Self {
+        let count = value.count as usize;
         Self {
-            count: value.count as usize,
+            count,
+            // Peers of older versions don't report the bounds of the count
+            exact: value.exact.unwrap_or(false),
+            min: value.min.map_or(count, |min| min as usize),
+            max: value.max.map_or(count, |max| max as usize),
         }
     }
 }
@@ -667,6 +1101,9 @@ impl From<CountResult> for api::grpc::qdrant::CountResult {
     fn from(value: CountResult) -> Self {
         Self {
             count: value.count as u64,
+            exact: Some(value.exact),
+            min: Some(value.min as u64),
+            max: Some(value.max as u64),
         }
     }
 }
@@ -676,9 +1113,19 @@ impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
     fn from(value: CollectionSearchRequest<'a>) -> Self {
         let (collection_id, request) = value.0;
 
+        let (vector, sparse_indices) = match &request.vector {
+            NamedVectorStruct::Sparse(sparse) => (
+                sparse.vector.values.clone(),
+                Some(api::grpc::qdrant::SparseIndices {
+                    data: sparse.vector.indices.clone(),
+                }),
+            ),
+            dense => (dense.get_vector().cloned().unwrap_or_default(), None),
+        };
+
         api::grpc::qdrant::SearchPoints {
             collection_name: collection_id,
-            vector: request.vector.get_vector().clone(),
+            vector,
             filter: request.filter.clone().map(|f| f.into()),
             limit: request.limit as u64,
             with_vectors: request.with_vector.clone().map(|wv| wv.into()),
@@ -691,6 +1138,12 @@ impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
                 vector_name => Some(vector_name.to_string()),
             },
             read_consistency: None,
+            with_usage: Some(request.with_usage),
+            allow_partial_results: Some(request.allow_partial_results),
+            sparse_indices,
+            with_shard_trace: Some(request.with_shard_trace),
+            // Payloads are truncated by the peer, which received the request
+            max_payload_return_bytes: None,
         }
     }
 }
@@ -721,19 +1174,53 @@ impl TryFrom<api::grpc::qdrant::WithLookup> for WithLookupInterface {
     }
 }
 
+impl From<WithLookup> for api::grpc::qdrant::WithLookup {
+    fn from(value: WithLookup) -> Self {
+        Self {
+            collection: value.collection_name,
+            with_payload: value.with_payload.map(|wp| wp.into()),
+            with_vectors: value.with_vectors.map(|wv| wv.into()),
+        }
+    }
+}
+
+impl From<WithLookupInterface> for api::grpc::qdrant::WithLookup {
+    fn from(value: WithLookupInterface) -> Self {
+        WithLookup::from(value).into()
+    }
+}
+
+/// Query vector of a gRPC search request, which is sparse if `sparse_indices` are given
+pub fn search_vector_from_proto(
+    vector: Vec<f32>,
+    vector_name: Option<String>,
+    sparse_indices: Option<api::grpc::qdrant::SparseIndices>,
+) -> Result<NamedVectorStruct, Status> {
+    match (vector_name, sparse_indices) {
+        (vector_name, Some(indices)) => {
+            let vector = SparseVector::new(indices.data, vector)
+                .map_err(|err| Status::invalid_argument(format!("Wrong sparse vector: {err}")))?;
+            Ok(NamedSparseVector {
+                name: vector_name.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string()),
+                vector,
+            }
+            .into())
+        }
+        (Some(name), None) => Ok(NamedVector { name, vector }.into()),
+        (None, None) => Ok(vector.into()),
+    }
+}
+
 impl TryFrom<api::grpc::qdrant::SearchPoints> for SearchRequest {
     type Error = Status;
 
     fn try_from(value: api::grpc::qdrant::SearchPoints) -> Result<Self, Self::Error> {
         Ok(SearchRequest {
-            vector: match value.vector_name {
-                Some(vector_name) => NamedVector {
-                    name: vector_name,
-                    vector: value.vector,
-                }
-                .into(),
-                None => value.vector.into(),
-            },
+            vector: search_vector_from_proto(
+                value.vector,
+                value.vector_name,
+                value.sparse_indices,
+            )?,
             filter: value.filter.map(|f| f.try_into()).transpose()?,
             params: value.params.map(|p| p.into()),
             limit: value.limit as usize,
@@ -746,6 +1233,10 @@ impl TryFrom<api::grpc::qdrant::SearchPoints> for SearchRequest {
                     .unwrap_or_default(),
             ),
             score_threshold: value.score_threshold,
+            with_usage: value.with_usage.unwrap_or(false),
+            allow_partial_results: value.allow_partial_results.unwrap_or(false),
+            with_shard_trace: value.with_shard_trace.unwrap_or(false),
+            max_payload_return_bytes: value.max_payload_return_bytes.map(|bytes| bytes as usize),
         })
     }
 }
@@ -754,54 +1245,113 @@ impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequest {
     type Error = Status;
 
     fn try_from(value: api::grpc::qdrant::SearchPointGroups) -> Result<Self, Self::Error> {
-        let search_points = api::grpc::qdrant::SearchPoints {
-            vector: value.vector,
-            filter: value.filter,
-            params: value.params,
-            with_payload: value.with_payload,
-            with_vectors: value.with_vectors,
-            score_threshold: value.score_threshold,
-            vector_name: value.vector_name,
-            limit: 0,
-            offset: None,
-            collection_name: String::new(),
-            read_consistency: None,
-        };
-
-        let SearchRequest {
-            vector,
-            filter,
-            params,
-            limit: _,
-            offset: _,
-            with_payload,
-            with_vector,
-            score_threshold,
-        } = search_points.try_into()?;
-
         Ok(SearchGroupsRequest {
-            vector,
-            filter,
-            params,
-            with_payload,
-            with_vector,
-            score_threshold,
+            vector: search_vector_from_proto(
+                value.vector,
+                value.vector_name,
+                value.sparse_indices,
+            )?,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: value.with_vectors.map(|wv| wv.into()),
+            score_threshold: value.score_threshold,
+            with_usage: value.with_usage.unwrap_or(false),
+            allow_partial_results: value.allow_partial_results.unwrap_or(false),
             group_request: BaseGroupRequest {
                 group_by: value.group_by,
                 limit: value.limit,
                 group_size: value.group_size,
                 with_lookup: value.with_lookup.map(|l| l.try_into()).transpose()?,
+                with_group_by_stats: value.with_group_by_stats.unwrap_or(false),
+                group_keys: group_keys_from_grpc(value.group_keys)?,
+                max_payload_return_bytes: value
+                    .max_payload_return_bytes
+                    .map(|bytes| bytes as usize),
+                group_oversampling: value.group_oversampling,
             },
         })
     }
 }
 
+impl From<CollectionGroupsRequest<SearchGroupsRequest>> for api::grpc::qdrant::SearchPointGroups {
+    fn from(value: CollectionGroupsRequest<SearchGroupsRequest>) -> Self {
+        let CollectionGroupsRequest(collection_name, request) = value;
+
+        let (vector, sparse_indices) = match &request.vector {
+            NamedVectorStruct::Sparse(sparse) => (
+                sparse.vector.values.clone(),
+                Some(api::grpc::qdrant::SparseIndices {
+                    data: sparse.vector.indices.clone(),
+                }),
+            ),
+            dense => (dense.get_vector().cloned().unwrap_or_default(), None),
+        };
+
+        Self {
+            collection_name,
+            vector,
+            filter: request.filter.map(|f| f.into()),
+            limit: request.group_request.limit,
+            with_payload: request.with_payload.map(|wp| wp.into()),
+            params: request.params.map(|sp| sp.into()),
+            score_threshold: request.score_threshold,
+            vector_name: match request.vector.get_name() {
+                DEFAULT_VECTOR_NAME => None,
+                vector_name => Some(vector_name.to_string()),
+            },
+            with_vectors: request.with_vector.map(|wv| wv.into()),
+            group_by: request.group_request.group_by,
+            group_size: request.group_request.group_size,
+            read_consistency: None,
+            with_lookup: request.group_request.with_lookup.map(|l| l.into()),
+            with_usage: Some(request.with_usage),
+            allow_partial_results: Some(request.allow_partial_results),
+            sparse_indices,
+            with_group_by_stats: Some(request.group_request.with_group_by_stats),
+            group_keys: group_keys_to_grpc(request.group_request.group_keys),
+            max_payload_return_bytes: request
+                .group_request
+                .max_payload_return_bytes
+                .map(|bytes| bytes as u64),
+            group_oversampling: request.group_request.group_oversampling,
+        }
+    }
+}
+
+fn group_keys_from_grpc(
+    keys: Vec<api::grpc::qdrant::GroupId>,
+) -> Result<Option<Vec<serde_json::Value>>, Status> {
+    if keys.is_empty() {
+        return Ok(None);
+    }
+    keys.into_iter()
+        .map(serde_json::Value::try_from)
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Keys of unsupported types can't be represented in gRPC, they are left out
+fn group_keys_to_grpc(keys: Option<Vec<serde_json::Value>>) -> Vec<api::grpc::qdrant::GroupId> {
+    keys.into_iter()
+        .flatten()
+        .filter_map(|key| GroupId::try_from(&key).ok())
+        .map(Into::into)
+        .collect()
+}
+
 impl From<PointGroup> for api::grpc::qdrant::PointGroup {
     fn from(group: PointGroup) -> Self {
         Self {
             hits: group.hits.into_iter().map_into().collect(),
             id: Some(group.id.into()),
             lookup: group.lookup.map(|record| record.into()),
+            examples: group
+                .examples
+                .into_iter()
+                .flatten()
+                .map(|record| record.into())
+                .collect(),
         }
     }
 }
@@ -811,6 +1361,17 @@ impl From<api::grpc::qdrant::LookupLocation> for LookupLocation {
         Self {
             collection: value.collection_name,
             vector: value.vector_name,
+            copy_payload_key: value.copy_payload_key,
+        }
+    }
+}
+
+impl From<LookupLocation> for api::grpc::qdrant::LookupLocation {
+    fn from(value: LookupLocation) -> Self {
+        Self {
+            collection_name: value.collection,
+            vector_name: value.vector,
+            copy_payload_key: value.copy_payload_key,
         }
     }
 }
@@ -852,60 +1413,110 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
     type Error = Status;
 
     fn try_from(value: api::grpc::qdrant::RecommendPointGroups) -> Result<Self, Self::Error> {
-        let recommend_points = api::grpc::qdrant::RecommendPoints {
-            positive: value.positive,
-            negative: value.negative,
-            using: value.using,
-            lookup_from: value.lookup_from,
-            filter: value.filter,
-            params: value.params,
-            with_payload: value.with_payload,
-            with_vectors: value.with_vectors,
-            score_threshold: value.score_threshold,
-            read_consistency: None,
-            limit: 0,
-            offset: None,
-            collection_name: String::new(),
-        };
-
-        let RecommendRequest {
-            positive,
-            negative,
-            using,
-            lookup_from,
-            filter,
-            params,
-            with_payload,
-            with_vector,
-            score_threshold,
-            limit: _,
-            offset: _,
-        } = recommend_points.try_into()?;
-
         Ok(RecommendGroupsRequest {
-            positive,
-            negative,
-            using,
-            lookup_from,
-            filter,
-            params,
-            with_payload,
-            with_vector,
-            score_threshold,
+            positive: value
+                .positive
+                .into_iter()
+                .map(|p| p.try_into())
+                .collect::<Result<_, _>>()?,
+            negative: value
+                .negative
+                .into_iter()
+                .map(|p| p.try_into())
+                .collect::<Result<_, _>>()?,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: value.with_vectors.map(|wv| wv.into()),
+            score_threshold: value.score_threshold,
+            using: value.using.map(|name| name.into()),
+            lookup_from: value.lookup_from.map(|x| x.into()),
             group_request: BaseGroupRequest {
                 group_by: value.group_by,
                 limit: value.limit,
                 group_size: value.group_size,
                 with_lookup: value.with_lookup.map(|l| l.try_into()).transpose()?,
+                with_group_by_stats: value.with_group_by_stats.unwrap_or(false),
+                group_keys: group_keys_from_grpc(value.group_keys)?,
+                max_payload_return_bytes: value
+                    .max_payload_return_bytes
+                    .map(|bytes| bytes as usize),
+                group_oversampling: value.group_oversampling,
             },
         })
     }
 }
 
+impl From<CollectionGroupsRequest<RecommendGroupsRequest>>
+    for api::grpc::qdrant::RecommendPointGroups
+{
+    fn from(value: CollectionGroupsRequest<RecommendGroupsRequest>) -> Self {
+        let CollectionGroupsRequest(collection_name, request) = value;
+
+        Self {
+            collection_name,
+            positive: request.positive.into_iter().map(|p| p.into()).collect(),
+            negative: request.negative.into_iter().map(|p| p.into()).collect(),
+            filter: request.filter.map(|f| f.into()),
+            limit: request.group_request.limit,
+            with_payload: request.with_payload.map(|wp| wp.into()),
+            params: request.params.map(|sp| sp.into()),
+            score_threshold: request.score_threshold,
+            using: request.using.map(|UsingVector::Name(name)| name),
+            with_vectors: request.with_vector.map(|wv| wv.into()),
+            lookup_from: request.lookup_from.map(|l| l.into()),
+            group_by: request.group_request.group_by,
+            group_size: request.group_request.group_size,
+            read_consistency: None,
+            with_lookup: request.group_request.with_lookup.map(|l| l.into()),
+            with_group_by_stats: Some(request.group_request.with_group_by_stats),
+            group_keys: group_keys_to_grpc(request.group_request.group_keys),
+            max_payload_return_bytes: request
+                .group_request
+                .max_payload_return_bytes
+                .map(|bytes| bytes as u64),
+            group_oversampling: request.group_request.group_oversampling,
+        }
+    }
+}
+
 impl From<GroupsResult> for api::grpc::qdrant::GroupsResult {
     fn from(value: GroupsResult) -> Self {
         Self {
             groups: value.groups.into_iter().map(Into::into).collect(),
+            stats: value.stats.map(Into::into),
+            warnings: value.warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<GroupByStats> for api::grpc::qdrant::GroupByStats {
+    fn from(value: GroupByStats) -> Self {
+        let GroupByStats {
+            groups_requests,
+            filling_requests,
+            fetched_points,
+            groups_created,
+            groups_filled,
+            groups_requests_limit_reached,
+            filling_requests_limit_reached,
+            dropped_filter_keys,
+            oversampling_factors,
+        } = value;
+
+        Self {
+            groups_requests: groups_requests as u64,
+            filling_requests: filling_requests as u64,
+            fetched_points: fetched_points
+                .into_iter()
+                .map(|count| count as u64)
+                .collect(),
+            groups_created: groups_created as u64,
+            groups_filled: groups_filled as u64,
+            groups_requests_limit_reached,
+            filling_requests_limit_reached,
+            dropped_filter_keys: dropped_filter_keys as u64,
+            oversampling_factors,
         }
     }
 }
@@ -923,10 +1534,80 @@ impl From<VectorParams> for api::grpc::qdrant::VectorParams {
             hnsw_config: value.hnsw_config.map(Into::into),
             quantization_config: value.quantization_config.map(Into::into),
             on_disk: value.on_disk,
+            datatype: value
+                .datatype
+                .map(|datatype| api::grpc::qdrant::Datatype::from(datatype) as i32),
         }
     }
 }
 
+impl From<api::grpc::qdrant::SparseIndexConfig> for SparseIndexConfig {
+    fn from(value: api::grpc::qdrant::SparseIndexConfig) -> Self {
+        Self {
+            full_scan_threshold: value.full_scan_threshold.map(|v| v as usize),
+        }
+    }
+}
+
+impl From<SparseIndexConfig> for api::grpc::qdrant::SparseIndexConfig {
+    fn from(value: SparseIndexConfig) -> Self {
+        Self {
+            full_scan_threshold: value.full_scan_threshold.map(|v| v as u64),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::SparseVectorParams> for SparseVectorParams {
+    fn from(value: api::grpc::qdrant::SparseVectorParams) -> Self {
+        Self {
+            index: value.index.map(Into::into),
+        }
+    }
+}
+
+impl From<SparseVectorParams> for api::grpc::qdrant::SparseVectorParams {
+    fn from(value: SparseVectorParams) -> Self {
+        Self {
+            index: value.index.map(Into::into),
+        }
+    }
+}
+
+pub fn sparse_vectors_from_proto(
+    config: api::grpc::qdrant::SparseVectorConfig,
+) -> BTreeMap<String, SparseVectorParams> {
+    config
+        .map
+        .into_iter()
+        .map(|(name, params)| (name, params.into()))
+        .collect()
+}
+
+pub fn payload_indexes_from_proto(
+    payload_indexes: HashMap<String, api::grpc::qdrant::PayloadSchemaInfo>,
+) -> Result<BTreeMap<PayloadKeyType, PayloadFieldSchema>, Status> {
+    payload_indexes
+        .into_iter()
+        .map(|(field, schema)| {
+            let index_info = PayloadIndexInfo::try_from(schema)?;
+            let field_schema =
+                PayloadFieldSchema::try_from(index_info).map_err(Status::invalid_argument)?;
+            Ok((field, field_schema))
+        })
+        .collect()
+}
+
+pub fn sparse_vectors_to_proto(
+    sparse_vectors: BTreeMap<String, SparseVectorParams>,
+) -> api::grpc::qdrant::SparseVectorConfig {
+    api::grpc::qdrant::SparseVectorConfig {
+        map: sparse_vectors
+            .into_iter()
+            .map(|(name, params)| (name, params.into()))
+            .collect(),
+    }
+}
+
 impl From<AliasDescription> for api::grpc::qdrant::AliasDescription {
     fn from(value: AliasDescription) -> Self {
         api::grpc::qdrant::AliasDescription {
@@ -987,6 +1668,21 @@ impl From<CollectionClusterInfo> for api::grpc::qdrant::CollectionClusterInfoRes
                 .into_iter()
                 .map(|shard| shard.into())
                 .collect(),
+            suggested_moves: value
+                .suggested_moves
+                .into_iter()
+                .map(|shard_move| shard_move.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<MoveShard> for api::grpc::qdrant::MoveShard {
+    fn from(value: MoveShard) -> Self {
+        Self {
+            shard_id: value.shard_id,
+            from_peer_id: value.from_peer_id,
+            to_peer_id: value.to_peer_id,
         }
     }
 }
@@ -1001,6 +1697,26 @@ impl From<api::grpc::qdrant::MoveShard> for MoveShard {
     }
 }
 
+impl From<ShardChecksum> for api::grpc::qdrant::ShardChecksum {
+    fn from(value: ShardChecksum) -> Self {
+        Self {
+            points_count: value.points_count as u64,
+            ids_checksum: value.ids_checksum,
+            versions_checksum: value.versions_checksum,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::ShardChecksum> for ShardChecksum {
+    fn from(value: api::grpc::qdrant::ShardChecksum) -> Self {
+        Self {
+            points_count: value.points_count as usize,
+            ids_checksum: value.ids_checksum,
+            versions_checksum: value.versions_checksum,
+        }
+    }
+}
+
 impl From<ClusterOperationsPb> for ClusterOperations {
     fn from(value: ClusterOperationsPb) -> Self {
         match value {
@@ -1030,3 +1746,174 @@ impl From<ClusterOperationsPb> for ClusterOperations {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use prost::Message;
+    use segment::types::{
+        Condition, FieldCondition, Filter, PayloadSelector, PayloadSelectorInclude, SearchParams,
+        WithPayloadInterface, WithVector,
+    };
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::*;
+
+    /// Converts the request into gRPC and back, passing it through the wire format
+    fn round_trip<T, G>(request: T) -> T
+    where
+        G: Message + Default + From<CollectionGroupsRequest<T>>,
+        T: TryFrom<G, Error = Status>,
+    {
+        let grpc_request = G::from(CollectionGroupsRequest("test".to_string(), request));
+        let decoded = G::decode(grpc_request.encode_to_vec().as_slice()).unwrap();
+        T::try_from(decoded).unwrap()
+    }
+
+    fn assert_same_json<T: Serialize + Debug>(left: &T, right: &T) {
+        assert_eq!(
+            serde_json::to_value(left).unwrap(),
+            serde_json::to_value(right).unwrap(),
+            "{left:?} != {right:?}",
+        );
+    }
+
+    fn group_request() -> BaseGroupRequest {
+        BaseGroupRequest {
+            group_by: "document_id".to_string(),
+            group_size: 3,
+            limit: 10,
+            with_lookup: Some(WithLookupInterface::WithLookup(WithLookup {
+                collection_name: "documents".to_string(),
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vectors: Some(WithVector::Bool(true)),
+            })),
+            with_group_by_stats: true,
+            group_keys: Some(vec![json!(1), json!("a"), json!(-2)]),
+            max_payload_return_bytes: Some(1024),
+            group_oversampling: Some(2.5),
+        }
+    }
+
+    fn filter() -> Filter {
+        Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "city",
+            "Berlin".to_string().into(),
+        )))
+    }
+
+    fn search_groups_request(vector: NamedVectorStruct) -> SearchGroupsRequest {
+        SearchGroupsRequest {
+            vector,
+            filter: Some(filter()),
+            params: Some(SearchParams {
+                hnsw_ef: Some(128),
+                exact: true,
+                ..Default::default()
+            }),
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: Some(WithVector::Selector(vec!["image".to_string()])),
+            score_threshold: Some(0.5),
+            with_usage: true,
+            allow_partial_results: true,
+            group_request: group_request(),
+        }
+    }
+
+    #[test]
+    fn test_search_groups_round_trip() {
+        let dense = search_groups_request(
+            NamedVector {
+                name: "image".to_string(),
+                vector: vec![0.1, 0.2, 0.3, 0.4],
+            }
+            .into(),
+        );
+        let sparse = search_groups_request(
+            NamedSparseVector {
+                name: "text".to_string(),
+                vector: SparseVector::new(vec![1, 7], vec![0.5, 0.25]).unwrap(),
+            }
+            .into(),
+        );
+
+        for request in [dense, sparse] {
+            let converted = round_trip::<_, api::grpc::qdrant::SearchPointGroups>(request.clone());
+            assert_same_json(&converted, &request);
+        }
+    }
+
+    #[test]
+    fn test_recommend_groups_round_trip() {
+        let request = RecommendGroupsRequest {
+            positive: vec![1.into(), 2.into()],
+            negative: vec![3.into()],
+            filter: Some(filter()),
+            params: Some(SearchParams {
+                hnsw_ef: Some(64),
+                ..Default::default()
+            }),
+            with_payload: Some(WithPayloadInterface::Selector(PayloadSelector::Include(
+                PayloadSelectorInclude {
+                    include: vec!["city".to_string()],
+                    exclude: vec!["city.name".to_string()],
+                },
+            ))),
+            with_vector: Some(WithVector::Bool(true)),
+            score_threshold: Some(0.25),
+            using: Some(UsingVector::Name("image".to_string())),
+            lookup_from: Some(LookupLocation {
+                collection: "images".to_string(),
+                vector: Some("photo".to_string()),
+                copy_payload_key: Some("title".to_string()),
+            }),
+            group_request: group_request(),
+        };
+
+        let converted = round_trip::<_, api::grpc::qdrant::RecommendPointGroups>(request.clone());
+        assert_same_json(&converted, &request);
+    }
+
+    #[test]
+    fn test_unset_grpc_fields_match_rest_defaults() {
+        let search = api::grpc::qdrant::SearchPointGroups {
+            vector: vec![0.1, 0.2],
+            group_by: "document_id".to_string(),
+            group_size: 2,
+            limit: 5,
+            ..Default::default()
+        };
+        let rest_search: SearchGroupsRequest = serde_json::from_value(json!({
+            "vector": [0.1, 0.2],
+            "group_by": "document_id",
+            "group_size": 2,
+            "limit": 5,
+        }))
+        .unwrap();
+        assert_same_json(
+            &SearchGroupsRequest::try_from(search).unwrap(),
+            &rest_search,
+        );
+
+        let recommend = api::grpc::qdrant::RecommendPointGroups {
+            positive: vec![PointIdType::from(1).into()],
+            group_by: "document_id".to_string(),
+            group_size: 2,
+            limit: 5,
+            ..Default::default()
+        };
+        let rest_recommend: RecommendGroupsRequest = serde_json::from_value(json!({
+            "positive": [1],
+            "group_by": "document_id",
+            "group_size": 2,
+            "limit": 5,
+        }))
+        .unwrap();
+        assert_same_json(
+            &RecommendGroupsRequest::try_from(recommend).unwrap(),
+            &rest_recommend,
+        );
+    }
+}
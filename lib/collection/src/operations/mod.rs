@@ -1,19 +1,22 @@
+pub mod arrow_ops;
 pub mod cluster_ops;
 pub mod config_diff;
 pub mod consistency_params;
 pub mod conversions;
 pub mod operation_effect;
 pub mod payload_ops;
+pub mod payload_validation;
 pub mod point_ops;
 pub mod shared_storage_config;
 pub mod snapshot_ops;
+pub mod strict_mode;
 pub mod types;
 pub mod validation;
 pub mod vector_ops;
 
 use std::collections::HashMap;
 
-use segment::types::{ExtendedPointId, PayloadFieldSchema};
+use segment::types::{ExtendedPointId, Filter, PayloadFieldSchema};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -34,6 +37,10 @@ pub enum FieldIndexOperations {
     CreateIndex(CreateIndex),
     /// Delete index for the field
     DeleteIndex(String),
+    /// Rebuild index of the field in background, while the current index keeps serving reads
+    RebuildIndex(String),
+    /// Stop building index of the field and remove its partially built storage
+    CancelIndexBuild(String),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -84,6 +91,8 @@ impl FieldIndexOperations {
         match self {
             FieldIndexOperations::CreateIndex(_) => true,
             FieldIndexOperations::DeleteIndex(_) => false,
+            FieldIndexOperations::RebuildIndex(_) => false,
+            FieldIndexOperations::CancelIndexBuild(_) => false,
         }
     }
 }
@@ -93,6 +102,8 @@ impl Validate for FieldIndexOperations {
         match self {
             FieldIndexOperations::CreateIndex(create_index) => create_index.validate(),
             FieldIndexOperations::DeleteIndex(_) => Ok(()),
+            FieldIndexOperations::RebuildIndex(_) => Ok(()),
+            FieldIndexOperations::CancelIndexBuild(_) => Ok(()),
         }
     }
 }
@@ -161,7 +172,60 @@ impl SplitByShard for CollectionUpdateOperations {
     }
 }
 
+/// Effect of an operation on the points, selected by a filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilteredOperation {
+    Delete,
+    Update,
+}
+
 impl CollectionUpdateOperations {
+    /// Filter, which selects the points of the operation, if they are not listed explicitly
+    pub fn points_filter(&self) -> Option<(FilteredOperation, &Filter)> {
+        match self {
+            CollectionUpdateOperations::PointOperation(
+                point_ops::PointOperations::DeletePointsByFilter(filter),
+            ) => Some((FilteredOperation::Delete, filter)),
+            CollectionUpdateOperations::PayloadOperation(operation) => operation
+                .points_filter()
+                .map(|filter| (FilteredOperation::Update, filter)),
+            _ => None,
+        }
+    }
+
+    /// Number of points, listed explicitly in the operation.
+    /// Operations, which select the points by filter, don't list any
+    pub fn listed_points_count(&self) -> usize {
+        match self {
+            CollectionUpdateOperations::PointOperation(operation) => match operation {
+                point_ops::PointOperations::UpsertPoints(insert) => match insert {
+                    point_ops::PointInsertOperations::PointsBatch(batch) => batch.ids.len(),
+                    point_ops::PointInsertOperations::PointsList(points) => points.len(),
+                },
+                point_ops::PointOperations::DeletePoints { ids } => ids.len(),
+                point_ops::PointOperations::DeletePointsByFilter(_) => 0,
+                point_ops::PointOperations::SyncPoints(sync) => sync.points.len(),
+            },
+            CollectionUpdateOperations::VectorOperation(operation) => match operation {
+                vector_ops::VectorOperations::UpdateVectors(update) => update.points.len(),
+                vector_ops::VectorOperations::DeleteVectors(list, _) => list.points.len(),
+                vector_ops::VectorOperations::DeleteVectorsByFilter(..) => 0,
+            },
+            CollectionUpdateOperations::PayloadOperation(operation) => match operation {
+                payload_ops::PayloadOps::SetPayload(set)
+                | payload_ops::PayloadOps::OverwritePayload(set) => {
+                    set.points.as_ref().map_or(0, Vec::len)
+                }
+                payload_ops::PayloadOps::DeletePayload(delete) => {
+                    delete.points.as_ref().map_or(0, Vec::len)
+                }
+                payload_ops::PayloadOps::ClearPayload { points } => points.len(),
+                payload_ops::PayloadOps::ClearPayloadByFilter(_) => 0,
+            },
+            CollectionUpdateOperations::FieldIndexOperation(_) => 0,
+        }
+    }
+
     pub fn is_write_operation(&self) -> bool {
         match self {
             CollectionUpdateOperations::PointOperation(operation) => operation.is_write_operation(),
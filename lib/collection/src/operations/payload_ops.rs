@@ -15,6 +15,7 @@ pub struct SetPayload {
     /// Assigns payload to each point in this list
     pub points: Option<Vec<PointIdType>>,
     /// Assigns payload to each point that satisfy this filter condition
+    #[validate]
     pub filter: Option<Filter>,
 }
 
@@ -60,6 +61,7 @@ pub struct DeletePayload {
     /// Deletes values from each point in this list
     pub points: Option<Vec<PointIdType>>,
     /// Deletes values from points that satisfy this filter condition
+    #[validate]
     pub filter: Option<Filter>,
 }
 
@@ -112,6 +114,26 @@ impl PayloadOps {
             PayloadOps::OverwritePayload(_) => true,
         }
     }
+
+    /// Filter, which selects the points, if they are not listed explicitly
+    pub fn points_filter(&self) -> Option<&Filter> {
+        // Explicit list of points takes precedence over the filter
+        match self {
+            PayloadOps::SetPayload(operation) | PayloadOps::OverwritePayload(operation)
+                if operation.points.is_none() =>
+            {
+                operation.filter.as_ref()
+            }
+            PayloadOps::DeletePayload(operation) if operation.points.is_none() => {
+                operation.filter.as_ref()
+            }
+            PayloadOps::ClearPayloadByFilter(filter) => Some(filter),
+            PayloadOps::SetPayload(_)
+            | PayloadOps::OverwritePayload(_)
+            | PayloadOps::DeletePayload(_)
+            | PayloadOps::ClearPayload { .. } => None,
+        }
+    }
 }
 
 impl Validate for PayloadOps {
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use schemars::JsonSchema;
+use segment::types::{
+    value_type, Payload, PayloadContainer, PayloadFieldSchema, PayloadKeyType, PayloadSchemaParams,
+    PayloadSchemaType, PointIdType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::CollectionUpdateOperations;
+
+/// How to handle points, which payload doesn't match the types of the indexed fields
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadValidationMode {
+    /// Reject the whole operation, if payload of any point is invalid
+    #[default]
+    Operation,
+    /// Reject only the points with invalid payload, the rest of the operation is applied
+    Point,
+}
+
+/// Indexed payload field, which value doesn't match the type of the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPayloadField {
+    pub key: PayloadKeyType,
+    pub expected: PayloadSchemaType,
+}
+
+/// Point with invalid payload. `id` is not set for payload operations, applied to several points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedPoint {
+    pub id: Option<PointIdType>,
+    pub fields: Vec<InvalidPayloadField>,
+}
+
+fn schema_type(schema: &PayloadFieldSchema) -> PayloadSchemaType {
+    match schema {
+        PayloadFieldSchema::FieldType(field_type) => *field_type,
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(_)) => {
+            PayloadSchemaType::Keyword
+        }
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(_)) => PayloadSchemaType::Text,
+    }
+}
+
+fn matches_type(value: &Value, expected: PayloadSchemaType) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(values) => values.iter().all(|value| matches_type(value, expected)),
+        _ => matches!(
+            (value_type(value), expected),
+            (
+                Some(PayloadSchemaType::Keyword),
+                PayloadSchemaType::Keyword | PayloadSchemaType::Text
+            ) | (
+                Some(PayloadSchemaType::Integer),
+                PayloadSchemaType::Integer | PayloadSchemaType::Float
+            ) | (Some(PayloadSchemaType::Float), PayloadSchemaType::Float)
+                | (Some(PayloadSchemaType::Geo), PayloadSchemaType::Geo)
+        ),
+    }
+}
+
+/// Indexed fields of the payload, which values don't match the type of the index.
+/// Fields without index are not checked.
+pub fn invalid_fields(
+    payload: &Payload,
+    schema: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+) -> Vec<InvalidPayloadField> {
+    schema
+        .iter()
+        .map(|(key, field_schema)| (key, schema_type(field_schema)))
+        .filter(|(key, expected)| {
+            !payload
+                .get_value(key)
+                .into_iter()
+                .all(|value| matches_type(value, *expected))
+        })
+        .map(|(key, expected)| InvalidPayloadField {
+            key: key.clone(),
+            expected,
+        })
+        .sorted_by(|a, b| a.key.cmp(&b.key))
+        .collect()
+}
+
+/// Whether the operation assigns payload values, which could be validated
+pub fn sets_payload(operation: &CollectionUpdateOperations) -> bool {
+    matches!(
+        operation,
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(_))
+            | CollectionUpdateOperations::PayloadOperation(
+                PayloadOps::SetPayload(_) | PayloadOps::OverwritePayload(_)
+            )
+    )
+}
+
+fn rejected_point(
+    id: Option<PointIdType>,
+    payload: Option<&Payload>,
+    schema: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+) -> Option<RejectedPoint> {
+    let fields = invalid_fields(payload?, schema);
+    (!fields.is_empty()).then_some(RejectedPoint { id, fields })
+}
+
+/// Description of the rejected points for the error message
+pub fn describe_rejected(rejected: &[RejectedPoint]) -> String {
+    rejected
+        .iter()
+        .map(|point| {
+            let fields = point
+                .fields
+                .iter()
+                .map(|field| format!("`{}` expected to be {:?}", field.key, field.expected))
+                .join(", ");
+            match point.id {
+                Some(id) => format!("point {id}: {fields}"),
+                None => fields,
+            }
+        })
+        .join("; ")
+}
+
+fn invalid_payload_error(rejected: &[RejectedPoint]) -> CollectionError {
+    CollectionError::bad_input(format!(
+        "Payload doesn't match the schema of the indexed fields: {}",
+        describe_rejected(rejected)
+    ))
+}
+
+/// Validate payload values of the operation against the schema of the indexed fields.
+///
+/// In [`PayloadValidationMode::Point`] mode the invalid points are removed from the operation and
+/// returned, so the rest of the operation can be applied. The operation is rejected as a whole
+/// if there are no valid points left, or if the same payload is assigned to several points.
+pub fn validate_operation_payload(
+    operation: &mut CollectionUpdateOperations,
+    schema: &HashMap<PayloadKeyType, PayloadFieldSchema>,
+    mode: PayloadValidationMode,
+) -> CollectionResult<Vec<RejectedPoint>> {
+    if schema.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let points = match operation {
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(points)) => points,
+        CollectionUpdateOperations::PayloadOperation(
+            PayloadOps::SetPayload(set_payload) | PayloadOps::OverwritePayload(set_payload),
+        ) => {
+            return match rejected_point(None, Some(&set_payload.payload), schema) {
+                Some(rejected) => Err(invalid_payload_error(&[rejected])),
+                None => Ok(vec![]),
+            };
+        }
+        _ => return Ok(vec![]),
+    };
+
+    let (rejected, total): (Vec<_>, _) = match points {
+        PointInsertOperations::PointsBatch(batch) => {
+            let payloads = batch.payloads.as_deref().unwrap_or_default();
+            let rejected = payloads
+                .iter()
+                .zip(&batch.ids)
+                .enumerate()
+                .filter_map(|(position, (payload, id))| {
+                    rejected_point(Some(*id), payload.as_ref(), schema)
+                        .map(|rejected| (position, rejected))
+                })
+                .collect();
+            (rejected, batch.ids.len())
+        }
+        PointInsertOperations::PointsList(list) => {
+            let rejected = list
+                .iter()
+                .enumerate()
+                .filter_map(|(position, point)| {
+                    rejected_point(Some(point.id), point.payload.as_ref(), schema)
+                        .map(|rejected| (position, rejected))
+                })
+                .collect();
+            (rejected, list.len())
+        }
+    };
+
+    if rejected.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let (positions, rejected): (Vec<usize>, Vec<RejectedPoint>) = rejected.into_iter().unzip();
+    if mode == PayloadValidationMode::Operation || positions.len() == total {
+        return Err(invalid_payload_error(&rejected));
+    }
+
+    // Positions are sorted, as they are collected in order
+    let is_valid = |position: usize| positions.binary_search(&position).is_err();
+    match points {
+        PointInsertOperations::PointsBatch(batch) => batch.retain_positions(is_valid),
+        PointInsertOperations::PointsList(list) => {
+            let mut position = 0;
+            list.retain(|_| {
+                let keep = is_valid(position);
+                position += 1;
+                keep
+            });
+        }
+    }
+    Ok(rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::point_ops::{Batch, PointStruct};
+
+    fn payload(value: Value) -> Payload {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn schema() -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        HashMap::from([
+            ("city".to_string(), PayloadSchemaType::Keyword.into()),
+            ("rating".to_string(), PayloadSchemaType::Float.into()),
+            ("count".to_string(), PayloadSchemaType::Integer.into()),
+            ("location".to_string(), PayloadSchemaType::Geo.into()),
+        ])
+    }
+
+    fn upsert(payloads: Vec<Value>) -> CollectionUpdateOperations {
+        let points = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(id, value)| PointStruct {
+                id: (id as u64).into(),
+                vector: vec![1.0, 0.0].into(),
+                payload: Some(payload(value)),
+            })
+            .collect::<Vec<_>>();
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(points.into()))
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let schema = schema();
+
+        let valid = payload(json!({
+            "city": ["Berlin", "London"],
+            "rating": 5,
+            "count": null,
+            "location": {"lon": 13.4, "lat": 52.5},
+            "unindexed": {"any": "value"},
+        }));
+        assert!(invalid_fields(&valid, &schema).is_empty());
+
+        let invalid = payload(json!({
+            "city": ["Berlin", 1],
+            "rating": "high",
+            "count": 1.5,
+            "location": {"lon": 13.4},
+        }));
+        let keys: Vec<_> = invalid_fields(&invalid, &schema)
+            .into_iter()
+            .map(|field| field.key)
+            .collect();
+        assert_eq!(keys, vec!["city", "count", "location", "rating"]);
+    }
+
+    #[test]
+    fn test_validate_operation_payload() {
+        let schema = schema();
+        let payloads = vec![
+            json!({"city": "Berlin"}),
+            json!({"city": 1}),
+            json!({"rating": 4.5}),
+        ];
+
+        let mut operation = upsert(payloads.clone());
+        let result =
+            validate_operation_payload(&mut operation, &schema, PayloadValidationMode::Operation);
+        assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+
+        let mut operation = upsert(payloads);
+        let rejected =
+            validate_operation_payload(&mut operation, &schema, PayloadValidationMode::Point)
+                .unwrap();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].id, Some(1u64.into()));
+        match operation {
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(points),
+            )) => {
+                let ids: Vec<_> = points.iter().map(|point| point.id).collect();
+                assert_eq!(ids, vec![0u64.into(), 2u64.into()]);
+            }
+            _ => panic!("unexpected operation"),
+        }
+
+        let mut batch = Batch {
+            ids: vec![0u64.into(), 1u64.into()],
+            vectors: vec![vec![1.0, 0.0], vec![0.0, 1.0]].into(),
+            payloads: Some(vec![Some(payload(json!({"count": "many"}))), None]),
+        };
+        let mut operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(batch.clone().into()),
+        );
+        validate_operation_payload(&mut operation, &schema, PayloadValidationMode::Point).unwrap();
+        batch.retain_positions(|position| position == 1);
+        match operation {
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsBatch(validated),
+            )) => {
+                assert_eq!(validated.ids, batch.ids);
+                assert_eq!(validated.payloads, batch.payloads);
+            }
+            _ => panic!("unexpected operation"),
+        }
+    }
+}
@@ -55,6 +55,10 @@ impl TryFrom<Record> for PointStruct {
             id,
             payload,
             vector,
+            last_modified: _,
+            order_value: _,
+            payload_truncation: _,
+            vector_slice: _,
         } = record;
 
         if vector.is_none() {
@@ -93,6 +97,29 @@ impl Batch {
             payloads: None,
         }
     }
+
+    /// Keep only the points, which positions in the batch satisfy `keep`
+    pub fn retain_positions(&mut self, keep: impl Fn(usize) -> bool) {
+        fn retain<T>(values: &mut Vec<T>, keep: &impl Fn(usize) -> bool) {
+            let mut position = 0;
+            values.retain(|_| {
+                let retain = keep(position);
+                position += 1;
+                retain
+            });
+        }
+
+        retain(&mut self.ids, &keep);
+        match &mut self.vectors {
+            BatchVectorStruct::Single(vectors) => retain(vectors, &keep),
+            BatchVectorStruct::Multi(named_vectors) => named_vectors
+                .values_mut()
+                .for_each(|vectors| retain(vectors, &keep)),
+        }
+        if let Some(payloads) = &mut self.payloads {
+            retain(payloads, &keep);
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -110,6 +137,7 @@ impl From<Vec<PointIdType>> for PointIdsList {
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
 #[serde(rename_all = "snake_case")]
 pub struct FilterSelector {
+    #[validate]
     pub filter: Filter,
 }
 
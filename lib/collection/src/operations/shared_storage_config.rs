@@ -1,7 +1,13 @@
+use crate::collection_manager::optimizers::vacuum_optimizer::FieldIndexBuildConfig;
 use crate::operations::types::NodeType;
+use crate::shards::replica_recovery::ReplicaRecoveryConfig;
+use crate::slow_log::SlowLogConfig;
+use crate::ttl::Clock;
+use crate::warmup::WarmupConfig;
 
 const DEFAULT_UPDATE_QUEUE_SIZE: usize = 100;
 const DEFAULT_UPDATE_QUEUE_SIZE_LISTENER: usize = 10_000;
+pub const DEFAULT_APPLIED_OPERATIONS_WINDOW: usize = 10_000;
 
 /// Storage configuration shared between all collections.
 /// Represents a per-node configuration, which might be changes with restart.
@@ -12,6 +18,15 @@ pub struct SharedStorageConfig {
     pub node_type: NodeType,
     pub handle_collection_load_errors: bool,
     pub recovery_mode: Option<String>,
+    pub background_wal_recovery: bool,
+    pub slow_log: SlowLogConfig,
+    pub warmup: WarmupConfig,
+    /// Number of the latest client-supplied operation ids, remembered by each shard
+    pub applied_operations_window: usize,
+    /// Current time to expire the points by
+    pub clock: Clock,
+    pub replica_recovery: ReplicaRecoveryConfig,
+    pub field_index_build: FieldIndexBuildConfig,
 }
 
 impl Default for SharedStorageConfig {
@@ -21,16 +36,30 @@ impl Default for SharedStorageConfig {
             node_type: Default::default(),
             handle_collection_load_errors: false,
             recovery_mode: None,
+            background_wal_recovery: false,
+            slow_log: Default::default(),
+            warmup: Default::default(),
+            applied_operations_window: DEFAULT_APPLIED_OPERATIONS_WINDOW,
+            clock: Clock::default(),
+            replica_recovery: Default::default(),
+            field_index_build: Default::default(),
         }
     }
 }
 
 impl SharedStorageConfig {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         update_queue_size: Option<usize>,
         node_type: NodeType,
         handle_collection_load_errors: bool,
         recovery_mode: Option<String>,
+        background_wal_recovery: bool,
+        slow_log: SlowLogConfig,
+        warmup: WarmupConfig,
+        applied_operations_window: usize,
+        replica_recovery: ReplicaRecoveryConfig,
+        field_index_build: FieldIndexBuildConfig,
     ) -> Self {
         let update_queue_size = update_queue_size.unwrap_or(match node_type {
             NodeType::Normal => DEFAULT_UPDATE_QUEUE_SIZE,
@@ -42,6 +71,13 @@ impl SharedStorageConfig {
             node_type,
             handle_collection_load_errors,
             recovery_mode,
+            background_wal_recovery,
+            slow_log,
+            warmup,
+            applied_operations_window,
+            clock: Clock::default(),
+            replica_recovery,
+            field_index_build,
         }
     }
 }
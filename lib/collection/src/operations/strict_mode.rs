@@ -0,0 +1,468 @@
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use segment::types::{Condition, Filter, PayloadKeyType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::grouping::group_by::{GroupRequest, SourceRequest};
+use crate::operations::types::{
+    CollectionError, CollectionResult, CountRequest, OrderedQueryRequest, RecommendRequest,
+    ScrollGroupsRequest, ScrollRequest, SearchRequest,
+};
+
+/// Limits of the read requests, which protect the collection from expensive queries
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct StrictModeConfig {
+    /// Whether the limits are enforced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Max allowed `limit` parameter of the read requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_query_limit: Option<usize>,
+    /// Max allowed `offset` parameter of the read requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_query_offset: Option<usize>,
+    /// Max number of conditions in a filter, including the conditions of the nested filters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_filter_conditions: Option<usize>,
+    /// If true - filtering by fields without payload index is forbidden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_payload_index: Option<bool>,
+    /// Max product of `limit` and `group_size` of the grouped requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_group_by_points: Option<usize>,
+    /// How to serve filters with conditions, which can't be served by payload indexes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unindexed_filtering: Option<UnindexedFiltering>,
+}
+
+/// Way to serve filters with conditions, which can't be served by payload indexes
+/// and require a scan of the payload of the points
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum UnindexedFiltering {
+    /// Scan the payload without notice
+    #[default]
+    Allow,
+    /// Serve the request, but warn about the unindexed conditions in the response
+    Warn,
+    /// Reject the request, listing the keys of the unindexed conditions
+    Deny,
+}
+
+impl StrictModeConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    /// Update the limits, which are specified in `diff`, and keep the rest
+    pub fn update(&self, diff: &StrictModeConfig) -> Self {
+        Self {
+            enabled: diff.enabled.or(self.enabled),
+            max_query_limit: diff.max_query_limit.or(self.max_query_limit),
+            max_query_offset: diff.max_query_offset.or(self.max_query_offset),
+            max_filter_conditions: diff.max_filter_conditions.or(self.max_filter_conditions),
+            require_payload_index: diff.require_payload_index.or(self.require_payload_index),
+            max_group_by_points: diff.max_group_by_points.or(self.max_group_by_points),
+            unindexed_filtering: diff.unindexed_filtering.or(self.unindexed_filtering),
+        }
+    }
+
+    pub fn unindexed_filtering(&self) -> UnindexedFiltering {
+        self.unindexed_filtering.unwrap_or_default()
+    }
+
+    pub fn requires_payload_index(&self) -> bool {
+        self.require_payload_index.unwrap_or(false)
+    }
+
+    /// Check the request against the limits.
+    ///
+    /// `indexed_fields` are only checked if known, e.g. if there are local shards of the collection.
+    pub fn check(
+        &self,
+        request: &StrictModeRequest,
+        indexed_fields: Option<&HashSet<PayloadKeyType>>,
+    ) -> CollectionResult<()> {
+        if let (Some(max_limit), Some(limit)) = (self.max_query_limit, request.limit) {
+            if limit > max_limit {
+                return Err(CollectionError::strict_mode(
+                    "max_query_limit",
+                    format!("limit {limit} is greater than {max_limit}"),
+                ));
+            }
+        }
+
+        if let (Some(max_offset), Some(offset)) = (self.max_query_offset, request.offset) {
+            if offset > max_offset {
+                return Err(CollectionError::strict_mode(
+                    "max_query_offset",
+                    format!("offset {offset} is greater than {max_offset}"),
+                ));
+            }
+        }
+
+        if let (Some(max_points), Some(limit), Some(group_size)) =
+            (self.max_group_by_points, request.limit, request.group_size)
+        {
+            let points = limit.saturating_mul(group_size);
+            if points > max_points {
+                return Err(CollectionError::strict_mode(
+                    "max_group_by_points",
+                    format!("limit * group_size = {points} is greater than {max_points}"),
+                ));
+            }
+        }
+
+        if let (true, Some(indexed_fields), Some(group_by)) = (
+            self.requires_payload_index(),
+            indexed_fields,
+            request.group_by,
+        ) {
+            if !indexed_fields.contains(group_by) {
+                return Err(CollectionError::GroupByFieldNotIndexed {
+                    field: group_by.to_string(),
+                });
+            }
+        }
+
+        let Some(filter) = request.filter else {
+            return Ok(());
+        };
+
+        if let Some(max_conditions) = self.max_filter_conditions {
+            let conditions = count_conditions(filter);
+            if conditions > max_conditions {
+                return Err(CollectionError::strict_mode(
+                    "max_filter_conditions",
+                    format!("filter has {conditions} conditions, max allowed is {max_conditions}"),
+                ));
+            }
+        }
+
+        if let (true, Some(indexed_fields)) = (self.requires_payload_index(), indexed_fields) {
+            let mut filter_fields = Vec::new();
+            collect_filter_fields(filter, None, &mut filter_fields);
+            if let Some(unindexed) = filter_fields
+                .iter()
+                .find(|key| !indexed_fields.contains(*key))
+            {
+                return Err(CollectionError::strict_mode(
+                    "require_payload_index",
+                    format!("filtering by field `{unindexed}` without payload index"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parameters of a read request, which are restricted by the strict mode
+#[derive(Debug, Default)]
+pub struct StrictModeRequest<'a> {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub filter: Option<&'a Filter>,
+    /// Only set for the grouped requests
+    pub group_size: Option<usize>,
+    /// Field, the points are grouped by. Only set for the grouped requests
+    pub group_by: Option<&'a str>,
+}
+
+impl<'a> From<&'a SearchRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a SearchRequest) -> Self {
+        Self {
+            limit: Some(request.limit),
+            offset: Some(request.offset),
+            filter: request.filter.as_ref(),
+            group_size: None,
+            group_by: None,
+        }
+    }
+}
+
+impl<'a> From<&'a RecommendRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a RecommendRequest) -> Self {
+        Self {
+            limit: Some(request.limit),
+            offset: Some(request.offset),
+            filter: request.filter.as_ref(),
+            group_size: None,
+            group_by: None,
+        }
+    }
+}
+
+impl<'a> From<&'a GroupRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a GroupRequest) -> Self {
+        let (offset, filter) = match &request.source {
+            SourceRequest::Search(request) => (request.offset, request.filter.as_ref()),
+            SourceRequest::Recommend(request) => (request.offset, request.filter.as_ref()),
+            SourceRequest::Query(request) => (request.offset, request.filter.as_ref()),
+        };
+        Self {
+            limit: Some(request.limit),
+            offset: Some(offset),
+            filter,
+            group_size: Some(request.group_size),
+            group_by: Some(&request.group_by),
+        }
+    }
+}
+
+impl<'a> From<&'a OrderedQueryRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a OrderedQueryRequest) -> Self {
+        Self {
+            limit: Some(request.limit),
+            offset: Some(request.offset),
+            filter: request.filter.as_ref(),
+            group_size: None,
+            group_by: None,
+        }
+    }
+}
+
+impl<'a> From<&'a ScrollRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a ScrollRequest) -> Self {
+        Self {
+            limit: request.limit,
+            offset: None,
+            filter: request.filter.as_ref(),
+            group_size: None,
+            group_by: None,
+        }
+    }
+}
+
+impl<'a> From<&'a ScrollGroupsRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a ScrollGroupsRequest) -> Self {
+        Self {
+            limit: Some(request.limit as usize),
+            offset: None,
+            filter: request.filter.as_ref(),
+            group_size: Some(request.group_size as usize),
+            group_by: Some(&request.group_by),
+        }
+    }
+}
+
+impl<'a> From<&'a CountRequest> for StrictModeRequest<'a> {
+    fn from(request: &'a CountRequest) -> Self {
+        Self {
+            filter: request.filter.as_ref(),
+            ..Default::default()
+        }
+    }
+}
+
+fn count_conditions(filter: &Filter) -> usize {
+    filter
+        .iter_conditions()
+        .map(|condition| match condition {
+            Condition::Filter(filter) => count_conditions(filter),
+            Condition::Nested(nested) => 1 + count_conditions(nested.filter()),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Collect keys of the fields, which are checked by the field conditions of the filter
+fn collect_filter_fields(filter: &Filter, nested_prefix: Option<&str>, fields: &mut Vec<String>) {
+    for condition in filter.iter_conditions() {
+        match condition {
+            Condition::Field(field_condition) => fields.push(match nested_prefix {
+                Some(prefix) => format!("{prefix}.{}", field_condition.key),
+                None => field_condition.key.clone(),
+            }),
+            Condition::Filter(filter) => collect_filter_fields(filter, nested_prefix, fields),
+            Condition::Nested(nested) => {
+                let prefix = match nested_prefix {
+                    Some(prefix) => format!("{prefix}.{}", nested.array_key()),
+                    None => nested.array_key(),
+                };
+                collect_filter_fields(nested.filter(), Some(&prefix), fields)
+            }
+            Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasId(_)
+            | Condition::ModifiedSince(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{FieldCondition, Match};
+
+    use super::*;
+    use crate::operations::types::ErrorReason;
+
+    fn match_condition(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            key,
+            Match::from("value".to_string()),
+        ))
+    }
+
+    fn strict_mode_error_limit(result: CollectionResult<()>) -> String {
+        match result {
+            Err(CollectionError::StrictMode { limit, .. }) => limit,
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_limits() {
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            max_query_limit: Some(100),
+            max_query_offset: Some(1000),
+            max_filter_conditions: Some(2),
+            require_payload_index: Some(true),
+            max_group_by_points: Some(500),
+            unindexed_filtering: Some(UnindexedFiltering::Deny),
+        };
+
+        let request = StrictModeRequest {
+            limit: Some(10),
+            offset: Some(10),
+            ..Default::default()
+        };
+        assert!(config.check(&request, None).is_ok());
+
+        let request = StrictModeRequest {
+            limit: Some(101),
+            ..Default::default()
+        };
+        assert_eq!(
+            strict_mode_error_limit(config.check(&request, None)),
+            "max_query_limit"
+        );
+
+        let request = StrictModeRequest {
+            offset: Some(1_000_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            strict_mode_error_limit(config.check(&request, None)),
+            "max_query_offset"
+        );
+
+        let request = StrictModeRequest {
+            limit: Some(100),
+            group_size: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            strict_mode_error_limit(config.check(&request, None)),
+            "max_group_by_points"
+        );
+
+        let filter = Filter {
+            should: Some(vec![match_condition("a"), match_condition("b")]),
+            min_should: None,
+            must: Some(vec![Condition::Filter(Filter::new_must(match_condition(
+                "c",
+            )))]),
+            must_not: None,
+        };
+        let request = StrictModeRequest {
+            filter: Some(&filter),
+            ..Default::default()
+        };
+        assert_eq!(
+            strict_mode_error_limit(config.check(&request, None)),
+            "max_filter_conditions"
+        );
+    }
+
+    #[test]
+    fn test_strict_mode_requires_payload_index() {
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            require_payload_index: Some(true),
+            ..Default::default()
+        };
+
+        let filter = Filter {
+            should: None,
+            min_should: None,
+            must: Some(vec![
+                match_condition("city"),
+                Condition::new_nested("country", Filter::new_must(match_condition("name"))),
+            ]),
+            must_not: None,
+        };
+        let request = StrictModeRequest {
+            filter: Some(&filter),
+            ..Default::default()
+        };
+
+        let indexed_fields: HashSet<_> = ["city".to_string()].into();
+        assert_eq!(
+            strict_mode_error_limit(config.check(&request, Some(&indexed_fields))),
+            "require_payload_index"
+        );
+
+        let indexed_fields: HashSet<_> = ["city".to_string(), "country[].name".to_string()].into();
+        assert!(config.check(&request, Some(&indexed_fields)).is_ok());
+
+        // Indexed fields are unknown without local shards
+        assert!(config.check(&request, None).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_requires_group_by_index() {
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            require_payload_index: Some(true),
+            ..Default::default()
+        };
+
+        let request = StrictModeRequest {
+            limit: Some(10),
+            group_size: Some(3),
+            group_by: Some("city"),
+            ..Default::default()
+        };
+
+        let indexed_fields: HashSet<_> = ["country".to_string()].into();
+        match config.check(&request, Some(&indexed_fields)) {
+            Err(err @ CollectionError::GroupByFieldNotIndexed { .. }) => {
+                assert_eq!(err.reason(), Some(ErrorReason::GroupByFieldNotIndexed));
+                assert!(err.to_string().contains("`city`"), "{err}");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let indexed_fields: HashSet<_> = ["city".to_string()].into();
+        assert!(config.check(&request, Some(&indexed_fields)).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_update() {
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            max_query_limit: Some(100),
+            ..Default::default()
+        };
+        let updated = config.update(&StrictModeConfig {
+            max_query_offset: Some(10),
+            unindexed_filtering: Some(UnindexedFiltering::Warn),
+            ..Default::default()
+        });
+        assert!(updated.is_enabled());
+        assert_eq!(updated.max_query_limit, Some(100));
+        assert_eq!(updated.max_query_offset, Some(10));
+        assert_eq!(updated.unindexed_filtering(), UnindexedFiltering::Warn);
+        assert_eq!(config.unindexed_filtering(), UnindexedFiltering::Allow);
+    }
+}
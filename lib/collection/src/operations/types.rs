@@ -4,25 +4,32 @@ use std::error::Error as _;
 use std::fmt::Write as _;
 use std::iter;
 use std::num::NonZeroU64;
-use std::time::SystemTimeError;
+use std::time::{Duration, SystemTimeError};
 
+pub use api::grpc::models::{Warning, WarningReason};
 use api::grpc::transport_channel_pool::RequestError;
+use chrono::{DateTime, Utc};
 use futures::io;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::common::file_operations::FileStorageError;
 use segment::data_types::groups::GroupId;
+use segment::data_types::order_by::OrderBy;
 use segment::data_types::vectors::{
-    NamedVectorStruct, VectorStruct, VectorType, DEFAULT_VECTOR_NAME,
+    NamedVectorStruct, Vector, VectorStruct, VectorType, DEFAULT_VECTOR_NAME,
 };
 use segment::entry::entry_point::OperationError;
+use segment::index::field_index::CardinalityEstimation;
+use segment::telemetry::HnswGraphHealth;
 use segment::types::{
-    Distance, Filter, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
-    ScoreType, ScoredPoint, SearchParams, SeqNumberType, WithPayloadInterface, WithVector,
+    Distance, Filter, Indexes, Payload, PayloadIndexInfo, PayloadKeyType, PayloadTruncation,
+    PointIdType, QuantizationConfig, ScoreType, ScoredPoint, SearchParams, SegmentType,
+    SeqNumberType, SparseIndexConfig, VectorSlice, VectorStorageDatatype, WithPayloadInterface,
+    WithVector,
 };
 use serde;
 use serde::{Deserialize, Serialize};
-use serde_json::Error as JsonError;
+use serde_json::{Error as JsonError, Value};
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::oneshot::error::RecvError as OneshotRecvError;
@@ -32,11 +39,17 @@ use validator::{Validate, ValidationErrors};
 
 use crate::config::CollectionConfig;
 use crate::lookup::types::WithLookupInterface;
+use crate::operations::cluster_ops::MoveShard;
 use crate::operations::config_diff::HnswConfigDiff;
+use crate::operations::FilteredOperation;
+use crate::optimizers_builder::OptimizersConfig;
+use crate::quota::QuotaUsage;
 use crate::save_on_disk;
+use crate::shards::replica_recovery::RecoveryState;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::wal::WalError;
+use crate::warmup::WarmupProgress;
 
 /// Current state of the collection.
 /// `Green` - all good. `Yellow` - optimization is running, `Red` - some operations failed and was not recovered
@@ -67,6 +80,167 @@ pub enum OptimizersStatus {
     Error(String),
 }
 
+/// Description of an optimization, which would be launched next, without actually running it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct OptimizationPlan {
+    /// Name of the optimizer which would be launched
+    pub optimizer: String,
+    /// Ids of the segments which would be optimized together
+    pub segment_ids: Vec<usize>,
+    /// Estimated size of vector data of the resulting segment in bytes
+    pub estimated_size_bytes: usize,
+}
+
+/// Optimization plan of a single local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardOptimizationPlan {
+    pub shard_id: ShardId,
+    /// Next optimization of the shard, `None` if the shard is already optimal
+    pub next: Option<OptimizationPlan>,
+}
+
+/// Optimization plans of the collection shards, located on this peer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct OptimizersPlan {
+    pub shards: Vec<ShardOptimizationPlan>,
+}
+
+/// Status of a single shard of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardStatus {
+    pub shard_id: ShardId,
+    pub status: CollectionStatus,
+}
+
+/// Outcome of waiting for the collection to reach a status
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct WaitForStatusResult {
+    /// If true - all shards reached the requested status before the timeout
+    pub reached: bool,
+    /// Current status of each shard of the collection
+    pub shards: Vec<ShardStatus>,
+}
+
+/// Layout of a single segment of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentDescription {
+    /// Id of the segment within the shard. Changes, once the segment is wrapped for optimization
+    pub segment_id: usize,
+    /// Name of the segment directory, which identifies the segment on disk.
+    /// Segments under optimization are reported with the name of the wrapped segment.
+    pub uuid: String,
+    pub segment_type: SegmentType,
+    pub is_appendable: bool,
+    /// Number of points, available in the segment
+    pub points_count: usize,
+    /// Number of points, marked as deleted and still occupying the segment storage
+    pub deleted_count: usize,
+    /// Vector index of each named vector
+    pub vector_indexes: HashMap<String, Indexes>,
+    /// Payload fields, indexed in the segment
+    pub payload_indexes: Vec<PayloadKeyType>,
+    /// Size of the segment directory on disk
+    pub disk_usage_bytes: u64,
+    /// Segment is locked by an ongoing optimization. Its points are still available for reads,
+    /// while updates are written into a separate temporary segment.
+    pub is_optimizing: bool,
+    /// Degradation of the HNSW graph of each indexed vector, caused by deleted points
+    pub graph_health: HashMap<String, HnswGraphHealth>,
+}
+
+/// Segments of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardSegmentsInfo {
+    pub shard_id: ShardId,
+    pub segments: Vec<SegmentDescription>,
+}
+
+/// Flush of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardFlushResult {
+    pub shard_id: ShardId,
+    pub success: bool,
+    /// Number of the last operation, which is persisted on disk after the flush
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flushed_operation: Option<SeqNumberType>,
+    /// Components of the shard, which failed to flush, with their errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Flush of all shards of the collection, located on this peer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionFlushResult {
+    /// `Acknowledged` - flush is started in background.
+    /// `Completed` - flush is finished, results of each shard are reported.
+    pub status: UpdateStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shards: Vec<ShardFlushResult>,
+}
+
+/// Progress of the rebuild of a payload field index in a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardIndexRebuildProgress {
+    pub shard_id: ShardId,
+    /// Number of segments, which are rebuilding the index of the field.
+    /// The current index of these segments keeps serving reads until the rebuild is complete.
+    pub rebuilding_segments: usize,
+    /// Points of the rebuilding segments, which are already indexed
+    pub indexed_points: usize,
+    /// All points of the rebuilding segments
+    pub total_points: usize,
+}
+
+/// Progress of the creation of a payload field index in a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FieldIndexBuildProgress {
+    pub field_name: PayloadKeyType,
+    /// Id of the operation, which started the creation of the index in the shard.
+    /// The build can be cancelled by this id.
+    pub operation_id: SeqNumberType,
+    pub shard_id: ShardId,
+    /// Number of segments, which are building the index.
+    /// Conditions on the field are checked against the payload in these segments until then.
+    pub building_segments: usize,
+    /// Points of the building segments, which are already indexed
+    pub indexed_points: usize,
+    /// All points of the building segments
+    pub total_points: usize,
+}
+
+/// Effective targets of the merge optimizer, with defaults resolved on this peer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct MergeTargets {
+    /// Merges are not proposed while the number of segments is at or below this value
+    pub max_segment_number: usize,
+    /// Segments of this size (in kilobytes) or larger are not merged
+    pub target_segment_size_kb: usize,
+    /// Merges never produce segments larger than this size (in kilobytes)
+    pub max_segment_size_kb: usize,
+}
+
+impl From<&OptimizersConfig> for MergeTargets {
+    fn from(config: &OptimizersConfig) -> Self {
+        Self {
+            max_segment_number: config.get_max_segment_number(),
+            target_segment_size_kb: config.get_target_segment_size(),
+            max_segment_size_kb: config.get_max_segment_size(),
+        }
+    }
+}
+
 /// Point data
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -77,6 +251,19 @@ pub struct Record {
     pub payload: Option<Payload>,
     /// Vector of the point
     pub vector: Option<VectorStruct>,
+    /// Time of the last update of the point, if requested. Unknown for points not updated since
+    /// the timestamps were introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Value of the `order_by` key of the point, if the points are ordered by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_value: Option<f64>,
+    /// Set if the payload is truncated to fit into `max_payload_return_bytes`
+    #[serde(flatten)]
+    pub payload_truncation: Option<PayloadTruncation>,
+    /// Dimensions of the vector, if requested with the slice `with_vector` selector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_slice: Option<VectorSlice>,
 }
 
 /// Current statistics and configuration of the collection
@@ -104,8 +291,24 @@ pub struct CollectionInfo {
     /// Collection settings
     #[validate]
     pub config: CollectionConfig,
-    /// Types of stored payload
+    /// Types of stored payload, with the statistics of their indexes.
+    /// Statistics are summed over the shards, counting a single replica of each shard
     pub payload_schema: HashMap<PayloadKeyType, PayloadIndexInfo>,
+    /// Data, counted against the quotas of the collection, summed over the shards.
+    /// Only reported if the collection has quotas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_usage: Option<QuotaUsage>,
+    /// Progress of the latest warmup of the local shards, made on this peer.
+    /// Not reported if there was no warmup since the start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<WarmupProgress>,
+    /// Payload field indexes, which are being created in the local shards of this peer.
+    /// Not reported if there are none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub index_builds: Vec<FieldIndexBuildProgress>,
+    /// Effective targets of the merge optimizer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_targets: Option<MergeTargets>,
 }
 
 /// Current clustering distribution for the collection
@@ -121,6 +324,9 @@ pub struct CollectionClusterInfo {
     pub remote_shards: Vec<RemoteShardInfo>,
     /// Shard transfers
     pub shard_transfers: Vec<ShardTransferInfo>,
+    /// Shard moves, which would even out the placement of the shards on the peers
+    #[serde(default)]
+    pub suggested_moves: Vec<MoveShard>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -142,6 +348,9 @@ pub struct LocalShardInfo {
     pub points_count: usize,
     /// Is replica active
     pub state: ReplicaState,
+    /// Automatic recovery of the replica, if it is dead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryState>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -157,20 +366,79 @@ pub struct RemoteShardInfo {
 
 /// `Acknowledged` - Request is saved to WAL and will be process in a queue.
 /// `Completed` - Request is completed, changes are actual.
-#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateStatus {
     Acknowledged,
     Completed,
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct UpdateResult {
     /// Sequential number of the operation
     pub operation_id: SeqNumberType,
     /// Update status
     pub status: UpdateStatus,
+    /// Number of points, deleted by a filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_count: Option<usize>,
+    /// Number of points, which payload is updated by a filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_count: Option<usize>,
+    /// If true - the count is computed, when the operation is accepted, before it is applied.
+    /// Points, changed by the preceding operations in the queue, are not taken into account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counted_before_apply: Option<bool>,
+}
+
+impl UpdateResult {
+    pub fn new(operation_id: SeqNumberType, status: UpdateStatus) -> Self {
+        Self {
+            operation_id,
+            status,
+            deleted_count: None,
+            updated_count: None,
+            counted_before_apply: None,
+        }
+    }
+
+    /// Report the number of points, affected by the operation with the given filter
+    pub fn with_filtered_count(
+        mut self,
+        operation: FilteredOperation,
+        count: usize,
+        counted_before_apply: bool,
+    ) -> Self {
+        match operation {
+            FilteredOperation::Delete => self.deleted_count = Some(count),
+            FilteredOperation::Update => self.updated_count = Some(count),
+        }
+        self.counted_before_apply = Some(counted_before_apply);
+        self
+    }
+
+    /// Combine the results of the same operation, applied to different shards.
+    ///
+    /// Operation id and status of the `other` result are kept, counts of the points are summed.
+    pub fn merge_shard_result(self, other: Self) -> Self {
+        fn sum(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+            match (a, b) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+            }
+        }
+        Self {
+            operation_id: other.operation_id,
+            status: other.status,
+            deleted_count: sum(self.deleted_count, other.deleted_count),
+            updated_count: sum(self.updated_count, other.updated_count),
+            counted_before_apply: match (self.counted_before_apply, other.counted_before_apply) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(false) || b.unwrap_or(false)),
+            },
+        }
+    }
 }
 
 /// Scroll request - paginate over all points which matches given condition
@@ -183,12 +451,21 @@ pub struct ScrollRequest {
     #[validate(range(min = 1))]
     pub limit: Option<usize>,
     /// Look only for points which satisfies this conditions. If not provided - all points.
+    #[validate]
     pub filter: Option<Filter>,
     /// Select which payload to return with the response. Default: All
     pub with_payload: Option<WithPayloadInterface>,
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Whether to return the time of the last update of the points. Default: false
+    #[serde(default)]
+    pub with_timestamps: bool,
+    /// Limit of the serialized JSON payload of each returned point, in bytes.
+    /// Larger payloads are truncated by dropping their largest fields. Default: collection default
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub max_payload_return_bytes: Option<usize>,
 }
 
 impl Default for ScrollRequest {
@@ -199,6 +476,8 @@ impl Default for ScrollRequest {
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(false),
+            with_timestamps: false,
+            max_payload_return_bytes: None,
         }
     }
 }
@@ -222,6 +501,7 @@ pub struct SearchRequest {
     /// Look for vectors closest to this
     pub vector: NamedVectorStruct,
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
     /// Additional search params
     #[validate]
@@ -245,6 +525,21 @@ pub struct SearchRequest {
     /// Score of the returned result might be higher or smaller than the threshold depending on the
     /// Distance function used. E.g. for cosine similarity only higher scores will be returned.
     pub score_threshold: Option<ScoreType>,
+    /// Return the amount of work, done to serve the request, in the `usage` field of the response
+    #[serde(default)]
+    pub with_usage: bool,
+    /// If true - shards, which failed to respond, are skipped and listed in the `partial` field of the response.
+    /// Otherwise a failure of any shard fails the whole request
+    #[serde(default)]
+    pub allow_partial_results: bool,
+    /// Return the replicas, which served the shards, and their latency in the `trace` of the response
+    #[serde(default)]
+    pub with_shard_trace: bool,
+    /// Limit of the serialized JSON payload of each returned point, in bytes.
+    /// Larger payloads are truncated by dropping their largest fields. Default: collection default
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub max_payload_return_bytes: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -254,12 +549,128 @@ pub struct SearchRequestBatch {
     pub searches: Vec<SearchRequest>,
 }
 
+/// Fuse the results of several searches into a single ranking.
+/// Searches may use different named vectors, e.g. a dense and a sparse one.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct FusionRequest {
+    /// Searches to fuse. `limit` of each search defines how many candidates it contributes,
+    /// while its `offset`, `with_payload` and `with_vector` are ignored
+    #[validate(length(min = 1))]
+    #[validate]
+    pub searches: Vec<SearchRequest>,
+    /// How to combine the scores of the searches
+    pub fusion: Fusion,
+    /// Max number of fused results to return
+    #[validate(range(min = 1))]
+    pub limit: usize,
+    /// Offset of the first fused result to return
+    #[serde(default)]
+    pub offset: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+    /// Return the rank of each point in every search, for debugging the fusion
+    #[serde(default)]
+    pub with_ranks: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Fusion {
+    /// Reciprocal rank fusion: the score of a point is the sum of `1 / (k + rank)` over searches
+    Rrf(RrfParams),
+    /// Scores of each search are normalized into `[0, 1]` and summed up with weights
+    MinMax(MinMaxParams),
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct RrfParams {
+    /// Dampens the impact of the top ranks. Default: 60
+    #[serde(default = "default_rrf_k")]
+    pub k: usize,
+}
+
+pub const fn default_rrf_k() -> usize {
+    60
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct MinMaxParams {
+    /// Weight of each search, in the order of `searches`. Default: 1 for every search
+    pub weights: Option<Vec<f32>>,
+}
+
+/// Point of the fused result
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct FusedPoint {
+    #[serde(flatten)]
+    pub point: ScoredPoint,
+    /// Rank of the point in each search, starting from 1. `null` if the search did not return the point.
+    /// Ranks are counted within the shard, holding the point. Only present if `with_ranks` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranks: Option<Vec<Option<usize>>>,
+}
+
+/// Query without a vector: return the first points in the order of a numeric payload field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderedQueryRequest {
+    /// Look only for points which satisfies this conditions
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Payload field to order the points by, and the direction of the order
+    #[validate]
+    pub order_by: OrderBy,
+    /// Max number of points to return
+    #[serde(alias = "top")]
+    #[validate(range(min = 1))]
+    pub limit: usize,
+    /// Offset of the first point to return
+    #[serde(default)]
+    pub offset: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+}
+
+/// Group the first points in the order of a numeric payload field, without a query vector.
+/// Scores of the hits are the values of the `order_by` field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct QueryGroupsRequest {
+    /// Look only for points which satisfies this conditions
+    #[validate]
+    pub filter: Option<Filter>,
+
+    /// Payload field to order the points by, and the direction of the order
+    #[validate]
+    pub order_by: OrderBy,
+
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+
+    #[serde(flatten)]
+    #[validate]
+    pub group_request: BaseGroupRequest,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct SearchGroupsRequest {
     /// Look for vectors closest to this
     pub vector: NamedVectorStruct,
 
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
 
     /// Additional search params
@@ -279,6 +690,15 @@ pub struct SearchGroupsRequest {
     /// Distance function used. E.g. for cosine similarity only higher scores will be returned.
     pub score_threshold: Option<ScoreType>,
 
+    /// Return the amount of work, done by all searches of the grouping, in the `usage` field of the response
+    #[serde(default)]
+    pub with_usage: bool,
+
+    /// If true - shards, which failed to respond in any of the searches of the grouping, are skipped
+    /// and listed in the `partial` field of the response
+    #[serde(default)]
+    pub allow_partial_results: bool,
+
     #[serde(flatten)]
     #[validate]
     pub group_request: BaseGroupRequest,
@@ -294,6 +714,14 @@ pub struct PointRequest {
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Whether to return the time of the last update of the points. Default: false
+    #[serde(default)]
+    pub with_timestamps: bool,
+    /// Limit of the serialized JSON payload of each returned point, in bytes.
+    /// Larger payloads are truncated by dropping their largest fields. Default: collection default
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub max_payload_return_bytes: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -318,8 +746,13 @@ pub struct LookupLocation {
     pub collection: String,
     /// Optional name of the vector field within the collection.
     /// If not provided, the default vector field will be used.
+    /// Must have the same size and distance as the vector used for the search.
     #[serde(default)]
     pub vector: Option<String>,
+    /// Grouped recommendations only: copy the value of this payload key of the looked up examples
+    /// into each of the result groups, e.g. to display the examples next to the results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_payload_key: Option<String>,
 }
 
 /// Recommendation request.
@@ -338,6 +771,7 @@ pub struct RecommendRequest {
     #[serde(default)]
     pub negative: Vec<PointIdType>,
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
     /// Additional search params
     #[validate]
@@ -387,6 +821,7 @@ pub struct RecommendGroupsRequest {
     pub negative: Vec<PointIdType>,
 
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
 
     /// Additional search params
@@ -428,11 +863,89 @@ pub struct PointGroup {
     /// Record that has been looked up using the group id
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lookup: Option<Record>,
+    /// Examples of the recommendation, looked up with `lookup_from.copy_payload_key`.
+    /// Payload of the examples contains only the value of this key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<Record>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct GroupsResult {
     pub groups: Vec<PointGroup>,
+    /// Statistics of the internal requests, only if requested with `with_group_by_stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<GroupByStats>,
+    /// Issues of the request, which doesn't prevent it from being served, e.g. slow execution
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+}
+
+/// Statistics of the internal requests, made to find and fill up the groups
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct GroupByStats {
+    /// Amount of requests, made to find the best groups
+    pub groups_requests: usize,
+    /// Amount of requests, made to fill up the best groups, which have less than `group_size` hits
+    pub filling_requests: usize,
+    /// Amount of points, fetched by each of the requests, in the order they were made
+    pub fetched_points: Vec<usize>,
+    /// Amount of groups, the fetched points belong to
+    pub groups_created: usize,
+    /// Amount of the returned groups, which have `group_size` hits
+    pub groups_filled: usize,
+    /// Whether the requests to find the groups have stopped at their maximum amount
+    pub groups_requests_limit_reached: bool,
+    /// Whether the requests to fill up the groups have stopped at their maximum amount
+    pub filling_requests_limit_reached: bool,
+    /// Amount of group keys, left out of the filters of the requests.
+    /// Only string and integer keys, fitting into `i64`, can be matched.
+    pub dropped_filter_keys: usize,
+    /// Oversampling factors of the requests, made to find the best groups, in their order
+    pub oversampling_factors: Vec<f32>,
+}
+
+/// Grouped request along with the name of the collection to run it on, e.g. to make a gRPC request
+#[derive(Debug, Clone)]
+pub struct CollectionGroupsRequest<T>(pub String, pub T);
+
+/// Scroll groups request - paginate over the groups of points which match given condition.
+/// Groups are ordered by the value of the `group_by` key, hits inside of a group - by point id.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ScrollGroupsRequest {
+    /// Look only for points which satisfies this conditions. If not provided - all points.
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Payload field to group by, must be a string or number field.
+    /// If the field contains more than 1 value, all values will be used for grouping.
+    /// One point can be in multiple groups.
+    #[validate(length(min = 1))]
+    pub group_by: String,
+    /// Maximum amount of points to return per group
+    #[validate(range(min = 1))]
+    pub group_size: u32,
+    /// Maximum amount of groups to return
+    #[validate(range(min = 1))]
+    pub limit: u32,
+    /// Value of the group_by key to start reading groups from.
+    /// Numeric keys go before the string ones.
+    pub offset: Option<GroupId>,
+    /// Select which payload to return with the response. Default: All
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: WithVector,
+}
+
+/// Result of the scroll groups request
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ScrollGroupsResult {
+    /// Groups of points, ordered by the group key. Scores of the hits are always 0
+    pub groups: Vec<PointGroup>,
+    /// Group key which should be used to retrieve a next page result
+    pub next_page_offset: Option<GroupId>,
 }
 
 /// Count Request
@@ -442,6 +955,7 @@ pub struct GroupsResult {
 #[serde(rename_all = "snake_case")]
 pub struct CountRequest {
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
     /// If true, count exact number of points. If false, count approximate number of points faster.
     /// Approximate count might be unreliable during the indexing process. Default: true
@@ -458,6 +972,100 @@ pub const fn default_exact_count() -> bool {
 pub struct CountResult {
     /// Number of points which satisfy the conditions
     pub count: usize,
+    /// If true, the `count` is exact. Otherwise it is estimated with the payload indexes
+    #[serde(default)]
+    pub exact: bool,
+    /// Smallest possible number of points which satisfy the conditions. Equals `count`, if exact
+    #[serde(default)]
+    pub min: usize,
+    /// Largest possible number of points which satisfy the conditions. Equals `count`, if exact
+    #[serde(default)]
+    pub max: usize,
+}
+
+impl CountResult {
+    pub fn exact(count: usize) -> Self {
+        Self {
+            count,
+            exact: true,
+            min: count,
+            max: count,
+        }
+    }
+
+    /// Count of points, which is only bounded by the cardinality estimation
+    pub fn estimated(cardinality: &CardinalityEstimation) -> Self {
+        Self {
+            count: cardinality.exp,
+            exact: false,
+            min: cardinality.min,
+            max: cardinality.max,
+        }
+    }
+
+    /// Total count of the points of several shards.
+    /// It is only exact if the counts of all shards are exact.
+    pub fn sum<'a>(counts: impl IntoIterator<Item = &'a CountResult>) -> Self {
+        counts
+            .into_iter()
+            .fold(Self::exact(0), |total, count| Self {
+                count: total.count + count.count,
+                exact: total.exact && count.exact,
+                min: total.min + count.min,
+                max: total.max + count.max,
+            })
+    }
+}
+
+/// Check Filter Request
+/// Checks the filter against a single point and reports the outcome of every condition.
+/// Helps to find out why the point doesn't show up in the filtered search.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(rename_all = "snake_case")]
+pub struct CheckFilterRequest {
+    /// Point to check
+    pub id: PointIdType,
+    /// Filter to check the point against
+    #[validate]
+    pub filter: Filter,
+}
+
+/// Stable code of the reason of an error, which clients can branch on
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorReason {
+    /// Strict mode requires a payload index on the field, the points are grouped by
+    GroupByFieldNotIndexed,
+    /// Values of the field, the points are grouped by, can't be used as group keys
+    UnsupportedGroupKeyType,
+    /// Points, used as examples of a recommendation, are not found
+    RecommendExampleNotFound,
+    /// Collection to look up the points from is not found
+    LookupCollectionMissing,
+    /// Requested vector is not configured in the collection
+    VectorNameMismatch,
+}
+
+impl ErrorReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorReason::GroupByFieldNotIndexed => "group_by_field_not_indexed",
+            ErrorReason::UnsupportedGroupKeyType => "unsupported_group_key_type",
+            ErrorReason::RecommendExampleNotFound => "recommend_example_not_found",
+            ErrorReason::LookupCollectionMissing => "lookup_collection_missing",
+            ErrorReason::VectorNameMismatch => "vector_name_mismatch",
+        }
+    }
+
+    /// If the error is caused by a missing entity rather than a malformed request
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            ErrorReason::RecommendExampleNotFound | ErrorReason::LookupCollectionMissing => true,
+            ErrorReason::GroupByFieldNotIndexed
+            | ErrorReason::UnsupportedGroupKeyType
+            | ErrorReason::VectorNameMismatch => false,
+        }
+    }
 }
 
 #[derive(Error, Debug, Clone)]
@@ -492,6 +1100,80 @@ pub enum CollectionError {
     ForwardProxyError { peer_id: PeerId, error: Box<Self> },
     #[error("Out of memory, free: {free}, {description}")]
     OutOfMemory { description: String, free: u64 },
+    #[error("Forbidden by strict mode, {limit}: {description}")]
+    StrictMode { limit: String, description: String },
+    #[error("Under-replicated: {description}")]
+    UnderReplicated { description: String },
+    #[error("Forbidden: {description}")]
+    Forbidden { description: String },
+    #[error("Quota exceeded, {quota}: {description}")]
+    QuotaExceeded { quota: String, description: String },
+    #[error("Rate limited: {description}")]
+    RateLimited {
+        description: String,
+        /// Suggested delay before the retry
+        retry_after: Duration,
+    },
+    #[error(
+        "Bad request: field `{field}` must have a payload index to group by it in strict mode"
+    )]
+    GroupByFieldNotIndexed { field: String },
+    #[error(
+        "Bad request: field `{field}` is indexed as {field_type}, \
+         only keyword and integer values can be used as group keys"
+    )]
+    UnsupportedGroupKeyType { field: String, field_type: String },
+    #[error(
+        "Bad request: group key {key} of field `{field}` is not supported, \
+         only strings and integers can be used as group keys"
+    )]
+    UnsupportedGroupKey { field: String, key: String },
+    #[error("No points with ids {} found to use as examples", display_ids(.ids))]
+    RecommendExampleNotFound { ids: Vec<PointIdType> },
+    #[error("Collection `{name}` to look up the points from not found")]
+    LookupCollectionMissing { name: String },
+    #[error(
+        "Wrong input: {} is not configured{}, available vectors: {}",
+        display_vector_name(.requested),
+        display_collection_suffix(.collection),
+        display_vector_names(.available)
+    )]
+    VectorNameMismatch {
+        requested: String,
+        available: Vec<String>,
+        /// Collection, the vector is requested from, if it is not the collection of the request
+        collection: Option<String>,
+    },
+}
+
+fn display_vector_name(name: &str) -> String {
+    if name == DEFAULT_VECTOR_NAME {
+        "default vector".to_string()
+    } else {
+        format!("vector `{name}`")
+    }
+}
+
+fn display_vector_names(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| display_vector_name(name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_collection_suffix(collection: &Option<String>) -> String {
+    collection
+        .as_ref()
+        .map(|name| format!(" in collection `{name}`"))
+        .unwrap_or_default()
+}
+
+fn display_ids(ids: &[PointIdType]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl CollectionError {
@@ -514,6 +1196,40 @@ impl CollectionError {
         CollectionError::BadShardSelection { description }
     }
 
+    pub fn strict_mode(limit: &str, description: String) -> CollectionError {
+        CollectionError::StrictMode {
+            limit: limit.to_string(),
+            description,
+        }
+    }
+
+    pub fn forbidden(description: String) -> CollectionError {
+        CollectionError::Forbidden { description }
+    }
+
+    pub fn quota_exceeded(quota: &str, description: String) -> CollectionError {
+        CollectionError::QuotaExceeded {
+            quota: quota.to_string(),
+            description,
+        }
+    }
+
+    pub fn rate_limited(description: String, retry_after: Duration) -> CollectionError {
+        CollectionError::RateLimited {
+            description,
+            retry_after,
+        }
+    }
+
+    /// Operation is applied, but by fewer replicas than the client asked to wait for
+    pub fn under_replicated(applied: usize, required: usize) -> CollectionError {
+        CollectionError::UnderReplicated {
+            description: format!(
+                "operation is applied by {applied} out of {required} required replicas"
+            ),
+        }
+    }
+
     pub fn forward_proxy_error(peer_id: PeerId, error: impl Into<Self>) -> Self {
         Self::ForwardProxyError {
             peer_id,
@@ -527,6 +1243,22 @@ impl CollectionError {
             _ => None,
         }
     }
+
+    /// Stable code of the reason of the error, if the error has one
+    pub fn reason(&self) -> Option<ErrorReason> {
+        match self {
+            Self::GroupByFieldNotIndexed { .. } => Some(ErrorReason::GroupByFieldNotIndexed),
+            Self::UnsupportedGroupKeyType { .. } | Self::UnsupportedGroupKey { .. } => {
+                Some(ErrorReason::UnsupportedGroupKeyType)
+            }
+            Self::RecommendExampleNotFound { .. } => Some(ErrorReason::RecommendExampleNotFound),
+            Self::LookupCollectionMissing { .. } => Some(ErrorReason::LookupCollectionMissing),
+            Self::VectorNameMismatch { .. } => Some(ErrorReason::VectorNameMismatch),
+            Self::InconsistentShardFailure { first_err, .. } => first_err.reason(),
+            Self::ForwardProxyError { error, .. } => error.reason(),
+            _ => None,
+        }
+    }
 }
 
 impl From<SystemTimeError> for CollectionError {
@@ -553,6 +1285,12 @@ impl From<OperationError> for CollectionError {
             OperationError::WrongVector { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
+            OperationError::WrongVectorElement { .. } => Self::BadInput {
+                description: format!("{err}"),
+            },
+            OperationError::WrongSparseVector { .. } => Self::BadInput {
+                description: format!("{err}"),
+            },
             OperationError::VectorNameNotExists { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
@@ -675,6 +1413,16 @@ impl From<tonic::Status> for CollectionError {
                 error: format!("Internal error: {err}"),
                 backtrace: Some(Backtrace::force_capture().to_string()),
             },
+            // Update, forwarded to the leader, is not replicated enough
+            tonic::Code::Aborted => CollectionError::UnderReplicated {
+                description: err
+                    .message()
+                    .trim_start_matches("Under-replicated: ")
+                    .to_string(),
+            },
+            tonic::Code::PermissionDenied => CollectionError::Forbidden {
+                description: err.message().trim_start_matches("Forbidden: ").to_string(),
+            },
             other => CollectionError::ServiceError {
                 error: format!("Tonic status error: {other}"),
                 backtrace: Some(Backtrace::force_capture().to_string()),
@@ -759,7 +1507,7 @@ impl Record {
                     None
                 }
             }
-            Some(VectorStruct::Multi(vectors)) => vectors.get(name),
+            Some(VectorStruct::Multi(vectors)) => vectors.get(name).and_then(Vector::as_dense),
             None => None,
         }
     }
@@ -789,6 +1537,14 @@ pub struct VectorParams {
     /// Default: false
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub on_disk: Option<bool>,
+    /// Type of the stored vector elements, converted from and to float32 on the fly.
+    /// Float16 halves the memory footprint at the cost of precision.
+    /// Uint8 stores pre-quantized vectors with integer elements in range 0..=255,
+    /// it can't be combined with quantization.
+    /// Can not be changed after the collection is created.
+    /// Default: float32
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<VectorStorageDatatype>,
 }
 
 impl Anonymize for VectorParams {
@@ -797,6 +1553,24 @@ impl Anonymize for VectorParams {
     }
 }
 
+/// Params of single sparse vector data storage
+#[derive(
+    Debug, Default, Hash, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorParams {
+    /// Custom params for the inverted index. If none - default values are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub index: Option<SparseIndexConfig>,
+}
+
+impl Anonymize for SparseVectorParams {
+    fn anonymize(&self) -> Self {
+        self.clone()
+    }
+}
+
 /// Vector params separator for single and multiple vector modes
 /// Single mode:
 ///
@@ -836,6 +1610,61 @@ impl VectorsConfig {
         }
     }
 
+    /// Check that the vectors, which have quantization configured, are stored in a datatype,
+    /// which can be quantized.
+    ///
+    /// `collection_quantization` is the quantization explicitly requested for the whole collection.
+    pub fn check_quantization_compatible(
+        &self,
+        collection_quantization: Option<&QuantizationConfig>,
+    ) -> CollectionResult<()> {
+        for (vector_name, params) in self.params_iter() {
+            let datatype = params.datatype.unwrap_or_default();
+            if datatype.supports_quantization() {
+                continue;
+            }
+            if params.quantization_config.is_some() || collection_quantization.is_some() {
+                return Err(CollectionError::BadInput {
+                    description: format!(
+                        "Quantization is not supported for vector {vector_name} with datatype {datatype:?}",
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the default vector of the collection is one of its dense vectors.
+    pub fn check_default_vector(&self, default_vector: Option<&str>) -> CollectionResult<()> {
+        match default_vector {
+            Some(vector_name) if self.get_params(vector_name).is_none() => {
+                Err(CollectionError::BadInput {
+                    description: format!(
+                        "Default vector {vector_name} is not a dense vector of the collection",
+                    ),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that names of sparse vectors don't clash with names of dense vectors.
+    pub fn check_sparse_vectors_compatible(
+        &self,
+        sparse_vectors: Option<&BTreeMap<String, SparseVectorParams>>,
+    ) -> CollectionResult<()> {
+        for vector_name in sparse_vectors.into_iter().flat_map(|sparse| sparse.keys()) {
+            if self.get_params(vector_name).is_some() {
+                return Err(CollectionError::BadInput {
+                    description: format!(
+                        "Sparse vector {vector_name} has the same name as a dense vector",
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
     fn check_vector_params_compatibility(
         self_params: &VectorParams,
         other_params: &VectorParams,
@@ -859,6 +1688,17 @@ impl VectorsConfig {
             });
         }
 
+        let self_datatype = self_params.datatype.unwrap_or_default();
+        let other_datatype = other_params.datatype.unwrap_or_default();
+        if self_datatype != other_datatype {
+            return Err(CollectionError::BadInput {
+                description: format!(
+                    "Vectors configuration is not compatible: origin vector {} datatype: {:?}, while other vector datatype: {:?}",
+                    vector_name, self_datatype, other_datatype
+                )
+            });
+        }
+
         Ok(())
     }
 
@@ -984,4 +1824,30 @@ pub struct BaseGroupRequest {
 
     /// Look for points in another collection using the group ids
     pub with_lookup: Option<WithLookupInterface>,
+
+    /// Return the statistics of the internal requests of the grouping in the `stats` field of
+    /// the response, e.g. to find out why less groups than requested are returned
+    #[serde(default)]
+    pub with_group_by_stats: bool,
+
+    /// Return the best points of exactly these groups, in the same order.
+    /// Keys must be strings or integers, integers may also be given in the stringified form.
+    /// Groups without points are returned empty.
+    /// If set, `limit` is not applied
+    #[serde(default)]
+    #[validate(length(min = 1, max = 1000))]
+    pub group_keys: Option<Vec<Value>>,
+
+    /// Limit of the serialized JSON payload of each returned point, in bytes.
+    /// Larger payloads are truncated by dropping their largest fields. Default: collection default
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub max_payload_return_bytes: Option<usize>,
+
+    /// Amount of points, fetched by the first request to find the groups, relative to
+    /// `limit * group_size`. It is doubled for the next request, up to 16, if less than a half
+    /// of `limit` groups are found. Default: 1
+    #[serde(default)]
+    #[validate(range(min = 0.1, max = 100.0))]
+    pub group_oversampling: Option<f32>,
 }
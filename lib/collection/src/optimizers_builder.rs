@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use schemars::JsonSchema;
 use segment::common::cpu::get_num_cpus;
+use segment::index::hnsw_index::max_rayon_threads;
 use segment::types::{HnswConfig, QuantizationConfig};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -10,7 +11,9 @@ use validator::Validate;
 use crate::collection_manager::optimizers::indexing_optimizer::IndexingOptimizer;
 use crate::collection_manager::optimizers::merge_optimizer::MergeOptimizer;
 use crate::collection_manager::optimizers::segment_optimizer::OptimizerThresholds;
-use crate::collection_manager::optimizers::vacuum_optimizer::VacuumOptimizer;
+use crate::collection_manager::optimizers::vacuum_optimizer::{
+    FieldIndexBuildConfig, VacuumOptimizer,
+};
 use crate::config::CollectionParams;
 use crate::update_handler::Optimizer;
 
@@ -45,6 +48,19 @@ pub struct OptimizersConfig {
     #[serde(alias = "max_segment_size_kb")]
     #[serde(default)]
     pub max_segment_size: Option<usize>,
+    /// Maximum number of segments the merge optimizer will try to keep.
+    /// Merges are not proposed while the number of segments is at or below this value.
+    /// If not set or `0`, `default_segment_number` is used.
+    #[serde(default)]
+    pub max_segment_number: Option<usize>,
+    /// Size (in kilobytes), at which segments are considered large enough and are not merged anymore.
+    /// Merges always pick the smallest segments first and never produce segments above `max_segment_size`.
+    ///
+    /// Note: 1Kb = 1 vector of size 256
+    /// If not set, `max_segment_size` is used.
+    #[serde(alias = "target_segment_size_kb")]
+    #[serde(default)]
+    pub target_segment_size: Option<usize>,
     /// Maximum size (in kilobytes) of vectors to store in-memory per segment.
     /// Segments larger than this threshold will be stored as read-only memmaped file.
     ///
@@ -70,6 +86,12 @@ pub struct OptimizersConfig {
     pub flush_interval_sec: u64,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: usize,
+    /// Maximum number of threads, which all concurrent optimizations of a shard can use for building indexes.
+    /// Each optimization takes as many of them as allowed by `max_indexing_threads` of the HNSW config,
+    /// optimizations are postponed while all threads are busy.
+    /// If not set or `0` - `NUM_CPU - 1` will be used.
+    #[serde(default)]
+    pub max_indexing_threads: Option<usize>,
 }
 
 impl OptimizersConfig {
@@ -80,10 +102,27 @@ impl OptimizersConfig {
             vacuum_min_vector_number: 1000,
             default_segment_number: 0,
             max_segment_size: None,
+            max_segment_number: None,
+            target_segment_size: None,
             memmap_threshold: None,
             indexing_threshold: Some(100_000),
             flush_interval_sec: 60,
             max_optimization_threads: 0,
+            max_indexing_threads: None,
+        }
+    }
+
+    /// Number of threads, shared by all optimizations of a shard for building indexes
+    pub fn get_max_indexing_threads(&self) -> usize {
+        max_rayon_threads(self.max_indexing_threads.unwrap_or(0))
+    }
+
+    /// Size of vectors (in kilobytes), above which a segment is expected to be indexed
+    pub fn get_indexing_threshold_kb(&self) -> usize {
+        match self.indexing_threshold {
+            None => DEFAULT_INDEXING_THRESHOLD_KB, // default value
+            Some(0) => usize::MAX,                 // disable vector index
+            Some(custom) => custom,
         }
     }
 
@@ -106,6 +145,22 @@ impl OptimizersConfig {
             num_cpus.saturating_mul(DEFAULT_MAX_SEGMENT_PER_CPU_KB)
         }
     }
+
+    /// Number of segments, at or below which the merge optimizer stops merging
+    pub fn get_max_segment_number(&self) -> usize {
+        match self.max_segment_number {
+            None | Some(0) => self.get_number_segments(),
+            Some(max_segment_number) => max_segment_number,
+        }
+    }
+
+    /// Size of segments (in kilobytes), which are not merged anymore. Never exceeds the max segment size
+    pub fn get_target_segment_size(&self) -> usize {
+        let max_segment_size = self.get_max_segment_size();
+        self.target_segment_size
+            .unwrap_or(max_segment_size)
+            .min(max_segment_size)
+    }
 }
 
 pub fn build_optimizers(
@@ -114,15 +169,12 @@ pub fn build_optimizers(
     optimizers_config: &OptimizersConfig,
     hnsw_config: &HnswConfig,
     quantization_config: &Option<QuantizationConfig>,
+    field_index_build: &FieldIndexBuildConfig,
 ) -> Arc<Vec<Arc<Optimizer>>> {
     let segments_path = shard_path.join("segments");
     let temp_segments_path = shard_path.join("temp_segments");
 
-    let indexing_threshold = match optimizers_config.indexing_threshold {
-        None => DEFAULT_INDEXING_THRESHOLD_KB, // default value
-        Some(0) => usize::MAX,                 // disable vector index
-        Some(custom) => custom,
-    };
+    let indexing_threshold = optimizers_config.get_indexing_threshold_kb();
 
     let memmap_threshold = match optimizers_config.memmap_threshold {
         None | Some(0) => usize::MAX, // default | disable memmap
@@ -137,7 +189,8 @@ pub fn build_optimizers(
 
     Arc::new(vec![
         Arc::new(MergeOptimizer::new(
-            optimizers_config.get_number_segments(),
+            optimizers_config.get_max_segment_number(),
+            optimizers_config.get_target_segment_size(),
             threshold_config.clone(),
             segments_path.clone(),
             temp_segments_path.clone(),
@@ -162,6 +215,7 @@ pub fn build_optimizers(
             collection_params.clone(),
             hnsw_config.clone(),
             quantization_config.clone(),
+            field_index_build.clone(),
         )),
     ])
 }
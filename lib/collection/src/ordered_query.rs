@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures::future::try_join_all;
+use tracing::Instrument;
+
+use crate::collection::Collection;
+use crate::common::measured_operation::{measure_phase, record_requests, record_shards};
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::strict_mode::StrictModeRequest;
+use crate::operations::types::{CollectionResult, OrderedQueryRequest, Record};
+use crate::shards::shard::ShardId;
+use crate::slow_log::RequestDetails;
+use crate::telemetry::CollectionOperationType;
+
+/// Return the first points in the order of a numeric payload field, without a query vector.
+///
+/// Function works in following stages:
+///
+/// - Checks that the field has an integer or float payload index
+/// - Every shard reads its first `offset + limit` points from the index, in the requested order
+/// - Points of the shards are merged by the values of the field, ties are broken by id
+///
+/// The values of the field are returned in the `order_value` of the points.
+pub async fn query_ordered(
+    mut request: OrderedQueryRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> CollectionResult<Vec<Record>> {
+    if let Some(config) = collection.config_for_defaults(shard_selection).await {
+        config.apply_search_defaults(
+            &mut None,
+            &mut request.with_payload,
+            &mut request.with_vector,
+        );
+        if let Some(with_vector) = &request.with_vector {
+            config.params.check_with_vector(with_vector)?;
+        }
+    }
+    collection
+        .check_strict_mode([StrictModeRequest::from(&request)], shard_selection)
+        .await?;
+    collection
+        .check_order_by_field(&request.order_by.key, shard_selection)
+        .await?;
+    collection
+        .restrict_read_filters([&mut request.filter], shard_selection)
+        .await;
+    collection
+        .measure_operation(CollectionOperationType::Query, shard_selection, async {
+            record_requests(|| vec![RequestDetails::from(&request)]);
+            query_ordered_impl(request, collection, read_consistency, shard_selection).await
+        })
+        .await
+}
+
+async fn query_ordered_impl(
+    request: OrderedQueryRequest,
+    collection: &Collection,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> CollectionResult<Vec<Record>> {
+    let limit = request.limit;
+    let offset = request.offset;
+    let direction = request.order_by.direction;
+
+    // Shards don't know the points of each other, so the offset is skipped after the merge
+    let shard_request = Arc::new(OrderedQueryRequest {
+        limit: offset + limit,
+        offset: 0,
+        ..request
+    });
+
+    collection.check_not_deleted()?;
+    let shard_points: Vec<_> = {
+        let shards_holder = collection.shards_holder.read().await;
+        let target_shards = shards_holder.target_shard(shard_selection)?;
+        record_shards(target_shards.iter().map(|shard| shard.shard_id));
+        let query_futures = target_shards.into_iter().map(|shard| {
+            shard
+                .query_ordered(shard_request.clone(), read_consistency)
+                .instrument(tracing::info_span!(
+                    "shard_query",
+                    shard_id = shard.shard_id
+                ))
+        });
+
+        measure_phase("query", try_join_all(query_futures)).await?
+    };
+
+    // Replicas of a shard are resolved by id, so the points are sorted again
+    let order_value = |point: &Record| point.order_value.unwrap_or_default();
+    let mut points: Vec<Record> = shard_points.into_iter().flatten().collect();
+    points.sort_unstable_by(|a, b| {
+        direction
+            .compare(order_value(a), order_value(b))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    // The same point might come from several shards, e.g. while it is being transferred
+    let mut seen = HashSet::new();
+    Ok(points
+        .into_iter()
+        .filter(|point| seen.insert(point.id))
+        .skip(offset)
+        .take(limit)
+        .map(|point| Record {
+            last_modified: None,
+            ..point
+        })
+        .collect())
+}
@@ -0,0 +1,658 @@
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+use parking_lot::{Mutex, RwLock};
+use schemars::JsonSchema;
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::sparse_vector::{DimId, DimWeight};
+use segment::data_types::vectors::{BatchVectorStruct, VectorElementType, DEFAULT_VECTOR_NAME};
+use segment::types::{Filter, Payload, PointIdType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::collection_manager::holders::segment_holder::SegmentHolder;
+use crate::collection_manager::segments_updater::points_by_filter;
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::CollectionUpdateOperations;
+
+/// Limits of the data, stored in the collection.
+///
+/// Updates, which would exceed a limit, are rejected. Updates, which don't increase the usage,
+/// e.g. deletions, are always accepted, so the data can be freed after a limit is lowered.
+/// Limits are split evenly between the shards of the collection, each shard enforces its share.
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct QuotaConfig {
+    /// Max number of points in the collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_points: Option<usize>,
+    /// Max total size of the vectors in bytes.
+    /// Dense vectors take 4 bytes per dimension, sparse vectors take 8 bytes per non-zero value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_vectors_size_bytes: Option<usize>,
+    /// Max total size of the payloads in bytes, measured as the size of their JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_payload_size_bytes: Option<usize>,
+}
+
+impl QuotaConfig {
+    /// Update the limits, which are specified in `diff`, and keep the rest
+    pub fn update(&self, diff: &QuotaConfig) -> Self {
+        Self {
+            max_points: diff.max_points.or(self.max_points),
+            max_vectors_size_bytes: diff.max_vectors_size_bytes.or(self.max_vectors_size_bytes),
+            max_payload_size_bytes: diff.max_payload_size_bytes.or(self.max_payload_size_bytes),
+        }
+    }
+
+    /// Limits of a single shard out of `shard_number`
+    pub fn shard_share(&self, shard_number: u32) -> Self {
+        let shard_number = shard_number.max(1) as usize;
+        let share = |limit: Option<usize>| {
+            limit.map(|limit| limit.saturating_add(shard_number - 1) / shard_number)
+        };
+        Self {
+            max_points: share(self.max_points),
+            max_vectors_size_bytes: share(self.max_vectors_size_bytes),
+            max_payload_size_bytes: share(self.max_payload_size_bytes),
+        }
+    }
+
+    /// Check the usage, which the update would lead to.
+    pub fn check(&self, current: &QuotaUsage, updated: &QuotaUsage) -> CollectionResult<()> {
+        let limits = [
+            (
+                "max_points",
+                self.max_points,
+                current.points,
+                updated.points,
+            ),
+            (
+                "max_vectors_size_bytes",
+                self.max_vectors_size_bytes,
+                current.vectors_size_bytes,
+                updated.vectors_size_bytes,
+            ),
+            (
+                "max_payload_size_bytes",
+                self.max_payload_size_bytes,
+                current.payload_size_bytes,
+                updated.payload_size_bytes,
+            ),
+        ];
+        for (quota, limit, current, updated) in limits {
+            let Some(limit) = limit else {
+                continue;
+            };
+            if updated > limit && updated > current {
+                return Err(CollectionError::quota_exceeded(
+                    quota,
+                    format!(
+                        "update would increase the usage from {current} to {updated}, \
+                         the limit is {limit}"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Data, counted against the quotas
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct QuotaUsage {
+    /// Number of points
+    pub points: usize,
+    /// Total size of the vectors in bytes
+    pub vectors_size_bytes: usize,
+    /// Total size of the payloads in bytes
+    pub payload_size_bytes: usize,
+}
+
+impl QuotaUsage {
+    pub fn add(&mut self, other: &QuotaUsage) {
+        self.points += other.points;
+        self.vectors_size_bytes += other.vectors_size_bytes;
+        self.payload_size_bytes += other.payload_size_bytes;
+    }
+
+    /// Usage with the data of `before` replaced by the data of `after`
+    fn replace(&self, before: &QuotaUsage, after: &QuotaUsage) -> QuotaUsage {
+        QuotaUsage {
+            points: self.points.saturating_sub(before.points) + after.points,
+            vectors_size_bytes: self
+                .vectors_size_bytes
+                .saturating_sub(before.vectors_size_bytes)
+                + after.vectors_size_bytes,
+            payload_size_bytes: self
+                .payload_size_bytes
+                .saturating_sub(before.payload_size_bytes)
+                + after.payload_size_bytes,
+        }
+    }
+}
+
+/// Usage of a single shard.
+///
+/// The usage is only tracked once the collection has quotas, so the updates of other collections
+/// don't pay for it. The points are counted once, when the tracking starts, and then the usage
+/// is adjusted by each applied operation.
+#[derive(Debug, Default)]
+pub struct QuotaUsageTracker {
+    usage: Mutex<Option<QuotaUsage>>,
+}
+
+impl QuotaUsageTracker {
+    /// Current usage, if tracked
+    pub fn usage(&self) -> Option<QuotaUsage> {
+        *self.usage.lock()
+    }
+
+    /// Current usage. Starts the tracking, if it is not started yet
+    pub fn tracked_usage(&self, segments: &RwLock<SegmentHolder>) -> CollectionResult<QuotaUsage> {
+        let mut usage = self.usage.lock();
+        if let Some(usage) = *usage {
+            return Ok(usage);
+        }
+        let counted = count_usage(&segments.read())?;
+        *usage = Some(counted);
+        Ok(counted)
+    }
+
+    /// Check, that the usage after the operation fits into the `quota`.
+    ///
+    /// Operations, which are written to WAL but not applied yet, are not counted.
+    pub fn check(
+        &self,
+        segments: &RwLock<SegmentHolder>,
+        operation: &CollectionUpdateOperations,
+        quota: &QuotaConfig,
+    ) -> CollectionResult<()> {
+        let current = self.tracked_usage(segments)?;
+        let change = operation_usage_change(&segments.read(), operation)?;
+        quota.check(&current, &current.replace(&change.before, &change.after))
+    }
+
+    /// Apply the operation with `apply` and adjust the usage, if it is tracked.
+    ///
+    /// The usage is locked for the whole operation, so it's never counted half-applied.
+    pub fn apply<T>(
+        &self,
+        segments: &RwLock<SegmentHolder>,
+        operation: CollectionUpdateOperations,
+        apply: impl FnOnce(CollectionUpdateOperations) -> CollectionResult<T>,
+    ) -> CollectionResult<T> {
+        let mut usage = self.usage.lock();
+        let Some(current) = usage.as_mut() else {
+            return apply(operation);
+        };
+        let change = operation_usage_change(&segments.read(), &operation);
+        let result = apply(operation)?;
+        match change {
+            Ok(change) => *current = current.replace(&change.before, &change.after),
+            Err(err) => {
+                // Count the points again on the next request instead of drifting
+                log::warn!("Can't estimate quota usage of the operation, recounting: {err}");
+                *usage = None;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Usage of all points of the shard
+fn count_usage(segments: &SegmentHolder) -> CollectionResult<QuotaUsage> {
+    let mut usage = QuotaUsage::default();
+    let mut counted = HashSet::new();
+    segments.for_each_segment(|segment| {
+        for point_id in segment.iter_points() {
+            // A point, which is moved between the segments, is counted once
+            if !counted.insert(point_id) {
+                continue;
+            }
+            let vectors = segment.all_vectors(point_id)?;
+            let payload = segment.payload(point_id)?;
+            usage.add(&PointUsage::new(&vectors, &payload).usage());
+        }
+        Ok(true)
+    })?;
+    Ok(usage)
+}
+
+/// Usage of the points, affected by an operation
+struct UsageChange {
+    before: QuotaUsage,
+    after: QuotaUsage,
+}
+
+/// Data of a single point, which is counted against the quotas.
+/// Payload is kept as is for the operations, which update a part of it
+#[derive(Clone)]
+struct PointUsage {
+    vectors: HashMap<String, usize>,
+    payload: Payload,
+}
+
+impl PointUsage {
+    fn new(vectors: &NamedVectors, payload: &Payload) -> Self {
+        Self {
+            vectors: named_vectors_size(vectors),
+            payload: payload.clone(),
+        }
+    }
+
+    fn usage(&self) -> QuotaUsage {
+        QuotaUsage {
+            points: 1,
+            vectors_size_bytes: self.vectors.values().sum(),
+            payload_size_bytes: payload_size(&self.payload),
+        }
+    }
+}
+
+fn named_vectors_size(vectors: &NamedVectors) -> HashMap<String, usize> {
+    let dense = vectors
+        .iter()
+        .map(|(name, vector)| (name, vector.len() * size_of::<VectorElementType>()));
+    let sparse = vectors.sparse_iter().map(|(name, vector)| {
+        (
+            name,
+            vector.len() * (size_of::<DimId>() + size_of::<DimWeight>()),
+        )
+    });
+    dense
+        .chain(sparse)
+        .map(|(name, size)| (name.to_string(), size))
+        .collect()
+}
+
+fn payload_size(payload: &Payload) -> usize {
+    if payload.is_empty() {
+        return 0;
+    }
+    serde_json::to_vec(payload).map_or(0, |json| json.len())
+}
+
+/// Points, stored in the shard, with their usage
+fn stored_points(
+    segments: &SegmentHolder,
+    ids: &[PointIdType],
+) -> CollectionResult<HashMap<PointIdType, PointUsage>> {
+    let mut points = HashMap::new();
+    segments.read_points(ids, |point_id, segment| {
+        if !points.contains_key(&point_id) {
+            let vectors = segment.all_vectors(point_id)?;
+            let payload = segment.payload(point_id)?;
+            points.insert(point_id, PointUsage::new(&vectors, &payload));
+        }
+        Ok(true)
+    })?;
+    Ok(points)
+}
+
+/// Usage of the points, which the operation touches, before and after the operation.
+///
+/// Operations, which fail, e.g. because of a missing point, have no effect on the usage.
+fn operation_usage_change(
+    segments: &SegmentHolder,
+    operation: &CollectionUpdateOperations,
+) -> CollectionResult<UsageChange> {
+    // Points after the operation, `None` for the deleted ones
+    let mut updated: HashMap<PointIdType, Option<PointUsage>> = HashMap::new();
+
+    let stored = match operation {
+        CollectionUpdateOperations::PointOperation(operation) => match operation {
+            PointOperations::UpsertPoints(PointInsertOperations::PointsList(points)) => {
+                let ids: Vec<_> = points.iter().map(|point| point.id).collect();
+                let stored = stored_points(segments, &ids)?;
+                for point in points {
+                    updated.insert(point.id, Some(upserted_point(point, &stored)));
+                }
+                stored
+            }
+            PointOperations::UpsertPoints(PointInsertOperations::PointsBatch(batch)) => {
+                let stored = stored_points(segments, &batch.ids)?;
+                for (idx, id) in batch.ids.iter().enumerate() {
+                    let payload = match batch.payloads.as_ref().and_then(|p| p.get(idx)) {
+                        Some(Some(payload)) => payload.clone(),
+                        _ => stored_payload(&stored, id),
+                    };
+                    let point = PointUsage {
+                        vectors: batch_vectors_size(&batch.vectors, idx),
+                        payload,
+                    };
+                    updated.insert(*id, Some(point));
+                }
+                stored
+            }
+            PointOperations::DeletePoints { ids } => {
+                updated.extend(ids.iter().map(|id| (*id, None)));
+                stored_points(segments, ids)?
+            }
+            PointOperations::DeletePointsByFilter(filter) => {
+                let ids = points_by_filter(segments, filter)?;
+                updated.extend(ids.iter().map(|id| (*id, None)));
+                stored_points(segments, &ids)?
+            }
+            PointOperations::SyncPoints(operation) => {
+                // Points of the range, which are not in the operation, are deleted
+                let mut ids = HashSet::new();
+                segments.for_each_segment(|segment| {
+                    ids.extend(segment.read_range(operation.from_id, operation.to_id));
+                    Ok(true)
+                })?;
+                ids.extend(operation.points.iter().map(|point| point.id));
+                let ids: Vec<_> = ids.into_iter().collect();
+                let stored = stored_points(segments, &ids)?;
+                updated.extend(ids.iter().map(|id| (*id, None)));
+                for point in &operation.points {
+                    updated.insert(point.id, Some(upserted_point(point, &stored)));
+                }
+                stored
+            }
+        },
+        CollectionUpdateOperations::VectorOperation(operation) => match operation {
+            VectorOperations::UpdateVectors(operation) => {
+                let ids: Vec<_> = operation.points.iter().map(|point| point.id).collect();
+                let stored = stored_points(segments, &ids)?;
+                for point in &operation.points {
+                    let Some(stored_point) = stored.get(&point.id) else {
+                        continue;
+                    };
+                    let mut point_usage = updated
+                        .get(&point.id)
+                        .and_then(Clone::clone)
+                        .unwrap_or_else(|| stored_point.clone());
+                    let vectors = point.vector.clone().into_all_vectors();
+                    point_usage.vectors.extend(named_vectors_size(&vectors));
+                    updated.insert(point.id, Some(point_usage));
+                }
+                stored
+            }
+            VectorOperations::DeleteVectors(ids, names) => {
+                let stored = stored_points(segments, &ids.points)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.vectors.retain(|name, _| !names.contains(name))
+                });
+                stored
+            }
+            VectorOperations::DeleteVectorsByFilter(filter, names) => {
+                let stored = stored_by_filter(segments, filter)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.vectors.retain(|name, _| !names.contains(name))
+                });
+                stored
+            }
+        },
+        CollectionUpdateOperations::PayloadOperation(operation) => match operation {
+            PayloadOps::SetPayload(set_payload) => {
+                let stored = stored_selected(segments, &set_payload.points, &set_payload.filter)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.payload.merge(&set_payload.payload)
+                });
+                stored
+            }
+            PayloadOps::OverwritePayload(set_payload) => {
+                let stored = stored_selected(segments, &set_payload.points, &set_payload.filter)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.payload = set_payload.payload.clone()
+                });
+                stored
+            }
+            PayloadOps::DeletePayload(delete_payload) => {
+                let stored =
+                    stored_selected(segments, &delete_payload.points, &delete_payload.filter)?;
+                update_stored(&stored, &mut updated, |point| {
+                    for key in &delete_payload.keys {
+                        point.payload.remove(key);
+                    }
+                });
+                stored
+            }
+            PayloadOps::ClearPayload { points } => {
+                let stored = stored_points(segments, points)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.payload = Payload::default()
+                });
+                stored
+            }
+            PayloadOps::ClearPayloadByFilter(filter) => {
+                let stored = stored_by_filter(segments, filter)?;
+                update_stored(&stored, &mut updated, |point| {
+                    point.payload = Payload::default()
+                });
+                stored
+            }
+        },
+        CollectionUpdateOperations::FieldIndexOperation(_) => HashMap::new(),
+    };
+
+    let mut change = UsageChange {
+        before: QuotaUsage::default(),
+        after: QuotaUsage::default(),
+    };
+    for (id, point) in &updated {
+        if let Some(stored_point) = stored.get(id) {
+            change.before.add(&stored_point.usage());
+        }
+        if let Some(point) = point {
+            change.after.add(&point.usage());
+        }
+    }
+    Ok(change)
+}
+
+/// Upsert replaces all vectors of the point, and keeps the payload, if there is no new one
+fn upserted_point(point: &PointStruct, stored: &HashMap<PointIdType, PointUsage>) -> PointUsage {
+    PointUsage {
+        vectors: named_vectors_size(&point.vector.clone().into_all_vectors()),
+        payload: point
+            .payload
+            .clone()
+            .unwrap_or_else(|| stored_payload(stored, &point.id)),
+    }
+}
+
+fn stored_payload(stored: &HashMap<PointIdType, PointUsage>, id: &PointIdType) -> Payload {
+    stored
+        .get(id)
+        .map(|point| point.payload.clone())
+        .unwrap_or_default()
+}
+
+fn batch_vectors_size(vectors: &BatchVectorStruct, idx: usize) -> HashMap<String, usize> {
+    let dense_size =
+        |vector: &Vec<VectorElementType>| vector.len() * size_of::<VectorElementType>();
+    match vectors {
+        BatchVectorStruct::Single(vectors) => vectors
+            .get(idx)
+            .map(|vector| (DEFAULT_VECTOR_NAME.to_string(), dense_size(vector)))
+            .into_iter()
+            .collect(),
+        BatchVectorStruct::Multi(vectors) => vectors
+            .iter()
+            .filter_map(|(name, vectors)| Some((name.clone(), dense_size(vectors.get(idx)?))))
+            .collect(),
+    }
+}
+
+fn stored_by_filter(
+    segments: &SegmentHolder,
+    filter: &Filter,
+) -> CollectionResult<HashMap<PointIdType, PointUsage>> {
+    let ids = points_by_filter(segments, filter)?;
+    stored_points(segments, &ids)
+}
+
+/// Points, selected by ids or by a filter
+fn stored_selected(
+    segments: &SegmentHolder,
+    points: &Option<Vec<PointIdType>>,
+    filter: &Option<Filter>,
+) -> CollectionResult<HashMap<PointIdType, PointUsage>> {
+    match (points, filter) {
+        (Some(points), _) => stored_points(segments, points),
+        (None, Some(filter)) => stored_by_filter(segments, filter),
+        (None, None) => Ok(HashMap::new()),
+    }
+}
+
+/// Change each of the stored points with `f`
+fn update_stored(
+    stored: &HashMap<PointIdType, PointUsage>,
+    updated: &mut HashMap<PointIdType, Option<PointUsage>>,
+    f: impl Fn(&mut PointUsage),
+) {
+    for (id, point) in stored {
+        let mut point = point.clone();
+        f(&mut point);
+        updated.insert(*id, Some(point));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::collection_manager::collection_updater::CollectionUpdater;
+    use crate::collection_manager::fixtures::empty_segment;
+    use crate::operations::payload_ops::SetPayload;
+
+    fn usage(points: usize, vectors_size_bytes: usize, payload_size_bytes: usize) -> QuotaUsage {
+        QuotaUsage {
+            points,
+            vectors_size_bytes,
+            payload_size_bytes,
+        }
+    }
+
+    fn quota_exceeded(result: CollectionResult<()>) -> String {
+        match result {
+            Err(CollectionError::QuotaExceeded { quota, .. }) => quota,
+            other => panic!("expected quota error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_quota_check() {
+        let quota = QuotaConfig {
+            max_points: Some(10),
+            max_payload_size_bytes: Some(100),
+            ..Default::default()
+        };
+
+        assert!(quota.check(&usage(9, 0, 0), &usage(10, 1000, 100)).is_ok());
+        assert_eq!(
+            quota_exceeded(quota.check(&usage(10, 0, 0), &usage(11, 0, 0))),
+            "max_points"
+        );
+        assert_eq!(
+            quota_exceeded(quota.check(&usage(0, 0, 50), &usage(1, 0, 101))),
+            "max_payload_size_bytes"
+        );
+
+        // Collection over the quota can still free the data
+        assert!(quota.check(&usage(20, 0, 0), &usage(19, 0, 0)).is_ok());
+    }
+
+    #[test]
+    fn test_quota_shard_share() {
+        let quota = QuotaConfig {
+            max_points: Some(10),
+            max_vectors_size_bytes: Some(9),
+            max_payload_size_bytes: None,
+        };
+        let share = quota.shard_share(3);
+        assert_eq!(share.max_points, Some(4));
+        assert_eq!(share.max_vectors_size_bytes, Some(3));
+        assert_eq!(share.max_payload_size_bytes, None);
+        assert_eq!(quota.shard_share(1), quota);
+    }
+
+    #[test]
+    fn test_usage_tracking() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+        holder.add(empty_segment(dir.path()));
+        let segments = RwLock::new(holder);
+
+        let tracker = QuotaUsageTracker::default();
+        assert_eq!(
+            tracker.tracked_usage(&segments).unwrap(),
+            QuotaUsage::default()
+        );
+
+        let payload: Payload = json!({ "city": "Berlin" }).into();
+        let payload_size = serde_json::to_vec(&payload).unwrap().len();
+        let points = (1..=3)
+            .map(|id| PointStruct {
+                id: id.into(),
+                vector: vec![1.0, 0.0, 1.0, 1.0].into(),
+                payload: Some(payload.clone()),
+            })
+            .collect();
+        let operations = [
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(points),
+            )),
+            // Payload is kept by an upsert without one
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(vec![PointStruct {
+                    id: 1.into(),
+                    vector: vec![0.0, 1.0, 1.0, 1.0].into(),
+                    payload: None,
+                }]),
+            )),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+                payload: json!({ "city": "London" }).into(),
+                points: Some(vec![2.into()]),
+                filter: None,
+            })),
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                ids: vec![3.into()],
+            }),
+        ];
+        for (op_num, operation) in operations.into_iter().enumerate() {
+            tracker
+                .apply(&segments, operation, |operation| {
+                    CollectionUpdater::update(&segments, op_num as u64 + 1, operation)
+                })
+                .unwrap();
+        }
+
+        let expected = usage(2, 2 * 4 * 4, 2 * payload_size);
+        assert_eq!(tracker.usage(), Some(expected));
+        assert_eq!(count_usage(&segments.read()).unwrap(), expected);
+
+        let upsert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(vec![PointStruct {
+                id: 4.into(),
+                vector: vec![1.0; 4].into(),
+                payload: None,
+            }]),
+        ));
+        let quota = QuotaConfig {
+            max_points: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            quota_exceeded(tracker.check(&segments, &upsert, &quota)),
+            "max_points"
+        );
+        let delete = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![1.into()],
+        });
+        assert!(tracker.check(&segments, &delete, &quota).is_ok());
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use parking_lot::{Mutex, RwLock};
+use schemars::JsonSchema;
+use segment::types::Filter;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::operations::consistency_params::ReadConsistency;
+
+/// Cache of the responses to the read operations of the collection: searches, counts and groups.
+///
+/// Useful for collections, which change rarely, but receive the same requests over and over,
+/// e.g. from dashboards. Responses are invalidated by any update of the collection.
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct ReadCacheConfig {
+    /// Max number of cached responses. If not set - responses are not cached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_entries: Option<usize>,
+    /// Max age of a cached response in seconds.
+    /// If not set - responses are kept until the collection is updated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub ttl_sec: Option<u64>,
+}
+
+impl ReadCacheConfig {
+    /// Update the parameters, which are specified in `diff`, and keep the rest
+    pub fn update(&self, diff: &ReadCacheConfig) -> Self {
+        Self {
+            max_entries: diff.max_entries.or(self.max_entries),
+            ttl_sec: diff.ttl_sec.or(self.ttl_sec),
+        }
+    }
+}
+
+/// Read operation, which responses are cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CachedOperation {
+    Search,
+    Count,
+    Group,
+}
+
+/// Version of the data of the collection, changed by every update of any of its local shards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DataVersion {
+    /// Sum of the numbers of operations, applied by the shards
+    pub applied_operations: u64,
+    /// Sum of the unique numbers of the shard instances.
+    /// Changed when a shard is replaced, e.g. recovered from a snapshot
+    pub shard_instances: u64,
+}
+
+impl DataVersion {
+    pub fn add(&mut self, other: DataVersion) {
+        self.applied_operations = self
+            .applied_operations
+            .wrapping_add(other.applied_operations);
+        self.shard_instances = self.shard_instances.wrapping_add(other.shard_instances);
+    }
+}
+
+/// Request, identifying a cached response, along with the access scope it is served in
+pub struct CacheKey {
+    hash: u64,
+    /// Canonical form of the request, to tell apart the requests with the same hash
+    request: String,
+}
+
+impl CacheKey {
+    pub fn new(
+        operation: CachedOperation,
+        request: &impl Serialize,
+        read_consistency: Option<ReadConsistency>,
+        scope: Option<&Filter>,
+    ) -> Option<Self> {
+        let request = serde_json::to_string(&(operation, request, read_consistency, scope)).ok()?;
+        let mut hasher = DefaultHasher::new();
+        request.hash(&mut hasher);
+        Some(Self {
+            hash: hasher.finish(),
+            request,
+        })
+    }
+}
+
+struct CachedResponse {
+    request: String,
+    version: DataVersion,
+    cached_at: Instant,
+    response: Vec<u8>,
+}
+
+/// Responses to the read operations of a collection, according to its [`ReadCacheConfig`]
+#[derive(Default)]
+pub struct ReadCache {
+    config: RwLock<Option<ReadCacheConfig>>,
+    responses: Mutex<HashMap<u64, CachedResponse>>,
+}
+
+impl ReadCache {
+    pub fn new(config: Option<&ReadCacheConfig>) -> Self {
+        let cache = Self::default();
+        cache.configure(config);
+        cache
+    }
+
+    /// Apply new parameters. Responses, cached so far, are dropped
+    pub fn configure(&self, config: Option<&ReadCacheConfig>) {
+        let config = config.filter(|config| config.max_entries.is_some());
+        *self.config.write() = config.cloned();
+        self.clear();
+    }
+
+    /// Drop the cached responses, e.g. if the defaults of the requests are changed
+    pub fn clear(&self) {
+        self.responses.lock().clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.read().is_some()
+    }
+
+    /// Response to the request, if it is cached at the same `version` of the data
+    pub fn get<T: DeserializeOwned>(&self, key: &CacheKey, version: DataVersion) -> Option<T> {
+        let ttl = self
+            .config
+            .read()
+            .as_ref()?
+            .ttl_sec
+            .map(Duration::from_secs);
+        let mut responses = self.responses.lock();
+        let cached = responses.get(&key.hash)?;
+        if cached.request != key.request {
+            return None;
+        }
+        let is_expired = ttl.map_or(false, |ttl| cached.cached_at.elapsed() >= ttl);
+        if cached.version != version || is_expired {
+            responses.remove(&key.hash);
+            return None;
+        }
+        match serde_json::from_slice(&cached.response) {
+            Ok(response) => Some(response),
+            Err(err) => {
+                log::warn!("Can't deserialize cached response: {err}");
+                responses.remove(&key.hash);
+                None
+            }
+        }
+    }
+
+    /// Cache the response to the request, made at the `version` of the data.
+    /// The oldest responses are dropped to fit into `max_entries`
+    pub fn insert<T: Serialize>(&self, key: CacheKey, version: DataVersion, response: &T) {
+        let Some(max_entries) = self
+            .config
+            .read()
+            .as_ref()
+            .and_then(|config| config.max_entries)
+        else {
+            return;
+        };
+        let response = match serde_json::to_vec(response) {
+            Ok(response) => response,
+            Err(err) => {
+                log::warn!("Can't serialize response to cache it: {err}");
+                return;
+            }
+        };
+
+        let mut responses = self.responses.lock();
+        responses.insert(
+            key.hash,
+            CachedResponse {
+                request: key.request,
+                version,
+                cached_at: Instant::now(),
+                response,
+            },
+        );
+        while responses.len() > max_entries {
+            let Some(oldest) = responses
+                .iter()
+                .min_by_key(|(_, cached)| cached.cached_at)
+                .map(|(hash, _)| *hash)
+            else {
+                break;
+            };
+            responses.remove(&oldest);
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Set while the reads, made by the current task, are parts of another read operation
+    static INTERNAL_READ: ();
+}
+
+/// Run the reads of `future` as parts of a read operation, which is cached as a whole.
+/// For example, the internal searches of a grouped search.
+pub async fn with_internal_reads<F: Future>(future: F) -> F::Output {
+    INTERNAL_READ.scope((), future).await
+}
+
+/// Whether the read of the current task is a part of another read operation.
+/// Responses to such reads are not cached on their own
+pub fn is_internal_read() -> bool {
+    INTERNAL_READ.try_with(|_| ()).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{Condition, FieldCondition};
+
+    use super::*;
+    use crate::operations::consistency_params::ReadConsistencyType;
+
+    fn cache(max_entries: usize, ttl_sec: Option<u64>) -> ReadCache {
+        ReadCache::new(Some(&ReadCacheConfig {
+            max_entries: Some(max_entries),
+            ttl_sec,
+        }))
+    }
+
+    fn key(request: &str) -> CacheKey {
+        CacheKey::new(CachedOperation::Count, &request, None, None).unwrap()
+    }
+
+    fn version(applied_operations: u64) -> DataVersion {
+        DataVersion {
+            applied_operations,
+            shard_instances: 1,
+        }
+    }
+
+    #[test]
+    fn test_cached_response_is_invalidated_by_update() {
+        let cache = cache(10, None);
+        cache.insert(key("a"), version(1), &vec![1, 2, 3]);
+
+        assert_eq!(cache.get(&key("a"), version(1)), Some(vec![1, 2, 3]));
+        assert_eq!(cache.get::<Vec<i32>>(&key("b"), version(1)), None);
+
+        assert_eq!(cache.get::<Vec<i32>>(&key("a"), version(2)), None);
+        // Not cached anymore, even for the old version
+        assert_eq!(cache.get::<Vec<i32>>(&key("a"), version(1)), None);
+
+        let all = Some(ReadConsistency::Type(ReadConsistencyType::All));
+        let other_consistency = CacheKey::new(CachedOperation::Count, &"a", all, None).unwrap();
+        cache.insert(key("a"), version(2), &vec![1]);
+        assert_eq!(cache.get::<Vec<i32>>(&other_consistency, version(2)), None);
+    }
+
+    #[test]
+    fn test_responses_are_not_shared_between_scopes() {
+        let cache = cache(10, None);
+        let scope = |tenant: &str| {
+            Filter::new_must(Condition::Field(FieldCondition::new_match(
+                "tenant",
+                tenant.to_string().into(),
+            )))
+        };
+        let scoped_key = |tenant: &str| {
+            CacheKey::new(CachedOperation::Count, &"a", None, Some(&scope(tenant))).unwrap()
+        };
+
+        cache.insert(scoped_key("x"), version(1), &1);
+        assert_eq!(cache.get(&scoped_key("x"), version(1)), Some(1));
+        assert_eq!(cache.get::<i32>(&scoped_key("y"), version(1)), None);
+        assert_eq!(cache.get::<i32>(&key("a"), version(1)), None);
+    }
+
+    #[test]
+    fn test_oldest_responses_are_evicted() {
+        let cache = cache(2, None);
+        cache.insert(key("a"), version(1), &1);
+        cache.insert(key("b"), version(1), &2);
+        cache.insert(key("c"), version(1), &3);
+
+        assert_eq!(cache.get::<i32>(&key("a"), version(1)), None);
+        assert_eq!(cache.get(&key("b"), version(1)), Some(2));
+        assert_eq!(cache.get(&key("c"), version(1)), Some(3));
+    }
+
+    #[test]
+    fn test_expired_responses() {
+        let cache = cache(2, Some(1));
+        cache.insert(key("a"), version(1), &1);
+        assert_eq!(cache.get(&key("a"), version(1)), Some(1));
+
+        cache
+            .responses
+            .lock()
+            .get_mut(&key("a").hash)
+            .unwrap()
+            .cached_at -= Duration::from_secs(1);
+        assert_eq!(cache.get::<i32>(&key("a"), version(1)), None);
+    }
+
+    #[test]
+    fn test_disabled_cache() {
+        let cache = ReadCache::new(Some(&ReadCacheConfig {
+            max_entries: None,
+            ttl_sec: Some(10),
+        }));
+        assert!(!cache.is_enabled());
+        cache.insert(key("a"), version(1), &1);
+        assert_eq!(cache.get::<i32>(&key("a"), version(1)), None);
+    }
+}
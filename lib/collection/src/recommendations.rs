@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::time::Instant;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
@@ -10,11 +11,15 @@ use segment::types::{
 use tokio::sync::RwLockReadGuard;
 
 use crate::collection::Collection;
+use crate::common::measured_operation::{measure_phase, record_requests};
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::strict_mode::StrictModeRequest;
 use crate::operations::types::{
     CollectionError, CollectionResult, PointRequest, RecommendRequest, RecommendRequestBatch,
     Record, SearchRequest, SearchRequestBatch, UsingVector,
 };
+use crate::slow_log::RequestDetails;
+use crate::telemetry::CollectionOperationType;
 
 fn avg_vectors<'a>(
     vectors: impl Iterator<Item = &'a Vec<VectorElementType>>,
@@ -39,6 +44,7 @@ fn avg_vectors<'a>(
     avg_vector
 }
 
+#[tracing::instrument(skip_all, fields(collection = %collection.id))]
 pub async fn recommend_by<'a, F, Fut>(
     request: RecommendRequest,
     collection: &Collection,
@@ -49,21 +55,60 @@ where
     F: Fn(String) -> Fut,
     Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
 {
-    if request.limit == 0 {
-        return Ok(vec![]);
-    }
     // `recommend_by` is a special case of recommend_by_batch with a single batch
-    let request_batch = RecommendRequestBatch {
+    let mut request_batch = RecommendRequestBatch {
         searches: vec![request],
     };
-    let results = recommend_batch_by(
-        request_batch,
+    recommend_single_by(
+        &mut request_batch,
         collection,
         collection_by_name,
         read_consistency,
     )
-    .await?;
-    Ok(results.into_iter().next().unwrap())
+    .await
+}
+
+/// Recommendation by the single request of the batch.
+///
+/// The batch is borrowed, so the internal requests of an operation, e.g. grouping,
+/// reuse it along with the examples, and only update the limit and the filter in between.
+pub(crate) async fn recommend_single_by<'a, F, Fut>(
+    request_batch: &mut RecommendRequestBatch,
+    collection: &Collection,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<ScoredPoint>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    let request = &mut request_batch.searches[0];
+    if let Some(config) = collection.config_for_defaults(None).await {
+        config.apply_search_defaults(
+            &mut request.params,
+            &mut request.with_payload,
+            &mut request.with_vector,
+        );
+        config.apply_default_using(&mut request.using);
+    }
+    collection
+        .check_strict_mode([StrictModeRequest::from(&*request)], None)
+        .await?;
+    collection
+        .measure_operation(CollectionOperationType::Recommend, None, async {
+            if request_batch.searches[0].limit == 0 {
+                return Ok(vec![]);
+            }
+            let results = recommend_batch_by_ref(
+                request_batch,
+                collection,
+                collection_by_name,
+                read_consistency,
+            )
+            .await?;
+            Ok(results.into_iter().next().unwrap())
+        })
+        .await
 }
 
 async fn retrieve_points(
@@ -78,6 +123,8 @@ async fn retrieve_points(
                 ids,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: WithVector::Selector(vector_names),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             read_consistency,
             None,
@@ -106,6 +153,13 @@ async fn retrieve_points_with_locked_collection(
     }
 }
 
+fn get_using_vector_name(request: &RecommendRequest) -> &str {
+    match &request.using {
+        None => DEFAULT_VECTOR_NAME,
+        Some(UsingVector::Name(name)) => name,
+    }
+}
+
 fn get_search_vector_name(request: &RecommendRequest) -> String {
     match &request.lookup_from {
         None => match &request.using {
@@ -119,6 +173,48 @@ fn get_search_vector_name(request: &RecommendRequest) -> String {
     }
 }
 
+/// Checks, that the vectors of the lookup collection can be used to search the collection.
+///
+/// `vector_pairs` are the names of the looked up vectors along with the names of the vectors
+/// they are used to search, both of them must have the same size and distance.
+async fn check_lookup_vectors(
+    collection: &Collection,
+    lookup_collection: &Collection,
+    vector_pairs: &HashSet<(String, &str)>,
+) -> CollectionResult<()> {
+    let collection_name = collection.name();
+    let lookup_collection_name = lookup_collection.name();
+
+    let mut used_params = Vec::with_capacity(vector_pairs.len());
+    {
+        let config = collection.collection_config.read().await;
+        for (_, using) in vector_pairs {
+            used_params.push(config.params.get_vector_params(using)?);
+        }
+    }
+
+    let lookup_config = lookup_collection.collection_config.read().await;
+    for ((lookup_vector, using), used) in vector_pairs.iter().zip(used_params) {
+        let looked_up = lookup_config
+            .params
+            .get_vector_params(lookup_vector)
+            .map_err(|_| CollectionError::VectorNameMismatch {
+                requested: lookup_vector.to_string(),
+                available: lookup_config.params.vector_names(),
+                collection: Some(lookup_collection_name.to_string()),
+            })?;
+        if looked_up.size != used.size || looked_up.distance != used.distance {
+            return Err(CollectionError::bad_input(format!(
+                "Vector `{lookup_vector}` of the lookup collection `{lookup_collection_name}` \
+                 (size: {}, distance: {:?}) doesn't match vector `{using}` of the collection \
+                 `{collection_name}` (size: {}, distance: {:?})",
+                looked_up.size, looked_up.distance, used.size, used.distance,
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Search points in a collection by already existing points in this or another collection.
 ///
 /// Function works in following stages:
@@ -135,8 +231,82 @@ fn get_search_vector_name(request: &RecommendRequest) -> String {
 /// * `collection` - collection to search in
 /// * `collection_by_name` - function to retrieve collection by name, used to retrieve points from other collections
 ///
+#[tracing::instrument(
+    skip_all,
+    fields(collection = %collection.id, batch_size = request_batch.searches.len())
+)]
 pub async fn recommend_batch_by<'a, F, Fut>(
-    request_batch: RecommendRequestBatch,
+    mut request_batch: RecommendRequestBatch,
+    collection: &Collection,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<Vec<ScoredPoint>>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    recommend_batch_by_ref(
+        &mut request_batch,
+        collection,
+        collection_by_name,
+        read_consistency,
+    )
+    .await
+}
+
+async fn recommend_batch_by_ref<'a, F, Fut>(
+    request_batch: &mut RecommendRequestBatch,
+    collection: &Collection,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<Vec<ScoredPoint>>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    if let Some(config) = collection.config_for_defaults(None).await {
+        for request in &mut request_batch.searches {
+            config.apply_search_defaults(
+                &mut request.params,
+                &mut request.with_payload,
+                &mut request.with_vector,
+            );
+            config.apply_default_using(&mut request.using);
+        }
+    }
+    collection
+        .check_strict_mode(
+            request_batch.searches.iter().map(StrictModeRequest::from),
+            None,
+        )
+        .await?;
+    collection
+        .measure_operation(CollectionOperationType::RecommendBatch, None, async {
+            let start = Instant::now();
+            let result = recommend_batch_by_impl(
+                request_batch,
+                collection,
+                collection_by_name,
+                read_consistency,
+            )
+            .await;
+
+            let mut telemetry = collection.operations_telemetry.lock();
+            telemetry.recommend.durations.observe(start.elapsed());
+            if matches!(
+                result,
+                Err(CollectionError::RecommendExampleNotFound { .. }
+                    | CollectionError::LookupCollectionMissing { .. })
+            ) {
+                telemetry.recommend.example_resolution_failures += 1;
+            }
+            result
+        })
+        .await
+}
+
+async fn recommend_batch_by_impl<'a, F, Fut>(
+    request_batch: &RecommendRequestBatch,
     collection: &Collection,
     collection_by_name: F,
     read_consistency: Option<ReadConsistency>,
@@ -149,9 +319,18 @@ where
     if request_batch.searches.iter().all(|s| s.limit == 0) {
         return Ok(vec![]);
     }
+    record_requests(|| {
+        request_batch
+            .searches
+            .iter()
+            .map(RequestDetails::from)
+            .collect()
+    });
     // pack all reference vector ids
     let mut all_reference_vectors_ids: HashMap<_, HashSet<PointIdType>> = Default::default();
     let mut vector_names_per_collection: HashMap<_, HashSet<String>> = Default::default();
+    // looked up vector names along with the searched ones, for the other collections only
+    let mut vector_pairs_per_collection: HashMap<_, HashSet<(String, &str)>> = Default::default();
 
     for request in &request_batch.searches {
         if request.positive.is_empty() {
@@ -171,6 +350,16 @@ where
 
         vector_names.insert(get_search_vector_name(request));
 
+        if let Some(name) = collection_name {
+            vector_pairs_per_collection
+                .entry(name)
+                .or_default()
+                .insert((
+                    get_search_vector_name(request),
+                    get_using_vector_name(request),
+                ));
+        }
+
         for point_id in request.positive.iter().chain(&request.negative) {
             reference_vectors_ids.insert(*point_id);
         }
@@ -199,6 +388,10 @@ where
                 let other_collection = collection_by_name(name.to_string()).await;
                 match other_collection {
                     Some(other_collection) => {
+                        if let Some(vector_pairs) = vector_pairs_per_collection.get(name) {
+                            check_lookup_vectors(collection, &other_collection, vector_pairs)
+                                .await?;
+                        }
                         vector_retrieves.push(retrieve_points_with_locked_collection(
                             CollectionRefHolder::Guard(other_collection),
                             points,
@@ -207,8 +400,8 @@ where
                         ))
                     }
                     None => {
-                        return Err(CollectionError::NotFound {
-                            what: format!("Collection {name}"),
+                        return Err(CollectionError::LookupCollectionMissing {
+                            name: name.to_string(),
                         })
                     }
                 }
@@ -216,7 +409,8 @@ where
         }
     }
 
-    let all_reference_vectors: Vec<Vec<Record>> = try_join_all(vector_retrieves).await?;
+    let all_reference_vectors: Vec<Vec<Record>> =
+        measure_phase("resolve_examples", try_join_all(vector_retrieves)).await?;
 
     let mut all_vectors_records_map: HashMap<_, _> = Default::default();
 
@@ -231,10 +425,7 @@ where
     let mut searches = Vec::with_capacity(request_batch.searches.len());
 
     for request in &request_batch.searches {
-        let vector_name = match &request.using {
-            None => DEFAULT_VECTOR_NAME,
-            Some(UsingVector::Name(name)) => name,
-        };
+        let vector_name = get_using_vector_name(request);
 
         let lookup_vector_name = get_search_vector_name(request);
 
@@ -247,12 +438,16 @@ where
 
         let request_from_collection = request.lookup_from.as_ref().map(|x| &x.collection);
 
-        for &point_id in &reference_vectors_ids {
-            if !all_vectors_records_map.contains_key(&(request_from_collection, point_id)) {
-                return Err(CollectionError::PointNotFound {
-                    missed_point_id: point_id,
-                });
-            }
+        let missed_ids = reference_vectors_ids
+            .iter()
+            .filter(|&&point_id| {
+                !all_vectors_records_map.contains_key(&(request_from_collection, point_id))
+            })
+            .copied()
+            .unique()
+            .collect_vec();
+        if !missed_ids.is_empty() {
+            return Err(CollectionError::RecommendExampleNotFound { ids: missed_ids });
         }
 
         let avg_positive = avg_vectors(request.positive.iter().filter_map(|vid| {
@@ -288,6 +483,7 @@ where
             .into(),
             filter: Some(Filter {
                 should: None,
+                min_should: None,
                 must: request
                     .filter
                     .clone()
@@ -302,6 +498,10 @@ where
             limit: request.limit,
             score_threshold: request.score_threshold,
             offset: request.offset,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
         };
         searches.push(search_request)
     }
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::operations::types::{CollectionError, CollectionResult, UpdateResult};
+
+pub const APPLIED_OPERATIONS_FILE: &str = "applied_operations.jsonl";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct AppliedOperation {
+    id: Uuid,
+    result: UpdateResult,
+}
+
+/// Client-supplied ids of the latest operations, applied to the shard, with their results.
+///
+/// Records are appended to a file next to the WAL, so that retried operations are skipped after
+/// a restart as well. The file is rewritten with the current window, once it holds twice as many
+/// records.
+#[derive(Debug)]
+pub struct AppliedOperations {
+    path: PathBuf,
+    /// Max number of remembered operations, `0` disables deduplication
+    window: usize,
+    results: HashMap<Uuid, UpdateResult>,
+    /// Ids in the order of application, oldest first
+    order: VecDeque<Uuid>,
+    file: Option<File>,
+    file_records: usize,
+}
+
+impl AppliedOperations {
+    pub fn get_path(shard_path: &Path) -> PathBuf {
+        shard_path.join(APPLIED_OPERATIONS_FILE)
+    }
+
+    pub fn load(shard_path: &Path, window: usize) -> Self {
+        let mut applied = Self {
+            path: Self::get_path(shard_path),
+            window,
+            results: HashMap::new(),
+            order: VecDeque::new(),
+            file: None,
+            file_records: 0,
+        };
+        if window == 0 {
+            return applied;
+        }
+
+        match File::open(&applied.path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    // Last record is incomplete, if the node was stopped in the middle of the write
+                    let Ok(record) = line
+                        .map_err(CollectionError::from)
+                        .and_then(|line| Ok(serde_json::from_str::<AppliedOperation>(&line)?))
+                    else {
+                        break;
+                    };
+                    applied.file_records += 1;
+                    applied.insert(record.id, record.result);
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => log::warn!(
+                "Can't read applied operations from {}: {err}",
+                applied.path.display()
+            ),
+        }
+        applied
+    }
+
+    fn insert(&mut self, id: Uuid, result: UpdateResult) {
+        if self.results.insert(id, result).is_none() {
+            self.order.push_back(id);
+        }
+        while self.order.len() > self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.results.remove(&oldest);
+            }
+        }
+    }
+
+    /// Result of the operation, if it was already applied
+    pub fn get(&self, id: &Uuid) -> Option<UpdateResult> {
+        self.results.get(id).cloned()
+    }
+
+    /// Remember the result of the applied operation
+    pub fn record(&mut self, id: Uuid, result: UpdateResult) -> CollectionResult<()> {
+        if self.window == 0 {
+            return Ok(());
+        }
+        let record = AppliedOperation {
+            id,
+            result: result.clone(),
+        };
+        self.insert(id, result);
+
+        if self.file_records >= 2 * self.window {
+            return self.compact();
+        }
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?,
+        };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        self.file = Some(file);
+        self.file_records += 1;
+        Ok(())
+    }
+
+    fn compact(&mut self) -> CollectionResult<()> {
+        self.file = None;
+        let mut data = Vec::new();
+        for id in &self.order {
+            let record = AppliedOperation {
+                id: *id,
+                result: self.results[id].clone(),
+            };
+            serde_json::to_writer(&mut data, &record)?;
+            data.push(b'\n');
+        }
+        AtomicFile::new(&self.path, OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(&data))
+            .map_err(|err| {
+                CollectionError::service_error(format!(
+                    "Can't save applied operations to {}: {err}",
+                    self.path.display()
+                ))
+            })?;
+        self.file_records = self.order.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::operations::types::UpdateStatus;
+
+    fn result(operation_id: u64) -> UpdateResult {
+        UpdateResult::new(operation_id, UpdateStatus::Acknowledged)
+    }
+
+    #[test]
+    fn test_applied_operations() {
+        let dir = Builder::new().prefix("shard").tempdir().unwrap();
+        let ids: Vec<_> = (0..10).map(|_| Uuid::new_v4()).collect();
+
+        let mut applied = AppliedOperations::load(dir.path(), 3);
+        for (operation_id, id) in ids.iter().enumerate() {
+            applied.record(*id, result(operation_id as u64)).unwrap();
+        }
+        assert_eq!(applied.get(&ids[9]).unwrap().operation_id, 9);
+        assert_eq!(applied.get(&ids[7]).unwrap().operation_id, 7);
+        assert!(applied.get(&ids[6]).is_none());
+        // File is compacted, once it holds twice as many records as the window
+        assert!(applied.file_records <= 6);
+
+        let mut loaded = AppliedOperations::load(dir.path(), 3);
+        assert_eq!(loaded.get(&ids[8]).unwrap().operation_id, 8);
+        assert!(loaded.get(&ids[6]).is_none());
+
+        loaded.record(ids[0], result(10)).unwrap();
+        drop(loaded);
+        let loaded = AppliedOperations::load(dir.path(), 3);
+        assert_eq!(loaded.get(&ids[0]).unwrap().operation_id, 10);
+        assert!(loaded.get(&ids[7]).is_none());
+    }
+}
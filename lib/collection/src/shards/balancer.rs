@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::operations::cluster_ops::MoveShard;
+use crate::shards::replica_set::ReplicaState;
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::transfer::shard_transfer::ShardTransfer;
+
+const DEFAULT_CHECK_INTERVAL_SEC: u64 = 60;
+
+/// Configuration of the automatic balancing of shards between peers
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ShardBalancingConfig {
+    /// If true - the leader of the consensus moves shards, to even out their number on the peers
+    #[serde(default)]
+    pub auto_shard_rebalancing: bool,
+    /// How often the placement of shards is checked
+    #[serde(default = "default_check_interval_sec")]
+    pub check_interval_sec: u64,
+}
+
+impl Default for ShardBalancingConfig {
+    fn default() -> Self {
+        Self {
+            auto_shard_rebalancing: false,
+            check_interval_sec: DEFAULT_CHECK_INTERVAL_SEC,
+        }
+    }
+}
+
+impl ShardBalancingConfig {
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_secs(self.check_interval_sec)
+    }
+}
+
+const fn default_check_interval_sec() -> u64 {
+    DEFAULT_CHECK_INTERVAL_SEC
+}
+
+/// Replicas of a shard and its size, as seen by the balancer
+#[derive(Debug, Clone)]
+pub struct ShardPlacement {
+    pub shard_id: ShardId,
+    pub replicas: HashMap<PeerId, ReplicaState>,
+    /// Approximate number of points in the shard
+    pub points: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct PeerShardLoad {
+    pub peer_id: PeerId,
+    /// Number of shard replicas on the peer
+    pub shards: usize,
+    /// Approximate number of points in the shard replicas on the peer
+    pub points: usize,
+}
+
+/// Current placement of the shards and the moves, which would even it out
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct RebalancingPlan {
+    /// Current load of the peers
+    pub peers: Vec<PeerShardLoad>,
+    /// Difference between the max and min number of shard replicas on a peer
+    pub shard_count_deviation: usize,
+    /// Difference between the max and min approximate number of points on a peer
+    pub points_deviation: usize,
+    /// Suggested shard moves. At most one move is suggested for each pair of peers,
+    /// and none for the pairs, which already have a transfer in progress
+    pub moves: Vec<MoveShard>,
+}
+
+fn peer_pair(a: PeerId, b: PeerId) -> (PeerId, PeerId) {
+    (a.min(b), a.max(b))
+}
+
+fn deviation(loads: &[PeerShardLoad], value: impl Fn(&PeerShardLoad) -> usize) -> usize {
+    let max = loads.iter().map(&value).max().unwrap_or(0);
+    let min = loads.iter().map(&value).min().unwrap_or(0);
+    max - min
+}
+
+/// Plan shard moves, which even out the number of shard replicas on the `peers`.
+///
+/// Shards are moved from the most loaded peer to the least loaded one, while they differ by more
+/// than one shard. The number of points is used to break ties and to pick the shard, which evens
+/// out the number of points the most. Shards in transfer are not moved.
+pub fn plan_rebalancing(
+    placements: &[ShardPlacement],
+    peers: &[PeerId],
+    transfers: &[ShardTransfer],
+) -> RebalancingPlan {
+    let mut loads: BTreeMap<PeerId, PeerShardLoad> = peers
+        .iter()
+        .map(|&peer_id| {
+            let load = PeerShardLoad {
+                peer_id,
+                shards: 0,
+                points: 0,
+            };
+            (peer_id, load)
+        })
+        .collect();
+    for placement in placements {
+        for &peer_id in placement.replicas.keys() {
+            let load = loads.entry(peer_id).or_insert(PeerShardLoad {
+                peer_id,
+                shards: 0,
+                points: 0,
+            });
+            load.shards += 1;
+            load.points += placement.points;
+        }
+    }
+
+    let current_loads: Vec<_> = loads.values().cloned().collect();
+    let shard_count_deviation = deviation(&current_loads, |load| load.shards);
+    let points_deviation = deviation(&current_loads, |load| load.points);
+
+    let mut candidates: Vec<_> = placements
+        .iter()
+        .filter(|placement| {
+            !transfers
+                .iter()
+                .any(|transfer| transfer.shard_id == placement.shard_id)
+        })
+        .collect();
+    candidates.sort_by_key(|placement| placement.shard_id);
+
+    // Only one transfer at a time between a pair of peers
+    let mut busy_pairs: HashSet<_> = transfers
+        .iter()
+        .map(|transfer| peer_pair(transfer.from, transfer.to))
+        .collect();
+    let mut moved_shards = HashSet::new();
+    let mut moves = Vec::new();
+
+    loop {
+        let mut ordered: Vec<_> = loads.values().cloned().collect();
+        ordered.sort_by_key(|load| (load.shards, load.points, load.peer_id));
+        let Some(most_loaded) = ordered.last() else {
+            break;
+        };
+
+        let mut shard_move = None;
+        for least_loaded in &ordered {
+            if most_loaded.shards <= least_loaded.shards + 1 {
+                break;
+            }
+            if busy_pairs.contains(&peer_pair(most_loaded.peer_id, least_loaded.peer_id)) {
+                continue;
+            }
+            let points_gap = most_loaded.points.saturating_sub(least_loaded.points);
+            let shard = candidates
+                .iter()
+                .filter(|placement| !moved_shards.contains(&placement.shard_id))
+                .filter(|placement| {
+                    placement.replicas.get(&most_loaded.peer_id) == Some(&ReplicaState::Active)
+                        && !placement.replicas.contains_key(&least_loaded.peer_id)
+                })
+                .min_by_key(|placement| (2 * placement.points).abs_diff(points_gap));
+            if let Some(shard) = shard {
+                shard_move = Some(MoveShard {
+                    shard_id: shard.shard_id,
+                    from_peer_id: most_loaded.peer_id,
+                    to_peer_id: least_loaded.peer_id,
+                });
+                break;
+            }
+        }
+
+        let Some(shard_move) = shard_move else {
+            break;
+        };
+        let points = candidates
+            .iter()
+            .find(|placement| placement.shard_id == shard_move.shard_id)
+            .map_or(0, |placement| placement.points);
+        if let Some(load) = loads.get_mut(&shard_move.from_peer_id) {
+            load.shards -= 1;
+            load.points -= points;
+        }
+        if let Some(load) = loads.get_mut(&shard_move.to_peer_id) {
+            load.shards += 1;
+            load.points += points;
+        }
+        busy_pairs.insert(peer_pair(shard_move.from_peer_id, shard_move.to_peer_id));
+        moved_shards.insert(shard_move.shard_id);
+        moves.push(shard_move);
+    }
+
+    RebalancingPlan {
+        peers: current_loads,
+        shard_count_deviation,
+        points_deviation,
+        moves,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(shard_id: ShardId, peers: &[PeerId], points: usize) -> ShardPlacement {
+        ShardPlacement {
+            shard_id,
+            replicas: peers
+                .iter()
+                .map(|&peer_id| (peer_id, ReplicaState::Active))
+                .collect(),
+            points,
+        }
+    }
+
+    #[test]
+    fn test_plan_rebalancing() {
+        let placements = vec![
+            placement(0, &[1], 100),
+            placement(1, &[1], 10),
+            placement(2, &[1], 50),
+            placement(3, &[1], 20),
+            placement(4, &[2], 30),
+        ];
+
+        let plan = plan_rebalancing(&placements, &[1, 2, 3], &[]);
+        assert_eq!(plan.shard_count_deviation, 4);
+        assert_eq!(plan.points_deviation, 180);
+        assert_eq!(
+            plan.moves,
+            vec![
+                // The shard, which evens out the points of the peers the most
+                MoveShard {
+                    shard_id: 0,
+                    from_peer_id: 1,
+                    to_peer_id: 3,
+                },
+                MoveShard {
+                    shard_id: 3,
+                    from_peer_id: 1,
+                    to_peer_id: 2,
+                },
+            ]
+        );
+
+        // Pair of peers with a transfer in progress is skipped, as well as the transferred shard
+        let transfers = vec![ShardTransfer {
+            shard_id: 4,
+            from: 2,
+            to: 1,
+            sync: false,
+            method: None,
+        }];
+        let plan = plan_rebalancing(&placements, &[1, 2, 3], &transfers);
+        assert_eq!(
+            plan.moves,
+            vec![MoveShard {
+                shard_id: 0,
+                from_peer_id: 1,
+                to_peer_id: 3,
+            }]
+        );
+
+        let balanced = vec![placement(0, &[1, 2], 10), placement(1, &[2, 3], 10)];
+        let plan = plan_rebalancing(&balanced, &[1, 2, 3], &[]);
+        assert_eq!(plan.shard_count_deviation, 1);
+        assert!(plan.moves.is_empty());
+    }
+}
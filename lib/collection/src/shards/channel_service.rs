@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use api::grpc::circuit_breaker::CircuitBreakerTelemetry;
 use api::grpc::transport_channel_pool::TransportChannelPool;
 use tonic::transport::Uri;
 
@@ -30,4 +31,16 @@ impl ChannelService {
             self.channel_pool.drop_pool(&uri).await;
         }
     }
+
+    /// Circuit breakers of the peers, which had connection failures
+    pub fn circuit_breakers_telemetry(&self) -> HashMap<PeerId, CircuitBreakerTelemetry> {
+        let id_to_address = self.id_to_address.read();
+        id_to_address
+            .iter()
+            .filter_map(|(peer_id, uri)| {
+                let telemetry = self.channel_pool.circuit_breaker_telemetry(uri)?;
+                Some((*peer_id, telemetry))
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use segment::types::{ExtendedPointId, PointIdType, SeqNumberType};
+use serde::{Deserialize, Serialize};
+
+use crate::shards::replica_set::ReplicaState;
+use crate::shards::shard::{PeerId, ShardId};
+
+/// Point count and order-independent hashes of the points of a shard replica
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShardChecksum {
+    pub points_count: usize,
+    /// Hash of the point ids
+    pub ids_checksum: u64,
+    /// Hash of the point ids and their versions.
+    /// Versions are assigned by the operations, applied to the replica, so replicas filled by
+    /// a shard transfer have the same ids, but different versions.
+    pub versions_checksum: u64,
+}
+
+/// Finalizer of the SplitMix64 generator, which is stable across versions and platforms,
+/// unlike the std hasher
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
+
+fn point_id_hash(point_id: PointIdType) -> u64 {
+    match point_id {
+        ExtendedPointId::NumId(id) => mix(id),
+        ExtendedPointId::Uuid(uuid) => {
+            let value = uuid.as_u128();
+            // Separate from the numeric ids with the same lower bits
+            mix(mix(value as u64) ^ (value >> 64) as u64 ^ 0x5555_5555_5555_5555)
+        }
+    }
+}
+
+impl ShardChecksum {
+    /// Checksum of the points with their latest versions
+    pub fn from_versions(versions: &HashMap<PointIdType, SeqNumberType>) -> Self {
+        let mut checksum = Self {
+            points_count: versions.len(),
+            ..Default::default()
+        };
+        // Sum is independent of the order of the points
+        for (&point_id, &version) in versions {
+            let id_hash = point_id_hash(point_id);
+            checksum.ids_checksum = checksum.ids_checksum.wrapping_add(id_hash);
+            checksum.versions_checksum = checksum
+                .versions_checksum
+                .wrapping_add(mix(id_hash ^ mix(version)));
+        }
+        checksum
+    }
+}
+
+/// Checksum of a single replica of the shard, or the error, if it is unavailable
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ReplicaChecksum {
+    pub peer_id: PeerId,
+    pub state: ReplicaState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<ShardChecksum>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ShardChecksumReport {
+    pub shard_id: ShardId,
+    pub replicas: Vec<ReplicaChecksum>,
+    /// If false - active replicas of the shard have different points, or some of them are
+    /// unavailable. Replicas in other states are not compared.
+    pub consistent: bool,
+}
+
+impl ShardChecksumReport {
+    pub fn new(shard_id: ShardId, mut replicas: Vec<ReplicaChecksum>) -> Self {
+        replicas.sort_by_key(|replica| replica.peer_id);
+        let mut active = replicas
+            .iter()
+            .filter(|replica| replica.state == ReplicaState::Active)
+            .map(|replica| {
+                replica
+                    .checksum
+                    .map(|checksum| (checksum.points_count, checksum.ids_checksum))
+            });
+        let consistent = match active.next() {
+            Some(Some(first)) => active.all(|checksum| checksum == Some(first)),
+            Some(None) => false,
+            None => true,
+        };
+        Self {
+            shard_id,
+            replicas,
+            consistent,
+        }
+    }
+}
+
+/// Comparison of the replicas of all shards of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct CollectionChecksum {
+    pub shards: Vec<ShardChecksumReport>,
+    /// Shards, which active replicas didn't converge
+    pub inconsistent_shards: Vec<ShardId>,
+}
+
+impl CollectionChecksum {
+    pub fn new(mut shards: Vec<ShardChecksumReport>) -> Self {
+        shards.sort_by_key(|shard| shard.shard_id);
+        let inconsistent_shards = shards
+            .iter()
+            .filter(|shard| !shard.consistent)
+            .map(|shard| shard.shard_id)
+            .collect();
+        Self {
+            shards,
+            inconsistent_shards,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replica(peer_id: PeerId, state: ReplicaState, checksum: ShardChecksum) -> ReplicaChecksum {
+        ReplicaChecksum {
+            peer_id,
+            state,
+            checksum: Some(checksum),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_shard_checksum() {
+        let versions: HashMap<PointIdType, SeqNumberType> = (0..100u64)
+            .map(|id| (id.into(), id * 2))
+            .chain([(ExtendedPointId::Uuid(uuid::Uuid::from_u128(7)), 5)])
+            .collect();
+        let checksum = ShardChecksum::from_versions(&versions);
+        assert_eq!(checksum.points_count, 101);
+
+        // Same points, transferred to another replica with different versions
+        let transferred: HashMap<_, _> = versions.keys().map(|&id| (id, 1)).collect();
+        let transferred_checksum = ShardChecksum::from_versions(&transferred);
+        assert_eq!(transferred_checksum.ids_checksum, checksum.ids_checksum);
+        assert_ne!(
+            transferred_checksum.versions_checksum,
+            checksum.versions_checksum
+        );
+
+        // Artificial divergence: one point is replaced on the replica
+        let mut diverged = versions.clone();
+        diverged.remove(&PointIdType::from(42u64));
+        diverged.insert(1000u64.into(), 84);
+        let diverged_checksum = ShardChecksum::from_versions(&diverged);
+        assert_eq!(diverged_checksum.points_count, checksum.points_count);
+        assert_ne!(diverged_checksum.ids_checksum, checksum.ids_checksum);
+
+        let report = ShardChecksumReport::new(
+            0,
+            vec![
+                replica(2, ReplicaState::Active, transferred_checksum),
+                replica(1, ReplicaState::Active, checksum),
+                replica(3, ReplicaState::Partial, diverged_checksum),
+            ],
+        );
+        assert!(report.consistent);
+
+        let report = ShardChecksumReport::new(
+            1,
+            vec![
+                replica(1, ReplicaState::Active, checksum),
+                replica(3, ReplicaState::Active, diverged_checksum),
+            ],
+        );
+        assert!(!report.consistent);
+
+        let unavailable = ReplicaChecksum {
+            peer_id: 2,
+            state: ReplicaState::Active,
+            checksum: None,
+            error: Some("Timeout".to_string()),
+        };
+        let report = ShardChecksumReport::new(
+            2,
+            vec![unavailable, replica(1, ReplicaState::Active, checksum)],
+        );
+        assert!(!report.consistent);
+
+        let collection = CollectionChecksum::new(vec![
+            ShardChecksumReport::new(1, vec![replica(1, ReplicaState::Active, checksum)]),
+            report,
+        ]);
+        assert_eq!(collection.inconsistent_shards, vec![2]);
+    }
+}
@@ -1,17 +1,19 @@
 use api::grpc::conversions::payload_to_proto;
 use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
 use api::grpc::qdrant::{
-    ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
-    CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
+    CancelFieldIndexBuildInternal, ClearPayloadPoints, ClearPayloadPointsInternal,
+    CreateFieldIndexCollection, CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
     DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointVectors,
-    PointsIdsList, PointsSelector, SetPayloadPoints, SetPayloadPointsInternal, SyncPoints,
+    PointsIdsList, PointsSelector, RebuildFieldIndexCollection,
+    RebuildFieldIndexCollectionInternal, SetPayloadPoints, SetPayloadPointsInternal, SyncPoints,
     SyncPointsInternal, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
     UpsertPointsInternal, VectorsSelector,
 };
 use segment::types::{Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint};
 use tonic::Status;
 
+use crate::common::update_context::{client_operation_id, update_context};
 use crate::operations::conversions::write_ordering_to_proto;
 use crate::operations::payload_ops::{DeletePayload, SetPayload};
 use crate::operations::point_ops::{PointInsertOperations, PointSyncOperation, WriteOrdering};
@@ -64,6 +66,8 @@ pub fn internal_upsert_points(
                     .collect::<Result<Vec<_>, Status>>()?,
             },
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     })
 }
@@ -86,6 +90,8 @@ pub fn internal_delete_points(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -106,6 +112,8 @@ pub fn internal_delete_points_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -131,6 +139,8 @@ pub fn internal_update_vectors(
                 })
                 .collect(),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -157,6 +167,8 @@ pub fn internal_delete_vectors(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -181,6 +193,8 @@ pub fn internal_delete_vectors_by_filter(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -212,6 +226,8 @@ pub fn internal_set_payload(
             payload: payload_to_proto(set_payload.payload),
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -243,6 +259,8 @@ pub fn internal_delete_payload(
             keys: delete_payload.keys,
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -265,6 +283,8 @@ pub fn internal_clear_payload(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -285,6 +305,8 @@ pub fn internal_clear_payload_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -320,6 +342,10 @@ pub fn internal_create_index(
                 None,
             ),
             PayloadFieldSchema::FieldParams(field_params) => match field_params {
+                PayloadSchemaParams::Keyword(keyword_index_params) => (
+                    api::grpc::qdrant::FieldType::Keyword as i32,
+                    Some(keyword_index_params.into()),
+                ),
                 PayloadSchemaParams::Text(text_index_params) => (
                     api::grpc::qdrant::FieldType::Text as i32,
                     Some(text_index_params.into()),
@@ -338,6 +364,8 @@ pub fn internal_create_index(
             field_type,
             field_index_params,
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
@@ -356,10 +384,50 @@ pub fn internal_delete_index(
             wait: Some(wait),
             field_name: delete_index,
             ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
         }),
     }
 }
 
+pub fn internal_rebuild_index(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    rebuild_index: String,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> RebuildFieldIndexCollectionInternal {
+    RebuildFieldIndexCollectionInternal {
+        shard_id,
+        rebuild_field_index_collection: Some(RebuildFieldIndexCollection {
+            collection_name,
+            wait: Some(wait),
+            field_name: rebuild_index,
+            ordering: ordering.map(write_ordering_to_proto),
+            operation_id: client_operation_id().map(|id| id.to_string()),
+            wait_for: update_context().wait_for.map(Into::into),
+        }),
+    }
+}
+
+pub fn internal_cancel_index_build(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    field_name: String,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> CancelFieldIndexBuildInternal {
+    CancelFieldIndexBuildInternal {
+        collection_name,
+        field_name,
+        wait: Some(wait),
+        ordering: ordering.map(write_ordering_to_proto),
+        shard_id,
+        operation_id: client_operation_id().map(|id| id.to_string()),
+        wait_for: update_context().wait_for.map(Into::into),
+    }
+}
+
 pub fn try_scored_point_from_grpc(
     point: api::grpc::qdrant::ScoredPoint,
     with_payload: bool,
@@ -387,5 +455,7 @@ pub fn try_scored_point_from_grpc(
         score: point.score,
         payload,
         vector,
+        payload_truncation: point.payload_truncation.map(|truncation| truncation.into()),
+        vector_slice: point.vector_slice.map(|slice| slice.into()),
     })
 }
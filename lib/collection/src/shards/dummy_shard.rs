@@ -2,16 +2,19 @@ use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, OptimizationPlan,
+    OrderedQueryRequest, PointRequest, Record, SearchRequestBatch, SegmentDescription,
+    UpdateResult,
 };
 use crate::operations::CollectionUpdateOperations;
+use crate::shards::checksum::ShardChecksum;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 
@@ -40,11 +43,32 @@ impl DummyShard {
         self.dummy()
     }
 
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        self.dummy()
+    }
+
+    pub async fn optimization_plan(&self) -> CollectionResult<Option<OptimizationPlan>> {
+        self.dummy()
+    }
+
+    pub fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        self.dummy()
+    }
+
+    pub fn segments_info(&self) -> CollectionResult<Vec<SegmentDescription>> {
+        self.dummy()
+    }
+
+    pub fn trace_filter(&self) -> CollectionResult<Option<FilterTrace>> {
+        self.dummy()
+    }
+
     pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
         LocalShardTelemetry {
             variant_name: Some("dummy shard".into()),
             segments: vec![],
             optimizations: Default::default(),
+            wal_recovery: None,
         }
     }
 
@@ -99,4 +123,8 @@ impl ShardOperation for DummyShard {
     ) -> CollectionResult<Vec<Record>> {
         self.dummy()
     }
+
+    async fn query_ordered(&self, _: Arc<OrderedQueryRequest>) -> CollectionResult<Vec<Record>> {
+        self.dummy()
+    }
 }
@@ -1,24 +1,32 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadFieldSchema, PayloadKeyType, PointIdType, ScoredPoint,
+    SeqNumberType, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 
 use crate::operations::point_ops::{PointOperations, PointStruct, PointSyncOperation};
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, OptimizationPlan,
+    OrderedQueryRequest, PointRequest, Record, SearchRequestBatch, SegmentDescription,
+    UpdateResult,
 };
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
-use crate::shards::local_shard::LocalShard;
+use crate::read_cache::DataVersion;
+use crate::shards::checksum::ShardChecksum;
+use crate::shards::local_shard::{LocalShard, ShardFlushHandle};
 use crate::shards::remote_shard::RemoteShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
+use crate::shards::transfer::wal_delta::changed_point_ids;
+use crate::wal::WalRetention;
 
 /// ForwardProxyShard
 ///
@@ -115,6 +123,60 @@ impl ForwardProxyShard {
         Ok(next_page_offset)
     }
 
+    /// Points of the wrapped shard, changed by the operations starting from `from`.
+    /// `None` if the changed points are unknown.
+    pub fn wal_delta_point_ids(&self, from: SeqNumberType) -> Option<Vec<PointIdType>> {
+        changed_point_ids(&self.wrapped_shard.wal.lock(), from)
+    }
+
+    /// Move the current state of the given points to the remote shard.
+    /// Points, which don't exist in the wrapped shard anymore, are deleted in the remote shard.
+    pub async fn transfer_points(
+        &self,
+        point_ids: Vec<PointIdType>,
+        wait: bool,
+    ) -> CollectionResult<()> {
+        let _update_lock = self.update_lock.lock().await;
+        let request = Arc::new(PointRequest {
+            ids: point_ids.clone(),
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: true.into(),
+            with_timestamps: false,
+            max_payload_return_bytes: None,
+        });
+        let records = self
+            .wrapped_shard
+            .retrieve(request, &WithPayload::from(true), &true.into())
+            .await?;
+
+        let existing: HashSet<_> = records.iter().map(|record| record.id).collect();
+        let deleted: Vec<_> = point_ids
+            .into_iter()
+            .filter(|point_id| !existing.contains(point_id))
+            .collect();
+
+        let points: Result<Vec<PointStruct>, String> =
+            records.into_iter().map(|point| point.try_into()).collect();
+        let points = points?;
+
+        if !points.is_empty() {
+            let upsert_operation = CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(points.into()),
+            );
+            self.remote_shard
+                .update(upsert_operation, wait && deleted.is_empty())
+                .await?;
+        }
+        if !deleted.is_empty() {
+            let delete_operation =
+                CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                    ids: deleted,
+                });
+            self.remote_shard.update(delete_operation, wait).await?;
+        }
+        Ok(())
+    }
+
     pub fn deconstruct(self) -> (LocalShard, RemoteShard) {
         (self.wrapped_shard, self.remote_shard)
     }
@@ -135,8 +197,73 @@ impl ForwardProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
-        self.wrapped_shard.get_telemetry_data()
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        self.wrapped_shard.trigger_optimizers().await
+    }
+
+    pub fn flush(&self) -> ShardFlushHandle {
+        self.wrapped_shard.flush()
+    }
+
+    pub fn subscribe_status_changes(&self) -> watch::Receiver<()> {
+        self.wrapped_shard.subscribe_status_changes()
+    }
+
+    pub async fn optimization_plan(&self) -> Option<OptimizationPlan> {
+        self.wrapped_shard.optimization_plan().await
+    }
+
+    pub async fn indexed_only_excluded_points(&self, vector_names: &[String]) -> usize {
+        self.wrapped_shard
+            .indexed_only_excluded_points(vector_names)
+            .await
+    }
+
+    pub fn indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        self.wrapped_shard.indexed_fields()
+    }
+
+    pub fn unindexed_filter_keys(&self, filters: &[&Filter]) -> Vec<PayloadKeyType> {
+        self.wrapped_shard.unindexed_filter_keys(filters)
+    }
+
+    pub async fn check_quota(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        self.wrapped_shard.check_quota(operation).await
+    }
+
+    pub fn get_telemetry_data(&self, with_graph_health: bool) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data(with_graph_health)
+    }
+
+    pub async fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        self.wrapped_shard.checksum().await
+    }
+
+    pub fn warmup_tasks(&self) -> Vec<Vec<WarmupTask>> {
+        self.wrapped_shard.warmup_tasks()
+    }
+
+    pub fn data_version(&self) -> Option<DataVersion> {
+        self.wrapped_shard.data_version()
+    }
+
+    pub async fn segments_info(&self) -> CollectionResult<Vec<SegmentDescription>> {
+        self.wrapped_shard.segments_info().await
+    }
+
+    pub async fn trace_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Arc<Filter>,
+    ) -> CollectionResult<Option<FilterTrace>> {
+        self.wrapped_shard.trace_filter(point_id, filter).await
+    }
+
+    pub fn set_wal_retention(&self, retention: Option<WalRetention>) {
+        self.wrapped_shard.set_wal_retention(retention)
     }
 }
 
@@ -205,4 +332,12 @@ impl ShardOperation for ForwardProxyShard {
             .retrieve(request, with_payload, with_vector)
             .await
     }
+
+    async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+    ) -> CollectionResult<Vec<Record>> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.query_ordered(request).await
+    }
 }
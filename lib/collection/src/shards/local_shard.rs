@@ -1,50 +1,78 @@
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::mem::size_of;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use parking_lot::{Mutex as ParkingMutex, RwLock};
+use segment::common::cpu::CpuBudget;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::data_types::vectors::VectorElementType;
 use segment::entry::entry_point::SegmentEntry;
 use segment::index::field_index::CardinalityEstimation;
 use segment::segment::Segment;
 use segment::segment_constructor::{build_segment, load_segment};
 use segment::types::{
-    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, SegmentType,
+    CompressionRatio, Filter, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType,
+    PayloadStorageType, PointIdType, QuantizationConfig, SegmentConfig, SegmentType, SeqNumberType,
 };
 use segment::utils::mem::Mem;
 use tokio::fs::{copy, create_dir_all, remove_dir_all};
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::{mpsc, oneshot, Mutex, RwLock as TokioRwLock};
+use tokio::sync::{mpsc, oneshot, watch, Mutex, RwLock as TokioRwLock};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 use wal::{Wal, WalOptions};
 
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
+use crate::collection_manager::optimizers::vacuum_optimizer::STAGED_INDEX_BATCH_SIZE;
+use crate::collection_manager::segments_searcher::is_large_unindexed_segment;
+use crate::common::io_throttle::IoThrottle;
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, OptimizersStatus,
+    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, FieldIndexBuildProgress,
+    OptimizationPlan, OptimizersStatus, SegmentDescription, ShardIndexRebuildProgress,
 };
 use crate::operations::CollectionUpdateOperations;
 use crate::optimizers_builder::build_optimizers;
+use crate::quota::QuotaUsageTracker;
+use crate::read_cache::DataVersion;
+use crate::shards::applied_operations::AppliedOperations;
+use crate::shards::checksum::ShardChecksum;
 use crate::shards::shard::ShardId;
 use crate::shards::shard_config::{ShardConfig, SHARD_CONFIG_FILE};
 use crate::shards::telemetry::{LocalShardTelemetry, OptimizerTelemetry};
+use crate::shards::wal_recovery::{WalRecoveryState, WAL_RECOVERY_LOG_INTERVAL};
 use crate::shards::CollectionId;
 use crate::update_handler::{Optimizer, UpdateHandler, UpdateSignal};
-use crate::wal::SerdeWal;
+use crate::wal::{SerdeWal, WalRetention};
 
 pub type LockedWal = Arc<ParkingMutex<SerdeWal<CollectionUpdateOperations>>>;
 
+/// Number of WAL records read at once during recovery
+const WAL_RECOVERY_BATCH_SIZE: usize = 1024;
+
+/// Interval to check, whether the segments under optimization have built a field index
+const FIELD_INDEX_BUILD_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Unique number of the next created instance of a shard
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Flush of the shard, running in the background.
+/// Resolves to the number of the last operation, which is persisted on disk
+pub type ShardFlushHandle = JoinHandle<CollectionResult<SeqNumberType>>;
+
 /// LocalShard
 ///
 /// LocalShard is an entity that can be moved between peers and contains some part of one collections data.
@@ -59,7 +87,19 @@ pub struct LocalShard {
     pub(super) update_sender: ArcSwap<Sender<UpdateSignal>>,
     pub(super) path: PathBuf,
     pub(super) optimizers: Arc<Vec<Arc<Optimizer>>>,
+    pub(super) wal_recovery: Arc<WalRecoveryState>,
+    /// CPUs, which optimizations of this shard can use for building indexes
+    pub(super) optimizer_cpu_budget: Arc<CpuBudget>,
+    /// Client-supplied ids of the latest applied operations
+    pub(super) applied_operations: ParkingMutex<AppliedOperations>,
+    /// Usage of the shard, counted against the quotas of the collection
+    pub(super) quota_usage: Arc<QuotaUsageTracker>,
+    /// Notified whenever the status of the shard might have changed
+    status_changes: Arc<watch::Sender<()>>,
     update_runtime: Handle,
+    /// Unique number of this instance, tells apart the data of the shard before and after
+    /// it is loaded again, e.g. from a snapshot
+    instance_id: u64,
 }
 
 /// Shard holds information about segments and WAL.
@@ -71,6 +111,10 @@ impl LocalShard {
         let segments_to = Self::segments_path(to);
         tokio::fs::rename(wal_from, wal_to).await?;
         tokio::fs::rename(segments_from, segments_to).await?;
+        let applied_operations_from = AppliedOperations::get_path(from);
+        if applied_operations_from.exists() {
+            tokio::fs::rename(applied_operations_from, AppliedOperations::get_path(to)).await?;
+        }
         Ok(())
     }
 
@@ -95,6 +139,11 @@ impl LocalShard {
         if segments_path.exists() {
             remove_dir_all(segments_path).await?;
         }
+        // Delete ids of the applied operations
+        let applied_operations_path = AppliedOperations::get_path(shard_path);
+        if applied_operations_path.exists() {
+            tokio::fs::remove_file(applied_operations_path).await?;
+        }
 
         Ok(())
     }
@@ -111,6 +160,12 @@ impl LocalShard {
         let segment_holder = Arc::new(RwLock::new(segment_holder));
         let config = collection_config.read().await;
         let locked_wal = Arc::new(ParkingMutex::new(wal));
+        let optimizer_cpu_budget = Arc::new(CpuBudget::new(
+            config.optimizer_config.get_max_indexing_threads(),
+        ));
+        let quota_usage = Arc::new(QuotaUsageTracker::default());
+        let (status_changes, _) = watch::channel(());
+        let status_changes = Arc::new(status_changes);
 
         let mut update_handler = UpdateHandler::new(
             shared_storage_config.clone(),
@@ -120,6 +175,9 @@ impl LocalShard {
             locked_wal.clone(),
             config.optimizer_config.flush_interval_sec,
             config.optimizer_config.max_optimization_threads,
+            optimizer_cpu_budget.clone(),
+            quota_usage.clone(),
+            status_changes.clone(),
         );
 
         let (update_sender, update_receiver) =
@@ -128,6 +186,9 @@ impl LocalShard {
 
         drop(config); // release `shared_config` from borrow checker
 
+        let applied_operations =
+            AppliedOperations::load(shard_path, shared_storage_config.applied_operations_window);
+
         Self {
             segments: segment_holder,
             collection_config,
@@ -138,6 +199,12 @@ impl LocalShard {
             path: shard_path.to_owned(),
             update_runtime,
             optimizers,
+            wal_recovery: Default::default(),
+            optimizer_cpu_budget,
+            applied_operations: ParkingMutex::new(applied_operations),
+            quota_usage,
+            status_changes,
+            instance_id: NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed),
         }
     }
 
@@ -145,6 +212,18 @@ impl LocalShard {
         self.segments.deref()
     }
 
+    /// Version of the data of the shard, changed by every applied update.
+    /// `None` while the data is being changed by the WAL recovery
+    pub fn data_version(&self) -> Option<DataVersion> {
+        if !self.wal_recovery.is_ready() {
+            return None;
+        }
+        Some(DataVersion {
+            applied_operations: self.segments.read().applied_operations(),
+            shard_instances: self.instance_id,
+        })
+    }
+
     /// Recovers shard from disk.
     pub async fn load(
         id: ShardId,
@@ -223,6 +302,7 @@ impl LocalShard {
             &collection_config_read.optimizer_config,
             &collection_config_read.hnsw_config,
             &collection_config_read.quantization_config,
+            &shared_storage_config.field_index_build,
         );
 
         drop(collection_config_read); // release `shared_config` from borrow checker
@@ -238,7 +318,11 @@ impl LocalShard {
         )
         .await;
 
-        collection.load_from_wal(collection_id)?;
+        if collection.shred_storage_config.background_wal_recovery {
+            collection.load_from_wal_in_background(collection_id)?;
+        } else {
+            collection.load_from_wal(collection_id)?;
+        }
 
         let available_memory_bytes = Mem::new().available_memory_bytes() as usize;
         let vectors_size_bytes = collection.estimate_vector_data_size().await;
@@ -261,6 +345,26 @@ impl LocalShard {
             }
         }
 
+        // Payload indexes, which failed to load, are rebuilt by the optimizers without waiting
+        // for the next update of the shard
+        let has_corrupted_indexes =
+            collection
+                .segments
+                .read()
+                .iter()
+                .any(|(_, segment)| match segment {
+                    LockedSegment::Original(segment) => !segment
+                        .read()
+                        .payload_index
+                        .borrow()
+                        .fields_to_rebuild()
+                        .is_empty(),
+                    LockedSegment::Proxy(_) => false,
+                });
+        if has_corrupted_indexes {
+            collection.trigger_optimizers().await?;
+        }
+
         Ok(collection)
     }
 
@@ -330,21 +434,31 @@ impl LocalShard {
         let mut build_handlers = vec![];
 
         let vector_params = config.params.into_base_vector_data()?;
+        let sparse_vector_params = config.params.into_sparse_vector_data();
         let segment_number = config.optimizer_config.get_number_segments();
 
         for _sid in 0..segment_number {
             let path_clone = segments_path.clone();
+            let payload_indexes = config.params.payload_indexes.clone();
             let segment_config = SegmentConfig {
                 vector_data: vector_params.clone(),
+                sparse_vector_data: sparse_vector_params.clone(),
                 payload_storage_type: if config.params.on_disk_payload {
                     PayloadStorageType::OnDisk
                 } else {
                     PayloadStorageType::InMemory
                 },
+                payload_cache_fields: config.params.payload_cache_fields.clone(),
             };
             let segment = thread::Builder::new()
                 .name(format!("shard-build-{collection_id}-{id}"))
-                .spawn(move || build_segment(&path_clone, &segment_config, true))
+                .spawn(move || {
+                    let mut segment = build_segment(&path_clone, &segment_config, true)?;
+                    for (field, schema) in &payload_indexes {
+                        segment.create_field_index(0, field, Some(schema))?;
+                    }
+                    Ok::<_, CollectionError>(segment)
+                })
                 .unwrap();
             build_handlers.push(segment);
         }
@@ -377,6 +491,7 @@ impl LocalShard {
             &config.optimizer_config,
             &config.hnsw_config,
             &config.quantization_config,
+            &shared_storage_config.field_index_build,
         );
 
         drop(config); // release `shared_config` from borrow checker
@@ -407,8 +522,95 @@ impl LocalShard {
 
     /// Loads latest collection operations from WAL
     pub fn load_from_wal(&self, collection_id: CollectionId) -> CollectionResult<()> {
-        let wal = self.wal.lock();
-        let bar = ProgressBar::new(wal.len());
+        self.start_wal_recovery();
+        Self::replay_wal(
+            &self.segments,
+            &self.wal,
+            &self.wal_recovery,
+            &self.path,
+            &collection_id,
+            true,
+        )
+    }
+
+    /// Replays WAL in a separate thread.
+    /// Meanwhile, the shard answers read requests from already loaded segments.
+    fn load_from_wal_in_background(&self, collection_id: CollectionId) -> CollectionResult<()> {
+        // Recovery is marked as started before the shard is returned, so no update can get in
+        self.start_wal_recovery();
+
+        let segments = self.segments.clone();
+        let wal = self.wal.clone();
+        let wal_recovery = self.wal_recovery.clone();
+        let path = self.path.clone();
+        let status_changes = self.status_changes.clone();
+
+        thread::Builder::new()
+            .name(format!("wal-recovery-{collection_id}"))
+            .spawn(move || {
+                let _ =
+                    Self::replay_wal(&segments, &wal, &wal_recovery, &path, &collection_id, false);
+                status_changes.send_replace(());
+            })?;
+
+        Ok(())
+    }
+
+    fn start_wal_recovery(&self) {
+        let total_records = self.wal.lock().len();
+        self.wal_recovery
+            .start(total_records, &Self::wal_path(&self.path));
+    }
+
+    fn replay_wal(
+        segments: &RwLock<SegmentHolder>,
+        wal: &LockedWal,
+        wal_recovery: &WalRecoveryState,
+        shard_path: &Path,
+        collection_id: &CollectionId,
+        show_progress: bool,
+    ) -> CollectionResult<()> {
+        let result = Self::apply_wal_records(
+            segments,
+            wal,
+            wal_recovery,
+            shard_path,
+            collection_id,
+            show_progress,
+        );
+
+        match &result {
+            Ok(()) => {
+                log::debug!("Recovered collection {collection_id} from WAL");
+                wal_recovery.finish();
+            }
+            Err(err) => {
+                log::error!("Failed to recover collection {collection_id} from WAL: {err}");
+                wal_recovery.fail(err.to_string());
+            }
+        }
+
+        result
+    }
+
+    fn apply_wal_records(
+        segments: &RwLock<SegmentHolder>,
+        wal: &LockedWal,
+        wal_recovery: &WalRecoveryState,
+        shard_path: &Path,
+        collection_id: &CollectionId,
+        show_progress: bool,
+    ) -> CollectionResult<()> {
+        let (first_index, total_records) = {
+            let wal = wal.lock();
+            (wal.first_index(), wal.len())
+        };
+
+        let bar = if show_progress {
+            ProgressBar::new(total_records)
+        } else {
+            ProgressBar::hidden()
+        };
 
         let progress_style = ProgressStyle::default_bar()
             .template("{msg} [{elapsed_precise}] {wide_bar} {pos}/{len} (eta:{eta})")
@@ -416,7 +618,6 @@ impl LocalShard {
         bar.set_style(progress_style);
 
         bar.set_message(format!("Recovering collection {collection_id}"));
-        let segments = self.segments();
 
         // When `Segment`s are flushed, WAL is truncated up to the index of the last operation
         // that has been applied and flushed.
@@ -424,44 +625,71 @@ impl LocalShard {
         // `SerdeWal` wrapper persists/keeps track of this index (in addition to any handling
         // in the `wal` crate itself).
         //
-        // `SerdeWal::read_all` starts reading WAL from the first "un-truncated" index,
+        // `SerdeWal::first_index` points to the first "un-truncated" index,
         // so no additional handling required to "skip" any potentially applied entries.
         //
         // Note, that it's not guaranted that some operation won't be re-applied to the storage.
-        // (`SerdeWal::read_all` may even start reading WAL from some already truncated
+        // (`SerdeWal::first_index` may even point to some already truncated
         // index *occasionally*), but the storage can handle it.
 
-        for (op_num, update) in wal.read_all() {
-            // Propagate `CollectionError::ServiceError`, but skip other error types.
-            match &CollectionUpdater::update(segments, op_num, update) {
-                Err(err @ CollectionError::ServiceError { error, backtrace }) => {
-                    let path = self.path.display();
-
-                    log::error!(
-                        "Can't apply WAL operation: {error}, \
-                         collection: {collection_id}, \
-                         shard: {path}, \
-                         op_num: {op_num}"
-                    );
+        let mut next_index = first_index;
 
-                    if let Some(backtrace) = &backtrace {
-                        log::error!("Backtrace: {}", backtrace);
-                    }
+        loop {
+            // WAL lock is released between batches, so the flush worker is not blocked for the
+            // whole duration of the recovery
+            let batch: Vec<_> = wal
+                .lock()
+                .read(next_index)
+                .take(WAL_RECOVERY_BATCH_SIZE)
+                .collect();
+
+            let Some((last_op_num, _)) = batch.last() else {
+                break;
+            };
+            next_index = *last_op_num + 1;
+
+            for (op_num, update) in batch {
+                wal_recovery.on_operation_start(op_num);
+
+                // Propagate `CollectionError::ServiceError`, but skip other error types.
+                match &CollectionUpdater::update(segments, op_num, update) {
+                    Err(err @ CollectionError::ServiceError { error, backtrace }) => {
+                        let path = shard_path.display();
 
-                    return Err(err.clone());
+                        log::error!(
+                            "Can't apply WAL operation: {error}, \
+                             collection: {collection_id}, \
+                             shard: {path}, \
+                             op_num: {op_num}"
+                        );
+
+                        if let Some(backtrace) = &backtrace {
+                            log::error!("Backtrace: {}", backtrace);
+                        }
+
+                        return Err(err.clone());
+                    }
+                    Err(err @ CollectionError::OutOfMemory { .. }) => {
+                        log::error!("{err}");
+                        return Err(err.clone());
+                    }
+                    Err(err @ CollectionError::NotFound { .. }) => log::warn!("{err}"),
+                    Err(err) => log::error!("{err}"),
+                    Ok(_) => (),
                 }
-                Err(err @ CollectionError::OutOfMemory { .. }) => {
-                    log::error!("{err}");
-                    return Err(err.clone());
+
+                let applied_records = wal_recovery.on_operation_applied();
+                if applied_records % WAL_RECOVERY_LOG_INTERVAL == 0 {
+                    log::info!(
+                        "Recovering collection {collection_id}: \
+                         {applied_records}/{total_records} WAL records applied"
+                    );
                 }
-                Err(err @ CollectionError::NotFound { .. }) => log::warn!("{err}"),
-                Err(err) => log::error!("{err}"),
-                Ok(_) => (),
+                bar.inc(1);
             }
-            bar.inc(1);
         }
 
-        self.segments.read().flush_all(true)?;
+        segments.read().flush_all(true)?;
         bar.finish();
 
         Ok(())
@@ -485,15 +713,351 @@ impl LocalShard {
             &config.optimizer_config,
             &config.hnsw_config,
             &config.quantization_config,
+            &self.shred_storage_config.field_index_build,
         );
         update_handler.optimizers = new_optimizers;
         update_handler.flush_interval_sec = config.optimizer_config.flush_interval_sec;
+        self.optimizer_cpu_budget
+            .set_capacity(config.optimizer_config.get_max_indexing_threads());
         update_handler.run_workers(update_receiver);
         self.update_sender.load().send(UpdateSignal::Nop).await?;
 
         Ok(())
     }
 
+    /// Wakes up optimization workers, so they check for required optimizations immediately
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        self.update_sender.load().send(UpdateSignal::Nop).await?;
+        Ok(())
+    }
+
+    /// Start a synchronous flush of WAL and all segments, like the one of the flush worker.
+    ///
+    /// Segments are flushed under the read lock of the segment holder,
+    /// so optimizations can't replace segments until the flush is finished.
+    pub fn flush(&self) -> ShardFlushHandle {
+        let segments = self.segments.clone();
+        let wal = self.wal.clone();
+        self.update_runtime
+            .spawn_blocking(move || UpdateHandler::flush_wal_and_segments(&segments, &wal, true))
+    }
+
+    /// Receiver, notified whenever the status of the shard might have changed
+    pub fn subscribe_status_changes(&self) -> watch::Receiver<()> {
+        self.status_changes.subscribe()
+    }
+
+    /// Describes the optimization, which would be launched next, without running it
+    pub async fn optimization_plan(&self) -> Option<OptimizationPlan> {
+        // Optimizers of the update handler are rebuilt on config update
+        let optimizers = self.update_handler.lock().await.optimizers.clone();
+        UpdateHandler::plan_optimization(&optimizers, self.segments.clone())
+    }
+
+    /// Number of points stored in segments, which are skipped by `indexed_only` searches
+    /// for any of the given vectors
+    pub async fn indexed_only_excluded_points(&self, vector_names: &[String]) -> usize {
+        let indexing_threshold_kb = self
+            .collection_config
+            .read()
+            .await
+            .optimizer_config
+            .get_indexing_threshold_kb();
+        self.segments()
+            .read()
+            .iter()
+            .map(|(_id, segment)| {
+                let segment_entry = segment.get();
+                let read_segment = segment_entry.read();
+                let is_excluded = vector_names.iter().any(|vector_name| {
+                    is_large_unindexed_segment(&*read_segment, vector_name, indexing_threshold_kb)
+                });
+                if is_excluded {
+                    read_segment.available_point_count()
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Payload fields, which have an index in the segments of the shard
+    pub fn indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        self.segments()
+            .read()
+            .iter()
+            .flat_map(|(_id, segment)| segment.get().read().get_indexed_fields())
+            .collect()
+    }
+
+    /// Keys of the `filters`, which can't be served by the payload index of some segment.
+    /// Sorted and deduplicated.
+    pub fn unindexed_filter_keys(&self, filters: &[&Filter]) -> Vec<PayloadKeyType> {
+        let segments = self.segments().read();
+        let mut keys: Vec<_> = segments
+            .iter()
+            .flat_map(|(_id, segment)| {
+                let segment = segment.get();
+                let segment = segment.read();
+                filters
+                    .iter()
+                    .flat_map(|filter| segment.unindexed_filter_keys(filter))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Data of the segments to read on warmup, grouped by segment.
+    /// Segments under optimization are skipped, they are replaced by the optimized ones soon.
+    pub fn warmup_tasks(&self) -> Vec<Vec<WarmupTask>> {
+        self.segments
+            .read()
+            .iter()
+            .filter_map(|(_, segment)| match segment {
+                LockedSegment::Original(segment) => Some(segment.read().warmup_tasks()),
+                LockedSegment::Proxy(_) => None,
+            })
+            .collect()
+    }
+
+    /// Keep the WAL records, which are still required to recover lagging replicas
+    pub fn set_wal_retention(&self, retention: Option<WalRetention>) {
+        self.wal.lock().set_retention(retention);
+    }
+
+    /// Point count and hashes of the points of the shard, to compare it with other replicas
+    pub async fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        let segments = self.segments.clone();
+        let checksum = tokio::task::spawn_blocking(move || {
+            // Point might be present in several segments during optimization
+            let mut versions: HashMap<PointIdType, SeqNumberType> = HashMap::new();
+            for (_id, segment) in segments.read().iter() {
+                let segment = segment.get();
+                let segment = segment.read();
+                for point_id in segment.iter_points() {
+                    if let Some(version) = segment.point_version(point_id) {
+                        let latest = versions.entry(point_id).or_insert(version);
+                        *latest = (*latest).max(version);
+                    }
+                }
+            }
+            ShardChecksum::from_versions(&versions)
+        })
+        .await?;
+        Ok(checksum)
+    }
+
+    /// Layout of the shard segments.
+    ///
+    /// Segments are locked for reading one at a time, so the layout might be slightly
+    /// inconsistent, if an optimization is finished in the meantime.
+    pub async fn segments_info(&self) -> CollectionResult<Vec<SegmentDescription>> {
+        let segments = self.segments.clone();
+        let descriptions = tokio::task::spawn_blocking(move || {
+            let locked_segments: Vec<_> = segments
+                .read()
+                .iter()
+                .map(|(segment_id, segment)| (*segment_id, segment.clone()))
+                .collect();
+            locked_segments
+                .iter()
+                .map(|(segment_id, segment)| describe_segment(*segment_id, segment))
+                .collect()
+        })
+        .await?;
+        Ok(descriptions)
+    }
+
+    /// Progress of the rebuild of the payload index of the field in the segments of the shard.
+    /// Segments under optimization report the progress of the wrapped segment.
+    pub async fn index_rebuild_progress(
+        &self,
+        shard_id: ShardId,
+        field: String,
+    ) -> CollectionResult<ShardIndexRebuildProgress> {
+        let segments = self.segments.clone();
+        let progress = tokio::task::spawn_blocking(move || {
+            let locked_segments: Vec<_> = segments
+                .read()
+                .iter()
+                .map(|(_segment_id, segment)| segment.clone())
+                .collect();
+            let mut progress = ShardIndexRebuildProgress {
+                shard_id,
+                ..Default::default()
+            };
+            for segment in locked_segments {
+                let segment = match segment {
+                    LockedSegment::Original(segment) => segment,
+                    LockedSegment::Proxy(proxy) => match &proxy.read().wrapped_segment {
+                        LockedSegment::Original(segment) => segment.clone(),
+                        LockedSegment::Proxy(_) => continue,
+                    },
+                };
+                let Some((indexed_points, total_points)) =
+                    segment.read().field_index_rebuild_progress(&field)
+                else {
+                    continue;
+                };
+                progress.rebuilding_segments += 1;
+                progress.indexed_points += indexed_points;
+                progress.total_points += total_points;
+            }
+            progress
+        })
+        .await?;
+        Ok(progress)
+    }
+
+    /// Creations of payload field indexes in the segments of the shard, ordered by field.
+    /// Segments under optimization report the creations of the wrapped segment.
+    pub async fn field_index_builds(
+        &self,
+        shard_id: ShardId,
+    ) -> CollectionResult<Vec<FieldIndexBuildProgress>> {
+        let segments = self.segments.clone();
+        let builds = tokio::task::spawn_blocking(move || {
+            let locked_segments: Vec<_> = segments
+                .read()
+                .iter()
+                .map(|(_segment_id, segment)| segment.clone())
+                .collect();
+            let mut builds: BTreeMap<PayloadKeyType, FieldIndexBuildProgress> = BTreeMap::new();
+            for segment in locked_segments {
+                let segment = match segment {
+                    LockedSegment::Original(segment) => segment,
+                    LockedSegment::Proxy(proxy) => match &proxy.read().wrapped_segment {
+                        LockedSegment::Original(segment) => segment.clone(),
+                        LockedSegment::Proxy(_) => continue,
+                    },
+                };
+                let segment = segment.read();
+                for (field, operation_id) in segment.building_field_indexes() {
+                    let (indexed_points, total_points) = segment
+                        .field_index_rebuild_progress(&field)
+                        .unwrap_or_default();
+                    let build =
+                        builds
+                            .entry(field.clone())
+                            .or_insert_with(|| FieldIndexBuildProgress {
+                                field_name: field,
+                                operation_id,
+                                shard_id,
+                                building_segments: 0,
+                                indexed_points: 0,
+                                total_points: 0,
+                            });
+                    build.operation_id = build.operation_id.max(operation_id);
+                    build.building_segments += 1;
+                    build.indexed_points += indexed_points;
+                    build.total_points += total_points;
+                }
+            }
+            builds.into_values().collect()
+        })
+        .await?;
+        Ok(builds)
+    }
+
+    /// Build the staged index of the field in the segments of the shard, until it is complete.
+    ///
+    /// Optimizers may build the same index concurrently, the progress is shared. Segments under
+    /// optimization get the index built by the optimization, so they are only waited for.
+    /// Reading of the payload is throttled according to the field index build config.
+    pub async fn build_field_index(&self, field: &str) -> CollectionResult<()> {
+        let mut throttle = IoThrottle::new(
+            self.shred_storage_config
+                .field_index_build
+                .max_io_mb_per_sec,
+        );
+        loop {
+            let segments = self.segments.clone();
+            let field = field.to_string();
+            let (is_building, is_progressed, bytes_read) = tokio::task::spawn_blocking(move || {
+                let locked_segments: Vec<_> = segments
+                    .read()
+                    .iter()
+                    .map(|(_segment_id, segment)| segment.clone())
+                    .collect();
+                let mut is_building = false;
+                let mut is_progressed = false;
+                let mut bytes_read = 0;
+                for segment in locked_segments {
+                    let segment = match segment {
+                        LockedSegment::Original(segment) => segment,
+                        LockedSegment::Proxy(proxy) => {
+                            let proxy = proxy.read();
+                            if let LockedSegment::Original(wrapped) = &proxy.wrapped_segment {
+                                is_building |=
+                                    wrapped.read().building_field_indexes().contains_key(&field);
+                            }
+                            match &proxy.write_segment {
+                                LockedSegment::Original(segment) => segment.clone(),
+                                LockedSegment::Proxy(_) => continue,
+                            }
+                        }
+                    };
+                    if segment
+                        .read()
+                        .field_index_rebuild_progress(&field)
+                        .is_none()
+                    {
+                        continue;
+                    }
+                    is_building = true;
+                    is_progressed = true;
+                    bytes_read += segment
+                        .write()
+                        .build_staged_field_index(&field, STAGED_INDEX_BATCH_SIZE)?
+                        .bytes_read;
+                }
+                CollectionResult::Ok((is_building, is_progressed, bytes_read))
+            })
+            .await??;
+
+            if !is_building {
+                return Ok(());
+            }
+            if !is_progressed {
+                tokio::time::sleep(FIELD_INDEX_BUILD_POLL_INTERVAL).await;
+            }
+            let delay = throttle.register(bytes_read);
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    /// Outcome of every condition of the filter for the point, if the shard has it.
+    /// A point might be present in several segments during optimization,
+    /// then its latest version is checked.
+    pub async fn trace_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Arc<Filter>,
+    ) -> CollectionResult<Option<FilterTrace>> {
+        let segments = self.segments.clone();
+        let trace = tokio::task::spawn_blocking(move || {
+            let mut latest: Option<(SeqNumberType, FilterTrace)> = None;
+            segments.read().read_points(&[point_id], |id, segment| {
+                let version = segment.point_version(id).unwrap_or_default();
+                if latest
+                    .as_ref()
+                    .map_or(true, |(latest_version, _)| *latest_version < version)
+                {
+                    latest = Some((version, segment.trace_filter(id, &filter)?));
+                }
+                Ok(true)
+            })?;
+            CollectionResult::Ok(latest.map(|(_, trace)| trace))
+        })
+        .await??;
+        Ok(trace)
+    }
+
     /// Finishes ongoing update tasks
     pub async fn stop_gracefully(&self) {
         if let Err(err) = self.update_sender.load().send(UpdateSignal::Stop).await {
@@ -671,11 +1235,20 @@ impl LocalShard {
         Ok(all_points)
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
+    /// Health of the HNSW graphs is only collected on request, since it scans the whole graphs
+    pub fn get_telemetry_data(&self, with_graph_health: bool) -> LocalShardTelemetry {
         let segments_read_guard = self.segments.read();
         let segments: Vec<_> = segments_read_guard
             .iter()
-            .map(|(_id, segment)| segment.get().read().get_telemetry_data())
+            .map(|(_id, segment)| {
+                let segment_entry = segment.get();
+                let read_segment = segment_entry.read();
+                let mut telemetry = read_segment.get_telemetry_data();
+                if with_graph_health {
+                    telemetry.graph_health = Some(read_segment.graph_health());
+                }
+                telemetry
+            })
             .collect();
 
         let optimizer_status = match &segments_read_guard.optimizer_errors {
@@ -688,6 +1261,12 @@ impl LocalShard {
             .iter()
             .map(|optimizer| optimizer.get_telemetry_data())
             .fold(Default::default(), |acc, x| acc + x);
+        let merges = self
+            .optimizers
+            .iter()
+            .filter(|optimizer| optimizer.name() == "merge")
+            .map(|optimizer| optimizer.get_telemetry_data().count)
+            .sum();
 
         LocalShardTelemetry {
             variant_name: None,
@@ -695,7 +1274,11 @@ impl LocalShard {
             optimizations: OptimizerTelemetry {
                 status: optimizer_status,
                 optimizations,
+                indexing_threads_used: self.optimizer_cpu_budget.used(),
+                indexing_threads_budget: self.optimizer_cpu_budget.capacity(),
+                merges,
             },
+            wal_recovery: self.wal_recovery.get_telemetry_data(),
         }
     }
 
@@ -735,8 +1318,44 @@ impl LocalShard {
         vector_size * info.points_count
     }
 
+    /// Check, that the operation fits into the share of this shard of the collection quotas.
+    ///
+    /// Called on the leader replica before the operation is written to WAL, so all replicas
+    /// apply or reject an operation together.
+    pub async fn check_quota(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        let quota = {
+            let config = self.collection_config.read().await;
+            match &config.quota_config {
+                Some(quota) => quota.shard_share(config.params.shard_number.get()),
+                None => return Ok(()),
+            }
+        };
+        // Points are not counted until the WAL is replayed, the update is rejected anyway
+        if !self.wal_recovery.is_ready() {
+            return Ok(());
+        }
+        self.quota_usage.check(self.segments(), operation, &quota)
+    }
+
     pub async fn local_shard_info(&self) -> CollectionInfo {
         let collection_config = self.collection_config.read().await.clone();
+        let quota_usage =
+            if collection_config.quota_config.is_some() && self.wal_recovery.is_ready() {
+                self.quota_usage
+                    .tracked_usage(self.segments())
+                    .map_err(|err| {
+                        log::warn!("Can't count quota usage of {}: {err}", self.path.display())
+                    })
+                    .ok()
+            } else {
+                self.quota_usage.usage()
+            };
+        // Segments, which have outgrown the thresholds, are optimized only once the optimizer
+        // worker picks them up, e.g. a segment is not indexed yet right after it was filled
+        let has_pending_optimizations = self.optimization_plan().await.is_some();
         let segments = self.segments().read();
         let mut vectors_count = 0;
         let mut indexed_vectors_count = 0;
@@ -775,7 +1394,7 @@ impl LocalShard {
             for (key, val) in segment_info.index_schema {
                 match schema.entry(key) {
                     Entry::Occupied(o) => {
-                        o.into_mut().points += val.points;
+                        o.into_mut().merge(val);
                     }
                     Entry::Vacant(v) => {
                         v.insert(val);
@@ -783,7 +1402,13 @@ impl LocalShard {
                 }
             }
         }
-        if !segments.failed_operation.is_empty() || segments.optimizer_errors.is_some() {
+        if self.wal_recovery.is_in_progress() || has_pending_optimizations {
+            status = CollectionStatus::Yellow;
+        }
+        if !segments.failed_operation.is_empty()
+            || segments.optimizer_errors.is_some()
+            || self.wal_recovery.error().is_some()
+        {
             status = CollectionStatus::Red;
         }
 
@@ -801,10 +1426,56 @@ impl LocalShard {
             segments_count,
             config: collection_config,
             payload_schema: schema,
+            quota_usage,
+            warmup: None,
+            index_builds: vec![],
+            merge_targets: None,
         }
     }
 }
 
+/// Segments, wrapped into a proxy during optimization, are described by the wrapped segment
+fn describe_segment(segment_id: SegmentId, segment: &LockedSegment) -> SegmentDescription {
+    let (segment, is_optimizing) = match segment {
+        LockedSegment::Original(_) => (segment.clone(), false),
+        LockedSegment::Proxy(proxy) => (proxy.read().wrapped_segment.clone(), true),
+    };
+    let (mut description, data_path) = {
+        let segment_entry = segment.get();
+        let read_segment = segment_entry.read();
+        let data_path = read_segment.data_path();
+        let description = SegmentDescription {
+            segment_id,
+            uuid: data_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            segment_type: read_segment.segment_type(),
+            is_appendable: read_segment.is_appendable(),
+            points_count: read_segment.available_point_count(),
+            deleted_count: read_segment.deleted_point_count(),
+            vector_indexes: read_segment
+                .config()
+                .vector_data
+                .into_iter()
+                .map(|(vector_name, vector_config)| (vector_name, vector_config.index))
+                .collect(),
+            payload_indexes: read_segment
+                .get_indexed_fields()
+                .into_keys()
+                .sorted()
+                .collect(),
+            disk_usage_bytes: 0,
+            is_optimizing,
+            graph_health: read_segment.graph_health(),
+        };
+        (description, data_path)
+    };
+    // Files are measured without holding the segment lock
+    description.disk_usage_bytes = fs_extra::dir::get_size(data_path).unwrap_or_default();
+    description
+}
+
 impl Drop for LocalShard {
     fn drop(&mut self) {
         thread::scope(|s| {
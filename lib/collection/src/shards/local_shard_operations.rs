@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -9,11 +10,13 @@ use tokio::runtime::Handle;
 use tokio::sync::oneshot;
 
 use crate::collection_manager::segments_searcher::SegmentsSearcher;
+use crate::collection_manager::segments_updater::points_by_filter;
+use crate::common::update_context::client_operation_id;
 use crate::operations::types::{
-    CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest, Record,
-    SearchRequestBatch, UpdateResult, UpdateStatus,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult,
+    OrderedQueryRequest, PointRequest, Record, SearchRequestBatch, UpdateResult, UpdateStatus,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::update_handler::{OperationData, UpdateSignal};
@@ -28,6 +31,19 @@ impl ShardOperation for LocalShard {
         operation: CollectionUpdateOperations,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
+        // WAL is being replayed in background, new operations can't be ordered with it yet
+        if !self.wal_recovery.is_ready() {
+            return Err(CollectionError::service_error(format!(
+                "Shard {} is not ready for updates: WAL recovery is {}",
+                self.path.display(),
+                if self.wal_recovery.is_in_progress() {
+                    "in progress"
+                } else {
+                    "failed"
+                },
+            )));
+        }
+
         let (callback_sender, callback_receiver) = if wait {
             let (tx, rx) = oneshot::channel();
             (Some(tx), Some(rx))
@@ -35,11 +51,67 @@ impl ShardOperation for LocalShard {
             (None, None)
         };
 
+        let client_operation_id = client_operation_id();
+
+        // Awaited index creation also waits for the index to be built in background
+        let created_index = match &operation {
+            CollectionUpdateOperations::FieldIndexOperation(FieldIndexOperations::CreateIndex(
+                create_index,
+            )) if wait => Some(create_index.field_name.clone()),
+            _ => None,
+        };
+
+        // Operations by a filter report the number of affected points. If the operation is not
+        // awaited, the points are counted right away, as they are by the moment of the request.
+        // The filter might match a lot of points, so they are counted outside of the async runtime
+        let filtered_operation = operation.points_filter().map(|(kind, _)| kind);
+        let count_before_apply = match operation.points_filter() {
+            Some((_, filter)) if !wait => {
+                let segments = self.segments.clone();
+                let filter = filter.clone();
+                let points = tokio::task::spawn_blocking(move || {
+                    points_by_filter(&segments.read(), &filter)
+                })
+                .await??;
+                Some(points.len())
+            }
+            _ => None,
+        };
+        let with_count = |result: UpdateResult, count: Option<usize>, before_apply: bool| {
+            if let (Some(kind), Some(count)) = (filtered_operation, count) {
+                result.with_filtered_count(kind, count, before_apply)
+            } else {
+                result
+            }
+        };
+
         let operation_id = {
             let update_sender = self.update_sender.load();
             let channel_permit = update_sender.reserve().await?;
             let mut wal_lock = self.wal.lock();
+            // Checked under the WAL lock, so that concurrent retries are not applied twice
+            let mut applied_operations = self.applied_operations.lock();
+            if let Some(client_operation_id) = client_operation_id {
+                if let Some(result) = applied_operations.get(&client_operation_id) {
+                    log::debug!(
+                        "Operation {client_operation_id} is already applied to shard {}, skipping",
+                        self.path.display()
+                    );
+                    return Ok(result);
+                }
+            }
             let operation_id = wal_lock.write(&operation)?;
+            if let Some(client_operation_id) = client_operation_id {
+                let result = with_count(
+                    UpdateResult::new(operation_id, UpdateStatus::Acknowledged),
+                    count_before_apply,
+                    true,
+                );
+                if let Err(err) = applied_operations.record(client_operation_id, result) {
+                    log::warn!("Can't record applied operation {client_operation_id}: {err}");
+                }
+            }
+            drop(applied_operations);
             channel_permit.send(UpdateSignal::Operation(OperationData {
                 op_num: operation_id,
                 operation,
@@ -50,19 +122,41 @@ impl ShardOperation for LocalShard {
         };
 
         if let Some(receiver) = callback_receiver {
-            let _res = receiver.await??;
-            Ok(UpdateResult {
-                operation_id,
-                status: UpdateStatus::Completed,
-            })
+            let affected_points = receiver.await??;
+            if let Some(field) = created_index {
+                self.build_field_index(&field).await?;
+                let is_indexed = self.segments().read().iter().any(|(_id, segment)| {
+                    segment
+                        .get()
+                        .read()
+                        .get_indexed_fields()
+                        .contains_key(&field)
+                });
+                if !is_indexed {
+                    return Err(CollectionError::Cancelled {
+                        description: format!("Build of the index of field `{field}` is cancelled"),
+                    });
+                }
+            }
+            Ok(with_count(
+                UpdateResult::new(operation_id, UpdateStatus::Completed),
+                Some(affected_points),
+                false,
+            ))
         } else {
-            Ok(UpdateResult {
-                operation_id,
-                status: UpdateStatus::Acknowledged,
-            })
+            Ok(with_count(
+                UpdateResult::new(operation_id, UpdateStatus::Acknowledged),
+                count_before_apply,
+                true,
+            ))
         }
     }
 
+    #[tracing::instrument(
+        name = "local_shard_scroll",
+        skip_all,
+        fields(segments = tracing::field::Empty, points = tracing::field::Empty)
+    )]
     async fn scroll_by(
         &self,
         offset: Option<ExtendedPointId>,
@@ -73,19 +167,24 @@ impl ShardOperation for LocalShard {
     ) -> CollectionResult<Vec<Record>> {
         // ToDo: Make faster points selection with a set
         let segments = self.segments();
-        let point_ids = segments
-            .read()
-            .iter()
-            .flat_map(|(_, segment)| {
-                segment
-                    .get()
-                    .read()
-                    .read_filtered(offset, Some(limit), filter)
-            })
-            .sorted()
-            .dedup()
-            .take(limit)
-            .collect_vec();
+        let point_ids = {
+            let segments_read = segments.read();
+            tracing::Span::current().record("segments", segments_read.len());
+            // Segments read the ids in order, so their pages are merged without sorting
+            segments_read
+                .iter()
+                .map(|(_, segment)| {
+                    segment
+                        .get()
+                        .read()
+                        .read_filtered(offset, Some(limit), filter)
+                })
+                .kmerge()
+                .dedup()
+                .take(limit)
+                .collect_vec()
+        };
+        tracing::Span::current().record("points", point_ids.len());
 
         let with_payload = WithPayload::from(with_payload_interface);
         let mut points =
@@ -105,16 +204,25 @@ impl ShardOperation for LocalShard {
         request: Arc<SearchRequestBatch>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        let collection_params = self.collection_config.read().await.params.clone();
+        let (collection_params, indexing_threshold_kb) = {
+            let collection_config = self.collection_config.read().await;
+            (
+                collection_config.params.clone(),
+                collection_config
+                    .optimizer_config
+                    .get_indexing_threshold_kb(),
+            )
+        };
         // check vector names existing
         for req in &request.searches {
-            collection_params.get_vector_params(req.vector.get_name())?;
+            collection_params.get_distance(req.vector.get_name())?;
         }
         let res = SegmentsSearcher::search(
             self.segments(),
             request.clone(),
             search_runtime_handle,
             true,
+            indexing_threshold_kb,
         )
         .await?;
         let top_results = res
@@ -122,10 +230,7 @@ impl ShardOperation for LocalShard {
             .zip(request.searches.iter())
             .map(|(vector_res, req)| {
                 let vector_name = req.vector.get_name();
-                let distance = collection_params
-                    .get_vector_params(vector_name)
-                    .unwrap()
-                    .distance;
+                let distance = collection_params.get_distance(vector_name).unwrap();
                 let processed_res = vector_res.into_iter().map(|mut scored_point| {
                     scored_point.score = distance.postprocess_score(scored_point.score);
                     scored_point
@@ -146,13 +251,13 @@ impl ShardOperation for LocalShard {
     }
 
     async fn count(&self, request: Arc<CountRequest>) -> CollectionResult<CountResult> {
-        let total_count = if request.exact {
+        if request.exact {
             let all_points = self.read_filtered(request.filter.as_ref())?;
-            all_points.len()
+            Ok(CountResult::exact(all_points.len()))
         } else {
-            self.estimate_cardinality(request.filter.as_ref())?.exp
-        };
-        Ok(CountResult { count: total_count })
+            let cardinality = self.estimate_cardinality(request.filter.as_ref())?;
+            Ok(CountResult::estimated(&cardinality))
+        }
     }
 
     async fn retrieve(
@@ -163,4 +268,62 @@ impl ShardOperation for LocalShard {
     ) -> CollectionResult<Vec<Record>> {
         SegmentsSearcher::retrieve(self.segments(), &request.ids, with_payload, with_vector).await
     }
+
+    async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+    ) -> CollectionResult<Vec<Record>> {
+        let limit = request.offset + request.limit;
+        let order_by = &request.order_by;
+        let direction = order_by.direction;
+        let segments = self.segments();
+        let ordered_points = {
+            let segments_read = segments.read();
+            let segments_points = segments_read
+                .iter()
+                .map(|(_, segment)| {
+                    segment.get().read().read_ordered_filtered(
+                        Some(limit),
+                        request.filter.as_ref(),
+                        order_by,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // Segments return the points in order, so they are merged without sorting.
+            // A point may be found in several segments, while it is moved by an optimizer
+            let mut seen = HashSet::new();
+            segments_points
+                .into_iter()
+                .kmerge_by(|(a, _), (b, _)| direction.compare(*a, *b).is_lt())
+                .filter(|(_, point_id)| seen.insert(*point_id))
+                .take(limit)
+                .collect_vec()
+        };
+
+        let point_ids = ordered_points
+            .iter()
+            .map(|(_, point_id)| *point_id)
+            .collect_vec();
+        let with_payload = request
+            .with_payload
+            .as_ref()
+            .map(WithPayload::from)
+            .unwrap_or_default();
+        let with_vector = request.with_vector.clone().unwrap_or_default();
+        let mut records: HashMap<_, _> =
+            SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, &with_vector)
+                .await?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+
+        Ok(ordered_points
+            .into_iter()
+            .filter_map(|(value, point_id)| {
+                let mut record = records.remove(&point_id)?;
+                record.order_value = Some(value);
+                Some(record)
+            })
+            .collect())
+    }
 }
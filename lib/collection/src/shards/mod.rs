@@ -1,4 +1,7 @@
+pub mod applied_operations;
+pub mod balancer;
 pub mod channel_service;
+pub mod checksum;
 pub mod collection_shard_distribution;
 mod conversions;
 pub mod dummy_shard;
@@ -7,6 +10,7 @@ pub mod local_shard;
 pub mod local_shard_operations;
 pub mod proxy_shard;
 pub mod remote_shard;
+pub mod replica_recovery;
 #[allow(dead_code)]
 pub mod replica_set;
 pub mod resolve;
@@ -17,6 +21,7 @@ pub mod shard_trait;
 pub mod shard_versioning;
 pub mod telemetry;
 pub mod transfer;
+pub mod wal_recovery;
 
 use std::path::{Path, PathBuf};
 
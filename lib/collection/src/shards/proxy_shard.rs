@@ -1,30 +1,36 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadFieldSchema, PayloadKeyType, PointIdType, ScoredPoint,
+    WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{oneshot, watch, RwLock};
 use tokio::time::timeout;
 
 use crate::operations::operation_effect::{
     EstimateOperationEffectArea, OperationEffectArea, PointsOperationEffect,
 };
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, OptimizationPlan,
+    OrderedQueryRequest, PointRequest, Record, SearchRequestBatch, SegmentDescription,
+    UpdateResult,
 };
 use crate::operations::CollectionUpdateOperations;
-use crate::shards::local_shard::LocalShard;
+use crate::read_cache::DataVersion;
+use crate::shards::checksum::ShardChecksum;
+use crate::shards::local_shard::{LocalShard, ShardFlushHandle};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 use crate::update_handler::UpdateSignal;
+use crate::wal::WalRetention;
 
 type ChangedPointsSet = Arc<RwLock<HashSet<PointIdType>>>;
 
@@ -77,6 +83,43 @@ impl ProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        self.wrapped_shard.trigger_optimizers().await
+    }
+
+    pub fn flush(&self) -> ShardFlushHandle {
+        self.wrapped_shard.flush()
+    }
+
+    pub fn subscribe_status_changes(&self) -> watch::Receiver<()> {
+        self.wrapped_shard.subscribe_status_changes()
+    }
+
+    pub async fn optimization_plan(&self) -> Option<OptimizationPlan> {
+        self.wrapped_shard.optimization_plan().await
+    }
+
+    pub async fn indexed_only_excluded_points(&self, vector_names: &[String]) -> usize {
+        self.wrapped_shard
+            .indexed_only_excluded_points(vector_names)
+            .await
+    }
+
+    pub fn indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        self.wrapped_shard.indexed_fields()
+    }
+
+    pub fn unindexed_filter_keys(&self, filters: &[&Filter]) -> Vec<PayloadKeyType> {
+        self.wrapped_shard.unindexed_filter_keys(filters)
+    }
+
+    pub async fn check_quota(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        self.wrapped_shard.check_quota(operation).await
+    }
+
     pub async fn reinit_changelog(&self) -> CollectionResult<()> {
         // Blocks updates in the wrapped shard.
         let mut changed_points_guard = self.changed_points.write().await;
@@ -116,8 +159,36 @@ impl ProxyShard {
         Ok(())
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
-        self.wrapped_shard.get_telemetry_data()
+    pub fn get_telemetry_data(&self, with_graph_health: bool) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data(with_graph_health)
+    }
+
+    pub async fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        self.wrapped_shard.checksum().await
+    }
+
+    pub fn warmup_tasks(&self) -> Vec<Vec<WarmupTask>> {
+        self.wrapped_shard.warmup_tasks()
+    }
+
+    pub fn data_version(&self) -> Option<DataVersion> {
+        self.wrapped_shard.data_version()
+    }
+
+    pub async fn segments_info(&self) -> CollectionResult<Vec<SegmentDescription>> {
+        self.wrapped_shard.segments_info().await
+    }
+
+    pub async fn trace_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Arc<Filter>,
+    ) -> CollectionResult<Option<FilterTrace>> {
+        self.wrapped_shard.trace_filter(point_id, filter).await
+    }
+
+    pub fn set_wal_retention(&self, retention: Option<WalRetention>) {
+        self.wrapped_shard.set_wal_retention(retention)
     }
 }
 
@@ -216,4 +287,12 @@ impl ShardOperation for ProxyShard {
             .retrieve(request, with_payload, with_vector)
             .await
     }
+
+    async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+    ) -> CollectionResult<Vec<Record>> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.query_ordered(request).await
+    }
 }
@@ -5,9 +5,9 @@ use std::sync::Arc;
 use api::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
 use api::grpc::qdrant::points_internal_client::PointsInternalClient;
 use api::grpc::qdrant::{
-    CollectionOperationResponse, CountPoints, CountPointsInternal, GetCollectionInfoRequest,
-    GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal, InitiateShardTransferRequest,
-    ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
+    ChecksumPointsInternal, CollectionOperationResponse, CountPoints, CountPointsInternal,
+    GetCollectionInfoRequest, GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal,
+    InitiateShardTransferRequest, ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
 };
 use async_trait::async_trait;
 use parking_lot::Mutex;
@@ -18,27 +18,30 @@ use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
-use tonic::transport::{Channel, Uri};
+use tonic::transport::Uri;
 use tonic::Status;
 
 use super::conversions::{
     internal_delete_vectors, internal_delete_vectors_by_filter, internal_update_vectors,
 };
+use crate::common::request_hw_counter::request_hw_counter;
+use crate::common::trace_context::{traced_channel, TracedChannel};
 use crate::operations::conversions::try_record_from_grpc;
 use crate::operations::payload_ops::PayloadOps;
 use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequest, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult,
+    OrderedQueryRequest, PointRequest, Record, SearchRequest, SearchRequestBatch, UpdateResult,
 };
 use crate::operations::vector_ops::VectorOperations;
 use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
 use crate::shards::channel_service::ChannelService;
+use crate::shards::checksum::ShardChecksum;
 use crate::shards::conversions::{
-    internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
-    internal_delete_index, internal_delete_payload, internal_delete_points,
-    internal_delete_points_by_filter, internal_set_payload, internal_sync_points,
-    internal_upsert_points, try_scored_point_from_grpc,
+    internal_cancel_index_build, internal_clear_payload, internal_clear_payload_by_filter,
+    internal_create_index, internal_delete_index, internal_delete_payload, internal_delete_points,
+    internal_delete_points_by_filter, internal_rebuild_index, internal_set_payload,
+    internal_sync_points, internal_upsert_points, try_scored_point_from_grpc,
 };
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_trait::ShardOperation;
@@ -93,13 +96,13 @@ impl RemoteShard {
 
     async fn with_points_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
-        f: impl Fn(PointsInternalClient<Channel>) -> O,
+        f: impl Fn(PointsInternalClient<TracedChannel>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
-                let client = PointsInternalClient::new(channel);
+                let client = PointsInternalClient::new(traced_channel(channel));
                 let client = client.max_decoding_message_size(usize::MAX);
                 f(client)
             })
@@ -109,13 +112,13 @@ impl RemoteShard {
 
     async fn with_collections_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
-        f: impl Fn(CollectionsInternalClient<Channel>) -> O,
+        f: impl Fn(CollectionsInternalClient<TracedChannel>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
-                let client = CollectionsInternalClient::new(channel);
+                let client = CollectionsInternalClient::new(traced_channel(channel));
                 let client = client.max_decoding_message_size(usize::MAX);
                 f(client)
             })
@@ -147,6 +150,28 @@ impl RemoteShard {
         Ok(res)
     }
 
+    /// Checksum of the points of the shard replica on the remote peer
+    pub async fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        let request = &ChecksumPointsInternal {
+            collection_name: self.collection_id.clone(),
+            shard_id: Some(self.id),
+        };
+        let checksum_response = self
+            .with_points_client(|mut client| async move {
+                client.checksum(tonic::Request::new(request.clone())).await
+            })
+            .await?
+            .into_inner();
+        checksum_response.result.map_or_else(
+            || {
+                Err(CollectionError::service_error(
+                    "Unexpected empty ShardChecksum".to_string(),
+                ))
+            },
+            |checksum| Ok(checksum.into()),
+        )
+    }
+
     pub async fn forward_update(
         &self,
         operation: CollectionUpdateOperations,
@@ -392,6 +417,38 @@ impl RemoteShard {
                     .await?
                     .into_inner()
                 }
+                FieldIndexOperations::RebuildIndex(rebuild_index) => {
+                    let request = &internal_rebuild_index(
+                        shard_id,
+                        collection_name,
+                        rebuild_index,
+                        wait,
+                        ordering,
+                    );
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .rebuild_field_index(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                FieldIndexOperations::CancelIndexBuild(field_name) => {
+                    let request = &internal_cancel_index_build(
+                        shard_id,
+                        collection_name,
+                        field_name,
+                        wait,
+                        ordering,
+                    );
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .cancel_field_index_build(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
             },
         };
         match point_operation_response.result {
@@ -420,6 +477,11 @@ impl ShardOperation for RemoteShard {
             .await
     }
 
+    #[tracing::instrument(
+        name = "remote_shard_scroll",
+        skip_all,
+        fields(shard_id = self.id, peer_id = self.peer_id)
+    )]
     async fn scroll_by(
         &self,
         offset: Option<ExtendedPointId>,
@@ -436,10 +498,13 @@ impl ShardOperation for RemoteShard {
             with_payload: Some(with_payload_interface.clone().into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            with_timestamps: Some(true),
+            max_payload_return_bytes: None,
         };
         let request = &ScrollPointsInternal {
             scroll_points: Some(scroll_points),
             shard_id: Some(self.id),
+            order_by: None,
         };
 
         let scroll_response = self
@@ -477,6 +542,11 @@ impl ShardOperation for RemoteShard {
         result.map_err(|e| e.into())
     }
 
+    #[tracing::instrument(
+        name = "remote_shard_search",
+        skip_all,
+        fields(shard_id = self.id, peer_id = self.peer_id)
+    )]
     async fn search(
         &self,
         batch_request: Arc<SearchRequestBatch>,
@@ -505,6 +575,11 @@ impl ShardOperation for RemoteShard {
             .await?
             .into_inner();
 
+        // Work of the remote peer is accounted to the request as well
+        if let Some(usage) = search_batch_response.usage {
+            request_hw_counter().add(&usage.into());
+        }
+
         let result: Result<Vec<Vec<ScoredPoint>>, Status> = search_batch_response
             .result
             .into_iter()
@@ -556,6 +631,11 @@ impl ShardOperation for RemoteShard {
         )
     }
 
+    #[tracing::instrument(
+        name = "remote_shard_retrieve",
+        skip_all,
+        fields(shard_id = self.id, peer_id = self.peer_id)
+    )]
     async fn retrieve(
         &self,
         request: Arc<PointRequest>,
@@ -568,6 +648,8 @@ impl ShardOperation for RemoteShard {
             with_payload: request.with_payload.clone().map(|wp| wp.into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            with_timestamps: Some(true),
+            max_payload_return_bytes: None,
         };
         let request = &GetPointsInternal {
             get_points: Some(get_points),
@@ -589,4 +671,45 @@ impl ShardOperation for RemoteShard {
 
         result.map_err(|e| e.into())
     }
+
+    async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+    ) -> CollectionResult<Vec<Record>> {
+        let with_payload_interface = request
+            .with_payload
+            .clone()
+            .unwrap_or(WithPayloadInterface::Bool(false));
+        let scroll_points = ScrollPoints {
+            collection_name: self.collection_id.clone(),
+            filter: request.filter.clone().map(|f| f.into()),
+            offset: None,
+            limit: Some((request.offset + request.limit) as u32),
+            with_payload: Some(with_payload_interface.clone().into()),
+            with_vectors: Some(request.with_vector.clone().unwrap_or_default().into()),
+            read_consistency: None,
+            with_timestamps: Some(true),
+            max_payload_return_bytes: None,
+        };
+        let request = &ScrollPointsInternal {
+            scroll_points: Some(scroll_points),
+            shard_id: Some(self.id),
+            order_by: Some(request.order_by.clone().into()),
+        };
+
+        let scroll_response = self
+            .with_points_client(|mut client| async move {
+                client.scroll(tonic::Request::new(request.clone())).await
+            })
+            .await?
+            .into_inner();
+
+        let result: Result<Vec<Record>, Status> = scroll_response
+            .result
+            .into_iter()
+            .map(|point| try_record_from_grpc(point, with_payload_interface.is_required()))
+            .collect();
+
+        result.map_err(|e| e.into())
+    }
 }
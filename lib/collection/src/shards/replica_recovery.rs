@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::shards::shard::PeerId;
+
+const DEFAULT_MAX_CONCURRENT_RECOVERIES: usize = 2;
+const DEFAULT_BACKOFF_BASE_SEC: u64 = 5;
+const DEFAULT_BACKOFF_MAX_SEC: u64 = 300;
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+
+/// If the transfer of a started recovery doesn't appear within this time,
+/// the request is considered lost and the recovery is retried
+pub const RECOVERY_START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Automatic recovery of the dead replicas, located on this peer
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ReplicaRecoveryConfig {
+    /// If false - dead replicas are only recovered by the operator, with a manual shard transfer
+    #[serde(default = "default_auto_recovery")]
+    pub auto_recovery: bool,
+    /// Max number of replicas, recovered on this peer at the same time
+    #[serde(default = "default_max_concurrent_recoveries")]
+    pub max_concurrent_recoveries: usize,
+    /// Delay before the retry of the first failed recovery, doubled with each failure
+    #[serde(default = "default_backoff_base_sec")]
+    pub backoff_base_sec: u64,
+    /// Max delay between the retries of a recovery
+    #[serde(default = "default_backoff_max_sec")]
+    pub backoff_max_sec: u64,
+    /// Recovery is not retried automatically after this number of failed attempts
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for ReplicaRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            auto_recovery: default_auto_recovery(),
+            max_concurrent_recoveries: DEFAULT_MAX_CONCURRENT_RECOVERIES,
+            backoff_base_sec: DEFAULT_BACKOFF_BASE_SEC,
+            backoff_max_sec: DEFAULT_BACKOFF_MAX_SEC,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+}
+
+impl ReplicaRecoveryConfig {
+    /// Delay before the next attempt, after the given number of failed attempts
+    pub fn backoff(&self, failed_attempts: u32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).min(31);
+        let backoff_sec = self
+            .backoff_base_sec
+            .saturating_mul(1 << exponent)
+            .min(self.backoff_max_sec);
+        Duration::from_secs(backoff_sec)
+    }
+}
+
+const fn default_auto_recovery() -> bool {
+    true
+}
+
+const fn default_max_concurrent_recoveries() -> usize {
+    DEFAULT_MAX_CONCURRENT_RECOVERIES
+}
+
+const fn default_backoff_base_sec() -> u64 {
+    DEFAULT_BACKOFF_BASE_SEC
+}
+
+const fn default_backoff_max_sec() -> u64 {
+    DEFAULT_BACKOFF_MAX_SEC
+}
+
+const fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+/// Recovery of the dead local replica.
+///
+/// `Dead` replica becomes `pending_recovery`, then `recovering` once a transfer is requested.
+/// Successful transfer makes the replica `Active` and the recovery state is cleared.
+/// Failed transfer returns the recovery to `pending_recovery` with a backoff,
+/// until the attempts are exhausted and the recovery is `failed`.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum RecoveryState {
+    PendingRecovery {
+        /// Number of the failed attempts
+        attempts: u32,
+        /// Recovery is not started before this time
+        retry_at: DateTime<Utc>,
+        /// Error of the last failed attempt
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_error: Option<String>,
+    },
+    Recovering {
+        /// Number of the attempts, including the current one
+        attempts: u32,
+        /// Peer, the replica is transferred from
+        from_peer_id: PeerId,
+        started_at: DateTime<Utc>,
+    },
+    Failed {
+        reason: String,
+    },
+}
+
+impl RecoveryState {
+    /// Recovery of the replica, which just became dead
+    pub fn pending(now: DateTime<Utc>) -> Self {
+        RecoveryState::PendingRecovery {
+            attempts: 0,
+            retry_at: now,
+            last_error: None,
+        }
+    }
+
+    /// Recovery may be started
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            RecoveryState::PendingRecovery { retry_at, .. } => *retry_at <= now,
+            RecoveryState::Recovering { .. } | RecoveryState::Failed { .. } => false,
+        }
+    }
+
+    pub fn is_recovering(&self) -> bool {
+        matches!(self, RecoveryState::Recovering { .. })
+    }
+
+    /// Transfer from the peer is requested
+    pub fn start(&self, from_peer_id: PeerId, now: DateTime<Utc>) -> Self {
+        let attempts = match self {
+            RecoveryState::PendingRecovery { attempts, .. } => *attempts,
+            RecoveryState::Recovering { attempts, .. } => *attempts,
+            RecoveryState::Failed { .. } => 0,
+        };
+        RecoveryState::Recovering {
+            attempts: attempts + 1,
+            from_peer_id,
+            started_at: now,
+        }
+    }
+
+    /// Attempt of the recovery failed, schedule the next one after the backoff
+    pub fn fail(&self, error: String, now: DateTime<Utc>, config: &ReplicaRecoveryConfig) -> Self {
+        let attempts = match self {
+            RecoveryState::PendingRecovery { attempts, .. } => *attempts,
+            RecoveryState::Recovering { attempts, .. } => *attempts,
+            RecoveryState::Failed { .. } => return self.clone(),
+        };
+        if attempts >= config.max_attempts {
+            return RecoveryState::Failed {
+                reason: format!("{attempts} recovery attempts failed, last error: {error}"),
+            };
+        }
+        let backoff = chrono::Duration::from_std(config.backoff(attempts))
+            .unwrap_or_else(|_| chrono::Duration::zero());
+        RecoveryState::PendingRecovery {
+            attempts,
+            retry_at: now + backoff,
+            last_error: Some(error),
+        }
+    }
+
+    /// Transfer of the started recovery did not appear in time
+    pub fn is_lost(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            RecoveryState::Recovering { started_at, .. } => now
+                .signed_duration_since(*started_at)
+                .to_std()
+                .map_or(false, |elapsed| elapsed > RECOVERY_START_TIMEOUT),
+            RecoveryState::PendingRecovery { .. } | RecoveryState::Failed { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let config = ReplicaRecoveryConfig {
+            backoff_base_sec: 5,
+            backoff_max_sec: 60,
+            ..Default::default()
+        };
+        assert_eq!(config.backoff(1), Duration::from_secs(5));
+        assert_eq!(config.backoff(2), Duration::from_secs(10));
+        assert_eq!(config.backoff(3), Duration::from_secs(20));
+        assert_eq!(config.backoff(5), Duration::from_secs(60));
+        assert_eq!(config.backoff(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_failed_recovery_returns_to_pending_with_backoff() {
+        let config = ReplicaRecoveryConfig {
+            max_attempts: 2,
+            ..Default::default()
+        };
+        let now = Utc::now();
+
+        let pending = RecoveryState::pending(now);
+        assert!(pending.is_due(now));
+
+        let recovering = pending.start(2, now);
+        assert!(recovering.is_recovering());
+        assert!(!recovering.is_due(now));
+
+        // Replica fails during recovery
+        let retry = recovering.fail("transfer aborted".to_string(), now, &config);
+        let RecoveryState::PendingRecovery {
+            attempts,
+            retry_at,
+            last_error,
+        } = &retry
+        else {
+            panic!("recovery must be pending, got {retry:?}");
+        };
+        assert_eq!(*attempts, 1);
+        assert_eq!(
+            *retry_at,
+            now + chrono::Duration::seconds(DEFAULT_BACKOFF_BASE_SEC as i64)
+        );
+        assert_eq!(last_error.as_deref(), Some("transfer aborted"));
+        assert!(!retry.is_due(now));
+        assert!(retry.is_due(*retry_at));
+
+        // Attempts are exhausted
+        let failed =
+            retry
+                .start(3, *retry_at)
+                .fail("transfer aborted".to_string(), *retry_at, &config);
+        assert!(matches!(failed, RecoveryState::Failed { .. }));
+        assert!(!failed.is_due(*retry_at + chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn test_lost_recovery() {
+        let now = Utc::now();
+        let recovering = RecoveryState::pending(now).start(2, now);
+        assert!(!recovering.is_lost(now));
+        assert!(recovering.is_lost(now + chrono::Duration::minutes(2)));
+    }
+}
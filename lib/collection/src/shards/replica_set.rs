@@ -5,45 +5,59 @@ use std::fmt::Write as _;
 use std::future::Future;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use api::grpc::models::ShardTrace;
+use chrono::Utc;
 use futures::future::{join, join_all};
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
 use itertools::Itertools;
 use rand::seq::SliceRandom;
 use schemars::JsonSchema;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadFieldSchema, PayloadKeyType, PointIdType, ScoredPoint,
+    SeqNumberType, WithPayload, WithPayloadInterface, WithVector,
 };
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Handle;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{watch, Mutex, RwLock};
 
-use super::local_shard::LocalShard;
+use super::local_shard::{LocalShard, ShardFlushHandle};
 use super::remote_shard::RemoteShard;
 use super::resolve::{Resolve, ResolveCondition};
 use super::{create_shard_dir, CollectionId};
+use crate::common::shard_trace::{record_shard_trace, shard_trace_enabled};
+use crate::common::update_context::update_context;
 use crate::config::CollectionConfig;
 use crate::operations::consistency_params::{ReadConsistency, ReadConsistencyType};
 use crate::operations::point_ops::WriteOrdering;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult,
+    FieldIndexBuildProgress, OrderedQueryRequest, PointRequest, Record, SearchRequest,
+    SearchRequestBatch, ShardIndexRebuildProgress, ShardOptimizationPlan, ShardSegmentsInfo,
+    UpdateResult, UpdateStatus,
 };
 use crate::operations::CollectionUpdateOperations;
+use crate::read_cache::DataVersion;
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
+use crate::shards::checksum::{ReplicaChecksum, ShardChecksum, ShardChecksumReport};
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
+use crate::shards::replica_recovery::RecoveryState;
 use crate::shards::shard::Shard::{Dummy, ForwardProxy, Local};
 use crate::shards::shard::{PeerId, Shard, ShardId};
 use crate::shards::shard_config::ShardConfig;
 use crate::shards::shard_trait::ShardOperation;
-use crate::shards::telemetry::ReplicaSetTelemetry;
+use crate::shards::telemetry::{HedgedReadsTelemetry, ReplicaSetTelemetry};
+use crate::shards::transfer::wal_delta::{AcknowledgedOperations, WAL_DELTA_MAX_RECORDS};
+use crate::wal::WalRetention;
 
 pub type ActivatePeer = Arc<dyn Fn(PeerId, ShardId) + Send + Sync>;
 pub type ChangePeerState = Arc<dyn Fn(PeerId, ShardId) + Send + Sync>;
@@ -120,6 +134,12 @@ pub struct ReplicaSetState {
     pub is_local: bool,
     pub this_peer_id: PeerId,
     peers: HashMap<PeerId, ReplicaState>,
+    /// Last operations of the local shard, acknowledged by the lagging remote replicas
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    acknowledged_operations: HashMap<PeerId, SeqNumberType>,
+    /// Automatic recovery of the local replica, if it is dead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recovery: Option<RecoveryState>,
 }
 
 impl ReplicaSetState {
@@ -171,6 +191,12 @@ pub struct ShardReplicaSet {
     update_runtime: Handle,
     /// Lock to serialized write operations on the replicaset when a write ordering is used.
     write_ordering_lock: Mutex<()>,
+    /// Number of reads, sent to another replica after the hedge delay
+    hedged_reads_fired: AtomicU64,
+    /// Number of hedged reads, answered first by the other replica
+    hedged_reads_won: AtomicU64,
+    /// Operations acknowledged by the remote replicas, used to recover them by WAL delta
+    acknowledged_operations: parking_lot::Mutex<AcknowledgedOperations>,
 }
 
 impl ShardReplicaSet {
@@ -320,15 +346,21 @@ impl ShardReplicaSet {
             shared_storage_config,
             update_runtime,
             write_ordering_lock: Mutex::new(()),
+            hedged_reads_fired: AtomicU64::new(0),
+            hedged_reads_won: AtomicU64::new(0),
+            acknowledged_operations: Default::default(),
         })
     }
 
     pub async fn remove_remote(&self, peer_id: PeerId) -> CollectionResult<()> {
+        let acknowledged = self.acknowledge_state_change(peer_id, None);
         self.replica_state.write(|rs| {
             rs.remove_peer_state(&peer_id);
+            rs.acknowledged_operations = acknowledged;
         })?;
 
         self.update_locally_disabled(peer_id);
+        self.update_wal_retention();
 
         let mut remotes = self.remotes.write().await;
         remotes.retain(|remote| remote.peer_id != peer_id);
@@ -336,11 +368,14 @@ impl ShardReplicaSet {
     }
 
     pub async fn add_remote(&self, peer_id: PeerId, state: ReplicaState) -> CollectionResult<()> {
+        let acknowledged = self.acknowledge_state_change(peer_id, Some(state));
         self.replica_state.write(|rs| {
             rs.set_peer_state(peer_id, state);
+            rs.acknowledged_operations = acknowledged;
         })?;
 
         self.update_locally_disabled(peer_id);
+        self.update_wal_retention();
 
         let mut remotes = self.remotes.write().await;
 
@@ -520,7 +555,11 @@ impl ShardReplicaSet {
             None
         };
 
-        Self {
+        let acknowledged_operations = AcknowledgedOperations::from_lagging(
+            replica_state.read().acknowledged_operations.clone(),
+        );
+
+        let replica_set = Self {
             shard_id,
             local: RwLock::new(local),
             remotes: RwLock::new(remote_shards),
@@ -536,7 +575,12 @@ impl ShardReplicaSet {
             shared_storage_config,
             update_runtime,
             write_ordering_lock: Mutex::new(()),
-        }
+            hedged_reads_fired: AtomicU64::new(0),
+            hedged_reads_won: AtomicU64::new(0),
+            acknowledged_operations: parking_lot::Mutex::new(acknowledged_operations),
+        };
+        replica_set.update_wal_retention();
+        replica_set
     }
 
     pub fn notify_peer_failure(&self, peer_id: PeerId) {
@@ -561,16 +605,84 @@ impl ShardReplicaSet {
     }
 
     pub fn set_replica_state(&self, peer_id: &PeerId, state: ReplicaState) -> CollectionResult<()> {
+        let acknowledged = self.acknowledge_state_change(*peer_id, Some(state));
+        let recovery_config = &self.shared_storage_config.replica_recovery;
         self.replica_state.write(|rs| {
             if rs.this_peer_id == *peer_id {
                 rs.is_local = true;
+                rs.recovery = match state {
+                    // Transfer of the recovery is aborted, or the recovered replica failed
+                    ReplicaState::Dead => rs.recovery.take().map(|recovery| {
+                        if recovery.is_recovering() {
+                            recovery.fail(
+                                "replica failed during the recovery".to_string(),
+                                Utc::now(),
+                                recovery_config,
+                            )
+                        } else {
+                            recovery
+                        }
+                    }),
+                    // Replica is receiving the data of the recovery
+                    ReplicaState::Partial => rs.recovery.take(),
+                    ReplicaState::Active | ReplicaState::Initializing | ReplicaState::Listener => {
+                        None
+                    }
+                };
             }
             rs.set_peer_state(*peer_id, state);
+            rs.acknowledged_operations = acknowledged;
         })?;
         self.update_locally_disabled(*peer_id);
+        self.update_wal_retention();
         Ok(())
     }
 
+    /// Automatic recovery of the local replica, if it is dead
+    pub fn recovery_state(&self) -> Option<RecoveryState> {
+        self.replica_state.read().recovery.clone()
+    }
+
+    pub fn set_recovery_state(&self, recovery: Option<RecoveryState>) -> CollectionResult<()> {
+        if self.replica_state.read().recovery == recovery {
+            return Ok(());
+        }
+        self.replica_state.write(|rs| rs.recovery = recovery)?;
+        Ok(())
+    }
+
+    /// Track the replicas, which stopped receiving updates.
+    /// Returns the acknowledged operations of the lagging replicas to persist.
+    fn acknowledge_state_change(
+        &self,
+        peer_id: PeerId,
+        state: Option<ReplicaState>,
+    ) -> HashMap<PeerId, SeqNumberType> {
+        let mut acknowledged_operations = self.acknowledged_operations.lock();
+        match state {
+            Some(ReplicaState::Dead) => acknowledged_operations.mark_lagging(peer_id),
+            // Removed replica doesn't need to be recovered
+            Some(ReplicaState::Active) | None => acknowledged_operations.mark_recovered(peer_id),
+            Some(_) => {}
+        }
+        acknowledged_operations.all_lagging_acknowledged()
+    }
+
+    /// Keep the WAL records of the local shard, which are missed by the lagging replicas
+    fn update_wal_retention(&self) {
+        let retain_from = self.acknowledged_operations.lock().retain_from();
+        let retention = retain_from.map(|retain_from| WalRetention {
+            retain_from,
+            max_records: WAL_DELTA_MAX_RECORDS,
+        });
+        // Retention is updated on the next state change, if the local shard is busy
+        if let Ok(local) = self.local.try_read() {
+            if let Some(local) = local.as_ref() {
+                local.set_wal_retention(retention);
+            }
+        }
+    }
+
     pub async fn apply_state(
         &self,
         replicas: HashMap<PeerId, ReplicaState>,
@@ -802,6 +914,27 @@ impl ShardReplicaSet {
         captured_error.expect("at this point `captured_error` must be defined by construction")
     }
 
+    /// Record the replica, which answered a read of the traced request
+    fn trace_read(&self, peer_id: PeerId, latency: Duration) {
+        record_shard_trace(ShardTrace {
+            shard_id: self.shard_id,
+            peer_id,
+            local: peer_id == self.this_peer_id(),
+            replica_state: self.peer_state(&peer_id).map(|state| format!("{state:?}")),
+            latency: latency.as_secs_f64(),
+        });
+    }
+
+    /// Delay, after which a read is also sent to another replica. Not set if hedging is disabled
+    async fn read_hedge_delay(&self) -> Option<Duration> {
+        self.collection_config
+            .read()
+            .await
+            .params
+            .read_hedge_delay_ms
+            .map(Duration::from_millis)
+    }
+
     pub async fn execute_and_resolve_read_operation<'a, F, Fut, Res>(
         &self,
         read_operation: F,
@@ -858,6 +991,15 @@ impl ShardReplicaSet {
         let mut active_remotes: Vec<_> = active_remotes_iter.collect();
         active_remotes.shuffle(&mut rand::thread_rng());
 
+        // Peers of the operations in the order of their numbers, if the request is traced
+        let traced_peers: Option<Vec<PeerId>> = shard_trace_enabled().then(|| {
+            active_local
+                .iter()
+                .map(|_| self.this_peer_id())
+                .chain(active_remotes.iter().map(|remote| remote.peer_id))
+                .collect()
+        });
+
         let local_operations = active_local
             .into_iter()
             .map(|local| read_operation(local.get()).left_future());
@@ -866,9 +1008,26 @@ impl ShardReplicaSet {
             .into_iter()
             .map(|remote| read_operation(remote).right_future());
 
-        let mut operations = local_operations.chain(remote_operations);
+        // Operations are numbered to know, which replica responded
+        let mut operations = local_operations.chain(remote_operations).enumerate();
+        let start = |(index, operation): (usize, _)| {
+            let started = traced_peers.as_ref().map(|_| Instant::now());
+            FutureExt::map(operation, move |result| {
+                (index, started.map(|started| started.elapsed()), result)
+            })
+        };
+
+        // Hedging only makes sense, if a single response is sufficient
+        let hedge_delay = if factor == 1 {
+            self.read_hedge_delay().await
+        } else {
+            None
+        };
 
-        let required_reads = if active_local_count > 0 {
+        let required_reads = if hedge_delay.is_some() {
+            // Other replicas are only requested, if the first one is slow or fails
+            factor
+        } else if active_local_count > 0 {
             // If there is a local shard, we can ignore fan-out `read_remote_replicas` param,
             // as we already know that the local peer is working.
             factor
@@ -876,15 +1035,54 @@ impl ShardReplicaSet {
             max(factor, usize::try_from(self.read_remote_replicas).unwrap())
         };
 
-        let mut pending_operations: FuturesUnordered<_> =
-            operations.by_ref().take(required_reads).collect();
+        let mut pending_operations: FuturesUnordered<_> = operations
+            .by_ref()
+            .take(required_reads)
+            .map(start)
+            .collect();
 
         let mut responses = Vec::new();
         let mut errors = Vec::new();
 
-        while let Some(result) = pending_operations.next().await {
+        let mut hedge_fired = false;
+        let mut hedged_operation = None;
+
+        loop {
+            let next = match hedge_delay {
+                Some(delay) if !hedge_fired => {
+                    match tokio::time::timeout(delay, pending_operations.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            // No response within the delay, also send the read to another replica.
+                            // Whichever responds first is used, the other read is dropped.
+                            hedge_fired = true;
+                            if let Some((index, operation)) = operations.next() {
+                                log::debug!("Hedging read of shard {}", self.shard_id);
+                                self.hedged_reads_fired.fetch_add(1, Ordering::Relaxed);
+                                hedged_operation = Some(index);
+                                pending_operations.push(start((index, operation)));
+                            }
+                            continue;
+                        }
+                    }
+                }
+                _ => pending_operations.next().await,
+            };
+
+            let Some((index, latency, result)) = next else {
+                break;
+            };
+
             match result {
-                Ok(resp) => responses.push(resp),
+                Ok(resp) => {
+                    if hedged_operation == Some(index) {
+                        self.hedged_reads_won.fetch_add(1, Ordering::Relaxed);
+                    }
+                    if let (Some(peers), Some(latency)) = (&traced_peers, latency) {
+                        self.trace_read(peers[index], latency);
+                    }
+                    responses.push(resp)
+                }
 
                 Err(err) => {
                     let is_transient = matches!(
@@ -908,7 +1106,7 @@ impl ShardReplicaSet {
             let maybe_responses = responses.len() + pending_operations.len();
 
             let schedule = factor.saturating_sub(maybe_responses);
-            pending_operations.extend(operations.by_ref().take(schedule));
+            pending_operations.extend(operations.by_ref().take(schedule).map(start));
 
             let maybe_responses = responses.len() + pending_operations.len();
 
@@ -952,11 +1150,86 @@ impl ShardReplicaSet {
         }
     }
 
-    pub(crate) async fn get_telemetry_data(&self) -> ReplicaSetTelemetry {
+    pub(crate) async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        let read_local = self.local.read().await;
+        if let Some(shard) = &*read_local {
+            shard.trigger_optimizers().await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `None` if there is no local replica of the shard
+    pub(crate) async fn subscribe_status_changes(&self) -> Option<watch::Receiver<()>> {
+        let read_local = self.local.read().await;
+        read_local
+            .as_ref()
+            .and_then(Shard::subscribe_status_changes)
+    }
+
+    /// Returns `None` if there is no local replica of the shard
+    pub(crate) async fn optimization_plan(
+        &self,
+    ) -> CollectionResult<Option<ShardOptimizationPlan>> {
+        let read_local = self.local.read().await;
+        if let Some(shard) = &*read_local {
+            Ok(Some(ShardOptimizationPlan {
+                shard_id: self.shard_id,
+                next: shard.optimization_plan().await?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns `None` if there is no local replica of the shard
+    pub(crate) async fn indexed_only_excluded_points(
+        &self,
+        vector_names: &[String],
+    ) -> Option<usize> {
+        let read_local = self.local.read().await;
+        match &*read_local {
+            Some(shard) => Some(shard.indexed_only_excluded_points(vector_names).await),
+            None => None,
+        }
+    }
+
+    /// Version of the data of the local replica.
+    ///
+    /// Returns `None` if there is no active local replica of the shard. Reads might be served by
+    /// the remote replicas then, which updates are not reflected in the version.
+    pub(crate) async fn local_data_version(&self) -> Option<DataVersion> {
+        if !self.peer_is_active(&self.this_peer_id()) {
+            return None;
+        }
+        let read_local = self.local.read().await;
+        read_local.as_ref().and_then(|shard| shard.data_version())
+    }
+
+    /// Returns `None` if there is no local replica of the shard
+    pub(crate) async fn indexed_fields(
+        &self,
+    ) -> Option<HashMap<PayloadKeyType, PayloadFieldSchema>> {
+        let read_local = self.local.read().await;
+        read_local.as_ref().map(|shard| shard.indexed_fields())
+    }
+
+    /// Returns `None` if there is no local replica of the shard
+    pub(crate) async fn unindexed_filter_keys(
+        &self,
+        filters: &[&Filter],
+    ) -> Option<Vec<PayloadKeyType>> {
+        let read_local = self.local.read().await;
+        read_local
+            .as_ref()
+            .map(|shard| shard.unindexed_filter_keys(filters))
+    }
+
+    pub(crate) async fn get_telemetry_data(&self, with_graph_health: bool) -> ReplicaSetTelemetry {
         let local_shard = self.local.read().await;
         let local = local_shard
             .as_ref()
-            .map(|local_shard| local_shard.get_telemetry_data());
+            .map(|local_shard| local_shard.get_telemetry_data(with_graph_health));
         ReplicaSetTelemetry {
             id: self.shard_id,
             local,
@@ -968,6 +1241,10 @@ impl ShardReplicaSet {
                 .map(|remote| remote.get_telemetry_data())
                 .collect(),
             replicate_states: self.replica_state.read().peers(),
+            hedged_reads: HedgedReadsTelemetry {
+                fired: self.hedged_reads_fired.load(Ordering::Relaxed),
+                won: self.hedged_reads_won.load(Ordering::Relaxed),
+            },
         }
     }
 
@@ -1192,6 +1469,64 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Points, changed since the last operation acknowledged by the replica, the local shard
+    /// is proxified to. `None` if the operations, missed by the replica, are unknown.
+    pub async fn wal_delta_point_ids(&self) -> CollectionResult<Option<Vec<PointIdType>>> {
+        let read_local = self.local.read().await;
+        if let Some(ForwardProxy(proxy)) = &*read_local {
+            let acknowledged = self
+                .acknowledged_operations
+                .lock()
+                .lagging_acknowledged(proxy.remote_shard.peer_id);
+            Ok(acknowledged.and_then(|acknowledged| proxy.wal_delta_point_ids(acknowledged + 1)))
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot transfer WAL delta from shard {} because it is not proxified",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Custom operation for transferring the given points during WAL delta transfer
+    pub async fn transfer_points(
+        &self,
+        point_ids: Vec<PointIdType>,
+        wait: bool,
+    ) -> CollectionResult<()> {
+        let read_local = self.local.read().await;
+        if let Some(ForwardProxy(proxy)) = &*read_local {
+            proxy.transfer_points(point_ids, wait).await
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot transfer points from shard {} because it is not proxified",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Record the results of the update on the remote replicas, which were active
+    fn record_acknowledged(
+        &self,
+        operation_id: SeqNumberType,
+        remote_results: &[(PeerId, bool)],
+    ) -> CollectionResult<()> {
+        let changed = {
+            let mut acknowledged_operations = self.acknowledged_operations.lock();
+            let lagging_before = acknowledged_operations.all_lagging_acknowledged();
+            for &(peer_id, applied) in remote_results {
+                acknowledged_operations.record(peer_id, operation_id, applied);
+            }
+            let lagging = acknowledged_operations.all_lagging_acknowledged();
+            (lagging != lagging_before).then_some(lagging)
+        };
+        if let Some(acknowledged) = changed {
+            self.replica_state
+                .write(|rs| rs.acknowledged_operations = acknowledged)?;
+            self.update_wal_retention();
+        }
+        Ok(())
+    }
+
     fn handle_failed_replicas(
         &self,
         failures: &Vec<(PeerId, CollectionError)>,
@@ -1341,6 +1676,34 @@ impl ShardReplicaSet {
         operation: CollectionUpdateOperations,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
+        // Number of replicas, which should apply the operation before it is completed
+        let required_replicas = match update_context().wait_for {
+            Some(wait_for) => {
+                let replicas = self.peers().len();
+                let required_replicas = wait_for.required_replicas(replicas);
+                if required_replicas > replicas {
+                    return Err(CollectionError::bad_request(format!(
+                        "Can't wait for {required_replicas} replicas, shard {} has only {replicas}",
+                        self.shard_id
+                    )));
+                }
+                Some(required_replicas)
+            }
+            None => None,
+        };
+        // Replicas report the operation as completed only if they wait for it to be applied
+        let wait = wait || required_replicas.is_some();
+
+        // Quotas are checked by the leader only, before the operation reaches any WAL, so the
+        // replicas never diverge on which operations are rejected. A peer without a local replica
+        // has no usage to check against
+        if let Some(local) = self.local.read().await.deref() {
+            local.check_quota(&operation).await?;
+        }
+
+        // Results of the remote replicas, which were active, and the local operation id
+        let mut acknowledged = None;
+
         let all_res: Vec<Result<_, _>> = {
             let local = self.local.read().await;
             let remotes = self.remotes.read().await;
@@ -1363,6 +1726,12 @@ impl ShardReplicaSet {
                 )));
             }
 
+            // Only replicas, which received all of the previous updates, acknowledge operations
+            let remote_is_active: Vec<_> = active_remote_shards
+                .iter()
+                .map(|remote| (remote.peer_id, self.peer_is_active(&remote.peer_id)))
+                .collect();
+
             let mut remote_futures = Vec::new();
             for remote in active_remote_shards {
                 let op = operation.clone();
@@ -1401,6 +1770,16 @@ impl ShardReplicaSet {
                         Vec<Result<UpdateResult, (PeerId, CollectionError)>>,
                         _,
                     ) = join(remote_updates, local_update).await;
+                    // Forward proxy reports the operation id of the remote shard
+                    if let (Local(_), Ok(local_result)) = (local, &local_res) {
+                        let remote_results: Vec<_> = remote_is_active
+                            .iter()
+                            .zip(&remote_res)
+                            .filter(|((_, is_active), _)| *is_active)
+                            .map(|((peer_id, _), result)| (*peer_id, result.is_ok()))
+                            .collect();
+                        acknowledged = Some((local_result.operation_id, remote_results));
+                    }
                     // return both remote and local results
                     remote_res.push(local_res);
                     remote_res
@@ -1409,6 +1788,10 @@ impl ShardReplicaSet {
             }
         };
 
+        if let Some((operation_id, remote_results)) = acknowledged {
+            self.record_acknowledged(operation_id, &remote_results)?;
+        }
+
         let total_results = all_res.len();
 
         let (successes, failures): (Vec<_>, Vec<_>) = all_res.into_iter().partition_result();
@@ -1469,6 +1852,22 @@ impl ShardReplicaSet {
                 return Err(err);
             }
         }
+
+        // Failed and timed out replicas, as well as listeners and replicas which do not receive
+        // updates, are not counted. The operation is still applied by the successful replicas.
+        if let Some(required_replicas) = required_replicas {
+            let applied_replicas = successes
+                .iter()
+                .filter(|result| result.status == UpdateStatus::Completed)
+                .count();
+            if applied_replicas < required_replicas {
+                return Err(CollectionError::under_replicated(
+                    applied_replicas,
+                    required_replicas,
+                ));
+            }
+        }
+
         // there are enough successes, return the first one
         let res = successes
             .into_iter()
@@ -1525,6 +1924,30 @@ impl ShardReplicaSet {
         .await
     }
 
+    /// Search in the local replica of the shard only. Returns `None` if there is no local replica
+    pub async fn search_local(
+        &self,
+        request: Arc<SearchRequestBatch>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Option<Vec<Vec<ScoredPoint>>>> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Ok(None),
+            Some(shard) => Ok(Some(
+                shard.get().search(request, search_runtime_handle).await?,
+            )),
+        }
+    }
+
+    /// Data of the segments of the local replica to read on warmup, grouped by segment
+    pub async fn local_warmup_tasks(&self) -> Vec<Vec<WarmupTask>> {
+        let local = self.local.read().await;
+        match &*local {
+            None => vec![],
+            Some(shard) => shard.warmup_tasks(),
+        }
+    }
+
     pub async fn count_local(
         &self,
         request: Arc<CountRequest>,
@@ -1536,6 +1959,123 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Checksum of the local replica of the shard
+    pub async fn local_checksum(&self) -> CollectionResult<ShardChecksum> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Err(CollectionError::service_error(format!(
+                "Shard {} has no local replica on peer {}",
+                self.shard_id,
+                self.this_peer_id()
+            ))),
+            Some(shard) => shard.checksum().await,
+        }
+    }
+
+    /// Outcome of every condition of the filter for the point in the local replica of the shard.
+    /// `None` if the replica doesn't have the point.
+    pub async fn local_trace_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Arc<Filter>,
+    ) -> CollectionResult<Option<FilterTrace>> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Err(CollectionError::service_error(format!(
+                "Shard {} has no local replica on peer {}",
+                self.shard_id,
+                self.this_peer_id()
+            ))),
+            Some(shard) => shard.trace_filter(point_id, filter).await,
+        }
+    }
+
+    /// Segments of the local replica of the shard
+    pub async fn local_segments_info(&self) -> CollectionResult<ShardSegmentsInfo> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Err(CollectionError::service_error(format!(
+                "Shard {} has no local replica on peer {}",
+                self.shard_id,
+                self.this_peer_id()
+            ))),
+            Some(shard) => Ok(ShardSegmentsInfo {
+                shard_id: self.shard_id,
+                segments: shard.segments_info().await?,
+            }),
+        }
+    }
+
+    /// Progress of the rebuild of the payload field index in the local replica, if there is one
+    pub async fn local_index_rebuild_progress(
+        &self,
+        field: &str,
+    ) -> CollectionResult<Option<ShardIndexRebuildProgress>> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Ok(None),
+            Some(shard) => shard
+                .index_rebuild_progress(self.shard_id, field.to_string())
+                .await
+                .map(Some),
+        }
+    }
+
+    /// Start a flush of the local replica of the shard.
+    /// `None` if there is no local replica or it doesn't hold any data
+    pub async fn local_flush(&self) -> Option<ShardFlushHandle> {
+        let local = self.local.read().await;
+        local.as_ref().and_then(Shard::flush)
+    }
+
+    /// Creations of payload field indexes in the local replica, if there is one
+    pub async fn local_field_index_builds(&self) -> CollectionResult<Vec<FieldIndexBuildProgress>> {
+        let local = self.local.read().await;
+        match &*local {
+            None => Ok(vec![]),
+            Some(shard) => shard.field_index_builds(self.shard_id).await,
+        }
+    }
+
+    /// Compare checksums of all replicas of the shard
+    pub async fn checksum_report(&self) -> ShardChecksumReport {
+        let local = self.local.read().await;
+        let remotes = self.remotes.read().await;
+        let this_peer_id = self.this_peer_id();
+
+        let local_checksum = async {
+            match &*local {
+                Some(shard) => Some((this_peer_id, shard.checksum().await)),
+                None => None,
+            }
+        };
+        let remote_checksums = join_all(
+            remotes
+                .iter()
+                .map(|remote| async move { (remote.peer_id, remote.checksum().await) }),
+        );
+        let (local_checksum, remote_checksums) = join(local_checksum, remote_checksums).await;
+
+        let replicas = local_checksum
+            .into_iter()
+            .chain(remote_checksums)
+            .filter_map(|(peer_id, result)| {
+                let state = self.peer_state(&peer_id)?;
+                let (checksum, error) = match result {
+                    Ok(checksum) => (Some(checksum), None),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+                Some(ReplicaChecksum {
+                    peer_id,
+                    state,
+                    checksum,
+                    error,
+                })
+            })
+            .collect();
+        ShardChecksumReport::new(self.shard_id, replicas)
+    }
+
     pub async fn count(&self, request: Arc<CountRequest>) -> CollectionResult<CountResult> {
         let local = self.local.read().await;
         let remotes = self.remotes.read().await;
@@ -1562,6 +2102,25 @@ impl ShardReplicaSet {
         )
         .await
     }
+
+    /// First points of the shard in the order of the `order_by` key of the request.
+    /// Points of the replicas are resolved by id, so they are not ordered anymore
+    pub async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+        read_consistency: Option<ReadConsistency>,
+    ) -> CollectionResult<Vec<Record>> {
+        let local = self.local.read().await;
+        let remotes = self.remotes.read().await;
+
+        self.execute_and_resolve_read_operation(
+            |shard| shard.query_ordered(request.clone()),
+            &local,
+            &remotes,
+            read_consistency.unwrap_or_default(),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -1581,10 +2140,13 @@ mod tests {
         vacuum_min_vector_number: 1000,
         default_segment_number: 2,
         max_segment_size: None,
+        max_segment_number: None,
+        target_segment_size: None,
         memmap_threshold: None,
         indexing_threshold: Some(50_000),
         flush_interval_sec: 30,
         max_optimization_threads: 2,
+        max_indexing_threads: None,
     };
 
     pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -1605,11 +2167,20 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
             }),
             shard_number: NonZeroU32::new(4).unwrap(),
             replication_factor: NonZeroU32::new(3).unwrap(),
             write_consistency_factor: NonZeroU32::new(2).unwrap(),
             on_disk_payload: false,
+            validate_payload: false,
+            payload_validation_mode: Default::default(),
+            sparse_vectors: None,
+            payload_indexes: Default::default(),
+            payload_cache_fields: Default::default(),
+            read_only: false,
+            auto_index_group_by_fields: false,
+            read_hedge_delay_ms: None,
         };
 
         let config = CollectionConfig {
@@ -1618,6 +2189,16 @@ mod tests {
             wal_config,
             hnsw_config: Default::default(),
             quantization_config: None,
+            strict_mode_config: None,
+            default_search_params: None,
+            default_with_payload: None,
+            default_with_vector: None,
+            default_max_payload_return_bytes: None,
+            default_vector: None,
+            quota_config: None,
+            admission_config: None,
+            read_cache_config: None,
+            ttl_config: None,
         };
 
         let shared_config = Arc::new(RwLock::new(config.clone()));
@@ -1656,4 +2237,45 @@ mod tests {
         assert_eq!(rs.highest_replica_peer_id(), Some(5));
         assert_eq!(rs.highest_alive_replica_peer_id(), Some(4));
     }
+
+    #[tokio::test]
+    async fn test_replica_failure_during_recovery() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_shard_replica_set(&collection_dir).await;
+
+        let now = Utc::now();
+        rs.set_recovery_state(Some(RecoveryState::pending(now).start(2, now)))
+            .unwrap();
+
+        // Transfer of the recovery is started
+        rs.set_replica_state(&1, ReplicaState::Partial).unwrap();
+        assert!(rs.recovery_state().unwrap().is_recovering());
+
+        // Transfer is aborted
+        rs.set_replica_state(&1, ReplicaState::Dead).unwrap();
+        let recovery = rs.recovery_state().unwrap();
+        match &recovery {
+            RecoveryState::PendingRecovery {
+                attempts, retry_at, ..
+            } => {
+                assert_eq!(*attempts, 1);
+                assert!(*retry_at > now);
+            }
+            other => panic!("recovery must be pending, got {other:?}"),
+        }
+        assert!(!recovery.is_due(Utc::now()));
+
+        // Recovery state is persisted
+        let reloaded =
+            SaveOnDisk::<ReplicaSetState>::load_or_init(rs.shard_path.join(REPLICA_STATE_FILE))
+                .unwrap();
+        assert_eq!(reloaded.read().recovery, Some(recovery.clone()));
+
+        // Successful recovery clears the state
+        rs.set_recovery_state(Some(recovery.start(3, Utc::now())))
+            .unwrap();
+        rs.set_replica_state(&1, ReplicaState::Partial).unwrap();
+        rs.set_replica_state(&1, ReplicaState::Active).unwrap();
+        assert_eq!(rs.recovery_state(), None);
+    }
 }
@@ -251,6 +251,8 @@ mod test {
             score,
             payload: None,
             vector: None,
+            payload_truncation: None,
+            vector_slice: None,
         }
     }
 
@@ -1,13 +1,24 @@
 use core::marker::{Send, Sync};
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 
-use crate::operations::types::CollectionResult;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::filter_trace::FilterTrace;
+use segment::types::{Filter, PayloadFieldSchema, PayloadKeyType, PointIdType};
+use tokio::sync::watch;
+
+use crate::operations::types::{CollectionResult, OptimizationPlan, SegmentDescription};
+use crate::operations::CollectionUpdateOperations;
+use crate::read_cache::DataVersion;
+use crate::shards::checksum::ShardChecksum;
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
-use crate::shards::local_shard::LocalShard;
+use crate::shards::local_shard::{LocalShard, ShardFlushHandle};
 use crate::shards::proxy_shard::ProxyShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
+use crate::wal::WalRetention;
 
 pub type ShardId = u32;
 
@@ -44,11 +55,11 @@ impl Shard {
         }
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
+    pub fn get_telemetry_data(&self, with_graph_health: bool) -> LocalShardTelemetry {
         let mut telemetry = match self {
-            Shard::Local(local_shard) => local_shard.get_telemetry_data(),
-            Shard::Proxy(proxy_shard) => proxy_shard.get_telemetry_data(),
-            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_telemetry_data(),
+            Shard::Local(local_shard) => local_shard.get_telemetry_data(with_graph_health),
+            Shard::Proxy(proxy_shard) => proxy_shard.get_telemetry_data(with_graph_health),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_telemetry_data(with_graph_health),
             Shard::Dummy(dummy_shard) => dummy_shard.get_telemetry_data(),
         };
         telemetry.variant_name = Some(self.variant_name().to_string());
@@ -93,4 +104,146 @@ impl Shard {
             Shard::Dummy(dummy_shard) => dummy_shard.on_optimizer_config_update().await,
         }
     }
+
+    pub async fn trigger_optimizers(&self) -> CollectionResult<()> {
+        match self {
+            Shard::Local(local_shard) => local_shard.trigger_optimizers().await,
+            Shard::Proxy(proxy_shard) => proxy_shard.trigger_optimizers().await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.trigger_optimizers().await,
+            Shard::Dummy(dummy_shard) => dummy_shard.trigger_optimizers().await,
+        }
+    }
+
+    /// Returns `None` if the shard doesn't hold any data
+    pub fn flush(&self) -> Option<ShardFlushHandle> {
+        match self {
+            Shard::Local(local_shard) => Some(local_shard.flush()),
+            Shard::Proxy(proxy_shard) => Some(proxy_shard.flush()),
+            Shard::ForwardProxy(proxy_shard) => Some(proxy_shard.flush()),
+            Shard::Dummy(_) => None,
+        }
+    }
+
+    /// Returns `None` if the shard doesn't hold any data
+    pub fn subscribe_status_changes(&self) -> Option<watch::Receiver<()>> {
+        match self {
+            Shard::Local(local_shard) => Some(local_shard.subscribe_status_changes()),
+            Shard::Proxy(proxy_shard) => Some(proxy_shard.subscribe_status_changes()),
+            Shard::ForwardProxy(proxy_shard) => Some(proxy_shard.subscribe_status_changes()),
+            Shard::Dummy(_) => None,
+        }
+    }
+
+    pub async fn optimization_plan(&self) -> CollectionResult<Option<OptimizationPlan>> {
+        match self {
+            Shard::Local(local_shard) => Ok(local_shard.optimization_plan().await),
+            Shard::Proxy(proxy_shard) => Ok(proxy_shard.optimization_plan().await),
+            Shard::ForwardProxy(proxy_shard) => Ok(proxy_shard.optimization_plan().await),
+            Shard::Dummy(dummy_shard) => dummy_shard.optimization_plan().await,
+        }
+    }
+
+    pub async fn indexed_only_excluded_points(&self, vector_names: &[String]) -> usize {
+        match self {
+            Shard::Local(local_shard) => {
+                local_shard.indexed_only_excluded_points(vector_names).await
+            }
+            Shard::Proxy(proxy_shard) => {
+                proxy_shard.indexed_only_excluded_points(vector_names).await
+            }
+            Shard::ForwardProxy(proxy_shard) => {
+                proxy_shard.indexed_only_excluded_points(vector_names).await
+            }
+            Shard::Dummy(_) => 0,
+        }
+    }
+
+    pub fn indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        match self {
+            Shard::Local(local_shard) => local_shard.indexed_fields(),
+            Shard::Proxy(proxy_shard) => proxy_shard.indexed_fields(),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.indexed_fields(),
+            Shard::Dummy(_) => HashMap::new(),
+        }
+    }
+
+    pub fn unindexed_filter_keys(&self, filters: &[&Filter]) -> Vec<PayloadKeyType> {
+        match self {
+            Shard::Local(local_shard) => local_shard.unindexed_filter_keys(filters),
+            Shard::Proxy(proxy_shard) => proxy_shard.unindexed_filter_keys(filters),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.unindexed_filter_keys(filters),
+            Shard::Dummy(_) => vec![],
+        }
+    }
+
+    pub async fn check_quota(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        match self {
+            Shard::Local(local_shard) => local_shard.check_quota(operation).await,
+            Shard::Proxy(proxy_shard) => proxy_shard.check_quota(operation).await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.check_quota(operation).await,
+            Shard::Dummy(_) => Ok(()),
+        }
+    }
+
+    /// Version of the data of the local shard, `None` if it is not known
+    pub fn data_version(&self) -> Option<DataVersion> {
+        match self {
+            Shard::Local(local_shard) => local_shard.data_version(),
+            Shard::Proxy(proxy_shard) => proxy_shard.data_version(),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.data_version(),
+            Shard::Dummy(_) => None,
+        }
+    }
+
+    pub fn warmup_tasks(&self) -> Vec<Vec<WarmupTask>> {
+        match self {
+            Shard::Local(local_shard) => local_shard.warmup_tasks(),
+            Shard::Proxy(proxy_shard) => proxy_shard.warmup_tasks(),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.warmup_tasks(),
+            Shard::Dummy(_) => vec![],
+        }
+    }
+
+    pub async fn checksum(&self) -> CollectionResult<ShardChecksum> {
+        match self {
+            Shard::Local(local_shard) => local_shard.checksum().await,
+            Shard::Proxy(proxy_shard) => proxy_shard.checksum().await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.checksum().await,
+            Shard::Dummy(dummy_shard) => dummy_shard.checksum(),
+        }
+    }
+
+    pub async fn segments_info(&self) -> CollectionResult<Vec<SegmentDescription>> {
+        match self {
+            Shard::Local(local_shard) => local_shard.segments_info().await,
+            Shard::Proxy(proxy_shard) => proxy_shard.segments_info().await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.segments_info().await,
+            Shard::Dummy(dummy_shard) => dummy_shard.segments_info(),
+        }
+    }
+
+    pub async fn trace_filter(
+        &self,
+        point_id: PointIdType,
+        filter: Arc<Filter>,
+    ) -> CollectionResult<Option<FilterTrace>> {
+        match self {
+            Shard::Local(local_shard) => local_shard.trace_filter(point_id, filter).await,
+            Shard::Proxy(proxy_shard) => proxy_shard.trace_filter(point_id, filter).await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.trace_filter(point_id, filter).await,
+            Shard::Dummy(dummy_shard) => dummy_shard.trace_filter(),
+        }
+    }
+
+    pub fn set_wal_retention(&self, retention: Option<WalRetention>) {
+        match self {
+            Shard::Local(local_shard) => local_shard.set_wal_retention(retention),
+            Shard::Proxy(proxy_shard) => proxy_shard.set_wal_retention(retention),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.set_wal_retention(retention),
+            Shard::Dummy(_) => {}
+        }
+    }
 }
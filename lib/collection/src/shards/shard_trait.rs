@@ -7,8 +7,8 @@ use segment::types::{
 use tokio::runtime::Handle;
 
 use crate::operations::types::{
-    CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest, Record,
-    SearchRequestBatch, UpdateResult,
+    CollectionInfo, CollectionResult, CountRequest, CountResult, OrderedQueryRequest, PointRequest,
+    Record, SearchRequestBatch, UpdateResult,
 };
 use crate::operations::CollectionUpdateOperations;
 
@@ -46,6 +46,13 @@ pub trait ShardOperation {
         with_payload: &WithPayload,
         with_vector: &WithVector,
     ) -> CollectionResult<Vec<Record>>;
+
+    /// First `offset + limit` points in the order of the `order_by` key of the request,
+    /// with the values of the key in `order_value`
+    async fn query_ordered(
+        &self,
+        request: Arc<OrderedQueryRequest>,
+    ) -> CollectionResult<Vec<Record>>;
 }
 
 pub type ShardOperationSS = dyn ShardOperation + Send + Sync;
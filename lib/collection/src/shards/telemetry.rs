@@ -17,6 +17,17 @@ pub struct ReplicaSetTelemetry {
     pub local: Option<LocalShardTelemetry>,
     pub remote: Vec<RemoteShardTelemetry>,
     pub replicate_states: HashMap<PeerId, ReplicaState>,
+    #[serde(default)]
+    pub hedged_reads: HedgedReadsTelemetry,
+}
+
+/// Statistics of the reads, which were also sent to another replica after the hedge delay
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct HedgedReadsTelemetry {
+    /// Number of reads, sent to another replica because the first one didn't respond in time
+    pub fired: u64,
+    /// Number of hedged reads, which were answered first by the other replica
+    pub won: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -32,12 +43,40 @@ pub struct LocalShardTelemetry {
     pub variant_name: Option<String>,
     pub segments: Vec<SegmentTelemetry>,
     pub optimizations: OptimizerTelemetry,
+    /// Progress of the WAL replay, present only while the shard is recovering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_recovery: Option<WalRecoveryTelemetry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct WalRecoveryTelemetry {
+    /// Number of WAL records which should be re-applied
+    pub total_records: u64,
+    /// Number of WAL records already re-applied
+    pub applied_records: u64,
+    /// Estimated amount of WAL data which is not yet re-applied
+    pub bytes_remaining: u64,
+    /// Number of the operation which is currently being applied
+    pub current_op_num: Option<u64>,
+    /// Unix timestamp (ms) of when the current operation started to be applied
+    pub current_op_started_at: Option<u64>,
+    /// Error message, if the recovery has failed
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default)]
 pub struct OptimizerTelemetry {
     pub status: OptimizersStatus,
     pub optimizations: OperationDurationStatistics,
+    /// Number of threads, currently used by optimizations for building indexes
+    #[serde(default)]
+    pub indexing_threads_used: usize,
+    /// Maximum number of threads, which optimizations can use for building indexes
+    #[serde(default)]
+    pub indexing_threads_budget: usize,
+    /// Number of segment merges, performed by the merge optimizer
+    #[serde(default)]
+    pub merges: usize,
 }
 
 impl std::ops::Add for OptimizerTelemetry {
@@ -47,6 +86,9 @@ impl std::ops::Add for OptimizerTelemetry {
         Self {
             status: max(self.status, other.status),
             optimizations: self.optimizations + other.optimizations,
+            indexing_threads_used: self.indexing_threads_used + other.indexing_threads_used,
+            indexing_threads_budget: self.indexing_threads_budget + other.indexing_threads_budget,
+            merges: self.merges + other.merges,
         }
     }
 }
@@ -56,6 +98,9 @@ impl Anonymize for OptimizerTelemetry {
         Self {
             status: self.status.clone(),
             optimizations: self.optimizations.anonymize(),
+            indexing_threads_used: self.indexing_threads_used,
+            indexing_threads_budget: self.indexing_threads_budget,
+            merges: self.merges,
         }
     }
 }
@@ -66,6 +111,7 @@ impl Anonymize for LocalShardTelemetry {
             variant_name: self.variant_name.clone(),
             segments: self.segments.anonymize(),
             optimizations: self.optimizations.anonymize(),
+            wal_recovery: self.wal_recovery.clone(),
         }
     }
 }
@@ -88,6 +134,7 @@ impl Anonymize for ReplicaSetTelemetry {
             local: self.local.anonymize(),
             remote: self.remote.anonymize(),
             replicate_states: Default::default(),
+            hedged_reads: self.hedged_reads.clone(),
         }
     }
 }
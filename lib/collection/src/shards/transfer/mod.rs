@@ -1,2 +1,3 @@
 pub mod shard_transfer;
 pub mod transfer_tasks_pool;
+pub mod wal_delta;
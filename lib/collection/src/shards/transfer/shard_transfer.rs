@@ -16,9 +16,10 @@ use crate::shards::remote_shard::RemoteShard;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_holder::{LockedShardHolder, ShardHolder};
+use crate::shards::transfer::wal_delta::transfer_wal_delta;
 use crate::shards::CollectionId;
 
-const TRANSFER_BATCH_SIZE: usize = 100;
+pub(super) const TRANSFER_BATCH_SIZE: usize = 100;
 const RETRY_TIMEOUT: Duration = Duration::from_secs(1);
 const MAX_RETRY_COUNT: usize = 3;
 
@@ -30,6 +31,20 @@ pub struct ShardTransfer {
     /// If this flag is true, the is a replication related transfer of shard from 1 peer to another
     /// Shard on original peer will not be deleted in this case
     pub sync: bool,
+    /// Method of transferring the shard data. If not set - all points are streamed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<ShardTransferMethod>,
+}
+
+/// Method of transferring the shard data to the target peer
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardTransferMethod {
+    /// Stream all points of the shard in batches
+    StreamRecords,
+    /// Only transfer the points, changed after the last operation acknowledged by the target
+    /// replica. Falls back to streaming all points, if the WAL doesn't contain all of the changes
+    WalDelta,
 }
 
 /// Unique identifier of a transfer
@@ -197,6 +212,7 @@ pub async fn transfer_shard(
     shard_id: ShardId,
     collection_id: CollectionId,
     peer_id: PeerId,
+    method: Option<ShardTransferMethod>,
     channel_service: ChannelService,
     stopped: Arc<AtomicBool>,
 ) -> CollectionResult<()> {
@@ -220,6 +236,16 @@ pub async fn transfer_shard(
             )));
         }
     };
+
+    if method == Some(ShardTransferMethod::WalDelta) {
+        if transfer_wal_delta(shard_holder.clone(), shard_id, stopped.clone()).await? {
+            return Ok(());
+        }
+        log::info!(
+            "Operations of shard {shard_id}, missed by peer {peer_id}, are unknown, transferring all points"
+        );
+    }
+
     // Transfer contents batch by batch
     transfer_batches(shard_holder.clone(), shard_id, stopped.clone()).await
 }
@@ -451,6 +477,7 @@ where
                 transfer.shard_id,
                 collection_id.clone(),
                 transfer.to,
+                transfer.method,
                 channel_service.clone(),
                 stopped.clone(),
             )
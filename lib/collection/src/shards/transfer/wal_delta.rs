@@ -0,0 +1,206 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use segment::types::{PointIdType, SeqNumberType};
+
+use crate::operations::operation_effect::{EstimateOperationEffectArea, OperationEffectArea};
+use crate::operations::point_ops::PointOperations;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard_holder::LockedShardHolder;
+use crate::shards::transfer::shard_transfer::TRANSFER_BATCH_SIZE;
+use crate::wal::SerdeWal;
+
+/// Max number of WAL records, kept to recover lagging replicas.
+/// Replicas, which are lagging further behind, are recovered by streaming all points.
+pub const WAL_DELTA_MAX_RECORDS: u64 = 1_000_000;
+
+/// Last operations of the local shard, acknowledged by the remote replicas
+#[derive(Debug, Default)]
+pub struct AcknowledgedOperations {
+    acknowledged: HashMap<PeerId, SeqNumberType>,
+    /// Replicas, which missed some operations after the acknowledged one
+    lagging: HashSet<PeerId>,
+}
+
+impl AcknowledgedOperations {
+    /// Restore the acknowledged operations of the lagging replicas
+    pub fn from_lagging(acknowledged: HashMap<PeerId, SeqNumberType>) -> Self {
+        Self {
+            lagging: acknowledged.keys().copied().collect(),
+            acknowledged,
+        }
+    }
+
+    /// Record the result of the operation on a remote replica.
+    ///
+    /// Replica stops acknowledging operations after the first failed one, so that all of the
+    /// missed operations follow the acknowledged one.
+    pub fn record(&mut self, peer_id: PeerId, operation_id: SeqNumberType, applied: bool) {
+        if applied {
+            if !self.lagging.contains(&peer_id) {
+                let acknowledged = self.acknowledged.entry(peer_id).or_insert(operation_id);
+                *acknowledged = (*acknowledged).max(operation_id);
+            }
+        } else {
+            self.lagging.insert(peer_id);
+            // Operations, applied concurrently with the failed one, are not trusted
+            if let Some(acknowledged) = self.acknowledged.get_mut(&peer_id) {
+                *acknowledged = (*acknowledged).min(operation_id.saturating_sub(1));
+            }
+        }
+    }
+
+    /// Replica stopped receiving updates, e.g. it was marked as dead by another peer
+    pub fn mark_lagging(&mut self, peer_id: PeerId) {
+        self.lagging.insert(peer_id);
+    }
+
+    /// Replica is recovered and receives all updates again
+    pub fn mark_recovered(&mut self, peer_id: PeerId) {
+        self.lagging.remove(&peer_id);
+        self.acknowledged.remove(&peer_id);
+    }
+
+    /// Last operation, acknowledged by the lagging replica
+    pub fn lagging_acknowledged(&self, peer_id: PeerId) -> Option<SeqNumberType> {
+        if self.lagging.contains(&peer_id) {
+            self.acknowledged.get(&peer_id).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Last operations, acknowledged by all of the lagging replicas
+    pub fn all_lagging_acknowledged(&self) -> HashMap<PeerId, SeqNumberType> {
+        self.lagging
+            .iter()
+            .filter_map(|peer_id| Some((*peer_id, *self.acknowledged.get(peer_id)?)))
+            .collect()
+    }
+
+    /// Oldest operation, which is missed by any of the lagging replicas
+    pub fn retain_from(&self) -> Option<SeqNumberType> {
+        self.all_lagging_acknowledged()
+            .values()
+            .min()
+            .map(|acknowledged| acknowledged + 1)
+    }
+}
+
+/// Points, changed by the WAL records starting from `from`.
+///
+/// Returns `None` if some of the records are already removed from the WAL, or if some of the
+/// operations select points by filter, so the changed points are unknown.
+pub fn changed_point_ids(
+    wal: &SerdeWal<CollectionUpdateOperations>,
+    from: SeqNumberType,
+) -> Option<Vec<PointIdType>> {
+    if from < wal.first_index() {
+        return None;
+    }
+    let mut point_ids = HashSet::new();
+    for (_, operation) in wal.read(from) {
+        if let CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(_)) =
+            operation
+        {
+            // Sync operation also deletes all points in the range
+            return None;
+        }
+        match operation.estimate_effect_area() {
+            OperationEffectArea::Empty => {}
+            OperationEffectArea::Points(ids) => point_ids.extend(ids),
+            OperationEffectArea::Filter(_) => return None,
+        }
+    }
+    Some(point_ids.into_iter().collect())
+}
+
+/// Transfer the current state of the points, changed by the operations the target replica missed.
+///
+/// Returns `false` if the missed operations are unknown, so all points have to be transferred.
+pub(super) async fn transfer_wal_delta(
+    shard_holder: Arc<LockedShardHolder>,
+    shard_id: ShardId,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<bool> {
+    let point_ids = {
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} is not found"
+            )));
+        };
+        let Some(point_ids) = replica_set.wal_delta_point_ids().await? else {
+            return Ok(false);
+        };
+        replica_set.transfer_indexes().await?;
+        point_ids
+    };
+
+    log::info!(
+        "Transferring {} points of shard {shard_id}, changed since the last acknowledged operation",
+        point_ids.len()
+    );
+
+    let batches = point_ids.chunks(TRANSFER_BATCH_SIZE).collect::<Vec<_>>();
+    let last_batch = batches.len().saturating_sub(1);
+    for (index, batch) in batches.into_iter().enumerate() {
+        if stopped.load(Ordering::Relaxed) {
+            return Err(CollectionError::Cancelled {
+                description: "Transfer cancelled".to_string(),
+            });
+        }
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} is not found"
+            )));
+        };
+        // We only need to wait for the last batch
+        replica_set
+            .transfer_points(batch.to_vec(), index == last_batch)
+            .await?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acknowledged_operations() {
+        let mut acknowledged = AcknowledgedOperations::default();
+        acknowledged.record(1, 10, true);
+        acknowledged.record(2, 10, true);
+        acknowledged.record(1, 11, true);
+        assert_eq!(acknowledged.lagging_acknowledged(1), None);
+        assert_eq!(acknowledged.retain_from(), None);
+
+        // Operation 13 is applied concurrently with the failed 12
+        acknowledged.record(1, 13, true);
+        acknowledged.record(1, 12, false);
+        acknowledged.record(1, 14, true);
+        assert_eq!(acknowledged.lagging_acknowledged(1), Some(11));
+
+        acknowledged.mark_lagging(2);
+        assert_eq!(acknowledged.retain_from(), Some(11));
+
+        acknowledged.mark_recovered(2);
+        assert_eq!(acknowledged.retain_from(), Some(12));
+
+        let restored =
+            AcknowledgedOperations::from_lagging(acknowledged.all_lagging_acknowledged());
+        assert_eq!(restored.lagging_acknowledged(1), Some(11));
+
+        acknowledged.mark_recovered(1);
+        assert_eq!(acknowledged.retain_from(), None);
+
+        // Nothing is known about the operations, missed by a replica without acknowledged ones
+        acknowledged.record(3, 20, false);
+        assert_eq!(acknowledged.lagging_acknowledged(3), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+
+use crate::shards::telemetry::WalRecoveryTelemetry;
+
+/// Emit a log line about WAL replay progress every `WAL_RECOVERY_LOG_INTERVAL` records
+pub const WAL_RECOVERY_LOG_INTERVAL: u64 = 10_000;
+
+/// Progress of the WAL replay, which is performed when a local shard is loaded from disk.
+///
+/// State is shared between the replay routine and info/telemetry readers,
+/// so it can be observed while recovery is still running.
+#[derive(Debug, Default)]
+pub struct WalRecoveryState {
+    in_progress: AtomicBool,
+    total_records: AtomicU64,
+    applied_records: AtomicU64,
+    total_bytes: AtomicU64,
+    /// Number of the operation being applied and unix timestamp (ms) of when it started
+    current_op: Mutex<Option<(u64, u64)>>,
+    error: Mutex<Option<String>>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl WalRecoveryState {
+    /// Mark recovery as started. WAL directory size is used to estimate remaining bytes.
+    pub fn start(&self, total_records: u64, wal_path: &Path) {
+        let total_bytes = fs_extra::dir::get_size(wal_path).unwrap_or_default();
+        self.total_records.store(total_records, Ordering::Relaxed);
+        self.total_bytes.store(total_bytes, Ordering::Relaxed);
+        self.applied_records.store(0, Ordering::Relaxed);
+        *self.current_op.lock() = None;
+        *self.error.lock() = None;
+        self.in_progress.store(true, Ordering::Release);
+    }
+
+    pub fn on_operation_start(&self, op_num: u64) {
+        *self.current_op.lock() = Some((op_num, now_ms()));
+    }
+
+    /// Returns number of records applied so far
+    pub fn on_operation_applied(&self) -> u64 {
+        self.applied_records.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn finish(&self) {
+        self.in_progress.store(false, Ordering::Release);
+    }
+
+    pub fn fail(&self, error: String) {
+        *self.error.lock() = Some(error);
+        self.in_progress.store(false, Ordering::Release);
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        self.in_progress.load(Ordering::Acquire)
+    }
+
+    /// Error of the background WAL replay, if it has failed
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().clone()
+    }
+
+    /// Shard is able to accept updates only if recovery is not running and has not failed
+    pub fn is_ready(&self) -> bool {
+        !self.is_in_progress() && self.error.lock().is_none()
+    }
+
+    /// Remaining bytes are estimated proportionally to the number of not yet applied records
+    fn bytes_remaining(&self, total_records: u64, applied_records: u64) -> u64 {
+        if total_records == 0 {
+            return 0;
+        }
+        let total_bytes = self.total_bytes.load(Ordering::Relaxed) as u128;
+        let remaining_records = total_records.saturating_sub(applied_records) as u128;
+        (total_bytes * remaining_records / total_records as u128) as u64
+    }
+
+    /// Telemetry is only reported while the recovery is running or if it has failed
+    pub fn get_telemetry_data(&self) -> Option<WalRecoveryTelemetry> {
+        let error = self.error();
+        if !self.is_in_progress() && error.is_none() {
+            return None;
+        }
+
+        let total_records = self.total_records.load(Ordering::Relaxed);
+        let applied_records = self.applied_records.load(Ordering::Relaxed);
+        let current_op = *self.current_op.lock();
+
+        Some(WalRecoveryTelemetry {
+            total_records,
+            applied_records,
+            bytes_remaining: self.bytes_remaining(total_records, applied_records),
+            current_op_num: current_op.map(|(op_num, _)| op_num),
+            current_op_started_at: current_op.map(|(_, started_at)| started_at),
+            error,
+        })
+    }
+}
@@ -0,0 +1,304 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use segment::types::{Filter, SearchParams};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::operations::types::{OrderedQueryRequest, RecommendRequest, SearchRequest};
+use crate::shards::shard::ShardId;
+use crate::telemetry::CollectionOperationType;
+
+const DEFAULT_SLOW_LOG_CAPACITY: usize = 128;
+
+/// Placeholder for the payload values in redacted filters
+const REDACTED_VALUE: &str = "<redacted>";
+
+/// Configuration of the log of slow read operations
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct SlowLogConfig {
+    /// Read operations, which take longer than this threshold, are recorded.
+    /// If not set - slow log is disabled
+    #[serde(default)]
+    pub threshold_ms: Option<u64>,
+    /// Maximal number of entries, kept in memory for each collection
+    #[serde(default = "default_slow_log_capacity")]
+    pub capacity: usize,
+    /// If true - entries are also written into the log as JSON
+    #[serde(default)]
+    pub log_to_file: bool,
+    /// If true - payload values in filters are replaced with a placeholder
+    #[serde(default = "default_redact_values")]
+    pub redact_values: bool,
+}
+
+impl Default for SlowLogConfig {
+    fn default() -> Self {
+        Self {
+            threshold_ms: None,
+            capacity: DEFAULT_SLOW_LOG_CAPACITY,
+            log_to_file: false,
+            redact_values: default_redact_values(),
+        }
+    }
+}
+
+const fn default_slow_log_capacity() -> usize {
+    DEFAULT_SLOW_LOG_CAPACITY
+}
+
+const fn default_redact_values() -> bool {
+    true
+}
+
+/// Parameters of a single request, captured while the operation is served
+#[derive(Debug, Clone, Default)]
+pub struct RequestDetails {
+    pub filter: Option<Filter>,
+    pub limit: Option<usize>,
+    pub params: Option<SearchParams>,
+}
+
+impl From<&SearchRequest> for RequestDetails {
+    fn from(request: &SearchRequest) -> Self {
+        Self {
+            filter: request.filter.clone(),
+            limit: Some(request.limit),
+            params: request.params,
+        }
+    }
+}
+
+impl From<&RecommendRequest> for RequestDetails {
+    fn from(request: &RecommendRequest) -> Self {
+        Self {
+            filter: request.filter.clone(),
+            limit: Some(request.limit),
+            params: request.params,
+        }
+    }
+}
+
+impl From<&OrderedQueryRequest> for RequestDetails {
+    fn from(request: &OrderedQueryRequest) -> Self {
+        Self {
+            filter: request.filter.clone(),
+            limit: Some(request.limit),
+            params: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SlowLogRequest {
+    /// Structure of the filter. Payload values are replaced with a placeholder, if redaction is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<SearchParams>,
+}
+
+impl SlowLogRequest {
+    fn new(details: RequestDetails, redact_values: bool) -> Self {
+        let filter = details
+            .filter
+            .and_then(|filter| serde_json::to_value(filter).ok())
+            .map(|filter| {
+                if redact_values {
+                    redact_filter_values(filter)
+                } else {
+                    filter
+                }
+            });
+        Self {
+            filter,
+            limit: details.limit,
+            params: details.params,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SlowLogPhase {
+    pub name: String,
+    pub duration_ms: f64,
+}
+
+impl SlowLogPhase {
+    pub fn new(name: &str, duration: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Record of a read operation, which took longer than the configured threshold
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SlowLogEntry {
+    pub collection: String,
+    pub operation: CollectionOperationType,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: f64,
+    /// Parameters of the requests, served by the operation
+    pub requests: Vec<SlowLogRequest>,
+    /// Durations of the operation phases, in order of completion
+    pub phases: Vec<SlowLogPhase>,
+    /// Shards, involved in the operation
+    pub shards: Vec<ShardId>,
+}
+
+impl SlowLogEntry {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        collection: String,
+        operation: CollectionOperationType,
+        started_at: DateTime<Utc>,
+        duration: Duration,
+        requests: Vec<RequestDetails>,
+        phases: Vec<SlowLogPhase>,
+        mut shards: Vec<ShardId>,
+        redact_values: bool,
+    ) -> Self {
+        shards.sort_unstable();
+        shards.dedup();
+        Self {
+            collection,
+            operation,
+            started_at,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            requests: requests
+                .into_iter()
+                .map(|request| SlowLogRequest::new(request, redact_values))
+                .collect(),
+            phases,
+            shards,
+        }
+    }
+}
+
+/// Replace all values of the serialized filter, except of the field keys, with a placeholder
+fn redact_filter_values(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(_) if key == "key" => value,
+                        value => redact_filter_values(value),
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(redact_filter_values).collect())
+        }
+        Value::Null => Value::Null,
+        _ => Value::String(REDACTED_VALUE.to_string()),
+    }
+}
+
+/// Bounded in-memory buffer of the slow log entries.
+///
+/// Each entry is written into its own slot, so concurrent writers almost never wait for each other.
+pub struct SlowLog {
+    slots: Box<[Mutex<Option<SlowLogEntry>>]>,
+    /// Number of recorded entries, next entry is written into the slot `next % capacity`
+    next: AtomicUsize,
+}
+
+impl SlowLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity).map(|_| Mutex::new(None)).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn record(&self, entry: SlowLogEntry) {
+        if self.slots.is_empty() {
+            return;
+        }
+        let slot = self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        *self.slots[slot].lock() = Some(entry);
+    }
+
+    /// Recorded entries, from the most recent to the oldest
+    pub fn entries(&self) -> Vec<SlowLogEntry> {
+        let mut entries: Vec<_> = self
+            .slots
+            .iter()
+            .filter_map(|slot| slot.lock().clone())
+            .collect();
+        entries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{Condition, FieldCondition};
+
+    use super::*;
+
+    fn entry(duration_ms: u64) -> SlowLogEntry {
+        SlowLogEntry::new(
+            "test".to_string(),
+            CollectionOperationType::Search,
+            Utc::now(),
+            Duration::from_millis(duration_ms),
+            vec![],
+            vec![],
+            vec![1, 0, 1],
+            true,
+        )
+    }
+
+    #[test]
+    fn test_slow_log_is_bounded() {
+        let slow_log = SlowLog::new(3);
+        for duration_ms in 0..10 {
+            slow_log.record(entry(duration_ms));
+        }
+        let entries = slow_log.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].shards, vec![0, 1]);
+
+        let mut durations: Vec<_> = entries.iter().map(|e| e.duration_ms as u64).collect();
+        durations.sort_unstable();
+        assert_eq!(durations, vec![7, 8, 9]);
+
+        let disabled = SlowLog::new(0);
+        disabled.record(entry(10));
+        assert!(disabled.entries().is_empty());
+    }
+
+    #[test]
+    fn test_redact_filter_values() {
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "city",
+            "Berlin".to_string().into(),
+        )));
+        let details = RequestDetails {
+            filter: Some(filter),
+            limit: Some(10),
+            params: None,
+        };
+
+        let redacted = SlowLogRequest::new(details.clone(), true).filter.unwrap();
+        let redacted = serde_json::to_string(&redacted).unwrap();
+        assert!(redacted.contains("\"key\":\"city\""));
+        assert!(redacted.contains(REDACTED_VALUE));
+        assert!(!redacted.contains("Berlin"));
+
+        let plain = SlowLogRequest::new(details, false).filter.unwrap();
+        assert!(serde_json::to_string(&plain).unwrap().contains("Berlin"));
+    }
+}
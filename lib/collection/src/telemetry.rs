@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
 
+use crate::admission::{ReadAdmissionTelemetry, WriteAdmissionTelemetry};
 use crate::config::CollectionConfig;
 use crate::operations::types::ShardTransferInfo;
+use crate::operations::CollectionUpdateOperations;
 use crate::shards::telemetry::ReplicaSetTelemetry;
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -13,6 +18,208 @@ pub struct CollectionTelemetry {
     pub config: CollectionConfig,
     pub shards: Vec<ReplicaSetTelemetry>,
     pub transfers: Vec<ShardTransferInfo>,
+    /// Number of threads, currently used for building indexes by optimizations of all local shards
+    #[serde(default)]
+    pub indexing_threads_used: usize,
+    #[serde(default)]
+    pub operations: OperationsTelemetry,
+}
+
+/// Upper bounds of the duration histogram buckets, in seconds
+pub const DURATION_HISTOGRAM_BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct HistogramBucket {
+    /// Upper bound of the bucket, in seconds
+    pub le: f64,
+    /// Number of observations, less or equal to the upper bound
+    pub count: u64,
+}
+
+/// Cumulative histogram of operation durations
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct DurationHistogram {
+    pub buckets: Vec<HistogramBucket>,
+    pub count: u64,
+    pub sum_seconds: f64,
+}
+
+impl Default for DurationHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: DURATION_HISTOGRAM_BUCKETS
+                .iter()
+                .map(|&le| HistogramBucket { le, count: 0 })
+                .collect(),
+            count: 0,
+            sum_seconds: 0.0,
+        }
+    }
+}
+
+impl DurationHistogram {
+    pub fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for bucket in self
+            .buckets
+            .iter_mut()
+            .filter(|bucket| seconds <= bucket.le)
+        {
+            bucket.count += 1;
+        }
+        self.count += 1;
+        self.sum_seconds += seconds;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct GroupByTelemetry {
+    pub durations: DurationHistogram,
+    /// Number of internal search requests, issued to find and fill up groups
+    pub internal_searches: u64,
+    /// Number of best groups, which were returned with less than `group_size` points
+    /// because the filling requests were exhausted
+    pub truncated_groups: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct RecommendTelemetry {
+    pub durations: DurationHistogram,
+    /// Number of requests, failed because positive or negative examples could not be found
+    pub example_resolution_failures: u64,
+}
+
+/// Client requests, which filter by conditions without payload index,
+/// counted according to the `unindexed_filtering` mode of the strict mode
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct UnindexedFilteringTelemetry {
+    /// Number of requests, served with a warning
+    pub warned_requests: u64,
+    /// Number of rejected requests
+    pub denied_requests: u64,
+}
+
+/// Type of the request, served by the collection
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionOperationType {
+    Search,
+    SearchBatch,
+    Recommend,
+    RecommendBatch,
+    GroupBy,
+    Fusion,
+    Query,
+    Scroll,
+    Count,
+    Retrieve,
+    UpdatePoints,
+    UpdateVectors,
+    UpdatePayload,
+    UpdateFieldIndex,
+}
+
+impl CollectionOperationType {
+    pub fn is_read(&self) -> bool {
+        match self {
+            Self::Search
+            | Self::SearchBatch
+            | Self::Recommend
+            | Self::RecommendBatch
+            | Self::GroupBy
+            | Self::Fusion
+            | Self::Query
+            | Self::Scroll
+            | Self::Count
+            | Self::Retrieve => true,
+            Self::UpdatePoints
+            | Self::UpdateVectors
+            | Self::UpdatePayload
+            | Self::UpdateFieldIndex => false,
+        }
+    }
+}
+
+impl From<&CollectionUpdateOperations> for CollectionOperationType {
+    fn from(operation: &CollectionUpdateOperations) -> Self {
+        match operation {
+            CollectionUpdateOperations::PointOperation(_) => Self::UpdatePoints,
+            CollectionUpdateOperations::VectorOperation(_) => Self::UpdateVectors,
+            CollectionUpdateOperations::PayloadOperation(_) => Self::UpdatePayload,
+            CollectionUpdateOperations::FieldIndexOperation(_) => Self::UpdateFieldIndex,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct OperationStatistics {
+    pub count: u64,
+    pub error_count: u64,
+    pub durations: DurationHistogram,
+}
+
+impl OperationStatistics {
+    pub fn record(&mut self, duration: Duration, is_error: bool) {
+        self.count += 1;
+        if is_error {
+            self.error_count += 1;
+        }
+        self.durations.observe(duration);
+    }
+}
+
+/// Statistics of the client requests, served by the collection.
+///
+/// Each request is only counted by the peer, which received it from the client.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct RequestsTelemetry {
+    /// Statistics of requests of all types
+    pub total: OperationStatistics,
+    /// Statistics of each type of requests. Only reported on higher telemetry detail levels
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub operations: BTreeMap<CollectionOperationType, OperationStatistics>,
+}
+
+impl RequestsTelemetry {
+    pub fn record(
+        &mut self,
+        operation_type: CollectionOperationType,
+        duration: Duration,
+        is_error: bool,
+    ) {
+        self.total.record(duration, is_error);
+        self.operations
+            .entry(operation_type)
+            .or_default()
+            .record(duration, is_error);
+    }
+
+    /// Same statistics without the breakdown by operation type
+    pub fn totals_only(&self) -> Self {
+        Self {
+            total: self.total.clone(),
+            operations: BTreeMap::new(),
+        }
+    }
+}
+
+/// Statistics of the operations, which are executed on the collection level
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct OperationsTelemetry {
+    pub group_by: GroupByTelemetry,
+    pub recommend: RecommendTelemetry,
+    #[serde(default)]
+    pub requests: RequestsTelemetry,
+    #[serde(default)]
+    pub read_admission: ReadAdmissionTelemetry,
+    #[serde(default)]
+    pub write_admission: WriteAdmissionTelemetry,
+    #[serde(default)]
+    pub unindexed_filtering: UnindexedFilteringTelemetry,
 }
 
 impl CollectionTelemetry {
@@ -24,6 +231,14 @@ impl CollectionTelemetry {
             .map(|s| s.info.num_vectors)
             .sum()
     }
+
+    pub fn count_indexing_threads(shards: &[ReplicaSetTelemetry]) -> usize {
+        shards
+            .iter()
+            .flat_map(|shard| shard.local.as_ref())
+            .map(|local| local.optimizations.indexing_threads_used)
+            .sum()
+    }
 }
 
 impl Anonymize for CollectionTelemetry {
@@ -34,6 +249,8 @@ impl Anonymize for CollectionTelemetry {
             init_time_ms: self.init_time_ms,
             shards: self.shards.anonymize(),
             transfers: vec![],
+            indexing_threads_used: self.indexing_threads_used,
+            operations: self.operations.clone(),
         }
     }
 }
@@ -46,6 +263,71 @@ impl Anonymize for CollectionConfig {
             optimizer_config: self.optimizer_config.clone(),
             wal_config: self.wal_config.clone(),
             quantization_config: self.quantization_config.clone(),
+            strict_mode_config: self.strict_mode_config.clone(),
+            default_search_params: self.default_search_params,
+            default_with_payload: self.default_with_payload.clone(),
+            default_with_vector: self.default_with_vector.clone(),
+            default_max_payload_return_bytes: self.default_max_payload_return_bytes,
+            ttl_config: self.ttl_config.clone(),
+            default_vector: self.default_vector.clone(),
+            quota_config: self.quota_config.clone(),
+            admission_config: self.admission_config.clone(),
+            read_cache_config: self.read_cache_config.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_telemetry_totals() {
+        let mut telemetry = RequestsTelemetry::default();
+        telemetry.record(
+            CollectionOperationType::Search,
+            Duration::from_millis(2),
+            false,
+        );
+        telemetry.record(
+            CollectionOperationType::Search,
+            Duration::from_millis(300),
+            true,
+        );
+        telemetry.record(
+            CollectionOperationType::Scroll,
+            Duration::from_millis(20),
+            false,
+        );
+
+        let search = &telemetry.operations[&CollectionOperationType::Search];
+        assert_eq!(search.count, 2);
+        assert_eq!(search.error_count, 1);
+
+        let total = &telemetry.total;
+        assert_eq!(total.count, 3);
+        assert_eq!(total.error_count, 1);
+        assert_eq!(total.durations.count, 3);
+        assert!((total.durations.sum_seconds - 0.322).abs() < 1e-9);
+
+        // Buckets are cumulative
+        let bucket_count = |le: f64| {
+            total
+                .durations
+                .buckets
+                .iter()
+                .find(|bucket| bucket.le == le)
+                .unwrap()
+                .count
+        };
+        assert_eq!(bucket_count(0.001), 0);
+        assert_eq!(bucket_count(0.005), 1);
+        assert_eq!(bucket_count(0.025), 2);
+        assert_eq!(bucket_count(0.5), 3);
+        assert_eq!(bucket_count(10.0), 3);
+
+        let totals_only = telemetry.totals_only();
+        assert!(totals_only.operations.is_empty());
+        assert_eq!(totals_only.total.count, 3);
+    }
+}
@@ -7,6 +7,7 @@ use std::time::Duration;
 use futures::future::join_all;
 use itertools::Itertools;
 use parking_lot::RwLock;
+use segment::common::cpu::CpuBudget;
 use tempfile::Builder;
 use tokio::time::{sleep, Instant};
 
@@ -46,14 +47,24 @@ async fn test_optimization_process() {
     let optimizers = Arc::new(vec![merge_optimizer, indexing_optimizer]);
 
     let segments: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
-    let handles = UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let cpu_budget = Arc::new(CpuBudget::new(usize::MAX));
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        &cpu_budget,
+        segments.clone(),
+        |_| {},
+    );
 
     assert_eq!(handles.len(), 2);
 
     let join_res = join_all(handles.into_iter().map(|x| x.join_handle).collect_vec()).await;
 
-    let handles_2 =
-        UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let handles_2 = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        &cpu_budget,
+        segments.clone(),
+        |_| {},
+    );
 
     assert_eq!(handles_2.len(), 0);
 
@@ -71,6 +82,54 @@ async fn test_optimization_process() {
     }
 }
 
+#[tokio::test]
+async fn test_optimization_cpu_budget() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let dim = 256;
+    let mut holder = SegmentHolder::default();
+
+    for _ in 0..3 {
+        holder.add(random_segment(dir.path(), 100, 110, dim));
+    }
+
+    let indexing_optimizer: Arc<Optimizer> =
+        Arc::new(get_indexing_optimizer(dir.path(), temp_dir.path(), dim));
+
+    let optimizers = Arc::new(vec![indexing_optimizer]);
+
+    let segments: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+    let cpu_budget = Arc::new(CpuBudget::new(1));
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        &cpu_budget,
+        segments.clone(),
+        |_| {},
+    );
+
+    // Single CPU allows only a single optimization at a time
+    assert_eq!(handles.len(), 1);
+    assert_eq!(cpu_budget.used(), 1);
+
+    let join_res = join_all(handles.into_iter().map(|x| x.join_handle).collect_vec()).await;
+    for res in join_res {
+        assert!(res.unwrap());
+    }
+
+    // CPU is released once optimization is finished
+    assert_eq!(cpu_budget.used(), 0);
+
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        &cpu_budget,
+        segments.clone(),
+        |_| {},
+    );
+    assert_eq!(handles.len(), 1);
+    join_all(handles.into_iter().map(|x| x.join_handle).collect_vec()).await;
+}
+
 #[tokio::test]
 async fn test_cancel_optimization() {
     let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -91,7 +150,13 @@ async fn test_cancel_optimization() {
     let now = Instant::now();
 
     let segments: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
-    let handles = UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let cpu_budget = Arc::new(CpuBudget::new(usize::MAX));
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        &cpu_budget,
+        segments.clone(),
+        |_| {},
+    );
 
     sleep(Duration::from_millis(100)).await;
 
@@ -19,10 +19,13 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     vacuum_min_vector_number: 1000,
     default_segment_number: 2,
     max_segment_size: None,
+    max_segment_number: None,
+    target_segment_size: None,
     memmap_threshold: None,
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    max_indexing_threads: None,
 };
 
 pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -46,11 +49,20 @@ async fn _test_snapshot_collection(node_type: NodeType) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
         }),
         shard_number: NonZeroU32::new(4).unwrap(),
         replication_factor: NonZeroU32::new(3).unwrap(),
         write_consistency_factor: NonZeroU32::new(2).unwrap(),
         on_disk_payload: false,
+        validate_payload: false,
+        payload_validation_mode: Default::default(),
+        sparse_vectors: None,
+        payload_indexes: Default::default(),
+        payload_cache_fields: Default::default(),
+        read_only: false,
+        auto_index_group_by_fields: false,
+        read_hedge_delay_ms: None,
     };
 
     let config = CollectionConfig {
@@ -59,6 +71,16 @@ async fn _test_snapshot_collection(node_type: NodeType) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+        default_search_params: Default::default(),
+        default_with_payload: Default::default(),
+        default_with_vector: Default::default(),
+        default_max_payload_return_bytes: Default::default(),
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: Default::default(),
     };
 
     let snapshots_path = Builder::new().prefix("test_snapshots").tempdir().unwrap();
@@ -8,6 +8,7 @@ use tokio::sync::RwLock;
 
 use crate::config::{CollectionConfig, CollectionParams, WalConfig};
 use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{VectorParams, VectorsConfig};
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::shards::local_shard::LocalShard;
@@ -27,11 +28,20 @@ fn create_collection_config() -> CollectionConfig {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
         }),
         shard_number: NonZeroU32::new(1).unwrap(),
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        validate_payload: false,
+        payload_validation_mode: Default::default(),
+        sparse_vectors: None,
+        payload_indexes: Default::default(),
+        payload_cache_fields: Default::default(),
+        read_only: false,
+        auto_index_group_by_fields: false,
+        read_hedge_delay_ms: None,
     };
 
     let mut optimizer_config = TEST_OPTIMIZERS_CONFIG.clone();
@@ -45,6 +55,16 @@ fn create_collection_config() -> CollectionConfig {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+        default_search_params: Default::default(),
+        default_with_payload: Default::default(),
+        default_with_vector: Default::default(),
+        default_max_payload_return_bytes: Default::default(),
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: Default::default(),
     }
 }
 
@@ -183,3 +203,59 @@ async fn test_delete_from_indexed_payload() {
     assert_eq!(number_of_indexed_points, 4);
     assert_eq!(number_of_indexed_points_after_load, 3);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_background_wal_recovery() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+    let config = create_collection_config();
+
+    let collection_name = "test".to_string();
+
+    let current_runtime: Handle = Handle::current();
+
+    let shard = LocalShard::build(
+        0,
+        collection_name.clone(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config.clone())),
+        Arc::new(Default::default()),
+        current_runtime.clone(),
+    )
+    .await
+    .unwrap();
+
+    shard.update(upsert_operation(), true).await.unwrap();
+    shard.update(delete_point_operation(4), true).await.unwrap();
+
+    drop(shard);
+
+    let shared_storage_config = SharedStorageConfig {
+        background_wal_recovery: true,
+        ..Default::default()
+    };
+
+    let shard = LocalShard::load(
+        0,
+        collection_name,
+        collection_dir.path(),
+        Arc::new(RwLock::new(config)),
+        Arc::new(shared_storage_config),
+        current_runtime,
+    )
+    .await
+    .unwrap();
+
+    // Wait for the background replay to finish
+    let mut attempts = 0;
+    while shard.get_telemetry_data(false).wal_recovery.is_some() {
+        attempts += 1;
+        assert!(attempts < 100, "WAL recovery did not finish in time");
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    let info = shard.info().await.unwrap();
+    assert_eq!(info.points_count, 4);
+
+    shard.update(delete_point_operation(5), true).await.unwrap();
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use segment::types::{
+    Condition, FieldCondition, Filter, IsEmptyCondition, PayloadField, PayloadKeyType, Range,
+};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
+use validator::Validate;
+
+use crate::config::CollectionConfig;
+use crate::operations::point_ops::{PointOperations, WriteOrdering};
+use crate::operations::types::{CollectionResult, CountRequest};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::shard::PeerId;
+use crate::shards::shard_holder::LockedShardHolder;
+
+const DEFAULT_VACUUM_INTERVAL_SEC: u64 = 60;
+
+/// Expiration of the points by the time, stored in their payload.
+///
+/// Expired points are excluded from searches, scrolls and counts right away, and are deleted
+/// in background later on.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct TtlConfig {
+    /// Payload field with the expiration time of the point, as UNIX timestamp in seconds.
+    /// Points without this field don't expire.
+    /// The field should be indexed as `integer` or `float` to keep the reads fast.
+    #[validate(length(min = 1))]
+    pub field: PayloadKeyType,
+    /// Interval between the deletions of the expired points in seconds. Default: 60
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub vacuum_interval_sec: Option<u64>,
+}
+
+impl TtlConfig {
+    pub fn vacuum_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.vacuum_interval_sec
+                .unwrap_or(DEFAULT_VACUUM_INTERVAL_SEC),
+        )
+    }
+
+    /// Condition, which matches the points, not expired at `now`
+    pub fn alive_condition(&self, now: DateTime<Utc>) -> Condition {
+        let not_expired = FieldCondition::new_range(
+            self.field.clone(),
+            Range {
+                gt: Some(unix_seconds(now)),
+                ..Default::default()
+            },
+        );
+        let no_expiration = IsEmptyCondition {
+            is_empty: PayloadField {
+                key: self.field.clone(),
+            },
+        };
+        Condition::Filter(Filter {
+            should: Some(vec![
+                Condition::Field(not_expired),
+                Condition::IsEmpty(no_expiration),
+            ]),
+            min_should: None,
+            must: None,
+            must_not: None,
+        })
+    }
+
+    /// Filter, which matches the points, expired at `now`
+    pub fn expired_filter(&self, now: DateTime<Utc>) -> Filter {
+        Filter::new_must(Condition::Field(FieldCondition::new_range(
+            self.field.clone(),
+            Range {
+                lte: Some(unix_seconds(now)),
+                ..Default::default()
+            },
+        )))
+    }
+
+    /// Restrict the filter of a read request to the points, not expired at `now`.
+    ///
+    /// The condition is added to the `must` clause, so `should` and `must_not` clauses of the
+    /// request keep their meaning.
+    pub fn exclude_expired(&self, filter: Option<Filter>, now: DateTime<Utc>) -> Filter {
+        let alive = Filter::new_must(self.alive_condition(now));
+        match filter {
+            None => alive,
+            Some(filter) => filter.merge(&alive),
+        }
+    }
+}
+
+fn unix_seconds(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
+
+/// Source of the current time, used to expire the points
+#[derive(Clone)]
+pub struct Clock(Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>);
+
+impl Clock {
+    pub fn new(now: impl Fn() -> DateTime<Utc> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(now))
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        (self.0)()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(Utc::now)
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Clock").field(&self.now()).finish()
+    }
+}
+
+/// Periodically delete the expired points of the collection.
+///
+/// The task only keeps weak references to the collection, so it finishes once the collection
+/// is dropped.
+pub(crate) fn spawn_vacuum_task(
+    shards_holder: Weak<LockedShardHolder>,
+    collection_config: Weak<RwLock<CollectionConfig>>,
+    clock: Clock,
+    this_peer_id: PeerId,
+    runtime: &Handle,
+) {
+    runtime.spawn(async move {
+        loop {
+            let interval = match collection_config.upgrade() {
+                None => return,
+                Some(config) => config
+                    .read()
+                    .await
+                    .ttl_config
+                    .as_ref()
+                    .map_or(Duration::from_secs(DEFAULT_VACUUM_INTERVAL_SEC), |ttl| {
+                        ttl.vacuum_interval()
+                    }),
+            };
+            tokio::time::sleep(interval).await;
+
+            let (Some(shards_holder), Some(config)) =
+                (shards_holder.upgrade(), collection_config.upgrade())
+            else {
+                return;
+            };
+            let Some(ttl_config) = config.read().await.ttl_config.clone() else {
+                continue;
+            };
+            if let Err(err) =
+                vacuum_expired(&shards_holder, &ttl_config, clock.now(), this_peer_id).await
+            {
+                log::warn!("Failed to delete expired points: {err}");
+            }
+        }
+    });
+}
+
+/// Delete the points, expired at `now`, from the shards, which have a local replica.
+///
+/// Deletion of a shard is issued by a single peer, which leads the updates of the shard,
+/// and is applied to the other replicas as a regular update.
+pub(crate) async fn vacuum_expired(
+    shards_holder: &LockedShardHolder,
+    ttl_config: &TtlConfig,
+    now: DateTime<Utc>,
+    this_peer_id: PeerId,
+) -> CollectionResult<()> {
+    let expired_filter = ttl_config.expired_filter(now);
+    let count_request = Arc::new(CountRequest {
+        filter: Some(expired_filter.clone()),
+        exact: true,
+    });
+
+    let shard_holder = shards_holder.read().await;
+    for replica_set in shard_holder.all_shards() {
+        if replica_set.leader_peer_for_update(WriteOrdering::Medium) != Some(this_peer_id) {
+            continue;
+        }
+        // Don't write an operation into WAL every interval, if nothing is expired
+        let expired = replica_set.count_local(count_request.clone()).await?;
+        if expired.map_or(true, |expired| expired.count == 0) {
+            continue;
+        }
+        let operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::DeletePointsByFilter(expired_filter.clone()),
+        );
+        replica_set
+            .update_with_consistency(operation, false, WriteOrdering::Medium)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use segment::index::field_index::FieldIndex;
+    use segment::payload_storage::query_checker::check_payload;
+    use segment::types::Payload;
+    use serde_json::json;
+
+    use super::*;
+
+    fn ttl_config() -> TtlConfig {
+        TtlConfig {
+            field: "expires_at".to_string(),
+            vacuum_interval_sec: None,
+        }
+    }
+
+    fn check(filter: &Filter, payload: serde_json::Value) -> bool {
+        let payload: Payload = payload.into();
+        let field_indexes: HashMap<PayloadKeyType, Vec<FieldIndex>> = HashMap::new();
+        check_payload(
+            Box::new(|| (&payload).into()),
+            None,
+            filter,
+            0,
+            &field_indexes,
+        )
+    }
+
+    #[test]
+    fn test_exclude_expired() {
+        let now = DateTime::parse_from_rfc3339("2023-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let now_sec = now.timestamp();
+
+        let alive = ttl_config().exclude_expired(None, now);
+        assert!(check(&alive, json!({})));
+        assert!(check(&alive, json!({ "expires_at": now_sec + 1 })));
+        assert!(!check(&alive, json!({ "expires_at": now_sec })));
+
+        let expired = ttl_config().expired_filter(now);
+        assert!(!check(&expired, json!({})));
+        assert!(check(&expired, json!({ "expires_at": now_sec - 1 })));
+
+        // `should` of the request still requires one of its conditions to match
+        let request_filter = Filter::new_should(Condition::Field(FieldCondition::new_match(
+            "color",
+            "red".to_string().into(),
+        )));
+        let alive = ttl_config().exclude_expired(Some(request_filter), now);
+        assert!(check(&alive, json!({ "color": "red" })));
+        assert!(!check(&alive, json!({ "color": "blue" })));
+        assert!(!check(
+            &alive,
+            json!({ "color": "red", "expires_at": now_sec - 1 })
+        ));
+    }
+}
@@ -4,11 +4,13 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use log::{debug, error, info, trace, warn};
+use segment::common::cpu::CpuBudget;
 use segment::entry::entry_point::OperationResult;
+use segment::index::hnsw_index::max_rayon_threads;
 use segment::types::SeqNumberType;
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::{self, Receiver, Sender};
-use tokio::sync::{oneshot, Mutex as TokioMutex};
+use tokio::sync::{oneshot, watch, Mutex as TokioMutex};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
@@ -17,8 +19,9 @@ use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
 use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
 use crate::common::stoppable_task::{spawn_stoppable, StoppableTaskHandle};
 use crate::operations::shared_storage_config::SharedStorageConfig;
-use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::types::{CollectionError, CollectionResult, OptimizationPlan};
 use crate::operations::CollectionUpdateOperations;
+use crate::quota::QuotaUsageTracker;
 use crate::shards::local_shard::LockedWal;
 use crate::wal::WalError;
 
@@ -82,6 +85,13 @@ pub struct UpdateHandler {
     wal: LockedWal,
     optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
     max_optimization_threads: usize,
+    /// CPUs, shared by all running optimizations for building indexes
+    cpu_budget: Arc<CpuBudget>,
+    /// Usage of the shard, adjusted by each applied operation
+    quota_usage: Arc<QuotaUsageTracker>,
+    /// Notified whenever the status of the shard might have changed:
+    /// an update is applied, an optimization is started or finished
+    status_changes: Arc<watch::Sender<()>>,
 }
 
 impl UpdateHandler {
@@ -93,6 +103,9 @@ impl UpdateHandler {
         wal: LockedWal,
         flush_interval_sec: u64,
         max_optimization_threads: usize,
+        cpu_budget: Arc<CpuBudget>,
+        quota_usage: Arc<QuotaUsageTracker>,
+        status_changes: Arc<watch::Sender<()>>,
     ) -> UpdateHandler {
         UpdateHandler {
             shared_storage_config,
@@ -107,6 +120,9 @@ impl UpdateHandler {
             flush_interval_sec,
             optimization_handles: Arc::new(TokioMutex::new(vec![])),
             max_optimization_threads,
+            cpu_budget,
+            quota_usage,
+            status_changes,
         }
     }
 
@@ -120,12 +136,16 @@ impl UpdateHandler {
             self.wal.clone(),
             self.optimization_handles.clone(),
             self.max_optimization_threads,
+            self.cpu_budget.clone(),
+            self.status_changes.clone(),
         )));
         self.update_worker = Some(self.runtime_handle.spawn(Self::update_worker_fn(
             update_receiver,
             tx,
             self.wal.clone(),
             self.segments.clone(),
+            self.quota_usage.clone(),
+            self.status_changes.clone(),
         )));
         let (flush_tx, flush_rx) = oneshot::channel();
         self.flush_worker = Some(self.runtime_handle.spawn(Self::flush_worker(
@@ -189,11 +209,22 @@ impl UpdateHandler {
         Ok(0)
     }
 
+    /// Returns the optimization, which `launch_optimization` would start first, without running it
+    pub(crate) fn plan_optimization(
+        optimizers: &[Arc<Optimizer>],
+        segments: LockedSegmentHolder,
+    ) -> Option<OptimizationPlan> {
+        optimizers
+            .iter()
+            .find_map(|optimizer| optimizer.plan_optimization(segments.clone(), &HashSet::new()))
+    }
+
     /// Checks conditions for all optimizers until there is no suggested segment
-    /// Starts a task for each optimization
+    /// Starts a task for each optimization, as long as there are free CPUs in the `cpu_budget`
     /// Returns handles for started tasks
     pub(crate) fn launch_optimization<F>(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
+        cpu_budget: &Arc<CpuBudget>,
         segments: LockedSegmentHolder,
         callback: F,
     ) -> Vec<StoppableTaskHandle<bool>>
@@ -204,13 +235,24 @@ impl UpdateHandler {
     {
         let mut scheduled_segment_ids: HashSet<_> = Default::default();
         let mut handles = vec![];
-        for optimizer in optimizers.iter() {
+        'outer: for optimizer in optimizers.iter() {
             loop {
                 let nonoptimal_segment_ids =
                     optimizer.check_condition(segments.clone(), &scheduled_segment_ids);
                 if nonoptimal_segment_ids.is_empty() {
                     break;
                 } else {
+                    let desired_cpus =
+                        max_rayon_threads(optimizer.hnsw_config().max_indexing_threads);
+                    let permit = match cpu_budget.try_acquire(desired_cpus) {
+                        Some(permit) => permit,
+                        None => {
+                            // All CPUs are busy with other optimizations.
+                            // Remaining optimizations will be launched once some of them finish
+                            debug!("No free CPUs for optimization, postponing");
+                            break 'outer;
+                        }
+                    };
                     let optim = optimizer.clone();
                     let segs = segments.clone();
                     let nsi = nonoptimal_segment_ids.clone();
@@ -220,7 +262,7 @@ impl UpdateHandler {
                     let callback_cloned = callback.clone();
 
                     handles.push(spawn_stoppable(move |stopped| {
-                        match optim.as_ref().optimize(segs.clone(), nsi, stopped) {
+                        match optim.as_ref().optimize(segs.clone(), nsi, permit, stopped) {
                             Ok(result) => {
                                 callback_cloned(result); // Perform some actions when optimization if finished
                                 result
@@ -253,14 +295,19 @@ impl UpdateHandler {
 
     pub(crate) async fn process_optimization(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
+        cpu_budget: &Arc<CpuBudget>,
         segments: LockedSegmentHolder,
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
         sender: Sender<OptimizerSignal>,
+        status_changes: Arc<watch::Sender<()>>,
     ) {
+        let finished_status_changes = status_changes.clone();
         let mut new_handles = Self::launch_optimization(
             optimizers.clone(),
+            cpu_budget,
             segments.clone(),
             move |_optimization_result| {
+                finished_status_changes.send_replace(());
                 // After optimization is finished, we still need to check if there are
                 // some further optimizations possible.
                 // If receiver is already dead - we do not care.
@@ -268,6 +315,9 @@ impl UpdateHandler {
                 let _ = sender.try_send(OptimizerSignal::Nop);
             },
         );
+        if !new_handles.is_empty() {
+            status_changes.send_replace(());
+        }
         let mut handles = optimization_handles.lock().await;
         handles.append(&mut new_handles);
         handles.retain(|h| !h.is_finished())
@@ -281,6 +331,8 @@ impl UpdateHandler {
         wal: LockedWal,
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
         max_handles: usize,
+        cpu_budget: Arc<CpuBudget>,
+        status_changes: Arc<watch::Sender<()>>,
     ) {
         while let Some(signal) = receiver.recv().await {
             match signal {
@@ -302,9 +354,11 @@ impl UpdateHandler {
                     }
                     Self::process_optimization(
                         optimizers.clone(),
+                        &cpu_budget,
                         segments.clone(),
                         optimization_handles.clone(),
                         sender.clone(),
+                        status_changes.clone(),
                     )
                     .await;
                 }
@@ -318,6 +372,8 @@ impl UpdateHandler {
         optimize_sender: Sender<OptimizerSignal>,
         wal: LockedWal,
         segments: LockedSegmentHolder,
+        quota_usage: Arc<QuotaUsageTracker>,
+        status_changes: Arc<watch::Sender<()>>,
     ) {
         while let Some(signal) = receiver.recv().await {
             match signal {
@@ -338,8 +394,11 @@ impl UpdateHandler {
                         Ok(())
                     };
 
-                    let operation_result = flush_res
-                        .and_then(|_| CollectionUpdater::update(&segments, op_num, operation));
+                    let operation_result = flush_res.and_then(|_| {
+                        quota_usage.apply(&segments, operation, |operation| {
+                            CollectionUpdater::update(&segments, op_num, operation)
+                        })
+                    });
 
                     let res = match operation_result {
                         Ok(update_res) => optimize_sender
@@ -349,6 +408,7 @@ impl UpdateHandler {
                             .map_err(|send_err| send_err.into()),
                         Err(err) => Err(err),
                     };
+                    status_changes.send_replace(());
 
                     if let Some(feedback) = sender {
                         feedback.send(res).unwrap_or_else(|_| {
@@ -406,40 +466,51 @@ impl UpdateHandler {
             };
 
             trace!("Attempting flushing");
-            let wal_flash_job = wal.lock().flush_async();
-
-            if let Err(err) = wal_flash_job.join() {
-                error!("Failed to flush wal: {:?}", err);
-                segments
-                    .write()
-                    .report_optimizer_error(WalError::WriteWalError(format!(
-                        "WAL flush error: {err:?}"
-                    )));
-                continue;
+            if let Err(err) = Self::flush_wal_and_segments(&segments, &wal, false) {
+                error!("Failed to flush: {err}");
+                segments.write().report_optimizer_error(err);
             }
+        }
+    }
 
-            let confirmed_version = Self::flush_segments(segments.clone());
-            let confirmed_version = match confirmed_version {
-                Ok(version) => version,
-                Err(err) => {
-                    error!("Failed to flush: {err}");
-                    segments.write().report_optimizer_error(err);
-                    continue;
-                }
-            };
-            if let Err(err) = wal.lock().ack(confirmed_version) {
-                segments.write().report_optimizer_error(err);
+    /// Flushes WAL and all segments, then acknowledges the flushed operations in WAL
+    ///
+    /// If `sync` is false, segments, which are already flushing in the background, are skipped.
+    /// Returns the number of the last operation, which is persisted in all segments.
+    ///
+    /// # Errors
+    /// Returns an error on flush failure, naming the failed component
+    pub(crate) fn flush_wal_and_segments(
+        segments: &LockedSegmentHolder,
+        wal: &LockedWal,
+        sync: bool,
+    ) -> CollectionResult<SeqNumberType> {
+        let wal_flush_job = wal.lock().flush_async();
+        match wal_flush_job.join() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                return Err(WalError::WriteWalError(format!("WAL flush error: {err}")).into())
+            }
+            Err(err) => {
+                return Err(WalError::WriteWalError(format!("WAL flush error: {err:?}")).into())
             }
         }
+
+        let confirmed_version = Self::flush_segments(segments, sync)?;
+        wal.lock().ack(confirmed_version)?;
+        Ok(confirmed_version)
     }
 
     /// Returns confirmed version after flush of all segments
     ///
     /// # Errors
     /// Returns an error on flush failure
-    fn flush_segments(segments: LockedSegmentHolder) -> OperationResult<SeqNumberType> {
+    fn flush_segments(
+        segments: &LockedSegmentHolder,
+        sync: bool,
+    ) -> OperationResult<SeqNumberType> {
         let read_segments = segments.read();
-        let flushed_version = read_segments.flush_all(false)?;
+        let flushed_version = read_segments.flush_all(sync)?;
         Ok(match read_segments.failed_operation.iter().cloned().min() {
             None => flushed_version,
             Some(failed_operation) => min(failed_operation, flushed_version),
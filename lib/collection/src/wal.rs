@@ -56,6 +56,15 @@ impl WalState {
     }
 }
 
+/// Records, which are kept in the WAL when older records are acknowledged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalRetention {
+    /// Sequence number of the oldest retained record
+    pub retain_from: u64,
+    /// Max number of retained records. Older records are removed anyway
+    pub max_records: u64,
+}
+
 /// Write-Ahead-Log wrapper with built-in type parsing.
 /// Stores sequences of records of type `R` in binary files.
 ///
@@ -67,6 +76,7 @@ pub struct SerdeWal<R> {
     wal: Wal,
     options: WalOptions,
     first_index: Option<u64>,
+    retention: Option<WalRetention>,
 }
 
 const FIRST_INDEX_FILE: &str = "first-index";
@@ -97,6 +107,7 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
             wal,
             options: wal_options,
             first_index,
+            retention: None,
         })
     }
 
@@ -164,6 +175,16 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     /// * `until_index` - the newest no longer required record sequence number
     ///
     pub fn ack(&mut self, until_index: u64) -> Result<()> {
+        let until_index = match self.retention {
+            Some(retention) => {
+                let retain_from = retention
+                    .retain_from
+                    .max(self.last_index().saturating_sub(retention.max_records));
+                until_index.min(retain_from.saturating_sub(1))
+            }
+            None => until_index,
+        };
+
         // Truncate WAL
         self.wal
             .prefix_truncate(until_index)
@@ -217,6 +238,11 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         self.wal.path()
     }
 
+    /// Keep the records, required by `retention`, on the following `ack` calls
+    pub fn set_retention(&mut self, retention: Option<WalRetention>) {
+        self.retention = retention;
+    }
+
     pub fn first_index(&self) -> u64 {
         self.first_index.unwrap_or_else(|| self.wal.first_index())
     }
@@ -293,4 +319,40 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_wal_retention() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 1024 * 1024,
+            segment_queue_len: 0,
+        };
+
+        let mut serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options).unwrap();
+        for data in 0..10 {
+            let record = TestRecord::Struct1(TestInternalStruct1 { data });
+            serde_wal.write(&record).expect("Can't write");
+        }
+
+        serde_wal.set_retention(Some(WalRetention {
+            retain_from: 4,
+            max_records: 100,
+        }));
+        serde_wal.ack(8).unwrap();
+        assert_eq!(serde_wal.first_index(), 3);
+        assert_eq!(serde_wal.read(4).count(), 6);
+
+        // Too many records to retain
+        serde_wal.set_retention(Some(WalRetention {
+            retain_from: 4,
+            max_records: 2,
+        }));
+        serde_wal.ack(8).unwrap();
+        assert_eq!(serde_wal.first_index(), serde_wal.last_index() - 3);
+
+        serde_wal.set_retention(None);
+        serde_wal.ack(8).unwrap();
+        assert_eq!(serde_wal.first_index(), 8);
+    }
 }
@@ -0,0 +1,316 @@
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rand::Rng;
+use schemars::JsonSchema;
+use segment::common::warmup::WarmupTask;
+use segment::data_types::vectors::NamedVector;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
+use validator::Validate;
+
+use crate::common::io_throttle::IoThrottle;
+use crate::common::stoppable_task_async::{spawn_async_stoppable, StoppableAsyncTaskHandle};
+use crate::config::CollectionConfig;
+use crate::operations::types::{CollectionResult, SearchRequest, SearchRequestBatch};
+use crate::shards::shard::ShardId;
+use crate::shards::shard_holder::LockedShardHolder;
+
+/// Number of the points, returned by each synthetic search
+const WARMUP_SEARCH_LIMIT: usize = 10;
+
+/// Warmup of the collections, which reads their data into memory
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct WarmupConfig {
+    /// If true - local shards of the collections are warmed up in background after the start
+    #[serde(default)]
+    pub on_load: bool,
+    /// Max speed of reading the data in megabytes per second.
+    /// If not set - reading is not throttled
+    #[serde(default)]
+    pub max_read_mb_per_sec: Option<usize>,
+    /// Number of searches with random vectors, made for each vector of each local shard
+    /// after the data is read
+    #[serde(default)]
+    pub searches_per_vector: usize,
+}
+
+/// Read the data of the local shards of the collection into memory, in background.
+/// Unset parameters are taken from the warmup config of the node
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct WarmupRequest {
+    /// Max speed of reading the data in megabytes per second
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_read_mb_per_sec: Option<usize>,
+    /// Number of searches with random vectors, made for each vector of each local shard
+    /// after the data is read. Populates the caches of the vector indexes
+    #[serde(default)]
+    #[validate(range(max = 1000))]
+    pub searches_per_vector: Option<usize>,
+}
+
+impl WarmupRequest {
+    /// Parameters of the request, with unset ones taken from the `config`
+    fn merge(self, config: &WarmupConfig) -> WarmupConfig {
+        WarmupConfig {
+            on_load: config.on_load,
+            max_read_mb_per_sec: self.max_read_mb_per_sec.or(config.max_read_mb_per_sec),
+            searches_per_vector: self
+                .searches_per_vector
+                .unwrap_or(config.searches_per_vector),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupState {
+    InProgress,
+    Finished,
+    Cancelled,
+    Failed,
+}
+
+/// Progress of the warmup of the local shards of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct WarmupProgress {
+    pub state: WarmupState,
+    /// Number of segments, which data is read completely
+    pub segments_warmed: usize,
+    /// Number of segments of the local shards at the start of the warmup
+    pub segments_total: usize,
+    /// Amount of data, read so far
+    pub bytes_touched: usize,
+    /// Number of searches with random vectors, made so far
+    pub searches_done: usize,
+    /// Reason of the failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Default for WarmupProgress {
+    fn default() -> Self {
+        Self {
+            state: WarmupState::InProgress,
+            segments_warmed: 0,
+            segments_total: 0,
+            bytes_touched: 0,
+            searches_done: 0,
+            error: None,
+        }
+    }
+}
+
+/// Warmup of the local shards of a collection, either running or finished
+pub(crate) struct Warmup {
+    progress: Arc<Mutex<WarmupProgress>>,
+    task: StoppableAsyncTaskHandle<()>,
+}
+
+impl Warmup {
+    pub fn start(
+        shards_holder: Arc<LockedShardHolder>,
+        collection_config: Arc<RwLock<CollectionConfig>>,
+        search_runtime: Handle,
+        request: WarmupRequest,
+        config: &WarmupConfig,
+    ) -> Self {
+        let params = request.merge(config);
+        let progress = Arc::new(Mutex::new(WarmupProgress::default()));
+        let task_progress = progress.clone();
+        let task = spawn_async_stoppable(move |stopped| async move {
+            let result = warmup_shards(
+                &shards_holder,
+                &collection_config,
+                &search_runtime,
+                &params,
+                &task_progress,
+                stopped,
+            )
+            .await;
+
+            let mut progress = task_progress.lock();
+            progress.state = match result {
+                Ok(true) => WarmupState::Finished,
+                Ok(false) => WarmupState::Cancelled,
+                Err(err) => {
+                    log::warn!("Warmup failed: {err}");
+                    progress.error = Some(err.to_string());
+                    WarmupState::Failed
+                }
+            };
+        });
+        Self { progress, task }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Ask the warmup to stop. It stops after the current chunk of data or search
+    pub fn cancel(&self) {
+        self.task.ask_to_stop();
+    }
+
+    pub fn progress(&self) -> WarmupProgress {
+        self.progress.lock().clone()
+    }
+}
+
+impl Drop for Warmup {
+    fn drop(&mut self) {
+        // Don't keep reading the data of a deleted collection
+        self.cancel();
+    }
+}
+
+/// Returns `false` if the warmup is stopped before it is complete
+async fn warmup_shards(
+    shards_holder: &LockedShardHolder,
+    collection_config: &RwLock<CollectionConfig>,
+    search_runtime: &Handle,
+    params: &WarmupConfig,
+    progress: &Arc<Mutex<WarmupProgress>>,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<bool> {
+    // Memory maps and columns are shared with the segments, so no locks are held while reading
+    let mut shard_ids = vec![];
+    let mut segments = vec![];
+    for (&shard_id, replica_set) in shards_holder.read().await.get_shards() {
+        let shard_segments = replica_set.local_warmup_tasks().await;
+        if !shard_segments.is_empty() {
+            shard_ids.push(shard_id);
+            segments.extend(shard_segments);
+        }
+    }
+    progress.lock().segments_total = segments.len();
+
+    let max_read_mb_per_sec = params.max_read_mb_per_sec;
+    let read_progress = progress.clone();
+    let read_stopped = stopped.clone();
+    let is_read = tokio::task::spawn_blocking(move || {
+        read_segments(segments, max_read_mb_per_sec, &read_progress, &read_stopped)
+    })
+    .await??;
+    if !is_read {
+        return Ok(false);
+    }
+
+    if params.searches_per_vector == 0 {
+        return Ok(true);
+    }
+
+    let vectors: Vec<(String, NonZeroU64)> = collection_config
+        .read()
+        .await
+        .params
+        .vectors
+        .params_iter()
+        .map(|(name, params)| (name.to_string(), params.size))
+        .collect();
+    for shard_id in shard_ids {
+        for (name, size) in &vectors {
+            if stopped.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let request = random_searches(name, size.get() as usize, params.searches_per_vector);
+            search_shard(shards_holder, shard_id, request, search_runtime).await?;
+            progress.lock().searches_done += params.searches_per_vector;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Read the data of the segments one by one.
+/// Returns `false` if `stopped` is set before all of the data is read.
+fn read_segments(
+    segments: Vec<Vec<WarmupTask>>,
+    max_read_mb_per_sec: Option<usize>,
+    progress: &Mutex<WarmupProgress>,
+    stopped: &AtomicBool,
+) -> CollectionResult<bool> {
+    let mut throttle = IoThrottle::new(max_read_mb_per_sec);
+    for tasks in segments {
+        for task in tasks {
+            let is_read = task.exec(|bytes| {
+                progress.lock().bytes_touched += bytes;
+                throttle.consume(bytes);
+                !stopped.load(Ordering::Relaxed)
+            })?;
+            if !is_read {
+                return Ok(false);
+            }
+        }
+        progress.lock().segments_warmed += 1;
+    }
+    Ok(true)
+}
+
+async fn search_shard(
+    shards_holder: &LockedShardHolder,
+    shard_id: ShardId,
+    request: SearchRequestBatch,
+    search_runtime: &Handle,
+) -> CollectionResult<()> {
+    let shards_holder = shards_holder.read().await;
+    // The shard might be moved from this peer in the meantime
+    if let Some(replica_set) = shards_holder.get_shard(&shard_id) {
+        replica_set
+            .search_local(Arc::new(request), search_runtime)
+            .await?;
+    }
+    Ok(())
+}
+
+fn random_searches(vector_name: &str, size: usize, count: usize) -> SearchRequestBatch {
+    let mut rng = rand::thread_rng();
+    let searches = (0..count)
+        .map(|_| SearchRequest {
+            vector: NamedVector {
+                name: vector_name.to_string(),
+                vector: (0..size).map(|_| rng.gen_range(-1.0..1.0)).collect(),
+            }
+            .into(),
+            filter: None,
+            params: None,
+            limit: WARMUP_SEARCH_LIMIT,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        })
+        .collect();
+    SearchRequestBatch { searches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warmup_request_merge() {
+        let config = WarmupConfig {
+            on_load: true,
+            max_read_mb_per_sec: Some(100),
+            searches_per_vector: 5,
+        };
+        let request = WarmupRequest {
+            max_read_mb_per_sec: None,
+            searches_per_vector: Some(0),
+        };
+        let params = request.merge(&config);
+        assert_eq!(params.max_read_mb_per_sec, Some(100));
+        assert_eq!(params.searches_per_vector, 0);
+    }
+}
@@ -90,6 +90,8 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: true.into(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -161,6 +163,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Fields(vec![String::from("k2")])),
                 with_vector: true.into(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -195,6 +199,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(PayloadSelectorExclude::new(vec!["k1".to_string()]).into()),
                 with_vector: false.into(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -66,6 +66,10 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
         limit: 3,
         offset: 0,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let search_res = collection.search(search_request, None, None).await;
@@ -123,6 +127,10 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
         limit: 3,
         offset: 0,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let search_res = collection.search(search_request, None, None).await;
@@ -154,6 +162,8 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
 
     let count_res = collection.count(count_request, None).await.unwrap();
     assert_eq!(count_res.count, 1);
+    assert!(count_res.exact);
+    assert_eq!((count_res.min, count_res.max), (1, 1));
 }
 
 // FIXME: dos not work
@@ -218,6 +228,8 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
         ids: vec![1.into(), 2.into()],
         with_payload: Some(WithPayloadInterface::Bool(true)),
         with_vector: true.into(),
+        with_timestamps: false,
+        max_payload_return_bytes: None,
     };
     let retrieved = loaded_collection
         .retrieve(request, None, None)
@@ -385,6 +397,8 @@ async fn test_read_api_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: false.into(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -441,6 +455,7 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     let to_be_deleted: HashSet<PointIdType> = vec![0.into(), 3.into()].into_iter().collect();
     let delete_filter = segment::types::Filter {
         should: None,
+        min_should: None,
         must: Some(vec![Condition::HasId(HasIdCondition::from(to_be_deleted))]),
         must_not: None,
     };
@@ -455,7 +470,10 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
 
     match delete_result {
         Ok(res) => {
-            assert_eq!(res.status, UpdateStatus::Completed)
+            assert_eq!(res.status, UpdateStatus::Completed);
+            // Count is summed over the shards
+            assert_eq!(res.deleted_count, Some(2));
+            assert_eq!(res.counted_before_apply, Some(false));
         }
         Err(err) => panic!("operation failed: {err:?}"),
     }
@@ -468,6 +486,8 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: false.into(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use collection::collection::{Collection, RequestShardTransfer};
 use collection::config::{CollectionConfig, CollectionParams, WalConfig};
+use collection::operations::shared_storage_config::SharedStorageConfig;
 use collection::operations::types::{CollectionError, VectorParams};
 use collection::optimizers_builder::OptimizersConfig;
 use collection::shards::channel_service::ChannelService;
@@ -25,15 +26,35 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     vacuum_min_vector_number: 1000,
     default_segment_number: 2,
     max_segment_size: None,
+    max_segment_number: None,
+    target_segment_size: None,
     memmap_threshold: None,
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    max_indexing_threads: None,
 };
 
 #[cfg(test)]
 #[allow(dead_code)]
 pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32) -> Collection {
+    let collection_config = simple_collection_config(shard_number);
+    let snapshot_path = collection_path.join("snapshots");
+
+    // Default to a collection with all the shards local
+    new_local_collection(
+        "test".to_string(),
+        collection_path,
+        &snapshot_path,
+        &collection_config,
+    )
+    .await
+    .unwrap()
+}
+
+/// Config of the collection of [`simple_collection_fixture`]
+#[allow(dead_code)]
+pub fn simple_collection_config(shard_number: u32) -> CollectionConfig {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
@@ -46,33 +67,40 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
         }
         .into(),
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        validate_payload: false,
+        payload_validation_mode: Default::default(),
+        sparse_vectors: None,
+        payload_indexes: Default::default(),
+        payload_cache_fields: Default::default(),
+        read_only: false,
+        auto_index_group_by_fields: false,
+        read_hedge_delay_ms: None,
     };
 
-    let collection_config = CollectionConfig {
+    CollectionConfig {
         params: collection_params,
         optimizer_config: TEST_OPTIMIZERS_CONFIG.clone(),
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
-    };
-
-    let snapshot_path = collection_path.join("snapshots");
-
-    // Default to a collection with all the shards local
-    new_local_collection(
-        "test".to_string(),
-        collection_path,
-        &snapshot_path,
-        &collection_config,
-    )
-    .await
-    .unwrap()
+        strict_mode_config: Default::default(),
+        default_search_params: Default::default(),
+        default_with_payload: Default::default(),
+        default_with_vector: Default::default(),
+        default_max_payload_return_bytes: Default::default(),
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: Default::default(),
+    }
 }
 
 pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -90,6 +118,19 @@ pub async fn new_local_collection(
     path: &Path,
     snapshots_path: &Path,
     config: &CollectionConfig,
+) -> Result<Collection, CollectionError> {
+    new_local_collection_with_shared_config(id, path, snapshots_path, config, Default::default())
+        .await
+}
+
+/// Collection with all the shards local, which shares the given storage config, e.g. its clock
+#[cfg(test)]
+pub async fn new_local_collection_with_shared_config(
+    id: CollectionId,
+    path: &Path,
+    snapshots_path: &Path,
+    config: &CollectionConfig,
+    shared_storage_config: Arc<SharedStorageConfig>,
 ) -> Result<Collection, CollectionError> {
     let collection = Collection::new(
         id,
@@ -97,7 +138,7 @@ pub async fn new_local_collection(
         path,
         snapshots_path,
         config,
-        Default::default(),
+        shared_storage_config,
         CollectionShardDistribution::all_local(Some(config.params.shard_number.into()), 0),
         ChannelService::default(),
         dummy_on_replica_failure(),
@@ -1,8 +1,10 @@
 use collection::collection::Collection;
-use collection::grouping::group_by::{group_by, GroupRequest, SourceRequest};
+use collection::grouping::group_by::{group_by, group_by_with_stats, GroupRequest, SourceRequest};
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::point_ops::{Batch, WriteOrdering};
-use collection::operations::types::{RecommendRequest, SearchRequest, UpdateStatus};
+use collection::operations::types::{
+    CollectionError, PointGroup, RecommendRequest, SearchRequest, UpdateStatus,
+};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
 use itertools::Itertools;
@@ -42,9 +44,14 @@ mod group_by {
             with_payload: None,
             with_vector: None,
             score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
         });
 
-        let request = GroupRequest::with_limit_from_request(source, "docId".to_string(), 3);
+        let request =
+            GroupRequest::with_limit_from_request(source, "docId".to_string(), 3).unwrap();
 
         let collection_dir = tempfile::Builder::new()
             .prefix("collection")
@@ -145,7 +152,8 @@ mod group_by {
             }),
             "docId".to_string(),
             2,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             request.clone(),
@@ -205,10 +213,15 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             3,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request,
@@ -240,10 +253,15 @@ mod group_by {
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: Some(WithVector::Bool(true)),
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             3,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -286,10 +304,15 @@ mod group_by {
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: Some(WithVector::Bool(true)),
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "other_stuff".to_string(),
             3,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -330,10 +353,15 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             0,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -370,10 +398,15 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             3,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -410,10 +443,15 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             3,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -454,10 +492,15 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                with_usage: false,
+                allow_partial_results: false,
+                with_shard_trace: false,
+                max_payload_return_bytes: None,
             }),
             "docId".to_string(),
             400,
-        );
+        )
+        .unwrap();
 
         let result = group_by(
             group_by_request.clone(),
@@ -478,17 +521,291 @@ mod group_by {
             assert_eq!(group.hits.len(), group_by_request.group_size);
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stats_of_internal_requests() {
+        let resources = setup(16, 8).await;
+
+        let (result, stats) = group_by_with_stats(
+            resources.request.clone(),
+            &resources.collection,
+            |_name| async { unreachable!() },
+            resources.read_consistency,
+            resources.shard_selection,
+        )
+        .await
+        .unwrap();
+
+        let group_req = resources.request;
+
+        assert!(stats.groups_requests >= 1);
+        assert_eq!(
+            stats.fetched_points.len(),
+            stats.groups_requests + stats.filling_requests
+        );
+        assert!(stats.groups_created >= result.len());
+        assert_eq!(
+            stats.groups_filled,
+            result
+                .iter()
+                .filter(|group| group.hits.len() == group_req.group_size)
+                .count()
+        );
+        // integer keys are always matched
+        assert_eq!(stats.dropped_filter_keys, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn oversampling_of_skewed_groups() {
+        let collection_dir = tempfile::Builder::new()
+            .prefix("collection")
+            .tempdir()
+            .unwrap();
+        let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+        // Points are scored in the order of their ids. The best 8 points and 152 of the worst
+        // ones, 91% in total, are in group 0. Each of the groups 1..=8 has a point among the
+        // next 8 ones, and another one among the 8 after them.
+        let points = 176;
+        let group_of = |id: u64| match id {
+            8..=15 => id - 7,
+            16..=23 => id - 15,
+            _ => 0,
+        };
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: (0..points).map(|id| id.into()).collect_vec(),
+                vectors: (0..points)
+                    .map(|id| vec![(1000 - id) as f32, 0.0, 0.0, 0.0])
+                    .collect_vec()
+                    .into(),
+                payloads: (0..points)
+                    .map(|id| Some(Payload::from(json!({ "docId": group_of(id) }))))
+                    .collect_vec()
+                    .into(),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        let source = SourceRequest::Search(SearchRequest {
+            vector: vec![1.0, 0.0, 0.0, 0.0].into(),
+            filter: None,
+            params: None,
+            limit: 4,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        });
+        let request =
+            GroupRequest::with_limit_from_request(source, "docId".to_string(), 2).unwrap();
+
+        let (result, stats) = group_by_with_stats(
+            request.clone(),
+            &collection,
+            |_name| async { unreachable!() },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let keys = result.iter().map(|group| group.id.as_u64()).collect_vec();
+        assert_eq!(keys, vec![Some(0), Some(1), Some(2), Some(3)]);
+        assert!(result.iter().all(|group| group.hits.len() == 2));
+
+        // The first request finds only group 0. With a fixed factor the second one would fetch
+        // only the first points of the groups 1..=8 and a third request would be needed.
+        assert_eq!(stats.oversampling_factors, vec![1.0, 2.0]);
+        assert_eq!(stats.fetched_points, vec![8, 16]);
+        assert_eq!(stats.groups_requests, 2);
+        assert_eq!(stats.filling_requests, 0);
+
+        // A large enough initial factor finds all of the groups at once
+        let mut request = request;
+        request.oversampling = 3.0;
+        let (_, stats) = group_by_with_stats(
+            request,
+            &collection,
+            |_name| async { unreachable!() },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(stats.oversampling_factors, vec![3.0]);
+        assert_eq!(stats.fetched_points, vec![24]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn explicit_group_keys() {
+        let resources = setup(16, 8).await;
+
+        let mut request = resources.request.clone();
+        request.with_stats = true;
+        request.group_keys = Some(vec![json!(7), json!(100), json!(2)]);
+
+        let (result, stats) = group_by_with_stats(
+            request.clone(),
+            &resources.collection,
+            |_name| async { unreachable!() },
+            resources.read_consistency,
+            resources.shard_selection,
+        )
+        .await
+        .unwrap();
+
+        // Groups are returned in the order of the keys, the missing one is empty
+        let keys = result.iter().map(|group| group.id.as_u64()).collect_vec();
+        assert_eq!(keys, vec![Some(7), Some(100), Some(2)]);
+        assert_eq!(result[0].hits.len(), request.group_size);
+        assert!(result[1].hits.is_empty());
+        assert_eq!(result[2].hits.len(), request.group_size);
+
+        for group in [&result[0], &result[2]] {
+            let mut last_score = f32::MAX;
+            for hit in &group.hits {
+                assert!(hit.score <= last_score);
+                last_score = hit.score;
+            }
+        }
+
+        assert_eq!(stats.groups_requests, 0);
+        assert_eq!(stats.filling_requests, 3);
+        assert_eq!(stats.groups_filled, 2);
+
+        request.group_keys = Some(vec![json!(7), json!(1.5)]);
+        let result = group_by(
+            request,
+            &resources.collection,
+            |_name| async { unreachable!() },
+            resources.read_consistency,
+            resources.shard_selection,
+        )
+        .await;
+
+        match result {
+            Err(CollectionError::UnsupportedGroupKey { key, .. }) => assert_eq!(key, "1.5"),
+            other => panic!("expected unsupported group key, got {other:?}"),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn group_keys_round_trip() {
+        let resources = setup(16, 8).await;
+
+        let groups = group_by(
+            resources.request.clone(),
+            &resources.collection,
+            |_name| async { unreachable!() },
+            resources.read_consistency,
+            resources.shard_selection,
+        )
+        .await
+        .unwrap();
+
+        // Integer keys are returned as numbers
+        let keys = groups
+            .iter()
+            .map(|group| serde_json::to_value(&group.id).unwrap())
+            .collect_vec();
+        assert!(keys.iter().all(|key| key.is_u64()), "{keys:?}");
+
+        let group_hits = |groups: &[PointGroup]| {
+            groups
+                .iter()
+                .map(|group| {
+                    let ids = group.hits.iter().map(|hit| hit.id).sorted().collect_vec();
+                    (group.id.clone(), ids)
+                })
+                .collect_vec()
+        };
+
+        // Keys are accepted as they are returned, and in the stringified form of older clients
+        let stringified_keys = keys.iter().map(|key| json!(key.to_string())).collect_vec();
+        for group_keys in [keys, stringified_keys] {
+            let mut request = resources.request.clone();
+            request.group_keys = Some(group_keys);
+            let restricted = group_by(
+                request,
+                &resources.collection,
+                |_name| async { unreachable!() },
+                resources.read_consistency,
+                resources.shard_selection,
+            )
+            .await
+            .unwrap();
+            assert_eq!(group_hits(&restricted), group_hits(&groups));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn collection_deleted_during_group_by() {
+        let resources = setup(16, 8).await;
+        let collection = &resources.collection;
+
+        let (result, _) = tokio::join!(
+            group_by(
+                resources.request.clone(),
+                collection,
+                |_name| async { unreachable!() },
+                resources.read_consistency,
+                resources.shard_selection,
+            ),
+            async {
+                tokio::task::yield_now().await;
+                collection.mark_deleted();
+            },
+        );
+
+        // Depending on the timing the request either completes or fails with a clean error
+        match result {
+            Ok(_) | Err(CollectionError::NotFound { .. }) => {}
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+
+        let result = group_by(
+            resources.request.clone(),
+            collection,
+            |_name| async { unreachable!() },
+            resources.read_consistency,
+            resources.shard_selection,
+        )
+        .await;
+
+        match result {
+            Err(CollectionError::NotFound { what }) => assert!(what.contains(&collection.name())),
+            other => panic!("expected collection not found, got {other:?}"),
+        }
+    }
 }
 
 /// Tests out the different features working together. The individual features are already tested in other places.
 mod group_by_builder {
 
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+
+    use chrono::{TimeZone, Utc};
     use collection::grouping::GroupBy;
     use collection::lookup::types::PseudoId;
     use collection::lookup::WithLookup;
+    use collection::operations::shared_storage_config::SharedStorageConfig;
+    use collection::operations::types::{ErrorReason, LookupLocation};
+    use collection::read_cache::ReadCacheConfig;
+    use collection::ttl::{Clock, TtlConfig};
     use tokio::sync::RwLock;
 
     use super::*;
+    use crate::common::{new_local_collection_with_shared_config, simple_collection_config};
 
     const BODY_TEXT: &str = "lorem ipsum dolor sit amet";
 
@@ -511,9 +828,14 @@ mod group_by_builder {
             with_payload: None,
             with_vector: None,
             score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
         });
 
-        let request = GroupRequest::with_limit_from_request(source_request, "docId".to_string(), 3);
+        let request =
+            GroupRequest::with_limit_from_request(source_request, "docId".to_string(), 3).unwrap();
 
         let collection_dir = tempfile::Builder::new().prefix("chunks").tempdir().unwrap();
         let collection = simple_collection_fixture(collection_dir.path(), 1).await;
@@ -586,6 +908,112 @@ mod group_by_builder {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn expired_points_are_not_served_from_read_cache() {
+        let now_sec = Arc::new(AtomicI64::new(1_700_000_000));
+        let clock = {
+            let now_sec = now_sec.clone();
+            Clock::new(move || {
+                Utc.timestamp_opt(now_sec.load(Ordering::Relaxed), 0)
+                    .unwrap()
+            })
+        };
+        let shared_storage_config = SharedStorageConfig {
+            clock,
+            ..Default::default()
+        };
+
+        let collection_dir = tempfile::Builder::new()
+            .prefix("collection")
+            .tempdir()
+            .unwrap();
+        let collection = new_local_collection_with_shared_config(
+            "test".to_string(),
+            collection_dir.path(),
+            &collection_dir.path().join("snapshots"),
+            &simple_collection_config(1),
+            Arc::new(shared_storage_config),
+        )
+        .await
+        .unwrap();
+        collection
+            .update_ttl_config(TtlConfig {
+                field: "expires_at".to_string(),
+                vacuum_interval_sec: None,
+            })
+            .await
+            .unwrap();
+        collection
+            .update_read_cache_config(ReadCacheConfig {
+                max_entries: Some(10),
+                ttl_sec: None,
+            })
+            .await
+            .unwrap();
+
+        // Point 0 expires in 10 seconds, the others - in a day
+        let start = now_sec.load(Ordering::Relaxed);
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: (0..4u64).map(|id| id.into()).collect_vec(),
+                vectors: (0..4)
+                    .map(|_| vec![1.0, 0.0, 0.0, 0.0])
+                    .collect_vec()
+                    .into(),
+                payloads: (0..4)
+                    .map(|id| {
+                        let expires_at = if id == 0 { start + 10 } else { start + 86_400 };
+                        Some(Payload::from(
+                            json!({ "docId": id, "expires_at": expires_at }),
+                        ))
+                    })
+                    .collect_vec()
+                    .into(),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        let source_request = SourceRequest::Search(SearchRequest {
+            vector: vec![1.0, 0.0, 0.0, 0.0].into(),
+            filter: None,
+            params: None,
+            limit: 4,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        });
+        let request =
+            GroupRequest::with_limit_from_request(source_request, "docId".to_string(), 1).unwrap();
+        let group_keys = || async {
+            GroupBy::new(request.clone(), &collection, |_: String| async {
+                unreachable!()
+            })
+            .execute()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|group| group.id.as_u64())
+            .sorted()
+            .collect_vec()
+        };
+
+        assert_eq!(group_keys().await, vec![0, 1, 2, 3]);
+
+        // Expiration is not an update of the collection, but the same request doesn't return
+        // the expired point anymore
+        now_sec.store(start + 20, Ordering::Relaxed);
+        assert_eq!(group_keys().await, vec![1, 2, 3]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn only_group_by() {
         let Resources {
@@ -651,4 +1079,116 @@ mod group_by_builder {
             assert_eq!(body, &format!("{} {BODY_TEXT}", lookup.id));
         }
     }
+
+    fn recommend_from_lookup(lookup_from: LookupLocation) -> GroupRequest {
+        let source_request = SourceRequest::Recommend(RecommendRequest {
+            positive: vec![1.into(), 2.into()],
+            negative: vec![3.into()],
+            filter: None,
+            params: None,
+            limit: 4,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            using: None,
+            lookup_from: Some(lookup_from),
+        });
+
+        GroupRequest::with_limit_from_request(source_request, "docId".to_string(), 3).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recommend_with_examples_payload() {
+        let Resources {
+            collection,
+            lookup_collection,
+            ..
+        } = setup(16, 8).await;
+
+        let request = recommend_from_lookup(LookupLocation {
+            collection: "test".to_string(),
+            vector: None,
+            copy_payload_key: Some("body".to_string()),
+        });
+
+        let collection_by_name = |_: String| async { Some(lookup_collection.read().await) };
+
+        let result = GroupBy::new(request.clone(), &collection, collection_by_name)
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), request.limit);
+
+        for group in result {
+            let examples = group.examples.expect("examples not found");
+            assert_eq!(examples.len(), 3);
+            for example in examples {
+                let payload = example.payload.unwrap();
+                assert_eq!(payload.0.len(), 1);
+                let body = payload.0.get("body").unwrap().as_str().unwrap();
+                assert_eq!(body, &format!("{} {BODY_TEXT}", example.id));
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recommend_with_missing_lookup_vector() {
+        let Resources {
+            collection,
+            lookup_collection,
+            ..
+        } = setup(16, 8).await;
+
+        let request = recommend_from_lookup(LookupLocation {
+            collection: "test".to_string(),
+            vector: Some("missing".to_string()),
+            copy_payload_key: None,
+        });
+
+        let collection_by_name = |_: String| async { Some(lookup_collection.read().await) };
+
+        let result = GroupBy::new(request, &collection, collection_by_name)
+            .execute()
+            .await;
+
+        match result {
+            Err(CollectionError::VectorNameMismatch {
+                requested,
+                available,
+                collection,
+            }) => {
+                assert_eq!(requested, "missing");
+                assert!(!available.contains(&requested));
+                assert_eq!(collection.as_deref(), Some("test"));
+            }
+            other => panic!("expected vector name mismatch, got {other:?}"),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recommend_with_missing_lookup_collection() {
+        let Resources { collection, .. } = setup(16, 8).await;
+
+        let request = recommend_from_lookup(LookupLocation {
+            collection: "missing".to_string(),
+            vector: None,
+            copy_payload_key: None,
+        });
+
+        let collection_by_name = |_: String| async { None };
+
+        let result = GroupBy::new(request, &collection, collection_by_name)
+            .execute()
+            .await;
+
+        match result {
+            Err(err @ CollectionError::LookupCollectionMissing { .. }) => {
+                assert_eq!(err.reason(), Some(ErrorReason::LookupCollectionMissing));
+                assert!(err.to_string().contains("`missing`"), "{err}");
+            }
+            other => panic!("expected missing lookup collection, got {other:?}"),
+        }
+    }
 }
@@ -13,4 +13,6 @@ pub mod multi_vec_test;
 #[cfg(test)]
 pub mod pagination_test;
 #[cfg(test)]
+pub mod point_scope_test;
+#[cfg(test)]
 pub mod snapshot_recovery_test;
@@ -8,7 +8,8 @@ use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
 };
 use collection::operations::types::{
-    CollectionError, PointRequest, RecommendRequest, SearchRequest, VectorParams, VectorsConfig,
+    CollectionError, PointRequest, RecommendRequest, ScrollRequest, SearchRequest, VectorParams,
+    VectorsConfig,
 };
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
@@ -41,6 +42,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        datatype: None,
     };
     let vector_params2 = VectorParams {
         size: NonZeroU64::new(4).unwrap(),
@@ -48,6 +50,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        datatype: None,
     };
 
     let mut vectors_config = BTreeMap::new();
@@ -61,6 +64,14 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        validate_payload: false,
+        payload_validation_mode: Default::default(),
+        sparse_vectors: None,
+        payload_indexes: Default::default(),
+        payload_cache_fields: Default::default(),
+        read_only: false,
+        auto_index_group_by_fields: false,
+        read_hedge_delay_ms: None,
     };
 
     let collection_config = CollectionConfig {
@@ -69,6 +80,16 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+        default_search_params: Default::default(),
+        default_with_payload: Default::default(),
+        default_with_vector: Default::default(),
+        default_max_payload_return_bytes: Default::default(),
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: Default::default(),
     };
 
     let snapshot_path = collection_path.join("snapshots");
@@ -128,6 +149,10 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let result = collection
@@ -156,6 +181,10 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let result = collection.search(failed_search_request, None, None).await;
@@ -178,6 +207,10 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let result = collection
@@ -201,6 +234,8 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
                 ids: vec![6.into()],
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: WithVector::Selector(vec![VEC_NAME1.to_string()]),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
             },
             None,
             None,
@@ -217,6 +252,63 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         }
     }
 
+    // Unknown names are ignored by the exclusion
+    let exclude_vec2 = WithVector::Exclude {
+        exclude: vec![VEC_NAME2.to_string(), "missing".to_string()],
+    };
+
+    let retrieve = collection
+        .retrieve(
+            PointRequest {
+                ids: vec![6.into()],
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: exclude_vec2.clone(),
+                with_timestamps: false,
+                max_payload_return_bytes: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(retrieve.len(), 1);
+    match retrieve[0].vector.as_ref().unwrap() {
+        VectorStruct::Single(_) => panic!("expected multi vector"),
+        VectorStruct::Multi(vectors) => {
+            assert!(vectors.contains_key(VEC_NAME1));
+            assert!(!vectors.contains_key(VEC_NAME2));
+        }
+    }
+
+    let scroll = collection
+        .scroll_by(
+            ScrollRequest {
+                offset: None,
+                limit: Some(5),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: exclude_vec2,
+                with_timestamps: false,
+                max_payload_return_bytes: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(scroll.points.len(), 5);
+    for point in scroll.points {
+        match point.vector.unwrap() {
+            VectorStruct::Single(_) => panic!("expected multi vector"),
+            VectorStruct::Multi(vectors) => {
+                assert!(vectors.contains_key(VEC_NAME1));
+                assert!(!vectors.contains_key(VEC_NAME2));
+            }
+        }
+    }
+
     let recommend_result = recommend_by(
         RecommendRequest {
             positive: vec![6.into()],
@@ -50,6 +50,10 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         with_vector: None,
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let reference_result = collection
@@ -71,6 +75,10 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         with_vector: None,
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let page_1_result = collection.search(page_1_request, None, None).await.unwrap();
@@ -90,6 +98,10 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         with_vector: None,
         params: None,
         score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
     };
 
     let page_9_result = collection.search(page_9_request, None, None).await.unwrap();
@@ -0,0 +1,412 @@
+use std::sync::Arc;
+
+use collection::collection::Collection;
+use collection::common::point_scope::with_point_scope;
+use collection::grouping::group_by::{GroupRequest, SourceRequest};
+use collection::grouping::GroupBy;
+use collection::lookup::WithLookup;
+use collection::operations::point_ops::{Batch, PointOperations, WriteOrdering};
+use collection::operations::types::{
+    CollectionError, CollectionResult, CountRequest, PointRequest, RecommendRequest, ScrollRequest,
+    SearchRequest, UpdateResult,
+};
+use collection::operations::CollectionUpdateOperations;
+use collection::read_cache::ReadCacheConfig;
+use collection::recommendations::recommend_by;
+use itertools::Itertools;
+use segment::data_types::groups::GroupId;
+use segment::types::{Filter, Payload, PointIdType, WithPayloadInterface, WithVector};
+use serde_json::json;
+use tempfile::Builder;
+use tokio::sync::RwLock;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+/// Points with even ids belong to tenant `a`, with odd ids - to tenant `b`
+fn tenant(id: u64) -> &'static str {
+    if id % 2 == 0 {
+        "a"
+    } else {
+        "b"
+    }
+}
+
+fn tenant_scope(tenant: &str) -> Option<Arc<Filter>> {
+    let filter = serde_json::from_value(json!({
+        "must": [{"key": "tenant_id", "match": {"value": tenant}}]
+    }))
+    .unwrap();
+    Some(Arc::new(filter))
+}
+
+fn upsert(ids: &[u64], payload: impl Fn(u64) -> serde_json::Value) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|&id| id.into()).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|&id| vec![1.0, id as f32 / 10.0, 0.0, 0.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                ids.iter()
+                    .map(|&id| Some(Payload::from(payload(id))))
+                    .collect(),
+            ),
+        }
+        .into(),
+    )
+}
+
+async fn tenants_collection(path: &std::path::Path) -> Collection {
+    let collection = simple_collection_fixture(path, N_SHARDS).await;
+    let ids = (0..10).collect_vec();
+    collection
+        .update_from_client(
+            upsert(&ids, |id| json!({"tenant_id": tenant(id), "doc_id": id})),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    collection
+}
+
+fn ids(points: impl IntoIterator<Item = PointIdType>) -> Vec<PointIdType> {
+    points.into_iter().sorted().collect()
+}
+
+fn tenant_ids(tenant_name: &str) -> Vec<PointIdType> {
+    (0..10)
+        .filter(|&id| tenant(id) == tenant_name)
+        .map(PointIdType::from)
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_reads() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+
+    with_point_scope(tenant_scope("a"), async {
+        let scroll = collection
+            .scroll_by(
+                ScrollRequest {
+                    limit: Some(100),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            ids(scroll.points.into_iter().map(|point| point.id)),
+            tenant_ids("a")
+        );
+
+        // The scope is applied together with the filter of the request
+        let filter: Filter = serde_json::from_value(json!({
+            "should": [{"key": "tenant_id", "match": {"value": "b"}}]
+        }))
+        .unwrap();
+        let count = collection
+            .count(
+                CountRequest {
+                    filter: Some(filter),
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(count.count, 0);
+
+        let search = collection
+            .search(
+                SearchRequest {
+                    vector: vec![1.0, 1.0, 0.0, 0.0].into(),
+                    filter: None,
+                    params: None,
+                    limit: 100,
+                    offset: 0,
+                    with_payload: Some(WithPayloadInterface::Bool(true)),
+                    with_vector: None,
+                    score_threshold: None,
+                    with_usage: false,
+                    allow_partial_results: false,
+                    with_shard_trace: false,
+                    max_payload_return_bytes: None,
+                },
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            ids(search.into_iter().map(|point| point.id)),
+            tenant_ids("a")
+        );
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_retrieve_by_id() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+
+    let retrieve = |ids: Vec<u64>| PointRequest {
+        ids: ids.into_iter().map(PointIdType::from).collect(),
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: WithVector::Bool(true),
+        with_timestamps: false,
+        max_payload_return_bytes: None,
+    };
+
+    let points = with_point_scope(
+        tenant_scope("a"),
+        collection.retrieve(retrieve(vec![0, 1, 2, 3]), None, None),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        ids(points.into_iter().map(|point| point.id)),
+        vec![0.into(), 2.into()]
+    );
+
+    // Points of another tenant look the same as missing ones
+    let points = with_point_scope(
+        tenant_scope("a"),
+        collection.retrieve(retrieve(vec![1]), None, None),
+    )
+    .await
+    .unwrap();
+    assert!(points.is_empty());
+
+    let trace = with_point_scope(
+        tenant_scope("a"),
+        collection.check_filter(1.into(), Filter::default(), None),
+    )
+    .await;
+    assert!(matches!(trace, Err(CollectionError::PointNotFound { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_recommend_examples() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+
+    let recommend = |positive: Vec<u64>| {
+        recommend_by(
+            RecommendRequest {
+                positive: positive.into_iter().map(PointIdType::from).collect(),
+                limit: 100,
+                ..Default::default()
+            },
+            &collection,
+            |_name| async { unreachable!("Should not be called in this test") },
+            None,
+        )
+    };
+
+    // Vector of a point of another tenant can't be used as an example
+    let result = with_point_scope(tenant_scope("a"), recommend(vec![1])).await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::RecommendExampleNotFound { .. })
+    ));
+
+    let result = with_point_scope(tenant_scope("a"), recommend(vec![0]))
+        .await
+        .unwrap();
+    assert!(!result.is_empty());
+    assert!(result
+        .iter()
+        .all(|point| tenant_ids("a").contains(&point.id)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_group_lookup() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+
+    // Documents of both tenants are looked up by the id of the group
+    let lookup_dir = Builder::new().prefix("lookup").tempdir().unwrap();
+    let lookup_collection = simple_collection_fixture(lookup_dir.path(), 1).await;
+    let doc_ids = (0..10).collect_vec();
+    lookup_collection
+        .update_from_client(
+            upsert(
+                &doc_ids,
+                |id| json!({"tenant_id": tenant(id), "body": "secret"}),
+            ),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    let lookup_collection = RwLock::new(lookup_collection);
+
+    // Point of tenant `a` refers to the document of tenant `b`
+    with_point_scope(tenant_scope("a"), async {
+        collection
+            .update_from_client(
+                upsert(&[0], |_| json!({"tenant_id": "a", "doc_id": 1})),
+                true,
+                WriteOrdering::default(),
+            )
+            .await
+            .unwrap();
+    })
+    .await;
+
+    let source = SourceRequest::Search(SearchRequest {
+        vector: vec![1.0, 1.0, 0.0, 0.0].into(),
+        filter: None,
+        params: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
+    });
+    let mut request =
+        GroupRequest::with_limit_from_request(source, "doc_id".to_string(), 1).unwrap();
+    request.limit = 10;
+    request.with_lookup = Some(WithLookup {
+        collection_name: "docs".to_string(),
+        with_payload: Some(true.into()),
+        with_vectors: None,
+    });
+
+    let collection_by_name = |_: String| async { Some(lookup_collection.read().await) };
+    let groups = with_point_scope(
+        tenant_scope("a"),
+        GroupBy::new(request, &collection, collection_by_name).execute(),
+    )
+    .await
+    .unwrap();
+
+    assert!(!groups.is_empty());
+    for group in groups {
+        for hit in &group.hits {
+            assert!(tenant_ids("a").contains(&hit.id));
+        }
+        match group.lookup {
+            Some(lookup) => assert!(tenant_ids("a").contains(&lookup.id)),
+            // Document of another tenant is not looked up
+            None => assert_eq!(group.id, GroupId::NumberU64(1)),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_groups_are_cached_per_scope() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+    collection
+        .update_read_cache_config(ReadCacheConfig {
+            max_entries: Some(10),
+            ttl_sec: None,
+        })
+        .await
+        .unwrap();
+
+    let source = SourceRequest::Search(SearchRequest {
+        vector: vec![1.0, 1.0, 0.0, 0.0].into(),
+        filter: None,
+        params: None,
+        limit: 10,
+        offset: 0,
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: None,
+        score_threshold: None,
+        with_usage: false,
+        allow_partial_results: false,
+        with_shard_trace: false,
+        max_payload_return_bytes: None,
+    });
+    let request = GroupRequest::with_limit_from_request(source, "doc_id".to_string(), 1).unwrap();
+    let group_hit_ids = |scope| {
+        let request = request.clone();
+        let collection = &collection;
+        async move {
+            let groups = with_point_scope(
+                scope,
+                GroupBy::new(request, collection, |_name| async { unreachable!() }).execute(),
+            )
+            .await
+            .unwrap();
+            ids(groups
+                .into_iter()
+                .flat_map(|group| group.hits.into_iter().map(|hit| hit.id)))
+        }
+    };
+
+    // The same request of another scope is not served from the cache, filled by the first one
+    assert_eq!(group_hit_ids(tenant_scope("a")).await, tenant_ids("a"));
+    assert_eq!(group_hit_ids(tenant_scope("b")).await, tenant_ids("b"));
+    assert_eq!(
+        group_hit_ids(None).await,
+        ids((0..10).map(PointIdType::from))
+    );
+    assert_eq!(group_hit_ids(tenant_scope("a")).await, tenant_ids("a"));
+}
+
+async fn update_in_scope(
+    collection: &Collection,
+    operation: CollectionUpdateOperations,
+) -> CollectionResult<UpdateResult> {
+    with_point_scope(
+        tenant_scope("a"),
+        collection.update_from_client(operation, true, WriteOrdering::default()),
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_updates() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = tenants_collection(collection_dir.path()).await;
+    let update = |operation| update_in_scope(&collection, operation);
+
+    // Points can't be written into another tenant
+    let result = update(upsert(&[10], |_| json!({"tenant_id": "b"}))).await;
+    assert!(matches!(result, Err(CollectionError::Forbidden { .. })));
+
+    // Existing point of another tenant can't be overwritten
+    let result = update(upsert(&[1], |_| json!({"tenant_id": "a"}))).await;
+    assert!(matches!(result, Err(CollectionError::Forbidden { .. })));
+
+    let result = update(CollectionUpdateOperations::PointOperation(
+        PointOperations::DeletePoints {
+            ids: vec![0.into(), 1.into()],
+        },
+    ))
+    .await;
+    assert!(matches!(result, Err(CollectionError::Forbidden { .. })));
+
+    // Deletion by filter only affects the points of the scope
+    update(CollectionUpdateOperations::PointOperation(
+        PointOperations::DeletePointsByFilter(Filter::default()),
+    ))
+    .await
+    .unwrap();
+
+    let count = collection
+        .count(
+            CountRequest {
+                filter: None,
+                exact: true,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(count.count, tenant_ids("b").len());
+}
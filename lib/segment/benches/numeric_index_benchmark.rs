@@ -0,0 +1,102 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::common::rocksdb_wrapper::open_db_with_existing_cf;
+use segment::data_types::order_by::Direction;
+use segment::index::field_index::numeric_index::NumericIndex;
+use segment::types::{PointOffsetType, Range};
+use tempfile::Builder;
+
+const NUM_POINTS: usize = 1_000_000;
+const PAGE_SIZE: usize = 100;
+
+/// Values within the range, sorted after they are collected, as it is done without the sorted index
+fn collect_and_sort(
+    index: &NumericIndex<f64>,
+    range: &Range,
+    direction: Direction,
+) -> Vec<(f64, PointOffsetType)> {
+    let mut values = (0..NUM_POINTS as PointOffsetType)
+        .flat_map(|idx| {
+            index
+                .get_values(idx)
+                .into_iter()
+                .flatten()
+                .map(move |value| (*value, idx))
+        })
+        .filter(|(value, _)| range.check_range(*value))
+        .collect_vec();
+    values.sort_unstable_by(|(a, a_idx), (b, b_idx)| {
+        direction.compare(*a, *b).then(a_idx.cmp(b_idx))
+    });
+    values
+}
+
+fn sorted_range(c: &mut Criterion) {
+    let mut group = c.benchmark_group("numeric-sorted-range-group");
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db_with_existing_cf(dir.path()).unwrap();
+    let mut index: NumericIndex<f64> = NumericIndex::new(db, "value");
+    index.recreate().unwrap();
+    for idx in 0..NUM_POINTS {
+        index
+            .add_many_to_list(idx as PointOffsetType, [rng.gen_range(0.0..1000.0)])
+            .unwrap();
+    }
+
+    let range = Range {
+        gte: Some(250.0),
+        lt: Some(750.0),
+        ..Default::default()
+    };
+
+    group.bench_function("range-iter-sorted-page", |b| {
+        b.iter(|| {
+            index
+                .range_iter_sorted(&range, Direction::Desc)
+                .take(PAGE_SIZE)
+                .count()
+        });
+    });
+
+    group.bench_function("collect-and-sort-page", |b| {
+        b.iter(|| {
+            collect_and_sort(&index, &range, Direction::Desc)
+                .into_iter()
+                .take(PAGE_SIZE)
+                .count()
+        });
+    });
+
+    group.bench_function("range-iter-sorted-all", |b| {
+        b.iter(|| index.range_iter_sorted(&range, Direction::Asc).count());
+    });
+
+    group.bench_function("collect-and-sort-all", |b| {
+        b.iter(|| collect_and_sort(&index, &range, Direction::Asc).len());
+    });
+
+    group.bench_function("first-n", |b| {
+        b.iter(|| index.first_n(PAGE_SIZE).len());
+    });
+
+    group.bench_function("last-n", |b| {
+        b.iter(|| index.last_n(PAGE_SIZE).len());
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = sorted_range
+}
+
+criterion_main!(benches);
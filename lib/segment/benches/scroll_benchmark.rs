@@ -0,0 +1,76 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use segment::id_tracker::simple_id_tracker::SimpleIdTracker;
+use segment::id_tracker::IdTracker;
+use segment::types::{PointIdType, PointOffsetType};
+use tempfile::Builder;
+use uuid::Uuid;
+
+const PAGE_SIZE: usize = 100;
+
+/// Amount of points in the id tracker, e.g. `SCROLL_BENCH_POINTS=10000000` for a 10M segment
+fn num_points() -> usize {
+    std::env::var("SCROLL_BENCH_POINTS")
+        .ok()
+        .and_then(|points| points.parse().ok())
+        .unwrap_or(1_000_000)
+}
+
+fn scroll_pages(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scroll-page-group");
+    let mut rng = StdRng::seed_from_u64(42);
+    let num_points = num_points();
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let mut id_tracker = SimpleIdTracker::open(db).unwrap();
+
+    let ids = (0..num_points)
+        .map(|i| {
+            if i % 2 == 0 {
+                PointIdType::NumId(rng.gen())
+            } else {
+                PointIdType::Uuid(Uuid::from_u128(rng.gen()))
+            }
+        })
+        .collect_vec();
+    for (internal_id, external_id) in ids.iter().enumerate() {
+        id_tracker
+            .set_link(*external_id, internal_id as PointOffsetType)
+            .unwrap();
+    }
+
+    let (num_ids, uuid_ids): (Vec<_>, Vec<_>) = ids
+        .into_iter()
+        .partition(|id| matches!(id, PointIdType::NumId(_)));
+
+    group.bench_function("page-from-num-offset", |b| {
+        b.iter(|| {
+            let offset = num_ids[rng.gen_range(0..num_ids.len())];
+            id_tracker.iter_from(Some(offset)).take(PAGE_SIZE).count()
+        });
+    });
+
+    group.bench_function("page-from-uuid-offset", |b| {
+        b.iter(|| {
+            let offset = uuid_ids[rng.gen_range(0..uuid_ids.len())];
+            id_tracker.iter_from(Some(offset)).take(PAGE_SIZE).count()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = scroll_pages
+}
+
+criterion_main!(benches);
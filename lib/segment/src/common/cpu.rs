@@ -1,3 +1,8 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
 /// Try to read number of CPUs from environment variable `QDRANT_NUM_CPUS`.
 /// If it is not set, use `num_cpus::get()`.
 pub fn get_num_cpus() -> usize {
@@ -13,3 +18,117 @@ pub fn get_num_cpus() -> usize {
         Err(_) => num_cpus::get(),
     }
 }
+
+/// Budget of CPUs, which are shared between concurrent background jobs, e.g. index building.
+///
+/// Each job acquires a permit for some number of CPUs before it starts, and returns them back
+/// when the permit is dropped. So all running jobs combined never use more CPUs than the budget.
+#[derive(Debug)]
+pub struct CpuBudget {
+    capacity: AtomicUsize,
+    used: Mutex<usize>,
+}
+
+impl CpuBudget {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity.max(1)),
+            used: Mutex::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Change size of the budget. Already issued permits are not affected.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity.max(1), Ordering::Relaxed);
+    }
+
+    /// Number of CPUs, currently occupied by issued permits
+    pub fn used(&self) -> usize {
+        *self.used.lock()
+    }
+
+    /// Try to acquire a permit for up to `desired_cpus` CPUs.
+    ///
+    /// If less CPUs are available, permit is issued for all of them.
+    /// Returns `None` if the budget is exhausted.
+    pub fn try_acquire(self: &Arc<Self>, desired_cpus: usize) -> Option<CpuPermit> {
+        let mut used = self.used.lock();
+        let available = self.capacity().saturating_sub(*used);
+        if available == 0 {
+            return None;
+        }
+        let num_cpus = desired_cpus.clamp(1, available);
+        *used += num_cpus;
+        Some(CpuPermit {
+            num_cpus,
+            budget: Some(self.clone()),
+        })
+    }
+
+    fn release(&self, num_cpus: usize) {
+        let mut used = self.used.lock();
+        *used = used.saturating_sub(num_cpus);
+    }
+}
+
+/// Permit to use a number of CPUs, issued by [`CpuBudget`]. CPUs are released on drop.
+#[derive(Debug)]
+pub struct CpuPermit {
+    num_cpus: usize,
+    budget: Option<Arc<CpuBudget>>,
+}
+
+impl CpuPermit {
+    /// Permit, which is not accounted in any budget
+    pub fn dummy(num_cpus: usize) -> Self {
+        Self {
+            num_cpus: num_cpus.max(1),
+            budget: None,
+        }
+    }
+
+    pub fn num_cpus(&self) -> usize {
+        self.num_cpus
+    }
+}
+
+impl Drop for CpuPermit {
+    fn drop(&mut self) {
+        if let Some(budget) = self.budget.take() {
+            budget.release(self.num_cpus);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_budget_permits() {
+        let budget = Arc::new(CpuBudget::new(4));
+
+        let permit1 = budget.try_acquire(3).unwrap();
+        assert_eq!(permit1.num_cpus(), 3);
+
+        // Only the remaining CPU is issued
+        let permit2 = budget.try_acquire(3).unwrap();
+        assert_eq!(permit2.num_cpus(), 1);
+        assert_eq!(budget.used(), 4);
+        assert!(budget.try_acquire(1).is_none());
+
+        drop(permit1);
+        assert_eq!(budget.used(), 1);
+
+        // Shrinking the budget does not revoke issued permits
+        budget.set_capacity(1);
+        assert!(budget.try_acquire(1).is_none());
+        drop(permit2);
+        assert_eq!(budget.try_acquire(2).unwrap().num_cpus(), 1);
+        assert_eq!(budget.used(), 0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Accounting of the work, done to serve a single request.
+//!
+//! Hot code paths record their work into thread-local accumulators, which are cheap enough
+//! to stay enabled all the time. [`HardwareCounterCell::measure`] collects the work, done by
+//! the current thread while running a closure, into the shared counters of the request.
+//! Strategies, chosen by the vector indexes for the searches, are collected the same way.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{SearchDebugInfo, SearchStrategy};
+
+/// Amount of work, done to serve a request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct HardwareUsage {
+    /// Number of vectors, scored against the query. Sum of quantized and original comparisons
+    pub scored_vectors: usize,
+    /// Number of query comparisons with quantized vectors
+    pub quantized_comparisons: usize,
+    /// Number of query comparisons with original vectors
+    pub original_comparisons: usize,
+    /// Number of payload values, read to check conditions or to return payload
+    pub payload_values_read: usize,
+    /// Number of evaluated filter conditions
+    pub filter_conditions_checked: usize,
+    /// Whether the response is served from the read cache of the collection, without any work
+    #[serde(default)]
+    pub cache_hit: bool,
+}
+
+impl HardwareUsage {
+    const EMPTY: HardwareUsage = HardwareUsage {
+        scored_vectors: 0,
+        quantized_comparisons: 0,
+        original_comparisons: 0,
+        payload_values_read: 0,
+        filter_conditions_checked: 0,
+        cache_hit: false,
+    };
+
+    pub fn merge(&mut self, other: &HardwareUsage) {
+        self.scored_vectors = self.scored_vectors.wrapping_add(other.scored_vectors);
+        self.quantized_comparisons = self
+            .quantized_comparisons
+            .wrapping_add(other.quantized_comparisons);
+        self.original_comparisons = self
+            .original_comparisons
+            .wrapping_add(other.original_comparisons);
+        self.payload_values_read = self
+            .payload_values_read
+            .wrapping_add(other.payload_values_read);
+        self.filter_conditions_checked = self
+            .filter_conditions_checked
+            .wrapping_add(other.filter_conditions_checked);
+        self.cache_hit |= other.cache_hit;
+    }
+}
+
+thread_local! {
+    /// Work, done by the current thread since the start of the innermost measurement
+    static LOCAL_USAGE: Cell<HardwareUsage> = const { Cell::new(HardwareUsage::EMPTY) };
+
+    /// Searches, done by the current thread since the start of the innermost measurement
+    static LOCAL_SEARCHES: Cell<SearchDebugInfo> = const { Cell::new(SearchDebugInfo::EMPTY) };
+}
+
+fn update_local_usage(update: impl FnOnce(&mut HardwareUsage)) {
+    LOCAL_USAGE.with(|local_usage| {
+        let mut usage = local_usage.get();
+        update(&mut usage);
+        local_usage.set(usage);
+    });
+}
+
+/// Record `count` comparisons of the query with stored vectors
+pub fn record_vector_comparisons(count: usize, quantized: bool) {
+    if count == 0 {
+        return;
+    }
+    update_local_usage(|usage| {
+        usage.scored_vectors = usage.scored_vectors.wrapping_add(count);
+        if quantized {
+            usage.quantized_comparisons = usage.quantized_comparisons.wrapping_add(count);
+        } else {
+            usage.original_comparisons = usage.original_comparisons.wrapping_add(count);
+        }
+    });
+}
+
+/// Record `count` payload values, read from the payload storage
+pub fn record_payload_values_read(count: usize) {
+    if count == 0 {
+        return;
+    }
+    update_local_usage(|usage| {
+        usage.payload_values_read = usage.payload_values_read.wrapping_add(count);
+    });
+}
+
+/// Record a single evaluation of a filter condition
+pub fn record_filter_condition_check() {
+    update_local_usage(|usage| {
+        usage.filter_conditions_checked = usage.filter_conditions_checked.wrapping_add(1);
+    });
+}
+
+/// Record `count` searches, done by a vector index with the `strategy` and `ef` it has chosen
+pub fn record_searches(strategy: SearchStrategy, ef: usize, count: usize) {
+    if count == 0 {
+        return;
+    }
+    LOCAL_SEARCHES.with(|local_searches| {
+        let mut searches = local_searches.get();
+        searches.add_searches(strategy, ef, count);
+        local_searches.set(searches);
+    });
+}
+
+/// Shared counters of the work, done by all threads serving a request
+#[derive(Debug, Default)]
+pub struct HardwareCounterCell {
+    scored_vectors: AtomicUsize,
+    quantized_comparisons: AtomicUsize,
+    original_comparisons: AtomicUsize,
+    payload_values_read: AtomicUsize,
+    filter_conditions_checked: AtomicUsize,
+    cache_hit: AtomicBool,
+    searches: Mutex<SearchDebugInfo>,
+}
+
+impl HardwareCounterCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` on the current thread and add the work, it has done, to the counters.
+    ///
+    /// Measurements may be nested, the work is then accounted in all of them.
+    pub fn measure<T>(&self, f: impl FnOnce() -> T) -> T {
+        let outer_usage = LOCAL_USAGE.with(|local_usage| local_usage.replace(HardwareUsage::EMPTY));
+        let outer_searches =
+            LOCAL_SEARCHES.with(|local_searches| local_searches.replace(SearchDebugInfo::EMPTY));
+        let result = f();
+        let usage = LOCAL_USAGE.with(|local_usage| local_usage.get());
+        let searches = LOCAL_SEARCHES.with(|local_searches| local_searches.get());
+        self.add(&usage);
+        self.searches.lock().merge(&searches);
+
+        let mut restored_usage = outer_usage;
+        restored_usage.merge(&usage);
+        LOCAL_USAGE.with(|local_usage| local_usage.set(restored_usage));
+        let mut restored_searches = outer_searches;
+        restored_searches.merge(&searches);
+        LOCAL_SEARCHES.with(|local_searches| local_searches.set(restored_searches));
+        result
+    }
+
+    /// Add work, done elsewhere. For example, by a remote peer
+    pub fn add(&self, usage: &HardwareUsage) {
+        self.scored_vectors
+            .fetch_add(usage.scored_vectors, Ordering::Relaxed);
+        self.quantized_comparisons
+            .fetch_add(usage.quantized_comparisons, Ordering::Relaxed);
+        self.original_comparisons
+            .fetch_add(usage.original_comparisons, Ordering::Relaxed);
+        self.payload_values_read
+            .fetch_add(usage.payload_values_read, Ordering::Relaxed);
+        self.filter_conditions_checked
+            .fetch_add(usage.filter_conditions_checked, Ordering::Relaxed);
+    }
+
+    /// Mark the response as served from a cache
+    pub fn set_cache_hit(&self) {
+        self.cache_hit.store(true, Ordering::Relaxed);
+    }
+
+    pub fn usage(&self) -> HardwareUsage {
+        HardwareUsage {
+            scored_vectors: self.scored_vectors.load(Ordering::Relaxed),
+            quantized_comparisons: self.quantized_comparisons.load(Ordering::Relaxed),
+            original_comparisons: self.original_comparisons.load(Ordering::Relaxed),
+            payload_values_read: self.payload_values_read.load(Ordering::Relaxed),
+            filter_conditions_checked: self.filter_conditions_checked.load(Ordering::Relaxed),
+            cache_hit: self.cache_hit.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Searches of the vector indexes, along with the strategies they have chosen
+    pub fn search_debug_info(&self) -> SearchDebugInfo {
+        *self.searches.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_measurements() {
+        let outer = HardwareCounterCell::new();
+        let inner = HardwareCounterCell::new();
+
+        outer.measure(|| {
+            record_vector_comparisons(10, false);
+            inner.measure(|| {
+                record_vector_comparisons(5, true);
+                record_filter_condition_check();
+            });
+            record_payload_values_read(3);
+        });
+        // Not measured
+        record_vector_comparisons(100, false);
+
+        assert_eq!(
+            inner.usage(),
+            HardwareUsage {
+                scored_vectors: 5,
+                quantized_comparisons: 5,
+                original_comparisons: 0,
+                payload_values_read: 0,
+                filter_conditions_checked: 1,
+                cache_hit: false,
+            }
+        );
+        assert_eq!(
+            outer.usage(),
+            HardwareUsage {
+                scored_vectors: 15,
+                quantized_comparisons: 5,
+                original_comparisons: 10,
+                payload_values_read: 3,
+                filter_conditions_checked: 1,
+                cache_hit: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_measured_searches() {
+        let counter = HardwareCounterCell::new();
+
+        counter.measure(|| {
+            record_searches(SearchStrategy::Plain, 100, 2);
+            record_searches(SearchStrategy::HnswAdaptiveEf, 400, 1);
+            record_searches(SearchStrategy::Hnsw, 100, 0);
+        });
+        counter.measure(|| record_searches(SearchStrategy::Hnsw, 100, 3));
+        // Not measured
+        record_searches(SearchStrategy::Hnsw, 1000, 1);
+
+        assert_eq!(
+            counter.search_debug_info(),
+            SearchDebugInfo {
+                plain_searches: 2,
+                hnsw_searches: 3,
+                hnsw_adaptive_ef_searches: 1,
+                max_ef: Some(400),
+            }
+        );
+    }
+}
@@ -5,8 +5,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{mem, ops, time};
 
-use memmap2::{Mmap, MmapMut};
+use memmap2::{Mmap, MmapMut, MmapOptions};
 
+use crate::common::on_disk_io::OnDiskIoConfig;
 use crate::entry::entry_point::OperationResult;
 use crate::madvise;
 use crate::madvise::Madviseable;
@@ -36,6 +37,55 @@ pub fn open_read_mmap(path: &Path) -> OperationResult<Mmap> {
     Ok(mmap)
 }
 
+/// Open read-only mmap and load it into page cache, if `populate` is enabled
+pub fn open_read_mmap_populated(path: &Path, on_disk_io: OnDiskIoConfig) -> OperationResult<Mmap> {
+    if !on_disk_io.populate {
+        return open_read_mmap(path);
+    }
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(false)
+        .append(true)
+        .create(true)
+        .open(path)?;
+
+    match on_disk_io.readahead_kb {
+        None => {
+            let mmap = unsafe { MmapOptions::new().populate().map(&file)? };
+            madvise::madvise(&mmap, madvise::get_global())?;
+            // `MAP_POPULATE` is only supported on Linux
+            #[cfg(not(target_os = "linux"))]
+            prefault_mmap_pages(&mmap, Some(path));
+            Ok(mmap)
+        }
+        Some(readahead_kb) => {
+            let mmap = unsafe { Mmap::map(&file)? };
+            madvise::madvise(&mmap, madvise::get_global())?;
+            readahead_mmap_pages(&mmap, readahead_kb.max(1) * 1024, path);
+            Ok(mmap)
+        }
+    }
+}
+
+/// Ask OS to read the whole mmap into page cache in background, one chunk per request
+fn readahead_mmap_pages(mmap: &Mmap, chunk_size: usize, path: &Path) {
+    #[cfg(unix)]
+    for offset in (0..mmap.len()).step_by(chunk_size) {
+        let len = chunk_size.min(mmap.len() - offset);
+        if let Err(err) = mmap.advise_range(memmap2::Advice::WillNeed, offset, len) {
+            log::debug!("Failed to advise MADV_WILLNEED for mmap {path:?}: {err}");
+            return;
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = chunk_size;
+        prefault_mmap_pages(mmap, Some(path));
+    }
+}
+
 pub fn open_write_mmap(path: &Path) -> OperationResult<MmapMut> {
     let file = OpenOptions::new()
         .read(true)
@@ -66,6 +116,29 @@ impl PrefaultMmapPages {
     pub fn exec(&self) {
         prefault_mmap_pages(self.mmap.as_ref(), self.path.as_deref());
     }
+
+    pub fn size_bytes(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Read the pages by chunks of `chunk_size` bytes, passing the size of each chunk to
+    /// `on_chunk`. Stops early and returns `false`, if `on_chunk` returns `false`.
+    pub fn exec_chunked(&self, chunk_size: usize, mut on_chunk: impl FnMut(usize) -> bool) -> bool {
+        let mut dst = [0; 8096];
+
+        for chunk in self.mmap.chunks(chunk_size.max(1)) {
+            for part in chunk.chunks(dst.len()) {
+                dst[..part.len()].copy_from_slice(part);
+            }
+            black_box(&dst);
+
+            if !on_chunk(chunk.len()) {
+                return false;
+            }
+        }
+
+        true
+    }
 }
 
 fn prefault_mmap_pages<T>(mmap: &T, path: Option<&Path>)
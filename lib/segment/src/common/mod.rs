@@ -3,18 +3,22 @@ pub mod arc_atomic_ref_cell_iterator;
 pub mod cpu;
 pub mod error_logging;
 pub mod file_operations;
+pub mod hardware_counter;
 pub mod mmap_ops;
 pub mod mmap_type;
+pub mod on_disk_io;
 pub mod operation_time_statistics;
 pub mod rocksdb_buffered_delete_wrapper;
 pub mod rocksdb_wrapper;
 pub mod utils;
 pub mod version;
+pub mod warmup;
 
 use crate::data_types::named_vectors::NamedVectors;
+use crate::data_types::sparse_vector::SparseVector;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{OperationError, OperationResult};
-use crate::types::{SegmentConfig, VectorDataConfig};
+use crate::types::{PointIdType, SegmentConfig, SparseVectorDataConfig, VectorDataConfig};
 
 pub type Flusher = Box<dyn FnOnce() -> OperationResult<()> + Send>;
 
@@ -64,6 +68,67 @@ pub fn check_named_vectors(
     for (vector_name, vector_data) in vectors.iter() {
         check_vector(vector_name, vector_data, segment_config)?;
     }
+    for (vector_name, vector_data) in vectors.sparse_iter() {
+        check_sparse_vector(vector_name, vector_data, segment_config)?;
+    }
+    Ok(())
+}
+
+/// Check that the given sparse vector name is part of the segment config and that the vector
+/// itself is well-formed.
+///
+/// Returns an error if incompatible.
+pub fn check_sparse_vector(
+    vector_name: &str,
+    vector: &SparseVector,
+    segment_config: &SegmentConfig,
+) -> OperationResult<()> {
+    get_sparse_vector_config_or_error(vector_name, segment_config)?;
+    vector
+        .validate_structure()
+        .map_err(|description| OperationError::WrongSparseVector { description })
+}
+
+/// Check that the given name refers to a sparse vector of the segment config.
+///
+/// Returns an error if incompatible.
+pub fn check_sparse_vector_name(
+    vector_name: &str,
+    segment_config: &SegmentConfig,
+) -> OperationResult<()> {
+    get_sparse_vector_config_or_error(vector_name, segment_config)?;
+    Ok(())
+}
+
+/// Check that the elements of the given named vectors can be stored in the datatypes,
+/// configured for the vectors.
+///
+/// Expects the vectors to be checked with [`check_named_vectors`] already.
+pub fn check_named_vectors_datatype(
+    point_id: PointIdType,
+    vectors: &NamedVectors,
+    segment_config: &SegmentConfig,
+) -> OperationResult<()> {
+    for (vector_name, vector_data) in vectors.iter() {
+        let datatype = get_vector_config_or_error(vector_name, segment_config)?.datatype;
+        let invalid = vector_data
+            .iter()
+            .enumerate()
+            .find(|(_, &value)| !datatype.is_valid_element(value));
+        if let Some((position, value)) = invalid {
+            let vector_name = if vector_name.is_empty() {
+                String::new()
+            } else {
+                format!(" of vector \"{vector_name}\"")
+            };
+            return Err(OperationError::WrongVectorElement {
+                point_id,
+                description: format!(
+                    "element {value} at position {position}{vector_name} can't be stored as {datatype:?}, expected integers in range 0..=255",
+                ),
+            });
+        }
+    }
     Ok(())
 }
 
@@ -82,6 +147,19 @@ fn get_vector_config_or_error<'a>(
         })
 }
 
+/// Get the sparse vector config for the given name, or return a name error.
+fn get_sparse_vector_config_or_error<'a>(
+    vector_name: &str,
+    segment_config: &'a SegmentConfig,
+) -> OperationResult<&'a SparseVectorDataConfig> {
+    segment_config
+        .sparse_vector_data
+        .get(vector_name)
+        .ok_or_else(|| OperationError::VectorNameNotExists {
+            received_name: vector_name.into(),
+        })
+}
+
 /// Check if the given vector data is compatible with the given configuration.
 ///
 /// Returns an error if incompatible.
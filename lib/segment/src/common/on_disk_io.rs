@@ -0,0 +1,41 @@
+//! Global settings of reading the on-disk vector storage.
+//!
+//! Like [`crate::madvise`], the settings are expected to be set once, before any segment is
+//! loaded, see [`set_global`].
+
+use serde::Deserialize;
+
+static ON_DISK_IO: parking_lot::RwLock<OnDiskIoConfig> = parking_lot::RwLock::new(OnDiskIoConfig {
+    populate: false,
+    direct_io: false,
+    readahead_kb: None,
+});
+
+/// How the on-disk vector storage of non-appendable segments is read
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct OnDiskIoConfig {
+    /// If true - vectors are loaded into page cache, once the segment is opened,
+    /// so that the first searches don't cause a storm of page faults.
+    /// Uses `MAP_POPULATE` on Linux and reads the whole file on other platforms.
+    #[serde(default)]
+    pub populate: bool,
+    /// If true - vectors for exact scoring are read asynchronously with direct IO,
+    /// so that rescoring doesn't evict hot data from page cache.
+    /// Falls back to buffered reads, if the platform or the file system doesn't support `O_DIRECT`.
+    #[serde(default)]
+    pub direct_io: bool,
+    /// If set - `populate` issues background readahead requests of this size instead of
+    /// `MAP_POPULATE`, so the segment load doesn't wait for the whole file to be read.
+    #[serde(default)]
+    pub readahead_kb: Option<usize>,
+}
+
+/// Set global on-disk IO settings, used by all segments opened afterwards
+pub fn set_global(config: OnDiskIoConfig) {
+    *ON_DISK_IO.write() = config;
+}
+
+/// Get current global on-disk IO settings
+pub fn get_global() -> OnDiskIoConfig {
+    *ON_DISK_IO.read()
+}
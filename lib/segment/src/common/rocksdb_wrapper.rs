@@ -14,6 +14,7 @@ const DB_MAX_LOG_SIZE: usize = 1024 * 1024; // 1 mb
 const DB_MAX_OPEN_FILES: usize = 256;
 
 pub const DB_VECTOR_CF: &str = "vector";
+pub const DB_SPARSE_VECTOR_CF: &str = "sparse_vector";
 pub const DB_PAYLOAD_CF: &str = "payload";
 pub const DB_MAPPING_CF: &str = "mapping";
 pub const DB_VERSIONS_CF: &str = "version";
@@ -235,17 +236,37 @@ impl<'a> LockedDatabaseColumnWrapper<'a> {
     pub fn iter(&self) -> OperationResult<DatabaseColumnIterator> {
         DatabaseColumnIterator::new(&self.guard, self.column_name)
     }
+
+    /// Iterate over the records, starting from the first key, which is not less than `key`
+    pub fn iter_from(&self, key: &[u8]) -> OperationResult<DatabaseColumnIterator> {
+        DatabaseColumnIterator::new_from(&self.guard, self.column_name, key)
+    }
 }
 
 impl<'a> DatabaseColumnIterator<'a> {
     pub fn new(db: &'a DB, column_name: &str) -> OperationResult<DatabaseColumnIterator<'a>> {
+        let mut iterator = Self::unpositioned(db, column_name)?;
+        iterator.iter.seek_to_first();
+        Ok(iterator)
+    }
+
+    pub fn new_from(
+        db: &'a DB,
+        column_name: &str,
+        key: &[u8],
+    ) -> OperationResult<DatabaseColumnIterator<'a>> {
+        let mut iterator = Self::unpositioned(db, column_name)?;
+        iterator.iter.seek(key);
+        Ok(iterator)
+    }
+
+    fn unpositioned(db: &'a DB, column_name: &str) -> OperationResult<DatabaseColumnIterator<'a>> {
         let handle = db.cf_handle(column_name).ok_or_else(|| {
             OperationError::service_error(format!(
                 "RocksDB cf_handle error: Cannot find column family {column_name}"
             ))
         })?;
-        let mut iter = db.raw_iterator_cf(&handle);
-        iter.seek_to_first();
+        let iter = db.raw_iterator_cf(&handle);
         Ok(DatabaseColumnIterator {
             handle,
             iter,
@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::data_types::named_vectors::NamedVectors;
 use crate::data_types::vectors::VectorElementType;
 use crate::index::field_index::FieldIndex;
-use crate::types::PayloadKeyType;
+use crate::types::{PayloadKeyType, ValuesCount};
 
 /// Avoids allocating Vec with a single element
 #[derive(Debug)]
@@ -29,6 +29,17 @@ impl<T> MultiValue<T> {
         Self::Single(value)
     }
 
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Single(opt) => opt.iter().len(),
+            Self::Multiple(vec) => vec.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     fn push(&mut self, value: T) {
         match self {
             Self::Single(opt) => match opt.take() {
@@ -84,6 +95,18 @@ impl MultiValue<&Value> {
         }
     }
 
+    /// Total amount of values of the field, counted across all the values, the path resolves to.
+    ///
+    /// { "a": [ { "b": [1, 2] }, { "b": 3 } ] } => 3 for `a[].b`
+    pub(crate) fn values_count(&self) -> usize {
+        match self {
+            MultiValue::Single(val) => val.map_or(0, ValuesCount::count_values),
+            MultiValue::Multiple(vals) => {
+                vals.iter().map(|val| ValuesCount::count_values(val)).sum()
+            }
+        }
+    }
+
     pub(crate) fn check_is_null(&self) -> bool {
         match self {
             MultiValue::Single(val) => {
@@ -316,6 +339,112 @@ pub fn remove_value_from_json_map(
     }
 }
 
+/// Selection of the parts of a JSON value, built from payload paths
+#[derive(Debug, Default)]
+struct JsonProjection {
+    /// The whole value is selected
+    whole: bool,
+    /// Selections of the object fields
+    fields: HashMap<String, JsonProjection>,
+    /// Selections of the array elements: `None` for all elements, `Some(i)` for the i-th one
+    elements: HashMap<Option<u32>, JsonProjection>,
+}
+
+impl JsonProjection {
+    fn from_paths(paths: &[impl AsRef<str>]) -> Self {
+        let mut projection = Self::default();
+        for path in paths {
+            projection.add_path(path.as_ref());
+        }
+        projection
+    }
+
+    fn add_path(&mut self, path: &str) {
+        let mut node = self;
+        for element in path.split('.') {
+            node = match parse_array_path(element) {
+                Some((array_path, array_index)) => node
+                    .fields
+                    .entry(array_path.to_string())
+                    .or_default()
+                    .elements
+                    .entry(array_index)
+                    .or_default(),
+                None => node.fields.entry(element.to_string()).or_default(),
+            };
+        }
+        node.whole = true;
+    }
+}
+
+/// Project the value by all selections, which apply to it.
+/// Returns `None` if nothing is selected.
+fn project_value(selections: &[&JsonProjection], value: &Value) -> Option<Value> {
+    if selections.iter().any(|selection| selection.whole) {
+        return Some(value.clone());
+    }
+    match value {
+        Value::Object(map) => project_map(selections, map).map(Value::Object),
+        Value::Array(array) => {
+            let projected: Vec<_> = array
+                .iter()
+                .enumerate()
+                .filter_map(|(i, element)| {
+                    let element_selections: Vec<_> = selections
+                        .iter()
+                        .flat_map(|selection| {
+                            [
+                                selection.elements.get(&None),
+                                selection.elements.get(&Some(i as u32)),
+                            ]
+                        })
+                        .flatten()
+                        .collect();
+                    if element_selections.is_empty() {
+                        return None;
+                    }
+                    project_value(&element_selections, element)
+                })
+                .collect();
+            (!projected.is_empty()).then_some(Value::Array(projected))
+        }
+        _ => None,
+    }
+}
+
+fn project_map(
+    selections: &[&JsonProjection],
+    map: &Map<String, Value>,
+) -> Option<Map<String, Value>> {
+    let projected: Map<String, Value> = map
+        .iter()
+        .filter_map(|(key, value)| {
+            let field_selections: Vec<_> = selections
+                .iter()
+                .filter_map(|selection| selection.fields.get(key))
+                .collect();
+            if field_selections.is_empty() {
+                return None;
+            }
+            Some((key.clone(), project_value(&field_selections, value)?))
+        })
+        .collect();
+    (!projected.is_empty()).then_some(projected)
+}
+
+/// Keep only the values, selected by any of the paths, preserving their structure.
+///
+/// Paths use the same notation as filters: `a.b`, `arr[].c`, `arr[1].c`.
+/// Array elements without selected values are omitted, as well as objects and arrays which end up
+/// empty. So positions of the elements in a projected array may differ from the original ones.
+pub fn select_paths_from_json_map(
+    paths: &[impl AsRef<str>],
+    map: &Map<String, Value>,
+) -> Map<String, Value> {
+    let projection = JsonProjection::from_paths(paths);
+    project_map(&[&projection], map).unwrap_or_default()
+}
+
 pub fn transpose_map_into_named_vector(
     map: HashMap<String, Vec<Vec<VectorElementType>>>,
 ) -> Vec<NamedVectors<'static>> {
@@ -355,6 +484,9 @@ impl JsonPathPayload {
 
 #[cfg(test)]
 mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -626,6 +758,255 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_select_paths_from_json_map() {
+        let map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "a": { "b": 1, "c": 2 },
+                "items": [
+                    { "name": "x", "price": 1 },
+                    { "price": 2 },
+                    { "name": "z", "tags": ["t1", "t2"] }
+                ],
+                "d": 3
+            }
+            "#,
+        )
+        .unwrap();
+
+        // elements without selected values are omitted
+        assert_eq!(
+            Value::Object(select_paths_from_json_map(&["a.b", "items[].name"], &map)),
+            serde_json::json!({
+                "a": { "b": 1 },
+                "items": [{ "name": "x" }, { "name": "z" }]
+            })
+        );
+
+        // selections of the same array are merged per element
+        assert_eq!(
+            Value::Object(select_paths_from_json_map(
+                &["items[].name", "items[1].price", "items[].tags[1]"],
+                &map
+            )),
+            serde_json::json!({
+                "items": [
+                    { "name": "x" },
+                    { "price": 2 },
+                    { "name": "z", "tags": ["t2"] }
+                ]
+            })
+        );
+
+        // missing and mismatching paths select nothing
+        assert!(select_paths_from_json_map(&["a.b.c", "a[]", "missing", "d.e"], &map).is_empty());
+    }
+
+    mod reference {
+        use std::collections::BTreeMap;
+
+        use serde_json::{Map, Value};
+
+        use crate::common::utils::parse_array_path;
+
+        enum PatternStep {
+            Key(String),
+            Index(Option<u32>),
+        }
+
+        #[derive(Clone)]
+        enum Step<'a> {
+            Key(&'a str),
+            Index(usize),
+        }
+
+        enum Node {
+            Leaf(Value),
+            Object(BTreeMap<String, Node>),
+            Array(BTreeMap<usize, Node>),
+        }
+
+        fn parse_pattern(path: &str) -> Vec<PatternStep> {
+            path.split('.')
+                .flat_map(|element| match parse_array_path(element) {
+                    Some((key, index)) => {
+                        vec![PatternStep::Key(key.to_string()), PatternStep::Index(index)]
+                    }
+                    None => vec![PatternStep::Key(element.to_string())],
+                })
+                .collect()
+        }
+
+        /// Collect all scalars and empty containers with their concrete paths
+        fn leaves<'a>(
+            value: &'a Value,
+            prefix: &mut Vec<Step<'a>>,
+            out: &mut Vec<(Vec<Step<'a>>, &'a Value)>,
+        ) {
+            match value {
+                Value::Object(map) if !map.is_empty() => {
+                    for (key, value) in map {
+                        prefix.push(Step::Key(key));
+                        leaves(value, prefix, out);
+                        prefix.pop();
+                    }
+                }
+                Value::Array(array) if !array.is_empty() => {
+                    for (index, value) in array.iter().enumerate() {
+                        prefix.push(Step::Index(index));
+                        leaves(value, prefix, out);
+                        prefix.pop();
+                    }
+                }
+                _ => out.push((prefix.clone(), value)),
+            }
+        }
+
+        fn matches(pattern: &[PatternStep], steps: &[Step]) -> bool {
+            pattern.len() <= steps.len()
+                && pattern.iter().zip(steps).all(|step| match step {
+                    (PatternStep::Key(key), Step::Key(leaf_key)) => key == leaf_key,
+                    (PatternStep::Index(None), Step::Index(_)) => true,
+                    (PatternStep::Index(Some(index)), Step::Index(leaf_index)) => {
+                        *index as usize == *leaf_index
+                    }
+                    _ => false,
+                })
+        }
+
+        fn insert(node: &mut Node, steps: &[Step], value: &Value) {
+            match steps.split_first() {
+                None => *node = Node::Leaf(value.clone()),
+                Some((Step::Key(key), rest)) => {
+                    if !matches!(node, Node::Object(_)) {
+                        *node = Node::Object(BTreeMap::new());
+                    }
+                    if let Node::Object(map) = node {
+                        let child = map
+                            .entry(key.to_string())
+                            .or_insert(Node::Leaf(Value::Null));
+                        insert(child, rest, value);
+                    }
+                }
+                Some((Step::Index(index), rest)) => {
+                    if !matches!(node, Node::Array(_)) {
+                        *node = Node::Array(BTreeMap::new());
+                    }
+                    if let Node::Array(map) = node {
+                        let child = map.entry(*index).or_insert(Node::Leaf(Value::Null));
+                        insert(child, rest, value);
+                    }
+                }
+            }
+        }
+
+        fn into_value(node: Node) -> Value {
+            match node {
+                Node::Leaf(value) => value,
+                Node::Object(map) => Value::Object(
+                    map.into_iter()
+                        .map(|(key, node)| (key, into_value(node)))
+                        .collect(),
+                ),
+                // Missing indexes are omitted, remaining elements keep their order
+                Node::Array(map) => Value::Array(map.into_values().map(into_value).collect()),
+            }
+        }
+
+        /// Straightforward projection: keep every leaf under any of the paths
+        pub fn select_paths(paths: &[String], map: &Map<String, Value>) -> Map<String, Value> {
+            let patterns: Vec<_> = paths.iter().map(|path| parse_pattern(path)).collect();
+            let root = Value::Object(map.clone());
+            let mut all_leaves = vec![];
+            leaves(&root, &mut vec![], &mut all_leaves);
+
+            let mut tree = Node::Object(BTreeMap::new());
+            for (steps, value) in all_leaves {
+                if !steps.is_empty() && patterns.iter().any(|pattern| matches(pattern, &steps)) {
+                    insert(&mut tree, &steps, value);
+                }
+            }
+            match into_value(tree) {
+                Value::Object(map) => map,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    const KEYS: [&str; 3] = ["a", "b", "c"];
+
+    fn random_value(rng: &mut StdRng, depth: usize) -> Value {
+        let kinds = if depth == 0 { 1 } else { 4 };
+        match rng.gen_range(0..kinds) {
+            0 => Value::from(rng.gen_range(0..10i64)),
+            1 => Value::Object(random_map(rng, depth - 1)),
+            _ => Value::Array(
+                (0..rng.gen_range(0..4))
+                    .map(|_| random_value(rng, depth - 1))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn random_map(rng: &mut StdRng, depth: usize) -> Map<String, Value> {
+        (0..rng.gen_range(0..4))
+            .map(|_| {
+                let key = KEYS[rng.gen_range(0..KEYS.len())].to_string();
+                (key, random_value(rng, depth))
+            })
+            .collect()
+    }
+
+    fn random_path(rng: &mut StdRng) -> String {
+        const SUFFIXES: [&str; 5] = ["", "", "[]", "[0]", "[1]"];
+        (0..rng.gen_range(1..4))
+            .map(|_| {
+                let key = KEYS[rng.gen_range(0..KEYS.len())];
+                let suffix = SUFFIXES[rng.gen_range(0..SUFFIXES.len())];
+                format!("{key}{suffix}")
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    #[test]
+    fn test_select_paths_against_reference() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let map = random_map(&mut rng, 4);
+            let paths: Vec<_> = (0..rng.gen_range(1..4))
+                .map(|_| random_path(&mut rng))
+                .collect();
+
+            let selected = select_paths_from_json_map(&paths, &map);
+            assert_eq!(
+                selected,
+                reference::select_paths(&paths, &map),
+                "paths: {paths:?}, payload: {map:?}",
+            );
+
+            // Without explicit indexes, paths address the same values in the projection
+            for path in paths
+                .iter()
+                .filter(|path| !path.contains(|c: char| c.is_numeric()))
+            {
+                assert_eq!(
+                    get_value_from_json_map(path, &selected).values(),
+                    get_value_from_json_map(path, &map).values(),
+                    "path: {path}, payload: {map:?}",
+                );
+
+                let mut excluded = selected.clone();
+                remove_value_from_json_map(path, &mut excluded);
+                assert!(
+                    get_value_from_json_map(path, &excluded).check_is_empty(),
+                    "path: {path}, payload: {map:?}",
+                );
+            }
+        }
+    }
 }
 
 pub type IndexesMap = HashMap<PayloadKeyType, Vec<FieldIndex>>;
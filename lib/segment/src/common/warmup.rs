@@ -0,0 +1,48 @@
+use crate::common::mmap_ops::PrefaultMmapPages;
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::entry::entry_point::OperationResult;
+
+/// Size of the chunks, the data is read by on warmup
+pub const WARMUP_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Data of a segment, which is read on warmup to get it into memory, e.g. after a restart
+#[derive(Clone)]
+pub enum WarmupTask {
+    /// Pages of a memory map, read into the page cache
+    Mmap(PrefaultMmapPages),
+    /// Values of a RocksDB column family, read through the caches of RocksDB and OS
+    Column(DatabaseColumnWrapper),
+}
+
+impl WarmupTask {
+    /// Size of the data in bytes, if known in advance
+    pub fn size_bytes(&self) -> Option<usize> {
+        match self {
+            WarmupTask::Mmap(pages) => Some(pages.size_bytes()),
+            WarmupTask::Column(_) => None,
+        }
+    }
+
+    /// Read the data by chunks of about [`WARMUP_CHUNK_SIZE`] bytes.
+    ///
+    /// The size of each chunk is passed to `on_chunk`, once it is read.
+    /// If `on_chunk` returns `false`, reading stops and `false` is returned.
+    pub fn exec(&self, mut on_chunk: impl FnMut(usize) -> bool) -> OperationResult<bool> {
+        match self {
+            WarmupTask::Mmap(pages) => Ok(pages.exec_chunked(WARMUP_CHUNK_SIZE, on_chunk)),
+            WarmupTask::Column(column) => {
+                let mut chunk = 0;
+                for (key, value) in column.lock_db().iter()? {
+                    chunk += key.len() + value.len();
+                    if chunk >= WARMUP_CHUNK_SIZE {
+                        if !on_chunk(chunk) {
+                            return Ok(false);
+                        }
+                        chunk = 0;
+                    }
+                }
+                Ok(chunk == 0 || on_chunk(chunk))
+            }
+        }
+    }
+}
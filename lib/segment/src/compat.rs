@@ -52,6 +52,7 @@ impl From<SegmentConfigV5> for SegmentConfig {
                     storage_type: (old_data.on_disk == Some(true))
                         .then_some(VectorStorageType::Mmap)
                         .unwrap_or_else(|| old_segment.storage_type.into()),
+                    datatype: Default::default(),
                 };
 
                 (vector_name, new_data)
@@ -60,7 +61,9 @@ impl From<SegmentConfigV5> for SegmentConfig {
 
         SegmentConfig {
             vector_data,
+            sparse_vector_data: Default::default(),
             payload_storage_type: old_segment.payload_storage_type,
+            payload_cache_fields: Default::default(),
         }
     }
 }
@@ -124,6 +127,7 @@ impl From<SegmentStateV5> for SegmentState {
         Self {
             version: old.version,
             config: old.config.into(),
+            format_version: None,
         }
     }
 }
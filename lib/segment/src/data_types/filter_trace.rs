@@ -0,0 +1,63 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::Condition;
+
+/// What decided the outcome of a condition
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionCheckSource {
+    /// Values of the field index
+    Index,
+    /// Stored payload of the point
+    Payload,
+}
+
+/// Outcome of every condition of a filter, checked against a single point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FilterTrace {
+    /// Whether the point matches the whole filter
+    pub matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub should: Option<Vec<ConditionTrace>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_should: Option<Vec<ConditionTrace>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub must: Option<Vec<ConditionTrace>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub must_not: Option<Vec<ConditionTrace>>,
+}
+
+/// Outcome of a single condition of a filter
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ConditionTrace {
+    /// Checked condition. Not set for a nested filter, which is described by `filters` instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    /// Whether the point matches the condition. For `must_not` conditions it is the outcome
+    /// of the condition itself, before negation
+    pub matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_by: Option<ConditionCheckSource>,
+    /// Breakdown of a nested filter.
+    /// `nested` conditions have one entry per object of the array, in the order of the array
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<FilterTrace>,
+}
+
+impl ConditionTrace {
+    pub fn new(
+        condition: &Condition,
+        matched: bool,
+        checked_by: Option<ConditionCheckSource>,
+    ) -> Self {
+        Self {
+            condition: Some(condition.clone()),
+            matched,
+            checked_by,
+            filters: vec![],
+        }
+    }
+}
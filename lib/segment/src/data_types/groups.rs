@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -66,6 +68,27 @@ impl TryFrom<&serde_json::Value> for GroupId {
     }
 }
 
+/// Numeric keys go before the string ones, so that the order of the keys is total.
+/// Equal numbers of different types are still different keys, like they are for `Eq`
+impl Ord for GroupId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn sort_key(key: &GroupId) -> (i128, u8, Option<&str>) {
+            match key {
+                GroupId::NumberI64(n) => (*n as i128, 0, None),
+                GroupId::NumberU64(n) => (*n as i128, 1, None),
+                GroupId::String(s) => (i128::MAX, 2, Some(s)),
+            }
+        }
+        sort_key(self).cmp(&sort_key(other))
+    }
+}
+
+impl PartialOrd for GroupId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl GroupId {
     pub fn as_i64(&self) -> Option<i64> {
         match self {
@@ -82,4 +105,53 @@ impl GroupId {
             GroupId::String(_) => None,
         }
     }
+
+    /// Numeric key, which the string key is the decimal form of, e.g. `5` for `"5"`.
+    /// Older clients send the numeric keys stringified.
+    pub fn unstringified(&self) -> Option<GroupId> {
+        let GroupId::String(key) = self else {
+            return None;
+        };
+        let number = if let Ok(n) = key.parse::<u64>() {
+            GroupId::NumberU64(n)
+        } else {
+            GroupId::NumberI64(key.parse::<i64>().ok()?)
+        };
+        // Other forms of the number, like "+5" or "05", are never produced by stringifying
+        (serde_json::Value::from(number.clone()).to_string() == *key).then_some(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_agrees_with_eq() {
+        let keys = [
+            GroupId::from(5i64),
+            GroupId::from(5u64),
+            GroupId::from(-5i64),
+            GroupId::from(u64::MAX),
+            GroupId::from("5"),
+        ];
+        for a in &keys {
+            for b in &keys {
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b, "{a:?} vs {b:?}");
+            }
+        }
+
+        let mut sorted = keys.to_vec();
+        sorted.sort();
+        assert_eq!(
+            sorted,
+            vec![
+                GroupId::from(-5i64),
+                GroupId::from(5i64),
+                GroupId::from(5u64),
+                GroupId::from(u64::MAX),
+                GroupId::from("5"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordIndexType {
+    #[default]
+    Keyword,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct KeywordIndexParams {
+    // Required for OpenAPI pattern matching
+    pub r#type: KeywordIndexType,
+    /// If true - the field identifies a tenant of a multitenant collection.
+    /// Points of a tenant are stored next to each other and get their own HNSW sub-graph,
+    /// so searches, filtered by a single tenant, don't traverse links to other tenants.
+    /// Default: false
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_tenant: Option<bool>,
+    /// If true - only the number of points of each value is kept in RAM,
+    /// while the points themselves are read from disk on request.
+    /// Changing it for an existing index converts the index in background.
+    /// Default: false
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_disk: Option<bool>,
+}
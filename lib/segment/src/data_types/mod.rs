@@ -1,5 +1,10 @@
+pub mod filter_trace;
 pub mod groups;
+pub mod keyword_index;
 pub mod named_vectors;
+pub mod order_by;
+pub mod primitive;
+pub mod sparse_vector;
 pub mod text_index;
 pub mod tiny_map;
 pub mod vectors;
@@ -1,23 +1,34 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+use super::sparse_vector::SparseVector;
 use super::tiny_map;
-use super::vectors::{VectorElementType, DEFAULT_VECTOR_NAME};
+use super::vectors::{Vector, VectorElementType, DEFAULT_VECTOR_NAME};
 
 type CowKey<'a> = Cow<'a, str>;
 type CowValue<'a> = Cow<'a, [VectorElementType]>;
+type CowSparseValue<'a> = Cow<'a, SparseVector>;
 type TinyMap<'a> = tiny_map::TinyMap<CowKey<'a>, CowValue<'a>>;
+type SparseTinyMap<'a> = tiny_map::TinyMap<CowKey<'a>, CowSparseValue<'a>>;
 
+/// Vectors of a single point by name.
+///
+/// Dense and sparse vectors are kept apart: `iter`, `keys` and `get` only cover dense vectors,
+/// sparse ones are accessed with the `sparse_` counterparts.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct NamedVectors<'a> {
     map: TinyMap<'a>,
+    sparse_map: SparseTinyMap<'a>,
 }
 
 impl<'a> NamedVectors<'a> {
     pub fn from_ref(key: &'a str, value: &'a [VectorElementType]) -> Self {
         let mut map = TinyMap::new();
         map.insert(Cow::Borrowed(key), Cow::Borrowed(value));
-        Self { map }
+        Self {
+            map,
+            sparse_map: SparseTinyMap::new(),
+        }
     }
 
     pub fn from<const N: usize>(arr: [(String, Vec<VectorElementType>); N]) -> Self {
@@ -26,25 +37,30 @@ impl<'a> NamedVectors<'a> {
                 .into_iter()
                 .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
                 .collect(),
+            sparse_map: SparseTinyMap::new(),
         }
     }
 
-    pub fn from_map(map: HashMap<String, Vec<VectorElementType>>) -> Self {
-        Self {
-            map: map
-                .into_iter()
-                .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
-                .collect(),
+    pub fn from_map(map: HashMap<String, Vector>) -> Self {
+        let mut result = Self::default();
+        for (name, vector) in map {
+            match vector {
+                Vector::Dense(vector) => result.insert(name, vector),
+                Vector::Sparse(vector) => result.insert_sparse(name, vector),
+            }
         }
+        result
     }
 
-    pub fn from_map_ref(map: &'a HashMap<String, Vec<VectorElementType>>) -> Self {
-        Self {
-            map: map
-                .iter()
-                .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
-                .collect(),
+    pub fn from_map_ref(map: &'a HashMap<String, Vector>) -> Self {
+        let mut result = Self::default();
+        for (name, vector) in map {
+            match vector {
+                Vector::Dense(vector) => result.insert_ref(name, vector),
+                Vector::Sparse(vector) => result.insert_sparse_ref(name, vector),
+            }
         }
+        result
     }
 
     pub fn insert(&mut self, name: String, vector: Vec<VectorElementType>) {
@@ -57,16 +73,28 @@ impl<'a> NamedVectors<'a> {
             .insert(CowKey::Borrowed(name), CowValue::Borrowed(vector));
     }
 
+    pub fn insert_sparse(&mut self, name: String, vector: SparseVector) {
+        self.sparse_map
+            .insert(CowKey::Owned(name), CowSparseValue::Owned(vector));
+    }
+
+    pub fn insert_sparse_ref(&mut self, name: &'a str, vector: &'a SparseVector) {
+        self.sparse_map
+            .insert(CowKey::Borrowed(name), CowSparseValue::Borrowed(vector));
+    }
+
+    /// Check if a dense or a sparse vector with the given name is present.
     pub fn contains_key(&self, key: &str) -> bool {
-        self.map.contains_key(key)
+        self.map.contains_key(key) || self.sparse_map.contains_key(key)
     }
 
+    /// Number of dense and sparse vectors.
     pub fn len(&self) -> usize {
-        self.map.len()
+        self.map.len() + self.sparse_map.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+        self.map.is_empty() && self.sparse_map.is_empty()
     }
 
     pub fn keys(&self) -> impl Iterator<Item = &str> {
@@ -79,11 +107,16 @@ impl<'a> NamedVectors<'a> {
             .map(|src| std::mem::take(src).into_owned())
     }
 
-    pub fn into_owned_map(self) -> HashMap<String, Vec<VectorElementType>> {
-        self.map
+    pub fn into_owned_map(self) -> HashMap<String, Vector> {
+        let dense = self
+            .map
             .into_iter()
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
-            .collect()
+            .map(|(k, v)| (k.into_owned(), Vector::Dense(v.into_owned())));
+        let sparse = self
+            .sparse_map
+            .into_iter()
+            .map(|(k, v)| (k.into_owned(), Vector::Sparse(v.into_owned())));
+        dense.chain(sparse).collect()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&str, &[VectorElementType])> {
@@ -93,6 +126,20 @@ impl<'a> NamedVectors<'a> {
     pub fn get(&self, key: &str) -> Option<&[VectorElementType]> {
         self.map.get(key).map(|v| v.as_ref())
     }
+
+    pub fn sparse_keys(&self) -> impl Iterator<Item = &str> {
+        self.sparse_map.iter().map(|(k, _)| k.as_ref())
+    }
+
+    pub fn sparse_iter(&self) -> impl Iterator<Item = (&str, &SparseVector)> {
+        self.sparse_map
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+    }
+
+    pub fn get_sparse(&self, key: &str) -> Option<&SparseVector> {
+        self.sparse_map.get(key).map(|v| v.as_ref())
+    }
 }
 
 impl<'a> IntoIterator for NamedVectors<'a> {
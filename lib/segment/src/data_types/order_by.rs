@@ -0,0 +1,73 @@
+use std::cmp::Ordering;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::types::{Order, PayloadKeyType};
+
+/// Direction of the order of the points by the values of a payload field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Smallest values first
+    #[default]
+    Asc,
+    /// Largest values first
+    Desc,
+}
+
+impl Direction {
+    /// Order of the scores, if the values of the points are used as their scores
+    pub fn as_order(self) -> Order {
+        match self {
+            Direction::Asc => Order::SmallBetter,
+            Direction::Desc => Order::LargeBetter,
+        }
+    }
+
+    /// Compare the values, so that the first value in this direction is the smallest
+    pub fn compare(self, a: f64, b: f64) -> Ordering {
+        match self {
+            Direction::Asc => a.total_cmp(&b),
+            Direction::Desc => b.total_cmp(&a),
+        }
+    }
+
+    /// The first of the values in this direction
+    pub fn first(self, a: f64, b: f64) -> f64 {
+        match self.compare(a, b) {
+            Ordering::Greater => b,
+            _ => a,
+        }
+    }
+}
+
+/// Order of the points by the values of a numeric payload field, instead of a query vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderBy {
+    /// Payload key to order by. It must have an integer or float payload index
+    #[validate(length(min = 1))]
+    pub key: PayloadKeyType,
+    /// Direction of the order. Default is ascending
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction() {
+        let mut values = vec![2.0, -1.0, 3.5, 0.0];
+        values.sort_by(|a, b| Direction::Desc.compare(*a, *b));
+        assert_eq!(values, vec![3.5, 2.0, 0.0, -1.0]);
+        values.sort_by(|a, b| Direction::Asc.compare(*a, *b));
+        assert_eq!(values, vec![-1.0, 0.0, 2.0, 3.5]);
+
+        assert_eq!(Direction::Asc.first(1.0, 2.0), 1.0);
+        assert_eq!(Direction::Desc.first(1.0, 2.0), 2.0);
+    }
+}
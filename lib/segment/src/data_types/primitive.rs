@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+
+use crate::data_types::vectors::{VectorElementType, VectorElementTypeByte, VectorElementTypeHalf};
+use crate::spaces::metric::Metric;
+use crate::types::{ScoreType, VectorStorageDatatype};
+
+/// Element type, in which vector storages keep the vectors.
+///
+/// Vectors are accepted and returned as [`VectorElementType`], storages convert them on the fly.
+pub trait PrimitiveVectorElement: Copy + Clone + Default + Send + Sync + 'static {
+    const DATATYPE: VectorStorageDatatype;
+
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]>;
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]>;
+
+    /// The vector itself, if it is stored in [`VectorElementType`]
+    fn as_float_slice(vector: &[Self]) -> Option<&[VectorElementType]>;
+
+    /// Similarity of the query with the stored vector, see [`Metric::similarity`]
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], vector: &[Self]) -> ScoreType;
+
+    /// Similarity of two stored vectors
+    fn similarity_internal<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType {
+        Self::similarity::<TMetric>(&Self::slice_to_float_cow(Cow::Borrowed(v1)), v2)
+    }
+}
+
+impl PrimitiveVectorElement for VectorElementType {
+    const DATATYPE: VectorStorageDatatype = VectorStorageDatatype::Float32;
+
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        vector
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        vector
+    }
+
+    fn as_float_slice(vector: &[Self]) -> Option<&[VectorElementType]> {
+        Some(vector)
+    }
+
+    #[inline]
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], vector: &[Self]) -> ScoreType {
+        TMetric::similarity(query, vector)
+    }
+
+    #[inline]
+    fn similarity_internal<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType {
+        TMetric::similarity(v1, v2)
+    }
+}
+
+impl PrimitiveVectorElement for VectorElementTypeHalf {
+    const DATATYPE: VectorStorageDatatype = VectorStorageDatatype::Float16;
+
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        Cow::Owned(vector.iter().copied().map(Self::from_f32).collect())
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        Cow::Owned(vector.iter().copied().map(Self::to_f32).collect())
+    }
+
+    fn as_float_slice(_vector: &[Self]) -> Option<&[VectorElementType]> {
+        None
+    }
+
+    #[inline]
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], vector: &[Self]) -> ScoreType {
+        TMetric::similarity_half(query, vector)
+    }
+}
+
+/// Conversion from float expects the values to be validated, see
+/// [`VectorStorageDatatype::is_valid_element`]
+impl PrimitiveVectorElement for VectorElementTypeByte {
+    const DATATYPE: VectorStorageDatatype = VectorStorageDatatype::Uint8;
+
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        Cow::Owned(vector.iter().map(|&x| x as Self).collect())
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        Cow::Owned(vector.iter().map(|&x| x as VectorElementType).collect())
+    }
+
+    fn as_float_slice(_vector: &[Self]) -> Option<&[VectorElementType]> {
+        None
+    }
+
+    #[inline]
+    fn similarity<TMetric: Metric>(query: &[VectorElementType], vector: &[Self]) -> ScoreType {
+        TMetric::similarity_uint8(query, vector)
+    }
+
+    #[inline]
+    fn similarity_internal<TMetric: Metric>(v1: &[Self], v2: &[Self]) -> ScoreType {
+        TMetric::similarity_uint8_internal(v1, v2)
+    }
+}
@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::ScoreType;
+
+/// Index of a dimension of a sparse vector.
+pub type DimId = u32;
+
+/// Value of a dimension of a sparse vector.
+pub type DimWeight = f32;
+
+/// Sparse vector structure
+///
+/// Only non-zero dimensions are stored: `indices` holds their positions and `values` the
+/// corresponding weights.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVector {
+    /// Indices of the non-zero dimensions, must be unique
+    pub indices: Vec<DimId>,
+    /// Values of the non-zero dimensions, in the same order as `indices`
+    pub values: Vec<DimWeight>,
+}
+
+impl SparseVector {
+    pub fn new(indices: Vec<DimId>, values: Vec<DimWeight>) -> Result<Self, String> {
+        let vector = Self { indices, values };
+        vector.validate_structure()?;
+        Ok(vector)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Check that indices and values are of the same length and that indices are unique.
+    pub fn validate_structure(&self) -> Result<(), String> {
+        if self.indices.len() != self.values.len() {
+            return Err(format!(
+                "sparse vector has {} indices but {} values",
+                self.indices.len(),
+                self.values.len(),
+            ));
+        }
+        let mut indices = self.indices.clone();
+        indices.sort_unstable();
+        if indices.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err("sparse vector indices must be unique".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.indices.windows(2).all(|pair| pair[0] < pair[1])
+    }
+
+    /// Return the vector with dimensions ordered by index, as expected by [`Self::dot`].
+    pub fn into_sorted(self) -> Self {
+        if self.is_sorted() {
+            return self;
+        }
+        let mut pairs: Vec<_> = self.indices.into_iter().zip(self.values).collect();
+        pairs.sort_unstable_by_key(|(index, _)| *index);
+        let (indices, values) = pairs.into_iter().unzip();
+        Self { indices, values }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (DimId, DimWeight)> + '_ {
+        self.indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+
+    /// Dot product of two sparse vectors, both must be sorted by index.
+    pub fn dot(&self, other: &SparseVector) -> ScoreType {
+        debug_assert!(self.is_sorted() && other.is_sorted());
+        let mut score = 0.0;
+        let (mut i, mut j) = (0, 0);
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    score += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_vector_structure() {
+        assert!(SparseVector::new(vec![1, 2, 3], vec![0.1, 0.2, 0.3]).is_ok());
+        assert!(SparseVector::new(vec![], vec![]).is_ok());
+        assert!(SparseVector::new(vec![1, 2], vec![0.1]).is_err());
+        assert!(SparseVector::new(vec![3, 1, 3], vec![0.1, 0.2, 0.3]).is_err());
+    }
+
+    #[test]
+    fn test_sparse_vector_dot() {
+        let a = SparseVector::new(vec![7, 1, 3], vec![2.0, 1.0, 3.0])
+            .unwrap()
+            .into_sorted();
+        let b = SparseVector::new(vec![3, 5, 7], vec![1.0, 4.0, 0.5])
+            .unwrap()
+            .into_sorted();
+        assert_eq!(a.indices, vec![1, 3, 7]);
+        assert_eq!(a.values, vec![1.0, 3.0, 2.0]);
+        assert_eq!(a.dot(&b), 4.0);
+        assert_eq!(b.dot(&a), 4.0);
+        assert_eq!(a.dot(&SparseVector::default()), 0.0);
+    }
+}
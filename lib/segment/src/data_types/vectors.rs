@@ -1,14 +1,25 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::named_vectors::NamedVectors;
+use super::sparse_vector::SparseVector;
 use crate::common::utils::transpose_map_into_named_vector;
 
 /// Type of vector element.
 pub type VectorElementType = f32;
 
+/// Type of vector element in half precision storages.
+pub type VectorElementTypeHalf = half::f16;
+
+/// Type of vector element in byte storages.
+pub type VectorElementTypeByte = u8;
+
+/// Vector, borrowed from the storage or converted from its element type.
+pub type CowVector<'a> = Cow<'a, [VectorElementType]>;
+
 pub const DEFAULT_VECTOR_NAME: &str = "";
 
 /// Type for vector
@@ -22,12 +33,55 @@ pub fn only_default_vector(vec: &[VectorElementType]) -> NamedVectors {
     NamedVectors::from_ref(DEFAULT_VECTOR_NAME, vec)
 }
 
+/// Named vector of a point, either dense or sparse
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged, rename_all = "snake_case")]
+pub enum Vector {
+    Dense(VectorType),
+    Sparse(SparseVector),
+}
+
+impl Vector {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Vector::Dense(vector) => vector.is_empty(),
+            Vector::Sparse(vector) => vector.is_empty(),
+        }
+    }
+
+    pub fn as_dense(&self) -> Option<&VectorType> {
+        match self {
+            Vector::Dense(vector) => Some(vector),
+            Vector::Sparse(_) => None,
+        }
+    }
+
+    pub fn as_sparse(&self) -> Option<&SparseVector> {
+        match self {
+            Vector::Dense(_) => None,
+            Vector::Sparse(vector) => Some(vector),
+        }
+    }
+}
+
+impl From<VectorType> for Vector {
+    fn from(v: VectorType) -> Self {
+        Vector::Dense(v)
+    }
+}
+
+impl From<SparseVector> for Vector {
+    fn from(v: SparseVector) -> Self {
+        Vector::Sparse(v)
+    }
+}
+
 /// Full vector data per point separator with single and multiple vector modes
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged, rename_all = "snake_case")]
 pub enum VectorStruct {
     Single(VectorType),
-    Multi(HashMap<String, VectorType>),
+    Multi(HashMap<String, Vector>),
 }
 
 impl VectorStruct {
@@ -54,7 +108,7 @@ impl From<&[VectorElementType]> for VectorStruct {
 
 impl<'a> From<NamedVectors<'a>> for VectorStruct {
     fn from(v: NamedVectors) -> Self {
-        if v.len() == 1 && v.contains_key(DEFAULT_VECTOR_NAME) {
+        if v.len() == 1 && v.get(DEFAULT_VECTOR_NAME).is_some() {
             VectorStruct::Single(v.into_default_vector().unwrap())
         } else {
             VectorStruct::Multi(v.into_owned_map())
@@ -62,11 +116,25 @@ impl<'a> From<NamedVectors<'a>> for VectorStruct {
     }
 }
 
+impl From<HashMap<String, VectorType>> for VectorStruct {
+    fn from(v: HashMap<String, VectorType>) -> Self {
+        VectorStruct::Multi(v.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
 impl VectorStruct {
+    /// Get dense vector by name, sparse vectors are not returned.
     pub fn get(&self, name: &str) -> Option<&VectorType> {
         match self {
             VectorStruct::Single(v) => (name == DEFAULT_VECTOR_NAME).then_some(v),
-            VectorStruct::Multi(v) => v.get(name),
+            VectorStruct::Multi(v) => v.get(name).and_then(Vector::as_dense),
+        }
+    }
+
+    pub fn get_sparse(&self, name: &str) -> Option<&SparseVector> {
+        match self {
+            VectorStruct::Single(_) => None,
+            VectorStruct::Multi(v) => v.get(name).and_then(Vector::as_sparse),
         }
     }
 
@@ -88,6 +156,16 @@ pub struct NamedVector {
     pub vector: VectorType,
 }
 
+/// Sparse vector data with name
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct NamedSparseVector {
+    /// Name of vector data
+    pub name: String,
+    /// Sparse vector data
+    pub vector: SparseVector,
+}
+
 /// Vector data separator for named and unnamed modes
 /// Unanmed mode:
 ///
@@ -103,12 +181,22 @@ pub struct NamedVector {
 ///     "name": "image-embeddings"
 ///   }
 /// }
+///
+/// or sparse mode:
+///
+/// {
+///   "vector": {
+///     "vector": {"indices": [6, 7], "values": [1.0, 2.0]},
+///     "name": "text-embeddings"
+///   }
+/// }
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum NamedVectorStruct {
     Default(VectorType),
     Named(NamedVector),
+    Sparse(NamedSparseVector),
 }
 
 impl From<VectorType> for NamedVectorStruct {
@@ -117,38 +205,46 @@ impl From<VectorType> for NamedVectorStruct {
     }
 }
 
-impl From<NamedVectorStruct> for NamedVector {
-    fn from(v: NamedVectorStruct) -> Self {
-        match v {
-            NamedVectorStruct::Default(v) => NamedVector {
-                name: DEFAULT_VECTOR_NAME.to_owned(),
-                vector: v,
-            },
-            NamedVectorStruct::Named(v) => v,
-        }
-    }
-}
-
 impl From<NamedVector> for NamedVectorStruct {
     fn from(v: NamedVector) -> Self {
         NamedVectorStruct::Named(v)
     }
 }
 
+impl From<NamedSparseVector> for NamedVectorStruct {
+    fn from(v: NamedSparseVector) -> Self {
+        NamedVectorStruct::Sparse(v)
+    }
+}
+
 impl NamedVectorStruct {
     pub fn get_name(&self) -> &str {
         match self {
             NamedVectorStruct::Default(_) => DEFAULT_VECTOR_NAME,
             NamedVectorStruct::Named(v) => &v.name,
+            NamedVectorStruct::Sparse(v) => &v.name,
         }
     }
 
-    pub fn get_vector(&self) -> &VectorType {
+    /// Dense query vector, `None` for sparse queries.
+    pub fn get_vector(&self) -> Option<&VectorType> {
         match self {
-            NamedVectorStruct::Default(v) => v,
-            NamedVectorStruct::Named(v) => &v.vector,
+            NamedVectorStruct::Default(v) => Some(v),
+            NamedVectorStruct::Named(v) => Some(&v.vector),
+            NamedVectorStruct::Sparse(_) => None,
         }
     }
+
+    pub fn get_sparse_vector(&self) -> Option<&SparseVector> {
+        match self {
+            NamedVectorStruct::Default(_) | NamedVectorStruct::Named(_) => None,
+            NamedVectorStruct::Sparse(v) => Some(&v.vector),
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, NamedVectorStruct::Sparse(_))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
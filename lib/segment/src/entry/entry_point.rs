@@ -11,14 +11,18 @@ use thiserror::Error;
 
 use crate::common::file_operations::FileStorageError;
 use crate::common::mmap_type::Error as MmapError;
+use crate::data_types::filter_trace::FilterTrace;
 use crate::data_types::named_vectors::NamedVectors;
+use crate::data_types::order_by::OrderBy;
+use crate::data_types::sparse_vector::SparseVector;
 use crate::data_types::vectors::VectorElementType;
 use crate::index::field_index::CardinalityEstimation;
-use crate::telemetry::SegmentTelemetry;
+use crate::index::filter_bitmask::FilterBitmask;
+use crate::telemetry::{HnswGraphHealth, SegmentTelemetry};
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSelector,
+    PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType,
+    WithPayload, WithVector,
 };
 use crate::utils::mem::Mem;
 
@@ -30,6 +34,13 @@ pub enum OperationError {
         expected_dim: usize,
         received_dim: usize,
     },
+    #[error("Wrong vector of point {point_id}: {description}")]
+    WrongVectorElement {
+        point_id: PointIdType,
+        description: String,
+    },
+    #[error("Wrong sparse vector: {description}")]
+    WrongSparseVector { description: String },
     #[error("Not existing vector name error: {received_name}")]
     VectorNameNotExists { received_name: String },
     #[error("Missed vector name error: {received_name}")]
@@ -197,6 +208,9 @@ pub trait SegmentEntry {
     /// Get version of specified point
     fn point_version(&self, point_id: PointIdType) -> Option<SeqNumberType>;
 
+    /// Get time of the last update of specified point, in milliseconds since UNIX epoch
+    fn point_timestamp(&self, point_id: PointIdType) -> Option<u64>;
+
     #[allow(clippy::too_many_arguments)]
     fn search(
         &self,
@@ -221,6 +235,39 @@ pub trait SegmentEntry {
         params: Option<&SearchParams>,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
 
+    /// Points of the segment, matching the filter, as a bitmask over internal ids.
+    /// It stays valid until the version of the segment is changed.
+    ///
+    /// `None` if the segment has no stable internal ids to build the bitmask on.
+    fn filter_bitmask(&self, filter: &Filter) -> Option<FilterBitmask>;
+
+    /// Same as `search_batch`, but among the points of the `bitmask`,
+    /// built by [`Self::filter_bitmask`].
+    /// Conditions, which are not evaluated in the bitmask, are passed in `filter`.
+    #[allow(clippy::too_many_arguments)]
+    fn search_batch_prefiltered(
+        &self,
+        vector_name: &str,
+        vectors: &[&[VectorElementType]],
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
+
+    /// Top-k search by dot product over a sparse vector, using its inverted index
+    fn search_sparse_batch(
+        &self,
+        vector_name: &str,
+        vectors: &[&SparseVector],
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -281,10 +328,31 @@ pub trait SegmentEntry {
         point_id: PointIdType,
     ) -> OperationResult<Option<Vec<VectorElementType>>>;
 
+    fn sparse_vector(
+        &self,
+        vector_name: &str,
+        point_id: PointIdType,
+    ) -> OperationResult<Option<SparseVector>>;
+
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors>;
 
     fn payload(&self, point_id: PointIdType) -> OperationResult<Payload>;
 
+    /// Payload of the point, processed by the `selector`.
+    /// Might avoid reading the whole payload, if only cached fields are selected
+    fn selected_payload(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload>;
+
+    /// Check the filter against the point and record the outcome of every condition
+    fn trace_filter(&self, point_id: PointIdType, filter: &Filter) -> OperationResult<FilterTrace>;
+
+    /// Keys of the conditions of the filter, which the query planner can't serve with payload
+    /// indexes, so the payload of the points is read to check them. Sorted and deduplicated.
+    fn unindexed_filter_keys(&self, filter: &Filter) -> Vec<PayloadKeyType>;
+
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_>;
 
     /// Paginate over points which satisfies filtering condition starting with `offset` id including.
@@ -295,6 +363,15 @@ pub trait SegmentEntry {
         filter: Option<&'a Filter>,
     ) -> Vec<PointIdType>;
 
+    /// Points, which satisfy the filtering condition, ordered by the values of the `order_by` key,
+    /// along with their values. Points without numeric values of the key are skipped.
+    fn read_ordered_filtered(
+        &self,
+        limit: Option<usize>,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+    ) -> OperationResult<Vec<(f64, PointIdType)>>;
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType>;
 
@@ -355,6 +432,30 @@ pub trait SegmentEntry {
         field_schema: Option<&PayloadFieldSchema>,
     ) -> OperationResult<bool>;
 
+    /// Start rebuilding index of a payload field, if exists.
+    /// The current index keeps serving reads, until the new one is built in background.
+    fn stage_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool>;
+
+    /// Start creating index for a payload field, if not exists.
+    /// The index is built in background and only serves reads, once it is complete.
+    fn stage_field_index_creation(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_schema: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool>;
+
+    /// Stop building index of a payload field, if it is being created or rebuilt
+    fn cancel_field_index_build(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool>;
+
     /// Get indexed fields
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema>;
 
@@ -377,4 +478,9 @@ pub trait SegmentEntry {
 
     // Get collected telemetry data of segment
     fn get_telemetry_data(&self) -> SegmentTelemetry;
+
+    /// Health of the HNSW graph of each indexed vector.
+    ///
+    /// Scans the links of the whole graph, so it is too expensive to be collected routinely.
+    fn graph_health(&self) -> HashMap<String, HnswGraphHealth>;
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
@@ -7,14 +8,16 @@ use bitvec::prelude::{BitSlice, BitVec};
 use rand::Rng;
 
 use crate::common::Flusher;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::vectors::{CowVector, VectorElementType};
 use crate::entry::entry_point::OperationResult;
 use crate::payload_storage::FilterContext;
 use crate::spaces::metric::Metric;
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::chunked_vectors::ChunkedVectors;
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
-use crate::vector_storage::{raw_scorer_impl, RawScorer, VectorStorage, VectorStorageEnum};
+use crate::vector_storage::{
+    raw_scorer_impl, DenseVectorStorage, RawScorer, VectorStorage, VectorStorageEnum,
+};
 
 pub fn random_vector<R: Rng + ?Sized>(rnd_gen: &mut R, size: usize) -> Vec<VectorElementType> {
     (0..size).map(|_| rnd_gen.gen_range(0.0..1.0)).collect()
@@ -35,6 +38,14 @@ pub struct TestRawScorerProducer<TMetric: Metric> {
     pub metric: PhantomData<TMetric>,
 }
 
+impl<TMetric: Metric> DenseVectorStorage for TestRawScorerProducer<TMetric> {
+    type Element = VectorElementType;
+
+    fn get_dense(&self, key: PointOffsetType) -> &[VectorElementType] {
+        self.vectors.get(key)
+    }
+}
+
 impl<TMetric: Metric> VectorStorage for TestRawScorerProducer<TMetric> {
     fn vector_dim(&self) -> usize {
         self.vectors.get(0).len()
@@ -48,8 +59,8 @@ impl<TMetric: Metric> VectorStorage for TestRawScorerProducer<TMetric> {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
+        Cow::Borrowed(self.vectors.get(key))
     }
 
     fn insert_vector(
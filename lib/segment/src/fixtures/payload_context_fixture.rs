@@ -44,10 +44,15 @@ impl IdTracker for FixtureIdTracker {
         Some(0)
     }
 
+    fn internal_timestamp(&self, _internal_id: PointOffsetType) -> Option<u64> {
+        None
+    }
+
     fn set_internal_version(
         &mut self,
         _internal_id: PointOffsetType,
         _version: SeqNumberType,
+        _timestamp: Option<u64>,
     ) -> OperationResult<()> {
         Ok(())
     }
@@ -224,7 +229,7 @@ pub fn create_struct_payload_index(
     ));
     let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(num_points)));
 
-    let mut index = StructPayloadIndex::open(payload_storage, id_tracker, path).unwrap();
+    let mut index = StructPayloadIndex::open(payload_storage, id_tracker, path, true).unwrap();
 
     index
         .set_indexed(STR_KEY, PayloadSchemaType::Keyword.into())
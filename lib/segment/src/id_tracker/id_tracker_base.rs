@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use bitvec::prelude::BitSlice;
 use rand::Rng;
 
@@ -13,10 +15,17 @@ use crate::types::{PointIdType, PointOffsetType, SeqNumberType};
 pub trait IdTracker {
     fn internal_version(&self, internal_id: PointOffsetType) -> Option<SeqNumberType>;
 
+    /// Time of the last update of the point, in milliseconds since UNIX epoch
+    ///
+    /// `None` if the time is unknown, e.g. the point was stored before update times were recorded
+    fn internal_timestamp(&self, internal_id: PointOffsetType) -> Option<u64>;
+
+    /// Set version of the point together with the time of its last update
     fn set_internal_version(
         &mut self,
         internal_id: PointOffsetType,
         version: SeqNumberType,
+        timestamp: Option<u64>,
     ) -> OperationResult<()>;
 
     /// Returns internal ID of the point, which is used inside this segment
@@ -73,6 +82,25 @@ pub trait IdTracker {
         }))
     }
 
+    /// Iterate over internal IDs (offsets) of the points, updated at or after `timestamp`
+    ///
+    /// - excludes removed points
+    /// - excludes points with unknown update time
+    fn iter_modified_since(
+        &self,
+        timestamp: u64,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        Box::new(self.iter_ids().filter(move |internal_id| {
+            self.internal_timestamp(*internal_id)
+                .map_or(false, |point_timestamp| point_timestamp >= timestamp)
+        }))
+    }
+
+    /// Upper bound of the number of points, updated at or after `timestamp`
+    fn modified_since_max_count(&self, _timestamp: u64) -> usize {
+        self.available_point_count()
+    }
+
     /// Flush id mapping to disk
     fn mapping_flusher(&self) -> Flusher;
 
@@ -129,3 +157,10 @@ pub trait IdTracker {
 }
 
 pub type IdTrackerSS = dyn IdTracker + Sync + Send;
+
+/// Current time in milliseconds since UNIX epoch, as recorded for the updated points
+pub fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::mem::size_of;
 use std::sync::Arc;
 
 use bincode;
@@ -53,10 +54,18 @@ fn external_to_stored_id(point_id: &PointIdType) -> StoredPointId {
     point_id.into()
 }
 
+/// Number of consecutive internal ids, for which the latest update time is tracked
+const TIMESTAMP_BLOCK_SIZE: usize = 1024;
+
 pub struct SimpleIdTracker {
     deleted: BitVec,
     internal_to_external: Vec<PointIdType>,
     internal_to_version: Vec<SeqNumberType>,
+    /// Time of the last update of each point, `0` if unknown
+    internal_to_timestamp: Vec<u64>,
+    /// Latest update time of the points of each block of `TIMESTAMP_BLOCK_SIZE` internal ids.
+    /// Used to skip blocks without recently updated points.
+    timestamp_blocks: Vec<u64>,
     external_to_internal_num: BTreeMap<u64, PointOffsetType>,
     external_to_internal_uuid: BTreeMap<Uuid, PointOffsetType>,
     mapping_db_wrapper: DatabaseColumnScheduledDeleteWrapper,
@@ -117,12 +126,14 @@ impl SimpleIdTracker {
         }
 
         let mut internal_to_version: Vec<SeqNumberType> = Default::default();
+        let mut internal_to_timestamp: Vec<u64> = Default::default();
+        let mut timestamp_blocks: Vec<u64> = Default::default();
         let versions_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(
             DatabaseColumnWrapper::new(store, DB_VERSIONS_CF),
         );
         for (key, val) in versions_db_wrapper.lock_db().iter()? {
             let external_id = Self::restore_key(&key);
-            let version: SeqNumberType = bincode::deserialize(&val).unwrap();
+            let (version, timestamp) = Self::restore_version(&val);
             let internal_id = match external_id {
                 PointIdType::NumId(idx) => external_to_internal_num.get(&idx).copied(),
                 PointIdType::Uuid(uuid) => external_to_internal_uuid.get(&uuid).copied(),
@@ -132,6 +143,14 @@ impl SimpleIdTracker {
                     internal_to_version.resize(internal_id as usize + 1, 0);
                 }
                 internal_to_version[internal_id as usize] = version;
+                if let Some(timestamp) = timestamp {
+                    Self::update_timestamp(
+                        &mut internal_to_timestamp,
+                        &mut timestamp_blocks,
+                        internal_id,
+                        timestamp,
+                    );
+                }
             } else {
                 log::debug!(
                     "Found version without internal id, external id: {}",
@@ -156,6 +175,8 @@ impl SimpleIdTracker {
             deleted,
             internal_to_external,
             internal_to_version,
+            internal_to_timestamp,
+            timestamp_blocks,
             external_to_internal_num,
             external_to_internal_uuid,
             mapping_db_wrapper,
@@ -163,6 +184,43 @@ impl SimpleIdTracker {
         })
     }
 
+    /// Versions are stored together with the time of the last update of the point.
+    /// Segments, created before the update time was recorded, store the version only.
+    fn store_version(version: SeqNumberType, timestamp: Option<u64>) -> Vec<u8> {
+        match timestamp {
+            Some(timestamp) => bincode::serialize(&(version, timestamp)).unwrap(),
+            None => bincode::serialize(&version).unwrap(),
+        }
+    }
+
+    fn restore_version(data: &[u8]) -> (SeqNumberType, Option<u64>) {
+        if data.len() >= size_of::<SeqNumberType>() + size_of::<u64>() {
+            let (version, timestamp) = bincode::deserialize(data).unwrap();
+            (version, Some(timestamp))
+        } else {
+            (bincode::deserialize(data).unwrap(), None)
+        }
+    }
+
+    fn update_timestamp(
+        internal_to_timestamp: &mut Vec<u64>,
+        timestamp_blocks: &mut Vec<u64>,
+        internal_id: PointOffsetType,
+        timestamp: u64,
+    ) {
+        let internal_id = internal_id as usize;
+        if internal_id >= internal_to_timestamp.len() {
+            internal_to_timestamp.resize(internal_id + 1, 0);
+        }
+        internal_to_timestamp[internal_id] = timestamp;
+
+        let block = internal_id / TIMESTAMP_BLOCK_SIZE;
+        if block >= timestamp_blocks.len() {
+            timestamp_blocks.resize(block + 1, 0);
+        }
+        timestamp_blocks[block] = timestamp_blocks[block].max(timestamp);
+    }
+
     fn store_key(external_id: &PointIdType) -> Vec<u8> {
         bincode::serialize(&external_to_stored_id(external_id)).unwrap()
     }
@@ -193,19 +251,42 @@ impl IdTracker for SimpleIdTracker {
         self.internal_to_version.get(internal_id as usize).copied()
     }
 
+    fn internal_timestamp(&self, internal_id: PointOffsetType) -> Option<u64> {
+        self.internal_to_timestamp
+            .get(internal_id as usize)
+            .copied()
+            .filter(|timestamp| *timestamp > 0)
+    }
+
     fn set_internal_version(
         &mut self,
         internal_id: PointOffsetType,
         version: SeqNumberType,
+        timestamp: Option<u64>,
     ) -> OperationResult<()> {
         if let Some(external_id) = self.external_id(internal_id) {
             if internal_id as usize >= self.internal_to_version.len() {
                 self.internal_to_version.resize(internal_id as usize + 1, 0);
             }
             self.internal_to_version[internal_id as usize] = version;
+            match timestamp {
+                Some(timestamp) => Self::update_timestamp(
+                    &mut self.internal_to_timestamp,
+                    &mut self.timestamp_blocks,
+                    internal_id,
+                    timestamp,
+                ),
+                None => {
+                    if let Some(point_timestamp) =
+                        self.internal_to_timestamp.get_mut(internal_id as usize)
+                    {
+                        *point_timestamp = 0;
+                    }
+                }
+            }
             self.versions_db_wrapper.put(
                 Self::store_key(&external_id),
-                bincode::serialize(&version).unwrap(),
+                Self::store_version(version, timestamp),
             )?;
         }
         Ok(())
@@ -355,6 +436,38 @@ impl IdTracker for SimpleIdTracker {
         self.iter_internal()
     }
 
+    fn iter_modified_since(
+        &self,
+        timestamp: u64,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let total = self.internal_to_timestamp.len();
+        Box::new(
+            self.timestamp_blocks
+                .iter()
+                .enumerate()
+                .filter(move |(_, block_timestamp)| **block_timestamp >= timestamp)
+                .flat_map(move |(block, _)| {
+                    let start = block * TIMESTAMP_BLOCK_SIZE;
+                    start..total.min(start + TIMESTAMP_BLOCK_SIZE)
+                })
+                .filter(move |internal_id| {
+                    self.internal_to_timestamp[*internal_id] >= timestamp
+                        && !self.is_deleted_point(*internal_id as PointOffsetType)
+                })
+                .map(|internal_id| internal_id as PointOffsetType),
+        )
+    }
+
+    fn modified_since_max_count(&self, timestamp: u64) -> usize {
+        let blocks = self
+            .timestamp_blocks
+            .iter()
+            .filter(|block_timestamp| **block_timestamp >= timestamp)
+            .count();
+        self.available_point_count()
+            .min(blocks * TIMESTAMP_BLOCK_SIZE)
+    }
+
     /// Creates a flusher function, that persists the removed points in the mapping database
     /// and flushes the mapping to disk.
     /// This function should be called _before_ flushing the version database.
@@ -468,4 +581,108 @@ mod tests {
 
         assert_eq!(sorted_from_tracker, values);
     }
+
+    #[test]
+    fn test_iter_from_uuid_after_reopen() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let mut values: Vec<PointIdType> = vec![
+            PointIdType::Uuid(Uuid::from_u128(u128::MAX)),
+            5.into(),
+            PointIdType::Uuid(Uuid::from_u128(1 << 64)),
+            PointIdType::Uuid(Uuid::from_u128(255)),
+            PointIdType::Uuid(Uuid::from_u128(256)),
+            3.into(),
+        ];
+
+        {
+            let mut id_tracker = SimpleIdTracker::open(db.clone()).unwrap();
+            for (id, value) in values.iter().enumerate() {
+                id_tracker.set_link(*value, id as PointOffsetType).unwrap();
+            }
+            id_tracker.mapping_flusher()().unwrap();
+            id_tracker.versions_flusher()().unwrap();
+        }
+
+        // UUIDs are ordered by their bytes
+        values.sort();
+        let offset = PointIdType::Uuid(Uuid::from_u128(256));
+        let expected = values
+            .iter()
+            .copied()
+            .filter(|id| *id >= offset)
+            .collect_vec();
+        assert_eq!(
+            expected,
+            vec![
+                PointIdType::Uuid(Uuid::from_u128(256)),
+                PointIdType::Uuid(Uuid::from_u128(1 << 64)),
+                PointIdType::Uuid(Uuid::from_u128(u128::MAX)),
+            ]
+        );
+
+        let id_tracker = SimpleIdTracker::open(db).unwrap();
+        let from_offset = id_tracker
+            .iter_from(Some(offset))
+            .map(|(external_id, _)| external_id)
+            .collect_vec();
+        assert_eq!(from_offset, expected);
+
+        let from_start = id_tracker
+            .iter_from(None)
+            .map(|(external_id, _)| external_id)
+            .collect_vec();
+        assert_eq!(from_start, values);
+    }
+
+    #[test]
+    fn test_modified_since() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let num_points = 3 * TIMESTAMP_BLOCK_SIZE as PointOffsetType;
+        {
+            let mut id_tracker = SimpleIdTracker::open(db.clone()).unwrap();
+            for internal_id in 0..num_points {
+                id_tracker
+                    .set_link((internal_id as u64).into(), internal_id)
+                    .unwrap();
+                // Only the points of the last block are updated recently, first one has no time
+                let timestamp = match internal_id {
+                    0 => None,
+                    _ if internal_id >= 2 * TIMESTAMP_BLOCK_SIZE as PointOffsetType => {
+                        Some(2000 + internal_id as u64)
+                    }
+                    _ => Some(1000),
+                };
+                id_tracker
+                    .set_internal_version(internal_id, 1, timestamp)
+                    .unwrap();
+            }
+            id_tracker.drop((num_points as u64 - 1).into()).unwrap();
+            id_tracker.mapping_flusher()().unwrap();
+            id_tracker.versions_flusher()().unwrap();
+        }
+
+        // Versions without time, as stored by older segments, are still readable
+        let id_tracker = SimpleIdTracker::open(db).unwrap();
+        assert_eq!(id_tracker.internal_timestamp(0), None);
+        assert_eq!(id_tracker.internal_version(0), Some(1));
+        assert_eq!(id_tracker.internal_timestamp(1), Some(1000));
+
+        let modified = id_tracker.iter_modified_since(2000).collect_vec();
+        assert_eq!(modified.len(), TIMESTAMP_BLOCK_SIZE - 1);
+        assert!(modified
+            .iter()
+            .all(|internal_id| *internal_id >= 2 * TIMESTAMP_BLOCK_SIZE as PointOffsetType));
+        assert_eq!(
+            id_tracker.modified_since_max_count(2000),
+            TIMESTAMP_BLOCK_SIZE
+        );
+        assert_eq!(
+            id_tracker.iter_modified_since(1000).count(),
+            num_points as usize - 2
+        );
+    }
 }
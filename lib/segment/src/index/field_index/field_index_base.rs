@@ -2,16 +2,18 @@ use serde_json::Value;
 
 use crate::common::utils::MultiValue;
 use crate::common::Flusher;
+use crate::data_types::order_by::Direction;
 use crate::entry::entry_point::OperationResult;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
+use crate::index::field_index::immutable_map_index::ImmutableMapIndex;
 use crate::index::field_index::map_index::MapIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     FieldCondition, FloatPayloadType, IntPayloadType, Match, MatchText, PayloadKeyType,
-    PointOffsetType,
+    PointOffsetType, Range,
 };
 
 pub trait PayloadFieldIndex {
@@ -47,6 +49,17 @@ pub trait PayloadFieldIndex {
 
     /// Returns an amount of unique indexed points
     fn count_indexed_points(&self) -> usize;
+
+    /// Get iterator over the indexed values within the `range` along with their points,
+    /// ordered by the value in the `direction`
+    /// Return `None` if the index doesn't keep the values ordered
+    fn range_iter_sorted(
+        &self,
+        _range: &Range,
+        _direction: Direction,
+    ) -> Option<Box<dyn Iterator<Item = (f64, PointOffsetType)> + '_>> {
+        None
+    }
 }
 
 pub trait ValueIndexer<T> {
@@ -110,6 +123,35 @@ pub trait ValueIndexer<T> {
     fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()>;
 }
 
+/// Fills a new field index with the points of the segment.
+/// Building is separated from querying, so that the built index may take another form,
+/// e.g. the immutable one, once all points are added.
+pub trait FieldIndexBuilder {
+    /// Prepare an empty storage for the index, dropping its previous content
+    fn init(&mut self) -> OperationResult<()>;
+
+    /// Add point with payload to the index
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()>;
+
+    /// Add multiple points with their payloads to the index
+    fn add_point_batch(
+        &mut self,
+        batch: &[(PointOffsetType, MultiValue<&Value>)],
+    ) -> OperationResult<()> {
+        for (id, payload) in batch {
+            self.add_point(*id, payload)?;
+        }
+        Ok(())
+    }
+
+    /// Complete the building and produce the index, ready for querying
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex>;
+}
+
 /// Common interface for all possible types of field indexes
 /// Enables polymorphism on field indexes
 /// TODO: Rename with major release
@@ -121,6 +163,8 @@ pub enum FieldIndex {
     FloatIndex(NumericIndex<FloatPayloadType>),
     GeoIndex(GeoMapIndex),
     FullTextIndex(FullTextIndex),
+    ImmutableIntMapIndex(ImmutableMapIndex<IntPayloadType>),
+    ImmutableKeywordIndex(ImmutableMapIndex<String>),
 }
 
 impl FieldIndex {
@@ -142,6 +186,8 @@ impl FieldIndex {
             FieldIndex::KeywordIndex(_) => None,
             FieldIndex::FloatIndex(_) => None,
             FieldIndex::GeoIndex(_) => None,
+            FieldIndex::ImmutableIntMapIndex(_) => None,
+            FieldIndex::ImmutableKeywordIndex(_) => None,
             FieldIndex::FullTextIndex(full_text_index) => match &condition.r#match {
                 Some(Match::Text(MatchText { text })) => {
                     let query = full_text_index.parse_query(text);
@@ -166,6 +212,8 @@ impl FieldIndex {
             FieldIndex::FloatIndex(payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(payload_field_index) => payload_field_index,
+            FieldIndex::ImmutableIntMapIndex(payload_field_index) => payload_field_index,
+            FieldIndex::ImmutableKeywordIndex(payload_field_index) => payload_field_index,
         }
     }
 
@@ -178,6 +226,8 @@ impl FieldIndex {
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::ImmutableIntMapIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::ImmutableKeywordIndex(ref mut payload_field_index) => payload_field_index,
         }
     }
 
@@ -189,6 +239,12 @@ impl FieldIndex {
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::ImmutableIntMapIndex(ref mut payload_field_index) => {
+                payload_field_index.load()
+            }
+            FieldIndex::ImmutableKeywordIndex(ref mut payload_field_index) => {
+                payload_field_index.load()
+            }
         }
     }
 
@@ -200,6 +256,8 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.clear(),
             FieldIndex::GeoIndex(index) => index.clear(),
             FieldIndex::FullTextIndex(index) => index.clear(),
+            FieldIndex::ImmutableIntMapIndex(index) => index.clear(),
+            FieldIndex::ImmutableKeywordIndex(index) => index.clear(),
         }
     }
 
@@ -211,6 +269,22 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.recreate(),
             FieldIndex::GeoIndex(index) => index.recreate(),
             FieldIndex::FullTextIndex(index) => index.recreate(),
+            FieldIndex::ImmutableIntMapIndex(index) => index.recreate(),
+            FieldIndex::ImmutableKeywordIndex(index) => index.recreate(),
+        }
+    }
+
+    /// Frozen variant of the index for the segments, which don't receive new points.
+    /// Indexes without such a variant are returned as is.
+    pub fn into_immutable(self) -> FieldIndex {
+        match self {
+            FieldIndex::IntMapIndex(index) if !index.is_on_disk() => {
+                FieldIndex::ImmutableIntMapIndex(index.into())
+            }
+            FieldIndex::KeywordIndex(index) if !index.is_on_disk() => {
+                FieldIndex::ImmutableKeywordIndex(index.into())
+            }
+            index => index,
         }
     }
 
@@ -274,6 +348,12 @@ impl FieldIndex {
             FieldIndex::FullTextIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
+            FieldIndex::ImmutableIntMapIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
+            FieldIndex::ImmutableKeywordIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
         }
     }
 
@@ -285,6 +365,8 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.remove_point(point_id),
             FieldIndex::GeoIndex(index) => index.remove_point(point_id),
             FieldIndex::FullTextIndex(index) => index.remove_point(point_id),
+            FieldIndex::ImmutableIntMapIndex(index) => index.remove_point(point_id),
+            FieldIndex::ImmutableKeywordIndex(index) => index.remove_point(point_id),
         }
     }
 
@@ -296,6 +378,8 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.get_telemetry_data(),
             FieldIndex::GeoIndex(index) => index.get_telemetry_data(),
             FieldIndex::FullTextIndex(index) => index.get_telemetry_data(),
+            FieldIndex::ImmutableIntMapIndex(index) => index.get_telemetry_data(),
+            FieldIndex::ImmutableKeywordIndex(index) => index.get_telemetry_data(),
         }
     }
 
@@ -307,9 +391,22 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.values_count(point_id),
             FieldIndex::GeoIndex(index) => index.values_count(point_id),
             FieldIndex::FullTextIndex(index) => index.values_count(point_id),
+            FieldIndex::ImmutableIntMapIndex(index) => index.values_count(point_id),
+            FieldIndex::ImmutableKeywordIndex(index) => index.values_count(point_id),
         }
     }
 
+    /// Indexed values within the `range` along with their points, ordered by the value.
+    /// `None` if the index doesn't keep the values ordered
+    pub fn range_iter_sorted(
+        &self,
+        range: &Range,
+        direction: Direction,
+    ) -> Option<Box<dyn Iterator<Item = (f64, PointOffsetType)> + '_>> {
+        self.get_payload_field_index()
+            .range_iter_sorted(range, direction)
+    }
+
     pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
         match self {
             FieldIndex::IntIndex(index) => index.values_is_empty(point_id),
@@ -318,6 +415,56 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.values_is_empty(point_id),
             FieldIndex::GeoIndex(index) => index.values_is_empty(point_id),
             FieldIndex::FullTextIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::ImmutableIntMapIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::ImmutableKeywordIndex(index) => index.values_is_empty(point_id),
         }
     }
 }
+
+impl From<NumericIndex<IntPayloadType>> for FieldIndex {
+    fn from(index: NumericIndex<IntPayloadType>) -> Self {
+        FieldIndex::IntIndex(index)
+    }
+}
+
+impl From<MapIndex<IntPayloadType>> for FieldIndex {
+    fn from(index: MapIndex<IntPayloadType>) -> Self {
+        FieldIndex::IntMapIndex(index)
+    }
+}
+
+impl From<MapIndex<String>> for FieldIndex {
+    fn from(index: MapIndex<String>) -> Self {
+        FieldIndex::KeywordIndex(index)
+    }
+}
+
+impl From<NumericIndex<FloatPayloadType>> for FieldIndex {
+    fn from(index: NumericIndex<FloatPayloadType>) -> Self {
+        FieldIndex::FloatIndex(index)
+    }
+}
+
+impl From<GeoMapIndex> for FieldIndex {
+    fn from(index: GeoMapIndex) -> Self {
+        FieldIndex::GeoIndex(index)
+    }
+}
+
+impl From<FullTextIndex> for FieldIndex {
+    fn from(index: FullTextIndex) -> Self {
+        FieldIndex::FullTextIndex(index)
+    }
+}
+
+impl From<ImmutableMapIndex<IntPayloadType>> for FieldIndex {
+    fn from(index: ImmutableMapIndex<IntPayloadType>) -> Self {
+        FieldIndex::ImmutableIntMapIndex(index)
+    }
+}
+
+impl From<ImmutableMapIndex<String>> for FieldIndex {
+    fn from(index: ImmutableMapIndex<String>) -> Self {
+        FieldIndex::ImmutableKeywordIndex(index)
+    }
+}
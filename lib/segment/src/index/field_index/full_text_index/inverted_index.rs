@@ -1,9 +1,10 @@
 use std::collections::{BTreeSet, HashMap};
+use std::mem::size_of;
 
 use serde::{Deserialize, Serialize};
 
-use super::posting_list::PostingList;
 use super::postings_iterator::intersect_postings_iterator;
+use crate::index::field_index::posting_list::PostingList;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition, PrimaryCondition};
 use crate::types::{FieldCondition, Match, MatchText, PayloadKeyType, PointOffsetType};
 
@@ -68,6 +69,22 @@ impl InvertedIndex {
         Default::default()
     }
 
+    /// Postings, vocabulary and tokens of the documents
+    pub fn estimated_ram_bytes(&self) -> usize {
+        // Each token of a document is also listed in the posting of the token
+        let postings_len: usize = self.postings.iter().flatten().map(PostingList::len).sum();
+        let postings_bytes = self.postings.capacity() * size_of::<Option<PostingList>>()
+            + postings_len * size_of::<PointOffsetType>();
+        let vocab_bytes: usize = self
+            .vocab
+            .keys()
+            .map(|token| size_of::<String>() + token.capacity() + size_of::<TokenId>())
+            .sum();
+        let documents_bytes = self.point_to_docs.capacity() * size_of::<Option<Document>>()
+            + postings_len * size_of::<TokenId>();
+        postings_bytes + vocab_bytes + documents_bytes
+    }
+
     pub fn document_from_tokens(&mut self, tokens: &BTreeSet<String>) -> Document {
         let mut document_tokens = vec![];
         for token in tokens {
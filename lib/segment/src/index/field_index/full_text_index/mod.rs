@@ -1,5 +1,4 @@
 mod inverted_index;
-mod posting_list;
 mod postings_iterator;
 pub mod text_index;
 mod tokenizers;
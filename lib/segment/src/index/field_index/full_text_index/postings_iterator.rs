@@ -1,4 +1,4 @@
-use super::posting_list::PostingList;
+use crate::index::field_index::posting_list::PostingList;
 use crate::types::PointOffsetType;
 
 pub fn intersect_postings_iterator<'a>(
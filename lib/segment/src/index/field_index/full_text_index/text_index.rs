@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
 use crate::common::Flusher;
 use crate::data_types::text_index::TextIndexParams;
 use crate::entry::entry_point::{OperationError, OperationResult};
@@ -15,7 +16,8 @@ use crate::index::field_index::full_text_index::inverted_index::{
 };
 use crate::index::field_index::full_text_index::tokenizers::Tokenizer;
 use crate::index::field_index::{
-    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, ValueIndexer,
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PayloadFieldIndex,
+    ValueIndexer,
 };
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{FieldCondition, Match, PayloadKeyType, PointOffsetType};
@@ -31,8 +33,10 @@ impl FullTextIndex {
         bincode::serialize(&id).unwrap()
     }
 
-    fn restore_key(data: &[u8]) -> PointOffsetType {
-        bincode::deserialize(data).unwrap()
+    fn restore_key(data: &[u8]) -> OperationResult<PointOffsetType> {
+        bincode::deserialize(data).map_err(|e| {
+            OperationError::service_error(format!("Failed to deserialize point key: {e}"))
+        })
     }
 
     fn serialize_document_tokens(&self, tokens: BTreeSet<String>) -> OperationResult<Vec<u8>> {
@@ -85,6 +89,10 @@ impl FullTextIndex {
             points_values_count: self.inverted_index.points_count,
             points_count: self.inverted_index.points_count,
             histogram_bucket_size: None,
+            obsolete_values_count: 0,
+            estimated_ram_bytes: self.inverted_index.estimated_ram_bytes(),
+            needs_rebuild: false,
+            on_disk: false,
         }
     }
 
@@ -185,7 +193,7 @@ impl PayloadFieldIndex for FullTextIndex {
         };
 
         for (key, value) in self.db_wrapper.lock_db().iter()? {
-            let idx = Self::restore_key(&key);
+            let idx = Self::restore_key(&key)?;
             let document = Self::deserialize_document(&value, &mut self.inverted_index)?;
             self.inverted_index.index_document(idx, document);
         }
@@ -235,6 +243,32 @@ impl PayloadFieldIndex for FullTextIndex {
     }
 }
 
+pub struct FullTextIndexBuilder(FullTextIndex);
+
+impl FullTextIndexBuilder {
+    pub fn new(db: Arc<RwLock<DB>>, config: TextIndexParams, field: &str) -> Self {
+        Self(FullTextIndex::new(db, config, field))
+    }
+}
+
+impl FieldIndexBuilder for FullTextIndexBuilder {
+    fn init(&mut self) -> OperationResult<()> {
+        self.0.recreate()
+    }
+
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        self.0.add_point(id, payload)
+    }
+
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex> {
+        Ok(self.0.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::Builder;
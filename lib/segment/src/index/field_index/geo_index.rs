@@ -1,5 +1,6 @@
 use std::cmp::{max, min};
 use std::collections::{BTreeMap, HashSet};
+use std::mem::size_of;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -9,6 +10,7 @@ use rocksdb::DB;
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
 use crate::common::Flusher;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::index::field_index::geo_hash::{
@@ -17,7 +19,8 @@ use crate::index::field_index::geo_hash::{
 };
 use crate::index::field_index::stat_tools::estimate_multi_value_selection_cardinality;
 use crate::index::field_index::{
-    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PayloadFieldIndex,
+    PrimaryCondition, ValueIndexer,
 };
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
@@ -28,6 +31,9 @@ use crate::types::{
 // TODO discuss value, should it be dynamically computed?
 const GEO_QUERY_MAX_REGION: usize = 12;
 
+/// Latitude and longitude as big-endian f64
+const GEO_POINT_ENCODED_SIZE: usize = 16;
+
 pub struct GeoMapIndex {
     /**
     {
@@ -175,7 +181,7 @@ impl GeoMapIndex {
             })?;
 
             let (geo_hash, idx) = Self::decode_db_key(key_str)?;
-            let geo_point = Self::decode_db_value(value)?;
+            let geo_points = Self::decode_db_values(value)?;
 
             if self.point_to_values.len() <= idx as usize {
                 self.point_to_values.resize(idx as usize + 1, Vec::new())
@@ -185,18 +191,12 @@ impl GeoMapIndex {
                 self.points_count += 1;
             }
 
-            points_to_hashes
-                .entry(idx)
-                .or_default()
-                .push(geo_hash.clone());
-
-            self.point_to_values[idx as usize].push(geo_point);
-            self.points_map
-                .entry(geo_hash.clone())
-                .or_default()
-                .insert(idx);
+            let point_hashes = points_to_hashes.entry(idx).or_default();
+            point_hashes.extend(geo_points.iter().map(|_| geo_hash.clone()));
 
-            self.values_count += 1;
+            self.values_count += geo_points.len();
+            self.point_to_values[idx as usize].extend(geo_points);
+            self.points_map.entry(geo_hash).or_default().insert(idx);
         }
 
         for (_idx, geo_hashes) in points_to_hashes.into_iter() {
@@ -228,14 +228,16 @@ impl GeoMapIndex {
         Ok((geohash, idx))
     }
 
-    fn decode_db_value<T: AsRef<[u8]>>(value: T) -> OperationResult<GeoPoint> {
-        let lat_bytes = value.as_ref()[0..8]
-            .try_into()
-            .map_err(|_| OperationError::service_error("invalid lat encoding"))?;
+    fn decode_db_value(value: &[u8]) -> OperationResult<GeoPoint> {
+        let lat_bytes = value
+            .get(0..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| OperationError::service_error("invalid lat encoding"))?;
 
-        let lon_bytes = value.as_ref()[8..16]
-            .try_into()
-            .map_err(|_| OperationError::service_error("invalid lat encoding"))?;
+        let lon_bytes = value
+            .get(8..16)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| OperationError::service_error("invalid lon encoding"))?;
 
         let lat = f64::from_be_bytes(lat_bytes);
         let lon = f64::from_be_bytes(lon_bytes);
@@ -243,13 +245,29 @@ impl GeoMapIndex {
         Ok(GeoPoint { lon, lat })
     }
 
-    fn encode_db_value(value: &GeoPoint) -> [u8; 16] {
-        let mut result: [u8; 16] = [0; 16];
+    /// Values of the point with the same geo hash share a single db record
+    fn decode_db_values<T: AsRef<[u8]>>(value: T) -> OperationResult<Vec<GeoPoint>> {
+        let value = value.as_ref();
+        if value.is_empty() || value.len() % GEO_POINT_ENCODED_SIZE != 0 {
+            return Err(OperationError::service_error("invalid geo point encoding"));
+        }
+        value
+            .chunks_exact(GEO_POINT_ENCODED_SIZE)
+            .map(Self::decode_db_value)
+            .collect()
+    }
+
+    fn encode_db_value(value: &GeoPoint) -> [u8; GEO_POINT_ENCODED_SIZE] {
+        let mut result: [u8; GEO_POINT_ENCODED_SIZE] = [0; GEO_POINT_ENCODED_SIZE];
         result[0..8].clone_from_slice(&value.lat.to_be_bytes());
         result[8..16].clone_from_slice(&value.lon.to_be_bytes());
         result
     }
 
+    fn encode_db_values(values: &[GeoPoint]) -> Vec<u8> {
+        values.iter().flat_map(Self::encode_db_value).collect()
+    }
+
     pub fn flusher(&self) -> Flusher {
         self.db_wrapper.flusher()
     }
@@ -276,32 +294,40 @@ impl GeoMapIndex {
         let total_points = self.points_per_hash.get(&common_hash).copied().unwrap_or(0);
         let total_values = self.values_per_hash.get(&common_hash).copied().unwrap_or(0);
 
-        let (sum, maximum_per_hash) = values
-            .iter()
-            .map(|region| self.points_per_hash.get(region).cloned().unwrap_or(0))
-            .fold((0, 0), |(sum, maximum), count| {
-                (sum + count, max(maximum, count))
-            });
+        // Point with multiple values may be counted in several regions,
+        // so the sum of points is only an upper bound of the selected points
+        let (points_sum, values_sum, maximum_per_hash) =
+            values
+                .iter()
+                .fold((0, 0, 0), |(points_sum, values_sum, maximum), region| {
+                    let points = self.points_per_hash.get(region).copied().unwrap_or(0);
+                    let region_values = self.values_per_hash.get(region).copied().unwrap_or(0);
+                    (
+                        points_sum + points,
+                        values_sum + region_values,
+                        max(maximum, points),
+                    )
+                });
 
         // Assume all selected points have `max_values_per_point` value hits.
         // Therefore number of points can't be less than `total_hits / max_values_per_point`
-        let min_hits_by_value_groups = sum / self.max_values_per_point;
+        let min_hits_by_value_groups = values_sum / self.max_values_per_point;
 
         // Assume that we have selected all possible duplications of the points
         let point_duplications = total_values - total_points;
-        let possible_non_duplicated = sum.saturating_sub(point_duplications);
+        let possible_non_duplicated = values_sum.saturating_sub(point_duplications);
 
         let estimation_min = max(
             max(min_hits_by_value_groups, possible_non_duplicated),
             maximum_per_hash,
         );
-        let estimation_max = min(sum, total_points);
+        let estimation_max = min(points_sum, total_points);
 
         // estimate_multi_value_selection_cardinality might overflow at some corner cases
         // so it is better to limit its value with min and max
         let estimation_exp =
-            estimate_multi_value_selection_cardinality(total_points, total_values, sum).round()
-                as usize;
+            estimate_multi_value_selection_cardinality(total_points, total_values, values_sum)
+                .round() as usize;
 
         CardinalityEstimation {
             primary_clauses: vec![],
@@ -317,9 +343,31 @@ impl GeoMapIndex {
             points_count: self.points_count,
             points_values_count: self.values_count,
             histogram_bucket_size: None,
+            obsolete_values_count: 0,
+            estimated_ram_bytes: self.estimated_ram_bytes(),
+            needs_rebuild: false,
+            on_disk: false,
         }
     }
 
+    /// Counters and points of the geo hashes, and the values of the points
+    fn estimated_ram_bytes(&self) -> usize {
+        let counters_bytes = (self.points_per_hash.len() + self.values_per_hash.len())
+            * (size_of::<GeoHash>() + size_of::<usize>());
+        let points_map_bytes: usize = self
+            .points_map
+            .values()
+            .map(|points| {
+                size_of::<GeoHash>()
+                    + size_of::<HashSet<PointOffsetType>>()
+                    + points.len() * size_of::<PointOffsetType>()
+            })
+            .sum();
+        let point_values_bytes = self.point_to_values.capacity() * size_of::<Vec<GeoPoint>>()
+            + self.values_count * size_of::<GeoPoint>();
+        counters_bytes + points_map_bytes + point_values_bytes
+    }
+
     fn remove_point(&mut self, idx: PointOffsetType) -> OperationResult<()> {
         if self.point_to_values.len() <= idx as usize {
             return Ok(()); // Already removed or never actually existed
@@ -333,17 +381,19 @@ impl GeoMapIndex {
 
         self.points_count -= 1;
         self.values_count -= removed_geo_points.len();
-        let mut removed_geo_hashes = Vec::with_capacity(removed_geo_points.len());
-
-        for removed_geo_point in removed_geo_points {
-            let removed_geo_hash: GeoHash =
-                encode_max_precision(removed_geo_point.lon, removed_geo_point.lat).unwrap();
-            removed_geo_hashes.push(removed_geo_hash.clone());
+        let removed_geo_hashes: Vec<GeoHash> = removed_geo_points
+            .iter()
+            .map(|removed_geo_point| {
+                encode_max_precision(removed_geo_point.lon, removed_geo_point.lat).unwrap()
+            })
+            .collect();
 
-            let key = Self::encode_db_key(&removed_geo_hash, idx);
+        // Several values of the point may share the geo hash, and so the db record
+        for removed_geo_hash in removed_geo_hashes.iter().unique() {
+            let key = Self::encode_db_key(removed_geo_hash, idx);
             self.db_wrapper.remove(key)?;
 
-            let is_last = if let Some(hash_ids) = self.points_map.get_mut(&removed_geo_hash) {
+            let is_last = if let Some(hash_ids) = self.points_map.get_mut(removed_geo_hash) {
                 hash_ids.remove(&idx);
                 hash_ids.is_empty()
             } else {
@@ -355,10 +405,12 @@ impl GeoMapIndex {
             };
 
             if is_last {
-                self.points_map.remove(&removed_geo_hash);
+                self.points_map.remove(removed_geo_hash);
             }
+        }
 
-            self.decrement_hash_value_counts(&removed_geo_hash);
+        for removed_geo_hash in &removed_geo_hashes {
+            self.decrement_hash_value_counts(removed_geo_hash);
         }
 
         self.decrement_hash_point_counts(&removed_geo_hashes);
@@ -374,6 +426,20 @@ impl GeoMapIndex {
             return Ok(());
         }
 
+        let mut geo_hashes = Vec::with_capacity(values.len());
+        let mut values_per_hash: BTreeMap<GeoHash, Vec<GeoPoint>> = Default::default();
+
+        for added_point in values {
+            let added_geo_hash: GeoHash = encode_max_precision(added_point.lon, added_point.lat)
+                .map_err(|e| OperationError::service_error(format!("Malformed geo points: {e}")))?;
+
+            values_per_hash
+                .entry(added_geo_hash.clone())
+                .or_default()
+                .push(added_point.clone());
+            geo_hashes.push(added_geo_hash);
+        }
+
         if self.point_to_values.len() <= idx as usize {
             // That's a smart reallocation
             self.point_to_values.resize(idx as usize + 1, vec![]);
@@ -381,26 +447,15 @@ impl GeoMapIndex {
 
         self.point_to_values[idx as usize] = values.to_vec();
 
-        let mut geo_hashes = vec![];
-
-        for added_point in values {
-            let added_geo_hash: GeoHash = encode_max_precision(added_point.lon, added_point.lat)
-                .map_err(|e| OperationError::service_error(format!("Malformed geo points: {e}")))?;
-
-            let key = Self::encode_db_key(&added_geo_hash, idx);
-            let value = Self::encode_db_value(added_point);
+        for (geo_hash, hash_values) in values_per_hash {
+            let key = Self::encode_db_key(&geo_hash, idx);
+            self.db_wrapper
+                .put(key, Self::encode_db_values(&hash_values))?;
 
-            geo_hashes.push(added_geo_hash);
-
-            self.db_wrapper.put(key, value)?;
+            self.points_map.entry(geo_hash).or_default().insert(idx);
         }
 
         for geo_hash in &geo_hashes {
-            self.points_map
-                .entry(geo_hash.to_owned())
-                .or_insert_with(HashSet::new)
-                .insert(idx);
-
             self.increment_hash_value_counts(geo_hash);
         }
 
@@ -598,11 +653,38 @@ impl PayloadFieldIndex for GeoMapIndex {
     }
 }
 
+pub struct GeoMapIndexBuilder(GeoMapIndex);
+
+impl GeoMapIndexBuilder {
+    pub fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
+        Self(GeoMapIndex::new(db, field))
+    }
+}
+
+impl FieldIndexBuilder for GeoMapIndexBuilder {
+    fn init(&mut self) -> OperationResult<()> {
+        self.0.recreate()
+    }
+
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        self.0.add_point(id, payload)
+    }
+
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex> {
+        Ok(self.0.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
     use rand::prelude::StdRng;
     use rand::SeedableRng;
+    use rstest::rstest;
     use serde_json::json;
     use tempfile::Builder;
 
@@ -638,6 +720,23 @@ mod tests {
         FieldCondition::new_geo_radius(key, geo_radius)
     }
 
+    fn geo_values(points: &[GeoPoint]) -> Value {
+        Value::Array(
+            points
+                .iter()
+                .map(|point| json!({"lon": point.lon, "lat": point.lat}))
+                .collect(),
+        )
+    }
+
+    fn berlin_radius() -> FieldCondition {
+        let geo_radius = GeoRadius {
+            center: BERLIN,
+            radius: 50_000.0, // Berlin <-> Potsdam is 27 km
+        };
+        condition_for_geo_radius("test".to_string(), geo_radius)
+    }
+
     fn build_random_index(num_points: usize, num_geo_values: usize) -> GeoMapIndex {
         let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
         let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
@@ -916,4 +1015,103 @@ mod tests {
         assert_eq!(new_index.points_count, 1);
         assert_eq!(new_index.values_count, 2);
     }
+
+    #[rstest]
+    #[case::first_matches(&[BERLIN, NYC, TOKYO])]
+    #[case::second_matches(&[NYC, BERLIN, TOKYO])]
+    #[case::last_matches(&[NYC, TOKYO, POTSDAM])]
+    #[case::all_match(&[BERLIN, POTSDAM, BERLIN])]
+    fn test_multi_value_filtering(#[case] locations: &[GeoPoint]) {
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+        let mut index = GeoMapIndex::new(db, FIELD_NAME);
+        index.recreate().unwrap();
+
+        let payload = geo_values(locations);
+        index.add_point(0, &MultiValue::one(&payload)).unwrap();
+        let other_payload = geo_values(&[NYC, TOKYO]);
+        index
+            .add_point(1, &MultiValue::one(&other_payload))
+            .unwrap();
+
+        assert_eq!(index.values_count(0), locations.len());
+        assert_eq!(index.values_count, locations.len() + 2);
+
+        // Point matches if any of its locations matches
+        let condition = berlin_radius();
+        let point_offsets = index.filter(&condition).unwrap().collect_vec();
+        assert_eq!(point_offsets, vec![0]);
+        assert!(index.check_radius(0, condition.geo_radius.as_ref().unwrap()));
+        assert!(!index.check_radius(1, condition.geo_radius.as_ref().unwrap()));
+    }
+
+    #[rstest]
+    #[case::distinct(&[BERLIN, NYC])]
+    #[case::duplicated(&[BERLIN, BERLIN, NYC])]
+    #[case::close(&[BERLIN, POTSDAM, TOKYO, NYC])]
+    fn test_multi_value_remove_point(#[case] locations: &[GeoPoint]) {
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = GeoMapIndex::new(db, FIELD_NAME);
+            index.recreate().unwrap();
+
+            let payload = geo_values(locations);
+            index.add_point(0, &MultiValue::one(&payload)).unwrap();
+            index.add_point(1, &MultiValue::one(&payload)).unwrap();
+            index.flusher()().unwrap();
+        }
+
+        // All values are restored, even if they share the geo hash
+        let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+        let mut index = GeoMapIndex::new(db.clone(), FIELD_NAME);
+        index.load().unwrap();
+        assert_eq!(index.points_count, 2);
+        assert_eq!(index.values_count, 2 * locations.len());
+        assert_eq!(index.values_count(0), locations.len());
+        assert_eq!(index.values_per_hash[""], 2 * locations.len());
+
+        index.remove_point(0).unwrap();
+        index.remove_point(1).unwrap();
+        index.flusher()().unwrap();
+
+        assert_eq!(index.points_count, 0);
+        assert_eq!(index.values_count, 0);
+        assert!(index.points_map.is_empty());
+        assert!(index.points_per_hash.values().all(|count| *count == 0));
+        assert!(index.values_per_hash.values().all(|count| *count == 0));
+        assert_eq!(index.filter(&berlin_radius()).unwrap().count(), 0);
+
+        let mut new_index = GeoMapIndex::new(db, FIELD_NAME);
+        new_index.load().unwrap();
+        assert_eq!(new_index.points_count, 0);
+        assert_eq!(new_index.values_count, 0);
+    }
+
+    #[rstest]
+    #[case::same_location(BERLIN)]
+    #[case::close_location(POTSDAM)]
+    fn test_multi_value_cardinality(#[case] second_location: GeoPoint) {
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+        let mut index = GeoMapIndex::new(db, FIELD_NAME);
+        index.recreate().unwrap();
+
+        let berlin_payload = geo_values(&[BERLIN, second_location]);
+        let other_payload = geo_values(&[NYC, TOKYO]);
+        for idx in 0..20 {
+            let payload = if idx % 2 == 0 {
+                &berlin_payload
+            } else {
+                &other_payload
+            };
+            index.add_point(idx, &MultiValue::one(payload)).unwrap();
+        }
+
+        // Distinct points are estimated, not the matching values
+        let card = index.estimate_cardinality(&berlin_radius()).unwrap();
+        assert_eq!(card.min, 10);
+        assert_eq!(card.exp, 10);
+        assert_eq!(card.max, 10);
+    }
 }
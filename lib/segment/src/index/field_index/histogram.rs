@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::collections::Bound::{Excluded, Included, Unbounded};
+use std::mem::size_of;
 use std::ops::Bound;
 
 use itertools::Itertools;
@@ -141,6 +142,10 @@ impl<T: Numericable> Histogram<T> {
         self.total_count
     }
 
+    pub fn estimated_ram_bytes(&self) -> usize {
+        self.borders.len() * (size_of::<Point<T>>() + size_of::<Counts>())
+    }
+
     /// Infers boundaries for bucket of given size and staring point.
     /// Returns `to` range of values starting provided `from`value which is expected to contain
     /// `range_size` values
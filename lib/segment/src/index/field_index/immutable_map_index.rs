@@ -0,0 +1,648 @@
+use std::fmt::Display;
+use std::hash::Hash;
+use std::iter;
+use std::mem::size_of;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bitvec::vec::BitVec;
+use itertools::Itertools;
+use parking_lot::RwLock;
+use rocksdb::DB;
+
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::field_index::map_index::{except_cardinality, HeapSize, MapIndex, ValueId};
+use crate::index::field_index::{
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PayloadFieldIndex,
+    PrimaryCondition, ValueIndexer,
+};
+use crate::index::query_estimator::combine_should_estimations;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    AnyVariants, FieldCondition, IntPayloadType, Match, MatchAny, MatchExcept, MatchValue,
+    PayloadKeyType, PointOffsetType, ValueVariants,
+};
+
+/// Frozen variant of the `MapIndex` for segments, which don't receive new points.
+///
+/// Values and postings are packed into a few flat arrays without spare capacity, and values
+/// are found by binary search instead of hashing. The storage format is the same as of the
+/// `MapIndex`, so both variants can load the index of each other.
+///
+/// New points can't be added. Removed points are only marked as such, because points are
+/// still deleted from the segments, which don't receive new points.
+pub struct ImmutableMapIndex<N: Ord + Clone + Display> {
+    /// Unique values in ascending order, position of the value is its id
+    values: Vec<N>,
+    /// Points of the value with id `i` are `points[value_offsets[i]..value_offsets[i + 1]]`
+    value_offsets: Vec<usize>,
+    /// Points of all values, sorted within each value
+    points: Vec<PointOffsetType>,
+    /// Number of not removed points of each value
+    points_counts: Vec<usize>,
+    /// Values of the point `i` are `point_values[point_offsets[i]..point_offsets[i + 1]]`
+    point_offsets: Vec<usize>,
+    point_values: Vec<ValueId>,
+    /// Points, removed after the index was built
+    removed: BitVec,
+    /// Amount of point which have at least one indexed payload value
+    indexed_points: usize,
+    values_count: usize,
+    db_wrapper: DatabaseColumnWrapper,
+}
+
+impl<N: Hash + Eq + Ord + Clone + Display + FromStr> ImmutableMapIndex<N> {
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> Self {
+        let store_cf_name = MapIndex::<N>::storage_cf_name(field_name);
+        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+        Self::from_entries(vec![], db_wrapper)
+    }
+
+    /// Pack the values with their points, listed in any order and possibly repeated
+    fn from_entries(
+        mut entries: Vec<(N, PointOffsetType)>,
+        db_wrapper: DatabaseColumnWrapper,
+    ) -> Self {
+        entries.sort_unstable();
+        entries.dedup();
+
+        let mut values: Vec<N> = vec![];
+        let mut value_offsets = vec![];
+        let mut points = Vec::with_capacity(entries.len());
+        let mut point_value_ids = Vec::with_capacity(entries.len());
+        for (value, idx) in entries {
+            if values.last() != Some(&value) {
+                value_offsets.push(points.len());
+                values.push(value);
+            }
+            points.push(idx);
+            point_value_ids.push((idx, (values.len() - 1) as ValueId));
+        }
+        value_offsets.push(points.len());
+        values.shrink_to_fit();
+
+        let points_counts = value_offsets
+            .iter()
+            .tuple_windows()
+            .map(|(start, end)| end - start)
+            .collect();
+
+        point_value_ids.sort_unstable();
+        let points_total = point_value_ids
+            .last()
+            .map_or(0, |(idx, _)| *idx as usize + 1);
+        let mut point_offsets = Vec::with_capacity(points_total + 1);
+        let mut point_values = Vec::with_capacity(point_value_ids.len());
+        let mut point_value_ids = point_value_ids.into_iter().peekable();
+        let mut indexed_points = 0;
+        for idx in 0..points_total {
+            point_offsets.push(point_values.len());
+            while let Some((_, value_id)) =
+                point_value_ids.next_if(|(value_idx, _)| *value_idx as usize == idx)
+            {
+                point_values.push(value_id);
+            }
+            if *point_offsets.last().unwrap() < point_values.len() {
+                indexed_points += 1;
+            }
+        }
+        point_offsets.push(point_values.len());
+
+        ImmutableMapIndex {
+            values,
+            value_offsets,
+            points,
+            points_counts,
+            point_offsets,
+            values_count: point_values.len(),
+            point_values,
+            removed: BitVec::repeat(false, points_total),
+            indexed_points,
+            db_wrapper,
+        }
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        if !self.db_wrapper.has_column_family()? {
+            return Ok(false);
+        }
+        let mut entries = vec![];
+        for (record, _) in self.db_wrapper.lock_db().iter()? {
+            let record = std::str::from_utf8(&record).map_err(|_| {
+                OperationError::service_error("Index load error: UTF8 error while DB parsing")
+            })?;
+            entries.push(MapIndex::<N>::decode_db_record(record)?);
+        }
+        let db_wrapper = self.db_wrapper.clone();
+        *self = Self::from_entries(entries, db_wrapper);
+        Ok(true)
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    fn value_id(&self, value: &N) -> Option<ValueId> {
+        self.values
+            .binary_search(value)
+            .ok()
+            .map(|value_id| value_id as ValueId)
+    }
+
+    fn value_points(&self, value_id: ValueId) -> &[PointOffsetType] {
+        let value_id = value_id as usize;
+        &self.points[self.value_offsets[value_id]..self.value_offsets[value_id + 1]]
+    }
+
+    fn point_value_ids(&self, idx: PointOffsetType) -> &[ValueId] {
+        let idx = idx as usize;
+        if idx + 1 >= self.point_offsets.len() || self.removed[idx] {
+            return &[];
+        }
+        &self.point_values[self.point_offsets[idx]..self.point_offsets[idx + 1]]
+    }
+
+    fn points_count(&self, value: &N) -> usize {
+        self.value_id(value)
+            .map_or(0, |value_id| self.points_counts[value_id as usize])
+    }
+
+    pub fn match_cardinality(&self, value: &N) -> CardinalityEstimation {
+        CardinalityEstimation::exact(self.points_count(value))
+    }
+
+    pub fn get_values(&self, idx: PointOffsetType) -> Option<impl Iterator<Item = &N> + '_> {
+        if idx as usize + 1 >= self.point_offsets.len() {
+            return None;
+        }
+        Some(
+            self.point_value_ids(idx)
+                .iter()
+                .map(|value_id| &self.values[*value_id as usize]),
+        )
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.point_value_ids(point_id).len()
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.point_value_ids(point_id).is_empty()
+    }
+
+    /// Points can't be added into the index, it is rebuilt by the optimization instead
+    pub fn add_point(
+        &mut self,
+        _id: PointOffsetType,
+        _payload: &MultiValue<&serde_json::Value>,
+    ) -> OperationResult<()> {
+        Err(OperationError::service_error(
+            "Can't add points into the immutable map index",
+        ))
+    }
+
+    pub fn remove_point(&mut self, idx: PointOffsetType) -> OperationResult<()> {
+        let value_ids = self.point_value_ids(idx).to_vec();
+        if value_ids.is_empty() {
+            return Ok(());
+        }
+        self.removed.set(idx as usize, true);
+        self.indexed_points -= 1;
+        self.values_count -= value_ids.len();
+        for value_id in value_ids {
+            self.points_counts[value_id as usize] -= 1;
+            let key = MapIndex::encode_db_record(&self.values[value_id as usize], idx);
+            self.db_wrapper.remove(key)?;
+        }
+        Ok(())
+    }
+
+    fn get_iterator(&self, value: &N) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        match self.value_id(value) {
+            Some(value_id) => Box::new(
+                self.value_points(value_id)
+                    .iter()
+                    .copied()
+                    .filter(|idx| !self.removed[*idx as usize]),
+            ),
+            None => Box::new(iter::empty::<PointOffsetType>()),
+        }
+    }
+
+    fn except_iterator<'a>(
+        &'a self,
+        excluded: &'a [N],
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
+        let iter = self
+            .values
+            .iter()
+            .filter(|value| !excluded.contains(*value))
+            .flat_map(|value| self.get_iterator(value))
+            .unique();
+        Box::new(iter)
+    }
+
+    fn except_cardinality(&self, excluded: &[N]) -> CardinalityEstimation {
+        let excluded_value_counts: Vec<_> = excluded
+            .iter()
+            .map(|value| self.points_count(value))
+            .collect();
+        except_cardinality(
+            &excluded_value_counts,
+            self.values.len(),
+            self.values_count,
+            self.indexed_points,
+        )
+    }
+
+    /// Values with the number of their points
+    fn value_counts(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.values.iter().zip(self.points_counts.iter().copied())
+    }
+}
+
+impl<N: Hash + Eq + Ord + Clone + Display + FromStr> From<MapIndex<N>> for ImmutableMapIndex<N> {
+    fn from(index: MapIndex<N>) -> Self {
+        let (entries, db_wrapper) = index.into_entries();
+        Self::from_entries(entries, db_wrapper)
+    }
+}
+
+impl<N: Ord + Clone + Display + HeapSize> ImmutableMapIndex<N> {
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        PayloadIndexTelemetry {
+            field_name: None,
+            points_count: self.indexed_points,
+            points_values_count: self.values_count,
+            histogram_bucket_size: None,
+            obsolete_values_count: 0,
+            estimated_ram_bytes: self.estimated_ram_bytes(),
+            needs_rebuild: false,
+            on_disk: false,
+        }
+    }
+
+    fn estimated_ram_bytes(&self) -> usize {
+        let values_bytes: usize = self
+            .values
+            .iter()
+            .map(|value| size_of::<N>() + value.heap_size())
+            .sum();
+        let offsets_bytes =
+            (self.value_offsets.len() + self.points_counts.len() + self.point_offsets.len())
+                * size_of::<usize>();
+        values_bytes
+            + offsets_bytes
+            + self.points.len() * size_of::<PointOffsetType>()
+            + self.point_values.len() * size_of::<ValueId>()
+            + self.removed.capacity() / u8::BITS as usize
+    }
+}
+
+impl PayloadFieldIndex for ImmutableMapIndex<String> {
+    fn indexed_points(&self) -> usize {
+        self.indexed_points
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        ImmutableMapIndex::load(self)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        ImmutableMapIndex::flusher(self)
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => Some(self.get_iterator(keyword)),
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => Some(Box::new(
+                keywords
+                    .iter()
+                    .flat_map(|keyword| self.get_iterator(keyword))
+                    .unique(),
+            )),
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => Some(self.except_iterator(keywords)),
+            _ => None,
+        }
+    }
+
+    fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => {
+                let mut estimation = self.match_cardinality(keyword);
+                estimation
+                    .primary_clauses
+                    .push(PrimaryCondition::Condition(condition.clone()));
+                Some(estimation)
+            }
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => {
+                let estimations = keywords
+                    .iter()
+                    .map(|keyword| self.match_cardinality(keyword))
+                    .collect::<Vec<_>>();
+                Some(combine_should_estimations(
+                    &estimations,
+                    self.indexed_points,
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => Some(self.except_cardinality(keywords)),
+            _ => None,
+        }
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        let iter = self
+            .value_counts()
+            .filter(move |(_value, points_count)| *points_count > threshold)
+            .map(move |(value, points_count)| PayloadBlockCondition {
+                condition: FieldCondition::new_match(key.clone(), value.to_owned().into()),
+                cardinality: points_count,
+            });
+        Box::new(iter)
+    }
+
+    fn count_indexed_points(&self) -> usize {
+        self.indexed_points
+    }
+}
+
+impl PayloadFieldIndex for ImmutableMapIndex<IntPayloadType> {
+    fn indexed_points(&self) -> usize {
+        self.indexed_points
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        ImmutableMapIndex::load(self)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        ImmutableMapIndex::flusher(self)
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Integer(integer),
+            })) => Some(self.get_iterator(integer)),
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Integers(integers),
+            })) => Some(Box::new(
+                integers
+                    .iter()
+                    .flat_map(|integer| self.get_iterator(integer))
+                    .unique(),
+            )),
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Integers(integers),
+            })) => Some(self.except_iterator(integers)),
+            _ => None,
+        }
+    }
+
+    fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Integer(integer),
+            })) => {
+                let mut estimation = self.match_cardinality(integer);
+                estimation
+                    .primary_clauses
+                    .push(PrimaryCondition::Condition(condition.clone()));
+                Some(estimation)
+            }
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Integers(integers),
+            })) => {
+                let estimations = integers
+                    .iter()
+                    .map(|integer| self.match_cardinality(integer))
+                    .collect::<Vec<_>>();
+                Some(combine_should_estimations(
+                    &estimations,
+                    self.indexed_points,
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Integers(integers),
+            })) => Some(self.except_cardinality(integers)),
+            _ => None,
+        }
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        let iter = self
+            .value_counts()
+            .filter(move |(_value, points_count)| *points_count >= threshold)
+            .map(move |(value, points_count)| PayloadBlockCondition {
+                condition: FieldCondition::new_match(key.clone(), (*value).into()),
+                cardinality: points_count,
+            });
+        Box::new(iter)
+    }
+
+    fn count_indexed_points(&self) -> usize {
+        self.indexed_points
+    }
+}
+
+/// Collects the points into a `MapIndex` and packs it into the `ImmutableMapIndex` at the end
+pub struct ImmutableMapIndexBuilder<N: Hash + Eq + Clone + Display>(MapIndex<N>);
+
+impl<N: Hash + Eq + Ord + Clone + Display + FromStr> ImmutableMapIndexBuilder<N> {
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> Self {
+        Self(MapIndex::new(db, field_name, false))
+    }
+}
+
+impl<N: Hash + Eq + Ord + Clone + Display + FromStr> FieldIndexBuilder
+    for ImmutableMapIndexBuilder<N>
+where
+    MapIndex<N>: ValueIndexer<N>,
+    ImmutableMapIndex<N>: Into<FieldIndex>,
+{
+    fn init(&mut self) -> OperationResult<()> {
+        self.0.recreate()
+    }
+
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&serde_json::Value>,
+    ) -> OperationResult<()> {
+        self.0.add_point(id, payload)
+    }
+
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex> {
+        Ok(ImmutableMapIndex::from(self.0).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+    use crate::index::field_index::ValueIndexer;
+
+    const FIELD_NAME: &str = "test";
+
+    fn filter(index: &dyn PayloadFieldIndex, r#match: Match) -> Vec<PointOffsetType> {
+        let condition = FieldCondition::new_match(FIELD_NAME, r#match);
+        index.filter(&condition).unwrap().sorted().collect()
+    }
+
+    fn estimation(index: &dyn PayloadFieldIndex, r#match: Match) -> (usize, usize, usize) {
+        let condition = FieldCondition::new_match(FIELD_NAME, r#match);
+        let estimation = index.estimate_cardinality(&condition).unwrap();
+        (estimation.min, estimation.exp, estimation.max)
+    }
+
+    #[test]
+    fn test_immutable_map_index_matches_mutable() {
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut mutable = MapIndex::<String>::new(db.clone(), FIELD_NAME, false);
+        mutable.recreate().unwrap();
+
+        let data = [
+            vec!["a", "b"],
+            vec![],
+            vec!["b", "b", "c"],
+            vec!["a/b"],
+            vec!["c"],
+            vec!["a"],
+        ];
+        for (idx, values) in data.iter().enumerate() {
+            let values = values.iter().map(|value| value.to_string()).collect();
+            mutable.add_many(idx as PointOffsetType, values).unwrap();
+        }
+        mutable.remove_point(4).unwrap();
+        mutable.flusher()().unwrap();
+
+        let mut loaded = ImmutableMapIndex::<String>::new(db.clone(), FIELD_NAME);
+        assert!(loaded.load().unwrap());
+
+        let mut reference = MapIndex::<String>::new(db, FIELD_NAME, false);
+        reference.load().unwrap();
+        let converted = ImmutableMapIndex::from(mutable);
+
+        let keywords = |values: &[&str]| values.iter().map(|value| value.to_string()).collect_vec();
+        let matches = [
+            Match::from("a".to_string()),
+            Match::from("b".to_string()),
+            Match::from("c".to_string()),
+            Match::from("missing".to_string()),
+            Match::from(keywords(&["a", "c"])),
+            Match::Except(keywords(&["b"]).into()),
+            Match::Except(keywords(&["a", "a/b", "b"]).into()),
+        ];
+        for immutable in [&loaded, &converted] {
+            for r#match in &matches {
+                assert_eq!(
+                    filter(immutable, r#match.clone()),
+                    filter(&reference, r#match.clone())
+                );
+                assert_eq!(
+                    estimation(immutable, r#match.clone()),
+                    estimation(&reference, r#match.clone())
+                );
+            }
+            assert_eq!(immutable.indexed_points(), reference.indexed_points());
+            for idx in 0..8 {
+                assert_eq!(immutable.values_count(idx), reference.values_count(idx));
+            }
+            assert_eq!(
+                immutable.get_values(2).unwrap().collect_vec(),
+                vec!["b", "c"]
+            );
+        }
+
+        // Points are still removed, but can't be added
+        loaded.remove_point(0).unwrap();
+        assert_eq!(filter(&loaded, Match::from("a".to_string())), vec![5]);
+        assert_eq!(loaded.match_cardinality(&"b".to_string()).exp, 1);
+        assert!(loaded.values_is_empty(0));
+        assert_eq!(loaded.indexed_points(), 3);
+        assert!(loaded
+            .add_point(0, &MultiValue::Single(Some(&serde_json::json!("a"))))
+            .is_err());
+    }
+
+    #[test]
+    fn test_builder_finalizes_into_immutable_index() {
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut builder: Box<dyn FieldIndexBuilder> = Box::new(
+            ImmutableMapIndexBuilder::<String>::new(db.clone(), FIELD_NAME),
+        );
+        builder.init().unwrap();
+
+        let payloads = [
+            serde_json::json!("a"),
+            serde_json::json!(["a", "b"]),
+            serde_json::json!(1),
+        ];
+        let batch = payloads
+            .iter()
+            .enumerate()
+            .map(|(idx, payload)| (idx as PointOffsetType, MultiValue::one(payload)))
+            .collect_vec();
+        builder.add_point_batch(&batch).unwrap();
+
+        let index = builder.finalize().unwrap();
+        assert!(matches!(index, FieldIndex::ImmutableKeywordIndex(_)));
+        let condition = FieldCondition::new_match(FIELD_NAME, Match::from("a".to_string()));
+        assert_eq!(
+            index.filter(&condition).unwrap().sorted().collect_vec(),
+            vec![0, 1]
+        );
+        index.flusher()().unwrap();
+
+        // Built index is stored in the same format as the mutable one
+        let mut reference = MapIndex::<String>::new(db, FIELD_NAME, false);
+        reference.load().unwrap();
+        assert_eq!(filter(&reference, Match::from("b".to_string())), vec![1]);
+    }
+}
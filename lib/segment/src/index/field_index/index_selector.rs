@@ -3,28 +3,38 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use rocksdb::DB;
 
-use crate::index::field_index::full_text_index::text_index::FullTextIndex;
-use crate::index::field_index::geo_index::GeoMapIndex;
-use crate::index::field_index::map_index::MapIndex;
-use crate::index::field_index::numeric_index::NumericIndex;
-use crate::index::field_index::FieldIndex;
+use crate::index::field_index::full_text_index::text_index::{FullTextIndex, FullTextIndexBuilder};
+use crate::index::field_index::geo_index::{GeoMapIndex, GeoMapIndexBuilder};
+use crate::index::field_index::immutable_map_index::{ImmutableMapIndex, ImmutableMapIndexBuilder};
+use crate::index::field_index::map_index::{MapIndex, MapIndexBuilder};
+use crate::index::field_index::numeric_index::{NumericIndex, NumericIndexBuilder};
+use crate::index::field_index::{FieldIndex, FieldIndexBuilder};
 use crate::types::{
     FloatPayloadType, IntPayloadType, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType,
 };
 
-/// Selects index types based on field type
+/// Selects index types based on field type.
+///
+/// Segments, which are not appendable, get the immutable variants of the in-memory map indexes.
 pub fn index_selector(
     field: &str,
     payload_schema: &PayloadFieldSchema,
     db: Arc<RwLock<DB>>,
+    is_appendable: bool,
 ) -> Vec<FieldIndex> {
     match payload_schema {
         PayloadFieldSchema::FieldType(payload_type) => match payload_type {
-            PayloadSchemaType::Keyword => {
-                vec![FieldIndex::KeywordIndex(MapIndex::new(db, field))]
-            }
+            PayloadSchemaType::Keyword => vec![keyword_index(db, field, false, is_appendable)],
             PayloadSchemaType::Integer => vec![
-                FieldIndex::IntMapIndex(MapIndex::<IntPayloadType>::new(db.clone(), field)),
+                if is_appendable {
+                    FieldIndex::IntMapIndex(MapIndex::<IntPayloadType>::new(
+                        db.clone(),
+                        field,
+                        false,
+                    ))
+                } else {
+                    FieldIndex::ImmutableIntMapIndex(ImmutableMapIndex::new(db.clone(), field))
+                },
                 FieldIndex::IntIndex(NumericIndex::<IntPayloadType>::new(db, field)),
             ],
             PayloadSchemaType::Float => {
@@ -40,9 +50,96 @@ pub fn index_selector(
             ))],
         },
         PayloadFieldSchema::FieldParams(payload_params) => match payload_params {
+            PayloadSchemaParams::Keyword(keyword_index_params) => vec![keyword_index(
+                db,
+                field,
+                keyword_index_params.on_disk.unwrap_or(false),
+                is_appendable,
+            )],
             PayloadSchemaParams::Text(text_index_params) => vec![FieldIndex::FullTextIndex(
                 FullTextIndex::new(db, text_index_params.clone(), field),
             )],
         },
     }
 }
+
+fn keyword_index(
+    db: Arc<RwLock<DB>>,
+    field: &str,
+    on_disk: bool,
+    is_appendable: bool,
+) -> FieldIndex {
+    if is_appendable || on_disk {
+        FieldIndex::KeywordIndex(MapIndex::new(db, field, on_disk))
+    } else {
+        FieldIndex::ImmutableKeywordIndex(ImmutableMapIndex::new(db, field))
+    }
+}
+
+/// Selects builders of the indexes for the field type, same as `index_selector` does.
+///
+/// Builders for the segments, which are not appendable, finalize into the immutable variants.
+pub fn index_builder_selector(
+    field: &str,
+    payload_schema: &PayloadFieldSchema,
+    db: Arc<RwLock<DB>>,
+    is_appendable: bool,
+) -> Vec<Box<dyn FieldIndexBuilder>> {
+    match payload_schema {
+        PayloadFieldSchema::FieldType(payload_type) => match payload_type {
+            PayloadSchemaType::Keyword => {
+                vec![keyword_index_builder(db, field, false, is_appendable)]
+            }
+            PayloadSchemaType::Integer => vec![
+                if is_appendable {
+                    Box::new(MapIndexBuilder::<IntPayloadType>::new(
+                        db.clone(),
+                        field,
+                        false,
+                    ))
+                } else {
+                    Box::new(ImmutableMapIndexBuilder::<IntPayloadType>::new(
+                        db.clone(),
+                        field,
+                    ))
+                },
+                Box::new(NumericIndexBuilder::<IntPayloadType>::new(db, field)),
+            ],
+            PayloadSchemaType::Float => {
+                vec![Box::new(NumericIndexBuilder::<FloatPayloadType>::new(
+                    db, field,
+                ))]
+            }
+            PayloadSchemaType::Geo => vec![Box::new(GeoMapIndexBuilder::new(db, field))],
+            PayloadSchemaType::Text => vec![Box::new(FullTextIndexBuilder::new(
+                db,
+                Default::default(),
+                field,
+            ))],
+        },
+        PayloadFieldSchema::FieldParams(payload_params) => match payload_params {
+            PayloadSchemaParams::Keyword(keyword_index_params) => vec![keyword_index_builder(
+                db,
+                field,
+                keyword_index_params.on_disk.unwrap_or(false),
+                is_appendable,
+            )],
+            PayloadSchemaParams::Text(text_index_params) => vec![Box::new(
+                FullTextIndexBuilder::new(db, text_index_params.clone(), field),
+            )],
+        },
+    }
+}
+
+fn keyword_index_builder(
+    db: Arc<RwLock<DB>>,
+    field: &str,
+    on_disk: bool,
+    is_appendable: bool,
+) -> Box<dyn FieldIndexBuilder> {
+    if is_appendable || on_disk {
+        Box::new(MapIndexBuilder::<String>::new(db, field, on_disk))
+    } else {
+        Box::new(ImmutableMapIndexBuilder::<String>::new(db, field))
+    }
+}
@@ -1,7 +1,8 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::iter;
+use std::mem::size_of;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -11,11 +12,14 @@ use rocksdb::DB;
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
 use crate::common::Flusher;
 use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::field_index::posting_list::PostingList;
 use crate::index::field_index::stat_tools::number_of_selected_points;
 use crate::index::field_index::{
-    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PayloadFieldIndex,
+    PrimaryCondition, ValueIndexer,
 };
 use crate::index::query_estimator::combine_should_estimations;
 use crate::telemetry::PayloadIndexTelemetry;
@@ -25,10 +29,103 @@ use crate::types::{
 };
 use crate::vector_storage::div_ceil;
 
+/// Id of the unique value of the index
+pub type ValueId = u32;
+
+/// Points of each indexed value, by the id of the value
+enum ValuePostings {
+    /// Points are kept in RAM
+    InMemory(Vec<PostingList>),
+    /// Only the number of points is kept in RAM, points are read from the storage on request
+    OnDisk(Vec<usize>),
+}
+
+impl ValuePostings {
+    fn new(on_disk: bool) -> Self {
+        if on_disk {
+            ValuePostings::OnDisk(Default::default())
+        } else {
+            ValuePostings::InMemory(Default::default())
+        }
+    }
+
+    /// Postings of the loaded points, which are listed in any order
+    fn from_points(on_disk: bool, points: Vec<Vec<PointOffsetType>>) -> Self {
+        if on_disk {
+            ValuePostings::OnDisk(points.iter().map(Vec::len).collect())
+        } else {
+            ValuePostings::InMemory(points.into_iter().map(PostingList::from_unsorted).collect())
+        }
+    }
+
+    fn is_on_disk(&self) -> bool {
+        matches!(self, ValuePostings::OnDisk(_))
+    }
+
+    fn points_count(&self, value_id: ValueId) -> usize {
+        match self {
+            ValuePostings::InMemory(postings) => {
+                postings.get(value_id as usize).map_or(0, PostingList::len)
+            }
+            ValuePostings::OnDisk(counts) => counts.get(value_id as usize).copied().unwrap_or(0),
+        }
+    }
+
+    fn insert(&mut self, value_id: ValueId, idx: PointOffsetType) {
+        let value_id = value_id as usize;
+        match self {
+            ValuePostings::InMemory(postings) => {
+                if postings.len() <= value_id {
+                    postings.resize_with(value_id + 1, Default::default);
+                }
+                postings[value_id].insert(idx);
+            }
+            ValuePostings::OnDisk(counts) => {
+                if counts.len() <= value_id {
+                    counts.resize(value_id + 1, 0);
+                }
+                counts[value_id] += 1;
+            }
+        }
+    }
+
+    fn remove(&mut self, value_id: ValueId, idx: PointOffsetType) {
+        match self {
+            ValuePostings::InMemory(postings) => {
+                if let Some(points) = postings.get_mut(value_id as usize) {
+                    points.remove(idx);
+                }
+            }
+            ValuePostings::OnDisk(counts) => {
+                if let Some(count) = counts.get_mut(value_id as usize) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    fn estimated_ram_bytes(&self) -> usize {
+        match self {
+            ValuePostings::InMemory(postings) => {
+                let points_count: usize = postings.iter().map(PostingList::len).sum();
+                postings.capacity() * size_of::<PostingList>()
+                    + points_count * size_of::<PointOffsetType>()
+            }
+            ValuePostings::OnDisk(counts) => counts.capacity() * size_of::<usize>(),
+        }
+    }
+}
+
 /// HashMap-based type of index
+///
+/// Each unique value is stored once and referred by its id, both in the postings
+/// and in the values of the points.
 pub struct MapIndex<N: Hash + Eq + Clone + Display> {
-    map: HashMap<N, BTreeSet<PointOffsetType>>,
-    point_to_values: Vec<Vec<N>>,
+    value_ids: HashMap<N, ValueId>,
+    /// Unique values by their ids, including the ones without points left
+    values: Vec<N>,
+    postings: ValuePostings,
+    point_to_values: Vec<Vec<ValueId>>,
     /// Amount of point which have at least one indexed payload value
     indexed_points: usize,
     values_count: usize,
@@ -36,11 +133,14 @@ pub struct MapIndex<N: Hash + Eq + Clone + Display> {
 }
 
 impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
-    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> MapIndex<N> {
+    /// If `on_disk` - points of the values are not kept in RAM, but read from the storage
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str, on_disk: bool) -> MapIndex<N> {
         let store_cf_name = Self::storage_cf_name(field_name);
         let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
         MapIndex {
-            map: Default::default(),
+            value_ids: HashMap::new(),
+            values: Vec::new(),
+            postings: ValuePostings::new(on_disk),
             point_to_values: Vec::new(),
             indexed_points: 0,
             values_count: 0,
@@ -48,7 +148,7 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         }
     }
 
-    fn storage_cf_name(field: &str) -> String {
+    pub(super) fn storage_cf_name(field: &str) -> String {
         format!("{field}_map")
     }
 
@@ -61,11 +161,15 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
             return Ok(false);
         }
         self.indexed_points = 0;
+        // Records are ordered by their string representation, so the points of the values
+        // are sorted once all of them are read
+        let mut value_points: Vec<Vec<PointOffsetType>> = vec![];
         for (record, _) in self.db_wrapper.lock_db().iter()? {
             let record = std::str::from_utf8(&record).map_err(|_| {
                 OperationError::service_error("Index load error: UTF8 error while DB parsing")
             })?;
             let (value, idx) = Self::decode_db_record(record)?;
+            let value_id = self.intern(value);
             if self.point_to_values.len() <= idx as usize {
                 self.point_to_values.resize(idx as usize + 1, Vec::new())
             }
@@ -73,18 +177,52 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
                 self.indexed_points += 1;
             }
             self.values_count += 1;
-            self.point_to_values[idx as usize].push(value.clone());
-            self.map.entry(value).or_default().insert(idx);
+            if value_points.len() <= value_id as usize {
+                value_points.resize(value_id as usize + 1, Vec::new());
+            }
+            value_points[value_id as usize].push(idx);
+            self.point_to_values[idx as usize].push(value_id);
         }
+        self.postings = ValuePostings::from_points(self.is_on_disk(), value_points);
         Ok(true)
     }
 
+    /// Id of the value, assigned on its first occurrence
+    fn intern(&mut self, value: N) -> ValueId {
+        if let Some(&value_id) = self.value_ids.get(&value) {
+            return value_id;
+        }
+        let value_id = self.values.len() as ValueId;
+        self.value_ids.insert(value.clone(), value_id);
+        self.values.push(value);
+        value_id
+    }
+
+    /// If true - points of the values are read from the storage on request
+    pub fn is_on_disk(&self) -> bool {
+        self.postings.is_on_disk()
+    }
+
     pub fn flusher(&self) -> Flusher {
         self.db_wrapper.flusher()
     }
 
+    fn points_count(&self, value: &N) -> usize {
+        self.value_ids
+            .get(value)
+            .map_or(0, |value_id| self.postings.points_count(*value_id))
+    }
+
+    /// Values with the number of their points
+    fn value_counts(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(value_id, value)| (value, self.postings.points_count(value_id as ValueId)))
+    }
+
     pub fn match_cardinality(&self, value: &N) -> CardinalityEstimation {
-        let values_count = self.map.get(value).map(|p| p.len()).unwrap_or(0);
+        let values_count = self.points_count(value);
 
         CardinalityEstimation {
             primary_clauses: vec![],
@@ -94,17 +232,12 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         }
     }
 
-    pub fn get_values(&self, idx: PointOffsetType) -> Option<&Vec<N>> {
-        self.point_to_values.get(idx as usize)
-    }
-
-    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
-        PayloadIndexTelemetry {
-            field_name: None,
-            points_count: self.indexed_points,
-            points_values_count: self.values_count,
-            histogram_bucket_size: None,
-        }
+    pub fn get_values(&self, idx: PointOffsetType) -> Option<impl Iterator<Item = &N> + '_> {
+        self.point_to_values.get(idx as usize).map(|value_ids| {
+            value_ids
+                .iter()
+                .map(|value_id| &self.values[*value_id as usize])
+        })
     }
 
     fn add_many_to_map(&mut self, idx: PointOffsetType, values: Vec<N>) -> OperationResult<()> {
@@ -116,30 +249,84 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         if self.point_to_values.len() <= idx as usize {
             self.point_to_values.resize(idx as usize + 1, Vec::new())
         }
-        self.point_to_values[idx as usize] = values.into_iter().collect();
-        for value in &self.point_to_values[idx as usize] {
-            let entry = self.map.entry(value.clone()).or_default();
-            entry.insert(idx);
+        let value_ids = values.into_iter().map(|value| self.intern(value)).collect();
+        self.point_to_values[idx as usize] = value_ids;
+        for value_id in self.point_to_values[idx as usize].iter().unique() {
+            self.postings.insert(*value_id, idx);
 
-            let db_record = Self::encode_db_record(value, idx);
+            let db_record = Self::encode_db_record(&self.values[*value_id as usize], idx);
             self.db_wrapper.put(db_record, [])?;
         }
         self.indexed_points += 1;
         Ok(())
     }
 
+    /// Indexed values with their points, along with the storage of the index
+    pub(super) fn into_entries(self) -> (Vec<(N, PointOffsetType)>, DatabaseColumnWrapper) {
+        let values = &self.values;
+        let entries = self
+            .point_to_values
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, value_ids)| {
+                let idx = idx as PointOffsetType;
+                value_ids
+                    .iter()
+                    .map(move |value_id| (values[*value_id as usize].clone(), idx))
+            })
+            .collect();
+        (entries, self.db_wrapper)
+    }
+
     fn get_iterator(&self, value: &N) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
-        self.map
-            .get(value)
-            .map(|ids| Box::new(ids.iter().copied()) as Box<dyn Iterator<Item = PointOffsetType>>)
-            .unwrap_or_else(|| Box::new(iter::empty::<PointOffsetType>()))
+        let Some(&value_id) = self.value_ids.get(value) else {
+            return Box::new(iter::empty::<PointOffsetType>());
+        };
+        match &self.postings {
+            ValuePostings::InMemory(postings) => match postings.get(value_id as usize) {
+                Some(points) => Box::new(points.iter()),
+                None => Box::new(iter::empty::<PointOffsetType>()),
+            },
+            ValuePostings::OnDisk(_) => match self.read_points(value) {
+                Ok(points) => Box::new(points.into_iter()),
+                Err(err) => {
+                    log::error!("Failed to read points of `{value}` from the index: {err}");
+                    Box::new(iter::empty::<PointOffsetType>())
+                }
+            },
+        }
+    }
+
+    /// Points of the value, read from the storage in ascending order
+    fn read_points(&self, value: &N) -> OperationResult<Vec<PointOffsetType>> {
+        if self.points_count(value) == 0 {
+            return Ok(vec![]);
+        }
+        let prefix = format!("{value}/");
+        let db = self.db_wrapper.lock_db();
+        let mut points = vec![];
+        for (record, _) in db.iter_from(prefix.as_bytes())? {
+            if !record.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let record = std::str::from_utf8(&record).map_err(|_| {
+                OperationError::service_error("Index read error: UTF8 error while DB parsing")
+            })?;
+            // Values, which contain the separator, share the prefix with the requested one
+            let (record_value, idx) = Self::decode_db_record(record)?;
+            if record_value == *value {
+                points.push(idx);
+            }
+        }
+        points.sort_unstable();
+        Ok(points)
     }
 
-    fn encode_db_record(value: &N, idx: PointOffsetType) -> String {
+    pub(super) fn encode_db_record(value: &N, idx: PointOffsetType) -> String {
         format!("{value}/{idx}")
     }
 
-    fn decode_db_record(s: &str) -> OperationResult<(N, PointOffsetType)> {
+    pub(super) fn decode_db_record(s: &str) -> OperationResult<(N, PointOffsetType)> {
         const DECODE_ERR: &str = "Index db parsing error: wrong data format";
         let separator_pos = s
             .rfind('/')
@@ -168,11 +355,9 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         }
         self.values_count -= removed_values.len();
 
-        for value in &removed_values {
-            if let Some(vals) = self.map.get_mut(value) {
-                vals.remove(&idx);
-            }
-            let key = MapIndex::encode_db_record(value, idx);
+        for value_id in removed_values.iter().unique() {
+            self.postings.remove(*value_id, idx);
+            let key = MapIndex::encode_db_record(&self.values[*value_id as usize], idx);
             self.db_wrapper.remove(key)?;
         }
 
@@ -180,11 +365,15 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
     }
 
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
-        self.get_values(point_id).map(|x| x.len()).unwrap_or(0)
+        self.point_to_values
+            .get(point_id as usize)
+            .map(|x| x.len())
+            .unwrap_or(0)
     }
 
     pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
-        self.get_values(point_id)
+        self.point_to_values
+            .get(point_id as usize)
             .map(|x| x.is_empty())
             .unwrap_or(true)
     }
@@ -199,111 +388,166 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
     ///
     /// * `CardinalityEstimation` - estimation of cardinality
     fn except_cardinality(&self, excluded: &[N]) -> CardinalityEstimation {
-        // Minimal case: we exclude as many points as possible.
-        // In this case, excluded points do not have any other values except excluded ones.
-        // So the first step - we estimate how many other points is needed to fit unused values.
-
-        // Example:
-        // Values: 20, 20
-        // Unique values: 5
-        // Total points: 100
-        // Total values: 110
-        // total_excluded_value_count = 40
-        // non_excluded_values_count = 110 - 40 = 70
-        // max_values_per_point = 5 - 2 = 3
-        // min_not_excluded_by_values = 70 / 3 = 24
-        // min = max(24, 100 - 40) = 60
-        // exp = ...
-        // max = min(20, 70) = 20
-
-        // Values: 60, 60
-        // Unique values: 5
-        // Total points: 100
-        // Total values: 200
-        // total_excluded_value_count = 120
-        // non_excluded_values_count = 200 - 120 = 80
-        // max_values_per_point = 5 - 2 = 3
-        // min_not_excluded_by_values = 80 / 3 = 27
-        // min = max(27, 100 - 120) = 27
-        // exp = ...
-        // max = min(60, 80) = 60
-
-        // Values: 60, 60, 60
-        // Unique values: 5
-        // Total points: 100
-        // Total values: 200
-        // total_excluded_value_count = 180
-        // non_excluded_values_count = 200 - 180 = 20
-        // max_values_per_point = 5 - 3 = 2
-        // min_not_excluded_by_values = 20 / 2 = 10
-        // min = max(10, 100 - 180) = 10
-        // exp = ...
-        // max = min(60, 20) = 20
-
-        let excluded_value_counts: Vec<_> = excluded
+        let excluded_value_counts: Vec<_> =
+            excluded.iter().map(|val| self.points_count(val)).collect();
+        except_cardinality(
+            &excluded_value_counts,
+            self.values.len(),
+            self.values_count,
+            self.indexed_points,
+        )
+    }
+
+    fn except_iterator<'a>(
+        &'a self,
+        excluded: &'a [N],
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
+        let iter = self
+            .values
             .iter()
-            .map(|val| self.map.get(val).map(|points| points.len()).unwrap_or(0))
-            .collect();
-        let total_excluded_value_count: usize = excluded_value_counts.iter().sum();
+            .filter(|key| !excluded.contains(*key))
+            .flat_map(|key| self.get_iterator(key))
+            .unique();
+        Box::new(iter)
+    }
+}
 
-        debug_assert!(total_excluded_value_count <= self.values_count);
+/// Estimates cardinality for `except` clause, given the number of points of each excluded value
+/// and the statistics of the whole index
+pub(super) fn except_cardinality(
+    excluded_value_counts: &[usize],
+    unique_values_count: usize,
+    values_count: usize,
+    indexed_points: usize,
+) -> CardinalityEstimation {
+    // Minimal case: we exclude as many points as possible.
+    // In this case, excluded points do not have any other values except excluded ones.
+    // So the first step - we estimate how many other points is needed to fit unused values.
+
+    // Example:
+    // Values: 20, 20
+    // Unique values: 5
+    // Total points: 100
+    // Total values: 110
+    // total_excluded_value_count = 40
+    // non_excluded_values_count = 110 - 40 = 70
+    // max_values_per_point = 5 - 2 = 3
+    // min_not_excluded_by_values = 70 / 3 = 24
+    // min = max(24, 100 - 40) = 60
+    // exp = ...
+    // max = min(20, 70) = 20
+
+    // Values: 60, 60
+    // Unique values: 5
+    // Total points: 100
+    // Total values: 200
+    // total_excluded_value_count = 120
+    // non_excluded_values_count = 200 - 120 = 80
+    // max_values_per_point = 5 - 2 = 3
+    // min_not_excluded_by_values = 80 / 3 = 27
+    // min = max(27, 100 - 120) = 27
+    // exp = ...
+    // max = min(60, 80) = 60
+
+    // Values: 60, 60, 60
+    // Unique values: 5
+    // Total points: 100
+    // Total values: 200
+    // total_excluded_value_count = 180
+    // non_excluded_values_count = 200 - 180 = 20
+    // max_values_per_point = 5 - 3 = 2
+    // min_not_excluded_by_values = 20 / 2 = 10
+    // min = max(10, 100 - 180) = 10
+    // exp = ...
+    // max = min(60, 20) = 20
+
+    let total_excluded_value_count: usize = excluded_value_counts.iter().sum();
+
+    debug_assert!(total_excluded_value_count <= values_count);
+
+    let non_excluded_values_count = values_count.saturating_sub(total_excluded_value_count);
+    let max_values_per_point = unique_values_count.saturating_sub(excluded_value_counts.len());
+
+    if max_values_per_point == 0 {
+        // All points are excluded, so we can't select any point
+        debug_assert_eq!(non_excluded_values_count, 0);
+        return CardinalityEstimation::exact(0);
+    }
 
-        let non_excluded_values_count =
-            self.values_count.saturating_sub(total_excluded_value_count);
-        let max_values_per_point = self.map.len().saturating_sub(excluded.len());
+    // Minimal amount of points, required to fit all unused values.
+    // Cardinality can't be less than this value.
+    let min_not_excluded_by_values = div_ceil(non_excluded_values_count, max_values_per_point);
 
-        if max_values_per_point == 0 {
-            // All points are excluded, so we can't select any point
-            debug_assert_eq!(non_excluded_values_count, 0);
-            return CardinalityEstimation::exact(0);
-        }
+    let min =
+        min_not_excluded_by_values.max(indexed_points.saturating_sub(total_excluded_value_count));
 
-        // Minimal amount of points, required to fit all unused values.
-        // Cardinality can't be less than this value.
-        let min_not_excluded_by_values = div_ceil(non_excluded_values_count, max_values_per_point);
+    // Maximum scenario: selected points overlap as much as possible.
+    // From one side, all excluded values should be assigned to the same point
+    // => we can take the value with the maximum amount of points.
+    // From another side, all other values should be enough to fill all other points.
 
-        let min = min_not_excluded_by_values.max(
-            self.indexed_points
-                .saturating_sub(total_excluded_value_count),
-        );
+    let max_excluded_value_count = excluded_value_counts.iter().max().copied().unwrap_or(0);
 
-        // Maximum scenario: selected points overlap as much as possible.
-        // From one side, all excluded values should be assigned to the same point
-        // => we can take the value with the maximum amount of points.
-        // From another side, all other values should be enough to fill all other points.
+    let max = indexed_points
+        .saturating_sub(max_excluded_value_count)
+        .min(non_excluded_values_count);
 
-        let max_excluded_value_count = excluded_value_counts.iter().max().copied().unwrap_or(0);
+    // Expected case: we assume that all points are filled equally.
+    // So we can estimate the probability of the point to have non-excluded value.
+    let exp = number_of_selected_points(indexed_points, non_excluded_values_count)
+        .max(min)
+        .min(max);
 
-        let max = self
-            .indexed_points
-            .saturating_sub(max_excluded_value_count)
-            .min(non_excluded_values_count);
+    CardinalityEstimation {
+        primary_clauses: vec![],
+        min,
+        exp,
+        max,
+    }
+}
 
-        // Expected case: we assume that all points are filled equally.
-        // So we can estimate the probability of the point to have non-excluded value.
-        let exp = number_of_selected_points(self.indexed_points, non_excluded_values_count)
-            .max(min)
-            .min(max);
+/// Memory, allocated by an indexed value outside of the index containers
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
 
-        CardinalityEstimation {
-            primary_clauses: vec![],
-            min,
-            exp,
-            max,
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl HeapSize for IntPayloadType {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<N: Hash + Eq + Clone + Display + HeapSize> MapIndex<N> {
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        PayloadIndexTelemetry {
+            field_name: None,
+            points_count: self.indexed_points,
+            points_values_count: self.values_count,
+            histogram_bucket_size: None,
+            obsolete_values_count: 0,
+            estimated_ram_bytes: self.estimated_ram_bytes(),
+            needs_rebuild: false,
+            on_disk: self.is_on_disk(),
         }
     }
 
-    fn except_iterator<'a>(
-        &'a self,
-        excluded: &'a [N],
-    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
-        let iter = self
-            .map
-            .keys()
-            .filter(|key| !excluded.contains(*key))
-            .flat_map(|key| self.get_iterator(key))
-            .unique();
-        Box::new(iter)
+    /// Unique values, kept both as keys of the ids and by the ids, postings and the value ids
+    /// of the points. Postings of the on-disk index are only a counter
+    fn estimated_ram_bytes(&self) -> usize {
+        let values_bytes: usize = self
+            .values
+            .iter()
+            .map(|value| 2 * (size_of::<N>() + value.heap_size()) + size_of::<ValueId>())
+            .sum();
+        let points_bytes = self.point_to_values.capacity() * size_of::<Vec<ValueId>>()
+            + self.values_count * size_of::<ValueId>();
+        values_bytes + self.postings.estimated_ram_bytes() + points_bytes
     }
 }
 
@@ -383,12 +627,11 @@ impl PayloadFieldIndex for MapIndex<String> {
         key: PayloadKeyType,
     ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
         let iter = self
-            .map
-            .iter()
-            .filter(move |(_value, point_ids)| point_ids.len() > threshold)
-            .map(move |(value, point_ids)| PayloadBlockCondition {
+            .value_counts()
+            .filter(move |(_value, points_count)| *points_count > threshold)
+            .map(move |(value, points_count)| PayloadBlockCondition {
                 condition: FieldCondition::new_match(key.clone(), value.to_owned().into()),
-                cardinality: point_ids.len(),
+                cardinality: points_count,
             });
         Box::new(iter)
     }
@@ -474,12 +717,11 @@ impl PayloadFieldIndex for MapIndex<IntPayloadType> {
         key: PayloadKeyType,
     ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
         let iter = self
-            .map
-            .iter()
-            .filter(move |(_value, point_ids)| point_ids.len() >= threshold)
-            .map(move |(value, point_ids)| PayloadBlockCondition {
+            .value_counts()
+            .filter(move |(_value, points_count)| *points_count >= threshold)
+            .map(move |(value, points_count)| PayloadBlockCondition {
                 condition: FieldCondition::new_match(key.clone(), (*value).into()),
-                cardinality: point_ids.len(),
+                cardinality: points_count,
             });
         Box::new(iter)
     }
@@ -527,13 +769,44 @@ impl ValueIndexer<IntPayloadType> for MapIndex<IntPayloadType> {
     }
 }
 
+pub struct MapIndexBuilder<N: Hash + Eq + Clone + Display>(MapIndex<N>);
+
+impl<N: Hash + Eq + Clone + Display + FromStr> MapIndexBuilder<N> {
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str, on_disk: bool) -> Self {
+        Self(MapIndex::new(db, field_name, on_disk))
+    }
+}
+
+impl<N: Hash + Eq + Clone + Display + FromStr> FieldIndexBuilder for MapIndexBuilder<N>
+where
+    MapIndex<N>: ValueIndexer<N> + Into<FieldIndex>,
+{
+    fn init(&mut self) -> OperationResult<()> {
+        self.0.recreate()
+    }
+
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        self.0.add_point(id, payload)
+    }
+
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex> {
+        Ok(self.0.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{BTreeSet, HashSet};
     use std::fmt::Debug;
     use std::iter::FromIterator;
     use std::path::Path;
 
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
     use tempfile::Builder;
 
     use super::*;
@@ -545,7 +818,8 @@ mod tests {
         data: &[Vec<N>],
         path: &Path,
     ) {
-        let mut index = MapIndex::<N>::new(open_db_with_existing_cf(path).unwrap(), FIELD_NAME);
+        let mut index =
+            MapIndex::<N>::new(open_db_with_existing_cf(path).unwrap(), FIELD_NAME, false);
         index.recreate().unwrap();
         for (idx, values) in data.iter().enumerate() {
             index
@@ -559,16 +833,12 @@ mod tests {
         data: &[Vec<N>],
         path: &Path,
     ) {
-        let mut index = MapIndex::<N>::new(open_db_with_existing_cf(path).unwrap(), FIELD_NAME);
+        let mut index =
+            MapIndex::<N>::new(open_db_with_existing_cf(path).unwrap(), FIELD_NAME, false);
         index.load().unwrap();
         for (idx, values) in data.iter().enumerate() {
-            let index_values: HashSet<N> = HashSet::from_iter(
-                index
-                    .get_values(idx as PointOffsetType)
-                    .unwrap()
-                    .iter()
-                    .cloned(),
-            );
+            let index_values: HashSet<N> =
+                HashSet::from_iter(index.get_values(idx as PointOffsetType).unwrap().cloned());
             let check_values: HashSet<N> = HashSet::from_iter(values.iter().cloned());
             assert_eq!(index_values, check_values);
         }
@@ -619,4 +889,195 @@ mod tests {
         save_map_index(&data, temp_dir.path());
         load_map_index(&data, temp_dir.path());
     }
+
+    fn points(index: &MapIndex<String>, value: &str) -> Vec<PointOffsetType> {
+        index.get_iterator(&value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_on_disk_map_index() {
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut index = MapIndex::<String>::new(db.clone(), FIELD_NAME, true);
+        index.recreate().unwrap();
+
+        let data = [vec!["a", "a/b"], vec!["a"], vec!["b", "b"], vec!["a/b"]];
+        for (idx, values) in data.iter().enumerate() {
+            let values = values.iter().map(|value| value.to_string()).collect();
+            index
+                .add_many_to_map(idx as PointOffsetType, values)
+                .unwrap();
+        }
+
+        // Value, which contains the separator, is not mixed up with its prefix
+        assert_eq!(points(&index, "a"), vec![0, 1]);
+        assert_eq!(points(&index, "a/b"), vec![0, 3]);
+        assert_eq!(points(&index, "b"), vec![2]);
+        assert!(points(&index, "c").is_empty());
+        assert_eq!(index.match_cardinality(&"b".to_string()).exp, 1);
+
+        index.remove_point(0).unwrap();
+        assert_eq!(points(&index, "a"), vec![1]);
+        assert_eq!(index.match_cardinality(&"a".to_string()).exp, 1);
+        index.flusher()().unwrap();
+
+        let mut loaded = MapIndex::<String>::new(db, FIELD_NAME, true);
+        loaded.load().unwrap();
+        assert!(loaded.is_on_disk());
+        assert_eq!(points(&loaded, "a/b"), vec![3]);
+        assert_eq!(loaded.match_cardinality(&"a/b".to_string()).exp, 1);
+        let telemetry = loaded.get_telemetry_data();
+        assert!(telemetry.on_disk);
+        assert_eq!(telemetry.points_count, 3);
+    }
+
+    /// RAM of the former layout: a copy of the value per point and per posting,
+    /// points of the postings in a `BTreeSet`
+    fn legacy_ram_bytes(data: &[Vec<String>]) -> usize {
+        let mut postings: HashMap<&String, BTreeSet<PointOffsetType>> = HashMap::new();
+        for (idx, values) in data.iter().enumerate() {
+            for value in values {
+                postings
+                    .entry(value)
+                    .or_default()
+                    .insert(idx as PointOffsetType);
+            }
+        }
+        let postings_bytes: usize = postings
+            .iter()
+            .map(|(value, points)| {
+                (size_of::<String>() + value.capacity()) * (1 + points.len())
+                    + size_of::<BTreeSet<PointOffsetType>>()
+                    + points.len() * size_of::<PointOffsetType>()
+            })
+            .sum();
+        postings_bytes + data.len() * size_of::<Vec<String>>()
+    }
+
+    #[test]
+    fn test_interned_values_memory() {
+        const NUM_POINTS: usize = 1_000_000;
+        const NUM_VALUES: usize = 100;
+
+        let data = (0..NUM_POINTS)
+            .map(|idx| vec![format!("category-{:032}", idx % NUM_VALUES)])
+            .collect_vec();
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut index = MapIndex::<String>::new(db.clone(), FIELD_NAME, false);
+        index.recreate().unwrap();
+        for (idx, values) in data.iter().enumerate() {
+            index
+                .add_many_to_map(idx as PointOffsetType, values.clone())
+                .unwrap();
+        }
+        index.flusher()().unwrap();
+
+        let ram_bytes = index.estimated_ram_bytes();
+        let legacy_bytes = legacy_ram_bytes(&data);
+        assert!(
+            ram_bytes * 2 < legacy_bytes,
+            "{ram_bytes} bytes, {legacy_bytes} bytes before"
+        );
+
+        let mut loaded = MapIndex::<String>::new(db, FIELD_NAME, false);
+        loaded.load().unwrap();
+
+        for value_idx in [0, 42, NUM_VALUES - 1] {
+            let value = format!("category-{value_idx:032}");
+            let expected = (value_idx..NUM_POINTS)
+                .step_by(NUM_VALUES)
+                .map(|idx| idx as PointOffsetType)
+                .collect_vec();
+            assert_eq!(points(&index, &value), expected);
+            assert_eq!(points(&loaded, &value), expected);
+            assert_eq!(
+                loaded.match_cardinality(&value).exp,
+                NUM_POINTS / NUM_VALUES
+            );
+        }
+    }
+
+    fn filter(index: &MapIndex<String>, r#match: Match) -> Vec<PointOffsetType> {
+        let condition = FieldCondition::new_match(FIELD_NAME, r#match);
+        index.filter(&condition).unwrap().sorted().collect()
+    }
+
+    #[test]
+    fn test_filter_matches_reference() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let all_values = (0..20).map(|i| format!("value_{i}")).collect_vec();
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut index = MapIndex::<String>::new(db.clone(), FIELD_NAME, false);
+        index.recreate().unwrap();
+
+        let mut reference: Vec<Vec<String>> = vec![];
+        for idx in 0..1000 {
+            let values = (0..rng.gen_range(0..5))
+                .map(|_| all_values[rng.gen_range(0..all_values.len())].clone())
+                .unique()
+                .collect_vec();
+            index
+                .add_many_to_map(idx as PointOffsetType, values.clone())
+                .unwrap();
+            reference.push(values);
+        }
+        for idx in (0..1000).step_by(7) {
+            index.remove_point(idx as PointOffsetType).unwrap();
+            reference[idx].clear();
+        }
+        index.flusher()().unwrap();
+
+        let mut loaded = MapIndex::<String>::new(db.clone(), FIELD_NAME, false);
+        loaded.load().unwrap();
+        let mut on_disk = MapIndex::<String>::new(db, FIELD_NAME, true);
+        on_disk.load().unwrap();
+
+        let expected = |check: &dyn Fn(&String) -> bool| {
+            reference
+                .iter()
+                .positions(|values| values.iter().any(check))
+                .map(|idx| idx as PointOffsetType)
+                .collect_vec()
+        };
+
+        let selected = all_values[..3].to_vec();
+        for index in [&index, &loaded, &on_disk] {
+            for value in &all_values {
+                let value_points = expected(&|v| v == value);
+                assert_eq!(filter(index, value.clone().into()), value_points);
+                assert_eq!(index.match_cardinality(value).exp, value_points.len());
+            }
+            assert!(filter(index, "missing".to_string().into()).is_empty());
+
+            assert_eq!(
+                filter(index, selected.clone().into()),
+                expected(&|v| selected.contains(v))
+            );
+            assert_eq!(
+                filter(index, Match::Except(selected.clone().into())),
+                expected(&|v| !selected.contains(v))
+            );
+
+            let condition =
+                FieldCondition::new_match(FIELD_NAME, Match::Except(selected.clone().into()));
+            let estimation = index.estimate_cardinality(&condition).unwrap();
+            let reference_estimation = loaded.estimate_cardinality(&condition).unwrap();
+            assert_eq!(estimation.min, reference_estimation.min);
+            assert_eq!(estimation.exp, reference_estimation.exp);
+            assert_eq!(estimation.max, reference_estimation.max);
+
+            for (idx, values) in reference.iter().enumerate() {
+                assert_eq!(
+                    index
+                        .get_values(idx as PointOffsetType)
+                        .map_or(0, |values| values.count()),
+                    values.len()
+                );
+            }
+        }
+    }
 }
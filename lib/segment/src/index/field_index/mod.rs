@@ -7,9 +7,11 @@ pub mod full_text_index;
 pub mod geo_hash;
 pub mod geo_index;
 mod histogram;
+pub mod immutable_map_index;
 pub mod index_selector;
 pub mod map_index;
 pub mod numeric_index;
+mod posting_list;
 mod stat_tools;
 
 #[cfg(test)]
@@ -24,6 +26,8 @@ pub enum PrimaryCondition {
     IsEmpty(IsEmptyCondition),
     IsNull(IsNullCondition),
     Ids(HashSet<PointOffsetType>),
+    /// Points, updated at or after the time, in milliseconds since UNIX epoch
+    ModifiedSince(u64),
 }
 
 #[derive(Debug, Clone)]
@@ -45,7 +49,6 @@ pub struct CardinalityEstimation {
 }
 
 impl CardinalityEstimation {
-    #[allow(dead_code)]
     pub fn exact(count: usize) -> Self {
         CardinalityEstimation {
             primary_clauses: vec![],
@@ -1,5 +1,6 @@
 use std::cmp::{max, min};
 use std::collections::BTreeMap;
+use std::mem::size_of;
 use std::ops::Bound;
 use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::sync::Arc;
@@ -9,16 +10,19 @@ use rocksdb::DB;
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
 use crate::common::Flusher;
+use crate::data_types::order_by::Direction;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::index::field_index::histogram::{Histogram, Numericable, Point};
 use crate::index::field_index::stat_tools::estimate_multi_value_selection_cardinality;
 use crate::index::field_index::{
-    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PayloadFieldIndex,
+    PrimaryCondition, ValueIndexer,
 };
 use crate::index::key_encoding::{
     decode_f64_key_ascending, decode_i64_key_ascending, encode_f64_key_ascending,
-    encode_i64_key_ascending,
+    encode_i64_key_ascending, F64_KEY_LEN, I64_KEY_LEN,
 };
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
@@ -29,11 +33,16 @@ const HISTOGRAM_MAX_BUCKET_SIZE: usize = 10_000;
 const HISTOGRAM_PRECISION: f64 = 0.01;
 
 pub trait Encodable: Copy {
+    /// Length of the encoded keys, which are decoded with `decode_key`
+    const KEY_LEN: usize;
+
     fn encode_key(&self, id: PointOffsetType) -> Vec<u8>;
     fn decode_key(key: &[u8]) -> (PointOffsetType, Self);
 }
 
 impl Encodable for IntPayloadType {
+    const KEY_LEN: usize = I64_KEY_LEN;
+
     fn encode_key(&self, id: PointOffsetType) -> Vec<u8> {
         encode_i64_key_ascending(*self, id)
     }
@@ -43,6 +52,8 @@ impl Encodable for IntPayloadType {
 }
 
 impl Encodable for FloatPayloadType {
+    const KEY_LEN: usize = F64_KEY_LEN;
+
     fn encode_key(&self, id: PointOffsetType) -> Vec<u8> {
         encode_f64_key_ascending(*self, id)
     }
@@ -115,7 +126,15 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         };
 
         for (key, value) in self.db_wrapper.lock_db().iter()? {
-            let value_idx = u32::from_be_bytes(value.as_ref().try_into().unwrap());
+            let value_idx = u32::from_be_bytes(
+                value
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| OperationError::service_error("incorrect value length"))?,
+            );
+            if key.len() != T::KEY_LEN {
+                return Err(OperationError::service_error("incorrect key length"));
+            }
             let (idx, value) = T::decode_key(&key);
 
             if idx != value_idx {
@@ -167,6 +186,87 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         self.point_to_values.get(idx as usize)
     }
 
+    /// Indexed values along with their points, ordered by the value.
+    /// A point with several values is yielded once per value
+    pub fn ordered_points(
+        &self,
+        direction: Direction,
+    ) -> Box<dyn Iterator<Item = (T, PointOffsetType)> + '_> {
+        self.range_iter_sorted(&Range::default(), direction)
+    }
+
+    /// Indexed values within the `range` along with their points, ordered by the value.
+    /// Values are read in order from the sorted map, so the iteration may be stopped at any point.
+    /// A point with several values in the range is yielded once per value
+    pub fn range_iter_sorted(
+        &self,
+        range: &Range,
+        direction: Direction,
+    ) -> Box<dyn Iterator<Item = (T, PointOffsetType)> + '_> {
+        let Some(bounds) = Self::range_bounds(range) else {
+            return Box::new(std::iter::empty());
+        };
+        let points = self.map.range(bounds).map(|(key, idx)| {
+            let (_, value) = T::decode_key(key);
+            (value, *idx)
+        });
+        match direction {
+            Direction::Asc => Box::new(points),
+            Direction::Desc => Box::new(points.rev()),
+        }
+    }
+
+    /// `n` smallest values along with their points
+    pub fn first_n(&self, n: usize) -> Vec<(T, PointOffsetType)> {
+        self.ordered_points(Direction::Asc).take(n).collect()
+    }
+
+    /// `n` largest values along with their points, the largest first
+    pub fn last_n(&self, n: usize) -> Vec<(T, PointOffsetType)> {
+        self.ordered_points(Direction::Desc).take(n).collect()
+    }
+
+    /// Bounds of the keys of the `range` in the map, `None` if the range is empty
+    fn range_bounds(range: &Range) -> Option<(Bound<Vec<u8>>, Bound<Vec<u8>>)> {
+        let start_bound = match range {
+            Range { gt: Some(gt), .. } => {
+                let v: T = T::from_f64(*gt);
+                Excluded(v.encode_key(PointOffsetType::MAX))
+            }
+            Range { gte: Some(gte), .. } => {
+                let v: T = T::from_f64(*gte);
+                Included(v.encode_key(PointOffsetType::MIN))
+            }
+            _ => Unbounded,
+        };
+
+        let end_bound = match range {
+            Range { lt: Some(lt), .. } => {
+                let v: T = T::from_f64(*lt);
+                Excluded(v.encode_key(PointOffsetType::MIN))
+            }
+            Range { lte: Some(lte), .. } => {
+                let v: T = T::from_f64(*lte);
+                Included(v.encode_key(PointOffsetType::MAX))
+            }
+            _ => Unbounded,
+        };
+
+        // map.range
+        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
+        match (&start_bound, &end_bound) {
+            (Excluded(s), Excluded(e)) if s == e => {
+                // range start and end are equal and excluded in BTreeMap
+                None
+            }
+            (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
+                //range start is greater than range end
+                None
+            }
+            _ => Some((start_bound, end_bound)),
+        }
+    }
+
     #[allow(clippy::manual_clamp)] // false positive
     fn range_cardinality(&self, range: &Range) -> CardinalityEstimation {
         let lbound = if let Some(lte) = range.lte {
@@ -291,9 +391,23 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
             points_count: self.points_count,
             points_values_count: self.histogram.get_total_count(),
             histogram_bucket_size: Some(self.histogram.current_bucket_size()),
+            obsolete_values_count: 0,
+            estimated_ram_bytes: self.estimated_ram_bytes(),
+            needs_rebuild: false,
+            on_disk: false,
         }
     }
 
+    /// Encoded values in the ordered map, the histogram and the values of the points
+    fn estimated_ram_bytes(&self) -> usize {
+        // Keys are of the same length: encoded value followed by the point offset
+        let key_bytes = self.map.keys().next().map_or(0, Vec::len);
+        let map_bytes = self.map.len() * (size_of::<Vec<u8>>() + key_bytes + size_of::<u32>());
+        let point_values_bytes = self.point_to_values.capacity() * size_of::<Vec<T>>()
+            + self.histogram.get_total_count() * size_of::<T>();
+        map_bytes + point_values_bytes + self.histogram.estimated_ram_bytes()
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         self.get_values(point_id).map(|x| x.len()).unwrap_or(0)
     }
@@ -328,46 +442,21 @@ impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
     ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
         let cond_range = condition.range.as_ref()?;
 
-        let start_bound = match cond_range {
-            Range { gt: Some(gt), .. } => {
-                let v: T = T::from_f64(*gt);
-                Excluded(v.encode_key(PointOffsetType::MAX))
-            }
-            Range { gte: Some(gte), .. } => {
-                let v: T = T::from_f64(*gte);
-                Included(v.encode_key(PointOffsetType::MIN))
-            }
-            _ => Unbounded,
+        let Some(bounds) = Self::range_bounds(cond_range) else {
+            return Some(Box::new(vec![].into_iter()));
         };
 
-        let end_bound = match cond_range {
-            Range { lt: Some(lt), .. } => {
-                let v: T = T::from_f64(*lt);
-                Excluded(v.encode_key(PointOffsetType::MIN))
-            }
-            Range { lte: Some(lte), .. } => {
-                let v: T = T::from_f64(*lte);
-                Included(v.encode_key(PointOffsetType::MAX))
-            }
-            _ => Unbounded,
-        };
-
-        // map.range
-        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
-        match (&start_bound, &end_bound) {
-            (Excluded(s), Excluded(e)) if s == e => {
-                // range start and end are equal and excluded in BTreeMap
-                return Some(Box::new(vec![].into_iter()));
-            }
-            (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
-                //range start is greater than range end
-                return Some(Box::new(vec![].into_iter()));
-            }
-            _ => {}
-        }
+        Some(Box::new(self.map.range(bounds).map(|(_, v)| *v)))
+    }
 
+    fn range_iter_sorted(
+        &self,
+        range: &Range,
+        direction: Direction,
+    ) -> Option<Box<dyn Iterator<Item = (f64, PointOffsetType)> + '_>> {
         Some(Box::new(
-            self.map.range((start_bound, end_bound)).map(|(_, v)| *v),
+            NumericIndex::range_iter_sorted(self, range, direction)
+                .map(|(value, idx)| (value.to_f64(), idx)),
         ))
     }
 
@@ -498,6 +587,35 @@ impl ValueIndexer<FloatPayloadType> for NumericIndex<FloatPayloadType> {
     }
 }
 
+pub struct NumericIndexBuilder<T: Encodable + Numericable>(NumericIndex<T>);
+
+impl<T: Encodable + Numericable> NumericIndexBuilder<T> {
+    pub fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
+        Self(NumericIndex::new(db, field))
+    }
+}
+
+impl<T: Encodable + Numericable> FieldIndexBuilder for NumericIndexBuilder<T>
+where
+    NumericIndex<T>: ValueIndexer<T> + Into<FieldIndex>,
+{
+    fn init(&mut self) -> OperationResult<()> {
+        self.0.recreate()
+    }
+
+    fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        self.0.add_point(id, payload)
+    }
+
+    fn finalize(self: Box<Self>) -> OperationResult<FieldIndex> {
+        Ok(self.0.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -675,6 +793,105 @@ mod tests {
         assert!(!blocks.is_empty());
     }
 
+    #[test]
+    fn test_ordered_points() {
+        let (_temp_dir, mut index) = get_index();
+        index.add_many_to_list(0, [3.0, -1.0]).unwrap();
+        index.add_many_to_list(1, [2.0]).unwrap();
+        index.add_many_to_list(2, []).unwrap();
+
+        let ascending = index.ordered_points(Direction::Asc).collect_vec();
+        assert_eq!(ascending, vec![(-1.0, 0), (2.0, 1), (3.0, 0)]);
+
+        let descending = index.ordered_points(Direction::Desc).collect_vec();
+        assert_eq!(descending, vec![(3.0, 0), (2.0, 1), (-1.0, 0)]);
+    }
+
+    #[test]
+    fn test_range_iter_sorted() {
+        let (_temp_dir, mut index) = get_index();
+        index.add_many_to_list(0, [3.0, -1.0]).unwrap();
+        index.add_many_to_list(1, [2.0]).unwrap();
+        index.add_many_to_list(2, [2.0, 5.0]).unwrap();
+        index.add_many_to_list(3, [4.5]).unwrap();
+
+        let range = Range {
+            gte: Some(2.0),
+            lt: Some(5.0),
+            ..Default::default()
+        };
+        let ascending = index
+            .range_iter_sorted(&range, Direction::Asc)
+            .collect_vec();
+        assert_eq!(ascending, vec![(2.0, 1), (2.0, 2), (3.0, 0), (4.5, 3)]);
+
+        let descending = index
+            .range_iter_sorted(&range, Direction::Desc)
+            .collect_vec();
+        assert_eq!(descending, vec![(4.5, 3), (3.0, 0), (2.0, 2), (2.0, 1)]);
+
+        let range = Range {
+            gt: Some(2.0),
+            lte: Some(5.0),
+            ..Default::default()
+        };
+        let ascending = index
+            .range_iter_sorted(&range, Direction::Asc)
+            .collect_vec();
+        assert_eq!(ascending, vec![(3.0, 0), (4.5, 3), (5.0, 2)]);
+
+        // Empty ranges don't panic
+        let range = Range {
+            gt: Some(3.0),
+            lt: Some(3.0),
+            ..Default::default()
+        };
+        assert_eq!(index.range_iter_sorted(&range, Direction::Asc).count(), 0);
+        let range = Range {
+            gte: Some(4.0),
+            lte: Some(1.0),
+            ..Default::default()
+        };
+        assert_eq!(index.range_iter_sorted(&range, Direction::Desc).count(), 0);
+
+        assert_eq!(index.first_n(2), vec![(-1.0, 0), (2.0, 1)]);
+        assert_eq!(index.last_n(2), vec![(5.0, 2), (4.5, 3)]);
+        assert_eq!(index.first_n(100).len(), 6);
+    }
+
+    #[test]
+    fn test_range_iter_sorted_matches_sorting() {
+        let (_temp_dir, index) = random_index(1000, 2);
+        let range = Range {
+            gte: Some(20.0),
+            lt: Some(60.0),
+            ..Default::default()
+        };
+
+        let mut expected = (0..1000)
+            .flat_map(|idx| {
+                index
+                    .get_values(idx)
+                    .unwrap()
+                    .iter()
+                    .map(move |value| (*value, idx))
+            })
+            .filter(|(value, _)| range.check_range(*value))
+            .collect_vec();
+        expected.sort_by(|(a, a_idx), (b, b_idx)| a.total_cmp(b).then(a_idx.cmp(b_idx)));
+
+        let ascending = index
+            .range_iter_sorted(&range, Direction::Asc)
+            .collect_vec();
+        assert_eq!(ascending, expected);
+
+        expected.reverse();
+        let descending = index
+            .range_iter_sorted(&range, Direction::Desc)
+            .collect_vec();
+        assert_eq!(descending, expected);
+    }
+
     #[test]
     fn test_numeric_index_load_from_disk() {
         let (_temp_dir, mut index) = get_index();
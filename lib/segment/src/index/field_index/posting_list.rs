@@ -10,6 +10,14 @@ impl PostingList {
         Self { list: vec![idx] }
     }
 
+    /// Posting of the points, listed in any order and possibly repeated
+    pub fn from_unsorted(mut list: Vec<PointOffsetType>) -> Self {
+        list.sort_unstable();
+        list.dedup();
+        list.shrink_to_fit();
+        Self { list }
+    }
+
     pub fn insert(&mut self, idx: PointOffsetType) {
         if let Err(insertion_idx) = self.list.binary_search(&idx) {
             // Yes, this is O(n) but:
@@ -0,0 +1,105 @@
+use std::mem::size_of_val;
+
+use bitvec::prelude::BitVec;
+
+use crate::payload_storage::FilterContext;
+use crate::types::PointOffsetType;
+
+/// Points of a segment, which match a filter, as a bitmask over their internal ids.
+///
+/// Searches with the same filter use the bitmask instead of checking the conditions of the filter
+/// again for every point.
+#[derive(Debug, Clone, Default)]
+pub struct FilterBitmask {
+    bits: BitVec,
+    count: usize,
+}
+
+impl FilterBitmask {
+    pub fn from_points(points: impl IntoIterator<Item = PointOffsetType>) -> Self {
+        let mut bits = BitVec::new();
+        let mut count = 0;
+        for point in points {
+            let idx = point as usize;
+            if idx >= bits.len() {
+                bits.resize(idx + 1, false);
+            }
+            if !bits.replace(idx, true) {
+                count += 1;
+            }
+        }
+        Self { bits, count }
+    }
+
+    /// Number of the matching points
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Memory, occupied by the bitmask
+    pub fn size_bytes(&self) -> usize {
+        size_of_val(self.bits.as_raw_slice())
+    }
+
+    pub fn contains(&self, point: PointOffsetType) -> bool {
+        self.bits
+            .get(point as usize)
+            .map_or(false, |matches| *matches)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
+        self.bits.iter_ones().map(|idx| idx as PointOffsetType)
+    }
+}
+
+/// Filter context of the points of a bitmask.
+/// Points are also checked by the `residual` context, with the conditions missing in the bitmask.
+pub struct BitmaskFilterContext<'a> {
+    bitmask: &'a FilterBitmask,
+    residual: Option<Box<dyn FilterContext + 'a>>,
+}
+
+impl<'a> BitmaskFilterContext<'a> {
+    pub fn new(bitmask: &'a FilterBitmask, residual: Option<Box<dyn FilterContext + 'a>>) -> Self {
+        Self { bitmask, residual }
+    }
+}
+
+impl FilterContext for BitmaskFilterContext<'_> {
+    fn check(&self, point_id: PointOffsetType) -> bool {
+        self.bitmask.contains(point_id)
+            && self
+                .residual
+                .as_ref()
+                .map_or(true, |residual| residual.check(point_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EvenFilterContext;
+
+    impl FilterContext for EvenFilterContext {
+        fn check(&self, point_id: PointOffsetType) -> bool {
+            point_id % 2 == 0
+        }
+    }
+
+    #[test]
+    fn test_bitmask_filter_context() {
+        let bitmask = FilterBitmask::from_points([7, 2, 4, 7, 1]);
+        assert_eq!(bitmask.count(), 4);
+        assert_eq!(bitmask.iter().collect::<Vec<_>>(), vec![1, 2, 4, 7]);
+        assert!(!bitmask.contains(100));
+
+        let context = BitmaskFilterContext::new(&bitmask, None);
+        assert!(context.check(1));
+        assert!(!context.check(3));
+
+        let context = BitmaskFilterContext::new(&bitmask, Some(Box::new(EvenFilterContext)));
+        let matched: Vec<_> = (0..10).filter(|&point| context.check(point)).collect();
+        assert_eq!(matched, vec![2, 4]);
+    }
+}
@@ -15,6 +15,7 @@ use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::index::hnsw_index::search_context::SearchContext;
 use crate::index::visited_pool::{VisitedList, VisitedPool};
 use crate::spaces::tools::FixedLengthPriorityQueue;
+use crate::telemetry::HnswGraphHealth;
 use crate::types::PointOffsetType;
 use crate::vector_storage::ScoredPointOffset;
 
@@ -220,6 +221,126 @@ impl<TGraphLinks: GraphLinks> GraphLayers<TGraphLinks> {
     pub fn num_points(&self) -> usize {
         self.links.num_points()
     }
+
+    /// Check the graph for links and entry points, which are degraded by deleted points.
+    ///
+    /// Links of all available points are checked on all layers. Reachability from the entry point
+    /// is only checked for a sample of at most `sample_size` available points.
+    pub fn health<F>(&self, is_deleted: F, sample_size: usize) -> HnswGraphHealth
+    where
+        F: Fn(PointOffsetType) -> bool,
+    {
+        let mut links_count = 0;
+        let mut deleted_links_count = 0;
+        let mut layer_points: Vec<usize> = vec![];
+        let mut layer_links: Vec<usize> = vec![];
+        for point_id in 0..self.num_points() as PointOffsetType {
+            if is_deleted(point_id) {
+                continue;
+            }
+            let point_level = self.point_level(point_id);
+            if layer_points.len() <= point_level {
+                layer_points.resize(point_level + 1, 0);
+                layer_links.resize(point_level + 1, 0);
+            }
+            for level in 0..=point_level {
+                layer_points[level] += 1;
+                self.links_map(point_id, level, |link| {
+                    layer_links[level] += 1;
+                    links_count += 1;
+                    if is_deleted(link) {
+                        deleted_links_count += 1;
+                    }
+                });
+            }
+        }
+
+        let entry_point_deleted = self
+            .entry_points
+            .get_entry_point(|_| true)
+            .map_or(false, |entry_point| is_deleted(entry_point.point_id));
+
+        // Searches don't follow the links of deleted points, neither does the reachability check
+        let mut visited_list = self.get_visited_list_from_pool();
+        if let Some(entry_point) = self.entry_points.get_entry_point(|id| !is_deleted(id)) {
+            visited_list.check_and_update_visited(entry_point.point_id);
+            let mut stack = vec![entry_point.point_id];
+            while let Some(point_id) = stack.pop() {
+                self.links_map(point_id, 0, |link| {
+                    if !is_deleted(link) && !visited_list.check_and_update_visited(link) {
+                        stack.push(link);
+                    }
+                });
+            }
+        }
+        let mut sampled_points = 0;
+        let mut disconnected_points = 0;
+        for point_id in self.sample_available_points(&is_deleted, sample_size) {
+            sampled_points += 1;
+            if !visited_list.check(point_id) {
+                disconnected_points += 1;
+            }
+        }
+        self.return_visited_list_to_pool(visited_list);
+
+        HnswGraphHealth {
+            deleted_links_ratio: ratio(deleted_links_count, links_count),
+            avg_out_degree: layer_links
+                .into_iter()
+                .zip(layer_points)
+                .map(|(links, points)| ratio(links, points))
+                .collect(),
+            entry_point_deleted,
+            sampled_points,
+            disconnected_points,
+        }
+    }
+
+    /// Fraction of the links on the bottom layer, which point to deleted points.
+    ///
+    /// Only the links of at most `sample_size` available points are checked, so it is cheap
+    /// enough to be checked by the optimizer.
+    pub fn sample_deleted_links_ratio<F>(&self, is_deleted: F, sample_size: usize) -> f64
+    where
+        F: Fn(PointOffsetType) -> bool,
+    {
+        let mut links_count = 0;
+        let mut deleted_links_count = 0;
+        for point_id in self.sample_available_points(&is_deleted, sample_size) {
+            self.links_map(point_id, 0, |link| {
+                links_count += 1;
+                if is_deleted(link) {
+                    deleted_links_count += 1;
+                }
+            });
+        }
+        ratio(deleted_links_count, links_count)
+    }
+
+    /// Available points, evenly spread over the graph
+    fn sample_available_points<'a, F>(
+        &self,
+        is_deleted: &'a F,
+        sample_size: usize,
+    ) -> impl Iterator<Item = PointOffsetType> + 'a
+    where
+        F: Fn(PointOffsetType) -> bool,
+    {
+        let num_points = self.num_points();
+        let step = (num_points / sample_size.max(1)).max(1);
+        (0..num_points as PointOffsetType)
+            .step_by(step)
+            .filter(move |point_id| !is_deleted(*point_id))
+            .take(sample_size)
+    }
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
 }
 
 impl<TGraphLinks> GraphLayers<TGraphLinks>
@@ -369,6 +490,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_graph_health() {
+        let mut graph_layers = GraphLayers {
+            m: 2,
+            m0: 4,
+            ef_construct: 16,
+            links: GraphLinksRam::default(),
+            entry_points: EntryPoints::new(1),
+            visited_pool: VisitedPool::new(),
+        };
+        // Points 3 and 4 are only linked to the rest of the graph through the upper layer
+        let graph_links = vec![
+            vec![vec![1, 2], vec![3]],
+            vec![vec![0, 2]],
+            vec![vec![0, 1]],
+            vec![vec![4], vec![0]],
+            vec![vec![3]],
+        ];
+        graph_layers.links =
+            GraphLinksRam::from_converter(GraphLinksConverter::new(graph_links)).unwrap();
+        graph_layers.entry_points.new_point(0, 1, |_| true);
+
+        let health = graph_layers.health(|_| false, 10);
+        assert_eq!(
+            health,
+            HnswGraphHealth {
+                deleted_links_ratio: 0.0,
+                avg_out_degree: vec![1.6, 1.0],
+                entry_point_deleted: false,
+                sampled_points: 5,
+                disconnected_points: 2,
+            },
+        );
+
+        let health = graph_layers.health(|point_id| point_id == 1, 10);
+        assert_eq!(health.deleted_links_ratio, 0.25);
+        assert_eq!(health.avg_out_degree, vec![1.5, 1.0]);
+        assert!(!health.entry_point_deleted);
+        assert_eq!(health.sampled_points, 4);
+        assert_eq!(health.disconnected_points, 2);
+
+        // No other entry point is available, so none of the points can be reached
+        let health = graph_layers.health(|point_id| point_id == 0, 10);
+        assert_eq!(health.deleted_links_ratio, 3.0 / 7.0);
+        assert!(health.entry_point_deleted);
+        assert_eq!(health.sampled_points, 4);
+        assert_eq!(health.disconnected_points, 4);
+
+        assert_eq!(
+            graph_layers.sample_deleted_links_ratio(|point_id| point_id == 1, 2),
+            0.5
+        );
+    }
+
     #[test]
     fn test_save_and_load() {
         let num_vectors = 100;
@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::BitSlice;
 use log::debug;
 use parking_lot::Mutex;
 use rand::thread_rng;
@@ -11,6 +12,8 @@ use rayon::prelude::*;
 use rayon::ThreadPool;
 
 use super::graph_links::{GraphLinks, GraphLinksMmap};
+use crate::common::cpu::CpuPermit;
+use crate::common::hardware_counter::record_searches;
 use crate::common::mmap_ops;
 use crate::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
@@ -18,29 +21,50 @@ use crate::common::operation_time_statistics::{
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::id_tracker::IdTrackerSS;
+use crate::index::filter_bitmask::{BitmaskFilterContext, FilterBitmask};
 use crate::index::hnsw_index::build_condition_checker::BuildConditionChecker;
 use crate::index::hnsw_index::config::HnswGraphConfig;
 use crate::index::hnsw_index::graph_layers::GraphLayers;
 use crate::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
-use crate::index::hnsw_index::max_rayon_threads;
 use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::index::query_estimator::adjust_to_available_vectors;
 use crate::index::sample_estimation::sample_check_cardinality;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::visited_pool::VisitedList;
 use crate::index::{PayloadIndex, VectorIndex};
-use crate::telemetry::VectorIndexSearchesTelemetry;
+use crate::payload_storage::FilterContext;
+use crate::telemetry::{HnswGraphHealth, VectorIndexSearchesTelemetry};
 use crate::types::Condition::Field;
-#[cfg(debug_assertions)]
-use crate::types::PointOffsetType;
 use crate::types::{
-    default_quantization_ignore_value, FieldCondition, Filter, HnswConfig,
-    QuantizationSearchParams, SearchParams, VECTOR_ELEMENT_SIZE,
+    default_quantization_ignore_value, Condition, FieldCondition, Filter, HnswConfig, Match,
+    MatchValue, PointOffsetType, QuantizationSearchParams, SearchParams, SearchStrategy,
+    ValueVariants, VECTOR_ELEMENT_SIZE,
 };
 use crate::vector_storage::{new_raw_scorer, ScoredPointOffset, VectorStorage, VectorStorageEnum};
 
 const HNSW_USE_HEURISTIC: bool = true;
 const BYTES_IN_KB: usize = 1024;
+/// Tenants get own sub-graphs starting from this fraction of the full scan threshold
+const TENANT_BLOCK_SIZE_DIVISOR: usize = 10;
+
+/// Number of points, checked by the sampled graph health diagnostics
+const GRAPH_HEALTH_SAMPLE_SIZE: usize = 1000;
+
+/// A point is deleted from the graph, if either the point or its vector is deleted
+fn deleted_checker<'a>(
+    point_deleted: &'a BitSlice,
+    vec_deleted: &'a BitSlice,
+) -> impl Fn(PointOffsetType) -> bool + 'a {
+    move |point_id| {
+        point_deleted.get(point_id as usize).map_or(true, |x| *x)
+            || vec_deleted.get(point_id as usize).map_or(false, |x| *x)
+    }
+}
+
+/// Minimal number of points of a tenant to build a separate sub-graph for it
+fn tenant_min_block_size(full_scan_threshold: usize) -> usize {
+    (full_scan_threshold / TENANT_BLOCK_SIZE_DIVISOR).max(1)
+}
 
 pub struct HNSWIndex<TGraphLinks: GraphLinks> {
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
@@ -136,6 +160,30 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         Ok(())
     }
 
+    /// Health of the graph, `None` if the graph is not built yet
+    pub fn graph_health(&self) -> Option<HnswGraphHealth> {
+        let graph = self.graph.as_ref()?;
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let is_deleted = deleted_checker(
+            id_tracker.deleted_point_bitslice(),
+            vector_storage.deleted_vector_bitslice(),
+        );
+        Some(graph.health(is_deleted, GRAPH_HEALTH_SAMPLE_SIZE))
+    }
+
+    /// Estimated fraction of the graph links, which point to deleted points
+    pub fn sample_deleted_links_ratio(&self) -> Option<f64> {
+        let graph = self.graph.as_ref()?;
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let is_deleted = deleted_checker(
+            id_tracker.deleted_point_bitslice(),
+            vector_storage.deleted_vector_bitslice(),
+        );
+        Some(graph.sample_deleted_links_ratio(is_deleted, GRAPH_HEALTH_SAMPLE_SIZE))
+    }
+
     pub fn build_filtered_graph(
         &self,
         pool: &ThreadPool,
@@ -211,6 +259,91 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         })
     }
 
+    /// Filter requires a single value of a tenant field, so the search stays in a tenant sub-graph
+    fn is_tenant_filter(filter: &Filter, payload_index: &StructPayloadIndex) -> bool {
+        filter
+            .must
+            .iter()
+            .flatten()
+            .any(|condition| match condition {
+                Condition::Field(FieldCondition {
+                    key,
+                    r#match:
+                        Some(Match::Value(MatchValue {
+                            value: ValueVariants::Keyword(_),
+                        })),
+                    ..
+                }) => payload_index.is_tenant_field(key),
+                _ => false,
+            })
+    }
+
+    /// `ef` of the graph search, requested by the params or configured for the index
+    fn search_ef(&self, params: Option<&SearchParams>) -> usize {
+        params
+            .and_then(|params| params.hnsw_ef)
+            .unwrap_or(self.config.ef)
+    }
+
+    /// Choose between plain and graph search for the filter, based on its cardinality
+    ///
+    /// Returns chosen strategy along with `ef` to use for the graph search
+    fn filtered_search_strategy(
+        &self,
+        query_filter: &Filter,
+        params: Option<&SearchParams>,
+    ) -> (SearchStrategy, usize) {
+        let ef = self.search_ef(params);
+
+        let payload_index = self.payload_index.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let available_vector_count = vector_storage.available_vector_count();
+        let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
+        let query_cardinality = adjust_to_available_vectors(
+            query_point_cardinality,
+            available_vector_count,
+            id_tracker.available_point_count(),
+        );
+
+        // Tenant sub-graphs are built for smaller blocks, than the regular payload ones
+        let use_tenant_graph = query_cardinality.exp
+            >= tenant_min_block_size(self.config.full_scan_threshold)
+            && Self::is_tenant_filter(query_filter, &payload_index);
+
+        if !use_tenant_graph && query_cardinality.max < self.config.full_scan_threshold {
+            return (SearchStrategy::Plain, ef);
+        }
+
+        let adaptive_ef = params.and_then(|params| params.adaptive_ef);
+        let selectivity = query_cardinality.exp as f64 / available_vector_count.max(1) as f64;
+
+        if adaptive_ef.map_or(false, |adaptive_ef| adaptive_ef.prefer_exact(selectivity)) {
+            return (SearchStrategy::Plain, ef);
+        }
+
+        let use_graph =
+            use_tenant_graph || query_cardinality.min > self.config.full_scan_threshold || {
+                // Fast cardinality estimation is not enough, do sample estimation of cardinality
+                let filter_context = payload_index.filter_context(query_filter);
+                sample_check_cardinality(
+                    id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
+                    |idx| filter_context.check(idx),
+                    self.config.full_scan_threshold,
+                    available_vector_count, // Check cardinality among available vectors
+                )
+            };
+
+        if !use_graph {
+            return (SearchStrategy::Plain, ef);
+        }
+
+        match adaptive_ef.and_then(|adaptive_ef| adaptive_ef.scaled_ef(ef, selectivity)) {
+            Some(scaled_ef) => (SearchStrategy::HnswAdaptiveEf, scaled_ef),
+            None => (SearchStrategy::Hnsw, ef),
+        }
+    }
+
     pub fn search_with_graph(
         &self,
         vector: &[VectorElementType],
@@ -218,9 +351,19 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         top: usize,
         params: Option<&SearchParams>,
     ) -> Vec<ScoredPointOffset> {
-        let ef = params
-            .and_then(|params| params.hnsw_ef)
-            .unwrap_or(self.config.ef);
+        let payload_index = self.payload_index.borrow();
+        let filter_context = filter.map(|f| payload_index.filter_context(f));
+        self.search_with_graph_context(vector, filter_context.as_deref(), top, params)
+    }
+
+    fn search_with_graph_context(
+        &self,
+        vector: &[VectorElementType],
+        filter_context: Option<&dyn FilterContext>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> Vec<ScoredPointOffset> {
+        let ef = self.search_ef(params);
 
         let id_tracker = self.id_tracker.borrow();
         let vector_storage = self.vector_storage.borrow();
@@ -255,9 +398,7 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
             }
         };
 
-        let payload_index = self.payload_index.borrow();
-        let filter_context = filter.map(|f| payload_index.filter_context(f));
-        let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context.as_deref());
+        let points_scorer = FilteredScorer::new(raw_scorer.as_ref(), filter_context);
 
         let Some(graph) = &self.graph else {
             return Vec::new();
@@ -311,11 +452,21 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         filter: &Filter,
         top: usize,
         params: Option<&SearchParams>,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let filtered_points = self.payload_index.borrow().query_points(filter);
+        self.search_points_plain(vectors, &filtered_points, top, params)
+    }
+
+    /// Score the given points, without using the graph
+    fn search_points_plain(
+        &self,
+        vectors: &[&[VectorElementType]],
+        filtered_points: &[PointOffsetType],
+        top: usize,
+        params: Option<&SearchParams>,
     ) -> Vec<Vec<ScoredPointOffset>> {
         let id_tracker = self.id_tracker.borrow();
-        let payload_index = self.payload_index.borrow();
         let vector_storage = self.vector_storage.borrow();
-        let filtered_points = payload_index.query_points(filter);
         let ignore_quantization = params
             .and_then(|p| p.quantization)
             .map(|q| q.ignore)
@@ -387,6 +538,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
 
                 // Do plain or graph search
                 if plain_search {
+                    record_searches(SearchStrategy::Plain, 0, vectors.len());
                     let _timer = ScopeDurationMeasurer::new(if exact {
                         &self.searches_telemetry.exact_unfiltered
                     } else {
@@ -404,6 +556,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                         })
                         .collect()
                 } else {
+                    record_searches(SearchStrategy::Hnsw, self.search_ef(params), vectors.len());
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.unfiltered_hnsw);
                     self.search_vectors_with_graph(vectors, None, top, params)
@@ -425,6 +578,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                         }); // disable quantization for exact search
                         params
                     });
+                    record_searches(SearchStrategy::Plain, 0, vectors.len());
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.exact_filtered);
                     return self.search_vectors_plain(
@@ -435,56 +589,82 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                     );
                 }
 
-                let payload_index = self.payload_index.borrow();
-                let vector_storage = self.vector_storage.borrow();
-                let id_tracker = self.id_tracker.borrow();
-                let available_vector_count = vector_storage.available_vector_count();
-                let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
-                let query_cardinality = adjust_to_available_vectors(
-                    query_point_cardinality,
-                    available_vector_count,
-                    id_tracker.available_point_count(),
-                );
-
-                if query_cardinality.max < self.config.full_scan_threshold {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    return self.search_vectors_plain(vectors, query_filter, top, params);
-                }
-
-                if query_cardinality.min > self.config.full_scan_threshold {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    return self.search_vectors_with_graph(vectors, filter, top, params);
+                let (strategy, ef) = self.filtered_search_strategy(query_filter, params);
+                record_searches(strategy, ef, vectors.len());
+                match strategy {
+                    SearchStrategy::Plain => {
+                        // if cardinality is small - use plain index
+                        let _timer =
+                            ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
+                        self.search_vectors_plain(vectors, query_filter, top, params)
+                    }
+                    SearchStrategy::Hnsw | SearchStrategy::HnswAdaptiveEf => {
+                        // if cardinality is high enough - use HNSW index
+                        let graph_params = SearchParams {
+                            hnsw_ef: Some(ef),
+                            ..params.copied().unwrap_or_default()
+                        };
+                        let _timer =
+                            ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+                        self.search_vectors_with_graph(vectors, filter, top, Some(&graph_params))
+                    }
                 }
+            }
+        }
+    }
 
-                let filter_context = payload_index.filter_context(query_filter);
+    fn search_prefiltered(
+        &self,
+        vectors: &[&[VectorElementType]],
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let exact = params.map(|params| params.exact).unwrap_or(false);
+        let payload_index = self.payload_index.borrow();
+        let filter_context = BitmaskFilterContext::new(
+            bitmask,
+            filter.map(|filter| payload_index.filter_context(filter)),
+        );
 
-                // Fast cardinality estimation is not enough, do sample estimation of cardinality
-                let id_tracker = self.id_tracker.borrow();
-                if sample_check_cardinality(
-                    id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
-                    |idx| filter_context.check(idx),
-                    self.config.full_scan_threshold,
-                    available_vector_count, // Check cardinality among available vectors
-                ) {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    self.search_vectors_with_graph(vectors, filter, top, params)
-                } else {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    self.search_vectors_plain(vectors, query_filter, top, params)
+        // Number of matching points is known exactly, no need to estimate it
+        if exact || bitmask.count() < self.config.full_scan_threshold {
+            record_searches(SearchStrategy::Plain, 0, vectors.len());
+            let _timer = ScopeDurationMeasurer::new(if exact {
+                &self.searches_telemetry.exact_filtered
+            } else {
+                &self.searches_telemetry.small_cardinality
+            });
+            let plain_params = params.map(|params| {
+                let mut params = *params;
+                if exact {
+                    params.quantization = Some(QuantizationSearchParams {
+                        ignore: true,
+                        rescore: false,
+                        oversampling: None,
+                    });
                 }
-            }
+                params
+            });
+            let filtered_points: Vec<_> = bitmask
+                .iter()
+                .filter(|&point_id| filter_context.check(point_id))
+                .collect();
+            return self.search_points_plain(vectors, &filtered_points, top, plain_params.as_ref());
         }
+
+        record_searches(SearchStrategy::Hnsw, self.search_ef(params), vectors.len());
+        let _timer = ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+        vectors
+            .iter()
+            .map(|vector| {
+                self.search_with_graph_context(vector, Some(&filter_context), top, params)
+            })
+            .collect()
     }
 
-    fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
+    fn build_index(&mut self, permit: &CpuPermit, stopped: &AtomicBool) -> OperationResult<()> {
         // Build main index graph
         let id_tracker = self.id_tracker.borrow();
         let vector_storage = self.vector_storage.borrow();
@@ -510,7 +690,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
 
         let pool = rayon::ThreadPoolBuilder::new()
             .thread_name(|idx| format!("hnsw-build-{idx}"))
-            .num_threads(max_rayon_threads(self.config.max_indexing_threads))
+            .num_threads(permit.num_cpus())
             .build()?;
 
         for vector_id in id_tracker.iter_ids_excluding(deleted_bitslice) {
@@ -563,7 +743,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
         let payload_m = self.config.payload_m.unwrap_or(self.config.m);
 
         if payload_m > 0 {
-            for (field, _) in payload_index.indexed_fields() {
+            for (field, schema) in payload_index.indexed_fields() {
                 debug!("building additional index for field {}", &field);
 
                 // It is expected, that graph will become disconnected less than
@@ -578,6 +758,14 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                 };
                 let min_block_size = indexing_threshold;
 
+                // Links between tenants are useless for the searches within a tenant,
+                // so every tenant, except the tiniest ones, gets its own sub-graph
+                let (min_block_size, max_block_size) = if schema.is_tenant() {
+                    (tenant_min_block_size(indexing_threshold), usize::MAX)
+                } else {
+                    (min_block_size, max_block_size)
+                };
+
                 for payload_block in payload_index.payload_blocks(&field, min_block_size) {
                     check_process_stopped(stopped)?;
                     if payload_block.cardinality > max_block_size {
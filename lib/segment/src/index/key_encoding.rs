@@ -3,8 +3,8 @@ const FLOAT_NEG: u8 = 0x01;
 const FLOAT_ZERO: u8 = 0x02;
 const FLOAT_POS: u8 = 0x03;
 
-const F64_KEY_LEN: usize = 13;
-const I64_KEY_LEN: usize = 12;
+pub const F64_KEY_LEN: usize = 13;
+pub const I64_KEY_LEN: usize = 12;
 
 /// Encode a f64 into `buf`
 ///
@@ -1,4 +1,5 @@
 pub mod field_index;
+pub mod filter_bitmask;
 pub mod hnsw_index;
 mod key_encoding;
 mod payload_config;
@@ -7,6 +8,7 @@ pub mod plain_payload_index;
 pub mod query_estimator;
 mod query_optimization;
 mod sample_estimation;
+pub mod sparse_index;
 mod struct_filter_context;
 pub mod struct_payload_index;
 mod vector_index_base;
@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use crate::common::file_operations::{atomic_save_json, read_json};
 use crate::entry::entry_point::OperationResult;
-use crate::types::{PayloadFieldSchema, PayloadKeyType};
+use crate::types::{PayloadFieldSchema, PayloadKeyType, SeqNumberType};
 
 pub const PAYLOAD_INDEX_CONFIG_FILE: &str = "config.json";
 
@@ -13,6 +13,21 @@ pub const PAYLOAD_INDEX_CONFIG_FILE: &str = "config.json";
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct PayloadConfig {
     pub indexed_fields: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Generation of the index storage of the fields, which indexes were rebuilt through staging.
+    /// Fields, which are not listed, are stored in the storage of the initial generation.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub index_generations: HashMap<PayloadKeyType, u32>,
+    /// Fields, which indexes are being rebuilt into the storage of the next generation
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub staged_rebuilds: HashSet<PayloadKeyType>,
+    /// Schemas, which the staged rebuilds convert the indexes of the fields to.
+    /// The schema replaces the one in `indexed_fields`, once the rebuild is complete
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub staged_schemas: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Fields, which indexes are being created, with the number of the operation, which
+    /// created them. Conditions on these fields are checked against the payload until then.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub building_indexes: HashMap<PayloadKeyType, SeqNumberType>,
 }
 
 impl PayloadConfig {
@@ -8,6 +8,8 @@ use atomic_refcell::AtomicRefCell;
 use parking_lot::Mutex;
 use schemars::_serde_json::Value;
 
+use crate::common::cpu::CpuPermit;
+use crate::common::hardware_counter::record_searches;
 use crate::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator, ScopeDurationMeasurer,
 };
@@ -17,6 +19,7 @@ use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
+use crate::index::filter_bitmask::{BitmaskFilterContext, FilterBitmask};
 use crate::index::payload_config::PayloadConfig;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndex, VectorIndex};
@@ -24,7 +27,7 @@ use crate::payload_storage::{ConditionCheckerSS, FilterContext};
 use crate::telemetry::VectorIndexSearchesTelemetry;
 use crate::types::{
     Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
-    PointOffsetType, SearchParams,
+    PointOffsetType, SearchParams, SearchStrategy,
 };
 use crate::vector_storage::{new_raw_scorer, ScoredPointOffset, VectorStorageEnum};
 
@@ -228,6 +231,7 @@ impl VectorIndex for PlainIndex {
         top: usize,
         _params: Option<&SearchParams>,
     ) -> Vec<Vec<ScoredPointOffset>> {
+        record_searches(SearchStrategy::Plain, 0, vectors.len());
         match filter {
             Some(filter) => {
                 let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
@@ -266,7 +270,41 @@ impl VectorIndex for PlainIndex {
         }
     }
 
-    fn build_index(&mut self, _stopped: &AtomicBool) -> OperationResult<()> {
+    fn search_prefiltered(
+        &self,
+        vectors: &[&[VectorElementType]],
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        _params: Option<&SearchParams>,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        record_searches(SearchStrategy::Plain, 0, vectors.len());
+        let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
+        let id_tracker = self.id_tracker.borrow();
+        let payload_index = self.payload_index.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let filter_context = BitmaskFilterContext::new(
+            bitmask,
+            filter.map(|filter| payload_index.filter_context(filter)),
+        );
+        let filtered_ids_vec: Vec<_> = bitmask
+            .iter()
+            .filter(|&point_id| filter_context.check(point_id))
+            .collect();
+        vectors
+            .iter()
+            .map(|vector| {
+                new_raw_scorer(
+                    vector.to_vec(),
+                    &vector_storage,
+                    id_tracker.deleted_point_bitslice(),
+                )
+                .peek_top_iter(&mut filtered_ids_vec.iter().copied(), top)
+            })
+            .collect()
+    }
+
+    fn build_index(&mut self, _permit: &CpuPermit, _stopped: &AtomicBool) -> OperationResult<()> {
         Ok(())
     }
 
@@ -8,7 +8,7 @@ use std::cmp::{max, min};
 use itertools::Itertools;
 
 use crate::index::field_index::{CardinalityEstimation, PrimaryCondition};
-use crate::types::{Condition, Filter};
+use crate::types::{Condition, Filter, MinShould};
 
 /// Re-estimate cardinality based on number of available vectors
 /// Assuming that deleted vectors are not correlated with the filter
@@ -125,6 +125,83 @@ pub fn combine_should_estimations(
     }
 }
 
+/// Estimate the number of points, matching at least `min_count` of the conditions.
+///
+/// Expected count assumes the conditions are independent. It is bounded by the counts, which
+/// could be reached if the points of the conditions overlap as much or as little as possible.
+pub fn combine_min_should_estimations(
+    estimations: &[CardinalityEstimation],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation {
+    if min_count <= 1 {
+        return combine_should_estimations(estimations, total);
+    }
+    if min_count >= estimations.len() {
+        let mut estimation = combine_must_estimations(estimations, total);
+        if min_count > estimations.len() {
+            estimation.min = 0;
+            estimation.exp = 0;
+            estimation.max = 0;
+        }
+        return estimation;
+    }
+
+    // Every matched point is counted by at least `min_count` conditions
+    let max_estimation = min(
+        estimations.iter().map(|x| x.max).sum::<usize>() / min_count,
+        total,
+    );
+    // Every point is counted by at most `min_count - 1` conditions without matching,
+    // and by all of them otherwise
+    let min_estimation = estimations
+        .iter()
+        .map(|x| x.min)
+        .sum::<usize>()
+        .saturating_sub((min_count - 1) * total)
+        / (estimations.len() - min_count + 1);
+
+    // Probability of exactly `i` matched conditions, one condition at a time
+    let mut matched_prob = vec![0.0; estimations.len() + 1];
+    matched_prob[0] = 1.0;
+    for (checked, estimation) in estimations.iter().enumerate() {
+        let hit_prob = if total == 0 {
+            0.0
+        } else {
+            estimation.exp as f64 / total as f64
+        };
+        for i in (0..=checked + 1).rev() {
+            let miss = matched_prob[i] * (1.0 - hit_prob);
+            let hit = if i > 0 {
+                matched_prob[i - 1] * hit_prob
+            } else {
+                0.0
+            };
+            matched_prob[i] = miss + hit;
+        }
+    }
+    let exp_prob: f64 = matched_prob[min_count..].iter().sum();
+    let exp_estimation =
+        ((exp_prob * total as f64).round() as usize).clamp(min_estimation, max_estimation);
+
+    // Points, matching at least one condition, cover the points, matching `min_count` of them
+    let clauses = if estimations.iter().all(|x| !x.primary_clauses.is_empty()) {
+        estimations
+            .iter()
+            .flat_map(|x| x.primary_clauses.iter().cloned())
+            .collect()
+    } else {
+        vec![]
+    };
+
+    CardinalityEstimation {
+        primary_clauses: clauses,
+        min: min_estimation,
+        exp: exp_estimation,
+        max: max_estimation,
+    }
+}
+
 pub fn combine_must_estimations(
     estimations: &[CardinalityEstimation],
     total: usize,
@@ -196,6 +273,19 @@ where
             }
         }
     }
+    match &filter.min_should {
+        None => {}
+        Some(MinShould {
+            conditions,
+            min_count,
+        }) => {
+            if !conditions.is_empty() {
+                filter_estimations.push(estimate_min_should(
+                    estimator, conditions, *min_count, total,
+                ));
+            }
+        }
+    }
     match &filter.must_not {
         None => {}
         Some(conditions) => {
@@ -221,6 +311,20 @@ where
     combine_should_estimations(&should_estimations, total)
 }
 
+fn estimate_min_should<F>(
+    estimator: &F,
+    conditions: &[Condition],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation
+where
+    F: Fn(&Condition) -> CardinalityEstimation,
+{
+    let estimate = |x| estimate_condition(estimator, x, total);
+    let min_should_estimations = conditions.iter().map(estimate).collect_vec();
+    combine_min_should_estimations(&min_should_estimations, min_count, total)
+}
+
 fn estimate_must<F>(estimator: &F, conditions: &[Condition], total: usize) -> CardinalityEstimation
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -314,6 +418,12 @@ mod tests {
                 exp: has_id.has_id.len(),
                 max: has_id.has_id.len(),
             },
+            Condition::ModifiedSince(condition) => CardinalityEstimation {
+                primary_clauses: vec![PrimaryCondition::ModifiedSince(condition.timestamp())],
+                min: 0,
+                exp: TOTAL / 2,
+                max: TOTAL,
+            },
             Condition::IsEmpty(condition) => CardinalityEstimation {
                 primary_clauses: vec![PrimaryCondition::IsEmpty(condition.to_owned())],
                 min: 0,
@@ -341,6 +451,7 @@ mod tests {
     fn must_estimation_query_test() {
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![
                 test_condition("color".to_owned()),
                 test_condition("size".to_owned()),
@@ -367,6 +478,7 @@ mod tests {
                 test_condition("color".to_owned()),
                 test_condition("size".to_owned()),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -386,6 +498,7 @@ mod tests {
                 test_condition("size".to_owned()),
                 test_condition("un-indexed".to_owned()),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -398,12 +511,62 @@ mod tests {
         assert!(estimation.min <= estimation.exp);
     }
 
+    #[test]
+    fn min_should_estimation_query_test() {
+        let conditions = vec![
+            test_condition("color".to_owned()),
+            test_condition("size".to_owned()),
+            test_condition("price".to_owned()),
+        ];
+        let should_query = Filter {
+            should: Some(conditions.clone()),
+            min_should: None,
+            must: None,
+            must_not: None,
+        };
+        let should_estimation = estimate_filter(&test_estimator, &should_query, TOTAL);
+
+        let query = Filter::new_min_should(MinShould {
+            conditions: conditions.clone(),
+            min_count: 2,
+        });
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert_eq!(estimation.primary_clauses.len(), 3);
+        assert_eq!(estimation.max, (300 + 100 + 20) / 2);
+        assert!(estimation.exp <= estimation.max);
+        assert!(estimation.min <= estimation.exp);
+        assert!(estimation.exp < should_estimation.exp);
+
+        // All of the conditions are required
+        let query = Filter::new_min_should(MinShould {
+            conditions: conditions.clone(),
+            min_count: 3,
+        });
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert!(estimation.max <= 20);
+
+        let query = Filter::new_min_should(MinShould {
+            conditions: vec![
+                test_condition("color".to_owned()),
+                test_condition("size".to_owned()),
+                test_condition("un-indexed".to_owned()),
+            ],
+            min_count: 2,
+        });
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert_eq!(estimation.primary_clauses.len(), 0);
+        assert!(estimation.max <= TOTAL);
+        assert!(estimation.exp <= estimation.max);
+        assert!(estimation.min <= estimation.exp);
+    }
+
     #[test]
     fn complex_estimation_query_test() {
         let query = Filter {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![
                         test_condition("color".to_owned()),
                         test_condition("size".to_owned()),
@@ -412,6 +575,7 @@ mod tests {
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![
                         test_condition("price".to_owned()),
                         test_condition("size".to_owned()),
@@ -419,6 +583,7 @@ mod tests {
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(HasIdCondition {
                 has_id: HashSet::from_iter([1, 2, 3, 4, 5].into_iter().map(|x| x.into())),
@@ -436,8 +601,10 @@ mod tests {
     fn another_complex_estimation_query_test() {
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![
                 Condition::Filter(Filter {
+                    min_should: None,
                     must: None,
                     should: Some(vec![
                         test_condition("color".to_owned()),
@@ -446,6 +613,7 @@ mod tests {
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
+                    min_should: None,
                     must: None,
                     should: Some(vec![
                         test_condition("price".to_owned()),
@@ -1,20 +1,23 @@
 use std::collections::HashSet;
 
+use bitvec::prelude::BitVec;
 use serde_json::Value;
 
+use crate::common::hardware_counter::record_payload_values_read;
 use crate::common::utils::IndexesMap;
+use crate::data_types::filter_trace::{ConditionCheckSource, ConditionTrace};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::FieldIndex;
 use crate::index::query_optimization::optimized_filter::ConditionCheckerFn;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::payload_storage::query_checker::{
-    check_field_condition, check_is_empty_condition, check_is_null_condition, check_payload,
-    select_nested_indexes,
+    check_field_condition, check_field_condition_with_source, check_is_empty_condition,
+    check_is_null_condition, check_payload, select_nested_indexes, trace_payload_condition,
 };
 use crate::types::{
     AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoRadius, Match,
-    MatchAny, MatchExcept, MatchText, MatchValue, OwnedPayloadRef, PayloadContainer,
-    PointOffsetType, Range, ValueVariants,
+    MatchAny, MatchExcept, MatchKeywordRange, MatchText, MatchValue, OwnedPayloadRef,
+    PayloadContainer, PointOffsetType, Range, ValueVariants, ValuesCount,
 };
 
 pub fn condition_converter<'a>(
@@ -33,7 +36,7 @@ pub fn condition_converter<'a>(
             })
             .unwrap_or_else(|| {
                 Box::new(move |point_id| {
-                    payload_provider.with_payload(point_id, |payload| {
+                    payload_provider.with_field_payload(point_id, &field_condition.key, |payload| {
                         check_field_condition(field_condition, &payload, field_indexes)
                     })
                 })
@@ -46,7 +49,7 @@ pub fn condition_converter<'a>(
                 .and_then(|indexes| indexes.first());
 
             let fallback = Box::new(move |point_id| {
-                payload_provider.with_payload(point_id, |payload| {
+                payload_provider.with_field_payload(point_id, &is_empty.is_empty.key, |payload| {
                     check_is_empty_condition(is_empty, &payload)
                 })
             });
@@ -58,7 +61,7 @@ pub fn condition_converter<'a>(
         }
 
         Condition::IsNull(is_null) => Box::new(move |point_id| {
-            payload_provider.with_payload(point_id, |payload| {
+            payload_provider.with_field_payload(point_id, &is_null.is_null.key, |payload| {
                 check_is_null_condition(is_null, &payload)
             })
         }),
@@ -71,6 +74,19 @@ pub fn condition_converter<'a>(
                 .collect();
             Box::new(move |point_id| segment_ids.contains(&point_id))
         }
+        Condition::ModifiedSince(modified_since) => {
+            let mut modified_points = BitVec::repeat(false, id_tracker.total_point_count());
+            for point_id in id_tracker.iter_modified_since(modified_since.timestamp()) {
+                if let Some(mut modified) = modified_points.get_mut(point_id as usize) {
+                    *modified = true;
+                }
+            }
+            Box::new(move |point_id| {
+                modified_points
+                    .get(point_id as usize)
+                    .map_or(false, |modified| *modified)
+            })
+        }
         Condition::Nested(nested) => {
             // Select indexes for nested fields. Trim nested part from key, so
             // that nested condition can address fields without nested part.
@@ -95,8 +111,10 @@ pub fn condition_converter<'a>(
             let nested_indexes = select_nested_indexes(&nested_path, field_indexes);
 
             Box::new(move |point_id| {
-                payload_provider.with_payload(point_id, |payload| {
-                    let field_values = payload.get_value(&nested_path).values();
+                payload_provider.with_field_payload(point_id, &nested_path, |payload| {
+                    let field_values = payload.get_value(&nested_path);
+                    record_payload_values_read(field_values.len());
+                    let field_values = field_values.values();
 
                     for value in field_values {
                         if let Value::Object(object) = value {
@@ -123,6 +141,51 @@ pub fn condition_converter<'a>(
     }
 }
 
+/// Check a single condition for `point_id` the same way, as the checker, built by
+/// [`condition_converter`], does, and record its outcome
+pub fn condition_trace(
+    condition: &Condition,
+    field_indexes: &IndexesMap,
+    payload_provider: &PayloadProvider,
+    id_tracker: &IdTrackerSS,
+    point_id: PointOffsetType,
+) -> ConditionTrace {
+    match condition {
+        Condition::Field(field_condition) => {
+            let index_checker = field_indexes.get(&field_condition.key).and_then(|indexes| {
+                indexes
+                    .iter()
+                    .find_map(|index| field_condition_index(index, field_condition))
+            });
+            let (matched, checked_by) = match index_checker {
+                Some(checker) => (checker(point_id), ConditionCheckSource::Index),
+                None => payload_provider.with_payload(point_id, |payload| {
+                    check_field_condition_with_source(field_condition, &payload, field_indexes)
+                }),
+            };
+            ConditionTrace::new(condition, matched, Some(checked_by))
+        }
+        // Index can only tell that the field is not empty, see `get_is_empty_checker`
+        Condition::IsEmpty(is_empty)
+            if field_indexes
+                .get(&is_empty.is_empty.key)
+                .and_then(|indexes| indexes.first())
+                .map_or(false, |index| !index.values_is_empty(point_id)) =>
+        {
+            ConditionTrace::new(condition, false, Some(ConditionCheckSource::Index))
+        }
+        _ => payload_provider.with_payload(point_id, |payload| {
+            trace_payload_condition(
+                condition,
+                &|| payload.clone(),
+                Some(id_tracker),
+                point_id,
+                field_indexes,
+            )
+        }),
+    }
+}
+
 pub fn field_condition_index<'a>(
     index: &'a FieldIndex,
     field_condition: &FieldCondition,
@@ -159,9 +222,38 @@ pub fn field_condition_index<'a>(
         return Some(checker);
     }
 
+    if let Some(checker) = field_condition
+        .values_count
+        .and_then(|cond| get_values_count_checker(index, cond))
+    {
+        return Some(checker);
+    }
+
     None
 }
 
+/// Checks the amount of values, the index holds for the point.
+/// Full-text index holds the tokens, not the values, so it can't be used for counting.
+pub fn get_values_count_checker(
+    index: &FieldIndex,
+    values_count: ValuesCount,
+) -> Option<ConditionCheckerFn> {
+    match index {
+        FieldIndex::FullTextIndex(_) => None,
+        FieldIndex::IntIndex(_)
+        | FieldIndex::IntMapIndex(_)
+        | FieldIndex::KeywordIndex(_)
+        | FieldIndex::FloatIndex(_)
+        | FieldIndex::GeoIndex(_)
+        | FieldIndex::ImmutableIntMapIndex(_)
+        | FieldIndex::ImmutableKeywordIndex(_) => {
+            Some(Box::new(move |point_id: PointOffsetType| {
+                values_count.check_count_number(index.values_count(point_id))
+            }))
+        }
+    }
+}
+
 pub fn get_geo_radius_checkers(
     index: &FieldIndex,
     geo_radius: GeoRadius,
@@ -223,14 +315,28 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|k| k == &keyword))
+                        .map_or(false, |mut values| values.any(|k| k == &keyword))
+                }))
+            }
+            (ValueVariants::Keyword(keyword), FieldIndex::ImmutableKeywordIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|k| k == &keyword))
                 }))
             }
             (ValueVariants::Integer(value), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|i| i == &value))
+                        .map_or(false, |mut values| values.any(|i| i == &value))
+                }))
+            }
+            (ValueVariants::Integer(value), FieldIndex::ImmutableIntMapIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|i| i == &value))
                 }))
             }
             _ => None,
@@ -251,14 +357,28 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|k| list.contains(k)))
+                        .map_or(false, |mut values| values.any(|k| list.contains(k)))
+                }))
+            }
+            (AnyVariants::Keywords(list), FieldIndex::ImmutableKeywordIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|k| list.contains(k)))
                 }))
             }
             (AnyVariants::Integers(list), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|i| list.contains(i)))
+                        .map_or(false, |mut values| values.any(|i| list.contains(i)))
+                }))
+            }
+            (AnyVariants::Integers(list), FieldIndex::ImmutableIntMapIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|i| list.contains(i)))
                 }))
             }
             _ => None,
@@ -268,14 +388,28 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|k| !list.contains(k)))
+                        .map_or(false, |mut values| values.any(|k| !list.contains(k)))
+                }))
+            }
+            (AnyVariants::Keywords(list), FieldIndex::ImmutableKeywordIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|k| !list.contains(k)))
                 }))
             }
             (AnyVariants::Integers(list), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
                         .get_values(point_id)
-                        .map_or(false, |values| values.iter().any(|i| !list.contains(i)))
+                        .map_or(false, |mut values| values.any(|i| !list.contains(i)))
+                }))
+            }
+            (AnyVariants::Integers(list), FieldIndex::ImmutableIntMapIndex(index)) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |mut values| values.any(|i| !list.contains(i)))
                 }))
             }
             (_, index) => Some(Box::new(|point_id: PointOffsetType| {
@@ -283,6 +417,21 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                 index.values_count(point_id) > 0
             })),
         },
+        Match::KeywordRange(MatchKeywordRange { keyword_range }) => match index {
+            FieldIndex::KeywordIndex(index) => Some(Box::new(move |point_id: PointOffsetType| {
+                index.get_values(point_id).map_or(false, |mut values| {
+                    values.any(|k| keyword_range.check_range(k))
+                })
+            })),
+            FieldIndex::ImmutableKeywordIndex(index) => {
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index.get_values(point_id).map_or(false, |mut values| {
+                        values.any(|k| keyword_range.check_range(k))
+                    })
+                }))
+            }
+            _ => None,
+        },
     }
 }
 
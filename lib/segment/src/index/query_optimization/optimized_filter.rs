@@ -1,3 +1,4 @@
+use crate::common::hardware_counter::record_filter_condition_check;
 use crate::types::PointOffsetType;
 
 pub type ConditionCheckerFn<'a> = Box<dyn Fn(PointOffsetType) -> bool + 'a>;
@@ -13,20 +14,31 @@ pub struct OptimizedFilter<'a> {
     pub should: Option<Vec<OptimizedCondition<'a>>>,
     /// All conditions must match
     pub must: Option<Vec<OptimizedCondition<'a>>>,
+    /// At least `min_count` of those conditions should match
+    pub min_should: Option<OptimizedMinShould<'a>>,
     /// All conditions must NOT match
     pub must_not: Option<Vec<OptimizedCondition<'a>>>,
 }
 
+pub struct OptimizedMinShould<'a> {
+    pub conditions: Vec<OptimizedCondition<'a>>,
+    pub min_count: usize,
+}
+
 pub fn check_optimized_filter(filter: &OptimizedFilter, point_id: PointOffsetType) -> bool {
     check_should(&filter.should, point_id)
         && check_must(&filter.must, point_id)
+        && check_min_should(&filter.min_should, point_id)
         && check_must_not(&filter.must_not, point_id)
 }
 
 fn check_condition(condition: &OptimizedCondition, point_id: PointOffsetType) -> bool {
     match condition {
         OptimizedCondition::Filter(filter) => check_optimized_filter(filter, point_id),
-        OptimizedCondition::Checker(checker) => checker(point_id),
+        OptimizedCondition::Checker(checker) => {
+            record_filter_condition_check();
+            checker(point_id)
+        }
     }
 }
 
@@ -46,6 +58,31 @@ fn check_must(must: &Option<Vec<OptimizedCondition>>, point_id: PointOffsetType)
     }
 }
 
+fn check_min_should(min_should: &Option<OptimizedMinShould>, point_id: PointOffsetType) -> bool {
+    let Some(OptimizedMinShould {
+        conditions,
+        min_count,
+    }) = min_should
+    else {
+        return true;
+    };
+
+    let mut matched = 0;
+    for (checked, condition) in conditions.iter().enumerate() {
+        if check_condition(condition, point_id) {
+            matched += 1;
+        }
+        if matched >= *min_count {
+            return true;
+        }
+        // Not enough conditions left to reach `min_count`
+        if matched + (conditions.len() - checked - 1) < *min_count {
+            return false;
+        }
+    }
+    matched >= *min_count
+}
+
 fn check_must_not(must: &Option<Vec<OptimizedCondition>>, point_id: PointOffsetType) -> bool {
     let check = |condition| !check_condition(condition, point_id);
     match must {
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use atomic_refcell::AtomicRefCell;
 
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
-use crate::types::{OwnedPayloadRef, Payload, PointOffsetType};
+use crate::types::{OwnedPayloadRef, Payload, PayloadKeyTypeRef, PointOffsetType};
 
 #[derive(Clone)]
 pub struct PayloadProvider {
@@ -20,6 +20,29 @@ impl PayloadProvider {
         }
     }
 
+    /// Same as [`Self::with_payload`], but only values of the `key` are used by the callback.
+    /// They are taken from memory, if the on-disk storage caches them
+    pub fn with_field_payload<F, G>(
+        &self,
+        point_id: PointOffsetType,
+        key: PayloadKeyTypeRef,
+        callback: F,
+    ) -> G
+    where
+        F: FnOnce(OwnedPayloadRef) -> G,
+    {
+        {
+            let payload_storage_guard = self.payload_storage.borrow();
+            if let PayloadStorageEnum::OnDiskPayloadStorage(s) = payload_storage_guard.deref() {
+                if s.is_cached(key) {
+                    let payload = s.cached_payload(point_id).unwrap_or(&self.empty_payload);
+                    return callback(payload.into());
+                }
+            }
+        }
+        self.with_payload(point_id, callback)
+    }
+
     pub fn with_payload<F, G>(&self, point_id: PointOffsetType, callback: F) -> G
     where
         F: FnOnce(OwnedPayloadRef) -> G,
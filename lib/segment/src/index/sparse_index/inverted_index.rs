@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::file_operations::{atomic_save_bin, read_bin};
+use crate::data_types::sparse_vector::{DimId, DimWeight, SparseVector};
+use crate::entry::entry_point::OperationResult;
+use crate::spaces::tools::FixedLengthPriorityQueue;
+use crate::types::{PointOffsetType, ScoreType};
+use crate::vector_storage::ScoredPointOffset;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PostingElement {
+    pub id: PointOffsetType,
+    pub weight: DimWeight,
+}
+
+/// Points having a non-zero value in a single dimension, ordered by id.
+///
+/// `max_weight` and `min_weight` bound the weights of the list. They are not tightened on
+/// removal, so they stay valid upper and lower bounds for the score contribution of the list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostingList {
+    elements: Vec<PostingElement>,
+    max_weight: DimWeight,
+    min_weight: DimWeight,
+}
+
+impl PostingList {
+    pub fn elements(&self) -> &[PostingElement] {
+        &self.elements
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn upsert(&mut self, id: PointOffsetType, weight: DimWeight) {
+        let element = PostingElement { id, weight };
+        match self.elements.binary_search_by_key(&id, |e| e.id) {
+            Ok(position) => self.elements[position] = element,
+            // Points are mostly appended in id order, so this is usually a push
+            Err(position) => self.elements.insert(position, element),
+        }
+        if self.elements.len() == 1 {
+            self.max_weight = weight;
+            self.min_weight = weight;
+        } else {
+            self.max_weight = self.max_weight.max(weight);
+            self.min_weight = self.min_weight.min(weight);
+        }
+    }
+
+    pub fn remove(&mut self, id: PointOffsetType) {
+        if let Ok(position) = self.elements.binary_search_by_key(&id, |e| e.id) {
+            self.elements.remove(position);
+        }
+    }
+
+    /// Largest absolute contribution of this list to the score of a query with the given weight.
+    fn score_upper_bound(&self, query_weight: DimWeight) -> ScoreType {
+        (query_weight * self.max_weight)
+            .max(query_weight * self.min_weight)
+            .max(0.0)
+    }
+}
+
+/// Inverted index over the dimensions of sparse vectors
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InvertedIndex {
+    postings: HashMap<DimId, PostingList>,
+}
+
+impl InvertedIndex {
+    pub fn load(path: &Path) -> OperationResult<Self> {
+        Ok(read_bin(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> OperationResult<()> {
+        Ok(atomic_save_bin(path, self)?)
+    }
+
+    pub fn get(&self, dim: DimId) -> Option<&PostingList> {
+        self.postings.get(&dim)
+    }
+
+    /// Number of dimensions with at least one point.
+    pub fn dimension_count(&self) -> usize {
+        self.postings.values().filter(|p| !p.is_empty()).count()
+    }
+
+    pub fn upsert(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        for (dim, weight) in vector.iter() {
+            self.postings.entry(dim).or_default().upsert(id, weight);
+        }
+    }
+
+    pub fn remove(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        for dim in &vector.indices {
+            if let Some(posting) = self.postings.get_mut(dim) {
+                posting.remove(id);
+            }
+        }
+    }
+
+    /// Top-k search by dot product using the MaxScore algorithm.
+    ///
+    /// Query dimensions are ordered by the upper bound of their score contribution. Lists whose
+    /// summed bounds can't lift a point above the current k-th score are non-essential: they
+    /// are never used to produce candidates and are only probed for candidates found in the
+    /// essential lists. Candidates rejected by `check` are skipped before scoring, which is how
+    /// filters and deletions are intersected with the traversal.
+    pub fn search(
+        &self,
+        query: &SparseVector,
+        top: usize,
+        check: impl Fn(PointOffsetType) -> bool,
+    ) -> Vec<ScoredPointOffset> {
+        if top == 0 {
+            return vec![];
+        }
+
+        let mut cursors: Vec<PostingCursor> = query
+            .iter()
+            .filter_map(|(dim, query_weight)| {
+                let posting = self.postings.get(&dim)?;
+                let max_score = posting.score_upper_bound(query_weight);
+                (!posting.is_empty() && query_weight != 0.0).then_some(PostingCursor {
+                    elements: posting.elements(),
+                    position: 0,
+                    query_weight,
+                    max_score,
+                })
+            })
+            .collect();
+        if cursors.is_empty() {
+            return vec![];
+        }
+        cursors.sort_by(|a, b| a.max_score.total_cmp(&b.max_score));
+
+        // Accumulated bounds: `prefix_bounds[i]` bounds the score gathered from cursors `0..=i`
+        let prefix_bounds: Vec<ScoreType> = cursors
+            .iter()
+            .scan(0.0, |sum, cursor| {
+                *sum += cursor.max_score;
+                Some(*sum)
+            })
+            .collect();
+
+        let mut queue = FixedLengthPriorityQueue::new(top);
+        let mut threshold = ScoreType::NEG_INFINITY;
+        // Cursors `0..first_essential` are non-essential
+        let mut first_essential = 0;
+
+        loop {
+            while first_essential < cursors.len() && prefix_bounds[first_essential] <= threshold {
+                first_essential += 1;
+            }
+            if first_essential == cursors.len() {
+                break;
+            }
+
+            let Some(candidate) = cursors[first_essential..]
+                .iter()
+                .filter_map(PostingCursor::current_id)
+                .min()
+            else {
+                break;
+            };
+
+            let accepted = check(candidate);
+            let mut score = 0.0;
+            for cursor in &mut cursors[first_essential..] {
+                if let Some(weight) = cursor.take_if_at(candidate) {
+                    score += cursor.query_weight * weight;
+                }
+            }
+            if !accepted {
+                continue;
+            }
+
+            for i in (0..first_essential).rev() {
+                if score + prefix_bounds[i] <= threshold {
+                    break;
+                }
+                let cursor = &mut cursors[i];
+                cursor.skip_to(candidate);
+                if let Some(weight) = cursor.take_if_at(candidate) {
+                    score += cursor.query_weight * weight;
+                }
+            }
+
+            if queue.len() < top || score > threshold {
+                queue.push(ScoredPointOffset {
+                    idx: candidate,
+                    score,
+                });
+                if queue.len() == top {
+                    threshold = queue.top().map_or(threshold, |worst| worst.score);
+                }
+            }
+        }
+
+        queue.into_vec()
+    }
+}
+
+struct PostingCursor<'a> {
+    elements: &'a [PostingElement],
+    position: usize,
+    query_weight: DimWeight,
+    max_score: ScoreType,
+}
+
+impl<'a> PostingCursor<'a> {
+    fn current_id(&self) -> Option<PointOffsetType> {
+        self.elements.get(self.position).map(|e| e.id)
+    }
+
+    /// Move to the first element with id not less than the given one.
+    fn skip_to(&mut self, id: PointOffsetType) {
+        let rest = &self.elements[self.position..];
+        self.position += rest.partition_point(|e| e.id < id);
+    }
+
+    /// Return the weight and advance, if the cursor stands at the given id.
+    fn take_if_at(&mut self, id: PointOffsetType) -> Option<DimWeight> {
+        let element = self.elements.get(self.position)?;
+        if element.id != id {
+            return None;
+        }
+        self.position += 1;
+        Some(element.weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::spaces::tools::peek_top_largest_iterable;
+
+    fn random_sparse_vector(rnd: &mut StdRng, dims: DimId, count: usize) -> SparseVector {
+        let mut indices: Vec<DimId> = (0..count).map(|_| rnd.gen_range(0..dims)).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        let values = indices.iter().map(|_| rnd.gen_range(-0.2..1.0)).collect();
+        SparseVector::new(indices, values).unwrap()
+    }
+
+    #[test]
+    fn test_max_score_matches_brute_force() {
+        let mut rnd = StdRng::seed_from_u64(42);
+        let vectors: Vec<_> = (0..500)
+            .map(|_| random_sparse_vector(&mut rnd, 50, 8))
+            .collect();
+
+        let mut index = InvertedIndex::default();
+        for (id, vector) in vectors.iter().enumerate() {
+            index.upsert(id as PointOffsetType, vector);
+        }
+        // Removed points must not be returned
+        for id in (0..500).step_by(7) {
+            index.remove(id, &vectors[id as usize]);
+        }
+
+        let is_even = |id: PointOffsetType| id % 2 == 0;
+        for _ in 0..20 {
+            let query = random_sparse_vector(&mut rnd, 50, 6);
+            for filtered in [false, true] {
+                let check = |id: PointOffsetType| !filtered || is_even(id);
+                let result = index.search(&query, 10, check);
+
+                let expected = peek_top_largest_iterable(
+                    vectors
+                        .iter()
+                        .enumerate()
+                        .map(|(id, v)| (id as PointOffsetType, v))
+                        .filter(|(id, v)| id % 7 != 0 && check(*id) && has_overlap(&query, v))
+                        .map(|(idx, v)| ScoredPointOffset {
+                            idx,
+                            score: query.dot(v),
+                        }),
+                    10,
+                );
+
+                assert_eq!(result.len(), expected.len());
+                for (found, expected) in result.iter().zip(&expected) {
+                    assert!((found.score - expected.score).abs() < 1e-5);
+                }
+                assert!(result.iter().all(|p| p.idx % 7 != 0 && check(p.idx)));
+            }
+        }
+    }
+
+    fn has_overlap(a: &SparseVector, b: &SparseVector) -> bool {
+        a.indices.iter().any(|i| b.indices.contains(i))
+    }
+
+    #[test]
+    fn test_inverted_index_persistence() {
+        let dir = tempfile::Builder::new()
+            .prefix("inverted_index")
+            .tempdir()
+            .unwrap();
+        let path = dir.path().join("inverted_index.bin");
+
+        let mut index = InvertedIndex::default();
+        index.upsert(0, &SparseVector::new(vec![1, 3], vec![0.5, 1.0]).unwrap());
+        index.upsert(1, &SparseVector::new(vec![3, 4], vec![2.0, 1.0]).unwrap());
+        index.save(&path).unwrap();
+
+        let loaded = InvertedIndex::load(&path).unwrap();
+        assert_eq!(loaded, index);
+        assert_eq!(loaded.get(3).unwrap().len(), 2);
+        assert_eq!(loaded.dimension_count(), 3);
+    }
+}
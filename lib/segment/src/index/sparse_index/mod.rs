@@ -0,0 +1,2 @@
+pub mod inverted_index;
+pub mod sparse_vector_index;
@@ -0,0 +1,215 @@
+use std::fs::{create_dir_all, remove_file};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+
+use super::inverted_index::InvertedIndex;
+use crate::data_types::sparse_vector::SparseVector;
+use crate::entry::entry_point::{check_process_stopped, OperationResult};
+use crate::id_tracker::IdTrackerSS;
+use crate::index::struct_payload_index::StructPayloadIndex;
+use crate::index::PayloadIndex;
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{Filter, PointOffsetType, SparseIndexConfig};
+use crate::vector_storage::sparse_vector_storage::SparseVectorStorage;
+use crate::vector_storage::ScoredPointOffset;
+
+const INVERTED_INDEX_FILE: &str = "inverted_index.bin";
+
+/// Search index for a single sparse vector, backed by an [`InvertedIndex`]
+///
+/// Updates go through this index, which keeps the inverted index in sync with the storage.
+/// The inverted index is saved to disk only when built for a finished segment. Appendable
+/// segments rebuild it from the storage on load.
+pub struct SparseVectorIndex {
+    config: SparseIndexConfig,
+    id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    vector_storage: Arc<AtomicRefCell<SparseVectorStorage>>,
+    payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+    path: PathBuf,
+    inverted_index: InvertedIndex,
+}
+
+impl SparseVectorIndex {
+    pub fn open(
+        config: SparseIndexConfig,
+        id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storage: Arc<AtomicRefCell<SparseVectorStorage>>,
+        payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+        path: &Path,
+    ) -> OperationResult<Self> {
+        create_dir_all(path)?;
+        let index_file = path.join(INVERTED_INDEX_FILE);
+        let inverted_index = if index_file.exists() {
+            InvertedIndex::load(&index_file)?
+        } else {
+            Self::index_storage(&vector_storage.borrow(), &AtomicBool::new(false))?
+        };
+        Ok(Self {
+            config,
+            id_tracker,
+            vector_storage,
+            payload_index,
+            path: path.to_owned(),
+            inverted_index,
+        })
+    }
+
+    fn index_storage(
+        vector_storage: &SparseVectorStorage,
+        stopped: &AtomicBool,
+    ) -> OperationResult<InvertedIndex> {
+        let mut inverted_index = InvertedIndex::default();
+        for id in vector_storage.iter_ids() {
+            check_process_stopped(stopped)?;
+            if let Some(vector) = vector_storage.get(id) {
+                inverted_index.upsert(id, vector);
+            }
+        }
+        Ok(inverted_index)
+    }
+
+    fn index_file(&self) -> PathBuf {
+        self.path.join(INVERTED_INDEX_FILE)
+    }
+
+    /// Rebuild the inverted index from the storage and save it to disk.
+    pub fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
+        self.inverted_index = Self::index_storage(&self.vector_storage.borrow(), stopped)?;
+        self.inverted_index.save(&self.index_file())
+    }
+
+    /// Drop the saved inverted index once it goes out of sync with the storage, so that it is
+    /// rebuilt on the next load.
+    fn invalidate_saved_index(&self) -> OperationResult<()> {
+        let index_file = self.index_file();
+        if index_file.exists() {
+            remove_file(index_file)?;
+        }
+        Ok(())
+    }
+
+    pub fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &SparseVector,
+    ) -> OperationResult<()> {
+        let mut vector_storage = self.vector_storage.borrow_mut();
+        if !vector_storage.is_deleted_vector(key) {
+            if let Some(old_vector) = vector_storage.get(key) {
+                self.inverted_index.remove(key, old_vector);
+            }
+        }
+        vector_storage.insert_vector(key, vector)?;
+        if let Some(stored) = vector_storage.get(key) {
+            self.inverted_index.upsert(key, stored);
+        }
+        drop(vector_storage);
+        self.invalidate_saved_index()
+    }
+
+    /// Delete vector from the storage. Returns `true` if the vector was not deleted before.
+    ///
+    /// Deleted vectors are never returned by search, so a saved index stays valid.
+    pub fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let mut vector_storage = self.vector_storage.borrow_mut();
+        if !vector_storage.is_deleted_vector(key) {
+            if let Some(old_vector) = vector_storage.get(key) {
+                self.inverted_index.remove(key, old_vector);
+            }
+        }
+        vector_storage.delete_vector(key)
+    }
+
+    /// Top-k search by dot product for a batch of query vectors.
+    ///
+    /// Filtered queries matching less than `full_scan_threshold` points are scored directly
+    /// against the storage, otherwise the filter is checked while traversing posting lists.
+    pub fn search(
+        &self,
+        vectors: &[&SparseVector],
+        filter: Option<&Filter>,
+        top: usize,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let payload_index = self.payload_index.borrow();
+        let deleted_points = id_tracker.deleted_point_bitslice();
+        let is_available = |id: PointOffsetType| {
+            !deleted_points.get(id as usize).map_or(false, |b| *b)
+                && !vector_storage.is_deleted_vector(id)
+        };
+        let full_scan_threshold = self.config.full_scan_threshold();
+
+        let queries = vectors.iter().map(|vector| (*vector).clone().into_sorted());
+
+        match filter {
+            Some(filter)
+                if payload_index.estimate_cardinality(filter).max < full_scan_threshold =>
+            {
+                let filtered_ids = payload_index.query_points(filter);
+                queries
+                    .map(|query| {
+                        let ids = filtered_ids.iter().copied().filter(|&id| is_available(id));
+                        Self::score_plain(&query, &vector_storage, ids, top)
+                    })
+                    .collect()
+            }
+            Some(filter) => {
+                let filter_context = payload_index.filter_context(filter);
+                queries
+                    .map(|query| {
+                        self.inverted_index.search(&query, top, |id| {
+                            is_available(id) && filter_context.check(id)
+                        })
+                    })
+                    .collect()
+            }
+            None if vector_storage.available_vector_count() < full_scan_threshold => queries
+                .map(|query| {
+                    let ids = vector_storage.iter_ids().filter(|&id| is_available(id));
+                    Self::score_plain(&query, &vector_storage, ids, top)
+                })
+                .collect(),
+            None => queries
+                .map(|query| self.inverted_index.search(&query, top, is_available))
+                .collect(),
+        }
+    }
+
+    fn score_plain(
+        query: &SparseVector,
+        vector_storage: &SparseVectorStorage,
+        ids: impl Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let scores = ids.filter_map(|idx| {
+            let vector = vector_storage.get(idx)?;
+            // Only points sharing a dimension with the query are matched, as with the index
+            let overlaps = vector
+                .indices
+                .iter()
+                .any(|dim| query.indices.binary_search(dim).is_ok());
+            overlaps.then(|| ScoredPointOffset {
+                idx,
+                score: query.dot(vector),
+            })
+        });
+        peek_top_largest_iterable(scores, top)
+    }
+
+    pub fn indexed_vector_count(&self) -> usize {
+        self.vector_storage.borrow().available_vector_count()
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        let index_file = self.index_file();
+        if index_file.exists() {
+            vec![index_file]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::create_dir_all;
+use std::mem::size_of;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -11,32 +12,78 @@ use rocksdb::DB;
 use schemars::_serde_json::Value;
 
 use crate::common::arc_atomic_ref_cell_iterator::ArcAtomicRefCellIterator;
-use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+use crate::common::file_operations::{atomic_save_json, read_json};
+use crate::common::rocksdb_wrapper::{open_db_with_existing_cf, DatabaseColumnWrapper};
 use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
 use crate::common::Flusher;
+use crate::data_types::filter_trace::FilterTrace;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::id_tracker::IdTrackerSS;
-use crate::index::field_index::index_selector::index_selector;
+use crate::index::field_index::index_selector::{index_builder_selector, index_selector};
 use crate::index::field_index::{
-    CardinalityEstimation, FieldIndex, PayloadBlockCondition, PrimaryCondition,
+    CardinalityEstimation, FieldIndex, FieldIndexBuilder, PayloadBlockCondition, PrimaryCondition,
 };
 use crate::index::payload_config::PayloadConfig;
 use crate::index::query_estimator::estimate_filter;
+use crate::index::query_optimization::condition_converter::condition_trace;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::index::struct_filter_context::StructFilterContext;
 use crate::index::visited_pool::VisitedPool;
 use crate::index::PayloadIndex;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
+use crate::payload_storage::query_checker::trace_filter;
 use crate::payload_storage::{FilterContext, PayloadStorage};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     infer_collection_value_type, infer_value_type, Condition, FieldCondition, Filter,
-    IsEmptyCondition, IsNullCondition, Payload, PayloadContainer, PayloadField, PayloadFieldSchema,
-    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
+    IsEmptyCondition, IsNullCondition, MinShould, Payload, PayloadContainer, PayloadField,
+    PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PayloadSelector,
+    PointOffsetType, SeqNumberType, ValuesCount,
 };
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
 
+/// Number of points, indexed into the staging indexes of each field, as of the last flush
+const STAGING_PROGRESS_FILE: &str = "staging_progress.json";
+
+/// Name of the storage of field indexes of the given generation.
+/// The initial generation keeps the name of the field for compatibility.
+fn index_storage_name(field: PayloadKeyTypeRef, generation: u32) -> String {
+    if generation == 0 {
+        field.to_owned()
+    } else {
+        format!("{field}#{generation}")
+    }
+}
+
+/// Approximate size of the values of the field, read from the payload storage
+fn field_value_bytes(field_value: &MultiValue<&Value>) -> usize {
+    fn value_bytes(value: &Value) -> usize {
+        match value {
+            Value::Null | Value::Bool(_) | Value::Number(_) => size_of::<f64>(),
+            Value::String(string) => string.len(),
+            Value::Array(values) => values.iter().map(value_bytes).sum(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| key.len() + value_bytes(value))
+                .sum(),
+        }
+    }
+    match field_value {
+        MultiValue::Single(value) => value.map_or(0, value_bytes),
+        MultiValue::Multiple(values) => values.iter().map(|value| value_bytes(value)).sum(),
+    }
+}
+
+/// Outcome of indexing a batch of points into the staging indexes of a field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StagedBatch {
+    /// Size of the payload values, read to index the batch
+    pub bytes_read: usize,
+    /// All points are indexed, the staging indexes replaced the current ones
+    pub is_complete: bool,
+}
+
 /// `PayloadIndex` implementation, which actually uses index structures for providing faster search
 pub struct StructPayloadIndex {
     /// Payload storage
@@ -51,6 +98,21 @@ pub struct StructPayloadIndex {
     /// Used to select unique point ids
     visited_pool: VisitedPool,
     db: Arc<RwLock<DB>>,
+    /// Number of values, removed or overwritten in field indexes since they were loaded or built.
+    /// Removed values are left in the index storage as garbage until the index is rebuilt.
+    obsolete_values: HashMap<PayloadKeyType, usize>,
+    /// Indexed fields, which persisted indexes are corrupted and failed to load.
+    /// Conditions on these fields are checked against the payload, until the index is rebuilt.
+    needs_rebuild: HashSet<PayloadKeyType>,
+    /// Indexes of the fields, which are being rebuilt into the storage of the next generation.
+    /// Updates are written into both current and staging indexes, while only the current
+    /// indexes serve reads until the rebuild is complete.
+    staging_indexes: HashMap<PayloadKeyType, Vec<FieldIndex>>,
+    /// Number of points, already indexed into the staging indexes of each field
+    staged_points: HashMap<PayloadKeyType, PointOffsetType>,
+    /// Segment receives new points. Otherwise, the immutable variants of the field indexes
+    /// are used, once they are built.
+    is_appendable: bool,
 }
 
 impl StructPayloadIndex {
@@ -61,6 +123,12 @@ impl StructPayloadIndex {
     ) -> Option<CardinalityEstimation> {
         let full_path = JsonPathPayload::extend_or_new(nested_path, &condition.key);
         self.field_indexes.get(&full_path.path).and_then(|indexes| {
+            if let Some(values_count) = &condition.values_count {
+                return indexes
+                    .iter()
+                    .find(|index| !matches!(index, FieldIndex::FullTextIndex(_)))
+                    .map(|index| self.values_count_cardinality(index, values_count));
+            }
             // rewrite condition with fullpath to enable cardinality estimation
             let full_path_condition = FieldCondition {
                 key: full_path.path,
@@ -77,6 +145,21 @@ impl StructPayloadIndex {
         })
     }
 
+    /// Every point is checked, as the index knows exactly how many values each point has
+    fn values_count_cardinality(
+        &self,
+        index: &FieldIndex,
+        values_count: &ValuesCount,
+    ) -> CardinalityEstimation {
+        let matched = self
+            .id_tracker
+            .borrow()
+            .iter_ids()
+            .filter(|&point_id| values_count.check_count_number(index.values_count(point_id)))
+            .count();
+        CardinalityEstimation::exact(matched)
+    }
+
     fn query_field<'a>(
         &'a self,
         field_condition: &'a FieldCondition,
@@ -93,6 +176,53 @@ impl StructPayloadIndex {
         indexes
     }
 
+    /// Points, matching at least `min_count` of the `min_should` conditions of the query,
+    /// merged from the field indexes.
+    ///
+    /// Returns `None` if the query has other positive clauses or if any of the conditions
+    /// can't be served by a field index.
+    fn query_min_should(&self, query: &Filter) -> Option<Vec<PointOffsetType>> {
+        let MinShould {
+            conditions,
+            min_count,
+        } = query.min_should.as_ref()?;
+        let has_other_clauses = [&query.should, &query.must]
+            .into_iter()
+            .any(|clause| clause.as_ref().map_or(false, |x| !x.is_empty()));
+        if has_other_clauses || conditions.is_empty() {
+            return None;
+        }
+
+        let posting_iterators: Vec<_> = conditions
+            .iter()
+            .map(|condition| match condition {
+                Condition::Field(field_condition) => self.query_field(field_condition),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+
+        // Number of matched conditions, and the last matched one, to count each condition once
+        let mut matched: BTreeMap<PointOffsetType, (usize, usize)> = BTreeMap::new();
+        for (condition_idx, posting_iterator) in posting_iterators.into_iter().enumerate() {
+            for point_id in posting_iterator {
+                let (count, last_condition_idx) =
+                    matched.entry(point_id).or_insert((0, usize::MAX));
+                if *last_condition_idx != condition_idx {
+                    *count += 1;
+                    *last_condition_idx = condition_idx;
+                }
+            }
+        }
+
+        Some(
+            matched
+                .into_iter()
+                .filter(|(_, (count, _))| count >= min_count)
+                .map(|(point_id, _)| point_id)
+                .collect(),
+        )
+    }
+
     fn config_path(&self) -> PathBuf {
         PayloadConfig::get_config_path(&self.path)
     }
@@ -102,29 +232,173 @@ impl StructPayloadIndex {
         self.config.save(&config_path)
     }
 
+    fn staging_progress_path(&self) -> PathBuf {
+        self.path.join(STAGING_PROGRESS_FILE)
+    }
+
+    fn field_generation(&self, field: PayloadKeyTypeRef) -> u32 {
+        self.config
+            .index_generations
+            .get(field)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Schema, which the staging indexes of the field are built with
+    fn staging_schema(&self, field: PayloadKeyTypeRef) -> Option<&PayloadFieldSchema> {
+        self.config
+            .staged_schemas
+            .get(field)
+            .or_else(|| self.config.indexed_fields.get(field))
+    }
+
+    /// Indexes of the field, stored in the storage of the `generation`.
+    /// Indexes, which are filled with points, must be `mutable`.
+    fn field_indexes_of_generation(
+        &self,
+        field: PayloadKeyTypeRef,
+        payload_schema: &PayloadFieldSchema,
+        generation: u32,
+        mutable: bool,
+    ) -> Vec<FieldIndex> {
+        let storage_name = index_storage_name(field, generation);
+        let is_appendable = mutable || self.is_appendable;
+        index_selector(
+            &storage_name,
+            payload_schema,
+            self.db.clone(),
+            is_appendable,
+        )
+    }
+
+    /// Builders of the indexes of the field, stored in the storage of the `generation`
+    fn field_index_builders_of_generation(
+        &self,
+        field: PayloadKeyTypeRef,
+        payload_schema: &PayloadFieldSchema,
+        generation: u32,
+    ) -> Vec<Box<dyn FieldIndexBuilder>> {
+        let storage_name = index_storage_name(field, generation);
+        index_builder_selector(
+            &storage_name,
+            payload_schema,
+            self.db.clone(),
+            self.is_appendable,
+        )
+    }
+
+    /// Replace the completely built indexes with their immutable variants,
+    /// if the segment doesn't receive new points
+    fn freeze_field_indexes(&self, indexes: Vec<FieldIndex>) -> Vec<FieldIndex> {
+        if self.is_appendable {
+            return indexes;
+        }
+        indexes
+            .into_iter()
+            .map(FieldIndex::into_immutable)
+            .collect()
+    }
+
+    /// Reopen staging indexes of the interrupted rebuilds, so that they continue from the last
+    /// flushed progress. Staging indexes, which failed to load, are rebuilt from scratch.
+    fn load_staging_fields(&mut self) -> OperationResult<()> {
+        let progress_path = self.staging_progress_path();
+        let mut staged_points: HashMap<PayloadKeyType, PointOffsetType> = if progress_path.exists()
+        {
+            read_json(&progress_path)?
+        } else {
+            Default::default()
+        };
+
+        let mut staging_indexes = HashMap::new();
+        for field in &self.config.staged_rebuilds {
+            let Some(payload_schema) = self.staging_schema(field) else {
+                continue;
+            };
+            let generation = self.field_generation(field) + 1;
+            let mut indexes =
+                self.field_indexes_of_generation(field, payload_schema, generation, true);
+
+            let mut is_loaded = true;
+            for index in indexes.iter_mut() {
+                match index.load() {
+                    Ok(true) => {}
+                    Ok(false) => is_loaded = false,
+                    Err(err) => {
+                        log::warn!("Staging index for `{field}` is corrupted: {err}");
+                        is_loaded = false;
+                    }
+                }
+            }
+            if !is_loaded {
+                indexes = self.field_indexes_of_generation(field, payload_schema, generation, true);
+                for index in &indexes {
+                    index.recreate()?;
+                }
+                staged_points.remove(field);
+            }
+            staging_indexes.insert(field.clone(), indexes);
+        }
+
+        staged_points.retain(|field, _| staging_indexes.contains_key(field));
+        for field in staging_indexes.keys() {
+            staged_points.entry(field.clone()).or_default();
+        }
+        self.staging_indexes = staging_indexes;
+        self.staged_points = staged_points;
+        Ok(())
+    }
+
     fn load_all_fields(&mut self) -> OperationResult<()> {
         let mut field_indexes: IndexesMap = Default::default();
+        let mut needs_rebuild = HashSet::new();
 
         for (field, payload_schema) in &self.config.indexed_fields {
-            let field_index = self.load_from_db(field, payload_schema.to_owned())?;
-            field_indexes.insert(field.clone(), field_index);
+            // Index is only available, once its staged build is complete
+            if self.config.building_indexes.contains_key(field) {
+                continue;
+            }
+            match self.load_from_db(field, payload_schema.to_owned())? {
+                Some(field_index) => {
+                    field_indexes.insert(field.clone(), field_index);
+                }
+                None => {
+                    needs_rebuild.insert(field.clone());
+                }
+            }
         }
         self.field_indexes = field_indexes;
-        Ok(())
+        self.needs_rebuild = needs_rebuild;
+        self.load_staging_fields()
     }
 
+    /// Load indexes of the field, or build them if they were never persisted.
+    ///
+    /// Returns `None` if the persisted indexes are corrupted, so that a single broken field
+    /// doesn't prevent the whole segment from loading.
     fn load_from_db(
         &self,
         field: PayloadKeyTypeRef,
         payload_schema: PayloadFieldSchema,
-    ) -> OperationResult<Vec<FieldIndex>> {
-        let mut indexes = index_selector(field, &payload_schema, self.db.clone());
+    ) -> OperationResult<Option<Vec<FieldIndex>>> {
+        let generation = self.field_generation(field);
+        let mut indexes =
+            self.field_indexes_of_generation(field, &payload_schema, generation, false);
 
         let mut is_loaded = true;
         for ref mut index in indexes.iter_mut() {
-            if !index.load()? {
-                is_loaded = false;
-                break;
+            match index.load() {
+                Ok(true) => {}
+                Ok(false) => {
+                    is_loaded = false;
+                    break;
+                }
+                Err(err) => {
+                    log::warn!(
+                        "Index for `{field}` is corrupted and will be rebuilt in background: {err}"
+                    );
+                    return Ok(None);
+                }
             }
         }
         if !is_loaded {
@@ -132,13 +406,14 @@ impl StructPayloadIndex {
             indexes = self.build_field_indexes(field, payload_schema)?;
         }
 
-        Ok(indexes)
+        Ok(Some(indexes))
     }
 
     pub fn open(
         payload: Arc<AtomicRefCell<PayloadStorageEnum>>,
         id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
         path: &Path,
+        is_appendable: bool,
     ) -> OperationResult<Self> {
         create_dir_all(path)?;
         let config_path = PayloadConfig::get_config_path(path);
@@ -159,6 +434,11 @@ impl StructPayloadIndex {
             path: path.to_owned(),
             visited_pool: Default::default(),
             db,
+            obsolete_values: Default::default(),
+            needs_rebuild: Default::default(),
+            staging_indexes: Default::default(),
+            staged_points: Default::default(),
+            is_appendable,
         };
 
         if !index.config_path().exists() {
@@ -177,19 +457,24 @@ impl StructPayloadIndex {
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<Vec<FieldIndex>> {
         let payload_storage = self.payload.borrow();
-        let mut field_indexes = index_selector(field, &payload_schema, self.db.clone());
-        for index in &field_indexes {
-            index.recreate()?;
+        let generation = self.field_generation(field);
+        let mut builders =
+            self.field_index_builders_of_generation(field, &payload_schema, generation);
+        for builder in builders.iter_mut() {
+            builder.init()?;
         }
 
         payload_storage.iter(|point_id, point_payload| {
             let field_value = &point_payload.get_value(field);
-            for field_index in field_indexes.iter_mut() {
-                field_index.add_point(point_id, field_value)?;
+            for builder in builders.iter_mut() {
+                builder.add_point(point_id, field_value)?;
             }
             Ok(true)
         })?;
-        Ok(field_indexes)
+        builders
+            .into_iter()
+            .map(|builder| builder.finalize())
+            .collect()
     }
 
     fn build_and_save(
@@ -202,6 +487,274 @@ impl StructPayloadIndex {
         Ok(())
     }
 
+    /// Rebuild indexes of the field from scratch, dropping all obsolete values from the storage
+    pub fn rebuild_field_index(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        if self.stage_field_index_rebuild(field)? {
+            self.build_staged_field_index(field, usize::MAX)?;
+        }
+        Ok(())
+    }
+
+    /// Start rebuilding indexes of the field into the storage of the next generation.
+    /// A rebuild, which is already in progress, is continued.
+    ///
+    /// Returns false if the field is not indexed.
+    pub fn stage_field_index_rebuild(&mut self, field: PayloadKeyTypeRef) -> OperationResult<bool> {
+        let Some(payload_schema) = self.staging_schema(field) else {
+            return Ok(false);
+        };
+        if self.staging_indexes.contains_key(field) {
+            return Ok(true);
+        }
+
+        let generation = self.field_generation(field) + 1;
+        let indexes = self.field_indexes_of_generation(field, payload_schema, generation, true);
+        for index in &indexes {
+            index.recreate()?;
+        }
+        self.staging_indexes.insert(field.to_owned(), indexes);
+        self.staged_points.insert(field.to_owned(), 0);
+        self.config.staged_rebuilds.insert(field.to_owned());
+        self.save_config()?;
+        Ok(true)
+    }
+
+    /// Convert the indexes of the field between the in-memory and the on-disk representation,
+    /// according to the `on_disk` flag of the `payload_schema`.
+    ///
+    /// Converted indexes are built through staging, while the current ones keep serving reads.
+    /// Requesting the current representation during the conversion cancels it.
+    ///
+    /// Returns false if the field is not indexed, or the indexes already have the representation.
+    pub fn stage_field_index_conversion(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        payload_schema: PayloadFieldSchema,
+    ) -> OperationResult<bool> {
+        let Some(current_schema) = self.config.indexed_fields.get(field) else {
+            return Ok(false);
+        };
+        let is_on_disk = payload_schema.is_on_disk();
+        let target_schema = self.staging_schema(field).unwrap_or(current_schema);
+        if target_schema.is_on_disk() == is_on_disk {
+            return Ok(false);
+        }
+        let is_current = current_schema.is_on_disk() == is_on_disk;
+
+        self.clear_staging_indexes(field)?;
+        if self.config.building_indexes.contains_key(field) {
+            // Index, which is being created, doesn't serve reads yet, so it is built anew
+            self.config
+                .indexed_fields
+                .insert(field.to_owned(), payload_schema);
+        } else if is_current {
+            self.save_config()?;
+            return Ok(true);
+        } else {
+            self.config
+                .staged_schemas
+                .insert(field.to_owned(), payload_schema);
+        }
+        // Saves the config
+        self.stage_field_index_rebuild(field)
+    }
+
+    /// Remove the staging indexes of the field, together with the progress of their build.
+    /// The config is not saved.
+    fn clear_staging_indexes(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        self.staged_points.remove(field);
+        self.config.staged_rebuilds.remove(field);
+        self.config.staged_schemas.remove(field);
+        for index in self.staging_indexes.remove(field).into_iter().flatten() {
+            index.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Index the next `batch_size` points into the staging indexes of the field.
+    /// Once all points are indexed, the staging indexes replace the current ones.
+    pub fn build_staged_field_index(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        batch_size: usize,
+    ) -> OperationResult<StagedBatch> {
+        let Some(staging_indexes) = self.staging_indexes.get_mut(field) else {
+            return Ok(StagedBatch {
+                bytes_read: 0,
+                is_complete: true,
+            });
+        };
+        let total_points = self.id_tracker.borrow().total_point_count() as PointOffsetType;
+        let staged_points = self.staged_points.entry(field.to_owned()).or_default();
+        let batch_size = PointOffsetType::try_from(batch_size).unwrap_or(PointOffsetType::MAX);
+        let batch_end = staged_points.saturating_add(batch_size).min(total_points);
+
+        let payload_storage = self.payload.borrow();
+        let mut bytes_read = 0;
+        for point_id in *staged_points..batch_end {
+            let point_payload = payload_storage.payload(point_id)?;
+            let field_value = &point_payload.get_value(field);
+            bytes_read += field_value_bytes(field_value);
+            for index in staging_indexes.iter_mut() {
+                index.add_point(point_id, field_value)?;
+            }
+        }
+        *staged_points = batch_end;
+        drop(payload_storage);
+
+        let is_complete = batch_end >= total_points;
+        if is_complete {
+            self.swap_staged_field_index(field)?;
+        }
+        Ok(StagedBatch {
+            bytes_read,
+            is_complete,
+        })
+    }
+
+    /// Replace the indexes of the field with the completely built staging indexes
+    fn swap_staged_field_index(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        let Some(staging_indexes) = self.staging_indexes.remove(field) else {
+            return Ok(());
+        };
+        // The config may only refer to the new generation, once its storage is persisted
+        for index in &staging_indexes {
+            index.flusher()()?;
+        }
+
+        let generation = self.field_generation(field) + 1;
+        let staging_indexes = self.freeze_field_indexes(staging_indexes);
+        let old_indexes = self.field_indexes.insert(field.to_owned(), staging_indexes);
+        self.config
+            .index_generations
+            .insert(field.to_owned(), generation);
+        if let Some(payload_schema) = self.config.staged_schemas.remove(field) {
+            self.config
+                .indexed_fields
+                .insert(field.to_owned(), payload_schema);
+        }
+        self.config.staged_rebuilds.remove(field);
+        self.config.building_indexes.remove(field);
+        self.save_config()?;
+
+        self.staged_points.remove(field);
+        self.obsolete_values.remove(field);
+        self.needs_rebuild.remove(field);
+        for index in old_indexes.into_iter().flatten() {
+            index.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Schemas of the indexed fields, as they are once the staged conversions are complete
+    pub fn target_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
+        let mut indexed_fields = self.config.indexed_fields.clone();
+        indexed_fields.extend(self.config.staged_schemas.clone());
+        indexed_fields
+    }
+
+    /// Fields, which indexes are being rebuilt through staging
+    pub fn staged_fields(&self) -> impl Iterator<Item = &PayloadKeyType> {
+        self.staging_indexes.keys()
+    }
+
+    /// Number of points, already indexed into the staging indexes of the field,
+    /// if its rebuild is in progress
+    pub fn staged_points_count(&self, field: PayloadKeyTypeRef) -> Option<usize> {
+        self.staging_indexes
+            .contains_key(field)
+            .then(|| self.staged_points.get(field).copied().unwrap_or(0) as usize)
+    }
+
+    /// Start creating the index of the field through staging, so that it is built in batches
+    /// by `build_staged_field_index`. Conditions on the field are checked against the payload,
+    /// until the index is built.
+    ///
+    /// An index of the same type, which differs by the `on_disk` flag, is converted instead.
+    /// Returns false if the field is already indexed.
+    pub fn stage_field_index_creation(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        payload_schema: PayloadFieldSchema,
+        op_num: SeqNumberType,
+    ) -> OperationResult<bool> {
+        if let Some(current_schema) = self.config.indexed_fields.get(field) {
+            if current_schema.type_name() != payload_schema.type_name() {
+                return Ok(false);
+            }
+            return self.stage_field_index_conversion(field, payload_schema);
+        }
+        if !payload_schema.is_text() {
+            self.payload
+                .borrow_mut()
+                .cache_fields(&[field.to_owned()])?;
+        }
+        self.config
+            .indexed_fields
+            .insert(field.to_owned(), payload_schema);
+        self.config
+            .building_indexes
+            .insert(field.to_owned(), op_num);
+        // Saves the config
+        self.stage_field_index_rebuild(field)
+    }
+
+    /// Stop building the index of the field and remove its staging storage.
+    /// An index, which is being created, is dropped, while a rebuilt one keeps the current index.
+    ///
+    /// Returns false if the index of the field is not being built.
+    pub fn cancel_field_index_build(&mut self, field: PayloadKeyTypeRef) -> OperationResult<bool> {
+        if self.config.building_indexes.contains_key(field) {
+            self.drop_index(field)?;
+            return Ok(true);
+        }
+        if !self.staging_indexes.contains_key(field) {
+            return Ok(false);
+        }
+        self.clear_staging_indexes(field)?;
+        self.save_config()?;
+        Ok(true)
+    }
+
+    /// Fields, which indexes are being created, with the number of the operation,
+    /// which created them
+    pub fn building_fields(&self) -> &HashMap<PayloadKeyType, SeqNumberType> {
+        &self.config.building_indexes
+    }
+
+    /// Indexed fields, which indexes failed to load and have to be rebuilt
+    pub fn fields_to_rebuild(&self) -> &HashSet<PayloadKeyType> {
+        &self.needs_rebuild
+    }
+
+    /// If true - the field is indexed as a tenant of a multitenant collection
+    pub fn is_tenant_field(&self, field: PayloadKeyTypeRef) -> bool {
+        self.config
+            .indexed_fields
+            .get(field)
+            .map_or(false, PayloadFieldSchema::is_tenant)
+    }
+
+    /// Number of values, removed or overwritten in the index of the field since it was built
+    pub fn obsolete_values_count(&self, field: PayloadKeyTypeRef) -> usize {
+        self.obsolete_values.get(field).copied().unwrap_or(0)
+    }
+
+    /// Account values of the point, which are about to be removed from the field indexes
+    fn register_obsolete_values(
+        obsolete_values: &mut HashMap<PayloadKeyType, usize>,
+        field: PayloadKeyTypeRef,
+        indexes: &[FieldIndex],
+        point_id: PointOffsetType,
+    ) {
+        let values_count = indexes
+            .first()
+            .map_or(0, |index| index.values_count(point_id));
+        if values_count > 0 {
+            *obsolete_values.entry(field.to_owned()).or_default() += values_count;
+        }
+    }
+
     /// Number of available points
     ///
     /// - excludes soft deleted points
@@ -223,6 +776,72 @@ impl StructPayloadIndex {
         )
     }
 
+    /// Check the filter against a single point and record the outcome of every condition.
+    /// Conditions are checked the same way, as filtered search checks them.
+    pub fn trace_filter(&self, point_id: PointOffsetType, filter: &Filter) -> FilterTrace {
+        let id_tracker = self.id_tracker.borrow();
+        let payload_provider = PayloadProvider::new(self.payload.clone());
+        let tracer = |condition: &Condition| {
+            condition_trace(
+                condition,
+                &self.field_indexes,
+                &payload_provider,
+                id_tracker.deref(),
+                point_id,
+            )
+        };
+        trace_filter(&tracer, filter)
+    }
+
+    /// Keys of the conditions of the filter, which can't be served by the field indexes,
+    /// so the query planner falls back to reading the payload of the points.
+    ///
+    /// Conditions on nested paths are only covered by the index of their full path,
+    /// e.g. an index of the parent object doesn't cover them.
+    pub fn unindexed_filter_keys(&self, filter: &Filter) -> Vec<PayloadKeyType> {
+        let mut keys = BTreeSet::new();
+        self.collect_unindexed_filter_keys(filter, None, &mut keys);
+        keys.into_iter().collect()
+    }
+
+    fn collect_unindexed_filter_keys(
+        &self,
+        filter: &Filter,
+        nested_path: Option<&JsonPathPayload>,
+        keys: &mut BTreeSet<PayloadKeyType>,
+    ) {
+        for condition in filter.iter_conditions() {
+            match condition {
+                Condition::Filter(filter) => {
+                    self.collect_unindexed_filter_keys(filter, nested_path, keys)
+                }
+                Condition::Nested(nested) => {
+                    let full_path =
+                        JsonPathPayload::extend_or_new(nested_path, &nested.array_key());
+                    self.collect_unindexed_filter_keys(nested.filter(), Some(&full_path), keys)
+                }
+                Condition::Field(field_condition) => {
+                    if self
+                        .estimate_field_condition(field_condition, nested_path)
+                        .is_none()
+                    {
+                        let full_path =
+                            JsonPathPayload::extend_or_new(nested_path, &field_condition.key);
+                        keys.insert(full_path.path);
+                    }
+                }
+                Condition::IsEmpty(IsEmptyCondition { is_empty: field })
+                | Condition::IsNull(IsNullCondition { is_null: field }) => {
+                    let full_path = JsonPathPayload::extend_or_new(nested_path, &field.key);
+                    if !self.field_indexes.contains_key(&full_path.path) {
+                        keys.insert(full_path.path);
+                    }
+                }
+                Condition::HasId(_) | Condition::ModifiedSince(_) => {}
+            }
+        }
+    }
+
     fn condition_cardinality(
         &self,
         condition: &Condition,
@@ -308,6 +927,17 @@ impl StructPayloadIndex {
                     max: num_ids,
                 }
             }
+            Condition::ModifiedSince(modified_since) => {
+                let timestamp = modified_since.timestamp();
+                // Only blocks of points with recent updates are counted
+                let max_points = self.id_tracker.borrow().modified_since_max_count(timestamp);
+                CardinalityEstimation {
+                    primary_clauses: vec![PrimaryCondition::ModifiedSince(timestamp)],
+                    min: 0,
+                    exp: max_points / 2,
+                    max: max_points,
+                }
+            }
             Condition::Field(field_condition) => self
                 .estimate_field_condition(field_condition, nested_path)
                 .unwrap_or_else(|| CardinalityEstimation::unknown(self.available_point_count())),
@@ -320,12 +950,54 @@ impl StructPayloadIndex {
             .flat_map(|(name, field)| -> Vec<PayloadIndexTelemetry> {
                 field
                     .iter()
-                    .map(|field| field.get_telemetry_data().set_name(name.to_string()))
+                    .map(|field| {
+                        field
+                            .get_telemetry_data()
+                            .set_name(name.to_string())
+                            .set_obsolete_values_count(self.obsolete_values_count(name))
+                    })
                     .collect()
             })
+            .chain(
+                self.needs_rebuild
+                    .iter()
+                    .map(|name| PayloadIndexTelemetry::needs_rebuild(name.to_string())),
+            )
             .collect()
     }
 
+    /// Keep values of the `fields`, as well as of the indexed fields, in memory,
+    /// if the payload is stored on disk.
+    /// Full-text indexed fields are not cached, as their values might be large
+    pub fn cache_payload_fields(&self, fields: &[PayloadKeyType]) -> OperationResult<()> {
+        let indexed_fields = self
+            .config
+            .indexed_fields
+            .iter()
+            .filter(|(_, payload_schema)| !payload_schema.is_text())
+            .map(|(field, _)| field.clone());
+        let fields: Vec<_> = fields.iter().cloned().chain(indexed_fields).collect();
+        self.payload.borrow_mut().cache_fields(&fields)
+    }
+
+    /// Column family of the payload, if it is stored on disk
+    pub fn on_disk_payload_column(&self) -> Option<DatabaseColumnWrapper> {
+        self.payload.borrow().on_disk_column()
+    }
+
+    pub fn payload_cache_ram_bytes(&self) -> usize {
+        self.payload.borrow().cache_ram_bytes()
+    }
+
+    /// Payload of the point, processed by the `selector`, read from the cache if possible
+    pub fn selected_payload(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        self.payload.borrow().selected_payload(point_id, selector)
+    }
+
     pub fn restore_database_snapshot(
         snapshot_path: &Path,
         segment_path: &Path,
@@ -344,14 +1016,25 @@ impl PayloadIndex for StructPayloadIndex {
         field: PayloadKeyTypeRef,
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<()> {
-        if self
-            .config
-            .indexed_fields
-            .insert(field.to_owned(), payload_schema.clone())
-            .is_none()
-        {
-            self.build_and_save(field, payload_schema)?;
-            self.save_config()?;
+        match self.config.indexed_fields.get(field) {
+            None => {
+                self.config
+                    .indexed_fields
+                    .insert(field.to_owned(), payload_schema.clone());
+                if !payload_schema.is_text() {
+                    self.payload
+                        .borrow_mut()
+                        .cache_fields(&[field.to_owned()])?;
+                }
+                self.build_and_save(field, payload_schema)?;
+                self.save_config()?;
+            }
+            Some(current_schema) if current_schema.type_name() == payload_schema.type_name() => {
+                if self.stage_field_index_conversion(field, payload_schema)? {
+                    self.build_staged_field_index(field, usize::MAX)?;
+                }
+            }
+            Some(_) => {}
         }
 
         Ok(())
@@ -359,12 +1042,18 @@ impl PayloadIndex for StructPayloadIndex {
 
     fn drop_index(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
         self.config.indexed_fields.remove(field);
+        self.config.index_generations.remove(field);
+        self.config.staged_rebuilds.remove(field);
+        self.config.staged_schemas.remove(field);
+        self.config.building_indexes.remove(field);
+        self.obsolete_values.remove(field);
+        self.needs_rebuild.remove(field);
+        self.staged_points.remove(field);
         let removed_indexes = self.field_indexes.remove(field);
+        let removed_staging = self.staging_indexes.remove(field);
 
-        if let Some(indexes) = removed_indexes {
-            for index in indexes {
-                index.clear()?;
-            }
+        for index in removed_indexes.into_iter().chain(removed_staging).flatten() {
+            index.clear()?;
         }
 
         self.save_config()?;
@@ -391,6 +1080,14 @@ impl PayloadIndex for StructPayloadIndex {
     fn query_points(&self, query: &Filter) -> Vec<PointOffsetType> {
         // Assume query is already estimated to be small enough so we can iterate over all matched ids
 
+        if let Some(candidates) = self.query_min_should(query) {
+            let struct_filtered_context = self.struct_filtered_context(query);
+            return candidates
+                .into_iter()
+                .filter(|&i| struct_filtered_context.check(i))
+                .collect();
+        }
+
         let query_cardinality = self.estimate_cardinality(query);
 
         if query_cardinality.primary_clauses.is_empty() {
@@ -426,6 +1123,9 @@ impl PayloadIndex for StructPayloadIndex {
                             )
                         }
                         PrimaryCondition::Ids(ids) => Box::new(ids.iter().copied()),
+                        PrimaryCondition::ModifiedSince(timestamp) => {
+                            points_iterator_ref.iter_modified_since(*timestamp)
+                        }
                         PrimaryCondition::IsEmpty(_) => points_iterator_ref.iter_ids(), /* there are no fast index for IsEmpty */
                         PrimaryCondition::IsNull(_) => points_iterator_ref.iter_ids(),  /* no fast index for IsNull too */
                     }
@@ -476,10 +1176,25 @@ impl PayloadIndex for StructPayloadIndex {
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
         for (field, field_index) in &mut self.field_indexes {
             let field_value = &payload.get_value(field);
+            if !matches!(field_value, MultiValue::Single(None)) {
+                // Previous values of the point are overwritten
+                Self::register_obsolete_values(
+                    &mut self.obsolete_values,
+                    field,
+                    field_index,
+                    point_id,
+                );
+            }
             for index in field_index {
                 index.add_point(point_id, field_value)?;
             }
         }
+        for (field, staging_indexes) in &mut self.staging_indexes {
+            let field_value = &payload.get_value(field);
+            for index in staging_indexes {
+                index.add_point(point_id, field_value)?;
+            }
+        }
         self.payload.borrow_mut().assign(point_id, payload)
     }
 
@@ -493,29 +1208,59 @@ impl PayloadIndex for StructPayloadIndex {
         key: PayloadKeyTypeRef,
     ) -> OperationResult<Vec<Value>> {
         if let Some(indexes) = self.field_indexes.get_mut(key) {
+            Self::register_obsolete_values(&mut self.obsolete_values, key, indexes, point_id);
             for index in indexes {
                 index.remove_point(point_id)?;
             }
         }
+        if let Some(staging_indexes) = self.staging_indexes.get_mut(key) {
+            for index in staging_indexes {
+                index.remove_point(point_id)?;
+            }
+        }
         self.payload.borrow_mut().delete(point_id, key)
     }
 
     fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
-        for (_, field_indexes) in self.field_indexes.iter_mut() {
+        for (field, field_indexes) in self.field_indexes.iter_mut() {
+            Self::register_obsolete_values(
+                &mut self.obsolete_values,
+                field,
+                field_indexes,
+                point_id,
+            );
             for index in field_indexes {
                 index.remove_point(point_id)?;
             }
         }
+        for staging_indexes in self.staging_indexes.values_mut() {
+            for index in staging_indexes {
+                index.remove_point(point_id)?;
+            }
+        }
         self.payload.borrow_mut().drop(point_id)
     }
 
     fn wipe(&mut self) -> OperationResult<()> {
         self.payload.borrow_mut().wipe()?;
+        self.obsolete_values.clear();
         for (_, field_indexes) in self.field_indexes.iter_mut() {
             for index in field_indexes.drain(..) {
                 index.clear()?;
             }
         }
+        // Staging indexes are of no use anymore, as the current indexes are built from scratch
+        for (_, staging_indexes) in self.staging_indexes.drain() {
+            for index in staging_indexes {
+                index.clear()?;
+            }
+        }
+        self.config.staged_rebuilds.clear();
+        for (field, payload_schema) in self.config.staged_schemas.drain() {
+            self.config.indexed_fields.insert(field, payload_schema);
+        }
+        self.config.building_indexes.clear();
+        self.save_config()?;
         self.load_all_fields()
     }
 
@@ -526,11 +1271,24 @@ impl PayloadIndex for StructPayloadIndex {
                 flushers.push(index.flusher());
             }
         }
+        for staging_indexes in self.staging_indexes.values() {
+            for index in staging_indexes {
+                flushers.push(index.flusher());
+            }
+        }
         flushers.push(self.payload.borrow().flusher());
+
+        let progress_path = self.staging_progress_path();
+        let staged_points = self.staged_points.clone();
         Box::new(move || {
             for flusher in flushers {
                 flusher()?
             }
+            // Progress is saved after the staging indexes are flushed,
+            // so that an interrupted rebuild never skips points on resume
+            if !staged_points.is_empty() || progress_path.exists() {
+                atomic_save_json(&progress_path, &staged_points)?;
+            }
             Ok(())
         })
     }
@@ -558,6 +1316,11 @@ impl PayloadIndex for StructPayloadIndex {
     }
 
     fn files(&self) -> Vec<PathBuf> {
-        vec![self.config_path()]
+        let mut files = vec![self.config_path()];
+        let progress_path = self.staging_progress_path();
+        if progress_path.exists() {
+            files.push(progress_path);
+        }
+        files
     }
 }
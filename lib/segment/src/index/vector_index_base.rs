@@ -1,12 +1,14 @@
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 
+use super::filter_bitmask::FilterBitmask;
 use super::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use super::hnsw_index::hnsw::HNSWIndex;
 use super::plain_payload_index::PlainIndex;
+use crate::common::cpu::CpuPermit;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
-use crate::telemetry::VectorIndexSearchesTelemetry;
+use crate::telemetry::{HnswGraphHealth, VectorIndexSearchesTelemetry};
 use crate::types::{Filter, SearchParams};
 use crate::vector_storage::ScoredPointOffset;
 
@@ -21,8 +23,23 @@ pub trait VectorIndex {
         params: Option<&SearchParams>,
     ) -> Vec<Vec<ScoredPointOffset>>;
 
+    /// Search among the points of the `bitmask`, which also match the `filter`.
+    ///
+    /// The bitmask is an already evaluated part of the filter of the search,
+    /// so only the remaining conditions are passed in `filter`.
+    fn search_prefiltered(
+        &self,
+        vectors: &[&[VectorElementType]],
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> Vec<Vec<ScoredPointOffset>>;
+
     /// Force internal index rebuild.
-    fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()>;
+    ///
+    /// Index building may use as many threads as `permit` allows.
+    fn build_index(&mut self, permit: &CpuPermit, stopped: &AtomicBool) -> OperationResult<()>;
 
     fn get_telemetry_data(&self) -> VectorIndexSearchesTelemetry;
 
@@ -49,6 +66,24 @@ impl VectorIndexEnum {
             Self::HnswMmap(_) => true,
         }
     }
+
+    /// Health of the HNSW graph, `None` for plain or not yet built indexes
+    pub fn graph_health(&self) -> Option<HnswGraphHealth> {
+        match self {
+            Self::Plain(_) => None,
+            Self::HnswRam(index) => index.graph_health(),
+            Self::HnswMmap(index) => index.graph_health(),
+        }
+    }
+
+    /// Estimated fraction of the HNSW graph links, which point to deleted points
+    pub fn sample_deleted_links_ratio(&self) -> Option<f64> {
+        match self {
+            Self::Plain(_) => None,
+            Self::HnswRam(index) => index.sample_deleted_links_ratio(),
+            Self::HnswMmap(index) => index.sample_deleted_links_ratio(),
+        }
+    }
 }
 
 impl VectorIndex for VectorIndexEnum {
@@ -66,11 +101,32 @@ impl VectorIndex for VectorIndexEnum {
         }
     }
 
-    fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
+    fn search_prefiltered(
+        &self,
+        vectors: &[&[VectorElementType]],
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        match self {
+            VectorIndexEnum::Plain(index) => {
+                index.search_prefiltered(vectors, bitmask, filter, top, params)
+            }
+            VectorIndexEnum::HnswRam(index) => {
+                index.search_prefiltered(vectors, bitmask, filter, top, params)
+            }
+            VectorIndexEnum::HnswMmap(index) => {
+                index.search_prefiltered(vectors, bitmask, filter, top, params)
+            }
+        }
+    }
+
+    fn build_index(&mut self, permit: &CpuPermit, stopped: &AtomicBool) -> OperationResult<()> {
         match self {
-            VectorIndexEnum::Plain(index) => index.build_index(stopped),
-            VectorIndexEnum::HnswRam(index) => index.build_index(stopped),
-            VectorIndexEnum::HnswMmap(index) => index.build_index(stopped),
+            VectorIndexEnum::Plain(index) => index.build_index(permit, stopped),
+            VectorIndexEnum::HnswRam(index) => index.build_index(permit, stopped),
+            VectorIndexEnum::HnswMmap(index) => index.build_index(permit, stopped),
         }
     }
 
@@ -4,7 +4,7 @@ use serde_json::Value;
 
 use crate::types::{
     AnyVariants, FieldCondition, GeoBoundingBox, GeoRadius, Match, MatchAny, MatchExcept,
-    MatchText, MatchValue, Range, ValueVariants, ValuesCount,
+    MatchKeywordRange, MatchText, MatchValue, Range, ValueVariants, ValuesCount,
 };
 
 pub trait ValueChecker {
@@ -100,6 +100,10 @@ impl ValueChecker for Match {
                 (Value::Number(_), _) => true,
                 (Value::String(_), _) => true,
             },
+            Match::KeywordRange(MatchKeywordRange { keyword_range }) => match payload {
+                Value::String(stored) => keyword_range.check_range(stored),
+                _ => false,
+            },
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::mem::size_of;
 use std::sync::Arc;
 
 use parking_lot::RwLock;
@@ -5,21 +7,113 @@ use rocksdb::DB;
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
+use crate::common::utils::select_paths_from_json_map;
 use crate::common::Flusher;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::payload_storage::PayloadStorage;
-use crate::types::{Payload, PayloadKeyTypeRef, PointOffsetType};
+use crate::types::{Payload, PayloadKeyType, PayloadKeyTypeRef, PointOffsetType};
 
 /// On-disk implementation of `PayloadStorage`.
-/// Persists all changes to disk using `store`, does not keep payload in memory
+/// Persists all changes to disk using `store`, does not keep payload in memory,
+/// except for the values of the cached fields
 pub struct OnDiskPayloadStorage {
     db_wrapper: DatabaseColumnWrapper,
+    /// Fields, which values are kept in memory
+    cached_fields: Vec<PayloadKeyType>,
+    /// Values of the cached fields. Points without any of them are not present
+    cache: HashMap<PointOffsetType, Payload>,
+    /// Approximate size of the cached values in RAM
+    cache_ram_bytes: usize,
 }
 
 impl OnDiskPayloadStorage {
     pub fn open(database: Arc<RwLock<DB>>) -> OperationResult<Self> {
         let db_wrapper = DatabaseColumnWrapper::new(database, DB_PAYLOAD_CF);
-        Ok(OnDiskPayloadStorage { db_wrapper })
+        Ok(OnDiskPayloadStorage {
+            db_wrapper,
+            cached_fields: vec![],
+            cache: HashMap::new(),
+            cache_ram_bytes: 0,
+        })
+    }
+
+    /// Start keeping values of the `fields` in memory. Reads the whole storage, if any of the
+    /// fields is not cached yet
+    pub fn cache_fields(&mut self, fields: &[PayloadKeyType]) -> OperationResult<()> {
+        let mut new_fields = false;
+        for field in fields {
+            if !self.cached_fields.contains(field) {
+                self.cached_fields.push(field.clone());
+                new_fields = true;
+            }
+        }
+        if !new_fields {
+            return Ok(());
+        }
+
+        self.cache.clear();
+        self.cache_ram_bytes = 0;
+        let mut cached_payloads = vec![];
+        self.iter(|point_id, payload| {
+            if let Some(cached) = self.cached_part(payload) {
+                cached_payloads.push((point_id, cached));
+            }
+            Ok(true)
+        })?;
+        for (point_id, cached) in cached_payloads {
+            self.cache_payload(point_id, Some(cached));
+        }
+        Ok(())
+    }
+
+    /// Whether all values of the `key`, including the nested ones, are kept in memory
+    pub fn is_cached(&self, key: PayloadKeyTypeRef) -> bool {
+        self.cached_fields.iter().any(|field| {
+            key.strip_prefix(field.as_str()).map_or(false, |rest| {
+                rest.is_empty() || rest.starts_with('.') || rest.starts_with('[')
+            })
+        })
+    }
+
+    /// Values of the cached fields of the point. `None` if the point has none of them
+    pub fn cached_payload(&self, point_id: PointOffsetType) -> Option<&Payload> {
+        self.cache.get(&point_id)
+    }
+
+    pub fn cache_ram_bytes(&self) -> usize {
+        self.cache_ram_bytes
+    }
+
+    fn cached_part(&self, payload: &Payload) -> Option<Payload> {
+        if self.cached_fields.is_empty() {
+            return None;
+        }
+        let cached = select_paths_from_json_map(&self.cached_fields, &payload.0);
+        (!cached.is_empty()).then(|| cached.into())
+    }
+
+    fn cache_payload(&mut self, point_id: PointOffsetType, cached: Option<Payload>) {
+        let entry_size = |payload: &Payload| {
+            size_of::<PointOffsetType>()
+                + size_of::<Payload>()
+                + serde_cbor::to_vec(payload).map_or(0, |raw| raw.len())
+        };
+        if let Some(old) = self.cache.remove(&point_id) {
+            self.cache_ram_bytes -= entry_size(&old);
+        }
+        if let Some(cached) = cached {
+            self.cache_ram_bytes += entry_size(&cached);
+            self.cache.insert(point_id, cached);
+        }
+    }
+
+    /// Keep the cache in sync with the payload, written into the storage
+    fn update_cache(&mut self, point_id: PointOffsetType, payload: &Payload) {
+        if self.cached_fields.is_empty() {
+            return;
+        }
+        let cached = self.cached_part(payload);
+        self.cache_payload(point_id, cached);
     }
 
     pub fn remove_from_storage(&self, point_id: PointOffsetType) -> OperationResult<()> {
@@ -46,6 +140,11 @@ impl OnDiskPayloadStorage {
             .map_err(OperationError::from)
     }
 
+    /// Column family of the stored payload
+    pub fn column(&self) -> &DatabaseColumnWrapper {
+        &self.db_wrapper
+    }
+
     pub fn iter<F>(&self, mut callback: F) -> OperationResult<()>
     where
         F: FnMut(PointOffsetType, &Payload) -> OperationResult<bool>,
@@ -65,7 +164,9 @@ impl OnDiskPayloadStorage {
 
 impl PayloadStorage for OnDiskPayloadStorage {
     fn assign_all(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
-        self.update_storage(point_id, payload)
+        self.update_storage(point_id, payload)?;
+        self.update_cache(point_id, payload);
+        Ok(())
     }
 
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
@@ -73,9 +174,13 @@ impl PayloadStorage for OnDiskPayloadStorage {
         match stored_payload {
             Some(mut point_payload) => {
                 point_payload.merge(payload);
-                self.update_storage(point_id, &point_payload)?
+                self.update_storage(point_id, &point_payload)?;
+                self.update_cache(point_id, &point_payload);
+            }
+            None => {
+                self.update_storage(point_id, payload)?;
+                self.update_cache(point_id, payload);
             }
-            None => self.update_storage(point_id, payload)?,
         }
         Ok(())
     }
@@ -100,6 +205,7 @@ impl PayloadStorage for OnDiskPayloadStorage {
                 let res = payload.remove(key);
                 if !res.is_empty() {
                     self.update_storage(point_id, &payload)?;
+                    self.update_cache(point_id, &payload);
                 }
                 Ok(res)
             }
@@ -110,10 +216,13 @@ impl PayloadStorage for OnDiskPayloadStorage {
     fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
         let payload = self.read_payload(point_id)?;
         self.remove_from_storage(point_id)?;
+        self.cache_payload(point_id, None);
         Ok(payload)
     }
 
     fn wipe(&mut self) -> OperationResult<()> {
+        self.cache.clear();
+        self.cache_ram_bytes = 0;
         self.db_wrapper.recreate_column_family()
     }
 
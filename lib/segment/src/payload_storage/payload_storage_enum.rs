@@ -1,12 +1,13 @@
 use serde_json::Value;
 
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
 use crate::entry::entry_point::OperationResult;
 use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::payload_storage::PayloadStorage;
-use crate::types::{Payload, PayloadKeyTypeRef, PointOffsetType};
+use crate::types::{Payload, PayloadKeyType, PayloadKeyTypeRef, PayloadSelector, PointOffsetType};
 
 pub enum PayloadStorageEnum {
     InMemoryPayloadStorage(InMemoryPayloadStorage),
@@ -43,6 +44,52 @@ impl PayloadStorageEnum {
             PayloadStorageEnum::OnDiskPayloadStorage(s) => s.iter(callback),
         }
     }
+
+    /// Keep values of the `fields` in memory. Only affects the on-disk storage,
+    /// other storages keep the whole payload in memory anyway
+    pub fn cache_fields(&mut self, fields: &[PayloadKeyType]) -> OperationResult<()> {
+        match self {
+            PayloadStorageEnum::InMemoryPayloadStorage(_) => Ok(()),
+            PayloadStorageEnum::SimplePayloadStorage(_) => Ok(()),
+            PayloadStorageEnum::OnDiskPayloadStorage(s) => s.cache_fields(fields),
+        }
+    }
+
+    /// Column family of the payload, if it is stored on disk and not kept in memory
+    pub fn on_disk_column(&self) -> Option<DatabaseColumnWrapper> {
+        match self {
+            PayloadStorageEnum::InMemoryPayloadStorage(_) => None,
+            PayloadStorageEnum::SimplePayloadStorage(_) => None,
+            PayloadStorageEnum::OnDiskPayloadStorage(s) => Some(s.column().clone()),
+        }
+    }
+
+    /// Approximate RAM, occupied by the cached fields of the on-disk storage
+    pub fn cache_ram_bytes(&self) -> usize {
+        match self {
+            PayloadStorageEnum::InMemoryPayloadStorage(_) => 0,
+            PayloadStorageEnum::SimplePayloadStorage(_) => 0,
+            PayloadStorageEnum::OnDiskPayloadStorage(s) => s.cache_ram_bytes(),
+        }
+    }
+
+    /// Payload of the point, processed by the `selector`.
+    /// Disk is not read if only cached fields are included
+    pub fn selected_payload(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        if let (PayloadStorageEnum::OnDiskPayloadStorage(s), PayloadSelector::Include(include)) =
+            (self, selector)
+        {
+            if include.include.iter().all(|key| s.is_cached(key)) {
+                let cached = s.cached_payload(point_id).cloned().unwrap_or_default();
+                return Ok(selector.process(cached));
+            }
+        }
+        Ok(selector.process(self.payload(point_id)?))
+    }
 }
 
 impl PayloadStorage for PayloadStorageEnum {
@@ -188,4 +235,54 @@ mod tests {
             eprintln!("res = {res:#?}");
         }
     }
+
+    #[test]
+    fn test_on_disk_storage_field_cache() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let user_only = PayloadSelector::new_include(vec!["user".to_string()]);
+
+        {
+            let mut storage: PayloadStorageEnum =
+                OnDiskPayloadStorage::open(db.clone()).unwrap().into();
+            let payload: Payload =
+                serde_json::from_str(r#"{"user": "alice", "text": "long text"}"#).unwrap();
+            storage.assign_all(1, &payload).unwrap();
+            storage.assign_all(2, &payload).unwrap();
+            assert_eq!(storage.cache_ram_bytes(), 0);
+        }
+
+        let mut storage: PayloadStorageEnum = OnDiskPayloadStorage::open(db).unwrap().into();
+        storage.cache_fields(&["user".to_string()]).unwrap();
+        let single_point_cache = storage.cache_ram_bytes() / 2;
+        assert!(single_point_cache > 0);
+
+        let PayloadStorageEnum::OnDiskPayloadStorage(on_disk) = &storage else {
+            unreachable!()
+        };
+        assert!(on_disk.is_cached("user"));
+        assert!(on_disk.is_cached("user[].name"));
+        assert!(!on_disk.is_cached("username"));
+        assert!(!on_disk.is_cached("text"));
+        assert_eq!(
+            on_disk.cached_payload(1),
+            Some(&serde_json::from_str(r#"{"user": "alice"}"#).unwrap())
+        );
+
+        let update: Payload = serde_json::from_str(r#"{"user": "carol"}"#).unwrap();
+        storage.assign(1, &update).unwrap();
+        assert_eq!(storage.selected_payload(1, &user_only).unwrap(), update);
+
+        storage.delete(2, "user").unwrap();
+        assert_eq!(
+            storage.selected_payload(2, &user_only).unwrap(),
+            Payload::default()
+        );
+        assert_eq!(storage.cache_ram_bytes(), single_point_cache);
+
+        storage.drop(1).unwrap();
+        assert_eq!(storage.cache_ram_bytes(), 0);
+        assert_eq!(storage.payload(2).unwrap().0.len(), 1);
+    }
 }
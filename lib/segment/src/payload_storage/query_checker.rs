@@ -5,15 +5,17 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 
+use crate::common::hardware_counter::{record_filter_condition_check, record_payload_values_read};
 use crate::common::utils::IndexesMap;
+use crate::data_types::filter_trace::{ConditionCheckSource, ConditionTrace, FilterTrace};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::FieldIndex;
 use crate::payload_storage::condition_checker::ValueChecker;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::ConditionChecker;
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
-    PayloadContainer, PayloadKeyType, PointOffsetType,
+    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, MinShould,
+    OwnedPayloadRef, Payload, PayloadContainer, PayloadKeyType, PointOffsetType,
 };
 
 fn check_condition<F>(checker: &F, condition: &Condition) -> bool
@@ -31,6 +33,7 @@ where
     F: Fn(&Condition) -> bool,
 {
     check_should(checker, &filter.should)
+        && check_min_should(checker, &filter.min_should)
         && check_must(checker, &filter.must)
         && check_must_not(checker, &filter.must_not)
 }
@@ -46,6 +49,33 @@ where
     }
 }
 
+fn check_min_should<F>(checker: &F, min_should: &Option<MinShould>) -> bool
+where
+    F: Fn(&Condition) -> bool,
+{
+    let Some(MinShould {
+        conditions,
+        min_count,
+    }) = min_should
+    else {
+        return true;
+    };
+    let mut matched = 0;
+    for (checked, condition) in conditions.iter().enumerate() {
+        if check_condition(checker, condition) {
+            matched += 1;
+            if matched >= *min_count {
+                return true;
+            }
+        }
+        // Not enough conditions left to reach the minimum
+        if matched + (conditions.len() - checked - 1) < *min_count {
+            return false;
+        }
+    }
+    matched >= *min_count
+}
+
 fn check_must<F>(checker: &F, must: &Option<Vec<Condition>>) -> bool
 where
     F: Fn(&Condition) -> bool,
@@ -68,6 +98,77 @@ where
     }
 }
 
+fn trace_condition<F>(tracer: &F, condition: &Condition) -> ConditionTrace
+where
+    F: Fn(&Condition) -> ConditionTrace,
+{
+    match condition {
+        Condition::Filter(filter) => {
+            let filter_trace = trace_filter(tracer, filter);
+            ConditionTrace {
+                condition: None,
+                matched: filter_trace.matched,
+                checked_by: None,
+                filters: vec![filter_trace],
+            }
+        }
+        _ => tracer(condition),
+    }
+}
+
+/// Same as [`check_filter`], but checks every condition, without short-circuiting,
+/// and records the outcome of each of them.
+pub fn trace_filter<F>(tracer: &F, filter: &Filter) -> FilterTrace
+where
+    F: Fn(&Condition) -> ConditionTrace,
+{
+    let trace_conditions = |conditions: &Option<Vec<Condition>>| {
+        conditions.as_ref().map(|conditions| {
+            conditions
+                .iter()
+                .map(|condition| trace_condition(tracer, condition))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let should = trace_conditions(&filter.should);
+    let min_should = filter.min_should.as_ref().map(|min_should| {
+        min_should
+            .conditions
+            .iter()
+            .map(|condition| trace_condition(tracer, condition))
+            .collect::<Vec<_>>()
+    });
+    let must = trace_conditions(&filter.must);
+    let must_not = trace_conditions(&filter.must_not);
+
+    let min_should_matched = match (&filter.min_should, &min_should) {
+        (Some(MinShould { min_count, .. }), Some(traces)) => {
+            traces.iter().filter(|trace| trace.matched).count() >= *min_count
+        }
+        _ => true,
+    };
+
+    let matched = should
+        .as_ref()
+        .map_or(true, |traces| traces.iter().any(|trace| trace.matched))
+        && min_should_matched
+        && must
+            .as_ref()
+            .map_or(true, |traces| traces.iter().all(|trace| trace.matched))
+        && must_not
+            .as_ref()
+            .map_or(true, |traces| traces.iter().all(|trace| !trace.matched));
+
+    FilterTrace {
+        matched,
+        should,
+        min_should,
+        must,
+        must_not,
+    }
+}
+
 pub fn select_nested_indexes<'a, R>(
     nested_path: &str,
     field_indexes: &'a HashMap<PayloadKeyType, R>,
@@ -96,7 +197,32 @@ pub fn check_payload<'a, R>(
 where
     R: AsRef<Vec<FieldIndex>>,
 {
-    let checker = |condition: &Condition| match condition {
+    let checker = |condition: &Condition| {
+        record_filter_condition_check();
+        check_payload_condition(
+            condition,
+            get_payload.as_ref(),
+            id_tracker,
+            point_id,
+            field_indexes,
+        )
+    };
+
+    check_filter(&checker, query)
+}
+
+/// Check a single condition, other than a nested filter, against the payload of the point
+fn check_payload_condition<'a, R>(
+    condition: &Condition,
+    get_payload: &(dyn Fn() -> OwnedPayloadRef<'a> + 'a),
+    id_tracker: Option<&IdTrackerSS>,
+    point_id: PointOffsetType,
+    field_indexes: &HashMap<PayloadKeyType, R>,
+) -> bool
+where
+    R: AsRef<Vec<FieldIndex>>,
+{
+    match condition {
         Condition::Field(field_condition) => {
             check_field_condition(field_condition, get_payload().deref(), field_indexes)
         }
@@ -105,11 +231,16 @@ where
         Condition::HasId(has_id) => id_tracker
             .and_then(|id_tracker| id_tracker.external_id(point_id))
             .map_or(false, |id| has_id.has_id.contains(&id)),
+        Condition::ModifiedSince(modified_since) => id_tracker
+            .and_then(|id_tracker| id_tracker.internal_timestamp(point_id))
+            .map_or(false, |timestamp| timestamp >= modified_since.timestamp()),
         Condition::Nested(nested) => {
             let nested_path = nested.array_key();
             let nested_indexes = select_nested_indexes(&nested_path, field_indexes);
-            get_payload()
-                .get_value(&nested_path)
+            let payload = get_payload();
+            let nested_values = payload.get_value(&nested_path);
+            record_payload_values_read(nested_values.len());
+            nested_values
                 .values()
                 .iter()
                 .filter_map(|value| value.as_object())
@@ -124,9 +255,96 @@ where
                 })
         }
         Condition::Filter(_) => unreachable!(),
+    }
+}
+
+/// Same as [`check_payload`], but records the outcome of every condition of the query
+pub fn trace_payload<'a, R>(
+    get_payload: &(dyn Fn() -> OwnedPayloadRef<'a> + 'a),
+    id_tracker: Option<&IdTrackerSS>,
+    query: &Filter,
+    point_id: PointOffsetType,
+    field_indexes: &HashMap<PayloadKeyType, R>,
+) -> FilterTrace
+where
+    R: AsRef<Vec<FieldIndex>>,
+{
+    let tracer = |condition: &Condition| {
+        trace_payload_condition(condition, get_payload, id_tracker, point_id, field_indexes)
     };
 
-    check_filter(&checker, query)
+    trace_filter(&tracer, query)
+}
+
+/// Check a single condition, other than a nested filter, against the payload of the point
+/// and record its outcome.
+/// `nested` conditions get the breakdown of every object of the array.
+pub fn trace_payload_condition<'a, R>(
+    condition: &Condition,
+    get_payload: &(dyn Fn() -> OwnedPayloadRef<'a> + 'a),
+    id_tracker: Option<&IdTrackerSS>,
+    point_id: PointOffsetType,
+    field_indexes: &HashMap<PayloadKeyType, R>,
+) -> ConditionTrace
+where
+    R: AsRef<Vec<FieldIndex>>,
+{
+    match condition {
+        Condition::Field(field_condition) => {
+            let (matched, checked_by) = check_field_condition_with_source(
+                field_condition,
+                get_payload().deref(),
+                field_indexes,
+            );
+            ConditionTrace::new(condition, matched, Some(checked_by))
+        }
+        Condition::IsEmpty(_) | Condition::IsNull(_) => {
+            let matched = check_payload_condition(
+                condition,
+                get_payload,
+                id_tracker,
+                point_id,
+                field_indexes,
+            );
+            ConditionTrace::new(condition, matched, Some(ConditionCheckSource::Payload))
+        }
+        Condition::HasId(_) | Condition::ModifiedSince(_) => {
+            let matched = check_payload_condition(
+                condition,
+                get_payload,
+                id_tracker,
+                point_id,
+                field_indexes,
+            );
+            ConditionTrace::new(condition, matched, None)
+        }
+        Condition::Nested(nested) => {
+            let nested_path = nested.array_key();
+            let nested_indexes = select_nested_indexes(&nested_path, field_indexes);
+            let payload = get_payload();
+            let filters: Vec<_> = payload
+                .get_value(&nested_path)
+                .values()
+                .iter()
+                .filter_map(|value| value.as_object())
+                .map(|object| {
+                    trace_payload(
+                        &|| OwnedPayloadRef::from(object),
+                        None,
+                        &nested.nested.filter,
+                        point_id,
+                        &nested_indexes,
+                    )
+                })
+                .collect();
+            ConditionTrace {
+                matched: filters.iter().any(|filter| filter.matched),
+                filters,
+                ..ConditionTrace::new(condition, false, None)
+            }
+        }
+        Condition::Filter(_) => unreachable!(),
+    }
 }
 
 pub fn check_is_empty_condition(
@@ -145,21 +363,49 @@ pub fn check_field_condition<R>(
     payload: &impl PayloadContainer,
     field_indexes: &HashMap<PayloadKeyType, R>,
 ) -> bool
+where
+    R: AsRef<Vec<FieldIndex>>,
+{
+    check_field_condition_with_source(field_condition, payload, field_indexes).0
+}
+
+/// Check the field condition and tell, whether the outcome was decided by the field index.
+///
+/// The outcome is attributed to the index only if the index has checked the matching value,
+/// or, for a condition that doesn't match, every value of the field.
+pub fn check_field_condition_with_source<R>(
+    field_condition: &FieldCondition,
+    payload: &impl PayloadContainer,
+    field_indexes: &HashMap<PayloadKeyType, R>,
+) -> (bool, ConditionCheckSource)
 where
     R: AsRef<Vec<FieldIndex>>,
 {
     let field_values = payload.get_value(&field_condition.key);
+    record_payload_values_read(field_values.len());
+
+    // Values are counted all together, so that a missing field or nested arrays count as well
+    if let Some(values_count) = &field_condition.values_count {
+        let matched = values_count.check_count_number(field_values.values_count());
+        return (matched, ConditionCheckSource::Payload);
+    }
+
     let field_indexes = field_indexes.get(&field_condition.key);
 
     // This covers a case, when a field index affects the result of the condition.
     if let Some(field_indexes) = field_indexes {
+        let mut checked_by = if field_values.is_empty() {
+            ConditionCheckSource::Payload
+        } else {
+            ConditionCheckSource::Index
+        };
         for p in field_values {
             let mut index_checked = false;
             for index in field_indexes.as_ref() {
                 if let Some(index_check_res) = index.check_condition(field_condition, p) {
                     if index_check_res {
                         // If at least one object matches the condition, we can return true
-                        return true;
+                        return (true, ConditionCheckSource::Index);
                     }
                     index_checked = true;
                     // If index check of the condition returned something, we don't need to check
@@ -171,14 +417,16 @@ where
                 // If none of the indexes returned anything, we need to check the condition
                 // against the payload
                 if field_condition.check(p) {
-                    return true;
+                    return (true, ConditionCheckSource::Payload);
                 }
+                checked_by = ConditionCheckSource::Payload;
             }
         }
-        false
+        (false, checked_by)
     } else {
         // Fallback to regular condition check if there are no indexes for the field
-        field_values.into_iter().any(|p| field_condition.check(p))
+        let matched = field_values.into_iter().any(|p| field_condition.check(p));
+        (matched, ConditionCheckSource::Payload)
     }
 }
 
@@ -266,7 +514,8 @@ mod tests {
     use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
     use crate::payload_storage::PayloadStorage;
     use crate::types::{
-        FieldCondition, GeoBoundingBox, GeoPoint, PayloadField, Range, ValuesCount,
+        FieldCondition, GeoBoundingBox, GeoPoint, Match, MatchValue, PayloadField, Range,
+        ValueVariants, ValuesCount,
     };
 
     #[test]
@@ -446,6 +695,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![match_red.clone()]),
             must_not: None,
         };
@@ -453,6 +703,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![match_blue.clone()]),
             must_not: None,
         };
@@ -460,6 +711,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![match_blue.clone()]),
         };
@@ -467,6 +719,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![match_red.clone()]),
         };
@@ -474,6 +727,7 @@ mod tests {
 
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
+            min_should: None,
             must: Some(vec![with_delivery.clone(), in_berlin.clone()]),
             must_not: None,
         };
@@ -481,6 +735,7 @@ mod tests {
 
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
+            min_should: None,
             must: Some(vec![with_delivery, in_moscow.clone()]),
             must_not: None,
         };
@@ -490,15 +745,18 @@ mod tests {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_red.clone(), in_moscow.clone()]),
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_blue.clone(), in_berlin.clone()]),
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -508,15 +766,18 @@ mod tests {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_blue, in_moscow]),
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_red, in_berlin]),
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -524,6 +785,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![with_bad_rating]),
         };
@@ -533,6 +795,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
@@ -542,6 +805,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
@@ -551,9 +815,105 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![Condition::HasId(ids.into())]),
             must_not: None,
         };
         assert!(payload_checker.check(2, &query));
     }
+
+    #[test]
+    fn test_min_should_condition() {
+        let has_tag = |tag: &str| {
+            Condition::Field(FieldCondition::new_match(
+                "tags".to_string(),
+                tag.to_owned().into(),
+            ))
+        };
+        let min_should = |min_count| {
+            Filter::new_min_should(MinShould {
+                conditions: vec![has_tag("a"), has_tag("b"), has_tag("c"), has_tag("d")],
+                min_count,
+            })
+        };
+        let point_tags = ["a", "c", "x"];
+        let checker = |condition: &Condition| match condition {
+            Condition::Field(FieldCondition {
+                r#match: Some(Match::Value(MatchValue { value })),
+                ..
+            }) => point_tags
+                .iter()
+                .any(|tag| *value == ValueVariants::Keyword(tag.to_string())),
+            _ => false,
+        };
+
+        assert!(check_filter(&checker, &min_should(1)));
+        assert!(check_filter(&checker, &min_should(2)));
+        assert!(!check_filter(&checker, &min_should(3)));
+
+        let trace = trace_filter(
+            &|condition: &Condition| ConditionTrace {
+                condition: Some(condition.clone()),
+                matched: checker(condition),
+                checked_by: None,
+                filters: vec![],
+            },
+            &min_should(2),
+        );
+        assert!(trace.matched);
+        assert_eq!(
+            trace
+                .min_should
+                .unwrap()
+                .iter()
+                .map(|trace| trace.matched)
+                .collect::<Vec<_>>(),
+            vec![true, false, true, false],
+        );
+
+        // Combined with other clauses of the filter
+        let mut filter = min_should(2);
+        filter.must_not = Some(vec![has_tag("x")]);
+        assert!(!check_filter(&checker, &filter));
+    }
+
+    #[test]
+    fn test_values_count_condition() {
+        let payload: Payload = json!({
+            "comments": [
+                { "replies": [1, 2] },
+                { "replies": 3 },
+                { "replies": [] },
+            ],
+            "flags": [true, false, true],
+            "parts": [],
+            "packaging": null,
+        })
+        .into();
+        let no_indexes: HashMap<PayloadKeyType, Vec<FieldIndex>> = HashMap::new();
+
+        let count = |key: &str, lt, gt, gte, lte| {
+            let condition =
+                FieldCondition::new_values_count(key.to_string(), ValuesCount { lt, gt, gte, lte });
+            check_field_condition(&condition, &payload, &no_indexes)
+        };
+
+        // Replies of all the comments are counted together
+        assert!(count("comments[].replies", None, None, Some(3), None));
+        assert!(!count("comments[].replies", None, Some(3), None, None));
+        assert!(count("comments[].replies", None, None, None, Some(3)));
+        assert!(!count("comments[].replies", Some(3), None, None, None));
+        assert!(count("comments[].replies", Some(4), Some(2), None, None));
+
+        assert!(count("flags", None, Some(2), None, None));
+        assert!(!count("flags", Some(3), None, None, None));
+
+        // Empty array, null and missing field have no values
+        for key in ["parts", "packaging", "missing"] {
+            assert!(count(key, Some(1), None, None, None));
+            assert!(count(key, None, None, None, Some(0)));
+            assert!(!count(key, None, Some(0), None, None));
+            assert!(!count(key, None, None, Some(1), None));
+        }
+    }
 }
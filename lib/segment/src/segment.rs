@@ -8,33 +8,45 @@ use std::thread::{self, JoinHandle};
 use atomic_refcell::AtomicRefCell;
 use parking_lot::{Mutex, RwLock};
 use rocksdb::DB;
+use serde_json::Value;
 use tar::Builder;
 use uuid::Uuid;
 
 use crate::common::file_operations::{atomic_save_json, read_json};
+use crate::common::hardware_counter::record_payload_values_read;
 use crate::common::version::{StorageVersion, VERSION_FILE};
+use crate::common::warmup::WarmupTask;
 use crate::common::{
-    check_named_vectors, check_vector, check_vector_name, check_vectors, mmap_ops,
+    check_named_vectors, check_named_vectors_datatype, check_sparse_vector_name, check_vector,
+    check_vector_name, check_vectors, mmap_ops,
 };
+use crate::data_types::filter_trace::FilterTrace;
 use crate::data_types::named_vectors::NamedVectors;
+use crate::data_types::order_by::OrderBy;
+use crate::data_types::sparse_vector::SparseVector;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationError::TypeInferenceError;
 use crate::entry::entry_point::{
     get_service_error, OperationError, OperationResult, SegmentEntry, SegmentFailedState,
 };
-use crate::id_tracker::IdTrackerSS;
+use crate::id_tracker::{current_timestamp, IdTrackerSS};
 use crate::index::field_index::CardinalityEstimation;
-use crate::index::struct_payload_index::StructPayloadIndex;
+use crate::index::filter_bitmask::FilterBitmask;
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
+use crate::index::struct_payload_index::{StagedBatch, StructPayloadIndex};
 use crate::index::{PayloadIndex, VectorIndex, VectorIndexEnum};
+use crate::segment_constructor::segment_migration::SEGMENT_FORMAT_VERSION;
 use crate::spaces::tools::peek_top_smallest_iterable;
-use crate::telemetry::SegmentTelemetry;
+use crate::telemetry::{HnswGraphHealth, SegmentTelemetry};
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointIdType, PointOffsetType, ScoredPoint, SearchParams, SegmentConfig,
-    SegmentInfo, SegmentState, SegmentType, SeqNumberType, WithPayload, WithVector,
+    Condition, Filter, Payload, PayloadContainer, PayloadFieldSchema, PayloadIndexInfo,
+    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PayloadSelector, PointIdType,
+    PointOffsetType, Range, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentState,
+    SegmentType, SeqNumberType, VectorSlice, WithPayload, WithVector,
 };
 use crate::utils;
 use crate::utils::fs::find_symlink;
+use crate::vector_storage::sparse_vector_storage::SparseVectorStorage;
 use crate::vector_storage::{ScoredPointOffset, VectorStorage, VectorStorageEnum};
 
 pub const SEGMENT_STATE_FILE: &str = "segment.json";
@@ -71,6 +83,7 @@ pub struct Segment {
     /// Component for mapping external ids to internal and also keeping track of point versions
     pub id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
     pub vector_data: HashMap<String, VectorData>,
+    pub sparse_vector_data: HashMap<String, SparseVectorData>,
     pub payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
     /// Shows if it is possible to insert more points into this segment
     pub appendable_flag: bool,
@@ -105,6 +118,8 @@ impl VectorData {
 
         let storage_task = match &*self.vector_storage.borrow() {
             VectorStorageEnum::Memmap(storage) => storage.prefault_mmap_pages(),
+            VectorStorageEnum::MemmapHalf(storage) => storage.prefault_mmap_pages(),
+            VectorStorageEnum::MemmapByte(storage) => storage.prefault_mmap_pages(),
             _ => None,
         };
 
@@ -112,6 +127,26 @@ impl VectorData {
     }
 }
 
+pub struct SparseVectorData {
+    /// Inverted index, all updates of the storage go through it
+    pub sparse_vector_index: Arc<AtomicRefCell<SparseVectorIndex>>,
+    pub sparse_vector_storage: Arc<AtomicRefCell<SparseVectorStorage>>,
+}
+
+/// Range of the `must` condition of the filter on the ordering `key`.
+/// Only the values in this range are iterated, and the points are placed at the first of them.
+fn order_by_range(filter: Option<&Filter>, key: &str) -> Range {
+    filter
+        .and_then(|filter| filter.must.as_ref())
+        .into_iter()
+        .flatten()
+        .find_map(|condition| match condition {
+            Condition::Field(field) if field.key == key => field.range.clone(),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
 impl Segment {
     /// Replace vectors in-place
     ///
@@ -147,6 +182,15 @@ impl Segment {
                 }
             }
         }
+        for (vector_name, sparse_vector_data) in self.sparse_vector_data.iter_mut() {
+            let mut sparse_index = sparse_vector_data.sparse_vector_index.borrow_mut();
+            match vectors.get_sparse(vector_name) {
+                Some(vector) => sparse_index.insert_vector(internal_id, vector)?,
+                None => {
+                    sparse_index.delete_vector(internal_id)?;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -170,6 +214,12 @@ impl Segment {
     ) -> OperationResult<()> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
+        for (vector_name, new_vector) in vectors.sparse_iter() {
+            self.sparse_vector_data[vector_name]
+                .sparse_vector_index
+                .borrow_mut()
+                .insert_vector(internal_id, new_vector)?;
+        }
         for (vector_name, new_vector) in vectors {
             let vector_data = &self.vector_data[vector_name.as_ref()];
             vector_data
@@ -207,6 +257,17 @@ impl Segment {
                 }
             }
         }
+        for (vector_name, sparse_vector_data) in self.sparse_vector_data.iter_mut() {
+            let mut sparse_index = sparse_vector_data.sparse_vector_index.borrow_mut();
+            match vectors.get_sparse(vector_name) {
+                None => {
+                    // Keep storage aligned with point offsets
+                    sparse_index.insert_vector(new_index, &SparseVector::default())?;
+                    sparse_index.delete_vector(new_index)?;
+                }
+                Some(vector) => sparse_index.insert_vector(new_index, vector)?,
+            }
+        }
         self.id_tracker.borrow_mut().set_link(point_id, new_index)?;
         Ok(new_index)
     }
@@ -327,9 +388,11 @@ impl Segment {
         self.version = Some(max(op_num, self.version.unwrap_or(0)));
 
         if let Some(point_id) = point_id {
-            self.id_tracker
-                .borrow_mut()
-                .set_internal_version(point_id, op_num)?;
+            self.id_tracker.borrow_mut().set_internal_version(
+                point_id,
+                op_num,
+                Some(current_timestamp()),
+            )?;
         }
 
         Ok(applied)
@@ -349,6 +412,7 @@ impl Segment {
         SegmentState {
             version: self.version,
             config: self.segment_config.clone(),
+            format_version: Some(SEGMENT_FORMAT_VERSION),
         }
     }
 
@@ -401,11 +465,53 @@ impl Segment {
         }
     }
 
+    /// Retrieve sparse vector by internal ID
+    ///
+    /// Returns None if the vector does not exists or deleted
+    fn sparse_vector_by_offset(
+        &self,
+        vector_name: &str,
+        point_offset: PointOffsetType,
+    ) -> OperationResult<Option<SparseVector>> {
+        check_sparse_vector_name(vector_name, &self.segment_config)?;
+        let vector_storage = self.sparse_vector_data[vector_name]
+            .sparse_vector_storage
+            .borrow();
+        if vector_storage.is_deleted_vector(point_offset)
+            || self.id_tracker.borrow().is_deleted_point(point_offset)
+        {
+            return Ok(None);
+        }
+        Ok(vector_storage.get(point_offset).cloned())
+    }
+
+    /// Add dense or sparse vector of the given name to `vectors`, if the point has it
+    fn insert_vector_by_offset(
+        &self,
+        vector_name: &str,
+        point_offset: PointOffsetType,
+        vectors: &mut NamedVectors,
+    ) -> OperationResult<()> {
+        if self.sparse_vector_data.contains_key(vector_name) {
+            if let Some(vector) = self.sparse_vector_by_offset(vector_name, point_offset)? {
+                vectors.insert_sparse(vector_name.to_owned(), vector);
+            }
+        } else if let Some(vector) = self.vector_by_offset(vector_name, point_offset)? {
+            vectors.insert(vector_name.to_owned(), vector);
+        }
+        Ok(())
+    }
+
     fn all_vectors_by_offset(
         &self,
         point_offset: PointOffsetType,
     ) -> OperationResult<NamedVectors> {
         let mut vectors = NamedVectors::default();
+        for vector_name in self.sparse_vector_data.keys() {
+            if let Some(vector) = self.sparse_vector_by_offset(vector_name, point_offset)? {
+                vectors.insert_sparse(vector_name.clone(), vector);
+            }
+        }
         for (vector_name, vector_data) in &self.vector_data {
             let is_vector_deleted = vector_data
                 .vector_storage
@@ -558,11 +664,12 @@ impl Segment {
                     ))
                 })?;
                 let payload = if with_payload.enable {
-                    let initial_payload = self.payload_by_offset(point_offset)?;
                     let processed_payload = if let Some(i) = &with_payload.payload_selector {
-                        i.process(initial_payload)
+                        self.payload_index
+                            .borrow()
+                            .selected_payload(point_offset, i)?
                     } else {
-                        initial_payload
+                        self.payload_by_offset(point_offset)?
                     };
                     Some(processed_payload)
                 } else {
@@ -576,14 +683,31 @@ impl Segment {
                     WithVector::Selector(vectors) => {
                         let mut result = NamedVectors::default();
                         for vector_name in vectors {
-                            if let Some(vector) =
-                                self.vector_by_offset(vector_name, point_offset)?
-                            {
-                                result.insert(vector_name.clone(), vector);
+                            self.insert_vector_by_offset(vector_name, point_offset, &mut result)?;
+                        }
+                        Some(result.into())
+                    }
+                    WithVector::Exclude { exclude } => {
+                        let mut result = NamedVectors::default();
+                        let vector_names = self
+                            .vector_data
+                            .keys()
+                            .chain(self.sparse_vector_data.keys());
+                        for vector_name in vector_names {
+                            if exclude.contains(vector_name) {
+                                continue;
                             }
+                            self.insert_vector_by_offset(vector_name, point_offset, &mut result)?;
                         }
                         Some(result.into())
                     }
+                    WithVector::Slice { .. } => None,
+                };
+                let vector_slice = match with_vector {
+                    WithVector::Slice { name, slice } => self
+                        .vector_by_offset(name, point_offset)?
+                        .and_then(|vector| VectorSlice::new(name, *slice, &vector)),
+                    _ => None,
                 };
 
                 Ok(ScoredPoint {
@@ -592,6 +716,8 @@ impl Segment {
                     score: scored_point_offset.score,
                     payload,
                     vector,
+                    payload_truncation: None,
+                    vector_slice,
                 })
             })
             .collect()
@@ -645,6 +771,74 @@ impl Segment {
             .collect()
     }
 
+    /// Points, matching the `filter`, ordered by the indexed values of the `order_by` key.
+    /// `None` if the key has no index, which keeps the values ordered.
+    pub fn ordered_read_by_index(
+        &self,
+        limit: Option<usize>,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+    ) -> Option<Vec<(f64, PointIdType)>> {
+        let payload_index = self.payload_index.borrow();
+        let range = order_by_range(filter, &order_by.key);
+        let ordered_points = payload_index
+            .field_indexes
+            .get(&order_by.key)?
+            .iter()
+            .find_map(|index| index.range_iter_sorted(&range, order_by.direction))?;
+        let id_tracker = self.id_tracker.borrow();
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+
+        // A point with several values is placed at the first of them in the direction
+        let mut visited = HashSet::new();
+        let points = ordered_points
+            .filter(|(_, internal_id)| visited.insert(*internal_id))
+            .filter(|(_, internal_id)| {
+                filter_context
+                    .as_ref()
+                    .map_or(true, |context| context.check(*internal_id))
+            })
+            .filter_map(|(value, internal_id)| Some((value, id_tracker.external_id(internal_id)?)))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Some(points)
+    }
+
+    /// Points, matching the `filter`, ordered by the values of the `order_by` key in their
+    /// payloads. Used if the key is not indexed in the segment, e.g. in the small segments,
+    /// which receive the updates during an optimization.
+    pub fn ordered_read_by_payload(
+        &self,
+        limit: Option<usize>,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+    ) -> OperationResult<Vec<(f64, PointIdType)>> {
+        let direction = order_by.direction;
+        let range = order_by_range(filter, &order_by.key);
+        let mut points = Vec::new();
+        for point_id in self.read_filtered(None, None, filter) {
+            let payload = self.payload_by_offset(self.lookup_internal_id(point_id)?)?;
+            let value = payload
+                .get_value(&order_by.key)
+                .into_iter()
+                .flat_map(|value| match value {
+                    Value::Array(values) => values.iter().collect(),
+                    value => vec![value],
+                })
+                .filter_map(Value::as_f64)
+                .filter(|value| range.check_range(*value))
+                .reduce(|a, b| direction.first(a, b));
+            if let Some(value) = value {
+                points.push((value, point_id));
+            }
+        }
+        points.sort_unstable_by(|(a, a_id), (b, b_id)| {
+            direction.compare(*a, *b).then_with(|| a_id.cmp(b_id))
+        });
+        points.truncate(limit.unwrap_or(usize::MAX));
+        Ok(points)
+    }
+
     /// Check consistency of the segment's data and repair it if possible.
     pub fn check_consistency_and_repair(&mut self) -> OperationResult<()> {
         let mut internal_ids_to_delete = HashSet::new();
@@ -670,6 +864,10 @@ impl Segment {
                     let mut vector_storage = vector_data.vector_storage.borrow_mut();
                     vector_storage.delete_vector(*internal_id)?;
                 }
+                for sparse_vector_data in self.sparse_vector_data.values() {
+                    let mut sparse_index = sparse_vector_data.sparse_vector_index.borrow_mut();
+                    sparse_index.delete_vector(*internal_id)?;
+                }
             }
 
             // We do not drop version here, because it is already not loaded into memory.
@@ -698,6 +896,68 @@ impl Segment {
         self.id_tracker.borrow().total_point_count()
     }
 
+    /// Rebuild payload index of the given field in place, without touching other segment data
+    ///
+    /// Removes obsolete values, left in the index storage by payload updates and deletions.
+    pub fn rebuild_field_index(&mut self, key: PayloadKeyTypeRef) -> OperationResult<()> {
+        self.payload_index.borrow_mut().rebuild_field_index(key)
+    }
+
+    /// Start rebuilding payload index of the given field into a staging storage.
+    ///
+    /// The index is built by `build_staged_field_index` in batches, so the segment doesn't have
+    /// to stay locked for the whole rebuild. Returns false if the field is not indexed.
+    pub fn start_field_index_rebuild(&mut self, key: PayloadKeyTypeRef) -> OperationResult<bool> {
+        self.payload_index
+            .borrow_mut()
+            .stage_field_index_rebuild(key)
+    }
+
+    /// Index the next batch of points into the staging index of the field.
+    /// The staging index replaces the current one, once all points are indexed.
+    pub fn build_staged_field_index(
+        &mut self,
+        key: PayloadKeyTypeRef,
+        batch_size: usize,
+    ) -> OperationResult<StagedBatch> {
+        self.payload_index
+            .borrow_mut()
+            .build_staged_field_index(key, batch_size)
+    }
+
+    /// Fields, which indexes are being created, with the number of the operation,
+    /// which created them. Their build progress is reported by `field_index_rebuild_progress`.
+    pub fn building_field_indexes(&self) -> HashMap<PayloadKeyType, SeqNumberType> {
+        self.payload_index.borrow().building_fields().clone()
+    }
+
+    /// Number of points, already indexed into the staging index of the field,
+    /// out of all points of the segment. `None` if the index is not being rebuilt.
+    pub fn field_index_rebuild_progress(&self, key: PayloadKeyTypeRef) -> Option<(usize, usize)> {
+        let staged_points = self.payload_index.borrow().staged_points_count(key)?;
+        Some((staged_points, self.total_point_count()))
+    }
+
+    /// Data of the segment to read on warmup.
+    ///
+    /// Field indexes are loaded into memory together with the segment, so only the memory maps of
+    /// the vectors and the payload, stored on disk, are read.
+    pub fn warmup_tasks(&self) -> Vec<WarmupTask> {
+        let mut tasks: Vec<_> = self
+            .vector_data
+            .values()
+            .flat_map(|data| data.prefault_mmap_pages())
+            .map(WarmupTask::Mmap)
+            .collect();
+        tasks.extend(
+            self.payload_index
+                .borrow()
+                .on_disk_payload_column()
+                .map(WarmupTask::Column),
+        );
+        tasks
+    }
+
     pub fn prefault_mmap_pages(&self) {
         let tasks: Vec<_> = self
             .vector_data
@@ -728,6 +988,13 @@ impl SegmentEntry for Segment {
             .and_then(|internal_id| id_tracker.internal_version(internal_id))
     }
 
+    fn point_timestamp(&self, point_id: PointIdType) -> Option<u64> {
+        let id_tracker = self.id_tracker.borrow();
+        id_tracker
+            .internal_id(point_id)
+            .and_then(|internal_id| id_tracker.internal_timestamp(internal_id))
+    }
+
     fn search(
         &self,
         vector_name: &str,
@@ -776,6 +1043,65 @@ impl SegmentEntry for Segment {
         res
     }
 
+    fn filter_bitmask(&self, filter: &Filter) -> Option<FilterBitmask> {
+        let points = self.payload_index.borrow().query_points(filter);
+        Some(FilterBitmask::from_points(points))
+    }
+
+    fn search_batch_prefiltered(
+        &self,
+        vector_name: &str,
+        vectors: &[&[VectorElementType]],
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        bitmask: &FilterBitmask,
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        check_vectors(vector_name, vectors, &self.segment_config)?;
+        let vector_data = &self.vector_data[vector_name];
+        let internal_results = vector_data
+            .vector_index
+            .borrow()
+            .search_prefiltered(vectors, bitmask, filter, top, params);
+
+        internal_results
+            .iter()
+            .map(|internal_result| {
+                self.process_search_result(internal_result, with_payload, with_vector)
+            })
+            .collect()
+    }
+
+    fn search_sparse_batch(
+        &self,
+        vector_name: &str,
+        vectors: &[&SparseVector],
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+    ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
+        check_sparse_vector_name(vector_name, &self.segment_config)?;
+        for vector in vectors {
+            vector
+                .validate_structure()
+                .map_err(|description| OperationError::WrongSparseVector { description })?;
+        }
+        let internal_results = self.sparse_vector_data[vector_name]
+            .sparse_vector_index
+            .borrow()
+            .search(vectors, filter, top);
+
+        internal_results
+            .iter()
+            .map(|internal_result| {
+                self.process_search_result(internal_result, with_payload, with_vector)
+            })
+            .collect()
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -784,14 +1110,18 @@ impl SegmentEntry for Segment {
     ) -> OperationResult<bool> {
         debug_assert!(self.is_appendable());
         check_named_vectors(vectors, &self.segment_config)?;
+        check_named_vectors_datatype(point_id, vectors, &self.segment_config)?;
         let stored_internal_point = self.id_tracker.borrow().internal_id(point_id);
         self.handle_version_and_failure(op_num, stored_internal_point, |segment| {
             let mut processed_vectors = NamedVectors::default();
             for (vector_name, vector) in vectors.iter() {
                 let vector_name: &str = vector_name;
-                let processed_vector_opt = segment.segment_config.vector_data[vector_name]
-                    .distance
-                    .preprocess_vector(vector);
+                let vector_config = &segment.segment_config.vector_data[vector_name];
+                let processed_vector_opt = if vector_config.datatype.supports_preprocessing() {
+                    vector_config.distance.preprocess_vector(vector)
+                } else {
+                    None
+                };
                 match processed_vector_opt {
                     None => processed_vectors.insert_ref(vector_name, vector),
                     Some(preprocess_vector) => {
@@ -799,6 +1129,9 @@ impl SegmentEntry for Segment {
                     }
                 }
             }
+            for (vector_name, vector) in vectors.sparse_iter() {
+                processed_vectors.insert_sparse_ref(vector_name, vector);
+            }
 
             if let Some(existing_internal_id) = stored_internal_point {
                 segment.replace_all_vectors(existing_internal_id, processed_vectors)?;
@@ -830,6 +1163,10 @@ impl SegmentEntry for Segment {
                         let mut vector_storage = vector_data.vector_storage.borrow_mut();
                         vector_storage.delete_vector(internal_id)?;
                     }
+                    for sparse_vector_data in segment.sparse_vector_data.values() {
+                        let mut sparse_index = sparse_vector_data.sparse_vector_index.borrow_mut();
+                        sparse_index.delete_vector(internal_id)?;
+                    }
 
                     Ok((true, Some(internal_id)))
                 })
@@ -844,6 +1181,7 @@ impl SegmentEntry for Segment {
         vectors: NamedVectors,
     ) -> OperationResult<bool> {
         check_named_vectors(&vectors, &self.segment_config)?;
+        check_named_vectors_datatype(point_id, &vectors, &self.segment_config)?;
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
         match internal_id {
             None => Err(OperationError::PointIdError {
@@ -864,7 +1202,9 @@ impl SegmentEntry for Segment {
         point_id: PointIdType,
         vector_name: &str,
     ) -> OperationResult<bool> {
-        check_vector_name(vector_name, &self.segment_config)?;
+        if !self.sparse_vector_data.contains_key(vector_name) {
+            check_vector_name(vector_name, &self.segment_config)?;
+        }
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
         match internal_id {
             None => Err(OperationError::PointIdError {
@@ -872,6 +1212,11 @@ impl SegmentEntry for Segment {
             }),
             Some(internal_id) => {
                 self.handle_version_and_failure(op_num, Some(internal_id), |segment| {
+                    if let Some(sparse_vector_data) = segment.sparse_vector_data.get(vector_name) {
+                        let mut sparse_index = sparse_vector_data.sparse_vector_index.borrow_mut();
+                        let is_deleted = sparse_index.delete_vector(internal_id)?;
+                        return Ok((is_deleted, Some(internal_id)));
+                    }
                     let vector_data = segment.vector_data.get(vector_name).ok_or(
                         OperationError::VectorNameNotExists {
                             received_name: vector_name.to_string(),
@@ -976,6 +1321,16 @@ impl SegmentEntry for Segment {
         Ok(vector_opt)
     }
 
+    fn sparse_vector(
+        &self,
+        vector_name: &str,
+        point_id: PointIdType,
+    ) -> OperationResult<Option<SparseVector>> {
+        check_sparse_vector_name(vector_name, &self.segment_config)?;
+        let internal_id = self.lookup_internal_id(point_id)?;
+        self.sparse_vector_by_offset(vector_name, internal_id)
+    }
+
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors> {
         let mut result = NamedVectors::default();
         for vector_name in self.vector_data.keys() {
@@ -983,12 +1338,45 @@ impl SegmentEntry for Segment {
                 result.insert(vector_name.clone(), vec);
             }
         }
+        for vector_name in self.sparse_vector_data.keys() {
+            if let Some(vec) = self.sparse_vector(vector_name, point_id)? {
+                result.insert_sparse(vector_name.clone(), vec);
+            }
+        }
         Ok(result)
     }
 
     fn payload(&self, point_id: PointIdType) -> OperationResult<Payload> {
         let internal_id = self.lookup_internal_id(point_id)?;
-        self.payload_by_offset(internal_id)
+        let payload = self.payload_by_offset(internal_id)?;
+        record_payload_values_read(payload.len());
+        Ok(payload)
+    }
+
+    fn selected_payload(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        let internal_id = self.lookup_internal_id(point_id)?;
+        let payload = self
+            .payload_index
+            .borrow()
+            .selected_payload(internal_id, selector)?;
+        record_payload_values_read(payload.len());
+        Ok(payload)
+    }
+
+    fn trace_filter(&self, point_id: PointIdType, filter: &Filter) -> OperationResult<FilterTrace> {
+        let internal_id = self.lookup_internal_id(point_id)?;
+        Ok(self
+            .payload_index
+            .borrow()
+            .trace_filter(internal_id, filter))
+    }
+
+    fn unindexed_filter_keys(&self, filter: &Filter) -> Vec<PayloadKeyType> {
+        self.payload_index.borrow().unindexed_filter_keys(filter)
     }
 
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
@@ -1057,6 +1445,18 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn read_ordered_filtered(
+        &self,
+        limit: Option<usize>,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+    ) -> OperationResult<Vec<(f64, PointIdType)>> {
+        match self.ordered_read_by_index(limit, filter, order_by) {
+            Some(points) => Ok(points),
+            None => self.ordered_read_by_payload(limit, filter, order_by),
+        }
+    }
+
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let id_tracker = self.id_tracker.borrow();
         let iterator = id_tracker.iter_from(from).map(|x| x.0);
@@ -1102,7 +1502,7 @@ impl SegmentEntry for Segment {
 
     fn info(&self) -> SegmentInfo {
         let payload_index = self.payload_index.borrow();
-        let schema = payload_index
+        let mut schema: HashMap<_, _> = payload_index
             .indexed_fields()
             .into_iter()
             .map(|(key, index_schema)| {
@@ -1110,6 +1510,15 @@ impl SegmentEntry for Segment {
                 (key, PayloadIndexInfo::new(index_schema, points_count))
             })
             .collect();
+        for index_telemetry in payload_index.get_telemetry_data() {
+            let field_info = index_telemetry
+                .field_name
+                .as_ref()
+                .and_then(|field_name| schema.get_mut(field_name));
+            if let Some(field_info) = field_info {
+                field_info.add_index(&index_telemetry);
+            }
+        }
         let num_vectors = self
             .vector_data
             .values()
@@ -1161,6 +1570,11 @@ impl SegmentEntry for Segment {
             .vector_data
             .values()
             .map(|v| v.vector_storage.borrow().flusher())
+            .chain(
+                self.sparse_vector_data
+                    .values()
+                    .map(|v| v.sparse_vector_storage.borrow().flusher()),
+            )
             .collect();
         let state = self.get_state();
         let current_path = self.current_path.clone();
@@ -1315,6 +1729,57 @@ impl SegmentEntry for Segment {
         })
     }
 
+    fn stage_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        self.handle_version_and_failure(op_num, None, |segment| {
+            let is_staged = segment.start_field_index_rebuild(key)?;
+            Ok((is_staged, None))
+        })
+    }
+
+    fn stage_field_index_creation(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_schema: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool> {
+        self.handle_version_and_failure(op_num, None, |segment| {
+            let schema = match field_schema {
+                Some(schema) => schema.clone(),
+                None => match segment.infer_from_payload_data(key)? {
+                    None => {
+                        return Err(TypeInferenceError {
+                            field_name: key.to_string(),
+                        })
+                    }
+                    Some(schema_type) => schema_type.into(),
+                },
+            };
+            segment
+                .payload_index
+                .borrow_mut()
+                .stage_field_index_creation(key, schema, op_num)?;
+            Ok((true, None))
+        })
+    }
+
+    fn cancel_field_index_build(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        self.handle_version_and_failure(op_num, None, |segment| {
+            let is_cancelled = segment
+                .payload_index
+                .borrow_mut()
+                .cancel_field_index_build(key)?;
+            Ok((is_cancelled, None))
+        })
+    }
+
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
         self.payload_index.borrow().indexed_fields()
     }
@@ -1432,6 +1897,17 @@ impl SegmentEntry for Segment {
             }
         }
 
+        for sparse_vector_data in self.sparse_vector_data.values() {
+            for file in sparse_vector_data.sparse_vector_index.borrow().files() {
+                utils::tar::append_file_relative_to_base(
+                    &mut builder,
+                    &self.current_path,
+                    &file,
+                    &files,
+                )?;
+            }
+        }
+
         for file in self.payload_index.borrow().files() {
             utils::tar::append_file_relative_to_base(
                 &mut builder,
@@ -1481,13 +1957,26 @@ impl SegmentEntry for Segment {
             })
             .collect();
 
+        let payload_index = self.payload_index.borrow();
         SegmentTelemetry {
             info: self.info(),
             config: self.config(),
             vector_index_searches,
-            payload_field_indices: self.payload_index.borrow().get_telemetry_data(),
+            payload_field_indices: payload_index.get_telemetry_data(),
+            payload_cache_ram_bytes: payload_index.payload_cache_ram_bytes(),
+            graph_health: None,
         }
     }
+
+    fn graph_health(&self) -> HashMap<String, HnswGraphHealth> {
+        self.vector_data
+            .iter()
+            .filter_map(|(vector_name, vector_data)| {
+                let health = vector_data.vector_index.borrow().graph_health()?;
+                Some((vector_name.clone(), health))
+            })
+            .collect()
+    }
 }
 
 impl Drop for Segment {
@@ -1504,7 +1993,10 @@ mod tests {
     use crate::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
     use crate::entry::entry_point::OperationError::PointIdError;
     use crate::segment_constructor::{build_segment, load_segment};
-    use crate::types::{Distance, Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+    use crate::types::{
+        Distance, Indexes, SegmentConfig, VectorDataConfig, VectorStorageDatatype,
+        VectorStorageType,
+    };
 
     // no longer valid since users are now allowed to store arbitrary json objects.
     // TODO(gvelo): add tests for invalid payload types on indexed fields.
@@ -1553,9 +2045,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1623,9 +2118,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
 
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1712,9 +2210,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1803,9 +2304,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1834,9 +2338,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1929,9 +2436,12 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1981,6 +2491,7 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
                 (
@@ -1991,10 +2502,13 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2090,6 +2604,7 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
                 (
@@ -2100,10 +2615,13 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2230,4 +2748,81 @@ mod tests {
                 .unwrap();
         }
     }
+
+    #[test]
+    fn test_byte_vector_checks() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let config = SegmentConfig {
+            vector_data: HashMap::from([(
+                DEFAULT_VECTOR_NAME.into(),
+                VectorDataConfig {
+                    size: 4,
+                    distance: Distance::Cosine,
+                    storage_type: VectorStorageType::Memory,
+                    index: Indexes::Plain {},
+                    quantization_config: None,
+                    datatype: VectorStorageDatatype::Uint8,
+                },
+            )]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+        let point_id = 1.into();
+        let vector = vec![0.0, 3.0, 255.0, 4.0];
+        segment
+            .upsert_point(100, point_id, &only_default_vector(&vector))
+            .unwrap();
+
+        // Cosine vectors are stored without normalization
+        assert_eq!(
+            segment.vector(DEFAULT_VECTOR_NAME, point_id).unwrap(),
+            Some(vector.clone()),
+        );
+
+        for wrong_vector in [
+            vec![0.0, 3.0, 256.0, 4.0],
+            vec![-1.0, 3.0, 255.0, 4.0],
+            vec![0.0, 3.5, 255.0, 4.0],
+        ] {
+            let err = segment
+                .upsert_point(101, 2.into(), &only_default_vector(&wrong_vector))
+                .unwrap_err();
+            assert!(
+                matches!(err, OperationError::WrongVectorElement { .. }),
+                "{err:?}"
+            );
+            let err = SegmentEntry::update_vectors(
+                &mut segment,
+                102,
+                point_id,
+                only_default_vector(&wrong_vector),
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, OperationError::WrongVectorElement { .. }),
+                "{err:?}"
+            );
+        }
+        assert_eq!(
+            segment.vector(DEFAULT_VECTOR_NAME, point_id).unwrap(),
+            Some(vector.clone()),
+        );
+
+        let result = segment
+            .search(
+                DEFAULT_VECTOR_NAME,
+                &vector,
+                &WithPayload::default(),
+                &false.into(),
+                None,
+                1,
+                None,
+            )
+            .unwrap();
+        assert_eq!(result[0].id, point_id);
+        assert!((result[0].score - 1.0).abs() < 1e-5);
+    }
 }
@@ -1,5 +1,6 @@
 pub mod segment_builder;
 mod segment_constructor_base;
+pub mod segment_migration;
 pub mod simple_segment_constructor;
 
 pub use segment_constructor_base::*;
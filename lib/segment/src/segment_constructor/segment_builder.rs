@@ -4,15 +4,17 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
 use super::get_vector_storage_path;
+use crate::common::cpu::CpuPermit;
 use crate::common::error_logging::LogError;
 use crate::entry::entry_point::{
     check_process_stopped, OperationError, OperationResult, SegmentEntry,
 };
-use crate::index::hnsw_index::max_rayon_threads;
 use crate::index::{PayloadIndex, VectorIndex};
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
+use crate::types::{
+    Indexes, PayloadContainer, PayloadFieldSchema, PayloadKeyType, PointOffsetType, SegmentConfig,
+};
 use crate::vector_storage::VectorStorage;
 
 /// Structure for constructing segment out of several other segments
@@ -72,7 +74,18 @@ impl SegmentBuilder {
                 (vector_name.to_owned(), vector_data.vector_storage.borrow())
             })
             .collect();
+        let other_sparse_vector_storages: HashMap<_, _> = other
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, sparse_vector_data)| {
+                (
+                    vector_name.to_owned(),
+                    sparse_vector_data.sparse_vector_storage.borrow(),
+                )
+            })
+            .collect();
         let other_payload_index = other.payload_index.borrow();
+        let other_ids = Self::ordered_internal_ids(other)?;
 
         let mut id_tracker = self_segment.id_tracker.borrow_mut();
         let mut vector_storages: HashMap<_, _> = self_segment
@@ -85,9 +98,22 @@ impl SegmentBuilder {
                 )
             })
             .collect();
+        // Sparse vectors are written to the storages directly, inverted indexes are built later
+        let mut sparse_vector_storages: HashMap<_, _> = self_segment
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, sparse_vector_data)| {
+                (
+                    vector_name.to_owned(),
+                    sparse_vector_data.sparse_vector_storage.borrow_mut(),
+                )
+            })
+            .collect();
         let mut payload_index = self_segment.payload_index.borrow_mut();
 
-        if vector_storages.len() != other_vector_storages.len() {
+        if vector_storages.len() != other_vector_storages.len()
+            || sparse_vector_storages.len() != other_sparse_vector_storages.len()
+        {
             return Err(OperationError::service_error(
                 format!("Self and other segments have different vector names count. Self count: {}, other count: {}", vector_storages.len(), other_vector_storages.len()),
             ));
@@ -103,7 +129,7 @@ impl SegmentBuilder {
             })?;
             let internal_range = vector_storage.update_from(
                 other_vector_storage,
-                &mut other_id_tracker.iter_ids(),
+                &mut other_ids.iter().copied(),
                 stopped,
             )?;
             match new_internal_range.clone() {
@@ -118,8 +144,34 @@ impl SegmentBuilder {
             }
         }
 
+        for (vector_name, sparse_vector_storage) in &mut sparse_vector_storages {
+            check_process_stopped(stopped)?;
+            let other_sparse_vector_storage = other_sparse_vector_storages
+                .get(vector_name)
+                .ok_or_else(|| {
+                    OperationError::service_error(format!(
+                        "Cannot update from other segment because if missing sparse vector name {vector_name}"
+                    ))
+                })?;
+            let internal_range = sparse_vector_storage.update_from(
+                other_sparse_vector_storage,
+                &mut other_ids.iter().copied(),
+                stopped,
+            )?;
+            match new_internal_range.clone() {
+                Some(new_internal_range) => {
+                    if new_internal_range != internal_range {
+                        return Err(OperationError::service_error(
+                            "Internal ids range mismatch between self segment vectors and other segment sparse vectors",
+                        ));
+                    }
+                }
+                None => new_internal_range = Some(internal_range.clone()),
+            }
+        }
+
         if let Some(new_internal_range) = new_internal_range {
-            let internal_id_iter = new_internal_range.zip(other_id_tracker.iter_ids());
+            let internal_id_iter = new_internal_range.zip(other_ids.iter().copied());
 
             for (new_internal_id, old_internal_id) in internal_id_iter {
                 check_process_stopped(stopped)?;
@@ -134,12 +186,17 @@ impl SegmentBuilder {
                         continue;
                     };
                 let other_version = other_id_tracker.internal_version(old_internal_id).unwrap();
+                let other_timestamp = other_id_tracker.internal_timestamp(old_internal_id);
 
                 match id_tracker.internal_id(external_id) {
                     None => {
                         // New point, just insert
                         id_tracker.set_link(external_id, new_internal_id)?;
-                        id_tracker.set_internal_version(new_internal_id, other_version)?;
+                        id_tracker.set_internal_version(
+                            new_internal_id,
+                            other_version,
+                            other_timestamp,
+                        )?;
                         payload_index.assign(
                             new_internal_id,
                             &other_payload_index.payload(old_internal_id)?,
@@ -154,7 +211,11 @@ impl SegmentBuilder {
                             // Other version is the newest, remove the existing one and replace
                             id_tracker.drop(external_id)?;
                             id_tracker.set_link(external_id, new_internal_id)?;
-                            id_tracker.set_internal_version(new_internal_id, other_version)?;
+                            id_tracker.set_internal_version(
+                                new_internal_id,
+                                other_version,
+                                other_timestamp,
+                            )?;
                             payload_index.drop(existing_internal_id)?;
                             payload_index.assign(
                                 new_internal_id,
@@ -169,19 +230,62 @@ impl SegmentBuilder {
                         for vector_storage in vector_storages.values_mut() {
                             vector_storage.delete_vector(remove_id)?;
                         }
+                        for sparse_vector_storage in sparse_vector_storages.values_mut() {
+                            sparse_vector_storage.delete_vector(remove_id)?;
+                        }
                     }
                 }
             }
         }
 
-        for (field, payload_schema) in other.payload_index.borrow().indexed_fields() {
+        // Indexes of the new segment are built right away, so unfinished conversions are applied
+        for (field, payload_schema) in other.payload_index.borrow().target_indexed_fields() {
             self.indexed_fields.insert(field, payload_schema);
         }
 
         Ok(true)
     }
 
-    pub fn build(mut self, stopped: &AtomicBool) -> Result<Segment, OperationError> {
+    /// Internal ids of the `other` segment in the order, in which its points are copied.
+    ///
+    /// If the segment has a tenant field, points of the same tenant are placed next to each
+    /// other, so each tenant occupies a continuous range of the new storages.
+    fn ordered_internal_ids(other: &Segment) -> OperationResult<Vec<PointOffsetType>> {
+        let ids: Vec<_> = other.id_tracker.borrow().iter_ids().collect();
+        let payload_index = other.payload_index.borrow();
+
+        let tenant_field = payload_index
+            .indexed_fields()
+            .into_iter()
+            .filter(|(_, schema)| schema.is_tenant())
+            .map(|(field, _)| field)
+            .min();
+        let Some(tenant_field) = tenant_field else {
+            return Ok(ids);
+        };
+
+        let mut tenant_ids = Vec::with_capacity(ids.len());
+        for id in ids {
+            let payload = payload_index.payload(id)?;
+            let tenant = payload
+                .get_value(&tenant_field)
+                .into_iter()
+                .find_map(|value| value.as_str().map(str::to_owned));
+            tenant_ids.push((tenant, id));
+        }
+        // Stable sort keeps the original order of the points within a tenant
+        tenant_ids.sort_by(|(tenant_a, _), (tenant_b, _)| tenant_a.cmp(tenant_b));
+        Ok(tenant_ids.into_iter().map(|(_, id)| id).collect())
+    }
+
+    /// Build the segment with all vector indexes and quantization
+    ///
+    /// Heavy index building uses as many threads as `permit` allows.
+    pub fn build(
+        mut self,
+        permit: CpuPermit,
+        stopped: &AtomicBool,
+    ) -> Result<Segment, OperationError> {
         {
             let mut segment = self.segment.take().ok_or(OperationError::service_error(
                 "Segment building error: created segment not found",
@@ -192,12 +296,25 @@ impl SegmentBuilder {
                 check_process_stopped(stopped)?;
             }
 
-            Self::update_quantization(&segment, stopped)?;
+            Self::update_quantization(&segment, &permit, stopped)?;
 
             for vector_data in segment.vector_data.values_mut() {
-                vector_data.vector_index.borrow_mut().build_index(stopped)?;
+                vector_data
+                    .vector_index
+                    .borrow_mut()
+                    .build_index(&permit, stopped)?;
             }
 
+            for sparse_vector_data in segment.sparse_vector_data.values_mut() {
+                sparse_vector_data
+                    .sparse_vector_index
+                    .borrow_mut()
+                    .build_index(stopped)?;
+            }
+
+            // Release CPUs for other jobs as soon as heavy work is done
+            drop(permit);
+
             segment.flush(true)?;
             drop(segment);
             // Now segment is evicted from RAM
@@ -216,7 +333,11 @@ impl SegmentBuilder {
         Ok(loaded_segment)
     }
 
-    fn update_quantization(segment: &Segment, stopped: &AtomicBool) -> OperationResult<()> {
+    fn update_quantization(
+        segment: &Segment,
+        permit: &CpuPermit,
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
         let config = segment.config();
         for (vector_name, vector_data) in &segment.vector_data {
             if let Some(quantization) = config.quantization_config(vector_name) {
@@ -230,7 +351,7 @@ impl SegmentBuilder {
                     .get(vector_name)
                     .map(|config| &config.index)
                 {
-                    Some(Indexes::Hnsw(hnsw)) => max_rayon_threads(hnsw.max_indexing_threads),
+                    Some(Indexes::Hnsw(_)) => permit.num_cpus(),
                     _ => 1,
                 };
                 vector_data.vector_storage.borrow_mut().quantize(
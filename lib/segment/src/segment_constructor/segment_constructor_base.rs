@@ -1,6 +1,4 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -8,35 +6,44 @@ use atomic_refcell::AtomicRefCell;
 use log::info;
 use parking_lot::Mutex;
 use semver::Version;
-use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::common::rocksdb_wrapper::{open_db, DB_SPARSE_VECTOR_CF, DB_VECTOR_CF};
 use crate::common::version::StorageVersion;
-use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
 use crate::id_tracker::IdTracker;
 use crate::index::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use crate::index::hnsw_index::hnsw::HNSWIndex;
 use crate::index::plain_payload_index::PlainIndex;
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::VectorIndexEnum;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
-use crate::segment::{Segment, SegmentVersion, VectorData, SEGMENT_STATE_FILE};
+use crate::segment::{Segment, SegmentVersion, SparseVectorData, VectorData};
+use crate::segment_constructor::segment_migration::{migrate_segment, stored_format_version};
 use crate::types::{
-    Distance, Indexes, PayloadStorageType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
+    Indexes, PayloadStorageType, SegmentConfig, SegmentType, SeqNumberType, VectorStorageDatatype,
     VectorStorageType,
 };
-use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
-use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
-use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+use crate::vector_storage::appendable_mmap_vector_storage::{
+    open_appendable_memmap_vector_storage, open_appendable_memmap_vector_storage_byte,
+    open_appendable_memmap_vector_storage_half,
+};
+use crate::vector_storage::memmap_vector_storage::{
+    open_memmap_vector_storage, open_memmap_vector_storage_byte, open_memmap_vector_storage_half,
+};
+use crate::vector_storage::simple_vector_storage::{
+    open_simple_vector_storage, open_simple_vector_storage_byte, open_simple_vector_storage_half,
+};
+use crate::vector_storage::sparse_vector_storage::open_sparse_vector_storage;
 use crate::vector_storage::VectorStorage;
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
 pub const VECTOR_STORAGE_PATH: &str = "vector_storage";
 pub const VECTOR_INDEX_PATH: &str = "vector_index";
+pub const SPARSE_VECTOR_INDEX_PATH: &str = "sparse_vector_index";
 
 fn sp<T>(t: T) -> Arc<AtomicRefCell<T>> {
     Arc::new(AtomicRefCell::new(t))
@@ -61,6 +68,13 @@ pub fn get_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf
     segment_path.join(get_vector_name_with_prefix(VECTOR_INDEX_PATH, vector_name))
 }
 
+pub fn get_sparse_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf {
+    segment_path.join(get_vector_name_with_prefix(
+        SPARSE_VECTOR_INDEX_PATH,
+        vector_name,
+    ))
+}
+
 fn create_segment(
     version: Option<SeqNumberType>,
     segment_path: &Path,
@@ -70,6 +84,12 @@ fn create_segment(
         .vector_data
         .keys()
         .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name))
+        .chain(
+            config
+                .sparse_vector_data
+                .keys()
+                .map(|vector_name| get_vector_name_with_prefix(DB_SPARSE_VECTOR_CF, vector_name)),
+        )
         .collect();
     let database = open_db(segment_path, &vector_db_names)
         .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
@@ -82,11 +102,14 @@ fn create_segment(
     let id_tracker = sp(SimpleIdTracker::open(database.clone())?);
 
     let payload_index_path = segment_path.join(PAYLOAD_INDEX_PATH);
-    let payload_index: Arc<AtomicRefCell<StructPayloadIndex>> = sp(StructPayloadIndex::open(
+    let payload_index = StructPayloadIndex::open(
         payload_storage,
         id_tracker.clone(),
         &payload_index_path,
-    )?);
+        config.is_appendable(),
+    )?;
+    payload_index.cache_payload_fields(&config.payload_cache_fields)?;
+    let payload_index: Arc<AtomicRefCell<StructPayloadIndex>> = sp(payload_index);
 
     let mut vector_data = HashMap::new();
     for (vector_name, vector_config) in &config.vector_data {
@@ -94,11 +117,16 @@ fn create_segment(
         let vector_index_path = get_vector_index_path(segment_path, vector_name);
 
         // Select suitable vector storage type based on configuration
-        let vector_storage = match vector_config.storage_type {
+        let vector_storage = match (vector_config.storage_type, vector_config.datatype) {
             // In memory
-            VectorStorageType::Memory => {
+            (VectorStorageType::Memory, datatype) => {
                 let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
-                open_simple_vector_storage(
+                let open = match datatype {
+                    VectorStorageDatatype::Float32 => open_simple_vector_storage,
+                    VectorStorageDatatype::Float16 => open_simple_vector_storage_half,
+                    VectorStorageDatatype::Uint8 => open_simple_vector_storage_byte,
+                };
+                open(
                     database.clone(),
                     &db_column_name,
                     vector_config.size,
@@ -106,17 +134,49 @@ fn create_segment(
                 )?
             }
             // Mmap on disk, not appendable
-            VectorStorageType::Mmap => open_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
+            (VectorStorageType::Mmap, VectorStorageDatatype::Float32) => {
+                open_memmap_vector_storage(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (VectorStorageType::Mmap, VectorStorageDatatype::Float16) => {
+                open_memmap_vector_storage_half(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (VectorStorageType::Mmap, VectorStorageDatatype::Uint8) => {
+                open_memmap_vector_storage_byte(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
             // Chunked mmap on disk, appendable
-            VectorStorageType::ChunkedMmap => open_appendable_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
+            (VectorStorageType::ChunkedMmap, VectorStorageDatatype::Float32) => {
+                open_appendable_memmap_vector_storage(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (VectorStorageType::ChunkedMmap, VectorStorageDatatype::Float16) => {
+                open_appendable_memmap_vector_storage_half(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (VectorStorageType::ChunkedMmap, VectorStorageDatatype::Uint8) => {
+                open_appendable_memmap_vector_storage_byte(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
         };
 
         // Warn when number of points between ID tracker and storage differs
@@ -172,6 +232,26 @@ fn create_segment(
         );
     }
 
+    let mut sparse_vector_data = HashMap::new();
+    for (vector_name, sparse_vector_config) in &config.sparse_vector_data {
+        let db_column_name = get_vector_name_with_prefix(DB_SPARSE_VECTOR_CF, vector_name);
+        let sparse_vector_storage = open_sparse_vector_storage(database.clone(), &db_column_name)?;
+        let sparse_vector_index = sp(SparseVectorIndex::open(
+            sparse_vector_config.index,
+            id_tracker.clone(),
+            sparse_vector_storage.clone(),
+            payload_index.clone(),
+            &get_sparse_vector_index_path(segment_path, vector_name),
+        )?);
+        sparse_vector_data.insert(
+            vector_name.to_owned(),
+            SparseVectorData {
+                sparse_vector_index,
+                sparse_vector_storage,
+            },
+        );
+    }
+
     let segment_type = if config.is_any_vector_indexed() {
         SegmentType::Indexed
     } else {
@@ -185,6 +265,7 @@ fn create_segment(
         current_path: segment_path.to_owned(),
         id_tracker,
         vector_data,
+        sparse_vector_data,
         segment_type,
         appendable_flag,
         payload_index,
@@ -209,30 +290,18 @@ pub fn load_segment(path: &Path) -> OperationResult<Option<Segment>> {
     let stored_version: Version = SegmentVersion::load(path)?.parse()?;
     let app_version: Version = SegmentVersion::current().parse()?;
 
-    if stored_version != app_version {
-        info!("Migrating segment {} -> {}", stored_version, app_version,);
-
-        if stored_version > app_version {
-            return Err(OperationError::service_error(format!(
-                "Data version {stored_version} is newer than application version {app_version}. \
-                Please upgrade the application. Compatibility is not guaranteed."
-            )));
-        }
-
-        if stored_version.major == 0 && stored_version.minor < 3 {
-            return Err(OperationError::service_error(format!(
-                "Segment version({stored_version}) is not compatible with current version({app_version})"
-            )));
-        }
+    if stored_version > app_version {
+        return Err(OperationError::service_error(format!(
+            "Data version {stored_version} is newer than application version {app_version}. \
+            Please upgrade the application. Compatibility is not guaranteed."
+        )));
+    }
 
-        if stored_version.major == 0 && stored_version.minor == 3 {
-            let segment_state = load_segment_state_v3(path)?;
-            Segment::save_state(&segment_state, path)?;
-        } else if stored_version.major == 0 && stored_version.minor <= 5 {
-            let segment_state = load_segment_state_v5(path)?;
-            Segment::save_state(&segment_state, path)?;
-        }
+    let format_version = stored_format_version(path, &stored_version)?;
+    migrate_segment(path, format_version)?;
 
+    if stored_version != app_version {
+        info!("Migrated segment {stored_version} -> {app_version}");
         SegmentVersion::save(path)?
     }
 
@@ -271,95 +340,3 @@ pub fn build_segment(path: &Path, config: &SegmentConfig, ready: bool) -> Operat
 
     Ok(segment)
 }
-
-/// Load v0.3.* segment data and migrate to current version
-#[allow(deprecated)]
-fn load_segment_state_v3(segment_path: &Path) -> OperationResult<SegmentState> {
-    use crate::compat::{SegmentConfigV5, StorageTypeV5, VectorDataConfigV5};
-
-    #[derive(Deserialize)]
-    #[serde(rename_all = "snake_case")]
-    #[deprecated]
-    pub struct SegmentStateV3 {
-        pub version: SeqNumberType,
-        pub config: SegmentConfigV3,
-    }
-
-    #[derive(Deserialize)]
-    #[serde(rename_all = "snake_case")]
-    #[deprecated]
-    pub struct SegmentConfigV3 {
-        /// Size of a vectors used
-        pub vector_size: usize,
-        /// Type of distance function used for measuring distance between vectors
-        pub distance: Distance,
-        /// Type of index used for search
-        pub index: Indexes,
-        /// Type of vector storage
-        pub storage_type: StorageTypeV5,
-        /// Defines payload storage type
-        #[serde(default)]
-        pub payload_storage_type: PayloadStorageType,
-    }
-
-    let path = segment_path.join(SEGMENT_STATE_FILE);
-
-    let mut contents = String::new();
-
-    let mut file = File::open(&path)?;
-    file.read_to_string(&mut contents)?;
-
-    serde_json::from_str::<SegmentStateV3>(&contents)
-        .map(|state| {
-            // Construct V5 version, then convert into current
-            let vector_data = VectorDataConfigV5 {
-                size: state.config.vector_size,
-                distance: state.config.distance,
-                hnsw_config: None,
-                quantization_config: None,
-                on_disk: None,
-            };
-            let segment_config = SegmentConfigV5 {
-                vector_data: HashMap::from([(DEFAULT_VECTOR_NAME.to_owned(), vector_data)]),
-                index: state.config.index,
-                storage_type: state.config.storage_type,
-                payload_storage_type: state.config.payload_storage_type,
-                quantization_config: None,
-            };
-
-            SegmentState {
-                version: Some(state.version),
-                config: segment_config.into(),
-            }
-        })
-        .map_err(|err| {
-            OperationError::service_error(format!(
-                "Failed to read segment {}. Error: {}",
-                path.to_str().unwrap(),
-                err
-            ))
-        })
-}
-
-/// Load v0.5.0 segment data and migrate to current version
-#[allow(deprecated)]
-fn load_segment_state_v5(segment_path: &Path) -> OperationResult<SegmentState> {
-    use crate::compat::SegmentStateV5;
-
-    let path = segment_path.join(SEGMENT_STATE_FILE);
-
-    let mut contents = String::new();
-
-    let mut file = File::open(&path)?;
-    file.read_to_string(&mut contents)?;
-
-    serde_json::from_str::<SegmentStateV5>(&contents)
-        .map(Into::into)
-        .map_err(|err| {
-            OperationError::service_error(format!(
-                "Failed to read segment {}. Error: {}",
-                path.to_str().unwrap(),
-                err
-            ))
-        })
-}
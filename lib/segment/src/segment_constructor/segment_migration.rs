@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use log::info;
+use semver::Version;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::common::file_operations::read_json;
+use crate::common::version::StorageVersion;
+use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::segment::{Segment, SegmentVersion, SEGMENT_STATE_FILE};
+use crate::types::{Distance, Indexes, PayloadStorageType, SegmentState, SeqNumberType};
+
+/// Version of the on-disk format of segments, stored in the segment state.
+///
+/// Must be increased on every change of the segment files, which previous versions can't read.
+/// Such change requires a `SegmentMigration` from the previous format to be added
+/// to `SEGMENT_MIGRATIONS`.
+pub const SEGMENT_FORMAT_VERSION: u32 = 3;
+
+/// Upgrade of the files of a segment from one format version to another.
+///
+/// Migrations are applied in place, before the segment is loaded.
+pub trait SegmentMigration: Sync {
+    /// Format version, which the migration is applicable to
+    fn from(&self) -> u32;
+
+    /// Format version of the segment after the migration
+    fn to(&self) -> u32;
+
+    fn migrate(&self, segment_path: &Path) -> OperationResult<()>;
+}
+
+/// Known upgrade paths of the segment format
+static SEGMENT_MIGRATIONS: &[&dyn SegmentMigration] =
+    &[&SegmentStateV3Migration, &SegmentStateV5Migration];
+
+/// Format version of segments, created before the format version was stored in the segment state.
+///
+/// Returns `None` if segments of the application version can't be migrated.
+fn legacy_format_version(app_version: &Version) -> Option<u32> {
+    match (app_version.major, app_version.minor) {
+        (0, 0..=2) => None,
+        (0, 3) => Some(1),
+        (0, 4..=5) => Some(2),
+        // Format, which was current when the format version was introduced
+        _ => Some(3),
+    }
+}
+
+/// Read the format version of the segment, written by the application of `stored_version`
+pub fn stored_format_version(
+    segment_path: &Path,
+    stored_version: &Version,
+) -> OperationResult<u32> {
+    #[derive(Deserialize)]
+    struct StateFormatVersion {
+        format_version: Option<u32>,
+    }
+
+    let state: StateFormatVersion = read_json(&segment_path.join(SEGMENT_STATE_FILE))?;
+    match state.format_version {
+        Some(format_version) => Ok(format_version),
+        None => legacy_format_version(stored_version).ok_or_else(|| {
+            OperationError::service_error(format!(
+                "Segment version({stored_version}) is not compatible with current version({})",
+                SegmentVersion::current(),
+            ))
+        }),
+    }
+}
+
+/// Upgrade the segment files from the `format_version` to the current format.
+///
+/// Segments of a newer format are refused, as they might contain data, which is unknown to
+/// this version of the application.
+pub fn migrate_segment(segment_path: &Path, format_version: u32) -> OperationResult<()> {
+    if format_version > SEGMENT_FORMAT_VERSION {
+        return Err(OperationError::service_error(format!(
+            "Segment format version {format_version} of {} is newer than supported version \
+            {SEGMENT_FORMAT_VERSION}. Downgrade is not supported",
+            segment_path.display(),
+        )));
+    }
+
+    let mut current_format = format_version;
+    while current_format < SEGMENT_FORMAT_VERSION {
+        // Prefer migrations, which skip intermediate formats
+        let migration = SEGMENT_MIGRATIONS
+            .iter()
+            .filter(|migration| migration.from() == current_format)
+            .filter(|migration| migration.to() <= SEGMENT_FORMAT_VERSION)
+            .max_by_key(|migration| migration.to())
+            .ok_or_else(|| {
+                OperationError::service_error(format!(
+                    "No migration of segment {} from format version {current_format}",
+                    segment_path.display(),
+                ))
+            })?;
+
+        info!(
+            "Migrating segment {} format {} -> {}",
+            segment_path.display(),
+            migration.from(),
+            migration.to(),
+        );
+        migration.migrate(segment_path)?;
+        current_format = migration.to();
+    }
+
+    Ok(())
+}
+
+fn read_segment_state<T: DeserializeOwned>(segment_path: &Path) -> OperationResult<T> {
+    let path = segment_path.join(SEGMENT_STATE_FILE);
+    read_json(&path).map_err(|err| {
+        OperationError::service_error(format!(
+            "Failed to read segment {}. Error: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// Converts state of v0.3.* segments with a single unnamed vector
+struct SegmentStateV3Migration;
+
+impl SegmentMigration for SegmentStateV3Migration {
+    fn from(&self) -> u32 {
+        1
+    }
+
+    fn to(&self) -> u32 {
+        3
+    }
+
+    #[allow(deprecated)]
+    fn migrate(&self, segment_path: &Path) -> OperationResult<()> {
+        use crate::compat::{SegmentConfigV5, StorageTypeV5, VectorDataConfigV5};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        #[deprecated]
+        pub struct SegmentStateV3 {
+            pub version: SeqNumberType,
+            pub config: SegmentConfigV3,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        #[deprecated]
+        pub struct SegmentConfigV3 {
+            /// Size of a vectors used
+            pub vector_size: usize,
+            /// Type of distance function used for measuring distance between vectors
+            pub distance: Distance,
+            /// Type of index used for search
+            pub index: Indexes,
+            /// Type of vector storage
+            pub storage_type: StorageTypeV5,
+            /// Defines payload storage type
+            #[serde(default)]
+            pub payload_storage_type: PayloadStorageType,
+        }
+
+        let state: SegmentStateV3 = read_segment_state(segment_path)?;
+
+        // Construct V5 version, then convert into current
+        let vector_data = VectorDataConfigV5 {
+            size: state.config.vector_size,
+            distance: state.config.distance,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+        };
+        let segment_config = SegmentConfigV5 {
+            vector_data: HashMap::from([(DEFAULT_VECTOR_NAME.to_owned(), vector_data)]),
+            index: state.config.index,
+            storage_type: state.config.storage_type,
+            payload_storage_type: state.config.payload_storage_type,
+            quantization_config: None,
+        };
+
+        let segment_state = SegmentState {
+            version: Some(state.version),
+            config: segment_config.into(),
+            format_version: Some(self.to()),
+        };
+        Segment::save_state(&segment_state, segment_path)
+    }
+}
+
+/// Converts state of v0.4.* and v0.5.* segments with the storage type shared by all vectors
+struct SegmentStateV5Migration;
+
+impl SegmentMigration for SegmentStateV5Migration {
+    fn from(&self) -> u32 {
+        2
+    }
+
+    fn to(&self) -> u32 {
+        3
+    }
+
+    #[allow(deprecated)]
+    fn migrate(&self, segment_path: &Path) -> OperationResult<()> {
+        use crate::compat::SegmentStateV5;
+
+        let state: SegmentStateV5 = read_segment_state(segment_path)?;
+        let segment_state = SegmentState {
+            format_version: Some(self.to()),
+            ..SegmentState::from(state)
+        };
+        Segment::save_state(&segment_state, segment_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::version::VERSION_FILE;
+    use crate::segment_constructor::load_segment;
+    use crate::types::VectorStorageType;
+
+    /// State of a segment, created by v0.5.1
+    const SEGMENT_STATE_V5: &str = r#"{
+        "version": 42,
+        "config": {
+            "vector_data": {
+                "": {"size": 4, "distance": "Dot"}
+            },
+            "index": {"type": "plain", "options": {}},
+            "storage_type": {"type": "in_memory"},
+            "payload_storage_type": {"type": "in_memory"}
+        }
+    }"#;
+
+    fn old_segment_fixture(app_version: &str, state: &str) -> tempfile::TempDir {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        fs::write(dir.path().join(VERSION_FILE), app_version).unwrap();
+        fs::write(dir.path().join(SEGMENT_STATE_FILE), state).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_old_format_segment() {
+        let dir = old_segment_fixture("0.5.1", SEGMENT_STATE_V5);
+
+        let segment = load_segment(dir.path()).unwrap().unwrap();
+        assert_eq!(segment.version, Some(42));
+        let vector_config = &segment.segment_config.vector_data[DEFAULT_VECTOR_NAME];
+        assert_eq!(vector_config.size, 4);
+        assert_eq!(vector_config.storage_type, VectorStorageType::Memory);
+        drop(segment);
+
+        let state = Segment::load_state(dir.path()).unwrap();
+        assert_eq!(state.format_version, Some(SEGMENT_FORMAT_VERSION));
+        assert_eq!(
+            SegmentVersion::load(dir.path()).unwrap(),
+            SegmentVersion::current(),
+        );
+    }
+
+    #[test]
+    fn test_refuse_segment_downgrade() {
+        let state = format!(
+            r#"{{"version": 1, "config": {{}}, "format_version": {}}}"#,
+            SEGMENT_FORMAT_VERSION + 1,
+        );
+        let dir = old_segment_fixture(&SegmentVersion::current(), &state);
+
+        let err = load_segment(dir.path()).err().unwrap();
+        assert!(
+            err.to_string().contains("Downgrade is not supported"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_incompatible_segment_version() {
+        let dir = old_segment_fixture("0.2.1", SEGMENT_STATE_V5);
+        assert!(load_segment(dir.path()).is_err());
+    }
+}
@@ -29,9 +29,12 @@ pub fn build_simple_segment(
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: Default::default(),
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         },
         true,
     )
@@ -52,6 +55,7 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            datatype: Default::default(),
         },
     );
     vectors_config.insert(
@@ -62,6 +66,7 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            datatype: Default::default(),
         },
     );
 
@@ -69,7 +74,9 @@ pub fn build_multivec_segment(
         path,
         &SegmentConfig {
             vector_data: vectors_config,
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         },
         true,
     )
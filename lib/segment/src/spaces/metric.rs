@@ -1,4 +1,4 @@
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::vectors::{VectorElementType, VectorElementTypeByte, VectorElementTypeHalf};
 use crate::types::{Distance, ScoreType};
 
 /// Defines how to compare vectors
@@ -8,6 +8,30 @@ pub trait Metric {
     /// Greater the value - closer the vectors
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType;
 
+    /// Same as [`Metric::similarity`], with the second vector in half precision
+    fn similarity_half(v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        let v2: Vec<_> = v2.iter().map(|x| x.to_f32()).collect();
+        Self::similarity(v1, &v2)
+    }
+
+    /// Same as [`Metric::similarity`], with the second vector in bytes
+    ///
+    /// Byte vectors are stored without preprocessing, metrics which require it
+    /// have to apply it on the fly.
+    fn similarity_uint8(v1: &[VectorElementType], v2: &[VectorElementTypeByte]) -> ScoreType {
+        let v2: Vec<_> = v2.iter().map(|&x| x as VectorElementType).collect();
+        Self::similarity(v1, &v2)
+    }
+
+    /// Similarity of two byte vectors, see [`Metric::similarity_uint8`]
+    fn similarity_uint8_internal(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        let v1: Vec<_> = v1.iter().map(|&x| x as VectorElementType).collect();
+        Self::similarity_uint8(&v1, v2)
+    }
+
     /// Necessary vector transformations performed before adding it to the collection (like normalization)
     /// Return None if metric does not required preprocessing
     fn preprocess(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>>;
@@ -1,5 +1,7 @@
 pub mod metric;
+pub mod simd;
 pub mod simple;
+pub mod simple_uint8;
 pub mod tools;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -8,5 +10,8 @@ pub mod simple_sse;
 #[cfg(target_arch = "x86_64")]
 pub mod simple_avx;
 
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+pub mod simple_avx512;
+
 #[cfg(target_arch = "aarch64")]
 pub mod simple_neon;
@@ -0,0 +1,784 @@
+//! Runtime selection of the distance kernels.
+//!
+//! The kernels are chosen once per process, based on the features of the CPU the binary runs on,
+//! so that a single build uses the best available instructions on every machine of the fleet.
+//! A specific implementation can be forced with [`set_global`], which is useful to debug
+//! mismatches between the implementations.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_types::vectors::{VectorElementType, VectorElementTypeByte, VectorElementTypeHalf};
+use crate::spaces::simple::{cosine_preprocess, dot_similarity, euclid_similarity};
+use crate::spaces::simple_uint8::{dot_similarity_uint8, euclid_similarity_uint8};
+use crate::types::ScoreType;
+
+static KERNELS: OnceLock<Kernels> = OnceLock::new();
+
+/// Implementation of the distance kernels
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimdImplementation {
+    /// Best implementation, supported by the CPU
+    #[default]
+    Auto,
+    /// Plain Rust, without explicit SIMD instructions
+    Scalar,
+    /// SSE, x86 and x86_64 only
+    Sse,
+    /// AVX with FMA, x86_64 only
+    #[serde(alias = "avx2")]
+    Avx,
+    /// AVX-512, x86_64 only. Requires the `avx512` feature of the build
+    Avx512,
+    /// Neon, aarch64 only
+    Neon,
+}
+
+impl SimdImplementation {
+    /// Explicit implementations, from the most preferable to the least one
+    const PREFERENCE: [SimdImplementation; 5] = [
+        SimdImplementation::Avx512,
+        SimdImplementation::Avx,
+        SimdImplementation::Sse,
+        SimdImplementation::Neon,
+        SimdImplementation::Scalar,
+    ];
+
+    /// Whether the implementation is compiled in and supported by the current CPU
+    pub fn is_supported(self) -> bool {
+        match self {
+            SimdImplementation::Auto | SimdImplementation::Scalar => true,
+            SimdImplementation::Sse => {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    is_x86_feature_detected!("sse")
+                }
+                #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                {
+                    false
+                }
+            }
+            SimdImplementation::Avx => {
+                #[cfg(target_arch = "x86_64")]
+                {
+                    is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma")
+                }
+                #[cfg(not(target_arch = "x86_64"))]
+                {
+                    false
+                }
+            }
+            SimdImplementation::Avx512 => {
+                #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+                {
+                    is_x86_feature_detected!("avx512f")
+                }
+                #[cfg(not(all(target_arch = "x86_64", feature = "avx512")))]
+                {
+                    false
+                }
+            }
+            SimdImplementation::Neon => {
+                #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                {
+                    std::arch::is_aarch64_feature_detected!("neon")
+                }
+                #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+                {
+                    false
+                }
+            }
+        }
+    }
+}
+
+type SimilarityFn = fn(&[VectorElementType], &[VectorElementType]) -> ScoreType;
+type PreprocessFn = fn(&[VectorElementType]) -> Option<Vec<VectorElementType>>;
+type HalfToFloatFn = fn(&[VectorElementTypeHalf], &mut [VectorElementType]);
+type Uint8SimilarityFn = fn(&[VectorElementTypeByte], &[VectorElementTypeByte]) -> u32;
+
+/// Half precision and byte vectors are converted and scored against float queries
+/// by chunks of this size
+const CONVERSION_CHUNK_SIZE: usize = 256;
+
+/// Kernels of a single implementation
+#[derive(Copy, Clone)]
+struct KernelSet {
+    implementation: SimdImplementation,
+    /// Shorter vectors are passed to the next kernel set, SIMD doesn't pay off for them
+    min_dim: usize,
+    dot: SimilarityFn,
+    euclid: SimilarityFn,
+    cosine_preprocess: PreprocessFn,
+}
+
+// Wrappers of the kernels, which require CPU features.
+// They are only selected, once the features are detected, see `SimdImplementation::is_supported`.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse {
+    use super::super::simple_sse::*;
+    use super::*;
+
+    pub(super) fn dot(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { dot_similarity_sse(v1, v2) }
+    }
+
+    pub(super) fn euclid(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { euclid_similarity_sse(v1, v2) }
+    }
+
+    pub(super) fn cosine(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        unsafe { cosine_preprocess_sse(vector) }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use super::super::simple_avx::*;
+    use super::*;
+
+    pub(super) fn dot(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { dot_similarity_avx(v1, v2) }
+    }
+
+    pub(super) fn euclid(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { euclid_similarity_avx(v1, v2) }
+    }
+
+    pub(super) fn cosine(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        unsafe { cosine_preprocess_avx(vector) }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+mod avx512 {
+    use super::super::simple_avx512::*;
+    use super::*;
+
+    pub(super) fn dot(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { dot_similarity_avx512(v1, v2) }
+    }
+
+    pub(super) fn euclid(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { euclid_similarity_avx512(v1, v2) }
+    }
+
+    pub(super) fn cosine(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        unsafe { cosine_preprocess_avx512(vector) }
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    use super::super::simple_neon::*;
+    use super::*;
+
+    pub(super) fn dot(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { dot_similarity_neon(v1, v2) }
+    }
+
+    pub(super) fn euclid(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        unsafe { euclid_similarity_neon(v1, v2) }
+    }
+
+    pub(super) fn cosine(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        unsafe { cosine_preprocess_neon(vector) }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod f16c {
+    use std::arch::x86_64::*;
+
+    use super::*;
+
+    #[target_feature(enable = "avx")]
+    #[target_feature(enable = "f16c")]
+    unsafe fn half_to_float_f16c(src: &[VectorElementTypeHalf], dst: &mut [VectorElementType]) {
+        debug_assert_eq!(src.len(), dst.len());
+        let n = src.len();
+        let m = n - (n % 8);
+        let ptr_src = src.as_ptr() as *const __m128i;
+        let ptr_dst = dst.as_mut_ptr();
+        let mut i: usize = 0;
+        while i < m {
+            let half = _mm_loadu_si128(ptr_src.add(i / 8));
+            _mm256_storeu_ps(ptr_dst.add(i), _mm256_cvtph_ps(half));
+            i += 8;
+        }
+        for (dst, src) in dst[m..].iter_mut().zip(&src[m..]) {
+            *dst = src.to_f32();
+        }
+    }
+
+    pub(super) fn is_supported() -> bool {
+        is_x86_feature_detected!("avx") && is_x86_feature_detected!("f16c")
+    }
+
+    pub(super) fn half_to_float(src: &[VectorElementTypeHalf], dst: &mut [VectorElementType]) {
+        unsafe { half_to_float_f16c(src, dst) }
+    }
+}
+
+fn half_to_float_scalar(src: &[VectorElementTypeHalf], dst: &mut [VectorElementType]) {
+    for (dst, src) in dst.iter_mut().zip(src) {
+        *dst = src.to_f32();
+    }
+}
+
+fn uint8_to_float(src: &[VectorElementTypeByte], dst: &mut [VectorElementType]) {
+    for (dst, &src) in dst.iter_mut().zip(src) {
+        *dst = VectorElementType::from(src);
+    }
+}
+
+/// Integer kernels of byte vectors, return the exact sums
+#[derive(Copy, Clone)]
+struct Uint8KernelSet {
+    dot: Uint8SimilarityFn,
+    euclid: Uint8SimilarityFn,
+}
+
+mod uint8 {
+    use super::super::simple_uint8::*;
+    use super::*;
+
+    pub(super) const SCALAR: Uint8KernelSet = Uint8KernelSet {
+        dot: dot_similarity_uint8,
+        euclid: euclid_similarity_uint8,
+    };
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub(super) const SSE: Uint8KernelSet = Uint8KernelSet {
+        dot: dot_sse,
+        euclid: euclid_sse,
+    };
+
+    #[cfg(target_arch = "x86_64")]
+    pub(super) const AVX2: Uint8KernelSet = Uint8KernelSet {
+        dot: dot_avx2,
+        euclid: euclid_avx2,
+    };
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    pub(super) const NEON: Uint8KernelSet = Uint8KernelSet {
+        dot: dot_neon,
+        euclid: euclid_neon,
+    };
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn dot_sse(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { sse::dot_similarity_uint8_sse(v1, v2) }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn euclid_sse(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { sse::euclid_similarity_uint8_sse(v1, v2) }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn dot_avx2(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { avx2::dot_similarity_uint8_avx2(v1, v2) }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn euclid_avx2(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { avx2::euclid_similarity_uint8_avx2(v1, v2) }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    fn dot_neon(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { neon::dot_similarity_uint8_neon(v1, v2) }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    fn euclid_neon(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+        unsafe { neon::euclid_similarity_uint8_neon(v1, v2) }
+    }
+}
+
+const SCALAR: KernelSet = KernelSet {
+    implementation: SimdImplementation::Scalar,
+    min_dim: 0,
+    dot: dot_similarity,
+    euclid: euclid_similarity,
+    cosine_preprocess,
+};
+
+impl KernelSet {
+    /// Kernels of the explicit implementation, `None` if it is not compiled in
+    fn new(implementation: SimdImplementation) -> Option<Self> {
+        match implementation {
+            SimdImplementation::Auto => None,
+            SimdImplementation::Scalar => Some(SCALAR),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdImplementation::Sse => Some(KernelSet {
+                implementation,
+                min_dim: 16,
+                dot: sse::dot,
+                euclid: sse::euclid,
+                cosine_preprocess: sse::cosine,
+            }),
+            #[cfg(target_arch = "x86_64")]
+            SimdImplementation::Avx => Some(KernelSet {
+                implementation,
+                min_dim: 32,
+                dot: avx::dot,
+                euclid: avx::euclid,
+                cosine_preprocess: avx::cosine,
+            }),
+            #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+            SimdImplementation::Avx512 => Some(KernelSet {
+                implementation,
+                min_dim: 64,
+                dot: avx512::dot,
+                euclid: avx512::euclid,
+                cosine_preprocess: avx512::cosine,
+            }),
+            #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+            SimdImplementation::Neon => Some(KernelSet {
+                implementation,
+                min_dim: 16,
+                dot: neon::dot,
+                euclid: neon::euclid,
+                cosine_preprocess: neon::cosine,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Distance kernels, selected for the current process
+pub struct Kernels {
+    /// Kernel sets in the order of preference, the last one is always scalar
+    sets: Vec<KernelSet>,
+    /// Conversion of half precision vectors, before they are passed to the kernels
+    half_to_float: HalfToFloatFn,
+    /// Kernels of byte vectors, they don't depend on the vector length
+    uint8: Uint8KernelSet,
+}
+
+impl Kernels {
+    /// Select the kernels of the implementation.
+    ///
+    /// `Auto` uses every supported implementation for the vectors, long enough to benefit from it.
+    /// An explicit implementation is used for vectors of any length, falls back to `Auto`,
+    /// if it is not supported.
+    pub fn new(implementation: SimdImplementation) -> Self {
+        let sets = Self::select_sets(implementation);
+        let half_to_float = Self::select_half_to_float(&sets);
+        let uint8 = Self::select_uint8(&sets);
+        Self {
+            sets,
+            half_to_float,
+            uint8,
+        }
+    }
+
+    fn select_sets(implementation: SimdImplementation) -> Vec<KernelSet> {
+        if implementation != SimdImplementation::Auto {
+            match KernelSet::new(implementation).filter(|_| implementation.is_supported()) {
+                Some(set) => return vec![KernelSet { min_dim: 0, ..set }],
+                None => log::warn!(
+                    "{implementation:?} distance implementation is not supported by this CPU or build, using the best available one"
+                ),
+            }
+        }
+
+        SimdImplementation::PREFERENCE
+            .into_iter()
+            .filter(|implementation| implementation.is_supported())
+            .filter_map(KernelSet::new)
+            .collect()
+    }
+
+    /// Hardware conversion of half precision floats is only used along with the AVX kernels,
+    /// so that forcing an older implementation disables it as well
+    fn select_half_to_float(sets: &[KernelSet]) -> HalfToFloatFn {
+        #[cfg(target_arch = "x86_64")]
+        {
+            let avx = sets.iter().any(|set| {
+                matches!(
+                    set.implementation,
+                    SimdImplementation::Avx | SimdImplementation::Avx512
+                )
+            });
+            if avx && f16c::is_supported() {
+                return f16c::half_to_float;
+            }
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        let _ = sets;
+
+        half_to_float_scalar
+    }
+
+    /// Integer kernels follow the float ones: the best of them, allowed by the selected
+    /// implementations and supported by the CPU
+    fn select_uint8(sets: &[KernelSet]) -> Uint8KernelSet {
+        let uses = |implementations: &[SimdImplementation]| {
+            sets.iter()
+                .any(|set| implementations.contains(&set.implementation))
+        };
+        #[cfg(target_arch = "x86_64")]
+        {
+            let avx = uses(&[SimdImplementation::Avx, SimdImplementation::Avx512]);
+            if avx && is_x86_feature_detected!("avx2") {
+                return uint8::AVX2;
+            }
+        }
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            let sse = uses(&[
+                SimdImplementation::Avx,
+                SimdImplementation::Avx512,
+                SimdImplementation::Sse,
+            ]);
+            if sse && is_x86_feature_detected!("sse4.1") {
+                return uint8::SSE;
+            }
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if uses(&[SimdImplementation::Neon]) {
+                return uint8::NEON;
+            }
+        }
+        #[cfg(not(any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "aarch64", target_feature = "neon")
+        )))]
+        let _ = uses;
+
+        uint8::SCALAR
+    }
+
+    /// Implementations in use, from the one for the longest vectors to the one for the shortest
+    pub fn implementations(&self) -> Vec<SimdImplementation> {
+        self.sets.iter().map(|set| set.implementation).collect()
+    }
+
+    #[inline]
+    fn select(&self, dim: usize) -> &KernelSet {
+        self.sets
+            .iter()
+            .find(|set| dim >= set.min_dim)
+            .unwrap_or(&SCALAR)
+    }
+
+    #[inline]
+    pub fn dot(&self, v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        (self.select(v1.len()).dot)(v1, v2)
+    }
+
+    #[inline]
+    pub fn euclid(&self, v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        (self.select(v1.len()).euclid)(v1, v2)
+    }
+
+    #[inline]
+    pub fn cosine_preprocess(
+        &self,
+        vector: &[VectorElementType],
+    ) -> Option<Vec<VectorElementType>> {
+        (self.select(vector.len()).cosine_preprocess)(vector)
+    }
+
+    /// Score the query against a vector of another element type.
+    ///
+    /// The vector is converted by chunks into a buffer on stack, each chunk is scored
+    /// with the kernel of the implementation in use.
+    #[inline]
+    fn score_converted<T>(
+        &self,
+        v1: &[VectorElementType],
+        v2: &[T],
+        convert: impl Fn(&[T], &mut [VectorElementType]),
+        kernel: impl Fn(&KernelSet) -> SimilarityFn,
+    ) -> ScoreType {
+        let mut buffer = [0.0; CONVERSION_CHUNK_SIZE];
+        v1.chunks(CONVERSION_CHUNK_SIZE)
+            .zip(v2.chunks(CONVERSION_CHUNK_SIZE))
+            .map(|(chunk1, chunk2)| {
+                let converted = &mut buffer[..chunk2.len()];
+                convert(chunk2, converted);
+                kernel(self.select(chunk1.len()))(chunk1, converted)
+            })
+            .sum()
+    }
+
+    #[inline]
+    pub fn dot_half(&self, v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        self.score_converted(v1, v2, self.half_to_float, |set| set.dot)
+    }
+
+    #[inline]
+    pub fn euclid_half(&self, v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        self.score_converted(v1, v2, self.half_to_float, |set| set.euclid)
+    }
+
+    #[inline]
+    pub fn dot_float_uint8(
+        &self,
+        v1: &[VectorElementType],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        self.score_converted(v1, v2, uint8_to_float, |set| set.dot)
+    }
+
+    #[inline]
+    pub fn euclid_float_uint8(
+        &self,
+        v1: &[VectorElementType],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        self.score_converted(v1, v2, uint8_to_float, |set| set.euclid)
+    }
+
+    /// Cosine similarity of the normalized query and a byte vector, which is stored as is
+    #[inline]
+    pub fn cosine_float_uint8(
+        &self,
+        v1: &[VectorElementType],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        let norm = self.norm_uint8(v2);
+        if norm < ScoreType::EPSILON {
+            return 0.0;
+        }
+        self.dot_float_uint8(v1, v2) / norm
+    }
+
+    #[inline]
+    pub fn dot_uint8(
+        &self,
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        (self.uint8.dot)(v1, v2) as ScoreType
+    }
+
+    #[inline]
+    pub fn euclid_uint8(
+        &self,
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        -((self.uint8.euclid)(v1, v2) as ScoreType)
+    }
+
+    #[inline]
+    pub fn cosine_uint8(
+        &self,
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        let norms = self.norm_uint8(v1) * self.norm_uint8(v2);
+        if norms < ScoreType::EPSILON {
+            return 0.0;
+        }
+        self.dot_uint8(v1, v2) / norms
+    }
+
+    #[inline]
+    fn norm_uint8(&self, vector: &[VectorElementTypeByte]) -> ScoreType {
+        self.dot_uint8(vector, vector).sqrt()
+    }
+}
+
+/// Select the distance kernels for the whole process.
+///
+/// Should be called before any distance is computed, the kernels can't be changed afterwards.
+/// Without the call, the best supported implementation is used.
+pub fn set_global(implementation: SimdImplementation) {
+    let mut selected = false;
+    let kernels = KERNELS.get_or_init(|| {
+        selected = true;
+        Kernels::new(implementation)
+    });
+    if selected {
+        log::debug!("Distance implementations: {:?}", kernels.implementations());
+    } else {
+        log::warn!("Distance implementation is already selected, {implementation:?} is ignored");
+    }
+}
+
+/// Distance kernels of the process
+#[inline]
+pub fn get_global() -> &'static Kernels {
+    KERNELS.get_or_init(|| Kernels::new(SimdImplementation::Auto))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32, implementation: SimdImplementation, dim: usize) {
+        let tolerance = 1e-4 * expected.abs().max(1.0);
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{implementation:?}, dim {dim}: {actual} != {expected}",
+        );
+    }
+
+    #[test]
+    fn test_kernels_match_scalar() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let implementations: Vec<_> = SimdImplementation::PREFERENCE
+            .into_iter()
+            .filter(|implementation| implementation.is_supported())
+            .collect();
+        // Scalar kernels are the fallback on any CPU
+        assert_eq!(implementations.last(), Some(&SimdImplementation::Scalar));
+
+        // Lengths, which are not multiples of the lanes, exercise the tails of the kernels
+        for dim in 1..=200 {
+            let v1: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let v2: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+
+            let dot = dot_similarity(&v1, &v2);
+            let euclid = euclid_similarity(&v1, &v2);
+            let cosine = cosine_preprocess(&v1);
+
+            for &implementation in &implementations {
+                let kernels = Kernels::new(implementation);
+                assert_eq!(kernels.implementations(), vec![implementation]);
+
+                assert_close(kernels.dot(&v1, &v2), dot, implementation, dim);
+                assert_close(kernels.euclid(&v1, &v2), euclid, implementation, dim);
+
+                let simd_cosine = kernels.cosine_preprocess(&v1);
+                assert_eq!(simd_cosine.is_some(), cosine.is_some());
+                for (actual, expected) in simd_cosine.iter().flatten().zip(cosine.iter().flatten())
+                {
+                    assert_close(*actual, *expected, implementation, dim);
+                }
+            }
+
+            let auto = Kernels::new(SimdImplementation::Auto);
+            assert_close(auto.dot(&v1, &v2), dot, SimdImplementation::Auto, dim);
+            assert_close(auto.euclid(&v1, &v2), euclid, SimdImplementation::Auto, dim);
+        }
+    }
+
+    #[test]
+    fn test_half_kernels_match_scalar() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut implementations: Vec<_> = SimdImplementation::PREFERENCE
+            .into_iter()
+            .filter(|implementation| implementation.is_supported())
+            .collect();
+        implementations.push(SimdImplementation::Auto);
+
+        // Vectors longer than a chunk are scored by parts
+        for dim in (1..=200).chain([CONVERSION_CHUNK_SIZE, CONVERSION_CHUNK_SIZE + 1, 1000]) {
+            let v1: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let v2: Vec<VectorElementTypeHalf> = (0..dim)
+                .map(|_| VectorElementTypeHalf::from_f32(rng.gen_range(-1.0..1.0)))
+                .collect();
+            let v2_float: Vec<f32> = v2.iter().map(|x| x.to_f32()).collect();
+
+            let dot = dot_similarity(&v1, &v2_float);
+            let euclid = euclid_similarity(&v1, &v2_float);
+
+            for &implementation in &implementations {
+                let kernels = Kernels::new(implementation);
+                assert_close(kernels.dot_half(&v1, &v2), dot, implementation, dim);
+                assert_close(kernels.euclid_half(&v1, &v2), euclid, implementation, dim);
+
+                let mut converted = vec![0.0; dim];
+                (kernels.half_to_float)(&v2, &mut converted);
+                assert_eq!(converted, v2_float);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint8_kernels_match_scalar() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut implementations: Vec<_> = SimdImplementation::PREFERENCE
+            .into_iter()
+            .filter(|implementation| implementation.is_supported())
+            .collect();
+        implementations.push(SimdImplementation::Auto);
+
+        for dim in (1..=200).chain([CONVERSION_CHUNK_SIZE + 1, 1000, 65536]) {
+            let v1: Vec<VectorElementTypeByte> = (0..dim).map(|_| rng.gen()).collect();
+            let v2: Vec<VectorElementTypeByte> = (0..dim).map(|_| rng.gen()).collect();
+            let v1_float: Vec<f32> = v1.iter().map(|&x| f32::from(x)).collect();
+            let v2_float: Vec<f32> = v2.iter().map(|&x| f32::from(x)).collect();
+
+            let dot = dot_similarity_uint8(&v1, &v2);
+            let euclid = euclid_similarity_uint8(&v1, &v2);
+            let query = cosine_preprocess(&v1_float).unwrap_or_else(|| v1_float.clone());
+            let cosine = dot_similarity(&query, &cosine_preprocess(&v2_float).unwrap_or(v2_float));
+
+            for &implementation in &implementations {
+                let kernels = Kernels::new(implementation);
+                // Integer arithmetic is exact
+                assert_eq!(
+                    (kernels.uint8.dot)(&v1, &v2),
+                    dot,
+                    "{implementation:?}, dim {dim}"
+                );
+                assert_eq!(
+                    (kernels.uint8.euclid)(&v1, &v2),
+                    euclid,
+                    "{implementation:?}, dim {dim}",
+                );
+
+                assert_close(
+                    kernels.dot_float_uint8(&v1_float, &v2),
+                    dot as f32,
+                    implementation,
+                    dim,
+                );
+                assert_close(
+                    kernels.euclid_float_uint8(&v1_float, &v2),
+                    -(euclid as f32),
+                    implementation,
+                    dim,
+                );
+                assert_close(kernels.cosine_uint8(&v1, &v2), cosine, implementation, dim);
+                assert_close(
+                    kernels.cosine_float_uint8(&query, &v2),
+                    cosine,
+                    implementation,
+                    dim,
+                );
+            }
+        }
+
+        let zero = vec![0; 100];
+        let kernels = Kernels::new(SimdImplementation::Auto);
+        assert_eq!(kernels.cosine_uint8(&zero, &zero), 0.0);
+        assert_eq!(kernels.cosine_float_uint8(&[0.1; 100], &zero), 0.0);
+    }
+
+    #[test]
+    fn test_auto_kernels() {
+        let kernels = Kernels::new(SimdImplementation::Auto);
+        let implementations = kernels.implementations();
+        assert_eq!(implementations.last(), Some(&SimdImplementation::Scalar));
+        assert!(implementations
+            .iter()
+            .all(|implementation| implementation.is_supported()));
+
+        // Unsupported implementation falls back to the auto selection
+        let unsupported = SimdImplementation::PREFERENCE
+            .into_iter()
+            .find(|implementation| !implementation.is_supported());
+        if let Some(unsupported) = unsupported {
+            assert_eq!(Kernels::new(unsupported).implementations(), implementations);
+        }
+    }
+}
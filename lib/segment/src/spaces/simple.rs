@@ -1,23 +1,8 @@
 use super::metric::Metric;
-#[cfg(target_arch = "x86_64")]
-use super::simple_avx::*;
-#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-use super::simple_neon::*;
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use super::simple_sse::*;
-use crate::data_types::vectors::VectorElementType;
+use super::simd;
+use crate::data_types::vectors::{VectorElementType, VectorElementTypeByte, VectorElementTypeHalf};
 use crate::types::{Distance, ScoreType};
 
-#[cfg(target_arch = "x86_64")]
-const MIN_DIM_SIZE_AVX: usize = 32;
-
-#[cfg(any(
-    target_arch = "x86",
-    target_arch = "x86_64",
-    all(target_arch = "aarch64", target_feature = "neon")
-))]
-const MIN_DIM_SIZE_SIMD: usize = 16;
-
 #[derive(Clone)]
 pub struct DotProductMetric {}
 
@@ -33,31 +18,22 @@ impl Metric for EuclidMetric {
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { euclid_similarity_avx(v1, v2) };
-            }
-        }
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { euclid_similarity_sse(v1, v2) };
-            }
-        }
-
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { euclid_similarity_neon(v1, v2) };
-            }
-        }
-
-        euclid_similarity(v1, v2)
+        simd::get_global().euclid(v1, v2)
+    }
+
+    fn similarity_half(v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        simd::get_global().euclid_half(v1, v2)
+    }
+
+    fn similarity_uint8(v1: &[VectorElementType], v2: &[VectorElementTypeByte]) -> ScoreType {
+        simd::get_global().euclid_float_uint8(v1, v2)
+    }
+
+    fn similarity_uint8_internal(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        simd::get_global().euclid_uint8(v1, v2)
     }
 
     fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
@@ -75,31 +51,22 @@ impl Metric for DotProductMetric {
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { dot_similarity_avx(v1, v2) };
-            }
-        }
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_sse(v1, v2) };
-            }
-        }
-
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_neon(v1, v2) };
-            }
-        }
-
-        dot_similarity(v1, v2)
+        simd::get_global().dot(v1, v2)
+    }
+
+    fn similarity_half(v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        simd::get_global().dot_half(v1, v2)
+    }
+
+    fn similarity_uint8(v1: &[VectorElementType], v2: &[VectorElementTypeByte]) -> ScoreType {
+        simd::get_global().dot_float_uint8(v1, v2)
+    }
+
+    fn similarity_uint8_internal(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        simd::get_global().dot_uint8(v1, v2)
     }
 
     fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
@@ -117,60 +84,26 @@ impl Metric for CosineMetric {
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { dot_similarity_avx(v1, v2) };
-            }
-        }
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_sse(v1, v2) };
-            }
-        }
-
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_neon(v1, v2) };
-            }
-        }
-
-        dot_similarity(v1, v2)
+        simd::get_global().dot(v1, v2)
+    }
+
+    fn similarity_half(v1: &[VectorElementType], v2: &[VectorElementTypeHalf]) -> ScoreType {
+        simd::get_global().dot_half(v1, v2)
+    }
+
+    fn similarity_uint8(v1: &[VectorElementType], v2: &[VectorElementTypeByte]) -> ScoreType {
+        simd::get_global().cosine_float_uint8(v1, v2)
+    }
+
+    fn similarity_uint8_internal(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> ScoreType {
+        simd::get_global().cosine_uint8(v1, v2)
     }
 
     fn preprocess(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && vector.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { cosine_preprocess_avx(vector) };
-            }
-        }
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && vector.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { cosine_preprocess_sse(vector) };
-            }
-        }
-
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && vector.len() >= MIN_DIM_SIZE_SIMD
-            {
-                return unsafe { cosine_preprocess_neon(vector) };
-            }
-        }
-
-        cosine_preprocess(vector)
+        simd::get_global().cosine_preprocess(vector)
     }
 
     fn postprocess(score: ScoreType) -> ScoreType {
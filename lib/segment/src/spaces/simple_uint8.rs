@@ -0,0 +1,197 @@
+//! Integer kernels for byte vectors.
+//!
+//! Products of bytes are accumulated in 32-bit integers, which can't overflow for the vectors
+//! of up to 65536 dimensions, the largest size allowed by the API.
+
+use crate::data_types::vectors::VectorElementTypeByte;
+
+pub fn dot_similarity_uint8(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+    v1.iter()
+        .zip(v2)
+        .map(|(&a, &b)| u32::from(a) * u32::from(b))
+        .sum()
+}
+
+pub fn euclid_similarity_uint8(v1: &[VectorElementTypeByte], v2: &[VectorElementTypeByte]) -> u32 {
+    v1.iter()
+        .zip(v2)
+        .map(|(&a, &b)| {
+            let diff = u32::from(a.abs_diff(b));
+            diff * diff
+        })
+        .sum()
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod sse {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use super::*;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn hsum_epi32(sum: __m128i) -> u32 {
+        let mut lanes = [0u32; 4];
+        _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, sum);
+        lanes.iter().sum()
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    pub(crate) unsafe fn dot_similarity_uint8_sse(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 16);
+        let mut sum = _mm_setzero_si128();
+        let mut i: usize = 0;
+        while i < m {
+            let a = _mm_loadu_si128(v1.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(v2.as_ptr().add(i) as *const __m128i);
+            let a_lo = _mm_cvtepu8_epi16(a);
+            let b_lo = _mm_cvtepu8_epi16(b);
+            let a_hi = _mm_cvtepu8_epi16(_mm_srli_si128(a, 8));
+            let b_hi = _mm_cvtepu8_epi16(_mm_srli_si128(b, 8));
+            sum = _mm_add_epi32(sum, _mm_madd_epi16(a_lo, b_lo));
+            sum = _mm_add_epi32(sum, _mm_madd_epi16(a_hi, b_hi));
+            i += 16;
+        }
+        hsum_epi32(sum) + dot_similarity_uint8(&v1[m..], &v2[m..])
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    pub(crate) unsafe fn euclid_similarity_uint8_sse(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 16);
+        let mut sum = _mm_setzero_si128();
+        let mut i: usize = 0;
+        while i < m {
+            let a = _mm_loadu_si128(v1.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(v2.as_ptr().add(i) as *const __m128i);
+            let diff_lo = _mm_sub_epi16(_mm_cvtepu8_epi16(a), _mm_cvtepu8_epi16(b));
+            let diff_hi = _mm_sub_epi16(
+                _mm_cvtepu8_epi16(_mm_srli_si128(a, 8)),
+                _mm_cvtepu8_epi16(_mm_srli_si128(b, 8)),
+            );
+            sum = _mm_add_epi32(sum, _mm_madd_epi16(diff_lo, diff_lo));
+            sum = _mm_add_epi32(sum, _mm_madd_epi16(diff_hi, diff_hi));
+            i += 16;
+        }
+        hsum_epi32(sum) + euclid_similarity_uint8(&v1[m..], &v2[m..])
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::*;
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn hsum_epi32(sum: __m256i) -> u32 {
+        let mut lanes = [0u32; 8];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+        lanes.iter().sum()
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(crate) unsafe fn dot_similarity_uint8_avx2(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 32);
+        let mut sum = _mm256_setzero_si256();
+        let mut i: usize = 0;
+        while i < m {
+            let a = _mm256_loadu_si256(v1.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_loadu_si256(v2.as_ptr().add(i) as *const __m256i);
+            let a_lo = _mm256_cvtepu8_epi16(_mm256_castsi256_si128(a));
+            let b_lo = _mm256_cvtepu8_epi16(_mm256_castsi256_si128(b));
+            let a_hi = _mm256_cvtepu8_epi16(_mm256_extracti128_si256(a, 1));
+            let b_hi = _mm256_cvtepu8_epi16(_mm256_extracti128_si256(b, 1));
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(a_lo, b_lo));
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(a_hi, b_hi));
+            i += 32;
+        }
+        hsum_epi32(sum) + dot_similarity_uint8(&v1[m..], &v2[m..])
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(crate) unsafe fn euclid_similarity_uint8_avx2(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 32);
+        let mut sum = _mm256_setzero_si256();
+        let mut i: usize = 0;
+        while i < m {
+            let a = _mm256_loadu_si256(v1.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_loadu_si256(v2.as_ptr().add(i) as *const __m256i);
+            let diff_lo = _mm256_sub_epi16(
+                _mm256_cvtepu8_epi16(_mm256_castsi256_si128(a)),
+                _mm256_cvtepu8_epi16(_mm256_castsi256_si128(b)),
+            );
+            let diff_hi = _mm256_sub_epi16(
+                _mm256_cvtepu8_epi16(_mm256_extracti128_si256(a, 1)),
+                _mm256_cvtepu8_epi16(_mm256_extracti128_si256(b, 1)),
+            );
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(diff_lo, diff_lo));
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(diff_hi, diff_hi));
+            i += 32;
+        }
+        hsum_epi32(sum) + euclid_similarity_uint8(&v1[m..], &v2[m..])
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+pub mod neon {
+    use std::arch::aarch64::*;
+
+    use super::*;
+
+    #[target_feature(enable = "neon")]
+    pub(crate) unsafe fn dot_similarity_uint8_neon(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 16);
+        let mut sum = vdupq_n_u32(0);
+        let mut i: usize = 0;
+        while i < m {
+            let a = vld1q_u8(v1.as_ptr().add(i));
+            let b = vld1q_u8(v2.as_ptr().add(i));
+            sum = vpadalq_u16(sum, vmull_u8(vget_low_u8(a), vget_low_u8(b)));
+            sum = vpadalq_u16(sum, vmull_u8(vget_high_u8(a), vget_high_u8(b)));
+            i += 16;
+        }
+        vaddvq_u32(sum) + dot_similarity_uint8(&v1[m..], &v2[m..])
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(crate) unsafe fn euclid_similarity_uint8_neon(
+        v1: &[VectorElementTypeByte],
+        v2: &[VectorElementTypeByte],
+    ) -> u32 {
+        let n = v1.len();
+        let m = n - (n % 16);
+        let mut sum = vdupq_n_u32(0);
+        let mut i: usize = 0;
+        while i < m {
+            let diff = vabdq_u8(vld1q_u8(v1.as_ptr().add(i)), vld1q_u8(v2.as_ptr().add(i)));
+            sum = vpadalq_u16(sum, vmull_u8(vget_low_u8(diff), vget_low_u8(diff)));
+            sum = vpadalq_u16(sum, vmull_u8(vget_high_u8(diff), vget_high_u8(diff)));
+            i += 16;
+        }
+        vaddvq_u32(sum) + euclid_similarity_uint8(&v1[m..], &v2[m..])
+    }
+}
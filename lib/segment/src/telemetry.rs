@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::common::anonymize::Anonymize;
 use crate::common::operation_time_statistics::OperationDurationStatistics;
-use crate::types::{PayloadIndexInfo, SegmentConfig, SegmentInfo, VectorDataConfig};
+use crate::types::{
+    PayloadIndexInfo, SegmentConfig, SegmentInfo, SparseVectorDataConfig, VectorDataConfig,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct VectorIndexesTelemetry {
@@ -16,6 +20,29 @@ pub struct SegmentTelemetry {
     pub config: SegmentConfig,
     pub vector_index_searches: Vec<VectorIndexSearchesTelemetry>,
     pub payload_field_indices: Vec<PayloadIndexTelemetry>,
+    /// Approximate RAM, occupied by the payload fields, cached along with the on-disk payload
+    #[serde(default)]
+    pub payload_cache_ram_bytes: usize,
+    /// Health of the HNSW graph of each indexed vector. Only collected on request,
+    /// since it requires a scan of the whole graph
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_health: Option<HashMap<String, HnswGraphHealth>>,
+}
+
+/// Degradation of the HNSW graph, caused by points deleted after it was built
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+pub struct HnswGraphHealth {
+    /// Fraction of the links of available points on all layers, which point to deleted points
+    pub deleted_links_ratio: f64,
+    /// Average number of links of a node on each layer, starting from the bottom one
+    pub avg_out_degree: Vec<f64>,
+    /// The top entry point of the graph is deleted, searches start from a fallback point
+    pub entry_point_deleted: bool,
+    /// Number of available points, checked for reachability from the entry point
+    pub sampled_points: usize,
+    /// Number of sampled points, which can't be reached from the entry point
+    /// on the bottom layer through the available points
+    pub disconnected_points: usize,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -27,13 +54,49 @@ pub struct PayloadIndexTelemetry {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub histogram_bucket_size: Option<usize>,
+
+    /// Number of removed or overwritten values, which are still occupying index storage
+    #[serde(default)]
+    pub obsolete_values_count: usize,
+
+    /// Approximate size of the index in RAM, without the overhead of the containers
+    #[serde(default)]
+    pub estimated_ram_bytes: usize,
+
+    /// The persisted index is corrupted. The field is served without the index,
+    /// until the index is rebuilt by the optimizer
+    #[serde(default)]
+    pub needs_rebuild: bool,
+
+    /// The index is served from disk, only the statistics of the values are kept in RAM
+    #[serde(default)]
+    pub on_disk: bool,
 }
 
 impl PayloadIndexTelemetry {
+    /// Telemetry of the field, which index failed to load and has to be rebuilt
+    pub fn needs_rebuild(field_name: String) -> Self {
+        PayloadIndexTelemetry {
+            field_name: Some(field_name),
+            points_values_count: 0,
+            points_count: 0,
+            histogram_bucket_size: None,
+            obsolete_values_count: 0,
+            estimated_ram_bytes: 0,
+            needs_rebuild: true,
+            on_disk: false,
+        }
+    }
+
     pub fn set_name(mut self, name: String) -> Self {
         self.field_name = Some(name);
         self
     }
+
+    pub fn set_obsolete_values_count(mut self, obsolete_values_count: usize) -> Self {
+        self.obsolete_values_count = obsolete_values_count;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default)]
@@ -70,6 +133,8 @@ impl Anonymize for SegmentTelemetry {
             config: self.config.anonymize(),
             vector_index_searches: self.vector_index_searches.anonymize(),
             payload_field_indices: self.payload_field_indices.anonymize(),
+            payload_cache_ram_bytes: self.payload_cache_ram_bytes,
+            graph_health: self.graph_health.clone(),
         }
     }
 }
@@ -99,6 +164,8 @@ impl Anonymize for PayloadIndexInfo {
             data_type: self.data_type,
             params: self.params.clone(),
             points: self.points.anonymize(),
+            values_count: self.values_count.anonymize(),
+            estimated_ram_bytes: self.estimated_ram_bytes.anonymize(),
         }
     }
 }
@@ -107,11 +174,19 @@ impl Anonymize for SegmentConfig {
     fn anonymize(&self) -> Self {
         SegmentConfig {
             vector_data: self.vector_data.anonymize(),
+            sparse_vector_data: self.sparse_vector_data.anonymize(),
             payload_storage_type: self.payload_storage_type,
+            payload_cache_fields: self.payload_cache_fields.anonymize(),
         }
     }
 }
 
+impl Anonymize for SparseVectorDataConfig {
+    fn anonymize(&self) -> Self {
+        *self
+    }
+}
+
 impl Anonymize for VectorDataConfig {
     fn anonymize(&self) -> Self {
         VectorDataConfig {
@@ -120,6 +195,7 @@ impl Anonymize for VectorDataConfig {
             storage_type: self.storage_type,
             index: self.index.clone(),
             quantization_config: None,
+            datatype: self.datatype,
         }
     }
 }
@@ -146,6 +222,10 @@ impl Anonymize for PayloadIndexTelemetry {
             points_count: self.points_count.anonymize(),
             points_values_count: self.points_values_count.anonymize(),
             histogram_bucket_size: self.histogram_bucket_size,
+            obsolete_values_count: self.obsolete_values_count.anonymize(),
+            estimated_ram_bytes: self.estimated_ram_bytes.anonymize(),
+            needs_rebuild: self.needs_rebuild,
+            on_disk: self.on_disk,
         }
     }
 }
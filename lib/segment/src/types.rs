@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Formatter;
@@ -7,6 +8,7 @@ use std::ops::Deref;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use geo::prelude::HaversineDistance;
 use geo::{Contains, Coord, Point, Polygon};
 use itertools::Itertools;
@@ -15,14 +17,16 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use uuid::Uuid;
-use validator::{Validate, ValidationErrors};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 use crate::common::utils;
 use crate::common::utils::MultiValue;
+use crate::data_types::keyword_index::KeywordIndexParams;
 use crate::data_types::text_index::TextIndexParams;
 use crate::data_types::vectors::{VectorElementType, VectorStruct};
 use crate::spaces::metric::Metric;
 use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::telemetry::PayloadIndexTelemetry;
 
 /// Type of point index inside a segment
 pub type PointOffsetType = u32;
@@ -176,6 +180,12 @@ pub struct ScoredPoint {
     pub payload: Option<Payload>,
     /// Vector of the point
     pub vector: Option<VectorStruct>,
+    /// Set if the payload is truncated to fit into `max_payload_return_bytes`
+    #[serde(flatten)]
+    pub payload_truncation: Option<PayloadTruncation>,
+    /// Dimensions of the vector, if requested with the slice `with_vector` selector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_slice: Option<VectorSlice>,
 }
 
 impl Eq for ScoredPoint {}
@@ -220,25 +230,61 @@ pub struct PayloadIndexInfo {
     pub params: Option<PayloadSchemaParams>,
     /// Number of points indexed with this index
     pub points: usize,
+    /// Number of indexed values. A point with several values of the field is counted once per value
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values_count: Option<usize>,
+    /// Approximate RAM, occupied by the indexes of the field, in bytes.
+    /// Indexes, served from disk, only keep the statistics of the values in RAM.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_ram_bytes: Option<usize>,
 }
 
 impl PayloadIndexInfo {
     pub fn new(field_type: PayloadFieldSchema, points_count: usize) -> Self {
-        match field_type {
-            PayloadFieldSchema::FieldType(data_type) => PayloadIndexInfo {
-                data_type,
-                params: None,
-                points: points_count,
-            },
+        let (data_type, params) = match field_type {
+            PayloadFieldSchema::FieldType(data_type) => (data_type, None),
             PayloadFieldSchema::FieldParams(schema_params) => match schema_params {
-                PayloadSchemaParams::Text(_) => PayloadIndexInfo {
-                    data_type: PayloadSchemaType::Text,
-                    params: Some(schema_params),
-                    points: points_count,
-                },
+                PayloadSchemaParams::Keyword(_) => {
+                    (PayloadSchemaType::Keyword, Some(schema_params))
+                }
+                PayloadSchemaParams::Text(_) => (PayloadSchemaType::Text, Some(schema_params)),
             },
+        };
+        PayloadIndexInfo {
+            data_type,
+            params,
+            points: points_count,
+            values_count: None,
+            estimated_ram_bytes: None,
         }
     }
+
+    /// Account one of the indexes of the field in a segment.
+    /// All indexes of the field contain the same values, so the values are counted once.
+    pub fn add_index(&mut self, telemetry: &PayloadIndexTelemetry) {
+        self.values_count = Some(
+            self.values_count
+                .unwrap_or(0)
+                .max(telemetry.points_values_count),
+        );
+        self.estimated_ram_bytes =
+            Some(self.estimated_ram_bytes.unwrap_or(0) + telemetry.estimated_ram_bytes);
+    }
+
+    /// Sum up the information about the same field in different segments or shards
+    pub fn merge(&mut self, other: PayloadIndexInfo) {
+        fn sum(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+            match (a, b) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+            }
+        }
+        self.points += other.points;
+        self.values_count = sum(self.values_count, other.values_count);
+        self.estimated_ram_bytes = sum(self.estimated_ram_bytes, other.estimated_ram_bytes);
+    }
 }
 
 /// Aggregated information about segment
@@ -293,6 +339,113 @@ pub const fn default_quantization_oversampling_value() -> Option<f64> {
     None
 }
 
+pub const DEFAULT_ADAPTIVE_EF_SELECTIVITY_THRESHOLD: f64 = 0.1;
+pub const DEFAULT_ADAPTIVE_EF_MAX_FACTOR: usize = 16;
+
+/// Parameters of `ef` scaling for selective filters in HNSW search
+///
+/// The more selective the filter is, the fewer suitable candidates the graph search meets,
+/// so `ef` is increased proportionally to how much the filter selectivity is below the threshold.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct AdaptiveEfParams {
+    /// Fraction of segment points, matching the filter, below which `ef` is scaled.
+    /// Default is 0.1
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub selectivity_threshold: Option<f64>,
+
+    /// Upper limit of the scaled `ef`.
+    /// Default is 16 times the original `ef`
+    #[validate(range(min = 1))]
+    pub max_ef: Option<usize>,
+
+    /// Fraction of segment points, matching the filter, below which exact filtered scoring is used
+    /// instead of HNSW graph search.
+    /// If not set, only `full_scan_threshold` of HNSW config is considered
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub exact_selectivity_threshold: Option<f64>,
+}
+
+impl AdaptiveEfParams {
+    /// `ef` to use for a filter, which matches `selectivity` fraction of points.
+    ///
+    /// Returns `None` if the filter is not selective enough to scale `ef`.
+    pub fn scaled_ef(&self, ef: usize, selectivity: f64) -> Option<usize> {
+        let threshold = self
+            .selectivity_threshold
+            .unwrap_or(DEFAULT_ADAPTIVE_EF_SELECTIVITY_THRESHOLD);
+        if selectivity >= threshold {
+            return None;
+        }
+        let max_ef = self
+            .max_ef
+            .unwrap_or_else(|| ef.saturating_mul(DEFAULT_ADAPTIVE_EF_MAX_FACTOR))
+            .max(ef);
+        let scaled_ef = (ef as f64 * threshold / selectivity.max(f64::EPSILON)).ceil();
+        Some((scaled_ef.min(max_ef as f64) as usize).max(ef))
+    }
+
+    /// Whether exact filtered scoring should be used instead of graph search
+    pub fn prefer_exact(&self, selectivity: f64) -> bool {
+        self.exact_selectivity_threshold
+            .map_or(false, |threshold| selectivity < threshold)
+    }
+}
+
+/// Way, in which vector index performs the search
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// Score all points, matching the filter
+    Plain,
+    /// HNSW graph search with configured `ef`
+    Hnsw,
+    /// HNSW graph search with `ef` scaled for a selective filter
+    HnswAdaptiveEf,
+}
+
+/// Debug information about the search execution
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchDebugInfo {
+    /// Number of segment searches, which scored all matching points
+    pub plain_searches: usize,
+    /// Number of segment searches, which used HNSW graph with configured `ef`
+    pub hnsw_searches: usize,
+    /// Number of segment searches, which used HNSW graph with scaled `ef`
+    pub hnsw_adaptive_ef_searches: usize,
+    /// Largest `ef`, used by the HNSW graph searches. Not set, if the graph is not used
+    pub max_ef: Option<usize>,
+}
+
+impl SearchDebugInfo {
+    pub const EMPTY: SearchDebugInfo = SearchDebugInfo {
+        plain_searches: 0,
+        hnsw_searches: 0,
+        hnsw_adaptive_ef_searches: 0,
+        max_ef: None,
+    };
+
+    /// Account `count` searches, done with the `strategy`. `ef` is ignored for plain searches
+    pub fn add_searches(&mut self, strategy: SearchStrategy, ef: usize, count: usize) {
+        match strategy {
+            SearchStrategy::Plain => self.plain_searches += count,
+            SearchStrategy::Hnsw => self.hnsw_searches += count,
+            SearchStrategy::HnswAdaptiveEf => self.hnsw_adaptive_ef_searches += count,
+        }
+        if strategy != SearchStrategy::Plain {
+            self.max_ef = self.max_ef.max(Some(ef));
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.plain_searches += other.plain_searches;
+        self.hnsw_searches += other.hnsw_searches;
+        self.hnsw_adaptive_ef_searches += other.hnsw_adaptive_ef_searches;
+        self.max_ef = self.max_ef.max(other.max_ef);
+    }
+}
+
 /// Additional parameters of the search
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -309,8 +462,36 @@ pub struct SearchParams {
     #[serde(default)]
     #[validate]
     pub quantization: Option<QuantizationSearchParams>,
+
+    /// If enabled, the engine will only perform search among indexed or small segments.
+    /// Using this option prevents slow searches in case of delayed index, but does not
+    /// guarantee that all uploaded vectors will be included in search results
+    #[serde(default)]
+    pub indexed_only: bool,
+
+    /// Scale `ef` of HNSW search for selective filters.
+    /// If not set, configured `ef` is used regardless of the filter selectivity
+    #[serde(default)]
+    #[validate]
+    pub adaptive_ef: Option<AdaptiveEfParams>,
 }
 
+impl std::hash::Hash for SearchParams {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hnsw_ef.hash(state);
+        self.exact.hash(state);
+        self.quantization
+            .map(|quantization| (quantization.ignore, quantization.rescore))
+            .hash(state);
+        self.indexed_only.hash(state);
+        self.adaptive_ef
+            .and_then(|adaptive_ef| adaptive_ef.max_ef)
+            .hash(state);
+    }
+}
+
+impl Eq for SearchParams {}
+
 /// Vector index configuration
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -519,8 +700,14 @@ pub enum PayloadStorageType {
 #[serde(rename_all = "snake_case")]
 pub struct SegmentConfig {
     pub vector_data: HashMap<String, VectorDataConfig>,
+    #[serde(default)]
+    pub sparse_vector_data: HashMap<String, SparseVectorDataConfig>,
     /// Defines payload storage type
     pub payload_storage_type: PayloadStorageType,
+    /// Payload fields, which values are also kept in memory if payload is stored on disk.
+    /// Indexed fields are kept in memory as well
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payload_cache_fields: Vec<PayloadKeyType>,
 }
 
 impl SegmentConfig {
@@ -556,6 +743,15 @@ impl SegmentConfig {
             .values()
             .any(|config| config.storage_type.is_mmap())
     }
+
+    /// Check if new points can be added into the segment
+    ///
+    /// This requires the storages and indexes of all named vectors to support appending.
+    pub fn is_appendable(&self) -> bool {
+        self.vector_data
+            .values()
+            .all(|config| config.storage_type.is_appendable() && !config.index.is_indexed())
+    }
 }
 
 /// Storage types for vectors
@@ -576,6 +772,54 @@ pub enum VectorStorageType {
     ChunkedMmap,
 }
 
+/// Type of the elements, in which vectors are stored
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorStorageDatatype {
+    /// Single precision floats, 4 bytes per element
+    #[default]
+    Float32,
+    /// Half precision floats, 2 bytes per element
+    ///
+    /// Halves the memory usage, with a negligible loss of precision for most embedding models.
+    /// Vectors are converted from and to single precision on the fly.
+    Float16,
+    /// Unsigned bytes, 1 byte per element
+    ///
+    /// For the embeddings, which are already quantized by the model. Only integer values in
+    /// range `0..=255` are accepted, distances are computed with integer arithmetic.
+    Uint8,
+}
+
+impl VectorStorageDatatype {
+    /// Whether the vector element can be stored in this datatype
+    pub fn is_valid_element(self, value: VectorElementType) -> bool {
+        match self {
+            VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16 => true,
+            VectorStorageDatatype::Uint8 => value.fract() == 0.0 && (0.0..=255.0).contains(&value),
+        }
+    }
+
+    /// Whether the vectors of this datatype can be quantized
+    pub fn supports_quantization(self) -> bool {
+        match self {
+            VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16 => true,
+            VectorStorageDatatype::Uint8 => false,
+        }
+    }
+
+    /// Whether the vectors may be preprocessed by the distance before they are stored,
+    /// see [`Distance::preprocess_vector`]
+    pub fn supports_preprocessing(self) -> bool {
+        match self {
+            VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16 => true,
+            // Normalized vectors can't be represented in integers, so cosine kernels of
+            // this datatype normalize on the fly
+            VectorStorageDatatype::Uint8 => false,
+        }
+    }
+}
+
 impl VectorStorageType {
     /// Whether this storage type is a mmap on disk
     fn is_mmap(&self) -> bool {
@@ -584,6 +828,14 @@ impl VectorStorageType {
             Self::Mmap | Self::ChunkedMmap => true,
         }
     }
+
+    /// Whether vectors can be added into this storage type
+    fn is_appendable(&self) -> bool {
+        match self {
+            Self::Memory | Self::ChunkedMmap => true,
+            Self::Mmap => false,
+        }
+    }
 }
 
 /// Config of single vector data storage
@@ -600,17 +852,56 @@ pub struct VectorDataConfig {
     pub index: Indexes,
     /// Vector specific quantization config that overrides collection config
     pub quantization_config: Option<QuantizationConfig>,
+    /// Type of the stored vector elements
+    #[serde(default)]
+    pub datatype: VectorStorageDatatype,
 }
 
 /// Default value based on <https://github.com/google-research/google-research/blob/master/scann/docs/algorithms.md>
 pub const DEFAULT_FULL_SCAN_THRESHOLD: usize = 20_000;
 
+/// Number of sparse vectors below which search scores all of them instead of using the index
+pub const DEFAULT_SPARSE_FULL_SCAN_THRESHOLD: usize = 5_000;
+
+/// Config of the inverted index of a sparse vector
+#[derive(
+    Debug, Default, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseIndexConfig {
+    /// Prefer a full scan search upto (excluding) this number of vectors.
+    /// Note: this is number of vectors, not KiloBytes. Default: 5000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_scan_threshold: Option<usize>,
+}
+
+impl SparseIndexConfig {
+    pub fn full_scan_threshold(&self) -> usize {
+        self.full_scan_threshold
+            .unwrap_or(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD)
+    }
+}
+
+/// Config of single sparse vector data storage
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorDataConfig {
+    /// Configuration of the inverted index
+    #[serde(default)]
+    pub index: SparseIndexConfig,
+}
+
 /// Persistable state of segment configuration
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct SegmentState {
     pub version: Option<SeqNumberType>,
     pub config: SegmentConfig,
+    /// Version of the on-disk format of the segment.
+    /// Not set in segments, created before the format version was introduced.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_version: Option<u32>,
 }
 
 /// Geo point payload schema
@@ -671,6 +962,31 @@ impl TryFrom<GeoPointShadow> for GeoPoint {
     }
 }
 
+/// Marker of the point, which payload is truncated to fit into the size limit of the response
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+pub struct PayloadTruncation {
+    /// Always true, if the payload is truncated
+    pub payload_truncated: bool,
+    /// Keys of the payload fields, dropped from the response. Largest fields are dropped first
+    pub dropped_payload_keys: Vec<PayloadKeyType>,
+}
+
+impl PayloadTruncation {
+    /// Truncate the `payload` to at most `max_bytes` of serialized JSON.
+    /// Returns the marker of the truncation, if any field is dropped.
+    pub fn truncate(payload: &mut Option<Payload>, max_bytes: usize) -> Option<Self> {
+        let payload = payload.as_mut()?;
+        let dropped_payload_keys = payload.truncate_to_size(max_bytes);
+        if dropped_payload_keys.is_empty() {
+            return None;
+        }
+        Some(PayloadTruncation {
+            payload_truncated: true,
+            dropped_payload_keys,
+        })
+    }
+}
+
 pub trait PayloadContainer {
     fn get_value(&self, path: &str) -> MultiValue<&Value>;
 }
@@ -707,6 +1023,42 @@ impl Payload {
     pub fn iter(&self) -> serde_json::map::Iter {
         self.0.iter()
     }
+
+    /// Drop the largest top-level fields, until the payload serializes into at most `max_bytes`
+    /// of JSON. Returns the keys of the dropped fields.
+    pub fn truncate_to_size(&mut self, max_bytes: usize) -> Vec<PayloadKeyType> {
+        let field_size = |key: &String, value: &Value| {
+            // `"key":value`, serializing a string or a JSON value can't fail
+            serde_json::to_vec(key).unwrap().len() + 1 + serde_json::to_vec(value).unwrap().len()
+        };
+        let mut fields: Vec<_> = self
+            .0
+            .iter()
+            .map(|(key, value)| (field_size(key, value), key.clone()))
+            .collect();
+        // Braces and the commas between the fields
+        let mut size =
+            2 + fields.len().saturating_sub(1) + fields.iter().map(|(size, _)| size).sum::<usize>();
+        if size <= max_bytes {
+            return vec![];
+        }
+
+        fields.sort_by(|(a_size, a_key), (b_size, b_key)| {
+            b_size.cmp(a_size).then_with(|| a_key.cmp(b_key))
+        });
+        let mut dropped = vec![];
+        let mut remaining = fields.len();
+        for (field_size, key) in fields {
+            if size <= max_bytes {
+                break;
+            }
+            remaining -= 1;
+            size -= field_size + usize::from(remaining > 0);
+            self.0.remove(&key);
+            dropped.push(key);
+        }
+        dropped
+    }
 }
 
 impl PayloadContainer for Map<String, Value> {
@@ -861,6 +1213,7 @@ pub enum PayloadSchemaType {
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum PayloadSchemaParams {
+    Keyword(KeywordIndexParams),
     Text(TextIndexParams),
 }
 
@@ -872,6 +1225,69 @@ pub enum PayloadFieldSchema {
     FieldParams(PayloadSchemaParams),
 }
 
+impl PayloadFieldSchema {
+    /// If true - the field is indexed for the full-text search
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Text)
+                | PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(_))
+        )
+    }
+
+    /// Name of the type, the field is indexed as
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword)
+            | PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(_)) => "keyword",
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Integer) => "integer",
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Float) => "float",
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Geo) => "geo",
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Text)
+            | PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(_)) => "text",
+        }
+    }
+
+    /// If true - the indexed values can be used as keys of the groups
+    pub fn is_group_key(&self) -> bool {
+        matches!(
+            self,
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword | PayloadSchemaType::Integer)
+                | PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(_))
+        )
+    }
+
+    /// If true - the index keeps the values ordered, so the points can be ordered by them
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Integer | PayloadSchemaType::Float)
+        )
+    }
+
+    /// If true - the index of the field is served from disk instead of RAM
+    pub fn is_on_disk(&self) -> bool {
+        matches!(
+            self,
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+                on_disk: Some(true),
+                ..
+            }))
+        )
+    }
+
+    /// If true - the field is a keyword, which identifies a tenant of a multitenant collection
+    pub fn is_tenant(&self) -> bool {
+        matches!(
+            self,
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+                is_tenant: Some(true),
+                ..
+            }))
+        )
+    }
+}
+
 impl From<PayloadSchemaType> for PayloadFieldSchema {
     fn from(payload_schema_type: PayloadSchemaType) -> Self {
         PayloadFieldSchema::FieldType(payload_schema_type)
@@ -883,6 +1299,9 @@ impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
 
     fn try_from(index_info: PayloadIndexInfo) -> Result<Self, Self::Error> {
         match (index_info.data_type, index_info.params) {
+            (PayloadSchemaType::Keyword, Some(PayloadSchemaParams::Keyword(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)),
+            ),
             (PayloadSchemaType::Text, Some(PayloadSchemaParams::Text(params))) => Ok(
                 PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(params)),
             ),
@@ -989,6 +1408,30 @@ pub struct MatchExcept {
     pub except: AnyVariants,
 }
 
+/// Range of the keywords, compared in the lexicographic order of their bytes
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct KeywordRange {
+    /// point.key >= range.gte
+    pub gte: Option<String>,
+    /// point.key <= range.lte
+    pub lte: Option<String>,
+}
+
+impl KeywordRange {
+    pub fn check_range(&self, keyword: &str) -> bool {
+        self.gte.as_deref().map_or(true, |x| keyword >= x)
+            && self.lte.as_deref().map_or(true, |x| keyword <= x)
+    }
+}
+
+/// Should have at least one keyword value in the given range
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchKeywordRange {
+    pub keyword_range: KeywordRange,
+}
+
 /// Match filter request
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -998,6 +1441,7 @@ pub enum MatchInterface {
     Text(MatchText),
     Any(MatchAny),
     Except(MatchExcept),
+    KeywordRange(MatchKeywordRange),
 }
 
 /// Match filter request
@@ -1009,6 +1453,7 @@ pub enum Match {
     Text(MatchText),
     Any(MatchAny),
     Except(MatchExcept),
+    KeywordRange(MatchKeywordRange),
 }
 
 impl Match {
@@ -1029,6 +1474,10 @@ impl Match {
     pub fn new_except(except: AnyVariants) -> Self {
         Self::Except(MatchExcept { except })
     }
+
+    pub fn new_keyword_range(keyword_range: KeywordRange) -> Self {
+        Self::KeywordRange(MatchKeywordRange { keyword_range })
+    }
 }
 
 impl From<AnyVariants> for Match {
@@ -1046,6 +1495,7 @@ impl From<MatchInterface> for Match {
             MatchInterface::Except(except) => Self::Except(MatchExcept {
                 except: except.except,
             }),
+            MatchInterface::KeywordRange(range) => Self::KeywordRange(range),
         }
     }
 }
@@ -1144,13 +1594,20 @@ pub struct ValuesCount {
 }
 
 impl ValuesCount {
-    pub fn check_count(&self, value: &Value) -> bool {
-        let count = match value {
+    /// Amount of values, the payload value counts for. Elements of an array are counted one by one
+    pub fn count_values(value: &Value) -> usize {
+        match value {
             Value::Null => 0,
             Value::Array(array) => array.len(),
             _ => 1,
-        };
+        }
+    }
+
+    pub fn check_count(&self, value: &Value) -> bool {
+        self.check_count_number(Self::count_values(value))
+    }
 
+    pub fn check_count_number(&self, count: usize) -> bool {
         self.lt.map_or(true, |x| count < x)
             && self.gt.map_or(true, |x| count > x)
             && self.lte.map_or(true, |x| count <= x)
@@ -1349,6 +1806,27 @@ impl From<HashSet<PointIdType>> for HasIdCondition {
     }
 }
 
+/// Select points, updated at or after the given time.
+///
+/// Points, stored before update times were recorded, do not match.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct ModifiedSinceCondition {
+    pub modified_since: DateTime<Utc>,
+}
+
+impl ModifiedSinceCondition {
+    /// Time of the condition in milliseconds since UNIX epoch, as recorded for the points
+    pub fn timestamp(&self) -> u64 {
+        self.modified_since.timestamp_millis().max(0) as u64
+    }
+}
+
+impl From<DateTime<Utc>> for ModifiedSinceCondition {
+    fn from(modified_since: DateTime<Utc>) -> Self {
+        ModifiedSinceCondition { modified_since }
+    }
+}
+
 /// Select points with payload for a specified nested field
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
 pub struct Nested {
@@ -1399,6 +1877,8 @@ pub enum Condition {
     IsNull(IsNullCondition),
     /// Check if points id is in a given set
     HasId(HasIdCondition),
+    /// Check if point was updated at or after a given time
+    ModifiedSince(ModifiedSinceCondition),
     /// Nested filters
     Nested(NestedCondition),
     /// Nested filter
@@ -1417,7 +1897,7 @@ impl Condition {
 }
 
 /// Options for specifying which payload to include or not
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum WithPayloadInterface {
@@ -1437,7 +1917,7 @@ impl From<bool> for WithPayloadInterface {
 }
 
 /// Options for specifying which vector to include
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum WithVector {
@@ -1446,6 +1926,20 @@ pub enum WithVector {
     Bool(bool),
     /// Specify which vector to return
     Selector(Vec<String>),
+    /// Return all vectors, except the specified ones
+    Exclude {
+        /// Names of the vectors to skip. Names, which don't exist in the collection, are ignored
+        exclude: Vec<String>,
+    },
+    /// Return a range of the dimensions of a single dense vector, e.g. its first PCA components.
+    /// The dimensions are returned in the `vector_slice` of the points, the `vector` is not set
+    Slice {
+        /// Name of the vector. Default: the unnamed vector
+        #[serde(default)]
+        name: String,
+        /// Dimensions to return
+        slice: DimensionRange,
+    },
 }
 
 impl WithVector {
@@ -1453,7 +1947,54 @@ impl WithVector {
         match self {
             WithVector::Bool(b) => *b,
             WithVector::Selector(_) => true,
+            WithVector::Exclude { .. } => true,
+            WithVector::Slice { .. } => true,
+        }
+    }
+}
+
+/// Range of the dimensions of a dense vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DimensionRange {
+    /// First dimension of the range
+    pub start: usize,
+    /// Dimension after the last one of the range
+    pub end: usize,
+}
+
+impl DimensionRange {
+    /// Whether the range is not empty and fits into vectors of `dim` dimensions
+    pub fn fits(&self, dim: usize) -> bool {
+        self.start < self.end && self.end <= dim
+    }
+}
+
+/// Dimensions of a dense vector, returned for the `with_vector` slice selector.
+/// They are kept apart from the `vector` of the point, so they can't be mistaken for a full vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct VectorSlice {
+    /// Name of the vector
+    pub name: String,
+    /// First returned dimension
+    pub start: usize,
+    /// Dimension after the last returned one
+    pub end: usize,
+    /// Values of the dimensions `start..end`
+    pub values: Vec<VectorElementType>,
+}
+
+impl VectorSlice {
+    /// Cut the dimensions of the `range` out of the `vector`
+    pub fn new(name: &str, range: DimensionRange, vector: &[VectorElementType]) -> Option<Self> {
+        if !range.fits(vector.len()) {
+            return None;
         }
+        Some(VectorSlice {
+            name: name.to_string(),
+            start: range.start,
+            end: range.end,
+            values: vector[range.start..range.end].to_vec(),
+        })
     }
 }
 
@@ -1506,25 +2047,34 @@ impl From<&WithPayloadInterface> for WithPayload {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 pub struct PayloadSelectorInclude {
-    /// Only include this payload keys
+    /// Only include this payload keys.
+    /// Nested keys are supported, as well as arrays: `a.b`, `arr[].c`, `arr[0].c`.
+    /// Array elements, which have nothing included, are omitted from the response
     pub include: Vec<PayloadKeyType>,
+    /// Exclude this keys from the included payload
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<PayloadKeyType>,
 }
 
 impl PayloadSelectorInclude {
     pub fn new(include: Vec<PayloadKeyType>) -> Self {
-        Self { include }
+        Self {
+            include,
+            exclude: vec![],
+        }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 pub struct PayloadSelectorExclude {
-    /// Exclude this fields from returning payload
+    /// Exclude this fields from returning payload.
+    /// Nested keys and arrays are supported, like in `include`
     pub exclude: Vec<PayloadKeyType>,
 }
 
@@ -1535,7 +2085,7 @@ impl PayloadSelectorExclude {
 }
 
 /// Specifies how to treat payload selector
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
 #[serde(untagged)]
 #[serde(rename_all = "snake_case")]
 pub enum PayloadSelector {
@@ -1559,22 +2109,20 @@ impl From<PayloadSelectorInclude> for WithPayloadInterface {
 
 impl PayloadSelector {
     pub fn new_include(vecs_payload_key_type: Vec<PayloadKeyType>) -> Self {
-        PayloadSelector::Include(PayloadSelectorInclude {
-            include: vecs_payload_key_type,
-        })
-    }
-
-    #[allow(clippy::ptr_arg)]
-    pub fn check(&self, key: &PayloadKeyType) -> bool {
-        match self {
-            PayloadSelector::Include(selector) => selector.include.contains(key),
-            PayloadSelector::Exclude(selector) => !selector.exclude.contains(key),
-        }
+        PayloadSelector::Include(PayloadSelectorInclude::new(vecs_payload_key_type))
     }
 
     pub fn process(&self, x: Payload) -> Payload {
-        let map: serde_json::Map<String, Value> =
-            x.into_iter().filter(|(key, _)| self.check(key)).collect();
+        let (mut map, exclude) = match self {
+            PayloadSelector::Include(selector) => (
+                utils::select_paths_from_json_map(&selector.include, &x.0),
+                &selector.exclude,
+            ),
+            PayloadSelector::Exclude(selector) => (x.0, &selector.exclude),
+        };
+        for path in exclude {
+            utils::remove_value_from_json_map(path, &mut map);
+        }
         map.into()
     }
 }
@@ -1589,22 +2137,85 @@ pub struct WithPayload {
     pub payload_selector: Option<PayloadSelector>,
 }
 
+impl Validate for Condition {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Condition::Filter(filter) => filter.validate(),
+            Condition::Nested(nested) => nested.filter().validate(),
+            Condition::Field(_)
+            | Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasId(_)
+            | Condition::ModifiedSince(_) => Ok(()),
+        }
+    }
+}
+
+/// Conditions, at least `min_count` of which should match
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "snake_case")]
+pub struct MinShould {
+    pub conditions: Vec<Condition>,
+    /// Minimal number of the conditions to match, from 1 up to the number of the conditions
+    pub min_count: usize,
+}
+
+impl Validate for MinShould {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        if self.min_count == 0 || self.min_count > self.conditions.len() {
+            let mut errors = ValidationErrors::new();
+            errors.add("min_count", {
+                let mut error = ValidationError::new("range");
+                error.add_param(Cow::from("value"), &self.min_count);
+                error.add_param(Cow::from("min"), &1);
+                error.add_param(Cow::from("max"), &self.conditions.len());
+                error
+            });
+            return Err(errors);
+        }
+        self.conditions.iter().try_for_each(Validate::validate)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 pub struct Filter {
     /// At least one of those conditions should match
     pub should: Option<Vec<Condition>>,
+    /// At least minimum amount of given conditions should match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_should: Option<MinShould>,
     /// All conditions must match
     pub must: Option<Vec<Condition>>,
     /// All conditions must NOT match
     pub must_not: Option<Vec<Condition>>,
 }
 
+impl Validate for Filter {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        if let Some(min_should) = &self.min_should {
+            min_should.validate()?;
+        }
+        self.iter_conditions().try_for_each(Validate::validate)
+    }
+}
+
 impl Filter {
     pub fn new_should(condition: Condition) -> Self {
         Filter {
             should: Some(vec![condition]),
+            min_should: None,
+            must: None,
+            must_not: None,
+        }
+    }
+
+    pub fn new_min_should(min_should: MinShould) -> Self {
+        Filter {
+            should: None,
+            min_should: Some(min_should),
             must: None,
             must_not: None,
         }
@@ -1613,6 +2224,7 @@ impl Filter {
     pub fn new_must(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: Some(vec![condition]),
             must_not: None,
         }
@@ -1621,11 +2233,26 @@ impl Filter {
     pub fn new_must_not(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![condition]),
         }
     }
 
+    /// All conditions of the filter, whichever clause they belong to
+    pub fn iter_conditions(&self) -> impl Iterator<Item = &Condition> {
+        self.must
+            .iter()
+            .chain(&self.should)
+            .chain(&self.must_not)
+            .flatten()
+            .chain(
+                self.min_should
+                    .iter()
+                    .flat_map(|min_should| &min_should.conditions),
+            )
+    }
+
     pub fn merge(&self, other: &Filter) -> Filter {
         let merge_component = |this, other| -> Option<Vec<Condition>> {
             match (this, other) {
@@ -1638,9 +2265,21 @@ impl Filter {
                 }
             }
         };
+        // Only one `min_should` clause fits the filter, the other one goes to `must` as nested
+        let (min_should, other_min_should) = match (&self.min_should, &other.min_should) {
+            (Some(this), Some(other)) => (
+                Some(this.clone()),
+                Some(vec![Condition::Filter(Filter::new_min_should(
+                    other.clone(),
+                ))]),
+            ),
+            (this, other) => (this.clone().or_else(|| other.clone()), None),
+        };
+        let must = merge_component(self.must.clone(), other.must.clone());
         Filter {
             should: merge_component(self.should.clone(), other.should.clone()),
-            must: merge_component(self.must.clone(), other.must.clone()),
+            min_should,
+            must: merge_component(must, other_min_should),
             must_not: merge_component(self.must_not.clone(), other.must_not.clone()),
         }
     }
@@ -1773,6 +2412,7 @@ mod tests {
     #[test]
     fn test_serialize_query() {
         let filter = Filter {
+            min_should: None,
             must: Some(vec![Condition::Field(FieldCondition::new_match(
                 "hello".to_owned(),
                 "world".to_owned().into(),
@@ -1784,6 +2424,57 @@ mod tests {
         eprintln!("{json}")
     }
 
+    #[test]
+    fn test_with_vector_serialization() {
+        let with_vector: WithVector = serde_json::from_str("true").unwrap();
+        assert_eq!(with_vector, WithVector::Bool(true));
+
+        let with_vector: WithVector = serde_json::from_str(r#"["text"]"#).unwrap();
+        assert_eq!(with_vector, WithVector::Selector(vec!["text".to_string()]));
+
+        let with_vector: WithVector = serde_json::from_str(r#"{"exclude": ["image"]}"#).unwrap();
+        assert_eq!(
+            with_vector,
+            WithVector::Exclude {
+                exclude: vec!["image".to_string()]
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&with_vector).unwrap(),
+            r#"{"exclude":["image"]}"#
+        );
+
+        let with_vector: WithVector =
+            serde_json::from_str(r#"{"name": "image", "slice": {"start": 0, "end": 3}}"#).unwrap();
+        assert_eq!(
+            with_vector,
+            WithVector::Slice {
+                name: "image".to_string(),
+                slice: DimensionRange { start: 0, end: 3 },
+            }
+        );
+        let with_vector: WithVector =
+            serde_json::from_str(r#"{"slice": {"start": 1, "end": 2}}"#).unwrap();
+        assert_eq!(
+            with_vector,
+            WithVector::Slice {
+                name: String::new(),
+                slice: DimensionRange { start: 1, end: 2 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_vector_slice() {
+        let vector = [0.1, 0.2, 0.3, 0.4];
+        let slice = VectorSlice::new("pca", DimensionRange { start: 1, end: 3 }, &vector).unwrap();
+        assert_eq!(slice.values, vec![0.2, 0.3]);
+        assert_eq!((slice.start, slice.end), (1, 3));
+
+        assert!(VectorSlice::new("pca", DimensionRange { start: 2, end: 5 }, &vector).is_none());
+        assert!(VectorSlice::new("pca", DimensionRange { start: 2, end: 2 }, &vector).is_none());
+    }
+
     #[test]
     fn test_deny_unknown_fields() {
         let query1 = r#"
@@ -1906,6 +2597,37 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_match_keyword_range() {
+        let query = r#"
+        {
+            "key": "user",
+            "match": {
+                "keyword_range": {
+                    "gte": "b",
+                    "lte": "d"
+                }
+            }
+        }
+        "#;
+
+        let condition: FieldCondition = serde_json::from_str(query).unwrap();
+        let keyword_range = KeywordRange {
+            gte: Some("b".to_string()),
+            lte: Some("d".to_string()),
+        };
+        assert_eq!(
+            condition.r#match,
+            Some(Match::new_keyword_range(keyword_range.clone()))
+        );
+
+        assert!(keyword_range.check_range("b"));
+        assert!(keyword_range.check_range("caa"));
+        assert!(keyword_range.check_range("d"));
+        assert!(!keyword_range.check_range("a"));
+        assert!(!keyword_range.check_range("da"));
+    }
+
     #[test]
     fn test_parse_nested_match_query() {
         let query = r#"
@@ -2004,6 +2726,28 @@ mod tests {
         assert_eq!(c.is_null.key.as_str(), "Jason");
     }
 
+    #[test]
+    fn test_parse_modified_since_query() {
+        let query = r#"
+        {
+            "must": [
+                {
+                    "modified_since": "2023-06-01T12:00:00Z"
+                }
+            ]
+        }
+        "#;
+        let filter: Filter = serde_json::from_str(query).unwrap();
+        let must = filter.must.unwrap();
+
+        let c = match must.get(0) {
+            Some(Condition::ModifiedSince(c)) => c,
+            _ => panic!("Condition::ModifiedSince expected"),
+        };
+
+        assert_eq!(c.timestamp(), 1_685_620_800_000);
+    }
+
     #[test]
     fn test_parse_nested_filter_query() {
         let query = r#"
@@ -2315,6 +3059,68 @@ mod tests {
         assert!(merged.must.as_ref().unwrap().contains(&condition2));
         assert!(merged.should.as_ref().unwrap().contains(&condition1));
     }
+
+    #[test]
+    fn test_adaptive_ef_scaling() {
+        let params = AdaptiveEfParams {
+            selectivity_threshold: Some(0.1),
+            max_ef: Some(500),
+            exact_selectivity_threshold: Some(0.001),
+        };
+
+        // Not selective enough filter keeps `ef`
+        assert_eq!(params.scaled_ef(100, 0.5), None);
+        // `ef` grows proportionally to the selectivity
+        assert_eq!(params.scaled_ef(100, 0.05), Some(200));
+        // but not above the limit
+        assert_eq!(params.scaled_ef(100, 0.002), Some(500));
+
+        assert!(!params.prefer_exact(0.002));
+        assert!(params.prefer_exact(0.0005));
+
+        // Default limit is relative to the original `ef`
+        let default_params = AdaptiveEfParams::default();
+        assert_eq!(
+            default_params.scaled_ef(100, 0.0),
+            Some(100 * DEFAULT_ADAPTIVE_EF_MAX_FACTOR)
+        );
+        assert!(!default_params.prefer_exact(0.0));
+    }
+
+    #[test]
+    fn test_payload_truncate_to_size() {
+        let original: Payload = json!({
+            "title": "short",
+            "body": "a".repeat(100),
+            "tags": ["x", "y"],
+            "embedding_text": "b".repeat(50),
+        })
+        .into();
+        let serialized_size = |payload: &Payload| serde_json::to_vec(payload).unwrap().len();
+
+        let mut payload = original.clone();
+        assert!(payload
+            .truncate_to_size(serialized_size(&original))
+            .is_empty());
+        assert_eq!(payload, original);
+
+        // Largest fields are dropped first, until the payload fits
+        let mut payload = original.clone();
+        let dropped = payload.truncate_to_size(110);
+        assert_eq!(dropped, vec!["body".to_string()]);
+        assert_eq!(serialized_size(&payload), 104);
+
+        let mut payload = original.clone();
+        let dropped = payload.truncate_to_size(30);
+        assert_eq!(dropped, vec!["body", "embedding_text", "tags"]);
+        assert_eq!(payload, Payload::from(json!({"title": "short"})));
+
+        let mut payload = Some(original);
+        let truncation = PayloadTruncation::truncate(&mut payload, 2).unwrap();
+        assert!(truncation.payload_truncated);
+        assert_eq!(truncation.dropped_payload_keys.len(), 4);
+        assert_eq!(serialized_size(&payload.unwrap()), 2);
+    }
 }
 
 pub type TheMap<K, V> = BTreeMap<K, V>;
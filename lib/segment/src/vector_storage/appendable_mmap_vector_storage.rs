@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fs::create_dir_all;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
@@ -8,19 +9,22 @@ use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
 
 use crate::common::Flusher;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{
+    CowVector, VectorElementType, VectorElementTypeByte, VectorElementTypeHalf,
+};
 use crate::entry::entry_point::{check_process_stopped, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::chunked_mmap_vectors::ChunkedMmapVectors;
 use crate::vector_storage::dynamic_mmap_flags::DynamicMmapFlags;
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
-use crate::vector_storage::{VectorStorage, VectorStorageEnum};
+use crate::vector_storage::{DenseVectorStorage, VectorStorage, VectorStorageEnum};
 
 const VECTORS_DIR_PATH: &str = "vectors";
 const DELETED_DIR_PATH: &str = "deleted";
 
-pub struct AppendableMmapVectorStorage {
-    vectors: ChunkedMmapVectors,
+pub struct AppendableMmapVectorStorage<T: PrimitiveVectorElement = VectorElementType> {
+    vectors: ChunkedMmapVectors<T>,
     deleted: DynamicMmapFlags,
     distance: Distance,
     deleted_count: usize,
@@ -32,12 +36,47 @@ pub fn open_appendable_memmap_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_appendable_memmap_vector_storage_impl(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmap(Box::new(storage)),
+    )))
+}
+
+pub fn open_appendable_memmap_vector_storage_half(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage =
+        open_appendable_memmap_vector_storage_impl::<VectorElementTypeHalf>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmapHalf(Box::new(storage)),
+    )))
+}
+
+pub fn open_appendable_memmap_vector_storage_byte(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage =
+        open_appendable_memmap_vector_storage_impl::<VectorElementTypeByte>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmapByte(Box::new(storage)),
+    )))
+}
+
+fn open_appendable_memmap_vector_storage_impl<T: PrimitiveVectorElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<AppendableMmapVectorStorage<T>> {
     create_dir_all(path)?;
 
     let vectors_path = path.join(VECTORS_DIR_PATH);
     let deleted_path = path.join(DELETED_DIR_PATH);
 
-    let vectors: ChunkedMmapVectors = ChunkedMmapVectors::open(&vectors_path, dim)?;
+    let vectors: ChunkedMmapVectors<T> = ChunkedMmapVectors::open(&vectors_path, dim)?;
 
     let num_vectors = vectors.len();
 
@@ -51,20 +90,16 @@ pub fn open_appendable_memmap_vector_storage(
         }
     }
 
-    let storage = AppendableMmapVectorStorage {
+    Ok(AppendableMmapVectorStorage {
         vectors,
         deleted,
         distance,
         deleted_count,
         quantized_vectors: None,
-    };
-
-    Ok(Arc::new(AtomicRefCell::new(
-        VectorStorageEnum::AppendableMemmap(Box::new(storage)),
-    )))
+    })
 }
 
-impl AppendableMmapVectorStorage {
+impl<T: PrimitiveVectorElement> AppendableMmapVectorStorage<T> {
     /// Set deleted flag for given key. Returns previous deleted state.
     #[inline]
     fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> OperationResult<bool> {
@@ -85,7 +120,15 @@ impl AppendableMmapVectorStorage {
     }
 }
 
-impl VectorStorage for AppendableMmapVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage for AppendableMmapVectorStorage<T> {
+    type Element = T;
+
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.vectors.get(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for AppendableMmapVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.vectors.dim()
     }
@@ -98,8 +141,8 @@ impl VectorStorage for AppendableMmapVectorStorage {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
+        T::slice_to_float_cow(Cow::Borrowed(self.vectors.get(key)))
     }
 
     fn insert_vector(
@@ -107,7 +150,8 @@ impl VectorStorage for AppendableMmapVectorStorage {
         key: PointOffsetType,
         vector: &[VectorElementType],
     ) -> OperationResult<()> {
-        self.vectors.insert(key, vector)?;
+        self.vectors
+            .insert(key, &T::slice_from_float_cow(Cow::Borrowed(vector)))?;
         self.set_deleted(key, false)?;
         Ok(())
     }
@@ -123,8 +167,8 @@ impl VectorStorage for AppendableMmapVectorStorage {
             check_process_stopped(stopped)?;
             // Do not perform preprocessing - vectors should be already processed
             let other_deleted = other.is_deleted_vector(point_id);
-            let other_vector = other.get_vector(point_id);
-            let new_id = self.vectors.push(other_vector)?;
+            let other_vector = T::slice_from_float_cow(other.get_vector(point_id));
+            let new_id = self.vectors.push(&other_vector)?;
             self.set_deleted(new_id, other_deleted)?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
@@ -151,7 +195,7 @@ impl VectorStorage for AppendableMmapVectorStorage {
         stopped: &AtomicBool,
     ) -> OperationResult<()> {
         let vector_data_iterator = (0..self.vectors.len() as u32).map(|i| self.vectors.get(i));
-        self.quantized_vectors = Some(QuantizedVectors::create(
+        self.quantized_vectors = Some(QuantizedVectors::create_from(
             vector_data_iterator,
             quantization_config,
             self.distance,
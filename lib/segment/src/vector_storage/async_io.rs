@@ -1,25 +1,38 @@
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
 
 use io_uring::{opcode, types, IoUring};
 
-use crate::common::mmap_ops::transmute_from_u8_to_slice;
+use super::div_ceil;
+use crate::common::mmap_ops::{transmute_from_u8_to_slice, transmute_to_u8_slice};
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::types::PointOffsetType;
 
 const DISK_PARALLELISM: usize = 16; // TODO: benchmark it better, or make it configurable
 
+/// Alignment of file offsets, read sizes and memory buffers, required by direct IO.
+/// Covers logical block sizes of the common devices.
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+#[repr(C, align(4096))]
+#[derive(Clone, Copy)]
+struct AlignedBlock([u8; DIRECT_IO_ALIGNMENT]);
+
 struct BufferMeta {
     /// Sequential index of the processing point
     pub index: usize,
     /// Id of the point that is currently being processed
     pub point_id: PointOffsetType,
+    /// Position of the vector within the buffer, non-zero for the aligned direct IO reads
+    pub vector_offset: usize,
 }
 
 struct Buffer {
-    /// Stores the buffer for the point vectors
-    pub buffer: Vec<u8>,
+    /// Stores the buffer for the point vectors, aligned for direct IO
+    pub blocks: Vec<AlignedBlock>,
     /// Stores the point ids that are currently being processed in each buffer.
     pub meta: Option<BufferMeta>,
 }
@@ -31,10 +44,11 @@ struct BufferStore {
 
 impl BufferStore {
     pub fn new(num_buffers: usize, buffer_raw_size: usize) -> Self {
+        let num_blocks = div_ceil(buffer_raw_size, DIRECT_IO_ALIGNMENT);
         Self {
             buffers: (0..num_buffers)
                 .map(|_| Buffer {
-                    buffer: vec![0; buffer_raw_size],
+                    blocks: vec![AlignedBlock([0; DIRECT_IO_ALIGNMENT]); num_blocks],
                     meta: None,
                 })
                 .collect(),
@@ -53,11 +67,52 @@ pub struct UringReader {
     io_uring: Option<IoUring>,
     raw_size: usize,
     header_size: usize,
+    /// File is opened with `O_DIRECT`, reads have to be aligned
+    direct_io: bool,
 }
 
 impl UringReader {
     pub fn new(file: File, raw_size: usize, header_size: usize) -> OperationResult<Self> {
-        let buffers = BufferStore::new(DISK_PARALLELISM, raw_size);
+        Self::with_direct_io(file, raw_size, header_size, false)
+    }
+
+    /// Open the file for reading, bypassing page cache if `direct_io` is requested.
+    /// Falls back to buffered reads, if the file system doesn't support direct IO.
+    pub fn open(
+        path: &Path,
+        raw_size: usize,
+        header_size: usize,
+        direct_io: bool,
+    ) -> OperationResult<Self> {
+        if direct_io {
+            match OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_DIRECT)
+                .open(path)
+            {
+                Ok(file) => return Self::with_direct_io(file, raw_size, header_size, true),
+                Err(err) => log::warn!(
+                    "Direct IO is not available for {}, falling back to buffered reads: {err}",
+                    path.display(),
+                ),
+            }
+        }
+        Self::new(File::open(path)?, raw_size, header_size)
+    }
+
+    fn with_direct_io(
+        file: File,
+        raw_size: usize,
+        header_size: usize,
+        direct_io: bool,
+    ) -> OperationResult<Self> {
+        // Unaligned vector might span one extra block
+        let buffer_size = if direct_io {
+            raw_size + DIRECT_IO_ALIGNMENT - 1
+        } else {
+            raw_size
+        };
+        let buffers = BufferStore::new(DISK_PARALLELISM, buffer_size);
         let io_uring = IoUring::new(DISK_PARALLELISM as _)?;
 
         Ok(Self {
@@ -66,9 +121,24 @@ impl UringReader {
             io_uring: Some(io_uring),
             raw_size,
             header_size,
+            direct_io,
         })
     }
 
+    /// Range of the file to read for the vector at `offset`: start of the read, its length and
+    /// the position of the vector within the read data
+    fn read_range(&self, offset: usize) -> (usize, usize, usize) {
+        if self.direct_io {
+            let vector_offset = offset % DIRECT_IO_ALIGNMENT;
+            let read_start = offset - vector_offset;
+            let read_len =
+                div_ceil(vector_offset + self.raw_size, DIRECT_IO_ALIGNMENT) * DIRECT_IO_ALIGNMENT;
+            (read_start, read_len, vector_offset)
+        } else {
+            (offset, self.raw_size, 0)
+        }
+    }
+
     /// Takes in iterator of point offsets, reads it, and yields a callback with the read data.
     pub fn read_stream(
         &mut self,
@@ -102,22 +172,26 @@ impl UringReader {
             // Assume there is at least one buffer available at this point
             let buffer_id = unused_buffer_ids.pop().unwrap();
 
+            let offset = self.header_size + self.raw_size * point as usize;
+            let (read_start, read_len, vector_offset) = self.read_range(offset);
+
             self.buffers.buffers[buffer_id].meta = Some(BufferMeta {
                 index: idx,
                 point_id: point,
+                vector_offset,
             });
 
-            let buffer = &mut self.buffers.buffers[buffer_id].buffer;
-            let offset = self.header_size + self.raw_size * point as usize;
+            let blocks = &mut self.buffers.buffers[buffer_id].blocks;
+            debug_assert!(read_len <= blocks.len() * DIRECT_IO_ALIGNMENT);
 
             let user_data = buffer_id;
 
             let read_e = opcode::Read::new(
                 types::Fd(self.file.as_raw_fd()),
-                buffer.as_mut_ptr(),
-                buffer.len() as _,
+                blocks.as_mut_ptr() as *mut u8,
+                read_len as _,
             )
-            .offset(offset as _)
+            .offset(read_start as _)
             .build()
             .user_data(user_data as _);
 
@@ -165,22 +239,24 @@ fn submit_and_read(
     let cqe = io_uring.completion();
     for entry in cqe {
         let result = entry.result();
+        let buffer_id = entry.user_data() as usize;
+        let meta = buffers.buffers[buffer_id].meta.take().unwrap();
+        // Aligned direct IO read might end beyond the vector, or be cut by the end of the file
+        let expected_size = meta.vector_offset + raw_size;
         if result < 0 {
             return Err(OperationError::service_error(format!(
                 "io_uring operation failed with {} error",
                 result
             )));
-        } else if (result as usize) != raw_size {
+        } else if (result as usize) < expected_size {
             return Err(OperationError::service_error(format!(
                 "io_uring operation returned {} bytes instead of {}",
-                result, raw_size
+                result, expected_size
             )));
         }
 
-        let buffer_id = entry.user_data() as usize;
-        let meta = buffers.buffers[buffer_id].meta.take().unwrap();
-        let buffer = &buffers.buffers[buffer_id].buffer;
-        let vector = transmute_from_u8_to_slice(buffer);
+        let data = transmute_to_u8_slice(&buffers.buffers[buffer_id].blocks);
+        let vector = transmute_from_u8_to_slice(&data[meta.vector_offset..expected_size]);
         callback(meta.index, meta.point_id, vector);
         unused_buffer_ids.push(buffer_id);
     }
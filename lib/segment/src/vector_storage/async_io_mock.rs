@@ -1,4 +1,5 @@
 use std::fs::File;
+use std::path::Path;
 
 use crate::entry::entry_point::OperationResult;
 
@@ -11,4 +12,13 @@ impl UringReader {
     pub fn new(_file: File, _raw_size: usize, _header_size: usize) -> OperationResult<Self> {
         Ok(Self {})
     }
+
+    pub fn open(
+        _path: &Path,
+        _raw_size: usize,
+        _header_size: usize,
+        _direct_io: bool,
+    ) -> OperationResult<Self> {
+        Ok(Self {})
+    }
 }
@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 
 use bitvec::prelude::BitSlice;
 
+use crate::common::hardware_counter::record_vector_comparisons;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::spaces::metric::Metric;
@@ -10,6 +11,7 @@ use crate::spaces::tools::FixedLengthPriorityQueue;
 use crate::types::{Distance, PointOffsetType, ScoreType};
 use crate::vector_storage::memmap_vector_storage::MemmapVectorStorage;
 use crate::vector_storage::mmap_vectors::MmapVectors;
+use crate::vector_storage::raw_scorer::{get_async_scorer_batch_size, nan_to_neg_infinity};
 use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorage as _};
 
 pub fn new<'a>(
@@ -26,6 +28,8 @@ pub struct AsyncRawScorerImpl<'a, TMetric: Metric> {
     storage: &'a MmapVectors,
     point_deleted: &'a BitSlice,
     vec_deleted: &'a BitSlice,
+    /// Number of vectors, read ahead while the previous ones are scored
+    batch_size: usize,
     metric: PhantomData<TMetric>,
 }
 
@@ -39,6 +43,7 @@ where
         storage: &'a MmapVectors,
         point_deleted: &'a BitSlice,
         vec_deleted: &'a BitSlice,
+        batch_size: usize,
     ) -> Self {
         Self {
             points_count,
@@ -46,6 +51,7 @@ where
             storage,
             point_deleted,
             vec_deleted,
+            batch_size,
             metric: PhantomData,
         }
     }
@@ -66,7 +72,7 @@ where
             .read_vectors_async(points_stream, |idx, point_id, other_vector| {
                 scores[idx] = ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: nan_to_neg_infinity(TMetric::similarity(&self.query, other_vector)),
                 };
                 processed += 1;
             })
@@ -76,6 +82,7 @@ where
         // Instead of silently falling back to the sync implementation, we prefer to panic
         // and notify the user that they better use the default IO implementation.
 
+        record_vector_comparisons(processed, false);
         processed
     }
 
@@ -83,13 +90,15 @@ where
         &self,
         points: &mut dyn Iterator<Item = PointOffsetType>,
     ) -> Vec<ScoredPointOffset> {
-        let mut scores = vec![];
+        let points: Vec<_> = points.collect();
+        let mut scores = Vec::with_capacity(points.len());
 
+        // Scores are in the order of `points`, same as for the sync scorer
         self.storage
-            .read_vectors_async(points, |_idx, point_id, other_vector| {
+            .process_vectors_prefetched(&points, self.batch_size, |point_id, other_vector| {
                 scores.push(ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: nan_to_neg_infinity(TMetric::similarity(&self.query, other_vector)),
                 });
             })
             .unwrap();
@@ -98,6 +107,7 @@ where
         // Instead of silently falling back to the sync implementation, we prefer to panic
         // and notify the user that they better use the default IO implementation.
 
+        record_vector_comparisons(scores.len(), false);
         scores
     }
 
@@ -120,14 +130,15 @@ where
     }
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        record_vector_comparisons(1, false);
         let other_vector = self.storage.get_vector(point);
-        TMetric::similarity(&self.query, other_vector)
+        nan_to_neg_infinity(TMetric::similarity(&self.query, other_vector))
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
         let vector_a = self.storage.get_vector(point_a);
         let vector_b = self.storage.get_vector(point_b);
-        TMetric::similarity(vector_a, vector_b)
+        nan_to_neg_infinity(TMetric::similarity(vector_a, vector_b))
     }
 
     fn peek_top_iter(
@@ -139,6 +150,7 @@ where
             return vec![];
         }
 
+        let mut scored = 0;
         let mut pq = FixedLengthPriorityQueue::new(top);
         let points_stream = points.filter(|point_id| self.check_vector(*point_id));
 
@@ -146,9 +158,10 @@ where
             .read_vectors_async(points_stream, |_, point_id, other_vector| {
                 let scored_point_offset = ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: nan_to_neg_infinity(TMetric::similarity(&self.query, other_vector)),
                 };
                 pq.push(scored_point_offset);
+                scored += 1;
             })
             .unwrap();
 
@@ -156,6 +169,7 @@ where
         // Instead of silently falling back to the sync implementation, we prefer to panic
         // and notify the user that they better use the default IO implementation.
 
+        record_vector_comparisons(scored, false);
         pq.into_vec()
     }
 
@@ -166,14 +180,16 @@ where
 
         let points_stream = (0..self.points_count).filter(|point_id| self.check_vector(*point_id));
 
+        let mut scored = 0;
         let mut pq = FixedLengthPriorityQueue::new(top);
         self.storage
             .read_vectors_async(points_stream, |_, point_id, other_vector| {
                 let scored_point_offset = ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: nan_to_neg_infinity(TMetric::similarity(&self.query, other_vector)),
                 };
                 pq.push(scored_point_offset);
+                scored += 1;
             })
             .unwrap();
 
@@ -181,6 +197,7 @@ where
         // Instead of silently falling back to the sync implementation, we prefer to panic
         // and notify the user that they better use the default IO implementation.
 
+        record_vector_comparisons(scored, false);
         pq.into_vec()
     }
 }
@@ -192,6 +209,7 @@ struct AsyncRawScorerBuilder<'a> {
     point_deleted: &'a BitSlice,
     vec_deleted: &'a BitSlice,
     distance: Distance,
+    batch_size: usize,
 }
 
 impl<'a> AsyncRawScorerBuilder<'a> {
@@ -213,6 +231,7 @@ impl<'a> AsyncRawScorerBuilder<'a> {
             vec_deleted,
             storage,
             distance,
+            batch_size: get_async_scorer_batch_size(),
         };
 
         Ok(builder)
@@ -233,6 +252,7 @@ impl<'a> AsyncRawScorerBuilder<'a> {
             self.storage,
             self.point_deleted,
             self.vec_deleted,
+            self.batch_size,
         )
     }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::ops::Range;
@@ -11,11 +12,14 @@ use bitvec::prelude::BitSlice;
 use super::quantized::quantized_vectors::QuantizedVectors;
 use super::VectorStorageEnum;
 use crate::common::{mmap_ops, Flusher};
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{
+    CowVector, VectorElementType, VectorElementTypeByte, VectorElementTypeHalf,
+};
 use crate::entry::entry_point::{check_process_stopped, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::mmap_vectors::MmapVectors;
-use crate::vector_storage::VectorStorage;
+use crate::vector_storage::{DenseVectorStorage, VectorStorage};
 
 const VECTORS_PATH: &str = "matrix.dat";
 const DELETED_PATH: &str = "deleted.dat";
@@ -26,10 +30,10 @@ const DELETED_PATH: &str = "deleted.dat";
 /// but possible to mark some vectors as removed
 ///
 /// Mem-mapped storage can only be constructed from another storage
-pub struct MemmapVectorStorage {
+pub struct MemmapVectorStorage<T: PrimitiveVectorElement = VectorElementType> {
     vectors_path: PathBuf,
     deleted_path: PathBuf,
-    mmap_store: Option<MmapVectors>,
+    mmap_store: Option<MmapVectors<T>>,
     distance: Distance,
 }
 
@@ -38,23 +42,54 @@ pub fn open_memmap_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Memmap(
+        Box::new(storage),
+    ))))
+}
+
+pub fn open_memmap_vector_storage_half(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl::<VectorElementTypeHalf>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::MemmapHalf(
+        Box::new(storage),
+    ))))
+}
+
+pub fn open_memmap_vector_storage_byte(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl::<VectorElementTypeByte>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::MemmapByte(
+        Box::new(storage),
+    ))))
+}
+
+fn open_memmap_vector_storage_impl<T: PrimitiveVectorElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<MemmapVectorStorage<T>> {
     create_dir_all(path)?;
 
     let vectors_path = path.join(VECTORS_PATH);
     let deleted_path = path.join(DELETED_PATH);
     let mmap_store = MmapVectors::open(&vectors_path, &deleted_path, dim)?;
 
-    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Memmap(
-        Box::new(MemmapVectorStorage {
-            vectors_path,
-            deleted_path,
-            mmap_store: Some(mmap_store),
-            distance,
-        }),
-    ))))
+    Ok(MemmapVectorStorage {
+        vectors_path,
+        deleted_path,
+        mmap_store: Some(mmap_store),
+        distance,
+    })
 }
 
-impl MemmapVectorStorage {
+impl<T: PrimitiveVectorElement> MemmapVectorStorage<T> {
     pub fn prefault_mmap_pages(&self) -> Option<mmap_ops::PrefaultMmapPages> {
         Some(
             self.mmap_store
@@ -63,12 +98,20 @@ impl MemmapVectorStorage {
         )
     }
 
-    pub fn get_mmap_vectors(&self) -> &MmapVectors {
+    pub fn get_mmap_vectors(&self) -> &MmapVectors<T> {
         self.mmap_store.as_ref().unwrap()
     }
 }
 
-impl VectorStorage for MemmapVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage for MemmapVectorStorage<T> {
+    type Element = T;
+
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.mmap_store.as_ref().unwrap().get_vector(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for MemmapVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.mmap_store.as_ref().unwrap().dim
     }
@@ -81,8 +124,10 @@ impl VectorStorage for MemmapVectorStorage {
         self.mmap_store.as_ref().unwrap().num_vectors
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.mmap_store.as_ref().unwrap().get_vector(key)
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
+        T::slice_to_float_cow(Cow::Borrowed(
+            self.mmap_store.as_ref().unwrap().get_vector(key),
+        ))
     }
 
     fn insert_vector(
@@ -109,8 +154,8 @@ impl VectorStorage for MemmapVectorStorage {
         let mut deleted_ids = vec![];
         for id in other_ids {
             check_process_stopped(stopped)?;
-            let vector = other.get_vector(id);
-            let raw_bites = mmap_ops::transmute_to_u8_slice(vector);
+            let vector = T::slice_from_float_cow(other.get_vector(id));
+            let raw_bites = mmap_ops::transmute_to_u8_slice(&vector);
             vectors_file.write_all(raw_bites)?;
             end_index += 1;
 
@@ -1,9 +1,11 @@
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::mem::{self, size_of, transmute};
+use std::marker::PhantomData;
+use std::mem::{self, size_of};
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 use bitvec::prelude::BitSlice;
 use memmap2::Mmap;
@@ -12,7 +14,8 @@ use parking_lot::Mutex;
 use super::div_ceil;
 use crate::common::error_logging::LogError;
 use crate::common::mmap_type::MmapBitSlice;
-use crate::common::{mmap_ops, Flusher};
+use crate::common::{mmap_ops, on_disk_io, Flusher};
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
@@ -27,7 +30,7 @@ const VECTORS_HEADER: &[u8; HEADER_SIZE] = b"data";
 const DELETED_HEADER: &[u8; HEADER_SIZE] = b"drop";
 
 /// Mem-mapped file
-pub struct MmapVectors {
+pub struct MmapVectors<T: PrimitiveVectorElement = VectorElementType> {
     pub dim: usize,
     pub num_vectors: usize,
     /// Memory mapped file for vector data
@@ -42,15 +45,19 @@ pub struct MmapVectors {
     /// Current number of deleted vectors.
     pub deleted_count: usize,
     pub quantized_vectors: Option<QuantizedVectors>,
+    _element: PhantomData<T>,
 }
 
-impl MmapVectors {
+impl<T: PrimitiveVectorElement> MmapVectors<T> {
     pub fn open(vectors_path: &Path, deleted_path: &Path, dim: usize) -> OperationResult<Self> {
+        let on_disk_io = on_disk_io::get_global();
+
         // Allocate/open vectors mmap
         ensure_mmap_file_size(vectors_path, VECTORS_HEADER, None)
             .describe("Create mmap data file")?;
-        let mmap = mmap_ops::open_read_mmap(vectors_path).describe("Open mmap for reading")?;
-        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<VectorElementType>();
+        let mmap = mmap_ops::open_read_mmap_populated(vectors_path, on_disk_io)
+            .describe("Open mmap for reading")?;
+        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<T>();
 
         // Allocate/open deleted mmap
         let deleted_mmap_size = deleted_mmap_size(num_vectors);
@@ -70,9 +77,9 @@ impl MmapVectors {
         let deleted_count = deleted.count_ones();
 
         // Keep file handle open for async IO
-        let vectors_file = File::open(vectors_path)?;
-        let raw_size = dim * size_of::<VectorElementType>();
-        let uring_reader = UringReader::new(vectors_file, raw_size, HEADER_SIZE)?;
+        let raw_size = dim * size_of::<T>();
+        let uring_reader =
+            UringReader::open(vectors_path, raw_size, HEADER_SIZE, on_disk_io.direct_io)?;
 
         Ok(MmapVectors {
             dim,
@@ -82,6 +89,7 @@ impl MmapVectors {
             deleted,
             deleted_count,
             quantized_vectors: None,
+            _element: PhantomData,
         })
     }
 
@@ -108,7 +116,7 @@ impl MmapVectors {
             let offset = self.data_offset(i as PointOffsetType).unwrap_or_default();
             self.raw_vector_offset(offset)
         });
-        self.quantized_vectors = Some(QuantizedVectors::create(
+        self.quantized_vectors = Some(QuantizedVectors::create_from(
             vector_data_iterator,
             quantization_config,
             distance,
@@ -141,7 +149,7 @@ impl MmapVectors {
     }
 
     pub fn data_offset(&self, key: PointOffsetType) -> Option<usize> {
-        let vector_data_length = self.dim * size_of::<VectorElementType>();
+        let vector_data_length = self.dim * size_of::<T>();
         let offset = (key as usize) * vector_data_length + HEADER_SIZE;
         if key >= (self.num_vectors as PointOffsetType) {
             return None;
@@ -150,17 +158,16 @@ impl MmapVectors {
     }
 
     pub fn raw_size(&self) -> usize {
-        self.dim * size_of::<VectorElementType>()
+        self.dim * size_of::<T>()
     }
 
-    pub fn raw_vector_offset(&self, offset: usize) -> &[VectorElementType] {
+    pub fn raw_vector_offset(&self, offset: usize) -> &[T] {
         let byte_slice = &self.mmap[offset..(offset + self.raw_size())];
-        let arr: &[VectorElementType] = unsafe { transmute(byte_slice) };
-        &arr[0..self.dim]
+        mmap_ops::transmute_from_u8_to_slice(byte_slice)
     }
 
     /// Returns reference to vector data by key
-    pub fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
+    pub fn get_vector(&self, key: PointOffsetType) -> &[T] {
         let offset = self.data_offset(key).unwrap();
         self.raw_vector_offset(offset)
     }
@@ -192,7 +199,10 @@ impl MmapVectors {
     pub fn prefault_mmap_pages(&self, path: &Path) -> mmap_ops::PrefaultMmapPages {
         mmap_ops::PrefaultMmapPages::new(self.mmap.clone(), Some(path))
     }
+}
 
+/// Async IO is only used to rescore full precision vectors.
+impl MmapVectors<VectorElementType> {
     #[cfg(target_os = "linux")]
     fn process_points_uring(
         &self,
@@ -233,6 +243,87 @@ impl MmapVectors {
             self.process_points_simple(points, callback)
         }
     }
+
+    /// Reads vectors of the given points into `vectors` one after another, in the order of
+    /// `points`. The buffer is reused between the calls.
+    pub fn get_vectors_async(
+        &self,
+        points: &[PointOffsetType],
+        vectors: &mut Vec<VectorElementType>,
+    ) -> OperationResult<()> {
+        let dim = self.dim;
+        vectors.clear();
+        vectors.resize(points.len() * dim, 0.0);
+        self.read_vectors_async(points.iter().copied(), |idx, _point, vector| {
+            vectors[idx * dim..(idx + 1) * dim].copy_from_slice(vector);
+        })
+    }
+
+    /// Calls the callback for the vector of each point, in the order of `points`.
+    ///
+    /// Vectors are read in batches of `batch_size`. The next batch is read in a separate thread,
+    /// while the callback processes the current one, so the IO overlaps with the computations.
+    pub fn process_vectors_prefetched(
+        &self,
+        points: &[PointOffsetType],
+        batch_size: usize,
+        mut callback: impl FnMut(PointOffsetType, &[VectorElementType]),
+    ) -> OperationResult<()> {
+        let batch_size = batch_size.max(1);
+        let mut process_batch = |batch: &[PointOffsetType], vectors: &[VectorElementType]| {
+            for (point, vector) in batch.iter().zip(vectors.chunks_exact(self.dim)) {
+                callback(*point, vector);
+            }
+        };
+
+        if points.len() <= batch_size {
+            let mut vectors = Vec::new();
+            self.get_vectors_async(points, &mut vectors)?;
+            process_batch(points, &vectors);
+            return Ok(());
+        }
+
+        // Pool of two buffers: one is filled by the reader, while the other one is processed
+        let (free_sender, free_receiver) = mpsc::channel::<Vec<VectorElementType>>();
+        let (ready_sender, ready_receiver) = mpsc::sync_channel(1);
+        for _ in 0..2 {
+            free_sender.send(Vec::new()).unwrap();
+        }
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(move || -> OperationResult<()> {
+                for batch in points.chunks(batch_size) {
+                    // Processing side is gone
+                    let Ok(mut vectors) = free_receiver.recv() else {
+                        break;
+                    };
+                    self.get_vectors_async(batch, &mut vectors)?;
+                    if ready_sender.send(vectors).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+
+            // Owned by this closure, so the reader is released if the callback panics
+            let free_sender = free_sender;
+            let ready_receiver = ready_receiver;
+            for batch in points.chunks(batch_size) {
+                // Reader has failed, the error is returned on join
+                let Ok(vectors) = ready_receiver.recv() else {
+                    break;
+                };
+                process_batch(batch, &vectors);
+                let _ = free_sender.send(vectors);
+            }
+            drop(free_sender);
+            drop(ready_receiver);
+
+            reader
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
 }
 
 /// Ensure the given mmap file exists and is the given size
@@ -1,7 +1,9 @@
 use bitvec::prelude::BitSlice;
 
+use crate::common::hardware_counter::record_vector_comparisons;
 use crate::spaces::tools::peek_top_largest_iterable;
 use crate::types::{PointOffsetType, ScoreType};
+use crate::vector_storage::raw_scorer::nan_to_neg_infinity;
 use crate::vector_storage::{RawScorer, ScoredPointOffset};
 
 pub struct QuantizedRawScorer<'a, TEncodedQuery, TEncodedVectors>
@@ -29,13 +31,14 @@ where
             }
             scores[size] = ScoredPointOffset {
                 idx: point_id,
-                score: self.quantized_data.score_point(&self.query, point_id),
+                score: nan_to_neg_infinity(self.quantized_data.score_point(&self.query, point_id)),
             };
             size += 1;
             if size == scores.len() {
-                return size;
+                break;
             }
         }
+        record_vector_comparisons(size, true);
         size
     }
 
@@ -47,9 +50,10 @@ where
         for point in points {
             scores.push(ScoredPointOffset {
                 idx: point,
-                score: self.quantized_data.score_point(&self.query, point),
+                score: nan_to_neg_infinity(self.quantized_data.score_point(&self.query, point)),
             });
         }
+        record_vector_comparisons(scores.len(), true);
         scores
     }
 
@@ -73,11 +77,12 @@ where
     }
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
-        self.quantized_data.score_point(&self.query, point)
+        record_vector_comparisons(1, true);
+        nan_to_neg_infinity(self.quantized_data.score_point(&self.query, point))
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
-        self.quantized_data.score_internal(point_a, point_b)
+        nan_to_neg_infinity(self.quantized_data.score_internal(point_a, point_b))
     }
 
     fn peek_top_iter(
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -7,10 +8,12 @@ use serde::{Deserialize, Serialize};
 
 use super::quantized_raw_scorer::QuantizedRawScorer;
 use crate::common::file_operations::{atomic_save_json, read_json};
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
-use crate::entry::entry_point::OperationResult;
+use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::types::{
     CompressionRatio, Distance, ProductQuantization, QuantizationConfig, ScalarQuantization,
+    VectorStorageDatatype,
 };
 use crate::vector_storage::chunked_vectors::ChunkedVectors;
 use crate::vector_storage::quantized::quantized_mmap_storage::{
@@ -116,6 +119,62 @@ impl QuantizedVectors {
         ]
     }
 
+    /// Same as [`QuantizedVectors::create`], for the vectors of any element type.
+    ///
+    /// Quantization operates on single precision floats, so vectors of other element types
+    /// are converted into a temporary in-memory copy first.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_from<'a, T: PrimitiveVectorElement>(
+        vectors: impl Iterator<Item = &'a [T]> + Clone + Send,
+        quantization_config: &QuantizationConfig,
+        distance: Distance,
+        dim: usize,
+        count: usize,
+        path: &Path,
+        on_disk_vector_storage: bool,
+        max_threads: usize,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Self> {
+        if !T::DATATYPE.supports_quantization() {
+            return Err(OperationError::service_error(format!(
+                "Quantization is not supported for {:?} vectors",
+                T::DATATYPE,
+            )));
+        }
+
+        if T::DATATYPE == VectorStorageDatatype::Float32 {
+            return Self::create(
+                vectors.filter_map(T::as_float_slice),
+                quantization_config,
+                distance,
+                dim,
+                count,
+                path,
+                on_disk_vector_storage,
+                max_threads,
+                stopped,
+            );
+        }
+
+        let mut converted = ChunkedVectors::new(dim);
+        converted.try_reserve_exact(count)?;
+        for vector in vectors {
+            check_process_stopped(stopped)?;
+            converted.push(&T::slice_to_float_cow(Cow::Borrowed(vector)))?;
+        }
+        Self::create(
+            (0..converted.len()).map(|i| converted.get(i)),
+            quantization_config,
+            distance,
+            dim,
+            count,
+            path,
+            on_disk_vector_storage,
+            max_threads,
+            stopped,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn create<'a>(
         vectors: impl Iterator<Item = &'a [f32]> + Clone + Send,
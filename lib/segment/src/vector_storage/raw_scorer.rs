@@ -1,9 +1,12 @@
 use std::marker::PhantomData;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use bitvec::prelude::BitSlice;
 
-use super::{ScoredPointOffset, VectorStorage, VectorStorageEnum};
+use super::{DenseVectorStorage, ScoredPointOffset, VectorStorage, VectorStorageEnum};
+use crate::common::hardware_counter::record_vector_comparisons;
+use crate::common::on_disk_io;
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::spaces::metric::Metric;
 use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
@@ -51,7 +54,7 @@ pub trait RawScorer {
     fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset>;
 }
 
-pub struct RawScorerImpl<'a, TMetric: Metric, TVectorStorage: VectorStorage> {
+pub struct RawScorerImpl<'a, TMetric: Metric, TVectorStorage: DenseVectorStorage> {
     pub points_count: PointOffsetType,
     pub query: Vec<VectorElementType>,
     pub vector_storage: &'a TVectorStorage,
@@ -64,6 +67,11 @@ pub struct RawScorerImpl<'a, TMetric: Metric, TVectorStorage: VectorStorage> {
 
 static ASYNC_SCORER: AtomicBool = AtomicBool::new(false);
 
+pub const DEFAULT_ASYNC_SCORER_BATCH_SIZE: usize = 256;
+
+/// Number of vectors, read ahead by the async scorer while the previous ones are scored
+static ASYNC_SCORER_BATCH_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_ASYNC_SCORER_BATCH_SIZE);
+
 pub fn set_async_scorer(async_scorer: bool) {
     ASYNC_SCORER.store(async_scorer, Ordering::Relaxed);
 }
@@ -72,6 +80,26 @@ pub fn get_async_scorer() -> bool {
     ASYNC_SCORER.load(Ordering::Relaxed)
 }
 
+pub fn set_async_scorer_batch_size(batch_size: usize) {
+    ASYNC_SCORER_BATCH_SIZE.store(batch_size.max(1), Ordering::Relaxed);
+}
+
+pub fn get_async_scorer_batch_size() -> usize {
+    ASYNC_SCORER_BATCH_SIZE.load(Ordering::Relaxed)
+}
+
+/// Map NaN score to negative infinity, so that it is always ranked below any real score
+///
+/// NaN is produced by malformed vectors, e.g. by a zero vector normalized for cosine distance.
+#[inline]
+pub fn nan_to_neg_infinity(score: ScoreType) -> ScoreType {
+    if score.is_nan() {
+        ScoreType::NEG_INFINITY
+    } else {
+        score
+    }
+}
+
 pub fn new_raw_scorer<'a>(
     vector: Vec<VectorElementType>,
     vector_storage: &'a VectorStorageEnum,
@@ -81,7 +109,8 @@ pub fn new_raw_scorer<'a>(
         VectorStorageEnum::Simple(vs) => raw_scorer_impl(vector, vs, point_deleted),
 
         VectorStorageEnum::Memmap(vs) => {
-            if get_async_scorer() {
+            // Direct IO reads are only implemented by the async scorer
+            if get_async_scorer() || on_disk_io::get_global().direct_io {
                 #[cfg(target_os = "linux")]
                 match super::async_raw_scorer::new(vector.clone(), vs, point_deleted) {
                     Ok(raw_scorer) => return raw_scorer,
@@ -98,10 +127,20 @@ pub fn new_raw_scorer<'a>(
         VectorStorageEnum::AppendableMemmap(vs) => {
             raw_scorer_impl(vector, vs.as_ref(), point_deleted)
         }
+        VectorStorageEnum::SimpleHalf(vs) => raw_scorer_impl(vector, vs, point_deleted),
+        VectorStorageEnum::MemmapHalf(vs) => raw_scorer_impl(vector, vs.as_ref(), point_deleted),
+        VectorStorageEnum::AppendableMemmapHalf(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted)
+        }
+        VectorStorageEnum::SimpleByte(vs) => raw_scorer_impl(vector, vs, point_deleted),
+        VectorStorageEnum::MemmapByte(vs) => raw_scorer_impl(vector, vs.as_ref(), point_deleted),
+        VectorStorageEnum::AppendableMemmapByte(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted)
+        }
     }
 }
 
-pub fn raw_scorer_impl<'a, TVectorStorage: VectorStorage>(
+pub fn raw_scorer_impl<'a, TVectorStorage: DenseVectorStorage>(
     vector: Vec<VectorElementType>,
     vector_storage: &'a TVectorStorage,
     point_deleted: &'a BitSlice,
@@ -136,10 +175,25 @@ pub fn raw_scorer_impl<'a, TVectorStorage: VectorStorage>(
     }
 }
 
+impl<'a, TMetric, TVectorStorage> RawScorerImpl<'a, TMetric, TVectorStorage>
+where
+    TMetric: Metric,
+    TVectorStorage: DenseVectorStorage,
+{
+    fn score_vector(&self, other_vector: &[TVectorStorage::Element]) -> ScoreType {
+        nan_to_neg_infinity(
+            <TVectorStorage::Element as PrimitiveVectorElement>::similarity::<TMetric>(
+                &self.query,
+                other_vector,
+            ),
+        )
+    }
+}
+
 impl<'a, TMetric, TVectorStorage> RawScorer for RawScorerImpl<'a, TMetric, TVectorStorage>
 where
     TMetric: Metric,
-    TVectorStorage: VectorStorage,
+    TVectorStorage: DenseVectorStorage,
 {
     fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
         let mut size: usize = 0;
@@ -147,17 +201,18 @@ where
             if !self.check_vector(point_id) {
                 continue;
             }
-            let other_vector = self.vector_storage.get_vector(point_id);
+            let other_vector = self.vector_storage.get_dense(point_id);
             scores[size] = ScoredPointOffset {
                 idx: point_id,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: self.score_vector(other_vector),
             };
 
             size += 1;
             if size == scores.len() {
-                return size;
+                break;
             }
         }
+        record_vector_comparisons(size, false);
         size
     }
 
@@ -167,12 +222,13 @@ where
     ) -> Vec<ScoredPointOffset> {
         let mut scores = vec![];
         for point_id in points {
-            let other_vector = self.vector_storage.get_vector(point_id);
+            let other_vector = self.vector_storage.get_dense(point_id);
             scores.push(ScoredPointOffset {
                 idx: point_id,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: self.score_vector(other_vector),
             });
         }
+        record_vector_comparisons(scores.len(), false);
         scores
     }
 
@@ -195,14 +251,19 @@ where
     }
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
-        let other_vector = self.vector_storage.get_vector(point);
-        TMetric::similarity(&self.query, other_vector)
+        record_vector_comparisons(1, false);
+        let other_vector = self.vector_storage.get_dense(point);
+        self.score_vector(other_vector)
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
-        let vector_a = self.vector_storage.get_vector(point_a);
-        let vector_b = self.vector_storage.get_vector(point_b);
-        TMetric::similarity(vector_a, vector_b)
+        let vector_a = self.vector_storage.get_dense(point_a);
+        let vector_b = self.vector_storage.get_dense(point_b);
+        nan_to_neg_infinity(
+            <TVectorStorage::Element as PrimitiveVectorElement>::similarity_internal::<TMetric>(
+                vector_a, vector_b,
+            ),
+        )
     }
 
     fn peek_top_iter(
@@ -210,29 +271,37 @@ where
         points: &mut dyn Iterator<Item = PointOffsetType>,
         top: usize,
     ) -> Vec<ScoredPointOffset> {
+        let mut scored = 0;
         let scores = points
             .filter(|point_id| self.check_vector(*point_id))
             .map(|point_id| {
-                let other_vector = self.vector_storage.get_vector(point_id);
+                scored += 1;
+                let other_vector = self.vector_storage.get_dense(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: self.score_vector(other_vector),
                 }
             });
-        peek_top_largest_iterable(scores, top)
+        let top_scores = peek_top_largest_iterable(scores, top);
+        record_vector_comparisons(scored, false);
+        top_scores
     }
 
     fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset> {
+        let mut scored = 0;
         let scores = (0..self.points_count)
             .filter(|point_id| self.check_vector(*point_id))
             .map(|point_id| {
+                scored += 1;
                 let point_id = point_id as PointOffsetType;
-                let other_vector = &self.vector_storage.get_vector(point_id);
+                let other_vector = self.vector_storage.get_dense(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: self.score_vector(other_vector),
                 }
             });
-        peek_top_largest_iterable(scores, top)
+        let top_scores = peek_top_largest_iterable(scores, top);
+        record_vector_comparisons(scored, false);
+        top_scores
     }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::mem::size_of;
 use std::ops::Range;
 use std::path::Path;
@@ -12,20 +13,25 @@ use rocksdb::DB;
 use serde::{Deserialize, Serialize};
 
 use super::chunked_vectors::ChunkedVectors;
-use super::vector_storage_base::VectorStorage;
+use super::vector_storage_base::{DenseVectorStorage, VectorStorage};
 use super::VectorStorageEnum;
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{
+    CowVector, VectorElementType, VectorElementTypeByte, VectorElementTypeHalf,
+};
 use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 
 /// In-memory vector storage with on-update persistence using `store`
-pub struct SimpleVectorStorage {
+///
+/// Vectors are kept in memory as `T`, but persisted as [`VectorElementType`].
+pub struct SimpleVectorStorage<T: PrimitiveVectorElement = VectorElementType> {
     dim: usize,
     distance: Distance,
-    vectors: ChunkedVectors<VectorElementType>,
+    vectors: ChunkedVectors<T>,
     quantized_vectors: Option<QuantizedVectors>,
     db_wrapper: DatabaseColumnWrapper,
     update_buffer: StoredRecord,
@@ -47,6 +53,52 @@ pub fn open_simple_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = load_simple_vector_storage(database, database_column_name, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Simple(
+        storage,
+    ))))
+}
+
+pub fn open_simple_vector_storage_half(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = load_simple_vector_storage::<VectorElementTypeHalf>(
+        database,
+        database_column_name,
+        dim,
+        distance,
+    )?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::SimpleHalf(
+        storage,
+    ))))
+}
+
+pub fn open_simple_vector_storage_byte(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = load_simple_vector_storage::<VectorElementTypeByte>(
+        database,
+        database_column_name,
+        dim,
+        distance,
+    )?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::SimpleByte(
+        storage,
+    ))))
+}
+
+fn load_simple_vector_storage<T: PrimitiveVectorElement>(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<SimpleVectorStorage<T>> {
     let mut vectors = ChunkedVectors::new(dim);
     let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
 
@@ -63,33 +115,32 @@ pub fn open_simple_vector_storage(
             bitvec_set_deleted(&mut deleted, point_id, true);
             deleted_count += 1;
         }
-        vectors.insert(point_id, &stored_record.vector)?;
+        let vector = T::slice_from_float_cow(Cow::Borrowed(&stored_record.vector));
+        vectors.insert(point_id, &vector)?;
     }
 
     debug!("Segment vectors: {}", vectors.len());
     debug!(
         "Estimated segment size {} MB",
-        vectors.len() * dim * size_of::<VectorElementType>() / 1024 / 1024
+        vectors.len() * dim * size_of::<T>() / 1024 / 1024
     );
 
-    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Simple(
-        SimpleVectorStorage {
-            dim,
-            distance,
-            vectors,
-            quantized_vectors: None,
-            db_wrapper,
-            update_buffer: StoredRecord {
-                deleted: false,
-                vector: vec![0.; dim],
-            },
-            deleted,
-            deleted_count,
+    Ok(SimpleVectorStorage {
+        dim,
+        distance,
+        vectors,
+        quantized_vectors: None,
+        db_wrapper,
+        update_buffer: StoredRecord {
+            deleted: false,
+            vector: vec![0.; dim],
         },
-    ))))
+        deleted,
+        deleted_count,
+    })
 }
 
-impl SimpleVectorStorage {
+impl<T: PrimitiveVectorElement> SimpleVectorStorage<T> {
     /// Set deleted flag for given key. Returns previous deleted state.
     #[inline]
     fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
@@ -130,7 +181,15 @@ impl SimpleVectorStorage {
     }
 }
 
-impl VectorStorage for SimpleVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage for SimpleVectorStorage<T> {
+    type Element = T;
+
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.vectors.get(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for SimpleVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.dim
     }
@@ -143,8 +202,8 @@ impl VectorStorage for SimpleVectorStorage {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
+        T::slice_to_float_cow(Cow::Borrowed(self.vectors.get(key)))
     }
 
     fn insert_vector(
@@ -152,7 +211,8 @@ impl VectorStorage for SimpleVectorStorage {
         key: PointOffsetType,
         vector: &[VectorElementType],
     ) -> OperationResult<()> {
-        self.vectors.insert(key, vector)?;
+        self.vectors
+            .insert(key, &T::slice_from_float_cow(Cow::Borrowed(vector)))?;
         self.set_deleted(key, false);
         self.update_stored(key, false, Some(vector))?;
         Ok(())
@@ -170,9 +230,11 @@ impl VectorStorage for SimpleVectorStorage {
             // Do not perform preprocessing - vectors should be already processed
             let other_vector = other.get_vector(point_id);
             let other_deleted = other.is_deleted_vector(point_id);
-            let new_id = self.vectors.push(other_vector)?;
+            let new_id = self
+                .vectors
+                .push(&T::slice_from_float_cow(Cow::Borrowed(&other_vector)))?;
             self.set_deleted(new_id, other_deleted);
-            self.update_stored(new_id, other_deleted, Some(other_vector))?;
+            self.update_stored(new_id, other_deleted, Some(&other_vector))?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
         Ok(start_index..end_index)
@@ -190,7 +252,7 @@ impl VectorStorage for SimpleVectorStorage {
         stopped: &AtomicBool,
     ) -> OperationResult<()> {
         let vector_data_iterator = (0..self.vectors.len() as u32).map(|i| self.vectors.get(i));
-        self.quantized_vectors = Some(QuantizedVectors::create(
+        self.quantized_vectors = Some(QuantizedVectors::create_from(
             vector_data_iterator,
             quantization_config,
             self.distance,
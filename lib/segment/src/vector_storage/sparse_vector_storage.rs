@@ -0,0 +1,187 @@
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::{BitSlice, BitVec};
+use log::debug;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+use crate::data_types::sparse_vector::SparseVector;
+use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
+use crate::types::PointOffsetType;
+
+/// In-memory storage of sparse vectors with on-update persistence in RocksDB
+///
+/// Vectors are kept sorted by dimension index, which is what the scoring expects.
+pub struct SparseVectorStorage {
+    vectors: Vec<SparseVector>,
+    db_wrapper: DatabaseColumnWrapper,
+    /// BitVec for deleted flags. Grows dynamically upto last set flag.
+    deleted: BitVec,
+    /// Current number of deleted vectors.
+    deleted_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredSparseRecord {
+    pub deleted: bool,
+    pub vector: SparseVector,
+}
+
+pub fn open_sparse_vector_storage(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+) -> OperationResult<Arc<AtomicRefCell<SparseVectorStorage>>> {
+    let mut vectors = Vec::new();
+    let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
+
+    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
+
+    for (key, value) in db_wrapper.lock_db().iter()? {
+        let point_id: PointOffsetType = bincode::deserialize(&key)
+            .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
+        let stored_record: StoredSparseRecord = bincode::deserialize(&value).map_err(|_| {
+            OperationError::service_error("cannot deserialize sparse vector record from db")
+        })?;
+
+        if point_id as usize >= vectors.len() {
+            vectors.resize(point_id as usize + 1, SparseVector::default());
+        }
+        if stored_record.deleted {
+            deleted.resize(deleted.len().max(point_id as usize + 1), false);
+            deleted.set(point_id as usize, true);
+            deleted_count += 1;
+        }
+        vectors[point_id as usize] = stored_record.vector;
+    }
+
+    debug!("Segment sparse vectors: {}", vectors.len());
+
+    Ok(Arc::new(AtomicRefCell::new(SparseVectorStorage {
+        vectors,
+        db_wrapper,
+        deleted,
+        deleted_count,
+    })))
+}
+
+impl SparseVectorStorage {
+    /// Number of vectors, including deleted ones.
+    pub fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn available_vector_count(&self) -> usize {
+        self.vectors.len() - self.deleted_count
+    }
+
+    pub fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    /// Get vector by key, deleted vectors are returned as well.
+    pub fn get(&self, key: PointOffsetType) -> Option<&SparseVector> {
+        self.vectors.get(key as usize)
+    }
+
+    pub fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key as usize).map(|b| *b).unwrap_or(false)
+    }
+
+    pub fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.as_bitslice()
+    }
+
+    /// Iterate over ids of vectors which are not deleted.
+    pub fn iter_ids(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
+        (0..self.vectors.len() as PointOffsetType).filter(|&id| !self.is_deleted_vector(id))
+    }
+
+    pub fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &SparseVector,
+    ) -> OperationResult<()> {
+        if key as usize >= self.vectors.len() {
+            self.vectors
+                .resize(key as usize + 1, SparseVector::default());
+        }
+        self.vectors[key as usize] = vector.clone().into_sorted();
+        self.set_deleted(key, false);
+        self.update_stored(key, false)
+    }
+
+    /// Mark vector as deleted. Returns `true` if the vector was not deleted before.
+    pub fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        if key as usize >= self.vectors.len() {
+            return Ok(false);
+        }
+        let is_deleted = !self.set_deleted(key, true);
+        if is_deleted {
+            self.update_stored(key, true)?;
+        }
+        Ok(is_deleted)
+    }
+
+    /// Append vectors of the other storage, returns the range of assigned keys.
+    pub fn update_from(
+        &mut self,
+        other: &SparseVectorStorage,
+        other_ids: &mut dyn Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            let new_id = self.vectors.len() as PointOffsetType;
+            let other_vector = other.get(point_id).cloned().unwrap_or_default();
+            let other_deleted = other.is_deleted_vector(point_id);
+            self.vectors.push(other_vector);
+            self.set_deleted(new_id, other_deleted);
+            self.update_stored(new_id, other_deleted)?;
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    /// Set deleted flag for given key. Returns previous deleted state.
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
+        let key = key as usize;
+        if key >= self.deleted.len() {
+            if !deleted {
+                return false;
+            }
+            self.deleted.resize(key + 1, false);
+        }
+        let was_deleted = self.deleted.replace(key, deleted);
+        if was_deleted != deleted {
+            if deleted {
+                self.deleted_count += 1;
+            } else {
+                self.deleted_count -= 1;
+            }
+        }
+        was_deleted
+    }
+
+    fn update_stored(&mut self, key: PointOffsetType, deleted: bool) -> OperationResult<()> {
+        let record = StoredSparseRecord {
+            deleted,
+            vector: self.vectors[key as usize].clone(),
+        };
+        self.db_wrapper.put(
+            bincode::serialize(&key).unwrap(),
+            bincode::serialize(&record).unwrap(),
+        )?;
+        Ok(())
+    }
+}
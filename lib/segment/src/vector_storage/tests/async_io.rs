@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::mem::size_of;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+use rand::seq::IteratorRandom as _;
+use rand::{Rng, SeedableRng as _};
+
+use crate::common::mmap_ops;
+use crate::common::on_disk_io::OnDiskIoConfig;
+use crate::data_types::vectors::VectorElementType;
+use crate::types::PointOffsetType;
+use crate::vector_storage::async_io::UringReader;
+
+const HEADER: &[u8] = b"data";
+
+fn write_random_vectors(path: &Path, rng: &mut impl Rng, dim: usize, count: usize) {
+    let mut file = BufWriter::new(File::create(path).unwrap());
+    file.write_all(HEADER).unwrap();
+    for _ in 0..count {
+        let vector: Vec<VectorElementType> = (0..dim).map(|_| rng.gen()).collect();
+        file.write_all(mmap_ops::transmute_to_u8_slice(&vector))
+            .unwrap();
+    }
+    file.flush().unwrap();
+}
+
+fn read_vectors(
+    reader: &mut UringReader,
+    points: &[PointOffsetType],
+) -> Vec<(PointOffsetType, Vec<VectorElementType>)> {
+    let mut result = vec![Default::default(); points.len()];
+    reader
+        .read_stream(points.iter().copied(), |idx, point, vector| {
+            result[idx] = (point, vector.to_vec());
+        })
+        .unwrap();
+    result
+}
+
+#[test]
+fn test_direct_io_reads() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let dir = tempfile::Builder::new()
+        .prefix("async-io")
+        .tempdir()
+        .unwrap();
+    let path = dir.path().join("vectors");
+
+    // Vectors are not aligned to the blocks of direct IO
+    let dim = 100;
+    let count = 1000;
+    write_random_vectors(&path, &mut rng, dim, count);
+    let raw_size = dim * size_of::<VectorElementType>();
+
+    let mut points: Vec<_> = (0..count as PointOffsetType).choose_multiple(&mut rng, 200);
+    // Aligned read of the last vector ends beyond the end of the file
+    points.push(count as PointOffsetType - 1);
+
+    let mut buffered = UringReader::open(&path, raw_size, HEADER.len(), false).unwrap();
+    let expected = read_vectors(&mut buffered, &points);
+
+    // Falls back to buffered reads, if the file system doesn't support direct IO
+    let mut direct = UringReader::open(&path, raw_size, HEADER.len(), true).unwrap();
+    assert_eq!(read_vectors(&mut direct, &points), expected);
+
+    let mmap = mmap_ops::open_read_mmap(&path).unwrap();
+    for (point, vector) in &expected {
+        let offset = HEADER.len() + raw_size * *point as usize;
+        let stored: &[VectorElementType] =
+            mmap_ops::transmute_from_u8_to_slice(&mmap[offset..offset + raw_size]);
+        assert_eq!(vector.as_slice(), stored);
+    }
+}
+
+/// Drop the pages of the file from page cache, to emulate the first access after a restart
+fn evict_page_cache(path: &Path) {
+    let file = File::open(path).unwrap();
+    let res = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+    assert_eq!(res, 0);
+}
+
+fn checksum(vector: &[VectorElementType]) -> u64 {
+    vector.iter().map(|value| u64::from(value.to_bits())).sum()
+}
+
+/// Cold-start reads of random vectors give the same data with all of the on-disk IO settings.
+///
+/// Run with `cargo test --release -p segment on_disk_io_cold_start_reads -- --ignored`
+#[test]
+#[ignore]
+fn on_disk_io_cold_start_reads() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let dir = tempfile::Builder::new()
+        .prefix("async-io")
+        .tempdir()
+        .unwrap();
+    let path = dir.path().join("vectors");
+
+    let dim = 768;
+    let count = 100_000;
+    write_random_vectors(&path, &mut rng, dim, count);
+    let raw_size = dim * size_of::<VectorElementType>();
+    let batches: Vec<Vec<PointOffsetType>> = (0..1000)
+        .map(|_| (0..count as PointOffsetType).choose_multiple(&mut rng, 64))
+        .collect();
+
+    let mut checksums = Vec::new();
+    for on_disk_io in [
+        OnDiskIoConfig::default(),
+        OnDiskIoConfig {
+            populate: true,
+            ..Default::default()
+        },
+        OnDiskIoConfig {
+            populate: true,
+            readahead_kb: Some(1024),
+            ..Default::default()
+        },
+    ] {
+        evict_page_cache(&path);
+        let mmap = mmap_ops::open_read_mmap_populated(&path, on_disk_io).unwrap();
+
+        let batch_checksums: Vec<u64> = batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|point| {
+                        let offset = HEADER.len() + raw_size * *point as usize;
+                        checksum(mmap_ops::transmute_from_u8_to_slice(
+                            &mmap[offset..offset + raw_size],
+                        ))
+                    })
+                    .sum()
+            })
+            .collect();
+        checksums.push(batch_checksums);
+    }
+
+    for direct_io in [false, true] {
+        evict_page_cache(&path);
+        let mut reader = UringReader::open(&path, raw_size, HEADER.len(), direct_io).unwrap();
+
+        let batch_checksums: Vec<u64> = batches
+            .iter()
+            .map(|batch| {
+                let mut batch_checksum = 0;
+                reader
+                    .read_stream(batch.iter().copied(), |_, _, vector| {
+                        batch_checksum += checksum(vector);
+                    })
+                    .unwrap();
+                batch_checksum
+            })
+            .collect();
+        checksums.push(batch_checksums);
+    }
+
+    assert!(checksums.iter().all(|other| *other == checksums[0]));
+}
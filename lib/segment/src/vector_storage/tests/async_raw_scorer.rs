@@ -139,6 +139,110 @@ fn test_random_score(
 
     assert_eq!(res, async_res);
 
+    // Rescoring keeps the order of the candidates
+    let res = raw_scorer.score_points_unfiltered(&mut points.iter().copied());
+    let async_res = async_raw_scorer.score_points_unfiltered(&mut points.iter().copied());
+
+    assert_eq!(res, async_res);
+
+    Ok(())
+}
+
+#[test]
+fn prefetched_vectors_order() -> Result<()> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let dim = 16;
+
+    let dir = tempfile::Builder::new()
+        .prefix("immutable-storage")
+        .tempdir()?;
+    let storage = open_memmap_vector_storage(dir.path(), dim, Distance::Dot)?;
+    let mut storage = storage.borrow_mut();
+
+    {
+        let dir = tempfile::Builder::new()
+            .prefix("mutable-storage")
+            .tempdir()?;
+        let db = rocksdb_wrapper::open_db(dir.path(), &[rocksdb_wrapper::DB_VECTOR_CF])?;
+        let mutable_storage =
+            open_simple_vector_storage(db, rocksdb_wrapper::DB_VECTOR_CF, dim, Distance::Dot)?;
+        let mut mutable_storage = mutable_storage.borrow_mut();
+        insert_random_vectors(&mut rng, &mut *mutable_storage, 500)?;
+        storage.update_from(&mutable_storage, &mut (0..500), &Default::default())?;
+    }
+
+    let VectorStorageEnum::Memmap(storage) = &*storage else {
+        unreachable!();
+    };
+    let mmap_vectors = storage.get_mmap_vectors();
+
+    let points = (0..500).choose_multiple(&mut rng, 300);
+    // Batch size doesn't divide the number of points, so the last batch is partial
+    for batch_size in [1, 7, 300, 1000] {
+        let mut read_points = vec![];
+        mmap_vectors.process_vectors_prefetched(&points, batch_size, |point, vector| {
+            assert_eq!(vector, mmap_vectors.get_vector(point));
+            read_points.push(point);
+        })?;
+        assert_eq!(read_points, points);
+    }
+
+    Ok(())
+}
+
+/// Rescoring of many candidates with prefetched async reads gives the same scores as sync reads.
+///
+/// Run with `cargo test --release -p segment prefetched_rescoring_at_scale -- --ignored`
+#[test]
+#[ignore]
+fn prefetched_rescoring_at_scale() -> Result<()> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let dim = 768;
+    let points_count = 50_000;
+
+    let dir = tempfile::Builder::new()
+        .prefix("immutable-storage")
+        .tempdir()?;
+    let storage = open_memmap_vector_storage(dir.path(), dim, Distance::Dot)?;
+    let mut storage = storage.borrow_mut();
+
+    let mut id_tracker = FixtureIdTracker::new(points_count);
+    {
+        let dir = tempfile::Builder::new()
+            .prefix("mutable-storage")
+            .tempdir()?;
+        let db = rocksdb_wrapper::open_db(dir.path(), &[rocksdb_wrapper::DB_VECTOR_CF])?;
+        let mutable_storage =
+            open_simple_vector_storage(db, rocksdb_wrapper::DB_VECTOR_CF, dim, Distance::Dot)?;
+        let mut mutable_storage = mutable_storage.borrow_mut();
+        insert_random_vectors(&mut rng, &mut *mutable_storage, points_count)?;
+        delete_random_vectors(&mut rng, &mut *mutable_storage, &mut id_tracker, 0)?;
+        storage.update_from(
+            &mutable_storage,
+            &mut (0..points_count as _),
+            &Default::default(),
+        )?;
+    }
+
+    let query: Vec<_> = sampler(&mut rng).take(dim).collect();
+    let deleted_points = id_tracker.deleted_point_bitslice();
+    let raw_scorer = new_raw_scorer(query.clone(), &storage, deleted_points);
+    let VectorStorageEnum::Memmap(memmap_storage) = &*storage else {
+        unreachable!();
+    };
+    let async_raw_scorer = async_raw_scorer::new(query, memmap_storage, deleted_points)?;
+
+    let candidates: Vec<Vec<PointOffsetType>> = (0..100)
+        .map(|_| (0..points_count as _).choose_multiple(&mut rng, 1000))
+        .collect();
+
+    for points in &candidates {
+        assert_eq!(
+            async_raw_scorer.score_points_unfiltered(&mut points.iter().copied()),
+            raw_scorer.score_points_unfiltered(&mut points.iter().copied()),
+        );
+    }
+
     Ok(())
 }
 
@@ -1,3 +1,5 @@
 #[cfg(target_os = "linux")]
+mod async_io;
+#[cfg(target_os = "linux")]
 mod async_raw_scorer;
 mod test_appendable_vector_storage;
@@ -2,17 +2,29 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use tempfile::Builder;
 
 use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
 use crate::data_types::vectors::VectorElementType;
+use crate::fixtures::index_fixtures::random_vector;
 use crate::fixtures::payload_context_fixture::FixtureIdTracker;
 use crate::id_tracker::{IdTracker, IdTrackerSS};
 use crate::types::{
     Distance, PointIdType, PointOffsetType, QuantizationConfig, ScalarQuantizationConfig,
+    VectorStorageDatatype,
+};
+use crate::vector_storage::appendable_mmap_vector_storage::{
+    open_appendable_memmap_vector_storage, open_appendable_memmap_vector_storage_byte,
+    open_appendable_memmap_vector_storage_half,
+};
+use crate::vector_storage::memmap_vector_storage::{
+    open_memmap_vector_storage_byte, open_memmap_vector_storage_half,
+};
+use crate::vector_storage::simple_vector_storage::{
+    open_simple_vector_storage, open_simple_vector_storage_byte, open_simple_vector_storage_half,
 };
-use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
-use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
 use crate::vector_storage::{new_raw_scorer, ScoredPointOffset, VectorStorage, VectorStorageEnum};
 
 fn do_test_delete_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
@@ -261,6 +273,41 @@ fn do_test_score_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
     assert!(!all_ids1.contains(&top_idx))
 }
 
+fn do_test_nan_score_ranked_last(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
+    let points = vec![
+        vec![1.0, 0.0, 1.0, 1.0],
+        vec![f32::NAN, 0.0, 1.0, 0.0],
+        vec![-1.0, -1.0, -1.0, -1.0],
+        vec![1.0, 1.0, 0.0, 1.0],
+    ];
+    let id_tracker = FixtureIdTracker::new(points.len());
+    let mut borrowed_storage = storage.borrow_mut();
+
+    for (i, vec) in points.iter().enumerate() {
+        borrowed_storage
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+    }
+
+    let query: Vec<VectorElementType> = vec![0.0, 1.0, 1.1, 1.0];
+    let raw_scorer = new_raw_scorer(
+        query,
+        &borrowed_storage,
+        id_tracker.deleted_point_bitslice(),
+    );
+
+    // Crafted vector produces NaN score, which must not outrank any real result
+    assert_eq!(raw_scorer.score_point(1), f32::NEG_INFINITY);
+
+    let closest = raw_scorer.peek_top_iter(&mut [0, 1, 2, 3].iter().cloned(), 4);
+    let closest_ids: Vec<_> = closest.iter().map(|scored| scored.idx).collect();
+    assert_eq!(closest_ids, vec![0, 3, 2, 1]);
+    assert!(closest.iter().all(|scored| !scored.score.is_nan()));
+
+    let closest = raw_scorer.peek_top_all(4);
+    assert_eq!(closest.last().map(|scored| scored.idx), Some(1));
+}
+
 fn test_score_quantized_points(storage: Arc<AtomicRefCell<VectorStorageEnum>>) {
     let points = vec![
         vec![1.0, 0.0, 1.0, 1.0],
@@ -388,6 +435,14 @@ fn test_score_points_in_simple_vector_storages() {
     let _storage = open_simple_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
 }
 
+#[test]
+fn test_nan_score_ranked_last_in_simple_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+    do_test_nan_score_ranked_last(storage);
+}
+
 #[test]
 fn test_score_quantized_points_simple_vector_storages() {
     let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
@@ -441,6 +496,13 @@ fn test_score_points_in_appendable_memmap_vector_storages() {
     let _storage = open_appendable_memmap_vector_storage(dir.path(), 4, Distance::Dot).unwrap();
 }
 
+#[test]
+fn test_nan_score_ranked_last_in_appendable_memmap_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_appendable_memmap_vector_storage(dir.path(), 4, Distance::Dot).unwrap();
+    do_test_nan_score_ranked_last(storage);
+}
+
 #[test]
 fn test_score_quantized_points_appendable_memmap_vector_storages() {
     let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
@@ -452,3 +514,326 @@ fn test_score_quantized_points_appendable_memmap_vector_storages() {
 
     let _storage = open_appendable_memmap_vector_storage(dir.path(), 4, Distance::Dot).unwrap();
 }
+
+// ----------------------------------------------
+
+/// Compare the half precision storage with a full precision one, filled with the same vectors
+fn do_test_half_storage_matches_float(
+    storage: Arc<AtomicRefCell<VectorStorageEnum>>,
+    distance: Distance,
+) {
+    let dim = 67;
+    let num_points = 50;
+    let mut rnd = StdRng::seed_from_u64(42);
+    let points: Vec<_> = (0..num_points)
+        .map(|_| random_vector(&mut rnd, dim))
+        .collect();
+
+    let dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let float_storage = open_simple_vector_storage(db, DB_VECTOR_CF, dim, distance).unwrap();
+    let mut float_storage = float_storage.borrow_mut();
+    let mut half_storage = storage.borrow_mut();
+    assert_eq!(half_storage.datatype(), VectorStorageDatatype::Float16);
+    assert_eq!(float_storage.datatype(), VectorStorageDatatype::Float32);
+
+    for (i, vec) in points.iter().enumerate() {
+        float_storage
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+        half_storage
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+    }
+
+    // Vectors are returned as float, with the precision of half
+    for i in 0..num_points as PointOffsetType {
+        let float_vector = float_storage.get_vector(i);
+        let half_vector = half_storage.get_vector(i);
+        assert_eq!(float_vector.len(), half_vector.len());
+        for (a, b) in float_vector.iter().zip(half_vector.iter()) {
+            assert!((a - b).abs() < 1e-2, "{a} != {b}");
+        }
+    }
+
+    let id_tracker = FixtureIdTracker::new(num_points);
+    let query = random_vector(&mut rnd, dim);
+    let float_scorer = new_raw_scorer(
+        query.clone(),
+        &float_storage,
+        id_tracker.deleted_point_bitslice(),
+    );
+    let half_scorer = new_raw_scorer(query, &half_storage, id_tracker.deleted_point_bitslice());
+    for i in 0..num_points as PointOffsetType {
+        let float_score = float_scorer.score_point(i);
+        let half_score = half_scorer.score_point(i);
+        assert!(
+            (float_score - half_score).abs() < 2e-2 * float_score.abs().max(1.0),
+            "{float_score} != {half_score}",
+        );
+
+        let float_score = float_scorer.score_internal(0, i);
+        let half_score = half_scorer.score_internal(0, i);
+        assert!(
+            (float_score - half_score).abs() < 2e-2 * float_score.abs().max(1.0),
+            "{float_score} != {half_score}",
+        );
+    }
+}
+
+#[test]
+fn test_half_simple_vector_storages() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        {
+            let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+            let storage = open_simple_vector_storage_half(db, DB_VECTOR_CF, 67, distance).unwrap();
+            do_test_half_storage_matches_float(storage.clone(), distance);
+            storage.borrow().flusher()().unwrap();
+        }
+
+        // Vectors are restored from the database
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_vector_storage_half(db, DB_VECTOR_CF, 67, distance).unwrap();
+        assert_eq!(storage.borrow().total_vector_count(), 50);
+    }
+}
+
+#[test]
+fn test_half_appendable_memmap_vector_storages() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        {
+            let storage =
+                open_appendable_memmap_vector_storage_half(dir.path(), 67, distance).unwrap();
+            do_test_half_storage_matches_float(storage.clone(), distance);
+            storage.borrow().flusher()().unwrap();
+        }
+
+        let storage = open_appendable_memmap_vector_storage_half(dir.path(), 67, distance).unwrap();
+        assert_eq!(storage.borrow().total_vector_count(), 50);
+    }
+}
+
+#[test]
+fn test_delete_points_in_half_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage_half(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+    do_test_delete_points(storage);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_appendable_memmap_vector_storage_half(dir.path(), 4, Distance::Dot).unwrap();
+    do_test_update_from_delete_points(storage);
+}
+
+#[test]
+fn test_score_quantized_points_half_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_appendable_memmap_vector_storage_half(dir.path(), 4, Distance::Dot).unwrap();
+    test_score_quantized_points(storage);
+}
+
+#[test]
+fn test_half_memmap_vector_storage_update_from() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let source = open_simple_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+    let points = vec![
+        vec![1.0, 0.0, 1.0, 1.0],
+        vec![1.0, 0.0, 1.0, 0.0],
+        vec![0.5, 0.25, 1.0, 2.0],
+    ];
+    for (i, vec) in points.iter().enumerate() {
+        source
+            .borrow_mut()
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+    }
+
+    let mmap_dir = Builder::new().prefix("mmap_dir").tempdir().unwrap();
+    {
+        let storage = open_memmap_vector_storage_half(mmap_dir.path(), 4, Distance::Dot).unwrap();
+        storage
+            .borrow_mut()
+            .update_from(
+                &source.borrow(),
+                &mut Box::new(0..points.len() as u32),
+                &Default::default(),
+            )
+            .unwrap();
+    }
+
+    // Values are exactly representable in half precision
+    let storage = open_memmap_vector_storage_half(mmap_dir.path(), 4, Distance::Dot).unwrap();
+    let storage = storage.borrow();
+    assert_eq!(storage.datatype(), VectorStorageDatatype::Float16);
+    for (i, vec) in points.iter().enumerate() {
+        assert_eq!(
+            storage.get_vector(i as PointOffsetType).as_ref(),
+            vec.as_slice()
+        );
+    }
+}
+
+// ----------------------------------------------
+
+/// Compare the byte storage with a full precision one, filled with the same integer vectors
+fn do_test_byte_storage_matches_float(
+    storage: Arc<AtomicRefCell<VectorStorageEnum>>,
+    distance: Distance,
+) {
+    let dim = 67;
+    let num_points = 50;
+    let mut rnd = StdRng::seed_from_u64(42);
+    let points: Vec<Vec<VectorElementType>> = (0..num_points)
+        .map(|_| (0..dim).map(|_| rnd.gen_range(0..=255) as f32).collect())
+        .collect();
+
+    let dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let float_storage = open_simple_vector_storage(db, DB_VECTOR_CF, dim, distance).unwrap();
+    let mut float_storage = float_storage.borrow_mut();
+    let mut byte_storage = storage.borrow_mut();
+    assert_eq!(byte_storage.datatype(), VectorStorageDatatype::Uint8);
+
+    for (i, vec) in points.iter().enumerate() {
+        // Byte vectors are stored as is, the float ones are preprocessed by the segment
+        let processed = distance.preprocess_vector(vec);
+        float_storage
+            .insert_vector(i as PointOffsetType, processed.as_ref().unwrap_or(vec))
+            .unwrap();
+        byte_storage
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+    }
+
+    // Integer values are returned exactly
+    for (i, vec) in points.iter().enumerate() {
+        assert_eq!(
+            byte_storage.get_vector(i as PointOffsetType).as_ref(),
+            vec.as_slice()
+        );
+    }
+
+    let id_tracker = FixtureIdTracker::new(num_points);
+    let query: Vec<VectorElementType> = (0..dim).map(|_| rnd.gen_range(-1.0..1.0)).collect();
+    let float_scorer = new_raw_scorer(
+        query.clone(),
+        &float_storage,
+        id_tracker.deleted_point_bitslice(),
+    );
+    let byte_scorer = new_raw_scorer(query, &byte_storage, id_tracker.deleted_point_bitslice());
+    for i in 0..num_points as PointOffsetType {
+        let float_score = float_scorer.score_point(i);
+        let byte_score = byte_scorer.score_point(i);
+        assert!(
+            (float_score - byte_score).abs() < 1e-4 * float_score.abs().max(1.0),
+            "{float_score} != {byte_score}",
+        );
+
+        let float_score = float_scorer.score_internal(0, i);
+        let byte_score = byte_scorer.score_internal(0, i);
+        assert!(
+            (float_score - byte_score).abs() < 1e-4 * float_score.abs().max(1.0),
+            "{float_score} != {byte_score}",
+        );
+    }
+}
+
+#[test]
+fn test_byte_simple_vector_storages() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        {
+            let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+            let storage = open_simple_vector_storage_byte(db, DB_VECTOR_CF, 67, distance).unwrap();
+            do_test_byte_storage_matches_float(storage.clone(), distance);
+            storage.borrow().flusher()().unwrap();
+        }
+
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_vector_storage_byte(db, DB_VECTOR_CF, 67, distance).unwrap();
+        assert_eq!(storage.borrow().total_vector_count(), 50);
+    }
+}
+
+#[test]
+fn test_byte_appendable_memmap_vector_storages() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        {
+            let storage =
+                open_appendable_memmap_vector_storage_byte(dir.path(), 67, distance).unwrap();
+            do_test_byte_storage_matches_float(storage.clone(), distance);
+            storage.borrow().flusher()().unwrap();
+        }
+
+        let storage = open_appendable_memmap_vector_storage_byte(dir.path(), 67, distance).unwrap();
+        assert_eq!(storage.borrow().total_vector_count(), 50);
+    }
+}
+
+#[test]
+fn test_byte_memmap_vector_storage_update_from() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let source = open_appendable_memmap_vector_storage_byte(dir.path(), 4, Distance::Dot).unwrap();
+    let points = vec![
+        vec![1.0, 0.0, 255.0, 1.0],
+        vec![1.0, 0.0, 1.0, 0.0],
+        vec![7.0, 128.0, 1.0, 2.0],
+    ];
+    for (i, vec) in points.iter().enumerate() {
+        source
+            .borrow_mut()
+            .insert_vector(i as PointOffsetType, vec)
+            .unwrap();
+    }
+
+    let mmap_dir = Builder::new().prefix("mmap_dir").tempdir().unwrap();
+    {
+        let storage = open_memmap_vector_storage_byte(mmap_dir.path(), 4, Distance::Dot).unwrap();
+        storage
+            .borrow_mut()
+            .update_from(
+                &source.borrow(),
+                &mut Box::new(0..points.len() as u32),
+                &Default::default(),
+            )
+            .unwrap();
+    }
+
+    let storage = open_memmap_vector_storage_byte(mmap_dir.path(), 4, Distance::Dot).unwrap();
+    let storage = storage.borrow();
+    assert_eq!(storage.datatype(), VectorStorageDatatype::Uint8);
+    for (i, vec) in points.iter().enumerate() {
+        assert_eq!(
+            storage.get_vector(i as PointOffsetType).as_ref(),
+            vec.as_slice()
+        );
+    }
+}
+
+#[test]
+fn test_byte_vector_storage_rejects_quantization() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_appendable_memmap_vector_storage_byte(dir.path(), 4, Distance::Dot).unwrap();
+    let mut storage = storage.borrow_mut();
+    storage.insert_vector(0, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+
+    let config: QuantizationConfig = ScalarQuantizationConfig {
+        r#type: Default::default(),
+        quantile: None,
+        always_ram: None,
+    }
+    .into();
+    let quantization_dir = Builder::new()
+        .prefix("quantization_path")
+        .tempdir()
+        .unwrap();
+    let stopped = AtomicBool::new(false);
+    assert!(storage
+        .quantize(quantization_dir.path(), &config, 1, &stopped)
+        .is_err());
+    assert!(storage.quantized_storage().is_none());
+}
@@ -10,9 +10,14 @@ use super::memmap_vector_storage::MemmapVectorStorage;
 use super::quantized::quantized_vectors::QuantizedVectors;
 use super::simple_vector_storage::SimpleVectorStorage;
 use crate::common::Flusher;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{
+    CowVector, VectorElementType, VectorElementTypeByte, VectorElementTypeHalf,
+};
 use crate::entry::entry_point::OperationResult;
-use crate::types::{Distance, PointOffsetType, QuantizationConfig, ScoreType};
+use crate::types::{
+    Distance, PointOffsetType, QuantizationConfig, ScoreType, VectorStorageDatatype,
+};
 use crate::vector_storage::appendable_mmap_vector_storage::AppendableMmapVectorStorage;
 
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
@@ -60,8 +65,8 @@ pub trait VectorStorage {
             .saturating_sub(self.deleted_vector_count())
     }
 
-    /// Number of all stored vectors including deleted
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType];
+    /// Get the vector by the given key, converted from the element type of the storage
+    fn get_vector(&self, key: PointOffsetType) -> CowVector;
 
     fn insert_vector(
         &mut self,
@@ -128,10 +133,41 @@ pub trait VectorStorage {
     fn is_appendable(&self) -> bool;
 }
 
+/// Vector storage, which keeps the vectors as slices of its element type
+pub trait DenseVectorStorage: VectorStorage {
+    type Element: PrimitiveVectorElement;
+
+    /// Get the vector by the given key, as it is stored
+    fn get_dense(&self, key: PointOffsetType) -> &[Self::Element];
+}
+
 pub enum VectorStorageEnum {
     Simple(SimpleVectorStorage),
     Memmap(Box<MemmapVectorStorage>),
     AppendableMemmap(Box<AppendableMmapVectorStorage>),
+    SimpleHalf(SimpleVectorStorage<VectorElementTypeHalf>),
+    MemmapHalf(Box<MemmapVectorStorage<VectorElementTypeHalf>>),
+    AppendableMemmapHalf(Box<AppendableMmapVectorStorage<VectorElementTypeHalf>>),
+    SimpleByte(SimpleVectorStorage<VectorElementTypeByte>),
+    MemmapByte(Box<MemmapVectorStorage<VectorElementTypeByte>>),
+    AppendableMemmapByte(Box<AppendableMmapVectorStorage<VectorElementTypeByte>>),
+}
+
+impl VectorStorageEnum {
+    /// Element type, in which the vectors are stored
+    pub fn datatype(&self) -> VectorStorageDatatype {
+        match self {
+            VectorStorageEnum::Simple(_)
+            | VectorStorageEnum::Memmap(_)
+            | VectorStorageEnum::AppendableMemmap(_) => VectorStorageDatatype::Float32,
+            VectorStorageEnum::SimpleHalf(_)
+            | VectorStorageEnum::MemmapHalf(_)
+            | VectorStorageEnum::AppendableMemmapHalf(_) => VectorStorageDatatype::Float16,
+            VectorStorageEnum::SimpleByte(_)
+            | VectorStorageEnum::MemmapByte(_)
+            | VectorStorageEnum::AppendableMemmapByte(_) => VectorStorageDatatype::Uint8,
+        }
+    }
 }
 
 impl VectorStorage for VectorStorageEnum {
@@ -140,6 +176,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.vector_dim(),
             VectorStorageEnum::Memmap(v) => v.vector_dim(),
             VectorStorageEnum::AppendableMemmap(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleHalf(v) => v.vector_dim(),
+            VectorStorageEnum::MemmapHalf(v) => v.vector_dim(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleByte(v) => v.vector_dim(),
+            VectorStorageEnum::MemmapByte(v) => v.vector_dim(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.vector_dim(),
         }
     }
 
@@ -148,6 +190,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.distance(),
             VectorStorageEnum::Memmap(v) => v.distance(),
             VectorStorageEnum::AppendableMemmap(v) => v.distance(),
+            VectorStorageEnum::SimpleHalf(v) => v.distance(),
+            VectorStorageEnum::MemmapHalf(v) => v.distance(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.distance(),
+            VectorStorageEnum::SimpleByte(v) => v.distance(),
+            VectorStorageEnum::MemmapByte(v) => v.distance(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.distance(),
         }
     }
 
@@ -156,14 +204,26 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.total_vector_count(),
             VectorStorageEnum::Memmap(v) => v.total_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::MemmapHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.total_vector_count(),
+            VectorStorageEnum::MemmapByte(v) => v.total_vector_count(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.total_vector_count(),
         }
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
+    fn get_vector(&self, key: PointOffsetType) -> CowVector {
         match self {
             VectorStorageEnum::Simple(v) => v.get_vector(key),
             VectorStorageEnum::Memmap(v) => v.get_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.get_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.get_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.get_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.get_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.get_vector(key),
         }
     }
 
@@ -176,6 +236,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.insert_vector(key, vector),
             VectorStorageEnum::Memmap(v) => v.insert_vector(key, vector),
             VectorStorageEnum::AppendableMemmap(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::MemmapHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleByte(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::MemmapByte(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.insert_vector(key, vector),
         }
     }
 
@@ -189,6 +255,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::Memmap(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::AppendableMemmap(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::MemmapHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleByte(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::MemmapByte(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.update_from(other, other_ids, stopped),
         }
     }
 
@@ -197,6 +269,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.flusher(),
             VectorStorageEnum::Memmap(v) => v.flusher(),
             VectorStorageEnum::AppendableMemmap(v) => v.flusher(),
+            VectorStorageEnum::SimpleHalf(v) => v.flusher(),
+            VectorStorageEnum::MemmapHalf(v) => v.flusher(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.flusher(),
+            VectorStorageEnum::SimpleByte(v) => v.flusher(),
+            VectorStorageEnum::MemmapByte(v) => v.flusher(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.flusher(),
         }
     }
 
@@ -217,6 +295,24 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::AppendableMemmap(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::SimpleHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::MemmapHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::AppendableMemmapHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::SimpleByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::MemmapByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::AppendableMemmapByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
         }
     }
 
@@ -225,6 +321,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.load_quantization(data_path),
             VectorStorageEnum::Memmap(v) => v.load_quantization(data_path),
             VectorStorageEnum::AppendableMemmap(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::MemmapHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleByte(v) => v.load_quantization(data_path),
+            VectorStorageEnum::MemmapByte(v) => v.load_quantization(data_path),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.load_quantization(data_path),
         }
     }
 
@@ -233,6 +335,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.quantized_storage(),
             VectorStorageEnum::Memmap(v) => v.quantized_storage(),
             VectorStorageEnum::AppendableMemmap(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::MemmapHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleByte(v) => v.quantized_storage(),
+            VectorStorageEnum::MemmapByte(v) => v.quantized_storage(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.quantized_storage(),
         }
     }
 
@@ -241,6 +349,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.files(),
             VectorStorageEnum::Memmap(v) => v.files(),
             VectorStorageEnum::AppendableMemmap(v) => v.files(),
+            VectorStorageEnum::SimpleHalf(v) => v.files(),
+            VectorStorageEnum::MemmapHalf(v) => v.files(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.files(),
+            VectorStorageEnum::SimpleByte(v) => v.files(),
+            VectorStorageEnum::MemmapByte(v) => v.files(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.files(),
         }
     }
 
@@ -249,6 +363,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.delete_vector(key),
             VectorStorageEnum::Memmap(v) => v.delete_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.delete_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.delete_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.delete_vector(key),
         }
     }
 
@@ -257,6 +377,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.is_deleted_vector(key),
             VectorStorageEnum::Memmap(v) => v.is_deleted_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.is_deleted_vector(key),
         }
     }
 
@@ -265,6 +391,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.deleted_vector_count(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::MemmapHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_count(),
+            VectorStorageEnum::MemmapByte(v) => v.deleted_vector_count(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.deleted_vector_count(),
         }
     }
 
@@ -273,6 +405,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::MemmapHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::MemmapByte(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.deleted_vector_bitslice(),
         }
     }
 
@@ -281,6 +419,12 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => v.is_appendable(),
             VectorStorageEnum::Memmap(v) => v.is_appendable(),
             VectorStorageEnum::AppendableMemmap(v) => v.is_appendable(),
+            VectorStorageEnum::SimpleHalf(v) => v.is_appendable(),
+            VectorStorageEnum::MemmapHalf(v) => v.is_appendable(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.is_appendable(),
+            VectorStorageEnum::SimpleByte(v) => v.is_appendable(),
+            VectorStorageEnum::MemmapByte(v) => v.is_appendable(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.is_appendable(),
         }
     }
 }
@@ -3,6 +3,7 @@ use std::sync::atomic::AtomicBool;
 
 use rand::prelude::StdRng;
 use rand::SeedableRng;
+use segment::common::cpu::CpuPermit;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::index_fixtures::random_vector;
@@ -38,9 +39,12 @@ fn test_batch_and_single_request_equivalency() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let int_key = "int";
@@ -136,6 +140,8 @@ fn test_batch_and_single_request_equivalency() {
         payload_m: None,
     };
 
+    let permit = CpuPermit::dummy(hnsw_config.max_indexing_threads);
+
     let vector_storage = &segment.vector_data[DEFAULT_VECTOR_NAME].vector_storage;
     let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
         hnsw_dir.path(),
@@ -146,7 +152,7 @@ fn test_batch_and_single_request_equivalency() {
     )
     .unwrap();
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     for _ in 0..10 {
         let query_vector_1 = random_vector(&mut rnd, dim);
@@ -3,6 +3,7 @@ const NUM_VECTORS_2: u64 = 500;
 
 use std::sync::atomic::AtomicBool;
 
+use segment::common::cpu::CpuPermit;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::entry::entry_point::SegmentEntry;
 use segment::segment::Segment;
@@ -88,7 +89,7 @@ fn test_rebuild_with_removed_vectors() {
     builder.update_from(&segment1, &stopped).unwrap();
     builder.update_from(&segment2, &stopped).unwrap();
 
-    let merged_segment: Segment = builder.build(&stopped).unwrap();
+    let merged_segment: Segment = builder.build(CpuPermit::dummy(1), &stopped).unwrap();
 
     let merged_points_count = merged_segment.available_point_count();
 
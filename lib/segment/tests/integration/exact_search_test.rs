@@ -3,6 +3,7 @@ use std::sync::atomic::AtomicBool;
 
 use itertools::Itertools;
 use rand::{thread_rng, Rng};
+use segment::common::cpu::CpuPermit;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_fixtures::{random_int_payload, random_vector};
@@ -46,9 +47,12 @@ fn exact_search_test() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let int_key = "int";
@@ -81,6 +85,8 @@ fn exact_search_test() {
         payload_m: None,
     };
 
+    let permit = CpuPermit::dummy(hnsw_config.max_indexing_threads);
+
     let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
         hnsw_dir.path(),
         segment.id_tracker.clone(),
@@ -92,7 +98,7 @@ fn exact_search_test() {
     )
     .unwrap();
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     payload_index_ptr
         .borrow_mut()
@@ -132,7 +138,7 @@ fn exact_search_test() {
         "not all points are covered by payload blocks"
     );
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     let top = 3;
     let attempts = 50;
@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicBool;
 use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
+use segment::common::cpu::CpuPermit;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_fixtures::{random_int_payload, random_vector};
@@ -47,9 +48,12 @@ fn test_filterable_hnsw() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let int_key = "int";
@@ -82,6 +86,8 @@ fn test_filterable_hnsw() {
         payload_m: None,
     };
 
+    let permit = CpuPermit::dummy(hnsw_config.max_indexing_threads);
+
     let vector_storage = &segment.vector_data[DEFAULT_VECTOR_NAME].vector_storage;
     let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
         hnsw_dir.path(),
@@ -92,7 +98,7 @@ fn test_filterable_hnsw() {
     )
     .unwrap();
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     payload_index_ptr
         .borrow_mut()
@@ -132,7 +138,7 @@ fn test_filterable_hnsw() {
         "not all points are covered by payload blocks"
     );
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     let top = 3;
     let mut hits = 0;
@@ -120,6 +120,7 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
                 (
@@ -130,6 +131,7 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
                 (
@@ -140,10 +142,13 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: Default::default(),
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
+            payload_cache_fields: Default::default(),
         },
         true,
     )
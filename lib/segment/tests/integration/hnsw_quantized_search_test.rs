@@ -2,6 +2,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::sync::atomic::AtomicBool;
 
 use rand::thread_rng;
+use segment::common::cpu::CpuPermit;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_fixtures::random_vector;
@@ -53,9 +54,12 @@ fn hnsw_quantized_search_test(
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -83,6 +87,8 @@ fn hnsw_quantized_search_test(
         payload_m: None,
     };
 
+    let permit = CpuPermit::dummy(hnsw_config.max_indexing_threads);
+
     let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
         hnsw_dir.path(),
         segment.id_tracker.clone(),
@@ -94,7 +100,7 @@ fn hnsw_quantized_search_test(
     )
     .unwrap();
 
-    hnsw_index.build_index(&stopped).unwrap();
+    hnsw_index.build_index(&permit, &stopped).unwrap();
 
     let top = 10;
     let attempts = 10;
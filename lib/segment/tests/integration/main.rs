@@ -15,6 +15,8 @@ pub mod fixtures;
 #[cfg(test)]
 pub mod hnsw_quantized_search_test;
 #[cfg(test)]
+pub mod multitenant_hnsw_test;
+#[cfg(test)]
 pub mod nested_filtering_test;
 #[cfg(test)]
 pub mod payload_index_test;
@@ -0,0 +1,211 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::common::cpu::CpuPermit;
+use segment::common::hardware_counter::HardwareCounterCell;
+use segment::data_types::keyword_index::{KeywordIndexParams, KeywordIndexType};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload, PayloadContainer,
+    PayloadFieldSchema, PayloadSchemaParams, PointOffsetType, SearchParams, SegmentConfig,
+    SeqNumberType, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const TENANT_KEY: &str = "tenant";
+
+fn tenant_schema(is_tenant: bool) -> PayloadFieldSchema {
+    PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+        r#type: KeywordIndexType::Keyword,
+        is_tenant: Some(is_tenant),
+        on_disk: None,
+    }))
+}
+
+fn tenant_filter(tenant: usize) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        TENANT_KEY,
+        format!("tenant-{tenant}").into(),
+    )))
+}
+
+/// Segment with points of the tenants interleaved: point `n` belongs to tenant `n % num_tenants`
+fn build_tenant_segment(
+    path: &Path,
+    dim: usize,
+    num_tenants: usize,
+    points_per_tenant: usize,
+    is_tenant: bool,
+    rnd: &mut StdRng,
+) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: Default::default(),
+            },
+        )]),
+        sparse_vector_data: Default::default(),
+        payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
+    };
+
+    let mut segment = build_segment(path, &config, true).unwrap();
+    segment
+        .create_field_index(0, TENANT_KEY, Some(&tenant_schema(is_tenant)))
+        .unwrap();
+
+    for n in 0..(num_tenants * points_per_tenant) {
+        let idx = (n as u64).into();
+        let op_num = n as SeqNumberType + 1;
+        let vector = random_vector(rnd, dim);
+        let payload: Payload = json!({ TENANT_KEY: format!("tenant-{}", n % num_tenants) }).into();
+        segment
+            .upsert_point(op_num, idx, &only_default_vector(&vector))
+            .unwrap();
+        segment.set_full_payload(op_num, idx, &payload).unwrap();
+    }
+    segment
+}
+
+#[test]
+fn test_tenant_points_are_colocated() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let num_tenants = 10;
+    let segment = build_tenant_segment(dir.path(), 4, num_tenants, 20, true, &mut rnd);
+
+    let mut builder =
+        SegmentBuilder::new(dir.path(), temp_dir.path(), &segment.segment_config).unwrap();
+    builder.update_from(&segment, &stopped).unwrap();
+    let built = builder.build(CpuPermit::dummy(1), &stopped).unwrap();
+
+    let id_tracker = built.id_tracker.borrow();
+    let tenants: Vec<String> = (0..built.available_point_count() as PointOffsetType)
+        .map(|internal_id| {
+            let external_id = id_tracker.external_id(internal_id).unwrap();
+            let payload = built.payload(external_id).unwrap();
+            payload.get_value(TENANT_KEY).values()[0]
+                .as_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect();
+
+    let switches = tenants.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    assert_eq!(tenants.len(), num_tenants * 20);
+    assert_eq!(switches, num_tenants - 1);
+}
+
+/// Recall and latency of the searches, filtered by a single tenant, on a 1000 tenants dataset.
+///
+/// Run with `cargo test --release -p segment multitenant -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn test_multitenant_hnsw_recall_and_latency() {
+    let dim = 32;
+    let num_tenants = 1000;
+    let points_per_tenant = 50;
+    let top = 10;
+    let attempts = 500;
+
+    for is_tenant in [false, true] {
+        let stopped = AtomicBool::new(false);
+        let mut rnd = StdRng::seed_from_u64(42);
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+        let segment = build_tenant_segment(
+            dir.path(),
+            dim,
+            num_tenants,
+            points_per_tenant,
+            is_tenant,
+            &mut rnd,
+        );
+        let mut builder =
+            SegmentBuilder::new(dir.path(), temp_dir.path(), &segment.segment_config).unwrap();
+        builder.update_from(&segment, &stopped).unwrap();
+        let segment = builder.build(CpuPermit::dummy(1), &stopped).unwrap();
+
+        let hnsw_config = HnswConfig {
+            m: 16,
+            ef_construct: 100,
+            full_scan_threshold: 32, // KB, 256 vectors of this size
+            max_indexing_threads: 4,
+            on_disk: Some(false),
+            payload_m: None,
+        };
+        let permit = CpuPermit::dummy(hnsw_config.max_indexing_threads);
+        let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+            hnsw_dir.path(),
+            segment.id_tracker.clone(),
+            segment.vector_data[DEFAULT_VECTOR_NAME]
+                .vector_storage
+                .clone(),
+            segment.payload_index.clone(),
+            hnsw_config,
+        )
+        .unwrap();
+        hnsw_index.build_index(&permit, &stopped).unwrap();
+
+        let plain_index = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow();
+        let exact_params = SearchParams {
+            exact: true,
+            ..Default::default()
+        };
+
+        let mut found = 0;
+        let mut graph_searches = 0;
+        for _ in 0..attempts {
+            let query = random_vector(&mut rnd, dim);
+            let filter = tenant_filter(rnd.gen_range(0..num_tenants));
+
+            let hw_counter = HardwareCounterCell::new();
+            let result =
+                hw_counter.measure(|| hnsw_index.search(&[&query], Some(&filter), top, None));
+            if hw_counter.search_debug_info().plain_searches == 0 {
+                graph_searches += 1;
+            }
+
+            let expected = plain_index.search(&[&query], Some(&filter), top, Some(&exact_params));
+            let expected: BTreeSet<_> = expected[0].iter().map(|point| point.idx).collect();
+            found += result[0]
+                .iter()
+                .filter(|point| expected.contains(&point.idx))
+                .count();
+        }
+
+        let recall = found as f64 / (attempts * top) as f64;
+        assert!(
+            recall > 0.9,
+            "is_tenant = {is_tenant}: recall {recall} is too low"
+        );
+        if is_tenant {
+            assert_eq!(graph_searches, attempts);
+        }
+    }
+}
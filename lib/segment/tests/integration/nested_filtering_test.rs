@@ -72,7 +72,7 @@ fn test_filtering_context_consistency() {
     let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(NUM_POINTS)));
 
     let mut index =
-        StructPayloadIndex::open(wrapped_payload_storage, id_tracker, dir.path()).unwrap();
+        StructPayloadIndex::open(wrapped_payload_storage, id_tracker, dir.path(), true).unwrap();
 
     index
         .set_indexed("f", PayloadSchemaType::Integer.into())
@@ -97,6 +97,7 @@ fn test_filtering_context_consistency() {
         let nested_condition_0 = Condition::new_nested(
             "arr1",
             Filter {
+                min_should: None,
                 must: Some(vec![
                     // E.g. idx = 6 => { "a" = 1, "b" = 7, "c" = 1, "d" = 0 }
                     Condition::Field(FieldCondition::new_match("a", 1.into())),
@@ -138,6 +139,7 @@ fn test_filtering_context_consistency() {
         let nested_condition_1 = Condition::new_nested(
             "arr1",
             Filter {
+                min_should: None,
                 must: Some(vec![
                     // E.g. idx = 6 => { "a" = 1, "b" = 7, "c" = 1, "d" = 0 }
                     Condition::Field(FieldCondition::new_match("a", 1.into())),
@@ -169,6 +171,7 @@ fn test_filtering_context_consistency() {
         let nested_condition_2 = Condition::new_nested(
             "arr1",
             Filter {
+                min_should: None,
                 must: Some(vec![
                     // E.g. idx = 6 => { "a" = 1, "b" = 7, "c" = 1, "d" = 0 }
                     Condition::Field(FieldCondition::new_match("a", 1.into())),
@@ -202,6 +205,7 @@ fn test_filtering_context_consistency() {
         let nested_condition_3 = Condition::new_nested(
             "arr1",
             Filter {
+                min_should: None,
                 must: Some(vec![Condition::Field(FieldCondition::new_match(
                     "b",
                     1.into(),
@@ -214,9 +218,11 @@ fn test_filtering_context_consistency() {
         let nester_condition_3_1 = Condition::new_nested(
             "arr2",
             Filter {
+                min_should: None,
                 must: Some(vec![Condition::new_nested(
                     "arr3",
                     Filter {
+                        min_should: None,
                         must: Some(vec![Condition::Field(FieldCondition::new_match(
                             "b",
                             10.into(),
@@ -231,6 +237,7 @@ fn test_filtering_context_consistency() {
         );
 
         let nested_filter_3 = Filter {
+            min_should: None,
             must: Some(vec![nested_condition_3, nester_condition_3_1]),
             should: None,
             must_not: None,
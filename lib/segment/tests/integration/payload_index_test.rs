@@ -1,25 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
+use segment::common::rocksdb_wrapper::{open_db_with_existing_cf, DatabaseColumnWrapper};
+use segment::data_types::filter_trace::ConditionCheckSource;
+use segment::data_types::keyword_index::{KeywordIndexParams, KeywordIndexType};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_fixtures::{
-    generate_diverse_nested_payload, generate_diverse_payload, random_filter, random_nested_filter,
-    random_vector, FLICKING_KEY, GEO_KEY, INT_KEY, INT_KEY_2, LAT_RANGE, LON_RANGE, STR_KEY,
-    STR_PROJ_KEY, STR_ROOT_PROJ_KEY, TEXT_KEY,
+    generate_diverse_nested_payload, generate_diverse_payload, random_filter, random_keyword,
+    random_nested_filter, random_vector, FLICKING_KEY, FLT_KEY, GEO_KEY, INT_KEY, INT_KEY_2,
+    LAT_RANGE, LON_RANGE, STR_KEY, STR_PROJ_KEY, STR_ROOT_PROJ_KEY, TEXT_KEY,
 };
 use segment::index::field_index::PrimaryCondition;
 use segment::index::PayloadIndex;
 use segment::segment::Segment;
-use segment::segment_constructor::build_segment;
+use segment::segment_constructor::{build_segment, load_segment, PAYLOAD_INDEX_PATH};
 use segment::types::{
     Condition, Distance, FieldCondition, Filter, GeoPoint, GeoRadius, Indexes, IsEmptyCondition,
-    Payload, PayloadField, PayloadSchemaType, Range, SegmentConfig, VectorDataConfig,
-    VectorStorageType, WithPayload,
+    Payload, PayloadField, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType, Range,
+    SegmentConfig, ValuesCount, VectorDataConfig, VectorStorageType, WithPayload,
 };
+use serde_json::json;
 use tempfile::Builder;
 
 use crate::utils::scored_point_ties::ScoredPointTies;
@@ -37,9 +41,12 @@ fn build_test_segments(path_struct: &Path, path_plain: &Path) -> (Segment, Segme
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -146,9 +153,12 @@ fn build_test_segments_nested_payload(path_struct: &Path, path_plain: &Path) ->
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -294,6 +304,31 @@ fn test_is_empty_conditions() {
     );
 }
 
+#[test]
+fn test_payload_index_info() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (struct_segment, _) = build_test_segments(dir1.path(), dir2.path());
+
+    let index_schema = struct_segment.info().index_schema;
+    for field in [STR_KEY, INT_KEY, GEO_KEY, TEXT_KEY] {
+        let field_info = &index_schema[field];
+        assert!(field_info.values_count.unwrap() >= field_info.points);
+        assert!(field_info.estimated_ram_bytes.unwrap() > 0);
+    }
+
+    // Integer field has both map and range indexes, which hold the same values
+    let int_indexes_ram: usize = struct_segment.payload_index.borrow().field_indexes[INT_KEY]
+        .iter()
+        .map(|index| index.get_telemetry_data().estimated_ram_bytes)
+        .sum();
+    assert_eq!(
+        index_schema[INT_KEY].estimated_ram_bytes,
+        Some(int_indexes_ram)
+    );
+}
+
 #[test]
 fn test_cardinality_estimation() {
     let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
@@ -333,6 +368,70 @@ fn test_cardinality_estimation() {
     assert!(exact >= estimation.min);
 }
 
+#[test]
+fn test_values_count_cardinality_estimation() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+
+    let values_counts = [
+        ValuesCount {
+            lt: Some(2),
+            gt: None,
+            gte: None,
+            lte: None,
+        },
+        ValuesCount {
+            lt: None,
+            gt: Some(1),
+            gte: None,
+            lte: Some(2),
+        },
+        ValuesCount {
+            lt: None,
+            gt: None,
+            gte: Some(3),
+            lte: None,
+        },
+        ValuesCount {
+            lt: None,
+            gt: None,
+            gte: None,
+            lte: Some(0),
+        },
+    ];
+
+    for key in [STR_KEY, GEO_KEY] {
+        for values_count in values_counts {
+            let filter = Filter::new_must(Condition::Field(FieldCondition::new_values_count(
+                key.to_owned(),
+                values_count,
+            )));
+
+            let estimation = struct_segment
+                .payload_index
+                .borrow()
+                .estimate_cardinality(&filter);
+
+            let struct_result = struct_segment.read_filtered(None, None, Some(&filter));
+            let plain_result = plain_segment.read_filtered(None, None, Some(&filter));
+
+            assert_eq!(struct_result, plain_result, "{key}: {values_count:?}");
+            assert_eq!(
+                estimation.min,
+                struct_result.len(),
+                "{key}: {values_count:?}"
+            );
+            assert_eq!(
+                estimation.max,
+                struct_result.len(),
+                "{key}: {values_count:?}"
+            );
+        }
+    }
+}
+
 #[test]
 fn test_root_nested_array_filter_cardinality_estimation() {
     let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
@@ -549,6 +648,7 @@ fn test_struct_payload_geo_index() {
 
         let query_filter = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![condition]),
             must_not: None,
         };
@@ -679,3 +779,493 @@ fn test_struct_payload_index_nested_fields() {
                 });
     }
 }
+
+#[test]
+fn test_trace_filter() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let (struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+    let point_ids = struct_segment.iter_points().take(100).collect_vec();
+
+    for _ in 0..20 {
+        let filter = random_filter(&mut rnd, 3);
+        let matching: HashSet<_> = struct_segment
+            .read_filtered(None, None, Some(&filter))
+            .into_iter()
+            .collect();
+
+        for &point_id in &point_ids {
+            let struct_trace = struct_segment.trace_filter(point_id, &filter).unwrap();
+            let plain_trace = plain_segment.trace_filter(point_id, &filter).unwrap();
+            assert_eq!(
+                struct_trace.matched,
+                matching.contains(&point_id),
+                "point {point_id}, filter {filter:?}",
+            );
+            assert_eq!(struct_trace.matched, plain_trace.matched);
+        }
+    }
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        STR_KEY,
+        random_keyword(&mut rnd).into(),
+    )));
+    let struct_trace = struct_segment.trace_filter(point_ids[0], &filter).unwrap();
+    let plain_trace = plain_segment.trace_filter(point_ids[0], &filter).unwrap();
+    assert_eq!(
+        struct_trace.must.unwrap()[0].checked_by,
+        Some(ConditionCheckSource::Index),
+    );
+    assert_eq!(
+        plain_trace.must.unwrap()[0].checked_by,
+        Some(ConditionCheckSource::Payload),
+    );
+}
+
+#[test]
+fn test_unindexed_filter_keys() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+
+    let int_range = Range {
+        lt: None,
+        gt: None,
+        gte: Some(10.),
+        lte: Some(50.),
+    };
+    let filter = Filter {
+        should: Some(vec![
+            Condition::Field(FieldCondition::new_range(INT_KEY, int_range.clone())),
+            Condition::Field(FieldCondition::new_range(FLT_KEY, int_range.clone())),
+        ]),
+        min_should: None,
+        must: Some(vec![
+            Condition::IsEmpty(IsEmptyCondition::from(STR_KEY.to_string())),
+            // Index of the parent doesn't cover the nested field
+            Condition::new_nested(
+                STR_KEY,
+                Filter::new_must(Condition::Field(FieldCondition::new_match(
+                    "nested_1",
+                    "keyword".to_string().into(),
+                ))),
+            ),
+        ]),
+        must_not: Some(vec![Condition::IsEmpty(IsEmptyCondition::from(
+            FLT_KEY.to_string(),
+        ))]),
+    };
+
+    assert_eq!(
+        struct_segment.unindexed_filter_keys(&filter),
+        vec![FLT_KEY.to_string(), format!("{STR_KEY}[].nested_1")],
+    );
+    assert_eq!(
+        plain_segment.unindexed_filter_keys(&filter),
+        vec![
+            FLT_KEY.to_string(),
+            INT_KEY.to_string(),
+            STR_KEY.to_string(),
+            format!("{STR_KEY}[].nested_1"),
+        ],
+    );
+
+    let indexed_filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        INT_KEY, int_range,
+    )));
+    assert!(struct_segment
+        .unindexed_filter_keys(&indexed_filter)
+        .is_empty());
+}
+
+#[test]
+fn test_corrupted_field_index_is_rebuilt() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+    struct_segment.flush(true).unwrap();
+    let segment_path = struct_segment.current_path.clone();
+    drop(struct_segment);
+
+    // Write a truncated key into the range index of the integer field
+    {
+        let db = open_db_with_existing_cf(&segment_path.join(PAYLOAD_INDEX_PATH)).unwrap();
+        let column = DatabaseColumnWrapper::new(db, &format!("{INT_KEY}_numeric"));
+        column.put([0x80, 0x00, 0x01], 1u32.to_be_bytes()).unwrap();
+        column.flusher()().unwrap();
+    }
+
+    // The segment is loaded, the field is served without the index until it is rebuilt
+    let mut struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    let fields_to_rebuild: Vec<_> = struct_segment
+        .payload_index
+        .borrow()
+        .fields_to_rebuild()
+        .iter()
+        .cloned()
+        .collect();
+    assert_eq!(fields_to_rebuild, vec![INT_KEY.to_owned()]);
+    assert!(struct_segment
+        .payload_index
+        .borrow()
+        .field_indexes
+        .get(INT_KEY)
+        .is_none());
+
+    let telemetry = struct_segment.payload_index.borrow().get_telemetry_data();
+    let degraded: Vec<_> = telemetry
+        .iter()
+        .filter(|index| index.needs_rebuild)
+        .map(|index| index.field_name.as_deref())
+        .collect();
+    assert_eq!(degraded, vec![Some(INT_KEY)]);
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        INT_KEY.to_owned(),
+        Range {
+            lt: None,
+            gt: None,
+            gte: Some(10.),
+            lte: Some(50.),
+        },
+    )));
+    let plain_points = plain_segment.read_filtered(None, None, Some(&filter));
+    assert!(!plain_points.is_empty());
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+
+    struct_segment.rebuild_field_index(INT_KEY).unwrap();
+    assert!(struct_segment
+        .payload_index
+        .borrow()
+        .fields_to_rebuild()
+        .is_empty());
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+}
+
+#[test]
+fn test_staged_field_index_rebuild() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (mut struct_segment, mut plain_segment) = build_test_segments(dir1.path(), dir2.path());
+    let total_points = struct_segment.total_point_count();
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        INT_KEY.to_owned(),
+        Range {
+            lt: None,
+            gt: None,
+            gte: Some(10.),
+            lte: Some(50.),
+        },
+    )));
+
+    assert!(struct_segment.start_field_index_rebuild(INT_KEY).unwrap());
+    assert!(!struct_segment
+        .start_field_index_rebuild("not_indexed")
+        .unwrap());
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(INT_KEY),
+        Some((0, total_points)),
+    );
+
+    assert!(
+        !struct_segment
+            .build_staged_field_index(INT_KEY, 1000)
+            .unwrap()
+            .is_complete
+    );
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(INT_KEY),
+        Some((1000, total_points)),
+    );
+
+    // Updates during the rebuild reach both the current and the staging index,
+    // no matter if the point is already indexed into the staging one
+    let point_ids = struct_segment.iter_points().collect_vec();
+    let mut opnum = 100_000;
+    let payload: Payload = json!({ INT_KEY: 30 }).into();
+    for point_id in [point_ids[0], point_ids[point_ids.len() - 1]] {
+        opnum += 1;
+        plain_segment
+            .set_payload(opnum, point_id, &payload)
+            .unwrap();
+        struct_segment
+            .set_payload(opnum, point_id, &payload)
+            .unwrap();
+    }
+    let plain_points = plain_segment.read_filtered(None, None, Some(&filter));
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+
+    // The rebuild is resumed from the flushed progress after reload
+    struct_segment.flush(true).unwrap();
+    let segment_path = struct_segment.current_path.clone();
+    drop(struct_segment);
+    let mut struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(INT_KEY),
+        Some((1000, total_points)),
+    );
+
+    while !struct_segment
+        .build_staged_field_index(INT_KEY, 1000)
+        .unwrap()
+        .is_complete
+    {}
+    assert_eq!(struct_segment.field_index_rebuild_progress(INT_KEY), None);
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+
+    // The rebuilt index is loaded from the storage of the new generation
+    struct_segment.flush(true).unwrap();
+    drop(struct_segment);
+    let struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    assert!(struct_segment
+        .payload_index
+        .borrow()
+        .fields_to_rebuild()
+        .is_empty());
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+}
+
+#[test]
+fn test_staged_field_index_creation() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (mut struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+    let total_points = struct_segment.total_point_count();
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        FLT_KEY.to_owned(),
+        Range {
+            lt: None,
+            gt: None,
+            gte: Some(2.0),
+            lte: Some(6.0),
+        },
+    )));
+    let plain_points = plain_segment.read_filtered(None, None, Some(&filter));
+
+    let mut opnum = 100_000;
+    assert!(struct_segment
+        .stage_field_index_creation(opnum, FLT_KEY, Some(&PayloadSchemaType::Float.into()))
+        .unwrap());
+    assert!(struct_segment.get_indexed_fields().contains_key(FLT_KEY));
+    assert_eq!(
+        struct_segment.building_field_indexes(),
+        HashMap::from([(FLT_KEY.to_string(), opnum)]),
+    );
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(FLT_KEY),
+        Some((0, total_points)),
+    );
+
+    // Conditions on the building field are checked against the payload
+    assert!(
+        !struct_segment
+            .build_staged_field_index(FLT_KEY, 1000)
+            .unwrap()
+            .is_complete
+    );
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+
+    // The build is resumed from the flushed progress after reload
+    struct_segment.flush(true).unwrap();
+    let segment_path = struct_segment.current_path.clone();
+    drop(struct_segment);
+    let mut struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(FLT_KEY),
+        Some((1000, total_points)),
+    );
+
+    while !struct_segment
+        .build_staged_field_index(FLT_KEY, 1000)
+        .unwrap()
+        .is_complete
+    {}
+    assert!(struct_segment.building_field_indexes().is_empty());
+    assert_eq!(
+        struct_segment.read_filtered(None, None, Some(&filter)),
+        plain_points,
+    );
+
+    // Nothing to cancel once the index is built
+    opnum += 1;
+    assert!(!struct_segment
+        .cancel_field_index_build(opnum, FLT_KEY)
+        .unwrap());
+    assert!(struct_segment.get_indexed_fields().contains_key(FLT_KEY));
+
+    // Cancelled creation removes the index entirely
+    opnum += 1;
+    assert!(struct_segment
+        .stage_field_index_creation(
+            opnum,
+            STR_PROJ_KEY,
+            Some(&PayloadSchemaType::Keyword.into())
+        )
+        .unwrap());
+    opnum += 1;
+    assert!(struct_segment
+        .cancel_field_index_build(opnum, STR_PROJ_KEY)
+        .unwrap());
+    assert!(!struct_segment
+        .get_indexed_fields()
+        .contains_key(STR_PROJ_KEY));
+    assert!(struct_segment.building_field_indexes().is_empty());
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(STR_PROJ_KEY),
+        None
+    );
+}
+
+#[test]
+fn test_field_index_on_disk_conversion() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let (mut struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+    let total_points = struct_segment.total_point_count();
+
+    let keyword_schema = |on_disk: bool| {
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            is_tenant: None,
+            on_disk: Some(on_disk),
+        }))
+    };
+    let is_on_disk = |segment: &Segment| {
+        segment
+            .payload_index
+            .borrow()
+            .get_telemetry_data()
+            .into_iter()
+            .find(|telemetry| telemetry.field_name.as_deref() == Some(STR_KEY))
+            .unwrap()
+            .on_disk
+    };
+
+    let mut rnd = StdRng::seed_from_u64(42);
+    let filters = [
+        Filter::new_must(Condition::Field(FieldCondition::new_match(
+            STR_KEY,
+            random_keyword(&mut rnd).into(),
+        ))),
+        Filter::new_must_not(Condition::Field(FieldCondition::new_match(
+            STR_KEY,
+            random_keyword(&mut rnd).into(),
+        ))),
+    ];
+    let check_filters = |segment: &Segment| {
+        for filter in &filters {
+            assert_eq!(
+                segment.read_filtered(None, None, Some(filter)),
+                plain_segment.read_filtered(None, None, Some(filter)),
+            );
+        }
+    };
+
+    // Same type with a different `on_disk` flag converts the existing index
+    let mut opnum = 100_000;
+    assert!(!is_on_disk(&struct_segment));
+    assert!(struct_segment
+        .stage_field_index_creation(opnum, STR_KEY, Some(&keyword_schema(true)))
+        .unwrap());
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(STR_KEY),
+        Some((0, total_points)),
+    );
+
+    // The current index serves reads until the conversion is complete
+    let batch = struct_segment
+        .build_staged_field_index(STR_KEY, 1000)
+        .unwrap();
+    assert!(!batch.is_complete);
+    assert!(batch.bytes_read > 0);
+    assert!(!is_on_disk(&struct_segment));
+    check_filters(&struct_segment);
+
+    // The conversion is resumed after reload
+    struct_segment.flush(true).unwrap();
+    let segment_path = struct_segment.current_path.clone();
+    drop(struct_segment);
+    let mut struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    assert_eq!(
+        struct_segment.field_index_rebuild_progress(STR_KEY),
+        Some((1000, total_points)),
+    );
+    while !struct_segment
+        .build_staged_field_index(STR_KEY, 1000)
+        .unwrap()
+        .is_complete
+    {}
+    assert!(is_on_disk(&struct_segment));
+    assert_eq!(
+        struct_segment.get_indexed_fields()[STR_KEY],
+        keyword_schema(true)
+    );
+    check_filters(&struct_segment);
+
+    // Nothing to convert, if the index already has the representation
+    opnum += 1;
+    struct_segment
+        .stage_field_index_creation(opnum, STR_KEY, Some(&keyword_schema(true)))
+        .unwrap();
+    assert_eq!(struct_segment.field_index_rebuild_progress(STR_KEY), None);
+
+    // Requesting the current representation cancels the conversion
+    opnum += 1;
+    assert!(struct_segment
+        .stage_field_index_creation(opnum, STR_KEY, Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap());
+    opnum += 1;
+    assert!(struct_segment
+        .stage_field_index_creation(opnum, STR_KEY, Some(&keyword_schema(true)))
+        .unwrap());
+    assert_eq!(struct_segment.field_index_rebuild_progress(STR_KEY), None);
+    assert!(is_on_disk(&struct_segment));
+
+    // The loaded on-disk index is converted back into memory
+    struct_segment.flush(true).unwrap();
+    drop(struct_segment);
+    let mut struct_segment = load_segment(&segment_path).unwrap().unwrap();
+    assert!(is_on_disk(&struct_segment));
+    check_filters(&struct_segment);
+
+    opnum += 1;
+    assert!(struct_segment
+        .stage_field_index_creation(opnum, STR_KEY, Some(&keyword_schema(false)))
+        .unwrap());
+    assert!(
+        struct_segment
+            .build_staged_field_index(STR_KEY, usize::MAX)
+            .unwrap()
+            .is_complete
+    );
+    assert!(!is_on_disk(&struct_segment));
+    check_filters(&struct_segment);
+}
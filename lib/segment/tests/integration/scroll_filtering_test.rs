@@ -1,7 +1,10 @@
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
-use segment::fixtures::payload_fixtures::random_filter;
+use segment::data_types::order_by::{Direction, OrderBy};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::{random_filter, FLT_KEY};
 use segment::fixtures::segment_fixtures::random_segment;
+use segment::types::{Condition, FieldCondition, PayloadSchemaType, Range};
 use tempfile::Builder;
 
 const NUM_POINTS: usize = 2000;
@@ -29,3 +32,94 @@ fn test_filtering_context_consistency() {
         assert_eq!(read_by_index_res, read_by_stream_res, "filter: {filter:#?}");
     }
 }
+
+#[test]
+fn test_ordered_read_consistency() {
+    let seed = 42;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let mut segment = random_segment(dir.path(), NUM_POINTS);
+    segment
+        .create_field_index(101, FLT_KEY, Some(&PayloadSchemaType::Float.into()))
+        .unwrap();
+
+    for attempt in 0..ATTEMPTS {
+        let filter = random_filter(&mut rng, 3);
+        let order_by = OrderBy {
+            key: FLT_KEY.to_string(),
+            direction: if attempt % 2 == 0 {
+                Direction::Asc
+            } else {
+                Direction::Desc
+            },
+        };
+
+        let read_by_index_res = segment
+            .ordered_read_by_index(Some(10), Some(&filter), &order_by)
+            .unwrap();
+        let read_by_payload_res = segment
+            .ordered_read_by_payload(Some(10), Some(&filter), &order_by)
+            .unwrap();
+
+        assert_eq!(
+            read_by_index_res, read_by_payload_res,
+            "filter: {filter:#?}, order_by: {order_by:?}"
+        );
+    }
+}
+
+#[test]
+fn test_ordered_range_read_consistency() {
+    let seed = 42;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let mut segment = random_segment(dir.path(), NUM_POINTS);
+    segment
+        .create_field_index(101, FLT_KEY, Some(&PayloadSchemaType::Float.into()))
+        .unwrap();
+
+    for attempt in 0..ATTEMPTS {
+        // Range on the ordering key narrows the iteration over the index
+        let from = rng.gen_range(0.0..10.0);
+        let range = Range {
+            gte: Some(from),
+            lt: Some(from + 3.0),
+            ..Default::default()
+        };
+        let mut filter = random_filter(&mut rng, 2);
+        filter
+            .must
+            .get_or_insert_with(Vec::new)
+            .push(Condition::Field(FieldCondition::new_range(
+                FLT_KEY.to_string(),
+                range.clone(),
+            )));
+        let order_by = OrderBy {
+            key: FLT_KEY.to_string(),
+            direction: if attempt % 2 == 0 {
+                Direction::Asc
+            } else {
+                Direction::Desc
+            },
+        };
+
+        let read_by_index_res = segment
+            .ordered_read_by_index(Some(10), Some(&filter), &order_by)
+            .unwrap();
+        let read_by_payload_res = segment
+            .ordered_read_by_payload(Some(10), Some(&filter), &order_by)
+            .unwrap();
+
+        assert_eq!(
+            read_by_index_res, read_by_payload_res,
+            "filter: {filter:#?}, order_by: {order_by:?}"
+        );
+        assert!(read_by_index_res
+            .iter()
+            .all(|(value, _)| range.check_range(*value)));
+    }
+}
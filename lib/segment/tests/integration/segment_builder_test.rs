@@ -4,6 +4,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use itertools::Itertools;
+use segment::common::cpu::CpuPermit;
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::{OperationError, SegmentEntry};
 use segment::segment::Segment;
@@ -50,7 +51,7 @@ fn test_building_new_segment() {
 
     // Now we finalize building
 
-    let merged_segment: Segment = builder.build(&stopped).unwrap();
+    let merged_segment: Segment = builder.build(CpuPermit::dummy(1), &stopped).unwrap();
 
     let new_segment_count = dir.path().read_dir().unwrap().count();
 
@@ -87,9 +88,12 @@ fn estimate_build_time(segment: &Segment, stop_timeout_millis: u64) -> (u64, boo
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Hnsw(Default::default()),
                 quantization_config: None,
+                datatype: Default::default(),
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
     };
 
     let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
@@ -108,7 +112,7 @@ fn estimate_build_time(segment: &Segment, stop_timeout_millis: u64) -> (u64, boo
         })
         .unwrap();
 
-    let res = builder.build(&stopped);
+    let res = builder.build(CpuPermit::dummy(1), &stopped);
 
     let is_cancelled = match res {
         Ok(_) => false,
@@ -1,11 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::sparse_vector::SparseVector;
 use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::entry::entry_point::{OperationError, SegmentEntry};
-use segment::segment_constructor::load_segment;
-use segment::types::{Condition, Filter, WithPayload};
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::types::{
+    Condition, Distance, Filter, Indexes, SegmentConfig, SparseVectorDataConfig, VectorDataConfig,
+    VectorStorageType, WithPayload,
+};
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_3};
@@ -39,6 +43,7 @@ fn test_point_exclusion() {
 
     let frt = Filter {
         should: None,
+        min_should: None,
         must: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
@@ -96,6 +101,7 @@ fn test_named_vector_search() {
 
     let frt = Filter {
         should: None,
+        min_should: None,
         must: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
@@ -205,3 +211,114 @@ fn ordered_deletion_test() {
     let best_match = res.get(0).expect("Non-empty result");
     assert_eq!(best_match.id, 3.into());
 }
+
+#[test]
+fn test_sparse_vector_search() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            "dense".to_owned(),
+            VectorDataConfig {
+                size: 4,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: Default::default(),
+            },
+        )]),
+        sparse_vector_data: HashMap::from([(
+            "sparse".to_owned(),
+            SparseVectorDataConfig::default(),
+        )]),
+        payload_storage_type: Default::default(),
+        payload_cache_fields: Default::default(),
+    };
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let sparse_vectors = [
+        (1, vec![0, 5], vec![1.0, 1.0]),
+        (2, vec![2, 0], vec![2.0, 1.0]),
+        (3, vec![1], vec![5.0]),
+        (4, vec![0, 2], vec![0.5, 0.5]),
+        (5, vec![2], vec![4.0]),
+    ];
+    for (id, indices, values) in sparse_vectors {
+        let mut vectors = NamedVectors::from([("dense".to_owned(), vec![1.0, 0.0, 0.0, 0.0])]);
+        vectors.insert_sparse(
+            "sparse".to_owned(),
+            SparseVector::new(indices, values).unwrap(),
+        );
+        segment.upsert_point(id, id.into(), &vectors).unwrap();
+    }
+    // Point without a sparse vector is never found by sparse search
+    segment
+        .upsert_point(
+            6,
+            6.into(),
+            &NamedVectors::from([("dense".to_owned(), vec![1.0, 1.0, 1.0, 1.0])]),
+        )
+        .unwrap();
+
+    let query = SparseVector::new(vec![0, 2], vec![1.0, 1.0]).unwrap();
+    let search = |segment: &dyn SegmentEntry, filter: Option<&Filter>, top: usize| {
+        segment
+            .search_sparse_batch(
+                "sparse",
+                &[&query],
+                &WithPayload::default(),
+                &false.into(),
+                filter,
+                top,
+            )
+            .unwrap()
+            .remove(0)
+    };
+
+    let res = search(&segment, None, 2);
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].id, 5.into());
+    assert_eq!(res[0].score, 4.0);
+    assert_eq!(res[1].id, 2.into());
+    assert_eq!(res[1].score, 3.0);
+    // Points 3 and 6 share no dimension with the query
+    assert_eq!(search(&segment, None, 10).len(), 4);
+
+    let exclude_best = Filter::new_must_not(Condition::HasId(HashSet::from([5.into()]).into()));
+    let res = search(&segment, Some(&exclude_best), 1);
+    assert_eq!(res[0].id, 2.into());
+
+    // Stored vectors are sorted by dimension
+    let stored = segment.sparse_vector("sparse", 2.into()).unwrap().unwrap();
+    assert_eq!(stored.indices, vec![0, 2]);
+    assert_eq!(stored.values, vec![1.0, 2.0]);
+    assert!(segment.sparse_vector("sparse", 6.into()).unwrap().is_none());
+
+    assert!(segment.delete_vector(7, 2.into(), "sparse").unwrap());
+    assert!(segment.sparse_vector("sparse", 2.into()).unwrap().is_none());
+    let res = search(&segment, None, 10);
+    assert_eq!(res.len(), 3);
+    assert!(res.iter().all(|point| point.id != 2.into()));
+
+    let wrong_vector = SparseVector {
+        indices: vec![1, 1],
+        values: vec![1.0, 2.0],
+    };
+    let mut vectors = NamedVectors::default();
+    vectors.insert_sparse("sparse".to_owned(), wrong_vector);
+    let result = segment.upsert_point(8, 7.into(), &vectors);
+    assert!(matches!(
+        result,
+        Err(OperationError::WrongSparseVector { .. })
+    ));
+
+    segment.flush(true).unwrap();
+    let path = segment.current_path.clone();
+    drop(segment);
+
+    // The inverted index of an appendable segment is rebuilt from the storage on load
+    let segment = load_segment(&path).unwrap().unwrap();
+    let res = search(&segment, None, 10);
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0].id, 5.into());
+}
@@ -0,0 +1,314 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use api::grpc::validate::validate_collection_name;
+use collection::config::CollectionConfig;
+use collection::operations::config_diff::{DiffConfig, HnswConfigDiff, OptimizersConfigDiff};
+use collection::operations::point_ops::{
+    PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
+};
+use collection::operations::types::{
+    CollectionError, CollectionResult, ScrollRequest, VectorsConfig,
+};
+use collection::operations::CollectionUpdateOperations;
+use collection::shards::shard::ShardId;
+use collection::shards::CollectionId;
+use schemars::JsonSchema;
+use segment::common::file_operations::{atomic_save_json, read_json};
+use segment::types::{
+    PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
+    WithPayloadInterface, WithVector,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use validator::Validate;
+
+use crate::content_manager::collection_meta_ops::CreateCollection;
+use crate::content_manager::collections_ops::Collections;
+use crate::content_manager::data_transfer::{handle_get_collection, wait_all_shards_active};
+use crate::content_manager::errors::StorageError;
+
+pub const CLONES_DIR: &str = "clones";
+const CLONE_BATCH_SIZE: usize = 1000;
+
+/// Copy all points of the collection into a new collection with different params.
+/// The new collection is created with the params of the source collection, overridden by the
+/// specified ones. Updates of the source collection are rejected until all points are copied.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CloneCollection {
+    /// Name of the new collection
+    #[validate(length(min = 1, max = 255), custom = "validate_collection_name")]
+    pub target: CollectionId,
+    /// Vector data config of the new collection.
+    /// Names and sizes of the vectors must be the same as in the source collection,
+    /// distances and index params may differ.
+    /// If none - vector config of the source collection is used
+    #[serde(default)]
+    #[validate]
+    pub vectors: Option<VectorsConfig>,
+    /// Number of shards of the new collection. If none - the same as in the source collection
+    #[serde(default)]
+    pub shard_number: Option<u32>,
+    /// Number of shard replicas of the new collection.
+    /// If none - the same as in the source collection
+    #[serde(default)]
+    pub replication_factor: Option<u32>,
+    /// Write consistency factor of the new collection.
+    /// If none - the same as in the source collection
+    #[serde(default)]
+    pub write_consistency_factor: Option<u32>,
+    /// If true - payload of the new collection is stored on disk.
+    /// If none - the same as in the source collection
+    #[serde(default)]
+    pub on_disk_payload: Option<bool>,
+    /// Changes of the HNSW index params of the source collection
+    #[serde(default)]
+    #[validate]
+    pub hnsw_config: Option<HnswConfigDiff>,
+    /// Changes of the optimizers params of the source collection
+    #[serde(default)]
+    #[validate]
+    pub optimizers_config: Option<OptimizersConfigDiff>,
+    /// Quantization params of the new collection.
+    /// If none - the same as in the source collection
+    #[serde(default)]
+    #[validate]
+    pub quantization_config: Option<QuantizationConfig>,
+}
+
+impl CloneCollection {
+    /// Params of the target collection: params of the source collection with the overrides.
+    /// Payload indexes of the source collection are created in the target one from the start.
+    pub fn create_collection(
+        self,
+        source_config: CollectionConfig,
+        payload_schema: impl IntoIterator<Item = (PayloadKeyType, PayloadIndexInfo)>,
+    ) -> Result<CreateCollection, StorageError> {
+        let hnsw_config = match self.hnsw_config {
+            Some(diff) => diff.update(&source_config.hnsw_config)?,
+            None => source_config.hnsw_config.clone(),
+        };
+        let optimizers_config = match self.optimizers_config {
+            Some(diff) => diff.update(&source_config.optimizer_config)?,
+            None => source_config.optimizer_config.clone(),
+        };
+
+        let mut create_collection = CreateCollection::from(source_config);
+        if let Some(vectors) = self.vectors {
+            check_vectors_copyable(&create_collection.vectors, &vectors)?;
+            create_collection.vectors = vectors;
+        }
+        create_collection.shard_number = self.shard_number.or(create_collection.shard_number);
+        create_collection.replication_factor = self
+            .replication_factor
+            .or(create_collection.replication_factor);
+        create_collection.write_consistency_factor = self
+            .write_consistency_factor
+            .or(create_collection.write_consistency_factor);
+        create_collection.on_disk_payload =
+            self.on_disk_payload.or(create_collection.on_disk_payload);
+        create_collection.hnsw_config = Some(hnsw_config.into());
+        create_collection.optimizers_config = Some(optimizers_config.into());
+        create_collection.quantization_config = self
+            .quantization_config
+            .or(create_collection.quantization_config);
+
+        let payload_indexes = create_collection
+            .payload_indexes
+            .get_or_insert_with(Default::default);
+        for (key, index_info) in payload_schema {
+            let schema = PayloadFieldSchema::try_from(index_info).map_err(|err| {
+                StorageError::service_error(format!("Can't copy index of the field {key}: {err}"))
+            })?;
+            payload_indexes.entry(key).or_insert(schema);
+        }
+
+        Ok(create_collection)
+    }
+}
+
+/// Points are upserted as they are, so the vectors of the target collection must have
+/// the same names and sizes. Distances and other params may differ.
+fn check_vectors_copyable(
+    source: &VectorsConfig,
+    target: &VectorsConfig,
+) -> Result<(), StorageError> {
+    let mut source_params: Vec<_> = source.params_iter().map(|(n, p)| (n, p.size)).collect();
+    let mut target_params: Vec<_> = target.params_iter().map(|(n, p)| (n, p.size)).collect();
+    source_params.sort_unstable();
+    target_params.sort_unstable();
+    if source_params != target_params {
+        return Err(StorageError::bad_input(&format!(
+            "Vectors of the cloned collection must have the same names and sizes as in the source \
+             collection: {source_params:?}, got {target_params:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneState {
+    InProgress,
+    Finished,
+    Failed,
+}
+
+/// Progress of copying the points of one collection into another.
+/// It is persisted after each batch, so the copying resumes after restart.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionCloneStatus {
+    /// Collection, the points are copied from
+    pub source: CollectionId,
+    /// Collection, the points are copied into
+    pub target: CollectionId,
+    pub state: CloneState,
+    /// Amount of points, copied so far
+    pub points_copied: usize,
+    /// Amount of points in the source collection at the start of the copying
+    pub points_total: usize,
+    /// Shards of the source collection, which are not copied completely yet
+    pub pending_shards: Vec<ShardId>,
+    /// Id of the next point to copy from the first pending shard
+    #[serde(default)]
+    pub offset: Option<PointIdType>,
+    /// Reason of the failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CollectionCloneStatus {
+    pub fn path(storage_path: &Path, target: &str) -> PathBuf {
+        storage_path.join(CLONES_DIR).join(format!("{target}.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        Ok(read_json(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        Ok(atomic_save_json(path, self)?)
+    }
+
+    /// Reason of rejecting updates of the source collection while it is cloned
+    pub fn updates_block_reason(&self) -> String {
+        format!(
+            "Collection `{}` is being cloned into `{}`, updates are rejected until the clone is \
+             finished. Progress is reported at /collections/{}/clone",
+            self.source, self.target, self.target,
+        )
+    }
+}
+
+/// Copies the pending shards of the source collection into the target one, batch by batch.
+///
+/// Updates of the source collection are unblocked at the end, whether the copying succeeded or not.
+pub async fn copy_collection_points(
+    collections: Arc<RwLock<Collections>>,
+    mut status: CollectionCloneStatus,
+    status_path: PathBuf,
+) {
+    let result = copy_pending_shards(&collections, &mut status, &status_path).await;
+
+    match result {
+        Ok(()) => {
+            log::info!(
+                "Collection {} cloned into {}: {} points copied",
+                status.source,
+                status.target,
+                status.points_copied,
+            );
+            status.state = CloneState::Finished;
+        }
+        Err(err) => {
+            log::error!(
+                "Cloning of collection {} into {} failed: {err}",
+                status.source,
+                status.target,
+            );
+            status.state = CloneState::Failed;
+            status.error = Some(err.to_string());
+        }
+    }
+    if let Err(err) = status.save(&status_path) {
+        log::error!(
+            "Can't save clone status of collection {}: {err}",
+            status.target
+        );
+    }
+
+    if let Some(source) = collections.read().await.get(&status.source) {
+        source.block_updates(None);
+    }
+}
+
+async fn copy_pending_shards(
+    collections: &Arc<RwLock<Collections>>,
+    status: &mut CollectionCloneStatus,
+    status_path: &Path,
+) -> Result<(), StorageError> {
+    wait_all_shards_active(collections.clone(), &status.target).await?;
+
+    while let Some(&shard_id) = status.pending_shards.first() {
+        let copied = copy_batch(collections, status, shard_id).await?;
+        status.points_copied += copied;
+        if status.offset.is_none() {
+            status.pending_shards.remove(0);
+        }
+        status.save(status_path)?;
+    }
+    Ok(())
+}
+
+/// Copies the next batch of the shard and moves the offset. Returns the amount of copied points.
+async fn copy_batch(
+    collections: &Arc<RwLock<Collections>>,
+    status: &mut CollectionCloneStatus,
+    shard_id: ShardId,
+) -> CollectionResult<usize> {
+    let request = ScrollRequest {
+        offset: status.offset,
+        limit: Some(CLONE_BATCH_SIZE),
+        filter: None,
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: WithVector::Bool(true),
+        with_timestamps: false,
+        max_payload_return_bytes: None,
+    };
+
+    let collections_read = collections.read().await;
+    let source = handle_get_collection(collections_read.get(&status.source))?;
+    let scroll_result = source.scroll_by(request, None, Some(shard_id)).await?;
+
+    let points: Vec<_> = scroll_result
+        .points
+        .into_iter()
+        .map(|point| {
+            let vector = point.vector.ok_or_else(|| {
+                CollectionError::service_error(format!("Point {} has no vectors", point.id))
+            })?;
+            Ok(PointStruct {
+                id: point.id,
+                vector,
+                payload: point.payload,
+            })
+        })
+        .collect::<CollectionResult<_>>()?;
+    let copied = points.len();
+
+    if !points.is_empty() {
+        let upsert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(points),
+        ));
+        let target = handle_get_collection(collections_read.get(&status.target))?;
+        // Wait for the batch to be applied, so the persisted offset never skips points
+        target
+            .update_internal(upsert, true, WriteOrdering::default())
+            .await?;
+    }
+
+    status.offset = scroll_result.next_page_offset;
+    Ok(copied)
+}
@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use collection::operations::config_diff::CollectionParamsDiff;
+use collection::operations::point_ops::WriteOrdering;
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use collection::shards::CollectionId;
+use schemars::JsonSchema;
+use segment::types::{PayloadFieldSchema, PayloadKeyType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::content_manager::collection_meta_ops::{
+    ChangeAliasesOperation, CollectionMetaOperations, CreateAlias, CreateCollection,
+    CreateCollectionOperation, UpdateCollectionOperation,
+};
+use crate::content_manager::errors::StorageError;
+use crate::content_manager::toc::TableOfContent;
+use crate::dispatcher::Dispatcher;
+
+/// Complete configuration of a collection: params, payload indexes and aliases.
+/// Exported from one cluster, it can be applied to another one to reproduce the collection
+/// without its data.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionConfigDocument {
+    pub collection_name: CollectionId,
+    /// Params of the collection. Payload indexes are listed separately
+    #[validate]
+    pub config: CreateCollection,
+    /// Schemas of the indexed payload fields
+    #[serde(default)]
+    pub payload_indexes: BTreeMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Aliases, pointing to the collection
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Param of the existing collection, which differs from the applied document,
+/// but can't be changed without re-creating the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ConfigDifference {
+    pub param: String,
+    pub current: serde_json::Value,
+    pub target: serde_json::Value,
+}
+
+/// Changes, made by applying a config document
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct ApplyCollectionConfigResult {
+    /// The collection did not exist and was created
+    pub created: bool,
+    /// Params of the existing collection, which were updated
+    pub updated_params: Vec<String>,
+    pub created_payload_indexes: Vec<PayloadKeyType>,
+    pub created_aliases: Vec<String>,
+    /// Params, which differ from the document and were left as is
+    pub immutable_differences: Vec<ConfigDifference>,
+}
+
+/// Config document of the collection, found by its name or alias
+pub async fn export_collection_config(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<CollectionConfigDocument, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    let real_collection_name = collection.name();
+
+    let mut config = CreateCollection::from(collection.state().await.config);
+    config.payload_indexes = None;
+
+    let mut payload_indexes = BTreeMap::new();
+    for (key, index_info) in collection.info(None).await?.payload_schema {
+        let schema = PayloadFieldSchema::try_from(index_info).map_err(|err| {
+            StorageError::service_error(format!("Can't export index of the field {key}: {err}"))
+        })?;
+        payload_indexes.insert(key, schema);
+    }
+    drop(collection);
+
+    let mut aliases = toc.collection_aliases(&real_collection_name).await?;
+    aliases.sort_unstable();
+
+    Ok(CollectionConfigDocument {
+        collection_name: real_collection_name,
+        config,
+        payload_indexes,
+        aliases,
+    })
+}
+
+/// Create the collection of the document, or bring the existing one in line with it.
+///
+/// Missing payload indexes and aliases are created, mutable params are updated.
+/// Params, which can't be changed in place, are reported and left as is, so the data of
+/// the collection is never changed. Params, not specified in the document, are not compared.
+/// Payload indexes and aliases, missing in the document, are kept.
+pub async fn apply_collection_config(
+    dispatcher: &Dispatcher,
+    document: CollectionConfigDocument,
+    wait_timeout: Option<Duration>,
+) -> Result<ApplyCollectionConfigResult, StorageError> {
+    let toc = dispatcher.toc();
+    let collection_name = document.collection_name;
+    let mut result = ApplyCollectionConfigResult::default();
+
+    let current_indexes = if toc.all_collections().await.contains(&collection_name) {
+        let current = export_collection_config(toc, &collection_name).await?;
+        let (update, updated_params, immutable_differences) =
+            compare_configs(&collection_name, &current.config, &document.config);
+        if !updated_params.is_empty() {
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::UpdateCollection(update),
+                    wait_timeout,
+                )
+                .await?;
+        }
+        result.updated_params = updated_params;
+        result.immutable_differences = immutable_differences;
+        current.payload_indexes
+    } else {
+        dispatcher
+            .submit_collection_meta_op(
+                CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                    collection_name.clone(),
+                    document.config,
+                )),
+                wait_timeout,
+            )
+            .await?;
+        result.created = true;
+        BTreeMap::new()
+    };
+
+    for (key, schema) in document.payload_indexes {
+        match current_indexes.get(&key) {
+            Some(current_schema) if current_schema == &schema => {}
+            Some(current_schema) => result.immutable_differences.push(ConfigDifference {
+                param: format!("payload_indexes.{key}"),
+                current: to_json(current_schema),
+                target: to_json(&schema),
+            }),
+            None => {
+                let operation = CollectionUpdateOperations::FieldIndexOperation(
+                    FieldIndexOperations::CreateIndex(CreateIndex {
+                        field_name: key.clone(),
+                        field_schema: Some(schema),
+                    }),
+                );
+                toc.update(
+                    &collection_name,
+                    operation,
+                    None,
+                    true,
+                    WriteOrdering::default(),
+                )
+                .await?;
+                result.created_payload_indexes.push(key);
+            }
+        }
+    }
+
+    let existing_aliases: BTreeMap<_, _> = toc
+        .list_aliases()
+        .await?
+        .into_iter()
+        .map(|alias| (alias.alias_name, alias.collection_name))
+        .collect();
+    let mut alias_actions = Vec::new();
+    for alias_name in document.aliases {
+        match existing_aliases.get(&alias_name) {
+            Some(target) if target == &collection_name => {}
+            // Alias of another collection is not re-pointed implicitly
+            Some(target) => result.immutable_differences.push(ConfigDifference {
+                param: format!("aliases.{alias_name}"),
+                current: serde_json::Value::String(target.clone()),
+                target: serde_json::Value::String(collection_name.clone()),
+            }),
+            None => {
+                alias_actions.push(
+                    CreateAlias {
+                        collection_name: collection_name.clone(),
+                        alias_name: alias_name.clone(),
+                    }
+                    .into(),
+                );
+                result.created_aliases.push(alias_name);
+            }
+        }
+    }
+    if !alias_actions.is_empty() {
+        dispatcher
+            .submit_collection_meta_op(
+                CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation {
+                    actions: alias_actions,
+                }),
+                wait_timeout,
+            )
+            .await?;
+    }
+
+    Ok(result)
+}
+
+fn to_json(value: &impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// Target value of the param, if it is specified and differs from the current one
+fn changed<T: PartialEq + Clone>(
+    param: &str,
+    current: &Option<T>,
+    target: &Option<T>,
+    updated_params: &mut Vec<String>,
+) -> Option<T> {
+    match target {
+        Some(target) if current.as_ref() != Some(target) => {
+            updated_params.push(param.to_string());
+            Some(target.clone())
+        }
+        _ => None,
+    }
+}
+
+fn check_immutable<T: PartialEq + Serialize>(
+    param: &str,
+    current: Option<&T>,
+    target: Option<&T>,
+    differences: &mut Vec<ConfigDifference>,
+) {
+    if let Some(target) = target {
+        if current != Some(target) {
+            differences.push(ConfigDifference {
+                param: param.to_string(),
+                current: to_json(&current),
+                target: to_json(target),
+            });
+        }
+    }
+}
+
+/// Update of the mutable params, names of the updated params and differences of the immutable ones
+fn compare_configs(
+    collection_name: &str,
+    current: &CreateCollection,
+    target: &CreateCollection,
+) -> (
+    UpdateCollectionOperation,
+    Vec<String>,
+    Vec<ConfigDifference>,
+) {
+    let mut operation = UpdateCollectionOperation::new_empty(collection_name.to_string());
+    let mut updated = Vec::new();
+    let mut differences = Vec::new();
+
+    let update = &mut operation.update_collection;
+    let params = CollectionParamsDiff {
+        replication_factor: changed(
+            "replication_factor",
+            &current.replication_factor,
+            &target.replication_factor,
+            &mut updated,
+        )
+        .and_then(NonZeroU32::new),
+        write_consistency_factor: changed(
+            "write_consistency_factor",
+            &current.write_consistency_factor,
+            &target.write_consistency_factor,
+            &mut updated,
+        )
+        .and_then(NonZeroU32::new),
+        validate_payload: changed(
+            "validate_payload",
+            &current.validate_payload,
+            &target.validate_payload,
+            &mut updated,
+        ),
+        payload_validation_mode: changed(
+            "payload_validation_mode",
+            &current.payload_validation_mode,
+            &target.payload_validation_mode,
+            &mut updated,
+        ),
+        read_hedge_delay_ms: changed(
+            "read_hedge_delay_ms",
+            &current.read_hedge_delay_ms,
+            &target.read_hedge_delay_ms,
+            &mut updated,
+        ),
+        read_only: None,
+        auto_index_group_by_fields: None,
+    };
+    if !updated.is_empty() {
+        update.params = Some(params);
+    }
+    update.optimizers_config = changed(
+        "optimizers_config",
+        &current.optimizers_config,
+        &target.optimizers_config,
+        &mut updated,
+    );
+    update.strict_mode_config = changed(
+        "strict_mode_config",
+        &current.strict_mode_config,
+        &target.strict_mode_config,
+        &mut updated,
+    );
+    update.default_search_params = changed(
+        "default_search_params",
+        &current.default_search_params,
+        &target.default_search_params,
+        &mut updated,
+    );
+    update.default_with_payload = changed(
+        "default_with_payload",
+        &current.default_with_payload,
+        &target.default_with_payload,
+        &mut updated,
+    );
+    update.default_with_vector = changed(
+        "default_with_vector",
+        &current.default_with_vector,
+        &target.default_with_vector,
+        &mut updated,
+    );
+    update.default_max_payload_return_bytes = changed(
+        "default_max_payload_return_bytes",
+        &current.default_max_payload_return_bytes,
+        &target.default_max_payload_return_bytes,
+        &mut updated,
+    );
+    update.ttl_config = changed(
+        "ttl_config",
+        &current.ttl_config,
+        &target.ttl_config,
+        &mut updated,
+    );
+    update.default_vector = changed(
+        "default_vector",
+        &current.default_vector,
+        &target.default_vector,
+        &mut updated,
+    );
+    update.quota_config = changed(
+        "quota_config",
+        &current.quota_config,
+        &target.quota_config,
+        &mut updated,
+    );
+    update.admission_config = changed(
+        "admission_config",
+        &current.admission_config,
+        &target.admission_config,
+        &mut updated,
+    );
+    update.read_cache_config = changed(
+        "read_cache_config",
+        &current.read_cache_config,
+        &target.read_cache_config,
+        &mut updated,
+    );
+
+    check_immutable(
+        "vectors",
+        Some(&current.vectors),
+        Some(&target.vectors),
+        &mut differences,
+    );
+    check_immutable(
+        "sparse_vectors",
+        current.sparse_vectors.as_ref(),
+        target.sparse_vectors.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "shard_number",
+        current.shard_number.as_ref(),
+        target.shard_number.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "on_disk_payload",
+        current.on_disk_payload.as_ref(),
+        target.on_disk_payload.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "hnsw_config",
+        current.hnsw_config.as_ref(),
+        target.hnsw_config.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "wal_config",
+        current.wal_config.as_ref(),
+        target.wal_config.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "quantization_config",
+        current.quantization_config.as_ref(),
+        target.quantization_config.as_ref(),
+        &mut differences,
+    );
+    check_immutable(
+        "payload_cache_fields",
+        current.payload_cache_fields.as_ref(),
+        target.payload_cache_fields.as_ref(),
+        &mut differences,
+    );
+
+    (operation, updated, differences)
+}
@@ -1,18 +1,31 @@
+use std::collections::BTreeMap;
+
+use collection::admission::AdmissionConfig;
 use collection::config::CollectionConfig;
 use collection::operations::config_diff::{
     CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, WalConfigDiff,
 };
-use collection::operations::types::VectorsConfig;
+use collection::operations::payload_validation::PayloadValidationMode;
+use collection::operations::strict_mode::StrictModeConfig;
+use collection::operations::types::{SparseVectorParams, VectorsConfig};
+use collection::quota::QuotaConfig;
+use collection::read_cache::ReadCacheConfig;
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
 use collection::shards::{replica_set, CollectionId};
+use collection::ttl::TtlConfig;
 use schemars::JsonSchema;
-use segment::types::QuantizationConfig;
+use segment::types::{
+    PayloadFieldSchema, PayloadKeyType, QuantizationConfig, SearchParams, WithPayloadInterface,
+    WithVector,
+};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::content_manager::shard_distribution::ShardDistributionProposal;
+use crate::content_manager::shard_distribution::{
+    PlacementPolicy, ShardDistributionProposal, ShardPlacement,
+};
 
 // *Operation wrapper structure is only required for better OpenAPI generation
 
@@ -103,6 +116,10 @@ pub struct CreateCollection {
     /// It is possible to provide one config for single vector mode and list of configs for multiple vectors mode.
     #[validate]
     pub vectors: VectorsConfig,
+    /// Sparse vector data config, by vector name.
+    /// Sparse vector names must not clash with the names of dense vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
     /// Number of shards in collection.
     /// Default is 1 for standalone, otherwise equal to the number of nodes
     /// Minimum is 1
@@ -113,6 +130,14 @@ pub struct CreateCollection {
     /// Minimum is 1
     #[serde(default)]
     pub replication_factor: Option<u32>,
+    /// Explicit placement of the replicas of each shard.
+    /// If none - shards are distributed evenly between the peers
+    #[serde(default)]
+    pub shard_placement: Option<Vec<ShardPlacement>>,
+    /// Placement of the shard replicas by the labels of the peers.
+    /// Can't be combined with `shard_placement`
+    #[serde(default)]
+    pub placement_policy: Option<PlacementPolicy>,
     /// Defines how many replicas should apply the operation for us to consider it successful.
     /// Increasing this number will make the collection more resilient to inconsistencies, but will
     /// also make it fail if not enough replicas are available.
@@ -125,6 +150,18 @@ pub struct CreateCollection {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// If true - payload values of the upserted points are checked against the types of the
+    /// indexed fields. Default: false
+    #[serde(default)]
+    pub validate_payload: Option<bool>,
+    /// Whether the whole operation or only the points with invalid payload are rejected,
+    /// if `validate_payload` is enabled. Default: operation
+    #[serde(default)]
+    pub payload_validation_mode: Option<PayloadValidationMode>,
+    /// If set - a read, which requires a single response, is also sent to another replica if the
+    /// first one doesn't respond within this delay in milliseconds. Default: not set
+    #[serde(default)]
+    pub read_hedge_delay_ms: Option<u64>,
     /// Custom params for HNSW index. If none - values from service configuration file are used.
     #[validate]
     pub hnsw_config: Option<HnswConfigDiff>,
@@ -142,6 +179,53 @@ pub struct CreateCollection {
     #[serde(default, alias = "quantization")]
     #[validate]
     pub quantization_config: Option<QuantizationConfig>,
+    /// Limits of the read requests. If none - strict mode is disabled.
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// Search params, used by the requests, which don't specify them
+    #[serde(default)]
+    #[validate]
+    pub default_search_params: Option<SearchParams>,
+    /// Payload selector, used by the requests, which don't specify `with_payload`
+    #[serde(default)]
+    pub default_with_payload: Option<WithPayloadInterface>,
+    /// Vector selector, used by the requests, which don't specify `with_vector`
+    #[serde(default)]
+    pub default_with_vector: Option<WithVector>,
+    /// Limit of the returned payload size of each point, used by the requests, which don't
+    /// specify `max_payload_return_bytes`. If none - payloads are returned in full
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub default_max_payload_return_bytes: Option<usize>,
+    /// Expiration of the points by a payload field. If none - points don't expire
+    #[serde(default)]
+    #[validate]
+    pub ttl_config: Option<TtlConfig>,
+    /// Named vector, used by the requests, which don't specify the vector name.
+    /// Must be one of the dense vectors of the collection
+    #[serde(default)]
+    pub default_vector: Option<String>,
+    /// Payload indexes, created in every segment of the collection from the start,
+    /// so the points are indexed as they are uploaded
+    #[serde(default)]
+    pub payload_indexes: Option<BTreeMap<PayloadKeyType, PayloadFieldSchema>>,
+    /// Payload fields, which values are kept in memory along with the on-disk payload,
+    /// so filtering and grouping by them don't read the payload from disk
+    #[serde(default)]
+    pub payload_cache_fields: Option<Vec<PayloadKeyType>>,
+    /// Limits of the data, stored in the collection. If none - the data is not limited
+    #[serde(default)]
+    #[validate]
+    pub quota_config: Option<QuotaConfig>,
+    /// Limits of the read and write operations. If none - operations are not limited
+    #[serde(default)]
+    #[validate]
+    pub admission_config: Option<AdmissionConfig>,
+    /// Cache of the responses to the read operations. If none - responses are not cached
+    #[serde(default)]
+    #[validate]
+    pub read_cache_config: Option<ReadCacheConfig>,
 }
 
 /// Operation for creating new collection and (optionally) specify index params
@@ -185,6 +269,48 @@ pub struct UpdateCollection {
     pub optimizers_config: Option<OptimizersConfigDiff>, // ToDo: Allow updates for other configuration params as well
     /// Collection base params.  If none - values from service configuration file are used.
     pub params: Option<CollectionParamsDiff>,
+    /// Limits of the read requests. Only specified limits are updated.
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
+    /// New search params, used by the requests, which don't specify them
+    #[serde(default)]
+    #[validate]
+    pub default_search_params: Option<SearchParams>,
+    /// New payload selector, used by the requests, which don't specify `with_payload`
+    #[serde(default)]
+    pub default_with_payload: Option<WithPayloadInterface>,
+    /// New vector selector, used by the requests, which don't specify `with_vector`
+    #[serde(default)]
+    pub default_with_vector: Option<WithVector>,
+    /// New limit of the returned payload size of each point, used by the requests,
+    /// which don't specify `max_payload_return_bytes`
+    #[serde(default)]
+    #[validate(range(min = 2))]
+    pub default_max_payload_return_bytes: Option<usize>,
+    /// New expiration of the points by a payload field
+    #[serde(default)]
+    #[validate]
+    pub ttl_config: Option<TtlConfig>,
+    /// New named vector, used by the requests, which don't specify the vector name.
+    /// Empty string resets it
+    #[serde(default)]
+    pub default_vector: Option<String>,
+    /// Limits of the data, stored in the collection. Only specified limits are updated.
+    /// Lowered limits don't delete the data, only further growth is rejected
+    #[serde(default)]
+    #[validate]
+    pub quota_config: Option<QuotaConfig>,
+    /// Limits of the read and write operations. Only specified limits are updated.
+    /// Operations, which are already admitted, are not affected
+    #[serde(default)]
+    #[validate]
+    pub admission_config: Option<AdmissionConfig>,
+    /// New parameters of the cache of the responses. Only specified parameters are updated.
+    /// Responses, cached so far, are dropped
+    #[serde(default)]
+    #[validate]
+    pub read_cache_config: Option<ReadCacheConfig>,
 }
 
 /// Operation for updating parameters of the existing collection
@@ -203,6 +329,16 @@ impl UpdateCollectionOperation {
             update_collection: UpdateCollection {
                 optimizers_config: None,
                 params: None,
+                strict_mode_config: None,
+                default_search_params: None,
+                default_with_payload: None,
+                default_with_vector: None,
+                default_max_payload_return_bytes: None,
+                default_vector: None,
+                quota_config: None,
+                admission_config: None,
+                read_cache_config: None,
+                ttl_config: None,
             },
             shard_replica_changes: None,
         }
@@ -299,13 +435,31 @@ impl From<CollectionConfig> for CreateCollection {
             vectors: value.params.vectors,
             shard_number: Some(value.params.shard_number.get()),
             replication_factor: Some(value.params.replication_factor.get()),
+            shard_placement: None,
+            placement_policy: None,
             write_consistency_factor: Some(value.params.write_consistency_factor.get()),
             on_disk_payload: Some(value.params.on_disk_payload),
+            validate_payload: Some(value.params.validate_payload),
+            payload_validation_mode: Some(value.params.payload_validation_mode),
+            read_hedge_delay_ms: value.params.read_hedge_delay_ms,
+            sparse_vectors: value.params.sparse_vectors,
             hnsw_config: Some(value.hnsw_config.into()),
             wal_config: Some(value.wal_config.into()),
             optimizers_config: Some(value.optimizer_config.into()),
             init_from: None,
             quantization_config: value.quantization_config,
+            strict_mode_config: value.strict_mode_config,
+            default_search_params: value.default_search_params,
+            default_with_payload: value.default_with_payload,
+            default_with_vector: value.default_with_vector,
+            default_max_payload_return_bytes: value.default_max_payload_return_bytes,
+            ttl_config: value.ttl_config,
+            default_vector: value.default_vector,
+            payload_indexes: Some(value.params.payload_indexes),
+            payload_cache_fields: Some(value.params.payload_cache_fields),
+            quota_config: value.quota_config,
+            admission_config: value.admission_config,
+            read_cache_config: value.read_cache_config,
         }
     }
 }
@@ -79,6 +79,19 @@ impl ConsensusOpWal {
         Ok(entry.transpose()?)
     }
 
+    /// Remove the entries up to `until_index` raft index, which are covered by a snapshot.
+    /// WAL is truncated by whole segments, so some of these entries might remain.
+    pub fn compact(&mut self, until_index: u64) -> Result<(), StorageError> {
+        let Some(offset) = self.index_offset()? else {
+            return Ok(());
+        };
+        if until_index <= offset {
+            return Ok(());
+        }
+        self.0.prefix_truncate(until_index - offset)?;
+        Ok(())
+    }
+
     /// Difference between raft index and WAL record number.
     /// Difference might be different because of consensus snapshot.
     fn index_offset(&self) -> Result<Option<u64>, StorageError> {
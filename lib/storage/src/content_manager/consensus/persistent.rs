@@ -14,7 +14,7 @@ use raft::RaftState;
 use serde::{Deserialize, Serialize};
 
 use crate::content_manager::consensus::entry_queue::{EntryApplyProgressQueue, EntryId};
-use crate::types::PeerAddressById;
+use crate::types::{PeerAddressById, PeerLabels, PeerLabelsById};
 use crate::StorageError;
 
 const STATE_FILE_NAME: &str = "raft_state";
@@ -36,6 +36,9 @@ pub struct Persistent {
     /// Last known cluster topology
     #[serde(with = "serialize_peer_addresses")]
     pub peer_address_by_id: Arc<RwLock<PeerAddressById>>,
+    /// Labels of the peers, assigned through the consensus
+    #[serde(default)]
+    pub peer_labels_by_id: PeerLabelsById,
     pub this_peer_id: PeerId,
     #[serde(skip)]
     pub path: PathBuf,
@@ -57,8 +60,10 @@ impl Persistent {
         &mut self,
         meta: &SnapshotMetadata,
         address_by_id: PeerAddressById,
+        labels_by_id: PeerLabelsById,
     ) -> Result<(), StorageError> {
         *self.peer_address_by_id.write() = address_by_id;
+        self.peer_labels_by_id = labels_by_id;
         self.state.conf_state = meta.get_conf_state().clone();
         self.state.hard_state.term = cmp::max(self.state.hard_state.term, meta.term);
         self.state.hard_state.commit = meta.index;
@@ -146,6 +151,23 @@ impl Persistent {
         self.peer_address_by_id.read().clone()
     }
 
+    pub fn peer_labels_by_id(&self) -> PeerLabelsById {
+        self.peer_labels_by_id.clone()
+    }
+
+    pub fn set_peer_labels(
+        &mut self,
+        peer_id: PeerId,
+        labels: PeerLabels,
+    ) -> Result<(), StorageError> {
+        if labels.is_empty() {
+            self.peer_labels_by_id.remove(&peer_id);
+        } else {
+            self.peer_labels_by_id.insert(peer_id, labels);
+        }
+        self.save()
+    }
+
     pub fn this_peer_id(&self) -> PeerId {
         self.this_peer_id
     }
@@ -176,6 +198,7 @@ impl Persistent {
             },
             apply_progress_queue: Default::default(),
             peer_address_by_id: Default::default(),
+            peer_labels_by_id: Default::default(),
             this_peer_id,
             path,
             latest_snapshot_meta: Default::default(),
@@ -29,10 +29,10 @@ use super::CollectionContainer;
 use crate::content_manager::consensus::consensus_wal::ConsensusOpWal;
 use crate::content_manager::consensus::entry_queue::EntryId;
 use crate::content_manager::consensus::operation_sender::OperationSender;
-use crate::content_manager::consensus::persistent::Persistent;
+use crate::content_manager::consensus::persistent::{Persistent, SnapshotMetadataSer};
 use crate::types::{
     ClusterInfo, ClusterStatus, ConsensusThreadStatus, MessageSendErrors, PeerAddressById,
-    PeerInfo, RaftInfo,
+    PeerInfo, PeerLabelsById, RaftInfo,
 };
 
 pub const DEFAULT_META_OP_WAIT: Duration = Duration::from_secs(10);
@@ -48,6 +48,8 @@ pub struct SnapshotData {
     pub collections_data: CollectionsSnapshot,
     #[serde(with = "crate::serialize_peer_addresses")]
     pub address_by_id: PeerAddressById,
+    #[serde(default)]
+    pub labels_by_id: PeerLabelsById,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -199,6 +201,11 @@ impl<C: CollectionContainer> ConsensusManager<C> {
                     peer_id,
                     PeerInfo {
                         uri: uri.to_string(),
+                        labels: persistent
+                            .peer_labels_by_id
+                            .get(&peer_id)
+                            .cloned()
+                            .unwrap_or_default(),
                     },
                 )
             })
@@ -437,6 +444,12 @@ impl<C: CollectionContainer> ConsensusManager<C> {
                 self.toc.perform_collection_meta_op(*operation)
             }
 
+            ConsensusOperations::UpdatePeerLabels { peer_id, labels } => self
+                .persistent
+                .write()
+                .set_peer_labels(peer_id, labels)
+                .map(|()| true),
+
             ConsensusOperations::AddPeer { .. } | ConsensusOperations::RemovePeer(_) => {
                 // RemovePeer or AddPeer should be converted into native ConfChangeV2 message before sending to the Raft.
                 // So we do not expect to receive these operations as a normal entry.
@@ -472,9 +485,11 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         let data: SnapshotData = snapshot.get_data().try_into()?;
         self.toc.apply_collections_snapshot(data.collections_data)?;
         self.wal.lock().clear()?;
-        self.persistent
-            .write()
-            .update_from_snapshot(meta, data.address_by_id)?;
+        self.persistent.write().update_from_snapshot(
+            meta,
+            data.address_by_id,
+            data.labels_by_id,
+        )?;
 
         Ok(Ok(()))
     }
@@ -520,7 +535,9 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         // plus we need to make additional removing in the `channel_pool`.
         // So we handle `remove_peer` inside the `toc` and persist changes in the `persistent` after that.
         self.toc.remove_peer(peer_id)?;
-        self.persistent.read().save()
+        let mut persistent = self.persistent.write();
+        persistent.peer_labels_by_id.remove(&peer_id);
+        persistent.save()
     }
 
     async fn await_receiver(
@@ -648,6 +665,10 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         self.persistent.read().peer_address_by_id()
     }
 
+    pub fn peer_labels_by_id(&self) -> PeerLabelsById {
+        self.persistent.read().peer_labels_by_id()
+    }
+
     pub fn peer_count(&self) -> usize {
         self.persistent.read().peer_address_by_id.read().len()
     }
@@ -660,8 +681,51 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         self.persistent.read().last_applied_entry()
     }
 
+    /// Compact the WAL of the consensus operations, if at least `min_entries` were applied
+    /// since the last snapshot.
+    ///
+    /// Collections, aliases and transfers are persisted by the storage itself,
+    /// so only the metadata of the snapshot is saved. Peers, which require the compacted entries,
+    /// receive the snapshot of the current state, followed by the rest of the log.
+    ///
+    /// Returns `true` if the WAL was compacted.
+    pub fn compact_wal(&self, min_entries: u64) -> Result<bool, StorageError> {
+        let mut wal = self.wal.lock();
+        let mut persistent = self.persistent.write();
+        let Some(last_applied) = persistent.last_applied_entry() else {
+            return Ok(false);
+        };
+        let snapshot_index = persistent.latest_snapshot_meta.index;
+        if last_applied < snapshot_index + min_entries {
+            return Ok(false);
+        }
+        let term = wal.entry(last_applied)?.term;
+        persistent.latest_snapshot_meta = SnapshotMetadataSer {
+            term,
+            index: last_applied,
+        };
+        persistent.save()?;
+        wal.compact(last_applied)?;
+        log::debug!(
+            "Compacted consensus WAL up to entry {last_applied}, previous snapshot at {snapshot_index}"
+        );
+        Ok(true)
+    }
+
     pub fn sync_local_state(&self) -> Result<(), StorageError> {
-        self.toc.sync_local_state()
+        self.toc.sync_local_state()?;
+        if self.is_leader() {
+            self.toc.balance_shards()?;
+        }
+        Ok(())
+    }
+
+    fn is_leader(&self) -> bool {
+        let this_peer_id = self.this_peer_id();
+        self.soft_state
+            .read()
+            .as_ref()
+            .map_or(false, |state| state.leader_id == this_peer_id)
     }
 }
 
@@ -706,9 +770,12 @@ impl<C: CollectionContainer> Storage for ConsensusManager<C> {
     }
 
     fn first_index(&self) -> raft::Result<u64> {
-        let index = match self.wal.lock().first_entry().map_err(raft_error_other)? {
-            Some(entry) => entry.index,
-            None => self.persistent.read().latest_snapshot_meta().index + 1,
+        let first_entry = self.wal.lock().first_entry().map_err(raft_error_other)?;
+        // WAL is compacted by segments, so it might still contain entries covered by the snapshot
+        let after_snapshot = self.persistent.read().latest_snapshot_meta().index + 1;
+        let index = match first_entry {
+            Some(entry) => entry.index.max(after_snapshot),
+            None => after_snapshot,
         };
         Ok(index)
     }
@@ -723,19 +790,31 @@ impl<C: CollectionContainer> Storage for ConsensusManager<C> {
 
     fn snapshot(&self, request_index: u64, _to: u64) -> raft::Result<raft::eraftpb::Snapshot> {
         let collections_data = self.toc.collections_snapshot();
-        let persistent = self.persistent.read();
-        let raft_state = persistent.state().clone();
-        if raft_state.hard_state.commit >= request_index {
+        let (snapshot, conf_state, applied_index) = {
+            let persistent = self.persistent.read();
             let snapshot = SnapshotData {
                 collections_data,
                 address_by_id: persistent.peer_address_by_id(),
+                labels_by_id: persistent.peer_labels_by_id(),
             };
+            // Collections state includes all applied entries, which might be behind the commit
+            let applied_index = persistent
+                .last_applied_entry()
+                .unwrap_or_default()
+                .max(persistent.latest_snapshot_meta().index);
+            (
+                snapshot,
+                persistent.state().conf_state.clone(),
+                applied_index,
+            )
+        };
+        if applied_index >= request_index {
             Ok(raft::eraftpb::Snapshot {
                 data: serde_cbor::to_vec(&snapshot).map_err(raft_error_other)?,
                 metadata: Some(raft::eraftpb::SnapshotMetadata {
-                    conf_state: Some(raft_state.conf_state),
-                    index: raft_state.hard_state.commit,
-                    term: raft_state.hard_state.term,
+                    conf_state: Some(conf_state),
+                    index: applied_index,
+                    term: self.term(applied_index)?,
                 }),
             })
         } else {
@@ -811,6 +890,7 @@ mod tests {
     use proptest::prelude::*;
     use raft::eraftpb::Entry;
     use raft::storage::{MemStorage, Storage};
+    use raft::GetEntriesContext;
     use tempfile::Builder;
 
     use super::ConsensusManager;
@@ -943,6 +1023,10 @@ mod tests {
         fn sync_local_state(&self) -> Result<(), crate::content_manager::errors::StorageError> {
             Ok(())
         }
+
+        fn balance_shards(&self) -> Result<(), crate::content_manager::errors::StorageError> {
+            Ok(())
+        }
     }
 
     fn setup_storages(
@@ -963,6 +1047,61 @@ mod tests {
         (consensus_state, mem_storage)
     }
 
+    #[test]
+    fn compact_wal() {
+        let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let entries = (1..=300)
+            .map(|index| Entry {
+                index,
+                term: 1 + index / 100,
+                ..Default::default()
+            })
+            .collect();
+        let (consensus_state, _) = setup_storages(entries, dir.path());
+        let apply = |count: usize| {
+            for _ in 0..count {
+                consensus_state.persistent.write().entry_applied().unwrap();
+            }
+        };
+
+        // Nothing is applied yet
+        assert!(!consensus_state.compact_wal(100).unwrap());
+
+        consensus_state.set_unapplied_entries(1, 300).unwrap();
+        apply(250);
+        assert!(consensus_state.compact_wal(100).unwrap());
+
+        assert_eq!(consensus_state.first_index(), Ok(251));
+        assert_eq!(consensus_state.last_index(), Ok(300));
+        assert_eq!(consensus_state.term(250), Ok(3));
+        let context = || GetEntriesContext::empty(false);
+        assert_eq!(
+            consensus_state
+                .entries(251, 301, None, context())
+                .unwrap()
+                .len(),
+            50
+        );
+        assert_eq!(
+            consensus_state.entries(200, 260, None, context()),
+            Err(raft::Error::Store(raft::StorageError::Compacted))
+        );
+
+        // Snapshot of the current state replaces the compacted entries
+        let snapshot = consensus_state.snapshot(0, 0).unwrap();
+        assert_eq!(snapshot.get_metadata().index, 250);
+        assert_eq!(snapshot.get_metadata().term, 3);
+
+        // Not enough entries applied since the last compaction
+        apply(20);
+        assert!(!consensus_state.compact_wal(100).unwrap());
+        assert_eq!(consensus_state.first_index(), Ok(251));
+
+        // Snapshot position survives restart
+        let persistent = Persistent::load_or_init(dir.path(), true).unwrap();
+        assert_eq!(persistent.latest_snapshot_meta().index, 250);
+    }
+
     prop_compose! {
         fn gen_entries(min_entries: u64, max_entries: u64)(n in min_entries..max_entries, inc_term_every in 1u64..max_entries) -> Vec<Entry> {
             (1..=n).map(|index| Entry {index, term: 1 + index/inc_term_every, ..Default::default()}).collect::<Vec<Entry>>()
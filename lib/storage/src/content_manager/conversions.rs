@@ -1,6 +1,10 @@
 use std::collections::BTreeMap;
 
+use collection::operations::conversions::{
+    payload_indexes_from_proto, payload_validation_mode_from_proto, sparse_vectors_from_proto,
+};
 use collection::operations::types::VectorsConfig;
+use tonic::metadata::MetadataValue;
 use tonic::Status;
 
 use crate::content_manager::collection_meta_ops::{
@@ -11,6 +15,12 @@ use crate::content_manager::collection_meta_ops::{
 };
 use crate::content_manager::errors::StorageError;
 
+/// Metadata key of the stable code of the error reason, if the error has one
+pub const ERROR_REASON_METADATA_KEY: &str = "qdrant-error-reason";
+
+/// Metadata key of the suggested delay before the retry of a rate limited request, in seconds
+pub const RETRY_AFTER_METADATA_KEY: &str = "retry-after";
+
 pub fn error_to_status(error: StorageError) -> tonic::Status {
     let error_code = match &error {
         StorageError::BadInput { .. } => tonic::Code::InvalidArgument,
@@ -18,8 +28,26 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::ServiceError { .. } => tonic::Code::Internal,
         StorageError::BadRequest { .. } => tonic::Code::InvalidArgument,
         StorageError::Locked { .. } => tonic::Code::FailedPrecondition,
+        StorageError::Forbidden { .. } => tonic::Code::PermissionDenied,
+        StorageError::UnderReplicated { .. } => tonic::Code::Aborted,
+        StorageError::RateLimited { .. } => tonic::Code::ResourceExhausted,
+        StorageError::WithReason { reason, .. } if reason.is_not_found() => tonic::Code::NotFound,
+        StorageError::WithReason { .. } => tonic::Code::InvalidArgument,
     };
-    tonic::Status::new(error_code, format!("{error}"))
+    let mut status = tonic::Status::new(error_code, format!("{error}"));
+    if let Some(reason) = error.reason() {
+        status.metadata_mut().insert(
+            ERROR_REASON_METADATA_KEY,
+            MetadataValue::from_static(reason.as_str()),
+        );
+    }
+    if let Some(retry_after_secs) = error.retry_after_secs() {
+        status.metadata_mut().insert(
+            RETRY_AFTER_METADATA_KEY,
+            MetadataValue::from(retry_after_secs),
+        );
+    }
+    status
 }
 
 impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
@@ -52,9 +80,18 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                 hnsw_config: value.hnsw_config.map(|v| v.into()),
                 wal_config: value.wal_config.map(|v| v.into()),
                 optimizers_config: value.optimizers_config.map(|v| v.into()),
+                sparse_vectors: value.sparse_vectors_config.map(sparse_vectors_from_proto),
                 shard_number: value.shard_number,
                 on_disk_payload: value.on_disk_payload,
+                validate_payload: value.validate_payload,
+                payload_validation_mode: value
+                    .payload_validation_mode
+                    .map(payload_validation_mode_from_proto)
+                    .transpose()?,
+                read_hedge_delay_ms: value.read_hedge_delay_ms,
                 replication_factor: value.replication_factor,
+                shard_placement: None,
+                placement_policy: None,
                 write_consistency_factor: value.write_consistency_factor,
                 init_from: value
                     .init_from_collection
@@ -66,6 +103,26 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                         None
                     }
                 },
+                strict_mode_config: value.strict_mode_config.map(Into::into),
+                default_search_params: None,
+                default_with_payload: None,
+                default_with_vector: None,
+                default_max_payload_return_bytes: None,
+                ttl_config: value.ttl_config.map(Into::into),
+                default_vector: value.default_vector,
+                payload_indexes: if value.payload_indexes.is_empty() {
+                    None
+                } else {
+                    Some(payload_indexes_from_proto(value.payload_indexes)?)
+                },
+                payload_cache_fields: if value.payload_cache_fields.is_empty() {
+                    None
+                } else {
+                    Some(value.payload_cache_fields)
+                },
+                quota_config: value.quota_config.map(Into::into),
+                admission_config: value.admission_config.map(Into::into),
+                read_cache_config: value.read_cache_config.map(Into::into),
             },
         )))
     }
@@ -80,6 +137,16 @@ impl TryFrom<api::grpc::qdrant::UpdateCollection> for CollectionMetaOperations {
             UpdateCollection {
                 optimizers_config: value.optimizers_config.map(Into::into),
                 params: value.params.map(TryInto::try_into).transpose()?,
+                strict_mode_config: value.strict_mode_config.map(Into::into),
+                default_search_params: None,
+                default_with_payload: None,
+                default_with_vector: None,
+                default_max_payload_return_bytes: None,
+                ttl_config: value.ttl_config.map(Into::into),
+                default_vector: value.default_vector,
+                quota_config: value.quota_config.map(Into::into),
+                admission_config: value.admission_config.map(Into::into),
+                read_cache_config: value.read_cache_config.map(Into::into),
             },
         )))
     }
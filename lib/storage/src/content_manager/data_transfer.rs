@@ -58,7 +58,9 @@ async fn get_local_source_shards(
     Ok(local_responsible_shards)
 }
 
-fn handle_get_collection(collection: Option<&Collection>) -> CollectionResult<&Collection> {
+pub(super) fn handle_get_collection(
+    collection: Option<&Collection>,
+) -> CollectionResult<&Collection> {
     match collection {
         Some(collection) => Ok(collection),
         None => Err(CollectionError::service_error(
@@ -83,6 +85,8 @@ async fn replicate_shard_data(
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(true),
+            with_timestamps: false,
+            max_payload_return_bytes: None,
         };
 
         let collections_read = collections.read().await;
@@ -118,7 +122,7 @@ async fn replicate_shard_data(
             handle_get_collection(collections_read.get(target_collection_name))?;
 
         target_collection
-            .update_from_client(upsert_request, false, WriteOrdering::default())
+            .update_internal(upsert_request, false, WriteOrdering::default())
             .await?;
 
         if offset.is_none() {
@@ -128,7 +132,7 @@ async fn replicate_shard_data(
     Ok(())
 }
 
-async fn wait_all_shards_active(
+pub(super) async fn wait_all_shards_active(
     collections: Arc<RwLock<Collections>>,
     collection_name: &CollectionId,
 ) -> CollectionResult<()> {
@@ -220,7 +224,7 @@ pub async fn transfer_indexes(
             }),
         );
         target_collection
-            .update_from_client(request, false, WriteOrdering::default())
+            .update_internal(request, false, WriteOrdering::default())
             .await?;
     }
 
@@ -1,7 +1,8 @@
 use std::backtrace::Backtrace;
 use std::io::Error as IoError;
+use std::time::Duration;
 
-use collection::operations::types::CollectionError;
+use collection::operations::types::{CollectionError, ErrorReason};
 use segment::common::file_operations::FileStorageError;
 use tempfile::PersistError;
 use thiserror::Error;
@@ -22,9 +23,55 @@ pub enum StorageError {
     BadRequest { description: String },
     #[error("Storage locked: {description}")]
     Locked { description: String },
+    #[error("Forbidden: {description}")]
+    Forbidden { description: String },
+    #[error("Under-replicated: {description}")]
+    UnderReplicated { description: String },
+    #[error("Rate limited: {description}")]
+    RateLimited {
+        description: String,
+        /// Suggested delay before the retry
+        retry_after: Duration,
+    },
+    /// Error with a stable reason code, reported to the clients along with the description
+    #[error("{description}")]
+    WithReason {
+        reason: ErrorReason,
+        description: String,
+    },
 }
 
 impl StorageError {
+    pub fn with_reason(reason: ErrorReason, description: impl std::fmt::Display) -> StorageError {
+        let description = if reason.is_not_found() {
+            format!("Not found: {description}")
+        } else {
+            description.to_string()
+        };
+        StorageError::WithReason {
+            reason,
+            description,
+        }
+    }
+
+    /// Stable code of the reason of the error, if the error has one
+    pub fn reason(&self) -> Option<ErrorReason> {
+        match self {
+            StorageError::WithReason { reason, .. } => Some(*reason),
+            _ => None,
+        }
+    }
+
+    /// Suggested delay before the retry of a rate limited request, in seconds rounded up
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            StorageError::RateLimited { retry_after, .. } => {
+                Some(retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0))
+            }
+            _ => None,
+        }
+    }
+
     pub fn service_error(description: impl Into<String>) -> StorageError {
         StorageError::ServiceError {
             description: description.into(),
@@ -86,6 +133,31 @@ impl StorageError {
                 description: overriding_description,
                 backtrace: None,
             },
+            CollectionError::StrictMode { .. } => StorageError::Forbidden {
+                description: overriding_description,
+            },
+            CollectionError::UnderReplicated { .. } => StorageError::UnderReplicated {
+                description: overriding_description,
+            },
+            CollectionError::Forbidden { .. } => StorageError::Forbidden {
+                description: overriding_description,
+            },
+            CollectionError::QuotaExceeded { .. } => StorageError::Forbidden {
+                description: overriding_description,
+            },
+            CollectionError::RateLimited { retry_after, .. } => StorageError::RateLimited {
+                description: overriding_description,
+                retry_after,
+            },
+            CollectionError::GroupByFieldNotIndexed { .. }
+            | CollectionError::UnsupportedGroupKeyType { .. }
+            | CollectionError::UnsupportedGroupKey { .. }
+            | CollectionError::RecommendExampleNotFound { .. }
+            | CollectionError::LookupCollectionMissing { .. }
+            | CollectionError::VectorNameMismatch { .. } => match err.reason() {
+                Some(reason) => StorageError::with_reason(reason, overriding_description),
+                None => StorageError::bad_input(&overriding_description),
+            },
         }
     }
 }
@@ -124,6 +196,32 @@ impl From<CollectionError> for StorageError {
                 description: format!("{err}"),
                 backtrace: None,
             },
+            CollectionError::StrictMode { .. } => StorageError::Forbidden {
+                description: format!("{err}"),
+            },
+            CollectionError::UnderReplicated { description } => {
+                StorageError::UnderReplicated { description }
+            }
+            CollectionError::Forbidden { description } => StorageError::Forbidden { description },
+            CollectionError::QuotaExceeded { .. } => StorageError::Forbidden {
+                description: format!("{err}"),
+            },
+            CollectionError::RateLimited {
+                description,
+                retry_after,
+            } => StorageError::RateLimited {
+                description,
+                retry_after,
+            },
+            CollectionError::GroupByFieldNotIndexed { .. }
+            | CollectionError::UnsupportedGroupKeyType { .. }
+            | CollectionError::UnsupportedGroupKey { .. }
+            | CollectionError::RecommendExampleNotFound { .. }
+            | CollectionError::LookupCollectionMissing { .. }
+            | CollectionError::VectorNameMismatch { .. } => match err.reason() {
+                Some(reason) => StorageError::with_reason(reason, &err),
+                None => StorageError::bad_input(&err.to_string()),
+            },
         }
     }
 }
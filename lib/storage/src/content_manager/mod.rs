@@ -5,6 +5,8 @@ use self::consensus_manager::CollectionsSnapshot;
 use self::errors::StorageError;
 
 pub mod alias_mapping;
+pub mod collection_clone;
+pub mod collection_config_document;
 pub mod collection_meta_ops;
 mod collections_ops;
 pub mod consensus;
@@ -29,6 +31,7 @@ pub mod consensus_ops {
         CollectionMetaOperations, SetShardReplicaState, ShardTransferOperations, UpdateCollection,
         UpdateCollectionOperation,
     };
+    use crate::types::PeerLabels;
 
     /// Operation that should pass consensus
     #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
@@ -39,6 +42,11 @@ pub mod consensus_ops {
             uri: String,
         },
         RemovePeer(PeerId),
+        /// Replace the labels of the peer, empty labels remove them
+        UpdatePeerLabels {
+            peer_id: PeerId,
+            labels: PeerLabels,
+        },
         RequestSnapshot,
         ReportSnapshot {
             peer_id: PeerId,
@@ -105,6 +113,16 @@ pub mod consensus_ops {
                 UpdateCollection {
                     optimizers_config: None,
                     params: None,
+                    strict_mode_config: None,
+                    default_search_params: None,
+                    default_with_payload: None,
+                    default_with_vector: None,
+                    default_max_payload_return_bytes: None,
+                    default_vector: None,
+                    quota_config: None,
+                    admission_config: None,
+                    read_cache_config: None,
+                    ttl_config: None,
                 },
             );
             operation
@@ -197,4 +215,8 @@ pub trait CollectionContainer {
     fn remove_peer(&self, peer_id: PeerId) -> Result<(), StorageError>;
 
     fn sync_local_state(&self) -> Result<(), StorageError>;
+
+    /// Move shards between the peers, if automatic balancing is enabled.
+    /// Only called on the leader, so that a single balancer is running in the cluster.
+    fn balance_shards(&self) -> Result<(), StorageError>;
 }
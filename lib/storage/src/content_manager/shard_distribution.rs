@@ -1,5 +1,5 @@
 use std::cmp::{self, Reverse};
-use std::collections::BinaryHeap;
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
 use std::num::NonZeroU32;
 
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
@@ -7,6 +7,9 @@ use collection::shards::shard::{PeerId, ShardId};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::content_manager::errors::StorageError;
+use crate::types::PeerLabelsById;
+
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 struct PeerShardCount {
     shard_count: usize, // self.shard_count and other.shard_count are compared first to determine eq & ord
@@ -26,6 +29,23 @@ impl PeerShardCount {
     }
 }
 
+/// Replicas of a shard, placed on the given peers
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone)]
+pub struct ShardPlacement {
+    pub shard_id: ShardId,
+    /// One replica is placed on each of the peers
+    pub peers: Vec<PeerId>,
+}
+
+/// Placement of the shard replicas, based on the labels of the peers
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PlacementPolicy {
+    /// Replicas of each shard are placed on peers with distinct values of this label.
+    /// E.g. `zone` places at most one replica of a shard per zone
+    pub spread_by_label: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Hash, Clone)]
 pub struct ShardDistributionProposal {
     /// A shard can be located on several peers if it has replicas
@@ -65,6 +85,120 @@ impl ShardDistributionProposal {
         Self { distribution }
     }
 
+    /// Builds a distribution from the explicit placement of each shard.
+    /// Fails if some shard is missing, placed on an unknown peer or on a number of peers,
+    /// which doesn't match the replication factor.
+    pub fn from_placement(
+        placement: &[ShardPlacement],
+        shard_number: Option<u32>,
+        replication_factor: NonZeroU32,
+        known_peers: &[PeerId],
+    ) -> Result<Self, StorageError> {
+        let shard_number = shard_number.unwrap_or(placement.len() as u32);
+        if placement.is_empty() || placement.len() != shard_number as usize {
+            return Err(StorageError::bad_input(&format!(
+                "Shard placement must cover all {shard_number} shards, got {}",
+                placement.len(),
+            )));
+        }
+        let n_replicas = replication_factor.get() as usize;
+        check_enough_peers(n_replicas, known_peers.len(), "peers")?;
+
+        let mut distribution: Vec<(ShardId, Vec<PeerId>)> = Vec::with_capacity(placement.len());
+        for ShardPlacement { shard_id, peers } in placement {
+            let shard_id = *shard_id;
+            if shard_id >= shard_number {
+                return Err(StorageError::bad_input(&format!(
+                    "Shard {shard_id} is out of range, shard number is {shard_number}",
+                )));
+            }
+            if distribution.iter().any(|(id, _)| *id == shard_id) {
+                return Err(StorageError::bad_input(&format!(
+                    "Shard {shard_id} is placed more than once",
+                )));
+            }
+            if let Some(peer_id) = peers.iter().find(|peer_id| !known_peers.contains(peer_id)) {
+                return Err(StorageError::bad_input(&format!(
+                    "Shard {shard_id} is placed on unknown peer {peer_id}",
+                )));
+            }
+            if peers.iter().collect::<HashSet<_>>().len() != peers.len() {
+                return Err(StorageError::bad_input(&format!(
+                    "Shard {shard_id} is placed on the same peer more than once",
+                )));
+            }
+            if peers.len() != n_replicas {
+                return Err(StorageError::bad_input(&format!(
+                    "Shard {shard_id} is placed on {} peers, but replication factor is {n_replicas}",
+                    peers.len(),
+                )));
+            }
+            distribution.push((shard_id, peers.clone()));
+        }
+        distribution.sort_by_key(|(shard_id, _)| *shard_id);
+
+        Ok(Self { distribution })
+    }
+
+    /// Builds a distribution, which places the replicas of each shard on peers with distinct
+    /// values of the label. Shards are spread evenly between the label values, and then
+    /// between the peers with the same value.
+    pub fn spread_by_label(
+        shard_number: NonZeroU32,
+        replication_factor: NonZeroU32,
+        known_peers: &[PeerId],
+        peer_labels: &PeerLabelsById,
+        label: &str,
+    ) -> Result<Self, StorageError> {
+        let mut groups: BTreeMap<&str, Vec<PeerShardCount>> = BTreeMap::new();
+        for peer_id in known_peers {
+            let value = peer_labels
+                .get(peer_id)
+                .and_then(|labels| labels.get(label))
+                .ok_or_else(|| {
+                    StorageError::bad_input(&format!(
+                        "Peer {peer_id} has no label `{label}`, required by the placement policy",
+                    ))
+                })?;
+            groups
+                .entry(value.as_str())
+                .or_default()
+                .push(PeerShardCount::new(*peer_id));
+        }
+        let n_replicas = replication_factor.get() as usize;
+        check_enough_peers(
+            n_replicas,
+            groups.len(),
+            &format!("distinct values of label `{label}`"),
+        )?;
+
+        let mut distribution = Vec::with_capacity(shard_number.get() as usize);
+        for shard_id in 0..shard_number.get() {
+            // Least loaded label values first
+            let mut values: Vec<_> = groups
+                .iter_mut()
+                .map(|(value, peers)| {
+                    let load: usize = peers.iter().map(|peer| peer.shard_count).sum();
+                    (load, *value, peers)
+                })
+                .collect();
+            values.sort_by_key(|(load, value, _)| (*load, *value));
+
+            let replicas = values
+                .into_iter()
+                .take(n_replicas)
+                .map(|(_, _, peers)| {
+                    let least_loaded_peer = peers.iter_mut().min().unwrap();
+                    least_loaded_peer.inc_shard_count();
+                    least_loaded_peer.peer_id
+                })
+                .collect();
+            distribution.push((shard_id, replicas));
+        }
+
+        Ok(Self { distribution })
+    }
+
     pub fn local_shards_for(&self, peer_id: PeerId) -> Vec<ShardId> {
         self.distribution
             .iter()
@@ -88,6 +222,19 @@ impl ShardDistributionProposal {
     }
 }
 
+fn check_enough_peers(
+    replication_factor: usize,
+    available: usize,
+    what: &str,
+) -> Result<(), StorageError> {
+    if available < replication_factor {
+        return Err(StorageError::bad_input(&format!(
+            "Replication factor {replication_factor} is greater than the number of {what}: {available}",
+        )));
+    }
+    Ok(())
+}
+
 impl From<ShardDistributionProposal> for CollectionShardDistribution {
     fn from(proposal: ShardDistributionProposal) -> Self {
         CollectionShardDistribution {
@@ -131,4 +278,117 @@ mod tests {
         assert_eq!(shard_counts.iter().min(), Some(&1));
         assert_eq!(shard_counts.iter().max(), Some(&2));
     }
+
+    #[test]
+    fn test_placement_validation() {
+        let known_peers = vec![1, 2, 3];
+        let replication_factor = NonZeroU32::new(2).unwrap();
+        let placement = |shards: &[(ShardId, &[PeerId])]| -> Vec<ShardPlacement> {
+            shards
+                .iter()
+                .map(|(shard_id, peers)| ShardPlacement {
+                    shard_id: *shard_id,
+                    peers: peers.to_vec(),
+                })
+                .collect()
+        };
+
+        let distribution = ShardDistributionProposal::from_placement(
+            &placement(&[(1, &[3, 1]), (0, &[1, 2])]),
+            None,
+            replication_factor,
+            &known_peers,
+        )
+        .unwrap();
+        assert_eq!(
+            distribution.distribution,
+            vec![(0, vec![1, 2]), (1, vec![3, 1])]
+        );
+
+        let invalid = [
+            // Unknown peer
+            placement(&[(0, &[1, 4])]),
+            // Fewer peers than replication factor
+            placement(&[(0, &[1])]),
+            // Same peer twice
+            placement(&[(0, &[2, 2])]),
+            // Missing shard 0
+            placement(&[(1, &[1, 2]), (1, &[2, 3])]),
+        ];
+        for placement in invalid {
+            let result = ShardDistributionProposal::from_placement(
+                &placement,
+                None,
+                replication_factor,
+                &known_peers,
+            );
+            assert!(result.is_err(), "{placement:?} must be rejected");
+        }
+
+        // Shard number doesn't match
+        assert!(ShardDistributionProposal::from_placement(
+            &placement(&[(0, &[1, 2])]),
+            Some(2),
+            replication_factor,
+            &known_peers,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_spread_by_label() {
+        let known_peers = vec![1, 2, 3, 4, 5];
+        let zones = [(1, "a"), (2, "a"), (3, "b"), (4, "b"), (5, "c")];
+        let peer_labels: PeerLabelsById = zones
+            .iter()
+            .map(|(peer_id, zone)| {
+                let labels = [("zone".to_string(), zone.to_string())].into();
+                (*peer_id, labels)
+            })
+            .collect();
+
+        let distribution = ShardDistributionProposal::spread_by_label(
+            NonZeroU32::new(6).unwrap(),
+            NonZeroU32::new(2).unwrap(),
+            &known_peers,
+            &peer_labels,
+            "zone",
+        )
+        .unwrap();
+
+        let zone_of = |peer_id: &PeerId| peer_labels[peer_id]["zone"].as_str();
+        let mut shards_per_zone: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_shard_id, peers) in &distribution.distribution {
+            assert_eq!(peers.len(), 2);
+            // One replica per zone
+            assert_ne!(zone_of(&peers[0]), zone_of(&peers[1]));
+            for peer_id in peers {
+                *shards_per_zone.entry(zone_of(peer_id)).or_default() += 1;
+            }
+        }
+        assert_eq!(
+            shards_per_zone.values().copied().collect::<Vec<_>>(),
+            [4, 4, 4]
+        );
+
+        // Not enough zones for the replication factor
+        assert!(ShardDistributionProposal::spread_by_label(
+            NonZeroU32::new(1).unwrap(),
+            NonZeroU32::new(4).unwrap(),
+            &known_peers,
+            &peer_labels,
+            "zone",
+        )
+        .is_err());
+
+        // Peer without the label
+        assert!(ShardDistributionProposal::spread_by_label(
+            NonZeroU32::new(1).unwrap(),
+            NonZeroU32::new(1).unwrap(),
+            &known_peers,
+            &peer_labels,
+            "rack",
+        )
+        .is_err());
+    }
 }
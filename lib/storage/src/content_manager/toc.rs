@@ -5,25 +5,31 @@ use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
+use api::grpc::circuit_breaker::CircuitBreakerTelemetry;
 use collection::collection::{Collection, RequestShardTransfer};
 use collection::collection_state;
 use collection::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
     CollectionParams,
 };
+use collection::fusion::fuse_searches;
 use collection::grouping::group_by::GroupRequest;
+use collection::grouping::scroll_groups::scroll_groups;
 use collection::grouping::GroupBy;
 use collection::operations::config_diff::DiffConfig;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::point_ops::WriteOrdering;
 use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
-    AliasDescription, CollectionResult, CountRequest, CountResult, GroupsResult, PointRequest,
-    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult, SearchRequest,
-    SearchRequestBatch, UpdateResult, VectorsConfig,
+    AliasDescription, CheckFilterRequest, CollectionResult, CountRequest, CountResult, FusedPoint,
+    FusionRequest, GroupsResult, OrderedQueryRequest, PointRequest, RecommendRequest,
+    RecommendRequestBatch, Record, ScrollGroupsRequest, ScrollGroupsResult, ScrollRequest,
+    ScrollResult, SearchRequest, SearchRequestBatch, UpdateResult, VectorsConfig,
 };
 use collection::operations::CollectionUpdateOperations;
+use collection::ordered_query::query_ordered;
 use collection::recommendations::{recommend_batch_by, recommend_by};
 use collection::shards::channel_service::ChannelService;
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
@@ -34,7 +40,9 @@ use collection::shards::transfer::shard_transfer::{
 };
 use collection::shards::{replica_set, CollectionId};
 use collection::telemetry::CollectionTelemetry;
+use itertools::Itertools;
 use segment::common::cpu::get_num_cpus;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::ScoredPoint;
 use tokio::runtime::Runtime;
 use tokio::sync::{Mutex, RwLock, RwLockReadGuard, Semaphore};
@@ -46,6 +54,9 @@ use super::collection_meta_ops::{
 };
 use super::{consensus_manager, CollectionContainer};
 use crate::content_manager::alias_mapping::AliasPersistence;
+use crate::content_manager::collection_clone::{
+    copy_collection_points, CloneCollection, CloneState, CollectionCloneStatus, CLONES_DIR,
+};
 use crate::content_manager::collection_meta_ops::{
     AliasOperations, ChangeAliasesOperation, CollectionMetaOperations, CreateAlias,
     CreateAliasOperation, CreateCollection, DeleteAlias, DeleteAliasOperation, RenameAlias,
@@ -56,7 +67,7 @@ use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::data_transfer::{populate_collection, transfer_indexes};
 use crate::content_manager::errors::StorageError;
 use crate::content_manager::shard_distribution::ShardDistributionProposal;
-use crate::types::{PeerAddressById, StorageConfig};
+use crate::types::{LastShardReplica, PeerAddressById, PeerLabelsById, StorageConfig};
 use crate::ConsensusOperations;
 
 pub const ALIASES_PATH: &str = "aliases";
@@ -90,6 +101,8 @@ pub struct TableOfContent {
     /// A lock to prevent concurrent collection creation.
     /// Effectively, this lock ensures that `create_collection` is called sequentially.
     collection_create_lock: Mutex<()>,
+    /// Time of the last check of the shard placement, made by the automatic balancing
+    last_shard_balancing: parking_lot::Mutex<Option<Instant>>,
 }
 
 impl TableOfContent {
@@ -184,7 +197,7 @@ impl TableOfContent {
             }
         };
 
-        TableOfContent {
+        let toc = TableOfContent {
             collections: Arc::new(RwLock::new(collections)),
             storage_config: Arc::new(storage_config.clone()),
             search_runtime,
@@ -198,7 +211,10 @@ impl TableOfContent {
             lock_error_message: parking_lot::Mutex::new(None),
             update_rate_limiter: rate_limiter,
             collection_create_lock: Default::default(),
-        }
+            last_shard_balancing: parking_lot::Mutex::new(None),
+        };
+        toc.resume_collection_clones();
+        toc
     }
 
     /// Return `true` if service is working in distributed mode.
@@ -343,15 +359,33 @@ impl TableOfContent {
 
         let CreateCollection {
             vectors,
+            sparse_vectors,
             shard_number,
             on_disk_payload,
+            validate_payload,
+            payload_validation_mode,
+            read_hedge_delay_ms,
             hnsw_config: hnsw_config_diff,
             wal_config: wal_config_diff,
             optimizers_config: optimizers_config_diff,
             replication_factor,
+            shard_placement: _,
+            placement_policy: _,
             write_consistency_factor,
             init_from,
             quantization_config,
+            strict_mode_config,
+            default_search_params,
+            default_with_payload,
+            default_with_vector,
+            default_max_payload_return_bytes,
+            ttl_config,
+            default_vector,
+            payload_indexes,
+            payload_cache_fields,
+            quota_config,
+            admission_config,
+            read_cache_config,
         } = operation;
 
         self.collections
@@ -376,6 +410,10 @@ impl TableOfContent {
                 .await?;
         }
 
+        vectors.check_quantization_compatible(quantization_config.as_ref())?;
+        vectors.check_sparse_vectors_compatible(sparse_vectors.as_ref())?;
+        vectors.check_default_vector(default_vector.as_deref())?;
+
         let collection_path = self.create_collection_path(collection_name).await?;
         let snapshots_path = self.create_snapshots_path(collection_name).await?;
 
@@ -399,6 +437,14 @@ impl TableOfContent {
                     description: "`shard_number` cannot be 0".to_string(),
                 })?,
             on_disk_payload: on_disk_payload.unwrap_or(self.storage_config.on_disk_payload),
+            validate_payload: validate_payload.unwrap_or_default(),
+            payload_validation_mode: payload_validation_mode.unwrap_or_default(),
+            read_hedge_delay_ms,
+            sparse_vectors,
+            payload_indexes: payload_indexes.unwrap_or_default(),
+            payload_cache_fields: payload_cache_fields.unwrap_or_default(),
+            read_only: false,
+            auto_index_group_by_fields: false,
             replication_factor: NonZeroU32::new(replication_factor).ok_or(
                 StorageError::BadInput {
                     description: "`replication_factor` cannot be 0".to_string(),
@@ -436,6 +482,16 @@ impl TableOfContent {
             optimizer_config: optimizers_config,
             hnsw_config,
             quantization_config,
+            strict_mode_config,
+            default_search_params,
+            default_with_payload,
+            default_with_vector,
+            default_max_payload_return_bytes,
+            ttl_config,
+            default_vector,
+            quota_config,
+            admission_config,
+            read_cache_config,
         };
         let collection = Collection::new(
             collection_name.to_string(),
@@ -535,6 +591,125 @@ impl TableOfContent {
         });
     }
 
+    /// Params of the new collection, cloned from the `source` one with the overrides of the request
+    pub async fn prepare_collection_clone(
+        &self,
+        source: &str,
+        request: CloneCollection,
+    ) -> Result<CreateCollectionOperation, StorageError> {
+        let collection = self.get_collection(source).await?;
+        // Updates are only blocked while the collection is being cloned
+        collection.check_updates_not_blocked()?;
+        let config = collection.state().await.config;
+        let payload_schema = collection.info(None).await?.payload_schema;
+        let target = request.target.clone();
+        let create_collection = request.create_collection(config, payload_schema)?;
+        Ok(CreateCollectionOperation::new(target, create_collection))
+    }
+
+    /// Block updates of the `source` collection and start copying its points into the `target`
+    /// one in background. Updates are unblocked once all points are copied.
+    pub async fn start_collection_clone(
+        &self,
+        source: &str,
+        target: &str,
+    ) -> Result<CollectionCloneStatus, StorageError> {
+        let collection = self.get_collection(source).await?;
+        let mut status = CollectionCloneStatus {
+            source: collection.name(),
+            target: target.to_string(),
+            state: CloneState::InProgress,
+            points_copied: 0,
+            points_total: 0,
+            pending_shards: collection
+                .state()
+                .await
+                .shards
+                .into_keys()
+                .sorted()
+                .collect(),
+            offset: None,
+            error: None,
+        };
+        collection.block_updates(Some(status.updates_block_reason()));
+
+        let status_path = CollectionCloneStatus::path(Path::new(self.storage_path()), target);
+        let started = async {
+            let count_request = CountRequest {
+                filter: None,
+                exact: true,
+            };
+            status.points_total = collection.count(count_request, None).await?.count;
+            create_dir_all(Path::new(self.storage_path()).join(CLONES_DIR))?;
+            status.save(&status_path)
+        };
+        if let Err(err) = started.await {
+            collection.block_updates(None);
+            return Err(err);
+        }
+        drop(collection);
+
+        self.general_runtime.spawn(copy_collection_points(
+            self.collections.clone(),
+            status.clone(),
+            status_path,
+        ));
+        Ok(status)
+    }
+
+    /// Progress of copying the points into the `target` collection, if it is a clone
+    pub fn collection_clone_status(
+        &self,
+        target: &str,
+    ) -> Result<CollectionCloneStatus, StorageError> {
+        let status_path = CollectionCloneStatus::path(Path::new(self.storage_path()), target);
+        if !status_path.exists() {
+            return Err(StorageError::NotFound {
+                description: format!("Collection {target} is not cloned from another collection"),
+            });
+        }
+        CollectionCloneStatus::load(&status_path)
+    }
+
+    /// Continue copying the points of the clones, which were interrupted by the restart
+    fn resume_collection_clones(&self) {
+        let clones_path = Path::new(self.storage_path()).join(CLONES_DIR);
+        let Ok(entries) = read_dir(clones_path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let status_path = entry.path();
+            let status = match CollectionCloneStatus::load(&status_path) {
+                Ok(status) => status,
+                Err(err) => {
+                    log::warn!("Can't read clone status {status_path:?}: {err}");
+                    continue;
+                }
+            };
+            if status.state != CloneState::InProgress {
+                continue;
+            }
+
+            log::info!(
+                "Resuming cloning of collection {} into {}: {} of {} points copied",
+                status.source,
+                status.target,
+                status.points_copied,
+                status.points_total,
+            );
+            let collections = self.general_runtime.block_on(self.collections.read());
+            if let Some(source) = collections.get(&status.source) {
+                source.block_updates(Some(status.updates_block_reason()));
+            }
+            drop(collections);
+            self.general_runtime.spawn(copy_collection_points(
+                self.collections.clone(),
+                status,
+                status_path,
+            ));
+        }
+    }
+
     async fn on_peer_created(
         &self,
         collection_name: String,
@@ -743,6 +918,7 @@ impl TableOfContent {
                 from: from_peer,
                 to: to_peer,
                 sync,
+                method: None,
             };
             let operation = ConsensusOperations::start_transfer(collection_name, transfer_request);
             proposal_sender.send(operation)?;
@@ -775,6 +951,16 @@ impl TableOfContent {
         let UpdateCollection {
             optimizers_config,
             params,
+            strict_mode_config,
+            default_search_params,
+            default_with_payload,
+            default_with_vector,
+            default_max_payload_return_bytes,
+            ttl_config,
+            default_vector,
+            quota_config,
+            admission_config,
+            read_cache_config,
         } = operation.update_collection;
         let collection = self.get_collection(&operation.collection_name).await?;
         if let Some(diff) = optimizers_config {
@@ -783,6 +969,38 @@ impl TableOfContent {
         if let Some(diff) = params {
             collection.update_params_from_diff(diff).await?;
         }
+        if let Some(diff) = strict_mode_config {
+            collection.update_strict_mode_config(diff).await?;
+        }
+        if default_search_params.is_some()
+            || default_with_payload.is_some()
+            || default_with_vector.is_some()
+            || default_max_payload_return_bytes.is_some()
+        {
+            collection
+                .update_search_defaults(
+                    default_search_params,
+                    default_with_payload,
+                    default_with_vector,
+                    default_max_payload_return_bytes,
+                )
+                .await?;
+        }
+        if let Some(ttl_config) = ttl_config {
+            collection.update_ttl_config(ttl_config).await?;
+        }
+        if let Some(default_vector) = default_vector {
+            collection.update_default_vector(default_vector).await?;
+        }
+        if let Some(diff) = quota_config {
+            collection.update_quota_config(diff).await?;
+        }
+        if let Some(diff) = admission_config {
+            collection.update_admission_config(diff).await?;
+        }
+        if let Some(diff) = read_cache_config {
+            collection.update_read_cache_config(diff).await?;
+        }
         if let Some(changes) = replica_changes {
             collection.handle_replica_changes(changes).await?;
         }
@@ -790,6 +1008,11 @@ impl TableOfContent {
     }
 
     async fn delete_collection(&self, collection_name: &str) -> Result<bool, StorageError> {
+        // Make in-flight reads of the collection fail fast, so they release it sooner
+        if let Some(collection) = self.collections.read().await.get(collection_name) {
+            collection.mark_deleted();
+        }
+
         if let Some(removed) = self.collections.write().await.remove(collection_name) {
             self.alias_persistence
                 .write()
@@ -809,6 +1032,12 @@ impl TableOfContent {
                 .with_extension(uuid);
             tokio::fs::rename(path, &deleted_path).await?;
 
+            let clone_status_path =
+                CollectionCloneStatus::path(Path::new(self.storage_path()), collection_name);
+            if clone_status_path.exists() {
+                tokio::fs::remove_file(clone_status_path).await?;
+            }
+
             // At this point collection is removed from memory and moved to ".deleted" folder.
             // Next time we load service the collection will not appear in the list of collections.
             // We can take our time to delete the collection from disk.
@@ -1108,6 +1337,7 @@ impl TableOfContent {
         read_consistency: Option<ReadConsistency>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(None).await?;
         recommend_by(
             request,
             &collection,
@@ -1135,6 +1365,7 @@ impl TableOfContent {
         read_consistency: Option<ReadConsistency>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(None).await?;
         recommend_batch_by(
             request,
             &collection,
@@ -1164,6 +1395,7 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(shard_selection).await?;
         collection
             .search(request, read_consistency, shard_selection)
             .await
@@ -1189,12 +1421,34 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(shard_selection).await?;
         collection
             .search_batch(request, read_consistency, shard_selection)
             .await
             .map_err(|err| err.into())
     }
 
+    /// Approximate number of points, which are skipped by `indexed_only` searches for the given
+    /// vectors, because they are not indexed yet
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `vector_names` - names of the vectors used by `indexed_only` searches
+    /// * `shard_selection` - which local shard to use
+    pub async fn indexed_only_excluded_points(
+        &self,
+        collection_name: &str,
+        vector_names: &[String],
+        shard_selection: Option<ShardId>,
+    ) -> Result<usize, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .indexed_only_excluded_points(vector_names, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Count points in the collection.
     ///
     /// # Arguments
@@ -1220,6 +1474,31 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Check the filter against the point and record the outcome of every condition
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we check
+    /// * `request` - [`CheckFilterRequest`]
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Outcome of every condition of the filter.
+    ///
+    pub async fn check_filter(
+        &self,
+        collection_name: &str,
+        request: CheckFilterRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<FilterTrace, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .check_filter(request.id, request.filter, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Return specific points by IDs
     ///
     /// # Arguments
@@ -1245,6 +1524,28 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Fuse the results of several searches into a single ranking
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`FusionRequest`]
+    ///
+    /// # Result
+    ///
+    /// Points with the highest fused scores
+    pub async fn fusion(
+        &self,
+        collection_name: &str,
+        request: FusionRequest,
+        read_consistency: Option<ReadConsistency>,
+    ) -> Result<Vec<FusedPoint>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        fuse_searches(request, &collection, read_consistency)
+            .await
+            .map_err(|err| err.into())
+    }
+
     pub async fn group(
         &self,
         collection_name: &str,
@@ -1253,6 +1554,8 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<GroupsResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        // Internal searches of the grouping are executed under the same permit
+        let _permit = collection.admit_read(shard_selection).await?;
 
         let collection_by_name = |name| self.get_collection_opt(name);
 
@@ -1267,9 +1570,60 @@ impl TableOfContent {
         }
 
         group_by
-            .execute()
+            .execute_with_details()
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Paginate over the groups of points, which match the filter
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we group
+    /// * `request` - [`ScrollGroupsRequest`]
+    /// * `read_consistency` - consistency of the internal scrolls
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Groups ordered by their key, and the key of the next page if any
+    pub async fn scroll_groups(
+        &self,
+        collection_name: &str,
+        request: ScrollGroupsRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> Result<ScrollGroupsResult, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(shard_selection).await?;
+        scroll_groups(request, &collection, read_consistency, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Query the points without a vector, in the order of a numeric payload field
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we query
+    /// * `request` - [`OrderedQueryRequest`]
+    /// * `read_consistency` - consistency of the reads of the shards
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// First points in the order of the `order_by` field, with the values of the field
+    pub async fn query_ordered(
+        &self,
+        collection_name: &str,
+        request: OrderedQueryRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> Result<Vec<Record>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(shard_selection).await?;
+        query_ordered(request, &collection, read_consistency, shard_selection)
             .await
-            .map(|groups| GroupsResult { groups })
             .map_err(|err| err.into())
     }
 
@@ -1335,6 +1689,7 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<ScrollResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        let _permit = collection.admit_read(shard_selection).await?;
         collection
             .scroll_by(request, read_consistency, shard_selection)
             .await
@@ -1380,6 +1735,10 @@ impl TableOfContent {
         self.channel_service.id_to_address.read().clone()
     }
 
+    pub fn peer_circuit_breakers(&self) -> HashMap<PeerId, CircuitBreakerTelemetry> {
+        self.channel_service.circuit_breakers_telemetry()
+    }
+
     pub fn collections_snapshot_sync(&self) -> consensus_manager::CollectionsSnapshot {
         self.general_runtime.block_on(self.collections_snapshot())
     }
@@ -1521,11 +1880,16 @@ impl TableOfContent {
             .await?)
     }
 
+    /// Suggest the distribution of the shards of a new collection.
+    ///
+    /// Explicit placement and placement policy of the operation are validated against the
+    /// known peers, so misconfigured placement fails before the operation is proposed.
     pub async fn suggest_shard_distribution(
         &self,
         op: &CreateCollectionOperation,
         suggested_shard_number: NonZeroU32,
-    ) -> ShardDistributionProposal {
+        peer_labels: &PeerLabelsById,
+    ) -> Result<ShardDistributionProposal, StorageError> {
         let shard_number = op
             .create_collection
             .shard_number
@@ -1546,25 +1910,49 @@ impl TableOfContent {
             .and_then(NonZeroU32::new)
             .unwrap_or_else(default_replication_factor);
 
-        let shard_distribution =
-            ShardDistributionProposal::new(shard_number, replication_factor, &known_peers);
+        let shard_distribution = match (
+            &op.create_collection.shard_placement,
+            &op.create_collection.placement_policy,
+        ) {
+            (Some(_), Some(_)) => {
+                return Err(StorageError::bad_input(
+                    "`shard_placement` and `placement_policy` can't be used together",
+                ))
+            }
+            (Some(placement), None) => ShardDistributionProposal::from_placement(
+                placement,
+                op.create_collection.shard_number,
+                replication_factor,
+                &known_peers,
+            )?,
+            (None, Some(policy)) => ShardDistributionProposal::spread_by_label(
+                shard_number,
+                replication_factor,
+                &known_peers,
+                peer_labels,
+                &policy.spread_by_label,
+            )?,
+            (None, None) => {
+                ShardDistributionProposal::new(shard_number, replication_factor, &known_peers)
+            }
+        };
 
         log::debug!(
             "Suggesting distribution for {} shards for collection '{}' among {} peers {:?}",
-            shard_number,
+            shard_distribution.distribution.len(),
             op.collection_name,
             known_peers.len(),
             shard_distribution.distribution
         );
-        shard_distribution
+        Ok(shard_distribution)
     }
 
-    pub async fn get_telemetry_data(&self) -> Vec<CollectionTelemetry> {
+    pub async fn get_telemetry_data(&self, with_graph_health: bool) -> Vec<CollectionTelemetry> {
         let mut result = Vec::new();
         let all_collections = self.all_collections().await;
         for collection_name in &all_collections {
             if let Ok(collection) = self.get_collection(collection_name).await {
-                result.push(collection.get_telemetry_data().await);
+                result.push(collection.get_telemetry_data(with_graph_health).await);
             }
         }
         result
@@ -1585,6 +1973,66 @@ impl TableOfContent {
         false
     }
 
+    /// Shards, which only active replica is located on the peer.
+    ///
+    /// Placement is read from the collection states, applied by the consensus,
+    /// so replicas, which are only disabled locally, are still counted as active.
+    /// For each shard, a peer to replicate it to is suggested: the one with the fewest replicas.
+    pub async fn last_replicas_on_peer(&self, peer_id: PeerId) -> Vec<LastShardReplica> {
+        let mut states = Vec::new();
+        for (collection_name, collection) in self.collections.read().await.iter() {
+            states.push((collection_name.clone(), collection.state().await));
+        }
+
+        // Number of replicas on each peer, to suggest the least loaded ones
+        let mut peer_loads: HashMap<PeerId, usize> = self
+            .peer_address_by_id()
+            .into_keys()
+            .filter(|peer| *peer != peer_id)
+            .map(|peer| (peer, 0))
+            .collect();
+        for (_, state) in &states {
+            for shard_info in state.shards.values() {
+                for peer in shard_info.replicas.keys() {
+                    if let Some(load) = peer_loads.get_mut(peer) {
+                        *load += 1;
+                    }
+                }
+            }
+        }
+
+        let mut last_replicas = Vec::new();
+        for (collection_name, state) in states {
+            for (shard_id, shard_info) in state.shards {
+                let mut active_peers = shard_info
+                    .replicas
+                    .iter()
+                    .filter(|(_, state)| **state == ReplicaState::Active)
+                    .map(|(peer, _)| *peer);
+                if active_peers.next() != Some(peer_id) || active_peers.next().is_some() {
+                    continue;
+                }
+                let replicate_to = peer_loads
+                    .iter()
+                    .filter(|(peer, _)| !shard_info.replicas.contains_key(peer))
+                    .min_by_key(|(peer, load)| (**load, **peer))
+                    .map(|(peer, _)| *peer);
+                if let Some(load) = replicate_to.and_then(|peer| peer_loads.get_mut(&peer)) {
+                    *load += 1;
+                }
+                last_replicas.push(LastShardReplica {
+                    collection_name: collection_name.clone(),
+                    shard_id,
+                    replicate_to,
+                });
+            }
+        }
+        last_replicas.sort_by(|a, b| {
+            (&a.collection_name, a.shard_id).cmp(&(&b.collection_name, b.shard_id))
+        });
+        last_replicas
+    }
+
     pub fn set_locks(&self, is_write_locked: bool, error_message: Option<String>) {
         self.is_write_locked
             .store(is_write_locked, Ordering::Relaxed);
@@ -1685,6 +2133,17 @@ impl CollectionContainer for TableOfContent {
             let transfer_success_callback =
                 Self::on_transfer_success_callback(self.consensus_proposal_sender.clone());
 
+            // Recoveries of the dead replicas are limited for the whole peer
+            let mut running_recoveries = 0;
+            for collection in collections.values() {
+                running_recoveries += collection.running_recoveries().await;
+            }
+            let mut recovery_slots = self
+                .storage_config
+                .replica_recovery
+                .max_concurrent_recoveries
+                .saturating_sub(running_recoveries);
+
             for collection in collections.values() {
                 let finish_shard_initialize = Self::change_peer_state_callback(
                     self.consensus_proposal_sender.clone(),
@@ -1712,10 +2171,60 @@ impl CollectionContainer for TableOfContent {
                         finish_shard_initialize,
                         convert_to_listener_callback,
                         convert_from_listener_to_active_callback,
+                        &mut recovery_slots,
                     )
                     .await?;
             }
             Ok(())
         })
     }
+
+    fn balance_shards(&self) -> Result<(), StorageError> {
+        let config = &self.storage_config.shard_balancing;
+        if !config.auto_shard_rebalancing {
+            return Ok(());
+        }
+        {
+            let mut last_shard_balancing = self.last_shard_balancing.lock();
+            if last_shard_balancing.map_or(false, |last| last.elapsed() < config.check_interval()) {
+                return Ok(());
+            }
+            *last_shard_balancing = Some(Instant::now());
+        }
+
+        // Planning counts points of the remote shards, so it must not block the consensus thread
+        let collections = self.collections.clone();
+        self.general_runtime.spawn(async move {
+            let collections = collections.read().await;
+            for collection in collections.values() {
+                let plan = match collection.rebalancing_plan().await {
+                    Ok(plan) => plan,
+                    Err(err) => {
+                        log::warn!(
+                            "Can't plan shard balancing of collection {}: {err}",
+                            collection.name()
+                        );
+                        continue;
+                    }
+                };
+                for shard_move in plan.moves {
+                    log::info!(
+                        "Balancing shards of collection {}: moving shard {} from peer {} to peer {}",
+                        collection.name(),
+                        shard_move.shard_id,
+                        shard_move.from_peer_id,
+                        shard_move.to_peer_id,
+                    );
+                    collection.request_shard_transfer(ShardTransfer {
+                        shard_id: shard_move.shard_id,
+                        from: shard_move.from_peer_id,
+                        to: shard_move.to_peer_id,
+                        sync: false,
+                        method: None,
+                    });
+                }
+            }
+        });
+        Ok(())
+    }
 }
@@ -3,6 +3,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::types::PeerLabelsById;
 use crate::{
     ClusterStatus, CollectionMetaOperations, ConsensusOperations, ConsensusStateRef, StorageError,
     TableOfContent,
@@ -60,8 +61,9 @@ impl Dispatcher {
                                 &op,
                                 NonZeroU32::new(number_of_peers as u32)
                                     .expect("Peer count should be always >= 1"),
+                                &state.peer_labels_by_id(),
                             )
-                            .await;
+                            .await?;
 
                         // Expect all replicas to become active eventually
                         for (shard_id, peer_ids) in &shard_distribution.distribution {
@@ -111,9 +113,28 @@ impl Dispatcher {
 
             Ok(res)
         } else {
-            if let CollectionMetaOperations::CreateCollection(_) = &operation {
-                self.toc.check_write_lock()?;
-            }
+            let operation = match operation {
+                CollectionMetaOperations::CreateCollection(mut op) => {
+                    self.toc.check_write_lock()?;
+                    let create_collection = &op.create_collection;
+                    if create_collection.shard_placement.is_some()
+                        || create_collection.placement_policy.is_some()
+                    {
+                        // Validate the placement against this single peer
+                        let shard_distribution = self
+                            .toc
+                            .suggest_shard_distribution(
+                                &op,
+                                NonZeroU32::new(1).unwrap(),
+                                &PeerLabelsById::new(),
+                            )
+                            .await?;
+                        op.set_distribution(shard_distribution);
+                    }
+                    CollectionMetaOperations::CreateCollection(op)
+                }
+                op => op,
+            };
             self.toc.perform_collection_meta_op(operation).await
         }
     }
@@ -1,20 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 use chrono::{DateTime, Utc};
+use collection::collection_manager::optimizers::vacuum_optimizer::FieldIndexBuildConfig;
 use collection::config::WalConfig;
-use collection::operations::shared_storage_config::SharedStorageConfig;
+use collection::operations::shared_storage_config::{
+    SharedStorageConfig, DEFAULT_APPLIED_OPERATIONS_WINDOW,
+};
 use collection::operations::types::NodeType;
 use collection::optimizers_builder::OptimizersConfig;
-use collection::shards::shard::PeerId;
+use collection::shards::balancer::ShardBalancingConfig;
+use collection::shards::replica_recovery::ReplicaRecoveryConfig;
+use collection::shards::shard::{PeerId, ShardId};
+use collection::slow_log::SlowLogConfig;
+use collection::warmup::WarmupConfig;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
+use segment::common::on_disk_io::OnDiskIoConfig;
 use segment::madvise;
+use segment::spaces::simd::SimdImplementation;
 use segment::types::{HnswConfig, QuantizationConfig};
+use segment::vector_storage::raw_scorer::DEFAULT_ASYNC_SCORER_BATCH_SIZE;
 use serde::{Deserialize, Serialize};
 use tonic::transport::Uri;
 use validator::Validate;
 
 pub type PeerAddressById = HashMap<PeerId, Uri>;
+pub type PeerLabelsById = HashMap<PeerId, PeerLabels>;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PerformanceConfig {
@@ -23,6 +35,9 @@ pub struct PerformanceConfig {
     pub max_optimization_threads: usize,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub update_rate_limit: Option<usize>,
+    /// Implementation of the distance computations. Auto-detected by default
+    #[serde(default)]
+    pub simd: SimdImplementation,
 }
 
 const fn default_max_optimization_threads() -> usize {
@@ -61,11 +76,41 @@ pub struct StorageConfig {
     pub handle_collection_load_errors: bool,
     #[serde(default)]
     pub async_scorer: bool,
+    /// Number of vectors, which the async scorer reads ahead, while the previous ones are rescored
+    #[serde(default = "default_async_scorer_batch_size")]
+    #[validate(range(min = 1))]
+    pub async_scorer_batch_size: usize,
+    /// Reading of the on-disk vector storage: page cache population and direct IO
+    #[serde(default)]
+    pub on_disk_io: OnDiskIoConfig,
     /// If provided - qdrant will start in recovery mode, which means that it will not accept any new data.
     /// Only collection metadata will be available, and it will only process collection delete requests.
     /// Provided value will be used error message for unavailable requests.
     #[serde(default)]
     pub recovery_mode: Option<String>,
+    /// If true - shards will be available for reads from already loaded segments
+    /// while WAL is replayed in background. Updates are rejected until the replay is finished.
+    #[serde(default)]
+    pub background_wal_recovery: bool,
+    /// Log of the read operations, which take longer than the configured threshold
+    #[serde(default)]
+    pub slow_log: SlowLogConfig,
+    /// Reading of the data of the collections into memory
+    #[serde(default)]
+    pub warmup: WarmupConfig,
+    /// Automatic balancing of the shards between the peers
+    #[serde(default)]
+    pub shard_balancing: ShardBalancingConfig,
+    /// Number of the latest client-supplied operation ids, remembered by each shard.
+    /// Retries of these operations are not applied again.
+    #[serde(default = "default_applied_operations_window")]
+    pub applied_operations_window: usize,
+    /// Automatic recovery of the dead replicas, located on this peer
+    #[serde(default)]
+    pub replica_recovery: ReplicaRecoveryConfig,
+    /// Background builds of the payload field indexes
+    #[serde(default)]
+    pub field_index_build: FieldIndexBuildConfig,
 }
 
 impl StorageConfig {
@@ -75,10 +120,24 @@ impl StorageConfig {
             self.node_type,
             self.handle_collection_load_errors,
             self.recovery_mode.clone(),
+            self.background_wal_recovery,
+            self.slow_log.clone(),
+            self.warmup.clone(),
+            self.applied_operations_window,
+            self.replica_recovery.clone(),
+            self.field_index_build.clone(),
         )
     }
 }
 
+const fn default_async_scorer_batch_size() -> usize {
+    DEFAULT_ASYNC_SCORER_BATCH_SIZE
+}
+
+const fn default_applied_operations_window() -> usize {
+    DEFAULT_APPLIED_OPERATIONS_WINDOW
+}
+
 fn default_snapshots_path() -> String {
     "./snapshots".to_string()
 }
@@ -91,10 +150,46 @@ const fn default_mmap_advice() -> madvise::Advice {
     madvise::Advice::Random
 }
 
+/// Labels of a peer, e.g. `zone` or `rack`
+pub type PeerLabels = BTreeMap<String, String>;
+
+/// Labels to assign to a peer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct SetPeerLabels {
+    /// Labels, which replace the current labels of the peer. Empty labels remove them
+    pub labels: PeerLabels,
+}
+
+/// Shard, which only active replica is located on a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastShardReplica {
+    pub collection_name: String,
+    pub shard_id: ShardId,
+    /// Peer to replicate the shard to, before the removal of the peer
+    pub replicate_to: Option<PeerId>,
+}
+
+impl fmt::Display for LastShardReplica {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "shard {} of collection `{}`",
+            self.shard_id, self.collection_name
+        )?;
+        match self.replicate_to {
+            Some(peer_id) => write!(f, " (replicate it to peer {peer_id})"),
+            None => write!(f, " (no other peer to replicate it to)"),
+        }
+    }
+}
+
 /// Information of a peer in the cluster
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct PeerInfo {
     pub uri: String,
+    /// Labels of the peer, used by the shard placement policies
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: PeerLabels,
     // ToDo: How long ago was the last communication? In milliseconds
     // pub last_responded_millis: usize
 }
@@ -189,6 +284,7 @@ impl Anonymize for PeerInfo {
     fn anonymize(&self) -> Self {
         PeerInfo {
             uri: self.uri.anonymize(),
+            labels: PeerLabels::new(),
         }
     }
 }
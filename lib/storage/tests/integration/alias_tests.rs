@@ -35,16 +35,20 @@ fn test_alias_operation() {
             vacuum_min_vector_number: 100,
             default_segment_number: 2,
             max_segment_size: None,
+            max_segment_number: None,
+            target_segment_size: None,
             memmap_threshold: Some(100),
             indexing_threshold: Some(100),
             flush_interval_sec: 2,
             max_optimization_threads: 2,
+            max_indexing_threads: None,
         },
         wal: Default::default(),
         performance: PerformanceConfig {
             max_search_threads: 1,
             max_optimization_threads: 1,
             update_rate_limit: None,
+            simd: Default::default(),
         },
         hnsw_index: Default::default(),
         quantization: None,
@@ -53,7 +57,15 @@ fn test_alias_operation() {
         update_queue_size: Default::default(),
         handle_collection_load_errors: false,
         recovery_mode: None,
+        background_wal_recovery: false,
         async_scorer: false,
+        async_scorer_batch_size: 256,
+        on_disk_io: Default::default(),
+        slow_log: Default::default(),
+        warmup: Default::default(),
+        shard_balancing: Default::default(),
+        applied_operations_window: 10_000,
+        replica_recovery: Default::default(),
     };
 
     let search_runtime = Runtime::new().unwrap();
@@ -89,17 +101,36 @@ fn test_alias_operation() {
                             hnsw_config: None,
                             quantization_config: None,
                             on_disk: None,
+                            datatype: None,
                         }
                         .into(),
                         hnsw_config: None,
                         wal_config: None,
                         optimizers_config: None,
+                        sparse_vectors: None,
                         shard_number: Some(1),
                         on_disk_payload: None,
+                        validate_payload: None,
+                        payload_validation_mode: None,
+                        read_hedge_delay_ms: None,
                         replication_factor: None,
+                        shard_placement: None,
+                        placement_policy: None,
                         write_consistency_factor: None,
                         init_from: None,
                         quantization_config: None,
+                        strict_mode_config: None,
+                        default_search_params: None,
+                        default_with_payload: None,
+                        default_with_vector: None,
+                        default_max_payload_return_bytes: None,
+                        default_vector: None,
+                        quota_config: None,
+                        admission_config: None,
+                        read_cache_config: None,
+                        ttl_config: None,
+                        payload_indexes: None,
+                        payload_cache_fields: None,
                     },
                 )),
                 None,
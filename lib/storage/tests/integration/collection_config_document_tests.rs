@@ -0,0 +1,271 @@
+use std::num::NonZeroU64;
+use std::path::Path;
+use std::sync::Arc;
+
+use collection::operations::config_diff::HnswConfigDiff;
+use collection::operations::point_ops::WriteOrdering;
+use collection::operations::types::VectorParams;
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use collection::optimizers_builder::OptimizersConfig;
+use segment::madvise;
+use segment::types::{Distance, PayloadFieldSchema, PayloadSchemaType};
+use storage::content_manager::collection_config_document::{
+    apply_collection_config, export_collection_config,
+};
+use storage::content_manager::collection_meta_ops::{
+    ChangeAliasesOperation, CollectionMetaOperations, CreateAlias, CreateCollection,
+    CreateCollectionOperation,
+};
+use storage::content_manager::consensus::operation_sender::OperationSender;
+use storage::content_manager::toc::TableOfContent;
+use storage::dispatcher::Dispatcher;
+use storage::types::{PerformanceConfig, StorageConfig};
+use tempfile::Builder;
+use tokio::runtime::{Handle, Runtime};
+
+fn make_dispatcher(storage_path: &Path) -> (Dispatcher, Handle) {
+    let config = StorageConfig {
+        storage_path: storage_path.to_str().unwrap().to_string(),
+        snapshots_path: storage_path.join("snapshots").to_str().unwrap().to_string(),
+        temp_path: None,
+        on_disk_payload: false,
+        optimizers: OptimizersConfig {
+            deleted_threshold: 0.5,
+            vacuum_min_vector_number: 100,
+            default_segment_number: 2,
+            max_segment_size: None,
+            max_segment_number: None,
+            target_segment_size: None,
+            memmap_threshold: Some(100),
+            indexing_threshold: Some(100),
+            flush_interval_sec: 2,
+            max_optimization_threads: 2,
+            max_indexing_threads: None,
+        },
+        wal: Default::default(),
+        performance: PerformanceConfig {
+            max_search_threads: 1,
+            max_optimization_threads: 1,
+            update_rate_limit: None,
+            simd: Default::default(),
+        },
+        hnsw_index: Default::default(),
+        quantization: None,
+        mmap_advice: madvise::Advice::Random,
+        node_type: Default::default(),
+        update_queue_size: Default::default(),
+        handle_collection_load_errors: false,
+        recovery_mode: None,
+        background_wal_recovery: false,
+        async_scorer: false,
+        async_scorer_batch_size: 256,
+        on_disk_io: Default::default(),
+        slow_log: Default::default(),
+        warmup: Default::default(),
+        shard_balancing: Default::default(),
+        applied_operations_window: 10_000,
+        replica_recovery: Default::default(),
+    };
+
+    let search_runtime = Runtime::new().unwrap();
+    let handle = search_runtime.handle().clone();
+    let update_runtime = Runtime::new().unwrap();
+    let general_runtime = Runtime::new().unwrap();
+
+    let (propose_sender, _propose_receiver) = std::sync::mpsc::channel();
+    let propose_operation_sender = OperationSender::new(propose_sender);
+
+    let toc = Arc::new(TableOfContent::new(
+        &config,
+        search_runtime,
+        update_runtime,
+        general_runtime,
+        Default::default(),
+        0,
+        Some(propose_operation_sender),
+    ));
+    (Dispatcher::new(toc), handle)
+}
+
+fn create_collection() -> CreateCollection {
+    CreateCollection {
+        vectors: VectorParams {
+            size: NonZeroU64::new(10).unwrap(),
+            distance: Distance::Cosine,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+        }
+        .into(),
+        hnsw_config: Some(HnswConfigDiff {
+            m: Some(20),
+            ef_construct: None,
+            full_scan_threshold: None,
+            max_indexing_threads: None,
+            on_disk: None,
+            payload_m: None,
+        }),
+        wal_config: None,
+        optimizers_config: None,
+        sparse_vectors: None,
+        shard_number: Some(2),
+        on_disk_payload: Some(true),
+        validate_payload: Some(true),
+        payload_validation_mode: None,
+        read_hedge_delay_ms: None,
+        replication_factor: None,
+        shard_placement: None,
+        placement_policy: None,
+        write_consistency_factor: None,
+        init_from: None,
+        quantization_config: None,
+        strict_mode_config: None,
+        default_search_params: None,
+        default_with_payload: None,
+        default_with_vector: None,
+        default_max_payload_return_bytes: None,
+        default_vector: None,
+        quota_config: None,
+        admission_config: None,
+        read_cache_config: None,
+        ttl_config: None,
+        payload_indexes: None,
+        payload_cache_fields: None,
+    }
+}
+
+async fn setup_source(dispatcher: &Dispatcher) {
+    dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                "source".to_string(),
+                create_collection(),
+            )),
+            None,
+        )
+        .await
+        .unwrap();
+
+    for (field_name, field_type) in [
+        ("city", PayloadSchemaType::Keyword),
+        ("price", PayloadSchemaType::Float),
+    ] {
+        dispatcher
+            .update(
+                "source",
+                CollectionUpdateOperations::FieldIndexOperation(FieldIndexOperations::CreateIndex(
+                    CreateIndex {
+                        field_name: field_name.to_string(),
+                        field_schema: Some(PayloadFieldSchema::FieldType(field_type)),
+                    },
+                )),
+                None,
+                true,
+                WriteOrdering::default(),
+            )
+            .await
+            .unwrap();
+    }
+
+    dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation {
+                actions: vec![CreateAlias {
+                    collection_name: "source".to_string(),
+                    alias_name: "production".to_string(),
+                }
+                .into()],
+            }),
+            None,
+        )
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_collection_config_round_trip() {
+    let source_dir = Builder::new().prefix("storage").tempdir().unwrap();
+    let (source, source_handle) = make_dispatcher(source_dir.path());
+    source_handle.block_on(setup_source(&source));
+
+    // Collection is exported by its alias as well
+    let document = source_handle
+        .block_on(export_collection_config(source.toc(), "production"))
+        .unwrap();
+    assert_eq!(document.collection_name, "source");
+    assert_eq!(document.aliases, vec!["production".to_string()]);
+    assert_eq!(document.payload_indexes.len(), 2);
+
+    let target_dir = Builder::new().prefix("storage").tempdir().unwrap();
+    let (target, target_handle) = make_dispatcher(target_dir.path());
+
+    let result = target_handle
+        .block_on(apply_collection_config(&target, document.clone(), None))
+        .unwrap();
+    assert!(result.created);
+    assert_eq!(result.created_payload_indexes, vec!["city", "price"]);
+    assert_eq!(result.created_aliases, vec!["production"]);
+    assert!(result.immutable_differences.is_empty());
+
+    let exported = target_handle
+        .block_on(export_collection_config(target.toc(), "source"))
+        .unwrap();
+    assert_eq!(exported, document);
+
+    // Applying the same document again changes nothing
+    let result = target_handle
+        .block_on(apply_collection_config(&target, document, None))
+        .unwrap();
+    assert!(!result.created);
+    assert!(result.updated_params.is_empty());
+    assert!(result.created_payload_indexes.is_empty());
+    assert!(result.created_aliases.is_empty());
+    assert!(result.immutable_differences.is_empty());
+}
+
+#[test]
+fn test_apply_collection_config_to_existing_collection() {
+    let storage_dir = Builder::new().prefix("storage").tempdir().unwrap();
+    let (dispatcher, handle) = make_dispatcher(storage_dir.path());
+    handle.block_on(setup_source(&dispatcher));
+
+    let mut document = handle
+        .block_on(export_collection_config(dispatcher.toc(), "source"))
+        .unwrap();
+    document.config.read_hedge_delay_ms = Some(100);
+    document.config.hnsw_config.as_mut().unwrap().m = Some(32);
+    document.payload_indexes.insert(
+        "tag".to_string(),
+        PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword),
+    );
+    document.payload_indexes.insert(
+        "price".to_string(),
+        PayloadFieldSchema::FieldType(PayloadSchemaType::Integer),
+    );
+
+    let result = handle
+        .block_on(apply_collection_config(&dispatcher, document, None))
+        .unwrap();
+    assert!(!result.created);
+    assert_eq!(result.updated_params, vec!["read_hedge_delay_ms"]);
+    assert_eq!(result.created_payload_indexes, vec!["tag"]);
+    let differences: Vec<_> = result
+        .immutable_differences
+        .iter()
+        .map(|difference| difference.param.as_str())
+        .collect();
+    assert_eq!(differences, vec!["hnsw_config", "payload_indexes.price"]);
+
+    // Immutable params are left as is
+    let exported = handle
+        .block_on(export_collection_config(dispatcher.toc(), "source"))
+        .unwrap();
+    assert_eq!(exported.config.read_hedge_delay_ms, Some(100));
+    assert_eq!(exported.config.hnsw_config.unwrap().m, Some(20));
+    assert_eq!(
+        exported.payload_indexes["price"],
+        PayloadFieldSchema::FieldType(PayloadSchemaType::Float)
+    );
+    assert!(exported.payload_indexes.contains_key("tag"));
+}
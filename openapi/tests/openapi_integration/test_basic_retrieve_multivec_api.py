@@ -118,6 +118,31 @@ def retrieve_invalid_vector():
     assert error == "Wrong input: Not existing vector name error: i_do_no_exist"
 
 
+def test_exclude_vectors():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"limit": 3, "with_vector": {"exclude": ["image", "i_do_no_exist"]}}
+    )
+    assert response.ok
+    assert len(response.json()['result']['points']) == 3
+    for point in response.json()['result']['points']:
+        assert len(point['vector']['text']) == 8
+        assert 'image' not in point['vector']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [1, 2], "with_vector": {"exclude": ["text"]}}
+    )
+    assert response.ok
+    for point in response.json()['result']:
+        assert len(point['vector']['image']) == 4
+        assert 'text' not in point['vector']
+
+
 def test_exclude_payload():
     exclude_payload()
 
@@ -0,0 +1,80 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_check_filter'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def check_filter(point_id, filter):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/check_filter',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"id": point_id, "filter": filter},
+    )
+
+
+def test_check_filter_breakdown():
+    response = check_filter(2, {
+        "must": [
+            {"key": "city", "match": {"value": "London"}},
+            {"key": "city", "match": {"value": "Moscow"}},
+        ],
+        "must_not": [
+            {"has_id": [1]},
+        ],
+    })
+    assert response.ok, response.json()
+    trace = response.json()['result']
+    assert trace['matched'] is False
+    assert [condition['matched'] for condition in trace['must']] == [True, False]
+    assert [condition['checked_by'] for condition in trace['must']] == ["payload", "payload"]
+    assert trace['must_not'][0]['matched'] is False
+    assert 'should' not in trace
+
+
+def test_check_filter_nested_filter():
+    response = check_filter(4, {
+        "should": [
+            {"must": [{"key": "city", "match": {"value": "Moscow"}}]},
+            {"key": "count", "range": {"gte": 0}},
+        ],
+    })
+    assert response.ok, response.json()
+    trace = response.json()['result']
+    assert trace['matched'] is True
+    nested, count = trace['should']
+    assert 'condition' not in nested
+    assert nested['matched'] is True
+    assert nested['filters'][0]['must'][0]['matched'] is True
+    assert count['matched'] is False
+
+
+def test_check_filter_uses_index():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"},
+    )
+    assert response.ok
+
+    response = check_filter(1, {"must": [{"key": "city", "match": {"value": "Berlin"}}]})
+    assert response.ok, response.json()
+    condition = response.json()['result']['must'][0]
+    assert condition['matched'] is True
+    assert condition['checked_by'] == "index"
+
+
+def test_check_filter_missing_point():
+    response = check_filter(1000, {"must": [{"key": "city", "match": {"value": "Berlin"}}]})
+    assert response.status_code == 404
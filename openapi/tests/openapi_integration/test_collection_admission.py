@@ -0,0 +1,119 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_admission'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def get_collection_info():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result']
+
+
+def test_admission_config_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"admission_config": {"max_concurrent_reads": 2, "max_queued_reads": 10}},
+    )
+    assert response.ok
+
+    config = get_collection_info()['config']['admission_config']
+    assert config == {"max_concurrent_reads": 2, "max_queued_reads": 10}
+
+    # Unspecified limits are kept
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"admission_config": {"max_queued_reads": 5}},
+    )
+    assert response.ok
+
+    config = get_collection_info()['config']['admission_config']
+    assert config == {"max_concurrent_reads": 2, "max_queued_reads": 5}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3},
+    )
+    assert response.ok
+    assert len(response.json()['result']) == 3
+
+
+def test_invalid_admission_config():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"admission_config": {"max_concurrent_reads": 0}},
+    )
+    assert response.status_code == 422
+
+
+def set_payload(point_id):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"payload": {"checked": True}, "points": [point_id]},
+    )
+
+
+def test_write_rate_limit_reject():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"admission_config": {"max_write_operations_per_second": 1, "write_burst_sec": 1}},
+    )
+    assert response.ok
+
+    response = set_payload(1)
+    assert response.ok, response.text
+
+    response = set_payload(2)
+    assert response.status_code == 429, response.text
+    assert int(response.headers['Retry-After']) >= 1
+
+    # Unspecified limits are kept
+    config = get_collection_info()['config']['admission_config']
+    assert config == {"max_write_operations_per_second": 1, "write_burst_sec": 1}
+
+
+def test_write_rate_limit_delay():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "admission_config": {
+                "max_write_operations_per_second": 5,
+                "write_rate_limit_mode": "delay",
+                "max_write_delay_ms": 2000,
+            }
+        },
+    )
+    assert response.ok
+
+    # Operations over the burst wait for the budget instead of failing
+    for point_id in range(1, 9):
+        response = set_payload(point_id)
+        assert response.ok, response.text
@@ -0,0 +1,115 @@
+import time
+
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_clone_source'
+clone_name = 'test_collection_clone_target'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    drop_collection(collection_name=clone_name)
+    yield
+    drop_collection(collection_name=clone_name)
+    drop_collection(collection_name=collection_name)
+
+
+def wait_clone_finished():
+    for _ in range(100):
+        response = request_with_validation(
+            api='/collections/{collection_name}/clone',
+            method="GET",
+            path_params={'collection_name': clone_name},
+        )
+        assert response.ok
+        status = response.json()['result']
+        if status['state'] != 'in_progress':
+            return status
+        time.sleep(0.1)
+    assert False, "Clone is not finished in time"
+
+
+def count_points(name):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count',
+        method="POST",
+        path_params={'collection_name': name},
+        body={"exact": True},
+    )
+    assert response.ok
+    return response.json()['result']['count']
+
+
+def test_clone_with_other_distance():
+    response = request_with_validation(
+        api='/collections/{collection_name}/clone',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "target": clone_name,
+            "vectors": {"size": 4, "distance": "Euclid"},
+            "shard_number": 2,
+        },
+    )
+    assert response.ok
+    assert response.json()['result']['source'] == collection_name
+
+    status = wait_clone_finished()
+    assert status['state'] == 'finished'
+    assert status['points_copied'] == status['points_total'] == count_points(collection_name)
+    assert count_points(clone_name) == count_points(collection_name)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': clone_name},
+    )
+    assert response.ok
+    params = response.json()['result']['config']['params']
+    assert params['vectors']['distance'] == 'Euclid'
+    assert params['shard_number'] == 2
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': clone_name, 'id': 2},
+    )
+    assert response.ok
+    assert response.json()['result']['payload'] == {"city": ["Berlin", "London"]}
+
+    # Updates of the source are accepted again
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [{"id": 100, "vector": [0.1, 0.2, 0.3, 0.4]}]},
+    )
+    assert response.ok
+
+
+def test_clone_with_other_vector_size():
+    response = request_with_validation(
+        api='/collections/{collection_name}/clone',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "target": clone_name,
+            "vectors": {"size": 8, "distance": "Dot"},
+        },
+    )
+    assert response.status_code == 400
+    assert 'same names and sizes' in response.json()['status']['error']
+
+
+def test_status_of_not_cloned_collection():
+    response = request_with_validation(
+        api='/collections/{collection_name}/clone',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.status_code == 404
@@ -0,0 +1,40 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_flush'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def flush(wait):
+    response = request_with_validation(
+        api='/collections/{collection_name}/flush',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': str(wait).lower()},
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def test_awaited_flush():
+    result = flush(wait=True)
+    assert result['status'] == 'completed'
+    assert len(result['shards']) > 0
+    for shard in result['shards']:
+        assert shard['success'], shard
+        assert shard['flushed_operation'] >= 0
+        assert 'error' not in shard
+
+
+def test_background_flush():
+    result = flush(wait=False)
+    assert result['status'] == 'acknowledged'
+    assert 'shards' not in result
@@ -0,0 +1,87 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_quota'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    drop_collection(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={
+            "vectors": {"size": 4, "distance": "Dot"},
+            "shard_number": 1,
+            "quota_config": {"max_points": 2},
+        },
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def upsert_points(ids):
+    return request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [{"id": i, "vector": [0.1, 0.2, 0.3, 0.4]} for i in ids]},
+    )
+
+
+def get_info():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result']
+
+
+def test_points_quota():
+    assert get_info()['config']['quota_config'] == {"max_points": 2}
+
+    response = upsert_points([1, 2])
+    assert response.ok
+
+    usage = get_info()['quota_usage']
+    assert usage['points'] == 2
+    assert usage['vectors_size_bytes'] == 2 * 4 * 4
+
+    response = upsert_points([3])
+    assert response.status_code == 403
+    assert 'max_points' in response.json()['status']['error']
+
+    # Overwriting existing points doesn't increase the usage
+    response = upsert_points([2])
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [1]},
+    )
+    assert response.ok
+
+    response = upsert_points([3])
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"quota_config": {"max_points": 10}},
+    )
+    assert response.ok
+
+    response = upsert_points([4, 5])
+    assert response.ok
+    assert get_info()['quota_usage']['points'] == 4
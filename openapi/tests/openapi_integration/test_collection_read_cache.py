@@ -0,0 +1,86 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_read_cache'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def enable_read_cache():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"read_cache_config": {"max_entries": 100}},
+    )
+    assert response.ok
+
+
+def search():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3, "with_usage": True},
+    )
+    assert response.ok, response.text
+    body = response.json()
+    return body['result'], body['usage']
+
+
+def test_cached_search():
+    enable_read_cache()
+
+    result, usage = search()
+    assert not usage['cache_hit']
+
+    cached_result, usage = search()
+    assert usage['cache_hit']
+    assert cached_result == result
+
+    # Any update invalidates the cached responses
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [{"id": 100, "vector": [1.0, 1.0, 1.0, 1.0]}]},
+    )
+    assert response.ok
+
+    result, usage = search()
+    assert not usage['cache_hit']
+    assert result[0]['id'] == 100
+
+
+def test_cache_is_disabled_by_default():
+    search()
+    _, usage = search()
+    assert not usage['cache_hit']
+
+
+def test_read_cache_config_update():
+    enable_read_cache()
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"read_cache_config": {"ttl_sec": 60}},
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    config = response.json()['result']['config']['read_cache_config']
+    assert config == {"max_entries": 100, "ttl_sec": 60}
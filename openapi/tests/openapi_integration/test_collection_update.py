@@ -1,3 +1,5 @@
+import time
+
 import pytest
 
 from .helpers.helpers import request_with_validation
@@ -50,3 +52,336 @@ def test_collection_update():
         path_params={'collection_name': collection_name, 'id': 7},
     )
     assert response.ok
+
+
+def test_merge_targets_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "optimizers_config": {
+                "max_segment_number": 3,
+                "target_segment_size_kb": 50_000,
+                "max_segment_size_kb": 100_000,
+            }
+        }
+    )
+    assert response.ok, response.text
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok, response.text
+    info = response.json()['result']
+    assert info['config']['optimizer_config']['max_segment_number'] == 3
+    assert info['merge_targets'] == {
+        "max_segment_number": 3,
+        "target_segment_size_kb": 50_000,
+        "max_segment_size_kb": 100_000,
+    }
+
+
+def test_strict_mode_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "strict_mode_config": {
+                "enabled": True,
+                "max_query_limit": 5,
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 10,
+        }
+    )
+    assert response.status_code == 403
+    assert "max_query_limit" in response.json()["status"]["error"]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "strict_mode_config": {
+                "require_payload_index": True,
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    strict_mode_config = response.json()['result']['config']['strict_mode_config']
+    assert strict_mode_config['max_query_limit'] == 5
+    assert strict_mode_config['require_payload_index']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "limit": 3,
+            "filter": {
+                "must": [
+                    {"key": "city", "match": {"value": "London"}}
+                ]
+            }
+        }
+    )
+    assert response.status_code == 403
+    assert "require_payload_index" in response.json()["status"]["error"]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": "keyword"
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "limit": 3,
+            "filter": {
+                "must": [
+                    {"key": "city", "match": {"value": "London"}}
+                ]
+            }
+        }
+    )
+    assert response.ok
+
+
+def test_search_defaults_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "default_search_params": {
+                "hnsw_ef": 256,
+                "exact": False,
+            },
+            "default_with_payload": True,
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    config = response.json()['result']['config']
+    assert config['default_search_params']['hnsw_ef'] == 256
+    assert config['default_with_payload']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+        }
+    )
+    assert response.ok
+    assert all(point['payload'] is not None for point in response.json()['result'])
+
+    # Explicit request values take precedence
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+            "with_payload": False,
+        }
+    )
+    assert response.ok
+    assert all(point['payload'] is None for point in response.json()['result'])
+
+
+def test_payload_validation_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": "keyword"
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "validate_payload": True,
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": "Paris"}},
+                {"id": 101, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": 42}},
+            ]
+        }
+    )
+    assert response.status_code == 400
+    assert "city" in response.json()["status"]["error"]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 100},
+    )
+    assert response.status_code == 404
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "params": {
+                "payload_validation_mode": "point",
+            }
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": "Paris"}},
+                {"id": 101, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": 42, "any": 1}},
+            ]
+        }
+    )
+    assert response.status_code == 400
+    assert "point 101" in response.json()["status"]["error"]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 100},
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 101},
+    )
+    assert response.status_code == 404
+
+
+def test_ttl_update():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "ttl_config": {
+                "field": "expires_at",
+            }
+        }
+    )
+    assert response.ok
+
+    now = int(time.time())
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"payload": {"expires_at": now - 10}, "points": [1, 2]}
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"payload": {"expires_at": now + 3600}, "points": [3]}
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"limit": 100}
+    )
+    assert response.ok
+    ids = [point['id'] for point in response.json()['result']['points']]
+    assert 1 not in ids
+    assert 2 not in ids
+    assert 3 in ids
+
+    # Expiration is combined with the filter of the request
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {
+                "should": [
+                    {"has_id": [1, 3]}
+                ]
+            },
+            "exact": True
+        }
+    )
+    assert response.ok
+    assert response.json()['result']['count'] == 1
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['ttl_config']['field'] == "expires_at"
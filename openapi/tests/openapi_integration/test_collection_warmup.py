@@ -0,0 +1,69 @@
+import time
+
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_warmup'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def get_warmup_progress():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result'].get('warmup')
+
+
+def wait_for_warmup():
+    for _ in range(100):
+        progress = get_warmup_progress()
+        if progress['state'] != 'in_progress':
+            return progress
+        time.sleep(0.1)
+    assert False, "Warmup is not finished in time"
+
+
+def start_warmup(body):
+    return request_with_validation(
+        api='/collections/{collection_name}/warmup',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+
+
+def test_collection_warmup():
+    assert get_warmup_progress() is None
+
+    response = start_warmup({"searches_per_vector": 3})
+    assert response.ok
+
+    progress = wait_for_warmup()
+    assert progress['state'] == 'finished'
+    assert progress['segments_warmed'] == progress['segments_total']
+    assert progress['searches_done'] == 3
+
+    # Nothing to cancel, once the warmup is finished
+    response = request_with_validation(
+        api='/collections/{collection_name}/warmup',
+        method="DELETE",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result'] is False
+
+
+def test_collection_warmup_validation():
+    response = start_warmup({"max_read_mb_per_sec": 0})
+    assert response.status_code == 422
@@ -38,7 +38,10 @@ def test_exact_count_search():
         }
     )
     assert response.ok
-    assert response.json()['result']['count'] == 4
+    result = response.json()['result']
+    assert result['count'] == 4
+    assert result['exact'] is True
+    assert result['min'] == result['max'] == 4
 
 
 def test_approx_count_search():
@@ -67,5 +70,8 @@ def test_approx_count_search():
         }
     )
     assert response.ok
-    assert response.json()['result']['count'] < 8
-    assert response.json()['result']['count'] > 0
+    result = response.json()['result']
+    assert result['count'] < 8
+    assert result['count'] > 0
+    assert result['exact'] is False
+    assert result['min'] <= result['count'] <= result['max']
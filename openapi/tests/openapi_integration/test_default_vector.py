@@ -0,0 +1,81 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection, multivec_collection_setup
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_default_vector'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    multivec_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def update_default_vector(default_vector):
+    return request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"default_vector": default_vector},
+    )
+
+
+def search_unnamed(vector):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": vector, "limit": 3},
+    )
+
+
+def test_default_vector():
+    text_query = [0.2, 0.1, 0.9, 0.7, 0.2, 0.1, 0.9, 0.7]
+
+    # Without a default, the unnamed vector doesn't exist in the collection
+    response = search_unnamed(text_query)
+    assert not response.ok
+
+    response = update_default_vector("text")
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['default_vector'] == "text"
+
+    response = search_unnamed(text_query)
+    assert response.ok
+    unnamed_result = response.json()['result']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": {"name": "text", "vector": text_query}, "limit": 3},
+    )
+    assert response.ok
+    assert [p['id'] for p in unnamed_result] == [p['id'] for p in response.json()['result']]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/recommend',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"positive": [1], "limit": 3},
+    )
+    assert response.ok
+
+    # Vector must exist in the collection
+    response = update_default_vector("audio")
+    assert response.status_code == 400
+
+    response = update_default_vector("")
+    assert response.ok
+
+    response = search_unnamed(text_query)
+    assert not response.ok
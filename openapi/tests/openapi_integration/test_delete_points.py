@@ -58,3 +58,72 @@ def test_delete_points():
     )
     assert response.ok
     assert response.json()['result']['vectors_count'] == 3
+
+
+def test_delete_points_by_filter_count():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "payload": {"to_delete": True},
+            "filter": {
+                "must": [
+                    {"has_id": [1, 2, 3]}
+                ]
+            }
+        }
+    )
+    assert response.ok
+    assert response.json()['result']['updated_count'] == 3
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "filter": {
+                "must": [
+                    {"key": "to_delete", "match": {"value": True}}
+                ]
+            }
+        }
+    )
+    assert response.ok
+    result = response.json()['result']
+    assert result['deleted_count'] == 3
+    assert not result['counted_before_apply']
+
+    # Without waiting, the points are counted when the operation is accepted
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'false'},
+        body={
+            "filter": {
+                "must": [
+                    {"has_id": [3, 4, 5]}
+                ]
+            }
+        }
+    )
+    assert response.ok
+    result = response.json()['result']
+    assert result['deleted_count'] == 2
+    assert result['counted_before_apply']
+
+    # Deletion by ids doesn't count the points
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [6]
+        }
+    )
+    assert response.ok
+    assert 'deleted_count' not in response.json()['result']
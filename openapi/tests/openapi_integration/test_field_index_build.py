@@ -0,0 +1,66 @@
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_field_index_build'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def get_collection_info():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def test_awaited_field_index_creation():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"}
+    )
+    assert response.ok, response.text
+    assert response.json()['result']['status'] == 'completed'
+
+    # Awaited creation returns once the index is built
+    info = get_collection_info()
+    assert 'city' in info['payload_schema']
+    assert 'index_builds' not in info
+
+
+def test_cancel_unknown_field_index_build():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index/builds/{operation_id}/cancel',
+        method="POST",
+        path_params={'collection_name': collection_name, 'operation_id': 1000000},
+        query_params={'wait': 'true'},
+    )
+    assert response.status_code == 404, response.text
+
+
+def test_field_index_on_disk_conversion():
+    for on_disk in [True, False]:
+        response = request_with_validation(
+            api='/collections/{collection_name}/index',
+            method="PUT",
+            path_params={'collection_name': collection_name},
+            query_params={'wait': 'true'},
+            body={"field_name": "city", "field_schema": {"type": "keyword", "on_disk": on_disk}}
+        )
+        assert response.ok, response.text
+
+        # Existing index is converted, instead of being left as is
+        params = get_collection_info()['payload_schema']['city']['params']
+        assert params['on_disk'] == on_disk
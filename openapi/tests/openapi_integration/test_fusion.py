@@ -0,0 +1,77 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection, multivec_collection_setup
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_fusion'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    multivec_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def fusion(body):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/search/fusion',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+
+
+SEARCHES = [
+    {"vector": {"name": "image", "vector": [0.2, 0.1, 0.9, 0.7]}, "limit": 4},
+    {"vector": {"name": "text", "vector": [0.3, 0.5, 0.4, 0.9, 0.3, 0.5, 0.4, 0.9]}, "limit": 4},
+]
+
+
+def test_rrf_fusion():
+    response = fusion({
+        "searches": SEARCHES,
+        "fusion": {"rrf": {}},
+        "limit": 3,
+        "with_payload": True,
+        "with_ranks": True,
+    })
+    assert response.ok, response.json()
+    result = response.json()['result']
+    assert len(result) == 3
+
+    scores = [point['score'] for point in result]
+    assert scores == sorted(scores, reverse=True)
+    for point in result:
+        assert len(point['ranks']) == 2
+        expected = sum(1 / (60 + rank) for rank in point['ranks'] if rank is not None)
+        assert point['score'] == pytest.approx(expected)
+    assert 'payload' in result[0]
+
+    response = fusion({
+        "searches": SEARCHES,
+        "fusion": {"rrf": {"k": 1}},
+        "limit": 3,
+    })
+    assert response.ok
+    assert all('ranks' not in point for point in response.json()['result'])
+
+
+def test_min_max_fusion():
+    response = fusion({
+        "searches": SEARCHES,
+        "fusion": {"min_max": {"weights": [1.0, 0.5]}},
+        "limit": 10,
+    })
+    assert response.ok, response.json()
+    result = response.json()['result']
+    assert 0 < len(result) <= 8
+    assert all(0.0 <= point['score'] <= 1.5 for point in result)
+
+    response = fusion({
+        "searches": SEARCHES,
+        "fusion": {"min_max": {"weights": [1.0]}},
+        "limit": 10,
+    })
+    assert response.status_code == 400
+
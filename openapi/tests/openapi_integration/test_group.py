@@ -175,6 +175,101 @@ def test_search():
         assert len(g["hits"]) == 3
         for h in g["hits"]:
             assert h["payload"]["docId"] == g["id"]
+    assert "stats" not in response.json()["result"]
+
+
+def test_search_without_payload():
+    response = request_with_validation(
+        api=SEARCH_GROUPS_API,
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "vector": [1.0, 0.0, 0.0, 0.0],
+            "limit": 10,
+            "with_payload": False,
+            "group_by": "docId",
+            "group_size": 3,
+        },
+    )
+    assert response.ok
+
+    groups = response.json()["result"]["groups"]
+
+    assert len(groups) == 10
+    assert len(set(g["id"] for g in groups)) == 10
+    for g in groups:
+        assert len(g["hits"]) == 3
+        for h in g["hits"]:
+            assert h.get("payload") is None
+            assert h.get("vector") is None
+
+
+def test_search_with_group_by_stats():
+    response = request_with_validation(
+        api=SEARCH_GROUPS_API,
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "vector": [1.0, 0.0, 0.0, 0.0],
+            "limit": 10,
+            "group_by": "docId",
+            "group_size": 3,
+            "with_group_by_stats": True,
+        },
+    )
+    assert response.ok
+
+    result = response.json()["result"]
+    stats = result["stats"]
+
+    assert stats["groups_requests"] >= 1
+    assert len(stats["fetched_points"]) == stats["groups_requests"] + stats["filling_requests"]
+    assert stats["groups_filled"] == len(result["groups"])
+    assert stats["dropped_filter_keys"] == 0
+
+
+def test_search_with_group_keys():
+    response = request_with_validation(
+        api=SEARCH_GROUPS_API,
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "vector": [1.0, 0.0, 0.0, 0.0],
+            "limit": 1,
+            "with_payload": True,
+            "group_by": "docId",
+            "group_size": 3,
+            "group_keys": [42, 1000, 7],
+        },
+    )
+    assert response.ok
+
+    groups = response.json()["result"]["groups"]
+
+    assert [g["id"] for g in groups] == [42, 1000, 7]
+    assert len(groups[0]["hits"]) == 3
+    assert groups[1]["hits"] == []
+    assert len(groups[2]["hits"]) == 3
+    for g in groups:
+        for h in g["hits"]:
+            assert h["payload"]["docId"] == g["id"]
+
+
+def test_search_with_unsupported_group_keys():
+    response = request_with_validation(
+        api=SEARCH_GROUPS_API,
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body={
+            "vector": [1.0, 0.0, 0.0, 0.0],
+            "limit": 1,
+            "group_by": "docId",
+            "group_size": 3,
+            "group_keys": [42, 2.5],
+        },
+    )
+    assert response.status_code == 400
+    assert response.json()["status"]["reason"] == "unsupported_group_key_type"
 
 
 def test_recommend():
@@ -500,4 +595,65 @@ def test_search_groups_with_full_lookup():
 
         lookup = group["lookup"]
         assert lookup["payload"]
-        assert lookup["vector"]
\ No newline at end of file
+        assert lookup["vector"]
+
+def scroll_groups(body):
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/scroll/groups",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body=body,
+    )
+    assert response.ok, response.json()
+    return response.json()["result"]
+
+
+def test_scroll_groups():
+    result = scroll_groups({"group_by": "docId", "group_size": 3, "limit": 10})
+
+    groups = result["groups"]
+    assert [g["id"] for g in groups] == list(range(10))
+    for g in groups:
+        assert [h["id"] for h in g["hits"]] == [g["id"] * 5 + chunk for chunk in range(3)]
+        for h in g["hits"]:
+            assert h["score"] == 0
+            assert h["payload"]["docId"] == g["id"]
+    assert result["next_page_offset"] == 10
+
+    result = scroll_groups({
+        "group_by": "docId",
+        "group_size": 1,
+        "limit": 10,
+        "offset": 45,
+        "with_payload": False,
+        "with_vector": True,
+    })
+
+    groups = result["groups"]
+    assert [g["id"] for g in groups] == list(range(45, 50))
+    for g in groups:
+        assert len(g["hits"]) == 1
+        assert "payload" not in g["hits"][0] or g["hits"][0]["payload"] is None
+        assert g["hits"][0]["vector"] is not None
+    assert result["next_page_offset"] is None
+
+
+def test_scroll_groups_with_filter():
+    result = scroll_groups({
+        "filter": {"must": [{"key": "docId", "range": {"gte": 20}}]},
+        "group_by": "docId",
+        "group_size": 5,
+        "limit": 2,
+    })
+
+    groups = result["groups"]
+    assert [g["id"] for g in groups] == [20, 21]
+    assert all(len(g["hits"]) == 5 for g in groups)
+    assert result["next_page_offset"] == 22
+
+    # Numeric keys are followed by the string ones
+    result = scroll_groups({"group_by": "multiId", "group_size": 2, "limit": 2})
+
+    groups = result["groups"]
+    assert [g["id"] for g in groups] == ["valid_0", "valid_1"]
+    assert result["next_page_offset"] == "valid_2"
@@ -0,0 +1,87 @@
+import time
+
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_group_by_auto_index'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def search_groups(group_by):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search/groups',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+            "group_size": 2,
+            "group_by": group_by,
+        },
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def get_payload_schema():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result']['payload_schema']
+
+
+def test_warning_for_unindexed_group_by_field():
+    result = search_groups("city")
+    assert len(result['groups']) > 0
+    assert [warning['reason'] for warning in result['warnings']] == ['group_by_field_not_indexed']
+    assert '`city`' in result['warnings'][0]['message']
+
+    # Without the opt-in the index is not created
+    assert 'city' not in get_payload_schema()
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"},
+    )
+    assert response.ok
+
+    result = search_groups("city")
+    assert 'warnings' not in result
+
+
+def test_auto_index_group_by_field():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"params": {"auto_index_group_by_fields": True}},
+    )
+    assert response.ok
+
+    result = search_groups("city")
+    assert [warning['reason'] for warning in result['warnings']] == ['group_by_field_not_indexed']
+
+    # The index is created in background
+    for _ in range(50):
+        schema = get_payload_schema()
+        if 'city' in schema:
+            break
+        time.sleep(0.1)
+    assert schema['city']['data_type'] == 'keyword'
+
+    result = search_groups("city")
+    assert 'warnings' not in result
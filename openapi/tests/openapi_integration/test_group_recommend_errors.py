@@ -0,0 +1,106 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_group_recommend_errors'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def assert_error(response, status_code, reason):
+    assert response.status_code == status_code, response.text
+    status = response.json()['status']
+    assert status['reason'] == reason
+    assert status['error']
+
+
+def search_groups(group_by):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/search/groups',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+            "group_size": 2,
+            "group_by": group_by,
+        },
+    )
+
+
+def recommend(body):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/recommend',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"limit": 3, **body},
+    )
+
+
+def test_group_by_field_not_indexed():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"strict_mode_config": {"enabled": True, "require_payload_index": True}},
+    )
+    assert response.ok
+
+    response = search_groups("city")
+    assert_error(response, 400, 'group_by_field_not_indexed')
+    assert '`city`' in response.json()['status']['error']
+
+
+def test_unsupported_group_key_type():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "count", "field_schema": "float"},
+    )
+    assert response.ok
+
+    response = search_groups("count")
+    assert_error(response, 400, 'unsupported_group_key_type')
+
+
+def test_recommend_example_not_found():
+    response = recommend({"positive": [1, 1000], "negative": [1001]})
+    assert_error(response, 404, 'recommend_example_not_found')
+    error = response.json()['status']['error']
+    assert '1000' in error
+    assert '1001' in error
+
+
+def test_lookup_collection_missing():
+    response = recommend({
+        "positive": [1],
+        "lookup_from": {"collection": "test_group_recommend_errors_missing"},
+    })
+    assert_error(response, 404, 'lookup_collection_missing')
+
+
+def test_vector_name_mismatch():
+    response = recommend({
+        "positive": [1],
+        "lookup_from": {"collection": collection_name, "vector": "missing"},
+    })
+    assert_error(response, 400, 'vector_name_mismatch')
+    assert '`missing`' in response.json()['status']['error']
+
+
+def test_error_without_reason():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': 'test_group_recommend_errors_missing'},
+    )
+    assert response.status_code == 404
+    assert 'reason' not in response.json()['status']
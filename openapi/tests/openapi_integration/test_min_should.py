@@ -0,0 +1,54 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_min_should'
+
+CITY_CONDITIONS = [
+    {"key": "city", "match": {"value": "Berlin"}},
+    {"key": "city", "match": {"value": "London"}},
+    {"key": "city", "match": {"value": "Moscow"}},
+]
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def scroll_min_should(min_count):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "limit": 10,
+            "filter": {
+                "min_should": {
+                    "conditions": CITY_CONDITIONS,
+                    "min_count": min_count,
+                }
+            }
+        },
+    )
+
+
+@pytest.mark.parametrize("min_count,expected_ids", [
+    (1, [1, 2, 3, 4]),
+    (2, [2, 3, 4]),
+    (3, []),
+])
+def test_min_should(min_count, expected_ids):
+    response = scroll_min_should(min_count)
+    assert response.ok, response.text
+    assert [point['id'] for point in response.json()['result']['points']] == expected_ids
+
+
+@pytest.mark.parametrize("min_count", [0, 4])
+def test_min_should_out_of_bounds(min_count):
+    response = scroll_min_should(min_count)
+    assert response.status_code == 422, response.text
+    assert "min_count" in response.json()["status"]["error"]
@@ -0,0 +1,57 @@
+import uuid
+
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_operation_id'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def set_payload(operation_id, payload):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true', 'operation_id': operation_id},
+        body={
+            "payload": payload,
+            "points": [1]
+        }
+    )
+
+
+def get_payload():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 1},
+    )
+    assert response.ok
+    return response.json()['result']['payload']
+
+
+def test_retried_operation_is_applied_once():
+    operation_id = str(uuid.uuid4())
+
+    response = set_payload(operation_id, {"attempt": 1})
+    assert response.ok
+    original_result = response.json()['result']
+    assert get_payload()['attempt'] == 1
+
+    # Retry with the same id returns the original result without applying the operation
+    response = set_payload(operation_id, {"attempt": 2})
+    assert response.ok
+    assert response.json()['result']['operation_id'] == original_result['operation_id']
+    assert get_payload()['attempt'] == 1
+
+    response = set_payload(str(uuid.uuid4()), {"attempt": 3})
+    assert response.ok
+    assert get_payload()['attempt'] == 3
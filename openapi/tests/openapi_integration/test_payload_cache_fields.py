@@ -0,0 +1,102 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_payload_cache_fields'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    drop_collection(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={
+            "vectors": {"size": 4, "distance": "Dot"},
+            "on_disk_payload": True,
+            "payload_cache_fields": ["user"],
+        },
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 1, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"user": "alice", "text": "a"}},
+                {"id": 2, "vector": [0.4, 0.3, 0.2, 0.1], "payload": {"user": "bob", "text": "b"}},
+                {"id": 3, "vector": [0.2, 0.2, 0.2, 0.2], "payload": {"user": "alice", "text": "c"}},
+            ]
+        },
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_cache_fields_in_config():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['params']['payload_cache_fields'] == ["user"]
+
+
+def test_filter_and_group_by_cached_field():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {"must": [{"key": "user", "match": {"value": "alice"}}]},
+            "with_payload": True,
+        },
+    )
+    assert response.ok
+    points = response.json()['result']['points']
+    assert [point['id'] for point in points] == [1, 3]
+    assert points[0]['payload'] == {"user": "alice", "text": "a"}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search/groups',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.1, 0.1, 0.1, 0.1],
+            "group_by": "user",
+            "limit": 2,
+            "group_size": 2,
+            "with_payload": ["user"],
+        },
+    )
+    assert response.ok
+    groups = response.json()['result']['groups']
+    assert {group['id'] for group in groups} == {"alice", "bob"}
+    for group in groups:
+        for hit in group['hits']:
+            assert hit['payload'] == {"user": group['id']}
+
+
+def test_cache_size_in_telemetry():
+    response = request_with_validation(
+        api='/telemetry',
+        method="GET",
+        query_params={'details_level': 2},
+    )
+    assert response.ok
+    collections = response.json()['result']['collections']['collections']
+    collection = next(c for c in collections if c['id'] == collection_name)
+    segments = [
+        segment
+        for shard in collection['shards']
+        if shard['local'] is not None
+        for segment in shard['local']['segments']
+    ]
+    assert sum(segment['payload_cache_ram_bytes'] for segment in segments) > 0
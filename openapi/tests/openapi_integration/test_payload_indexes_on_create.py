@@ -0,0 +1,87 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_payload_indexes_on_create'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    drop_collection(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={
+            "vectors": {"size": 4, "distance": "Dot"},
+            "payload_indexes": {
+                "city": "keyword",
+                "count": "integer",
+                "description": {"type": "text", "tokenizer": "word", "lowercase": True},
+            },
+        },
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def get_collection_info():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result']
+
+
+def test_indexes_exist_before_upload():
+    info = get_collection_info()
+    assert set(info['payload_schema']) == {"city", "count", "description"}
+    assert info['payload_schema']['city']['data_type'] == "keyword"
+    assert info['payload_schema']['description']['data_type'] == "text"
+    assert set(info['config']['params']['payload_indexes']) == {"city", "count", "description"}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 1, "vector": [0.1, 0.2, 0.3, 0.4], "payload": {"city": "Berlin", "count": 1}},
+                {"id": 2, "vector": [0.4, 0.3, 0.2, 0.1], "payload": {"city": "London", "count": 2}},
+            ]
+        },
+    )
+    assert response.ok
+
+    info = get_collection_info()
+    assert info['payload_schema']['city']['points'] == 2
+    assert info['payload_schema']['count']['points'] == 2
+
+
+def test_duplicate_index_is_noop():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"},
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "count"},
+    )
+    assert response.ok
+
+    info = get_collection_info()
+    assert info['payload_schema']['city']['data_type'] == "keyword"
+    assert info['payload_schema']['count']['data_type'] == "integer"
@@ -0,0 +1,94 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_payload_projection'
+
+PAYLOAD = {
+    "a": {"b": 1, "c": 2},
+    "items": [
+        {"name": "x", "price": 1},
+        {"price": 2},
+        {"name": "z", "price": 3},
+    ],
+    "d": 3,
+}
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"payload": PAYLOAD, "points": [1]},
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def retrieve_payload(with_payload):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [1], "with_payload": with_payload},
+    )
+    assert response.ok
+    return response.json()['result'][0]['payload']
+
+
+def test_nested_include():
+    payload = retrieve_payload({"include": ["a.b", "items[].name"]})
+    assert payload == {
+        "a": {"b": 1},
+        "items": [{"name": "x"}, {"name": "z"}],
+    }
+
+
+def test_include_with_exclude():
+    payload = retrieve_payload({"include": ["a", "items"], "exclude": ["a.c", "items[].price"]})
+    assert payload == {
+        "a": {"b": 1},
+        "items": [{"name": "x"}, {}, {"name": "z"}],
+    }
+
+
+def test_nested_exclude():
+    payload = retrieve_payload({"exclude": ["a.b", "items[].name", "d"]})
+    assert payload == {
+        "a": {"c": 2},
+        "items": [{"price": 1}, {"price": 2}, {"price": 3}],
+    }
+
+
+def test_projection_in_search_and_scroll():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.05, 0.61, 0.76, 0.74],
+            "filter": {"must": [{"has_id": [1]}]},
+            "limit": 1,
+            "with_payload": {"include": ["items[1]"]},
+        },
+    )
+    assert response.ok
+    assert response.json()['result'][0]['payload'] == {"items": [{"price": 2}]}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "filter": {"must": [{"has_id": [1]}]},
+            "with_payload": ["a.c"],
+        },
+    )
+    assert response.ok
+    assert response.json()['result']['points'][0]['payload'] == {"a": {"c": 2}}
@@ -0,0 +1,109 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_payload_size_limit'
+
+LARGE_PAYLOAD = {
+    "title": "short",
+    "body": "a" * 100,
+    "tags": ["x", "y"],
+    "embedding_text": "b" * 50,
+}
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 100, "vector": [0.2, 0.1, 0.9, 0.7], "payload": LARGE_PAYLOAD},
+            ]
+        },
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_search_payload_truncation():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 10,
+            "with_payload": True,
+            "max_payload_return_bytes": 30,
+        },
+    )
+    assert response.ok, response.text
+    points = {point['id']: point for point in response.json()['result']}
+
+    # Largest fields are dropped first
+    truncated = points[100]
+    assert truncated['payload'] == {"title": "short"}
+    assert truncated['payload_truncated'] is True
+    assert truncated['dropped_payload_keys'] == ["body", "embedding_text", "tags"]
+
+
+def test_retrieve_and_scroll_payload_truncation():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [100], "with_payload": True, "max_payload_return_bytes": 110},
+    )
+    assert response.ok, response.text
+    point = response.json()['result'][0]
+    assert point['dropped_payload_keys'] == ["body"]
+    assert set(point['payload']) == {"title", "tags", "embedding_text"}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"offset": 100, "limit": 1, "max_payload_return_bytes": 110},
+    )
+    assert response.ok, response.text
+    point = response.json()['result']['points'][0]
+    assert point['id'] == 100
+    assert point['dropped_payload_keys'] == ["body"]
+
+
+def test_collection_default_payload_size_limit():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"default_max_payload_return_bytes": 30},
+    )
+    assert response.ok, response.text
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [100], "with_payload": True},
+    )
+    assert response.ok, response.text
+    assert response.json()['result'][0]['payload'] == {"title": "short"}
+
+    # Limit of the request takes precedence
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [100], "with_payload": True, "max_payload_return_bytes": 1000},
+    )
+    assert response.ok, response.text
+    point = response.json()['result'][0]
+    assert point['payload'] == LARGE_PAYLOAD
+    assert 'payload_truncated' not in point
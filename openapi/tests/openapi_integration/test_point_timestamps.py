@@ -0,0 +1,64 @@
+import time
+from datetime import datetime, timezone
+
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_point_timestamps'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def scroll(body):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+    assert response.ok
+    return response.json()['result']['points']
+
+
+def test_point_timestamps():
+    points = scroll({"limit": 100})
+    assert all('last_modified' not in point for point in points)
+
+    points = scroll({"limit": 100, "with_timestamps": True})
+    assert len(points) > 0
+    assert all(point['last_modified'] is not None for point in points)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [1, 2], "with_timestamps": True},
+    )
+    assert response.ok
+    assert all(point['last_modified'] is not None for point in response.json()['result'])
+
+    time.sleep(0.01)
+    modified_since = datetime.now(timezone.utc).isoformat()
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"payload": {"audited": True}, "points": [2]},
+    )
+    assert response.ok
+
+    points = scroll({
+        "limit": 100,
+        "with_timestamps": True,
+        "filter": {"must": [{"modified_since": modified_since}]},
+    })
+    assert [point['id'] for point in points] == [2]
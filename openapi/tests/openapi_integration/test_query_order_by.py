@@ -0,0 +1,109 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_query_order_by'
+
+QUERY_API = '/collections/{collection_name}/points/query'
+QUERY_GROUPS_API = '/collections/{collection_name}/points/query/groups'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    upsert_prices()
+    create_index("price", "float")
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def upsert_prices():
+    points = [
+        {
+            "id": 100 + i,
+            "vector": [0.1, 0.2, 0.3, 0.4],
+            "payload": {"price": price, "shop": f"shop_{i % 3}"},
+        }
+        for i, price in enumerate([10.5, 3.0, 7.25, 100.0, 42.0, 0.5, 15.0])
+    ]
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": points},
+    )
+    assert response.ok
+
+
+def create_index(field_name, field_schema):
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": field_name, "field_schema": field_schema},
+    )
+    assert response.ok
+
+
+def query(body):
+    return request_with_validation(
+        api=QUERY_API,
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+
+
+def test_query_order_by():
+    response = query({
+        "order_by": {"key": "price", "direction": "desc"},
+        "limit": 3,
+        "with_payload": True,
+    })
+    assert response.ok, response.text
+    result = response.json()['result']
+    assert [point['order_value'] for point in result] == [100.0, 42.0, 15.0]
+    assert [point['payload']['price'] for point in result] == [100.0, 42.0, 15.0]
+
+    response = query({
+        "order_by": {"key": "price"},
+        "filter": {"must": [{"key": "price", "range": {"gte": 5.0}}]},
+        "limit": 2,
+        "offset": 1,
+    })
+    assert response.ok, response.text
+    result = response.json()['result']
+    assert [point['id'] for point in result] == [100, 106]
+    assert all(point['payload'] is None for point in result)
+
+
+def test_query_order_by_unindexed_field():
+    response = query({"order_by": {"key": "shop"}, "limit": 3})
+    assert response.status_code == 400
+    assert "no payload index" in response.json()['status']['error']
+
+    create_index("shop", "keyword")
+    response = query({"order_by": {"key": "shop"}, "limit": 3})
+    assert response.status_code == 400
+    assert "indexed as keyword" in response.json()['status']['error']
+
+
+def test_query_groups_order_by():
+    response = request_with_validation(
+        api=QUERY_GROUPS_API,
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "order_by": {"key": "price", "direction": "desc"},
+            "group_by": "shop",
+            "group_size": 2,
+            "limit": 3,
+        },
+    )
+    assert response.ok, response.text
+    groups = response.json()['result']['groups']
+    assert [group['id'] for group in groups] == ["shop_0", "shop_1", "shop_2"]
+    assert [hit['score'] for hit in groups[0]['hits']] == [100.0, 15.0]
@@ -0,0 +1,72 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_read_only_collection'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def set_read_only(read_only):
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={"params": {"read_only": read_only}},
+    )
+    assert response.ok
+
+
+def upsert_point():
+    return request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [{"id": 100, "vector": [0.1, 0.2, 0.3, 0.4]}]},
+    )
+
+
+def test_read_only_collection():
+    set_read_only(True)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    assert response.json()['result']['config']['params']['read_only'] is True
+
+    response = upsert_point()
+    assert response.status_code == 403
+    assert '"read_only": false' in response.json()['status']['error']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={"field_name": "city", "field_schema": "keyword"},
+    )
+    assert response.status_code == 403
+
+    # Reads are served as usual
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3},
+    )
+    assert response.ok
+    assert len(response.json()['result']) == 3
+
+    set_read_only(False)
+
+    response = upsert_point()
+    assert response.ok
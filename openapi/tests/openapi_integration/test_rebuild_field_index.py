@@ -0,0 +1,87 @@
+import time
+
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_rebuild_field_index'
+
+CITY_FILTER = {"must": [{"key": "city", "match": {"value": "Berlin"}}]}
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"}
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def scroll_city_points():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"filter": CITY_FILTER, "limit": 10},
+    )
+    assert response.ok, response.text
+    return [point['id'] for point in response.json()['result']['points']]
+
+
+def get_rebuild_progress(field_name):
+    response = request_with_validation(
+        api='/collections/{collection_name}/index/{field_name}/rebuild',
+        method="GET",
+        path_params={'collection_name': collection_name, 'field_name': field_name},
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def test_rebuild_field_index():
+    points_before = scroll_city_points()
+    assert len(points_before) > 0
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index/{field_name}/rebuild',
+        method="POST",
+        path_params={'collection_name': collection_name, 'field_name': 'city'},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok, response.text
+
+    # The current index serves reads until the rebuilt one replaces it
+    assert scroll_city_points() == points_before
+
+    for _ in range(50):
+        progress = get_rebuild_progress('city')
+        assert [shard['shard_id'] for shard in progress] == [0]
+        if progress[0]['rebuilding_segments'] == 0:
+            break
+        assert progress[0]['indexed_points'] <= progress[0]['total_points']
+        time.sleep(0.1)
+    else:
+        pytest.fail("Rebuild of the index is not complete")
+
+    assert scroll_city_points() == points_before
+
+
+def test_rebuild_not_indexed_field():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index/{field_name}/rebuild',
+        method="POST",
+        path_params={'collection_name': collection_name, 'field_name': 'count'},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok, response.text
+
+    progress = get_rebuild_progress('count')
+    assert progress[0]['rebuilding_segments'] == 0
@@ -0,0 +1,41 @@
+import pytest
+
+from .helpers.collection_setup import multivec_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_search_vector_validation'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    multivec_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def search(name, vector):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": {"name": name, "vector": vector}, "limit": 3},
+    )
+
+
+@pytest.mark.parametrize("name", ["image", "text"])
+def test_empty_search_vector(name):
+    response = search(name, [])
+    assert response.status_code == 400, response.text
+    assert "empty" in response.json()["status"]["error"]
+
+
+def test_zero_search_vector_cosine():
+    response = search("text", [0.0] * 8)
+    assert response.status_code == 400, response.text
+    assert "cosine" in response.json()["status"]["error"]
+
+
+def test_zero_search_vector_dot():
+    response = search("image", [0.0] * 4)
+    assert response.ok, response.text
+    assert all(point['score'] == 0.0 for point in response.json()['result'])
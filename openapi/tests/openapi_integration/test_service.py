@@ -25,6 +25,33 @@ def test_metrics():
     assert 'app_info{name="qdrant",version="' in response.text
     assert 'collections_total ' in response.text
 
+
+def test_operations_metrics():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search/groups',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 2,
+            "group_by": "city",
+            "group_size": 2,
+        },
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/metrics',
+        method="GET",
+    )
+    assert response.ok
+
+    assert '# TYPE qdrant_group_by_duration_seconds histogram' in response.text
+    assert f'qdrant_group_by_duration_seconds_count{{collection="{collection_name}"}} ' in response.text
+    assert f'qdrant_group_by_internal_searches_total{{collection="{collection_name}"}} ' in response.text
+    assert '# TYPE qdrant_recommend_duration_seconds histogram' in response.text
+    assert 'qdrant_recommend_example_resolution_failures_total{' in response.text
+
 def test_telemetry():
     response = request_with_validation(
         api='/telemetry',
@@ -43,4 +70,14 @@ def test_telemetry():
     last_queried = endpoint['200']['last_responded']
     last_queried = datetime.fromisoformat(last_queried)
     # Assert today
-    assert last_queried.date() == datetime.now().date()
\ No newline at end of file
+    assert last_queried.date() == datetime.now().date()
+
+def test_slow_log():
+    response = request_with_validation(
+        api='/collections/{collection_name}/slow_log',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    # Slow log is disabled in the default configuration
+    assert response.json()['result'] == []
@@ -0,0 +1,78 @@
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_shard_segments'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_shard_segments():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"field_name": "city", "field_schema": "keyword"}
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/segments',
+        method="GET",
+        path_params={'collection_name': collection_name, 'shard_id': 0},
+    )
+    assert response.ok
+    result = response.json()['result']
+    assert result['shard_id'] == 0
+
+    segments = result['segments']
+    assert len(segments) > 0
+    assert sum(segment['points_count'] for segment in segments) == 8
+    for segment in segments:
+        assert segment['uuid']
+        assert segment['vector_indexes']['']['type'] == 'plain'
+        assert segment['payload_indexes'] == ['city']
+        assert segment['disk_usage_bytes'] > 0
+        # Plain segments have no HNSW graph to check
+        assert segment['graph_health'] == {}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/segments',
+        method="GET",
+        path_params={'collection_name': collection_name, 'shard_id': 100},
+    )
+    assert response.status_code == 400
+
+
+def telemetry_segments(details_level):
+    response = request_with_validation(
+        api='/telemetry',
+        method="GET",
+        query_params={'details_level': details_level},
+    )
+    assert response.ok
+    collections = response.json()['result']['collections']['collections']
+    collection = next(c for c in collections if c['id'] == collection_name)
+    return [
+        segment
+        for shard in collection['shards']
+        if shard['local'] is not None
+        for segment in shard['local']['segments']
+    ]
+
+
+def test_graph_health_telemetry_level():
+    segments = telemetry_segments(2)
+    assert len(segments) > 0
+    assert all('graph_health' not in segment for segment in segments)
+
+    segments = telemetry_segments(3)
+    assert len(segments) > 0
+    assert all(segment['graph_health'] == {} for segment in segments)
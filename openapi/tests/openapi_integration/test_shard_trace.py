@@ -0,0 +1,53 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_shard_trace'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def search(body):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3, **body},
+    )
+    assert response.ok, response.text
+    return response.json()
+
+
+def test_search_shard_trace():
+    body = search({"with_shard_trace": True})
+    trace = body['trace']
+    assert len(trace) == 1
+    assert trace[0]['shard_id'] == 0
+    assert trace[0]['local']
+    assert trace[0]['latency'] >= 0
+
+    body = search({})
+    assert 'trace' not in body
+
+
+def test_search_batch_shard_trace():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search/batch',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "searches": [
+                {"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3, "with_shard_trace": True},
+                {"vector": [0.5, 0.3, 0.2, 0.3], "limit": 3},
+            ]
+        },
+    )
+    assert response.ok, response.text
+    # Searches of the batch are sent to the shards together
+    assert len(response.json()['trace']) == 1
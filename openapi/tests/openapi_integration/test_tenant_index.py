@@ -0,0 +1,55 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_tenant_index'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_tenant_keyword_index():
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": {"type": "keyword", "is_tenant": True},
+        },
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    schema = response.json()['result']['payload_schema']['city']
+    assert schema['data_type'] == "keyword"
+    assert schema['params'] == {"type": "keyword", "is_tenant": True}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 10,
+            "filter": {"must": [{"key": "city", "match": {"value": "London"}}]},
+            "with_payload": True,
+        },
+    )
+    assert response.ok
+    result = response.json()['result']
+    assert len(result) > 0
+    for point in result:
+        city = point['payload']['city']
+        assert city == "London" or "London" in city
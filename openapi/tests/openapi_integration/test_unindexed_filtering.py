@@ -0,0 +1,95 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_unindexed_filtering'
+
+CITY_FILTER = {
+    "must": [
+        {"key": "city", "match": {"value": "London"}}
+    ]
+}
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def set_unindexed_filtering(mode):
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PATCH",
+        path_params={'collection_name': collection_name},
+        body={
+            "strict_mode_config": {
+                "enabled": True,
+                "unindexed_filtering": mode,
+            }
+        }
+    )
+    assert response.ok, response.text
+
+
+def scroll_by_city():
+    return request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"limit": 3, "filter": CITY_FILTER},
+    )
+
+
+def test_unindexed_filtering_warn():
+    response = scroll_by_city()
+    assert response.ok, response.text
+    assert 'warnings' not in response.json()
+
+    set_unindexed_filtering("warn")
+
+    response = scroll_by_city()
+    assert response.ok, response.text
+    assert len(response.json()['result']['points']) > 0
+    warnings = response.json()['warnings']
+    assert len(warnings) == 1
+    assert warnings[0]['reason'] == "unindexed_filter"
+    assert "city" in warnings[0]['message']
+
+    # Requests without filter are not affected
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3},
+    )
+    assert response.ok, response.text
+    assert 'warnings' not in response.json()
+
+
+def test_unindexed_filtering_deny():
+    set_unindexed_filtering("deny")
+
+    response = scroll_by_city()
+    assert response.status_code == 403
+    error = response.json()["status"]["error"]
+    assert "unindexed_filtering" in error
+    assert "city" in error
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/index',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "field_name": "city",
+            "field_schema": "keyword"
+        }
+    )
+    assert response.ok, response.text
+
+    response = scroll_by_city()
+    assert response.ok, response.text
+    assert 'warnings' not in response.json()
@@ -0,0 +1,75 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_vector_slice'
+
+VECTOR_SLICE = {"slice": {"start": 0, "end": 2}}
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_search_vector_slice():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+            "with_vector": VECTOR_SLICE,
+        },
+    )
+    assert response.ok, response.text
+    for point in response.json()['result']:
+        assert point['vector'] is None
+        assert point['vector_slice']['start'] == 0
+        assert point['vector_slice']['end'] == 2
+        assert len(point['vector_slice']['values']) == 2
+
+
+def test_retrieve_and_scroll_vector_slice():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"ids": [1, 2], "with_vector": {"slice": {"start": 1, "end": 4}}},
+    )
+    assert response.ok, response.text
+    points = response.json()['result']
+    assert len(points) == 2
+    for point in points:
+        assert point['vector'] is None
+        assert len(point['vector_slice']['values']) == 3
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"limit": 2, "with_vector": VECTOR_SLICE},
+    )
+    assert response.ok, response.text
+    for point in response.json()['result']['points']:
+        assert point['vector'] is None
+        assert len(point['vector_slice']['values']) == 2
+
+
+def test_vector_slice_out_of_range():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.2, 0.1, 0.9, 0.7],
+            "limit": 3,
+            "with_vector": {"slice": {"start": 0, "end": 5}},
+        },
+    )
+    assert response.status_code == 400
+    assert "out of range" in response.json()['status']['error']
@@ -0,0 +1,39 @@
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_wait_for'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def set_payload(wait_for):
+    return request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait_for': wait_for},
+        body={
+            "payload": {"city": "Berlin"},
+            "points": [1]
+        }
+    )
+
+
+@pytest.mark.parametrize("wait_for", ["1", "majority", "all"])
+def test_wait_for_replicas(wait_for):
+    response = set_payload(wait_for)
+    assert response.ok
+    # Waiting for replicas implies waiting for the operation to be applied
+    assert response.json()['result']['status'] == 'completed'
+
+
+def test_wait_for_more_replicas_than_exist():
+    response = set_payload("2")
+    assert response.status_code == 400
@@ -0,0 +1,37 @@
+import pytest
+
+from .helpers.helpers import request_with_validation
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+
+collection_name = 'test_collection_wait_for_status'
+
+
+@pytest.fixture(autouse=True)
+def setup():
+    basic_collection_setup(collection_name=collection_name)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def test_wait_for_green_status():
+    response = request_with_validation(
+        api='/collections/{collection_name}/status/wait',
+        method="GET",
+        path_params={'collection_name': collection_name},
+        query_params={'status': 'green', 'timeout': 10},
+    )
+    assert response.ok, response.text
+    result = response.json()['result']
+    assert result['reached']
+    assert len(result['shards']) > 0
+    assert all(shard['status'] == 'green' for shard in result['shards'])
+
+
+def test_wait_for_status_invalid_timeout():
+    response = request_with_validation(
+        api='/collections/{collection_name}/status/wait',
+        method="GET",
+        path_params={'collection_name': collection_name},
+        query_params={'timeout': 0},
+    )
+    assert response.status_code == 400
@@ -1,19 +1,32 @@
 use actix_web::rt::time::Instant;
-use actix_web::{delete, get, post, web, Responder};
-use actix_web_validator::Query;
+use actix_web::{delete, get, post, put, web, Responder};
+use actix_web_validator::{Json, Query};
+use itertools::Itertools;
 use serde::Deserialize;
 use storage::content_manager::consensus_ops::ConsensusOperations;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
+use storage::types::SetPeerLabels;
 use validator::Validate;
 
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_global_access, AccessLevel};
 
 #[derive(Debug, Deserialize, Validate)]
 struct QueryParams {
     #[serde(default)]
     force: bool,
+    /// Along with `force`, allows to remove the last active replicas of the shards
+    #[serde(default)]
+    allow_data_loss: bool,
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    timeout: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct SetPeerLabelsParams {
     #[serde(default)]
     #[validate(range(min = 1))]
     timeout: Option<u64>,
@@ -29,7 +42,10 @@ async fn cluster_status(dispatcher: web::Data<Dispatcher>) -> impl Responder {
 #[post("/cluster/recover")]
 async fn recover_current_peer(toc: web::Data<TableOfContent>) -> impl Responder {
     let timing = Instant::now();
-    process_response(toc.request_snapshot().map(|_| true), timing)
+    let response = check_global_access(AccessLevel::Manage)
+        .and_then(|()| toc.request_snapshot())
+        .map(|_| true);
+    process_response(response, timing)
 }
 
 #[delete("/cluster/peer/{peer_id}")]
@@ -42,6 +58,28 @@ async fn remove_peer(
     let dispatcher = dispatcher.into_inner();
     let peer_id = peer_id.into_inner();
 
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
+
+    let last_replicas = dispatcher.last_replicas_on_peer(peer_id).await;
+    if !last_replicas.is_empty() && !(params.force && params.allow_data_loss) {
+        let hint = if params.force {
+            "Pass `allow_data_loss=true` along with `force=true` to remove the peer anyway"
+        } else {
+            "Replicate the shards to other peers first"
+        };
+        return process_response::<()>(
+            Err(StorageError::BadRequest {
+                description: format!(
+                    "Cannot remove peer {peer_id} as it holds the last active replica of {}. {hint}",
+                    last_replicas.iter().join(", "),
+                ),
+            }),
+            timing,
+        );
+    }
+
     let has_shards = dispatcher.peer_has_shards(peer_id).await;
     if !params.force && has_shards {
         return process_response::<()>(
@@ -68,9 +106,46 @@ async fn remove_peer(
     process_response(response, timing)
 }
 
+#[put("/cluster/peer/{peer_id}/labels")]
+async fn set_peer_labels(
+    dispatcher: web::Data<Dispatcher>,
+    peer_id: web::Path<u64>,
+    request: Json<SetPeerLabels>,
+    Query(params): Query<SetPeerLabelsParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let peer_id = peer_id.into_inner();
+    let SetPeerLabels { labels } = request.into_inner();
+
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
+
+    let response = match dispatcher.consensus_state() {
+        Some(consensus_state) if !consensus_state.peer_address_by_id().contains_key(&peer_id) => {
+            Err(StorageError::NotFound {
+                description: format!("Peer {peer_id} does not exist"),
+            })
+        }
+        Some(consensus_state) => {
+            consensus_state
+                .propose_consensus_op_with_await(
+                    ConsensusOperations::UpdatePeerLabels { peer_id, labels },
+                    params.timeout.map(std::time::Duration::from_secs),
+                )
+                .await
+        }
+        None => Err(StorageError::BadRequest {
+            description: "Distributed mode disabled.".to_string(),
+        }),
+    };
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_cluster_api(cfg: &mut web::ServiceConfig) {
     cfg.service(cluster_status)
         .service(remove_peer)
+        .service(set_peer_labels)
         .service(recover_current_peer);
 }
@@ -4,7 +4,12 @@ use actix_web::rt::time::Instant;
 use actix_web::{delete, get, patch, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::cluster_ops::ClusterOperations;
+use collection::operations::types::CollectionStatus;
+use collection::shards::shard::ShardId;
+use collection::warmup::WarmupRequest;
 use serde::Deserialize;
+use storage::content_manager::collection_clone::CloneCollection;
+use storage::content_manager::collection_config_document::CollectionConfigDocument;
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CollectionMetaOperations, CreateCollection, CreateCollectionOperation,
     DeleteCollectionOperation, UpdateCollection, UpdateCollectionOperation,
@@ -16,6 +21,7 @@ use validator::Validate;
 use super::CollectionPath;
 use crate::actix::api::StrictCollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_collection_access, AccessLevel};
 use crate::common::collections::*;
 
 #[derive(Debug, Deserialize, Validate)]
@@ -30,6 +36,36 @@ impl WaitTimeout {
     }
 }
 
+/// Collection status is awaited for this long, unless another timeout is requested
+const DEFAULT_WAIT_FOR_STATUS_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize, Validate)]
+struct WaitForStatusParams {
+    /// Status to wait for, `green` by default
+    status: Option<CollectionStatus>,
+    /// Timeout of waiting in seconds
+    #[validate(range(min = 1))]
+    timeout: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct FlushParams {
+    /// If true, wait until the flush is finished
+    wait: Option<bool>,
+}
+
+#[derive(Deserialize, Validate)]
+struct ShardPath {
+    shard_id: ShardId,
+}
+
+#[derive(Deserialize, Validate)]
+struct FieldPath {
+    #[serde(rename = "field_name")]
+    #[validate(length(min = 1))]
+    name: String,
+}
+
 #[get("/collections")]
 async fn get_collections(toc: web::Data<TableOfContent>) -> impl Responder {
     let timing = Instant::now();
@@ -72,15 +108,15 @@ async fn create_collection(
     Query(query): Query<WaitTimeout>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let response = dispatcher
-        .submit_collection_meta_op(
-            CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
-                collection.name.clone(),
-                operation.into_inner(),
-            )),
-            query.timeout(),
-        )
-        .await;
+    let response = do_submit_collection_meta_op(
+        dispatcher.get_ref(),
+        CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+            collection.name.clone(),
+            operation.into_inner(),
+        )),
+        query.timeout(),
+    )
+    .await;
     process_response(response, timing)
 }
 
@@ -93,15 +129,15 @@ async fn update_collection(
 ) -> impl Responder {
     let timing = Instant::now();
     let name = collection.name.clone();
-    let response = dispatcher
-        .submit_collection_meta_op(
-            CollectionMetaOperations::UpdateCollection(UpdateCollectionOperation::new(
-                name,
-                operation.into_inner(),
-            )),
-            query.timeout(),
-        )
-        .await;
+    let response = do_submit_collection_meta_op(
+        dispatcher.get_ref(),
+        CollectionMetaOperations::UpdateCollection(UpdateCollectionOperation::new(
+            name,
+            operation.into_inner(),
+        )),
+        query.timeout(),
+    )
+    .await;
     process_response(response, timing)
 }
 
@@ -112,14 +148,70 @@ async fn delete_collection(
     Query(query): Query<WaitTimeout>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let response = dispatcher
-        .submit_collection_meta_op(
-            CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(
-                collection.name.clone(),
-            )),
-            query.timeout(),
-        )
-        .await;
+    let response = do_submit_collection_meta_op(
+        dispatcher.get_ref(),
+        CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(
+            collection.name.clone(),
+        )),
+        query.timeout(),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/clone")]
+async fn clone_collection(
+    dispatcher: web::Data<Dispatcher>,
+    collection: Path<CollectionPath>,
+    operation: Json<CloneCollection>,
+    Query(query): Query<WaitTimeout>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_clone_collection(
+        dispatcher.get_ref(),
+        &collection.name,
+        operation.into_inner(),
+        query.timeout(),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/clone")]
+async fn get_collection_clone_status(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = check_collection_access(&collection.name, AccessLevel::Read)
+        .and_then(|()| toc.collection_clone_status(&collection.name));
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/config")]
+async fn export_collection_config(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_export_collection_config(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+/// Collection is created or updated under the name of the path,
+/// regardless of the name in the document
+#[put("/collections/{name}/config")]
+async fn apply_collection_config(
+    dispatcher: web::Data<Dispatcher>,
+    collection: Path<StrictCollectionPath>,
+    document: Json<CollectionConfigDocument>,
+    Query(query): Query<WaitTimeout>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let mut document = document.into_inner();
+    document.collection_name = collection.into_inner().name;
+    let response =
+        do_apply_collection_config(dispatcher.get_ref(), document, query.timeout()).await;
     process_response(response, timing)
 }
 
@@ -130,12 +222,12 @@ async fn update_aliases(
     Query(query): Query<WaitTimeout>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let response = dispatcher
-        .submit_collection_meta_op(
-            CollectionMetaOperations::ChangeAliases(operation.0),
-            query.timeout(),
-        )
-        .await;
+    let response = do_submit_collection_meta_op(
+        dispatcher.get_ref(),
+        CollectionMetaOperations::ChangeAliases(operation.0),
+        query.timeout(),
+    )
+    .await;
     process_response(response, timing)
 }
 
@@ -149,6 +241,26 @@ async fn get_cluster_info(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/cluster/rebalancing")]
+async fn get_rebalancing_plan(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_rebalancing_plan(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/cluster/checksum")]
+async fn get_collection_checksum(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_checksum(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
 #[post("/collections/{name}/cluster")]
 async fn update_collection_cluster(
     toc: web::Data<TableOfContent>,
@@ -170,6 +282,111 @@ async fn update_collection_cluster(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/shards/{shard_id}/segments")]
+async fn get_shard_segments(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    shard: Path<ShardPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_shard_segments(toc.get_ref(), &collection.name, shard.shard_id).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/index/{field_name}/rebuild")]
+async fn get_index_rebuild_progress(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    field: Path<FieldPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response =
+        do_get_index_rebuild_progress(toc.get_ref(), &collection.name, &field.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimizers/trigger")]
+async fn trigger_optimizers(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_trigger_optimizers(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/flush")]
+async fn flush_collection(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    Query(params): Query<FlushParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(false);
+    let response = do_flush_collection(toc.get_ref(), &collection.name, wait).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/optimizers/plan")]
+async fn get_optimizers_plan(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_optimizers_plan(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/status/wait")]
+async fn wait_for_collection_status(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    Query(params): Query<WaitForStatusParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_wait_for_collection_status(
+        toc.get_ref(),
+        &collection.name,
+        params.status.unwrap_or(CollectionStatus::Green),
+        params
+            .timeout
+            .map_or(DEFAULT_WAIT_FOR_STATUS_TIMEOUT, Duration::from_secs),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/slow_log")]
+async fn get_slow_log(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_collection_slow_log(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/warmup")]
+async fn start_warmup(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<WarmupRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_start_warmup(toc.get_ref(), &collection.name, request.into_inner()).await;
+    process_response(response, timing)
+}
+
+#[delete("/collections/{name}/warmup")]
+async fn cancel_warmup(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_cancel_warmup(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collections)
@@ -177,11 +394,26 @@ pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
         .service(create_collection)
         .service(update_collection)
         .service(delete_collection)
+        .service(clone_collection)
+        .service(get_collection_clone_status)
+        .service(export_collection_config)
+        .service(apply_collection_config)
         .service(get_aliases)
         .service(get_collection_aliases)
         .service(update_aliases)
         .service(get_cluster_info)
-        .service(update_collection_cluster);
+        .service(get_rebalancing_plan)
+        .service(get_collection_checksum)
+        .service(update_collection_cluster)
+        .service(get_shard_segments)
+        .service(get_index_rebuild_progress)
+        .service(trigger_optimizers)
+        .service(flush_collection)
+        .service(get_optimizers_plan)
+        .service(wait_for_collection_status)
+        .service(get_slow_log)
+        .service(start_warmup)
+        .service(cancel_warmup);
 }
 
 #[cfg(test)]
@@ -0,0 +1,56 @@
+use actix_web::rt::time::Instant;
+use actix_web::{post, web, Responder};
+use actix_web_validator::{Json, Path, Query};
+use collection::operations::types::{OrderedQueryRequest, QueryGroupsRequest};
+use storage::content_manager::toc::TableOfContent;
+
+use super::read_params::ReadParams;
+use super::CollectionPath;
+use crate::actix::helpers::process_response;
+use crate::common::points::{do_query_point_groups, do_query_points};
+
+#[post("/collections/{name}/points/query")]
+async fn query_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<OrderedQueryRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = do_query_points(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        params.consistency,
+        None,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/query/groups")]
+async fn query_point_groups(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<QueryGroupsRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = do_query_point_groups(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        params.consistency,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+// Configure services
+pub fn config_query_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(query_points).service(query_point_groups);
+}
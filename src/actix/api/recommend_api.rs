@@ -1,27 +1,15 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::types::{
     RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch,
 };
-use segment::types::ScoredPoint;
-use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
-
-async fn do_recommend_points(
-    toc: &TableOfContent,
-    collection_name: &str,
-    request: RecommendRequest,
-    read_consistency: Option<ReadConsistency>,
-) -> Result<Vec<ScoredPoint>, StorageError> {
-    toc.recommend(collection_name, request, read_consistency)
-        .await
-}
+use crate::common::points::{do_recommend_batch_points, do_recommend_points};
 
 #[post("/collections/{name}/points/recommend")]
 async fn recommend_points(
@@ -43,16 +31,6 @@ async fn recommend_points(
     process_response(response, timing)
 }
 
-async fn do_recommend_batch_points(
-    toc: &TableOfContent,
-    collection_name: &str,
-    request: RecommendRequestBatch,
-    read_consistency: Option<ReadConsistency>,
-) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
-    toc.recommend_batch(collection_name, request, read_consistency)
-        .await
-}
-
 #[post("/collections/{name}/points/recommend/batch")]
 async fn recommend_batch_points(
     toc: web::Data<TableOfContent>,
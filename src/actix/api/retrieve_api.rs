@@ -2,7 +2,9 @@ use actix_web::rt::time::Instant;
 use actix_web::{get, post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::types::{PointRequest, Record, ScrollRequest, ScrollResult};
+use collection::operations::types::{
+    CheckFilterRequest, PointRequest, Record, ScrollGroupsRequest, ScrollRequest, ScrollResult,
+};
 use segment::types::{PointIdType, WithPayloadInterface};
 use serde::Deserialize;
 use storage::content_manager::errors::StorageError;
@@ -12,7 +14,8 @@ use validator::Validate;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
-use crate::common::points::do_get_points;
+use crate::common::auth::{check_collection_access, AccessLevel};
+use crate::common::points::{do_check_filter, do_get_points, do_scroll_point_groups};
 
 #[derive(Deserialize, Validate)]
 struct PointPath {
@@ -27,10 +30,13 @@ async fn do_get_point(
     point_id: PointIdType,
     read_consistency: Option<ReadConsistency>,
 ) -> Result<Option<Record>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     let request = PointRequest {
         ids: vec![point_id],
         with_payload: Some(WithPayloadInterface::Bool(true)),
         with_vector: true.into(),
+        with_timestamps: false,
+        max_payload_return_bytes: None,
     };
 
     toc.retrieve(collection_name, request, read_consistency, None)
@@ -44,6 +50,7 @@ async fn scroll_get_points(
     request: ScrollRequest,
     read_consistency: Option<ReadConsistency>,
 ) -> Result<ScrollResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     toc.scroll(collection_name, request, read_consistency, None)
         .await
 }
@@ -128,3 +135,37 @@ async fn scroll_points(
     .await;
     process_response(response, timing)
 }
+
+#[post("/collections/{name}/points/scroll/groups")]
+async fn scroll_point_groups(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<ScrollGroupsRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = do_scroll_point_groups(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        params.consistency,
+        None,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/check_filter")]
+async fn check_filter(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<CheckFilterRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response =
+        do_check_filter(toc.get_ref(), &collection.name, request.into_inner(), None).await;
+
+    process_response(response, timing)
+}
@@ -1,13 +1,17 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::types::{SearchGroupsRequest, SearchRequest, SearchRequestBatch};
+use collection::operations::types::{
+    FusionRequest, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
+};
 use storage::content_manager::toc::TableOfContent;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
-use crate::actix::helpers::process_response;
-use crate::common::points::{do_search_batch_points, do_search_point_groups, do_search_points};
+use crate::actix::helpers::process_search_response;
+use crate::common::points::{
+    do_search_batch_points, do_search_fusion, do_search_point_groups, do_search_points,
+};
 
 #[post("/collections/{name}/points/search")]
 async fn search_points(
@@ -27,7 +31,7 @@ async fn search_points(
     )
     .await;
 
-    process_response(response, timing)
+    process_search_response(response, timing)
 }
 
 #[post("/collections/{name}/points/search/batch")]
@@ -48,7 +52,7 @@ async fn batch_search_points(
     )
     .await;
 
-    process_response(response, timing)
+    process_search_response(response, timing)
 }
 
 #[post("/collections/{name}/points/search/groups")]
@@ -69,12 +73,33 @@ async fn search_point_groups(
     )
     .await;
 
-    process_response(response, timing)
+    process_search_response(response, timing)
+}
+
+#[post("/collections/{name}/points/search/fusion")]
+async fn search_fusion(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<FusionRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = do_search_fusion(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        params.consistency,
+    )
+    .await;
+
+    process_search_response(response, timing)
 }
 
 // Configure services
 pub fn config_search_api(cfg: &mut web::ServiceConfig) {
     cfg.service(search_points)
         .service(batch_search_points)
-        .service(search_point_groups);
+        .service(search_point_groups)
+        .service(search_fusion);
 }
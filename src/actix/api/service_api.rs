@@ -10,6 +10,7 @@ use storage::content_manager::toc::TableOfContent;
 use tokio::sync::Mutex;
 
 use crate::actix::helpers::process_response;
+use crate::common::auth::{check_global_access, AccessLevel};
 use crate::common::helpers::LocksOption;
 use crate::common::metrics::MetricsData;
 use crate::common::telemetry::TelemetryCollector;
@@ -26,6 +27,9 @@ async fn telemetry(
     params: Query<TelemetryParam>,
 ) -> impl Responder {
     let timing = Instant::now();
+    if let Err(err) = check_global_access(AccessLevel::Read) {
+        return process_response::<()>(Err(err), timing);
+    }
     let anonymize = params.anonymize.unwrap_or(false);
     let details_level = params.details_level.unwrap_or(0);
     let telemetry_collector = telemetry_collector.lock().await;
@@ -48,6 +52,9 @@ async fn metrics(
     telemetry_collector: web::Data<Mutex<TelemetryCollector>>,
     params: Query<MetricsParam>,
 ) -> impl Responder {
+    if let Err(err) = check_global_access(AccessLevel::Read) {
+        return process_response::<()>(Err(err), Instant::now());
+    }
     let anonymize = params.anonymize.unwrap_or(false);
     let telemetry_collector = telemetry_collector.lock().await;
     let telemetry_data = telemetry_collector.prepare_data(1).await;
@@ -68,6 +75,9 @@ async fn put_locks(
     locks_option: Json<LocksOption>,
 ) -> impl Responder {
     let timing = Instant::now();
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
     let result = LocksOption {
         write: toc.get_ref().is_write_locked(),
         error_message: toc.get_ref().get_lock_error_message(),
@@ -23,6 +23,7 @@ use super::CollectionPath;
 use crate::actix::helpers::{
     accepted_response, collection_into_actix_error, process_response, storage_into_actix_error,
 };
+use crate::common::auth::{check_collection_access, check_global_access, AccessLevel};
 use crate::common::collections::*;
 
 #[derive(Deserialize, Validate)]
@@ -50,6 +51,7 @@ pub struct SnapshottingForm {
 
 // Actix specific code
 pub async fn do_get_full_snapshot(toc: &TableOfContent, snapshot_name: &str) -> Result<NamedFile> {
+    check_global_access(AccessLevel::Manage).map_err(storage_into_actix_error)?;
     let file_name = get_full_snapshot_path(toc, snapshot_name)
         .await
         .map_err(storage_into_actix_error)?;
@@ -62,6 +64,7 @@ pub async fn do_save_uploaded_snapshot(
     collection_name: &str,
     snapshot: TempFile,
 ) -> std::result::Result<Url, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
     let filename = snapshot
         .file_name
         .unwrap_or_else(|| Uuid::new_v4().to_string());
@@ -96,6 +99,8 @@ pub async fn do_get_snapshot(
     collection_name: &str,
     snapshot_name: &str,
 ) -> Result<NamedFile> {
+    check_collection_access(collection_name, AccessLevel::Manage)
+        .map_err(storage_into_actix_error)?;
     let collection = toc
         .get_collection(collection_name)
         .await
@@ -183,6 +188,10 @@ async fn recover_from_snapshot(
     let snapshot_recover = request.into_inner();
     let wait = params.wait.unwrap_or(true);
 
+    if let Err(err) = check_collection_access(&collection.name, AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
+
     let response = do_recover_from_snapshot(
         dispatcher.get_ref(),
         &collection.name,
@@ -208,6 +217,9 @@ async fn get_snapshot(
 #[get("/snapshots")]
 async fn list_full_snapshots(toc: web::Data<TableOfContent>) -> impl Responder {
     let timing = Instant::now();
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
     let response = do_list_full_snapshots(toc.get_ref()).await;
     process_response(response, timing)
 }
@@ -219,6 +231,9 @@ async fn create_full_snapshot(
 ) -> impl Responder {
     let timing = Instant::now();
     let wait = params.wait.unwrap_or(true);
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
     let response = do_create_full_snapshot(dispatcher.get_ref(), wait).await;
     match response {
         Err(_) => process_response(response, timing),
@@ -245,6 +260,9 @@ async fn delete_full_snapshot(
     let snapshot_name = path.into_inner();
     let timing = Instant::now();
     let wait = params.wait.unwrap_or(true);
+    if let Err(err) = check_global_access(AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
     let response = do_delete_full_snapshot(dispatcher.get_ref(), &snapshot_name, wait).await;
     match response {
         Err(_) => process_response(response, timing),
@@ -262,6 +280,9 @@ async fn delete_collection_snapshot(
     let (collection_name, snapshot_name) = path.into_inner();
     let timing = Instant::now();
     let wait = params.wait.unwrap_or(true);
+    if let Err(err) = check_collection_access(&collection_name, AccessLevel::Manage) {
+        return process_response::<()>(Err(err), timing);
+    }
     let response =
         do_delete_collection_snapshot(dispatcher.get_ref(), &collection_name, &snapshot_name, wait)
             .await;
@@ -1,20 +1,24 @@
 use actix_web::rt::time::Instant;
 use actix_web::{delete, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
+use collection::common::update_context::{with_update_context, UpdateContext};
+use collection::operations::consistency_params::WriteConsistency;
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
 use schemars::JsonSchema;
+use segment::types::SeqNumberType;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::toc::TableOfContent;
+use uuid::Uuid;
 use validator::Validate;
 
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
 use crate::common::points::{
-    do_clear_payload, do_create_index, do_delete_index, do_delete_payload, do_delete_points,
-    do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors, do_upsert_points,
-    CreateFieldIndex,
+    do_cancel_index_build, do_clear_payload, do_create_index, do_delete_index, do_delete_payload,
+    do_delete_points, do_delete_vectors, do_overwrite_payload, do_rebuild_index, do_set_payload,
+    do_update_vectors, do_upsert_points, CreateFieldIndex,
 };
 
 #[derive(Deserialize, Validate)]
@@ -24,10 +28,31 @@ struct FieldPath {
     name: String,
 }
 
+#[derive(Deserialize, Validate)]
+struct IndexBuildPath {
+    operation_id: SeqNumberType,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct UpdateParam {
     pub wait: Option<bool>,
     pub ordering: Option<WriteOrdering>,
+    /// Client-supplied id of the operation. Retries of the operation with the same id are
+    /// applied only once
+    pub operation_id: Option<Uuid>,
+    /// Wait until the specified number of replicas of each shard applies the operation.
+    /// Implies `wait`
+    #[validate]
+    pub wait_for: Option<WriteConsistency>,
+}
+
+impl UpdateParam {
+    fn update_context(&self) -> UpdateContext {
+        UpdateContext {
+            operation_id: self.operation_id,
+            wait_for: self.wait_for,
+        }
+    }
 }
 
 #[put("/collections/{name}/points")]
@@ -42,13 +67,16 @@ async fn upsert_points(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_upsert_points(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_upsert_points(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -66,13 +94,16 @@ async fn delete_points(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_delete_points(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_delete_points(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -90,13 +121,16 @@ async fn update_vectors(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_update_vectors(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_update_vectors(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -114,13 +148,16 @@ async fn delete_vectors(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_delete_vectors(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_delete_vectors(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -138,13 +175,16 @@ async fn set_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_set_payload(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_set_payload(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -162,13 +202,16 @@ async fn overwrite_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_overwrite_payload(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_overwrite_payload(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -186,13 +229,16 @@ async fn delete_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_delete_payload(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_delete_payload(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -210,13 +256,16 @@ async fn clear_payload(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_clear_payload(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_clear_payload(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -234,13 +283,16 @@ async fn create_field_index(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_create_index(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_create_index(
+            toc.get_ref(),
+            &collection.name,
+            operation,
+            None,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -257,13 +309,67 @@ async fn delete_field_index(
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_delete_index(
-        toc.get_ref(),
-        &collection.name,
-        field.name.clone(),
-        None,
-        wait,
-        ordering,
+    let response = with_update_context(
+        params.update_context(),
+        do_delete_index(
+            toc.get_ref(),
+            &collection.name,
+            field.name.clone(),
+            None,
+            wait,
+            ordering,
+        ),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/index/{field_name}/rebuild")]
+async fn rebuild_field_index(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    field: Path<FieldPath>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = with_update_context(
+        params.update_context(),
+        do_rebuild_index(
+            toc.get_ref(),
+            &collection.name,
+            field.name.clone(),
+            None,
+            wait,
+            ordering,
+        ),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/index/builds/{operation_id}/cancel")]
+async fn cancel_field_index_build(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    build: Path<IndexBuildPath>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = with_update_context(
+        params.update_context(),
+        do_cancel_index_build(
+            toc.get_ref(),
+            &collection.name,
+            build.operation_id,
+            wait,
+            ordering,
+        ),
     )
     .await;
     process_response(response, timing)
@@ -280,5 +386,7 @@ pub fn config_update_api(cfg: &mut web::ServiceConfig) {
         .service(delete_payload)
         .service(clear_payload)
         .service(create_field_index)
-        .service(delete_field_index);
+        .service(delete_field_index)
+        .service(rebuild_field_index)
+        .service(cancel_field_index_build);
 }
@@ -3,18 +3,19 @@ use std::future::{ready, Ready};
 use actix_web::body::{BoxBody, EitherBody};
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
 use actix_web::{Error, HttpResponse};
-use constant_time_eq::constant_time_eq;
 use futures_util::future::LocalBoxFuture;
 
+use crate::common::auth::{with_claims, ApiKeys};
+
 pub struct ApiKey {
-    api_key: String,
+    api_keys: ApiKeys,
     skip_prefixes: Vec<String>,
 }
 
 impl ApiKey {
-    pub fn new(api_key: &str, skip_prefixes: Vec<String>) -> Self {
+    pub fn new(api_keys: ApiKeys, skip_prefixes: Vec<String>) -> Self {
         Self {
-            api_key: api_key.to_string(),
+            api_keys,
             skip_prefixes,
         }
     }
@@ -34,7 +35,7 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(ApiKeyMiddleware {
-            api_key: self.api_key.clone(),
+            api_keys: self.api_keys.clone(),
             skip_prefixes: self.skip_prefixes.clone(),
             service,
         }))
@@ -42,7 +43,7 @@ where
 }
 
 pub struct ApiKeyMiddleware<S> {
-    api_key: String,
+    api_keys: ApiKeys,
     skip_prefixes: Vec<String>,
     service: S,
 }
@@ -68,18 +69,21 @@ where
             return Box::pin(self.service.call(req));
         }
 
-        if let Some(key) = req.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    return Box::pin(self.service.call(req));
-                }
-            }
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        match self
+            .api_keys
+            .authorize(header("api-key"), header("authorization"))
+        {
+            Ok(claims) => Box::pin(with_claims(claims, self.service.call(req))),
+            Err(description) => Box::pin(async {
+                Ok(req
+                    .into_response(HttpResponse::Forbidden().body(description))
+                    .map_into_right_body())
+            }),
         }
-
-        Box::pin(async {
-            Ok(req
-                .into_response(HttpResponse::Forbidden().body("Invalid api-key"))
-                .map_into_right_body())
-        })
     }
 }
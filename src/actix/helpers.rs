@@ -1,12 +1,16 @@
 use std::fmt::Debug;
 
+use actix_web::http::header;
 use actix_web::rt::time::Instant;
 use actix_web::{error, Error, HttpResponse};
 use api::grpc::models::{ApiResponse, ApiStatus};
+use collection::common::request_warnings::take_request_warnings;
 use collection::operations::types::CollectionError;
 use serde::Serialize;
 use storage::content_manager::errors::StorageError;
 
+use crate::common::points::SearchReport;
+
 pub fn collection_into_actix_error(err: CollectionError) -> Error {
     let storage_error: StorageError = err.into();
     storage_into_actix_error(storage_error)
@@ -19,6 +23,13 @@ pub fn storage_into_actix_error(err: StorageError) -> Error {
         StorageError::ServiceError { .. } => error::ErrorInternalServerError(format!("{err}")),
         StorageError::BadRequest { .. } => error::ErrorBadRequest(format!("{err}")),
         StorageError::Locked { .. } => error::ErrorForbidden(format!("{err}")),
+        StorageError::Forbidden { .. } => error::ErrorForbidden(format!("{err}")),
+        StorageError::UnderReplicated { .. } => error::ErrorGatewayTimeout(format!("{err}")),
+        StorageError::RateLimited { .. } => error::ErrorTooManyRequests(format!("{err}")),
+        StorageError::WithReason { reason, .. } if reason.is_not_found() => {
+            error::ErrorNotFound(format!("{err}"))
+        }
+        StorageError::WithReason { .. } => error::ErrorBadRequest(format!("{err}")),
     }
 }
 
@@ -27,6 +38,12 @@ pub fn accepted_response(timing: Instant) -> HttpResponse {
         result: None,
         status: ApiStatus::Accepted,
         time: timing.elapsed().as_secs_f64(),
+        skipped_unindexed_points: None,
+        debug: None,
+        usage: None,
+        partial: None,
+        trace: None,
+        warnings: None,
     })
 }
 
@@ -39,9 +56,24 @@ where
             result: Some(res),
             status: ApiStatus::Ok,
             time: timing.elapsed().as_secs_f64(),
+            skipped_unindexed_points: None,
+            debug: None,
+            usage: None,
+            partial: None,
+            trace: None,
+            warnings: take_request_warnings(),
         }),
         Err(err) => {
             let error_description = format!("{err}");
+            let status = match err.reason() {
+                Some(reason) => ApiStatus::ErrorWithReason {
+                    error: error_description,
+                    reason: reason.as_str().to_string(),
+                },
+                None => ApiStatus::Error(error_description),
+            };
+
+            let retry_after_secs = err.retry_after_secs();
 
             let mut resp = match err {
                 StorageError::BadInput { .. } => HttpResponse::BadRequest(),
@@ -58,13 +90,54 @@ where
                 }
                 StorageError::BadRequest { .. } => HttpResponse::BadRequest(),
                 StorageError::Locked { .. } => HttpResponse::Forbidden(),
+                StorageError::Forbidden { .. } => HttpResponse::Forbidden(),
+                StorageError::UnderReplicated { .. } => HttpResponse::GatewayTimeout(),
+                StorageError::RateLimited { .. } => HttpResponse::TooManyRequests(),
+                StorageError::WithReason { reason, .. } if reason.is_not_found() => {
+                    HttpResponse::NotFound()
+                }
+                StorageError::WithReason { .. } => HttpResponse::BadRequest(),
             };
 
+            if let Some(retry_after_secs) = retry_after_secs {
+                resp.insert_header((header::RETRY_AFTER, retry_after_secs));
+            }
+
             resp.json(ApiResponse::<()> {
                 result: None,
-                status: ApiStatus::Error(error_description),
+                status,
                 time: timing.elapsed().as_secs_f64(),
+                skipped_unindexed_points: None,
+                debug: None,
+                usage: None,
+                partial: None,
+                trace: None,
+                warnings: None,
             })
         }
     }
 }
+
+/// Same as [`process_response`], but also includes the report of the search execution
+pub fn process_search_response<D>(
+    response: Result<(D, SearchReport), StorageError>,
+    timing: Instant,
+) -> HttpResponse
+where
+    D: Serialize + Debug,
+{
+    match response {
+        Ok((res, report)) => HttpResponse::Ok().json(ApiResponse {
+            result: Some(res),
+            status: ApiStatus::Ok,
+            time: timing.elapsed().as_secs_f64(),
+            skipped_unindexed_points: report.skipped_unindexed_points,
+            debug: report.debug,
+            usage: report.usage,
+            partial: report.partial,
+            trace: report.trace,
+            warnings: take_request_warnings(),
+        }),
+        Err(err) => process_response::<()>(Err(err), timing),
+    }
+}
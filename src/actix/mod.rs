@@ -14,21 +14,27 @@ use ::api::grpc::models::{ApiResponse, ApiStatus, VersionInfo};
 use actix_cors::Cors;
 use actix_multipart::form::tempfile::TempFileConfig;
 use actix_multipart::form::MultipartFormConfig;
+use actix_web::dev::Service;
 use actix_web::middleware::{Compress, Condition, Logger};
 use actix_web::{error, get, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use collection::common::request_warnings::{with_request_warnings, RequestWarningsCell};
 use collection::operations::validation;
 use storage::dispatcher::Dispatcher;
 
 use crate::actix::api::cluster_api::config_cluster_api;
 use crate::actix::api::collections_api::config_collections_api;
 use crate::actix::api::count_api::count_points;
+use crate::actix::api::query_api::config_query_api;
 use crate::actix::api::recommend_api::config_recommend_api;
-use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points};
+use crate::actix::api::retrieve_api::{
+    check_filter, get_point, get_points, scroll_point_groups, scroll_points,
+};
 use crate::actix::api::search_api::config_search_api;
 use crate::actix::api::service_api::config_service_api;
 use crate::actix::api::snapshot_api::config_snapshots_api;
 use crate::actix::api::update_api::config_update_api;
 use crate::actix::api_key::ApiKey;
+use crate::common::auth::ApiKeys;
 use crate::common::telemetry::TelemetryCollector;
 use crate::settings::{max_web_workers, Settings};
 
@@ -55,7 +61,7 @@ pub fn init(
             .actix_telemetry_collector
             .clone();
         let telemetry_collector_data = web::Data::from(telemetry_collector);
-        let api_key = settings.service.api_key.clone();
+        let api_keys = ApiKeys::from_config(&settings.service);
         let static_folder = settings
             .service
             .static_content_dir
@@ -102,12 +108,16 @@ pub fn init(
 
             let mut app = App::new()
                 .wrap(Compress::default()) // Reads the `Accept-Encoding` header to negotiate which compression codec to use.
+                // Warnings about the request, reported by collections, are attached to its response
+                .wrap_fn(|req, srv| {
+                    with_request_warnings(Arc::new(RequestWarningsCell::new()), srv.call(req))
+                })
                 // api_key middleware
                 // note: the last call to `wrap()` or `wrap_fn()` is executed first
                 .wrap(Condition::new(
-                    api_key.is_some(),
+                    api_keys.is_some(),
                     ApiKey::new(
-                        &api_key.clone().unwrap_or_default(),
+                        api_keys.clone().unwrap_or_default(),
                         skip_api_key_prefixes.clone(),
                     ),
                 ))
@@ -134,10 +144,13 @@ pub fn init(
                 .configure(config_service_api)
                 .configure(config_search_api)
                 .configure(config_recommend_api)
+                .configure(config_query_api)
                 .service(get_point)
                 .service(get_points)
                 .service(scroll_points)
-                .service(count_points);
+                .service(scroll_point_groups)
+                .service(count_points)
+                .service(check_filter);
 
             if web_ui_available {
                 app = app.service(
@@ -214,6 +227,12 @@ fn validation_error_handler(
         result: None,
         status: ApiStatus::Error(msg),
         time: 0.0,
+        skipped_unindexed_points: None,
+        debug: None,
+        usage: None,
+        partial: None,
+        trace: None,
+        warnings: None,
     });
     error::InternalError::from_response(err, response).into()
 }
@@ -0,0 +1,436 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use collection::common::point_scope::with_point_scope;
+use constant_time_eq::constant_time_eq;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use segment::types::Filter;
+use serde::{Deserialize, Serialize};
+use storage::content_manager::collection_meta_ops::{AliasOperations, CollectionMetaOperations};
+use storage::content_manager::errors::StorageError;
+
+use crate::settings::ServiceConfig;
+
+/// API key, which only gives access to the points, matching the payload filter of the key
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScopedApiKey {
+    pub key: String,
+    /// Requests with the key only read and modify the points, matching the filter.
+    /// Points with payload out of the filter can't be written with the key either
+    pub payload_filter: Filter,
+}
+
+/// Operations, allowed on a collection. Each level includes the previous ones
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    /// Search and retrieval of the points, collection info
+    Read,
+    /// Modification of the points and their payload
+    Write,
+    /// Creation, update and deletion of the collections, payload indexes and snapshots
+    Manage,
+}
+
+impl fmt::Display for AccessLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessLevel::Read => write!(f, "read"),
+            AccessLevel::Write => write!(f, "write"),
+            AccessLevel::Manage => write!(f, "manage"),
+        }
+    }
+}
+
+/// Collections and operations, available to a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+    pub level: AccessLevel,
+    /// Collections, available to the request. `None` - all collections
+    pub collections: Option<Vec<String>>,
+}
+
+impl Default for Access {
+    fn default() -> Self {
+        Self {
+            level: AccessLevel::Manage,
+            collections: None,
+        }
+    }
+}
+
+impl Access {
+    pub fn is_collection_visible(&self, collection_name: &str) -> bool {
+        self.collections.as_ref().map_or(true, |collections| {
+            collections.iter().any(|name| name == collection_name)
+        })
+    }
+
+    pub fn check_collection(
+        &self,
+        collection_name: &str,
+        level: AccessLevel,
+    ) -> Result<(), StorageError> {
+        if !self.is_collection_visible(collection_name) {
+            return Err(StorageError::Forbidden {
+                description: format!("No access to collection {collection_name}"),
+            });
+        }
+        if self.level < level {
+            return Err(StorageError::Forbidden {
+                description: format!(
+                    "{level} access to collection {collection_name} is required, got {}",
+                    self.level,
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check access to the operations, not limited to a single collection,
+    /// like the full snapshots or the cluster management
+    pub fn check_global(&self, level: AccessLevel) -> Result<(), StorageError> {
+        if self.collections.is_some() {
+            return Err(StorageError::Forbidden {
+                description: "Access to all collections is required".to_string(),
+            });
+        }
+        if self.level < level {
+            return Err(StorageError::Forbidden {
+                description: format!("{level} access is required, got {}", self.level),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Access of a request, resolved from the credentials of the client
+#[derive(Debug, Clone, Default)]
+pub struct Claims {
+    pub access: Access,
+    /// Points, the request has access to. `None` - all points
+    pub payload_filter: Option<Arc<Filter>>,
+}
+
+/// Claims of the JWT tokens, accepted in the `Authorization: Bearer <token>` header
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TokenClaims {
+    /// Expiration time of the token, as a unix timestamp in seconds
+    pub exp: u64,
+    pub access: AccessLevel,
+    /// Collections, the token gives access to. All collections if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<String>>,
+}
+
+#[derive(Clone)]
+struct JwtValidator {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtValidator {
+    fn new(secret: &str, audience: Option<&str>) -> Self {
+        // Expiration is required and checked by default
+        let mut validation = Validation::new(Algorithm::HS256);
+        match audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        Self {
+            key: DecodingKey::from_secret(secret.as_bytes()),
+            validation,
+        }
+    }
+
+    fn claims(&self, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+        let TokenClaims {
+            access,
+            collections,
+            ..
+        } = jsonwebtoken::decode::<TokenClaims>(token, &self.key, &self.validation)?.claims;
+        Ok(Claims {
+            access: Access {
+                level: access,
+                collections,
+            },
+            payload_filter: None,
+        })
+    }
+}
+
+/// Credentials, accepted by the REST and gRPC API
+#[derive(Clone, Default)]
+pub struct ApiKeys {
+    api_key: Option<String>,
+    scoped_api_keys: Vec<(String, Arc<Filter>)>,
+    jwt: Option<JwtValidator>,
+}
+
+impl ApiKeys {
+    /// Credentials of the service config.
+    /// `None` if none are configured, and the requests are not checked
+    pub fn from_config(config: &ServiceConfig) -> Option<Self> {
+        if config.api_key.is_none()
+            && config.scoped_api_keys.is_empty()
+            && config.jwt_secret.is_none()
+        {
+            return None;
+        }
+        Some(Self {
+            api_key: config.api_key.clone(),
+            scoped_api_keys: config
+                .scoped_api_keys
+                .iter()
+                .map(|scoped| (scoped.key.clone(), Arc::new(scoped.payload_filter.clone())))
+                .collect(),
+            jwt: config
+                .jwt_secret
+                .as_deref()
+                .map(|secret| JwtValidator::new(secret, config.jwt_audience.as_deref())),
+        })
+    }
+
+    /// Access, given by the key. `None` if the key is not valid
+    pub fn claims(&self, key: &str) -> Option<Claims> {
+        if let Some(api_key) = &self.api_key {
+            if constant_time_eq(api_key.as_bytes(), key.as_bytes()) {
+                return Some(Claims::default());
+            }
+        }
+        // Scoped keys can't manage collections, as it would give access to all the points,
+        // e.g. through the snapshots
+        self.scoped_api_keys
+            .iter()
+            .find(|(scoped_key, _)| constant_time_eq(scoped_key.as_bytes(), key.as_bytes()))
+            .map(|(_, payload_filter)| Claims {
+                access: Access {
+                    level: AccessLevel::Write,
+                    collections: None,
+                },
+                payload_filter: Some(payload_filter.clone()),
+            })
+    }
+
+    /// Access of a request with the given `api-key` and `authorization` headers.
+    /// Error describes why the request is rejected
+    pub fn authorize(
+        &self,
+        api_key: Option<&str>,
+        authorization: Option<&str>,
+    ) -> Result<Claims, String> {
+        if let Some(claims) = api_key.and_then(|key| self.claims(key)) {
+            return Ok(claims);
+        }
+        let token = authorization.and_then(|value| value.strip_prefix("Bearer "));
+        match (&self.jwt, token) {
+            (Some(jwt), Some(token)) => jwt
+                .claims(token)
+                .map_err(|err| format!("Invalid JWT token: {err}")),
+            _ => Err("Invalid api-key".to_string()),
+        }
+    }
+}
+
+tokio::task_local! {
+    static ACCESS: Access;
+}
+
+/// Run `future` with the access of the request
+pub async fn with_claims<F: Future>(claims: Claims, future: F) -> F::Output {
+    ACCESS
+        .scope(
+            claims.access,
+            with_point_scope(claims.payload_filter, future),
+        )
+        .await
+}
+
+/// Check the access of the current request to the collection.
+/// Requests without credentials, e.g. the internal ones between the peers, are not restricted
+pub fn check_collection_access(
+    collection_name: &str,
+    level: AccessLevel,
+) -> Result<(), StorageError> {
+    ACCESS
+        .try_with(|access| access.check_collection(collection_name, level))
+        .unwrap_or(Ok(()))
+}
+
+/// Check the access of the current request to the operations, not limited to a collection
+pub fn check_global_access(level: AccessLevel) -> Result<(), StorageError> {
+    ACCESS
+        .try_with(|access| access.check_global(level))
+        .unwrap_or(Ok(()))
+}
+
+/// If the collection is listed to the current request
+pub fn is_collection_visible(collection_name: &str) -> bool {
+    ACCESS
+        .try_with(|access| access.is_collection_visible(collection_name))
+        .unwrap_or(true)
+}
+
+pub fn check_meta_operation_access(
+    operation: &CollectionMetaOperations,
+) -> Result<(), StorageError> {
+    let manage = |name: &str| check_collection_access(name, AccessLevel::Manage);
+    match operation {
+        CollectionMetaOperations::CreateCollection(create) => {
+            manage(&create.collection_name)?;
+            if let Some(init_from) = &create.create_collection.init_from {
+                check_collection_access(&init_from.collection, AccessLevel::Read)?;
+            }
+            Ok(())
+        }
+        CollectionMetaOperations::UpdateCollection(update) => manage(&update.collection_name),
+        CollectionMetaOperations::DeleteCollection(delete) => manage(&delete.0),
+        CollectionMetaOperations::ChangeAliases(change) => {
+            change.actions.iter().try_for_each(|action| match action {
+                AliasOperations::CreateAlias(create) => {
+                    manage(&create.create_alias.collection_name)?;
+                    manage(&create.create_alias.alias_name)
+                }
+                AliasOperations::DeleteAlias(delete) => manage(&delete.delete_alias.alias_name),
+                AliasOperations::RenameAlias(rename) => {
+                    manage(&rename.rename_alias.old_alias_name)?;
+                    manage(&rename.rename_alias.new_alias_name)
+                }
+            })
+        }
+        CollectionMetaOperations::TransferShard(collection_name, _) => manage(collection_name),
+        CollectionMetaOperations::SetShardReplicaState(state) => manage(&state.collection_name),
+        CollectionMetaOperations::Nop { .. } => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use storage::content_manager::collection_meta_ops::DeleteCollectionOperation;
+
+    use super::*;
+
+    const SECRET: &str = "secret";
+
+    fn api_keys(audience: Option<&str>) -> ApiKeys {
+        ApiKeys {
+            api_key: Some("master".to_string()),
+            scoped_api_keys: vec![],
+            jwt: Some(JwtValidator::new(SECRET, audience)),
+        }
+    }
+
+    fn token(claims: serde_json::Value) -> String {
+        let key = EncodingKey::from_secret(SECRET.as_bytes());
+        jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap()
+    }
+
+    fn bearer(token: &str) -> String {
+        format!("Bearer {token}")
+    }
+
+    fn exp_in(seconds: i64) -> i64 {
+        Utc::now().timestamp() + seconds
+    }
+
+    #[test]
+    fn test_static_api_key() {
+        let keys = api_keys(None);
+        let claims = keys.authorize(Some("master"), None).unwrap();
+        assert_eq!(claims.access, Access::default());
+        assert!(claims.payload_filter.is_none());
+
+        assert!(keys.authorize(Some("wrong"), None).is_err());
+        assert!(keys.authorize(None, None).is_err());
+    }
+
+    #[test]
+    fn test_valid_token() {
+        let keys = api_keys(Some("qdrant"));
+        let token = token(json!({
+            "exp": exp_in(3600),
+            "aud": "qdrant",
+            "access": "write",
+            "collections": ["docs"],
+        }));
+        let claims = keys.authorize(None, Some(&bearer(&token))).unwrap();
+        assert_eq!(claims.access.level, AccessLevel::Write);
+
+        let access = claims.access;
+        assert!(access.check_collection("docs", AccessLevel::Read).is_ok());
+        assert!(access.check_collection("docs", AccessLevel::Write).is_ok());
+        assert!(access
+            .check_collection("docs", AccessLevel::Manage)
+            .is_err());
+        assert!(access.check_global(AccessLevel::Read).is_err());
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let keys = api_keys(None);
+        let token = token(json!({"exp": exp_in(-3600), "access": "manage"}));
+        let err = keys.authorize(None, Some(&bearer(&token))).unwrap_err();
+        assert!(err.contains("ExpiredSignature"), "{err}");
+
+        // Expiration is required
+        let token = token(json!({"access": "manage"}));
+        assert!(keys.authorize(None, Some(&bearer(&token))).is_err());
+    }
+
+    #[test]
+    fn test_wrong_audience() {
+        let keys = api_keys(Some("qdrant"));
+        let token = token(json!({"exp": exp_in(3600), "aud": "other", "access": "read"}));
+        let err = keys.authorize(None, Some(&bearer(&token))).unwrap_err();
+        assert!(err.contains("InvalidAudience"), "{err}");
+    }
+
+    #[test]
+    fn test_wrong_secret() {
+        let keys = api_keys(None);
+        let key = EncodingKey::from_secret(b"another secret");
+        let claims = json!({"exp": exp_in(3600), "access": "manage"});
+        let token = jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap();
+        assert!(keys.authorize(None, Some(&bearer(&token))).is_err());
+    }
+
+    #[test]
+    fn test_collection_mismatch() {
+        let keys = api_keys(None);
+        let token = token(json!({
+            "exp": exp_in(3600),
+            "access": "manage",
+            "collections": ["docs"],
+        }));
+        let access = keys.authorize(None, Some(&bearer(&token))).unwrap().access;
+        assert!(access.check_collection("docs", AccessLevel::Manage).is_ok());
+        assert!(access
+            .check_collection("images", AccessLevel::Read)
+            .is_err());
+        assert!(!access.is_collection_visible("images"));
+    }
+
+    #[tokio::test]
+    async fn test_meta_operation_access() {
+        let claims = Claims {
+            access: Access {
+                level: AccessLevel::Write,
+                collections: None,
+            },
+            payload_filter: None,
+        };
+        let delete = CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(
+            "docs".to_string(),
+        ));
+
+        let result = with_claims(claims, async { check_meta_operation_access(&delete) }).await;
+        assert!(matches!(result, Err(StorageError::Forbidden { .. })));
+
+        // Requests without credentials are not restricted
+        assert!(check_meta_operation_access(&delete).is_ok());
+    }
+}
@@ -7,12 +7,23 @@ use collection::operations::cluster_ops::{
 };
 use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
+    AliasDescription, CollectionClusterInfo, CollectionFlushResult, CollectionInfo,
+    CollectionStatus, CollectionsAliasesResponse, OptimizersPlan, ShardIndexRebuildProgress,
+    ShardSegmentsInfo, WaitForStatusResult,
 };
+use collection::shards::balancer::RebalancingPlan;
+use collection::shards::checksum::{CollectionChecksum, ShardChecksum};
 use collection::shards::replica_set;
 use collection::shards::shard::ShardId;
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
+use collection::slow_log::SlowLogEntry;
+use collection::warmup::WarmupRequest;
 use itertools::Itertools;
+use storage::content_manager::collection_clone::{CloneCollection, CollectionCloneStatus};
+use storage::content_manager::collection_config_document::{
+    apply_collection_config, export_collection_config, ApplyCollectionConfigResult,
+    CollectionConfigDocument,
+};
 use storage::content_manager::collection_meta_ops::ShardTransferOperations::{Abort, Start};
 use storage::content_manager::collection_meta_ops::{
     CollectionMetaOperations, UpdateCollectionOperation,
@@ -21,20 +32,80 @@ use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 
+use crate::common::auth::{
+    check_collection_access, check_meta_operation_access, is_collection_visible, AccessLevel,
+};
+
 pub async fn do_get_collection(
     toc: &TableOfContent,
     name: &str,
     shard_selection: Option<ShardId>,
 ) -> Result<CollectionInfo, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
     let collection = toc.get_collection(name).await?;
     Ok(collection.info(shard_selection).await?)
 }
 
+/// Create a clone of the `source` collection and start copying the points into it
+pub async fn do_clone_collection(
+    dispatcher: &Dispatcher,
+    source: &str,
+    operation: CloneCollection,
+    wait_timeout: Option<Duration>,
+) -> Result<CollectionCloneStatus, StorageError> {
+    check_collection_access(source, AccessLevel::Read)?;
+    check_collection_access(&operation.target, AccessLevel::Manage)?;
+    let toc = dispatcher.toc();
+    let target = operation.target.clone();
+    let create_collection = toc.prepare_collection_clone(source, operation).await?;
+    dispatcher
+        .submit_collection_meta_op(
+            CollectionMetaOperations::CreateCollection(create_collection),
+            wait_timeout,
+        )
+        .await?;
+    toc.start_collection_clone(source, &target).await
+}
+
+pub async fn do_export_collection_config(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionConfigDocument, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    export_collection_config(toc, name).await
+}
+
+/// Create or update the collection of the document, its payload indexes and aliases
+pub async fn do_apply_collection_config(
+    dispatcher: &Dispatcher,
+    document: CollectionConfigDocument,
+    wait_timeout: Option<Duration>,
+) -> Result<ApplyCollectionConfigResult, StorageError> {
+    check_collection_access(&document.collection_name, AccessLevel::Manage)?;
+    for alias in &document.aliases {
+        check_collection_access(alias, AccessLevel::Manage)?;
+    }
+    apply_collection_config(dispatcher, document, wait_timeout).await
+}
+
+/// Submit the operation, if the request is allowed to manage the involved collections
+pub async fn do_submit_collection_meta_op(
+    dispatcher: &Dispatcher,
+    operation: CollectionMetaOperations,
+    wait_timeout: Option<Duration>,
+) -> Result<bool, StorageError> {
+    check_meta_operation_access(&operation)?;
+    dispatcher
+        .submit_collection_meta_op(operation, wait_timeout)
+        .await
+}
+
 pub async fn do_list_collections(toc: &TableOfContent) -> CollectionsResponse {
     let collections = toc
         .all_collections()
         .await
         .into_iter()
+        .filter(|name| is_collection_visible(name))
         .map(|name| CollectionDescription { name })
         .collect_vec();
 
@@ -45,6 +116,7 @@ pub async fn do_list_collection_aliases(
     toc: &TableOfContent,
     collection_name: &str,
 ) -> Result<CollectionsAliasesResponse, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     let mut aliases: Vec<AliasDescription> = Default::default();
     for alias in toc.collection_aliases(collection_name).await? {
         aliases.push(AliasDescription {
@@ -58,7 +130,12 @@ pub async fn do_list_collection_aliases(
 pub async fn do_list_aliases(
     toc: &TableOfContent,
 ) -> Result<CollectionsAliasesResponse, StorageError> {
-    let aliases = toc.list_aliases().await?;
+    let aliases = toc
+        .list_aliases()
+        .await?
+        .into_iter()
+        .filter(|alias| is_collection_visible(&alias.collection_name))
+        .collect();
     Ok(CollectionsAliasesResponse { aliases })
 }
 
@@ -66,6 +143,7 @@ pub async fn do_list_snapshots(
     toc: &TableOfContent,
     collection_name: &str,
 ) -> Result<Vec<SnapshotDescription>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
     Ok(toc
         .get_collection(collection_name)
         .await?
@@ -78,6 +156,7 @@ pub async fn do_create_snapshot(
     collection_name: &str,
     wait: bool,
 ) -> Result<SnapshotDescription, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
     let collection = collection_name.to_string();
     let dispatcher = dispatcher.clone();
     let snapshot = tokio::spawn(async move { dispatcher.create_snapshot(&collection).await });
@@ -96,10 +175,122 @@ pub async fn do_get_collection_cluster(
     toc: &TableOfContent,
     name: &str,
 ) -> Result<CollectionClusterInfo, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
     let collection = toc.get_collection(name).await?;
     Ok(collection.cluster_info(toc.this_peer_id).await?)
 }
 
+pub async fn do_get_collection_rebalancing_plan(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<RebalancingPlan, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.rebalancing_plan().await?)
+}
+
+pub async fn do_get_collection_checksum(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionChecksum, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.checksum().await)
+}
+
+pub async fn do_get_shard_checksum(
+    toc: &TableOfContent,
+    name: &str,
+    shard_id: ShardId,
+) -> Result<ShardChecksum, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.local_shard_checksum(shard_id).await?)
+}
+
+pub async fn do_get_shard_segments(
+    toc: &TableOfContent,
+    name: &str,
+    shard_id: ShardId,
+) -> Result<ShardSegmentsInfo, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.local_shard_segments(shard_id).await?)
+}
+
+pub async fn do_get_index_rebuild_progress(
+    toc: &TableOfContent,
+    name: &str,
+    field_name: &str,
+) -> Result<Vec<ShardIndexRebuildProgress>, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.local_index_rebuild_progress(field_name).await?)
+}
+
+pub async fn do_trigger_optimizers(toc: &TableOfContent, name: &str) -> Result<bool, StorageError> {
+    check_collection_access(name, AccessLevel::Manage)?;
+    let collection = toc.get_collection(name).await?;
+    collection.trigger_optimizers().await?;
+    Ok(true)
+}
+
+pub async fn do_flush_collection(
+    toc: &TableOfContent,
+    name: &str,
+    wait: bool,
+) -> Result<CollectionFlushResult, StorageError> {
+    check_collection_access(name, AccessLevel::Manage)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.flush_local_shards(wait).await?)
+}
+
+pub async fn do_get_optimizers_plan(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<OptimizersPlan, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.optimizers_plan().await?)
+}
+
+pub async fn do_wait_for_collection_status(
+    toc: &TableOfContent,
+    name: &str,
+    status: CollectionStatus,
+    timeout: Duration,
+) -> Result<WaitForStatusResult, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.wait_for_status(status, timeout).await?)
+}
+
+pub async fn do_get_collection_slow_log(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<SlowLogEntry>, StorageError> {
+    check_collection_access(name, AccessLevel::Read)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.slow_log_entries())
+}
+
+pub async fn do_start_warmup(
+    toc: &TableOfContent,
+    name: &str,
+    request: WarmupRequest,
+) -> Result<bool, StorageError> {
+    check_collection_access(name, AccessLevel::Manage)?;
+    let collection = toc.get_collection(name).await?;
+    collection.start_warmup(request)?;
+    Ok(true)
+}
+
+pub async fn do_cancel_warmup(toc: &TableOfContent, name: &str) -> Result<bool, StorageError> {
+    check_collection_access(name, AccessLevel::Manage)?;
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.cancel_warmup())
+}
+
 pub async fn do_update_collection_cluster(
     toc: &TableOfContent,
     collection_name: String,
@@ -107,6 +298,7 @@ pub async fn do_update_collection_cluster(
     dispatcher: &Dispatcher,
     wait_timeout: Option<Duration>,
 ) -> Result<bool, StorageError> {
+    check_collection_access(&collection_name, AccessLevel::Manage)?;
     if dispatcher.consensus_state().is_none() {
         return Err(StorageError::BadRequest {
             description: "Distributed mode disabled".to_string(),
@@ -159,6 +351,7 @@ pub async fn do_update_collection_cluster(
                             to: move_shard.to_peer_id,
                             from: move_shard.from_peer_id,
                             sync: false,
+                            method: None,
                         }),
                     ),
                     wait_timeout,
@@ -192,6 +385,7 @@ pub async fn do_update_collection_cluster(
                             to: replicate_shard.to_peer_id,
                             from: replicate_shard.from_peer_id,
                             sync: true,
+                            method: None,
                         }),
                     ),
                     wait_timeout,
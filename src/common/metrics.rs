@@ -1,4 +1,7 @@
-use prometheus::proto::{Counter, Gauge, LabelPair, Metric, MetricFamily, MetricType};
+use collection::telemetry::{DurationHistogram, OperationsTelemetry};
+use prometheus::proto::{
+    Bucket, Counter, Gauge, Histogram, LabelPair, Metric, MetricFamily, MetricType,
+};
 use prometheus::TextEncoder;
 
 use crate::common::telemetry::TelemetryData;
@@ -114,7 +117,134 @@ impl MetricsProvider for CollectionsTelemetry {
             MetricType::GAUGE,
             vec![gauge(vector_count as f64, &[])],
         ));
+
+        let operations = self.collections.iter().flatten().map(|p| match p {
+            CollectionTelemetryEnum::Aggregated(a) => (a.id.as_str(), &a.operations),
+            CollectionTelemetryEnum::Full(c) => (c.id.as_str(), &c.operations),
+        });
+        add_operations_metrics(operations, metrics);
+    }
+}
+
+fn add_operations_metrics<'a>(
+    operations: impl Iterator<Item = (&'a str, &'a OperationsTelemetry)>,
+    metrics: &mut Vec<MetricFamily>,
+) {
+    let (
+        mut group_by_durations,
+        mut group_by_searches,
+        mut group_by_truncated,
+        mut recommend_durations,
+        mut recommend_failures,
+        mut read_queue_depth,
+        mut reads_rejected,
+        mut write_operations_fill,
+        mut writes_delayed,
+        mut writes_rejected,
+    ) = (
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    for (collection, operations) in operations {
+        let labels = [("collection", collection)];
+        let group_by = &operations.group_by;
+        group_by_durations.push(histogram(&group_by.durations, &labels));
+        group_by_searches.push(counter(group_by.internal_searches as f64, &labels));
+        group_by_truncated.push(counter(group_by.truncated_groups as f64, &labels));
+
+        let recommend = &operations.recommend;
+        recommend_durations.push(histogram(&recommend.durations, &labels));
+        recommend_failures.push(counter(
+            recommend.example_resolution_failures as f64,
+            &labels,
+        ));
+
+        let read_admission = &operations.read_admission;
+        read_queue_depth.push(gauge(read_admission.queue_depth as f64, &labels));
+        reads_rejected.push(counter(read_admission.rejected as f64, &labels));
+
+        let write_admission = &operations.write_admission;
+        if let Some(fill) = write_admission.operations_fill {
+            write_operations_fill.push(gauge(fill, &labels));
+        }
+        writes_delayed.push(counter(write_admission.delayed as f64, &labels));
+        writes_rejected.push(counter(write_admission.rejected as f64, &labels));
+    }
+
+    if group_by_durations.is_empty() {
+        return;
+    }
+
+    metrics.push(metric_family(
+        "qdrant_group_by_duration_seconds",
+        "duration of group by operations",
+        MetricType::HISTOGRAM,
+        group_by_durations,
+    ));
+    metrics.push(metric_family(
+        "qdrant_group_by_internal_searches_total",
+        "total number of internal search requests, issued by group by operations",
+        MetricType::COUNTER,
+        group_by_searches,
+    ));
+    metrics.push(metric_family(
+        "qdrant_group_by_truncated_groups_total",
+        "total number of groups, returned with less than group_size points",
+        MetricType::COUNTER,
+        group_by_truncated,
+    ));
+    metrics.push(metric_family(
+        "qdrant_recommend_duration_seconds",
+        "duration of recommend operations",
+        MetricType::HISTOGRAM,
+        recommend_durations,
+    ));
+    metrics.push(metric_family(
+        "qdrant_recommend_example_resolution_failures_total",
+        "total number of recommend requests, failed to resolve positive or negative examples",
+        MetricType::COUNTER,
+        recommend_failures,
+    ));
+    metrics.push(metric_family(
+        "qdrant_read_queue_depth",
+        "number of read operations, waiting for the concurrency limit of the collection",
+        MetricType::GAUGE,
+        read_queue_depth,
+    ));
+    metrics.push(metric_family(
+        "qdrant_read_rejected_total",
+        "total number of read operations, rejected because the queue of the collection was full",
+        MetricType::COUNTER,
+        reads_rejected,
+    ));
+    if !write_operations_fill.is_empty() {
+        metrics.push(metric_family(
+            "qdrant_write_rate_budget_fill",
+            "fraction of the burst budget of the write operations, available in the collection",
+            MetricType::GAUGE,
+            write_operations_fill,
+        ));
     }
+    metrics.push(metric_family(
+        "qdrant_write_delayed_total",
+        "total number of write operations, delayed to fit the rate limit of the collection",
+        MetricType::COUNTER,
+        writes_delayed,
+    ));
+    metrics.push(metric_family(
+        "qdrant_write_rejected_total",
+        "total number of write operations, rejected because of the rate limit of the collection",
+        MetricType::COUNTER,
+        writes_rejected,
+    ));
 }
 
 impl MetricsProvider for ClusterTelemetry {
@@ -362,6 +492,30 @@ fn gauge(value: f64, labels: &[(&str, &str)]) -> Metric {
     metric
 }
 
+fn histogram(durations: &DurationHistogram, labels: &[(&str, &str)]) -> Metric {
+    let mut metric = Metric::default();
+    metric.set_label(labels.iter().map(|(n, v)| label_pair(n, v)).collect());
+    metric.set_histogram({
+        let mut histogram = Histogram::default();
+        histogram.set_sample_count(durations.count);
+        histogram.set_sample_sum(durations.sum_seconds);
+        histogram.set_bucket(
+            durations
+                .buckets
+                .iter()
+                .map(|b| {
+                    let mut bucket = Bucket::default();
+                    bucket.set_upper_bound(b.le);
+                    bucket.set_cumulative_count(b.count);
+                    bucket
+                })
+                .collect(),
+        );
+        histogram
+    });
+    metric
+}
+
 fn label_pair(name: &str, value: &str) -> LabelPair {
     let mut label = LabelPair::default();
     label.set_name(name.into());
@@ -1,4 +1,6 @@
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod auth;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod collections;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod error_reporting;
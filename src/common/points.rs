@@ -1,23 +1,40 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use api::grpc::models::{PartialResults, ShardTrace};
+use collection::common::partial_results::{with_partial_results, PartialResultsCell};
+use collection::common::request_hw_counter::with_request_hw_counter;
+use collection::common::shard_trace::{with_shard_trace, ShardTraceCell};
+use collection::lookup::types::WithLookupInterface;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointsSelector, WriteOrdering,
 };
 use collection::operations::types::{
-    CountRequest, CountResult, GroupsResult, PointRequest, RecommendGroupsRequest, Record,
+    BaseGroupRequest, CheckFilterRequest, CountRequest, CountResult, FusedPoint, FusionRequest,
+    GroupsResult, OrderedQueryRequest, PointRequest, QueryGroupsRequest, RecommendGroupsRequest,
+    RecommendRequest, RecommendRequestBatch, Record, ScrollGroupsRequest, ScrollGroupsResult,
     ScrollRequest, ScrollResult, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
     UpdateResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors, VectorOperations};
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shards::shard::ShardId;
+use itertools::Itertools;
 use schemars::JsonSchema;
-use segment::types::{PayloadFieldSchema, ScoredPoint};
+use segment::common::hardware_counter::{HardwareCounterCell, HardwareUsage};
+use segment::data_types::filter_trace::FilterTrace;
+use segment::types::{
+    PayloadFieldSchema, ScoredPoint, SearchDebugInfo, SearchParams, SeqNumberType,
+};
 use serde::{Deserialize, Serialize};
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use validator::Validate;
 
+use crate::common::auth::{check_collection_access, AccessLevel};
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
 pub struct CreateFieldIndex {
     pub field_name: String,
@@ -33,6 +50,7 @@ pub async fn do_upsert_points(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let collection_operation =
         CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(operation));
     toc.update(
@@ -53,6 +71,7 @@ pub async fn do_delete_points(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let point_operation = match points {
         PointsSelector::PointIdsSelector(points) => {
             PointOperations::DeletePoints { ids: points.points }
@@ -80,6 +99,7 @@ pub async fn do_update_vectors(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let collection_operation =
         CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(operation));
     toc.update(
@@ -100,6 +120,7 @@ pub async fn do_delete_vectors(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let vector_names: Vec<_> = operation.vector.into_iter().collect();
 
     let mut result = None;
@@ -146,6 +167,7 @@ pub async fn do_set_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let collection_operation =
         CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(operation));
     toc.update(
@@ -166,6 +188,7 @@ pub async fn do_overwrite_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let collection_operation =
         CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(operation));
     toc.update(
@@ -186,6 +209,7 @@ pub async fn do_delete_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let collection_operation =
         CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(operation));
     toc.update(
@@ -206,6 +230,7 @@ pub async fn do_clear_payload(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Write)?;
     let points_operation = match points {
         PointsSelector::PointIdsSelector(points) => PayloadOps::ClearPayload {
             points: points.points,
@@ -234,6 +259,7 @@ pub async fn do_create_index(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
     let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
         FieldIndexOperations::CreateIndex(CreateIndex {
             field_name: operation.field_name,
@@ -258,6 +284,7 @@ pub async fn do_delete_index(
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
     let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
         FieldIndexOperations::DeleteIndex(index_name),
     );
@@ -271,15 +298,213 @@ pub async fn do_delete_index(
     .await
 }
 
+pub async fn do_rebuild_index(
+    toc: &TableOfContent,
+    collection_name: &str,
+    index_name: String,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
+    let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::RebuildIndex(index_name),
+    );
+    toc.update(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub async fn do_cancel_field_index_build(
+    toc: &TableOfContent,
+    collection_name: &str,
+    field_name: String,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
+    let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CancelIndexBuild(field_name),
+    );
+    toc.update(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+/// Cancel the creation of the field index, started by the operation with the given id
+pub async fn do_cancel_index_build(
+    toc: &TableOfContent,
+    collection_name: &str,
+    build_operation_id: SeqNumberType,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Manage)?;
+    let field_name = toc
+        .get_collection(collection_name)
+        .await?
+        .field_index_build_field(build_operation_id)
+        .await?;
+    do_cancel_field_index_build(toc, collection_name, field_name, None, wait, ordering).await
+}
+
+/// Additional information about the execution of search requests
+#[derive(Debug, Default)]
+pub struct SearchReport {
+    /// Approximate number of points skipped by `indexed_only` searches,
+    /// `None` if none of the searches is `indexed_only`
+    pub skipped_unindexed_points: Option<usize>,
+    /// Strategies, chosen by vector indexes of the local shards for the searches,
+    /// reported if any of the searches uses `adaptive_ef`
+    pub debug: Option<SearchDebugInfo>,
+    /// Work done to serve the request, reported if any of the searches sets `with_usage`
+    pub usage: Option<HardwareUsage>,
+    /// Shards, which failed to respond, reported if any of the searches sets `allow_partial_results`
+    pub partial: Option<PartialResults>,
+    /// Replicas, which served the shards, reported if any of the searches sets `with_shard_trace`
+    pub trace: Option<Vec<ShardTrace>>,
+}
+
+fn is_report_required(params: Option<&SearchParams>) -> bool {
+    params.map_or(false, |params| params.indexed_only)
+}
+
+fn is_debug_required(params: Option<&SearchParams>) -> bool {
+    params.map_or(false, |params| params.adaptive_ef.is_some())
+}
+
+async fn do_search_report(
+    toc: &TableOfContent,
+    collection_name: &str,
+    searches: &[SearchRequest],
+    shard_selection: Option<ShardId>,
+) -> Result<SearchReport, StorageError> {
+    let indexed_only_vectors: Vec<_> = searches
+        .iter()
+        .filter(|search| search.params.map_or(false, |params| params.indexed_only))
+        .map(|search| search.vector.get_name().to_string())
+        .unique()
+        .collect();
+    let skipped_unindexed_points = if indexed_only_vectors.is_empty() {
+        None
+    } else {
+        let skipped = toc
+            .indexed_only_excluded_points(collection_name, &indexed_only_vectors, shard_selection)
+            .await?;
+        Some(skipped)
+    };
+
+    Ok(SearchReport {
+        skipped_unindexed_points,
+        debug: None,
+        usage: None,
+        partial: None,
+        trace: None,
+    })
+}
+
+/// Run `future`, skipping the failed shards if partial results are allowed
+async fn serve_with_partial_results<F: Future>(
+    allow_partial_results: bool,
+    partial_results: Arc<PartialResultsCell>,
+    future: F,
+) -> F::Output {
+    if allow_partial_results {
+        with_partial_results(partial_results, future).await
+    } else {
+        future.await
+    }
+}
+
+/// Run `future`, recording the replicas, which served its shard reads, if the trace is requested
+async fn serve_with_shard_trace<F: Future>(
+    trace_shards: bool,
+    shard_trace: Arc<ShardTraceCell>,
+    future: F,
+) -> F::Output {
+    if trace_shards {
+        with_shard_trace(shard_trace, future).await
+    } else {
+        future.await
+    }
+}
+
+/// Check the access to the collection, the groups are looked up in
+fn check_lookup_access(group_request: &BaseGroupRequest) -> Result<(), StorageError> {
+    let lookup_collection = match &group_request.with_lookup {
+        Some(WithLookupInterface::Collection(collection_name)) => collection_name,
+        Some(WithLookupInterface::WithLookup(with_lookup)) => &with_lookup.collection_name,
+        None => return Ok(()),
+    };
+    check_collection_access(lookup_collection, AccessLevel::Read)
+}
+
+/// Check the access to the collection, the vectors of the examples are taken from
+fn check_lookup_from_access(request: &RecommendRequest) -> Result<(), StorageError> {
+    match &request.lookup_from {
+        Some(lookup_from) => check_collection_access(&lookup_from.collection, AccessLevel::Read),
+        None => Ok(()),
+    }
+}
+
 pub async fn do_search_points(
     toc: &TableOfContent,
     collection_name: &str,
     request: SearchRequest,
     read_consistency: Option<ReadConsistency>,
     shard_selection: Option<ShardId>,
-) -> Result<Vec<ScoredPoint>, StorageError> {
-    toc.search(collection_name, request, read_consistency, shard_selection)
-        .await
+) -> Result<(Vec<ScoredPoint>, SearchReport), StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    let report = if is_report_required(request.params.as_ref()) {
+        do_search_report(
+            toc,
+            collection_name,
+            std::slice::from_ref(&request),
+            shard_selection,
+        )
+        .await?
+    } else {
+        SearchReport::default()
+    };
+    let with_debug = is_debug_required(request.params.as_ref());
+    let with_usage = request.with_usage;
+    let allow_partial_results = request.allow_partial_results;
+    let trace_shards = request.with_shard_trace;
+    let hw_counter = Arc::new(HardwareCounterCell::new());
+    let partial_results = Arc::new(PartialResultsCell::new());
+    let shard_trace = Arc::new(ShardTraceCell::new());
+    let result = with_request_hw_counter(
+        hw_counter.clone(),
+        serve_with_partial_results(
+            allow_partial_results,
+            partial_results.clone(),
+            serve_with_shard_trace(
+                trace_shards,
+                shard_trace.clone(),
+                toc.search(collection_name, request, read_consistency, shard_selection),
+            ),
+        ),
+    )
+    .await?;
+    let report = SearchReport {
+        debug: with_debug.then(|| hw_counter.search_debug_info()),
+        usage: with_usage.then(|| hw_counter.usage()),
+        partial: partial_results.report(),
+        trace: trace_shards.then(|| shard_trace.report()),
+        ..report
+    };
+    Ok((result, report))
 }
 
 pub async fn do_search_batch_points(
@@ -288,9 +513,111 @@ pub async fn do_search_batch_points(
     request: SearchRequestBatch,
     read_consistency: Option<ReadConsistency>,
     shard_selection: Option<ShardId>,
-) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
-    toc.search_batch(collection_name, request, read_consistency, shard_selection)
-        .await
+) -> Result<(Vec<Vec<ScoredPoint>>, SearchReport), StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    let report = if request
+        .searches
+        .iter()
+        .any(|search| is_report_required(search.params.as_ref()))
+    {
+        do_search_report(toc, collection_name, &request.searches, shard_selection).await?
+    } else {
+        SearchReport::default()
+    };
+    let with_debug = request
+        .searches
+        .iter()
+        .any(|search| is_debug_required(search.params.as_ref()));
+    let with_usage = request.searches.iter().any(|search| search.with_usage);
+    // Searches of the batch are served together, so failed shards are skipped for all of them
+    let allow_partial_results = request
+        .searches
+        .iter()
+        .any(|search| search.allow_partial_results);
+    let trace_shards = request
+        .searches
+        .iter()
+        .any(|search| search.with_shard_trace);
+    let hw_counter = Arc::new(HardwareCounterCell::new());
+    let partial_results = Arc::new(PartialResultsCell::new());
+    let shard_trace = Arc::new(ShardTraceCell::new());
+    let result = with_request_hw_counter(
+        hw_counter.clone(),
+        serve_with_partial_results(
+            allow_partial_results,
+            partial_results.clone(),
+            serve_with_shard_trace(
+                trace_shards,
+                shard_trace.clone(),
+                toc.search_batch(collection_name, request, read_consistency, shard_selection),
+            ),
+        ),
+    )
+    .await?;
+    let report = SearchReport {
+        debug: with_debug.then(|| hw_counter.search_debug_info()),
+        usage: with_usage.then(|| hw_counter.usage()),
+        partial: partial_results.report(),
+        trace: trace_shards.then(|| shard_trace.report()),
+        ..report
+    };
+    Ok((result, report))
+}
+
+pub async fn do_search_fusion(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: FusionRequest,
+    read_consistency: Option<ReadConsistency>,
+) -> Result<(Vec<FusedPoint>, SearchReport), StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    let report = if request
+        .searches
+        .iter()
+        .any(|search| is_report_required(search.params.as_ref()))
+    {
+        do_search_report(toc, collection_name, &request.searches, None).await?
+    } else {
+        SearchReport::default()
+    };
+    // Fused searches are served as a single batch, like in `do_search_batch_points`
+    let with_debug = request
+        .searches
+        .iter()
+        .any(|search| is_debug_required(search.params.as_ref()));
+    let with_usage = request.searches.iter().any(|search| search.with_usage);
+    let allow_partial_results = request
+        .searches
+        .iter()
+        .any(|search| search.allow_partial_results);
+    let trace_shards = request
+        .searches
+        .iter()
+        .any(|search| search.with_shard_trace);
+    let hw_counter = Arc::new(HardwareCounterCell::new());
+    let partial_results = Arc::new(PartialResultsCell::new());
+    let shard_trace = Arc::new(ShardTraceCell::new());
+    let result = with_request_hw_counter(
+        hw_counter.clone(),
+        serve_with_partial_results(
+            allow_partial_results,
+            partial_results.clone(),
+            serve_with_shard_trace(
+                trace_shards,
+                shard_trace.clone(),
+                toc.fusion(collection_name, request, read_consistency),
+            ),
+        ),
+    )
+    .await?;
+    let report = SearchReport {
+        debug: with_debug.then(|| hw_counter.search_debug_info()),
+        usage: with_usage.then(|| hw_counter.usage()),
+        partial: partial_results.report(),
+        trace: trace_shards.then(|| shard_trace.report()),
+        ..report
+    };
+    Ok((result, report))
 }
 
 pub async fn do_search_point_groups(
@@ -299,14 +626,84 @@ pub async fn do_search_point_groups(
     request: SearchGroupsRequest,
     read_consistency: Option<ReadConsistency>,
     shard_selection: Option<ShardId>,
-) -> Result<GroupsResult, StorageError> {
-    toc.group(
-        collection_name,
-        request.into(),
-        read_consistency,
-        shard_selection,
+) -> Result<(GroupsResult, SearchReport), StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    check_lookup_access(&request.group_request)?;
+    let report = if is_report_required(request.params.as_ref()) {
+        // Internal searches of the grouping use the same vector, filter and params
+        let search = SearchRequest {
+            vector: request.vector.clone(),
+            filter: request.filter.clone(),
+            params: request.params,
+            limit: request.group_request.limit as usize,
+            offset: 0,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: request.score_threshold,
+            with_usage: false,
+            allow_partial_results: false,
+            with_shard_trace: false,
+            max_payload_return_bytes: None,
+        };
+        do_search_report(toc, collection_name, &[search], shard_selection).await?
+    } else {
+        SearchReport::default()
+    };
+    // Strategies and usage of all internal searches of the grouping are summed up
+    let with_debug = is_debug_required(request.params.as_ref());
+    let with_usage = request.with_usage;
+    // Shards, failed in any of the internal searches of the grouping, are reported
+    let allow_partial_results = request.allow_partial_results;
+    let hw_counter = Arc::new(HardwareCounterCell::new());
+    let partial_results = Arc::new(PartialResultsCell::new());
+    let result = with_request_hw_counter(
+        hw_counter.clone(),
+        serve_with_partial_results(
+            allow_partial_results,
+            partial_results.clone(),
+            toc.group(
+                collection_name,
+                request.into(),
+                read_consistency,
+                shard_selection,
+            ),
+        ),
     )
-    .await
+    .await?;
+    let report = SearchReport {
+        debug: with_debug.then(|| hw_counter.search_debug_info()),
+        usage: with_usage.then(|| hw_counter.usage()),
+        partial: partial_results.report(),
+        ..report
+    };
+    Ok((result, report))
+}
+
+pub async fn do_recommend_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: RecommendRequest,
+    read_consistency: Option<ReadConsistency>,
+) -> Result<Vec<ScoredPoint>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    check_lookup_from_access(&request)?;
+    toc.recommend(collection_name, request, read_consistency)
+        .await
+}
+
+pub async fn do_recommend_batch_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: RecommendRequestBatch,
+    read_consistency: Option<ReadConsistency>,
+) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    request
+        .searches
+        .iter()
+        .try_for_each(check_lookup_from_access)?;
+    toc.recommend_batch(collection_name, request, read_consistency)
+        .await
 }
 
 pub async fn do_recommend_point_groups(
@@ -315,6 +712,47 @@ pub async fn do_recommend_point_groups(
     request: RecommendGroupsRequest,
     read_consistency: Option<ReadConsistency>,
 ) -> Result<GroupsResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    check_lookup_access(&request.group_request)?;
+    if let Some(lookup_from) = &request.lookup_from {
+        check_collection_access(&lookup_from.collection, AccessLevel::Read)?;
+    }
+    toc.group(collection_name, request.into(), read_consistency, None)
+        .await
+}
+
+pub async fn do_scroll_point_groups(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: ScrollGroupsRequest,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> Result<ScrollGroupsResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    toc.scroll_groups(collection_name, request, read_consistency, shard_selection)
+        .await
+}
+
+pub async fn do_query_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: OrderedQueryRequest,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> Result<Vec<Record>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    toc.query_ordered(collection_name, request, read_consistency, shard_selection)
+        .await
+}
+
+pub async fn do_query_point_groups(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: QueryGroupsRequest,
+    read_consistency: Option<ReadConsistency>,
+) -> Result<GroupsResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    check_lookup_access(&request.group_request)?;
     toc.group(collection_name, request.into(), read_consistency, None)
         .await
 }
@@ -325,9 +763,21 @@ pub async fn do_count_points(
     request: CountRequest,
     shard_selection: Option<ShardId>,
 ) -> Result<CountResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     toc.count(collection_name, request, shard_selection).await
 }
 
+pub async fn do_check_filter(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: CheckFilterRequest,
+    shard_selection: Option<ShardId>,
+) -> Result<FilterTrace, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
+    toc.check_filter(collection_name, request, shard_selection)
+        .await
+}
+
 pub async fn do_get_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -335,6 +785,7 @@ pub async fn do_get_points(
     read_consistency: Option<ReadConsistency>,
     shard_selection: Option<ShardId>,
 ) -> Result<Vec<Record>, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     toc.retrieve(collection_name, request, read_consistency, shard_selection)
         .await
 }
@@ -346,6 +797,7 @@ pub async fn do_scroll_points(
     read_consistency: Option<ReadConsistency>,
     shard_selection: Option<ShardId>,
 ) -> Result<ScrollResult, StorageError> {
+    check_collection_access(collection_name, AccessLevel::Read)?;
     toc.scroll(collection_name, request, read_consistency, shard_selection)
         .await
 }
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use api::grpc::circuit_breaker::CircuitBreakerTelemetry;
 use collection::shards::shard::PeerId;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
@@ -10,6 +13,8 @@ use crate::settings::Settings;
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct P2pConfigTelemetry {
     connection_pool_size: usize,
+    circuit_breaker_threshold: usize,
+    circuit_breaker_backoff_ms: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -32,6 +37,8 @@ impl From<&Settings> for ClusterConfigTelemetry {
             grpc_timeout_ms: settings.cluster.grpc_timeout_ms,
             p2p: P2pConfigTelemetry {
                 connection_pool_size: settings.cluster.p2p.connection_pool_size,
+                circuit_breaker_threshold: settings.cluster.p2p.circuit_breaker_threshold,
+                circuit_breaker_backoff_ms: settings.cluster.p2p.circuit_breaker_backoff_ms,
             },
             consensus: ConsensusConfigTelemetry {
                 max_message_queue_size: settings.cluster.consensus.max_message_queue_size,
@@ -52,6 +59,9 @@ pub struct ClusterStatusTelemetry {
     pub is_voter: bool,
     pub peer_id: Option<PeerId>,
     pub consensus_thread_status: ConsensusThreadStatus,
+    /// Circuit breakers of the peers, which had connection failures
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub peer_circuit_breakers: HashMap<PeerId, CircuitBreakerTelemetry>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -77,6 +87,7 @@ impl ClusterTelemetry {
                     is_voter: cluster_info.raft_info.is_voter,
                     peer_id: Some(cluster_info.peer_id),
                     consensus_thread_status: cluster_info.consensus_thread_status,
+                    peer_circuit_breakers: dispatcher.toc().peer_circuit_breakers(),
                 }),
             }
         } else {
@@ -118,6 +129,7 @@ impl Anonymize for ClusterStatusTelemetry {
             is_voter: self.is_voter,
             peer_id: None,
             consensus_thread_status: self.consensus_thread_status.clone(),
+            peer_circuit_breakers: HashMap::new(),
         }
     }
 }
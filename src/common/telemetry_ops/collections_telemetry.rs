@@ -1,6 +1,6 @@
 use collection::config::CollectionParams;
 use collection::operations::types::OptimizersStatus;
-use collection::telemetry::CollectionTelemetry;
+use collection::telemetry::{CollectionTelemetry, OperationsTelemetry};
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
@@ -8,9 +8,11 @@ use storage::content_manager::toc::TableOfContent;
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct CollectionsAggregatedTelemetry {
+    pub id: String,
     pub vectors: usize,
     pub optimizers_status: OptimizersStatus,
     pub params: CollectionParams,
+    pub operations: OperationsTelemetry,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -39,7 +41,12 @@ impl From<CollectionTelemetry> for CollectionsAggregatedTelemetry {
         CollectionsAggregatedTelemetry {
             vectors: telemetry.count_vectors(),
             optimizers_status,
+            id: telemetry.id,
             params: telemetry.config.params,
+            operations: OperationsTelemetry {
+                requests: telemetry.operations.requests.totals_only(),
+                ..telemetry.operations
+            },
         }
     }
 }
@@ -48,8 +55,9 @@ impl CollectionsTelemetry {
     pub async fn collect(level: usize, toc: &TableOfContent) -> Self {
         let number_of_collections = toc.all_collections().await.len();
         let collections = if level > 0 {
+            // Health of the HNSW graphs is expensive to collect, so it has its own level
             let telemetry_data = toc
-                .get_telemetry_data()
+                .get_telemetry_data(level > 2)
                 .await
                 .into_iter()
                 .map(|telemetry| {
@@ -98,9 +106,11 @@ impl Anonymize for CollectionTelemetryEnum {
 impl Anonymize for CollectionsAggregatedTelemetry {
     fn anonymize(&self) -> Self {
         CollectionsAggregatedTelemetry {
+            id: self.id.anonymize(),
             optimizers_status: self.optimizers_status.clone(),
             vectors: self.vectors.anonymize(),
             params: self.params.anonymize(),
+            operations: self.operations.clone(),
         }
     }
 }
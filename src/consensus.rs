@@ -448,6 +448,7 @@ impl Consensus {
                 if stop_consensus {
                     return Ok(());
                 }
+                self.try_compact_wal();
             } else {
                 timeout -= d;
             }
@@ -458,6 +459,20 @@ impl Consensus {
         }
     }
 
+    /// Replace applied entries of the consensus WAL with a snapshot, once there are enough of them
+    fn try_compact_wal(&self) {
+        if self.config.compact_wal_entries == 0 {
+            return;
+        }
+        if let Err(err) = self
+            .node
+            .store()
+            .compact_wal(self.config.compact_wal_entries)
+        {
+            log::warn!("Failed to compact consensus WAL: {err}");
+        }
+    }
+
     fn try_sync_local_state(&mut self) -> anyhow::Result<()> {
         if !self.node.has_ready() {
             // No updates to process
@@ -1110,17 +1125,36 @@ mod tests {
                                 hnsw_config: None,
                                 quantization_config: None,
                                 on_disk: None,
+                                datatype: None,
                             }
                             .into(),
                             hnsw_config: None,
                             wal_config: None,
                             optimizers_config: None,
+                            sparse_vectors: None,
                             shard_number: Some(2),
                             on_disk_payload: None,
+                            validate_payload: None,
+                            payload_validation_mode: None,
+                            read_hedge_delay_ms: None,
                             replication_factor: None,
+                            shard_placement: None,
+                            placement_policy: None,
                             write_consistency_factor: None,
                             init_from: None,
                             quantization_config: None,
+                            strict_mode_config: None,
+                            default_search_params: None,
+                            default_with_payload: None,
+                            default_with_vector: None,
+                            default_max_payload_return_bytes: None,
+                            default_vector: None,
+                            quota_config: None,
+                            admission_config: None,
+                            read_cache_config: None,
+                            ttl_config: None,
+                            payload_indexes: None,
+                            payload_cache_fields: None,
                         },
                     )),
                     None,
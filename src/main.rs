@@ -19,6 +19,7 @@ use std::thread::JoinHandle;
 use std::time::Duration;
 
 use ::tonic::transport::Uri;
+use api::grpc::circuit_breaker::CircuitBreakerConfig;
 use api::grpc::transport_channel_pool::TransportChannelPool;
 use clap::Parser;
 use collection::shards::channel_service::ChannelService;
@@ -107,8 +108,6 @@ struct Args {
 }
 
 fn main() -> anyhow::Result<()> {
-    tracing::setup()?;
-
     remove_started_file_indicator();
 
     let args = Args::parse();
@@ -123,6 +122,11 @@ fn main() -> anyhow::Result<()> {
 
     segment::madvise::set_global(settings.storage.mmap_advice);
     segment::vector_storage::raw_scorer::set_async_scorer(settings.storage.async_scorer);
+    segment::vector_storage::raw_scorer::set_async_scorer_batch_size(
+        settings.storage.async_scorer_batch_size,
+    );
+    segment::common::on_disk_io::set_global(settings.storage.on_disk_io);
+    segment::spaces::simd::set_global(settings.storage.performance.simd);
 
     welcome(&settings);
 
@@ -176,6 +180,12 @@ fn main() -> anyhow::Result<()> {
         create_general_purpose_runtime().expect("Can't optimizer general purpose runtime.");
     let runtime_handle = general_runtime.handle().clone();
 
+    // Exporter of the spans runs on the general purpose runtime
+    {
+        let _runtime_guard = runtime_handle.enter();
+        tracing::setup(&settings.service.tracing)?;
+    }
+
     // Create a signal sender and receiver. It is used to communicate with the consensus thread.
     let (propose_sender, propose_receiver) = std::sync::mpsc::channel();
 
@@ -198,12 +208,17 @@ fn main() -> anyhow::Result<()> {
         let connection_timeout = Duration::from_millis(settings.cluster.connection_timeout_ms);
 
         let tls_config = load_tls_client_config(&settings)?;
+        let circuit_breaker_config = CircuitBreakerConfig {
+            failure_threshold: settings.cluster.p2p.circuit_breaker_threshold,
+            backoff: Duration::from_millis(settings.cluster.p2p.circuit_breaker_backoff_ms),
+        };
 
         channel_service.channel_pool = Arc::new(TransportChannelPool::new(
             p2p_grpc_timeout,
             connection_timeout,
             settings.cluster.p2p.connection_pool_size,
             tls_config,
+            circuit_breaker_config,
         ));
         channel_service.id_to_address = persistent_consensus_state.peer_address_by_id.clone();
     }
@@ -441,5 +456,6 @@ fn main() -> anyhow::Result<()> {
     }
     drop(toc_arc);
     drop(settings);
+    tracing::shutdown();
     Ok(())
 }
@@ -34,8 +34,11 @@ pub async fn handle_existing_collections(
             collection.to_string(),
             CreateCollection {
                 vectors: collection_state.config.params.vectors,
+                sparse_vectors: collection_state.config.params.sparse_vectors,
                 shard_number: Some(shards_number),
                 replication_factor: Some(collection_state.config.params.replication_factor.get()),
+                shard_placement: None,
+                placement_policy: None,
                 write_consistency_factor: Some(
                     collection_state
                         .config
@@ -44,11 +47,30 @@ pub async fn handle_existing_collections(
                         .get(),
                 ),
                 on_disk_payload: Some(collection_state.config.params.on_disk_payload),
+                validate_payload: Some(collection_state.config.params.validate_payload),
+                payload_validation_mode: Some(
+                    collection_state.config.params.payload_validation_mode,
+                ),
+                read_hedge_delay_ms: collection_state.config.params.read_hedge_delay_ms,
                 hnsw_config: Some(collection_state.config.hnsw_config.into()),
                 wal_config: Some(collection_state.config.wal_config.into()),
                 optimizers_config: Some(collection_state.config.optimizer_config.into()),
                 init_from: None,
                 quantization_config: collection_state.config.quantization_config,
+                strict_mode_config: collection_state.config.strict_mode_config,
+                default_search_params: collection_state.config.default_search_params,
+                default_with_payload: collection_state.config.default_with_payload,
+                default_with_vector: collection_state.config.default_with_vector,
+                default_max_payload_return_bytes: collection_state
+                    .config
+                    .default_max_payload_return_bytes,
+                ttl_config: collection_state.config.ttl_config,
+                default_vector: collection_state.config.default_vector,
+                quota_config: collection_state.config.quota_config,
+                admission_config: collection_state.config.admission_config,
+                read_cache_config: collection_state.config.read_cache_config,
+                payload_indexes: Some(collection_state.config.params.payload_indexes),
+                payload_cache_fields: Some(collection_state.config.params.payload_cache_fields),
             },
         );
 
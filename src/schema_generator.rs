@@ -1,24 +1,36 @@
 use api::grpc::models::CollectionsResponse;
 use collection::operations::cluster_ops::ClusterOperations;
-use collection::operations::consistency_params::ReadConsistency;
+use collection::operations::consistency_params::{ReadConsistency, WriteConsistency};
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
 use collection::operations::snapshot_ops::{SnapshotDescription, SnapshotRecover};
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
-    CountRequest, CountResult, GroupsResult, PointGroup, PointRequest, RecommendGroupsRequest,
-    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
-    SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult,
+    AliasDescription, CheckFilterRequest, CollectionClusterInfo, CollectionFlushResult,
+    CollectionInfo, CollectionsAliasesResponse, CountRequest, CountResult, FusedPoint,
+    FusionRequest, GroupsResult, OptimizersPlan, OrderedQueryRequest, PointGroup, PointRequest,
+    QueryGroupsRequest, RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch, Record,
+    ScrollGroupsRequest, ScrollGroupsResult, ScrollRequest, ScrollResult, SearchGroupsRequest,
+    SearchRequest, SearchRequestBatch, ShardIndexRebuildProgress, ShardSegmentsInfo, UpdateResult,
+    WaitForStatusResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use collection::shards::balancer::RebalancingPlan;
+use collection::shards::checksum::CollectionChecksum;
+use collection::slow_log::SlowLogEntry;
+use collection::warmup::WarmupRequest;
 use schemars::gen::SchemaSettings;
 use schemars::JsonSchema;
+use segment::data_types::filter_trace::FilterTrace;
 use segment::types::ScoredPoint;
 use serde::{Deserialize, Serialize};
+use storage::content_manager::collection_clone::{CloneCollection, CollectionCloneStatus};
+use storage::content_manager::collection_config_document::{
+    ApplyCollectionConfigResult, CollectionConfigDocument,
+};
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CreateCollection, UpdateCollection,
 };
-use storage::types::ClusterStatus;
+use storage::types::{ClusterStatus, SetPeerLabels};
 
 use crate::common::helpers::LocksOption;
 use crate::common::points::CreateFieldIndex;
@@ -71,6 +83,29 @@ struct AllDefinitions {
     b5: SearchGroupsRequest,
     b6: RecommendGroupsRequest,
     b7: GroupsResult,
+    b8: OptimizersPlan,
+    b9: SlowLogEntry,
+    c1: RebalancingPlan,
+    c2: CollectionChecksum,
+    c3: WriteConsistency,
+    c4: ShardSegmentsInfo,
+    c5: FusionRequest,
+    c6: FusedPoint,
+    c7: CheckFilterRequest,
+    c8: FilterTrace,
+    c9: ScrollGroupsRequest,
+    d1: ScrollGroupsResult,
+    d2: CloneCollection,
+    d3: CollectionCloneStatus,
+    d4: WarmupRequest,
+    d5: OrderedQueryRequest,
+    d6: QueryGroupsRequest,
+    d7: WaitForStatusResult,
+    d8: ShardIndexRebuildProgress,
+    d9: CollectionFlushResult,
+    da: SetPeerLabels,
+    db: CollectionConfigDocument,
+    dc: ApplyCollectionConfigResult,
 }
 
 fn save_schema<T: JsonSchema>() {
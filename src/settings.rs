@@ -1,5 +1,8 @@
 use std::{env, io};
 
+use api::grpc::circuit_breaker::{
+    DEFAULT_CIRCUIT_BREAKER_BACKOFF, DEFAULT_CIRCUIT_BREAKER_THRESHOLD,
+};
 use api::grpc::transport_channel_pool::{
     DEFAULT_CONNECT_TIMEOUT, DEFAULT_GRPC_TIMEOUT, DEFAULT_POOL_SIZE,
 };
@@ -10,6 +13,8 @@ use serde::Deserialize;
 use storage::types::StorageConfig;
 use validator::Validate;
 
+use crate::common::auth::ScopedApiKey;
+
 const DEFAULT_CONFIG: &str = include_str!("../config/config.yaml");
 
 #[derive(Debug, Deserialize, Validate, Clone)]
@@ -27,6 +32,15 @@ pub struct ServiceConfig {
     #[serde(default)]
     pub verify_https_client_certificate: bool,
     pub api_key: Option<String>,
+    /// API keys, which only give access to the points, matching the payload filter of the key
+    #[serde(default)]
+    pub scoped_api_keys: Vec<ScopedApiKey>,
+    /// Secret, the JWT tokens of the clients are signed with. Tokens are not accepted if not set
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+    /// If set - JWT tokens must have this `aud` claim
+    #[serde(default)]
+    pub jwt_audience: Option<String>,
     /// Directory where static files are served from.
     /// For example, the Web-UI should be placed here.
     #[serde(default)]
@@ -36,6 +50,38 @@ pub struct ServiceConfig {
     /// This includes the Web-UI. True by default.
     #[serde(default)]
     pub enable_static_content: Option<bool>,
+
+    /// Export of the tracing spans of the requests
+    #[serde(default)]
+    #[validate]
+    pub tracing: TracingConfig,
+}
+
+#[derive(Debug, Deserialize, Validate, Clone)]
+pub struct TracingConfig {
+    /// gRPC endpoint of the OpenTelemetry collector, e.g. `http://localhost:4317`.
+    /// If not set - spans are not exported
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Name of the service, the spans are reported with
+    #[serde(default = "default_tracing_service_name")]
+    #[validate(length(min = 1))]
+    pub service_name: String,
+    /// Share of the traces to export, from 0.0 to 1.0.
+    /// Traces, continued from another peer, follow the sampling decision of that peer
+    #[serde(default = "default_tracing_sampling_ratio")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub sampling_ratio: f64,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        TracingConfig {
+            otlp_endpoint: None,
+            service_name: default_tracing_service_name(),
+            sampling_ratio: default_tracing_sampling_ratio(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, Default, Validate)]
@@ -64,6 +110,14 @@ pub struct P2pConfig {
     pub connection_pool_size: usize,
     #[serde(default)]
     pub enable_tls: bool,
+    /// Consecutive connection failures to a peer, after which requests to it fail immediately.
+    /// 0 disables the circuit breaker
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: usize,
+    /// How long requests to the peer fail immediately, before a probe request is sent
+    #[serde(default = "default_circuit_breaker_backoff_ms")]
+    #[validate(range(min = 1))]
+    pub circuit_breaker_backoff_ms: u64,
 }
 
 impl Default for P2pConfig {
@@ -72,6 +126,8 @@ impl Default for P2pConfig {
             port: None,
             connection_pool_size: default_connection_pool_size(),
             enable_tls: false,
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_backoff_ms: default_circuit_breaker_backoff_ms(),
         }
     }
 }
@@ -89,6 +145,11 @@ pub struct ConsensusConfig {
     #[validate(range(min = 1))]
     #[serde(default = "default_message_timeout_tics")]
     pub message_timeout_ticks: u64,
+    /// Compact the consensus WAL once this number of entries is applied since the last snapshot.
+    /// Peers, which need the compacted entries, receive a snapshot of the consensus state instead.
+    /// 0 disables the compaction
+    #[serde(default = "default_compact_wal_entries")]
+    pub compact_wal_entries: u64,
 }
 
 impl Default for ConsensusConfig {
@@ -98,6 +159,7 @@ impl Default for ConsensusConfig {
             tick_period_ms: default_tick_period_ms(),
             bootstrap_timeout_sec: default_bootstrap_timeout_sec(),
             message_timeout_ticks: default_message_timeout_tics(),
+            compact_wal_entries: default_compact_wal_entries(),
         }
     }
 }
@@ -217,10 +279,30 @@ const fn default_connection_pool_size() -> usize {
     DEFAULT_POOL_SIZE
 }
 
+const fn default_circuit_breaker_threshold() -> usize {
+    DEFAULT_CIRCUIT_BREAKER_THRESHOLD
+}
+
+const fn default_circuit_breaker_backoff_ms() -> u64 {
+    DEFAULT_CIRCUIT_BREAKER_BACKOFF.as_millis() as u64
+}
+
 const fn default_message_timeout_tics() -> u64 {
     10
 }
 
+const fn default_compact_wal_entries() -> u64 {
+    128
+}
+
+fn default_tracing_service_name() -> String {
+    "qdrant".to_string()
+}
+
+const fn default_tracing_sampling_ratio() -> f64 {
+    1.0
+}
+
 const fn default_tls_cert_ttl() -> Option<u64> {
     // Default one hour
     Some(3600)
@@ -3,7 +3,7 @@ use std::time::{Duration, Instant};
 
 use api::grpc::qdrant::collections_server::Collections;
 use api::grpc::qdrant::{
-    AliasDescription, ChangeAliases, CollectionClusterInfoRequest, CollectionClusterInfoResponse,
+    ChangeAliases, CollectionClusterInfoRequest, CollectionClusterInfoResponse,
     CollectionOperationResponse, CreateCollection, DeleteCollection, GetCollectionInfoRequest,
     GetCollectionInfoResponse, ListAliasesRequest, ListAliasesResponse,
     ListCollectionAliasesRequest, ListCollectionsRequest, ListCollectionsResponse,
@@ -40,11 +40,10 @@ impl CollectionsService {
         let timing = Instant::now();
         let operation = request.into_inner();
         let wait_timeout = operation.wait_timeout();
-        let result = self
-            .dispatcher
-            .submit_collection_meta_op(operation.try_into()?, wait_timeout)
-            .await
-            .map_err(error_to_status)?;
+        let result =
+            do_submit_collection_meta_op(&self.dispatcher, operation.try_into()?, wait_timeout)
+                .await
+                .map_err(error_to_status)?;
 
         let response = CollectionOperationResponse::from((timing, result));
         Ok(Response::new(response))
@@ -55,12 +54,15 @@ impl CollectionsService {
         _request: Request<ListAliasesRequest>,
     ) -> Result<Response<ListAliasesResponse>, Status> {
         let timing = Instant::now();
-        let aliases = self
-            .dispatcher
-            .toc()
-            .list_aliases()
+        let aliases = do_list_aliases(self.dispatcher.toc())
             .await
-            .map(|aliases| aliases.into_iter().map(|alias| alias.into()).collect())
+            .map(|response| {
+                response
+                    .aliases
+                    .into_iter()
+                    .map(|alias| alias.into())
+                    .collect()
+            })
             .map_err(error_to_status)?;
         let response = ListAliasesResponse {
             aliases,
@@ -75,18 +77,13 @@ impl CollectionsService {
     ) -> Result<Response<ListAliasesResponse>, Status> {
         let timing = Instant::now();
         let ListCollectionAliasesRequest { collection_name } = request.into_inner();
-        let aliases = self
-            .dispatcher
-            .toc()
-            .collection_aliases(&collection_name)
+        let aliases = do_list_collection_aliases(self.dispatcher.toc(), &collection_name)
             .await
-            .map(|aliases| {
-                aliases
+            .map(|response| {
+                response
+                    .aliases
                     .into_iter()
-                    .map(|alias| AliasDescription {
-                        alias_name: alias,
-                        collection_name: collection_name.clone(),
-                    })
+                    .map(|alias| alias.into())
                     .collect()
             })
             .map_err(error_to_status)?;
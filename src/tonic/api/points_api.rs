@@ -2,23 +2,24 @@ use std::sync::Arc;
 
 use api::grpc::qdrant::points_server::Points;
 use api::grpc::qdrant::{
-    ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
-    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, GetPoints,
-    GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
+    CancelFieldIndexBuildCollection, ClearPayloadPoints, CountPoints, CountResponse,
+    CreateFieldIndexCollection, DeleteFieldIndexCollection, DeletePayloadPoints,
+    DeletePointVectors, DeletePoints, GetPoints, GetResponse, PointsOperationResponse,
+    RebuildFieldIndexCollection, RecommendBatchPoints, RecommendBatchResponse,
     RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
     ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
     SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdatePointVectors,
-    UpsertPoints,
+    UpsertArrowPoints, UpsertPoints,
 };
 use storage::content_manager::toc::TableOfContent;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 
 use super::points_common::{delete_vectors, recommend_groups, search_groups, update_vectors};
 use super::validate;
 use crate::tonic::api::points_common::{
-    clear_payload, count, create_field_index, delete, delete_field_index, delete_payload, get,
-    overwrite_payload, recommend, recommend_batch, scroll, search, search_batch, set_payload,
-    upsert,
+    cancel_field_index_build, clear_payload, count, create_field_index, delete, delete_field_index,
+    delete_payload, get, overwrite_payload, rebuild_field_index, recommend, recommend_batch,
+    scroll, scroll_stream, search, search_batch, set_payload, upsert, upsert_arrow, ScrollStream,
 };
 
 pub struct PointsService {
@@ -33,6 +34,8 @@ impl PointsService {
 
 #[tonic::async_trait]
 impl Points for PointsService {
+    type ScrollStreamStream = ScrollStream;
+
     async fn upsert(
         &self,
         request: Request<UpsertPoints>,
@@ -41,6 +44,13 @@ impl Points for PointsService {
         upsert(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn upsert_arrow(
+        &self,
+        request: Request<Streaming<UpsertArrowPoints>>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        upsert_arrow(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
     async fn delete(
         &self,
         request: Request<DeletePoints>,
@@ -118,6 +128,22 @@ impl Points for PointsService {
         delete_field_index(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn rebuild_field_index(
+        &self,
+        request: Request<RebuildFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        rebuild_field_index(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn cancel_field_index_build(
+        &self,
+        request: Request<CancelFieldIndexBuildCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        cancel_field_index_build(self.toc.as_ref(), request.into_inner()).await
+    }
+
     async fn search(
         &self,
         request: Request<SearchPoints>,
@@ -162,6 +188,14 @@ impl Points for PointsService {
         scroll(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn scroll_stream(
+        &self,
+        request: Request<ScrollPoints>,
+    ) -> Result<Response<Self::ScrollStreamStream>, Status> {
+        validate(request.get_ref())?;
+        scroll_stream(self.toc.clone(), request.into_inner(), None).await
+    }
+
     async fn recommend(
         &self,
         request: Request<RecommendPoints>,
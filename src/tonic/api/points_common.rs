@@ -1,42 +1,74 @@
-use std::time::Instant;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use api::grpc::conversions::proto_to_payloads;
 use api::grpc::qdrant::payload_index_params::IndexParams;
 use api::grpc::qdrant::{
-    BatchResult, ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
+    BatchResult, CancelFieldIndexBuildCollection, CancelFieldIndexBuildInternal,
+    ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
     DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, FieldType,
-    GetPoints, GetResponse, PayloadIndexParams, PointsOperationResponse,
-    ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse, RecommendGroupsResponse,
-    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
-    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
-    SetPayloadPoints, SyncPoints, UpdatePointVectors, UpsertPoints,
+    GetPoints, GetResponse, OrderBy as OrderByGrpc, PayloadIndexParams, PointsOperationResponse,
+    ReadConsistency as ReadConsistencyGrpc, RebuildFieldIndexCollection, RecommendBatchResponse,
+    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
+    ScrollPoints, ScrollResponse, SearchBatchResponse, SearchGroupsResponse, SearchPointGroups,
+    SearchPoints, SearchResponse, SetPayloadPoints, SyncPoints, UpdatePointVectors,
+    UpsertArrowPoints, UpsertPoints, WriteConsistency as WriteConsistencyGrpc,
 };
-use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::conversions::write_ordering_from_proto;
+use collection::common::point_scope::{point_scope, with_point_scope};
+use collection::common::update_context::{with_update_context, UpdateContext};
+use collection::operations::consistency_params::{ReadConsistency, WriteConsistency};
+use collection::operations::conversions::{search_vector_from_proto, write_ordering_from_proto};
 use collection::operations::payload_ops::DeletePayload;
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointSyncOperation, PointsSelector,
 };
 use collection::operations::types::{
-    default_exact_count, PointRequest, RecommendRequestBatch, ScrollRequest, SearchRequest,
-    SearchRequestBatch,
+    default_exact_count, OrderedQueryRequest, PointRequest, RecommendRequestBatch, ScrollRequest,
+    SearchRequest, SearchRequestBatch,
 };
 use collection::operations::vector_ops::{DeleteVectors, PointVectors, UpdateVectors};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
-use segment::data_types::vectors::NamedVector;
+use futures::Stream;
 use segment::types::{PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType};
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
-use tonic::{Response, Status};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::SendTimeoutError;
+use tonic::{Response, Status, Streaming};
+use uuid::Uuid;
 
+use crate::common::auth::{check_collection_access, AccessLevel};
 use crate::common::points::{
-    do_clear_payload, do_count_points, do_create_index, do_delete_index, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload, do_scroll_points,
-    do_search_batch_points, do_search_points, do_set_payload, do_update_vectors, do_upsert_points,
-    CreateFieldIndex,
+    do_cancel_field_index_build, do_cancel_index_build, do_clear_payload, do_count_points,
+    do_create_index, do_delete_index, do_delete_payload, do_delete_points, do_delete_vectors,
+    do_get_points, do_overwrite_payload, do_query_points, do_rebuild_index,
+    do_recommend_batch_points, do_recommend_points, do_scroll_points, do_search_batch_points,
+    do_search_points, do_set_payload, do_update_vectors, do_upsert_points, CreateFieldIndex,
 };
 
+/// Max time to wait for the client to read the next batch of a scroll stream
+const SCROLL_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Parse the client-supplied parameters of the update operation
+fn update_context_from_proto(
+    operation_id: Option<String>,
+    wait_for: Option<WriteConsistencyGrpc>,
+) -> Result<UpdateContext, Status> {
+    let operation_id = operation_id
+        .map(|operation_id| {
+            Uuid::parse_str(&operation_id).map_err(|err| {
+                Status::invalid_argument(format!("Invalid operation id {operation_id}: {err}"))
+            })
+        })
+        .transpose()?;
+    Ok(UpdateContext {
+        operation_id,
+        wait_for: WriteConsistency::try_from_optional(wait_for)?,
+    })
+}
+
 pub fn points_operation_response(
     timing: Instant,
     update_result: collection::operations::types::UpdateResult,
@@ -57,6 +89,8 @@ pub async fn upsert(
         wait,
         points,
         ordering,
+        operation_id,
+        wait_for,
     } = upsert_points;
     let points = points
         .into_iter()
@@ -64,13 +98,16 @@ pub async fn upsert(
         .collect::<Result<_, _>>()?;
     let operation = PointInsertOperations::PointsList(points);
     let timing = Instant::now();
-    let result = do_upsert_points(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_upsert_points(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -79,6 +116,67 @@ pub async fn upsert(
     Ok(Response::new(response))
 }
 
+/// Upsert points from the stream of Arrow IPC messages.
+///
+/// Record batches of a message are converted and upserted in chunks, before the next message is
+/// read. The schema of each message is checked before any of its points are written, so a
+/// mismatching stream is rejected on the first message without any writes.
+pub async fn upsert_arrow(
+    toc: &TableOfContent,
+    mut stream: Streaming<UpsertArrowPoints>,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let Some(UpsertArrowPoints {
+        collection_name,
+        wait,
+        arrow_ipc,
+        ordering,
+    }) = stream.message().await?
+    else {
+        return Err(Status::invalid_argument(
+            "Stream of Arrow record batches is empty",
+        ));
+    };
+    let wait = wait.unwrap_or(false);
+    let ordering = write_ordering_from_proto(ordering)?;
+
+    let timing = Instant::now();
+    let mut result = None;
+    let mut next_message = Some(arrow_ipc);
+    while let Some(arrow_ipc) = next_message {
+        let reader = toc
+            .get_collection(&collection_name)
+            .await
+            .map_err(error_to_status)?
+            .arrow_points_reader(arrow_ipc)
+            .await
+            .map_err(|err| error_to_status(err.into()))?;
+        for points in reader {
+            let points = points.map_err(|err| error_to_status(err.into()))?;
+            let update_result = do_upsert_points(
+                toc,
+                &collection_name,
+                PointInsertOperations::PointsList(points),
+                shard_selection,
+                wait,
+                ordering,
+            )
+            .await
+            .map_err(error_to_status)?;
+            result = Some(update_result);
+        }
+        next_message = stream.message().await?.map(|message| message.arrow_ipc);
+    }
+
+    let Some(result) = result else {
+        return Err(Status::invalid_argument(
+            "Arrow record batches contain no points",
+        ));
+    };
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
 pub async fn sync(
     toc: &TableOfContent,
     sync_points: SyncPoints,
@@ -100,6 +198,7 @@ pub async fn sync(
 
     let timing = Instant::now();
 
+    check_collection_access(&collection_name, AccessLevel::Write).map_err(error_to_status)?;
     let operation = PointSyncOperation {
         points,
         from_id: from_id.map(|x| x.try_into()).transpose()?,
@@ -132,6 +231,8 @@ pub async fn delete(
         wait,
         points,
         ordering,
+        operation_id,
+        wait_for,
     } = delete_points;
 
     let points_selector = match points {
@@ -140,13 +241,16 @@ pub async fn delete(
     };
 
     let timing = Instant::now();
-    let result = do_delete_points(
-        toc,
-        &collection_name,
-        points_selector,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_delete_points(
+            toc,
+            &collection_name,
+            points_selector,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -165,6 +269,8 @@ pub async fn update_vectors(
         wait,
         points,
         ordering,
+        operation_id,
+        wait_for,
     } = update_point_vectors;
 
     // Build list of operation points
@@ -184,13 +290,16 @@ pub async fn update_vectors(
     let operation = UpdateVectors { points: op_points };
 
     let timing = Instant::now();
-    let result = do_update_vectors(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_update_vectors(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -210,6 +319,8 @@ pub async fn delete_vectors(
         points_selector,
         vectors,
         ordering,
+        operation_id,
+        wait_for,
     } = delete_point_vectors;
 
     let (points, filter) = if let Some(points_selector) = points_selector {
@@ -234,13 +345,16 @@ pub async fn delete_vectors(
     };
 
     let timing = Instant::now();
-    let result = do_delete_vectors(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_delete_vectors(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -260,6 +374,8 @@ pub async fn set_payload(
         payload,
         points_selector,
         ordering,
+        operation_id,
+        wait_for,
     } = set_payload_points;
 
     let (points, filter) = if let Some(points_selector) = points_selector {
@@ -279,13 +395,16 @@ pub async fn set_payload(
     };
 
     let timing = Instant::now();
-    let result = do_set_payload(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_set_payload(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -305,6 +424,8 @@ pub async fn overwrite_payload(
         payload,
         points_selector,
         ordering,
+        operation_id,
+        wait_for,
     } = set_payload_points;
 
     let (points, filter) = if let Some(points_selector) = points_selector {
@@ -324,13 +445,16 @@ pub async fn overwrite_payload(
     };
 
     let timing = Instant::now();
-    let result = do_overwrite_payload(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_overwrite_payload(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -350,6 +474,8 @@ pub async fn delete_payload(
         keys,
         points_selector,
         ordering,
+        operation_id,
+        wait_for,
     } = delete_payload_points;
 
     let (points, filter) = if let Some(points_selector) = points_selector {
@@ -369,13 +495,16 @@ pub async fn delete_payload(
     };
 
     let timing = Instant::now();
-    let result = do_delete_payload(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_delete_payload(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -394,6 +523,8 @@ pub async fn clear_payload(
         wait,
         points,
         ordering,
+        operation_id,
+        wait_for,
     } = clear_payload_points;
 
     let points_selector = match points {
@@ -402,13 +533,16 @@ pub async fn clear_payload(
     };
 
     let timing = Instant::now();
-    let result = do_clear_payload(
-        toc,
-        &collection_name,
-        points_selector,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_clear_payload(
+            toc,
+            &collection_name,
+            points_selector,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -429,6 +563,8 @@ pub async fn create_field_index(
         field_type,
         field_index_params,
         ordering,
+        operation_id,
+        wait_for,
     } = create_field_index_collection;
 
     let field_type_parsed = field_type
@@ -451,6 +587,21 @@ pub async fn create_field_index(
                 ))
             }
         },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::KeywordIndexParams(keyword_index_params)),
+            }),
+        ) => match v {
+            FieldType::Keyword => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
         (Some(v), None | Some(PayloadIndexParams { index_params: None })) => match v {
             FieldType::Keyword => Some(PayloadSchemaType::Keyword.into()),
             FieldType::Integer => Some(PayloadSchemaType::Integer.into()),
@@ -468,13 +619,16 @@ pub async fn create_field_index(
     };
 
     let timing = Instant::now();
-    let result = do_create_index(
-        toc,
-        &collection_name,
-        operation,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_create_index(
+            toc,
+            &collection_name,
+            operation,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -493,16 +647,118 @@ pub async fn delete_field_index(
         wait,
         field_name,
         ordering,
+        operation_id,
+        wait_for,
     } = delete_field_index_collection;
 
     let timing = Instant::now();
-    let result = do_delete_index(
-        toc,
-        &collection_name,
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_delete_index(
+            toc,
+            &collection_name,
+            field_name,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn rebuild_field_index(
+    toc: &TableOfContent,
+    rebuild_field_index_collection: RebuildFieldIndexCollection,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let RebuildFieldIndexCollection {
+        collection_name,
+        wait,
         field_name,
-        shard_selection,
-        wait.unwrap_or(false),
-        write_ordering_from_proto(ordering)?,
+        ordering,
+        operation_id,
+        wait_for,
+    } = rebuild_field_index_collection;
+
+    let timing = Instant::now();
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_rebuild_index(
+            toc,
+            &collection_name,
+            field_name,
+            shard_selection,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn cancel_field_index_build(
+    toc: &TableOfContent,
+    cancel_field_index_build_collection: CancelFieldIndexBuildCollection,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let CancelFieldIndexBuildCollection {
+        collection_name,
+        wait,
+        build_operation_id,
+        ordering,
+        operation_id,
+        wait_for,
+    } = cancel_field_index_build_collection;
+
+    let timing = Instant::now();
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_cancel_index_build(
+            toc,
+            &collection_name,
+            build_operation_id,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn cancel_field_index_build_internal(
+    toc: &TableOfContent,
+    cancel_field_index_build: CancelFieldIndexBuildInternal,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let CancelFieldIndexBuildInternal {
+        collection_name,
+        field_name,
+        wait,
+        ordering,
+        shard_id,
+        operation_id,
+        wait_for,
+    } = cancel_field_index_build;
+
+    let timing = Instant::now();
+    let result = with_update_context(
+        update_context_from_proto(operation_id, wait_for)?,
+        do_cancel_field_index_build(
+            toc,
+            &collection_name,
+            field_name,
+            shard_id,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -528,13 +784,15 @@ pub async fn search(
         vector_name,
         with_vectors,
         read_consistency,
+        with_usage,
+        allow_partial_results,
+        sparse_indices,
+        with_shard_trace,
+        max_payload_return_bytes,
     } = search_points;
 
     let search_request = SearchRequest {
-        vector: match vector_name {
-            None => vector.into(),
-            Some(name) => NamedVector { name, vector }.into(),
-        },
+        vector: search_vector_from_proto(vector, vector_name, sparse_indices)?,
         filter: filter.map(|f| f.try_into()).transpose()?,
         params: params.map(|p| p.into()),
         limit: limit as usize,
@@ -546,12 +804,16 @@ pub async fn search(
                 .unwrap_or_default(),
         ),
         score_threshold,
+        with_usage: with_usage.unwrap_or(false),
+        allow_partial_results: allow_partial_results.unwrap_or(false),
+        with_shard_trace: with_shard_trace.unwrap_or(false),
+        max_payload_return_bytes: max_payload_return_bytes.map(|bytes| bytes as usize),
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let scored_points = do_search_points(
+    let (scored_points, report) = do_search_points(
         toc,
         &collection_name,
         search_request,
@@ -567,6 +829,18 @@ pub async fn search(
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        skipped_unindexed_points: report
+            .skipped_unindexed_points
+            .map(|skipped| skipped as u64),
+        debug: report.debug.map(|debug| debug.into()),
+        usage: report.usage.map(|usage| usage.into()),
+        partial: report.partial.map(|partial| partial.into()),
+        trace: report
+            .trace
+            .into_iter()
+            .flatten()
+            .map(|trace| trace.into())
+            .collect(),
     };
 
     Ok(Response::new(response))
@@ -591,7 +865,7 @@ pub async fn search_batch(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let scored_points = do_search_batch_points(
+    let (scored_points, report) = do_search_batch_points(
         toc,
         &collection_name,
         search_requests,
@@ -609,6 +883,18 @@ pub async fn search_batch(
             })
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        skipped_unindexed_points: report
+            .skipped_unindexed_points
+            .map(|skipped| skipped as u64),
+        debug: report.debug.map(|debug| debug.into()),
+        usage: report.usage.map(|usage| usage.into()),
+        partial: report.partial.map(|partial| partial.into()),
+        trace: report
+            .trace
+            .into_iter()
+            .flatten()
+            .map(|trace| trace.into())
+            .collect(),
     };
 
     Ok(Response::new(response))
@@ -630,7 +916,7 @@ pub async fn search_groups(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let groups_result = crate::common::points::do_search_point_groups(
+    let (groups_result, report) = crate::common::points::do_search_point_groups(
         toc,
         &collection_name,
         search_groups_request,
@@ -643,6 +929,12 @@ pub async fn search_groups(
     let response = SearchGroupsResponse {
         result: Some(groups_result.into()),
         time: timing.elapsed().as_secs_f64(),
+        skipped_unindexed_points: report
+            .skipped_unindexed_points
+            .map(|skipped| skipped as u64),
+        debug: report.debug.map(|debug| debug.into()),
+        usage: report.usage.map(|usage| usage.into()),
+        partial: report.partial.map(|partial| partial.into()),
     };
 
     Ok(Response::new(response))
@@ -695,8 +987,7 @@ pub async fn recommend(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let recommended_points = toc
-        .recommend(&collection_name, request, read_consistency)
+    let recommended_points = do_recommend_points(toc, &collection_name, request, read_consistency)
         .await
         .map_err(error_to_status)?;
 
@@ -728,10 +1019,10 @@ pub async fn recommend_batch(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let scored_points = toc
-        .recommend_batch(&collection_name, recommend_batch, read_consistency)
-        .await
-        .map_err(error_to_status)?;
+    let scored_points =
+        do_recommend_batch_points(toc, &collection_name, recommend_batch, read_consistency)
+            .await
+            .map_err(error_to_status)?;
 
     let response = RecommendBatchResponse {
         result: scored_points
@@ -777,11 +1068,10 @@ pub async fn recommend_groups(
 
     Ok(Response::new(response))
 }
-pub async fn scroll(
-    toc: &TableOfContent,
+/// Collection name, scroll request and read consistency of the gRPC scroll request
+fn scroll_request_from_grpc(
     scroll_points: ScrollPoints,
-    shard_selection: Option<ShardId>,
-) -> Result<Response<ScrollResponse>, Status> {
+) -> Result<(String, ScrollRequest, Option<ReadConsistency>), Status> {
     let ScrollPoints {
         collection_name,
         filter,
@@ -790,6 +1080,8 @@ pub async fn scroll(
         with_payload,
         with_vectors,
         read_consistency,
+        with_timestamps,
+        max_payload_return_bytes,
     } = scroll_points;
 
     let scroll_request = ScrollRequest {
@@ -800,10 +1092,23 @@ pub async fn scroll(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        with_timestamps: with_timestamps.unwrap_or(false),
+        max_payload_return_bytes: max_payload_return_bytes.map(|bytes| bytes as usize),
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
+    Ok((collection_name, scroll_request, read_consistency))
+}
+
+pub async fn scroll(
+    toc: &TableOfContent,
+    scroll_points: ScrollPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<ScrollResponse>, Status> {
+    let (collection_name, scroll_request, read_consistency) =
+        scroll_request_from_grpc(scroll_points)?;
+
     let timing = Instant::now();
     let scrolled_points = do_scroll_points(
         toc,
@@ -828,6 +1133,132 @@ pub async fn scroll(
     Ok(Response::new(response))
 }
 
+/// Read the first points in the order of a payload field, e.g. for an ordered query of another peer
+pub async fn scroll_ordered(
+    toc: &TableOfContent,
+    scroll_points: ScrollPoints,
+    order_by: OrderByGrpc,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<ScrollResponse>, Status> {
+    let (collection_name, scroll_request, read_consistency) =
+        scroll_request_from_grpc(scroll_points)?;
+    let limit = scroll_request
+        .limit
+        .ok_or_else(|| Status::invalid_argument("limit is required to order the points"))?;
+
+    let request = OrderedQueryRequest {
+        filter: scroll_request.filter,
+        order_by: order_by.into(),
+        limit,
+        offset: 0,
+        with_payload: scroll_request.with_payload,
+        with_vector: Some(scroll_request.with_vector),
+    };
+
+    let timing = Instant::now();
+    let points = do_query_points(
+        toc,
+        &collection_name,
+        request,
+        read_consistency,
+        shard_selection,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = ScrollResponse {
+        next_page_offset: None,
+        result: points.into_iter().map(|point| point.into()).collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+/// Stream of the scrolled batches of points
+pub type ScrollStream = Pin<Box<dyn Stream<Item = Result<ScrollResponse, Status>> + Send>>;
+
+/// Scroll through all points, matching the request, sending a batch of `limit` points at a time.
+///
+/// Next batch is only fetched after the client has received the previous one. The stream is
+/// stopped if the client disconnects, or doesn't read the next batch within
+/// `SCROLL_STREAM_IDLE_TIMEOUT`, so that the collection is not held by abandoned streams.
+pub async fn scroll_stream(
+    toc: Arc<TableOfContent>,
+    scroll_points: ScrollPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<ScrollStream>, Status> {
+    let (collection_name, mut scroll_request, read_consistency) =
+        scroll_request_from_grpc(scroll_points)?;
+
+    // Fail the request itself, rather than the first message of the stream
+    check_collection_access(&collection_name, AccessLevel::Read).map_err(error_to_status)?;
+    toc.get_collection(&collection_name)
+        .await
+        .map_err(error_to_status)?;
+
+    let (sender, receiver) = mpsc::channel(1);
+    // Batches are read in the background, still within the access scope of the request
+    tokio::spawn(with_point_scope(point_scope(), async move {
+        loop {
+            let timing = Instant::now();
+            let scroll = do_scroll_points(
+                &toc,
+                &collection_name,
+                scroll_request.clone(),
+                read_consistency,
+                shard_selection,
+            );
+            let result = tokio::select! {
+                result = scroll => result,
+                _ = sender.closed() => return,
+            };
+
+            let (response, next_page_offset) = match result {
+                Ok(scrolled_points) => {
+                    let next_page_offset = scrolled_points.next_page_offset;
+                    let response = ScrollResponse {
+                        next_page_offset: next_page_offset.map(|n| n.into()),
+                        result: scrolled_points
+                            .points
+                            .into_iter()
+                            .map(|point| point.into())
+                            .collect(),
+                        time: timing.elapsed().as_secs_f64(),
+                    };
+                    (Ok(response), next_page_offset)
+                }
+                Err(err) => (Err(error_to_status(err)), None),
+            };
+
+            match sender
+                .send_timeout(response, SCROLL_STREAM_IDLE_TIMEOUT)
+                .await
+            {
+                Ok(()) => {}
+                Err(SendTimeoutError::Timeout(_)) => {
+                    log::debug!(
+                        "Scroll stream of collection {collection_name} is idle for {:?}, closing",
+                        SCROLL_STREAM_IDLE_TIMEOUT
+                    );
+                    return;
+                }
+                Err(SendTimeoutError::Closed(_)) => return,
+            }
+
+            match next_page_offset {
+                Some(offset) => scroll_request.offset = Some(offset),
+                None => return,
+            }
+        }
+    }));
+
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|item| (item, receiver))
+    });
+    Ok(Response::new(Box::pin(stream)))
+}
+
 pub async fn count(
     toc: &TableOfContent,
     count_points: CountPoints,
@@ -868,6 +1299,8 @@ pub async fn get(
         with_payload,
         with_vectors,
         read_consistency,
+        with_timestamps,
+        max_payload_return_bytes,
     } = get_points;
 
     let point_request = PointRequest {
@@ -879,6 +1312,8 @@ pub async fn get(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        with_timestamps: with_timestamps.unwrap_or(false),
+        max_payload_return_bytes: max_payload_return_bytes.map(|bytes| bytes as usize),
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
@@ -1,22 +1,27 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use api::grpc::qdrant::points_internal_server::PointsInternal;
 use api::grpc::qdrant::{
+    CancelFieldIndexBuildInternal, ChecksumPointsInternal, ChecksumResponse,
     ClearPayloadPointsInternal, CountPointsInternal, CountResponse,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollectionInternal,
     DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, GetPointsInternal,
-    GetResponse, PointsOperationResponse, RecommendPointsInternal, RecommendResponse,
-    ScrollPointsInternal, ScrollResponse, SearchBatchPointsInternal, SearchBatchResponse,
-    SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, SyncPointsInternal,
-    UpdateVectorsInternal, UpsertPointsInternal,
+    GetResponse, PointsOperationResponse, RebuildFieldIndexCollectionInternal,
+    RecommendPointsInternal, RecommendResponse, ScrollPointsInternal, ScrollResponse,
+    SearchBatchPointsInternal, SearchBatchResponse, SearchPointsInternal, SearchResponse,
+    SetPayloadPointsInternal, SyncPointsInternal, UpdateVectorsInternal, UpsertPointsInternal,
 };
+use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Request, Response, Status};
 
 use super::validate_and_log;
+use crate::common::collections::do_get_shard_checksum;
 use crate::tonic::api::points_common::{
-    clear_payload, count, create_field_index, delete, delete_field_index, delete_payload,
-    delete_vectors, get, overwrite_payload, recommend, scroll, search, search_batch, set_payload,
+    cancel_field_index_build_internal, clear_payload, count, create_field_index, delete,
+    delete_field_index, delete_payload, delete_vectors, get, overwrite_payload,
+    rebuild_field_index, recommend, scroll, scroll_ordered, search, search_batch, set_payload,
     sync, update_vectors, upsert,
 };
 
@@ -175,6 +180,30 @@ impl PointsInternal for PointsInternalService {
         delete_field_index(self.toc.as_ref(), delete_field_index_collection, shard_id).await
     }
 
+    async fn rebuild_field_index(
+        &self,
+        request: Request<RebuildFieldIndexCollectionInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let RebuildFieldIndexCollectionInternal {
+            rebuild_field_index_collection,
+            shard_id,
+        } = request.into_inner();
+
+        let rebuild_field_index_collection = rebuild_field_index_collection
+            .ok_or_else(|| Status::invalid_argument("RebuildFieldIndexCollection is missing"))?;
+
+        rebuild_field_index(self.toc.as_ref(), rebuild_field_index_collection, shard_id).await
+    }
+
+    async fn cancel_field_index_build(
+        &self,
+        request: Request<CancelFieldIndexBuildInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        cancel_field_index_build_internal(self.toc.as_ref(), request.into_inner()).await
+    }
+
     async fn search(
         &self,
         request: Request<SearchPointsInternal>,
@@ -247,6 +276,7 @@ impl PointsInternal for PointsInternalService {
         let ScrollPointsInternal {
             scroll_points,
             shard_id,
+            order_by,
         } = request.into_inner();
 
         let mut scroll_points =
@@ -254,6 +284,10 @@ impl PointsInternal for PointsInternalService {
 
         scroll_points.read_consistency = None; // *Have* to be `None`!
 
+        if let Some(order_by) = order_by {
+            return scroll_ordered(self.toc.as_ref(), scroll_points, order_by, shard_id).await;
+        }
+
         scroll(self.toc.as_ref(), scroll_points, shard_id).await
     }
 
@@ -319,4 +353,27 @@ impl PointsInternal for PointsInternalService {
 
         overwrite_payload(self.toc.as_ref(), set_payload_points, shard_id).await
     }
+
+    async fn checksum(
+        &self,
+        request: Request<ChecksumPointsInternal>,
+    ) -> Result<Response<ChecksumResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let ChecksumPointsInternal {
+            collection_name,
+            shard_id,
+        } = request.into_inner();
+        let shard_id = shard_id.ok_or_else(|| Status::invalid_argument("Shard id is missing"))?;
+
+        let timing = Instant::now();
+        let checksum = do_get_shard_checksum(self.toc.as_ref(), &collection_name, shard_id)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = ChecksumResponse {
+            result: Some(checksum.into()),
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
 }
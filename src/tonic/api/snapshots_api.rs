@@ -16,6 +16,7 @@ use storage::dispatcher::Dispatcher;
 use tonic::{async_trait, Request, Response, Status};
 
 use super::validate;
+use crate::common::auth::{check_collection_access, check_global_access, AccessLevel};
 use crate::common::collections::{do_create_snapshot, do_list_snapshots};
 
 pub struct SnapshotsService {
@@ -74,6 +75,7 @@ impl Snapshots for SnapshotsService {
             snapshot_name,
         } = request.into_inner();
         let timing = Instant::now();
+        check_collection_access(&collection_name, AccessLevel::Manage).map_err(error_to_status)?;
         let _response =
             do_delete_collection_snapshot(&self.dispatcher, &collection_name, &snapshot_name, true)
                 .await
@@ -89,6 +91,7 @@ impl Snapshots for SnapshotsService {
     ) -> Result<Response<CreateSnapshotResponse>, Status> {
         validate(request.get_ref())?;
         let timing = Instant::now();
+        check_global_access(AccessLevel::Manage).map_err(error_to_status)?;
         let response = do_create_full_snapshot(&self.dispatcher, true)
             .await
             .map_err(error_to_status)?;
@@ -104,6 +107,7 @@ impl Snapshots for SnapshotsService {
     ) -> Result<Response<ListSnapshotsResponse>, Status> {
         validate(request.get_ref())?;
         let timing = Instant::now();
+        check_global_access(AccessLevel::Manage).map_err(error_to_status)?;
         let snapshots = do_list_full_snapshots(&self.dispatcher)
             .await
             .map_err(error_to_status)?;
@@ -120,6 +124,7 @@ impl Snapshots for SnapshotsService {
         validate(request.get_ref())?;
         let snapshot_name = request.into_inner().snapshot_name;
         let timing = Instant::now();
+        check_global_access(AccessLevel::Manage).map_err(error_to_status)?;
         let _response = do_delete_full_snapshot(&self.dispatcher, &snapshot_name, true)
             .await
             .map_err(error_to_status)?;
@@ -1,6 +1,5 @@
 use std::task::{Context, Poll};
 
-use constant_time_eq::constant_time_eq;
 use futures_util::future::BoxFuture;
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
@@ -9,15 +8,17 @@ use tonic::Code;
 use tower::Service;
 use tower_layer::Layer;
 
+use crate::common::auth::{with_claims, ApiKeys};
+
 #[derive(Clone)]
 pub struct ApiKeyMiddleware<T> {
     service: T,
-    api_key: String,
+    api_keys: ApiKeys,
 }
 
 #[derive(Clone)]
 pub struct ApiKeyMiddlewareLayer {
-    api_key: String,
+    api_keys: ApiKeys,
 }
 
 impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for ApiKeyMiddleware<S>
@@ -40,18 +41,26 @@ where
         &mut self,
         request: tonic::codegen::http::Request<tonic::transport::Body>,
     ) -> Self::Future {
-        if let Some(key) = request.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    let future = self.service.call(request);
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let description = match self
+            .api_keys
+            .authorize(header("api-key"), header("authorization"))
+        {
+            Ok(claims) => {
+                let future = self.service.call(request);
 
-                    return Box::pin(async move {
-                        let response = future.await?;
-                        Ok(response)
-                    });
-                }
+                return Box::pin(with_claims(claims, async move {
+                    let response = future.await?;
+                    Ok(response)
+                }));
             }
-        }
+            Err(description) => description,
+        };
 
         let mut response = Self::Response::new(BoxBody::default());
         *response.status_mut() = StatusCode::FORBIDDEN;
@@ -59,17 +68,17 @@ where
             "grpc-status",
             HeaderValue::from(Code::PermissionDenied as i32),
         );
-        response
-            .headers_mut()
-            .append("grpc-message", HeaderValue::from_static("Invalid api-key"));
+        let message = HeaderValue::from_str(&description)
+            .unwrap_or_else(|_| HeaderValue::from_static("Invalid api-key"));
+        response.headers_mut().append("grpc-message", message);
 
         Box::pin(async move { Ok(response) })
     }
 }
 
 impl ApiKeyMiddlewareLayer {
-    pub fn new(api_key: String) -> Self {
-        Self { api_key }
+    pub fn new(api_keys: ApiKeys) -> Self {
+        Self { api_keys }
     }
 }
 
@@ -79,7 +88,7 @@ impl<S> Layer<S> for ApiKeyMiddlewareLayer {
     fn layer(&self, service: S) -> Self::Service {
         ApiKeyMiddleware {
             service,
-            api_key: self.api_key.clone(),
+            api_keys: self.api_keys.clone(),
         }
     }
 }
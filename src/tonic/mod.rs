@@ -2,6 +2,8 @@ mod api;
 mod api_key;
 mod logging;
 mod tonic_telemetry;
+#[cfg(feature = "opentelemetry")]
+mod trace_context;
 
 use std::io;
 use std::net::{IpAddr, SocketAddr};
@@ -24,6 +26,7 @@ use tonic::codec::CompressionEncoding;
 use tonic::transport::{Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 
+use crate::common::auth::ApiKeys;
 use crate::common::helpers;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
 use crate::settings::Settings;
@@ -102,10 +105,7 @@ pub fn init(
                 telemetry_collector,
             ))
             .option_layer(
-                settings
-                    .service
-                    .api_key
-                    .map(api_key::ApiKeyMiddlewareLayer::new),
+                ApiKeys::from_config(&settings.service).map(api_key::ApiKeyMiddlewareLayer::new),
             )
             .into_inner();
 
@@ -190,7 +190,14 @@ pub fn init_internal(
             };
 
             // The stack of middleware that our service will be wrapped in
-            let middleware_layer = tower::ServiceBuilder::new()
+            let middleware_layer = tower::ServiceBuilder::new();
+
+            // Spans of the internal requests are recorded into the traces of the calling peers
+            #[cfg(feature = "opentelemetry")]
+            let middleware_layer =
+                middleware_layer.layer(trace_context::TraceContextMiddlewareLayer::new());
+
+            let middleware_layer = middleware_layer
                 .layer(logging::LoggingMiddlewareLayer::new())
                 .layer(tonic_telemetry::TonicTelemetryLayer::new(
                     telemetry_collector,
@@ -0,0 +1,62 @@
+use std::task::{Context, Poll};
+
+use collection::common::trace_context::extract_trace_context;
+use futures_util::future::BoxFuture;
+use tonic::body::BoxBody;
+use tonic::codegen::http::Response;
+use tower::Service;
+use tower_layer::Layer;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Serves each internal request in a span, which continues the trace of the calling peer
+#[derive(Clone)]
+pub struct TraceContextMiddleware<T> {
+    inner: T,
+}
+
+#[derive(Clone)]
+pub struct TraceContextMiddlewareLayer;
+
+impl TraceContextMiddlewareLayer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for TraceContextMiddleware<S>
+where
+    S: Service<tonic::codegen::http::Request<tonic::transport::Body>, Response = Response<BoxBody>>
+        + Clone,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let span = tracing::info_span!("internal_grpc", method = request.uri().path());
+        span.set_parent(extract_trace_context(request.headers()));
+
+        let future = span.in_scope(|| inner.call(request));
+        Box::pin(future.instrument(span))
+    }
+}
+
+impl<S> Layer<S> for TraceContextMiddlewareLayer {
+    type Service = TraceContextMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        TraceContextMiddleware { inner: service }
+    }
+}
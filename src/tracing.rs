@@ -1,4 +1,6 @@
-pub fn setup() -> anyhow::Result<()> {
+use crate::settings::TracingConfig;
+
+pub fn setup(config: &TracingConfig) -> anyhow::Result<()> {
     // Use `console` and/or `tracy` features to enable both `tracing-subscriber` and the layer(s)
     #[cfg(feature = "tracing-subscriber")]
     {
@@ -28,8 +30,72 @@ pub fn setup() -> anyhow::Result<()> {
             tracing_subscriber::filter::filter_fn(|metadata| metadata.is_span()),
         ));
 
+        // Use `opentelemetry` feature to enable both `tracing-subscriber` and the OTLP exporter
+        #[cfg(feature = "opentelemetry")]
+        let reg = reg.with(otlp::layer(config)?);
+
         tracing::subscriber::set_global_default(reg)?;
     }
 
+    #[cfg(not(feature = "opentelemetry"))]
+    if config.otlp_endpoint.is_some() {
+        log::warn!(
+            "`service.tracing.otlp_endpoint` is ignored, \
+             Qdrant is built without the `opentelemetry` feature"
+        );
+    }
+
     Ok(())
 }
+
+/// Export the spans, which are not exported yet
+pub fn shutdown() {
+    #[cfg(feature = "opentelemetry")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otlp {
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::sdk::trace::{self, Sampler};
+    use opentelemetry::sdk::Resource;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    use crate::settings::TracingConfig;
+
+    /// Layer, which exports the spans to the configured collector.
+    ///
+    /// The batches of the spans are sent from the current Tokio runtime.
+    pub fn layer<S>(config: &TracingConfig) -> anyhow::Result<Option<impl Layer<S>>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let Some(endpoint) = &config.otlp_endpoint else {
+            return Ok(None);
+        };
+
+        // Context of the traces is passed to the other peers in the W3C `traceparent` header
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let sampler =
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sampling_ratio)));
+        let resource = Resource::new([KeyValue::new("service.name", config.service_name.clone())]);
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(endpoint);
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                trace::config()
+                    .with_sampler(sampler)
+                    .with_resource(resource),
+            )
+            .install_batch(opentelemetry::runtime::Tokio)?;
+
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+    }
+}
@@ -0,0 +1,62 @@
+import pathlib
+import re
+
+from .fixtures import create_collection, drop_collection
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 2
+N_COLLECTIONS = 50
+
+
+def read_log(log_file: str) -> str:
+    with open(f"{init_pytest_log_folder()}/{log_file}") as f:
+        return f.read()
+
+
+def wal_compacted(log_file: str) -> bool:
+    return "Compacted consensus WAL" in read_log(log_file)
+
+
+def get_collection_names(peer_api_uri: str) -> set:
+    r = requests.get(f"{peer_api_uri}/collections")
+    assert_http_ok(r)
+    return {collection["name"] for collection in r.json()["result"]["collections"]}
+
+
+def collections_converged(peer_api_uri: str, expected: set) -> bool:
+    return get_collection_names(peer_api_uri) == expected
+
+
+def test_new_peer_bootstraps_from_consensus_snapshot(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    # Enough consensus operations to exceed `compact_wal_entries`
+    for i in range(N_COLLECTIONS):
+        create_collection(peer_api_uris[0], collection=f"test_collection_{i}")
+    for i in range(0, N_COLLECTIONS, 2):
+        drop_collection(peer_api_uris[0], collection=f"test_collection_{i}")
+    expected_collections = {f"test_collection_{i}" for i in range(1, N_COLLECTIONS, 2)}
+    assert get_collection_names(peer_api_uris[0]) == expected_collections
+
+    # Leader replaces the applied operations with a snapshot
+    wait_for(wal_compacted, "peer_0_0.log")
+
+    new_peer_dir = make_peer_folder(tmp_path, N_PEERS)
+    new_peer_uri = start_peer(new_peer_dir, f"peer_0_{N_PEERS}.log", bootstrap_uri)
+    peer_api_uris.append(new_peer_uri)
+
+    leader = get_leader(peer_api_uris[0])
+    wait_for_uniform_cluster_status(peer_api_uris, leader)
+    wait_for(collections_converged, new_peer_uri, expected_collections)
+
+    # New peer receives the snapshot and only the tail of the log
+    new_peer_log = read_log(f"peer_0_{N_PEERS}.log")
+    assert "Applying snapshot" in new_peer_log
+    appended_indexes = [
+        int(index) for index in re.findall(r"Appending (?:operation|entry): .*?index: (\d+)", new_peer_log)
+    ]
+    assert len(appended_indexes) > 0
+    assert min(appended_indexes) > N_COLLECTIONS
@@ -0,0 +1,43 @@
+import pathlib
+
+from .fixtures import create_collection
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+N_SHARDS = 3
+
+
+def get_peer_id(peer_api_uri):
+    r = requests.get(f"{peer_api_uri}/cluster")
+    assert_http_ok(r)
+    return r.json()["result"]["peer_id"]
+
+
+def test_remove_peer_with_last_replica(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name="test_collection", peer_api_uris=peer_api_uris)
+
+    # Find a peer, which is the only holder of some shard
+    peer_uri = next(
+        uri for uri in peer_api_uris[1:]
+        if len(get_collection_cluster_info(uri, "test_collection")["local_shards"]) > 0
+    )
+    peer_id = get_peer_id(peer_uri)
+
+    res = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{peer_id}")
+    assert res.status_code == 400, res.text
+    assert "last active replica" in res.json()["status"]["error"]
+    assert "test_collection" in res.json()["status"]["error"]
+
+    # Force alone is not enough to lose data
+    res = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{peer_id}?force=true")
+    assert res.status_code == 400, res.text
+    assert "allow_data_loss" in res.json()["status"]["error"]
+
+    res = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{peer_id}?force=true&allow_data_loss=true")
+    assert_http_ok(res)
@@ -0,0 +1,110 @@
+import pathlib
+
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+ZONES = ["a", "a", "b"]
+
+
+def get_peer_id(peer_api_uri):
+    r = requests.get(f"{peer_api_uri}/cluster")
+    assert_http_ok(r)
+    return r.json()["result"]["peer_id"]
+
+
+def create_collection(peer_url, collection, **params):
+    return requests.put(
+        f"{peer_url}/collections/{collection}?timeout=10", json={
+            "vectors": {
+                "size": 4,
+                "distance": "Dot"
+            },
+            **params,
+        })
+
+
+def shard_peers(peer_url, collection):
+    """Peers of the replicas of each shard"""
+    info = get_collection_cluster_info(peer_url, collection)
+    shards = {}
+    for shard in info["local_shards"]:
+        shards.setdefault(shard["shard_id"], set()).add(info["peer_id"])
+    for shard in info["remote_shards"]:
+        shards.setdefault(shard["shard_id"], set()).add(shard["peer_id"])
+    return shards
+
+
+def test_explicit_shard_placement(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+    peer_ids = [get_peer_id(uri) for uri in peer_api_uris]
+
+    placement = [
+        {"shard_id": 0, "peers": [peer_ids[0], peer_ids[1]]},
+        {"shard_id": 1, "peers": [peer_ids[1], peer_ids[2]]},
+    ]
+    r = create_collection(peer_api_uris[0], "test_collection", replication_factor=2, shard_placement=placement)
+    assert_http_ok(r)
+    wait_collection_exists_and_active_on_all_peers(collection_name="test_collection", peer_api_uris=peer_api_uris)
+
+    assert shard_peers(peer_api_uris[0], "test_collection") == {
+        0: {peer_ids[0], peer_ids[1]},
+        1: {peer_ids[1], peer_ids[2]},
+    }
+
+    # Unknown peer
+    r = create_collection(peer_api_uris[0], "unknown_peer", shard_placement=[
+        {"shard_id": 0, "peers": [max(peer_ids) + 1]},
+    ])
+    assert r.status_code == 400, r.text
+    assert "unknown peer" in r.json()["status"]["error"]
+
+    # Fewer peers than replication factor
+    r = create_collection(peer_api_uris[0], "not_enough_replicas", replication_factor=2, shard_placement=[
+        {"shard_id": 0, "peers": [peer_ids[0]]},
+    ])
+    assert r.status_code == 400, r.text
+    assert "replication factor" in r.json()["status"]["error"]
+
+    # Misconfigured collections are not created
+    r = requests.get(f"{peer_api_uris[0]}/collections")
+    assert_http_ok(r)
+    assert [c["name"] for c in r.json()["result"]["collections"]] == ["test_collection"]
+
+
+def test_placement_policy_by_label(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+    peer_ids = [get_peer_id(uri) for uri in peer_api_uris]
+    zone_of = dict(zip(peer_ids, ZONES))
+
+    for peer_id, zone in zone_of.items():
+        r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{peer_id}/labels", json={"labels": {"zone": zone}})
+        assert_http_ok(r)
+
+    # Labels are replicated through the consensus
+    r = requests.get(f"{peer_api_uris[-1]}/cluster")
+    assert_http_ok(r)
+    peers = r.json()["result"]["peers"]
+    assert {int(peer_id): peer["labels"]["zone"] for peer_id, peer in peers.items()} == zone_of
+
+    r = create_collection(peer_api_uris[0], "test_collection", shard_number=4, replication_factor=2,
+                          placement_policy={"spread_by_label": "zone"})
+    assert_http_ok(r)
+    wait_collection_exists_and_active_on_all_peers(collection_name="test_collection", peer_api_uris=peer_api_uris)
+
+    for shard_id, peers in shard_peers(peer_api_uris[0], "test_collection").items():
+        assert {zone_of[peer_id] for peer_id in peers} == {"a", "b"}, shard_id
+
+    # Only 2 zones for 3 replicas
+    r = create_collection(peer_api_uris[0], "too_many_replicas", replication_factor=3,
+                          placement_policy={"spread_by_label": "zone"})
+    assert r.status_code == 400, r.text
+    assert "zone" in r.json()["status"]["error"]
+
+    # Unknown peer can't be labeled
+    r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{max(peer_ids) + 1}/labels", json={"labels": {"zone": "c"}})
+    assert r.status_code == 404, r.text
@@ -96,7 +96,7 @@ def recover_from_snapshot(tmp_path: pathlib.Path, n_replicas):
     p.kill()
 
     # Remove last peer from cluster
-    res = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{process_peer_id}?force=true")
+    res = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{process_peer_id}?force=true&allow_data_loss=true")
     assert_http_ok(res)
 
     new_peer_dir = make_peer_folder(tmp_path, N_PEERS + 1)
@@ -92,7 +92,7 @@ def recover_from_uploaded_snapshot(tmp_path: pathlib.Path, n_replicas):
 
     # Remove last peer from cluster
     res = requests.delete(
-        f"{peer_api_uris[0]}/cluster/peer/{process_peer_id}?force=true"
+        f"{peer_api_uris[0]}/cluster/peer/{process_peer_id}?force=true&allow_data_loss=true"
     )
     assert_http_ok(res)
 
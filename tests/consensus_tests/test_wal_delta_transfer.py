@@ -0,0 +1,67 @@
+import glob
+import pathlib
+
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_REPLICA = 3
+BATCH_SIZE = 100
+
+
+def count_points(peer_url, collection_name="test_collection"):
+    r = requests.post(
+        f"{peer_url}/collections/{collection_name}/points/count", json={"exact": True}
+    )
+    assert_http_ok(r)
+    return r.json()["result"]["count"]
+
+
+def test_wal_delta_transfer(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=1, replication_factor=N_REPLICA)
+    wait_collection_exists_and_active_on_all_peers(collection_name="test_collection", peer_api_uris=peer_api_uris)
+
+    # Ingest through both of the remaining peers
+    offset = 0
+    for i in range(10):
+        upsert_random_points(peer_api_uris[i % 2], BATCH_SIZE, offset=offset)
+        offset += BATCH_SIZE
+
+    # Kill last peer in the middle of the ingestion
+    p = processes.pop()
+    p.kill()
+
+    for i in range(10):
+        upsert_random_points(peer_api_uris[i % 2], BATCH_SIZE, offset=offset)
+        offset += BATCH_SIZE
+
+    wait_for_some_replicas_not_active(peer_api_uris[0], "test_collection")
+
+    # Overwrite some of the points, inserted before the peer was killed
+    upsert_random_points(peer_api_uris[0], BATCH_SIZE, offset=0)
+
+    new_url = start_peer(peer_dirs[-1], "peer_0_restarted.log", bootstrap_uri)
+
+    # Dead replica is recovered from its last acknowledged operation
+    wait_for_all_replicas_active(peer_api_uris[0], "test_collection")
+
+    expected_count = count_points(peer_api_uris[0])
+    assert expected_count == offset
+    for peer_url in peer_api_uris[:-1] + [new_url]:
+        assert count_points(peer_url) == expected_count
+
+    # Replicas converged to the same points
+    r = requests.get(f"{peer_api_uris[0]}/collections/test_collection/cluster/checksum")
+    assert_http_ok(r)
+    assert r.json()["result"]["inconsistent_shards"] == []
+
+    # Only the points, changed after the acknowledged operation, are transferred
+    log_files = glob.glob(f"{init_pytest_log_folder()}/*.log")
+    assert any(
+        "changed since the last acknowledged operation" in open(log_file).read()
+        for log_file in log_files
+    )